@@ -3,17 +3,24 @@ use std::{env, time::Duration};
 use anyhow::Context;
 use serde::Deserialize;
 use url::Url;
-use zksync_basic_types::{Address, L1ChainId, L2ChainId};
-use zksync_config::ObjectStoreConfig;
+use zksync_basic_types::{Address, L1ChainId, L2ChainId, H256};
+use zksync_config::{configs::api::EthCallFastPathMethod, ObjectStoreConfig};
 use zksync_consensus_roles::node;
 use zksync_core::{
     api_server::{
         tx_sender::TxSenderConfig,
-        web3::{state::InternalApiConfig, Namespace},
+        web3::{
+            state::{GasCaps, InternalApiConfig, LogsDenylist},
+            Namespace,
+        },
     },
     consensus,
+    consistency_checker::ResyncPolicy,
+};
+use zksync_types::{
+    api::BridgeAddresses,
+    fee_model::{FeeParams, IntrinsicGasOverrides},
 };
-use zksync_types::{api::BridgeAddresses, fee_model::FeeParams};
 use zksync_web3_decl::{
     error::ClientRpcContext,
     jsonrpsee::http_client::{HttpClient, HttpClientBuilder},
@@ -165,6 +172,36 @@ pub struct OptionalENConfig {
     /// different node.
     #[serde(default)]
     pub filters_disabled: bool,
+    /// Contract addresses for which logs are not served via `eth_getLogs` and `eth_newFilter`. Filters explicitly
+    /// referencing any of these addresses are rejected.
+    #[serde(default)]
+    pub logs_denylisted_addresses: Vec<Address>,
+    /// Event topics for which logs are not served; works similarly to `logs_denylisted_addresses`.
+    #[serde(default)]
+    pub logs_denylisted_topics: Vec<H256>,
+    /// Methods which responses are streamed by the HTTP server using chunked transfer encoding
+    /// (e.g., `eth_getLogs`), so that the entire serialized response is never held in memory.
+    #[serde(default)]
+    pub streamed_methods: Vec<String>,
+    /// Whether to persist call traces for executed transactions. Persisted traces are served by the `debug_trace*`,
+    /// `trace_filter` and `zks_getRawBlockTraces` methods. If not specified, traces are persisted iff the `debug`
+    /// or `trace` namespace is enabled.
+    save_call_traces: Option<bool>,
+    /// Maximum number of miniblocks in a `trace_filter` block range.
+    #[serde(default = "OptionalENConfig::default_trace_filter_max_block_range")]
+    pub trace_filter_max_block_range: u32,
+    /// Maximum number of traces returned by a single `trace_filter` call.
+    #[serde(default = "OptionalENConfig::default_trace_filter_max_traces")]
+    pub trace_filter_max_traces: usize,
+    /// Maximum number of transactions in a single `zks_estimateGasBatch` call.
+    #[serde(default = "OptionalENConfig::default_estimate_gas_batch_max_size")]
+    pub estimate_gas_batch_max_size: usize,
+    /// Maximum number of storage keys in a single `eth_getProof` call.
+    #[serde(default = "OptionalENConfig::default_get_proof_max_keys")]
+    pub get_proof_max_keys: usize,
+    /// Maximum number of transactions in a bundle accepted by `zks_simulateBundle`.
+    #[serde(default = "OptionalENConfig::default_simulate_bundle_max_size")]
+    pub simulate_bundle_max_size: usize,
 
     // Health checks
     /// Time limit in milliseconds to mark a health check as slow and log the corresponding warning.
@@ -181,6 +218,33 @@ pub struct OptionalENConfig {
     /// The max possible number of gas that `eth_estimateGas` is allowed to overestimate.
     #[serde(default = "OptionalENConfig::default_estimate_gas_acceptable_overestimation")]
     pub estimate_gas_acceptable_overestimation: u32,
+    /// Whether to optimize the binary search in gas estimation by initializing its bounds based on the gas
+    /// consumed by the transaction executed with the maximum gas limit.
+    #[serde(default)]
+    pub estimate_gas_optimize_search: bool,
+    /// Maximum number of cached gas estimation results; 0 disables caching.
+    #[serde(default = "OptionalENConfig::default_estimate_gas_cache_size")]
+    pub estimate_gas_cache_size: usize,
+    /// Override of the intrinsic gas of L2 transactions used to validate submitted transactions. Should be set
+    /// to the same value as on the main node, so that transactions rejected by the main node are rejected early.
+    pub l2_tx_intrinsic_gas_override: Option<u32>,
+    /// View methods served by `eth_call` directly from storage for recognized token contracts, without invoking the VM.
+    #[serde(default)]
+    pub eth_call_fast_path_methods: Vec<EthCallFastPathMethod>,
+    /// Bytecode hashes of bridged token contracts with the standard storage layout. The `eth_call` fast path serves
+    /// calls to a bridged token only if its bytecode hash is in this list.
+    #[serde(default)]
+    pub eth_call_fast_path_token_code_hashes: Vec<H256>,
+    /// Maximum gas for sandbox executions (`eth_call`, gas estimation etc.). If not set, executions are only limited
+    /// by the maximum gas limit of a transaction.
+    pub rpc_gas_cap: Option<u32>,
+    /// Gas cap replacing `rpc_gas_cap` for clients authenticated with an API key from `rpc_privileged_api_key_hashes`.
+    /// If not set, sandbox executions for such clients are not capped.
+    pub rpc_privileged_gas_cap: Option<u32>,
+    /// SHA-256 hashes of API keys (provided by clients in the `x-api-key` HTTP header) to which
+    /// `rpc_privileged_gas_cap` applies.
+    #[serde(default)]
+    pub rpc_privileged_api_key_hashes: Vec<H256>,
     /// Whether to use the compatibility mode for gas estimation for L1->L2 transactions.
     /// During the migration to the 1.4.1 fee model, there will be a period, when the server
     /// will already have the 1.4.1 fee model, while the L1 contracts will still expect the transactions
@@ -240,6 +304,56 @@ pub struct OptionalENConfig {
     /// Path to KZG trusted setup path.
     #[serde(default = "OptionalENConfig::default_kzg_trusted_setup_path")]
     pub kzg_trusted_setup_path: String,
+
+    /// Enables indexing token transfers served by the `zks_getTokenTransfers` and `zks_getAccountBalances` methods.
+    /// Transfers are indexed from persisted events, including events of miniblocks sealed before indexing was enabled.
+    #[serde(default)]
+    pub token_transfers_indexer_enabled: bool,
+
+    // Pruning config
+    /// Enables pruning of the historical node state (Postgres). If enabled, miniblock-level data
+    /// (miniblocks, transactions, events etc.) of old L1 batches is removed from Postgres; L1 batch-level data is retained.
+    /// Pruned data is not available via the API.
+    #[serde(default)]
+    pub pruning_enabled: bool,
+    /// Number of L1 batches pruned at a time.
+    #[serde(default = "OptionalENConfig::default_pruning_chunk_size")]
+    pub pruning_chunk_size: u32,
+    /// Delay between soft pruning (the data is no longer served via the API) and hard pruning (the data is removed
+    /// from Postgres) of L1 batches. Should be larger than the maximum expected duration of a single API request.
+    #[serde(default = "OptionalENConfig::default_pruning_removal_delay_sec")]
+    pruning_removal_delay_sec: u64,
+    /// Minimum age of an L1 batch for it to be pruned. The age is determined by the L1 batch timestamp.
+    /// L1 batches are additionally required to be executed on L1 before they can be pruned.
+    #[serde(default = "OptionalENConfig::default_pruning_data_retention_hours")]
+    pruning_data_retention_hours: u64,
+
+    // Consistency checker config
+    /// Enables resyncing the node if the consistency checker detects an L1 batch inconsistent with L1. The node
+    /// is reverted to the last consistent L1 batch, after which correct data is re-fetched from the main node.
+    #[serde(default)]
+    pub consistency_checker_resync_enabled: bool,
+    /// Maximum number of L1 batches that can be reverted during a resync. Resyncs requiring to revert more batches
+    /// are not performed.
+    #[serde(default = "OptionalENConfig::default_consistency_checker_max_batches_to_revert")]
+    pub consistency_checker_max_batches_to_revert: u32,
+    /// Whether a resync requires explicit operator confirmation. If set, the node doesn't revert itself; instead,
+    /// the revert target is logged and reported via health check, and the operator should restart the node
+    /// with the `--revert-to-l1-batch` command-line arg. Should be disabled for unattended nodes.
+    #[serde(
+        default = "OptionalENConfig::default_consistency_checker_resync_requires_confirmation"
+    )]
+    pub consistency_checker_resync_requires_confirmation: bool,
+    /// Maximum number of automatic resyncs caused by the same inconsistent L1 batch. If the batch is still
+    /// inconsistent after this number of resyncs, the node stops resyncing and only logs the inconsistency.
+    #[serde(default = "OptionalENConfig::default_consistency_checker_max_resync_attempts")]
+    pub consistency_checker_max_resync_attempts: u32,
+
+    // Consensus fallback config
+    /// Period of main node unavailability after which the node switches to fetching L2 blocks from peers
+    /// over the consensus gossip network. Only used if the consensus fallback is enabled.
+    #[serde(default = "OptionalENConfig::default_consensus_fallback_timeout_sec")]
+    consensus_fallback_timeout_sec: u64,
 }
 
 impl OptionalENConfig {
@@ -259,6 +373,36 @@ impl OptionalENConfig {
         1_000_000
     }
 
+    const fn default_trace_filter_max_block_range() -> u32 {
+        100
+    }
+
+    const fn default_trace_filter_max_traces() -> usize {
+        1_000
+    }
+
+    const fn default_estimate_gas_batch_max_size() -> usize {
+        32
+    }
+
+    const fn default_get_proof_max_keys() -> usize {
+        100
+    }
+
+    const fn default_simulate_bundle_max_size() -> usize {
+        32
+    }
+
+    const fn default_estimate_gas_cache_size() -> usize {
+        1_024
+    }
+
+    fn intrinsic_gas_overrides(&self) -> IntrinsicGasOverrides {
+        IntrinsicGasOverrides {
+            l2_tx_intrinsic_gas: self.l2_tx_intrinsic_gas_override,
+        }
+    }
+
     const fn default_polling_interval() -> u64 {
         200
     }
@@ -350,6 +494,34 @@ impl OptionalENConfig {
         "./trusted_setup.json".to_owned()
     }
 
+    const fn default_pruning_chunk_size() -> u32 {
+        10
+    }
+
+    const fn default_pruning_removal_delay_sec() -> u64 {
+        60
+    }
+
+    const fn default_pruning_data_retention_hours() -> u64 {
+        24 * 7
+    }
+
+    const fn default_consistency_checker_max_batches_to_revert() -> u32 {
+        10
+    }
+
+    const fn default_consistency_checker_resync_requires_confirmation() -> bool {
+        true
+    }
+
+    const fn default_consistency_checker_max_resync_attempts() -> u32 {
+        1
+    }
+
+    const fn default_consensus_fallback_timeout_sec() -> u64 {
+        60
+    }
+
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.polling_interval)
     }
@@ -404,6 +576,13 @@ impl OptionalENConfig {
             .unwrap_or_else(|| Namespace::DEFAULT.to_vec())
     }
 
+    pub fn save_call_traces(&self) -> bool {
+        self.save_call_traces.unwrap_or_else(|| {
+            let namespaces = self.api_namespaces();
+            namespaces.contains(&Namespace::Debug) || namespaces.contains(&Namespace::Trace)
+        })
+    }
+
     pub fn max_response_body_size(&self) -> usize {
         self.max_response_body_size_mb * BYTES_IN_MEGABYTE
     }
@@ -417,6 +596,27 @@ impl OptionalENConfig {
         self.healthcheck_hard_time_limit_ms
             .map(Duration::from_millis)
     }
+
+    pub fn pruning_removal_delay(&self) -> Duration {
+        Duration::from_secs(self.pruning_removal_delay_sec)
+    }
+
+    pub fn pruning_data_retention(&self) -> Duration {
+        Duration::from_secs(self.pruning_data_retention_hours * 3_600)
+    }
+
+    pub fn consensus_fallback_timeout(&self) -> Duration {
+        Duration::from_secs(self.consensus_fallback_timeout_sec)
+    }
+
+    pub fn consistency_checker_resync_policy(&self) -> Option<ResyncPolicy> {
+        self.consistency_checker_resync_enabled
+            .then_some(ResyncPolicy {
+                max_batches_to_revert: self.consistency_checker_max_batches_to_revert,
+                requires_confirmation: self.consistency_checker_resync_requires_confirmation,
+                max_attempts: self.consistency_checker_max_resync_attempts,
+            })
+    }
 }
 
 /// This part of the external node config is required for its operation.
@@ -506,6 +706,54 @@ pub(crate) fn read_snapshots_recovery_config() -> anyhow::Result<SnapshotsRecove
     })
 }
 
+/// Configuration for state keeper cache backups. Loaded optionally, only if the corresponding command-line argument
+/// is supplied to the EN binary.
+#[derive(Debug, Clone)]
+pub struct StateCacheBackupsConfig {
+    pub object_store: ObjectStoreConfig,
+    /// Prefix of object store keys for backups of this node. Must be unique for each node sharing
+    /// the object store bucket.
+    pub prefix: String,
+    /// Interval between creating backups.
+    pub interval: Duration,
+    /// Whether to back up the Merkle tree in addition to the state keeper cache.
+    pub merkle_tree: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct StateCacheBackupsEnvConfig {
+    prefix: String,
+    #[serde(default = "StateCacheBackupsEnvConfig::default_interval_sec")]
+    interval_sec: u64,
+    #[serde(default)]
+    merkle_tree: bool,
+}
+
+impl StateCacheBackupsEnvConfig {
+    const fn default_interval_sec() -> u64 {
+        3_600
+    }
+}
+
+pub(crate) fn read_state_cache_backups_config() -> anyhow::Result<StateCacheBackupsConfig> {
+    let object_store = envy::prefixed("EN_STATE_CACHE_BACKUPS_OBJECT_STORE_")
+        .from_env::<ObjectStoreConfig>()
+        .context("failed loading state cache backups object store config from env variables")?;
+    let env_config = envy::prefixed("EN_STATE_CACHE_BACKUPS_")
+        .from_env::<StateCacheBackupsEnvConfig>()
+        .context("failed loading state cache backups config from env variables")?;
+    anyhow::ensure!(
+        !env_config.prefix.is_empty(),
+        "state cache backups prefix must not be empty"
+    );
+    Ok(StateCacheBackupsConfig {
+        object_store,
+        prefix: env_config.prefix,
+        interval: Duration::from_secs(env_config.interval_sec),
+        merkle_tree: env_config.merkle_tree,
+    })
+}
+
 /// External Node Config contains all the configuration required for the EN operation.
 /// It is split into three parts: required, optional and remote for easier navigation.
 #[derive(Debug, Clone)]
@@ -515,6 +763,8 @@ pub struct ExternalNodeConfig {
     pub optional: OptionalENConfig,
     pub remote: RemoteENConfig,
     pub consensus: Option<consensus::FetcherConfig>,
+    /// If set, `consensus` is used to fetch L2 blocks from peers only while the main node is unavailable.
+    pub consensus_fallback: bool,
 }
 
 impl ExternalNodeConfig {
@@ -528,6 +778,10 @@ impl ExternalNodeConfig {
         let optional = envy::prefixed("EN_")
             .from_env::<OptionalENConfig>()
             .context("could not load external node config")?;
+        optional
+            .intrinsic_gas_overrides()
+            .validate(u32::MAX)
+            .context("invalid intrinsic gas overrides")?;
 
         let client = HttpClientBuilder::default()
             .build(required.main_node_url()?)
@@ -580,6 +834,7 @@ impl ExternalNodeConfig {
             required,
             optional,
             consensus: None,
+            consensus_fallback: false,
         })
     }
 }
@@ -617,6 +872,22 @@ impl From<ExternalNodeConfig> for InternalApiConfig {
             req_entities_limit: config.optional.req_entities_limit,
             fee_history_limit: config.optional.fee_history_limit,
             filters_disabled: config.optional.filters_disabled,
+            logs_denylist: LogsDenylist::new(
+                config.optional.logs_denylisted_addresses,
+                config.optional.logs_denylisted_topics,
+            ),
+            // Miniblock root hashes are not computed by the external node tree.
+            provisional_state_roots_enabled: false,
+            trace_filter_max_block_range: config.optional.trace_filter_max_block_range,
+            trace_filter_max_traces: config.optional.trace_filter_max_traces,
+            estimate_gas_batch_max_size: config.optional.estimate_gas_batch_max_size,
+            get_proof_max_keys: config.optional.get_proof_max_keys,
+            simulate_bundle_max_size: config.optional.simulate_bundle_max_size,
+            gas_caps: GasCaps::new(
+                config.optional.rpc_gas_cap,
+                config.optional.rpc_privileged_gas_cap,
+                config.optional.rpc_privileged_api_key_hashes,
+            ),
         }
     }
 }
@@ -641,6 +912,16 @@ impl From<ExternalNodeConfig> for TxSenderConfig {
                 .optional
                 .l1_to_l2_transactions_compatibility_mode,
             max_pubdata_per_batch: config.remote.max_pubdata_per_batch,
+            estimate_gas_optimize_search: config.optional.estimate_gas_optimize_search,
+            estimate_gas_cache_size: config.optional.estimate_gas_cache_size,
+            // Overrides are validated when loading the config.
+            intrinsic_constants: config.optional.intrinsic_gas_overrides().apply(),
+            eth_call_fast_path_methods: config.optional.eth_call_fast_path_methods,
+            eth_call_fast_path_token_code_hashes: config
+                .optional
+                .eth_call_fast_path_token_code_hashes,
+            // Overriding base system contracts is only supported on the main node used for local development.
+            system_contracts_override_enabled: false,
         }
     }
 }
@@ -25,6 +25,25 @@ fn parsing_optional_config_from_empty_env() {
         128 * BYTES_IN_MEGABYTE
     );
     assert_eq!(config.max_response_body_size(), 10 * BYTES_IN_MEGABYTE);
+    assert_eq!(config.trace_filter_max_block_range, 100);
+    assert_eq!(config.trace_filter_max_traces, 1_000);
+    assert_eq!(config.estimate_gas_batch_max_size, 32);
+    assert_eq!(config.get_proof_max_keys, 100);
+    assert_eq!(config.simulate_bundle_max_size, 32);
+    assert_eq!(config.estimate_gas_cache_size, 1_024);
+    assert_eq!(config.l2_tx_intrinsic_gas_override, None);
+    assert!(!config.pruning_enabled);
+    assert!(!config.token_transfers_indexer_enabled);
+    assert_eq!(
+        config.save_call_traces(),
+        config.api_namespaces().contains(&Namespace::Debug)
+    );
+    assert_eq!(config.pruning_removal_delay(), Duration::from_secs(60));
+    assert_eq!(
+        config.pruning_data_retention(),
+        Duration::from_secs(7 * 24 * 3_600)
+    );
+    assert_eq!(config.consistency_checker_resync_policy(), None);
 }
 
 #[test]
@@ -45,6 +64,18 @@ fn parsing_optional_config_from_env() {
         ("EN_MERKLE_TREE_MULTI_GET_CHUNK_SIZE", "1000"),
         ("EN_MERKLE_TREE_BLOCK_CACHE_SIZE_MB", "32"),
         ("EN_MAX_RESPONSE_BODY_SIZE_MB", "1"),
+        ("EN_PRUNING_ENABLED", "true"),
+        ("EN_PRUNING_CHUNK_SIZE", "5"),
+        ("EN_PRUNING_DATA_RETENTION_HOURS", "2"),
+        ("EN_API_NAMESPACES", "eth,zks,debug"),
+        ("EN_SAVE_CALL_TRACES", "false"),
+        ("EN_CONSISTENCY_CHECKER_RESYNC_ENABLED", "true"),
+        ("EN_CONSISTENCY_CHECKER_MAX_BATCHES_TO_REVERT", "3"),
+        (
+            "EN_CONSISTENCY_CHECKER_RESYNC_REQUIRES_CONFIRMATION",
+            "false",
+        ),
+        ("EN_L2_TX_INTRINSIC_GAS_OVERRIDE", "20000"),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -72,4 +103,21 @@ fn parsing_optional_config_from_env() {
         32 * BYTES_IN_MEGABYTE
     );
     assert_eq!(config.max_response_body_size(), BYTES_IN_MEGABYTE);
+    assert!(config.pruning_enabled);
+    assert_eq!(config.pruning_chunk_size, 5);
+    assert_eq!(
+        config.pruning_data_retention(),
+        Duration::from_secs(2 * 3_600)
+    );
+    assert!(!config.save_call_traces());
+    assert_eq!(
+        config.consistency_checker_resync_policy(),
+        Some(ResyncPolicy {
+            max_batches_to_revert: 3,
+            requires_confirmation: false,
+            max_attempts: 1,
+        })
+    );
+    assert_eq!(config.l2_tx_intrinsic_gas_override, Some(20_000));
+    config.intrinsic_gas_overrides().validate(u32::MAX).unwrap();
 }
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{path::Path, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use clap::Parser;
@@ -6,7 +6,7 @@ use futures::{future::FusedFuture, FutureExt as _};
 use metrics::EN_METRICS;
 use prometheus_exporter::PrometheusExporterConfig;
 use tokio::{sync::watch, task, time::sleep};
-use zksync_basic_types::{Address, L2ChainId};
+use zksync_basic_types::{Address, L1BatchNumber, L2ChainId};
 use zksync_concurrency::{ctx, scope};
 use zksync_config::configs::database::MerkleTreeMode;
 use zksync_core::{
@@ -14,35 +14,43 @@ use zksync_core::{
         execution_sandbox::VmConcurrencyLimiter,
         healthcheck::HealthCheckHandle,
         tx_sender::{proxy::TxProxy, ApiContracts, TxSenderBuilder},
-        web3::{ApiBuilder, Namespace},
+        web3::ApiBuilder,
     },
     block_reverter::{BlockReverter, BlockReverterFlags, L1ExecutedBatchesRevert},
     commitment_generator::CommitmentGenerator,
     consensus,
     consistency_checker::ConsistencyChecker,
+    db_pruner::{DbPruner, DbPrunerConfig},
     l1_gas_price::MainNodeFeeParamsFetcher,
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
     reorg_detector::ReorgDetector,
+    rocksdb_backup::{restore_rocksdb_backup, RocksdbBackupKind, RocksdbCheckpointer},
     setup_sigint_handler,
     state_keeper::{
         seal_criteria::NoopSealer, BatchExecutor, MainBatchExecutor, MiniblockSealer,
-        MiniblockSealerHandle, ZkSyncStateKeeper,
+        MiniblockSealerHandle, StateKeeperHealthCheck, ZkSyncStateKeeper,
     },
     sync_layer::{
         batch_status_updater::BatchStatusUpdater, external_io::ExternalIO,
         fetcher::MainNodeFetcher, ActionQueue, MainNodeClient, SyncState,
     },
+    token_transfers_indexer::TokenTransfersIndexer,
 };
 use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
+use zksync_eth_client::clients::QueryClient;
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_l1_contract_interface::i_executor::commit::kzg::KzgSettings;
+use zksync_object_store::ObjectStoreFactory;
 use zksync_state::PostgresStorageCaches;
 use zksync_storage::RocksDB;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 use zksync_web3_decl::jsonrpsee::http_client::HttpClient;
 
 use crate::{
-    config::{observability::observability_config_from_env, ExternalNodeConfig},
+    config::{
+        observability::observability_config_from_env, read_state_cache_backups_config,
+        ExternalNodeConfig,
+    },
     helpers::MainNodeHealthCheck,
     init::ensure_storage_initialized,
 };
@@ -66,17 +74,18 @@ async fn build_state_keeper(
     miniblock_sealer_handle: MiniblockSealerHandle,
     stop_receiver: watch::Receiver<bool>,
     chain_id: L2ChainId,
+    cache_checkpointer: Option<RocksdbCheckpointer>,
 ) -> anyhow::Result<ZkSyncStateKeeper> {
     // These config values are used on the main node, and depending on these values certain transactions can
     // be *rejected* (that is, not included into the block). However, external node only mirrors what the main
     // node has already executed, so we can safely set these values to the maximum possible values - if the main
     // node has already executed the transaction, then the external node must execute it too.
+    // For the same reason, the transaction execution timeout is never set for the external node.
     let max_allowed_l2_tx_gas_limit = u32::MAX.into();
     let validation_computational_gas_limit = u32::MAX;
-    // We only need call traces on the external node if the `debug_` namespace is enabled.
-    let save_call_traces = config.optional.api_namespaces().contains(&Namespace::Debug);
+    let save_call_traces = config.optional.save_call_traces();
 
-    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(MainBatchExecutor::new(
+    let mut batch_executor_base = MainBatchExecutor::new(
         state_keeper_db_path,
         connection_pool.clone(),
         max_allowed_l2_tx_gas_limit,
@@ -84,7 +93,11 @@ async fn build_state_keeper(
         false,
         config.optional.enum_index_migration_chunk_size,
         true,
-    ));
+    );
+    if let Some(checkpointer) = cache_checkpointer {
+        batch_executor_base.set_cache_checkpointer(checkpointer);
+    }
+    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(batch_executor_base);
 
     let main_node_url = config.required.main_node_url()?;
     let main_node_client = <dyn MainNodeClient>::json_rpc(&main_node_url)
@@ -117,7 +130,8 @@ async fn init_tasks(
     task_handles: &mut Vec<task::JoinHandle<anyhow::Result<()>>>,
     app_health: &AppHealthCheck,
     stop_receiver: watch::Receiver<bool>,
-) -> anyhow::Result<()> {
+    backup_checkpointers: BackupCheckpointers,
+) -> anyhow::Result<task::JoinHandle<anyhow::Result<Option<L1BatchNumber>>>> {
     let release_manifest: serde_json::Value = serde_json::from_str(RELEASE_MANIFEST)
         .expect("release manifest is a valid json document; qed");
     let release_manifest_version = release_manifest["core"].as_str().expect(
@@ -166,12 +180,52 @@ async fn init_tasks(
         miniblock_sealer_handle,
         stop_receiver.clone(),
         config.remote.l2_chain_id,
+        backup_checkpointers.state_keeper_cache,
     )
     .await?;
 
     let singleton_pool_builder = ConnectionPool::singleton(&config.postgres.database_url);
 
-    let fetcher_handle = if let Some(cfg) = config.consensus.clone() {
+    let fetcher_handle = if let Some(cfg) = config
+        .consensus
+        .clone()
+        .filter(|_| config.consensus_fallback)
+    {
+        let fetcher = consensus::FallbackFetcher {
+            executor: cfg.executor,
+            main_node_client: Box::new(main_node_client.clone()),
+            l1_client: Box::new(
+                QueryClient::new(
+                    &config
+                        .required
+                        .eth_client_url()
+                        .context("L1 client URL is incorrect")?,
+                )
+                .context("failed creating L1 client")?,
+            ),
+            diamond_proxy_addr: config.remote.diamond_proxy_addr,
+            sync_state: sync_state.clone(),
+            unavailable_timeout: config.optional.consensus_fallback_timeout(),
+        };
+        let pool = connection_pool.clone();
+        let mut stop_receiver = stop_receiver.clone();
+
+        tokio::spawn(async move {
+            scope::run!(&ctx::root(), |ctx, s| async {
+                s.spawn_bg(async {
+                    let res = fetcher
+                        .run(ctx, pool, action_queue_sender, stop_receiver.clone())
+                        .await;
+                    tracing::info!("Fallback consensus fetcher stopped");
+                    res
+                });
+                ctx.wait(stop_receiver.wait_for(|stop| *stop)).await??;
+                Ok(())
+            })
+            .await
+            .context("fallback consensus fetcher")
+        })
+    } else if let Some(cfg) = config.consensus.clone() {
         let pool = connection_pool.clone();
         let mut stop_receiver = stop_receiver.clone();
         let sync_state = sync_state.clone();
@@ -224,16 +278,21 @@ async fn init_tasks(
         block_cache_capacity: config.optional.merkle_tree_block_cache_size(),
         memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
         stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
+        miniblock_root_hashes_enabled: false,
     };
-    let metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
+    let mut metadata_calculator = MetadataCalculator::new(metadata_calculator_config, None)
         .await
         .context("failed initializing metadata calculator")?;
+    if let Some(checkpointer) = backup_checkpointers.merkle_tree {
+        metadata_calculator = metadata_calculator.with_tree_checkpointer(checkpointer);
+    }
     app_health.insert_component(metadata_calculator.tree_health_check());
+    app_health.add_dependency("tree", "connection_pool");
 
     let kzg_settings = Some(Arc::new(KzgSettings::new(
         &config.optional.kzg_trusted_setup_path,
     )));
-    let consistency_checker = ConsistencyChecker::new(
+    let mut consistency_checker = ConsistencyChecker::new(
         &config
             .required
             .eth_client_url()
@@ -245,6 +304,10 @@ async fn init_tasks(
             .context("failed to build connection pool for ConsistencyChecker")?,
         kzg_settings,
     );
+    if let Some(resync_policy) = config.optional.consistency_checker_resync_policy() {
+        consistency_checker =
+            consistency_checker.with_resync_policy(resync_policy, main_node_client.clone());
+    }
     app_health.insert_component(consistency_checker.health_check().clone());
     let consistency_checker_handle = tokio::spawn(consistency_checker.run(stop_receiver.clone()));
 
@@ -274,12 +337,50 @@ async fn init_tasks(
         &config.optional.kzg_trusted_setup_path,
     );
     app_health.insert_component(commitment_generator.health_check());
+    app_health.add_dependency("commitment_generator", "tree");
     let commitment_generator_handle = tokio::spawn(commitment_generator.run(stop_receiver.clone()));
 
+    if config.optional.pruning_enabled {
+        tracing::warn!(
+            "Pruning is enabled; historical node data beyond the retention period will be removed"
+        );
+        let db_pruner_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a db_pruner_pool")?;
+        let db_pruner = DbPruner::new(
+            DbPrunerConfig {
+                soft_and_hard_pruning_time_delta: config.optional.pruning_removal_delay(),
+                next_iterations_delay: Duration::from_secs(30),
+                pruned_batch_chunk_size: config.optional.pruning_chunk_size,
+                minimum_l1_batch_age: config.optional.pruning_data_retention(),
+            },
+            db_pruner_pool,
+        );
+        app_health.insert_component(db_pruner.health_check());
+        task_handles.push(tokio::spawn(db_pruner.run(stop_receiver.clone())));
+    }
+
+    if config.optional.token_transfers_indexer_enabled {
+        let token_transfers_indexer_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a token_transfers_indexer_pool")?;
+        let token_transfers_indexer = TokenTransfersIndexer::new(token_transfers_indexer_pool);
+        app_health.insert_component(token_transfers_indexer.health_check());
+        task_handles.push(tokio::spawn(
+            token_transfers_indexer.run(stop_receiver.clone()),
+        ));
+    }
+
     let updater_handle = task::spawn(batch_status_updater.run(stop_receiver.clone()));
     let fee_address_migration_handle =
         task::spawn(state_keeper.run_fee_address_migration(connection_pool.clone()));
     let sk_handle = task::spawn(state_keeper.run());
+    app_health.insert_custom_component(Arc::new(StateKeeperHealthCheck::new(
+        connection_pool.clone(),
+    )));
+    app_health.add_dependency("state_keeper", "connection_pool");
     let fee_params_fetcher_handle =
         tokio::spawn(fee_params_fetcher.clone().run(stop_receiver.clone()));
 
@@ -341,6 +442,7 @@ async fn init_tasks(
             .with_filter_limit(config.optional.filters_limit)
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
+            .with_streamed_methods(config.optional.streamed_methods.clone())
             .with_tx_sender(tx_sender.clone(), vm_barrier.clone())
             .with_sync_state(sync_state.clone())
             .enable_api_namespaces(config.optional.api_namespaces())
@@ -390,12 +492,11 @@ async fn init_tasks(
         fetcher_handle,
         updater_handle,
         tree_handle,
-        consistency_checker_handle,
         fee_params_fetcher_handle,
         commitment_generator_handle,
     ]);
 
-    Ok(())
+    Ok(consistency_checker_handle)
 }
 
 async fn shutdown_components(
@@ -411,6 +512,69 @@ async fn shutdown_components(
     healthcheck_handle.stop().await;
 }
 
+/// Checkpointers for RocksDB instances backed up to the object store.
+#[derive(Debug, Default)]
+struct BackupCheckpointers {
+    state_keeper_cache: Option<RocksdbCheckpointer>,
+    merkle_tree: Option<RocksdbCheckpointer>,
+}
+
+/// Restores the state keeper cache (and, if configured, the Merkle tree) from backups if necessary
+/// and starts uploading backups.
+async fn init_state_cache_backups(
+    config: &ExternalNodeConfig,
+    connection_pool: &ConnectionPool,
+    task_handles: &mut Vec<task::JoinHandle<anyhow::Result<()>>>,
+    stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<BackupCheckpointers> {
+    let backups_config = read_state_cache_backups_config()?;
+    let object_store = ObjectStoreFactory::new(backups_config.object_store)
+        .create_store()
+        .await;
+
+    let mut db_paths = vec![(
+        RocksdbBackupKind::StateKeeperCache,
+        Path::new(&config.required.state_cache_path),
+    )];
+    if backups_config.merkle_tree {
+        db_paths.push((
+            RocksdbBackupKind::MerkleTree,
+            Path::new(&config.required.merkle_tree_path),
+        ));
+    }
+
+    let mut checkpointers = BackupCheckpointers::default();
+    for (kind, db_path) in db_paths {
+        restore_rocksdb_backup(
+            &*object_store,
+            connection_pool,
+            kind,
+            &backups_config.prefix,
+            db_path,
+        )
+        .await
+        .with_context(|| format!("failed restoring `{kind}` from backup"))?;
+
+        // Checkpoints should reside on the same filesystem as the DB, so that they are cheap to create.
+        let checkpoints_dir = db_path.with_extension("checkpoints");
+        let (checkpointer, uploader) = RocksdbCheckpointer::new(
+            kind,
+            backups_config.prefix.clone(),
+            checkpoints_dir,
+            backups_config.interval,
+            object_store.clone(),
+        );
+        task_handles.push(tokio::spawn(uploader.run(stop_receiver.clone())));
+        match kind {
+            RocksdbBackupKind::StateKeeperCache => {
+                checkpointers.state_keeper_cache = Some(checkpointer);
+            }
+            RocksdbBackupKind::MerkleTree => checkpointers.merkle_tree = Some(checkpointer),
+        }
+    }
+    Ok(checkpointers)
+}
+
 /// External node for zkSync Era.
 #[derive(Debug, Parser)]
 #[command(author = "Matter Labs", version)]
@@ -418,6 +582,10 @@ struct Cli {
     /// Revert the pending L1 batch and exit.
     #[arg(long)]
     revert_pending_l1_batch: bool,
+    /// Revert the node to the specified L1 batch and exit. Can be used to confirm the resync requested
+    /// by the consistency checker.
+    #[arg(long, conflicts_with = "revert_pending_l1_batch")]
+    revert_to_l1_batch: Option<u32>,
     /// Enables consensus-based syncing instead of JSON-RPC based one. This is an experimental and incomplete feature;
     /// do not use unless you know what you're doing.
     #[arg(long)]
@@ -427,8 +595,17 @@ struct Cli {
     /// or was synced from genesis.
     ///
     /// This is an experimental and incomplete feature; do not use unless you know what you're doing.
-    #[arg(long, conflicts_with = "enable_consensus")]
+    #[arg(long, conflicts_with_all = ["enable_consensus", "enable_consensus_fallback"])]
     enable_snapshots_recovery: bool,
+    /// Enables fetching L2 blocks from peers over the consensus gossip network while the main node API
+    /// is unavailable. L1 batches containing such blocks are verified against batch hashes committed on L1.
+    /// This is an experimental and incomplete feature; do not use unless you know what you're doing.
+    #[arg(long, conflicts_with = "enable_consensus")]
+    enable_consensus_fallback: bool,
+    /// Enables periodic backups of the state keeper cache (and optionally the Merkle tree) to the object store,
+    /// and restoring them from the latest backups on startup if they don't exist locally.
+    #[arg(long)]
+    enable_state_cache_backups: bool,
 }
 
 #[tokio::main]
@@ -462,7 +639,7 @@ async fn main() -> anyhow::Result<()> {
     let mut config = ExternalNodeConfig::collect()
         .await
         .context("Failed to load external node config")?;
-    if opt.enable_consensus {
+    if opt.enable_consensus || opt.enable_consensus_fallback {
         // This is more of a sanity check; the mutual exclusion of `enable_consensus` and `enable_snapshots_recovery`
         // should be ensured by `clap`.
         anyhow::ensure!(
@@ -471,6 +648,7 @@ async fn main() -> anyhow::Result<()> {
         );
         config.consensus =
             Some(config::read_consensus_config().context("read_consensus_config()")?);
+        config.consensus_fallback = opt.enable_consensus_fallback;
     }
 
     if let Some(threshold) = config.optional.slow_query_threshold() {
@@ -519,6 +697,38 @@ async fn main() -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if let Some(l1_batch_number) = opt.revert_to_l1_batch {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let mut connection = connection_pool.access_storage().await?;
+        let sealed_l1_batch_number = connection
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("Failed getting sealed L1 batch number")?
+            .context("Cannot roll back since there are no L1 batches in Postgres")?;
+        drop(connection);
+        anyhow::ensure!(
+            l1_batch_number <= sealed_l1_batch_number,
+            "Cannot roll back to L1 batch #{l1_batch_number}: the last sealed L1 batch is #{sealed_l1_batch_number}"
+        );
+
+        tracing::info!("Rolling back to L1 batch #{l1_batch_number}");
+        let reverter = BlockReverter::new(
+            config.required.state_cache_path,
+            config.required.merkle_tree_path,
+            None,
+            connection_pool.clone(),
+            L1ExecutedBatchesRevert::Allowed,
+        );
+        reverter
+            .rollback_db(l1_batch_number, BlockReverterFlags::all())
+            .await;
+        tracing::info!(
+            "Rollback successfully completed, the node has to restart to continue working"
+        );
+        return Ok(());
+    }
+
     let sigint_receiver = setup_sigint_handler();
     tracing::warn!("The external node is in the alpha phase, and should be used with caution.");
     tracing::info!("Started the external node");
@@ -559,16 +769,31 @@ async fn main() -> anyhow::Result<()> {
 
     let (stop_sender, stop_receiver) = watch::channel(false);
     let mut task_handles = vec![];
-    init_tasks(
+    let backup_checkpointers = if opt.enable_state_cache_backups {
+        init_state_cache_backups(
+            &config,
+            &connection_pool,
+            &mut task_handles,
+            stop_receiver.clone(),
+        )
+        .await
+        .context("init_state_cache_backups")?
+    } else {
+        BackupCheckpointers::default()
+    };
+    let consistency_checker_handle = init_tasks(
         &config,
         connection_pool.clone(),
         main_node_client.clone(),
         &mut task_handles,
         &app_health,
         stop_receiver.clone(),
+        backup_checkpointers,
     )
     .await
     .context("init_tasks")?;
+    let mut consistency_checker_handle = consistency_checker_handle.fuse();
+    let mut consistency_checker_result = None;
 
     let reorg_detector = ReorgDetector::new(main_node_client, connection_pool.clone());
     app_health.insert_component(reorg_detector.health_check().clone());
@@ -588,6 +813,10 @@ async fn main() -> anyhow::Result<()> {
             tracing::info!("Reorg detector terminated, shutting down");
             reorg_detector_result = Some(result);
         }
+        result = &mut consistency_checker_handle => {
+            tracing::info!("Consistency checker terminated, shutting down");
+            consistency_checker_result = Some(result);
+        }
     };
 
     // Reaching this point means that either some actor exited unexpectedly or we received a stop signal.
@@ -597,6 +826,9 @@ async fn main() -> anyhow::Result<()> {
     if !reorg_detector_handle.is_terminated() {
         reorg_detector_result = Some(reorg_detector_handle.await);
     }
+    if !consistency_checker_handle.is_terminated() {
+        consistency_checker_result = Some(consistency_checker_handle.await);
+    }
     let reorg_detector_last_correct_batch = reorg_detector_result.and_then(|result| match result {
         Ok(Ok(last_correct_batch)) => last_correct_batch,
         Ok(Err(err)) => {
@@ -608,8 +840,28 @@ async fn main() -> anyhow::Result<()> {
             None
         }
     });
-
-    if let Some(last_correct_batch) = reorg_detector_last_correct_batch {
+    let consistency_checker_last_correct_batch =
+        consistency_checker_result.and_then(|result| match result {
+            Ok(Ok(last_correct_batch)) => last_correct_batch,
+            Ok(Err(err)) => {
+                tracing::error!("Consistency checker failed: {err:#}");
+                None
+            }
+            Err(err) => {
+                tracing::error!("Consistency checker panicked: {err}");
+                None
+            }
+        });
+    // If both components request a rollback, the earliest L1 batch covers both requests.
+    let last_correct_batch = [
+        reorg_detector_last_correct_batch,
+        consistency_checker_last_correct_batch,
+    ]
+    .into_iter()
+    .flatten()
+    .min();
+
+    if let Some(last_correct_batch) = last_correct_batch {
         tracing::info!("Performing rollback to L1 batch #{last_correct_batch}");
 
         let reverter = BlockReverter::new(
@@ -0,0 +1,321 @@
+//! Validates pending DAL migrations before they are applied to the primary database. Pending migrations are applied
+//! to a disposable clone of the primary database (e.g., restored from a recent backup) in a DB transaction, after which
+//! all queries used by the node are prepared (but not executed) against the migrated schema, and their estimated plans
+//! are compared to the plans before the migrations. The transaction is rolled back afterwards.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use clap::Parser;
+use serde::Deserialize;
+use zksync_config::{configs::ObservabilityConfig, PostgresConfig};
+use zksync_dal::{migration_validation_dal::QueryPlan, ConnectionPool};
+use zksync_env_config::FromEnv;
+
+#[derive(Debug, Parser)]
+#[command(
+    author = "Matter Labs",
+    version,
+    about = "Validates DAL migrations against a clone of the primary database",
+    long_about = None
+)]
+struct Cli {
+    /// Path to the DAL crate containing migrations and offline query data.
+    #[arg(long, default_value = "core/lib/dal")]
+    dal_path: PathBuf,
+    /// URL of a disposable clone of the primary database. Must not point to the primary database itself.
+    #[arg(long)]
+    database_url: String,
+    /// Maximum allowed ratio of the estimated cost of a query after migrations to its cost before migrations.
+    #[arg(long, default_value_t = 2.0)]
+    max_cost_ratio: f64,
+}
+
+/// Migration loaded from the migrations directory of the DAL crate.
+#[derive(Debug)]
+struct Migration {
+    version: i64,
+    description: String,
+    up_sql: String,
+}
+
+impl Migration {
+    fn load_all(dir: &Path) -> anyhow::Result<Vec<Self>> {
+        let mut migrations = vec![];
+        for entry in fs::read_dir(dir).with_context(|| format!("cannot read {dir:?}"))? {
+            let path = entry?.path();
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Some(name) = file_name.strip_suffix(".up.sql") else {
+                continue;
+            };
+            let (version, description) = name
+                .split_once('_')
+                .with_context(|| format!("invalid migration name: {file_name}"))?;
+            let version = version
+                .parse()
+                .with_context(|| format!("invalid migration version: {file_name}"))?;
+            let up_sql =
+                fs::read_to_string(&path).with_context(|| format!("cannot read {path:?}"))?;
+            migrations.push(Self {
+                version,
+                description: description.to_owned(),
+                up_sql,
+            });
+        }
+        migrations.sort_unstable_by_key(|migration| migration.version);
+        Ok(migrations)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ColumnDescription {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct QueryDescription {
+    columns: Vec<ColumnDescription>,
+}
+
+/// Query used by the node, loaded from the offline query data of the DAL crate.
+#[derive(Debug, Deserialize)]
+struct OfflineQuery {
+    hash: String,
+    query: String,
+    describe: QueryDescription,
+}
+
+impl OfflineQuery {
+    fn load_all(dir: &Path) -> anyhow::Result<Vec<Self>> {
+        let mut queries = vec![];
+        for entry in fs::read_dir(dir).with_context(|| format!("cannot read {dir:?}"))? {
+            let path = entry?.path();
+            if path.extension().map_or(true, |ext| ext != "json") {
+                continue;
+            }
+            let contents =
+                fs::read_to_string(&path).with_context(|| format!("cannot read {path:?}"))?;
+            let query: Self = serde_json::from_str(&contents)
+                .with_context(|| format!("cannot parse query data at {path:?}"))?;
+            queries.push(query);
+        }
+        Ok(queries)
+    }
+
+    fn expected_columns(&self) -> Vec<&str> {
+        let columns = self.describe.columns.iter();
+        columns.map(|column| column.name.as_str()).collect()
+    }
+}
+
+impl Cli {
+    async fn run(self, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let start = Instant::now();
+        let migrations = Migration::load_all(&self.dal_path.join("migrations"))?;
+        let queries = OfflineQuery::load_all(&self.dal_path.join(".sqlx"))?;
+
+        let mut storage = pool.access_storage_tagged("migration_validator").await?;
+        let applied_migrations = storage
+            .migration_validation_dal()
+            .get_applied_migrations()
+            .await
+            .context("failed getting applied migrations")?;
+        let pending_migrations: Vec<_> = migrations
+            .iter()
+            .filter(|migration| !applied_migrations.contains(&migration.version))
+            .collect();
+        tracing::info!(
+            "Found {} pending migrations: {:?}",
+            pending_migrations.len(),
+            pending_migrations
+                .iter()
+                .map(|migration| migration.version)
+                .collect::<Vec<_>>()
+        );
+
+        // The transaction is never committed, so the database clone is unaffected.
+        let mut transaction = storage.start_transaction().await?;
+        let mut dal = transaction.migration_validation_dal();
+
+        // Queries introduced together with pending migrations are invalid before the migrations are applied;
+        // such queries don't have a baseline plan.
+        let mut baseline_plans = HashMap::new();
+        for query in &queries {
+            if dal.validate_query(&query.query).await.is_err() {
+                continue;
+            }
+            match dal.explain_query(&query.query).await {
+                Ok(Some(plan)) => {
+                    baseline_plans.insert(query.hash.as_str(), plan);
+                }
+                Ok(None) => tracing::warn!("Cannot parse plan for query {}", query.hash),
+                Err(err) => tracing::warn!("Cannot explain query {}: {err}", query.hash),
+            }
+        }
+        tracing::info!(
+            "Estimated baseline plans for {} out of {} queries",
+            baseline_plans.len(),
+            queries.len()
+        );
+
+        for migration in &pending_migrations {
+            let migration_start = Instant::now();
+            dal.apply_migration(&migration.up_sql)
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed applying migration {} ({})",
+                        migration.version, migration.description
+                    )
+                })?;
+            tracing::info!(
+                "Applied migration {} ({}) in {:?}",
+                migration.version,
+                migration.description,
+                migration_start.elapsed()
+            );
+        }
+
+        let mut failed_query_count = 0;
+        for query in &queries {
+            match dal.validate_query(&query.query).await {
+                Ok(columns) => {
+                    let expected_columns = query.expected_columns();
+                    if columns != expected_columns {
+                        tracing::error!(
+                            "Query {} returns columns {columns:?} instead of {expected_columns:?}:\n{}",
+                            query.hash,
+                            query.query
+                        );
+                        failed_query_count += 1;
+                        continue;
+                    }
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Query {} is invalid for the migrated schema: {err}\n{}",
+                        query.hash,
+                        query.query
+                    );
+                    failed_query_count += 1;
+                    continue;
+                }
+            }
+
+            let Some(baseline_plan) = baseline_plans.get(query.hash.as_str()) else {
+                continue;
+            };
+            let plan = match dal.explain_query(&query.query).await {
+                Ok(Some(plan)) => plan,
+                Ok(None) => {
+                    tracing::warn!("Cannot parse plan for query {}", query.hash);
+                    continue;
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Cannot explain query {} for the migrated schema: {err}\n{}",
+                        query.hash,
+                        query.query
+                    );
+                    failed_query_count += 1;
+                    continue;
+                }
+            };
+            if !self.check_plan(query, baseline_plan, &plan) {
+                failed_query_count += 1;
+            }
+        }
+        drop(transaction);
+
+        anyhow::ensure!(
+            failed_query_count == 0,
+            "{failed_query_count} out of {} queries failed validation",
+            queries.len()
+        );
+        tracing::info!(
+            "Validated {} queries after applying {} pending migrations in {:?}",
+            queries.len(),
+            pending_migrations.len(),
+            start.elapsed()
+        );
+        Ok(())
+    }
+
+    /// Compares the query plan after migrations with the baseline plan. Returns `false` if the query
+    /// has regressed beyond the configured cost ratio.
+    fn check_plan(
+        &self,
+        query: &OfflineQuery,
+        baseline_plan: &QueryPlan,
+        plan: &QueryPlan,
+    ) -> bool {
+        let new_seq_scans: Vec<_> = plan
+            .seq_scanned_relations
+            .difference(&baseline_plan.seq_scanned_relations)
+            .collect();
+        if !new_seq_scans.is_empty() {
+            tracing::warn!(
+                "Query {} now sequentially scans relations {new_seq_scans:?}:\n{}",
+                query.hash,
+                query.query
+            );
+        }
+
+        // Costs are compared only if the baseline cost is meaningful; otherwise, the ratio is unstable.
+        let cost_ratio = plan.total_cost / baseline_plan.total_cost.max(1.0);
+        if cost_ratio > self.max_cost_ratio {
+            tracing::error!(
+                "Estimated cost of query {} has increased from {} to {} ({cost_ratio:.2}x, max allowed: {}x):\n{}",
+                query.hash,
+                baseline_plan.total_cost,
+                plan.total_cost,
+                self.max_cost_ratio,
+                query.query
+            );
+            return false;
+        }
+        true
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let observability_config =
+        ObservabilityConfig::from_env().context("ObservabilityConfig::from_env()")?;
+    let log_format: vlog::LogFormat = observability_config
+        .log_format
+        .parse()
+        .context("Invalid log format")?;
+    let mut builder = vlog::ObservabilityBuilder::new().with_log_format(log_format);
+    if let Some(sentry_url) = observability_config.sentry_url {
+        builder = builder
+            .with_sentry_url(&sentry_url)
+            .context("Invalid Sentry URL")?
+            .with_sentry_environment(observability_config.sentry_environment);
+    }
+    let _guard = builder.build();
+
+    let cli = Cli::parse();
+    // Migrations are applied to the database for real (albeit in a transaction), so protect against
+    // accidentally running the validator against the primary database.
+    if let Ok(postgres_config) = PostgresConfig::from_env() {
+        if let Ok(master_url) = postgres_config.master_url() {
+            anyhow::ensure!(
+                cli.database_url != master_url,
+                "`--database-url` must point to a clone of the primary database, not the primary database itself"
+            );
+        }
+    }
+    let pool = ConnectionPool::singleton(&cli.database_url)
+        .build()
+        .await
+        .context("failed building connection pool")?;
+    cli.run(&pool).await
+}
@@ -6,18 +6,21 @@ use anyhow::Context as _;
 use tokio::sync::Semaphore;
 use zksync_config::SnapshotsCreatorConfig;
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_object_store::ObjectStore;
+use zksync_object_store::{ObjectStore, ZstdSnapshotFactoryDependencies};
 use zksync_types::{
     snapshots::{
         uniform_hashed_keys_chunk, SnapshotFactoryDependencies, SnapshotFactoryDependency,
-        SnapshotMetadata, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotMetadata, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
     L1BatchNumber, MiniblockNumber,
 };
 
-use crate::metrics::{FactoryDepsStage, StorageChunkStage, METRICS};
 #[cfg(test)]
 use crate::tests::HandleEvent;
+use crate::{
+    metrics::{FactoryDepsStage, StorageChunkStage, METRICS},
+    throttle::SnapshotThrottler,
+};
 
 /// Encapsulates progress of creating a particular storage snapshot.
 #[derive(Debug)]
@@ -76,12 +79,14 @@ impl SnapshotCreator {
     async fn process_storage_logs_single_chunk(
         &self,
         semaphore: &Semaphore,
+        throttler: &SnapshotThrottler,
         miniblock_number: MiniblockNumber,
         l1_batch_number: L1BatchNumber,
         chunk_id: u64,
         chunk_count: u64,
     ) -> anyhow::Result<()> {
         let _permit = semaphore.acquire().await?;
+        throttler.wait(&self.replica_pool).await?;
         #[cfg(test)]
         if self.event_listener.on_chunk_started().should_exit() {
             return Ok(());
@@ -143,6 +148,8 @@ impl SnapshotCreator {
 
     async fn process_factory_deps(
         &self,
+        config: &SnapshotsCreatorConfig,
+        version: SnapshotVersion,
         miniblock_number: MiniblockNumber,
         l1_batch_number: L1BatchNumber,
     ) -> anyhow::Result<String> {
@@ -169,19 +176,32 @@ impl SnapshotCreator {
             })
             .collect();
         let factory_deps = SnapshotFactoryDependencies { factory_deps };
-        let filename = self
-            .blob_store
-            .put(l1_batch_number, &factory_deps)
-            .await
-            .context("Error storing factory deps in blob store")?;
-        let output_filepath_prefix = self
-            .blob_store
-            .get_storage_prefix::<SnapshotFactoryDependencies>();
+        let factory_deps_count = factory_deps.factory_deps.len();
+        let (filename, output_filepath_prefix) = match version {
+            SnapshotVersion::Version0 => {
+                let filename = self.blob_store.put(l1_batch_number, &factory_deps).await;
+                let prefix = self
+                    .blob_store
+                    .get_storage_prefix::<SnapshotFactoryDependencies>();
+                (filename, prefix)
+            }
+            SnapshotVersion::Version1 => {
+                let factory_deps = ZstdSnapshotFactoryDependencies {
+                    factory_deps,
+                    compression_level: config.factory_deps_compression_level(),
+                };
+                let filename = self.blob_store.put(l1_batch_number, &factory_deps).await;
+                let prefix = self
+                    .blob_store
+                    .get_storage_prefix::<ZstdSnapshotFactoryDependencies>();
+                (filename, prefix)
+            }
+        };
+        let filename = filename.context("Error storing factory deps in blob store")?;
         let output_filepath = format!("{output_filepath_prefix}/{filename}");
         let latency = latency.observe();
         tracing::info!(
-            "Saved {} factory deps in {latency:?} to location: {output_filepath}",
-            factory_deps.factory_deps.len()
+            "Saved {factory_deps_count} factory deps in {latency:?} to location: {output_filepath}"
         );
 
         Ok(output_filepath)
@@ -288,6 +308,7 @@ impl SnapshotCreator {
             .context("Error fetching last miniblock number")?;
         drop(conn);
 
+        let throttler = SnapshotThrottler::new(&config);
         METRICS.storage_logs_chunks_count.set(progress.chunk_count);
         tracing::info!(
             "Creating snapshot for storage logs up to miniblock {last_miniblock_number_in_batch}, \
@@ -296,8 +317,15 @@ impl SnapshotCreator {
         );
 
         if progress.is_new_snapshot {
+            let version = SnapshotVersion::try_from(config.version)?;
+            throttler.wait(&self.replica_pool).await?;
             let factory_deps_output_file = self
-                .process_factory_deps(last_miniblock_number_in_batch, progress.l1_batch_number)
+                .process_factory_deps(
+                    &config,
+                    version,
+                    last_miniblock_number_in_batch,
+                    progress.l1_batch_number,
+                )
                 .await?;
 
             let mut master_conn = self
@@ -307,6 +335,7 @@ impl SnapshotCreator {
             master_conn
                 .snapshots_dal()
                 .add_snapshot(
+                    version,
                     progress.l1_batch_number,
                     progress.chunk_count,
                     &factory_deps_output_file,
@@ -321,6 +350,7 @@ impl SnapshotCreator {
         let tasks = progress.remaining_chunk_ids.into_iter().map(|chunk_id| {
             self.process_storage_logs_single_chunk(
                 &semaphore,
+                &throttler,
                 last_miniblock_number_in_batch,
                 progress.l1_batch_number,
                 chunk_id,
@@ -26,6 +26,7 @@ mod creator;
 mod metrics;
 #[cfg(test)]
 mod tests;
+mod throttle;
 
 async fn maybe_enable_prometheus_metrics(
     stop_receiver: watch::Receiver<bool>,
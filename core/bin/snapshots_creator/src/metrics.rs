@@ -2,7 +2,9 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
@@ -18,6 +20,14 @@ pub(crate) enum StorageChunkStage {
     SaveToGcs,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(crate) enum ThrottleReason {
+    OffPeakWindow,
+    ReplicationLag,
+    ProbeLatency,
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "snapshots_creator")]
 pub(crate) struct SnapshotsCreatorMetrics {
@@ -37,6 +47,14 @@ pub(crate) struct SnapshotsCreatorMetrics {
     /// Latency of factory deps processing split by stage.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub factory_deps_processing_duration: Family<FactoryDepsStage, Histogram<Duration>>,
+    /// Number of times snapshot creation was paused split by the reason.
+    pub throttled_tasks: Family<ThrottleReason, Counter>,
+    /// Replication lag of the replica as reported during the last load check.
+    #[metrics(unit = Unit::Seconds)]
+    pub replica_lag: Gauge<Duration>,
+    /// Latency of the probe query to the replica used for load checks.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub replica_probe_latency: Histogram<Duration>,
 }
 
 #[vise::register]
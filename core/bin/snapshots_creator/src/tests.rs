@@ -7,30 +7,48 @@ use std::{
         atomic::{AtomicUsize, Ordering},
         Arc,
     },
+    time::{Duration, UNIX_EPOCH},
 };
 
 use rand::{thread_rng, Rng};
 use zksync_dal::StorageProcessor;
-use zksync_object_store::ObjectStore;
+use zksync_object_store::{ObjectStore, ZstdSnapshotFactoryDependencies};
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     snapshots::{
         SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
     AccountTreeId, Address, L1BatchNumber, MiniblockNumber, ProtocolVersion, StorageKey,
     StorageLog, H256,
 };
 
 use super::*;
+use crate::throttle::{duration_until_window, is_within_window, SnapshotThrottler};
 
 const TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 10,
+    version: 0,
+    factory_deps_compression_level: None,
+    max_chunks_per_minute: None,
+    off_peak_start_hour_utc: None,
+    off_peak_end_hour_utc: None,
+    max_replication_lag_sec: None,
+    max_probe_latency_ms: None,
+    throttle_backoff_interval_ms: None,
 };
 const SEQUENTIAL_TEST_CONFIG: SnapshotsCreatorConfig = SnapshotsCreatorConfig {
     storage_logs_chunk_size: 1_000_000,
     concurrent_queries_count: 1,
+    version: 0,
+    factory_deps_compression_level: None,
+    max_chunks_per_minute: None,
+    off_peak_start_hour_utc: None,
+    off_peak_end_hour_utc: None,
+    max_replication_lag_sec: None,
+    max_probe_latency_ms: None,
+    throttle_backoff_interval_ms: None,
 };
 
 #[derive(Debug)]
@@ -149,6 +167,7 @@ async fn create_miniblock(
         base_system_contracts_hashes: Default::default(),
         protocol_version: Some(Default::default()),
         virtual_blocks: 0,
+        logs_bloom: Default::default(),
     };
 
     conn.blocks_dal()
@@ -310,6 +329,43 @@ async fn persisting_snapshot_factory_deps() {
     assert_eq!(actual_deps, expected_outputs.deps);
 }
 
+#[tokio::test]
+async fn persisting_zstd_compressed_snapshot_factory_deps() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    let config = SnapshotsCreatorConfig {
+        version: SnapshotVersion::Version1 as u16,
+        ..TEST_CONFIG
+    };
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+
+    let snapshot_metadata = conn
+        .snapshots_dal()
+        .get_snapshot_metadata(snapshot_l1_batch_number)
+        .await
+        .unwrap()
+        .expect("No snapshot metadata");
+    assert_eq!(snapshot_metadata.version, SnapshotVersion::Version1);
+    assert!(snapshot_metadata
+        .factory_deps_filepath
+        .ends_with(".proto.zst"));
+
+    let object_store = object_store_factory.create_store().await;
+    let ZstdSnapshotFactoryDependencies { factory_deps, .. } =
+        object_store.get(snapshot_l1_batch_number).await.unwrap();
+    let actual_deps: HashSet<_> = factory_deps.factory_deps.into_iter().collect();
+    assert_eq!(actual_deps, expected_outputs.deps);
+}
+
 #[tokio::test]
 async fn persisting_snapshot_logs() {
     let pool = ConnectionPool::test_pool().await;
@@ -456,3 +512,78 @@ async fn recovery_workflow_with_varying_chunk_size() {
     let object_store = object_store_factory.create_store().await;
     assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
 }
+
+#[test]
+fn checking_off_peak_window() {
+    let window = (2, 6);
+    assert!(is_within_window(2, window));
+    assert!(is_within_window(5, window));
+    assert!(!is_within_window(6, window));
+    assert!(!is_within_window(1, window));
+    assert!(!is_within_window(23, window));
+
+    let wrapping_window = (22, 4);
+    assert!(is_within_window(22, wrapping_window));
+    assert!(is_within_window(0, wrapping_window));
+    assert!(is_within_window(3, wrapping_window));
+    assert!(!is_within_window(4, wrapping_window));
+    assert!(!is_within_window(21, wrapping_window));
+
+    assert!(is_within_window(10, (7, 7)));
+}
+
+#[test]
+fn computing_duration_until_off_peak_window() {
+    const HOUR: Duration = Duration::from_secs(3_600);
+
+    // 1970-01-02, 01:30 UTC
+    let now = UNIX_EPOCH + HOUR * 25 + HOUR / 2;
+    assert_eq!(duration_until_window(now, (1, 2)), None);
+    assert_eq!(duration_until_window(now, (23, 2)), None);
+    assert_eq!(duration_until_window(now, (3, 5)), Some(HOUR * 3 / 2));
+    assert_eq!(duration_until_window(now, (0, 1)), Some(HOUR * 45 / 2));
+}
+
+#[tokio::test(start_paused = true)]
+async fn rate_limiting_tasks() {
+    let config = SnapshotsCreatorConfig {
+        max_chunks_per_minute: Some(6),
+        ..TEST_CONFIG
+    };
+    let throttler = SnapshotThrottler::new(&config);
+
+    let started_at = tokio::time::Instant::now();
+    let tasks = (0..3).map(|_| throttler.wait_for_rate_limit());
+    futures::future::join_all(tasks).await;
+    // The first task should start immediately, and the following ones should be spaced by 10 seconds.
+    assert_eq!(started_at.elapsed(), Duration::from_secs(20));
+}
+
+#[tokio::test]
+async fn persisting_snapshot_logs_with_throttling() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut rng = thread_rng();
+    let object_store_factory = ObjectStoreFactory::mock();
+    let object_store = object_store_factory.create_store().await;
+    let mut conn = pool.access_storage().await.unwrap();
+    let expected_outputs = prepare_postgres(&mut rng, &mut conn, 10).await;
+
+    // The test Postgres is not a replica, so its replication lag is always 0.
+    let config = SnapshotsCreatorConfig {
+        max_chunks_per_minute: Some(60_000),
+        off_peak_start_hour_utc: Some(0),
+        off_peak_end_hour_utc: Some(0),
+        max_replication_lag_sec: Some(10),
+        max_probe_latency_ms: Some(60_000),
+        throttle_backoff_interval_ms: Some(10),
+        ..TEST_CONFIG
+    };
+    SnapshotCreator::for_tests(object_store, pool.clone())
+        .run(config, MIN_CHUNK_COUNT)
+        .await
+        .unwrap();
+    let snapshot_l1_batch_number = L1BatchNumber(8);
+
+    let object_store = object_store_factory.create_store().await;
+    assert_storage_logs(&*object_store, snapshot_l1_batch_number, &expected_outputs).await;
+}
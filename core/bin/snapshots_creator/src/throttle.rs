@@ -0,0 +1,146 @@
+//! Throttling for snapshot creation, so that it doesn't compete with API servers for Postgres resources.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::{
+    sync::Mutex,
+    time::{sleep, sleep_until, Instant},
+};
+use zksync_config::SnapshotsCreatorConfig;
+use zksync_dal::ConnectionPool;
+
+use crate::metrics::{ThrottleReason, METRICS};
+
+/// Checks whether the specified UTC hour is within the `[start, end)` window. The window may wrap
+/// around midnight; a window with `start == end` is considered to span the entire day.
+pub(crate) fn is_within_window(hour: u32, (start, end): (u32, u32)) -> bool {
+    match start.cmp(&end) {
+        std::cmp::Ordering::Less => (start..end).contains(&hour),
+        std::cmp::Ordering::Greater => hour >= start || hour < end,
+        std::cmp::Ordering::Equal => true,
+    }
+}
+
+/// Returns the duration until the start of the off-peak window, or `None` if `now` is within the window.
+pub(crate) fn duration_until_window(now: SystemTime, window: (u32, u32)) -> Option<Duration> {
+    const SECS_IN_HOUR: u64 = 3_600;
+    const SECS_IN_DAY: u64 = 24 * SECS_IN_HOUR;
+
+    let secs_since_midnight = now.duration_since(UNIX_EPOCH).ok()?.as_secs() % SECS_IN_DAY;
+    let hour = (secs_since_midnight / SECS_IN_HOUR) as u32;
+    if is_within_window(hour, window) {
+        return None;
+    }
+    let window_start = u64::from(window.0) * SECS_IN_HOUR;
+    let secs_until_start = (window_start + SECS_IN_DAY - secs_since_midnight) % SECS_IN_DAY;
+    Some(Duration::from_secs(secs_until_start))
+}
+
+/// Throttler for snapshot creation tasks. Combines a rate limit on started tasks, an off-peak window,
+/// and backoff based on the replica load.
+#[derive(Debug)]
+pub(crate) struct SnapshotThrottler {
+    min_task_interval: Option<Duration>,
+    next_task_start: Mutex<Instant>,
+    off_peak_window: Option<(u32, u32)>,
+    max_replication_lag_sec: Option<u32>,
+    max_probe_latency: Option<Duration>,
+    backoff_interval: Duration,
+}
+
+impl SnapshotThrottler {
+    pub fn new(config: &SnapshotsCreatorConfig) -> Self {
+        let min_task_interval = config
+            .max_chunks_per_minute
+            .filter(|&count| count > 0)
+            .map(|count| Duration::from_secs(60) / count);
+        Self {
+            min_task_interval,
+            next_task_start: Mutex::new(Instant::now()),
+            off_peak_window: config.off_peak_window(),
+            max_replication_lag_sec: config.max_replication_lag_sec,
+            max_probe_latency: config.max_probe_latency_ms.map(Duration::from_millis),
+            backoff_interval: config.throttle_backoff_interval(),
+        }
+    }
+
+    fn checks_replica_load(&self) -> bool {
+        self.max_replication_lag_sec.is_some() || self.max_probe_latency.is_some()
+    }
+
+    /// Waits until a task querying the replica can be started.
+    pub async fn wait(&self, replica_pool: &ConnectionPool) -> anyhow::Result<()> {
+        self.wait_for_rate_limit().await;
+        loop {
+            if let Some(window) = self.off_peak_window {
+                if let Some(wait_duration) = duration_until_window(SystemTime::now(), window) {
+                    tracing::info!(
+                        "Outside off-peak window {window:?} (UTC hours); \
+                         pausing snapshot creation for {wait_duration:?}"
+                    );
+                    METRICS.throttled_tasks[&ThrottleReason::OffPeakWindow].inc();
+                    sleep(wait_duration).await;
+                    continue;
+                }
+            }
+
+            if self.checks_replica_load() {
+                if let Some(reason) = self.check_replica_load(replica_pool).await? {
+                    tracing::info!(
+                        "Backing off snapshot creation for {:?} because of {reason:?}",
+                        self.backoff_interval
+                    );
+                    METRICS.throttled_tasks[&reason].inc();
+                    sleep(self.backoff_interval).await;
+                    continue;
+                }
+            }
+            return Ok(());
+        }
+    }
+
+    pub async fn wait_for_rate_limit(&self) {
+        let Some(min_task_interval) = self.min_task_interval else {
+            return;
+        };
+        let start = {
+            let mut next_task_start = self.next_task_start.lock().await;
+            let start = (*next_task_start).max(Instant::now());
+            *next_task_start = start + min_task_interval;
+            start
+        };
+        sleep_until(start).await;
+    }
+
+    /// Returns the reason to back off, or `None` if the replica load is acceptable.
+    async fn check_replica_load(
+        &self,
+        replica_pool: &ConnectionPool,
+    ) -> anyhow::Result<Option<ThrottleReason>> {
+        let started_at = Instant::now();
+        let mut conn = replica_pool
+            .access_storage_tagged("snapshots_creator")
+            .await?;
+        let lag_sec = conn.system_dal().get_replication_lag_sec().await;
+        drop(conn);
+        let probe_latency = started_at.elapsed();
+
+        METRICS.replica_lag.set(Duration::from_secs(lag_sec.into()));
+        METRICS.replica_probe_latency.observe(probe_latency);
+        if let Some(max_lag_sec) = self.max_replication_lag_sec {
+            if lag_sec > max_lag_sec {
+                tracing::debug!("Replication lag {lag_sec}s exceeds threshold {max_lag_sec}s");
+                return Ok(Some(ThrottleReason::ReplicationLag));
+            }
+        }
+        if let Some(max_latency) = self.max_probe_latency {
+            if probe_latency > max_latency {
+                tracing::debug!(
+                    "Replica probe latency {probe_latency:?} exceeds threshold {max_latency:?}"
+                );
+                return Ok(Some(ThrottleReason::ProbeLatency));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -1,14 +1,38 @@
 use anyhow::Context as _;
-use zksync_consensus_roles::{node, validator};
-use zksync_core::consensus;
+use zksync_consensus_roles::node;
+use zksync_core::consensus::{
+    self,
+    validator_key::{EnvValidatorKey, FileValidatorKey, ValidatorKeySource},
+};
 
-pub(crate) fn read_consensus_config() -> anyhow::Result<consensus::MainNodeConfig> {
+/// Creates a validator key source based on the `CONSENSUS_VALIDATOR_KEY_SOURCE` env var (`env` or `file`).
+/// If this var is not set, the key is read from the `CONSENSUS_VALIDATOR_KEY` env var.
+fn read_validator_key_source() -> anyhow::Result<Box<dyn ValidatorKeySource>> {
+    let source_kind = std::env::var("CONSENSUS_VALIDATOR_KEY_SOURCE");
+    Ok(match source_kind.as_deref().unwrap_or("env") {
+        "env" => Box::new(EnvValidatorKey {
+            var_name: "CONSENSUS_VALIDATOR_KEY".to_owned(),
+        }),
+        "file" => {
+            let path = std::env::var("CONSENSUS_VALIDATOR_KEY_PATH")
+                .context("CONSENSUS_VALIDATOR_KEY_PATH")?;
+            Box::new(FileValidatorKey { path: path.into() })
+        }
+        other => anyhow::bail!(
+            "unknown CONSENSUS_VALIDATOR_KEY_SOURCE: {other:?}; expected `env` or `file`"
+        ),
+    })
+}
+
+pub(crate) async fn read_consensus_config() -> anyhow::Result<consensus::MainNodeConfig> {
     let path = std::env::var("CONSENSUS_CONFIG_PATH").context("CONSENSUS_CONFIG_PATH")?;
     let cfg = std::fs::read_to_string(&path).context(path)?;
     let cfg: consensus::config::Config =
         consensus::config::decode_json(&cfg).context("failed decoding JSON")?;
-    let validator_key: validator::SecretKey =
-        consensus::config::read_secret("CONSENSUS_VALIDATOR_KEY")?;
+    let validator_key = read_validator_key_source()?
+        .validator_key()
+        .await
+        .context("failed loading validator key")?;
     let node_key: node::SecretKey = consensus::config::read_secret("CONSENSUS_NODE_KEY")?;
     Ok(consensus::MainNodeConfig {
         executor: cfg.executor_config(node_key),
@@ -1,7 +1,7 @@
-use std::{str::FromStr, time::Duration};
+use std::{path::PathBuf, str::FromStr, time::Duration};
 
 use anyhow::Context as _;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use zksync_config::{
     configs::{
         api::{HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig},
@@ -11,18 +11,21 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, KzgConfig,
-        ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
+        BaseTokenFetcherConfig, FriProofCompressorConfig, FriProverConfig,
+        FriWitnessGeneratorConfig, KzgConfig, ObservabilityConfig, PrometheusConfig,
+        ProofDataHandlerConfig, WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     GasAdjusterConfig, ObjectStoreConfig, PostgresConfig,
 };
 use zksync_core::{
-    genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
-    temp_config_store::TempConfigStore, Component, Components,
+    batch_replay::BatchReplayBundle, genesis_init, initialize_components, is_genesis_needed,
+    setup_sigint_handler, temp_config_store::TempConfigStore, Component, Components,
 };
-use zksync_env_config::FromEnv;
+use zksync_dal::ConnectionPool;
+use zksync_env_config::{object_store::ProverObjectStoreConfig, FromEnv};
 use zksync_storage::RocksDB;
+use zksync_types::L1BatchNumber;
 use zksync_utils::wait_for_tasks::wait_for_tasks;
 
 mod config;
@@ -41,6 +44,10 @@ struct Cli {
     /// If `--genesis` is not set, this flag is ignored.
     #[arg(long)]
     set_chain_id: bool,
+    /// Path to a JSON state dump (accounts, balances, contracts and storage slots) to include into genesis
+    /// in addition to system contracts. Used when genesis is performed; ignored otherwise.
+    #[arg(long)]
+    genesis_state_dump: Option<PathBuf>,
     /// Rebuild tree.
     #[arg(long)]
     rebuild_tree: bool,
@@ -50,6 +57,67 @@ struct Cli {
         default_value = "api,tree,eth,state_keeper,housekeeper,basic_witness_input_producer,commitment_generator"
     )]
     components: ComponentsToRun,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+/// Auxiliary commands that are run instead of the server.
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Exports a self-contained replay bundle for a sealed L1 batch from Postgres.
+    ExportBatch {
+        /// Number of the L1 batch to export.
+        l1_batch: u32,
+        /// Path of the output JSON file.
+        #[arg(long, short)]
+        output: PathBuf,
+    },
+    /// Re-executes an L1 batch from a replay bundle without accessing any databases, and prints differences
+    /// with the output recorded in the bundle. Exits with an error if there are any differences.
+    ReplayBatch {
+        /// Path to the bundle produced by the `export-batch` command.
+        bundle: PathBuf,
+    },
+}
+
+async fn run_command(command: Command) -> anyhow::Result<()> {
+    match command {
+        Command::ExportBatch { l1_batch, output } => {
+            let postgres_config = PostgresConfig::from_env().context("PostgresConfig")?;
+            let network_config = NetworkConfig::from_env().context("NetworkConfig")?;
+            let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+                .build()
+                .await
+                .context("failed to build connection pool")?;
+            let bundle = BatchReplayBundle::export(
+                &pool,
+                network_config.zksync_network_id,
+                L1BatchNumber(l1_batch),
+            )
+            .await?;
+            let file = std::fs::File::create(&output)
+                .with_context(|| format!("failed creating bundle file {output:?}"))?;
+            serde_json::to_writer(std::io::BufWriter::new(file), &bundle)
+                .context("failed writing bundle")?;
+            tracing::info!("Exported replay bundle for L1 batch #{l1_batch} to {output:?}");
+        }
+        Command::ReplayBatch { bundle } => {
+            let file = std::fs::File::open(&bundle)
+                .with_context(|| format!("failed opening bundle file {bundle:?}"))?;
+            let bundle: BatchReplayBundle = serde_json::from_reader(std::io::BufReader::new(file))
+                .context("failed reading bundle")?;
+            let l1_batch_number = bundle.l1_batch_number();
+            let report = tokio::task::spawn_blocking(move || bundle.replay())
+                .await
+                .context("replay panicked")??;
+            println!("{report}");
+            anyhow::ensure!(
+                report.is_consistent(),
+                "Replayed L1 batch #{l1_batch_number} diverges from the recorded output"
+            );
+        }
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
@@ -95,6 +163,10 @@ async fn main() -> anyhow::Result<()> {
         tracing::info!("No sentry URL was provided");
     }
 
+    if let Some(command) = opt.command {
+        return run_command(command).await;
+    }
+
     // TODO (QIT-22): Only deserialize configs on demand.
     // Right now, we are trying to deserialize all the configs that may be needed by `zksync_core`.
     // "May" is the key word here, since some configs are only used by certain component configuration,
@@ -125,13 +197,20 @@ async fn main() -> anyhow::Result<()> {
         eth_watch_config: ETHWatchConfig::from_env().ok(),
         gas_adjuster_config: GasAdjusterConfig::from_env().ok(),
         object_store_config: ObjectStoreConfig::from_env().ok(),
+        prover_object_store_config: ProverObjectStoreConfig::from_env()
+            .ok()
+            .map(|config| config.0),
         kzg_config: KzgConfig::from_env().ok(),
+        base_token_fetcher_config: BaseTokenFetcherConfig::from_env().ok(),
         consensus_config: None,
     };
 
     if opt.components.0.contains(&Component::Consensus) {
-        configs.consensus_config =
-            Some(config::read_consensus_config().context("read_consensus_config()")?);
+        configs.consensus_config = Some(
+            config::read_consensus_config()
+                .await
+                .context("read_consensus_config()")?,
+        );
     }
 
     let postgres_config = configs.postgres_config.clone().context("PostgresConfig")?;
@@ -148,6 +227,7 @@ async fn main() -> anyhow::Result<()> {
             &contracts,
             &eth_client.web3_url,
             opt.set_chain_id,
+            opt.genesis_state_dump.as_deref(),
         )
         .await
         .context("genesis_init")?;
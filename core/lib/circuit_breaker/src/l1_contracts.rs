@@ -0,0 +1,160 @@
+use std::{convert::TryFrom, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use zksync_config::configs::chain::CircuitBreakerConfig;
+use zksync_contracts::zksync_contract;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::{CallFunctionArgs, Error as EthClientError, EthInterface};
+use zksync_types::{
+    ethabi::Contract, web3::contract::tokens::Detokenize, Address, ProtocolVersionId, U256,
+};
+
+use crate::{CircuitBreaker, CircuitBreakerError};
+
+/// Checks that the protocol version and the verifier used by the L1 diamond proxy match
+/// the protocol versions known to the node.
+#[derive(Debug)]
+pub struct L1ContractsVersionChecker {
+    pool: ConnectionPool,
+    eth_client: Arc<dyn EthInterface>,
+    diamond_proxy_address: Address,
+    diamond_proxy_abi: Contract,
+    http_req_max_retry_number: usize,
+    http_req_retry_interval: Duration,
+}
+
+impl L1ContractsVersionChecker {
+    pub fn new(
+        pool: ConnectionPool,
+        eth_client: Arc<dyn EthInterface>,
+        diamond_proxy_address: Address,
+        config: &CircuitBreakerConfig,
+    ) -> Self {
+        Self {
+            pool,
+            eth_client,
+            diamond_proxy_address,
+            diamond_proxy_abi: zksync_contract(),
+            http_req_max_retry_number: config.http_req_max_retry_number,
+            http_req_retry_interval: config.http_req_retry_interval(),
+        }
+    }
+
+    async fn call_diamond_proxy<T: Detokenize>(
+        &self,
+        function_name: &str,
+    ) -> Result<T, EthClientError> {
+        let mut attempt = 0;
+        loop {
+            let args = CallFunctionArgs::new(function_name, ())
+                .for_contract(self.diamond_proxy_address, self.diamond_proxy_abi.clone());
+            let result = self.eth_client.call_contract_function(args).await;
+            match result.and_then(|tokens| Ok(T::from_tokens(tokens)?)) {
+                Ok(value) => return Ok(value),
+                Err(err) if attempt < self.http_req_max_retry_number => {
+                    tracing::debug!("Calling `{function_name}` on L1 failed: {err}; retrying");
+                    attempt += 1;
+                    tokio::time::sleep(self.http_req_retry_interval).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    async fn check_inner(&self) -> Result<(), CircuitBreakerError> {
+        let (l1_version, l1_verifier) = match self.fetch_l1_state().await {
+            Ok(state) => state,
+            Err(err) => {
+                // L1 unavailability doesn't indicate whether the contracts match, so the check is indeterminate.
+                return Err(CircuitBreakerError::Indeterminate(format!(
+                    "failed fetching L1 contracts version: {err}"
+                )));
+            }
+        };
+        let l1_version =
+            parse_l1_version(l1_version).map_err(CircuitBreakerError::L1ContractsMismatch)?;
+
+        let mut storage = self
+            .pool
+            .access_storage_tagged("circuit_breaker")
+            .await
+            .context("access_storage()")?;
+        let known_verifier = storage
+            .protocol_versions_dal()
+            .get_protocol_version(l1_version)
+            .await
+            .map(|version| version.verifier_address);
+        drop(storage);
+
+        check_verifier(l1_version, l1_verifier, known_verifier)
+            .map_err(CircuitBreakerError::L1ContractsMismatch)
+    }
+
+    async fn fetch_l1_state(&self) -> Result<(U256, Address), EthClientError> {
+        let l1_version = self.call_diamond_proxy("getProtocolVersion").await?;
+        let l1_verifier = self.call_diamond_proxy("getVerifier").await?;
+        Ok((l1_version, l1_verifier))
+    }
+}
+
+fn parse_l1_version(l1_version: U256) -> Result<ProtocolVersionId, String> {
+    match ProtocolVersionId::try_from(l1_version) {
+        Ok(version) if version <= ProtocolVersionId::latest() => Ok(version),
+        _ => Err(format!(
+            "protocol version {l1_version} used by L1 contracts is not supported"
+        )),
+    }
+}
+
+fn check_verifier(
+    l1_version: ProtocolVersionId,
+    l1_verifier: Address,
+    known_verifier: Option<Address>,
+) -> Result<(), String> {
+    let Some(known_verifier) = known_verifier else {
+        return Err(format!(
+            "protocol version {l1_version:?} used by L1 contracts is not known to the node"
+        ));
+    };
+    if known_verifier != l1_verifier {
+        return Err(format!(
+            "verifier {l1_verifier:?} used by L1 contracts differs from the verifier {known_verifier:?} \
+             expected for protocol version {l1_version:?}"
+        ));
+    }
+    Ok(())
+}
+
+#[async_trait::async_trait]
+impl CircuitBreaker for L1ContractsVersionChecker {
+    async fn check(&self) -> Result<(), CircuitBreakerError> {
+        self.check_inner().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_l1_version() {
+        let latest = ProtocolVersionId::latest();
+        assert_eq!(parse_l1_version((latest as u16).into()).unwrap(), latest);
+        let err = parse_l1_version((latest as u16 + 1).into()).unwrap_err();
+        assert!(err.contains("not supported"), "{err}");
+        let err = parse_l1_version(U256::MAX).unwrap_err();
+        assert!(err.contains("not supported"), "{err}");
+    }
+
+    #[test]
+    fn checking_verifier() {
+        let version = ProtocolVersionId::latest();
+        let verifier = Address::repeat_byte(1);
+        check_verifier(version, verifier, Some(verifier)).unwrap();
+
+        let err = check_verifier(version, verifier, None).unwrap_err();
+        assert!(err.contains("not known"), "{err}");
+        let err = check_verifier(version, verifier, Some(Address::repeat_byte(2))).unwrap_err();
+        assert!(err.contains("differs"), "{err}");
+    }
+}
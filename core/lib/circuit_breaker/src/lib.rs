@@ -6,6 +6,7 @@ use thiserror::Error;
 use tokio::sync::watch;
 use zksync_config::configs::chain::CircuitBreakerConfig;
 
+pub mod l1_contracts;
 pub mod l1_txs;
 pub mod replication_lag;
 pub mod utils;
@@ -16,6 +17,22 @@ pub enum CircuitBreakerError {
     FailedL1Transaction,
     #[error("Replication lag ({0:?}) is above the threshold ({1:?})")]
     ReplicationLag(u32, u32),
+    #[error("L1 contracts don't match the expected protocol version: {0}")]
+    L1ContractsMismatch(String),
+    /// The check couldn't be performed (e.g., because L1 is temporarily unavailable). Such errors are not fatal;
+    /// the outcome of the previous check remains in effect.
+    #[error("Circuit breaker check is indeterminate: {0}")]
+    Indeterminate(String),
+    #[error("Internal error running circuit breaker check")]
+    Internal(#[from] anyhow::Error),
+}
+
+impl CircuitBreakerError {
+    /// Checks whether the node can keep running with the Ethereum sender paused in response to this error
+    /// (provided that the degraded mode is enabled in the config).
+    pub fn allows_degraded_mode(&self) -> bool {
+        matches!(self, Self::L1ContractsMismatch(_))
+    }
 }
 
 /// Checks circuit breakers
@@ -23,6 +40,8 @@ pub enum CircuitBreakerError {
 pub struct CircuitBreakerChecker {
     circuit_breakers: Vec<Box<dyn CircuitBreaker>>,
     sync_interval: Duration,
+    degraded_mode: bool,
+    eth_sender_pause_sender: watch::Sender<bool>,
 }
 
 #[async_trait::async_trait]
@@ -38,12 +57,55 @@ impl CircuitBreakerChecker {
         Self {
             circuit_breakers,
             sync_interval: config.sync_interval(),
+            degraded_mode: config.l1_contracts_mismatch_degraded_mode,
+            eth_sender_pause_sender: watch::channel(false).0,
         }
     }
 
+    /// Returns a receiver for the flag signaling whether the Ethereum sender should be paused.
+    /// The flag is only raised in the degraded mode; otherwise, the checker reports errors via [`Self::run()`].
+    pub fn eth_sender_pause_receiver(&self) -> watch::Receiver<bool> {
+        self.eth_sender_pause_sender.subscribe()
+    }
+
+    /// Runs all circuit breakers. Errors allowing the degraded mode (if it's enabled) are not returned;
+    /// instead, they pause the Ethereum sender until they are resolved. If some checks are indeterminate
+    /// and no other checks pause the Ethereum sender, the pause state is left unchanged.
     pub async fn check(&self) -> Result<(), CircuitBreakerError> {
+        let mut degraded_mode_errors = vec![];
+        let mut is_indeterminate = false;
         for circuit_breaker in &self.circuit_breakers {
-            circuit_breaker.check().await?;
+            match circuit_breaker.check().await {
+                Ok(()) => { /* Everything is fine */ }
+                Err(CircuitBreakerError::Indeterminate(reason)) => {
+                    tracing::warn!(
+                        "Circuit breaker {circuit_breaker:?} is indeterminate: {reason}"
+                    );
+                    is_indeterminate = true;
+                }
+                Err(err) if self.degraded_mode && err.allows_degraded_mode() => {
+                    degraded_mode_errors.push(err);
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        let should_pause = !degraded_mode_errors.is_empty();
+        if !should_pause && is_indeterminate {
+            return Ok(());
+        }
+        let was_paused = self.eth_sender_pause_sender.send_replace(should_pause);
+        metrics::gauge!(
+            "circuit_breaker.eth_sender_paused",
+            if should_pause { 1.0 } else { 0.0 }
+        );
+        if should_pause {
+            tracing::warn!(
+                "Circuit breaker errors {degraded_mode_errors:?} pause Ethereum sender; \
+                 the node continues running in the degraded mode"
+            );
+        } else if was_paused {
+            tracing::info!("Circuit breaker errors are resolved; resuming Ethereum sender");
         }
         Ok(())
     }
@@ -69,3 +131,103 @@ impl CircuitBreakerChecker {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct MockL1ContractsChecker {
+        mismatch: Arc<AtomicBool>,
+        l1_unavailable: Arc<AtomicBool>,
+    }
+
+    #[async_trait::async_trait]
+    impl CircuitBreaker for MockL1ContractsChecker {
+        async fn check(&self) -> Result<(), CircuitBreakerError> {
+            if self.l1_unavailable.load(Ordering::Relaxed) {
+                Err(CircuitBreakerError::Indeterminate(
+                    "L1 is unavailable".into(),
+                ))
+            } else if self.mismatch.load(Ordering::Relaxed) {
+                Err(CircuitBreakerError::L1ContractsMismatch("mock".into()))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    fn mock_config(degraded_mode: bool) -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            sync_interval_ms: 10,
+            http_req_max_retry_number: 0,
+            http_req_retry_interval_sec: 0,
+            replication_lag_limit_sec: None,
+            l1_contracts_version_check: true,
+            l1_contracts_mismatch_degraded_mode: degraded_mode,
+        }
+    }
+
+    #[tokio::test]
+    async fn mismatch_pauses_eth_sender_in_degraded_mode() {
+        let mock = MockL1ContractsChecker::default();
+        let mismatch = mock.mismatch.clone();
+        let checker = CircuitBreakerChecker::new(vec![Box::new(mock)], &mock_config(true));
+        let pause_receiver = checker.eth_sender_pause_receiver();
+
+        checker.check().await.unwrap();
+        assert!(!*pause_receiver.borrow());
+
+        mismatch.store(true, Ordering::Relaxed);
+        checker.check().await.unwrap();
+        assert!(*pause_receiver.borrow());
+
+        mismatch.store(false, Ordering::Relaxed);
+        checker.check().await.unwrap();
+        assert!(!*pause_receiver.borrow());
+    }
+
+    #[tokio::test]
+    async fn l1_unavailability_retains_eth_sender_pause() {
+        let mock = MockL1ContractsChecker::default();
+        let mismatch = mock.mismatch.clone();
+        let l1_unavailable = mock.l1_unavailable.clone();
+        let checker = CircuitBreakerChecker::new(vec![Box::new(mock)], &mock_config(true));
+        let pause_receiver = checker.eth_sender_pause_receiver();
+
+        mismatch.store(true, Ordering::Relaxed);
+        checker.check().await.unwrap();
+        assert!(*pause_receiver.borrow());
+
+        l1_unavailable.store(true, Ordering::Relaxed);
+        checker.check().await.unwrap();
+        assert!(*pause_receiver.borrow());
+
+        // The mismatch is resolved while L1 is unavailable, so the sender remains paused until the next successful check.
+        mismatch.store(false, Ordering::Relaxed);
+        checker.check().await.unwrap();
+        assert!(*pause_receiver.borrow());
+        l1_unavailable.store(false, Ordering::Relaxed);
+        checker.check().await.unwrap();
+        assert!(!*pause_receiver.borrow());
+    }
+
+    #[tokio::test]
+    async fn mismatch_is_fatal_without_degraded_mode() {
+        let mock = MockL1ContractsChecker::default();
+        mock.mismatch.store(true, Ordering::Relaxed);
+        let checker = CircuitBreakerChecker::new(vec![Box::new(mock)], &mock_config(false));
+        let pause_receiver = checker.eth_sender_pause_receiver();
+
+        let err = checker.check().await.unwrap_err();
+        assert_matches!(err, CircuitBreakerError::L1ContractsMismatch(_));
+        assert!(!*pause_receiver.borrow());
+    }
+}
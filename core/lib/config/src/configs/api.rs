@@ -1,7 +1,7 @@
-use std::{net::SocketAddr, num::NonZeroU32, time::Duration};
+use std::{fmt, net::SocketAddr, num::NonZeroU32, str::FromStr, time::Duration};
 
-use serde::Deserialize;
-use zksync_basic_types::H256;
+use serde::{de, Deserialize, Deserializer};
+use zksync_basic_types::{Address, H256};
 
 pub use crate::configs::PrometheusConfig;
 
@@ -96,6 +96,183 @@ pub struct Web3JsonRpcConfig {
     pub websocket_requests_per_minute_limit: Option<NonZeroU32>,
     /// Tree API url, currently used to proxy `getProof` calls to the tree
     pub tree_api_url: Option<String>,
+    /// Path to the directory of a secondary RocksDB instance following the state keeper cache. If set, the VM sandbox
+    /// will read storage for the pending L1 batch from the state keeper cache instead of Postgres. Requires the API server
+    /// to run on the same machine as the state keeper.
+    pub state_keeper_db_replica_path: Option<String>,
+    /// Max number of miniblocks by which the state requested by a VM invocation can be ahead of the state keeper cache
+    /// replica for the replica to be used. Default is 100.
+    pub state_keeper_db_replica_max_lag: Option<u32>,
+    /// Whether to enable the `admin` namespace (e.g., allowing to reload the validation allow-list) for the HTTP API.
+    /// The namespace is disabled by default since it's intended for node operators only.
+    #[serde(default)]
+    pub admin_namespace_enabled: bool,
+    /// Contract addresses for which logs are not served via `eth_getLogs`, `eth_newFilter` and log subscriptions
+    /// (e.g., spam tokens emitting an extreme number of events). Filters explicitly referencing any of these addresses
+    /// are rejected with a structured error.
+    #[serde(default)]
+    pub logs_denylisted_addresses: Vec<Address>,
+    /// Event topics for which logs are not served; works similarly to `logs_denylisted_addresses`.
+    #[serde(default)]
+    pub logs_denylisted_topics: Vec<H256>,
+    /// Whether to optimize the binary search in gas estimation. If enabled, the search bounds are initialized
+    /// based on the gas consumed by the transaction executed with the maximum gas limit, which usually reduces
+    /// the number of VM invocations several times. Disabled by default.
+    #[serde(default)]
+    pub estimate_gas_optimize_search: bool,
+    /// Methods which responses are streamed by the HTTP server using chunked transfer encoding, so that the entire
+    /// serialized response is never held in memory. Supported methods are `eth_getLogs`, `debug_traceBlockByNumber`
+    /// and `debug_traceBlockByHash`. The response body size limit is enforced while a response is streamed;
+    /// if it is exceeded after the first chunk of the response is sent, the response is aborted.
+    #[serde(default)]
+    pub streamed_methods: Vec<String>,
+    /// Whether to include provisional miniblock state roots (as `provisionalStateRoot`) in blocks returned by
+    /// `eth_getBlockBy*` methods. The roots are only available if they are computed by the Merkle tree
+    /// (see `miniblock_root_hashes_enabled` in the Merkle tree config). Disabled by default.
+    #[serde(default)]
+    pub provisional_state_roots_enabled: bool,
+    /// Whether to enable the `trace` namespace (OpenEthereum-style `trace_filter`) for the HTTP API.
+    /// Traces are served from call traces persisted by the state keeper (see `save_call_traces` in the state keeper
+    /// config). Disabled by default.
+    #[serde(default)]
+    pub trace_namespace_enabled: bool,
+    /// Maximum number of miniblocks in a `trace_filter` block range. Default is 100.
+    pub trace_filter_max_block_range: Option<u32>,
+    /// Maximum number of traces returned by a single `trace_filter` call (i.e., the maximum value of the `count`
+    /// filter field). Default is 1000.
+    pub trace_filter_max_traces: Option<usize>,
+    /// View methods served by `eth_call` directly from storage for recognized token contracts (the L2 ETH token
+    /// and tokens bridged via the default bridge), without invoking the VM. Calls to other contracts or with
+    /// non-standard arguments are still executed in the VM. The fast path is disabled by default.
+    #[serde(default)]
+    pub eth_call_fast_path_methods: Vec<EthCallFastPathMethod>,
+    /// Bytecode hashes of bridged token contracts with the standard storage layout (e.g., the beacon proxy deployed
+    /// by the default bridge). The fast path serves calls to a bridged token only if its bytecode hash is in this list;
+    /// calls to other tokens are executed in the VM. If empty, only the L2 ETH token is served via the fast path.
+    #[serde(default)]
+    pub eth_call_fast_path_token_code_hashes: Vec<H256>,
+    /// Maximum gas for sandbox executions performed by the API server (`eth_call`, `eth_estimateGas`,
+    /// `zks_estimateFee`, `debug_traceCall` etc.). Calls explicitly providing more gas are rejected, and gas estimation
+    /// fails if the transaction requires more gas. If not set, executions are only limited by the maximum gas limit
+    /// of a transaction.
+    pub rpc_gas_cap: Option<u32>,
+    /// Gas cap replacing `rpc_gas_cap` for clients authenticated with an API key from `rpc_privileged_api_key_hashes`.
+    /// If not set, sandbox executions for such clients are not capped.
+    pub rpc_privileged_gas_cap: Option<u32>,
+    /// SHA-256 hashes of API keys (provided by clients in the `x-api-key` HTTP header) to which `rpc_privileged_gas_cap`
+    /// applies. Only hashes are stored, so that the keys cannot be recovered from the config.
+    #[serde(default)]
+    pub rpc_privileged_api_key_hashes: Vec<H256>,
+    /// Whether to allow overriding the bootloader and default account bytecodes used by the API sandbox
+    /// via the `admin` namespace. Intended for local development of system contracts only; disabled by default.
+    #[serde(default)]
+    pub system_contracts_override_enabled: bool,
+    /// Address of the operator-controlled paymaster subsidizing fees for sponsored transactions, i.e. transactions
+    /// matching one of `sponsored_calls`. If set, transactions
+    /// using this paymaster are only accepted if they are sponsored and fit into the sponsorship budget
+    /// and the per-account daily cap. If not set, sponsorship is disabled.
+    pub sponsor_paymaster_addr: Option<Address>,
+    /// Calls sponsored by the operator.
+    #[serde(default)]
+    pub sponsored_calls: Vec<SponsoredCall>,
+    /// Total budget of the operator for sponsored transactions (in gwei). If not set, the budget is unlimited.
+    pub sponsorship_budget_gwei: Option<u64>,
+    /// Maximum fees (in gwei) sponsored for transactions initiated by a single account during the last 24 hours.
+    /// If not set, sponsored transactions are not capped per account.
+    pub sponsorship_daily_cap_gwei: Option<u64>,
+    /// Maximum number of transactions in a single `zks_estimateGasBatch` call. Default is 32.
+    pub estimate_gas_batch_max_size: Option<usize>,
+    /// Maximum number of storage keys in a single `eth_getProof` call. Default is 100.
+    pub get_proof_max_keys: Option<usize>,
+    /// Maximum number of transactions in a bundle accepted by `zks_simulateBundle`. If not set, 32 is used.
+    pub simulate_bundle_max_size: Option<usize>,
+    /// Maximum number of cached `eth_estimateGas` / `zks_estimateFee` results. Setting to 0 disables caching.
+    /// If not set, 1,024 is used.
+    pub estimate_gas_cache_size: Option<usize>,
+}
+
+/// 4-byte function selector. Deserialized from a `0x`-prefixed hex string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FunctionSelector(pub [u8; 4]);
+
+impl fmt::Display for FunctionSelector {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "0x{:08x}", u32::from_be_bytes(self.0))
+    }
+}
+
+impl FromStr for FunctionSelector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .strip_prefix("0x")
+            .ok_or_else(|| anyhow::anyhow!("function selector must be `0x`-prefixed"))?;
+        anyhow::ensure!(
+            digits.len() == 8,
+            "function selector must consist of 8 hex digits"
+        );
+        let selector = u32::from_str_radix(digits, 16)?;
+        Ok(Self(selector.to_be_bytes()))
+    }
+}
+
+/// Call sponsored by the operator: either any call to a contract, or a call to a specific function of a contract.
+/// Deserialized from a string in the `0x<contract address>` or `0x<contract address>:0x<function selector>` format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SponsoredCall {
+    pub contract: Address,
+    /// If not set, calls to any function of the contract are sponsored.
+    pub selector: Option<FunctionSelector>,
+}
+
+impl fmt::Display for SponsoredCall {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{:?}", self.contract)?;
+        if let Some(selector) = &self.selector {
+            write!(formatter, ":{selector}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SponsoredCall {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (contract, selector) = match s.split_once(':') {
+            Some((contract, selector)) => (contract, Some(selector.parse()?)),
+            None => (s, None),
+        };
+        let digits = contract
+            .strip_prefix("0x")
+            .ok_or_else(|| anyhow::anyhow!("contract address must be `0x`-prefixed"))?;
+        anyhow::ensure!(
+            digits.len() == 40,
+            "contract address must consist of 40 hex digits"
+        );
+        Ok(Self {
+            contract: digits.parse()?,
+            selector,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for SponsoredCall {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+/// View method that can be served by `eth_call` without invoking the VM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EthCallFastPathMethod {
+    /// ERC-20 `balanceOf(address)`.
+    BalanceOf,
+    /// ERC-20 `allowance(address,address)`. Not supported by the L2 ETH token.
+    Allowance,
 }
 
 impl Web3JsonRpcConfig {
@@ -131,6 +308,31 @@ impl Web3JsonRpcConfig {
             max_response_body_size_mb: Default::default(),
             websocket_requests_per_minute_limit: Default::default(),
             tree_api_url: None,
+            state_keeper_db_replica_path: None,
+            state_keeper_db_replica_max_lag: None,
+            admin_namespace_enabled: false,
+            logs_denylisted_addresses: vec![],
+            logs_denylisted_topics: vec![],
+            estimate_gas_optimize_search: false,
+            streamed_methods: vec![],
+            provisional_state_roots_enabled: false,
+            trace_namespace_enabled: false,
+            trace_filter_max_block_range: None,
+            trace_filter_max_traces: None,
+            eth_call_fast_path_methods: vec![],
+            eth_call_fast_path_token_code_hashes: vec![],
+            rpc_gas_cap: None,
+            rpc_privileged_gas_cap: None,
+            rpc_privileged_api_key_hashes: vec![],
+            system_contracts_override_enabled: false,
+            sponsor_paymaster_addr: None,
+            sponsored_calls: vec![],
+            sponsorship_budget_gwei: None,
+            sponsorship_daily_cap_gwei: None,
+            estimate_gas_batch_max_size: None,
+            get_proof_max_keys: None,
+            simulate_bundle_max_size: None,
+            estimate_gas_cache_size: None,
         }
     }
 
@@ -210,6 +412,34 @@ impl Web3JsonRpcConfig {
     pub fn tree_api_url(&self) -> Option<String> {
         self.tree_api_url.clone()
     }
+
+    pub fn state_keeper_db_replica_max_lag(&self) -> u32 {
+        self.state_keeper_db_replica_max_lag.unwrap_or(100)
+    }
+
+    pub fn trace_filter_max_block_range(&self) -> u32 {
+        self.trace_filter_max_block_range.unwrap_or(100)
+    }
+
+    pub fn trace_filter_max_traces(&self) -> usize {
+        self.trace_filter_max_traces.unwrap_or(1_000)
+    }
+
+    pub fn estimate_gas_batch_max_size(&self) -> usize {
+        self.estimate_gas_batch_max_size.unwrap_or(32)
+    }
+
+    pub fn get_proof_max_keys(&self) -> usize {
+        self.get_proof_max_keys.unwrap_or(100)
+    }
+
+    pub fn simulate_bundle_max_size(&self) -> usize {
+        self.simulate_bundle_max_size.unwrap_or(32)
+    }
+
+    pub fn estimate_gas_cache_size(&self) -> usize {
+        self.estimate_gas_cache_size.unwrap_or(1_024)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -258,6 +488,8 @@ pub struct MerkleTreeApiConfig {
     /// Port to bind the Merkle tree API server to.
     #[serde(default = "MerkleTreeApiConfig::default_port")]
     pub port: u16,
+    /// Port to bind the gRPC Merkle tree API server to. If not set, the gRPC server is not started.
+    pub grpc_port: Option<u16>,
 }
 
 impl MerkleTreeApiConfig {
@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+use zksync_basic_types::Address;
+
+/// Source of the conversion rate between ETH and the base token of the chain.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum BaseTokenPriceSource {
+    /// The base token is ETH, so the conversion rate is always 1:1.
+    #[default]
+    Eth,
+    /// The conversion rate is periodically fetched from an external price API.
+    PriceApi,
+    /// The conversion rate is periodically read from an oracle contract on L1.
+    OnChainOracle,
+    /// The conversion rate is fixed in the config. Mostly useful for testing.
+    Fixed,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct BaseTokenFetcherConfig {
+    /// Source of the conversion rate.
+    #[serde(default)]
+    pub source: BaseTokenPriceSource,
+    /// URL of the price API; required for the `PriceApi` source. The API should respond to `GET` requests
+    /// with a JSON object `{ "numerator": _, "denominator": _ }` specifying the number of base token units
+    /// worth 1 wei as a fraction.
+    pub price_api_url: Option<String>,
+    /// Address of the L1 oracle contract; required for the `OnChainOracle` source. The contract must expose
+    /// a `conversionRatio()` view function returning `(uint256 numerator, uint256 denominator)` with the same meaning
+    /// as for the price API.
+    pub oracle_address: Option<Address>,
+    /// Numerator of the fixed conversion rate; required for the `Fixed` source.
+    pub fixed_numerator: Option<u64>,
+    /// Denominator of the fixed conversion rate; required for the `Fixed` source.
+    pub fixed_denominator: Option<u64>,
+    /// Interval between polling the conversion rate source.
+    #[serde(default = "BaseTokenFetcherConfig::default_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+    /// Conversion rate that wasn't updated for this long is considered stale. Stale rates are still used
+    /// by the fee model, but are reported via logs, metrics and the health check.
+    #[serde(default = "BaseTokenFetcherConfig::default_max_staleness_ms")]
+    pub max_staleness_ms: u64,
+}
+
+impl Default for BaseTokenFetcherConfig {
+    fn default() -> Self {
+        Self {
+            source: BaseTokenPriceSource::Eth,
+            price_api_url: None,
+            oracle_address: None,
+            fixed_numerator: None,
+            fixed_denominator: None,
+            poll_interval_ms: Self::default_poll_interval_ms(),
+            max_staleness_ms: Self::default_max_staleness_ms(),
+        }
+    }
+}
+
+impl BaseTokenFetcherConfig {
+    const fn default_poll_interval_ms() -> u64 {
+        30_000
+    }
+
+    const fn default_max_staleness_ms() -> u64 {
+        300_000
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        Duration::from_millis(self.poll_interval_ms)
+    }
+
+    pub fn max_staleness(&self) -> Duration {
+        Duration::from_millis(self.max_staleness_ms)
+    }
+}
@@ -49,6 +49,11 @@ impl Default for FeeModelVersion {
 pub struct StateKeeperConfig {
     /// The max number of slots for txs in a block before it should be sealed by the slots sealer.
     pub transaction_slots: usize,
+    /// If set, the slots sealer ignores `transaction_slots` and only enforces the bootloader limit on the number
+    /// of transactions, so that L1 batches are limited by the bootloader memory consumed by transaction encodings
+    /// (see `close_block_at_geometry_percentage`) rather than by a fixed number of transactions.
+    #[serde(default)]
+    pub dynamic_transaction_slots: bool,
 
     /// Number of ms after which an L1 batch is going to be unconditionally sealed.
     pub block_commit_deadline_ms: u64,
@@ -99,6 +104,9 @@ pub struct StateKeeperConfig {
     pub max_gas_per_batch: u64,
     /// The maximum amount of pubdata that can be used by the batch. Note that if the calldata is used as pubdata, this variable should not exceed 128kb.
     pub max_pubdata_per_batch: u64,
+    /// The maximum amount of pubdata that can be used by the batch published via blobs. If not set or exceeding
+    /// the total capacity of blobs in a batch, the blob capacity is used.
+    pub max_pubdata_per_batch_with_blobs: Option<u64>,
 
     /// The version of the fee model to use.
     pub fee_model_version: FeeModelVersion,
@@ -116,6 +124,56 @@ pub struct StateKeeperConfig {
 
     /// Number of keys that is processed by enum_index migration in State Keeper each L1 batch.
     pub enum_index_migration_chunk_size: Option<usize>,
+
+    /// Initiators of L2 transactions that are rejected by the state keeper.
+    #[serde(default)]
+    pub denylisted_tx_initiators: Vec<Address>,
+    /// Max size of L2 transaction calldata in bytes; transactions with larger calldata are rejected by the state keeper.
+    pub max_tx_calldata_size: Option<usize>,
+    /// Wall-clock budget for executing a single transaction in the state keeper, in milliseconds. Transactions
+    /// exceeding it are rejected as unexecutable. The budget is not applied when re-executing transactions
+    /// that are already included into a block.
+    pub tx_execution_timeout_ms: Option<u64>,
+
+    /// Chain-specific override of the intrinsic gas of L2 transactions used to validate transactions in the API server.
+    /// Must not be less than the intrinsic gas charged by the bootloader. Priority operations are always validated
+    /// using the default constants, since they are validated by the L1 contracts as well.
+    pub l2_tx_intrinsic_gas_override: Option<u32>,
+
+    /// If set, the L1 state checker stops the node (including the state keeper) once it detects an L1 batch
+    /// executed on L1 that doesn't match the locally computed state root or commitment. Otherwise, the mismatch
+    /// is only reported via logs, metrics and the health check.
+    #[serde(default)]
+    pub halt_on_l1_state_mismatch: bool,
+    /// If set, L1 batches around protocol upgrades are additionally executed in the state keeper with the VM
+    /// of the adjacent protocol version, and divergences between the VMs are logged. The value is the number
+    /// of L1 batches after an upgrade to shadow; all batches are shadowed while an upcoming protocol version
+    /// with a different VM version is known to the node.
+    pub upgrade_shadow_execution_batches: Option<u32>,
+    /// Path to the local write-ahead log (a RocksDB instance) for miniblocks. If set, the state keeper continues
+    /// sealing miniblocks while Postgres is unavailable: sealed miniblocks are buffered in the log and are persisted
+    /// to Postgres once it becomes available again. Buffering requires asynchronous miniblock sealing
+    /// (i.e., non-zero `miniblock_seal_queue_capacity`); L1 batches are not sealed until all buffered miniblocks are persisted.
+    pub miniblock_seal_wal_path: Option<String>,
+    /// Maximum number of miniblocks buffered in the write-ahead log. Once reached, the state keeper blocks
+    /// until Postgres becomes available. If not set, 100 miniblocks are buffered at most.
+    pub miniblock_seal_wal_max_miniblocks: Option<usize>,
+
+    /// Path to a JSON file with an emergency override of the batch fee input (fair L2 gas price and / or
+    /// fair pubdata price) applied by the state keeper and the API server. The file is read on node startup
+    /// and re-read each time the node receives `SIGUSR1`; removing the file (or leaving it empty) and sending
+    /// the signal resets the override. If not set, the batch fee input cannot be overridden.
+    pub fee_input_override_path: Option<String>,
+    /// Maximum fair L2 gas price that can be set by the fee input override. If not set, the price is not bounded
+    /// from above. The price is always bounded from below by `minimal_l2_gas_price`.
+    pub fee_input_override_max_fair_l2_gas_price: Option<u64>,
+    /// Maximum fair pubdata price that can be set by the fee input override. If not set, the price is not bounded.
+    pub fee_input_override_max_fair_pubdata_price: Option<u64>,
+
+    /// Maximum estimated size of basic witness inputs for an L1 batch (Merkle paths, witness block state etc.).
+    /// If set, L1 batches are sealed before the estimate exceeds this limit, so that the inputs can be handled
+    /// by the object store and the prover. The estimate is conservative, i.e., it's expected to exceed the actual size.
+    pub max_witness_input_size_bytes: Option<u64>,
 }
 
 impl StateKeeperConfig {
@@ -124,6 +182,7 @@ impl StateKeeperConfig {
     pub fn for_tests() -> Self {
         Self {
             transaction_slots: 250,
+            dynamic_transaction_slots: false,
             block_commit_deadline_ms: 2500,
             miniblock_commit_deadline_ms: 1000,
             miniblock_seal_queue_capacity: 10,
@@ -142,6 +201,7 @@ impl StateKeeperConfig {
             batch_overhead_l1_gas: 800_000,
             max_gas_per_batch: 200_000_000,
             max_pubdata_per_batch: 100_000,
+            max_pubdata_per_batch_with_blobs: None,
             minimal_l2_gas_price: 100000000,
             fee_model_version: FeeModelVersion::V2,
             validation_computational_gas_limit: 300000,
@@ -150,12 +210,32 @@ impl StateKeeperConfig {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: None,
+            denylisted_tx_initiators: vec![],
+            max_tx_calldata_size: None,
+            tx_execution_timeout_ms: None,
+            l2_tx_intrinsic_gas_override: None,
+            halt_on_l1_state_mismatch: false,
+            upgrade_shadow_execution_batches: None,
+            miniblock_seal_wal_path: None,
+            miniblock_seal_wal_max_miniblocks: None,
+            fee_input_override_path: None,
+            fee_input_override_max_fair_l2_gas_price: None,
+            fee_input_override_max_fair_pubdata_price: None,
+            max_witness_input_size_bytes: None,
         }
     }
 
     pub fn enum_index_migration_chunk_size(&self) -> usize {
         self.enum_index_migration_chunk_size.unwrap_or(1_000)
     }
+
+    pub fn miniblock_seal_wal_max_miniblocks(&self) -> usize {
+        self.miniblock_seal_wal_max_miniblocks.unwrap_or(100)
+    }
+
+    pub fn tx_execution_timeout(&self) -> Option<Duration> {
+        self.tx_execution_timeout_ms.map(Duration::from_millis)
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -176,6 +256,15 @@ pub struct CircuitBreakerConfig {
     pub http_req_max_retry_number: usize,
     pub http_req_retry_interval_sec: u8,
     pub replication_lag_limit_sec: Option<u32>,
+    /// Whether to check that the protocol version and the verifier used by the L1 contracts
+    /// are known to the node.
+    #[serde(default)]
+    pub l1_contracts_version_check: bool,
+    /// If set, a mismatch between the L1 contracts and the protocol version expected by the node
+    /// pauses the Ethereum sender instead of stopping the entire node. The API and the state keeper
+    /// keep running in this degraded mode until the mismatch is resolved.
+    #[serde(default)]
+    pub l1_contracts_mismatch_degraded_mode: bool,
 }
 
 impl CircuitBreakerConfig {
@@ -196,6 +285,13 @@ pub struct MempoolConfig {
     pub stuck_tx_timeout: u64,
     pub remove_stuck_txs: bool,
     pub delay_interval: u64,
+    /// Whether to order L2 transactions from different accounts by their effective priority fee at the current
+    /// base fee instead of the time they were received. Transactions from the same account are always ordered by nonce.
+    #[serde(default)]
+    pub fee_priority_ordering: bool,
+    /// If fee priority ordering is enabled, transactions waiting in the mempool for this interval or longer
+    /// are prioritized regardless of their fees to prevent starvation. If not set, 60 seconds is used.
+    pub fee_priority_max_wait_ms: Option<u64>,
 }
 
 impl MempoolConfig {
@@ -210,4 +306,8 @@ impl MempoolConfig {
     pub fn delay_interval(&self) -> Duration {
         Duration::from_millis(self.delay_interval)
     }
+
+    pub fn fee_priority_max_wait(&self) -> Duration {
+        Duration::from_millis(self.fee_priority_max_wait_ms.unwrap_or(60_000))
+    }
 }
@@ -44,6 +44,11 @@ pub struct MerkleTreeConfig {
     /// Maximum number of L1 batches to be processed by the Merkle tree at a time.
     #[serde(default = "MerkleTreeConfig::default_max_l1_batches_per_iter")]
     pub max_l1_batches_per_iter: usize,
+    /// Whether to compute provisional state root hashes for each miniblock in addition to L1 batch root hashes.
+    /// Root hashes for miniblocks in the open L1 batch are computed as soon as miniblocks are sealed, and are recomputed
+    /// once the batch is sealed. This roughly doubles the tree update workload. Disabled by default.
+    #[serde(default)]
+    pub miniblock_root_hashes_enabled: bool,
 }
 
 impl Default for MerkleTreeConfig {
@@ -56,6 +61,7 @@ impl Default for MerkleTreeConfig {
             memtable_capacity_mb: Self::default_memtable_capacity_mb(),
             stalled_writes_timeout_sec: Self::default_stalled_writes_timeout_sec(),
             max_l1_batches_per_iter: Self::default_max_l1_batches_per_iter(),
+            miniblock_root_hashes_enabled: false,
         }
     }
 }
@@ -144,6 +150,13 @@ pub struct PostgresConfig {
     pub long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
     pub slow_query_threshold_ms: Option<u64>,
+    /// URLs of additional read replicas. If specified, read-only connections used by the API servers
+    /// are distributed among these replicas, falling back to `replica_url` if all of them are stale.
+    /// Connections used to handle a single API call are acquired from the same replica. The API connection pool size
+    /// is split evenly among replicas.
+    pub read_replica_urls: Vec<String>,
+    /// Maximum replication lag in seconds for a read replica to receive traffic. Defaults to 5 seconds.
+    pub max_replica_lag_sec: Option<u64>,
 }
 
 impl PostgresConfig {
@@ -190,4 +203,12 @@ impl PostgresConfig {
     pub fn slow_query_threshold(&self) -> Option<Duration> {
         self.slow_query_threshold_ms.map(Duration::from_millis)
     }
+
+    /// Returns the maximum replication lag for read replicas.
+    pub fn max_replica_lag(&self) -> Duration {
+        const DEFAULT_MAX_REPLICA_LAG: Duration = Duration::from_secs(5);
+
+        self.max_replica_lag_sec
+            .map_or(DEFAULT_MAX_REPLICA_LAG, Duration::from_secs)
+    }
 }
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use serde::Deserialize;
-use zksync_basic_types::H256;
+use zksync_basic_types::{Address, H256};
 
 /// Configuration for the Ethereum sender crate.
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -10,6 +10,12 @@ pub struct ETHSenderConfig {
     pub sender: SenderConfig,
     /// Options related to the `GasAdjuster` submodule.
     pub gas_adjuster: GasAdjusterConfig,
+    /// External signer for operator transactions. If not set, transactions are signed using
+    /// the operator private keys.
+    pub remote_signer: Option<RemoteSignerConfig>,
+    /// Private transaction relay for operator transactions. If not set, transactions are sent
+    /// to the public mempool via the L1 client.
+    pub tx_relay: Option<TxRelayConfig>,
 }
 
 impl ETHSenderConfig {
@@ -36,6 +42,11 @@ impl ETHSenderConfig {
                 max_acceptable_priority_fee_in_gwei: 100000000000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
                 pubdata_sending_mode: PubdataSendingMode::Calldata,
+                dynamic_aggregation_windows: false,
+                send_gate_max_base_fee_in_gwei: None,
+                send_gate_max_blob_base_fee_in_gwei: None,
+                send_gate_max_delay_seconds: None,
+                kzg_precomputation_workers: None,
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 1000000000,
@@ -50,6 +61,8 @@ impl ETHSenderConfig {
                 internal_pubdata_pricing_multiplier: 1.0,
                 max_blob_base_fee: None,
             },
+            remote_signer: None,
+            tx_relay: None,
         }
     }
 }
@@ -110,6 +123,22 @@ pub struct SenderConfig {
 
     /// The mode in which we send pubdata, either Calldata or Blobs
     pub pubdata_sending_mode: PubdataSendingMode,
+    /// If set, prove and execute aggregation windows are sized dynamically: ready L1 batches are published
+    /// as soon as their cost per batch at the current L1 gas price doesn't exceed the cost per batch
+    /// of a full window at the median L1 gas price. The deadlines still bound the publishing delay.
+    #[serde(default)]
+    pub dynamic_aggregation_windows: bool,
+    /// If set, commit and execute operations are withheld while the L1 base fee exceeds this value.
+    pub send_gate_max_base_fee_in_gwei: Option<u64>,
+    /// If set, commit operations with pubdata sent in blobs are withheld while the L1 blob base fee exceeds this value.
+    pub send_gate_max_blob_base_fee_in_gwei: Option<u64>,
+    /// Maximum time an operation can be withheld because of high L1 fees, measured from the moment
+    /// it was first withheld. Once exceeded, the operation is sent regardless of fees.
+    /// If not set, operations are withheld until fees drop below the thresholds.
+    pub send_gate_max_delay_seconds: Option<u64>,
+    /// Number of workers computing KZG info for L1 batch pubdata as soon as the pubdata is available, so that it's
+    /// cached by the time the batches are committed. If not set, KZG info is computed when forming commit operations.
+    pub kzg_precomputation_workers: Option<usize>,
 }
 
 impl SenderConfig {
@@ -123,6 +152,17 @@ impl SenderConfig {
         Duration::from_secs(self.aggregate_tx_poll_period)
     }
 
+    /// Returns whether sending operations is gated by L1 fees.
+    pub fn is_send_gated(&self) -> bool {
+        self.send_gate_max_base_fee_in_gwei.is_some()
+            || self.send_gate_max_blob_base_fee_in_gwei.is_some()
+    }
+
+    /// Converts `self.send_gate_max_delay_seconds` into `Duration`.
+    pub fn send_gate_max_delay(&self) -> Option<Duration> {
+        self.send_gate_max_delay_seconds.map(Duration::from_secs)
+    }
+
     // Don't load private key, if it's not required.
     pub fn private_key(&self) -> Option<H256> {
         std::env::var("ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY")
@@ -138,6 +178,60 @@ impl SenderConfig {
     }
 }
 
+/// Configuration of an external service (e.g., an HSM or a KMS accessible over HTTP) signing operator transactions
+/// instead of an in-process private key.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct RemoteSignerConfig {
+    /// URL of the signer service.
+    pub url: String,
+    /// Address of the operator account managed by the signer service.
+    pub operator_address: Address,
+    /// Address of the operator account used for blob transactions managed by the signer service.
+    pub operator_blobs_address: Option<Address>,
+    /// Timeout for a single request to the signer service in milliseconds.
+    #[serde(default = "RemoteSignerConfig::default_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+    /// Maximum number of retries for a failed request to the signer service.
+    #[serde(default = "RemoteSignerConfig::default_max_retries")]
+    pub max_retries: u32,
+}
+
+impl RemoteSignerConfig {
+    const fn default_request_timeout_ms() -> u64 {
+        5_000
+    }
+
+    const fn default_max_retries() -> u32 {
+        3
+    }
+
+    pub fn request_timeout(&self) -> Duration {
+        Duration::from_millis(self.request_timeout_ms)
+    }
+}
+
+/// Configuration of a private transaction relay (e.g., a Flashbots-style RPC endpoint) that operator transactions
+/// are sent to instead of the public mempool, so that they cannot be front-run.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct TxRelayConfig {
+    /// URL of the relay RPC endpoint accepting `eth_sendRawTransaction` requests.
+    pub url: String,
+    /// Time since the first sending attempt of a transaction in seconds after which its attempts are sent
+    /// to the public mempool. Attempts are also sent to the public mempool if the relay request fails.
+    #[serde(default = "TxRelayConfig::default_public_fallback_timeout_sec")]
+    pub public_fallback_timeout_sec: u64,
+}
+
+impl TxRelayConfig {
+    const fn default_public_fallback_timeout_sec() -> u64 {
+        120
+    }
+
+    pub fn public_fallback_timeout(&self) -> Duration {
+        Duration::from_secs(self.public_fallback_timeout_sec)
+    }
+}
+
 #[derive(Debug, Deserialize, Copy, Clone, PartialEq)]
 pub struct GasAdjusterConfig {
     /// Priority Fee to be used by GasAdjuster
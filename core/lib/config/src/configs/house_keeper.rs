@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 /// Configuration for the house keeper.
@@ -16,4 +18,50 @@ pub struct HouseKeeperConfig {
     pub fri_prover_stats_reporting_interval_ms: u64,
     pub fri_proof_compressor_job_retrying_interval_ms: u64,
     pub fri_proof_compressor_stats_reporting_interval_ms: u64,
+    /// Interval between checks whether a daily L1 batch utilization report should be generated.
+    #[serde(default = "HouseKeeperConfig::default_utilization_report_generation_interval_ms")]
+    pub utilization_report_generation_interval_ms: u64,
+    /// Interval between garbage collection runs for prover artifacts.
+    #[serde(default = "HouseKeeperConfig::default_prover_artifacts_gc_interval_ms")]
+    pub prover_artifacts_gc_interval_ms: u64,
+    /// Minimum time since the proof of an L1 batch was verified on L1 before intermediate prover artifacts
+    /// (witness inputs, circuits, intermediate proofs etc.) for the batch are removed from the prover object store.
+    /// If not set, prover artifacts are never removed.
+    pub prover_artifacts_retention_secs: Option<u64>,
+    /// If set, prover artifacts garbage collection only reports the reclaimable space without removing artifacts.
+    #[serde(default)]
+    pub prover_artifacts_gc_dry_run: bool,
+    /// Interval between garbage collection runs for factory deps.
+    #[serde(default = "HouseKeeperConfig::default_factory_deps_gc_interval_ms")]
+    pub factory_deps_gc_interval_ms: u64,
+    /// Minimum time since an L1 batch was executed on L1 before factory deps inserted in the batch are removed
+    /// from Postgres if they were never marked as known (e.g., because the transaction publishing them has failed).
+    /// If not set, factory deps are never removed.
+    pub factory_deps_retention_secs: Option<u64>,
+    /// If set, factory deps garbage collection only reports the reclaimable space without removing factory deps.
+    #[serde(default)]
+    pub factory_deps_gc_dry_run: bool,
+}
+
+impl HouseKeeperConfig {
+    pub const fn default_utilization_report_generation_interval_ms() -> u64 {
+        3_600_000
+    }
+
+    pub const fn default_prover_artifacts_gc_interval_ms() -> u64 {
+        600_000
+    }
+
+    pub const fn default_factory_deps_gc_interval_ms() -> u64 {
+        3_600_000
+    }
+
+    pub fn prover_artifacts_retention(&self) -> Option<Duration> {
+        self.prover_artifacts_retention_secs
+            .map(Duration::from_secs)
+    }
+
+    pub fn factory_deps_retention(&self) -> Option<Duration> {
+        self.factory_deps_retention_secs.map(Duration::from_secs)
+    }
 }
@@ -2,6 +2,7 @@
 pub use self::{
     alerts::AlertsConfig,
     api::ApiConfig,
+    base_token_fetcher::BaseTokenFetcherConfig,
     contract_verifier::ContractVerifierConfig,
     contracts::ContractsConfig,
     database::{DBConfig, PostgresConfig},
@@ -24,6 +25,7 @@ pub use self::{
 
 pub mod alerts;
 pub mod api;
+pub mod base_token_fetcher;
 pub mod chain;
 pub mod contract_verifier;
 pub mod contracts;
@@ -31,4 +31,16 @@ pub enum ObjectStoreMode {
     FileBacked {
         file_backed_base_path: String,
     },
+    /// S3-compatible storage (e.g., AWS S3, MinIO or Cloudflare R2). `bucket_base_url` is the bucket name.
+    /// If `s3_credential_file_path` is not specified, credentials are obtained using the default AWS credentials chain
+    /// (e.g., from the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN` env variables).
+    S3 {
+        bucket_base_url: String,
+        /// Endpoint URL of the service, e.g. `https://s3.us-east-1.amazonaws.com`.
+        s3_endpoint: String,
+        s3_region: String,
+        /// Path to a JSON file with `access_key_id`, `secret_access_key` and optional `session_token` fields.
+        /// The file is periodically re-read, so that credentials can be rotated.
+        s3_credential_file_path: Option<String>,
+    },
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 #[derive(Debug, Clone, PartialEq, Deserialize)]
@@ -7,6 +9,62 @@ pub struct SnapshotsCreatorConfig {
 
     #[serde(default = "snapshots_creator_concurrent_queries_count")]
     pub concurrent_queries_count: u32,
+
+    /// Version of snapshots to create. Version 0 snapshots store gzip-compressed factory deps;
+    /// version 1 snapshots store zstd-compressed factory deps.
+    #[serde(default)]
+    pub version: u16,
+
+    /// zstd compression level for factory deps. Only used when creating version 1 snapshots. Defaults to 3.
+    #[serde(default)]
+    pub factory_deps_compression_level: Option<i32>,
+
+    /// Maximum number of storage log chunks started per minute. If not set, chunks are only limited
+    /// by `concurrent_queries_count`.
+    #[serde(default)]
+    pub max_chunks_per_minute: Option<u32>,
+    /// Start hour (UTC, 0..=23) of the off-peak window. If both window bounds are set, snapshot data
+    /// is only loaded from Postgres within the window; outside it, snapshot creation is paused.
+    /// The window may wrap around midnight (e.g., 22..6).
+    #[serde(default)]
+    pub off_peak_start_hour_utc: Option<u32>,
+    /// End hour (UTC, 0..=23, exclusive) of the off-peak window.
+    #[serde(default)]
+    pub off_peak_end_hour_utc: Option<u32>,
+    /// If the replica lags behind the master by more than this number of seconds, snapshot creation
+    /// backs off until the lag recovers.
+    #[serde(default)]
+    pub max_replication_lag_sec: Option<u32>,
+    /// If a lightweight probe query to the replica takes longer than this number of milliseconds,
+    /// snapshot creation backs off. Since API servers read from the same replica, this is a proxy
+    /// for the API latency.
+    #[serde(default)]
+    pub max_probe_latency_ms: Option<u64>,
+    /// Interval between load checks while snapshot creation backs off. Defaults to 10 seconds.
+    #[serde(default)]
+    pub throttle_backoff_interval_ms: Option<u64>,
+}
+
+impl SnapshotsCreatorConfig {
+    const DEFAULT_THROTTLE_BACKOFF_INTERVAL: Duration = Duration::from_secs(10);
+    const DEFAULT_FACTORY_DEPS_COMPRESSION_LEVEL: i32 = 3;
+
+    /// Returns the off-peak window as `(start_hour, end_hour)` if both bounds are configured.
+    pub fn off_peak_window(&self) -> Option<(u32, u32)> {
+        self.off_peak_start_hour_utc.zip(self.off_peak_end_hour_utc)
+    }
+
+    pub fn factory_deps_compression_level(&self) -> i32 {
+        self.factory_deps_compression_level
+            .unwrap_or(Self::DEFAULT_FACTORY_DEPS_COMPRESSION_LEVEL)
+    }
+
+    pub fn throttle_backoff_interval(&self) -> Duration {
+        self.throttle_backoff_interval_ms.map_or(
+            Self::DEFAULT_THROTTLE_BACKOFF_INTERVAL,
+            Duration::from_millis,
+        )
+    }
 }
 
 fn snapshots_creator_storage_logs_chunk_size_default() -> u64 {
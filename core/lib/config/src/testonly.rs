@@ -204,6 +204,31 @@ impl RandomConfig for configs::api::Web3JsonRpcConfig {
             max_response_body_size_mb: g.gen(),
             websocket_requests_per_minute_limit: g.gen(),
             tree_api_url: g.gen(),
+            state_keeper_db_replica_path: g.gen(),
+            state_keeper_db_replica_max_lag: g.gen(),
+            admin_namespace_enabled: g.gen(),
+            logs_denylisted_addresses: g.gen(),
+            logs_denylisted_topics: g.gen(),
+            estimate_gas_optimize_search: g.gen(),
+            streamed_methods: g.gen(),
+            provisional_state_roots_enabled: g.gen(),
+            trace_namespace_enabled: g.gen(),
+            trace_filter_max_block_range: g.gen(),
+            trace_filter_max_traces: g.gen(),
+            eth_call_fast_path_methods: g.gen(),
+            eth_call_fast_path_token_code_hashes: g.gen(),
+            rpc_gas_cap: g.gen(),
+            rpc_privileged_gas_cap: g.gen(),
+            rpc_privileged_api_key_hashes: g.gen(),
+            system_contracts_override_enabled: g.gen(),
+            sponsor_paymaster_addr: g.gen(),
+            sponsored_calls: g.gen(),
+            sponsorship_budget_gwei: g.gen(),
+            sponsorship_daily_cap_gwei: g.gen(),
+            estimate_gas_batch_max_size: g.gen(),
+            get_proof_max_keys: g.gen(),
+            simulate_bundle_max_size: g.gen(),
+            estimate_gas_cache_size: g.gen(),
         }
     }
 }
@@ -229,7 +254,10 @@ impl RandomConfig for configs::api::ContractVerificationApiConfig {
 
 impl RandomConfig for configs::api::MerkleTreeApiConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
-        Self { port: g.gen() }
+        Self {
+            port: g.gen(),
+            grpc_port: g.gen(),
+        }
     }
 }
 
@@ -257,6 +285,7 @@ impl RandomConfig for configs::chain::StateKeeperConfig {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         Self {
             transaction_slots: g.gen(),
+            dynamic_transaction_slots: g.gen(),
             block_commit_deadline_ms: g.gen(),
             miniblock_commit_deadline_ms: g.gen(),
             miniblock_seal_queue_capacity: g.gen(),
@@ -275,6 +304,7 @@ impl RandomConfig for configs::chain::StateKeeperConfig {
             batch_overhead_l1_gas: g.gen(),
             max_gas_per_batch: g.gen(),
             max_pubdata_per_batch: g.gen(),
+            max_pubdata_per_batch_with_blobs: g.gen(),
             fee_model_version: g.gen(),
             validation_computational_gas_limit: g.gen(),
             save_call_traces: g.gen(),
@@ -282,6 +312,18 @@ impl RandomConfig for configs::chain::StateKeeperConfig {
             virtual_blocks_per_miniblock: g.gen(),
             upload_witness_inputs_to_gcs: g.gen(),
             enum_index_migration_chunk_size: g.gen(),
+            denylisted_tx_initiators: g.gen(),
+            max_tx_calldata_size: g.gen(),
+            tx_execution_timeout_ms: g.gen(),
+            l2_tx_intrinsic_gas_override: g.gen(),
+            halt_on_l1_state_mismatch: g.gen(),
+            upgrade_shadow_execution_batches: g.gen(),
+            miniblock_seal_wal_path: g.gen(),
+            miniblock_seal_wal_max_miniblocks: g.gen(),
+            fee_input_override_path: g.gen(),
+            fee_input_override_max_fair_l2_gas_price: g.gen(),
+            fee_input_override_max_fair_pubdata_price: g.gen(),
+            max_witness_input_size_bytes: g.gen(),
         }
     }
 }
@@ -301,6 +343,8 @@ impl RandomConfig for configs::chain::CircuitBreakerConfig {
             http_req_max_retry_number: g.gen(),
             http_req_retry_interval_sec: g.gen(),
             replication_lag_limit_sec: g.gen(),
+            l1_contracts_version_check: g.gen(),
+            l1_contracts_mismatch_degraded_mode: g.gen(),
         }
     }
 }
@@ -314,6 +358,8 @@ impl RandomConfig for configs::chain::MempoolConfig {
             stuck_tx_timeout: g.gen(),
             remove_stuck_txs: g.gen(),
             delay_interval: g.gen(),
+            fee_priority_ordering: g.gen(),
+            fee_priority_max_wait_ms: g.gen(),
         }
     }
 }
@@ -377,6 +423,27 @@ impl RandomConfig for configs::ContractsConfig {
     }
 }
 
+impl RandomConfig for configs::api::EthCallFastPathMethod {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        match g.rng.gen_range(0..2) {
+            0 => Self::BalanceOf,
+            _ => Self::Allowance,
+        }
+    }
+}
+
+impl RandomConfig for configs::api::SponsoredCall {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            contract: g.rng.gen(),
+            selector: g
+                .rng
+                .gen::<Option<[u8; 4]>>()
+                .map(configs::api::FunctionSelector),
+        }
+    }
+}
+
 impl RandomConfig for configs::database::MerkleTreeMode {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
         match g.rng.gen_range(0..2) {
@@ -396,6 +463,7 @@ impl RandomConfig for configs::database::MerkleTreeConfig {
             memtable_capacity_mb: g.gen(),
             stalled_writes_timeout_sec: g.gen(),
             max_l1_batches_per_iter: g.gen(),
+            miniblock_root_hashes_enabled: g.gen(),
         }
     }
 }
@@ -420,6 +488,8 @@ impl RandomConfig for configs::database::PostgresConfig {
             statement_timeout_sec: g.gen(),
             long_connection_threshold_ms: g.gen(),
             slow_query_threshold_ms: g.gen(),
+            read_replica_urls: g.gen(),
+            max_replica_lag_sec: g.gen(),
         }
     }
 }
@@ -438,6 +508,29 @@ impl RandomConfig for configs::ETHSenderConfig {
         Self {
             sender: g.gen(),
             gas_adjuster: g.gen(),
+            remote_signer: g.gen(),
+            tx_relay: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::TxRelayConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            url: g.gen(),
+            public_fallback_timeout_sec: g.gen(),
+        }
+    }
+}
+
+impl RandomConfig for configs::eth_sender::RemoteSignerConfig {
+    fn sample(g: &mut Gen<impl Rng>) -> Self {
+        Self {
+            url: g.gen(),
+            operator_address: g.gen(),
+            operator_blobs_address: g.gen(),
+            request_timeout_ms: g.gen(),
+            max_retries: g.gen(),
         }
     }
 }
@@ -491,6 +584,11 @@ impl RandomConfig for configs::eth_sender::SenderConfig {
             max_acceptable_priority_fee_in_gwei: g.gen(),
             proof_loading_mode: g.gen(),
             pubdata_sending_mode: PubdataSendingMode::Calldata,
+            dynamic_aggregation_windows: g.gen(),
+            send_gate_max_base_fee_in_gwei: g.gen(),
+            send_gate_max_blob_base_fee_in_gwei: g.gen(),
+            send_gate_max_delay_seconds: g.gen(),
+            kzg_precomputation_workers: g.gen(),
         }
     }
 }
@@ -652,13 +750,20 @@ impl RandomConfig for configs::house_keeper::HouseKeeperConfig {
             fri_prover_stats_reporting_interval_ms: g.gen(),
             fri_proof_compressor_job_retrying_interval_ms: g.gen(),
             fri_proof_compressor_stats_reporting_interval_ms: g.gen(),
+            utilization_report_generation_interval_ms: g.gen(),
+            prover_artifacts_gc_interval_ms: g.gen(),
+            prover_artifacts_retention_secs: g.gen(),
+            prover_artifacts_gc_dry_run: g.gen(),
+            factory_deps_gc_interval_ms: g.gen(),
+            factory_deps_retention_secs: g.gen(),
+            factory_deps_gc_dry_run: g.gen(),
         }
     }
 }
 
 impl RandomConfig for configs::object_store::ObjectStoreMode {
     fn sample(g: &mut Gen<impl Rng>) -> Self {
-        match g.rng.gen_range(0..4) {
+        match g.rng.gen_range(0..5) {
             0 => Self::GCS {
                 bucket_base_url: g.gen(),
             },
@@ -669,6 +774,12 @@ impl RandomConfig for configs::object_store::ObjectStoreMode {
             2 => Self::FileBacked {
                 file_backed_base_path: g.gen(),
             },
+            3 => Self::S3 {
+                bucket_base_url: g.gen(),
+                s3_endpoint: g.gen(),
+                s3_region: g.gen(),
+                s3_credential_file_path: g.gen(),
+            },
             _ => Self::GCSAnonymousReadOnly {
                 bucket_base_url: g.gen(),
             },
@@ -710,6 +821,14 @@ impl RandomConfig for configs::SnapshotsCreatorConfig {
         Self {
             storage_logs_chunk_size: g.gen(),
             concurrent_queries_count: g.gen(),
+            version: g.gen(),
+            factory_deps_compression_level: g.gen(),
+            max_chunks_per_minute: g.gen(),
+            off_peak_start_hour_utc: g.gen(),
+            off_peak_end_hour_utc: g.gen(),
+            max_replication_lag_sec: g.gen(),
+            max_probe_latency_ms: g.gen(),
+            throttle_backoff_interval_ms: g.gen(),
         }
     }
 }
@@ -2,6 +2,7 @@ mod intrinsic;
 
 pub use intrinsic::*;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct IntrinsicSystemGasConstants {
     // The overhead for each L2 transaction in computation (it is assumed that it is roughly independent of its structure)
     pub l2_tx_intrinsic_gas: u32,
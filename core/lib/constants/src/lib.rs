@@ -5,7 +5,6 @@ pub mod ethereum;
 pub mod fees;
 pub mod system_context;
 pub mod system_logs;
-pub mod trusted_slots;
 
 pub use blocks::*;
 pub use contracts::*;
@@ -14,4 +13,3 @@ pub use ethereum::*;
 pub use fees::*;
 pub use system_context::*;
 pub use system_logs::*;
-pub use trusted_slots::*;
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS pruning_log;
+
+DROP TYPE IF EXISTS prune_type;
@@ -0,0 +1,12 @@
+CREATE TYPE prune_type AS ENUM ('Soft', 'Hard');
+
+CREATE TABLE IF NOT EXISTS pruning_log
+(
+    pruned_l1_batch  BIGINT     NOT NULL,
+    pruned_miniblock BIGINT     NOT NULL,
+    type             prune_type NOT NULL,
+
+    created_at       TIMESTAMP  NOT NULL,
+    updated_at       TIMESTAMP  NOT NULL,
+    PRIMARY KEY (type, pruned_l1_batch)
+);
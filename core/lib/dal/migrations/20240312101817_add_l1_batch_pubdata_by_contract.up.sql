@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS l1_batch_pubdata_by_contract
+(
+    l1_batch_number BIGINT NOT NULL REFERENCES l1_batches (number) ON DELETE CASCADE,
+    address         BYTEA  NOT NULL,
+    pubdata_bytes   BIGINT NOT NULL,
+    PRIMARY KEY (l1_batch_number, address)
+);
@@ -0,0 +1 @@
+ALTER TABLE transactions ADD COLUMN revert_reason JSONB;
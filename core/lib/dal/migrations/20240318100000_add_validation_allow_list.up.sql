@@ -0,0 +1,28 @@
+CREATE TABLE IF NOT EXISTS validation_allow_list (
+    id SERIAL PRIMARY KEY,
+    kind TEXT NOT NULL CHECK (kind IN ('slot', 'address', 'address_slot')),
+    -- `NULL` for slot entries means that the slot is trusted for all L2 tokens.
+    address BYTEA,
+    slot BYTEA,
+    created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+    CHECK ((kind = 'address') = (slot IS NULL)),
+    CHECK (kind <> 'address' OR address IS NOT NULL)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS validation_allow_list_entry_idx
+    ON validation_allow_list (kind, COALESCE(address, '\x'::BYTEA), COALESCE(slot, '\x'::BYTEA));
+
+-- Well-known slots of L2 tokens used by popular EIPs (e.g., EIP-1967 proxies), which were previously hardcoded.
+INSERT INTO validation_allow_list (kind, address, slot)
+VALUES
+    -- EIP-1967 rollback slot
+    ('slot', NULL, '\x4910fdfa16fed3260ed0e7147f7cc6da11a60208b5b9406d12a635614ffd9143'),
+    -- EIP-1967 implementation slot
+    ('slot', NULL, '\x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc'),
+    -- EIP-1967 admin slot
+    ('slot', NULL, '\xb53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103'),
+    -- `initializing` slot of OpenZeppelin `Initializable`
+    ('slot', NULL, '\x0000000000000000000000000000000000000000000000000000000000000001'),
+    -- EIP-1967 beacon slot; contains an address that should itself be trusted
+    ('address_slot', NULL, '\xa3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50')
+ON CONFLICT DO NOTHING;
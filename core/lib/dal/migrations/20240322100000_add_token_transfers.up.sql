@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS token_transfers (
+    miniblock_number BIGINT NOT NULL REFERENCES miniblocks (number) ON DELETE CASCADE,
+    event_index_in_block INT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    token_address BYTEA NOT NULL,
+    from_address BYTEA NOT NULL,
+    to_address BYTEA NOT NULL,
+    amount NUMERIC(80) NOT NULL,
+    PRIMARY KEY (miniblock_number, event_index_in_block)
+);
+
+CREATE INDEX IF NOT EXISTS token_transfers_from_address_idx
+    ON token_transfers (from_address, miniblock_number, event_index_in_block);
+CREATE INDEX IF NOT EXISTS token_transfers_to_address_idx
+    ON token_transfers (to_address, miniblock_number, event_index_in_block);
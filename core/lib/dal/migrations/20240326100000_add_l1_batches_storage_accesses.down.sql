@@ -0,0 +1,2 @@
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS cold_storage_accesses;
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS warm_storage_accesses;
@@ -0,0 +1,2 @@
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS cold_storage_accesses BIGINT;
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS warm_storage_accesses BIGINT;
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS ix_l1_batches_protocol_version;
@@ -0,0 +1 @@
+CREATE INDEX IF NOT EXISTS ix_l1_batches_protocol_version ON l1_batches (protocol_version, number);
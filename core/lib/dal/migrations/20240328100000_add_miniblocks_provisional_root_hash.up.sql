@@ -0,0 +1 @@
+ALTER TABLE miniblocks ADD COLUMN IF NOT EXISTS provisional_root_hash BYTEA;
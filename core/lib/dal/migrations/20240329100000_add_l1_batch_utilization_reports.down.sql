@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS l1_batch_utilization_reports;
+
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS fullness;
+ALTER TABLE l1_batches DROP COLUMN IF EXISTS seal_criterion;
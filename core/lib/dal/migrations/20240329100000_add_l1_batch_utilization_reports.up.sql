@@ -0,0 +1,15 @@
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS seal_criterion TEXT;
+ALTER TABLE l1_batches ADD COLUMN IF NOT EXISTS fullness JSONB;
+
+CREATE TABLE IF NOT EXISTS l1_batch_utilization_reports (
+    report_date DATE PRIMARY KEY,
+    first_l1_batch_number BIGINT,
+    last_l1_batch_number BIGINT,
+    l1_batch_count BIGINT NOT NULL,
+    avg_gas_utilization DOUBLE PRECISION,
+    avg_pubdata_utilization DOUBLE PRECISION,
+    avg_circuits_utilization DOUBLE PRECISION,
+    seal_criteria JSONB NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS sponsored_transactions (
+    tx_hash BYTEA PRIMARY KEY,
+    initiator_address BYTEA NOT NULL,
+    contract_address BYTEA NOT NULL,
+    subsidy NUMERIC(80) NOT NULL,
+    created_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS sponsored_transactions_initiator_address_created_at_idx
+    ON sponsored_transactions (initiator_address, created_at);
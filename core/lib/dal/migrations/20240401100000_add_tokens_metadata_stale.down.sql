@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS tokens_metadata_stale_idx;
+ALTER TABLE tokens DROP COLUMN IF EXISTS metadata_stale;
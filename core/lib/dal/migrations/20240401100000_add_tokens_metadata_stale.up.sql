@@ -0,0 +1,5 @@
+ALTER TABLE tokens ADD COLUMN IF NOT EXISTS metadata_stale BOOLEAN NOT NULL DEFAULT FALSE;
+-- Tokens bridged with metadata that couldn't be read by the bridge (e.g., `bytes32` names or symbols)
+-- have empty names or symbols.
+UPDATE tokens SET metadata_stale = TRUE WHERE name = '' OR symbol = '';
+CREATE INDEX IF NOT EXISTS tokens_metadata_stale_idx ON tokens (l2_address) WHERE metadata_stale;
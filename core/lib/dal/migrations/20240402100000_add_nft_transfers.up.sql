@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS nft_transfers (
+    miniblock_number BIGINT NOT NULL REFERENCES miniblocks (number) ON DELETE CASCADE,
+    event_index_in_block INT NOT NULL,
+    -- Index of the transfer in the event; non-zero only for ERC-1155 `TransferBatch` events.
+    index_in_event INT NOT NULL,
+    tx_hash BYTEA NOT NULL,
+    token_address BYTEA NOT NULL,
+    standard TEXT NOT NULL,
+    token_id NUMERIC(80) NOT NULL,
+    from_address BYTEA NOT NULL,
+    to_address BYTEA NOT NULL,
+    amount NUMERIC(80) NOT NULL,
+    PRIMARY KEY (miniblock_number, event_index_in_block, index_in_event)
+);
+
+CREATE INDEX IF NOT EXISTS nft_transfers_from_address_idx
+    ON nft_transfers (from_address, token_address, token_id);
+CREATE INDEX IF NOT EXISTS nft_transfers_to_address_idx
+    ON nft_transfers (to_address, token_address, token_id);
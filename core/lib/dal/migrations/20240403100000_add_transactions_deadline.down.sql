@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS transactions_pending_deadline_idx;
+ALTER TABLE transactions DROP COLUMN IF EXISTS valid_until_timestamp;
+ALTER TABLE transactions DROP COLUMN IF EXISTS valid_until_miniblock;
+ALTER TABLE transactions DROP COLUMN IF EXISTS expired_at;
@@ -0,0 +1,6 @@
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS valid_until_timestamp BIGINT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS valid_until_miniblock BIGINT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS expired_at TIMESTAMP;
+CREATE INDEX IF NOT EXISTS transactions_pending_deadline_idx ON transactions (hash)
+    WHERE miniblock_number IS NULL AND error IS NULL
+        AND (valid_until_timestamp IS NOT NULL OR valid_until_miniblock IS NOT NULL);
@@ -0,0 +1,8 @@
+-- Resyncs requested by the consistency checker on external nodes. Used to prevent resync loops
+-- if the data re-fetched after a revert is still inconsistent with L1.
+CREATE TABLE IF NOT EXISTS consistency_checker_resyncs (
+    inconsistent_l1_batch_number BIGINT PRIMARY KEY,
+    attempts INT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,6 @@
+-- Progress of the token transfers indexer. Contains at most one row.
+CREATE TABLE IF NOT EXISTS token_transfers_indexer_state (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    last_indexed_miniblock BIGINT NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
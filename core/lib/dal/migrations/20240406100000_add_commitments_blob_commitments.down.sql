@@ -0,0 +1 @@
+ALTER TABLE commitments DROP COLUMN IF EXISTS blob_commitments;
@@ -0,0 +1,2 @@
+-- KZG commitments to the blobs published for an L1 batch, in the order of the blobs.
+ALTER TABLE commitments ADD COLUMN IF NOT EXISTS blob_commitments BYTEA[];
@@ -0,0 +1,10 @@
+-- Addresses participating in persisted call traces, used to filter traces by caller / callee.
+-- Traces persisted before this table was introduced have no rows here.
+CREATE TABLE IF NOT EXISTS call_trace_addresses (
+    tx_hash BYTEA NOT NULL REFERENCES call_traces (tx_hash) ON DELETE CASCADE,
+    address BYTEA NOT NULL,
+    is_caller BOOLEAN NOT NULL,
+    is_callee BOOLEAN NOT NULL,
+    PRIMARY KEY (address, tx_hash)
+);
+CREATE INDEX IF NOT EXISTS call_trace_addresses_tx_hash_idx ON call_trace_addresses (tx_hash);
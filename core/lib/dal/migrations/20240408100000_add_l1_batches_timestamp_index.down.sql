@@ -0,0 +1 @@
+DROP INDEX IF EXISTS l1_batches_timestamp_idx;
@@ -0,0 +1,2 @@
+-- Used to select L1 batches for daily utilization reports.
+CREATE INDEX IF NOT EXISTS l1_batches_timestamp_idx ON l1_batches (timestamp);
@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS sponsorship_totals;
+
+DROP INDEX IF EXISTS sponsored_transactions_unsettled_created_at_idx;
+
+ALTER TABLE sponsored_transactions
+    DROP COLUMN IF EXISTS is_settled;
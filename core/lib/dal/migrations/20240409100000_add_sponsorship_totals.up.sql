@@ -0,0 +1,17 @@
+ALTER TABLE sponsored_transactions
+    ADD COLUMN IF NOT EXISTS is_settled BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS sponsored_transactions_unsettled_created_at_idx
+    ON sponsored_transactions (created_at) WHERE NOT is_settled;
+
+-- Single-row table with the running total of subsidies. The row is locked while reserving subsidies,
+-- so that concurrent reservations (including ones from different API instances) cannot exceed the budget.
+CREATE TABLE IF NOT EXISTS sponsorship_totals (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    total_subsidy NUMERIC(80) NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+INSERT INTO sponsorship_totals (id, total_subsidy, updated_at)
+SELECT TRUE, COALESCE(SUM(subsidy), 0), NOW() FROM sponsored_transactions
+ON CONFLICT (id) DO NOTHING;
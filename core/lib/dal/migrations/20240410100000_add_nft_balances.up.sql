@@ -0,0 +1,24 @@
+-- NFT balances maintained by the token transfers indexer. Unlike `nft_transfers`, balances have no foreign key
+-- to miniblocks, so they are retained when old miniblocks are hard-pruned. Balances of the zero address
+-- (i.e., the source of mints and the destination of burns) are not tracked.
+CREATE TABLE IF NOT EXISTS nft_balances (
+    address BYTEA NOT NULL,
+    token_address BYTEA NOT NULL,
+    token_id NUMERIC(80) NOT NULL,
+    standard TEXT NOT NULL,
+    amount NUMERIC(80) NOT NULL,
+    PRIMARY KEY (address, token_address, token_id)
+);
+
+-- Backfill balances from the transfers indexed so far.
+INSERT INTO nft_balances (address, token_address, token_id, standard, amount)
+SELECT address, token_address, token_id, MIN(standard), SUM(amount)
+FROM (
+    SELECT to_address AS address, token_address, token_id, standard, amount FROM nft_transfers
+    UNION ALL
+    SELECT from_address AS address, token_address, token_id, standard, -amount FROM nft_transfers
+) AS deltas
+WHERE address <> '\x0000000000000000000000000000000000000000'::BYTEA
+GROUP BY address, token_address, token_id
+HAVING SUM(amount) <> 0
+ON CONFLICT (address, token_address, token_id) DO NOTHING;
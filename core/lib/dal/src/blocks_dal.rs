@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     convert::{Into, TryInto},
     ops,
+    time::Duration,
 };
 
 use anyhow::Context as _;
@@ -9,16 +10,21 @@ use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
 use sqlx::Row;
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
-    block::{BlockGasCount, L1BatchHeader, L1BatchTreeData, MiniblockHeader},
+    block::{BlockGasCount, ContractPubdata, L1BatchHeader, L1BatchTreeData, MiniblockHeader},
     circuit::CircuitStatistic,
     commitment::{L1BatchCommitmentArtifacts, L1BatchWithMetadata},
+    pubdata_da::PubdataDA,
+    tx::tx_execution_info::StorageAccessStatistic,
     zk_evm_types::LogQuery,
     Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H256, U256,
 };
 
 use crate::{
     instrument::InstrumentExt,
-    models::storage_block::{StorageL1Batch, StorageL1BatchHeader, StorageMiniblockHeader},
+    models::storage_block::{
+        convert_pubdata_mode, StorageL1Batch, StorageL1BatchHeader, StorageMiniblockHeader,
+    },
+    time_utils::pg_interval_from_duration,
     StorageProcessor,
 };
 
@@ -187,7 +193,8 @@ impl BlocksDal<'_, '_> {
                 protocol_version,
                 system_logs,
                 compressed_state_diffs,
-                pubdata_input
+                pubdata_input,
+                pubdata_mode
             FROM
                 l1_batches
             WHERE
@@ -243,7 +250,8 @@ impl BlocksDal<'_, '_> {
                 compressed_state_diffs,
                 events_queue_commitment,
                 bootloader_initial_content_commitment,
-                pubdata_input
+                pubdata_input,
+                pubdata_mode
             FROM
                 l1_batches
                 LEFT JOIN commitments ON commitments.l1_batch_number = l1_batches.number
@@ -280,7 +288,8 @@ impl BlocksDal<'_, '_> {
                 protocol_version,
                 compressed_state_diffs,
                 system_logs,
-                pubdata_input
+                pubdata_input,
+                pubdata_mode
             FROM
                 l1_batches
             WHERE
@@ -455,6 +464,7 @@ impl BlocksDal<'_, '_> {
         events_queue: &[LogQuery],
         storage_refunds: &[u32],
         predicted_circuits_by_type: CircuitStatistic, // predicted number of circuits for each circuit type
+        storage_accesses: StorageAccessStatistic,
     ) -> anyhow::Result<()> {
         let priority_onchain_data: Vec<Vec<u8>> = header
             .priority_ops_onchain_data
@@ -508,6 +518,9 @@ impl BlocksDal<'_, '_> {
                     storage_refunds,
                     pubdata_input,
                     predicted_circuits_by_type,
+                    pubdata_mode,
+                    cold_storage_accesses,
+                    warm_storage_accesses,
                     created_at,
                     updated_at
                 )
@@ -533,6 +546,9 @@ impl BlocksDal<'_, '_> {
                     $18,
                     $19,
                     $20,
+                    $21,
+                    $22,
+                    $23,
                     NOW(),
                     NOW()
                 )
@@ -557,6 +573,9 @@ impl BlocksDal<'_, '_> {
             &storage_refunds,
             pubdata_input,
             serde_json::to_value(predicted_circuits_by_type).unwrap(),
+            header.pubdata_mode.map(|mode| mode as i16),
+            storage_accesses.cold as i64,
+            storage_accesses.warm as i64,
         )
         .execute(transaction.conn())
         .await?;
@@ -604,11 +623,31 @@ impl BlocksDal<'_, '_> {
                     protocol_version,
                     virtual_blocks,
                     fair_pubdata_price,
+                    logs_bloom,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, NOW(), NOW())
+                (
+                    $1,
+                    $2,
+                    $3,
+                    $4,
+                    $5,
+                    $6,
+                    $7,
+                    $8,
+                    $9,
+                    $10,
+                    $11,
+                    $12,
+                    $13,
+                    $14,
+                    $15,
+                    $16,
+                    NOW(),
+                    NOW()
+                )
             "#,
             miniblock_header.number.0 as i64,
             miniblock_header.timestamp as i64,
@@ -631,6 +670,7 @@ impl BlocksDal<'_, '_> {
             miniblock_header.protocol_version.map(|v| v as i32),
             miniblock_header.virtual_blocks as i64,
             miniblock_header.batch_fee_input.fair_pubdata_price() as i64,
+            miniblock_header.logs_bloom.as_bytes(),
         )
         .execute(self.storage.conn())
         .await?;
@@ -658,7 +698,8 @@ impl BlocksDal<'_, '_> {
                 default_aa_code_hash,
                 protocol_version,
                 virtual_blocks,
-                fair_pubdata_price
+                fair_pubdata_price,
+                logs_bloom
             FROM
                 miniblocks
             ORDER BY
@@ -704,7 +745,8 @@ impl BlocksDal<'_, '_> {
                 default_aa_code_hash,
                 protocol_version,
                 virtual_blocks,
-                fair_pubdata_price
+                fair_pubdata_price,
+                logs_bloom
             FROM
                 miniblocks
             WHERE
@@ -890,18 +932,30 @@ impl BlocksDal<'_, '_> {
             );
         }
 
+        let blob_commitments = commitment_artifacts
+            .blob_commitments
+            .map(|commitments| commitments.map(|commitment| commitment.0.to_vec()).to_vec());
         sqlx::query!(
             r#"
             INSERT INTO
-                commitments (l1_batch_number, events_queue_commitment, bootloader_initial_content_commitment)
+                commitments (
+                    l1_batch_number,
+                    events_queue_commitment,
+                    bootloader_initial_content_commitment,
+                    blob_commitments
+                )
             VALUES
-                ($1, $2, $3)
+                ($1, $2, $3, $4)
             ON CONFLICT (l1_batch_number) DO NOTHING
             "#,
             number.0 as i64,
-            commitment_artifacts.aux_commitments.map(|a| a.events_queue_commitment.0.to_vec()),
-            commitment_artifacts.aux_commitments
+            commitment_artifacts
+                .aux_commitments
+                .map(|a| a.events_queue_commitment.0.to_vec()),
+            commitment_artifacts
+                .aux_commitments
                 .map(|a| a.bootloader_initial_content_commitment.0.to_vec()),
+            blob_commitments.as_deref(),
         )
         .instrument("save_batch_aux_commitments")
         .with_arg("number", &number)
@@ -913,6 +967,38 @@ impl BlocksDal<'_, '_> {
         Ok(())
     }
 
+    /// Returns KZG commitments to the blobs published for the specified L1 batch. Returns `None` if the batch
+    /// doesn't exist, is pre-boojum, or its commitment was generated before blob commitments were persisted.
+    pub async fn get_l1_batch_blob_commitments(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<[H256; 2]>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                blob_commitments
+            FROM
+                commitments
+            WHERE
+                l1_batch_number = $1
+            "#,
+            number.0 as i64
+        )
+        .instrument("get_l1_batch_blob_commitments")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(commitments) = row.and_then(|row| row.blob_commitments) else {
+            return Ok(None);
+        };
+        let commitments: Vec<_> = commitments
+            .iter()
+            .map(|commitment| H256::from_slice(commitment))
+            .collect();
+        Ok(commitments.try_into().ok())
+    }
+
     pub async fn get_last_committed_to_eth_l1_batch(
         &mut self,
     ) -> anyhow::Result<Option<L1BatchWithMetadata>> {
@@ -951,7 +1037,8 @@ impl BlocksDal<'_, '_> {
                 system_logs,
                 events_queue_commitment,
                 bootloader_initial_content_commitment,
-                pubdata_input
+                pubdata_input,
+                pubdata_mode
             FROM
                 l1_batches
                 LEFT JOIN commitments ON commitments.l1_batch_number = l1_batches.number
@@ -1065,6 +1152,66 @@ impl BlocksDal<'_, '_> {
         .map(|record| L1BatchNumber(record.number as u32)))
     }
 
+    /// Returns the number of the last L1 batch for which an Ethereum prove tx was confirmed at least
+    /// `min_age` ago.
+    pub async fn get_number_of_last_l1_batch_proven_on_eth_before(
+        &mut self,
+        min_age: Duration,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let min_age = pg_interval_from_duration(min_age);
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                number
+            FROM
+                l1_batches
+                JOIN eth_txs_history AS prove_tx ON (l1_batches.eth_prove_tx_id = prove_tx.eth_tx_id)
+            WHERE
+                prove_tx.confirmed_at <= NOW() - $1::INTERVAL
+            ORDER BY
+                number DESC
+            LIMIT
+                1
+            "#,
+            &min_age
+        )
+        .instrument("get_number_of_last_l1_batch_proven_on_eth_before")
+        .with_arg("min_age", &min_age)
+        .fetch_optional(self.storage)
+        .await?
+        .map(|record| L1BatchNumber(record.number as u32)))
+    }
+
+    /// Returns the number of the last L1 batch for which an Ethereum execute tx was confirmed at least
+    /// `min_age` ago.
+    pub async fn get_number_of_last_l1_batch_executed_on_eth_before(
+        &mut self,
+        min_age: Duration,
+    ) -> sqlx::Result<Option<L1BatchNumber>> {
+        let min_age = pg_interval_from_duration(min_age);
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                number
+            FROM
+                l1_batches
+                JOIN eth_txs_history AS execute_tx ON (l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id)
+            WHERE
+                execute_tx.confirmed_at <= NOW() - $1::INTERVAL
+            ORDER BY
+                number DESC
+            LIMIT
+                1
+            "#,
+            &min_age
+        )
+        .instrument("get_number_of_last_l1_batch_executed_on_eth_before")
+        .with_arg("min_age", &min_age)
+        .fetch_optional(self.storage)
+        .await?
+        .map(|record| L1BatchNumber(record.number as u32)))
+    }
+
     /// Returns the number of the last L1 batch for which an Ethereum execute tx was sent and confirmed.
     pub async fn get_number_of_last_l1_batch_executed_on_eth(
         &mut self,
@@ -1141,7 +1288,8 @@ impl BlocksDal<'_, '_> {
                 system_logs,
                 events_queue_commitment,
                 bootloader_initial_content_commitment,
-                pubdata_input
+                pubdata_input,
+                pubdata_mode
             FROM
                 l1_batches
                 LEFT JOIN commitments ON commitments.l1_batch_number = l1_batches.number
@@ -1252,7 +1400,8 @@ impl BlocksDal<'_, '_> {
                 protocol_version,
                 events_queue_commitment,
                 bootloader_initial_content_commitment,
-                pubdata_input
+                pubdata_input,
+                pubdata_mode
             FROM
                 (
                     SELECT
@@ -1330,7 +1479,8 @@ impl BlocksDal<'_, '_> {
                         system_logs,
                         events_queue_commitment,
                         bootloader_initial_content_commitment,
-                        pubdata_input
+                        pubdata_input,
+                        pubdata_mode
                     FROM
                         l1_batches
                         LEFT JOIN commitments ON commitments.l1_batch_number = l1_batches.number
@@ -1460,7 +1610,8 @@ impl BlocksDal<'_, '_> {
                     system_logs,
                     events_queue_commitment,
                     bootloader_initial_content_commitment,
-                    pubdata_input
+                    pubdata_input,
+                    pubdata_mode
                 FROM
                     l1_batches
                     LEFT JOIN commitments ON commitments.l1_batch_number = l1_batches.number
@@ -1529,7 +1680,8 @@ impl BlocksDal<'_, '_> {
                 system_logs,
                 events_queue_commitment,
                 bootloader_initial_content_commitment,
-                pubdata_input
+                pubdata_input,
+                pubdata_mode
             FROM
                 l1_batches
                 LEFT JOIN commitments ON commitments.l1_batch_number = l1_batches.number
@@ -1608,7 +1760,8 @@ impl BlocksDal<'_, '_> {
                 system_logs,
                 events_queue_commitment,
                 bootloader_initial_content_commitment,
-                pubdata_input
+                pubdata_input,
+                pubdata_mode
             FROM
                 l1_batches
                 LEFT JOIN commitments ON commitments.l1_batch_number = l1_batches.number
@@ -1648,6 +1801,60 @@ impl BlocksDal<'_, '_> {
             .context("map_l1_batches()")
     }
 
+    /// Returns pubdata inputs for sealed L1 batches that don't have a commit transaction yet, in the ascending
+    /// batch number order. L1 batches without a pubdata input are skipped.
+    pub async fn get_pubdata_inputs_pending_commit(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<
+        Vec<(
+            L1BatchNumber,
+            Option<ProtocolVersionId>,
+            Option<PubdataDA>,
+            Vec<u8>,
+        )>,
+    > {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                number,
+                protocol_version,
+                pubdata_mode,
+                pubdata_input AS "pubdata_input!"
+            FROM
+                l1_batches
+            WHERE
+                eth_commit_tx_id IS NULL
+                AND number != 0
+                AND pubdata_input IS NOT NULL
+            ORDER BY
+                number
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .instrument("get_pubdata_inputs_pending_commit")
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let protocol_version = row
+                    .protocol_version
+                    .map(|id| ProtocolVersionId::try_from(id as u16).unwrap());
+                (
+                    L1BatchNumber(row.number as u32),
+                    protocol_version,
+                    convert_pubdata_mode(row.pubdata_mode),
+                    row.pubdata_input,
+                )
+            })
+            .collect())
+    }
+
     pub async fn get_l1_batch_state_root(
         &mut self,
         number: L1BatchNumber,
@@ -1669,6 +1876,37 @@ impl BlocksDal<'_, '_> {
         .map(|hash| H256::from_slice(&hash)))
     }
 
+    /// Returns cold / warm storage accesses aggregated over all transactions in the specified L1 batch.
+    /// Returns `None` if the batch doesn't exist or was sealed before storage accesses were tracked.
+    pub async fn get_l1_batch_storage_accesses(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> sqlx::Result<Option<StorageAccessStatistic>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                cold_storage_accesses,
+                warm_storage_accesses
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            number.0 as i64
+        )
+        .instrument("get_l1_batch_storage_accesses")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(row.and_then(|row| {
+            Some(StorageAccessStatistic {
+                cold: row.cold_storage_accesses? as usize,
+                warm: row.warm_storage_accesses? as usize,
+            })
+        }))
+    }
+
     pub async fn get_l1_batch_state_root_and_timestamp(
         &mut self,
         number: L1BatchNumber,
@@ -1788,6 +2026,132 @@ impl BlocksDal<'_, '_> {
         .collect())
     }
 
+    pub async fn insert_l1_batch_pubdata_by_contract(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        pubdata_by_contract: &[ContractPubdata],
+    ) -> sqlx::Result<()> {
+        let addresses: Vec<_> = pubdata_by_contract
+            .iter()
+            .map(|contract| contract.address.as_bytes().to_vec())
+            .collect();
+        let pubdata_bytes: Vec<_> = pubdata_by_contract
+            .iter()
+            .map(|contract| contract.pubdata_bytes as i64)
+            .collect();
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_batch_pubdata_by_contract (l1_batch_number, address, pubdata_bytes)
+            SELECT
+                $1,
+                u.address,
+                u.pubdata_bytes
+            FROM
+                UNNEST($2::bytea[], $3::BIGINT[]) AS u (address, pubdata_bytes)
+            "#,
+            l1_batch_number.0 as i64,
+            &addresses,
+            &pubdata_bytes
+        )
+        .instrument("insert_l1_batch_pubdata_by_contract")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("contracts.len", &pubdata_by_contract.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Saves the criterion that has triggered sealing of the L1 batch and the L1 batch fullness
+    /// for each criterion tracking capacity (e.g., `gas` or `pub_data_size`).
+    pub async fn set_l1_batch_seal_stats(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        seal_criterion: Option<&str>,
+        fullness: &[(&str, f64)],
+    ) -> sqlx::Result<()> {
+        let fullness: serde_json::Map<_, _> = fullness
+            .iter()
+            .map(|&(criterion, value)| (criterion.to_owned(), value.into()))
+            .collect();
+        sqlx::query!(
+            r#"
+            UPDATE l1_batches
+            SET
+                seal_criterion = $2,
+                fullness = $3,
+                updated_at = NOW()
+            WHERE
+                number = $1
+            "#,
+            i64::from(l1_batch_number.0),
+            seal_criterion,
+            serde_json::Value::Object(fullness)
+        )
+        .instrument("set_l1_batch_seal_stats")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Saves provisional state root hashes computed by the Merkle tree for miniblocks.
+    pub async fn save_miniblock_provisional_root_hashes(
+        &mut self,
+        root_hashes: &[(MiniblockNumber, H256)],
+    ) -> sqlx::Result<()> {
+        let numbers: Vec<_> = root_hashes
+            .iter()
+            .map(|(number, _)| i64::from(number.0))
+            .collect();
+        let hashes: Vec<_> = root_hashes
+            .iter()
+            .map(|(_, hash)| hash.as_bytes().to_vec())
+            .collect();
+
+        sqlx::query!(
+            r#"
+            UPDATE miniblocks
+            SET
+                provisional_root_hash = u.root_hash
+            FROM
+                UNNEST($1::BIGINT[], $2::bytea[]) AS u (number, root_hash)
+            WHERE
+                miniblocks.number = u.number
+            "#,
+            &numbers,
+            &hashes
+        )
+        .instrument("save_miniblock_provisional_root_hashes")
+        .with_arg("root_hashes.len", &root_hashes.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_miniblock_provisional_root_hash(
+        &mut self,
+        miniblock_number: MiniblockNumber,
+    ) -> sqlx::Result<Option<H256>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                provisional_root_hash
+            FROM
+                miniblocks
+            WHERE
+                number = $1
+            "#,
+            i64::from(miniblock_number.0)
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+        Ok(row
+            .and_then(|row| row.provisional_root_hash)
+            .map(|hash| H256::from_slice(&hash)))
+    }
+
     pub async fn delete_initial_writes(
         &mut self,
         last_batch_to_keep: L1BatchNumber,
@@ -1996,6 +2360,29 @@ impl BlocksDal<'_, '_> {
         .map(|row| row.timestamp as u64))
     }
 
+    /// Returns the number and timestamp of the last sealed L1 batch.
+    pub async fn get_last_sealed_l1_batch_timestamp(
+        &mut self,
+    ) -> sqlx::Result<Option<(L1BatchNumber, u64)>> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                number,
+                timestamp
+            FROM
+                l1_batches
+            ORDER BY
+                number DESC
+            LIMIT
+                1
+            "#,
+        )
+        .instrument("get_last_sealed_l1_batch_timestamp")
+        .fetch_optional(self.storage)
+        .await?
+        .map(|row| (L1BatchNumber(row.number as u32), row.timestamp as u64)))
+    }
+
     pub async fn get_batch_protocol_version_id(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -2322,6 +2709,7 @@ impl BlocksDal<'_, '_> {
             &[],
             &[],
             Default::default(),
+            Default::default(),
         )
         .await
     }
@@ -2345,6 +2733,7 @@ impl BlocksDal<'_, '_> {
 mod tests {
     use zksync_contracts::BaseSystemContractsHashes;
     use zksync_types::{
+        commitment::L1BatchCommitmentHash,
         l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
         Address, ProtocolVersion, ProtocolVersionId,
     };
@@ -2408,6 +2797,104 @@ mod tests {
             .is_none());
     }
 
+    #[tokio::test]
+    async fn storing_blob_commitments() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let blob_commitments = [H256::repeat_byte(1), H256::repeat_byte(2)];
+        for number in [1, 2] {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                100,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::latest(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+            let artifacts = L1BatchCommitmentArtifacts {
+                commitment_hash: L1BatchCommitmentHash {
+                    pass_through_data: H256::zero(),
+                    aux_output: H256::zero(),
+                    meta_parameters: H256::zero(),
+                    commitment: H256::zero(),
+                },
+                l2_l1_merkle_root: H256::zero(),
+                compressed_state_diffs: Some(vec![]),
+                compressed_initial_writes: None,
+                compressed_repeated_writes: None,
+                zkporter_is_available: false,
+                aux_commitments: None,
+                blob_commitments: (number == 1).then_some(blob_commitments),
+            };
+            conn.blocks_dal()
+                .save_l1_batch_commitment_artifacts(L1BatchNumber(number), &artifacts)
+                .await
+                .unwrap();
+        }
+
+        let loaded = conn
+            .blocks_dal()
+            .get_l1_batch_blob_commitments(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(loaded, Some(blob_commitments));
+        for number in [2, 3] {
+            let loaded = conn
+                .blocks_dal()
+                .get_l1_batch_blob_commitments(L1BatchNumber(number))
+                .await
+                .unwrap();
+            assert_eq!(loaded, None);
+        }
+    }
+
+    #[tokio::test]
+    async fn storing_storage_accesses() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        let storage_accesses = StorageAccessStatistic { cold: 42, warm: 7 };
+        conn.blocks_dal()
+            .insert_l1_batch(
+                &header,
+                &[],
+                Default::default(),
+                &[],
+                &[],
+                Default::default(),
+                storage_accesses,
+            )
+            .await
+            .unwrap();
+
+        let loaded = conn
+            .blocks_dal()
+            .get_l1_batch_storage_accesses(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(loaded, Some(storage_accesses));
+        let missing = conn
+            .blocks_dal()
+            .get_l1_batch_storage_accesses(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(missing, None);
+    }
+
     #[tokio::test]
     async fn getting_predicted_gas() {
         let pool = ConnectionPool::test_pool().await;
@@ -2427,7 +2914,15 @@ mod tests {
             execute: 10,
         };
         conn.blocks_dal()
-            .insert_l1_batch(&header, &[], predicted_gas, &[], &[], Default::default())
+            .insert_l1_batch(
+                &header,
+                &[],
+                predicted_gas,
+                &[],
+                &[],
+                Default::default(),
+                Default::default(),
+            )
             .await
             .unwrap();
 
@@ -2435,7 +2930,15 @@ mod tests {
         header.timestamp += 100;
         predicted_gas += predicted_gas;
         conn.blocks_dal()
-            .insert_l1_batch(&header, &[], predicted_gas, &[], &[], Default::default())
+            .insert_l1_batch(
+                &header,
+                &[],
+                predicted_gas,
+                &[],
+                &[],
+                Default::default(),
+                Default::default(),
+            )
             .await
             .unwrap();
 
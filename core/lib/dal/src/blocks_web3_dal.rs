@@ -1,12 +1,15 @@
+use std::{ops, str::FromStr};
+
 use bigdecimal::BigDecimal;
 use sqlx::Row;
 use zksync_system_constants::EMPTY_UNCLES_HASH;
 use zksync_types::{
     api,
+    block::ContractPubdata,
     l2_to_l1_log::L2ToL1Log,
     vm_trace::Call,
     web3::types::{BlockHeader, U64},
-    Bytes, L1BatchNumber, L2ChainId, MiniblockNumber, H160, H2048, H256, U256,
+    Address, Bytes, L1BatchNumber, L2ChainId, MiniblockNumber, H160, H2048, H256, U256,
 };
 use zksync_utils::bigdecimal_to_u256;
 
@@ -49,6 +52,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 miniblocks.l1_batch_number,
                 miniblocks.timestamp,
                 miniblocks.base_fee_per_gas,
+                miniblocks.logs_bloom,
+                miniblocks.provisional_root_hash,
                 prev_miniblock.hash as parent_hash,
                 l1_batches.timestamp as l1_batch_timestamp,
                 transactions.gas_limit as gas_limit,
@@ -90,6 +95,13 @@ impl BlocksWeb3Dal<'_, '_> {
                     .try_get("parent_hash")
                     .map_or_else(|_| H256::zero(), H256::from_slice);
                 let base_fee_per_gas = db_row.get::<BigDecimal, &str>("base_fee_per_gas");
+                let logs_bloom = db_row
+                    .get::<Option<&[u8]>, &str>("logs_bloom")
+                    .map(H2048::from_slice)
+                    .unwrap_or_default();
+                let provisional_state_root = db_row
+                    .get::<Option<&[u8]>, &str>("provisional_root_hash")
+                    .map(H256::from_slice);
 
                 api::Block {
                     hash,
@@ -101,7 +113,8 @@ impl BlocksWeb3Dal<'_, '_> {
                     base_fee_per_gas: bigdecimal_to_u256(base_fee_per_gas),
                     timestamp: db_row.get::<i64, &str>("timestamp").into(),
                     l1_batch_timestamp,
-                    // TODO: include logs
+                    logs_bloom,
+                    provisional_state_root,
                     ..api::Block::default()
                 }
             });
@@ -181,7 +194,8 @@ impl BlocksWeb3Dal<'_, '_> {
             SELECT
                 hash,
                 number,
-                timestamp
+                timestamp,
+                logs_bloom
             FROM
                 miniblocks
             WHERE
@@ -207,8 +221,10 @@ impl BlocksWeb3Dal<'_, '_> {
             gas_limit: U256::zero(),
             base_fee_per_gas: None,
             extra_data: Bytes::default(),
-            // TODO: include logs
-            logs_bloom: H2048::default(),
+            logs_bloom: row
+                .logs_bloom
+                .map(|bloom| H2048::from_slice(&bloom))
+                .unwrap_or_default(),
             timestamp: U256::from(row.timestamp),
             difficulty: U256::zero(),
             mix_hash: None,
@@ -474,6 +490,156 @@ impl BlocksWeb3Dal<'_, '_> {
         .collect())
     }
 
+    /// Returns call traces together with the corresponding transaction hashes for all transactions
+    /// in the specified miniblock in the order of their execution.
+    pub async fn get_raw_traces_for_miniblock(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> sqlx::Result<Vec<api::TransactionTrace>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.hash AS tx_hash,
+                transactions.index_in_block AS "index_in_block!",
+                call_traces.call_trace
+            FROM
+                call_traces
+                INNER JOIN transactions ON tx_hash = transactions.hash
+            WHERE
+                transactions.miniblock_number = $1
+            ORDER BY
+                transactions.index_in_block
+            "#,
+            block_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::TransactionTrace {
+                transaction_hash: H256::from_slice(&row.tx_hash),
+                transaction_index: U64::from(row.index_in_block as u64),
+                trace: Call::from(CallTrace {
+                    call_trace: row.call_trace,
+                }),
+            })
+            .collect())
+    }
+
+    /// Returns a page of call traces for transactions in the specified miniblock range together with the numbers
+    /// and hashes of the containing miniblocks. Traces are ordered by their execution; the page starts after
+    /// the transaction at `after_tx` (miniblock number + index in miniblock), if specified, and contains
+    /// at most `limit` traces.
+    ///
+    /// If `from_addresses` / `to_addresses` are specified, only traces containing a call made by / to one
+    /// of the specified addresses are returned. This is a pre-filter; it doesn't guarantee that a single call
+    /// matches both conditions. Traces persisted before caller / callee addresses were indexed are not filtered.
+    pub async fn get_raw_traces_for_miniblock_range(
+        &mut self,
+        block_range: ops::RangeInclusive<MiniblockNumber>,
+        after_tx: Option<(MiniblockNumber, u32)>,
+        from_addresses: Option<&[Address]>,
+        to_addresses: Option<&[Address]>,
+        limit: usize,
+    ) -> sqlx::Result<Vec<(MiniblockNumber, H256, api::TransactionTrace)>> {
+        let (after_miniblock, after_index) =
+            after_tx.map_or((-1, -1), |(number, index)| (number.0 as i64, index as i32));
+        let from_addresses: Option<Vec<_>> =
+            from_addresses.map(|addresses| addresses.iter().map(Address::as_bytes).collect());
+        let to_addresses: Option<Vec<_>> =
+            to_addresses.map(|addresses| addresses.iter().map(Address::as_bytes).collect());
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.miniblock_number AS "miniblock_number!",
+                miniblocks.hash AS miniblock_hash,
+                transactions.hash AS tx_hash,
+                transactions.index_in_block AS "index_in_block!",
+                call_traces.call_trace
+            FROM
+                call_traces
+                INNER JOIN transactions ON call_traces.tx_hash = transactions.hash
+                INNER JOIN miniblocks ON transactions.miniblock_number = miniblocks.number
+            WHERE
+                transactions.miniblock_number BETWEEN $1 AND $2
+                AND (transactions.miniblock_number, transactions.index_in_block) > ($3, $4)
+                AND (
+                    NOT EXISTS (
+                        SELECT
+                            1
+                        FROM
+                            call_trace_addresses
+                        WHERE
+                            call_trace_addresses.tx_hash = call_traces.tx_hash
+                    )
+                    OR (
+                        (
+                            $5::bytea[] IS NULL
+                            OR EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    call_trace_addresses
+                                WHERE
+                                    call_trace_addresses.tx_hash = call_traces.tx_hash
+                                    AND call_trace_addresses.address = ANY ($5)
+                                    AND call_trace_addresses.is_caller
+                            )
+                        )
+                        AND (
+                            $6::bytea[] IS NULL
+                            OR EXISTS (
+                                SELECT
+                                    1
+                                FROM
+                                    call_trace_addresses
+                                WHERE
+                                    call_trace_addresses.tx_hash = call_traces.tx_hash
+                                    AND call_trace_addresses.address = ANY ($6)
+                                    AND call_trace_addresses.is_callee
+                            )
+                        )
+                    )
+                )
+            ORDER BY
+                transactions.miniblock_number,
+                transactions.index_in_block
+            LIMIT
+                $7
+            "#,
+            block_range.start().0 as i64,
+            block_range.end().0 as i64,
+            after_miniblock,
+            after_index,
+            from_addresses.as_deref() as Option<&[&[u8]]>,
+            to_addresses.as_deref() as Option<&[&[u8]]>,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let trace = api::TransactionTrace {
+                    transaction_hash: H256::from_slice(&row.tx_hash),
+                    transaction_index: U64::from(row.index_in_block as u64),
+                    trace: Call::from(CallTrace {
+                        call_trace: row.call_trace,
+                    }),
+                };
+                let miniblock_number = MiniblockNumber(row.miniblock_number as u32);
+                (
+                    miniblock_number,
+                    H256::from_slice(&row.miniblock_hash),
+                    trace,
+                )
+            })
+            .collect())
+    }
+
     /// Returns `base_fee_per_gas` for miniblock range [min(newest_block - block_count + 1, 0), newest_block]
     /// in descending order of miniblock numbers.
     pub async fn get_fee_history(
@@ -614,7 +780,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 mb.l1_gas_price,
                 mb.l2_fair_gas_price,
                 l1_batches.bootloader_code_hash,
-                l1_batches.default_aa_code_hash
+                l1_batches.default_aa_code_hash,
+                l1_batches.pubdata_mode
             FROM
                 l1_batches
                 INNER JOIN mb ON TRUE
@@ -643,12 +810,150 @@ impl BlocksWeb3Dal<'_, '_> {
 
         Ok(l1_batch_details.map(Into::into))
     }
+
+    /// Returns L1 batches that have reached the committed, proven or executed stage (i.e., have the corresponding
+    /// L1 transaction confirmed) after the specified batches. Updates are ordered by stage and then by batch number;
+    /// at most `limit` updates are returned for each stage.
+    pub async fn get_l1_batch_stage_updates(
+        &mut self,
+        last_committed: L1BatchNumber,
+        last_proven: L1BatchNumber,
+        last_executed: L1BatchNumber,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::L1BatchStatusUpdate>> {
+        let rows = sqlx::query!(
+            r#"
+            (
+                SELECT
+                    1 AS "stage!",
+                    l1_batches.number AS "number!",
+                    eth_txs_history.tx_hash AS "tx_hash!"
+                FROM
+                    l1_batches
+                    INNER JOIN eth_txs_history ON (
+                        l1_batches.eth_commit_tx_id = eth_txs_history.eth_tx_id
+                        AND eth_txs_history.confirmed_at IS NOT NULL
+                    )
+                WHERE
+                    l1_batches.number > $1
+                ORDER BY
+                    l1_batches.number
+                LIMIT
+                    $4
+            )
+            UNION ALL
+            (
+                SELECT
+                    2 AS "stage!",
+                    l1_batches.number AS "number!",
+                    eth_txs_history.tx_hash AS "tx_hash!"
+                FROM
+                    l1_batches
+                    INNER JOIN eth_txs_history ON (
+                        l1_batches.eth_prove_tx_id = eth_txs_history.eth_tx_id
+                        AND eth_txs_history.confirmed_at IS NOT NULL
+                    )
+                WHERE
+                    l1_batches.number > $2
+                ORDER BY
+                    l1_batches.number
+                LIMIT
+                    $4
+            )
+            UNION ALL
+            (
+                SELECT
+                    3 AS "stage!",
+                    l1_batches.number AS "number!",
+                    eth_txs_history.tx_hash AS "tx_hash!"
+                FROM
+                    l1_batches
+                    INNER JOIN eth_txs_history ON (
+                        l1_batches.eth_execute_tx_id = eth_txs_history.eth_tx_id
+                        AND eth_txs_history.confirmed_at IS NOT NULL
+                    )
+                WHERE
+                    l1_batches.number > $3
+                ORDER BY
+                    l1_batches.number
+                LIMIT
+                    $4
+            )
+            ORDER BY
+                "stage!",
+                "number!"
+            "#,
+            i64::from(last_committed.0),
+            i64::from(last_proven.0),
+            i64::from(last_executed.0),
+            limit as i64
+        )
+        .instrument("get_l1_batch_stage_updates")
+        .with_arg("last_committed", &last_committed)
+        .with_arg("last_proven", &last_proven)
+        .with_arg("last_executed", &last_executed)
+        .fetch_all(self.storage)
+        .await?;
+
+        let updates = rows.into_iter().map(|row| {
+            let stage = match row.stage {
+                1 => api::L1BatchStage::Committed,
+                2 => api::L1BatchStage::Proven,
+                _ => api::L1BatchStage::Executed,
+            };
+            let tx_hash = H256::from_str(&row.tx_hash).expect("Incorrect tx hash in DB");
+            api::L1BatchStatusUpdate {
+                number: L1BatchNumber(row.number as u32),
+                stage,
+                tx_hash: Some(tx_hash),
+            }
+        });
+        Ok(updates.collect())
+    }
+
+    /// Returns pubdata published in the specified L1 batch split by the contracts it's attributed to,
+    /// ordered by the number of pubdata bytes (largest first). Returns an empty list if the batch is not sealed
+    /// or was sealed by a VM version not tracking pubdata attribution.
+    pub async fn get_l1_batch_pubdata_by_contract(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<ContractPubdata>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                address,
+                pubdata_bytes
+            FROM
+                l1_batch_pubdata_by_contract
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                pubdata_bytes DESC,
+                address
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_l1_batch_pubdata_by_contract")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .report_latency()
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ContractPubdata {
+                address: Address::from_slice(&row.address),
+                pubdata_bytes: row.pubdata_bytes as u64,
+            })
+            .collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
     use zksync_types::{
-        block::{MiniblockHasher, MiniblockHeader},
+        block::{L1BatchHeader, MiniblockHasher, MiniblockHeader},
         fee::TransactionExecutionMetrics,
         Address, MiniblockNumber, ProtocolVersion, ProtocolVersionId,
     };
@@ -901,5 +1206,170 @@ mod tests {
             let expected_trace = tx_result.call_trace().unwrap();
             assert_eq!(*trace, expected_trace);
         }
+
+        let raw_traces = conn
+            .blocks_web3_dal()
+            .get_raw_traces_for_miniblock(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(raw_traces.len(), 2);
+        for (i, (trace, tx_result)) in raw_traces.iter().zip(&tx_results).enumerate() {
+            assert_eq!(trace.transaction_hash, tx_result.hash);
+            assert_eq!(trace.transaction_index, U64::from(i));
+            assert_eq!(trace.trace, tx_result.call_trace().unwrap());
+        }
+
+        let all_miniblocks = MiniblockNumber(0)..=MiniblockNumber(1);
+        let range_traces = conn
+            .blocks_web3_dal()
+            .get_raw_traces_for_miniblock_range(all_miniblocks.clone(), None, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(range_traces.len(), 2);
+        let miniblock_hash = create_miniblock_header(1).hash;
+        for ((number, hash, trace), raw_trace) in range_traces.iter().zip(&raw_traces) {
+            assert_eq!(*number, MiniblockNumber(1));
+            assert_eq!(*hash, miniblock_hash);
+            assert_eq!(trace, raw_trace);
+        }
+
+        // Pagination
+        let range_traces = conn
+            .blocks_web3_dal()
+            .get_raw_traces_for_miniblock_range(all_miniblocks.clone(), None, None, None, 1)
+            .await
+            .unwrap();
+        assert_eq!(range_traces.len(), 1);
+        assert_eq!(range_traces[0].2, raw_traces[0]);
+        let after_tx = Some((MiniblockNumber(1), 0));
+        let range_traces = conn
+            .blocks_web3_dal()
+            .get_raw_traces_for_miniblock_range(all_miniblocks.clone(), after_tx, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(range_traces.len(), 1);
+        assert_eq!(range_traces[0].2, raw_traces[1]);
+
+        // Filtering by addresses
+        let address = Address::from_low_u64_be(1);
+        let range_traces = conn
+            .blocks_web3_dal()
+            .get_raw_traces_for_miniblock_range(
+                all_miniblocks.clone(),
+                None,
+                Some(&[address]),
+                None,
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(range_traces.len(), 1);
+        assert_eq!(range_traces[0].2, raw_traces[1]);
+        let range_traces = conn
+            .blocks_web3_dal()
+            .get_raw_traces_for_miniblock_range(
+                all_miniblocks.clone(),
+                None,
+                None,
+                Some(&[address]),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(range_traces.len(), 1);
+        assert_eq!(range_traces[0].2, raw_traces[0]);
+        let range_traces = conn
+            .blocks_web3_dal()
+            .get_raw_traces_for_miniblock_range(
+                all_miniblocks,
+                None,
+                Some(&[address]),
+                Some(&[address]),
+                10,
+            )
+            .await
+            .unwrap();
+        assert!(range_traces.is_empty());
+
+        let range_traces = conn
+            .blocks_web3_dal()
+            .get_raw_traces_for_miniblock_range(
+                MiniblockNumber(2)..=MiniblockNumber(3),
+                None,
+                None,
+                None,
+                10,
+            )
+            .await
+            .unwrap();
+        assert!(range_traces.is_empty());
+    }
+
+    #[tokio::test]
+    async fn getting_pubdata_by_contract() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            100,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::latest(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+
+        let pubdata = conn
+            .blocks_web3_dal()
+            .get_l1_batch_pubdata_by_contract(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(pubdata.is_empty());
+
+        let pubdata_by_contract = [
+            ContractPubdata {
+                address: Address::repeat_byte(1),
+                pubdata_bytes: 100,
+            },
+            ContractPubdata {
+                address: Address::repeat_byte(2),
+                pubdata_bytes: 300,
+            },
+            ContractPubdata {
+                address: Address::repeat_byte(3),
+                pubdata_bytes: 100,
+            },
+        ];
+        conn.blocks_dal()
+            .insert_l1_batch_pubdata_by_contract(L1BatchNumber(1), &pubdata_by_contract)
+            .await
+            .unwrap();
+
+        let pubdata = conn
+            .blocks_web3_dal()
+            .get_l1_batch_pubdata_by_contract(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let expected_pubdata = [
+            pubdata_by_contract[1],
+            pubdata_by_contract[0],
+            pubdata_by_contract[2],
+        ];
+        assert_eq!(pubdata, expected_pubdata);
+
+        conn.blocks_dal()
+            .delete_l1_batches(L1BatchNumber(0))
+            .await
+            .unwrap();
+        let pubdata = conn
+            .blocks_web3_dal()
+            .get_l1_batch_pubdata_by_contract(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert!(pubdata.is_empty());
     }
 }
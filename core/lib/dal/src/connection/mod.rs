@@ -16,12 +16,16 @@ use sqlx::{
     postgres::{PgConnectOptions, PgPool, PgPoolOptions, Postgres},
 };
 
-pub use self::processor::StorageProcessor;
 pub(crate) use self::processor::StorageProcessorTags;
-use self::processor::TracedConnections;
+pub use self::{processor::StorageProcessor, replicas::PinnedReadReplicaFuture};
+use self::{
+    processor::TracedConnections,
+    replicas::{PinnedReadReplicaSeed, ReadReplicas},
+};
 use crate::metrics::CONNECTION_METRICS;
 
 mod processor;
+mod replicas;
 
 /// Builder for [`ConnectionPool`]s.
 #[derive(Clone)]
@@ -30,6 +34,8 @@ pub struct ConnectionPoolBuilder {
     max_size: u32,
     acquire_timeout: Duration,
     statement_timeout: Option<Duration>,
+    read_replica_urls: Vec<String>,
+    max_replica_lag: Duration,
 }
 
 impl fmt::Debug for ConnectionPoolBuilder {
@@ -40,6 +46,8 @@ impl fmt::Debug for ConnectionPoolBuilder {
             .field("max_size", &self.max_size)
             .field("acquire_timeout", &self.acquire_timeout)
             .field("statement_timeout", &self.statement_timeout)
+            .field("read_replica_count", &self.read_replica_urls.len())
+            .field("max_replica_lag", &self.max_replica_lag)
             .finish()
     }
 }
@@ -63,34 +71,78 @@ impl ConnectionPoolBuilder {
         self
     }
 
+    /// Sets read replicas for the pool. Connections are distributed among replicas with the replication lag
+    /// not exceeding `max_lag`; if all replicas are stale, connections are acquired using the main database URL.
+    /// Hence, read replicas must only be set for pools used exclusively for reading data.
+    ///
+    /// The maximum pool size is split evenly among replicas (each replica gets at least 1 connection), so that
+    /// the total number of connections to replicas doesn't exceed it.
+    pub fn set_read_replicas(&mut self, urls: Vec<String>, max_lag: Duration) -> &mut Self {
+        self.read_replica_urls = urls;
+        self.max_replica_lag = max_lag;
+        self
+    }
+
     /// Returns the maximum number of connections that can be allocated by the pool.
     pub fn max_size(&self) -> u32 {
         self.max_size
     }
 
-    /// Builds a connection pool from this builder.
-    pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
-        let options = PgPoolOptions::new()
-            .max_connections(self.max_size)
-            .acquire_timeout(self.acquire_timeout);
-        let mut connect_options: PgConnectOptions = self
-            .database_url
+    fn pool_options(&self, max_size: u32) -> PgPoolOptions {
+        PgPoolOptions::new()
+            .max_connections(max_size)
+            .acquire_timeout(self.acquire_timeout)
+    }
+
+    fn connect_options(&self, database_url: &str) -> anyhow::Result<PgConnectOptions> {
+        let mut connect_options: PgConnectOptions = database_url
             .parse()
             .context("Failed parsing database URL")?;
         if let Some(timeout) = self.statement_timeout {
             let timeout_string = format!("{}s", timeout.as_secs());
             connect_options = connect_options.options([("statement_timeout", timeout_string)]);
         }
-        let pool = options
-            .connect_with(connect_options)
+        Ok(connect_options)
+    }
+
+    /// Builds a connection pool from this builder.
+    pub async fn build(&self) -> anyhow::Result<ConnectionPool> {
+        let pool = self
+            .pool_options(self.max_size)
+            .connect_with(self.connect_options(&self.database_url)?)
             .await
             .context("Failed connecting to database")?;
+
+        let read_replicas = if self.read_replica_urls.is_empty() {
+            None
+        } else {
+            // Replica pools are lazy, so that an unavailable replica doesn't prevent the pool from being built.
+            // Such a replica will be considered stale until it becomes available.
+            let replica_count = self.read_replica_urls.len() as u32;
+            let replica_max_size = (self.max_size / replica_count).max(1);
+            let replica_pools = self
+                .read_replica_urls
+                .iter()
+                .map(|url| {
+                    Ok(self
+                        .pool_options(replica_max_size)
+                        .connect_lazy_with(self.connect_options(url)?))
+                })
+                .collect::<anyhow::Result<_>>()
+                .context("failed creating read replica pools")?;
+            let read_replicas = Arc::new(ReadReplicas::new(replica_pools, self.max_replica_lag));
+            ReadReplicas::spawn_monitor(&read_replicas);
+            Some(read_replicas)
+        };
+
         tracing::info!("Created DB pool with parameters {self:?}");
         Ok(ConnectionPool {
             database_url: self.database_url.clone(),
             inner: pool,
             max_size: self.max_size,
             traced_connections: None,
+            read_replicas,
+            pinned_replica_seed: None,
         })
     }
 
@@ -231,12 +283,35 @@ impl GlobalConnectionPoolConfig {
     }
 }
 
-#[derive(Clone)]
+/// Postgres connection pool.
+///
+/// If the pool has [read replicas](ConnectionPoolBuilder::set_read_replicas()), connections acquired within
+/// a future wrapped in [`Self::pin_read_replica()`] are routed to the same replica (as long as it stays fresh),
+/// so that they observe a consistent database state. Cloning a pool within such a future pins the clone
+/// to the same replica; this allows to keep consistency for connections acquired in blocking or spawned tasks.
+/// Connections acquired outside pinned futures are distributed among replicas in the round-robin fashion.
 pub struct ConnectionPool {
     pub(crate) inner: PgPool,
     database_url: String,
     max_size: u32,
     traced_connections: Option<Arc<TracedConnections>>,
+    read_replicas: Option<Arc<ReadReplicas>>,
+    pinned_replica_seed: Option<PinnedReadReplicaSeed>,
+}
+
+impl Clone for ConnectionPool {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            database_url: self.database_url.clone(),
+            max_size: self.max_size,
+            traced_connections: self.traced_connections.clone(),
+            read_replicas: self.read_replicas.clone(),
+            pinned_replica_seed: self
+                .pinned_replica_seed
+                .or_else(PinnedReadReplicaSeed::current),
+        }
+    }
 }
 
 impl fmt::Debug for ConnectionPool {
@@ -246,6 +321,7 @@ impl fmt::Debug for ConnectionPool {
         formatter
             .debug_struct("ConnectionPool")
             .field("max_size", &self.max_size)
+            .field("has_read_replicas", &self.read_replicas.is_some())
             .finish_non_exhaustive()
     }
 }
@@ -294,6 +370,8 @@ impl ConnectionPool {
             max_size: max_pool_size,
             acquire_timeout: Duration::from_secs(30), // Default value used by `sqlx`
             statement_timeout: None,
+            read_replica_urls: Vec::new(),
+            max_replica_lag: Duration::from_secs(5),
         }
     }
 
@@ -363,6 +441,37 @@ impl ConnectionPool {
         ))
     }
 
+    /// Wraps the provided future so that all connections acquired within it (including via pools cloned within it)
+    /// are routed to the same read replica. Nested calls retain the outer replica. This is a no-op for pools
+    /// without read replicas.
+    pub fn pin_read_replica<F: Future>(future: F) -> PinnedReadReplicaFuture<F> {
+        PinnedReadReplicaSeed::scope(future)
+    }
+
+    /// Returns a pool that acquires all connections using the main database URL, ignoring read replicas.
+    /// This should be used by components that must not observe the database state going back
+    /// (e.g., when switching to a replica lagging more than the one used previously).
+    pub fn without_read_replicas(&self) -> Self {
+        Self {
+            read_replicas: None,
+            pinned_replica_seed: None,
+            ..self.clone()
+        }
+    }
+
+    /// Selects the underlying pool to acquire a connection from.
+    fn select_pool(&self) -> &PgPool {
+        self.read_replicas
+            .as_ref()
+            .and_then(|replicas| {
+                let seed = self
+                    .pinned_replica_seed
+                    .or_else(PinnedReadReplicaSeed::current);
+                replicas.select(seed)
+            })
+            .unwrap_or(&self.inner)
+    }
+
     async fn acquire_connection_retried(
         &self,
         tags: Option<&StorageProcessorTags>,
@@ -376,7 +485,7 @@ impl ConnectionPool {
                 .observe(self.inner.size() as usize);
             CONNECTION_METRICS.pool_idle.observe(self.inner.num_idle());
 
-            let connection = self.inner.acquire().await;
+            let connection = self.select_pool().acquire().await;
             let connection_err = match connection {
                 Ok(connection) => return Ok(connection),
                 Err(err) => err,
@@ -394,7 +503,7 @@ impl ConnectionPool {
         }
 
         // Attempting to get the pooled connection for the last time
-        match self.inner.acquire().await {
+        match self.select_pool().acquire().await {
             Ok(conn) => Ok(conn),
             Err(err) => {
                 Self::report_connection_error(&err);
@@ -448,4 +557,97 @@ mod tests {
             sqlx::Error::Database(db_err) if db_err.message().contains("statement timeout")
         );
     }
+
+    #[tokio::test]
+    async fn routing_connections_to_read_replicas() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db(2)
+            .await
+            .unwrap()
+            .database_url;
+
+        // The test database is not a replica, so its replication lag is always zero.
+        let pool = ConnectionPool::builder(&db_url, 2)
+            .set_acquire_timeout(Some(Duration::from_secs(1)))
+            .set_read_replicas(
+                vec![
+                    db_url.clone(),
+                    "postgres://localhost:1/unavailable".to_owned(),
+                ],
+                Duration::from_secs(1),
+            )
+            .build()
+            .await
+            .unwrap();
+        let read_replicas = pool.read_replicas.clone().unwrap();
+        read_replicas.check_lag().await;
+        assert_eq!(read_replicas.fresh_count(), 1);
+        for _ in 0..4 {
+            assert!(read_replicas.select(None).is_some());
+        }
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let value: i32 = sqlx::query_scalar("SELECT 1")
+            .fetch_one(storage.conn())
+            .await
+            .unwrap();
+        assert_eq!(value, 1);
+    }
+
+    fn selected_pool_address(pool: &ConnectionPool) -> usize {
+        pool.select_pool() as *const PgPool as usize
+    }
+
+    #[tokio::test]
+    async fn pinning_read_replica() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db(4)
+            .await
+            .unwrap()
+            .database_url;
+        let pool = ConnectionPool::builder(&db_url, 4)
+            .set_acquire_timeout(Some(Duration::from_secs(1)))
+            .set_read_replicas(vec![db_url.clone(), db_url.clone()], Duration::from_secs(1))
+            .build()
+            .await
+            .unwrap();
+        let read_replicas = pool.read_replicas.clone().unwrap();
+        read_replicas.check_lag().await;
+        assert_eq!(read_replicas.fresh_count(), 2);
+
+        // Unpinned connections are distributed among replicas.
+        let first_pool = selected_pool_address(&pool);
+        let second_pool = selected_pool_address(&pool);
+        assert_ne!(first_pool, second_pool);
+
+        ConnectionPool::pin_read_replica(async {
+            let pinned_pool = selected_pool_address(&pool);
+            for _ in 0..4 {
+                assert_eq!(selected_pool_address(&pool), pinned_pool);
+            }
+            // Pools cloned within a pinned future retain the pinned replica.
+            let cloned_pool = pool.clone();
+            tokio::task::spawn_blocking(move || {
+                assert_eq!(selected_pool_address(&cloned_pool), pinned_pool);
+            })
+            .await
+            .unwrap();
+            // Nested pinning retains the outer replica.
+            ConnectionPool::pin_read_replica(async {
+                assert_eq!(selected_pool_address(&pool), pinned_pool);
+            })
+            .await;
+
+            // Pools without read replicas use the main database URL.
+            let main_pool = pool.without_read_replicas();
+            assert!(main_pool.read_replicas.is_none());
+            assert_eq!(
+                selected_pool_address(&main_pool),
+                &main_pool.inner as *const _ as usize
+            );
+        })
+        .await;
+    }
 }
@@ -0,0 +1,168 @@
+//! Read replica routing for [`ConnectionPool`](super::ConnectionPool)s.
+
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use sqlx::{postgres::PgPool, Row};
+use tokio::task::futures::TaskLocalFuture;
+
+use crate::metrics::CONNECTION_METRICS;
+
+tokio::task_local! {
+    static PINNED_SEED: PinnedReadReplicaSeed;
+}
+
+/// Future returned by [`ConnectionPool::pin_read_replica()`](super::ConnectionPool::pin_read_replica()).
+pub type PinnedReadReplicaFuture<F> = TaskLocalFuture<PinnedReadReplicaSeed, F>;
+
+/// Seed determining the read replica used by connections acquired within a
+/// [pinned future](super::ConnectionPool::pin_read_replica()).
+#[derive(Debug, Clone, Copy)]
+pub struct PinnedReadReplicaSeed(usize);
+
+impl PinnedReadReplicaSeed {
+    /// Returns the seed for the current pinned future, if any.
+    pub(super) fn current() -> Option<Self> {
+        PINNED_SEED.try_with(|seed| *seed).ok()
+    }
+
+    pub(super) fn scope<F: Future>(future: F) -> PinnedReadReplicaFuture<F> {
+        static NEXT_SEED: AtomicUsize = AtomicUsize::new(0);
+
+        let seed =
+            Self::current().unwrap_or_else(|| Self(NEXT_SEED.fetch_add(1, Ordering::Relaxed)));
+        PINNED_SEED.scope(seed, future)
+    }
+}
+
+#[derive(Debug)]
+struct ReadReplica {
+    pool: PgPool,
+    /// Whether the replica lag is within the configured threshold. Replicas are considered stale
+    /// until they are checked for the first time.
+    is_fresh: AtomicBool,
+}
+
+/// Set of read replicas used to distribute read-only connections. Connections are assigned to fresh replicas
+/// in the round-robin fashion, unless they are acquired for a [pinned seed](PinnedReadReplicaSeed).
+#[derive(Debug)]
+pub(super) struct ReadReplicas {
+    replicas: Vec<ReadReplica>,
+    next_index: AtomicUsize,
+    max_lag: Duration,
+}
+
+impl ReadReplicas {
+    /// Interval between replica lag checks.
+    const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(pools: Vec<PgPool>, max_lag: Duration) -> Self {
+        let replicas = pools
+            .into_iter()
+            .map(|pool| ReadReplica {
+                pool,
+                is_fresh: AtomicBool::new(false),
+            })
+            .collect();
+        Self {
+            replicas,
+            next_index: AtomicUsize::new(0),
+            max_lag,
+        }
+    }
+
+    /// Selects a fresh replica to acquire a connection from. Returns `None` if all replicas are stale.
+    ///
+    /// For a pinned seed, the same replica is returned as long as it is fresh; if it becomes stale,
+    /// the selection falls back to the next fresh replica.
+    pub fn select(&self, pinned_seed: Option<PinnedReadReplicaSeed>) -> Option<&PgPool> {
+        let replica_count = self.replicas.len();
+        let start_index = match pinned_seed {
+            Some(PinnedReadReplicaSeed(seed)) => seed,
+            None => self.next_index.fetch_add(1, Ordering::Relaxed),
+        };
+        (0..replica_count).find_map(|offset| {
+            let replica = &self.replicas[(start_index + offset) % replica_count];
+            replica
+                .is_fresh
+                .load(Ordering::Relaxed)
+                .then_some(&replica.pool)
+        })
+    }
+
+    pub fn fresh_count(&self) -> usize {
+        self.replicas
+            .iter()
+            .filter(|replica| replica.is_fresh.load(Ordering::Relaxed))
+            .count()
+    }
+
+    async fn replication_lag(pool: &PgPool) -> anyhow::Result<Duration> {
+        // Same semantics as in `SystemDal::get_replication_lag_sec()`: the lag is only meaningful
+        // if the replica is not synced.
+        let row = sqlx::query(
+            "SELECT \
+                 pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() AS synced, \
+                 EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())::BIGINT AS lag",
+        )
+        .fetch_one(pool)
+        .await
+        .context("failed querying replication lag")?;
+
+        let lag_sec = match row.try_get::<Option<bool>, _>("synced")? {
+            Some(false) => row.try_get::<Option<i64>, _>("lag")?.unwrap_or(0),
+            _ => 0,
+        };
+        Ok(Duration::from_secs(lag_sec.max(0) as u64))
+    }
+
+    /// Checks lag for all replicas and updates their freshness.
+    pub async fn check_lag(&self) {
+        for (i, replica) in self.replicas.iter().enumerate() {
+            let is_fresh = match Self::replication_lag(&replica.pool).await {
+                Ok(lag) if lag <= self.max_lag => true,
+                Ok(lag) => {
+                    tracing::info!(
+                        "Read replica #{i} lags by {lag:?}, which exceeds {:?}; \
+                         excluding it from routing",
+                        self.max_lag
+                    );
+                    false
+                }
+                Err(err) => {
+                    tracing::warn!("Failed checking lag for read replica #{i}: {err:#}");
+                    false
+                }
+            };
+            replica.is_fresh.store(is_fresh, Ordering::Relaxed);
+        }
+        CONNECTION_METRICS
+            .fresh_read_replicas
+            .set(self.fresh_count());
+    }
+
+    /// Periodically checks replica lag until the replica set is dropped (i.e., all connection pools using it
+    /// are dropped).
+    pub async fn monitor(this: Weak<Self>) {
+        loop {
+            let Some(replicas) = this.upgrade() else {
+                return;
+            };
+            replicas.check_lag().await;
+            drop(replicas);
+            tokio::time::sleep(Self::CHECK_INTERVAL).await;
+        }
+    }
+
+    /// Spawns a task monitoring replica lag.
+    pub fn spawn_monitor(this: &Arc<Self>) {
+        tokio::spawn(Self::monitor(Arc::downgrade(this)));
+    }
+}
@@ -0,0 +1,85 @@
+use zksync_types::L1BatchNumber;
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// DAL for resyncs requested by the consistency checker on external nodes.
+#[derive(Debug)]
+pub struct ConsistencyCheckerDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl ConsistencyCheckerDal<'_, '_> {
+    /// Returns the number of resyncs requested because of the specified inconsistent L1 batch.
+    pub async fn get_resync_attempts(
+        &mut self,
+        inconsistent_l1_batch: L1BatchNumber,
+    ) -> sqlx::Result<u32> {
+        let attempts = sqlx::query_scalar!(
+            r#"
+            SELECT
+                attempts
+            FROM
+                consistency_checker_resyncs
+            WHERE
+                inconsistent_l1_batch_number = $1
+            "#,
+            i64::from(inconsistent_l1_batch.0)
+        )
+        .instrument("get_resync_attempts")
+        .with_arg("inconsistent_l1_batch", &inconsistent_l1_batch)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(attempts.map_or(0, |attempts| attempts as u32))
+    }
+
+    /// Records a resync requested because of the specified inconsistent L1 batch.
+    pub async fn record_resync_attempt(
+        &mut self,
+        inconsistent_l1_batch: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                consistency_checker_resyncs (
+                    inconsistent_l1_batch_number,
+                    attempts,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, 1, NOW(), NOW())
+            ON CONFLICT (inconsistent_l1_batch_number) DO
+            UPDATE
+            SET
+                attempts = consistency_checker_resyncs.attempts + 1,
+                updated_at = NOW()
+            "#,
+            i64::from(inconsistent_l1_batch.0)
+        )
+        .instrument("record_resync_attempt")
+        .with_arg("inconsistent_l1_batch", &inconsistent_l1_batch)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn recording_resync_attempts() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let mut dal = conn.consistency_checker_dal();
+        assert_eq!(dal.get_resync_attempts(L1BatchNumber(5)).await.unwrap(), 0);
+
+        dal.record_resync_attempt(L1BatchNumber(5)).await.unwrap();
+        assert_eq!(dal.get_resync_attempts(L1BatchNumber(5)).await.unwrap(), 1);
+        dal.record_resync_attempt(L1BatchNumber(5)).await.unwrap();
+        assert_eq!(dal.get_resync_attempts(L1BatchNumber(5)).await.unwrap(), 2);
+        assert_eq!(dal.get_resync_attempts(L1BatchNumber(6)).await.unwrap(), 0);
+    }
+}
@@ -1,4 +1,4 @@
-use std::{convert::TryFrom, str::FromStr};
+use std::{convert::TryFrom, str::FromStr, time::Duration};
 
 use anyhow::Context as _;
 use sqlx::{
@@ -51,6 +51,26 @@ impl EthSenderDal<'_, '_> {
         Ok(txs.into_iter().map(|tx| tx.into()).collect())
     }
 
+    /// Returns all transactions that are not confirmed yet (including ones that were never sent), ordered by ID.
+    pub async fn get_unconfirmed_txs(&mut self) -> sqlx::Result<Vec<EthTx>> {
+        let txs = sqlx::query_as!(
+            StorageEthTx,
+            r#"
+            SELECT
+                *
+            FROM
+                eth_txs
+            WHERE
+                confirmed_eth_tx_history_id IS NULL
+            ORDER BY
+                id
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+        Ok(txs.into_iter().map(|tx| tx.into()).collect())
+    }
+
     pub async fn get_eth_l1_batches(&mut self) -> sqlx::Result<L1BatchEthSenderStats> {
         let mut stats = L1BatchEthSenderStats::default();
         for tx_type in ["execute_tx", "commit_tx", "prove_tx"] {
@@ -498,6 +518,33 @@ impl EthSenderDal<'_, '_> {
         Ok(sent_at_block.flatten().map(|block| block as u32))
     }
 
+    /// Returns the time elapsed since the first successfully sent attempt for the specified Ethereum transaction.
+    /// The time is computed by Postgres since `sent_at` is a timestamp without time zone set using `NOW()`.
+    pub async fn get_time_since_first_sent_attempt(
+        &mut self,
+        eth_tx_id: u32,
+    ) -> sqlx::Result<Option<Duration>> {
+        let elapsed_secs = sqlx::query_scalar!(
+            r#"
+            SELECT
+                EXTRACT(
+                    EPOCH
+                    FROM
+                        NOW()::TIMESTAMP - MIN(sent_at)
+                )::FLOAT8 AS "elapsed_secs"
+            FROM
+                eth_txs_history
+            WHERE
+                eth_tx_id = $1
+                AND sent_at IS NOT NULL
+            "#,
+            eth_tx_id as i32
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(elapsed_secs.map(|secs| Duration::from_secs_f64(secs.max(0.0))))
+    }
+
     pub async fn get_last_sent_eth_tx(
         &mut self,
         eth_tx_id: u32,
@@ -596,6 +643,24 @@ impl EthSenderDal<'_, '_> {
         .context("count field is missing")
     }
 
+    /// Removes all unconfirmed transactions with ID greater or equal to `eth_tx_id` together with their
+    /// sending attempts. References to the removed transactions from L1 batches are reset, so the corresponding
+    /// aggregated operations will be re-created. Returns the number of removed transactions.
+    pub async fn delete_unconfirmed_txs_since(&mut self, eth_tx_id: u32) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM eth_txs
+            WHERE
+                id >= $1
+                AND confirmed_eth_tx_history_id IS NULL
+            "#,
+            eth_tx_id as i32
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn clear_failed_transactions(&mut self) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
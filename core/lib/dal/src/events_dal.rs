@@ -7,7 +7,7 @@ use zksync_types::{
     event::L1_MESSENGER_BYTECODE_PUBLICATION_EVENT_SIGNATURE,
     l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
     tx::IncludedTxLocation,
-    L1BatchNumber, MiniblockNumber, VmEvent, H256,
+    Address, L1BatchNumber, MiniblockNumber, VmEvent, H256,
 };
 
 use crate::{
@@ -237,6 +237,65 @@ impl EventsDal<'_, '_> {
         Ok(result)
     }
 
+    /// Returns all events emitted in the specified L1 batch in the order they were emitted by the VM.
+    /// Event locations are restored to match the ones produced by the VM, i.e., they contain the index
+    /// of the emitting transaction in the L1 batch (events emitted in the fictive miniblock have the index
+    /// equal to the number of transactions in the batch).
+    pub async fn get_vm_events_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Vec<VmEvent>, SqlxError> {
+        let rows = sqlx::query!(
+            r#"
+            WITH
+                batch_miniblocks AS (
+                    SELECT
+                        number,
+                        SUM(l1_tx_count + l2_tx_count) OVER (
+                            ORDER BY
+                                number
+                        ) - l1_tx_count - l2_tx_count AS first_tx_index
+                    FROM
+                        miniblocks
+                    WHERE
+                        l1_batch_number = $1
+                )
+            SELECT
+                events.address,
+                events.topic1,
+                events.topic2,
+                events.topic3,
+                events.topic4,
+                events.value,
+                events.tx_index_in_block + batch_miniblocks.first_tx_index AS "tx_index_in_l1_batch!"
+            FROM
+                events
+                INNER JOIN batch_miniblocks ON events.miniblock_number = batch_miniblocks.number
+            ORDER BY
+                events.miniblock_number,
+                events.event_index_in_block
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let events = rows.into_iter().map(|row| {
+            let indexed_topics = [row.topic1, row.topic2, row.topic3, row.topic4]
+                .into_iter()
+                .filter(|topic| !topic.is_empty())
+                .map(|topic| H256::from_slice(&topic))
+                .collect();
+            VmEvent {
+                location: (l1_batch_number, row.tx_index_in_l1_batch as u32),
+                address: Address::from_slice(&row.address),
+                indexed_topics,
+                value: row.value,
+            }
+        });
+        Ok(events.collect())
+    }
+
     pub(crate) async fn get_l1_batch_raw_published_bytecode_hashes(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -331,7 +390,8 @@ impl EventsDal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::{Address, L1BatchNumber, ProtocolVersion};
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
 
     use super::*;
     use crate::{tests::create_miniblock_header, ConnectionPool};
@@ -504,4 +564,90 @@ mod tests {
             assert_eq!(log.sender.as_bytes(), expected_log.0.sender.as_bytes());
         }
     }
+
+    #[tokio::test]
+    async fn loading_vm_events_for_l1_batch() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let header = L1BatchHeader::new(
+            L1BatchNumber(1),
+            0,
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        let mut miniblock_header = create_miniblock_header(1);
+        miniblock_header.l2_tx_count = 2;
+        conn.blocks_dal()
+            .insert_miniblock(&miniblock_header)
+            .await
+            .unwrap();
+        // Fictive miniblock
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(2))
+            .await
+            .unwrap();
+
+        let first_location = IncludedTxLocation {
+            tx_hash: H256([1; 32]),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        let second_location = IncludedTxLocation {
+            tx_hash: H256([2; 32]),
+            tx_index_in_miniblock: 1,
+            tx_initiator_address: Address::default(),
+        };
+        let mut events = vec![
+            create_vm_event(0, 0),
+            create_vm_event(1, 4),
+            create_vm_event(2, 1),
+        ];
+        events[2].location.1 = 1;
+        let all_events = vec![
+            (first_location, vec![&events[0]]),
+            (second_location, vec![&events[1], &events[2]]),
+        ];
+        conn.events_dal()
+            .save_events(MiniblockNumber(1), &all_events)
+            .await;
+
+        let fictive_location = IncludedTxLocation {
+            tx_hash: H256::zero(),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Address::default(),
+        };
+        let fictive_event = create_vm_event(2, 2);
+        conn.events_dal()
+            .save_events(
+                MiniblockNumber(2),
+                &[(fictive_location, vec![&fictive_event])],
+            )
+            .await;
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+
+        let loaded_events = conn
+            .events_dal()
+            .get_vm_events_for_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let expected_events: Vec<_> = events.into_iter().chain([fictive_event]).collect();
+        assert_eq!(loaded_events, expected_events);
+
+        let loaded_events = conn
+            .events_dal()
+            .get_vm_events_for_l1_batch(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert!(loaded_events.is_empty());
+    }
 }
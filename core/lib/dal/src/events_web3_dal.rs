@@ -1,6 +1,7 @@
 use sqlx::Row;
 use zksync_types::{
     api::{GetLogsFilter, Log},
+    event::bloom_bit_indices,
     Address, MiniblockNumber, H256,
 };
 
@@ -111,6 +112,86 @@ impl EventsWeb3Dal<'_, '_> {
         }
     }
 
+    /// Narrows down the miniblock range of the filter using logs bloom filters of miniblocks, so that the range
+    /// starts and ends with miniblocks that may contain matching logs. Returns `None` if no miniblock in the range
+    /// may contain matching logs. Miniblocks without a bloom filter (e.g., ones sealed before bloom filters
+    /// were introduced) are never skipped.
+    pub async fn get_logs_block_range(
+        &mut self,
+        filter: &GetLogsFilter,
+    ) -> Result<Option<(MiniblockNumber, MiniblockNumber)>, SqlxError> {
+        let Some(bloom_condition) = Self::build_bloom_condition(filter) else {
+            return Ok(Some((filter.from_block, filter.to_block)));
+        };
+
+        let query = format!(
+            r#"
+                SELECT MIN(number) AS "from_block", MAX(number) AS "to_block"
+                FROM miniblocks
+                WHERE number BETWEEN $1 AND $2 AND (logs_bloom IS NULL OR {})
+            "#,
+            bloom_condition
+        );
+        let row = sqlx::query(&query)
+            .bind(filter.from_block.0 as i64)
+            .bind(filter.to_block.0 as i64)
+            .instrument("get_logs_block_range")
+            .report_latency()
+            .with_arg("filter", filter)
+            .fetch_optional(self.storage)
+            .await?;
+
+        Ok(row.and_then(|row| {
+            let from_block = row.get::<Option<i64>, _>("from_block")?;
+            let to_block = row.get::<Option<i64>, _>("to_block")?;
+            Some((
+                MiniblockNumber(from_block as u32),
+                MiniblockNumber(to_block as u32),
+            ))
+        }))
+    }
+
+    /// Builds an SQL condition checking whether the `logs_bloom` may contain logs matching the filter.
+    /// Returns `None` if the filter doesn't restrict addresses or topics.
+    fn build_bloom_condition(filter: &GetLogsFilter) -> Option<String> {
+        let mut conditions = vec![];
+        if !filter.addresses.is_empty() {
+            let addresses = filter.addresses.iter().map(Address::as_bytes);
+            conditions.push(Self::build_bloom_condition_for_items(addresses));
+        }
+        for (_, topics) in &filter.topics {
+            let topics = topics.iter().map(H256::as_bytes);
+            conditions.push(Self::build_bloom_condition_for_items(topics));
+        }
+
+        if conditions.is_empty() {
+            None
+        } else {
+            Some(format!("({})", conditions.join(" AND ")))
+        }
+    }
+
+    /// Builds an SQL condition checking whether the `logs_bloom` may contain any of the `items`.
+    fn build_bloom_condition_for_items<'a>(items: impl Iterator<Item = &'a [u8]>) -> String {
+        let item_conditions: Vec<_> = items
+            .map(|item| {
+                let bit_conditions = bloom_bit_indices(item).map(|bit| {
+                    // Postgres numbers bits starting from the least significant bit of the first byte,
+                    // while bloom bits are numbered starting from the least significant bit of the last byte.
+                    let pg_bit = (255 - bit / 8) * 8 + bit % 8;
+                    format!("get_bit(logs_bloom, {pg_bit}) = 1")
+                });
+                format!("({})", bit_conditions.join(" AND "))
+            })
+            .collect();
+
+        if item_conditions.is_empty() {
+            "FALSE".to_owned()
+        } else {
+            format!("({})", item_conditions.join(" OR "))
+        }
+    }
+
     fn build_get_logs_where_clause(&self, filter: &GetLogsFilter) -> (String, u8) {
         let mut arg_index = 1;
 
@@ -189,14 +270,82 @@ impl EventsWeb3Dal<'_, '_> {
             Ok(logs)
         }
     }
+
+    /// Returns at most `limit` latest logs from miniblocks after `from_block`, in the ascending order.
+    pub async fn get_latest_logs(
+        &mut self,
+        from_block: MiniblockNumber,
+        limit: usize,
+    ) -> Result<Vec<Log>, SqlxError> {
+        let db_logs: Vec<StorageWeb3Log> = sqlx::query_as!(
+            StorageWeb3Log,
+            r#"
+            WITH
+                events_select AS (
+                    SELECT
+                        address,
+                        topic1,
+                        topic2,
+                        topic3,
+                        topic4,
+                        value,
+                        miniblock_number,
+                        tx_hash,
+                        tx_index_in_block,
+                        event_index_in_block,
+                        event_index_in_tx
+                    FROM
+                        events
+                    WHERE
+                        miniblock_number > $1
+                    ORDER BY
+                        miniblock_number DESC,
+                        event_index_in_block DESC
+                    LIMIT
+                        $2
+                )
+            SELECT
+                miniblocks.hash AS "block_hash?",
+                address AS "address!",
+                topic1 AS "topic1!",
+                topic2 AS "topic2!",
+                topic3 AS "topic3!",
+                topic4 AS "topic4!",
+                value AS "value!",
+                miniblock_number AS "miniblock_number!",
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                tx_hash AS "tx_hash!",
+                tx_index_in_block AS "tx_index_in_block!",
+                event_index_in_block AS "event_index_in_block!",
+                event_index_in_tx AS "event_index_in_tx!"
+            FROM
+                events_select
+                INNER JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+            ORDER BY
+                miniblock_number ASC,
+                event_index_in_block ASC
+            "#,
+            i64::from(from_block.0),
+            limit as i64
+        )
+        .instrument("get_latest_logs")
+        .with_arg("from_block", &from_block)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+        Ok(db_logs.into_iter().map(Into::into).collect())
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::{Address, H256};
+    use zksync_types::{
+        block::MiniblockHeader, event::logs_bloom, tx::IncludedTxLocation, Address, L1BatchNumber,
+        ProtocolVersion, VmEvent, H256,
+    };
 
     use super::*;
-    use crate::connection::ConnectionPool;
+    use crate::{connection::ConnectionPool, tests::create_miniblock_header};
 
     #[tokio::test]
     async fn test_build_get_logs_where_clause() {
@@ -218,4 +367,140 @@ mod tests {
         assert_eq!(actual_sql, expected_sql);
         assert_eq!(actual_arg_index, expected_arg_index);
     }
+
+    #[tokio::test]
+    async fn narrowing_logs_block_range_using_bloom() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![H256::repeat_byte(2), H256::repeat_byte(3)],
+            value: vec![],
+        };
+        for number in 0..5 {
+            let events = if number == 1 || number == 3 {
+                vec![&event]
+            } else {
+                vec![]
+            };
+            let header = MiniblockHeader {
+                logs_bloom: logs_bloom(events),
+                ..create_miniblock_header(number)
+            };
+            conn.blocks_dal().insert_miniblock(&header).await.unwrap();
+        }
+
+        let mut filter = GetLogsFilter {
+            from_block: MiniblockNumber(0),
+            to_block: MiniblockNumber(4),
+            addresses: vec![],
+            topics: vec![],
+        };
+        let range = conn
+            .events_web3_dal()
+            .get_logs_block_range(&filter)
+            .await
+            .unwrap();
+        assert_eq!(range, Some((MiniblockNumber(0), MiniblockNumber(4))));
+
+        filter.addresses = vec![Address::repeat_byte(0xff), event.address];
+        filter.topics = vec![(2, vec![H256::repeat_byte(3)])];
+        let range = conn
+            .events_web3_dal()
+            .get_logs_block_range(&filter)
+            .await
+            .unwrap();
+        assert_eq!(range, Some((MiniblockNumber(1), MiniblockNumber(3))));
+
+        filter.from_block = MiniblockNumber(2);
+        let range = conn
+            .events_web3_dal()
+            .get_logs_block_range(&filter)
+            .await
+            .unwrap();
+        assert_eq!(range, Some((MiniblockNumber(3), MiniblockNumber(3))));
+
+        filter.topics = vec![(1, vec![H256::repeat_byte(0xff)])];
+        let range = conn
+            .events_web3_dal()
+            .get_logs_block_range(&filter)
+            .await
+            .unwrap();
+        assert_eq!(range, None);
+
+        // Miniblocks without a bloom filter must not be skipped.
+        sqlx::query("UPDATE miniblocks SET logs_bloom = NULL WHERE number = 4")
+            .execute(conn.conn())
+            .await
+            .unwrap();
+        let range = conn
+            .events_web3_dal()
+            .get_logs_block_range(&filter)
+            .await
+            .unwrap();
+        assert_eq!(range, Some((MiniblockNumber(4), MiniblockNumber(4))));
+    }
+
+    #[tokio::test]
+    async fn getting_latest_logs() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let events: Vec<_> = (0..2)
+            .map(|i| VmEvent {
+                location: (L1BatchNumber(1), i),
+                address: Address::repeat_byte(1),
+                indexed_topics: vec![H256::repeat_byte(2)],
+                value: vec![],
+            })
+            .collect();
+        for number in 0..4 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+            let location = IncludedTxLocation {
+                tx_hash: H256::from_low_u64_be(number.into()),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Address::default(),
+            };
+            conn.events_dal()
+                .save_events(
+                    MiniblockNumber(number),
+                    &[(location, events.iter().collect())],
+                )
+                .await;
+        }
+
+        let logs = conn
+            .events_web3_dal()
+            .get_latest_logs(MiniblockNumber(0), 3)
+            .await
+            .unwrap();
+        let log_positions: Vec<_> = logs
+            .iter()
+            .map(|log| {
+                (
+                    log.block_number.unwrap().as_u32(),
+                    log.log_index.unwrap().as_u32(),
+                )
+            })
+            .collect();
+        assert_eq!(log_positions, [(2, 1), (3, 0), (3, 1)]);
+
+        let logs = conn
+            .events_web3_dal()
+            .get_latest_logs(MiniblockNumber(2), 10)
+            .await
+            .unwrap();
+        assert_eq!(logs.len(), 2);
+    }
 }
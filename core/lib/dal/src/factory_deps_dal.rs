@@ -2,10 +2,10 @@ use std::collections::{HashMap, HashSet};
 
 use anyhow::Context as _;
 use zksync_contracts::{BaseSystemContracts, SystemContractCode};
-use zksync_types::{MiniblockNumber, H256, U256};
+use zksync_types::{get_known_code_key, MiniblockNumber, H256, U256};
 use zksync_utils::{bytes_to_be_words, bytes_to_chunks};
 
-use crate::StorageProcessor;
+use crate::{instrument::InstrumentExt, StorageProcessor};
 
 /// DAL methods related to factory dependencies.
 #[derive(Debug)]
@@ -156,6 +156,170 @@ impl FactoryDepsDal<'_, '_> {
         .collect())
     }
 
+    /// Returns factory deps inserted in miniblocks up to and including `last_miniblock` that were never marked as known
+    /// in `KnownCodesStorage` (e.g., because the transaction publishing them has failed) and aren't base system
+    /// contracts of any protocol version. Known deps must be retained even if they aren't deployed yet, since
+    /// they can be deployed by their hash at any time (e.g., by a factory contract).
+    ///
+    /// At most `limit` deps ordered by bytecode hash are scanned, starting after `after_hash` (if specified).
+    /// Returns `(bytecode_hash, bytecode_len)` pairs for unknown deps among the scanned ones, and the hash
+    /// of the last scanned dep if the scan has not reached the end.
+    pub async fn get_unknown_factory_deps(
+        &mut self,
+        last_miniblock: MiniblockNumber,
+        after_hash: Option<H256>,
+        limit: usize,
+    ) -> sqlx::Result<(Vec<(H256, u64)>, Option<H256>)> {
+        let after_hash = after_hash.unwrap_or_else(H256::zero);
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                bytecode_hash,
+                LENGTH(bytecode) AS "bytecode_len!"
+            FROM
+                factory_deps
+            WHERE
+                miniblock_number <= $1
+                AND bytecode_hash > $2
+            ORDER BY
+                bytecode_hash
+            LIMIT
+                $3
+            "#,
+            i64::from(last_miniblock.0),
+            after_hash.as_bytes(),
+            limit as i64
+        )
+        .instrument("get_unknown_factory_deps")
+        .with_arg("last_miniblock", &last_miniblock)
+        .with_arg("after_hash", &after_hash)
+        .fetch_all(self.storage)
+        .await?;
+
+        let next_hash = if rows.len() < limit {
+            None
+        } else {
+            rows.last().map(|row| H256::from_slice(&row.bytecode_hash))
+        };
+        let deps: Vec<_> = rows
+            .into_iter()
+            .map(|row| {
+                let hash = H256::from_slice(&row.bytecode_hash);
+                (hash, row.bytecode_len as u64)
+            })
+            .collect();
+        let hashes: Vec<_> = deps.iter().map(|&(hash, _)| hash).collect();
+        let retained_hashes = self.get_known_or_system_factory_deps(&hashes).await?;
+        let unknown_deps = deps
+            .into_iter()
+            .filter(|(hash, _)| !retained_hashes.contains(hash))
+            .collect();
+        Ok((unknown_deps, next_hash))
+    }
+
+    /// Filters the provided bytecode `hashes`, leaving only hashes marked as known in `KnownCodesStorage`
+    /// or used as base system contracts by any protocol version.
+    async fn get_known_or_system_factory_deps(
+        &mut self,
+        hashes: &[H256],
+    ) -> sqlx::Result<HashSet<H256>> {
+        let (bytecode_hashes, known_code_keys) = Self::known_code_keys(hashes);
+        // Known code keys are looked up by hashed keys, which allows using the primary key index of `storage_logs`.
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                candidates.bytecode_hash AS "bytecode_hash!"
+            FROM
+                UNNEST($1::bytea[], $2::bytea[]) AS candidates (bytecode_hash, known_code_key)
+            WHERE
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        storage_logs
+                    WHERE
+                        storage_logs.hashed_key = candidates.known_code_key
+                )
+                OR EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        protocol_versions
+                    WHERE
+                        protocol_versions.bootloader_code_hash = candidates.bytecode_hash
+                        OR protocol_versions.default_account_code_hash = candidates.bytecode_hash
+                )
+            "#,
+            &bytecode_hashes,
+            &known_code_keys
+        )
+        .instrument("get_known_or_system_factory_deps")
+        .with_arg("hashes.len", &hashes.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.bytecode_hash))
+            .collect())
+    }
+
+    /// Returns bytecode hashes together with hashed storage keys marking them as known in `KnownCodesStorage`.
+    fn known_code_keys(hashes: &[H256]) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        hashes
+            .iter()
+            .map(|hash| {
+                let known_code_key = get_known_code_key(hash).hashed_key();
+                (hash.as_bytes().to_vec(), known_code_key.as_bytes().to_vec())
+            })
+            .unzip()
+    }
+
+    /// Removes factory deps with the specified bytecode `hashes`. The check from
+    /// [`Self::get_unknown_factory_deps()`] is repeated, so that deps marked as known in the meantime are retained.
+    /// Returns the number of removed deps and the total length of their bytecodes.
+    pub async fn remove_unknown_factory_deps(
+        &mut self,
+        hashes: &[H256],
+    ) -> sqlx::Result<(usize, u64)> {
+        let (bytecode_hashes, known_code_keys) = Self::known_code_keys(hashes);
+        let rows = sqlx::query!(
+            r#"
+            DELETE FROM factory_deps USING UNNEST($1::bytea[], $2::bytea[]) AS candidates (bytecode_hash, known_code_key)
+            WHERE
+                factory_deps.bytecode_hash = candidates.bytecode_hash
+                AND NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        storage_logs
+                    WHERE
+                        storage_logs.hashed_key = candidates.known_code_key
+                )
+                AND NOT EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        protocol_versions
+                    WHERE
+                        protocol_versions.bootloader_code_hash = factory_deps.bytecode_hash
+                        OR protocol_versions.default_account_code_hash = factory_deps.bytecode_hash
+                )
+            RETURNING
+                LENGTH(factory_deps.bytecode) AS "bytecode_len!"
+            "#,
+            &bytecode_hashes,
+            &known_code_keys
+        )
+        .instrument("remove_unknown_factory_deps")
+        .with_arg("hashes.len", &hashes.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        let byte_count = rows.iter().map(|row| row.bytecode_len as u64).sum();
+        Ok((rows.len(), byte_count))
+    }
+
     /// Removes all factory deps with a miniblock number strictly greater than the specified `block_number`.
     pub async fn rollback_factory_deps(
         &mut self,
@@ -174,3 +338,124 @@ impl FactoryDepsDal<'_, '_> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::L1BatchHeader, get_code_key, Address, L1BatchNumber, ProtocolVersion,
+        ProtocolVersionId, StorageLog,
+    };
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    #[tokio::test]
+    async fn removing_unknown_factory_deps() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let deployed_hash = H256::repeat_byte(1);
+        let orphaned_hash = H256::repeat_byte(2);
+        let bootloader_hash = H256::repeat_byte(3);
+        let new_orphaned_hash = H256::repeat_byte(4);
+        // Known, but not yet deployed dep (e.g., a bytecode deployed by a factory contract).
+        let known_hash = H256::repeat_byte(5);
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion {
+                base_system_contracts_hashes: BaseSystemContractsHashes {
+                    bootloader: bootloader_hash,
+                    default_aa: H256::zero(),
+                },
+                ..ProtocolVersion::default()
+            })
+            .await;
+
+        for number in [1, 2] {
+            let header = L1BatchHeader::new(
+                L1BatchNumber(number),
+                0,
+                BaseSystemContractsHashes::default(),
+                ProtocolVersionId::default(),
+            );
+            conn.blocks_dal()
+                .insert_mock_l1_batch(&header)
+                .await
+                .unwrap();
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+        let factory_deps = HashMap::from([
+            (deployed_hash, vec![0; 32]),
+            (orphaned_hash, vec![0; 64]),
+            (bootloader_hash, vec![0; 96]),
+            (known_hash, vec![0; 128]),
+        ]);
+        conn.factory_deps_dal()
+            .insert_factory_deps(MiniblockNumber(1), &factory_deps)
+            .await
+            .unwrap();
+        let factory_deps = HashMap::from([(new_orphaned_hash, vec![0; 32])]);
+        conn.factory_deps_dal()
+            .insert_factory_deps(MiniblockNumber(2), &factory_deps)
+            .await
+            .unwrap();
+        let marker = H256::from_low_u64_be(1);
+        let logs = vec![
+            StorageLog::new_write_log(get_known_code_key(&deployed_hash), marker),
+            StorageLog::new_write_log(get_known_code_key(&known_hash), marker),
+            StorageLog::new_write_log(get_code_key(&Address::repeat_byte(1)), deployed_hash),
+        ];
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), logs)])
+            .await
+            .unwrap();
+
+        let (unknown_deps, next_hash) = conn
+            .factory_deps_dal()
+            .get_unknown_factory_deps(MiniblockNumber(1), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(unknown_deps, [(orphaned_hash, 64)]);
+        assert_eq!(next_hash, None);
+        let (unknown_deps, next_hash) = conn
+            .factory_deps_dal()
+            .get_unknown_factory_deps(MiniblockNumber(2), Some(orphaned_hash), 10)
+            .await
+            .unwrap();
+        assert_eq!(unknown_deps, [(new_orphaned_hash, 32)]);
+        assert_eq!(next_hash, None);
+        // The scan position must advance even if all scanned deps are known.
+        let (unknown_deps, next_hash) = conn
+            .factory_deps_dal()
+            .get_unknown_factory_deps(MiniblockNumber(2), None, 1)
+            .await
+            .unwrap();
+        assert!(unknown_deps.is_empty());
+        assert_eq!(next_hash, Some(deployed_hash));
+
+        let removed = conn
+            .factory_deps_dal()
+            .remove_unknown_factory_deps(&[
+                orphaned_hash,
+                new_orphaned_hash,
+                deployed_hash,
+                known_hash,
+                bootloader_hash,
+            ])
+            .await
+            .unwrap();
+        assert_eq!(removed, (2, 96));
+        let (unknown_deps, _) = conn
+            .factory_deps_dal()
+            .get_unknown_factory_deps(MiniblockNumber(2), None, 10)
+            .await
+            .unwrap();
+        assert!(unknown_deps.is_empty());
+        for hash in [deployed_hash, bootloader_hash, known_hash] {
+            let dep = conn.factory_deps_dal().get_factory_dep(hash).await.unwrap();
+            assert!(dep.is_some());
+        }
+    }
+}
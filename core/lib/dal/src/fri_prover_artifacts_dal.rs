@@ -0,0 +1,349 @@
+use std::str::FromStr;
+
+use strum::{Display, EnumString};
+use zksync_types::L1BatchNumber;
+
+use crate::{
+    fri_proof_compressor_dal::ProofCompressionJobStatus, instrument::InstrumentExt,
+    StorageProcessor,
+};
+
+/// DAL for intermediate artifacts of the FRI proving pipeline (witness inputs, circuits, intermediate proofs
+/// etc.) persisted in the object store.
+#[derive(Debug)]
+pub struct FriProverArtifactsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Type of intermediate artifact produced by the FRI proving pipeline. Each type is stored in a separate bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum ProverArtifactType {
+    /// Inputs of basic witness generation (`witness_inputs_fri.merkle_tree_paths_blob_url`).
+    WitnessInputs,
+    /// Circuits to prove (`prover_jobs_fri.circuit_blob_url`).
+    Circuit,
+    /// Proofs produced by the FRI prover, including the scheduler proof (`prover_jobs_fri.proof_blob_url`).
+    Proof,
+    /// Closed-form inputs for leaf aggregation (`leaf_aggregation_witness_jobs_fri.closed_form_inputs_blob_url`).
+    LeafAggregationInputs,
+    /// Aggregations for node aggregation (`node_aggregation_witness_jobs_fri.aggregations_url`).
+    NodeAggregationInputs,
+    /// Partial inputs for the scheduler (`scheduler_witness_jobs_fri.scheduler_partial_input_blob_url`).
+    SchedulerInputs,
+}
+
+/// Intermediate artifact of the FRI proving pipeline.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProverArtifact {
+    pub artifact_type: ProverArtifactType,
+    pub blob_url: String,
+}
+
+impl FriProverArtifactsDal<'_, '_> {
+    /// Returns L1 batches in the specified range with artifacts that weren't garbage-collected yet,
+    /// in the ascending order.
+    ///
+    /// Only batches with a completed (or skipped) proof compression job are returned. With proof sending modes
+    /// that don't wait for real proofs (`SkipEveryProof`, `OnlySampledProofs`), a batch can be proven on L1 while
+    /// its proving pipeline is still in flight. The returned batches stop at the first such batch, so that it
+    /// isn't skipped by the caller.
+    pub async fn get_l1_batches_with_uncleaned_artifacts(
+        &mut self,
+        from_l1_batch: L1BatchNumber,
+        to_l1_batch: L1BatchNumber,
+        limit: usize,
+    ) -> sqlx::Result<Vec<L1BatchNumber>> {
+        let completed_statuses = [
+            ProofCompressionJobStatus::Successful.to_string(),
+            ProofCompressionJobStatus::SentToServer.to_string(),
+            ProofCompressionJobStatus::Skipped.to_string(),
+        ];
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number AS "l1_batch_number!"
+            FROM
+                (
+                    SELECT
+                        witness_inputs_fri.l1_batch_number,
+                        BOOL_AND(COALESCE(proof_compression_jobs_fri.status = ANY ($3), FALSE)) OVER (
+                            ORDER BY
+                                witness_inputs_fri.l1_batch_number
+                        ) AS all_compressed
+                    FROM
+                        witness_inputs_fri
+                        LEFT JOIN proof_compression_jobs_fri ON proof_compression_jobs_fri.l1_batch_number = witness_inputs_fri.l1_batch_number
+                    WHERE
+                        witness_inputs_fri.l1_batch_number BETWEEN $1 AND $2
+                        AND witness_inputs_fri.is_blob_cleaned IS NOT TRUE
+                ) AS batches
+            WHERE
+                all_compressed
+            ORDER BY
+                l1_batch_number
+            LIMIT
+                $4
+            "#,
+            i64::from(from_l1_batch.0),
+            i64::from(to_l1_batch.0),
+            &completed_statuses[..],
+            limit as i64
+        )
+        .instrument("get_l1_batches_with_uncleaned_artifacts")
+        .with_arg("from_l1_batch", &from_l1_batch)
+        .with_arg("to_l1_batch", &to_l1_batch)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| L1BatchNumber(row.l1_batch_number as u32))
+            .collect())
+    }
+
+    /// Returns all intermediate artifacts for the specified L1 batch. The final (compressed) proof submitted to L1
+    /// is not an intermediate artifact and is never returned.
+    pub async fn get_artifacts(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<ProverArtifact>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                'witness_inputs' AS "artifact_type!",
+                merkle_tree_paths_blob_url AS "blob_url!"
+            FROM
+                witness_inputs_fri
+            WHERE
+                l1_batch_number = $1
+                AND merkle_tree_paths_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                'circuit',
+                circuit_blob_url
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            UNION ALL
+            SELECT
+                'proof',
+                proof_blob_url
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number = $1
+                AND proof_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                'leaf_aggregation_inputs',
+                closed_form_inputs_blob_url
+            FROM
+                leaf_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+                AND closed_form_inputs_blob_url IS NOT NULL
+            UNION ALL
+            SELECT
+                'node_aggregation_inputs',
+                aggregations_url
+            FROM
+                node_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+                AND aggregations_url IS NOT NULL
+            UNION ALL
+            SELECT
+                'scheduler_inputs',
+                scheduler_partial_input_blob_url
+            FROM
+                scheduler_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_prover_artifacts")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        rows.into_iter()
+            .map(|row| {
+                let artifact_type = ProverArtifactType::from_str(&row.artifact_type)
+                    .map_err(|err| sqlx::Error::Decode(err.into()))?;
+                Ok(ProverArtifact {
+                    artifact_type,
+                    blob_url: row.blob_url,
+                })
+            })
+            .collect()
+    }
+
+    /// Marks artifacts for the specified L1 batch as garbage-collected.
+    pub async fn mark_artifacts_as_cleaned(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            UPDATE witness_inputs_fri
+            SET
+                is_blob_cleaned = TRUE,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("mark_artifacts_as_cleaned#witness_inputs_fri")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(&mut transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE leaf_aggregation_witness_jobs_fri
+            SET
+                is_blob_cleaned = TRUE,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("mark_artifacts_as_cleaned#leaf_aggregation_witness_jobs_fri")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(&mut transaction)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE prover_jobs_fri
+            SET
+                is_blob_cleaned = TRUE,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("mark_artifacts_as_cleaned#prover_jobs_fri")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(&mut transaction)
+        .await?;
+
+        transaction.commit().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use zksync_types::{basic_fri_types::AggregationRound, protocol_version::FriProtocolVersionId};
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn garbage_collecting_prover_artifacts() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let protocol_version = FriProtocolVersionId::default();
+        conn.fri_protocol_versions_dal()
+            .save_prover_protocol_version(protocol_version, Default::default())
+            .await;
+        for number in 1..=4 {
+            let l1_batch_number = L1BatchNumber(number);
+            conn.fri_witness_generator_dal()
+                .save_witness_inputs(
+                    l1_batch_number,
+                    &format!("witness_inputs_{number}.bin"),
+                    protocol_version,
+                )
+                .await;
+            conn.fri_prover_jobs_dal()
+                .insert_prover_job(
+                    l1_batch_number,
+                    1,
+                    0,
+                    0,
+                    AggregationRound::BasicCircuits,
+                    &format!("circuit_{number}.bin"),
+                    false,
+                    protocol_version,
+                )
+                .await;
+        }
+
+        // Batches without completed proof compression must not be returned, even if they are proven on L1.
+        let l1_batches = conn
+            .fri_prover_artifacts_dal()
+            .get_l1_batches_with_uncleaned_artifacts(L1BatchNumber(0), L1BatchNumber(2), 10)
+            .await
+            .unwrap();
+        assert!(l1_batches.is_empty());
+
+        conn.fri_proof_compressor_dal()
+            .insert_proof_compression_job(L1BatchNumber(1), "proof_1.bin")
+            .await;
+        conn.fri_proof_compressor_dal()
+            .mark_proof_compression_job_successful(
+                L1BatchNumber(1),
+                Duration::from_secs(1),
+                "l1_proof_1.bin",
+            )
+            .await;
+        conn.fri_proof_compressor_dal()
+            .skip_proof_compression_job(L1BatchNumber(2))
+            .await;
+        conn.fri_proof_compressor_dal()
+            .insert_proof_compression_job(L1BatchNumber(3), "proof_3.bin")
+            .await;
+        conn.fri_proof_compressor_dal()
+            .skip_proof_compression_job(L1BatchNumber(4))
+            .await;
+
+        let dal = &mut conn.fri_prover_artifacts_dal();
+        let l1_batches = dal
+            .get_l1_batches_with_uncleaned_artifacts(L1BatchNumber(0), L1BatchNumber(2), 10)
+            .await
+            .unwrap();
+        assert_eq!(l1_batches, [L1BatchNumber(1), L1BatchNumber(2)]);
+        // L1 batch #3 is still being compressed, so L1 batch #4 must not be returned either.
+        let l1_batches = dal
+            .get_l1_batches_with_uncleaned_artifacts(L1BatchNumber(0), L1BatchNumber(4), 10)
+            .await
+            .unwrap();
+        assert_eq!(l1_batches, [L1BatchNumber(1), L1BatchNumber(2)]);
+
+        let mut artifacts = dal.get_artifacts(L1BatchNumber(1)).await.unwrap();
+        artifacts.sort_unstable_by(|a, b| a.blob_url.cmp(&b.blob_url));
+        assert_eq!(
+            artifacts,
+            [
+                ProverArtifact {
+                    artifact_type: ProverArtifactType::Circuit,
+                    blob_url: "circuit_1.bin".to_owned(),
+                },
+                ProverArtifact {
+                    artifact_type: ProverArtifactType::WitnessInputs,
+                    blob_url: "witness_inputs_1.bin".to_owned(),
+                },
+            ]
+        );
+
+        dal.mark_artifacts_as_cleaned(L1BatchNumber(1))
+            .await
+            .unwrap();
+        let l1_batches = dal
+            .get_l1_batches_with_uncleaned_artifacts(L1BatchNumber(0), L1BatchNumber(3), 1)
+            .await
+            .unwrap();
+        assert_eq!(l1_batches, [L1BatchNumber(2)]);
+    }
+}
@@ -4,6 +4,7 @@
 //!
 //! - Report query latency as a metric
 //! - Report slow and failing queries as metrics
+//! - Attribute query latency to the component that has requested the connection (see [`ConnectionPool::access_storage_tagged()`])
 //! - Log slow and failing queries together with their arguments, which makes it easier to debug.
 //!
 //! The entry point for instrumentation is the [`InstrumentExt`] trait. After it is imported into the scope,
@@ -11,7 +12,7 @@
 //! [`Instrumented`] methods on the returned struct, e.g. to [report query latency](Instrumented::report_latency())
 //! and/or [to add logged args](Instrumented::with_arg()) for a query.
 
-use std::{fmt, future::Future, panic::Location};
+use std::{fmt, future::Future, panic::Location, time::Duration};
 
 use sqlx::{
     postgres::{PgQueryResult, PgRow},
@@ -104,6 +105,7 @@ struct InstrumentedData<'a> {
     args: QueryArgs<'a>,
     report_latency: bool,
     slow_query_reporting_enabled: bool,
+    slow_query_threshold: Option<Duration>,
 }
 
 impl<'a> InstrumentedData<'a> {
@@ -114,6 +116,7 @@ impl<'a> InstrumentedData<'a> {
             args: QueryArgs::default(),
             report_latency: false,
             slow_query_reporting_enabled: true,
+            slow_query_threshold: None,
         }
     }
 
@@ -128,25 +131,30 @@ impl<'a> InstrumentedData<'a> {
             args,
             report_latency,
             slow_query_reporting_enabled,
+            slow_query_threshold,
         } = self;
         let started_at = Instant::now();
         tokio::pin!(query_future);
 
-        let slow_query_threshold = ConnectionPool::global_config().slow_query_threshold();
+        let slow_query_threshold = slow_query_threshold
+            .unwrap_or_else(|| ConnectionPool::global_config().slow_query_threshold());
         let mut is_slow = false;
         let output =
             tokio::time::timeout_at(started_at + slow_query_threshold, &mut query_future).await;
         let output = match output {
             Ok(output) => output,
             Err(_) => {
-                let connection_tags = StorageProcessorTags::display(connection_tags);
                 if slow_query_reporting_enabled {
+                    let tags_display = StorageProcessorTags::display(connection_tags);
                     tracing::warn!(
-                        "Query {name}{args} called at {file}:{line} [{connection_tags}] is executing for more than {slow_query_threshold:?}",
+                        "Query {name}{args} called at {file}:{line} [{tags_display}] is executing for more than {slow_query_threshold:?}",
                         file = location.file(),
                         line = location.line()
                     );
                     REQUEST_METRICS.request_slow[&name].inc();
+                    if let Some(tags) = connection_tags {
+                        REQUEST_METRICS.request_slow_tagged[&(name, tags.requester)].inc();
+                    }
                     is_slow = true;
                 }
                 query_future.await
@@ -157,6 +165,9 @@ impl<'a> InstrumentedData<'a> {
         if report_latency {
             REQUEST_METRICS.request[&name].observe(elapsed);
         }
+        if let Some(tags) = connection_tags {
+            REQUEST_METRICS.request_tagged[&(name, tags.requester)].observe(elapsed);
+        }
 
         let connection_tags = StorageProcessorTags::display(connection_tags);
         if let Err(err) = &output {
@@ -188,6 +199,9 @@ impl<'a> InstrumentedData<'a> {
 ///   included in the case of a slow query, plus the error info.
 /// - Slow and erroneous queries are also reported using metrics (`dal.request.slow` and `dal.request.error`,
 ///   respectively). The query name is included as a metric label; args are not included for obvious reasons.
+/// - If the query is executed on a [tagged connection](ConnectionPool::access_storage_tagged()), its latency
+///   and slowness are additionally reported with the requester label (`sql_request_tagged` and `sql_request_slow_tagged`),
+///   so that DB load can be attributed to specific node components.
 #[derive(Debug)]
 pub(crate) struct Instrumented<'a, Q> {
     query: Q,
@@ -206,6 +220,13 @@ impl<'a, Q> Instrumented<'a, Q> {
         self
     }
 
+    /// Overrides the [global slow query threshold](crate::connection::GlobalConnectionPoolConfig::set_slow_query_threshold())
+    /// for this query. Useful for queries expected to be much faster or slower than typical ones.
+    pub fn with_slow_query_threshold(mut self, threshold: Duration) -> Self {
+        self.data.slow_query_threshold = Some(threshold);
+        self
+    }
+
     /// Adds a traced query argument. The argument will be logged (using `Debug`) if the query executes too slow
     /// or finishes with an error.
     pub fn with_arg(mut self, name: &'static str, value: &'a ThreadSafeDebug) -> Self {
@@ -312,4 +333,47 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn instrumenting_query_on_tagged_connection() {
+        const NAME: &str = "slow_tagged";
+
+        let pool = ConnectionPool::test_pool().await;
+        // Add `vlog::init()` here to debug this test
+
+        let slow_tagged_count = || REQUEST_METRICS.request_slow_tagged[&(NAME, "test")].get();
+        let slow_count = || REQUEST_METRICS.request_slow[&NAME].get();
+        let mut conn = pool.access_storage_tagged("test").await.unwrap();
+        sqlx::query("SELECT pg_sleep(0.05)")
+            .map(drop)
+            .instrument(NAME)
+            .with_arg("miniblock", &MiniblockNumber(1))
+            .with_slow_query_threshold(Duration::from_millis(10))
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(slow_tagged_count(), 1);
+        assert_eq!(slow_count(), 1);
+
+        // The query is not slow with the default threshold.
+        sqlx::query("SELECT 1")
+            .map(drop)
+            .instrument(NAME)
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(slow_tagged_count(), 1);
+
+        // Queries on untagged connections must not be reported with the requester label.
+        let mut conn = pool.access_storage().await.unwrap();
+        sqlx::query("SELECT pg_sleep(0.05)")
+            .map(drop)
+            .instrument(NAME)
+            .with_slow_query_threshold(Duration::from_millis(10))
+            .fetch_optional(&mut conn)
+            .await
+            .unwrap();
+        assert_eq!(slow_tagged_count(), 1);
+        assert_eq!(slow_count(), 2);
+    }
 }
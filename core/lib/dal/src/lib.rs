@@ -6,21 +6,26 @@ pub use crate::connection::{ConnectionPool, StorageProcessor};
 use crate::{
     basic_witness_input_producer_dal::BasicWitnessInputProducerDal, blocks_dal::BlocksDal,
     blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
+    consistency_checker_dal::ConsistencyCheckerDal,
     contract_verification_dal::ContractVerificationDal, eth_sender_dal::EthSenderDal,
     events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
     fri_gpu_prover_queue_dal::FriGpuProverQueueDal,
     fri_proof_compressor_dal::FriProofCompressorDal,
-    fri_protocol_versions_dal::FriProtocolVersionsDal, fri_prover_dal::FriProverDal,
+    fri_protocol_versions_dal::FriProtocolVersionsDal,
+    fri_prover_artifacts_dal::FriProverArtifactsDal, fri_prover_dal::FriProverDal,
     fri_scheduler_dependency_tracker_dal::FriSchedulerDependencyTrackerDal,
-    fri_witness_generator_dal::FriWitnessGeneratorDal, proof_generation_dal::ProofGenerationDal,
+    fri_witness_generator_dal::FriWitnessGeneratorDal,
+    migration_validation_dal::MigrationValidationDal, proof_generation_dal::ProofGenerationDal,
     protocol_versions_dal::ProtocolVersionsDal,
-    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal,
-    snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
-    snapshots_dal::SnapshotsDal, storage_logs_dal::StorageLogsDal,
+    protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, prover_status_dal::ProverStatusDal,
+    pruning_dal::PruningDal, snapshot_recovery_dal::SnapshotRecoveryDal,
+    snapshots_creator_dal::SnapshotsCreatorDal, snapshots_dal::SnapshotsDal,
+    sponsored_transactions_dal::SponsoredTransactionsDal, storage_logs_dal::StorageLogsDal,
     storage_logs_dedup_dal::StorageLogsDedupDal, storage_web3_dal::StorageWeb3Dal,
-    sync_dal::SyncDal, system_dal::SystemDal, tokens_dal::TokensDal,
-    tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
-    transactions_web3_dal::TransactionsWeb3Dal,
+    sync_dal::SyncDal, system_dal::SystemDal, token_transfers_dal::TokenTransfersDal,
+    tokens_dal::TokensDal, tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
+    transactions_web3_dal::TransactionsWeb3Dal, utilization_reports_dal::UtilizationReportsDal,
+    validation_allow_list_dal::ValidationAllowListDal,
 };
 
 #[macro_use]
@@ -30,6 +35,7 @@ pub mod blocks_dal;
 pub mod blocks_web3_dal;
 pub mod connection;
 pub mod consensus_dal;
+pub mod consistency_checker_dal;
 pub mod contract_verification_dal;
 pub mod eth_sender_dal;
 pub mod events_dal;
@@ -38,19 +44,24 @@ pub mod factory_deps_dal;
 pub mod fri_gpu_prover_queue_dal;
 pub mod fri_proof_compressor_dal;
 pub mod fri_protocol_versions_dal;
+pub mod fri_prover_artifacts_dal;
 pub mod fri_prover_dal;
 pub mod fri_scheduler_dependency_tracker_dal;
 pub mod fri_witness_generator_dal;
 pub mod healthcheck;
 mod instrument;
 mod metrics;
+pub mod migration_validation_dal;
 mod models;
 pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
+pub mod prover_status_dal;
+pub mod pruning_dal;
 pub mod snapshot_recovery_dal;
 pub mod snapshots_creator_dal;
 pub mod snapshots_dal;
+pub mod sponsored_transactions_dal;
 mod storage_dal;
 pub mod storage_logs_dal;
 pub mod storage_logs_dedup_dal;
@@ -58,10 +69,13 @@ pub mod storage_web3_dal;
 pub mod sync_dal;
 pub mod system_dal;
 pub mod time_utils;
+pub mod token_transfers_dal;
 pub mod tokens_dal;
 pub mod tokens_web3_dal;
 pub mod transactions_dal;
 pub mod transactions_web3_dal;
+pub mod utilization_reports_dal;
+pub mod validation_allow_list_dal;
 
 #[cfg(test)]
 mod tests;
@@ -133,6 +147,10 @@ impl<'a> StorageProcessor<'a> {
         TokensWeb3Dal { storage: self }
     }
 
+    pub fn token_transfers_dal(&mut self) -> TokenTransfersDal<'_, 'a> {
+        TokenTransfersDal { storage: self }
+    }
+
     pub fn contract_verification_dal(&mut self) -> ContractVerificationDal<'_, 'a> {
         ContractVerificationDal { storage: self }
     }
@@ -179,6 +197,14 @@ impl<'a> StorageProcessor<'a> {
         FriProofCompressorDal { storage: self }
     }
 
+    pub fn fri_prover_artifacts_dal(&mut self) -> FriProverArtifactsDal<'_, 'a> {
+        FriProverArtifactsDal { storage: self }
+    }
+
+    pub fn prover_status_dal(&mut self) -> ProverStatusDal<'_, 'a> {
+        ProverStatusDal { storage: self }
+    }
+
     pub fn system_dal(&mut self) -> SystemDal<'_, 'a> {
         SystemDal { storage: self }
     }
@@ -191,7 +217,31 @@ impl<'a> StorageProcessor<'a> {
         SnapshotsCreatorDal { storage: self }
     }
 
+    pub fn migration_validation_dal(&mut self) -> MigrationValidationDal<'_, 'a> {
+        MigrationValidationDal { storage: self }
+    }
+
     pub fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a> {
         SnapshotRecoveryDal { storage: self }
     }
+
+    pub fn pruning_dal(&mut self) -> PruningDal<'_, 'a> {
+        PruningDal { storage: self }
+    }
+
+    pub fn validation_allow_list_dal(&mut self) -> ValidationAllowListDal<'_, 'a> {
+        ValidationAllowListDal { storage: self }
+    }
+
+    pub fn utilization_reports_dal(&mut self) -> UtilizationReportsDal<'_, 'a> {
+        UtilizationReportsDal { storage: self }
+    }
+
+    pub fn sponsored_transactions_dal(&mut self) -> SponsoredTransactionsDal<'_, 'a> {
+        SponsoredTransactionsDal { storage: self }
+    }
+
+    pub fn consistency_checker_dal(&mut self) -> ConsistencyCheckerDal<'_, 'a> {
+        ConsistencyCheckerDal { storage: self }
+    }
 }
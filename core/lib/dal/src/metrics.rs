@@ -3,7 +3,7 @@
 use std::{thread, time::Duration};
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     LatencyObserver, Metrics, Unit,
 };
 
@@ -14,9 +14,16 @@ pub(crate) struct RequestMetrics {
     /// Latency of a DB request.
     #[metrics(buckets = Buckets::LATENCIES, labels = ["method"])]
     pub request: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Latency of an instrumented DB request, tagged with the requester label of the connection
+    /// it was executed on. Unlike `request`, this metric is reported for all instrumented requests.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["method", "requester"])]
+    pub request_tagged: LabeledFamily<(&'static str, &'static str), Histogram<Duration>, 2>,
     /// Counter of slow DB requests.
     #[metrics(labels = ["method"])]
     pub request_slow: LabeledFamily<&'static str, Counter>,
+    /// Counter of slow DB requests, tagged with the requester label of the connection.
+    #[metrics(labels = ["method", "requester"])]
+    pub request_slow_tagged: LabeledFamily<(&'static str, &'static str), Counter, 2>,
     /// Counter of errored DB requests.
     #[metrics(labels = ["method"])]
     pub request_error: LabeledFamily<&'static str, Counter>,
@@ -92,6 +99,8 @@ pub(crate) struct ConnectionMetrics {
     /// Lifetime of a DB connection, tagged with the requester label.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["requester"])]
     pub lifetime: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of read replicas receiving traffic, i.e. ones with replication lag within the threshold.
+    pub fresh_read_replicas: Gauge<usize>,
 }
 
 #[vise::register]
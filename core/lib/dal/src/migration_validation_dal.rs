@@ -0,0 +1,207 @@
+use std::collections::{BTreeSet, HashSet};
+
+use sqlx::{Column, Executor, PgConnection};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Name of the savepoint used to isolate errors of individual validated queries.
+const VALIDATION_SAVEPOINT: &str = "migration_validation";
+/// Name of the prepared statement used to explain queries.
+const EXPLAINED_STATEMENT: &str = "migration_validation_explained";
+
+/// Executes SQL (potentially consisting of multiple statements) without preparing it.
+async fn execute_raw(conn: &mut PgConnection, sql: &str) -> sqlx::Result<()> {
+    conn.execute(sql).await?;
+    Ok(())
+}
+
+/// Plan of a query estimated by `EXPLAIN`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryPlan {
+    /// Estimated total cost of the query.
+    pub total_cost: f64,
+    /// Relations scanned sequentially by the query.
+    pub seq_scanned_relations: BTreeSet<String>,
+}
+
+impl QueryPlan {
+    fn new(plan: &serde_json::Value) -> Option<Self> {
+        let root = plan.get(0)?.get("Plan")?;
+        let mut this = Self {
+            total_cost: root.get("Total Cost")?.as_f64()?,
+            seq_scanned_relations: BTreeSet::new(),
+        };
+        this.collect_seq_scans(root);
+        Some(this)
+    }
+
+    fn collect_seq_scans(&mut self, node: &serde_json::Value) {
+        if node.get("Node Type").and_then(serde_json::Value::as_str) == Some("Seq Scan") {
+            if let Some(relation) = node
+                .get("Relation Name")
+                .and_then(serde_json::Value::as_str)
+            {
+                self.seq_scanned_relations.insert(relation.to_owned());
+            }
+        }
+        let children = node.get("Plans").and_then(serde_json::Value::as_array);
+        for child in children.into_iter().flatten() {
+            self.collect_seq_scans(child);
+        }
+    }
+}
+
+/// DAL for validating migrations before they are applied to the primary database.
+///
+/// Must be used with a disposable clone of the primary database (e.g., restored from a backup), so that migrations
+/// are validated on a realistic amount of data without affecting the primary database. All methods except for
+/// [`Self::get_applied_migrations()`] must be called within a single DB transaction that is rolled back afterwards.
+#[derive(Debug)]
+pub struct MigrationValidationDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl MigrationValidationDal<'_, '_> {
+    /// Returns versions of migrations successfully applied to the database.
+    pub async fn get_applied_migrations(&mut self) -> sqlx::Result<HashSet<i64>> {
+        // `_sqlx_migrations` is managed by `sqlx` itself, so it cannot be checked at compile time.
+        let rows: Vec<(i64,)> =
+            sqlx::query_as("SELECT version FROM _sqlx_migrations WHERE success")
+                .instrument("get_applied_migrations")
+                .fetch_all(self.storage)
+                .await?;
+        Ok(rows.into_iter().map(|(version,)| version).collect())
+    }
+
+    /// Applies a migration (potentially consisting of multiple statements).
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside a DB transaction.
+    pub async fn apply_migration(&mut self, migration_sql: &str) -> sqlx::Result<()> {
+        assert!(
+            self.storage.in_transaction(),
+            "migrations must be validated in a DB transaction"
+        );
+        execute_raw(self.storage.conn(), migration_sql).await?;
+        Ok(())
+    }
+
+    /// Prepares the query without executing it. Returns the names of the columns returned by the query.
+    pub async fn validate_query(&mut self, query: &str) -> sqlx::Result<Vec<String>> {
+        let conn = self.storage.conn();
+        // Any error aborts the enclosing transaction, so we need a savepoint to roll back to.
+        execute_raw(conn, format!("SAVEPOINT {VALIDATION_SAVEPOINT}").as_str()).await?;
+        let describe_result = Executor::describe(&mut *conn, query).await;
+        execute_raw(
+            conn,
+            format!("ROLLBACK TO SAVEPOINT {VALIDATION_SAVEPOINT}").as_str(),
+        )
+        .await?;
+
+        let columns = describe_result?.columns;
+        Ok(columns
+            .iter()
+            .map(|column| column.name().to_owned())
+            .collect())
+    }
+
+    /// Estimates the plan of the query using `EXPLAIN` without executing the query. Query parameters
+    /// are not known, so the generic plan (i.e., one not depending on parameter values) is estimated.
+    /// Returns `Ok(None)` if the plan cannot be parsed.
+    pub async fn explain_query(&mut self, query: &str) -> sqlx::Result<Option<QueryPlan>> {
+        let conn = self.storage.conn();
+        execute_raw(conn, format!("SAVEPOINT {VALIDATION_SAVEPOINT}").as_str()).await?;
+        let mut is_prepared = false;
+        let plan_result = async {
+            execute_raw(conn, "SET LOCAL plan_cache_mode = force_generic_plan").await?;
+            execute_raw(
+                conn,
+                format!("PREPARE {EXPLAINED_STATEMENT} AS {query}").as_str(),
+            )
+            .await?;
+            is_prepared = true;
+
+            let (param_count,): (i32,) = sqlx::query_as(
+                "SELECT COALESCE(CARDINALITY(parameter_types), 0) FROM pg_prepared_statements \
+                 WHERE name = $1",
+            )
+            .bind(EXPLAINED_STATEMENT)
+            .fetch_one(&mut *conn)
+            .await?;
+            let execute_statement = if param_count == 0 {
+                format!("EXECUTE {EXPLAINED_STATEMENT}")
+            } else {
+                let args = vec!["NULL"; param_count as usize].join(", ");
+                format!("EXECUTE {EXPLAINED_STATEMENT}({args})")
+            };
+            // The prepared statement is recreated for each query, so the `EXPLAIN` statement must not be cached.
+            let (plan,): (serde_json::Value,) =
+                sqlx::query_as(&format!("EXPLAIN (FORMAT JSON) {execute_statement}"))
+                    .persistent(false)
+                    .fetch_one(&mut *conn)
+                    .await?;
+            Ok::<_, sqlx::Error>(plan)
+        }
+        .await;
+        execute_raw(
+            conn,
+            format!("ROLLBACK TO SAVEPOINT {VALIDATION_SAVEPOINT}").as_str(),
+        )
+        .await?;
+        // Prepared statements are not transactional, so they must be deallocated explicitly.
+        if is_prepared {
+            execute_raw(conn, format!("DEALLOCATE {EXPLAINED_STATEMENT}").as_str()).await?;
+        }
+        Ok(QueryPlan::new(&plan_result?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn validating_queries_after_migration() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let query = "SELECT number, hash FROM miniblocks WHERE number = $1";
+
+        let mut transaction = conn.start_transaction().await.unwrap();
+        let mut dal = transaction.migration_validation_dal();
+        let columns = dal.validate_query(query).await.unwrap();
+        assert_eq!(columns, ["number", "hash"]);
+        let plan = dal.explain_query(query).await.unwrap().unwrap();
+        assert!(plan.seq_scanned_relations.is_empty(), "{plan:?}");
+
+        dal.apply_migration(
+            "ALTER TABLE miniblocks RENAME COLUMN hash TO block_hash; \
+             ALTER TABLE miniblocks DROP CONSTRAINT miniblocks_pkey CASCADE; \
+             DROP INDEX ix_miniblocks_t1, ix_miniblocks_t2;",
+        )
+        .await
+        .unwrap();
+        dal.validate_query(query).await.unwrap_err();
+        dal.explain_query(query).await.unwrap_err();
+        // Check that the transaction is not aborted by the failed query.
+        let query = "SELECT number, block_hash FROM miniblocks WHERE number = $1";
+        let columns = dal.validate_query(query).await.unwrap();
+        assert_eq!(columns, ["number", "block_hash"]);
+        // Since all indices on the miniblock number are dropped, the query should use a sequential scan.
+        let plan = dal.explain_query(query).await.unwrap().unwrap();
+        assert!(
+            plan.seq_scanned_relations.contains("miniblocks"),
+            "{plan:?}"
+        );
+        drop(transaction);
+
+        // The migration must be rolled back.
+        let mut transaction = conn.start_transaction().await.unwrap();
+        let columns = transaction
+            .migration_validation_dal()
+            .validate_query("SELECT number, hash FROM miniblocks")
+            .await;
+        assert_eq!(columns.unwrap(), ["number", "hash"]);
+    }
+}
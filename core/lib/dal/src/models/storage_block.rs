@@ -14,6 +14,7 @@ use zksync_types::{
     commitment::{L1BatchMetaParameters, L1BatchMetadata},
     fee_model::{BatchFeeInput, L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput},
     l2_to_l1_log::{L2ToL1Log, SystemL2ToL1Log, UserL2ToL1Log},
+    pubdata_da::PubdataDA,
     Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, H2048, H256,
 };
 
@@ -47,6 +48,7 @@ pub struct StorageL1BatchHeader {
     pub system_logs: Vec<Vec<u8>>,
     pub compressed_state_diffs: Option<Vec<u8>>,
     pub pubdata_input: Option<Vec<u8>>,
+    pub pubdata_mode: Option<i16>,
 }
 
 impl From<StorageL1BatchHeader> for L1BatchHeader {
@@ -81,6 +83,7 @@ impl From<StorageL1BatchHeader> for L1BatchHeader {
                 .protocol_version
                 .map(|v| (v as u16).try_into().unwrap()),
             pubdata_input: l1_batch.pubdata_input,
+            pubdata_mode: convert_pubdata_mode(l1_batch.pubdata_mode),
         }
     }
 }
@@ -92,6 +95,16 @@ fn convert_l2_to_l1_logs(raw_logs: Vec<Vec<u8>>) -> Vec<L2ToL1Log> {
         .collect()
 }
 
+/// Pubdata mode is not recorded for L1 batches sealed before it was introduced, or on external nodes.
+pub(crate) fn convert_pubdata_mode(pubdata_mode: Option<i16>) -> Option<PubdataDA> {
+    pubdata_mode.map(|mode| {
+        u8::try_from(mode)
+            .ok()
+            .and_then(|mode| PubdataDA::try_from(mode).ok())
+            .unwrap_or_else(|| panic!("invalid pubdata mode: {mode}"))
+    })
+}
+
 // TODO (SMA-1635): Make these fields non optional in database
 fn convert_base_system_contracts_hashes(
     bootloader_code_hash: Option<Vec<u8>>,
@@ -150,6 +163,7 @@ pub struct StorageL1Batch {
     pub events_queue_commitment: Option<Vec<u8>>,
     pub bootloader_initial_content_commitment: Option<Vec<u8>>,
     pub pubdata_input: Option<Vec<u8>>,
+    pub pubdata_mode: Option<i16>,
 }
 
 impl From<StorageL1Batch> for L1BatchHeader {
@@ -184,6 +198,7 @@ impl From<StorageL1Batch> for L1BatchHeader {
                 .protocol_version
                 .map(|v| (v as u16).try_into().unwrap()),
             pubdata_input: l1_batch.pubdata_input,
+            pubdata_mode: convert_pubdata_mode(l1_batch.pubdata_mode),
         }
     }
 }
@@ -412,6 +427,7 @@ pub struct StorageL1BatchDetails {
     pub l2_fair_gas_price: i64,
     pub bootloader_code_hash: Option<Vec<u8>>,
     pub default_aa_code_hash: Option<Vec<u8>>,
+    pub pubdata_mode: Option<i16>,
 }
 
 impl From<StorageL1BatchDetails> for api::L1BatchDetails {
@@ -459,6 +475,8 @@ impl From<StorageL1BatchDetails> for api::L1BatchDetails {
         api::L1BatchDetails {
             base,
             number: L1BatchNumber(details.number as u32),
+            pubdata_mode: convert_pubdata_mode(details.pubdata_mode),
+            prover_status: None,
         }
     }
 }
@@ -488,6 +506,7 @@ pub struct StorageMiniblockHeader {
     // `min(virtual_blocks`, `miniblock_number - virtual_block_number`), i.e. making sure that virtual blocks
     // never go beyond the miniblock they are based on.
     pub virtual_blocks: i64,
+    pub logs_bloom: Option<Vec<u8>>,
 }
 
 impl From<StorageMiniblockHeader> for MiniblockHeader {
@@ -529,6 +548,10 @@ impl From<StorageMiniblockHeader> for MiniblockHeader {
             gas_per_pubdata_limit: row.gas_per_pubdata_limit as u64,
             protocol_version,
             virtual_blocks: row.virtual_blocks as u32,
+            logs_bloom: row
+                .logs_bloom
+                .map(|bloom| H2048::from_slice(&bloom))
+                .unwrap_or_default(),
         }
     }
 }
@@ -9,7 +9,7 @@ use sqlx::{
 };
 use zksync_types::{
     api,
-    api::{TransactionDetails, TransactionReceipt, TransactionStatus},
+    api::{PriorityOpStatus, TransactionDetails, TransactionReceipt, TransactionStatus},
     fee::Fee,
     l1::{OpProcessingType, PriorityQueueType},
     l2::TransactionType,
@@ -17,9 +17,10 @@ use zksync_types::{
     transaction_request::PaymasterParams,
     vm_trace::Call,
     web3::types::U64,
-    Address, Bytes, Execute, ExecuteTransactionCommon, L1TxCommonData, L2ChainId, L2TxCommonData,
-    Nonce, PackedEthSignature, PriorityOpId, Transaction, EIP_1559_TX_TYPE, EIP_2930_TX_TYPE,
-    EIP_712_TX_TYPE, H160, H256, PRIORITY_OPERATION_L2_TX_TYPE, PROTOCOL_UPGRADE_TX_TYPE, U256,
+    Address, Bytes, Execute, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber,
+    L1TxCommonData, L2ChainId, L2TxCommonData, MiniblockNumber, Nonce, PackedEthSignature,
+    PriorityOpId, Transaction, EIP_1559_TX_TYPE, EIP_2930_TX_TYPE, EIP_712_TX_TYPE, H160, H256,
+    PRIORITY_OPERATION_L2_TX_TYPE, PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
 use zksync_utils::{bigdecimal_to_u256, h256_to_account_address};
 
@@ -55,6 +56,7 @@ pub struct StorageTransaction {
     pub effective_gas_price: Option<BigDecimal>,
     pub contract_address: Option<Vec<u8>>,
     pub value: BigDecimal,
+    pub revert_reason: Option<serde_json::Value>,
 
     pub paymaster: Vec<u8>,
     pub paymaster_input: Vec<u8>,
@@ -65,9 +67,14 @@ pub struct StorageTransaction {
 
     pub l1_tx_mint: Option<BigDecimal>,
     pub l1_tx_refund_recipient: Option<Vec<u8>>,
+    pub l1_tx_hash: Option<Vec<u8>>,
 
     pub upgrade_id: Option<i32>,
 
+    pub valid_until_timestamp: Option<i64>,
+    pub valid_until_miniblock: Option<i64>,
+    pub expired_at: Option<NaiveDateTime>,
+
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -142,6 +149,10 @@ impl From<StorageTransaction> for L1TxCommonData {
                 .map(bigdecimal_to_u256)
                 .unwrap_or_else(|| U256::from(1u32)),
             deadline_block: 0,
+            // The L1 transaction hash is intentionally not loaded: it is only known for transactions persisted
+            // after it started being stored, so loading it would make the encoding of the same transaction
+            // (e.g., in consensus payloads) depend on when and by which node it was persisted.
+            // The hash is exposed separately via `zks_getPriorityOpStatus`.
             eth_hash: Default::default(),
             eth_block: tx.l1_block_number.unwrap_or_default() as u64,
             canonical_tx_hash,
@@ -286,6 +297,7 @@ impl From<StorageTransaction> for ProtocolUpgradeTxCommonData {
                 .gas_per_pubdata_limit
                 .map(bigdecimal_to_u256)
                 .expect("gas_per_pubdata_limit field is missing for protocol upgrade tx"),
+            // Not loaded for the same reason as for L1 transactions.
             eth_hash: Default::default(),
             eth_block: tx.l1_block_number.unwrap_or_default() as u64,
             canonical_tx_hash,
@@ -492,6 +504,8 @@ pub struct StorageTransactionDetails {
     pub error: Option<String>,
     pub effective_gas_price: Option<BigDecimal>,
     pub refunded_gas: i64,
+    pub revert_reason: Option<serde_json::Value>,
+    pub expired_at: Option<NaiveDateTime>,
     pub eth_commit_tx_hash: Option<String>,
     pub eth_prove_tx_hash: Option<String>,
     pub eth_execute_tx_hash: Option<String>,
@@ -499,7 +513,9 @@ pub struct StorageTransactionDetails {
 
 impl StorageTransactionDetails {
     fn get_transaction_status(&self) -> TransactionStatus {
-        if self.error.is_some() {
+        if self.expired_at.is_some() {
+            TransactionStatus::Expired
+        } else if self.error.is_some() {
             TransactionStatus::Failed
         } else if self.eth_execute_tx_hash.is_some() {
             TransactionStatus::Verified
@@ -541,6 +557,9 @@ impl From<StorageTransactionDetails> for TransactionDetails {
         let eth_execute_tx_hash = tx_details
             .eth_execute_tx_hash
             .map(|hash| H256::from_str(&hash).unwrap());
+        let revert_reason = tx_details
+            .revert_reason
+            .map(|reason| serde_json::from_value(reason).expect("invalid revert reason in DB"));
 
         TransactionDetails {
             is_l1_originated: tx_details.is_priority,
@@ -552,6 +571,79 @@ impl From<StorageTransactionDetails> for TransactionDetails {
             eth_commit_tx_hash,
             eth_prove_tx_hash,
             eth_execute_tx_hash,
+            revert_reason,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StoragePriorityOpStatus {
+    pub priority_op_id: i64,
+    pub hash: Vec<u8>,
+    pub l1_tx_hash: Option<Vec<u8>>,
+    pub l1_block_number: Option<i32>,
+    pub miniblock_number: Option<i64>,
+    pub l1_batch_number: Option<i64>,
+    pub error: Option<String>,
+    pub eth_execute_tx_hash: Option<String>,
+    /// ID of the last priority operation executed in an L2 block. Only loaded for queued operations.
+    pub last_executed_priority_op_id: Option<i64>,
+    /// Only loaded for operations executed in a not yet sealed L1 batch.
+    pub last_sealed_l1_batch_number: Option<i64>,
+}
+
+impl StoragePriorityOpStatus {
+    fn get_transaction_status(&self) -> TransactionStatus {
+        if self.error.is_some() {
+            TransactionStatus::Failed
+        } else if self.eth_execute_tx_hash.is_some() {
+            TransactionStatus::Verified
+        } else if self.miniblock_number.is_some() {
+            TransactionStatus::Included
+        } else {
+            TransactionStatus::Pending
+        }
+    }
+}
+
+impl From<StoragePriorityOpStatus> for PriorityOpStatus {
+    fn from(op: StoragePriorityOpStatus) -> Self {
+        let status = op.get_transaction_status();
+        let id = PriorityOpId(op.priority_op_id as u64);
+        let l2_tx_hash = H256::from_slice(&op.hash);
+        let is_executed = op.miniblock_number.is_some();
+
+        // Priority operations are executed strictly in the order of their IDs, so the queue position
+        // can be derived from the ID of the last executed operation.
+        let queue_position = (!is_executed).then(|| {
+            let next_op_id = op
+                .last_executed_priority_op_id
+                .map_or(0, |last_id| last_id as u64 + 1);
+            id.0.saturating_sub(next_op_id)
+        });
+        let l1_batch_number = op
+            .l1_batch_number
+            .map(|number| L1BatchNumber(number as u32));
+        // An executed operation belongs to the pending L1 batch until the batch is sealed. For queued operations,
+        // the batch depends on the queue and sealing criteria, so it's not estimated.
+        let estimated_l1_batch_number = (is_executed && l1_batch_number.is_none()).then(|| {
+            op.last_sealed_l1_batch_number
+                .map_or(L1BatchNumber(0), |number| L1BatchNumber(number as u32 + 1))
+        });
+
+        PriorityOpStatus {
+            id,
+            status,
+            l1_tx_hash: op.l1_tx_hash.map(|hash| H256::from_slice(&hash)),
+            l1_block_number: L1BlockNumber(op.l1_block_number.unwrap_or_default() as u32),
+            l2_tx_hash,
+            queue_position,
+            miniblock_number: op
+                .miniblock_number
+                .map(|number| MiniblockNumber(number as u32)),
+            l1_batch_number,
+            estimated_l1_batch_number,
+            l2_receipt_hash: is_executed.then_some(l2_tx_hash),
         }
     }
 }
@@ -1,4 +1,9 @@
-use zksync_types::api::ProtocolVersion;
+use std::collections::HashMap;
+
+use zksync_types::{
+    api::{ProtocolVersion, ProtocolVersionInfo},
+    Address, L1BatchNumber,
+};
 
 use crate::{models::storage_protocol_version::StorageProtocolVersion, StorageProcessor};
 
@@ -48,4 +53,68 @@ impl ProtocolVersionsWeb3Dal<'_, '_> {
 
         ProtocolVersion::from(storage_protocol_version)
     }
+
+    /// Returns all known protocol versions ordered by their IDs, together with batches they were activated in.
+    pub async fn get_protocol_version_history(&mut self) -> Vec<ProtocolVersionInfo> {
+        let versions = sqlx::query_as!(
+            StorageProtocolVersion,
+            r#"
+            SELECT
+                *
+            FROM
+                protocol_versions
+            ORDER BY
+                id
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+
+        let first_batches = sqlx::query!(
+            r#"
+            SELECT
+                protocol_versions.id AS "version_id!",
+                first_batch.number AS "number?",
+                first_batch.timestamp AS "timestamp?"
+            FROM
+                protocol_versions
+                LEFT JOIN LATERAL (
+                    SELECT
+                        number,
+                        timestamp
+                    FROM
+                        l1_batches
+                    WHERE
+                        l1_batches.protocol_version = protocol_versions.id
+                    ORDER BY
+                        number
+                    LIMIT
+                        1
+                ) AS first_batch ON TRUE
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap();
+        let first_batches: HashMap<_, _> = first_batches
+            .into_iter()
+            .filter_map(|row| Some((row.version_id, (row.number?, row.timestamp?))))
+            .collect();
+
+        versions
+            .into_iter()
+            .map(|version| {
+                let first_batch = first_batches.get(&version.id);
+                let verifier_address = Address::from_slice(&version.verifier_address);
+                ProtocolVersionInfo {
+                    version: ProtocolVersion::from(version),
+                    verifier_address,
+                    first_l1_batch_number: first_batch
+                        .map(|&(number, _)| L1BatchNumber(number as u32)),
+                    first_l1_batch_timestamp: first_batch.map(|&(_, timestamp)| timestamp as u64),
+                }
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,249 @@
+//! Read-only access to prover tables used to report the status of the proving pipeline.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Mutex,
+};
+
+use zksync_types::{
+    api::{AggregationRoundStatus, L1BatchProverStatus, ProverJobCounts},
+    basic_fri_types::AggregationRound,
+    L1BatchNumber,
+};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// Read-only facade for prover tables (witness generation, proving and proof compression jobs).
+/// Unlike other prover DALs, it never modifies prover data, so it's safe to use from the API server.
+#[derive(Debug)]
+pub struct ProverStatusDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Prover job statuses that were already reported as unknown, so that they are not reported on each call.
+static REPORTED_UNKNOWN_STATUSES: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Adds jobs with the specified status (as stored in prover tables) to `counts`.
+fn add_jobs(counts: &mut ProverJobCounts, status: &str, count: u64) {
+    match status {
+        "queued" | "waiting_for_proofs" => counts.queued += count,
+        "in_progress" | "in_gpu_proof" => counts.in_progress += count,
+        "successful" | "skipped" | "sent_to_server" => counts.successful += count,
+        "failed" => counts.failed += count,
+        _ => {
+            let mut reported_statuses = REPORTED_UNKNOWN_STATUSES
+                .lock()
+                .expect("reported statuses are poisoned");
+            if reported_statuses.insert(status.to_owned()) {
+                tracing::warn!(
+                    "Unknown prover job status `{status}`; the jobs are counted as in progress"
+                );
+            }
+            counts.in_progress += count;
+        }
+    }
+}
+
+impl ProverStatusDal<'_, '_> {
+    /// Returns the status of the proving pipeline for the specified L1 batch. Returns `None` if proving
+    /// hasn't started for the batch, i.e., there is no basic witness generation job for it.
+    ///
+    /// All prover tables are queried in a single round trip since this method is used by a public API method.
+    pub async fn get_l1_batch_prover_status(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<L1BatchProverStatus>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                'witness' AS "kind!",
+                0 AS "aggregation_round!",
+                status AS "status!",
+                COUNT(*) AS "count!"
+            FROM
+                witness_inputs_fri
+            WHERE
+                l1_batch_number = $1
+            GROUP BY
+                status
+            UNION ALL
+            SELECT
+                'witness',
+                1,
+                status,
+                COUNT(*)
+            FROM
+                leaf_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            GROUP BY
+                status
+            UNION ALL
+            SELECT
+                'witness',
+                2,
+                status,
+                COUNT(*)
+            FROM
+                node_aggregation_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            GROUP BY
+                status
+            UNION ALL
+            SELECT
+                'witness',
+                3,
+                status,
+                COUNT(*)
+            FROM
+                scheduler_witness_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            GROUP BY
+                status
+            UNION ALL
+            SELECT
+                'proof',
+                aggregation_round::INT,
+                status,
+                COUNT(*)
+            FROM
+                prover_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            GROUP BY
+                aggregation_round,
+                status
+            UNION ALL
+            SELECT
+                'compression',
+                0,
+                status,
+                1
+            FROM
+                proof_compression_jobs_fri
+            WHERE
+                l1_batch_number = $1
+            UNION ALL
+            SELECT
+                'proof_generation',
+                0,
+                status,
+                1
+            FROM
+                proof_generation_details
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_l1_batch_prover_status")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        let has_basic_witness_job = rows
+            .iter()
+            .any(|row| row.kind == "witness" && row.aggregation_round == 0);
+        if !has_basic_witness_job {
+            return Ok(None);
+        }
+
+        let mut rounds = BTreeMap::<u8, (ProverJobCounts, ProverJobCounts)>::new();
+        let mut proof_compression_status = None;
+        let mut proof_generation_status = None;
+        for row in rows {
+            match row.kind.as_str() {
+                "witness" => {
+                    let (witness_generation, _) =
+                        rounds.entry(row.aggregation_round as u8).or_default();
+                    add_jobs(witness_generation, &row.status, row.count as u64);
+                }
+                "proof" => {
+                    let (_, proofs) = rounds.entry(row.aggregation_round as u8).or_default();
+                    add_jobs(proofs, &row.status, row.count as u64);
+                }
+                "compression" => proof_compression_status = Some(row.status),
+                "proof_generation" => proof_generation_status = Some(row.status),
+                kind => unreachable!("unexpected row kind: {kind}"),
+            }
+        }
+        let aggregation_rounds = rounds
+            .into_iter()
+            .map(
+                |(round, (witness_generation, proofs))| AggregationRoundStatus {
+                    aggregation_round: AggregationRound::from(round),
+                    witness_generation,
+                    proofs,
+                },
+            )
+            .collect();
+
+        Ok(Some(L1BatchProverStatus {
+            aggregation_rounds,
+            proof_compression_status,
+            proof_submitted: proof_generation_status.as_deref() == Some("generated"),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn getting_prover_status() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.fri_protocol_versions_dal()
+            .save_prover_protocol_version(Default::default(), Default::default())
+            .await;
+        let l1_batch_number = L1BatchNumber(1);
+
+        let status = conn
+            .prover_status_dal()
+            .get_l1_batch_prover_status(l1_batch_number)
+            .await
+            .unwrap();
+        assert_eq!(status, None);
+
+        conn.fri_witness_generator_dal()
+            .save_witness_inputs(l1_batch_number, "inputs", Default::default())
+            .await;
+        let status = conn
+            .prover_status_dal()
+            .get_l1_batch_prover_status(l1_batch_number)
+            .await
+            .unwrap()
+            .expect("no prover status");
+        assert_eq!(status.aggregation_rounds.len(), 1);
+        let basic_round = &status.aggregation_rounds[0];
+        assert_eq!(
+            basic_round.aggregation_round,
+            AggregationRound::BasicCircuits
+        );
+        assert_eq!(
+            basic_round.witness_generation,
+            ProverJobCounts {
+                queued: 1,
+                ..ProverJobCounts::default()
+            }
+        );
+        assert_eq!(basic_round.proofs, ProverJobCounts::default());
+        assert_eq!(status.proof_compression_status, None);
+        assert!(!status.proof_submitted);
+
+        conn.fri_proof_compressor_dal()
+            .insert_proof_compression_job(l1_batch_number, "proof")
+            .await;
+        let status = conn
+            .prover_status_dal()
+            .get_l1_batch_prover_status(l1_batch_number)
+            .await
+            .unwrap()
+            .expect("no prover status");
+        assert_eq!(status.proof_compression_status.as_deref(), Some("queued"));
+    }
+}
@@ -0,0 +1,531 @@
+use std::ops;
+
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+#[derive(Debug)]
+pub struct PruningDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Information about Postgres pruning.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PruningInfo {
+    /// Last L1 batch that was soft-pruned, i.e., is no longer served via the API, but its data may still be present in Postgres.
+    pub last_soft_pruned_l1_batch: Option<L1BatchNumber>,
+    /// Last miniblock that was soft-pruned.
+    pub last_soft_pruned_miniblock: Option<MiniblockNumber>,
+    /// Last L1 batch for which all miniblock-level data (miniblocks, transactions, events etc.) was removed from Postgres.
+    pub last_hard_pruned_l1_batch: Option<L1BatchNumber>,
+    /// Last miniblock that was hard-pruned.
+    pub last_hard_pruned_miniblock: Option<MiniblockNumber>,
+}
+
+/// Statistics about a single hard pruning iteration.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HardPruningStats {
+    pub deleted_miniblocks: u64,
+    pub deleted_transactions: u64,
+    pub deleted_events: u64,
+    pub deleted_l2_to_l1_logs: u64,
+    pub deleted_token_transfers: u64,
+    pub deleted_storage_logs_from_past_batches: u64,
+    pub deleted_storage_logs_from_pruned_batches: u64,
+}
+
+#[derive(Debug, sqlx::Type)]
+#[sqlx(type_name = "prune_type")]
+enum PruneType {
+    /// Data is no longer served via the API, but is still present in Postgres.
+    Soft,
+    /// Data is removed from Postgres.
+    Hard,
+}
+
+impl PruningDal<'_, '_> {
+    pub async fn get_pruning_info(&mut self) -> sqlx::Result<PruningInfo> {
+        let row = sqlx::query!(
+            r#"
+            WITH
+                soft AS (
+                    SELECT
+                        pruned_l1_batch,
+                        pruned_miniblock
+                    FROM
+                        pruning_log
+                    WHERE
+                        TYPE = 'Soft'
+                    ORDER BY
+                        pruned_l1_batch DESC
+                    LIMIT
+                        1
+                ),
+                hard AS (
+                    SELECT
+                        pruned_l1_batch,
+                        pruned_miniblock
+                    FROM
+                        pruning_log
+                    WHERE
+                        TYPE = 'Hard'
+                    ORDER BY
+                        pruned_l1_batch DESC
+                    LIMIT
+                        1
+                )
+            SELECT
+                soft.pruned_l1_batch AS last_soft_pruned_l1_batch,
+                soft.pruned_miniblock AS last_soft_pruned_miniblock,
+                hard.pruned_l1_batch AS last_hard_pruned_l1_batch,
+                hard.pruned_miniblock AS last_hard_pruned_miniblock
+            FROM
+                soft
+                FULL JOIN hard ON TRUE
+            "#
+        )
+        .instrument("get_pruning_info")
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(PruningInfo::default());
+        };
+        Ok(PruningInfo {
+            last_soft_pruned_l1_batch: row
+                .last_soft_pruned_l1_batch
+                .map(|number| L1BatchNumber(number as u32)),
+            last_soft_pruned_miniblock: row
+                .last_soft_pruned_miniblock
+                .map(|number| MiniblockNumber(number as u32)),
+            last_hard_pruned_l1_batch: row
+                .last_hard_pruned_l1_batch
+                .map(|number| L1BatchNumber(number as u32)),
+            last_hard_pruned_miniblock: row
+                .last_hard_pruned_miniblock
+                .map(|number| MiniblockNumber(number as u32)),
+        })
+    }
+
+    /// Marks all L1 batches and miniblocks up to and including the specified ones as soft-pruned.
+    /// Soft-pruned data is not served via the API, but is not yet removed from Postgres.
+    pub async fn soft_prune_batches_range(
+        &mut self,
+        last_l1_batch_to_prune: L1BatchNumber,
+        last_miniblock_to_prune: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_log (
+                    pruned_l1_batch,
+                    pruned_miniblock,
+                    TYPE,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            "#,
+            last_l1_batch_to_prune.0 as i64,
+            last_miniblock_to_prune.0 as i64,
+            PruneType::Soft as PruneType,
+        )
+        .instrument("soft_prune_batches_range#insert_pruning_log")
+        .with_arg("last_l1_batch_to_prune", &last_l1_batch_to_prune)
+        .with_arg("last_miniblock_to_prune", &last_miniblock_to_prune)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes miniblock-level data (miniblocks, transactions, events, L2-to-L1 logs, token transfers and overwritten storage logs)
+    /// for all miniblocks up to and including the specified one. L1 batch-level data (headers, metadata,
+    /// commitments, initial writes) is retained.
+    ///
+    /// The latest storage log for each key is always retained since it is required to read the current VM state.
+    pub async fn hard_prune_batches_range(
+        &mut self,
+        last_l1_batch_to_prune: L1BatchNumber,
+        last_miniblock_to_prune: MiniblockNumber,
+    ) -> sqlx::Result<HardPruningStats> {
+        let mut transaction = self.storage.start_transaction().await?;
+        let pruning_info = transaction.pruning_dal().get_pruning_info().await?;
+        let first_miniblock_to_prune = pruning_info
+            .last_hard_pruned_miniblock
+            .map_or(MiniblockNumber(0), |number| number + 1);
+        let range = first_miniblock_to_prune..=last_miniblock_to_prune;
+
+        let mut this = transaction.pruning_dal();
+        let deleted_events = this.delete_events(range.clone()).await?;
+        let deleted_l2_to_l1_logs = this.delete_l2_to_l1_logs(range.clone()).await?;
+        let deleted_token_transfers = this
+            .storage
+            .token_transfers_dal()
+            .delete_token_transfers(range.clone())
+            .await?;
+        let deleted_transactions = this.delete_transactions(range.clone()).await?;
+        let deleted_storage_logs_from_past_batches = this
+            .prune_storage_logs_from_past_miniblocks(range.clone())
+            .await?;
+        let deleted_storage_logs_from_pruned_batches =
+            this.prune_storage_logs_in_range(range.clone()).await?;
+        let deleted_miniblocks = this.delete_miniblocks(range).await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_log (
+                    pruned_l1_batch,
+                    pruned_miniblock,
+                    TYPE,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            "#,
+            last_l1_batch_to_prune.0 as i64,
+            last_miniblock_to_prune.0 as i64,
+            PruneType::Hard as PruneType,
+        )
+        .instrument("hard_prune_batches_range#insert_pruning_log")
+        .with_arg("last_l1_batch_to_prune", &last_l1_batch_to_prune)
+        .with_arg("last_miniblock_to_prune", &last_miniblock_to_prune)
+        .report_latency()
+        .execute(&mut transaction)
+        .await?;
+        transaction.commit().await?;
+
+        Ok(HardPruningStats {
+            deleted_miniblocks,
+            deleted_transactions,
+            deleted_events,
+            deleted_l2_to_l1_logs,
+            deleted_token_transfers,
+            deleted_storage_logs_from_past_batches,
+            deleted_storage_logs_from_pruned_batches,
+        })
+    }
+
+    async fn delete_events(
+        &mut self,
+        range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let execution_result = sqlx::query!(
+            r#"
+            DELETE FROM events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("hard_prune_batches_range#delete_events")
+        .with_arg("range", &range)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(execution_result.rows_affected())
+    }
+
+    async fn delete_l2_to_l1_logs(
+        &mut self,
+        range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let execution_result = sqlx::query!(
+            r#"
+            DELETE FROM l2_to_l1_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("hard_prune_batches_range#delete_l2_to_l1_logs")
+        .with_arg("range", &range)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(execution_result.rows_affected())
+    }
+
+    /// Deletes transactions included into the specified miniblocks. Call traces are removed via cascading.
+    async fn delete_transactions(
+        &mut self,
+        range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let execution_result = sqlx::query!(
+            r#"
+            DELETE FROM transactions
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("hard_prune_batches_range#delete_transactions")
+        .with_arg("range", &range)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(execution_result.rows_affected())
+    }
+
+    /// Deletes storage logs from miniblocks preceding the range that are overwritten by logs in the range.
+    /// The deletion is driven by the keys touched in the range, so that it uses the primary key index on `storage_logs`
+    /// instead of scanning all logs preceding the range.
+    async fn prune_storage_logs_from_past_miniblocks(
+        &mut self,
+        range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let execution_result = sqlx::query!(
+            r#"
+            DELETE FROM storage_logs
+            USING
+                (
+                    SELECT DISTINCT
+                        hashed_key
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                ) AS keys_in_range
+            WHERE
+                storage_logs.hashed_key = keys_in_range.hashed_key
+                AND storage_logs.miniblock_number < $1
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("hard_prune_batches_range#prune_storage_logs_from_past_miniblocks")
+        .with_arg("range", &range)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(execution_result.rows_affected())
+    }
+
+    /// Deletes storage logs in the range that are overwritten by later logs in the same range.
+    async fn prune_storage_logs_in_range(
+        &mut self,
+        range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let execution_result = sqlx::query!(
+            r#"
+            DELETE FROM storage_logs
+            USING
+                (
+                    SELECT
+                        hashed_key,
+                        MAX(ARRAY[miniblock_number, operation_number]::INT[]) AS op
+                    FROM
+                        storage_logs
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                    GROUP BY
+                        hashed_key
+                ) AS last_storage_logs
+            WHERE
+                storage_logs.miniblock_number BETWEEN $1 AND $2
+                AND last_storage_logs.hashed_key = storage_logs.hashed_key
+                AND (
+                    storage_logs.miniblock_number != last_storage_logs.op[1]
+                    OR storage_logs.operation_number != last_storage_logs.op[2]
+                )
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("hard_prune_batches_range#prune_storage_logs_in_range")
+        .with_arg("range", &range)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(execution_result.rows_affected())
+    }
+
+    async fn delete_miniblocks(
+        &mut self,
+        range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let execution_result = sqlx::query!(
+            r#"
+            DELETE FROM miniblocks
+            WHERE
+                number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("hard_prune_batches_range#delete_miniblocks")
+        .with_arg("range", &range)
+        .report_latency()
+        .execute(self.storage)
+        .await?;
+        Ok(execution_result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_contracts::BaseSystemContractsHashes;
+    use zksync_types::{
+        block::L1BatchHeader, AccountTreeId, Address, ProtocolVersion, ProtocolVersionId,
+        StorageKey, StorageLog, H256,
+    };
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    async fn insert_l1_batch(conn: &mut StorageProcessor<'_>, number: u32, logs: Vec<StorageLog>) {
+        let header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            number.into(),
+            BaseSystemContractsHashes::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(number))
+            .await
+            .unwrap();
+        conn.storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(number))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn pruning_info_is_updated() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(info, PruningInfo::default());
+
+        conn.pruning_dal()
+            .soft_prune_batches_range(L1BatchNumber(5), MiniblockNumber(10))
+            .await
+            .unwrap();
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(
+            info,
+            PruningInfo {
+                last_soft_pruned_l1_batch: Some(L1BatchNumber(5)),
+                last_soft_pruned_miniblock: Some(MiniblockNumber(10)),
+                last_hard_pruned_l1_batch: None,
+                last_hard_pruned_miniblock: None,
+            }
+        );
+
+        conn.pruning_dal()
+            .soft_prune_batches_range(L1BatchNumber(8), MiniblockNumber(16))
+            .await
+            .unwrap();
+        conn.pruning_dal()
+            .hard_prune_batches_range(L1BatchNumber(5), MiniblockNumber(10))
+            .await
+            .unwrap();
+        let info = conn.pruning_dal().get_pruning_info().await.unwrap();
+        assert_eq!(
+            info,
+            PruningInfo {
+                last_soft_pruned_l1_batch: Some(L1BatchNumber(8)),
+                last_soft_pruned_miniblock: Some(MiniblockNumber(16)),
+                last_hard_pruned_l1_batch: Some(L1BatchNumber(5)),
+                last_hard_pruned_miniblock: Some(MiniblockNumber(10)),
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn hard_pruning_retains_latest_storage_logs() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let account = AccountTreeId::new(Address::repeat_byte(1));
+        let first_key = StorageKey::new(account, H256::zero());
+        let second_key = StorageKey::new(account, H256::from_low_u64_be(1));
+        let logs = vec![
+            StorageLog::new_write_log(first_key, H256::repeat_byte(1)),
+            StorageLog::new_write_log(second_key, H256::repeat_byte(2)),
+        ];
+        insert_l1_batch(&mut conn, 0, logs).await;
+        let logs = vec![
+            StorageLog::new_write_log(first_key, H256::repeat_byte(3)),
+            StorageLog::new_write_log(first_key, H256::repeat_byte(4)),
+        ];
+        insert_l1_batch(&mut conn, 1, logs).await;
+        insert_l1_batch(&mut conn, 2, vec![]).await;
+
+        let stats = conn
+            .pruning_dal()
+            .hard_prune_batches_range(L1BatchNumber(1), MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(stats.deleted_miniblocks, 2);
+        assert_eq!(stats.deleted_storage_logs_from_past_batches, 1);
+        assert_eq!(stats.deleted_storage_logs_from_pruned_batches, 1);
+
+        // Only the latest log for each key must remain in the pruned miniblocks.
+        let mut remaining_logs: Vec<_> = conn
+            .storage_logs_dal()
+            .dump_all_storage_logs_for_tests()
+            .await
+            .into_iter()
+            .map(|log| (log.hashed_key, log.value, log.miniblock_number))
+            .collect();
+        remaining_logs.sort_unstable();
+        let mut expected_logs = vec![
+            (
+                first_key.hashed_key(),
+                H256::repeat_byte(4),
+                MiniblockNumber(1),
+            ),
+            (
+                second_key.hashed_key(),
+                H256::repeat_byte(2),
+                MiniblockNumber(0),
+            ),
+        ];
+        expected_logs.sort_unstable();
+        assert_eq!(remaining_logs, expected_logs);
+
+        let sealed_miniblock = conn
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap();
+        assert_eq!(sealed_miniblock, Some(MiniblockNumber(2)));
+        let first_value = conn
+            .storage_web3_dal()
+            .get_historical_value_unchecked(&first_key, MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(first_value, H256::repeat_byte(4));
+        let second_value = conn
+            .storage_web3_dal()
+            .get_historical_value_unchecked(&second_key, MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(second_value, H256::repeat_byte(2));
+        // L1 batch headers must be retained.
+        let header = conn
+            .blocks_dal()
+            .get_l1_batch_header(L1BatchNumber(0))
+            .await
+            .unwrap();
+        assert!(header.is_some());
+    }
+}
@@ -1,5 +1,5 @@
 use zksync_types::{
-    snapshots::{AllSnapshots, SnapshotMetadata},
+    snapshots::{AllSnapshots, SnapshotMetadata, SnapshotVersion},
     L1BatchNumber,
 };
 
@@ -7,6 +7,7 @@ use crate::{instrument::InstrumentExt, StorageProcessor};
 
 #[derive(Debug, sqlx::FromRow)]
 struct StorageSnapshotMetadata {
+    version: i32,
     l1_batch_number: i64,
     storage_logs_filepaths: Vec<String>,
     factory_deps_filepath: String,
@@ -15,6 +16,8 @@ struct StorageSnapshotMetadata {
 impl From<StorageSnapshotMetadata> for SnapshotMetadata {
     fn from(row: StorageSnapshotMetadata) -> Self {
         Self {
+            version: SnapshotVersion::try_from(row.version as u16)
+                .expect("Stored snapshot has unsupported version"),
             l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
             storage_logs_filepaths: row
                 .storage_logs_filepaths
@@ -34,6 +37,7 @@ pub struct SnapshotsDal<'a, 'c> {
 impl SnapshotsDal<'_, '_> {
     pub async fn add_snapshot(
         &mut self,
+        version: SnapshotVersion,
         l1_batch_number: L1BatchNumber,
         storage_logs_chunk_count: u64,
         factory_deps_filepaths: &str,
@@ -42,6 +46,7 @@ impl SnapshotsDal<'_, '_> {
             r#"
             INSERT INTO
                 snapshots (
+                    version,
                     l1_batch_number,
                     storage_logs_filepaths,
                     factory_deps_filepath,
@@ -49,8 +54,9 @@ impl SnapshotsDal<'_, '_> {
                     updated_at
                 )
             VALUES
-                ($1, ARRAY_FILL(''::TEXT, ARRAY[$2::INTEGER]), $3, NOW(), NOW())
+                ($1, $2, ARRAY_FILL(''::TEXT, ARRAY[$3::INTEGER]), $4, NOW(), NOW())
             "#,
+            version as i32,
             l1_batch_number.0 as i32,
             storage_logs_chunk_count as i32,
             factory_deps_filepaths,
@@ -120,6 +126,7 @@ impl SnapshotsDal<'_, '_> {
             StorageSnapshotMetadata,
             r#"
             SELECT
+                version,
                 l1_batch_number,
                 factory_deps_filepath,
                 storage_logs_filepaths
@@ -147,6 +154,7 @@ impl SnapshotsDal<'_, '_> {
             StorageSnapshotMetadata,
             r#"
             SELECT
+                version,
                 l1_batch_number,
                 factory_deps_filepath,
                 storage_logs_filepaths
@@ -168,7 +176,7 @@ impl SnapshotsDal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::L1BatchNumber;
+    use zksync_types::{snapshots::SnapshotVersion, L1BatchNumber};
 
     use crate::ConnectionPool;
 
@@ -178,9 +186,14 @@ mod tests {
         let mut conn = pool.access_storage().await.unwrap();
         let mut dal = conn.snapshots_dal();
         let l1_batch_number = L1BatchNumber(100);
-        dal.add_snapshot(l1_batch_number, 2, "gs:///bucket/factory_deps.bin")
-            .await
-            .expect("Failed to add snapshot");
+        dal.add_snapshot(
+            SnapshotVersion::Version0,
+            l1_batch_number,
+            2,
+            "gs:///bucket/factory_deps.bin",
+        )
+        .await
+        .expect("Failed to add snapshot");
 
         let snapshots = dal
             .get_all_complete_snapshots()
@@ -210,6 +223,7 @@ mod tests {
             .expect("Failed to retrieve snapshot")
             .unwrap();
         assert_eq!(snapshot_metadata.l1_batch_number, l1_batch_number);
+        assert_eq!(snapshot_metadata.version, SnapshotVersion::Version0);
     }
 
     #[tokio::test]
@@ -218,9 +232,14 @@ mod tests {
         let mut conn = pool.access_storage().await.unwrap();
         let mut dal = conn.snapshots_dal();
         let l1_batch_number = L1BatchNumber(100);
-        dal.add_snapshot(l1_batch_number, 2, "gs:///bucket/factory_deps.bin")
-            .await
-            .expect("Failed to add snapshot");
+        dal.add_snapshot(
+            SnapshotVersion::Version0,
+            l1_batch_number,
+            2,
+            "gs:///bucket/factory_deps.bin",
+        )
+        .await
+        .expect("Failed to add snapshot");
 
         let storage_log_filepaths = ["gs:///bucket/test_file1.bin", "gs:///bucket/test_file2.bin"];
         dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 1, storage_log_filepaths[1])
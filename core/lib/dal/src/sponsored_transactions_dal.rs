@@ -0,0 +1,469 @@
+use std::time::Duration;
+
+use zksync_types::{Address, H256, U256};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::{instrument::InstrumentExt, time_utils::pg_interval_from_duration, StorageProcessor};
+
+/// DAL for transactions with fees subsidized by the operator.
+///
+/// Subsidies are first reserved based on the maximum fee of a transaction, and are settled to the actual fee
+/// once the transaction is executed, or released if the transaction is rejected or replaced. The running total
+/// of subsidies is kept in a single row, which is locked by reservations to serialize them.
+#[derive(Debug)]
+pub struct SponsoredTransactionsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Result of [`SponsoredTransactionsDal::settle_sponsored_transactions()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SettledSubsidies {
+    /// Number of transactions with subsidies settled to the actual fee.
+    pub settled_count: usize,
+    /// Number of transactions with released subsidies.
+    pub released_count: usize,
+    /// Difference between the reserved and settled subsidies of all processed transactions.
+    pub released_subsidy: U256,
+}
+
+impl SponsoredTransactionsDal<'_, '_> {
+    /// Returns the total subsidy provided by the operator, locking it until the end of the current DB transaction.
+    /// Must be called before modifying subsidies.
+    pub async fn lock_total_subsidy(&mut self) -> sqlx::Result<U256> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                total_subsidy
+            FROM
+                sponsorship_totals
+            FOR UPDATE
+            "#
+        )
+        .instrument("lock_total_subsidy")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(bigdecimal_to_u256(row.total_subsidy))
+    }
+
+    /// Returns the total subsidy provided by the operator.
+    pub async fn get_total_subsidy(&mut self) -> sqlx::Result<U256> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                total_subsidy
+            FROM
+                sponsorship_totals
+            "#
+        )
+        .instrument("get_total_subsidy")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(bigdecimal_to_u256(row.total_subsidy))
+    }
+
+    /// Sets the total subsidy provided by the operator. Should be called after [`Self::lock_total_subsidy()`]
+    /// in the same DB transaction.
+    pub async fn set_total_subsidy(&mut self, total_subsidy: U256) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE sponsorship_totals
+            SET
+                total_subsidy = $1,
+                updated_at = NOW()
+            "#,
+            u256_to_big_decimal(total_subsidy)
+        )
+        .instrument("set_total_subsidy")
+        .with_arg("total_subsidy", &total_subsidy)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Records a subsidy for the specified transaction. Returns `false` if the subsidy for the transaction
+    /// is already recorded. The total subsidy is not updated.
+    pub async fn insert_sponsored_transaction(
+        &mut self,
+        tx_hash: H256,
+        initiator_address: Address,
+        contract_address: Address,
+        subsidy: U256,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                sponsored_transactions (
+                    tx_hash,
+                    initiator_address,
+                    contract_address,
+                    subsidy,
+                    created_at
+                )
+            VALUES
+                ($1, $2, $3, $4, NOW())
+            ON CONFLICT (tx_hash) DO NOTHING
+            "#,
+            tx_hash.as_bytes(),
+            initiator_address.as_bytes(),
+            contract_address.as_bytes(),
+            u256_to_big_decimal(subsidy)
+        )
+        .instrument("insert_sponsored_transaction")
+        .with_arg("tx_hash", &tx_hash)
+        .with_arg("initiator_address", &initiator_address)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Removes the subsidy for the specified transaction, e.g. if the transaction was not accepted to the mempool.
+    /// Returns the removed subsidy, or `None` if there is no unsettled subsidy for the transaction.
+    /// The total subsidy is not updated.
+    pub async fn remove_sponsored_transaction(
+        &mut self,
+        tx_hash: H256,
+    ) -> sqlx::Result<Option<U256>> {
+        let row = sqlx::query!(
+            r#"
+            DELETE FROM sponsored_transactions
+            WHERE
+                tx_hash = $1
+                AND NOT is_settled
+            RETURNING
+                subsidy
+            "#,
+            tx_hash.as_bytes()
+        )
+        .instrument("remove_sponsored_transaction")
+        .with_arg("tx_hash", &tx_hash)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| bigdecimal_to_u256(row.subsidy)))
+    }
+
+    /// Settles subsidies for at most `limit` oldest unsettled transactions:
+    ///
+    /// - Subsidies for executed transactions are reduced to the actual transaction fee.
+    /// - Subsidies for rejected (incl. expired) transactions are released.
+    /// - Subsidies for transactions missing from the `transactions` table for longer than `missing_tx_timeout`
+    ///   are released. Such transactions were replaced by a transaction with the same nonce, or removed
+    ///   from the mempool.
+    ///
+    /// Transactions that are still pending are not processed. The total subsidy is not updated.
+    pub async fn settle_sponsored_transactions(
+        &mut self,
+        missing_tx_timeout: Duration,
+        limit: usize,
+    ) -> sqlx::Result<SettledSubsidies> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                sponsored_transactions.tx_hash,
+                sponsored_transactions.subsidy,
+                CASE
+                    WHEN transactions.miniblock_number IS NOT NULL THEN LEAST(
+                        sponsored_transactions.subsidy,
+                        (transactions.gas_limit - transactions.refunded_gas) * transactions.effective_gas_price
+                    )
+                END AS "actual_fee?"
+            FROM
+                sponsored_transactions
+                LEFT JOIN transactions ON transactions.hash = sponsored_transactions.tx_hash
+            WHERE
+                NOT sponsored_transactions.is_settled
+                AND (
+                    transactions.miniblock_number IS NOT NULL
+                    OR transactions.error IS NOT NULL
+                    OR (
+                        transactions.hash IS NULL
+                        AND sponsored_transactions.created_at < NOW() - $1::INTERVAL
+                    )
+                )
+            ORDER BY
+                sponsored_transactions.created_at
+            LIMIT
+                $2
+            FOR UPDATE OF
+                sponsored_transactions SKIP LOCKED
+            "#,
+            pg_interval_from_duration(missing_tx_timeout),
+            limit as i64
+        )
+        .instrument("settle_sponsored_transactions#select")
+        .with_arg("missing_tx_timeout", &missing_tx_timeout)
+        .with_arg("limit", &limit)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut output = SettledSubsidies::default();
+        let mut settled_hashes = vec![];
+        let mut settled_subsidies = vec![];
+        let mut released_hashes = vec![];
+        for row in rows {
+            let subsidy = bigdecimal_to_u256(row.subsidy);
+            if let Some(actual_fee) = row.actual_fee {
+                let actual_fee = bigdecimal_to_u256(actual_fee);
+                output.settled_count += 1;
+                output.released_subsidy += subsidy.saturating_sub(actual_fee);
+                settled_hashes.push(row.tx_hash);
+                settled_subsidies.push(u256_to_big_decimal(actual_fee));
+            } else {
+                output.released_count += 1;
+                output.released_subsidy += subsidy;
+                released_hashes.push(row.tx_hash);
+            }
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE sponsored_transactions
+            SET
+                subsidy = data_table.subsidy,
+                is_settled = TRUE
+            FROM
+                (
+                    SELECT
+                        UNNEST($1::bytea[]) AS tx_hash,
+                        UNNEST($2::NUMERIC[]) AS subsidy
+                ) AS data_table
+            WHERE
+                sponsored_transactions.tx_hash = data_table.tx_hash
+            "#,
+            &settled_hashes,
+            &settled_subsidies
+        )
+        .instrument("settle_sponsored_transactions#update")
+        .with_arg("settled_hashes.len", &settled_hashes.len())
+        .execute(self.storage)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            DELETE FROM sponsored_transactions
+            WHERE
+                tx_hash = ANY ($1)
+            "#,
+            &released_hashes
+        )
+        .instrument("settle_sponsored_transactions#delete")
+        .with_arg("released_hashes.len", &released_hashes.len())
+        .execute(self.storage)
+        .await?;
+
+        Ok(output)
+    }
+
+    /// Returns the subsidy provided to transactions initiated by the specified account during the `period`
+    /// preceding the current moment.
+    pub async fn get_account_subsidy(
+        &mut self,
+        initiator_address: Address,
+        period: Duration,
+    ) -> sqlx::Result<U256> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                SUM(subsidy) AS total_subsidy
+            FROM
+                sponsored_transactions
+            WHERE
+                initiator_address = $1
+                AND created_at > NOW() - $2::INTERVAL
+            "#,
+            initiator_address.as_bytes(),
+            pg_interval_from_duration(period)
+        )
+        .instrument("get_account_subsidy")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("period", &period)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row
+            .total_subsidy
+            .map(bigdecimal_to_u256)
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx, MiniblockNumber};
+
+    use super::*;
+    use crate::{
+        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        ConnectionPool,
+    };
+
+    #[tokio::test]
+    async fn accounting_sponsored_transactions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let dal = &mut conn.sponsored_transactions_dal();
+        let initiator = Address::repeat_byte(1);
+        let contract = Address::repeat_byte(0x10);
+        let day = Duration::from_secs(86_400);
+
+        assert_eq!(dal.get_total_subsidy().await.unwrap(), U256::zero());
+        assert_eq!(
+            dal.get_account_subsidy(initiator, day).await.unwrap(),
+            U256::zero()
+        );
+
+        let inserted = dal
+            .insert_sponsored_transaction(H256::repeat_byte(1), initiator, contract, 100.into())
+            .await
+            .unwrap();
+        assert!(inserted);
+        let inserted = dal
+            .insert_sponsored_transaction(H256::repeat_byte(1), initiator, contract, 100.into())
+            .await
+            .unwrap();
+        assert!(!inserted);
+        dal.insert_sponsored_transaction(
+            H256::repeat_byte(2),
+            Address::repeat_byte(2),
+            contract,
+            50.into(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            dal.get_account_subsidy(initiator, day).await.unwrap(),
+            U256::from(100)
+        );
+        assert_eq!(
+            dal.get_account_subsidy(initiator, Duration::ZERO)
+                .await
+                .unwrap(),
+            U256::zero()
+        );
+
+        let removed = dal
+            .remove_sponsored_transaction(H256::repeat_byte(1))
+            .await
+            .unwrap();
+        assert_eq!(removed, Some(100.into()));
+        let removed = dal
+            .remove_sponsored_transaction(H256::repeat_byte(1))
+            .await
+            .unwrap();
+        assert_eq!(removed, None);
+        assert_eq!(
+            dal.get_account_subsidy(initiator, day).await.unwrap(),
+            U256::zero()
+        );
+
+        let mut transaction = conn.start_transaction().await.unwrap();
+        let dal = &mut transaction.sponsored_transactions_dal();
+        assert_eq!(dal.lock_total_subsidy().await.unwrap(), U256::zero());
+        dal.set_total_subsidy(50.into()).await.unwrap();
+        transaction.commit().await.unwrap();
+        assert_eq!(
+            conn.sponsored_transactions_dal()
+                .get_total_subsidy()
+                .await
+                .unwrap(),
+            U256::from(50)
+        );
+    }
+
+    async fn insert_sponsored_tx(conn: &mut StorageProcessor<'_>, tx: &L2Tx) -> U256 {
+        let subsidy = tx.common_data.fee.gas_limit * tx.common_data.fee.max_fee_per_gas;
+        conn.sponsored_transactions_dal()
+            .insert_sponsored_transaction(
+                tx.hash(),
+                tx.initiator_account(),
+                tx.execute.contract_address,
+                subsidy,
+            )
+            .await
+            .unwrap();
+        subsidy
+    }
+
+    #[tokio::test]
+    async fn settling_sponsored_transactions() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let executed_tx = mock_l2_transaction();
+        let rejected_tx = mock_l2_transaction();
+        let replaced_tx = mock_l2_transaction();
+        let pending_tx = mock_l2_transaction();
+        for tx in [&executed_tx, &rejected_tx, &pending_tx] {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        let executed_subsidy = insert_sponsored_tx(&mut conn, &executed_tx).await;
+        let rejected_subsidy = insert_sponsored_tx(&mut conn, &rejected_tx).await;
+        let replaced_subsidy = insert_sponsored_tx(&mut conn, &replaced_tx).await;
+        insert_sponsored_tx(&mut conn, &pending_tx).await;
+
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let mut execution_result = mock_execution_result(executed_tx.clone());
+        execution_result.refunded_gas = 400_000;
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[execution_result], 100.into())
+            .await;
+        conn.transactions_dal()
+            .mark_tx_as_rejected(rejected_tx.hash(), "rejected")
+            .await;
+
+        let day = Duration::from_secs(86_400);
+        let settled = conn
+            .sponsored_transactions_dal()
+            .settle_sponsored_transactions(day, 10)
+            .await
+            .unwrap();
+        // (1_000_000 gas limit - 400_000 refunded gas) * 100 wei effective gas price
+        let actual_fee = U256::from(60_000_000);
+        assert_eq!(
+            settled,
+            SettledSubsidies {
+                settled_count: 1,
+                released_count: 1,
+                released_subsidy: executed_subsidy - actual_fee + rejected_subsidy,
+            }
+        );
+        assert_eq!(
+            conn.sponsored_transactions_dal()
+                .get_account_subsidy(executed_tx.initiator_account(), day)
+                .await
+                .unwrap(),
+            actual_fee
+        );
+
+        // The replaced transaction should be released once it's missing for long enough.
+        let settled = conn
+            .sponsored_transactions_dal()
+            .settle_sponsored_transactions(Duration::ZERO, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            settled,
+            SettledSubsidies {
+                settled_count: 0,
+                released_count: 1,
+                released_subsidy: replaced_subsidy,
+            }
+        );
+        let settled = conn
+            .sponsored_transactions_dal()
+            .settle_sponsored_transactions(Duration::ZERO, 10)
+            .await
+            .unwrap();
+        assert_eq!(settled, SettledSubsidies::default());
+        // Settled subsidies cannot be removed.
+        let removed = conn
+            .sponsored_transactions_dal()
+            .remove_sponsored_transaction(executed_tx.hash())
+            .await
+            .unwrap();
+        assert_eq!(removed, None);
+    }
+}
@@ -1,4 +1,8 @@
-use std::{collections::HashMap, ops, time::Instant};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops,
+    time::Instant,
+};
 
 use sqlx::{types::chrono::Utc, Row};
 use zksync_types::{
@@ -351,6 +355,10 @@ impl StorageLogsDal<'_, '_> {
 
     /// Returns latest values for all [`StorageKey`]s written to in the specified L1 batch
     /// judging by storage logs (i.e., not taking deduplication logic into account).
+    ///
+    /// Hard pruning removes overwritten storage logs together with miniblocks, so the returned map is empty
+    /// for hard-pruned L1 batches. Batches are only pruned after they have metadata, i.e., after the Merkle tree
+    /// no longer needs their touched slots.
     pub async fn get_touched_slots_for_l1_batch(
         &mut self,
         l1_batch_number: L1BatchNumber,
@@ -398,6 +406,47 @@ impl StorageLogsDal<'_, '_> {
         Ok(touched_slots.collect())
     }
 
+    /// Returns the final values of storage slots touched in each miniblock in the specified range.
+    /// Miniblocks without touched slots are not included into the returned map.
+    pub async fn get_touched_slots_for_miniblocks(
+        &mut self,
+        miniblock_numbers: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<BTreeMap<MiniblockNumber, HashMap<StorageKey, H256>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number,
+                address,
+                key,
+                value
+            FROM
+                storage_logs
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                miniblock_number,
+                operation_number
+            "#,
+            i64::from(miniblock_numbers.start().0),
+            i64::from(miniblock_numbers.end().0)
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let mut touched_slots = BTreeMap::<_, HashMap<_, _>>::new();
+        for row in rows {
+            let key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            touched_slots
+                .entry(MiniblockNumber(row.miniblock_number as u32))
+                .or_default()
+                .insert(key, H256::from_slice(&row.value));
+        }
+        Ok(touched_slots)
+    }
+
     /// Returns (hashed) storage keys and the corresponding values that need to be applied to a storage
     /// in order to revert it to the specified L1 batch. Deduplication is taken into account.
     pub async fn get_storage_logs_for_revert(
@@ -15,14 +15,14 @@ impl SystemDal<'_, '_> {
         let pg_row = sqlx::query(
             "SELECT \
                  pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() AS synced, \
-                 EXTRACT(SECONDS FROM now() - pg_last_xact_replay_timestamp())::int AS lag",
+                 EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())::int AS lag",
         )
         .fetch_one(self.storage.conn())
         .await
         .unwrap();
 
         match pg_row.get("synced") {
-            Some(false) => pg_row.try_get::<i64, &str>("lag").unwrap_or_default() as u32,
+            Some(false) => pg_row.try_get::<i32, &str>("lag").unwrap_or_default() as u32,
             // We are synced, no lag
             _ => 0,
         }
@@ -12,7 +12,7 @@ use zksync_types::{
     snapshots::SnapshotRecoveryStatus,
     tx::{tx_execution_info::TxExecutionStatus, ExecutionMetrics, TransactionExecutionResult},
     Address, Execute, L1BatchNumber, L1BlockNumber, L1TxCommonData, L2ChainId, MiniblockNumber,
-    PriorityOpId, ProtocolVersionId, H160, H256, U256,
+    PriorityOpId, ProtocolVersionId, Transaction, H160, H256, U256,
 };
 
 use crate::{
@@ -45,6 +45,7 @@ pub(crate) fn create_miniblock_header(number: u32) -> MiniblockHeader {
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
         protocol_version: Some(protocol_version),
         virtual_blocks: 1,
+        logs_bloom: Default::default(),
     }
 }
 
@@ -146,6 +147,7 @@ pub(crate) fn mock_execution_result(transaction: L2Tx) -> TransactionExecutionRe
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        decoded_revert_reason: None,
     }
 }
 
@@ -302,3 +304,52 @@ async fn remove_stuck_txs() {
 
     assert_eq!(receipts.len(), 1);
 }
+
+#[tokio::test]
+async fn persisting_mempool_accounts() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let storage = &mut connection_pool.access_storage().await.unwrap();
+    let mut protocol_versions_dal = ProtocolVersionsDal { storage };
+    protocol_versions_dal
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+
+    let storage = protocol_versions_dal.storage;
+    let mut transactions_dal = TransactionsDal { storage };
+    let stashed_tx = mock_l2_transaction();
+    let purged_tx = mock_l2_transaction();
+    for tx in [&stashed_tx, &purged_tx] {
+        transactions_dal
+            .insert_transaction_l2(tx.clone(), mock_tx_execution_metrics())
+            .await;
+    }
+    transactions_dal.reset_mempool().await.unwrap();
+    let txs = transactions_dal
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    assert_eq!(txs.len(), 2);
+
+    transactions_dal
+        .persist_mempool_accounts(
+            &[stashed_tx.initiator_account()],
+            &[purged_tx.initiator_account()],
+        )
+        .await
+        .unwrap();
+
+    // The stashed transaction should be loaded to the mempool again, and the purged one should be removed.
+    let txs = transactions_dal
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    let tx_hashes: Vec<_> = txs.iter().map(Transaction::hash).collect();
+    assert_eq!(tx_hashes, [stashed_tx.hash()]);
+    // Check that the purged transaction is removed from the storage.
+    transactions_dal.reset_mempool().await.unwrap();
+    let txs = transactions_dal
+        .sync_mempool(&[], &[], 0, 0, 1000)
+        .await
+        .unwrap();
+    assert_eq!(txs.len(), 1);
+}
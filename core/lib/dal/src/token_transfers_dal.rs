@@ -0,0 +1,1049 @@
+use std::ops;
+
+use bigdecimal::BigDecimal;
+use zksync_types::{
+    api,
+    event::{NftTransfer, TokenTransfer},
+    tx::IncludedTxLocation,
+    Address, L1BatchNumber, MiniblockNumber, VmEvent, H256,
+};
+use zksync_utils::{bigdecimal_to_u256, u256_to_big_decimal};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// DAL for the index of token transfers extracted from `Transfer` events. ERC-721 and ERC-1155 transfers
+/// are indexed separately from fungible token transfers.
+///
+/// The index is built from the persisted events by the token transfers indexer component, so it may lag behind
+/// the sealed miniblocks; the indexer progress is tracked in a separate table.
+#[derive(Debug)]
+pub struct TokenTransfersDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+impl TokenTransfersDal<'_, '_> {
+    /// Extracts fungible and NFT transfers from the events of the specified miniblock and saves them. Events must be
+    /// provided in the same format as for [`EventsDal::save_events()`](crate::events_dal::EventsDal::save_events()),
+    /// so that transfer log indices match event indices.
+    pub async fn insert_token_transfers(
+        &mut self,
+        block_number: MiniblockNumber,
+        all_block_events: &[(IncludedTxLocation, Vec<&VmEvent>)],
+    ) -> sqlx::Result<()> {
+        let mut event_indices = vec![];
+        let mut tx_hashes = vec![];
+        let mut token_addresses = vec![];
+        let mut from_addresses = vec![];
+        let mut to_addresses = vec![];
+        let mut amounts = vec![];
+
+        let all_events = all_block_events
+            .iter()
+            .flat_map(|(location, events)| events.iter().map(move |event| (location, event)));
+        for (event_index_in_block, (location, event)) in all_events.clone().enumerate() {
+            let Some(transfer) = TokenTransfer::from_event(event) else {
+                continue;
+            };
+            event_indices.push(event_index_in_block as i32);
+            tx_hashes.push(location.tx_hash.as_bytes().to_vec());
+            token_addresses.push(transfer.token.as_bytes().to_vec());
+            from_addresses.push(transfer.from.as_bytes().to_vec());
+            to_addresses.push(transfer.to.as_bytes().to_vec());
+            amounts.push(u256_to_big_decimal(transfer.amount));
+        }
+        self.insert_nft_transfers(block_number, all_events).await?;
+        if event_indices.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                token_transfers (
+                    miniblock_number,
+                    event_index_in_block,
+                    tx_hash,
+                    token_address,
+                    from_address,
+                    to_address,
+                    amount
+                )
+            SELECT
+                $1,
+                u.event_index_in_block,
+                u.tx_hash,
+                u.token_address,
+                u.from_address,
+                u.to_address,
+                u.amount
+            FROM
+                UNNEST(
+                    $2::INT[],
+                    $3::bytea[],
+                    $4::bytea[],
+                    $5::bytea[],
+                    $6::bytea[],
+                    $7::NUMERIC[]
+                ) AS u (
+                    event_index_in_block,
+                    tx_hash,
+                    token_address,
+                    from_address,
+                    to_address,
+                    amount
+                )
+            "#,
+            block_number.0 as i64,
+            &event_indices,
+            &tx_hashes,
+            &token_addresses,
+            &from_addresses,
+            &to_addresses,
+            &amounts
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    async fn insert_nft_transfers(
+        &mut self,
+        block_number: MiniblockNumber,
+        all_events: impl Iterator<Item = (&IncludedTxLocation, &&VmEvent)>,
+    ) -> sqlx::Result<()> {
+        let mut event_indices = vec![];
+        let mut indices_in_event = vec![];
+        let mut tx_hashes = vec![];
+        let mut token_addresses = vec![];
+        let mut standards = vec![];
+        let mut token_ids = vec![];
+        let mut from_addresses = vec![];
+        let mut to_addresses = vec![];
+        let mut amounts = vec![];
+
+        for (event_index_in_block, (location, event)) in all_events.enumerate() {
+            let transfers = NftTransfer::from_event(event);
+            for (index_in_event, transfer) in transfers.into_iter().enumerate() {
+                event_indices.push(event_index_in_block as i32);
+                indices_in_event.push(index_in_event as i32);
+                tx_hashes.push(location.tx_hash.as_bytes().to_vec());
+                token_addresses.push(transfer.token.as_bytes().to_vec());
+                standards.push(transfer.standard.as_str().to_owned());
+                token_ids.push(u256_to_big_decimal(transfer.token_id));
+                from_addresses.push(transfer.from.as_bytes().to_vec());
+                to_addresses.push(transfer.to.as_bytes().to_vec());
+                amounts.push(u256_to_big_decimal(transfer.amount));
+            }
+        }
+        if event_indices.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                nft_transfers (
+                    miniblock_number,
+                    event_index_in_block,
+                    index_in_event,
+                    tx_hash,
+                    token_address,
+                    standard,
+                    token_id,
+                    from_address,
+                    to_address,
+                    amount
+                )
+            SELECT
+                $1,
+                u.event_index_in_block,
+                u.index_in_event,
+                u.tx_hash,
+                u.token_address,
+                u.standard,
+                u.token_id,
+                u.from_address,
+                u.to_address,
+                u.amount
+            FROM
+                UNNEST(
+                    $2::INT[],
+                    $3::INT[],
+                    $4::bytea[],
+                    $5::bytea[],
+                    $6::TEXT[],
+                    $7::NUMERIC[],
+                    $8::bytea[],
+                    $9::bytea[],
+                    $10::NUMERIC[]
+                ) AS u (
+                    event_index_in_block,
+                    index_in_event,
+                    tx_hash,
+                    token_address,
+                    standard,
+                    token_id,
+                    from_address,
+                    to_address,
+                    amount
+                )
+            "#,
+            block_number.0 as i64,
+            &event_indices,
+            &indices_in_event,
+            &tx_hashes,
+            &token_addresses,
+            &standards,
+            &token_ids,
+            &from_addresses,
+            &to_addresses,
+            &amounts
+        )
+        .execute(self.storage.conn())
+        .await?;
+
+        self.update_nft_balances(block_number..=block_number, false)
+            .await
+    }
+
+    /// Applies NFT transfers in the specified miniblock range to NFT balances. If `revert` is set, transfers
+    /// are reverted instead. Must be called while the transfers in the range are still present.
+    async fn update_nft_balances(
+        &mut self,
+        range: ops::RangeInclusive<MiniblockNumber>,
+        revert: bool,
+    ) -> sqlx::Result<()> {
+        let sign = if revert { -1 } else { 1 };
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                nft_balances (address, token_address, token_id, standard, amount)
+            SELECT
+                address,
+                token_address,
+                token_id,
+                MIN(standard),
+                SUM(amount) * $3::INT
+            FROM
+                (
+                    SELECT
+                        to_address AS address,
+                        token_address,
+                        token_id,
+                        standard,
+                        amount
+                    FROM
+                        nft_transfers
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                    UNION ALL
+                    SELECT
+                        from_address AS address,
+                        token_address,
+                        token_id,
+                        standard,
+                        -amount
+                    FROM
+                        nft_transfers
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                ) AS deltas
+            WHERE
+                address <> $4
+            GROUP BY
+                address,
+                token_address,
+                token_id
+            ON CONFLICT (address, token_address, token_id) DO
+            UPDATE
+            SET
+                amount = nft_balances.amount + excluded.amount
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64,
+            sign,
+            Address::zero().as_bytes()
+        )
+        .instrument("update_nft_balances")
+        .with_arg("range", &range)
+        .with_arg("revert", &revert)
+        .execute(self.storage)
+        .await?;
+
+        // Remove emptied balances so that the table doesn't grow with every traded NFT.
+        sqlx::query!(
+            r#"
+            DELETE FROM nft_balances
+            WHERE
+                amount = 0
+                AND (address, token_address, token_id) IN (
+                    SELECT
+                        from_address,
+                        token_address,
+                        token_id
+                    FROM
+                        nft_transfers
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                    UNION
+                    SELECT
+                        to_address,
+                        token_address,
+                        token_id
+                    FROM
+                        nft_transfers
+                    WHERE
+                        miniblock_number BETWEEN $1 AND $2
+                )
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("update_nft_balances#remove_empty")
+        .with_arg("range", &range)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the last miniblock processed by the token transfers indexer, or `None` if the indexer didn't process
+    /// any miniblocks yet.
+    pub async fn get_last_indexed_miniblock(&mut self) -> sqlx::Result<Option<MiniblockNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_indexed_miniblock
+            FROM
+                token_transfers_indexer_state
+            "#
+        )
+        .instrument("get_last_indexed_miniblock")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| MiniblockNumber(row.last_indexed_miniblock as u32)))
+    }
+
+    /// Indexes token transfers from the persisted events of the specified miniblocks and records the end
+    /// of the range as the indexer progress. Should be called in a transaction.
+    pub async fn index_token_transfers(
+        &mut self,
+        range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<()> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number,
+                tx_hash,
+                tx_index_in_block,
+                tx_initiator_address,
+                address,
+                topic1,
+                topic2,
+                topic3,
+                topic4,
+                value
+            FROM
+                events
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            ORDER BY
+                miniblock_number,
+                event_index_in_block
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("index_token_transfers#get_events")
+        .with_arg("range", &range)
+        .fetch_all(self.storage)
+        .await?;
+
+        // Restore events grouped by miniblock and transaction in the format expected by `insert_token_transfers()`.
+        let mut miniblocks: Vec<(MiniblockNumber, Vec<(IncludedTxLocation, Vec<VmEvent>)>)> =
+            vec![];
+        for row in rows {
+            let miniblock_number = MiniblockNumber(row.miniblock_number as u32);
+            let location = IncludedTxLocation {
+                tx_hash: H256::from_slice(&row.tx_hash),
+                tx_index_in_miniblock: row.tx_index_in_block as u32,
+                tx_initiator_address: Address::from_slice(&row.tx_initiator_address),
+            };
+            let indexed_topics = [row.topic1, row.topic2, row.topic3, row.topic4]
+                .into_iter()
+                .filter(|topic| !topic.is_empty())
+                .map(|topic| H256::from_slice(&topic))
+                .collect();
+            let event = VmEvent {
+                // The location is not used for extracting transfers.
+                location: (L1BatchNumber(0), location.tx_index_in_miniblock),
+                address: Address::from_slice(&row.address),
+                indexed_topics,
+                value: row.value,
+            };
+
+            if miniblocks.last().map(|(number, _)| *number) != Some(miniblock_number) {
+                miniblocks.push((miniblock_number, vec![]));
+            }
+            let (_, txs) = miniblocks.last_mut().unwrap();
+            match txs.last_mut() {
+                Some((last_location, events)) if last_location.tx_hash == location.tx_hash => {
+                    events.push(event);
+                }
+                _ => txs.push((location, vec![event])),
+            }
+        }
+
+        for (miniblock_number, txs) in &miniblocks {
+            let events: Vec<_> = txs
+                .iter()
+                .map(|(location, events)| (*location, events.iter().collect()))
+                .collect();
+            self.insert_token_transfers(*miniblock_number, &events)
+                .await?;
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                token_transfers_indexer_state (id, last_indexed_miniblock, updated_at)
+            VALUES
+                (TRUE, $1, NOW())
+            ON CONFLICT (id) DO
+            UPDATE
+            SET
+                last_indexed_miniblock = excluded.last_indexed_miniblock,
+                updated_at = NOW()
+            "#,
+            range.end().0 as i64
+        )
+        .instrument("index_token_transfers#update_state")
+        .with_arg("range", &range)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes fungible and NFT transfers in the specified miniblock range. Used when hard-pruning the range;
+    /// NFT balances are not affected.
+    pub(crate) async fn delete_token_transfers(
+        &mut self,
+        range: ops::RangeInclusive<MiniblockNumber>,
+    ) -> sqlx::Result<u64> {
+        let token_transfers = sqlx::query!(
+            r#"
+            DELETE FROM token_transfers
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("delete_token_transfers")
+        .with_arg("range", &range)
+        .execute(self.storage)
+        .await?;
+        let nft_transfers = sqlx::query!(
+            r#"
+            DELETE FROM nft_transfers
+            WHERE
+                miniblock_number BETWEEN $1 AND $2
+            "#,
+            range.start().0 as i64,
+            range.end().0 as i64
+        )
+        .instrument("delete_nft_transfers")
+        .with_arg("range", &range)
+        .execute(self.storage)
+        .await?;
+        Ok(token_transfers.rows_affected() + nft_transfers.rows_affected())
+    }
+
+    /// Returns NFT holdings of `address` strictly after the `after` position, ordered by the token address and ID.
+    /// Holdings are read from NFT balances maintained together with indexed NFT transfers; only holdings with
+    /// a positive amount are returned. If `tokens` are specified, only holdings of these tokens are returned.
+    pub async fn get_nft_holdings(
+        &mut self,
+        address: Address,
+        after: Option<&api::AccountBalancesCursor>,
+        tokens: Option<&[Address]>,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::NftHolding>> {
+        // An empty address is less than any real address, and -1 is less than any token ID; this way,
+        // `(token_address, token_id) > ($2, $3)` covers all cursor variants.
+        let (after_token, after_token_id) = after.map_or_else(
+            || (vec![], BigDecimal::from(-1)),
+            |cursor| {
+                let token_id = cursor
+                    .token_id
+                    .map_or_else(|| BigDecimal::from(-1), u256_to_big_decimal);
+                (cursor.token.as_bytes().to_vec(), token_id)
+            },
+        );
+        let tokens: Option<Vec<_>> = tokens.map(|tokens| {
+            tokens
+                .iter()
+                .map(|token| token.as_bytes().to_vec())
+                .collect()
+        });
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                token_address,
+                token_id,
+                standard,
+                amount
+            FROM
+                nft_balances
+            WHERE
+                address = $1
+                AND (token_address, token_id) > ($2, $3)
+                AND (
+                    $4::BYTEA[] IS NULL
+                    OR token_address = ANY ($4)
+                )
+                AND amount > 0
+            ORDER BY
+                token_address,
+                token_id
+            LIMIT
+                $5
+            "#,
+            address.as_bytes(),
+            &after_token,
+            after_token_id,
+            tokens.as_deref(),
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::NftHolding {
+                token: Address::from_slice(&row.token_address),
+                standard: row.standard.parse().expect("invalid NFT standard"),
+                token_id: bigdecimal_to_u256(row.token_id),
+                amount: bigdecimal_to_u256(row.amount),
+            })
+            .collect())
+    }
+
+    /// Returns token transfers sent or received by `address` in the specified range, in the order of their execution.
+    pub async fn get_token_transfers(
+        &mut self,
+        address: Address,
+        range: &api::TokenTransfersRange,
+        limit: usize,
+    ) -> sqlx::Result<Vec<api::TokenTransfer>> {
+        let from_block = range.from_block.map_or(0, |number| i64::from(number.0));
+        let to_block = range
+            .to_block
+            .map_or(i64::MAX, |number| i64::from(number.0));
+        let (after_block, after_index) = range.after.map_or((-1, -1), |cursor| {
+            (i64::from(cursor.block_number.0), cursor.log_index as i32)
+        });
+
+        // Sent and received transfers are queried separately, so that each subquery can use the corresponding index.
+        // Self-transfers are only returned by the first subquery.
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                miniblock_number AS "miniblock_number!",
+                event_index_in_block AS "event_index_in_block!",
+                tx_hash AS "tx_hash!",
+                token_address AS "token_address!",
+                from_address AS "from_address!",
+                to_address AS "to_address!",
+                amount AS "amount!"
+            FROM
+                (
+                    (
+                        SELECT
+                            miniblock_number,
+                            event_index_in_block,
+                            tx_hash,
+                            token_address,
+                            from_address,
+                            to_address,
+                            amount
+                        FROM
+                            token_transfers
+                        WHERE
+                            from_address = $1
+                            AND miniblock_number BETWEEN $2 AND $3
+                            AND (miniblock_number, event_index_in_block) > ($4, $5)
+                        ORDER BY
+                            miniblock_number,
+                            event_index_in_block
+                        LIMIT
+                            $6
+                    )
+                    UNION ALL
+                    (
+                        SELECT
+                            miniblock_number,
+                            event_index_in_block,
+                            tx_hash,
+                            token_address,
+                            from_address,
+                            to_address,
+                            amount
+                        FROM
+                            token_transfers
+                        WHERE
+                            to_address = $1
+                            AND from_address <> $1
+                            AND miniblock_number BETWEEN $2 AND $3
+                            AND (miniblock_number, event_index_in_block) > ($4, $5)
+                        ORDER BY
+                            miniblock_number,
+                            event_index_in_block
+                        LIMIT
+                            $6
+                    )
+                ) AS transfers
+            ORDER BY
+                miniblock_number,
+                event_index_in_block
+            LIMIT
+                $6
+            "#,
+            address.as_bytes(),
+            from_block,
+            to_block,
+            after_block,
+            after_index,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| api::TokenTransfer {
+                block_number: MiniblockNumber(row.miniblock_number as u32),
+                log_index: row.event_index_in_block as u32,
+                transaction_hash: H256::from_slice(&row.tx_hash),
+                token: Address::from_slice(&row.token_address),
+                from: Address::from_slice(&row.from_address),
+                to: Address::from_slice(&row.to_address),
+                amount: bigdecimal_to_u256(row.amount),
+            })
+            .collect())
+    }
+
+    /// Removes fungible and NFT transfers with a block number strictly greater than the specified `block_number`.
+    /// NFT balances and the indexer progress are rolled back accordingly.
+    pub async fn rollback_token_transfers(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> sqlx::Result<()> {
+        self.update_nft_balances(block_number + 1..=MiniblockNumber(u32::MAX), true)
+            .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM token_transfers
+            WHERE
+                miniblock_number > $1
+            "#,
+            block_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM nft_transfers
+            WHERE
+                miniblock_number > $1
+            "#,
+            block_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        sqlx::query!(
+            r#"
+            UPDATE token_transfers_indexer_state
+            SET
+                last_indexed_miniblock = $1,
+                updated_at = NOW()
+            WHERE
+                last_indexed_miniblock > $1
+            "#,
+            block_number.0 as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        ethabi::{self, Token},
+        event::{TRANSFER_EVENT_SIGNATURE, TRANSFER_SINGLE_EVENT_SIGNATURE},
+        tokens::NftStandard,
+        L1BatchNumber, ProtocolVersion, U256,
+    };
+    use zksync_utils::{address_to_h256, u256_to_h256};
+
+    use super::*;
+    use crate::{tests::create_miniblock_header, ConnectionPool};
+
+    fn create_transfer_event(token: Address, from: Address, to: Address, amount: u64) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: token,
+            indexed_topics: vec![
+                *TRANSFER_EVENT_SIGNATURE,
+                address_to_h256(&from),
+                address_to_h256(&to),
+            ],
+            value: u256_to_h256(U256::from(amount)).as_bytes().to_vec(),
+        }
+    }
+
+    fn create_tx_location(index: u8) -> IncludedTxLocation {
+        IncludedTxLocation {
+            tx_hash: H256::repeat_byte(index),
+            tx_index_in_miniblock: index.into(),
+            tx_initiator_address: Address::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn storing_and_querying_token_transfers() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=2 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let token = Address::repeat_byte(0x10);
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+        let carol = Address::repeat_byte(3);
+        let unrelated_event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: token,
+            indexed_topics: vec![H256::repeat_byte(0xff)],
+            value: vec![],
+        };
+        let first_block_events = [
+            unrelated_event,
+            create_transfer_event(token, alice, bob, 100),
+            create_transfer_event(token, bob, carol, 50),
+        ];
+        let first_block_events = vec![
+            (create_tx_location(0), vec![&first_block_events[0]]),
+            (
+                create_tx_location(1),
+                first_block_events[1..].iter().collect(),
+            ),
+        ];
+        conn.token_transfers_dal()
+            .insert_token_transfers(MiniblockNumber(1), &first_block_events)
+            .await
+            .unwrap();
+        let second_block_event = create_transfer_event(token, carol, alice, 10);
+        conn.token_transfers_dal()
+            .insert_token_transfers(
+                MiniblockNumber(2),
+                &[(create_tx_location(2), vec![&second_block_event])],
+            )
+            .await
+            .unwrap();
+
+        let transfers = conn
+            .token_transfers_dal()
+            .get_token_transfers(alice, &api::TokenTransfersRange::default(), 10)
+            .await
+            .unwrap();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].block_number, MiniblockNumber(1));
+        assert_eq!(transfers[0].log_index, 1);
+        assert_eq!(transfers[0].transaction_hash, H256::repeat_byte(1));
+        assert_eq!((transfers[0].from, transfers[0].to), (alice, bob));
+        assert_eq!(transfers[0].amount, 100.into());
+        assert_eq!(transfers[1].block_number, MiniblockNumber(2));
+        assert_eq!(transfers[1].log_index, 0);
+        assert_eq!((transfers[1].from, transfers[1].to), (carol, alice));
+
+        // Pagination using a cursor
+        let range = api::TokenTransfersRange {
+            after: Some(api::TokenTransferCursor {
+                block_number: MiniblockNumber(1),
+                log_index: 1,
+            }),
+            ..api::TokenTransfersRange::default()
+        };
+        let transfers = conn
+            .token_transfers_dal()
+            .get_token_transfers(bob, &range, 10)
+            .await
+            .unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].log_index, 2);
+        assert_eq!((transfers[0].from, transfers[0].to), (bob, carol));
+
+        let range = api::TokenTransfersRange {
+            to_block: Some(MiniblockNumber(1)),
+            ..api::TokenTransfersRange::default()
+        };
+        let transfers = conn
+            .token_transfers_dal()
+            .get_token_transfers(carol, &range, 10)
+            .await
+            .unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].block_number, MiniblockNumber(1));
+
+        let transfers = conn
+            .token_transfers_dal()
+            .get_token_transfers(alice, &api::TokenTransfersRange::default(), 1)
+            .await
+            .unwrap();
+        assert_eq!(transfers.len(), 1);
+
+        conn.token_transfers_dal()
+            .rollback_token_transfers(MiniblockNumber(1))
+            .await
+            .unwrap();
+        let transfers = conn
+            .token_transfers_dal()
+            .get_token_transfers(alice, &api::TokenTransfersRange::default(), 10)
+            .await
+            .unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].block_number, MiniblockNumber(1));
+    }
+
+    fn create_erc721_transfer_event(
+        token: Address,
+        from: Address,
+        to: Address,
+        id: u64,
+    ) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: token,
+            indexed_topics: vec![
+                *TRANSFER_EVENT_SIGNATURE,
+                address_to_h256(&from),
+                address_to_h256(&to),
+                u256_to_h256(U256::from(id)),
+            ],
+            value: vec![],
+        }
+    }
+
+    fn create_erc1155_transfer_event(
+        token: Address,
+        from: Address,
+        to: Address,
+        id: u64,
+        amount: u64,
+    ) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: token,
+            indexed_topics: vec![
+                *TRANSFER_SINGLE_EVENT_SIGNATURE,
+                address_to_h256(&from),
+                address_to_h256(&from),
+                address_to_h256(&to),
+            ],
+            value: ethabi::encode(&[Token::Uint(id.into()), Token::Uint(amount.into())]),
+        }
+    }
+
+    #[tokio::test]
+    async fn computing_nft_holdings() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        for number in 1..=2 {
+            conn.blocks_dal()
+                .insert_miniblock(&create_miniblock_header(number))
+                .await
+                .unwrap();
+        }
+
+        let erc721_token = Address::repeat_byte(0x10);
+        let erc1155_token = Address::repeat_byte(0x20);
+        let alice = Address::repeat_byte(1);
+        let bob = Address::repeat_byte(2);
+        let first_block_events = [
+            create_erc721_transfer_event(erc721_token, Address::zero(), alice, 1),
+            create_erc721_transfer_event(erc721_token, Address::zero(), alice, 2),
+            create_erc1155_transfer_event(erc1155_token, Address::zero(), alice, 5, 100),
+        ];
+        conn.token_transfers_dal()
+            .insert_token_transfers(
+                MiniblockNumber(1),
+                &[(create_tx_location(0), first_block_events.iter().collect())],
+            )
+            .await
+            .unwrap();
+        let second_block_events = [
+            create_erc721_transfer_event(erc721_token, alice, bob, 1),
+            create_erc1155_transfer_event(erc1155_token, alice, bob, 5, 30),
+        ];
+        conn.token_transfers_dal()
+            .insert_token_transfers(
+                MiniblockNumber(2),
+                &[(create_tx_location(1), second_block_events.iter().collect())],
+            )
+            .await
+            .unwrap();
+
+        // NFT transfers must not be indexed as fungible token transfers.
+        let transfers = conn
+            .token_transfers_dal()
+            .get_token_transfers(alice, &api::TokenTransfersRange::default(), 10)
+            .await
+            .unwrap();
+        assert!(transfers.is_empty(), "{transfers:?}");
+
+        let holdings = conn
+            .token_transfers_dal()
+            .get_nft_holdings(alice, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            holdings,
+            [
+                api::NftHolding {
+                    token: erc721_token,
+                    standard: NftStandard::Erc721,
+                    token_id: 2.into(),
+                    amount: 1.into(),
+                },
+                api::NftHolding {
+                    token: erc1155_token,
+                    standard: NftStandard::Erc1155,
+                    token_id: 5.into(),
+                    amount: 70.into(),
+                },
+            ]
+        );
+
+        let bob_holdings = conn
+            .token_transfers_dal()
+            .get_nft_holdings(bob, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(bob_holdings.len(), 2);
+        assert_eq!(bob_holdings[0].token_id, 1.into());
+        assert_eq!(bob_holdings[1].amount, 30.into());
+
+        // Pagination and filtering
+        let holdings = conn
+            .token_transfers_dal()
+            .get_nft_holdings(alice, Some(&holdings[0].cursor()), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(holdings.len(), 1);
+        assert_eq!(holdings[0].token, erc1155_token);
+        let cursor = api::AccountBalancesCursor {
+            token: erc721_token,
+            token_id: None,
+        };
+        let holdings = conn
+            .token_transfers_dal()
+            .get_nft_holdings(alice, Some(&cursor), None, 1)
+            .await
+            .unwrap();
+        assert_eq!(holdings.len(), 1);
+        assert_eq!(holdings[0].token, erc721_token);
+        let holdings = conn
+            .token_transfers_dal()
+            .get_nft_holdings(alice, None, Some(&[erc1155_token]), 10)
+            .await
+            .unwrap();
+        assert_eq!(holdings.len(), 1);
+        assert_eq!(holdings[0].token, erc1155_token);
+
+        conn.token_transfers_dal()
+            .rollback_token_transfers(MiniblockNumber(1))
+            .await
+            .unwrap();
+        let holdings = conn
+            .token_transfers_dal()
+            .get_nft_holdings(alice, None, None, 10)
+            .await
+            .unwrap();
+        let amounts: Vec<_> = holdings.iter().map(|holding| holding.amount).collect();
+        assert_eq!(amounts, [1.into(), 1.into(), 100.into()]);
+    }
+
+    #[tokio::test]
+    async fn nft_holdings_are_retained_after_pruning() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+
+        let erc721_token = Address::repeat_byte(0x10);
+        let alice = Address::repeat_byte(1);
+        let events = [create_erc721_transfer_event(
+            erc721_token,
+            Address::zero(),
+            alice,
+            1,
+        )];
+        conn.token_transfers_dal()
+            .insert_token_transfers(
+                MiniblockNumber(1),
+                &[(create_tx_location(0), events.iter().collect())],
+            )
+            .await
+            .unwrap();
+        // Balances of the zero address are not tracked.
+        let holdings = conn
+            .token_transfers_dal()
+            .get_nft_holdings(Address::zero(), None, None, 10)
+            .await
+            .unwrap();
+        assert!(holdings.is_empty(), "{holdings:?}");
+
+        // Emulate hard pruning; NFT transfers are also removed via cascading from miniblocks.
+        conn.token_transfers_dal()
+            .delete_token_transfers(MiniblockNumber(0)..=MiniblockNumber(1))
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM miniblocks")
+            .execute(conn.conn())
+            .await
+            .unwrap();
+
+        let holdings = conn
+            .token_transfers_dal()
+            .get_nft_holdings(alice, None, None, 10)
+            .await
+            .unwrap();
+        assert_eq!(
+            holdings,
+            [api::NftHolding {
+                token: erc721_token,
+                standard: NftStandard::Erc721,
+                token_id: 1.into(),
+                amount: 1.into(),
+            }]
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use sqlx::types::chrono::Utc;
-use zksync_types::{tokens::TokenInfo, Address, MiniblockNumber};
+use zksync_types::{
+    tokens::{TokenInfo, TokenMetadata},
+    Address, MiniblockNumber,
+};
 
 use crate::StorageProcessor;
 
@@ -9,12 +12,13 @@ pub struct TokensDal<'a, 'c> {
 }
 
 impl TokensDal<'_, '_> {
+    /// Adds new tokens. Tokens with missing (i.e., empty) name or symbol are marked as having stale metadata.
     pub async fn add_tokens(&mut self, tokens: &[TokenInfo]) -> sqlx::Result<()> {
         let mut copy = self
             .storage
             .conn()
             .copy_in_raw(
-                "COPY tokens (l1_address, l2_address, name, symbol, decimals, well_known, created_at, updated_at)
+                "COPY tokens (l1_address, l2_address, name, symbol, decimals, well_known, metadata_stale, created_at, updated_at)
                 FROM STDIN WITH (DELIMITER '|')",
             )
             .await?;
@@ -22,6 +26,8 @@ impl TokensDal<'_, '_> {
         let mut buffer = String::new();
         let now = Utc::now().naive_utc().to_string();
         for token_info in tokens {
+            let metadata_stale =
+                token_info.metadata.name.is_empty() || token_info.metadata.symbol.is_empty();
             write_str!(
                 &mut buffer,
                 "\\\\x{:x}|\\\\x{:x}|",
@@ -30,7 +36,7 @@ impl TokensDal<'_, '_> {
             );
             writeln_str!(
                 &mut buffer,
-                "{}|{}|{}|FALSE|{now}|{now}",
+                "{}|{}|{}|FALSE|{metadata_stale}|{now}|{now}",
                 token_info.metadata.name,
                 token_info.metadata.symbol,
                 token_info.metadata.decimals
@@ -58,6 +64,93 @@ impl TokensDal<'_, '_> {
         Ok(())
     }
 
+    /// Marks metadata of the token with the specified L2 address as stale, so that it is re-read from L1.
+    pub async fn mark_token_metadata_as_stale(&mut self, l2_address: Address) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE tokens
+            SET
+                metadata_stale = TRUE,
+                updated_at = NOW()
+            WHERE
+                l2_address = $1
+            "#,
+            l2_address.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` tokens with stale metadata, starting from the least recently updated ones.
+    pub async fn get_tokens_with_stale_metadata(
+        &mut self,
+        limit: usize,
+    ) -> sqlx::Result<Vec<TokenInfo>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l1_address,
+                l2_address,
+                NAME,
+                symbol,
+                decimals
+            FROM
+                tokens
+            WHERE
+                metadata_stale
+            ORDER BY
+                updated_at
+            LIMIT
+                $1
+            "#,
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TokenInfo {
+                l1_address: Address::from_slice(&row.l1_address),
+                l2_address: Address::from_slice(&row.l2_address),
+                metadata: TokenMetadata {
+                    name: row.name,
+                    symbol: row.symbol,
+                    decimals: row.decimals as u8,
+                },
+            })
+            .collect())
+    }
+
+    /// Updates metadata of the token with the specified L2 address and marks it as fresh.
+    pub async fn update_token_metadata(
+        &mut self,
+        l2_address: Address,
+        metadata: &TokenMetadata,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE tokens
+            SET
+                NAME = $2,
+                symbol = $3,
+                decimals = $4,
+                metadata_stale = FALSE,
+                updated_at = NOW()
+            WHERE
+                l2_address = $1
+            "#,
+            l2_address.as_bytes(),
+            metadata.name,
+            metadata.symbol,
+            i32::from(metadata.decimals)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
     pub async fn get_all_l2_token_addresses(&mut self) -> sqlx::Result<Vec<Address>> {
         let rows = sqlx::query!(
             r#"
@@ -108,7 +201,7 @@ mod tests {
     use std::{collections::HashSet, slice};
 
     use zksync_system_constants::FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH;
-    use zksync_types::{get_code_key, tokens::TokenMetadata, StorageLog, H256};
+    use zksync_types::{get_code_key, StorageLog, H256};
 
     use super::*;
     use crate::ConnectionPool;
@@ -184,6 +277,55 @@ mod tests {
         assert!(well_known_tokens.contains(&tokens[1]));
     }
 
+    #[tokio::test]
+    async fn refreshing_stale_token_metadata() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        let mut token_without_metadata = test_token_info();
+        token_without_metadata.metadata.name = String::new();
+        token_without_metadata.metadata.symbol = String::new();
+        let tokens = [token_without_metadata.clone(), eth_token_info()];
+        storage.tokens_dal().add_tokens(&tokens).await.unwrap();
+
+        let stale_tokens = storage
+            .tokens_dal()
+            .get_tokens_with_stale_metadata(10)
+            .await
+            .unwrap();
+        assert_eq!(stale_tokens, [token_without_metadata]);
+
+        let test_info = test_token_info();
+        storage
+            .tokens_dal()
+            .update_token_metadata(test_info.l2_address, &test_info.metadata)
+            .await
+            .unwrap();
+        let stale_tokens = storage
+            .tokens_dal()
+            .get_tokens_with_stale_metadata(10)
+            .await
+            .unwrap();
+        assert_eq!(stale_tokens, []);
+        let token = storage
+            .tokens_web3_dal()
+            .get_token_info(test_info.l2_address)
+            .await
+            .unwrap();
+        assert_eq!(token, Some(test_info.clone()));
+
+        storage
+            .tokens_dal()
+            .mark_token_metadata_as_stale(test_info.l2_address)
+            .await
+            .unwrap();
+        let stale_tokens = storage
+            .tokens_dal()
+            .get_tokens_with_stale_metadata(10)
+            .await
+            .unwrap();
+        assert_eq!(stale_tokens, [test_info]);
+    }
+
     #[tokio::test]
     async fn rolling_back_tokens() {
         let pool = ConnectionPool::test_pool().await;
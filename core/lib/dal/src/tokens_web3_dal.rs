@@ -59,6 +59,97 @@ impl TokensWeb3Dal<'_, '_> {
         Ok(records.into_iter().map(Into::into).collect())
     }
 
+    /// Checks whether a token with the specified L2 address is registered (i.e., it was bridged via
+    /// the default bridge).
+    pub async fn is_l2_token_registered(&mut self, l2_address: Address) -> sqlx::Result<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        tokens
+                    WHERE
+                        l2_address = $1
+                ) AS "exists!"
+            "#,
+            l2_address.as_bytes()
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(row.exists)
+    }
+
+    /// Returns information about the token with the specified L2 address, or `None` if the token is not registered.
+    pub async fn get_token_info(&mut self, l2_address: Address) -> sqlx::Result<Option<TokenInfo>> {
+        let record = sqlx::query_as!(
+            StorageTokenInfo,
+            r#"
+            SELECT
+                l1_address,
+                l2_address,
+                NAME,
+                symbol,
+                decimals
+            FROM
+                tokens
+            WHERE
+                l2_address = $1
+            "#,
+            l2_address.as_bytes()
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(record.map(Into::into))
+    }
+
+    /// Returns L2 addresses of registered tokens strictly greater than `after`, ordered by the address. If `tokens`
+    /// are specified, only addresses from this list are returned.
+    pub async fn get_l2_token_addresses(
+        &mut self,
+        after: Option<Address>,
+        tokens: Option<&[Address]>,
+        limit: usize,
+    ) -> sqlx::Result<Vec<Address>> {
+        let after = after.map_or_else(Vec::new, |address| address.as_bytes().to_vec());
+        let tokens: Option<Vec<_>> = tokens.map(|tokens| {
+            tokens
+                .iter()
+                .map(|token| token.as_bytes().to_vec())
+                .collect()
+        });
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                l2_address
+            FROM
+                tokens
+            WHERE
+                l2_address > $1
+                AND (
+                    $2::BYTEA[] IS NULL
+                    OR l2_address = ANY ($2)
+                )
+            ORDER BY
+                l2_address
+            LIMIT
+                $3
+            "#,
+            &after,
+            tokens.as_deref(),
+            limit as i64
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Address::from_slice(&row.l2_address))
+            .collect())
+    }
+
     /// Returns information about all tokens.
     pub async fn get_all_tokens(
         &mut self,
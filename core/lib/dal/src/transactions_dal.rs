@@ -5,13 +5,14 @@ use bigdecimal::BigDecimal;
 use itertools::Itertools;
 use sqlx::{error, types::chrono::NaiveDateTime};
 use zksync_types::{
+    api::TransactionDeadline,
     block::MiniblockExecutionData,
     fee::TransactionExecutionMetrics,
     l1::L1Tx,
     l2::L2Tx,
     protocol_version::ProtocolUpgradeTx,
     tx::{tx_execution_info::TxExecutionStatus, TransactionExecutionResult},
-    vm_trace::Call,
+    vm_trace::{Call, CallType},
     Address, ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, MiniblockNumber, PriorityOpId,
     Transaction, H256, PROTOCOL_UPGRADE_TX_TYPE, U256,
 };
@@ -24,6 +25,19 @@ use crate::{
     StorageProcessor,
 };
 
+/// Collects addresses of callers / callees in the call trace. Near calls and their subcalls are skipped,
+/// consistently with the `trace_filter` API method.
+fn collect_call_trace_addresses(call: &Call, addresses: &mut HashMap<Address, (bool, bool)>) {
+    if matches!(call.r#type, CallType::NearCall) {
+        return;
+    }
+    addresses.entry(call.from).or_default().0 = true;
+    addresses.entry(call.to).or_default().1 = true;
+    for subcall in &call.calls {
+        collect_call_trace_addresses(subcall, addresses);
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum L2TxSubmissionResult {
     Added,
@@ -52,6 +66,9 @@ pub struct TransactionsDal<'c, 'a> {
 
 type TxLocations = Vec<(MiniblockNumber, Vec<(H256, u32, u16)>)>;
 
+/// Error recorded for L2 transactions that weren't included before their deadline.
+const EXPIRED_TX_ERROR: &str = "expired: inclusion deadline has passed";
+
 impl TransactionsDal<'_, '_> {
     pub async fn insert_transaction_l1(&mut self, tx: L1Tx, l1_block_number: L1BlockNumber) {
         {
@@ -73,6 +90,9 @@ impl TransactionsDal<'_, '_> {
 
             let to_mint = u256_to_big_decimal(tx.common_data.to_mint);
             let refund_recipient = tx.common_data.refund_recipient.as_bytes();
+            // The hash is unknown (zero) for transactions received by external nodes from the main node.
+            let l1_tx_hash =
+                (!tx.common_data.eth_hash.is_zero()).then(|| tx.common_data.eth_hash.as_bytes());
 
             let secs = (tx.received_timestamp_ms / 1000) as i64;
             let nanosecs = ((tx.received_timestamp_ms % 1000) * 1_000_000) as u32;
@@ -100,6 +120,7 @@ impl TransactionsDal<'_, '_> {
                         tx_format,
                         l1_tx_mint,
                         l1_tx_refund_recipient,
+                        l1_tx_hash,
                         received_at,
                         created_at,
                         updated_at
@@ -125,6 +146,7 @@ impl TransactionsDal<'_, '_> {
                         $16,
                         $17,
                         $18,
+                        $19,
                         NOW(),
                         NOW()
                     )
@@ -147,6 +169,7 @@ impl TransactionsDal<'_, '_> {
                 tx_format,
                 to_mint,
                 refund_recipient,
+                l1_tx_hash,
                 received_at,
             )
             .fetch_optional(self.storage.conn())
@@ -173,6 +196,8 @@ impl TransactionsDal<'_, '_> {
 
             let to_mint = u256_to_big_decimal(tx.common_data.to_mint);
             let refund_recipient = tx.common_data.refund_recipient.as_bytes().to_vec();
+            let l1_tx_hash = (!tx.common_data.eth_hash.is_zero())
+                .then(|| tx.common_data.eth_hash.as_bytes().to_vec());
 
             let secs = (tx.received_timestamp_ms / 1000) as i64;
             let nanosecs = ((tx.received_timestamp_ms % 1000) * 1_000_000) as u32;
@@ -198,6 +223,7 @@ impl TransactionsDal<'_, '_> {
                         tx_format,
                         l1_tx_mint,
                         l1_tx_refund_recipient,
+                        l1_tx_hash,
                         received_at,
                         created_at,
                         updated_at
@@ -221,6 +247,7 @@ impl TransactionsDal<'_, '_> {
                         $14,
                         $15,
                         $16,
+                        $17,
                         NOW(),
                         NOW()
                     )
@@ -241,6 +268,7 @@ impl TransactionsDal<'_, '_> {
                 tx_format,
                 to_mint,
                 refund_recipient,
+                l1_tx_hash,
                 received_at,
             )
             .fetch_optional(self.storage.conn())
@@ -376,7 +404,10 @@ impl TransactionsDal<'_, '_> {
                     received_at = $19,
                     created_at = NOW(),
                     updated_at = NOW(),
-                    error = NULL
+                    error = NULL,
+                    valid_until_timestamp = NULL,
+                    valid_until_miniblock = NULL,
+                    expired_at = NULL
                 WHERE
                     transactions.is_priority = FALSE
                     AND transactions.miniblock_number IS NULL
@@ -453,6 +484,35 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Sets the inclusion deadline for a pending L2 transaction. Deadlines are reset
+    /// if the transaction is replaced.
+    pub async fn set_transaction_deadline(
+        &mut self,
+        tx_hash: H256,
+        deadline: &TransactionDeadline,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                valid_until_timestamp = $2,
+                valid_until_miniblock = $3,
+                updated_at = NOW()
+            WHERE
+                hash = $1
+                AND miniblock_number IS NULL
+            "#,
+            tx_hash.as_bytes(),
+            deadline.timestamp.map(|timestamp| timestamp as i64),
+            deadline.block_number.map(|number| i64::from(number.0))
+        )
+        .instrument("set_transaction_deadline")
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
     pub async fn mark_txs_as_executed_in_l1_batch(
         &mut self,
         block_number: L1BatchNumber,
@@ -501,6 +561,7 @@ impl TransactionsDal<'_, '_> {
             let mut l1_execution_infos = Vec::with_capacity(transactions.len());
             let mut l1_refunded_gas = Vec::with_capacity(transactions.len());
             let mut l1_effective_gas_prices = Vec::with_capacity(transactions.len());
+            let mut l1_revert_reasons = Vec::with_capacity(transactions.len());
 
             let mut upgrade_hashes = Vec::new();
             let mut upgrade_indices_in_block = Vec::new();
@@ -508,6 +569,7 @@ impl TransactionsDal<'_, '_> {
             let mut upgrade_execution_infos = Vec::new();
             let mut upgrade_refunded_gas = Vec::new();
             let mut upgrade_effective_gas_prices = Vec::new();
+            let mut upgrade_revert_reasons = Vec::new();
 
             let mut l2_hashes = Vec::with_capacity(transactions.len());
             let mut l2_values = Vec::with_capacity(transactions.len());
@@ -529,9 +591,14 @@ impl TransactionsDal<'_, '_> {
             let mut l2_max_priority_fees_per_gas = Vec::with_capacity(transactions.len());
             let mut l2_gas_per_pubdata_limit = Vec::with_capacity(transactions.len());
             let mut l2_refunded_gas = Vec::with_capacity(transactions.len());
+            let mut l2_revert_reasons = Vec::with_capacity(transactions.len());
 
             let mut call_traces_tx_hashes = Vec::with_capacity(transactions.len());
             let mut bytea_call_traces = Vec::with_capacity(transactions.len());
+            let mut call_trace_address_tx_hashes = vec![];
+            let mut call_trace_addresses = vec![];
+            let mut call_trace_address_is_caller = vec![];
+            let mut call_trace_address_is_callee = vec![];
             transactions
                 .iter()
                 .enumerate()
@@ -542,8 +609,11 @@ impl TransactionsDal<'_, '_> {
                         transaction,
                         execution_status,
                         refunded_gas,
+                        decoded_revert_reason,
                         ..
                     } = tx_res;
+                    // `None` is serialized to JSON `null`, which is converted to SQL `NULL` in the queries below.
+                    let revert_reason = serde_json::to_value(decoded_revert_reason).unwrap();
 
                     // Bootloader currently doesn't return detailed errors.
                     let error = match execution_status {
@@ -555,6 +625,14 @@ impl TransactionsDal<'_, '_> {
                     };
 
                     if let Some(call_trace) = tx_res.call_trace() {
+                        let mut addresses = HashMap::new();
+                        collect_call_trace_addresses(&call_trace, &mut addresses);
+                        for (address, (is_caller, is_callee)) in addresses {
+                            call_trace_address_tx_hashes.push(hash.0.to_vec());
+                            call_trace_addresses.push(address.0.to_vec());
+                            call_trace_address_is_caller.push(is_caller);
+                            call_trace_address_is_callee.push(is_callee);
+                        }
                         bytea_call_traces.push(bincode::serialize(&call_trace).unwrap());
                         call_traces_tx_hashes.push(hash.0.to_vec());
                     }
@@ -568,6 +646,7 @@ impl TransactionsDal<'_, '_> {
                             l1_refunded_gas.push(*refunded_gas as i64);
                             l1_effective_gas_prices
                                 .push(u256_to_big_decimal(common_data.max_fee_per_gas));
+                            l1_revert_reasons.push(revert_reason);
                         }
                         ExecuteTransactionCommon::L2(common_data) => {
                             let data = serde_json::to_value(&transaction.execute).unwrap();
@@ -603,6 +682,7 @@ impl TransactionsDal<'_, '_> {
                             l2_gas_per_pubdata_limit
                                 .push(u256_to_big_decimal(common_data.fee.gas_per_pubdata_limit));
                             l2_refunded_gas.push(*refunded_gas as i64);
+                            l2_revert_reasons.push(revert_reason);
                         }
                         ExecuteTransactionCommon::ProtocolUpgrade(common_data) => {
                             upgrade_hashes.push(hash.0.to_vec());
@@ -613,6 +693,7 @@ impl TransactionsDal<'_, '_> {
                             upgrade_refunded_gas.push(*refunded_gas as i64);
                             upgrade_effective_gas_prices
                                 .push(u256_to_big_decimal(common_data.max_fee_per_gas));
+                            upgrade_revert_reasons.push(revert_reason);
                         }
                     }
                 });
@@ -647,7 +728,9 @@ impl TransactionsDal<'_, '_> {
                         contract_address = data_table.contract_address,
                         paymaster = data_table.paymaster,
                         paymaster_input = data_table.paymaster_input,
+                        revert_reason = NULLIF(data_table.revert_reason, 'null'::jsonb),
                         in_mempool = FALSE,
+                        expired_at = NULL,
                         updated_at = NOW()
                     FROM
                         (
@@ -675,7 +758,8 @@ impl TransactionsDal<'_, '_> {
                                         UNNEST($17::NUMERIC[]) AS value,
                                         UNNEST($18::bytea[]) AS contract_address,
                                         UNNEST($19::bytea[]) AS paymaster,
-                                        UNNEST($20::bytea[]) AS paymaster_input
+                                        UNNEST($20::bytea[]) AS paymaster_input,
+                                        UNNEST($22::jsonb[]) AS revert_reason
                                 ) AS data_table_temp
                                 JOIN transactions ON transactions.initiator_address = data_table_temp.initiator_address
                                 AND transactions.nonce = data_table_temp.nonce
@@ -707,6 +791,7 @@ impl TransactionsDal<'_, '_> {
                     &l2_paymaster,
                     &l2_paymaster_input,
                     miniblock_number.0 as i32,
+                    &l2_revert_reasons,
                 )
                 .execute(transaction.conn())
                 .await
@@ -726,6 +811,7 @@ impl TransactionsDal<'_, '_> {
                         execution_info = execution_info || data_table.new_execution_info,
                         refunded_gas = data_table.refunded_gas,
                         effective_gas_price = data_table.effective_gas_price,
+                        revert_reason = NULLIF(data_table.revert_reason, 'null'::jsonb),
                         updated_at = NOW()
                     FROM
                         (
@@ -735,7 +821,8 @@ impl TransactionsDal<'_, '_> {
                                 UNNEST($4::VARCHAR[]) AS error,
                                 UNNEST($5::jsonb[]) AS new_execution_info,
                                 UNNEST($6::BIGINT[]) AS refunded_gas,
-                                UNNEST($7::NUMERIC[]) AS effective_gas_price
+                                UNNEST($7::NUMERIC[]) AS effective_gas_price,
+                                UNNEST($8::jsonb[]) AS revert_reason
                         ) AS data_table
                     WHERE
                         transactions.hash = data_table.hash
@@ -747,6 +834,7 @@ impl TransactionsDal<'_, '_> {
                     &l1_execution_infos,
                     &l1_refunded_gas,
                     &l1_effective_gas_prices,
+                    &l1_revert_reasons,
                 )
                 .execute(transaction.conn())
                 .await
@@ -765,6 +853,7 @@ impl TransactionsDal<'_, '_> {
                         execution_info = execution_info || data_table.new_execution_info,
                         refunded_gas = data_table.refunded_gas,
                         effective_gas_price = data_table.effective_gas_price,
+                        revert_reason = NULLIF(data_table.revert_reason, 'null'::jsonb),
                         updated_at = NOW()
                     FROM
                         (
@@ -774,7 +863,8 @@ impl TransactionsDal<'_, '_> {
                                 UNNEST($4::VARCHAR[]) AS error,
                                 UNNEST($5::jsonb[]) AS new_execution_info,
                                 UNNEST($6::BIGINT[]) AS refunded_gas,
-                                UNNEST($7::NUMERIC[]) AS effective_gas_price
+                                UNNEST($7::NUMERIC[]) AS effective_gas_price,
+                                UNNEST($8::jsonb[]) AS revert_reason
                         ) AS data_table
                     WHERE
                         transactions.hash = data_table.hash
@@ -786,6 +876,7 @@ impl TransactionsDal<'_, '_> {
                     &upgrade_execution_infos,
                     &upgrade_refunded_gas,
                     &upgrade_effective_gas_prices,
+                    &upgrade_revert_reasons,
                 )
                 .execute(transaction.conn())
                 .await
@@ -812,6 +903,31 @@ impl TransactionsDal<'_, '_> {
                 .await
                 .unwrap();
             }
+
+            if !call_trace_addresses.is_empty() {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO
+                        call_trace_addresses (tx_hash, address, is_caller, is_callee)
+                    SELECT
+                        u.tx_hash,
+                        u.address,
+                        u.is_caller,
+                        u.is_callee
+                    FROM
+                        UNNEST($1::bytea[], $2::bytea[], $3::BOOLEAN[], $4::BOOLEAN[]) AS u (tx_hash, address, is_caller, is_callee)
+                    "#,
+                    &call_trace_address_tx_hashes,
+                    &call_trace_addresses,
+                    &call_trace_address_is_caller,
+                    &call_trace_address_is_callee
+                )
+                .instrument("insert_call_trace_addresses")
+                .report_latency()
+                .execute(&mut transaction)
+                .await
+                .unwrap();
+            }
             transaction.commit().await.unwrap();
         }
     }
@@ -839,6 +955,31 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Marks a pending L2 transaction as expired, i.e., not included before its deadline.
+    /// Returns `false` if the transaction is not pending or was already expired or rejected.
+    pub async fn mark_tx_as_expired(&mut self, tx_hash: H256) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                error = $2,
+                expired_at = NOW(),
+                updated_at = NOW()
+            WHERE
+                hash = $1
+                AND miniblock_number IS NULL
+                AND error IS NULL
+            "#,
+            tx_hash.as_bytes(),
+            EXPIRED_TX_ERROR
+        )
+        .instrument("mark_tx_as_expired")
+        .with_arg("tx_hash", &tx_hash)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn reset_transactions_state(&mut self, miniblock_number: MiniblockNumber) {
         {
             let tx_hashes = sqlx::query!(
@@ -848,6 +989,7 @@ impl TransactionsDal<'_, '_> {
                     l1_batch_number = NULL,
                     miniblock_number = NULL,
                     error = NULL,
+                    revert_reason = NULL,
                     index_in_block = NULL,
                     execution_info = '{}'
                 WHERE
@@ -898,16 +1040,67 @@ impl TransactionsDal<'_, '_> {
         Ok(rows.len())
     }
 
-    /// Fetches new updates for mempool. Returns new transactions and current nonces for related accounts;
-    /// the latter are only used to bootstrap mempool for given account.
-    pub async fn sync_mempool(
+    /// Marks pending L2 transactions with passed deadlines as expired. A deadline is passed if its timestamp
+    /// is less than `now_timestamp`, or if a miniblock with the deadline number is already sealed.
+    /// Returns hashes of expired transactions.
+    pub async fn expire_transactions(&mut self, now_timestamp: u64) -> sqlx::Result<Vec<H256>> {
+        // Note, that transactions are updated in order of their hashes to avoid deadlocks with other UPDATE queries.
+        let rows = sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET
+                error = $2,
+                expired_at = NOW(),
+                updated_at = NOW()
+            FROM
+                (
+                    SELECT
+                        hash
+                    FROM
+                        transactions
+                    WHERE
+                        miniblock_number IS NULL
+                        AND is_priority = FALSE
+                        AND error IS NULL
+                        AND (
+                            valid_until_timestamp < $1
+                            OR valid_until_miniblock <= (
+                                SELECT
+                                    MAX(number)
+                                FROM
+                                    miniblocks
+                            )
+                        )
+                    ORDER BY
+                        hash
+                ) AS subquery
+            WHERE
+                transactions.hash = subquery.hash
+            RETURNING
+                transactions.hash
+            "#,
+            now_timestamp as i64,
+            EXPIRED_TX_ERROR
+        )
+        .instrument("expire_transactions")
+        .with_arg("now_timestamp", &now_timestamp)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect())
+    }
+
+    /// Persists changes in the mempool state that weren't yet reflected in Postgres: transactions
+    /// of `stashed_accounts` are returned to the pool of transactions not loaded to the mempool,
+    /// and transactions of `purged_accounts` are removed.
+    pub async fn persist_mempool_accounts(
         &mut self,
         stashed_accounts: &[Address],
         purged_accounts: &[Address],
-        gas_per_pubdata: u32,
-        fee_per_gas: u64,
-        limit: usize,
-    ) -> sqlx::Result<Vec<Transaction>> {
+    ) -> sqlx::Result<()> {
         let stashed_addresses: Vec<_> = stashed_accounts.iter().map(Address::as_bytes).collect();
         sqlx::query!(
             r#"
@@ -937,6 +1130,21 @@ impl TransactionsDal<'_, '_> {
         )
         .execute(self.storage.conn())
         .await?;
+        Ok(())
+    }
+
+    /// Fetches new updates for mempool. Returns new transactions and current nonces for related accounts;
+    /// the latter are only used to bootstrap mempool for given account.
+    pub async fn sync_mempool(
+        &mut self,
+        stashed_accounts: &[Address],
+        purged_accounts: &[Address],
+        gas_per_pubdata: u32,
+        fee_per_gas: u64,
+        limit: usize,
+    ) -> sqlx::Result<Vec<Transaction>> {
+        self.persist_mempool_accounts(stashed_accounts, purged_accounts)
+            .await?;
 
         // Note, that transactions are updated in order of their hashes to avoid deadlocks with other UPDATE queries.
         let transactions = sqlx::query_as!(
@@ -994,6 +1202,49 @@ impl TransactionsDal<'_, '_> {
         Ok(transactions)
     }
 
+    /// Returns inclusion deadlines for the specified transactions. Transactions without a deadline
+    /// are not included into the returned map.
+    pub async fn get_transaction_deadlines(
+        &mut self,
+        tx_hashes: &[H256],
+    ) -> sqlx::Result<HashMap<H256, TransactionDeadline>> {
+        let tx_hashes: Vec<_> = tx_hashes.iter().map(H256::as_bytes).collect();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                valid_until_timestamp,
+                valid_until_miniblock
+            FROM
+                transactions
+            WHERE
+                hash = ANY ($1)
+                AND (
+                    valid_until_timestamp IS NOT NULL
+                    OR valid_until_miniblock IS NOT NULL
+                )
+            "#,
+            &tx_hashes as &[&[u8]]
+        )
+        .instrument("get_transaction_deadlines")
+        .with_arg("tx_hashes.len", &tx_hashes.len())
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let deadline = TransactionDeadline {
+                    timestamp: row.valid_until_timestamp.map(|timestamp| timestamp as u64),
+                    block_number: row
+                        .valid_until_miniblock
+                        .map(|number| MiniblockNumber(number as u32)),
+                };
+                (H256::from_slice(&row.hash), deadline)
+            })
+            .collect())
+    }
+
     pub async fn reset_mempool(&mut self) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
@@ -1288,6 +1539,39 @@ impl TransactionsDal<'_, '_> {
         }
     }
 
+    /// Returns hashes, execution statuses and refunded gas for all transactions in the specified L1 batch,
+    /// ordered by their index in the batch.
+    pub async fn get_execution_outcomes_for_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> sqlx::Result<Vec<(H256, TxExecutionStatus, u32)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                error,
+                refunded_gas
+            FROM
+                transactions
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                l1_batch_tx_index
+            "#,
+            l1_batch_number.0 as i64
+        )
+        .instrument("get_execution_outcomes_for_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let outcomes = rows.into_iter().map(|row| {
+            let status = TxExecutionStatus::from_has_failed(row.error.is_some());
+            (H256::from_slice(&row.hash), status, row.refunded_gas as u32)
+        });
+        Ok(outcomes.collect())
+    }
+
     pub async fn get_call_trace(&mut self, tx_hash: H256) -> sqlx::Result<Option<Call>> {
         Ok(sqlx::query_as!(
             CallTrace,
@@ -1328,7 +1612,10 @@ impl TransactionsDal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::ProtocolVersion;
+    use std::collections::HashSet;
+
+    use assert_matches::assert_matches;
+    use zksync_types::{api, ProtocolVersion};
 
     use super::*;
     use crate::{
@@ -1373,4 +1660,93 @@ mod tests {
             .expect("no call trace");
         assert_eq!(call_trace, expected_call_trace);
     }
+
+    #[tokio::test]
+    async fn expiring_transactions() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+
+        let txs: Vec<_> = (0..4).map(|_| mock_l2_transaction()).collect();
+        let tx_hashes: Vec<_> = txs.iter().map(L2Tx::hash).collect();
+        for tx in txs {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await;
+        }
+        let deadlines = [
+            TransactionDeadline {
+                timestamp: Some(100),
+                block_number: None,
+            },
+            TransactionDeadline {
+                timestamp: None,
+                block_number: Some(MiniblockNumber(1)),
+            },
+            TransactionDeadline {
+                timestamp: Some(100),
+                block_number: Some(MiniblockNumber(2)),
+            },
+        ];
+        for (&tx_hash, deadline) in tx_hashes.iter().zip(&deadlines) {
+            conn.transactions_dal()
+                .set_transaction_deadline(tx_hash, deadline)
+                .await
+                .unwrap();
+        }
+
+        let loaded_deadlines = conn
+            .transactions_dal()
+            .get_transaction_deadlines(&tx_hashes)
+            .await
+            .unwrap();
+        let expected_deadlines: HashMap<_, _> = tx_hashes.iter().copied().zip(deadlines).collect();
+        assert_eq!(loaded_deadlines, expected_deadlines);
+
+        // The miniblock deadline of the second transaction has passed since miniblock #1 is sealed.
+        let expired = conn
+            .transactions_dal()
+            .expire_transactions(100)
+            .await
+            .unwrap();
+        assert_eq!(expired, [tx_hashes[1]]);
+        let expired: HashSet<_> = conn
+            .transactions_dal()
+            .expire_transactions(101)
+            .await
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(expired, HashSet::from([tx_hashes[0], tx_hashes[2]]));
+
+        let details = conn
+            .transactions_web3_dal()
+            .get_transaction_details(tx_hashes[0])
+            .await
+            .unwrap()
+            .expect("no transaction details");
+        assert_matches!(details.status, api::TransactionStatus::Expired);
+        let details = conn
+            .transactions_web3_dal()
+            .get_transaction_details(tx_hashes[3])
+            .await
+            .unwrap()
+            .expect("no transaction details");
+        assert_matches!(details.status, api::TransactionStatus::Pending);
+
+        // Expired transactions must not be loaded to the mempool.
+        let mempool_txs = conn
+            .transactions_dal()
+            .sync_mempool(&[], &[], 0, 0, 1000)
+            .await
+            .unwrap();
+        let mempool_tx_hashes: Vec<_> = mempool_txs.iter().map(Transaction::hash).collect();
+        assert_eq!(mempool_tx_hashes, [tx_hashes[3]]);
+    }
 }
@@ -1,6 +1,6 @@
 use sqlx::types::chrono::NaiveDateTime;
 use zksync_types::{
-    api, api::TransactionReceipt, Address, L2ChainId, MiniblockNumber, Transaction,
+    api, api::TransactionReceipt, Address, L2ChainId, MiniblockNumber, PriorityOpId, Transaction,
     ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
 
@@ -9,8 +9,8 @@ use crate::{
     models::{
         storage_block::{bind_block_where_sql_params, web3_block_where_sql},
         storage_transaction::{
-            extract_web3_transaction, web3_transaction_select_sql, StorageTransaction,
-            StorageTransactionDetails, StorageTransactionReceipt,
+            extract_web3_transaction, web3_transaction_select_sql, StoragePriorityOpStatus,
+            StorageTransaction, StorageTransactionDetails, StorageTransactionReceipt,
         },
     },
     SqlxError, StorageProcessor,
@@ -82,19 +82,102 @@ impl TransactionsWeb3Dal<'_, '_> {
         .map(Into::into)
         .collect();
 
+        self.fill_receipt_logs(&mut receipts).await?;
+        Ok(receipts)
+    }
+
+    /// Returns receipts for all transactions in the specified miniblock, ordered by the transaction index
+    /// in the block. Unlike [`Self::get_transaction_receipts()`], doesn't require transaction hashes to be known
+    /// in advance.
+    pub async fn get_block_receipts(
+        &mut self,
+        block_number: MiniblockNumber,
+    ) -> Result<Vec<TransactionReceipt>, SqlxError> {
+        let mut receipts: Vec<TransactionReceipt> = sqlx::query_as!(
+            StorageTransactionReceipt,
+            r#"
+            WITH
+                sl AS (
+                    SELECT DISTINCT
+                        ON (storage_logs.tx_hash) *
+                    FROM
+                        storage_logs
+                    WHERE
+                        storage_logs.address = $1
+                        AND storage_logs.miniblock_number = $3
+                    ORDER BY
+                        storage_logs.tx_hash,
+                        storage_logs.miniblock_number DESC,
+                        storage_logs.operation_number DESC
+                )
+            SELECT
+                transactions.hash AS tx_hash,
+                transactions.index_in_block AS index_in_block,
+                transactions.l1_batch_tx_index AS l1_batch_tx_index,
+                transactions.miniblock_number AS "block_number!",
+                transactions.error AS error,
+                transactions.effective_gas_price AS effective_gas_price,
+                transactions.initiator_address AS initiator_address,
+                transactions.data -> 'to' AS "transfer_to?",
+                transactions.data -> 'contractAddress' AS "execute_contract_address?",
+                transactions.tx_format AS "tx_format?",
+                transactions.refunded_gas AS refunded_gas,
+                transactions.gas_limit AS gas_limit,
+                miniblocks.hash AS "block_hash",
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                sl.key AS "contract_address?"
+            FROM
+                transactions
+                JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+                LEFT JOIN sl ON sl.value != $2
+                AND sl.tx_hash = transactions.hash
+            WHERE
+                transactions.miniblock_number = $3
+            ORDER BY
+                transactions.index_in_block
+            "#,
+            ACCOUNT_CODE_STORAGE_ADDRESS.as_bytes(),
+            FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH.as_bytes(),
+            i64::from(block_number.0)
+        )
+        .instrument("get_block_receipts")
+        .with_arg("block_number", &block_number)
+        .fetch_all(self.storage)
+        .await?
+        .into_iter()
+        .map(Into::into)
+        .collect();
+
+        self.fill_receipt_logs(&mut receipts).await?;
+        Ok(receipts)
+    }
+
+    /// Fills event logs and L2-to-L1 logs for the provided receipts using a constant number of queries.
+    async fn fill_receipt_logs(
+        &mut self,
+        receipts: &mut [TransactionReceipt],
+    ) -> Result<(), SqlxError> {
+        if receipts.is_empty() {
+            return Ok(());
+        }
+        let hashes: Vec<_> = receipts
+            .iter()
+            .map(|receipt| receipt.transaction_hash)
+            .collect();
+
         let mut logs = self
             .storage
             .events_dal()
-            .get_logs_by_tx_hashes(hashes)
+            .get_logs_by_tx_hashes(&hashes)
             .await?;
 
         let mut l2_to_l1_logs = self
             .storage
             .events_dal()
-            .get_l2_to_l1_logs_by_hashes(hashes)
+            .get_l2_to_l1_logs_by_hashes(&hashes)
             .await?;
 
-        for receipt in &mut receipts {
+        for receipt in receipts {
             let logs_for_tx = logs.remove(&receipt.transaction_hash);
 
             if let Some(logs) = logs_for_tx {
@@ -120,8 +203,7 @@ impl TransactionsWeb3Dal<'_, '_> {
                     .collect();
             }
         }
-
-        Ok(receipts)
+        Ok(())
     }
 
     pub async fn get_transaction(
@@ -184,6 +266,8 @@ impl TransactionsWeb3Dal<'_, '_> {
                     transactions.error,
                     transactions.effective_gas_price,
                     transactions.refunded_gas,
+                    transactions.revert_reason,
+                    transactions.expired_at,
                     commit_tx.tx_hash AS "eth_commit_tx_hash?",
                     prove_tx.tx_hash AS "eth_prove_tx_hash?",
                     execute_tx.tx_hash AS "eth_execute_tx_hash?"
@@ -219,6 +303,68 @@ impl TransactionsWeb3Dal<'_, '_> {
         }
     }
 
+    /// Returns the status of a priority operation with the specified ID, or `None` if the operation is unknown.
+    pub async fn get_priority_op_status(
+        &mut self,
+        id: PriorityOpId,
+    ) -> Result<Option<api::PriorityOpStatus>, SqlxError> {
+        let storage_op = sqlx::query_as!(
+            StoragePriorityOpStatus,
+            r#"
+            SELECT
+                transactions.priority_op_id AS "priority_op_id!",
+                transactions.hash,
+                transactions.l1_tx_hash,
+                transactions.l1_block_number,
+                transactions.miniblock_number,
+                transactions.l1_batch_number,
+                transactions.error,
+                execute_tx.tx_hash AS "eth_execute_tx_hash?",
+                CASE
+                    WHEN transactions.miniblock_number IS NULL THEN (
+                        -- Queued operations are few, so the backward index scan terminates quickly.
+                        SELECT
+                            executed_txs.priority_op_id
+                        FROM
+                            transactions AS executed_txs
+                        WHERE
+                            executed_txs.priority_op_id IS NOT NULL
+                            AND executed_txs.miniblock_number IS NOT NULL
+                        ORDER BY
+                            executed_txs.priority_op_id DESC
+                        LIMIT
+                            1
+                    )
+                END AS last_executed_priority_op_id,
+                CASE
+                    WHEN transactions.miniblock_number IS NOT NULL
+                    AND transactions.l1_batch_number IS NULL THEN (
+                        SELECT
+                            MAX(number)
+                        FROM
+                            l1_batches
+                    )
+                END AS last_sealed_l1_batch_number
+            FROM
+                transactions
+                LEFT JOIN l1_batches ON l1_batches.number = transactions.l1_batch_number
+                LEFT JOIN eth_txs_history AS execute_tx ON (
+                    l1_batches.eth_execute_tx_id = execute_tx.eth_tx_id
+                    AND execute_tx.confirmed_at IS NOT NULL
+                )
+            WHERE
+                transactions.priority_op_id = $1
+            "#,
+            id.0 as i64
+        )
+        .instrument("get_priority_op_status")
+        .with_arg("id", &id)
+        .fetch_optional(self.storage)
+        .await?;
+
+        Ok(storage_op.map(Into::into))
+    }
+
     /// Returns hashes of txs which were received after `from_timestamp` and the time of receiving the last tx.
     pub async fn get_pending_txs_hashes_after(
         &mut self,
@@ -335,14 +481,21 @@ impl TransactionsWeb3Dal<'_, '_> {
 mod tests {
     use std::collections::HashMap;
 
+    use assert_matches::assert_matches;
     use zksync_types::{
-        block::MiniblockHasher, fee::TransactionExecutionMetrics, l2::L2Tx, Nonce, ProtocolVersion,
+        block::MiniblockHasher,
+        fee::TransactionExecutionMetrics,
+        l2::L2Tx,
+        tx::{tx_execution_info::TxExecutionStatus, TransactionExecutionResult},
+        ExecuteTransactionCommon, L1BatchNumber, L1BlockNumber, Nonce, ProtocolVersion,
         ProtocolVersionId,
     };
 
     use super::*;
     use crate::{
-        tests::{create_miniblock_header, mock_execution_result, mock_l2_transaction},
+        tests::{
+            create_miniblock_header, mock_execution_result, mock_l1_execute, mock_l2_transaction,
+        },
         ConnectionPool,
     };
 
@@ -467,6 +620,157 @@ mod tests {
         assert_eq!(receipts.len(), 2);
         assert_eq!(receipts[0].transaction_hash, tx1_hash);
         assert_eq!(receipts[1].transaction_hash, tx2_hash);
+
+        let block_receipts = conn
+            .transactions_web3_dal()
+            .get_block_receipts(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(block_receipts, receipts);
+
+        let block_receipts = conn
+            .transactions_web3_dal()
+            .get_block_receipts(MiniblockNumber(2))
+            .await
+            .unwrap();
+        assert!(block_receipts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn getting_transaction_details_with_revert_reason() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+        let successful_tx = mock_l2_transaction();
+        let successful_tx_hash = successful_tx.hash();
+        let failed_tx = mock_l2_transaction();
+        let failed_tx_hash = failed_tx.hash();
+        for tx in [&successful_tx, &failed_tx] {
+            conn.transactions_dal()
+                .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+                .await;
+        }
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+
+        let revert_reason = api::RevertReason::Error {
+            message: "insufficient balance".to_owned(),
+        };
+        let failed_tx_result = TransactionExecutionResult {
+            execution_status: TxExecutionStatus::Failure,
+            decoded_revert_reason: Some(revert_reason.clone()),
+            ..mock_execution_result(failed_tx)
+        };
+        let tx_results = [mock_execution_result(successful_tx), failed_tx_result];
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &tx_results, U256::from(1))
+            .await;
+
+        let details = conn
+            .transactions_web3_dal()
+            .get_transaction_details(successful_tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_matches!(details.status, api::TransactionStatus::Included);
+        assert_eq!(details.revert_reason, None);
+
+        let details = conn
+            .transactions_web3_dal()
+            .get_transaction_details(failed_tx_hash)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_matches!(details.status, api::TransactionStatus::Failed);
+        assert_eq!(details.revert_reason, Some(revert_reason));
+    }
+
+    #[tokio::test]
+    async fn getting_priority_op_status() {
+        let connection_pool = ConnectionPool::test_pool().await;
+        let mut conn = connection_pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let l1_txs: Vec<_> = (0..2)
+            .map(|serial_id| {
+                let mut tx = mock_l1_execute();
+                tx.common_data.serial_id = PriorityOpId(serial_id);
+                tx.common_data.canonical_tx_hash = H256::from_low_u64_be(serial_id + 1);
+                tx
+            })
+            .collect();
+        for tx in &l1_txs {
+            conn.transactions_dal()
+                .insert_transaction_l1(tx.clone(), L1BlockNumber(1))
+                .await;
+        }
+        conn.blocks_dal()
+            .insert_miniblock(&create_miniblock_header(1))
+            .await
+            .unwrap();
+        let executed_tx = &l1_txs[0];
+        let tx_result = TransactionExecutionResult {
+            hash: executed_tx.hash(),
+            transaction: executed_tx.clone().into(),
+            ..mock_execution_result(mock_l2_transaction())
+        };
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_miniblock(MiniblockNumber(1), &[tx_result], U256::from(1))
+            .await;
+
+        let status = conn
+            .transactions_web3_dal()
+            .get_priority_op_status(PriorityOpId(0))
+            .await
+            .unwrap()
+            .expect("no status for executed op");
+        assert_matches!(status.status, api::TransactionStatus::Included);
+        assert_eq!(status.l1_tx_hash, Some(executed_tx.common_data.eth_hash));
+        assert_eq!(status.l1_block_number, L1BlockNumber(1));
+        assert_eq!(status.l2_tx_hash, executed_tx.hash());
+        assert_eq!(status.queue_position, None);
+        assert_eq!(status.miniblock_number, Some(MiniblockNumber(1)));
+        assert_eq!(status.l1_batch_number, None);
+        assert_eq!(status.estimated_l1_batch_number, Some(L1BatchNumber(0)));
+        assert_eq!(status.l2_receipt_hash, Some(executed_tx.hash()));
+
+        // The L1 transaction hash must not leak into loaded transactions (e.g., consensus payloads).
+        let raw_txs = conn
+            .transactions_web3_dal()
+            .get_raw_miniblock_transactions(MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(raw_txs.len(), 1);
+        let ExecuteTransactionCommon::L1(common_data) = &raw_txs[0].common_data else {
+            panic!("unexpected raw transaction: {:?}", raw_txs[0]);
+        };
+        assert_eq!(common_data.eth_hash, H256::zero());
+
+        let status = conn
+            .transactions_web3_dal()
+            .get_priority_op_status(PriorityOpId(1))
+            .await
+            .unwrap()
+            .expect("no status for queued op");
+        assert_matches!(status.status, api::TransactionStatus::Pending);
+        assert_eq!(status.l1_tx_hash, Some(l1_txs[1].common_data.eth_hash));
+        assert_eq!(status.queue_position, Some(0));
+        assert_eq!(status.miniblock_number, None);
+        assert_eq!(status.estimated_l1_batch_number, None);
+        assert_eq!(status.l2_receipt_hash, None);
+
+        let status = conn
+            .transactions_web3_dal()
+            .get_priority_op_status(PriorityOpId(2))
+            .await
+            .unwrap();
+        assert!(status.is_none());
     }
 
     #[tokio::test]
@@ -0,0 +1,342 @@
+use std::collections::BTreeMap;
+
+use sqlx::types::chrono::{Duration, NaiveDate};
+use zksync_types::{api::UtilizationReport, L1BatchNumber};
+
+use crate::{instrument::InstrumentExt, StorageProcessor};
+
+/// DAL for daily reports on the capacity utilization of sealed L1 batches.
+#[derive(Debug)]
+pub struct UtilizationReportsDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Returns the UNIX timestamp range (in seconds) for the specified UTC date.
+pub fn date_timestamp_range(date: NaiveDate) -> (i64, i64) {
+    let start = date
+        .and_hms_opt(0, 0, 0)
+        .expect("midnight is a valid time")
+        .timestamp();
+    (start, start + Duration::days(1).num_seconds())
+}
+
+impl UtilizationReportsDal<'_, '_> {
+    /// Computes a utilization report for L1 batches with timestamps within the specified UTC date.
+    /// The report is final only if an L1 batch with a timestamp after the date is sealed
+    /// (see [`Self::get_latest_l1_batch_timestamp()`]).
+    pub async fn compute_report(&mut self, date: NaiveDate) -> sqlx::Result<UtilizationReport> {
+        let (start_timestamp, end_timestamp) = date_timestamp_range(date);
+        let mut transaction = self.storage.start_transaction().await?;
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "l1_batch_count!",
+                MIN(number) AS first_l1_batch_number,
+                MAX(number) AS last_l1_batch_number,
+                AVG((fullness ->> 'gas')::DOUBLE PRECISION) AS avg_gas_utilization,
+                AVG((fullness ->> 'pub_data_size')::DOUBLE PRECISION) AS avg_pubdata_utilization,
+                AVG((fullness ->> 'circuits')::DOUBLE PRECISION) AS avg_circuits_utilization
+            FROM
+                l1_batches
+            WHERE
+                timestamp >= $1
+                AND timestamp < $2
+            "#,
+            start_timestamp,
+            end_timestamp
+        )
+        .instrument("compute_utilization_report")
+        .with_arg("date", &date)
+        .report_latency()
+        .fetch_one(&mut transaction)
+        .await?;
+
+        let seal_criteria = sqlx::query!(
+            r#"
+            SELECT
+                COALESCE(seal_criterion, 'unknown') AS "seal_criterion!",
+                COUNT(*) AS "count!"
+            FROM
+                l1_batches
+            WHERE
+                timestamp >= $1
+                AND timestamp < $2
+            GROUP BY
+                COALESCE(seal_criterion, 'unknown')
+            "#,
+            start_timestamp,
+            end_timestamp
+        )
+        .instrument("compute_utilization_report#seal_criteria")
+        .with_arg("date", &date)
+        .report_latency()
+        .fetch_all(&mut transaction)
+        .await?;
+        transaction.commit().await?;
+
+        Ok(UtilizationReport {
+            date,
+            first_l1_batch_number: row
+                .first_l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            last_l1_batch_number: row
+                .last_l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            l1_batch_count: row.l1_batch_count as u64,
+            avg_gas_utilization: row.avg_gas_utilization,
+            avg_pubdata_utilization: row.avg_pubdata_utilization,
+            avg_circuits_utilization: row.avg_circuits_utilization,
+            seal_criteria: seal_criteria
+                .into_iter()
+                .map(|row| (row.seal_criterion, row.count as u64))
+                .collect(),
+        })
+    }
+
+    /// Inserts a utilization report, overwriting the existing report for the same date.
+    pub async fn insert_report(&mut self, report: &UtilizationReport) -> sqlx::Result<()> {
+        let seal_criteria = serde_json::to_value(&report.seal_criteria)
+            .expect("failed to serialize seal criteria to JSON value");
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                l1_batch_utilization_reports (
+                    report_date,
+                    first_l1_batch_number,
+                    last_l1_batch_number,
+                    l1_batch_count,
+                    avg_gas_utilization,
+                    avg_pubdata_utilization,
+                    avg_circuits_utilization,
+                    seal_criteria,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, $4, $5, $6, $7, $8, NOW(), NOW())
+            ON CONFLICT (report_date) DO
+            UPDATE
+            SET
+                first_l1_batch_number = excluded.first_l1_batch_number,
+                last_l1_batch_number = excluded.last_l1_batch_number,
+                l1_batch_count = excluded.l1_batch_count,
+                avg_gas_utilization = excluded.avg_gas_utilization,
+                avg_pubdata_utilization = excluded.avg_pubdata_utilization,
+                avg_circuits_utilization = excluded.avg_circuits_utilization,
+                seal_criteria = excluded.seal_criteria,
+                updated_at = NOW()
+            "#,
+            report.date,
+            report
+                .first_l1_batch_number
+                .map(|number| i64::from(number.0)),
+            report
+                .last_l1_batch_number
+                .map(|number| i64::from(number.0)),
+            report.l1_batch_count as i64,
+            report.avg_gas_utilization,
+            report.avg_pubdata_utilization,
+            report.avg_circuits_utilization,
+            seal_criteria
+        )
+        .instrument("insert_utilization_report")
+        .with_arg("date", &report.date)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_report(&mut self, date: NaiveDate) -> sqlx::Result<Option<UtilizationReport>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                first_l1_batch_number,
+                last_l1_batch_number,
+                l1_batch_count,
+                avg_gas_utilization,
+                avg_pubdata_utilization,
+                avg_circuits_utilization,
+                seal_criteria
+            FROM
+                l1_batch_utilization_reports
+            WHERE
+                report_date = $1
+            "#,
+            date
+        )
+        .instrument("get_utilization_report")
+        .with_arg("date", &date)
+        .fetch_optional(self.storage)
+        .await?
+        else {
+            return Ok(None);
+        };
+
+        let seal_criteria: BTreeMap<String, u64> = serde_json::from_value(row.seal_criteria)
+            .map_err(|err| sqlx::Error::Decode(err.into()))?;
+        Ok(Some(UtilizationReport {
+            date,
+            first_l1_batch_number: row
+                .first_l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            last_l1_batch_number: row
+                .last_l1_batch_number
+                .map(|number| L1BatchNumber(number as u32)),
+            l1_batch_count: row.l1_batch_count as u64,
+            avg_gas_utilization: row.avg_gas_utilization,
+            avg_pubdata_utilization: row.avg_pubdata_utilization,
+            avg_circuits_utilization: row.avg_circuits_utilization,
+            seal_criteria,
+        }))
+    }
+
+    /// Returns the timestamp of the latest sealed L1 batch. Since L1 batch timestamps are monotonic, all L1 batches
+    /// with lesser timestamps are guaranteed to be sealed.
+    pub async fn get_latest_l1_batch_timestamp(&mut self) -> sqlx::Result<Option<i64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                timestamp
+            FROM
+                l1_batches
+            ORDER BY
+                number DESC
+            LIMIT
+                1
+            "#
+        )
+        .instrument("get_latest_l1_batch_timestamp")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| row.timestamp))
+    }
+
+    /// Returns the date of the latest generated report.
+    pub async fn get_latest_report_date(&mut self) -> sqlx::Result<Option<NaiveDate>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(report_date) AS report_date
+            FROM
+                l1_batch_utilization_reports
+            "#
+        )
+        .instrument("get_latest_utilization_report_date")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.report_date)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{
+        block::{BlockGasCount, L1BatchHeader},
+        circuit::CircuitStatistic,
+        ProtocolVersion, ProtocolVersionId,
+    };
+
+    use super::*;
+    use crate::ConnectionPool;
+
+    async fn insert_l1_batch(
+        conn: &mut StorageProcessor<'_>,
+        number: u32,
+        timestamp: i64,
+        seal_criterion: Option<&str>,
+        fullness: &[(&str, f64)],
+    ) {
+        let header = L1BatchHeader::new(
+            L1BatchNumber(number),
+            timestamp as u64,
+            Default::default(),
+            ProtocolVersionId::default(),
+        );
+        conn.blocks_dal()
+            .insert_l1_batch(
+                &header,
+                &[],
+                BlockGasCount::default(),
+                &[],
+                &[],
+                CircuitStatistic::default(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .set_l1_batch_seal_stats(L1BatchNumber(number), seal_criterion, fullness)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn generating_utilization_reports() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(ProtocolVersion::default())
+            .await;
+
+        let date = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let (start_timestamp, end_timestamp) = date_timestamp_range(date);
+        insert_l1_batch(
+            &mut conn,
+            1,
+            start_timestamp - 1,
+            Some("gas"),
+            &[("gas", 1.0)],
+        )
+        .await;
+        insert_l1_batch(
+            &mut conn,
+            2,
+            start_timestamp,
+            Some("pub_data_size"),
+            &[("gas", 0.2), ("pub_data_size", 0.9), ("circuits", 0.5)],
+        )
+        .await;
+        insert_l1_batch(
+            &mut conn,
+            3,
+            start_timestamp + 100,
+            Some("pub_data_size"),
+            &[("gas", 0.4), ("pub_data_size", 1.0), ("circuits", 0.3)],
+        )
+        .await;
+        insert_l1_batch(&mut conn, 4, end_timestamp - 1, None, &[]).await;
+        insert_l1_batch(&mut conn, 5, end_timestamp, Some("gas"), &[]).await;
+
+        let dal = &mut conn.utilization_reports_dal();
+        assert_eq!(
+            dal.get_latest_l1_batch_timestamp().await.unwrap(),
+            Some(end_timestamp)
+        );
+        assert_eq!(dal.get_latest_report_date().await.unwrap(), None);
+        let report = dal.compute_report(date).await.unwrap();
+        assert_eq!(report.first_l1_batch_number, Some(L1BatchNumber(2)));
+        assert_eq!(report.last_l1_batch_number, Some(L1BatchNumber(4)));
+        assert_eq!(report.l1_batch_count, 3);
+        assert!((report.avg_gas_utilization.unwrap() - 0.3).abs() < 1e-9);
+        assert!((report.avg_pubdata_utilization.unwrap() - 0.95).abs() < 1e-9);
+        assert!((report.avg_circuits_utilization.unwrap() - 0.4).abs() < 1e-9);
+        let expected_criteria =
+            BTreeMap::from([("pub_data_size".to_owned(), 2), ("unknown".to_owned(), 1)]);
+        assert_eq!(report.seal_criteria, expected_criteria);
+
+        dal.insert_report(&report).await.unwrap();
+        assert_eq!(dal.get_latest_report_date().await.unwrap(), Some(date));
+        assert_eq!(dal.get_report(date).await.unwrap(), Some(report.clone()));
+        // Reports should be idempotently overwritten.
+        dal.insert_report(&report).await.unwrap();
+        assert_eq!(dal.get_report(date).await.unwrap(), Some(report));
+
+        let empty_date = date.pred_opt().unwrap().pred_opt().unwrap();
+        let empty_report = dal.compute_report(empty_date).await.unwrap();
+        assert_eq!(empty_report.l1_batch_count, 0);
+        assert_eq!(empty_report.first_l1_batch_number, None);
+        assert_eq!(empty_report.avg_gas_utilization, None);
+        assert!(empty_report.seal_criteria.is_empty());
+        assert_eq!(dal.get_report(empty_date).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,250 @@
+use zksync_types::{api::ValidationAllowListEntry, Address, H256};
+
+use crate::StorageProcessor;
+
+/// DAL for the allow-list of storage slots and addresses trusted when validating transactions.
+#[derive(Debug)]
+pub struct ValidationAllowListDal<'a, 'c> {
+    pub(crate) storage: &'a mut StorageProcessor<'c>,
+}
+
+/// Version of the validation allow-list, which changes whenever an entry is inserted or removed.
+///
+/// Since entry IDs are never reused, an inserted entry always increases the max ID, and removing entries
+/// reduces their count; thus, different allow-list contents cannot have the same version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValidationAllowListVersion {
+    entry_count: i64,
+    max_id: Option<i32>,
+}
+
+fn entry_to_row(entry: &ValidationAllowListEntry) -> (&'static str, Option<Address>, Option<H256>) {
+    match *entry {
+        ValidationAllowListEntry::Slot { address, slot } => ("slot", address, Some(slot)),
+        ValidationAllowListEntry::Address { address } => ("address", Some(address), None),
+        ValidationAllowListEntry::AddressSlot { address, slot } => {
+            ("address_slot", address, Some(slot))
+        }
+    }
+}
+
+impl ValidationAllowListDal<'_, '_> {
+    /// Returns the current version of the allow-list. This is cheaper than loading all entries,
+    /// so it can be used to check whether the cached allow-list is up to date.
+    pub async fn get_version(&mut self) -> sqlx::Result<ValidationAllowListVersion> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "entry_count!",
+                MAX(id) AS "max_id"
+            FROM
+                validation_allow_list
+            "#
+        )
+        .fetch_one(self.storage.conn())
+        .await?;
+        Ok(ValidationAllowListVersion {
+            entry_count: row.entry_count,
+            max_id: row.max_id,
+        })
+    }
+
+    pub async fn get_entries(&mut self) -> sqlx::Result<Vec<ValidationAllowListEntry>> {
+        Ok(self.get_entries_with_version().await?.0)
+    }
+
+    /// Returns all allow-list entries together with the allow-list version. Unlike calling [`Self::get_entries()`]
+    /// and [`Self::get_version()`] separately, the returned version is guaranteed to correspond to the entries.
+    pub async fn get_entries_with_version(
+        &mut self,
+    ) -> sqlx::Result<(Vec<ValidationAllowListEntry>, ValidationAllowListVersion)> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                id,
+                kind,
+                address,
+                slot
+            FROM
+                validation_allow_list
+            ORDER BY
+                id
+            "#
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        let version = ValidationAllowListVersion {
+            entry_count: rows.len() as i64,
+            // Rows are ordered by ID.
+            max_id: rows.last().map(|row| row.id),
+        };
+        let entries = rows
+            .into_iter()
+            .map(|row| {
+                let address = row.address.as_deref().map(Address::from_slice);
+                let slot = row.slot.as_deref().map(H256::from_slice);
+                // The invariants below are enforced by the table constraints.
+                match row.kind.as_str() {
+                    "slot" => ValidationAllowListEntry::Slot {
+                        address,
+                        slot: slot.expect("slot entry without slot"),
+                    },
+                    "address" => ValidationAllowListEntry::Address {
+                        address: address.expect("address entry without address"),
+                    },
+                    "address_slot" => ValidationAllowListEntry::AddressSlot {
+                        address,
+                        slot: slot.expect("address slot entry without slot"),
+                    },
+                    other => panic!("unknown validation allow-list entry kind: {other}"),
+                }
+            })
+            .collect();
+        Ok((entries, version))
+    }
+
+    /// Inserts an entry into the allow-list. Returns `false` if the entry is already present.
+    pub async fn insert_entry(&mut self, entry: &ValidationAllowListEntry) -> sqlx::Result<bool> {
+        let (kind, address, slot) = entry_to_row(entry);
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                validation_allow_list (kind, address, slot)
+            VALUES
+                ($1, $2, $3)
+            ON CONFLICT (kind, COALESCE(address, '\x'::BYTEA), COALESCE(slot, '\x'::BYTEA)) DO NOTHING
+            "#,
+            kind,
+            address.as_ref().map(Address::as_bytes),
+            slot.as_ref().map(H256::as_bytes)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Removes an entry from the allow-list. Returns `false` if the entry is not present.
+    pub async fn remove_entry(&mut self, entry: &ValidationAllowListEntry) -> sqlx::Result<bool> {
+        let (kind, address, slot) = entry_to_row(entry);
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM validation_allow_list
+            WHERE
+                kind = $1
+                AND address IS NOT DISTINCT FROM $2
+                AND slot IS NOT DISTINCT FROM $3
+            "#,
+            kind,
+            address.as_ref().map(Address::as_bytes),
+            slot.as_ref().map(H256::as_bytes)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ConnectionPool;
+
+    #[tokio::test]
+    async fn managing_validation_allow_list() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        // The allow-list is populated with well-known slots by migrations.
+        let default_entries = conn
+            .validation_allow_list_dal()
+            .get_entries()
+            .await
+            .unwrap();
+        assert!(!default_entries.is_empty());
+        assert!(default_entries.iter().any(|entry| matches!(
+            entry,
+            ValidationAllowListEntry::AddressSlot { address: None, .. }
+        )));
+        let default_version = conn
+            .validation_allow_list_dal()
+            .get_version()
+            .await
+            .unwrap();
+
+        let new_entries = [
+            ValidationAllowListEntry::Slot {
+                address: Some(Address::repeat_byte(1)),
+                slot: H256::repeat_byte(2),
+            },
+            ValidationAllowListEntry::Address {
+                address: Address::repeat_byte(3),
+            },
+            ValidationAllowListEntry::AddressSlot {
+                address: None,
+                slot: H256::repeat_byte(4),
+            },
+        ];
+        for entry in &new_entries {
+            let inserted = conn
+                .validation_allow_list_dal()
+                .insert_entry(entry)
+                .await
+                .unwrap();
+            assert!(inserted);
+            let inserted = conn
+                .validation_allow_list_dal()
+                .insert_entry(entry)
+                .await
+                .unwrap();
+            assert!(!inserted);
+        }
+
+        let entries = conn
+            .validation_allow_list_dal()
+            .get_entries()
+            .await
+            .unwrap();
+        assert_eq!(entries[..default_entries.len()], default_entries);
+        assert_eq!(entries[default_entries.len()..], new_entries);
+
+        let mut versions = vec![default_version];
+        let version = conn
+            .validation_allow_list_dal()
+            .get_version()
+            .await
+            .unwrap();
+        versions.push(version);
+        for entry in &new_entries {
+            let removed = conn
+                .validation_allow_list_dal()
+                .remove_entry(entry)
+                .await
+                .unwrap();
+            assert!(removed);
+            let version = conn
+                .validation_allow_list_dal()
+                .get_version()
+                .await
+                .unwrap();
+            versions.push(version);
+        }
+        // The version after removing all new entries is the same as the initial one since the contents are the same.
+        assert_eq!(versions.pop(), Some(default_version));
+        for (i, version) in versions.iter().enumerate() {
+            assert!(!versions[..i].contains(version), "{versions:?}");
+        }
+        let removed = conn
+            .validation_allow_list_dal()
+            .remove_entry(&new_entries[0])
+            .await
+            .unwrap();
+        assert!(!removed);
+        let (entries, version) = conn
+            .validation_allow_list_dal()
+            .get_entries_with_version()
+            .await
+            .unwrap();
+        assert_eq!(entries, default_entries);
+        assert_eq!(version, default_version);
+    }
+}
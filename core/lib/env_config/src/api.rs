@@ -50,8 +50,10 @@ impl FromEnv for MerkleTreeApiConfig {
 mod tests {
     use std::num::NonZeroU32;
 
+    use zksync_config::configs::api::{EthCallFastPathMethod, FunctionSelector, SponsoredCall};
+
     use super::*;
-    use crate::test_utils::{hash, EnvMutex};
+    use crate::test_utils::{addr, hash, EnvMutex};
 
     static MUTEX: EnvMutex = EnvMutex::new();
 
@@ -88,6 +90,54 @@ mod tests {
                 max_response_body_size_mb: Some(10),
                 websocket_requests_per_minute_limit: Some(NonZeroU32::new(10).unwrap()),
                 tree_api_url: None,
+                state_keeper_db_replica_path: Some("./db/api/state_keeper_replica".into()),
+                state_keeper_db_replica_max_lag: Some(50),
+                admin_namespace_enabled: true,
+                logs_denylisted_addresses: vec![addr("1111111111111111111111111111111111111111")],
+                logs_denylisted_topics: vec![
+                    hash("0x2222222222222222222222222222222222222222222222222222222222222222"),
+                    hash("0x3333333333333333333333333333333333333333333333333333333333333333"),
+                ],
+                estimate_gas_optimize_search: true,
+                streamed_methods: vec![
+                    "eth_getLogs".to_owned(),
+                    "debug_traceBlockByNumber".to_owned(),
+                ],
+                provisional_state_roots_enabled: true,
+                trace_namespace_enabled: true,
+                trace_filter_max_block_range: Some(50),
+                trace_filter_max_traces: Some(500),
+                eth_call_fast_path_methods: vec![
+                    EthCallFastPathMethod::BalanceOf,
+                    EthCallFastPathMethod::Allowance,
+                ],
+                eth_call_fast_path_token_code_hashes: vec![hash(
+                    "0x4444444444444444444444444444444444444444444444444444444444444444",
+                )],
+                rpc_gas_cap: Some(50_000_000),
+                rpc_privileged_gas_cap: Some(500_000_000),
+                rpc_privileged_api_key_hashes: vec![
+                    hash("0x5555555555555555555555555555555555555555555555555555555555555555"),
+                    hash("0x6666666666666666666666666666666666666666666666666666666666666666"),
+                ],
+                system_contracts_override_enabled: true,
+                sponsor_paymaster_addr: Some(addr("0x4444444444444444444444444444444444444444")),
+                sponsored_calls: vec![
+                    SponsoredCall {
+                        contract: addr("0x5555555555555555555555555555555555555555"),
+                        selector: None,
+                    },
+                    SponsoredCall {
+                        contract: addr("0x6666666666666666666666666666666666666666"),
+                        selector: Some(FunctionSelector([0xa9, 0x05, 0x9c, 0xbb])),
+                    },
+                ],
+                sponsorship_budget_gwei: Some(1_000_000_000),
+                sponsorship_daily_cap_gwei: Some(10_000_000),
+                estimate_gas_batch_max_size: Some(16),
+                get_proof_max_keys: Some(50),
+                simulate_bundle_max_size: Some(16),
+                estimate_gas_cache_size: Some(512),
             },
             contract_verification: ContractVerificationApiConfig {
                 port: 3070,
@@ -103,7 +153,10 @@ mod tests {
                 slow_time_limit_ms: Some(250),
                 hard_time_limit_ms: Some(2_000),
             },
-            merkle_tree: MerkleTreeApiConfig { port: 8082 },
+            merkle_tree: MerkleTreeApiConfig {
+                port: 8082,
+                grpc_port: Some(8083),
+            },
         }
     }
 
@@ -126,6 +179,26 @@ mod tests {
             API_WEB3_JSON_RPC_ACCOUNT_PKS="0x0000000000000000000000000000000000000000000000000000000000000001,0x0000000000000000000000000000000000000000000000000000000000000002"
             API_WEB3_JSON_RPC_ESTIMATE_GAS_SCALE_FACTOR=1.0
             API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
+            API_WEB3_JSON_RPC_ESTIMATE_GAS_OPTIMIZE_SEARCH=true
+            API_WEB3_JSON_RPC_STREAMED_METHODS="eth_getLogs,debug_traceBlockByNumber"
+            API_WEB3_JSON_RPC_PROVISIONAL_STATE_ROOTS_ENABLED=true
+            API_WEB3_JSON_RPC_TRACE_NAMESPACE_ENABLED=true
+            API_WEB3_JSON_RPC_TRACE_FILTER_MAX_BLOCK_RANGE=50
+            API_WEB3_JSON_RPC_TRACE_FILTER_MAX_TRACES=500
+            API_WEB3_JSON_RPC_ETH_CALL_FAST_PATH_METHODS="balance_of,allowance"
+            API_WEB3_JSON_RPC_ETH_CALL_FAST_PATH_TOKEN_CODE_HASHES="0x4444444444444444444444444444444444444444444444444444444444444444"
+            API_WEB3_JSON_RPC_RPC_GAS_CAP=50000000
+            API_WEB3_JSON_RPC_RPC_PRIVILEGED_GAS_CAP=500000000
+            API_WEB3_JSON_RPC_RPC_PRIVILEGED_API_KEY_HASHES="0x5555555555555555555555555555555555555555555555555555555555555555,0x6666666666666666666666666666666666666666666666666666666666666666"
+            API_WEB3_JSON_RPC_SYSTEM_CONTRACTS_OVERRIDE_ENABLED=true
+            API_WEB3_JSON_RPC_SPONSOR_PAYMASTER_ADDR="0x4444444444444444444444444444444444444444"
+            API_WEB3_JSON_RPC_SPONSORED_CALLS="0x5555555555555555555555555555555555555555,0x6666666666666666666666666666666666666666:0xa9059cbb"
+            API_WEB3_JSON_RPC_SPONSORSHIP_BUDGET_GWEI=1000000000
+            API_WEB3_JSON_RPC_SPONSORSHIP_DAILY_CAP_GWEI=10000000
+            API_WEB3_JSON_RPC_ESTIMATE_GAS_BATCH_MAX_SIZE=16
+            API_WEB3_JSON_RPC_GET_PROOF_MAX_KEYS=50
+            API_WEB3_JSON_RPC_SIMULATE_BUNDLE_MAX_SIZE=16
+            API_WEB3_JSON_RPC_ESTIMATE_GAS_CACHE_SIZE=512
             API_WEB3_JSON_RPC_L1_TO_L2_TRANSACTIONS_COMPATIBILITY_MODE=true
             API_WEB3_JSON_RPC_MAX_TX_SIZE=1000000
             API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT=512
@@ -135,6 +208,11 @@ mod tests {
             API_WEB3_JSON_RPC_FEE_HISTORY_LIMIT=100
             API_WEB3_JSON_RPC_MAX_BATCH_REQUEST_SIZE=200
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
+            API_WEB3_JSON_RPC_STATE_KEEPER_DB_REPLICA_PATH="./db/api/state_keeper_replica"
+            API_WEB3_JSON_RPC_STATE_KEEPER_DB_REPLICA_MAX_LAG=50
+            API_WEB3_JSON_RPC_ADMIN_NAMESPACE_ENABLED=true
+            API_WEB3_JSON_RPC_LOGS_DENYLISTED_ADDRESSES="0x1111111111111111111111111111111111111111"
+            API_WEB3_JSON_RPC_LOGS_DENYLISTED_TOPICS="0x2222222222222222222222222222222222222222222222222222222222222222,0x3333333333333333333333333333333333333333333333333333333333333333"
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_WEB3_JSON_RPC_MAX_RESPONSE_BODY_SIZE_MB=10
@@ -145,6 +223,7 @@ mod tests {
             API_HEALTHCHECK_SLOW_TIME_LIMIT_MS=250
             API_HEALTHCHECK_HARD_TIME_LIMIT_MS=2000
             API_MERKLE_TREE_PORT=8082
+            API_MERKLE_TREE_GRPC_PORT=8083
         "#;
         lock.set_env(config);
 
@@ -0,0 +1,45 @@
+use zksync_config::configs::BaseTokenFetcherConfig;
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for BaseTokenFetcherConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("base_token_fetcher", "BASE_TOKEN_FETCHER_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::configs::base_token_fetcher::BaseTokenPriceSource;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    fn expected_config() -> BaseTokenFetcherConfig {
+        BaseTokenFetcherConfig {
+            source: BaseTokenPriceSource::PriceApi,
+            price_api_url: Some("http://127.0.0.1:3030/price".to_owned()),
+            oracle_address: None,
+            fixed_numerator: None,
+            fixed_denominator: None,
+            poll_interval_ms: 10_000,
+            max_staleness_ms: 60_000,
+        }
+    }
+
+    #[test]
+    fn from_env() {
+        let config = r#"
+            BASE_TOKEN_FETCHER_SOURCE="PriceApi"
+            BASE_TOKEN_FETCHER_PRICE_API_URL="http://127.0.0.1:3030/price"
+            BASE_TOKEN_FETCHER_POLL_INTERVAL_MS="10000"
+            BASE_TOKEN_FETCHER_MAX_STALENESS_MS="60000"
+        "#;
+        let mut lock = MUTEX.lock();
+        lock.set_env(config);
+        let actual = BaseTokenFetcherConfig::from_env().unwrap();
+        assert_eq!(actual, expected_config());
+    }
+}
@@ -69,6 +69,7 @@ mod tests {
     fn expected_state_keeper_config() -> StateKeeperConfig {
         StateKeeperConfig {
             transaction_slots: 50,
+            dynamic_transaction_slots: true,
             block_commit_deadline_ms: 2500,
             miniblock_commit_deadline_ms: 1000,
             miniblock_seal_queue_capacity: 10,
@@ -94,6 +95,22 @@ mod tests {
             virtual_blocks_per_miniblock: 1,
             upload_witness_inputs_to_gcs: false,
             enum_index_migration_chunk_size: Some(2_000),
+            denylisted_tx_initiators: vec![
+                addr("1111111111111111111111111111111111111111"),
+                addr("2222222222222222222222222222222222222222"),
+            ],
+            max_tx_calldata_size: Some(100_000),
+            tx_execution_timeout_ms: Some(5_000),
+            l2_tx_intrinsic_gas_override: Some(20_000),
+            halt_on_l1_state_mismatch: true,
+            upgrade_shadow_execution_batches: Some(5),
+            miniblock_seal_wal_path: Some("./db/main/miniblock_seal_wal".to_owned()),
+            miniblock_seal_wal_max_miniblocks: Some(50),
+            fee_input_override_path: Some("./etc/env/fee_input_override.json".to_owned()),
+            fee_input_override_max_fair_l2_gas_price: Some(1_000_000_000),
+            fee_input_override_max_fair_pubdata_price: None,
+            max_witness_input_size_bytes: Some(4_294_967_296),
+            max_pubdata_per_batch_with_blobs: Some(200_000),
         }
     }
 
@@ -102,6 +119,7 @@ mod tests {
         let mut lock = MUTEX.lock();
         let config = r#"
             CHAIN_STATE_KEEPER_TRANSACTION_SLOTS="50"
+            CHAIN_STATE_KEEPER_DYNAMIC_TRANSACTION_SLOTS="true"
             CHAIN_STATE_KEEPER_FEE_ACCOUNT_ADDR="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
             CHAIN_STATE_KEEPER_MAX_SINGLE_TX_GAS="1000000"
             CHAIN_STATE_KEEPER_MAX_ALLOWED_L2_TX_GAS_LIMIT="2000000000"
@@ -127,6 +145,18 @@ mod tests {
             CHAIN_STATE_KEEPER_ENUM_INDEX_MIGRATION_CHUNK_SIZE="2000"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_PER_MINIBLOCK="1"
             CHAIN_STATE_KEEPER_VIRTUAL_BLOCKS_INTERVAL="1"
+            CHAIN_STATE_KEEPER_DENYLISTED_TX_INITIATORS="0x1111111111111111111111111111111111111111,0x2222222222222222222222222222222222222222"
+            CHAIN_STATE_KEEPER_MAX_TX_CALLDATA_SIZE="100000"
+            CHAIN_STATE_KEEPER_TX_EXECUTION_TIMEOUT_MS="5000"
+            CHAIN_STATE_KEEPER_L2_TX_INTRINSIC_GAS_OVERRIDE="20000"
+            CHAIN_STATE_KEEPER_HALT_ON_L1_STATE_MISMATCH="true"
+            CHAIN_STATE_KEEPER_UPGRADE_SHADOW_EXECUTION_BATCHES="5"
+            CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_WAL_PATH="./db/main/miniblock_seal_wal"
+            CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_WAL_MAX_MINIBLOCKS="50"
+            CHAIN_STATE_KEEPER_FEE_INPUT_OVERRIDE_PATH="./etc/env/fee_input_override.json"
+            CHAIN_STATE_KEEPER_FEE_INPUT_OVERRIDE_MAX_FAIR_L2_GAS_PRICE="1000000000"
+            CHAIN_STATE_KEEPER_MAX_WITNESS_INPUT_SIZE_BYTES="4294967296"
+            CHAIN_STATE_KEEPER_MAX_PUBDATA_PER_BATCH_WITH_BLOBS="200000"
         "#;
         lock.set_env(config);
 
@@ -160,6 +190,8 @@ mod tests {
             stuck_tx_timeout: 10,
             remove_stuck_txs: true,
             delay_interval: 100,
+            fee_priority_ordering: true,
+            fee_priority_max_wait_ms: Some(30_000),
         }
     }
 
@@ -173,6 +205,8 @@ mod tests {
             CHAIN_MEMPOOL_REMOVE_STUCK_TXS="true"
             CHAIN_MEMPOOL_DELAY_INTERVAL="100"
             CHAIN_MEMPOOL_CAPACITY="1000000"
+            CHAIN_MEMPOOL_FEE_PRIORITY_ORDERING="true"
+            CHAIN_MEMPOOL_FEE_PRIORITY_MAX_WAIT_MS="30000"
         "#;
         lock.set_env(config);
 
@@ -186,6 +220,8 @@ mod tests {
             http_req_max_retry_number: 5,
             http_req_retry_interval_sec: 2,
             replication_lag_limit_sec: Some(10),
+            l1_contracts_version_check: true,
+            l1_contracts_mismatch_degraded_mode: true,
         }
     }
 
@@ -197,6 +233,8 @@ mod tests {
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_MAX_RETRY_NUMBER="5"
             CHAIN_CIRCUIT_BREAKER_HTTP_REQ_RETRY_INTERVAL_SEC="2"
             CHAIN_CIRCUIT_BREAKER_REPLICATION_LAG_LIMIT_SEC="10"
+            CHAIN_CIRCUIT_BREAKER_L1_CONTRACTS_VERSION_CHECK="true"
+            CHAIN_CIRCUIT_BREAKER_L1_CONTRACTS_MISMATCH_DEGRADED_MODE="true"
         "#;
         lock.set_env(config);
 
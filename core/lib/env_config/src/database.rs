@@ -43,6 +43,16 @@ impl FromEnv for PostgresConfig {
         let long_connection_threshold_ms =
             parse_optional_var("DATABASE_LONG_CONNECTION_THRESHOLD_MS")?;
         let slow_query_threshold_ms = parse_optional_var("DATABASE_SLOW_QUERY_THRESHOLD_MS")?;
+        let read_replica_urls = env::var("DATABASE_READ_REPLICA_URLS")
+            .map(|urls| {
+                urls.split(',')
+                    .map(str::trim)
+                    .filter(|url| !url.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let max_replica_lag_sec = parse_optional_var("DATABASE_MAX_REPLICA_LAG_SEC")?;
 
         Ok(Self {
             master_url,
@@ -53,6 +63,8 @@ impl FromEnv for PostgresConfig {
             statement_timeout_sec,
             long_connection_threshold_ms,
             slow_query_threshold_ms,
+            read_replica_urls,
+            max_replica_lag_sec,
         })
     }
 }
@@ -79,6 +91,7 @@ mod tests {
             DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB=512
             DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC=60
             DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=50
+            DATABASE_MERKLE_TREE_MINIBLOCK_ROOT_HASHES_ENABLED=true
         "#;
         lock.set_env(config);
 
@@ -90,6 +103,7 @@ mod tests {
         assert_eq!(db_config.merkle_tree.max_l1_batches_per_iter, 50);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 512);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 60);
+        assert!(db_config.merkle_tree.miniblock_root_hashes_enabled);
     }
 
     #[test]
@@ -105,6 +119,7 @@ mod tests {
             "DATABASE_MERKLE_TREE_MEMTABLE_CAPACITY_MB",
             "DATABASE_MERKLE_TREE_STALLED_WRITES_TIMEOUT_SEC",
             "DATABASE_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+            "DATABASE_MERKLE_TREE_MINIBLOCK_ROOT_HASHES_ENABLED",
         ]);
 
         let db_config = DBConfig::from_env().unwrap();
@@ -116,6 +131,7 @@ mod tests {
         assert_eq!(db_config.merkle_tree.block_cache_size_mb, 128);
         assert_eq!(db_config.merkle_tree.memtable_capacity_mb, 256);
         assert_eq!(db_config.merkle_tree.stalled_writes_timeout_sec, 30);
+        assert!(!db_config.merkle_tree.miniblock_root_hashes_enabled);
 
         // Check that new env variable for Merkle tree path is supported
         lock.set_env("DATABASE_MERKLE_TREE_PATH=/db/tree/main");
@@ -145,6 +161,8 @@ mod tests {
             DATABASE_STATEMENT_TIMEOUT_SEC=300
             DATABASE_LONG_CONNECTION_THRESHOLD_MS=3000
             DATABASE_SLOW_QUERY_THRESHOLD_MS=150
+            DATABASE_READ_REPLICA_URLS="postgres://replica1/zksync_local, postgres://replica2/zksync_local"
+            DATABASE_MAX_REPLICA_LAG_SEC=3
         "#;
         lock.set_env(config);
 
@@ -170,5 +188,13 @@ mod tests {
             postgres_config.slow_query_threshold(),
             Some(Duration::from_millis(150))
         );
+        assert_eq!(
+            postgres_config.read_replica_urls,
+            [
+                "postgres://replica1/zksync_local",
+                "postgres://replica2/zksync_local"
+            ]
+        );
+        assert_eq!(postgres_config.max_replica_lag(), Duration::from_secs(3));
     }
 }
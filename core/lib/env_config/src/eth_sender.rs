@@ -1,5 +1,8 @@
 use anyhow::Context as _;
-use zksync_config::{configs::eth_sender::SenderConfig, ETHSenderConfig, GasAdjusterConfig};
+use zksync_config::{
+    configs::eth_sender::{RemoteSignerConfig, SenderConfig, TxRelayConfig},
+    ETHSenderConfig, GasAdjusterConfig,
+};
 
 use crate::{envy_load, FromEnv};
 
@@ -8,6 +11,16 @@ impl FromEnv for ETHSenderConfig {
         Ok(Self {
             sender: SenderConfig::from_env().context("SenderConfig")?,
             gas_adjuster: GasAdjusterConfig::from_env().context("GasAdjusterConfig")?,
+            remote_signer: if std::env::var("ETH_SENDER_REMOTE_SIGNER_URL").is_ok() {
+                Some(RemoteSignerConfig::from_env().context("RemoteSignerConfig")?)
+            } else {
+                None
+            },
+            tx_relay: if std::env::var("ETH_SENDER_TX_RELAY_URL").is_ok() {
+                Some(TxRelayConfig::from_env().context("TxRelayConfig")?)
+            } else {
+                None
+            },
         })
     }
 }
@@ -18,6 +31,18 @@ impl FromEnv for SenderConfig {
     }
 }
 
+impl FromEnv for RemoteSignerConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("eth_sender.remote_signer", "ETH_SENDER_REMOTE_SIGNER_")
+    }
+}
+
+impl FromEnv for TxRelayConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("eth_sender.tx_relay", "ETH_SENDER_TX_RELAY_")
+    }
+}
+
 impl FromEnv for GasAdjusterConfig {
     fn from_env() -> anyhow::Result<Self> {
         envy_load("eth_sender.gas_adjuster", "ETH_SENDER_GAS_ADJUSTER_")
@@ -31,7 +56,7 @@ mod tests {
     };
 
     use super::*;
-    use crate::test_utils::{hash, EnvMutex};
+    use crate::test_utils::{addr, hash, EnvMutex};
 
     static MUTEX: EnvMutex = EnvMutex::new();
 
@@ -57,6 +82,11 @@ mod tests {
                 max_acceptable_priority_fee_in_gwei: 100_000_000_000,
                 proof_loading_mode: ProofLoadingMode::OldProofFromDb,
                 pubdata_sending_mode: PubdataSendingMode::Calldata,
+                dynamic_aggregation_windows: true,
+                send_gate_max_base_fee_in_gwei: Some(100),
+                send_gate_max_blob_base_fee_in_gwei: None,
+                send_gate_max_delay_seconds: Some(3_600),
+                kzg_precomputation_workers: Some(2),
             },
             gas_adjuster: GasAdjusterConfig {
                 default_priority_fee_per_gas: 20000000000,
@@ -71,6 +101,8 @@ mod tests {
                 internal_pubdata_pricing_multiplier: 1.0,
                 max_blob_base_fee: None,
             },
+            remote_signer: None,
+            tx_relay: None,
         }
     }
 
@@ -107,6 +139,10 @@ mod tests {
             ETH_SENDER_SENDER_MAX_ACCEPTABLE_PRIORITY_FEE_IN_GWEI="100000000000"
             ETH_SENDER_SENDER_PROOF_LOADING_MODE="OldProofFromDb"
             ETH_SENDER_SENDER_PUBDATA_SENDING_MODE="Calldata"
+            ETH_SENDER_SENDER_DYNAMIC_AGGREGATION_WINDOWS="true"
+            ETH_SENDER_SENDER_SEND_GATE_MAX_BASE_FEE_IN_GWEI="100"
+            ETH_SENDER_SENDER_SEND_GATE_MAX_DELAY_SECONDS="3600"
+            ETH_SENDER_SENDER_KZG_PRECOMPUTATION_WORKERS="2"
         "#;
         lock.set_env(config);
 
@@ -117,4 +153,46 @@ mod tests {
             hash("27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be")
         );
     }
+
+    #[test]
+    fn from_env_with_remote_signer() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            ETH_SENDER_REMOTE_SIGNER_URL="http://127.0.0.1:8050/"
+            ETH_SENDER_REMOTE_SIGNER_OPERATOR_ADDRESS="0xde03a0B5963f75f1C8485B355fF6D30f3093BDE7"
+            ETH_SENDER_REMOTE_SIGNER_REQUEST_TIMEOUT_MS="1000"
+        "#;
+        lock.set_env(config);
+
+        let actual = RemoteSignerConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            RemoteSignerConfig {
+                url: "http://127.0.0.1:8050/".to_owned(),
+                operator_address: addr("de03a0B5963f75f1C8485B355fF6D30f3093BDE7"),
+                operator_blobs_address: None,
+                request_timeout_ms: 1_000,
+                max_retries: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn from_env_with_tx_relay() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            ETH_SENDER_TX_RELAY_URL="https://relay.example.com/"
+            ETH_SENDER_TX_RELAY_PUBLIC_FALLBACK_TIMEOUT_SEC="60"
+        "#;
+        lock.set_env(config);
+
+        let actual = TxRelayConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            TxRelayConfig {
+                url: "https://relay.example.com/".to_owned(),
+                public_fallback_timeout_sec: 60,
+            }
+        );
+    }
 }
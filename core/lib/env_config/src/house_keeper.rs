@@ -30,6 +30,13 @@ mod tests {
             fri_prover_stats_reporting_interval_ms: 30_000,
             fri_proof_compressor_job_retrying_interval_ms: 30_000,
             fri_proof_compressor_stats_reporting_interval_ms: 30_000,
+            utilization_report_generation_interval_ms: 3_600_000,
+            prover_artifacts_gc_interval_ms: 600_000,
+            prover_artifacts_retention_secs: Some(604_800),
+            prover_artifacts_gc_dry_run: true,
+            factory_deps_gc_interval_ms: 3_600_000,
+            factory_deps_retention_secs: Some(2_592_000),
+            factory_deps_gc_dry_run: true,
         }
     }
 
@@ -50,6 +57,13 @@ mod tests {
             HOUSE_KEEPER_FRI_PROVER_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_STATS_REPORTING_INTERVAL_MS="30000"
             HOUSE_KEEPER_FRI_PROOF_COMPRESSOR_JOB_RETRYING_INTERVAL_MS="30000"
+            HOUSE_KEEPER_UTILIZATION_REPORT_GENERATION_INTERVAL_MS="3600000"
+            HOUSE_KEEPER_PROVER_ARTIFACTS_GC_INTERVAL_MS="600000"
+            HOUSE_KEEPER_PROVER_ARTIFACTS_RETENTION_SECS="604800"
+            HOUSE_KEEPER_PROVER_ARTIFACTS_GC_DRY_RUN="true"
+            HOUSE_KEEPER_FACTORY_DEPS_GC_INTERVAL_MS="3600000"
+            HOUSE_KEEPER_FACTORY_DEPS_RETENTION_SECS="2592000"
+            HOUSE_KEEPER_FACTORY_DEPS_GC_DRY_RUN="true"
         "#;
         lock.set_env(config);
 
@@ -3,6 +3,7 @@ use serde::de::DeserializeOwned;
 
 mod alerts;
 mod api;
+mod base_token_fetcher;
 mod chain;
 mod contract_verifier;
 mod contracts;
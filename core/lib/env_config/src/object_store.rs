@@ -90,6 +90,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn s3_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="S3"
+            OBJECT_STORE_BUCKET_BASE_URL="zksync-artifacts"
+            OBJECT_STORE_S3_ENDPOINT="http://127.0.0.1:9000"
+            OBJECT_STORE_S3_REGION="us-east-1"
+            OBJECT_STORE_S3_CREDENTIAL_FILE_PATH="/path/to/s3_credentials.json"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::S3 {
+                bucket_base_url: "zksync-artifacts".to_owned(),
+                s3_endpoint: "http://127.0.0.1:9000".to_owned(),
+                s3_region: "us-east-1".to_owned(),
+                s3_credential_file_path: Some("/path/to/s3_credentials.json".to_owned()),
+            }
+        );
+    }
+
     #[test]
     fn public_bucket_config_from_env() {
         let mut lock = MUTEX.lock();
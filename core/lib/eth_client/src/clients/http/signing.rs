@@ -1,9 +1,13 @@
 use std::{fmt, sync::Arc};
 
 use async_trait::async_trait;
-use zksync_config::{ContractsConfig, ETHClientConfig, ETHSenderConfig};
+use zksync_config::{
+    configs::eth_sender::RemoteSignerConfig, ContractsConfig, ETHClientConfig, ETHSenderConfig,
+};
 use zksync_contracts::zksync_contract;
-use zksync_eth_signer::{raw_ethereum_tx::TransactionParameters, EthereumSigner, PrivateKeySigner};
+use zksync_eth_signer::{
+    raw_ethereum_tx::TransactionParameters, EthereumSigner, PrivateKeySigner, RemoteSigner,
+};
 use zksync_types::{
     web3::{
         self,
@@ -96,6 +100,77 @@ impl PKSigningClient {
     }
 }
 
+/// HTTP-based Ethereum client, backed by an external signer service to sign transactions.
+pub type RemoteSigningClient = SigningClient<RemoteSigner>;
+
+impl RemoteSigningClient {
+    pub fn from_config(
+        eth_sender: &ETHSenderConfig,
+        remote_signer: &RemoteSignerConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> Self {
+        Self::from_config_inner(
+            eth_sender,
+            remote_signer,
+            contracts_config,
+            eth_client,
+            remote_signer.operator_address,
+        )
+    }
+
+    /// Create a signing client for the blobs account.
+    pub fn from_config_blobs(
+        eth_sender: &ETHSenderConfig,
+        remote_signer: &RemoteSignerConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+    ) -> Option<Self> {
+        let operator_address = remote_signer.operator_blobs_address?;
+        Some(Self::from_config_inner(
+            eth_sender,
+            remote_signer,
+            contracts_config,
+            eth_client,
+            operator_address,
+        ))
+    }
+
+    fn from_config_inner(
+        eth_sender: &ETHSenderConfig,
+        remote_signer: &RemoteSignerConfig,
+        contracts_config: &ContractsConfig,
+        eth_client: &ETHClientConfig,
+        operator_address: Address,
+    ) -> Self {
+        let transport = Http::new(&eth_client.web3_url).expect("Failed to create transport");
+        let signer = RemoteSigner::new(
+            &remote_signer.url,
+            operator_address,
+            remote_signer.request_timeout(),
+            remote_signer.max_retries,
+        )
+        .expect("Failed to create remote signer");
+
+        tracing::info!("Operator address (signed remotely): {operator_address:?}");
+
+        SigningClient::new(
+            transport,
+            zksync_contract(),
+            operator_address,
+            signer,
+            contracts_config.diamond_proxy_addr,
+            eth_sender.gas_adjuster.default_priority_fee_per_gas.into(),
+            L1ChainId(eth_client.chain_id),
+        )
+    }
+
+    /// Returns the signer used by this client.
+    pub fn signer(&self) -> &RemoteSigner {
+        &self.inner.eth_signer
+    }
+}
+
 /// Gas limit value to be used in transaction if for some reason
 /// gas limit was not set for it.
 ///
@@ -168,6 +168,18 @@ impl MockEthereum {
         );
     }
 
+    /// Increments the blocks by a provided `confirmations` and consumes the next operator nonce by a transaction
+    /// that was not sent via this client (e.g., a transaction sent manually from the operator account).
+    pub fn execute_external_tx(&self, confirmations: u64) {
+        let mut inner = self.inner.write().unwrap();
+        let block_number = inner.block_number;
+        inner.block_number += confirmations;
+        inner.current_nonce += 1;
+        inner.pending_nonce = inner.pending_nonce.max(inner.current_nonce);
+        let nonce = inner.current_nonce;
+        inner.nonces.insert(block_number, nonce);
+    }
+
     pub fn sign_prepared_tx(
         &self,
         mut raw_tx: Vec<u8>,
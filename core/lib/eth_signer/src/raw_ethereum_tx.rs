@@ -16,7 +16,7 @@ use zksync_types::{
         signing::{self, Signature},
         types::{AccessList, SignedTransaction},
     },
-    H256, U256, U64,
+    PackedEthSignature, H256, U256, U64,
 };
 
 const LEGACY_TX_ID: u64 = 0;
@@ -56,6 +56,26 @@ pub struct TransactionParameters {
     pub blob_versioned_hashes: Option<Vec<H256>>,
 }
 
+impl From<TransactionParameters> for Transaction {
+    fn from(raw_tx: TransactionParameters) -> Self {
+        Self {
+            to: raw_tx.to,
+            nonce: raw_tx.nonce,
+            gas: raw_tx.gas,
+            // According to the code in web3 <https://docs.rs/web3/latest/src/web3/api/accounts.rs.html#86>
+            // We should use `max_fee_per_gas` as `gas_price` if we use EIP1559
+            gas_price: raw_tx.max_fee_per_gas,
+            value: raw_tx.value,
+            data: raw_tx.data,
+            transaction_type: raw_tx.transaction_type,
+            access_list: raw_tx.access_list.unwrap_or_default(),
+            max_priority_fee_per_gas: raw_tx.max_priority_fee_per_gas,
+            max_fee_per_blob_gas: raw_tx.max_fee_per_blob_gas,
+            blob_versioned_hashes: raw_tx.blob_versioned_hashes,
+        }
+    }
+}
+
 /// A transaction used for RLP encoding, hashing and signing.
 #[derive(Debug)]
 pub struct Transaction {
@@ -243,12 +263,49 @@ impl Transaction {
         }
     }
 
-    /// Sign and return a raw signed transaction.
-    pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
-        let adjust_v_value = matches!(
+    fn is_legacy(&self) -> bool {
+        matches!(
             self.transaction_type.map(|t| t.as_u64()),
             Some(LEGACY_TX_ID) | None
-        );
+        )
+    }
+
+    /// Returns the hash of the unsigned transaction, which should be signed by the sender.
+    pub fn signing_hash(&self, chain_id: u64) -> H256 {
+        signing::keccak256(&self.encode(chain_id, None)).into()
+    }
+
+    /// Returns a raw signed transaction given a signature of its [signing hash](Self::signing_hash()).
+    /// The `v` value of the signature must be the recovery ID (i.e., 0 or 1); it is adjusted
+    /// for legacy transactions according to EIP-155.
+    pub fn into_signed(self, chain_id: u64, signature: &PackedEthSignature) -> SignedTransaction {
+        let v = if self.is_legacy() {
+            signature.v_with_chain_id(chain_id)
+        } else {
+            signature.v().into()
+        };
+        let signature = Signature {
+            v,
+            r: H256::from_slice(signature.r()),
+            s: H256::from_slice(signature.s()),
+        };
+
+        let message_hash = self.signing_hash(chain_id);
+        let signed = self.encode(chain_id, Some(&signature));
+        let transaction_hash = signing::keccak256(signed.as_ref()).into();
+        SignedTransaction {
+            message_hash,
+            v: signature.v,
+            r: signature.r,
+            s: signature.s,
+            raw_transaction: signed.into(),
+            transaction_hash,
+        }
+    }
+
+    /// Sign and return a raw signed transaction.
+    pub fn sign(self, sign: impl signing::Key, chain_id: u64) -> SignedTransaction {
+        let adjust_v_value = self.is_legacy();
 
         let encoded = self.encode(chain_id, None);
 
@@ -0,0 +1,331 @@
+//! Signer delegating signing to an external service (e.g., an HSM or a KMS) accessible over HTTP.
+//!
+//! The service is expected to implement the following API:
+//!
+//! - `POST /sign` with a JSON body `{ "address": "0x...", "digest": "0x..." }` signs a 32-byte digest
+//!   with the key of the specified account and responds with `{ "signature": "0x..." }`, where the signature
+//!   is 65 bytes long and has `r || s || v` layout (`v` can be either the recovery ID or the recovery ID + 27).
+//! - `GET /health` responds with a successful status code if the service is ready to sign requests.
+
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use zksync_types::{
+    tx::primitives::PackedEthSignature, web3::types::Bytes, Address, EIP712TypedStructure,
+    Eip712Domain, H256,
+};
+
+use crate::{
+    json_rpc_signer::is_signature_from_address,
+    raw_ethereum_tx::{Transaction, TransactionParameters},
+    EthereumSigner, SignerError,
+};
+
+/// Initial interval between retries of a failed request; doubled after each retry.
+const INITIAL_RETRY_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Serialize)]
+struct SignRequest {
+    address: Address,
+    digest: H256,
+}
+
+#[derive(Debug, Deserialize)]
+struct SignResponse {
+    signature: Bytes,
+}
+
+#[derive(Debug, thiserror::Error)]
+enum RequestError {
+    #[error("request to signer service failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("signer service responded with {status}: {body}")]
+    Status { status: StatusCode, body: String },
+}
+
+impl RequestError {
+    fn is_retriable(&self) -> bool {
+        match self {
+            Self::Request(err) => err.is_timeout() || err.is_connect() || err.is_request(),
+            Self::Status { status, .. } => {
+                status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+            }
+        }
+    }
+}
+
+/// [`EthereumSigner`] implementation delegating signing to an external service over HTTP.
+/// Failed requests are retried with exponential backoff; signatures returned by the service
+/// are checked to be produced by the expected account.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    url: String,
+    address: Address,
+    client: reqwest::Client,
+    max_retries: u32,
+}
+
+impl RemoteSigner {
+    pub fn new(
+        url: &str,
+        address: Address,
+        request_timeout: Duration,
+        max_retries: u32,
+    ) -> Result<Self, SignerError> {
+        let client = reqwest::Client::builder()
+            .timeout(request_timeout)
+            .build()
+            .map_err(|err| SignerError::CustomError(err.to_string()))?;
+        Ok(Self {
+            url: url.trim_end_matches('/').to_owned(),
+            address,
+            client,
+            max_retries,
+        })
+    }
+
+    /// Checks whether the signer service is available.
+    pub async fn check_availability(&self) -> Result<(), SignerError> {
+        let response = self
+            .client
+            .get(format!("{}/health", self.url))
+            .send()
+            .await
+            .map_err(|err| SignerError::CustomError(err.to_string()))?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(())
+        } else {
+            Err(SignerError::CustomError(format!(
+                "signer service responded with {status}"
+            )))
+        }
+    }
+
+    async fn request_signature(&self, digest: H256) -> Result<Bytes, RequestError> {
+        let request = SignRequest {
+            address: self.address,
+            digest,
+        };
+        let response = self
+            .client
+            .post(format!("{}/sign", self.url))
+            .json(&request)
+            .send()
+            .await?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(RequestError::Status { status, body });
+        }
+        let response: SignResponse = response.json().await?;
+        Ok(response.signature)
+    }
+
+    /// Signs the provided digest, retrying failed requests if necessary.
+    async fn sign_digest(&self, digest: H256) -> Result<PackedEthSignature, SignerError> {
+        let mut retry_interval = INITIAL_RETRY_INTERVAL;
+        let mut attempt = 0;
+        let signature = loop {
+            match self.request_signature(digest).await {
+                Ok(signature) => break signature,
+                Err(err) if err.is_retriable() && attempt < self.max_retries => {
+                    attempt += 1;
+                    tokio::time::sleep(retry_interval).await;
+                    retry_interval *= 2;
+                }
+                Err(err) => return Err(SignerError::SigningFailed(err.to_string())),
+            }
+        };
+
+        let signature = PackedEthSignature::deserialize_packed(&signature.0)
+            .map_err(|err| SignerError::SigningFailed(err.to_string()))?;
+        if is_signature_from_address(&signature, &digest, self.address)? {
+            Ok(signature)
+        } else {
+            Err(SignerError::SigningFailed(format!(
+                "signature returned by the signer service is not produced by {:?}",
+                self.address
+            )))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl EthereumSigner for RemoteSigner {
+    async fn sign_typed_data<S: EIP712TypedStructure + Sync>(
+        &self,
+        domain: &Eip712Domain,
+        typed_struct: &S,
+    ) -> Result<PackedEthSignature, SignerError> {
+        let signed_bytes = PackedEthSignature::typed_data_to_signed_bytes(domain, typed_struct);
+        self.sign_digest(signed_bytes).await
+    }
+
+    /// Signs and returns the RLP-encoded transaction.
+    async fn sign_transaction(
+        &self,
+        raw_tx: TransactionParameters,
+    ) -> Result<Vec<u8>, SignerError> {
+        let chain_id = raw_tx.chain_id;
+        let tx = Transaction::from(raw_tx);
+        let signature = self.sign_digest(tx.signing_hash(chain_id)).await?;
+        let signed = tx.into_signed(chain_id, &signature);
+        Ok(signed.raw_transaction.0)
+    }
+
+    async fn get_address(&self) -> Result<Address, SignerError> {
+        Ok(self.address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use actix_web::{
+        get, post,
+        web::{self, Data},
+        App, HttpResponse, HttpServer, Responder,
+    };
+    use futures::future::{AbortHandle, Abortable};
+    use serde_json::json;
+    use zksync_types::{H160, U256, U64};
+
+    use super::*;
+    use crate::PrivateKeySigner;
+
+    #[derive(Debug, Deserialize)]
+    struct MockSignRequest {
+        address: Address,
+        digest: H256,
+    }
+
+    #[derive(Clone)]
+    struct State {
+        private_key: H256,
+        /// Number of initial sign requests that will fail with a server error.
+        failing_requests: usize,
+        sign_requests: Arc<AtomicUsize>,
+    }
+
+    #[post("/sign")]
+    async fn sign(req: web::Json<MockSignRequest>, state: Data<State>) -> impl Responder {
+        let request_idx = state.sign_requests.fetch_add(1, Ordering::SeqCst);
+        if request_idx < state.failing_requests {
+            return HttpResponse::ServiceUnavailable().body("not ready");
+        }
+        let expected_address = PackedEthSignature::address_from_private_key(&state.private_key);
+        if req.address != expected_address.unwrap() {
+            return HttpResponse::NotFound().body("unknown address");
+        }
+        let signature = PackedEthSignature::sign_raw(&state.private_key, &req.digest).unwrap();
+        let signature = Bytes(signature.serialize_packed().to_vec());
+        HttpResponse::Ok().json(json!({ "signature": signature }))
+    }
+
+    #[get("/health")]
+    async fn health() -> impl Responder {
+        HttpResponse::Ok().finish()
+    }
+
+    fn run_server(state: State) -> (String, AbortHandle) {
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(Data::new(state.clone()))
+                .service(sign)
+                .service(health)
+        })
+        .bind("127.0.0.1:0")
+        .unwrap();
+        let local_addr = server.addrs()[0];
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        tokio::spawn(Abortable::new(server.run(), abort_registration));
+        (format!("http://{local_addr}/"), abort_handle)
+    }
+
+    fn mock_state(private_key: H256, failing_requests: usize) -> State {
+        State {
+            private_key,
+            failing_requests,
+            sign_requests: Arc::default(),
+        }
+    }
+
+    fn mock_transaction(transaction_type: Option<U64>) -> TransactionParameters {
+        TransactionParameters {
+            nonce: U256::from(1u32),
+            to: Some(H160::default()),
+            gas: U256::from(100_000u32),
+            gas_price: Some(U256::from(2u32)),
+            max_fee_per_gas: U256::from(2u32),
+            max_priority_fee_per_gas: U256::from(1u32),
+            value: Default::default(),
+            data: vec![1, 2, 3],
+            chain_id: 270,
+            transaction_type,
+            access_list: None,
+            blob_versioned_hashes: None,
+            max_fee_per_blob_gas: None,
+        }
+    }
+
+    #[actix_rt::test]
+    async fn signing_transactions() {
+        let private_key = H256::repeat_byte(5);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let (url, abort_handle) = run_server(mock_state(private_key, 0));
+        let signer = RemoteSigner::new(&url, address, Duration::from_secs(5), 0).unwrap();
+        signer.check_availability().await.unwrap();
+        assert_eq!(signer.get_address().await.unwrap(), address);
+
+        let pk_signer = PrivateKeySigner::new(private_key);
+        for transaction_type in [None, Some(U64::from(1)), Some(U64::from(2))] {
+            let tx = mock_transaction(transaction_type);
+            let raw_tx = signer.sign_transaction(tx.clone()).await.unwrap();
+            let expected_raw_tx = pk_signer.sign_transaction(tx).await.unwrap();
+            assert_eq!(raw_tx, expected_raw_tx, "{transaction_type:?}");
+        }
+        abort_handle.abort();
+    }
+
+    #[actix_rt::test]
+    async fn retrying_failed_requests() {
+        let private_key = H256::repeat_byte(5);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let state = mock_state(private_key, 2);
+        let sign_requests = state.sign_requests.clone();
+        let (url, abort_handle) = run_server(state);
+
+        let signer = RemoteSigner::new(&url, address, Duration::from_secs(5), 1).unwrap();
+        let tx = mock_transaction(Some(U64::from(2)));
+        let err = signer.sign_transaction(tx.clone()).await.unwrap_err();
+        assert!(matches!(err, SignerError::SigningFailed(_)), "{err:?}");
+        assert_eq!(sign_requests.load(Ordering::SeqCst), 2);
+
+        // The service should respond successfully after 2 failed requests.
+        signer.sign_transaction(tx).await.unwrap();
+        assert_eq!(sign_requests.load(Ordering::SeqCst), 3);
+        abort_handle.abort();
+    }
+
+    #[actix_rt::test]
+    async fn not_retrying_client_errors() {
+        let private_key = H256::repeat_byte(5);
+        let state = mock_state(private_key, 0);
+        let sign_requests = state.sign_requests.clone();
+        let (url, abort_handle) = run_server(state);
+
+        let signer =
+            RemoteSigner::new(&url, Address::repeat_byte(1), Duration::from_secs(5), 3).unwrap();
+        let tx = mock_transaction(Some(U64::from(2)));
+        let err = signer.sign_transaction(tx).await.unwrap_err().to_string();
+        assert!(err.contains("unknown address"), "{err}");
+        assert_eq!(sign_requests.load(Ordering::SeqCst), 1);
+        abort_handle.abort();
+    }
+}
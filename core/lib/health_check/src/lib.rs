@@ -45,6 +45,12 @@ impl HealthStatus {
         matches!(self, Self::Ready | Self::Affected)
     }
 
+    /// Checks whether a component is alive according to this status. Unlike [`Self::is_healthy()`], this is true
+    /// for components that are initializing or shutting down; only panicked components are not considered alive.
+    pub fn is_live(self) -> bool {
+        !matches!(self, Self::Panicked)
+    }
+
     fn priority_for_aggregation(self) -> usize {
         match self {
             Self::Ready => 0,
@@ -94,6 +100,8 @@ impl From<HealthStatus> for Health {
 #[derive(Debug)]
 pub struct AppHealthCheck {
     components: Mutex<Vec<Arc<dyn CheckHealth>>>,
+    /// Maps a component name to names of components it depends on.
+    dependencies: Mutex<HashMap<&'static str, Vec<&'static str>>>,
     slow_time_limit: Duration,
     hard_time_limit: Duration,
 }
@@ -114,6 +122,7 @@ impl AppHealthCheck {
         tracing::debug!("Created app health with time limits: slow={slow_time_limit:?}, hard={hard_time_limit:?}");
         Self {
             components: Mutex::default(),
+            dependencies: Mutex::default(),
             slow_time_limit,
             hard_time_limit,
         }
@@ -140,6 +149,20 @@ impl AppHealthCheck {
         guard.push(health_check);
     }
 
+    /// Records that the `dependent` component depends on the `dependency` component (e.g., because it consumes
+    /// data produced by it). Dependencies are reported in the `/health` endpoint output, so that it's easier to find
+    /// the root cause if multiple components are unhealthy. Components don't need to be inserted beforehand.
+    pub fn add_dependency(&self, dependent: &'static str, dependency: &'static str) {
+        let mut guard = self
+            .dependencies
+            .lock()
+            .expect("`AppHealthCheck` is poisoned");
+        let dependencies = guard.entry(dependent).or_default();
+        if !dependencies.contains(&dependency) {
+            dependencies.push(dependency);
+        }
+    }
+
     /// Checks the overall application health. This will query all component checks concurrently.
     pub async fn check_health(&self) -> AppHealth {
         // Clone checks so that we don't hold a lock for them across a wait point.
@@ -164,11 +187,30 @@ impl AppHealthCheck {
             .max_by_key(|status| status.priority_for_aggregation())
             .unwrap_or(HealthStatus::Ready);
         let inner = aggregated_status.into();
+        let is_live = components.values().all(|health| health.status.is_live());
+        let dependencies = self
+            .dependencies
+            .lock()
+            .expect("`AppHealthCheck` is poisoned")
+            .clone();
 
-        let health = AppHealth { inner, components };
+        let mut health = AppHealth {
+            inner,
+            is_live,
+            components,
+            dependencies,
+            root_causes: vec![],
+        };
+        health.root_causes = health.find_root_causes();
         if !health.inner.status.is_healthy() {
             // Only log non-ready application health so that logs are not spammed without a reason.
             tracing::debug!("Aggregated application health: {health:?}");
+            if !health.root_causes.is_empty() {
+                tracing::info!(
+                    "Application is unhealthy; root cause components: {:?}",
+                    health.root_causes
+                );
+            }
         }
         health
     }
@@ -244,13 +286,62 @@ impl AppHealthCheck {
 pub struct AppHealth {
     #[serde(flatten)]
     inner: Health,
+    #[serde(skip)]
+    is_live: bool,
     components: HashMap<&'static str, Health>,
+    /// Dependency graph of components; see [`AppHealthCheck::add_dependency()`].
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    dependencies: HashMap<&'static str, Vec<&'static str>>,
+    /// Unhealthy components that don't have unhealthy dependencies, sorted by name.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    root_causes: Vec<&'static str>,
 }
 
 impl AppHealth {
+    /// Checks whether the application is ready to serve, i.e., all its components are healthy.
     pub fn is_healthy(&self) -> bool {
         self.inner.status.is_healthy()
     }
+
+    /// Checks whether the application is alive, i.e., none of its components has panicked. A live application
+    /// may still be not ready (e.g., if some of its components are initializing).
+    pub fn is_live(&self) -> bool {
+        self.is_live
+    }
+
+    /// Returns names of unhealthy dependencies for the specified component. Only direct dependencies are returned.
+    pub fn unhealthy_dependencies(&self, component: &str) -> Vec<&'static str> {
+        let Some(dependencies) = self.dependencies.get(component) else {
+            return vec![];
+        };
+        dependencies
+            .iter()
+            .copied()
+            .filter(|&dependency| {
+                self.components
+                    .get(dependency)
+                    .map_or(true, |health| !health.status.is_healthy())
+            })
+            .collect()
+    }
+
+    /// Returns unhealthy components that are not explained by unhealthy dependencies.
+    pub fn root_causes(&self) -> &[&'static str] {
+        &self.root_causes
+    }
+
+    fn find_root_causes(&self) -> Vec<&'static str> {
+        let mut root_causes: Vec<_> = self
+            .components
+            .iter()
+            .filter(|(name, health)| {
+                !health.status.is_healthy() && self.unhealthy_dependencies(name).is_empty()
+            })
+            .map(|(&name, _)| name)
+            .collect();
+        root_causes.sort_unstable();
+        root_causes
+    }
 }
 
 /// Interface to be used for health checks.
@@ -134,3 +134,106 @@ async fn aggregating_health_checks() {
         HealthStatus::Affected
     );
 }
+
+#[tokio::test]
+async fn checking_app_liveness() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let checks = AppHealthCheck::default();
+    checks.insert_component(first_check);
+    checks.insert_component(second_check);
+
+    let app_health = checks.check_health().await;
+    assert!(!app_health.is_healthy());
+    assert!(app_health.is_live());
+
+    first_updater.update(HealthStatus::Ready.into());
+    second_updater.update(HealthStatus::ShuttingDown.into());
+    let app_health = checks.check_health().await;
+    assert!(!app_health.is_healthy());
+    assert!(app_health.is_live());
+
+    second_updater.update(HealthStatus::Panicked.into());
+    let app_health = checks.check_health().await;
+    assert!(!app_health.is_healthy());
+    assert!(!app_health.is_live());
+}
+
+#[tokio::test]
+async fn reporting_dependencies() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let checks = AppHealthCheck::default();
+    checks.insert_component(first_check);
+    checks.insert_component(second_check);
+
+    let app_health = checks.check_health().await;
+    let app_health_json = serde_json::to_value(&app_health).unwrap();
+    assert!(
+        app_health_json.get("dependencies").is_none(),
+        "{app_health_json}"
+    );
+
+    checks.add_dependency("second", "first");
+    checks.add_dependency("second", "first");
+    checks.add_dependency("second", "missing");
+    first_updater.update(HealthStatus::Ready.into());
+    second_updater.update(HealthStatus::Ready.into());
+
+    let app_health = checks.check_health().await;
+    assert_eq!(app_health.dependencies["second"], ["first", "missing"]);
+    assert_eq!(app_health.unhealthy_dependencies("second"), ["missing"]);
+    assert!(app_health.unhealthy_dependencies("first").is_empty());
+    let app_health_json = serde_json::to_value(&app_health).unwrap();
+    assert_eq!(
+        app_health_json["dependencies"],
+        serde_json::json!({ "second": ["first", "missing"] })
+    );
+
+    first_updater.update(HealthStatus::Affected.into());
+    let app_health = checks.check_health().await;
+    assert_eq!(app_health.unhealthy_dependencies("second"), ["missing"]);
+    drop(first_updater);
+    let app_health = checks.check_health().await;
+    assert_eq!(
+        app_health.unhealthy_dependencies("second"),
+        ["first", "missing"]
+    );
+}
+
+#[tokio::test]
+async fn reporting_root_causes() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let (third_check, third_updater) = ReactiveHealthCheck::new("third");
+    let checks = AppHealthCheck::default();
+    checks.insert_component(first_check);
+    checks.insert_component(second_check);
+    checks.insert_component(third_check);
+    checks.add_dependency("second", "first");
+    checks.add_dependency("third", "second");
+
+    for updater in [&first_updater, &second_updater, &third_updater] {
+        updater.update(HealthStatus::Ready.into());
+    }
+    let app_health = checks.check_health().await;
+    assert!(app_health.root_causes().is_empty());
+    let app_health_json = serde_json::to_value(&app_health).unwrap();
+    assert!(
+        app_health_json.get("root_causes").is_none(),
+        "{app_health_json}"
+    );
+
+    // The failure of `first` propagates to dependent components, but only `first` is the root cause.
+    first_updater.update(HealthStatus::NotReady.into());
+    second_updater.update(HealthStatus::NotReady.into());
+    third_updater.update(HealthStatus::NotReady.into());
+    let app_health = checks.check_health().await;
+    assert_eq!(app_health.root_causes(), ["first"]);
+    let app_health_json = serde_json::to_value(&app_health).unwrap();
+    assert_eq!(app_health_json["root_causes"], serde_json::json!(["first"]));
+
+    first_updater.update(HealthStatus::Ready.into());
+    let app_health = checks.check_health().await;
+    assert_eq!(app_health.root_causes(), ["second"]);
+}
@@ -21,7 +21,7 @@ use zkevm_test_harness_1_4_2::{
         },
     },
 };
-use zksync_types::H256;
+use zksync_types::{pubdata_da::PubdataDA, H256};
 
 pub const ZK_SYNC_BYTES_PER_BLOB: usize = BLOB_CHUNK_SIZE * ELEMENTS_PER_4844_BLOCK;
 const EIP_4844_BYTES_PER_BLOB: usize = 32 * ELEMENTS_PER_4844_BLOCK;
@@ -264,6 +264,22 @@ impl KzgInfo {
     }
 }
 
+/// Computes KZG info for the pubdata as it's committed to L1 in the specified DA mode. If pubdata is sent in blobs,
+/// returns info for each pubdata blob; otherwise, returns a single entry used for the blob commitment.
+pub fn pubdata_to_kzg_info(
+    kzg_settings: &KzgSettings,
+    pubdata: &[u8],
+    pubdata_da: PubdataDA,
+) -> Vec<KzgInfo> {
+    match pubdata_da {
+        PubdataDA::Calldata => vec![KzgInfo::new(kzg_settings, pubdata)],
+        PubdataDA::Blobs => pubdata
+            .chunks(ZK_SYNC_BYTES_PER_BLOB)
+            .map(|blob| KzgInfo::new(kzg_settings, blob))
+            .collect(),
+    }
+}
+
 pub fn pubdata_to_blob_commitments(pubdata_input: &[u8], kzg_settings: &KzgSettings) -> [H256; 2] {
     assert!(
         pubdata_input.len() <= 2 * ZK_SYNC_BYTES_PER_BLOB,
@@ -1,10 +1,15 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use zkevm_test_harness_1_4_2::kzg::KzgSettings;
-use zksync_types::{commitment::L1BatchWithMetadata, ethabi::Token, pubdata_da::PubdataDA};
+use zksync_types::{
+    commitment::L1BatchWithMetadata, ethabi::Token, pubdata_da::PubdataDA, L1BatchNumber,
+};
 
 use crate::{
-    i_executor::structures::{CommitBatchInfo, StoredBatchInfo},
+    i_executor::{
+        commit::kzg::KzgInfo,
+        structures::{CommitBatchInfo, StoredBatchInfo},
+    },
     Tokenizable, Tokenize,
 };
 
@@ -15,6 +20,8 @@ pub struct CommitBatches {
     pub l1_batches: Vec<L1BatchWithMetadata>,
     pub pubdata_da: PubdataDA,
     pub kzg_settings: Option<Arc<KzgSettings>>,
+    /// KZG info precomputed for some of the committed batches. KZG info for other batches is computed on encoding.
+    pub precomputed_kzg_info: HashMap<L1BatchNumber, Arc<[KzgInfo]>>,
 }
 
 impl Tokenize for CommitBatches {
@@ -24,7 +31,10 @@ impl Tokenize for CommitBatches {
             .l1_batches
             .iter()
             .map(|batch| {
-                CommitBatchInfo::new(batch, self.pubdata_da, self.kzg_settings.clone()).into_token()
+                let kzg_info = self.precomputed_kzg_info.get(&batch.header.number);
+                CommitBatchInfo::new(batch, self.pubdata_da, self.kzg_settings.clone())
+                    .with_precomputed_kzg_info(kzg_info.cloned())
+                    .into_token()
             })
             .collect();
 
@@ -10,7 +10,7 @@ use zksync_types::{
 };
 
 use crate::{
-    i_executor::commit::kzg::{KzgInfo, ZK_SYNC_BYTES_PER_BLOB},
+    i_executor::commit::kzg::{pubdata_to_kzg_info, KzgInfo},
     Tokenizable,
 };
 
@@ -24,6 +24,9 @@ pub struct CommitBatchInfo<'a> {
     pub l1_batch_with_metadata: &'a L1BatchWithMetadata,
     pub pubdata_da: PubdataDA,
     pub kzg_settings: Option<Arc<KzgSettings>>,
+    /// KZG info for the batch pubdata computed ahead of time (see [`pubdata_to_kzg_info()`]).
+    /// If not set, KZG info is computed when encoding the batch.
+    pub precomputed_kzg_info: Option<Arc<[KzgInfo]>>,
 }
 
 impl<'a> CommitBatchInfo<'a> {
@@ -36,9 +39,27 @@ impl<'a> CommitBatchInfo<'a> {
             l1_batch_with_metadata,
             pubdata_da,
             kzg_settings,
+            precomputed_kzg_info: None,
         }
     }
 
+    /// Sets KZG info precomputed for the batch pubdata in the same DA mode.
+    pub fn with_precomputed_kzg_info(mut self, kzg_info: Option<Arc<[KzgInfo]>>) -> Self {
+        self.precomputed_kzg_info = kzg_info;
+        self
+    }
+
+    fn kzg_info(&self, pubdata: &[u8]) -> Arc<[KzgInfo]> {
+        if let Some(kzg_info) = &self.precomputed_kzg_info {
+            return kzg_info.clone();
+        }
+        let kzg_settings = self
+            .kzg_settings
+            .as_ref()
+            .expect("KZG settings are required to commit L1 batches with pubdata commitments");
+        pubdata_to_kzg_info(kzg_settings, pubdata, self.pubdata_da).into()
+    }
+
     fn base_tokens(&self) -> Vec<Token> {
         if self
             .l1_batch_with_metadata
@@ -207,13 +228,12 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                 .pubdata_input
                 .clone()
                 .unwrap_or(self.l1_batch_with_metadata.construct_pubdata());
+            let kzg_info = self.kzg_info(&pubdata);
             match self.pubdata_da {
                 PubdataDA::Calldata => {
                     // We compute and add the blob commitment to the pubdata payload so that we can verify the proof
                     // even if we are not using blobs.
-                    let blob_commitment =
-                        KzgInfo::new(self.kzg_settings.as_ref().unwrap(), &pubdata)
-                            .to_blob_commitment();
+                    let blob_commitment = kzg_info[0].to_blob_commitment();
 
                     let result = std::iter::once(PUBDATA_SOURCE_CALLDATA)
                         .chain(pubdata)
@@ -223,12 +243,9 @@ impl<'a> Tokenizable for CommitBatchInfo<'a> {
                     tokens.push(Token::Bytes(result));
                 }
                 PubdataDA::Blobs => {
-                    let pubdata_commitments = pubdata
-                        .chunks(ZK_SYNC_BYTES_PER_BLOB)
-                        .flat_map(|blob| {
-                            let kzg_info = KzgInfo::new(self.kzg_settings.as_ref().unwrap(), blob);
-                            kzg_info.to_pubdata_commitment().to_vec()
-                        })
+                    let pubdata_commitments = kzg_info
+                        .iter()
+                        .flat_map(KzgInfo::to_pubdata_commitment)
                         .collect::<Vec<u8>>();
 
                     let result = std::iter::once(PUBDATA_SOURCE_BLOBS)
@@ -1,8 +1,10 @@
 use zksync_types::{
     commitment::L1BatchWithMetadata,
-    ethabi::Token,
-    web3::{contract::Error as Web3ContractError, error::Error as Web3ApiError},
-    U256,
+    ethabi::{self, Token},
+    web3::{
+        contract::Error as Web3ContractError, error::Error as Web3ApiError, signing::keccak256,
+    },
+    H256, U256,
 };
 
 use crate::Tokenizable;
@@ -11,6 +13,13 @@ use crate::Tokenizable;
 #[derive(Debug)]
 pub struct StoredBatchInfo<'a>(pub &'a L1BatchWithMetadata);
 
+impl StoredBatchInfo<'_> {
+    /// Returns the hash of the encoded info, as returned by the `storedBatchHash` getter of the L1 contract.
+    pub fn hash(self) -> H256 {
+        H256(keccak256(&ethabi::encode(&[self.into_token()])))
+    }
+}
+
 impl<'a> Tokenizable for StoredBatchInfo<'a> {
     fn from_token(_token: Token) -> Result<Self, zksync_types::web3::contract::Error>
     where
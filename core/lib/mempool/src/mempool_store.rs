@@ -1,15 +1,22 @@
-use std::collections::{hash_map, BTreeSet, HashMap, HashSet};
+use std::{
+    collections::{hash_map, BTreeSet, HashMap, HashSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use zksync_types::{
-    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction,
+    l1::L1Tx, l2::L2Tx, Address, ExecuteTransactionCommon, Nonce, PriorityOpId, Transaction, H256,
+    U256,
 };
 
-use crate::types::{AccountTransactions, L2TxFilter, MempoolScore};
+use crate::types::{AccountTransactions, L2TxFilter, MempoolOrdering, MempoolScore};
 
 #[derive(Debug)]
 pub struct MempoolInfo {
     pub stashed_accounts: Vec<Address>,
     pub purged_accounts: Vec<Address>,
+    /// Hashes of L2 transactions dropped from the mempool since the previous call to
+    /// [`MempoolStore::get_mempool_info()`] (replaced by nonce, having an outdated nonce, stashed or purged).
+    pub dropped_transactions: Vec<H256>,
 }
 
 #[derive(Debug)]
@@ -27,12 +34,16 @@ pub struct MempoolStore {
     l2_transactions_per_account: HashMap<Address, AccountTransactions>,
     /// Global priority queue for L2 transactions. Used for scoring
     l2_priority_queue: BTreeSet<MempoolScore>,
+    /// Entries of `l2_priority_queue` indexed by the max priority fee. Only maintained for the fee priority ordering.
+    fee_priority_queue: BTreeSet<(U256, MempoolScore)>,
     /// Next priority operation
     next_priority_id: PriorityOpId,
     stashed_accounts: Vec<Address>,
+    dropped_transactions: Vec<H256>,
     /// Number of L2 transactions in the mempool.
     size: u64,
     capacity: u64,
+    ordering: MempoolOrdering,
 }
 
 impl MempoolStore {
@@ -41,13 +52,53 @@ impl MempoolStore {
             l1_transactions: HashMap::new(),
             l2_transactions_per_account: HashMap::new(),
             l2_priority_queue: BTreeSet::new(),
+            fee_priority_queue: BTreeSet::new(),
             next_priority_id,
             stashed_accounts: vec![],
+            dropped_transactions: vec![],
             size: 0,
             capacity,
+            ordering: MempoolOrdering::default(),
         }
     }
 
+    /// Sets the ordering of L2 transactions from different accounts.
+    #[must_use]
+    pub fn with_ordering(mut self, ordering: MempoolOrdering) -> Self {
+        self.ordering = ordering;
+        self.fee_priority_queue = if self.maintains_fee_priority_queue() {
+            self.l2_priority_queue
+                .iter()
+                .map(|score| (score.fee_data.max_priority_fee_per_gas, score.clone()))
+                .collect()
+        } else {
+            BTreeSet::new()
+        };
+        self
+    }
+
+    fn maintains_fee_priority_queue(&self) -> bool {
+        matches!(self.ordering, MempoolOrdering::FeePriority { .. })
+    }
+
+    fn insert_score(&mut self, score: MempoolScore) {
+        if self.maintains_fee_priority_queue() {
+            let max_priority_fee = score.fee_data.max_priority_fee_per_gas;
+            self.fee_priority_queue
+                .insert((max_priority_fee, score.clone()));
+        }
+        self.l2_priority_queue.insert(score);
+    }
+
+    fn remove_score(&mut self, score: &MempoolScore) {
+        if self.maintains_fee_priority_queue() {
+            let max_priority_fee = score.fee_data.max_priority_fee_per_gas;
+            self.fee_priority_queue
+                .remove(&(max_priority_fee, score.clone()));
+        }
+        self.l2_priority_queue.remove(score);
+    }
+
     /// Inserts batch of new transactions to mempool
     /// `initial_nonces` provides current committed nonce information to mempool
     /// variable is used only if account is not present in mempool yet and we have to bootstrap it
@@ -112,14 +163,15 @@ impl MempoolStore {
             }
         };
         if let Some(score) = metadata.previous_score {
-            self.l2_priority_queue.remove(&score);
+            self.remove_score(&score);
         }
         if let Some(score) = metadata.new_score {
-            self.l2_priority_queue.insert(score);
+            self.insert_score(score);
         }
         if metadata.is_new {
             self.size += 1;
         }
+        self.dropped_transactions.extend(metadata.dropped_tx_hash);
     }
 
     /// Returns `true` if there is a transaction in the mempool satisfying the filter.
@@ -139,26 +191,46 @@ impl MempoolStore {
             return Some(transaction.into());
         }
 
+        let (tx_pointer, stashed_pointers) = match self.ordering {
+            MempoolOrdering::Fifo => {
+                // We want to fetch the next transaction that would match the fee requirements.
+                let tx_pointer = self
+                    .l2_priority_queue
+                    .iter()
+                    .rfind(|el| el.matches_filter(filter))?
+                    .clone();
+                // Stash all observed transactions that don't meet criteria
+                let stashed_pointers: Vec<_> = self
+                    .l2_priority_queue
+                    .split_off(&tx_pointer)
+                    .into_iter()
+                    .skip(1)
+                    .collect();
+                (tx_pointer, stashed_pointers)
+            }
+            MempoolOrdering::FeePriority { max_wait } => {
+                let aged_before_ms =
+                    millis_since_epoch().saturating_sub(max_wait.as_millis() as u64);
+                let (tx_pointer, stashed_pointers) =
+                    self.next_fee_priority_pointer(filter, aged_before_ms);
+                let tx_pointer = tx_pointer?;
+                self.remove_score(&tx_pointer);
+                // Stash all observed transactions that don't meet criteria
+                for stashed_pointer in &stashed_pointers {
+                    self.remove_score(stashed_pointer);
+                }
+                (tx_pointer, stashed_pointers.into_iter().collect())
+            }
+        };
+
         let mut removed = 0;
-        // We want to fetch the next transaction that would match the fee requirements.
-        let tx_pointer = self
-            .l2_priority_queue
-            .iter()
-            .rfind(|el| el.matches_filter(filter))?
-            .clone();
-
-        // Stash all observed transactions that don't meet criteria
-        for stashed_pointer in self
-            .l2_priority_queue
-            .split_off(&tx_pointer)
-            .into_iter()
-            .skip(1)
-        {
-            removed += self
+        for stashed_pointer in stashed_pointers {
+            let stashed_txs = self
                 .l2_transactions_per_account
                 .remove(&stashed_pointer.account)
-                .expect("mempool: dangling pointer in priority queue")
-                .len();
+                .expect("mempool: dangling pointer in priority queue");
+            removed += stashed_txs.len();
+            self.dropped_transactions.extend(stashed_txs.tx_hashes());
 
             self.stashed_accounts.push(stashed_pointer.account);
         }
@@ -170,7 +242,7 @@ impl MempoolStore {
             .next();
 
         if let Some(score) = score {
-            self.l2_priority_queue.insert(score);
+            self.insert_score(score);
         }
         self.size = self
             .size
@@ -179,6 +251,45 @@ impl MempoolStore {
         Some(transaction.into())
     }
 
+    /// Selects the next transaction for the fee priority ordering. Returns the selected transaction (if any)
+    /// together with the observed transactions that don't match the filter.
+    fn next_fee_priority_pointer(
+        &self,
+        filter: &L2TxFilter,
+        aged_before_ms: u64,
+    ) -> (Option<MempoolScore>, BTreeSet<MempoolScore>) {
+        let mut stashed_pointers = BTreeSet::new();
+        // Aged transactions are prioritized regardless of their fees and are ordered by the time they were received.
+        for el in self.l2_priority_queue.iter().rev() {
+            if el.received_at_ms > aged_before_ms {
+                break;
+            }
+            if el.matches_filter(filter) {
+                return (Some(el.clone()), stashed_pointers);
+            }
+            stashed_pointers.insert(el.clone());
+        }
+
+        // The effective priority fee never exceeds the max priority fee, so transactions are scanned
+        // in the descending max priority fee order until the max priority fee is lower than the best effective fee.
+        let mut best: Option<(U256, &MempoolScore)> = None;
+        for (max_priority_fee, el) in self.fee_priority_queue.iter().rev() {
+            if matches!(best, Some((best_fee, _)) if *max_priority_fee < best_fee) {
+                break;
+            }
+            if !el.matches_filter(filter) {
+                stashed_pointers.insert(el.clone());
+                continue;
+            }
+            let fee = el.effective_priority_fee(filter.fee_per_gas);
+            // Ties are broken by the time transactions were received.
+            if best.map_or(true, |best| (fee, el) > best) {
+                best = Some((fee, el));
+            }
+        }
+        (best.map(|(_, el)| el.clone()), stashed_pointers)
+    }
+
     /// When a state_keeper starts the block over after a rejected transaction,
     /// we have to rollback the nonces/ids in the mempool and
     /// reinsert the transactions from the block back into mempool.
@@ -196,7 +307,7 @@ impl MempoolStore {
                     .expect("account is not available in mempool")
                     .reset(tx)
                 {
-                    self.l2_priority_queue.remove(&score);
+                    self.remove_score(&score);
                 }
             }
             ExecuteTransactionCommon::ProtocolUpgrade(_) => {
@@ -206,9 +317,11 @@ impl MempoolStore {
     }
 
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
+        let purged_accounts = self.gc();
         MempoolInfo {
             stashed_accounts: std::mem::take(&mut self.stashed_accounts),
-            purged_accounts: self.gc(),
+            purged_accounts,
+            dropped_transactions: std::mem::take(&mut self.dropped_transactions),
         }
     }
 
@@ -236,8 +349,17 @@ impl MempoolStore {
                 .l2_transactions_per_account
                 .iter()
                 .fold(0, |agg, (_, tnxs)| agg + tnxs.len() as u64);
+            self.dropped_transactions
+                .extend(drained.values().flat_map(AccountTransactions::tx_hashes));
             return drained.into_keys().collect();
         }
         vec![]
     }
 }
+
+fn millis_since_epoch() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("incorrect system time")
+        .as_millis() as u64
+}
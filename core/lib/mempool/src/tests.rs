@@ -1,6 +1,7 @@
 use std::{
     collections::{HashMap, HashSet},
     iter::FromIterator,
+    time::Duration,
 };
 
 use zksync_types::{
@@ -12,7 +13,10 @@ use zksync_types::{
     H256, U256,
 };
 
-use crate::{mempool_store::MempoolStore, types::L2TxFilter};
+use crate::{
+    mempool_store::MempoolStore,
+    types::{L2TxFilter, MempoolOrdering},
+};
 
 #[test]
 fn basic_flow() {
@@ -365,6 +369,153 @@ fn mempool_capacity() {
     );
 }
 
+#[test]
+fn fee_priority_ordering() {
+    let filter = L2TxFilter {
+        fee_input: Default::default(),
+        fee_per_gas: 100,
+        gas_per_pubdata: 0,
+    };
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_ordering(MempoolOrdering::FeePriority {
+            max_wait: Duration::from_secs(3_600),
+        });
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let account3 = Address::random();
+    let now = unix_timestamp_ms();
+    mempool.insert(
+        vec![
+            gen_l2_tx_with_fee(account0, Nonce(0), now - 30, 110, 10),
+            gen_l2_tx_with_fee(account0, Nonce(1), now - 30, 200, 100),
+            // Effective priority fee is capped by the max fee: `150 - 100 = 50`.
+            gen_l2_tx_with_fee(account1, Nonce(0), now - 10, 150, 80),
+            // Same effective priority fee as the previous transaction, but received earlier.
+            gen_l2_tx_with_fee(account2, Nonce(0), now - 20, 200, 50),
+            // Doesn't match the filter.
+            gen_l2_tx_with_fee(account3, Nonce(0), now - 40, 90, 90),
+        ],
+        HashMap::new(),
+    );
+
+    assert_eq!(view(mempool.next_transaction(&filter)), (account2, 0));
+    assert_eq!(mempool.get_mempool_info().stashed_accounts, [account3]);
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 1));
+    assert_eq!(mempool.next_transaction(&filter), None);
+}
+
+#[test]
+fn fee_priority_ordering_with_aging() {
+    let filter = L2TxFilter {
+        fee_input: Default::default(),
+        fee_per_gas: 100,
+        gas_per_pubdata: 0,
+    };
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_ordering(MempoolOrdering::FeePriority {
+            max_wait: Duration::from_secs(60),
+        });
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let now = unix_timestamp_ms();
+    mempool.insert(
+        vec![
+            gen_l2_tx_with_fee(account0, Nonce(0), now, 1_000, 1_000),
+            // Aged transactions are prioritized regardless of fees and ordered by the time they were received.
+            gen_l2_tx_with_fee(account1, Nonce(0), now - 60_000, 100, 0),
+            gen_l2_tx_with_fee(account2, Nonce(0), now - 120_000, 100, 0),
+        ],
+        HashMap::new(),
+    );
+
+    assert_eq!(view(mempool.next_transaction(&filter)), (account2, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+}
+
+#[test]
+fn fee_priority_ordering_with_replacements_and_rollbacks() {
+    let filter = L2TxFilter {
+        fee_input: Default::default(),
+        fee_per_gas: 100,
+        gas_per_pubdata: 0,
+    };
+    let mut mempool =
+        MempoolStore::new(PriorityOpId(0), 100).with_ordering(MempoolOrdering::FeePriority {
+            max_wait: Duration::from_secs(3_600),
+        });
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let account2 = Address::random();
+    let now = unix_timestamp_ms();
+    mempool.insert(
+        vec![
+            gen_l2_tx_with_fee(account0, Nonce(0), now - 10, 200, 10),
+            gen_l2_tx_with_fee(account1, Nonce(0), now - 20, 200, 50),
+            // Doesn't match the filter, but has a low priority fee, so it's not observed.
+            gen_l2_tx_with_fee(account2, Nonce(0), now - 30, 50, 5),
+        ],
+        HashMap::new(),
+    );
+    // Replace the transaction with one with a higher priority fee.
+    let replacement = gen_l2_tx_with_fee(account0, Nonce(0), now - 5, 200, 90);
+    mempool.insert(vec![replacement.clone()], HashMap::new());
+    assert_eq!(mempool.stats().l2_transaction_count, 3);
+
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    mempool.rollback(&replacement);
+    mempool.insert(vec![replacement], HashMap::new());
+    assert_eq!(view(mempool.next_transaction(&filter)), (account0, 0));
+    assert!(mempool.get_mempool_info().stashed_accounts.is_empty());
+
+    assert_eq!(view(mempool.next_transaction(&filter)), (account1, 0));
+    assert_eq!(mempool.next_transaction(&filter), None);
+    assert_eq!(mempool.stats().l2_priority_queue_size, 1);
+}
+
+#[test]
+fn dropped_transactions() {
+    let mut mempool = MempoolStore::new(PriorityOpId(0), 3);
+    let account0 = Address::random();
+    let account1 = Address::random();
+    let replaced_tx = gen_l2_tx(account0, Nonce(0));
+    let purged_tx = gen_l2_tx(account1, Nonce(1));
+    mempool.insert(vec![replaced_tx.clone(), purged_tx.clone()], HashMap::new());
+    assert!(mempool.get_mempool_info().dropped_transactions.is_empty());
+
+    let replacement_tx = gen_l2_tx(account0, Nonce(0));
+    mempool.insert(vec![replacement_tx.clone()], HashMap::new());
+    // Reinserting the same transaction doesn't drop it.
+    mempool.insert(vec![replacement_tx.clone()], HashMap::new());
+    assert_eq!(
+        mempool.get_mempool_info().dropped_transactions,
+        [replaced_tx.hash()]
+    );
+
+    let next_tx = mempool.next_transaction(&L2TxFilter::default()).unwrap();
+    assert_eq!(next_tx.hash(), replacement_tx.hash());
+    let outdated_tx = gen_l2_tx(account0, Nonce(0));
+    mempool.insert(
+        vec![
+            outdated_tx.clone(),
+            gen_l2_tx(account0, Nonce(1)),
+            gen_l2_tx(account0, Nonce(2)),
+        ],
+        HashMap::new(),
+    );
+    // The mempool is full, so `account1` with a nonce gap is purged.
+    let mempool_info = mempool.get_mempool_info();
+    assert_eq!(mempool_info.purged_accounts, [account1]);
+    assert_eq!(
+        HashSet::<_>::from_iter(mempool_info.dropped_transactions),
+        HashSet::<_>::from_iter([outdated_tx.hash(), purged_tx.hash()])
+    );
+}
+
 fn gen_l2_tx(address: Address, nonce: Nonce) -> Transaction {
     gen_l2_tx_with_timestamp(address, nonce, unix_timestamp_ms())
 }
@@ -381,9 +532,28 @@ fn gen_l2_tx_with_timestamp(address: Address, nonce: Nonce, received_at_ms: u64)
         Default::default(),
     );
     txn.received_timestamp_ms = received_at_ms;
+    txn.set_input(vec![], H256::random());
     txn.into()
 }
 
+fn gen_l2_tx_with_fee(
+    address: Address,
+    nonce: Nonce,
+    received_at_ms: u64,
+    max_fee_per_gas: u64,
+    max_priority_fee_per_gas: u64,
+) -> Transaction {
+    let mut tx = gen_l2_tx_with_timestamp(address, nonce, received_at_ms);
+    match &mut tx.common_data {
+        ExecuteTransactionCommon::L2(data) => {
+            data.fee.max_fee_per_gas = max_fee_per_gas.into();
+            data.fee.max_priority_fee_per_gas = max_priority_fee_per_gas.into();
+        }
+        _ => unreachable!(),
+    }
+    tx
+}
+
 fn gen_l1_tx(priority_id: PriorityOpId) -> Transaction {
     let execute = Execute {
         contract_address: Address::repeat_byte(0x11),
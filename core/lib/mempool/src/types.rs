@@ -1,7 +1,7 @@
-use std::{cmp::Ordering, collections::HashMap};
+use std::{cmp::Ordering, collections::HashMap, time::Duration};
 
 use zksync_types::{
-    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, U256,
+    fee::Fee, fee_model::BatchFeeInput, l2::L2Tx, Address, Nonce, Transaction, H256, U256,
 };
 
 /// Pending mempool transactions of account
@@ -28,13 +28,17 @@ impl AccountTransactions {
         let nonce = transaction.common_data.nonce;
         // skip insertion if transaction is old
         if nonce < self.nonce {
+            metadata.dropped_tx_hash = Some(transaction.hash());
             return metadata;
         }
         let new_score = Self::score_for_transaction(&transaction);
-        let previous_score = self
-            .transactions
-            .insert(nonce, transaction)
-            .map(|tx| Self::score_for_transaction(&tx));
+        let tx_hash = transaction.hash();
+        let previous_tx = self.transactions.insert(nonce, transaction);
+        metadata.dropped_tx_hash = previous_tx
+            .as_ref()
+            .map(L2Tx::hash)
+            .filter(|&hash| hash != tx_hash);
+        let previous_score = previous_tx.as_ref().map(Self::score_for_transaction);
         metadata.is_new = previous_score.is_none();
         if nonce == self.nonce {
             metadata.new_score = Some(new_score);
@@ -74,6 +78,10 @@ impl AccountTransactions {
         self.transactions.len()
     }
 
+    pub fn tx_hashes(&self) -> impl Iterator<Item = H256> + '_ {
+        self.transactions.values().map(L2Tx::hash)
+    }
+
     fn score_for_transaction(transaction: &L2Tx) -> MempoolScore {
         MempoolScore {
             account: transaction.initiator_account(),
@@ -101,6 +109,29 @@ impl MempoolScore {
         self.fee_data.max_fee_per_gas >= U256::from(filter.fee_per_gas)
             && self.fee_data.gas_per_pubdata_limit >= U256::from(filter.gas_per_pubdata)
     }
+
+    /// Returns the priority fee per gas that the transaction would effectively pay at the specified base fee.
+    pub fn effective_priority_fee(&self, base_fee_per_gas: u64) -> U256 {
+        let max_priority_fee = self
+            .fee_data
+            .max_fee_per_gas
+            .saturating_sub(U256::from(base_fee_per_gas));
+        max_priority_fee.min(self.fee_data.max_priority_fee_per_gas)
+    }
+}
+
+/// Ordering of L2 transactions from different accounts in the mempool. Transactions from the same account
+/// are always ordered by nonce.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MempoolOrdering {
+    /// Transactions are ordered by the time they were received.
+    #[default]
+    Fifo,
+    /// Transactions are ordered by the effective priority fee at the current base fee; ties are broken
+    /// by the time transactions were received. Transactions waiting for `max_wait` or longer are prioritized
+    /// regardless of their fees (and are ordered by the time they were received), so that transactions
+    /// with low fees are not starved.
+    FeePriority { max_wait: Duration },
 }
 
 impl Ord for MempoolScore {
@@ -124,6 +155,9 @@ pub(crate) struct InsertionMetadata {
     pub new_score: Option<MempoolScore>,
     pub previous_score: Option<MempoolScore>,
     pub is_new: bool,
+    /// Hash of the transaction dropped by the insertion: either the replaced transaction with the same nonce,
+    /// or the inserted transaction itself if its nonce is outdated.
+    pub dropped_tx_hash: Option<H256>,
 }
 
 /// Structure that can be used by state keeper to describe
@@ -1,8 +1,11 @@
 //! Tying the Merkle tree implementation to the problem domain.
 
+use std::path::Path;
+
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_prover_interface::inputs::{PrepareBasicCircuitsJob, StorageLogMetadata};
+use zksync_storage::rocksdb;
 use zksync_types::{
     writes::{InitialStorageWrite, RepeatedStorageWrite},
     L1BatchNumber, StorageKey,
@@ -376,6 +379,31 @@ impl ZkSyncTree {
         kvs.collect()
     }
 
+    /// Computes provisional root hashes after each of the miniblocks in the next L1 batch without modifying the tree.
+    /// `miniblock_writes` must contain deduplicated writes performed in each miniblock, in the miniblock order.
+    ///
+    /// The hash for the last miniblock matches the root hash produced by [`Self::process_l1_batch()`]
+    /// for the batch.
+    pub fn miniblock_root_hashes(
+        &mut self,
+        miniblock_writes: &[Vec<TreeEntry<StorageKey>>],
+    ) -> Vec<ValueHash> {
+        let chunks = miniblock_writes
+            .iter()
+            .map(|writes| {
+                writes
+                    .iter()
+                    .map(|entry| entry.map_key(StorageKey::hashed_key_u256))
+                    .collect()
+            })
+            .collect();
+        if let Some(thread_pool) = &self.thread_pool {
+            thread_pool.install(|| self.tree.provisional_root_hashes(chunks))
+        } else {
+            self.tree.provisional_root_hashes(chunks)
+        }
+    }
+
     /// Reverts the tree to a previous state.
     ///
     /// This method will overwrite all unsaved changes in the tree.
@@ -430,6 +458,16 @@ impl ZkSyncTreeReader {
         self.0.latest_root().leaf_count()
     }
 
+    /// Creates a consistent checkpoint of the tree RocksDB at the specified `path`, which must not exist.
+    /// The checkpoint only contains changes saved via [`ZkSyncTree::save()`].
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        self.0.db.create_checkpoint(path)
+    }
+
     /// Reads entries together with Merkle proofs with the specified keys from the tree. The entries are returned
     /// in the same order as requested.
     ///
@@ -444,4 +482,35 @@ impl ZkSyncTreeReader {
         let version = u64::from(l1_batch_number.0);
         self.0.entries_with_proofs(version, keys)
     }
+
+    /// Batched version of [`Self::entries_with_proofs()`] sharing tree traversal among keys.
+    /// See [`MerkleTree::entries_with_proofs_batched()`] for details.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn entries_with_proofs_batched(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        keys: &[Key],
+    ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.entries_with_proofs_batched(version, keys)
+    }
+
+    /// Reads up to `limit` entries with keys greater than or equal to `start_key` from the tree.
+    /// Entries are returned in the ascending key order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn entries_in_range(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        start_key: Key,
+        limit: usize,
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        self.0.entries_in_range(version, start_key, limit)
+    }
 }
@@ -4,8 +4,8 @@ use crate::{
     hasher::HasherWithStats,
     recovery::MerkleTreeRecovery,
     storage::{LoadAncestorsResult, SortedKeys, WorkingPatchSet},
-    types::{Nibbles, Node, TreeEntry, TreeEntryWithProof},
-    Database, HashTree, Key, MerkleTree, NoVersionError, PruneDatabase, ValueHash,
+    types::{Nibbles, Node, Root, TreeEntry, TreeEntryWithProof, TREE_DEPTH},
+    utils, Database, HashTree, Key, MerkleTree, NoVersionError, PruneDatabase, ValueHash,
 };
 
 impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
@@ -55,21 +55,167 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
             },
         )
     }
+
+    /// Batched version of [`Self::entries_with_proofs()`]. Keys are processed in the ascending order, so that
+    /// the Merkle proof for a key reuses the upper part of the proof for the preceding key (namely, the part above
+    /// the deepest internal node shared by the keys) instead of traversing the tree to the root again. Duplicate keys
+    /// are only proven once. This is beneficial for large sets of keys, especially ones sharing key prefixes.
+    /// The entries are returned in the same order as requested, and are equivalent to ones returned by
+    /// [`Self::entries_with_proofs()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn entries_with_proofs_batched(
+        &self,
+        version: u64,
+        leaf_keys: &[Key],
+    ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
+        let root = load_root(&self.db, version)?;
+        let sorted_keys = SortedKeys::new(leaf_keys.iter().copied());
+        let mut patch_set = WorkingPatchSet::new(version, root);
+        let LoadAncestorsResult {
+            longest_prefixes, ..
+        } = patch_set.load_ancestors(&sorted_keys, &self.db);
+
+        let mut hasher = HasherWithStats::new(&self.hasher);
+        let mut entries: Vec<Option<TreeEntryWithProof>> = vec![None; leaf_keys.len()];
+        // Index of the previously proven entry in `entries`.
+        let mut prev_idx = None;
+        for (idx, leaf_key) in sorted_keys.iter() {
+            let longest_prefix = &longest_prefixes[idx];
+            let prev = prev_idx.map(|prev_idx: usize| {
+                let prev_entry = entries[prev_idx].as_ref().unwrap();
+                (prev_entry, longest_prefixes[prev_idx].nibble_count())
+            });
+            if let Some((prev_entry, _)) = prev.filter(|(entry, _)| entry.base.key == leaf_key) {
+                entries[idx] = Some(prev_entry.clone());
+                continue;
+            }
+
+            // Levels `1..=4 * shared_nibble_count` of the Merkle path are shared with the preceding key.
+            let shared_nibble_count = prev.map_or(0, |(prev_entry, prev_prefix_len)| {
+                let common_nibbles = utils::find_diverging_bit(prev_entry.base.key, leaf_key) / 4;
+                common_nibbles
+                    .min(prev_prefix_len)
+                    .min(longest_prefix.nibble_count())
+            });
+            let (leaf, mut merkle_path) =
+                patch_set.create_proof(&mut hasher, leaf_key, longest_prefix, shared_nibble_count);
+            if let Some((prev_entry, _)) = prev {
+                let prev_path = &prev_entry.merkle_path;
+                for level in (1..=4 * shared_nibble_count).rev() {
+                    // Hashes at the lower levels of proofs are omitted if they correspond to empty subtrees.
+                    let empty_hash = hasher.empty_subtree_hash(TREE_DEPTH - level);
+                    let hash = (level <= prev_path.len())
+                        .then(|| prev_path[prev_path.len() - level])
+                        .filter(|hash| *hash != empty_hash);
+                    merkle_path.push(&hasher, hash);
+                }
+            }
+
+            let value = leaf
+                .as_ref()
+                .map_or_else(ValueHash::zero, |leaf| leaf.value_hash);
+            let entry = TreeEntry {
+                key: leaf_key,
+                value,
+                leaf_index: leaf.map_or(0, |leaf| leaf.leaf_index),
+            };
+            entries[idx] = Some(entry.with_merkle_path(merkle_path.into_inner()));
+            prev_idx = Some(idx);
+        }
+        Ok(entries.into_iter().map(Option::unwrap).collect())
+    }
+
+    /// Reads up to `limit` entries with keys greater than or equal to `start_key` from the tree.
+    /// Entries are returned in the ascending key order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn entries_in_range(
+        &self,
+        version: u64,
+        start_key: Key,
+        limit: usize,
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        let root = load_root(&self.db, version)?;
+        let mut entries = vec![];
+        if let Root::Filled { node, .. } = root {
+            collect_leaves(
+                &self.db,
+                node,
+                Nibbles::EMPTY,
+                &start_key,
+                limit,
+                &mut entries,
+            );
+        }
+        Ok(entries)
+    }
 }
 
-fn load_and_transform_entries<T>(
+/// Traverses the subtree rooted at `node` in the ascending key order, collecting leaves with keys
+/// not less than `start_key` until `limit` is reached.
+fn collect_leaves(
     db: &impl Database,
-    version: u64,
-    leaf_keys: &[Key],
-    mut transform: impl FnMut(&mut WorkingPatchSet, &Key, &Nibbles) -> T,
-) -> Result<Vec<T>, NoVersionError> {
-    let root = db.root(version).ok_or_else(|| {
+    node: Node,
+    nibbles: Nibbles,
+    start_key: &Key,
+    limit: usize,
+    entries: &mut Vec<TreeEntry>,
+) {
+    match node {
+        Node::Leaf(leaf) => {
+            if leaf.full_key >= *start_key && entries.len() < limit {
+                entries.push(leaf.into());
+            }
+        }
+        Node::Internal(node) => {
+            let nibble_count = nibbles.nibble_count();
+            // If the node is on the path to `start_key`, children with lesser nibbles only contain lesser keys.
+            let min_nibble = if nibbles == Nibbles::new(start_key, nibble_count) {
+                Nibbles::nibble(start_key, nibble_count)
+            } else {
+                0
+            };
+            for (nibble, child_ref) in node.children() {
+                if entries.len() >= limit {
+                    return;
+                }
+                if nibble < min_nibble {
+                    continue;
+                }
+                let child_nibbles = nibbles.push(nibble).expect("nibbles overflow");
+                let child_key = child_nibbles.with_version(child_ref.version);
+                let child = db
+                    .tree_node(&child_key, child_ref.is_leaf)
+                    .unwrap_or_else(|| panic!("node at {child_key} is missing"));
+                collect_leaves(db, child, child_nibbles, start_key, limit, entries);
+            }
+        }
+    }
+}
+
+fn load_root(db: &impl Database, version: u64) -> Result<Root, NoVersionError> {
+    db.root(version).ok_or_else(|| {
         let manifest = db.manifest().unwrap_or_default();
         NoVersionError {
             missing_version: version,
             version_count: manifest.version_count,
         }
-    })?;
+    })
+}
+
+fn load_and_transform_entries<T>(
+    db: &impl Database,
+    version: u64,
+    leaf_keys: &[Key],
+    mut transform: impl FnMut(&mut WorkingPatchSet, &Key, &Nibbles) -> T,
+) -> Result<Vec<T>, NoVersionError> {
+    let root = load_root(db, version)?;
     let sorted_keys = SortedKeys::new(leaf_keys.iter().copied());
     let mut patch_set = WorkingPatchSet::new(version, root);
     let LoadAncestorsResult {
@@ -149,4 +295,41 @@ mod tests {
         assert!(entries[1].base.is_empty());
         entries[1].verify(&tree.hasher, output.root_hash);
     }
+
+    #[test]
+    fn entries_in_range() {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        let entries = tree.entries_in_range(0, Key::zero(), 10);
+        assert!(entries.is_err());
+        tree.extend(vec![]);
+        let entries = tree.entries_in_range(0, Key::zero(), 10).unwrap();
+        assert!(entries.is_empty());
+
+        let tree_entries: Vec<_> = (1_u64..=100)
+            .map(|i| {
+                TreeEntry::new(
+                    Key::from(i * 0x1_0000_0001),
+                    i,
+                    ValueHash::from_low_u64_be(i),
+                )
+            })
+            .collect();
+        tree.extend(tree_entries.clone());
+
+        let entries = tree.entries_in_range(1, Key::zero(), 1_000).unwrap();
+        assert_eq!(entries, tree_entries);
+        let entries = tree.entries_in_range(1, Key::zero(), 10).unwrap();
+        assert_eq!(entries, tree_entries[..10]);
+        let start_key = tree_entries[20].key;
+        let entries = tree.entries_in_range(1, start_key, 10).unwrap();
+        assert_eq!(entries, tree_entries[20..30]);
+        let entries = tree.entries_in_range(1, start_key + 1, 10).unwrap();
+        assert_eq!(entries, tree_entries[21..31]);
+        let entries = tree.entries_in_range(1, Key::MAX, 10).unwrap();
+        assert!(entries.is_empty());
+
+        // Older tree versions should still be accessible.
+        let entries = tree.entries_in_range(0, Key::zero(), 10).unwrap();
+        assert!(entries.is_empty());
+    }
 }
@@ -239,6 +239,24 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         self.db.apply_patch(patch);
         output
     }
+
+    /// Computes root hashes the tree would have after sequentially extending it with each of the provided
+    /// chunks of entries. Unlike [`Self::extend()`], this method does not modify the tree; intermediate
+    /// changes are held in RAM and discarded once the method returns.
+    pub fn provisional_root_hashes(&mut self, chunks: Vec<Vec<TreeEntry>>) -> Vec<ValueHash> {
+        let mut scratch_db = Patched::new(&mut self.db);
+        let mut next_version = scratch_db.manifest().unwrap_or_default().version_count;
+        let mut root_hashes = Vec::with_capacity(chunks.len());
+        for entries in chunks {
+            let storage = Storage::new(&scratch_db, &self.hasher, next_version, true);
+            let (output, patch) = storage.extend(entries);
+            scratch_db.apply_patch(patch);
+            root_hashes.push(output.root_hash);
+            next_version += 1;
+        }
+        // `scratch_db` is dropped without flushing, so the tree remains unchanged.
+        root_hashes
+    }
 }
 
 #[cfg(test)]
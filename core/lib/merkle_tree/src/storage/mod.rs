@@ -402,6 +402,11 @@ impl SortedKeys {
         keys.sort_unstable_by_key(|(_, key)| *key);
         Self(keys)
     }
+
+    /// Iterates over keys in the ascending order, together with their indices in the original sequence.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, Key)> + '_ {
+        self.0.iter().copied()
+    }
 }
 
 /// Outcome of traversing a tree for a specific key.
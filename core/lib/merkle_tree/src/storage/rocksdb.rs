@@ -133,6 +133,16 @@ impl RocksDBWrapper {
         })
     }
 
+    /// Creates a consistent checkpoint of the database at the specified `path`, which must not exist.
+    /// Only changes flushed to RocksDB are included into the checkpoint.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        self.db.create_checkpoint(path)
+    }
+
     /// Returns the wrapped RocksDB instance.
     pub fn into_inner(self) -> RocksDB<MerkleTreeColumnFamily> {
         self.db
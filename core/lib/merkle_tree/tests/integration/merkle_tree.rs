@@ -110,6 +110,39 @@ fn entry_proofs_are_computed_correctly_on_empty_tree(kv_count: u64) {
     }
 }
 
+#[test_casing(8, KV_COUNTS)]
+fn batched_entry_proofs_match_unbatched_ones(kv_count: u64) {
+    const RNG_SEED: u64 = 321;
+
+    let mut rng = StdRng::seed_from_u64(RNG_SEED);
+    let mut tree = MerkleTree::new(PatchSet::default());
+    let kvs = generate_key_value_pairs(0..kv_count);
+    let expected_hash = compute_tree_hash(kvs.iter().copied());
+    tree.extend(kvs.clone());
+
+    // Mix existing keys, keys adjacent to existing ones and duplicate keys.
+    let existing_keys = kvs.iter().map(|entry| entry.key);
+    let adjacent_keys = kvs
+        .iter()
+        .map(|entry| entry.key ^ (U256::one() << rng.gen_range(0..256)));
+    let duplicate_keys = kvs.iter().take(3).map(|entry| entry.key);
+    let mut keys: Vec<_> = existing_keys
+        .chain(adjacent_keys)
+        .chain(duplicate_keys)
+        .collect();
+    keys.shuffle(&mut rng);
+
+    let entries = tree.entries_with_proofs(0, &keys).unwrap();
+    let batched_entries = tree.entries_with_proofs_batched(0, &keys).unwrap();
+    assert_eq!(batched_entries.len(), keys.len());
+    for ((key, entry), batched_entry) in keys.iter().zip(entries).zip(batched_entries) {
+        assert_eq!(batched_entry.base.key, *key);
+        assert_eq!(batched_entry.base, entry.base);
+        assert_eq!(batched_entry.merkle_path, entry.merkle_path);
+        batched_entry.verify(&Blake2Hasher, expected_hash);
+    }
+}
+
 #[test]
 fn proofs_are_computed_correctly_for_mixed_instructions() {
     const RNG_SEED: u64 = 123;
@@ -234,6 +267,28 @@ fn entry_proofs_are_computed_correctly_with_intermediate_commits(chunk_size: usi
     }
 }
 
+#[test_casing(4, [3, 10, 17, 42])]
+fn provisional_root_hashes_match_intermediate_commits(chunk_size: usize) {
+    let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
+    let (initial_update, final_update) = kvs.split_at(50);
+    let mut tree = MerkleTree::new(PatchSet::default());
+    let initial_root_hash = tree.extend(initial_update.to_vec()).root_hash;
+
+    let chunks: Vec<_> = final_update.chunks(chunk_size).map(<[_]>::to_vec).collect();
+    let provisional_hashes = tree.provisional_root_hashes(chunks.clone());
+    assert_eq!(provisional_hashes.len(), chunks.len());
+    assert_eq!(provisional_hashes.last(), Some(expected_hash));
+    // The tree must not be modified.
+    assert_eq!(tree.latest_version(), Some(0));
+    assert_eq!(tree.latest_root_hash(), initial_root_hash);
+
+    let hashes: Vec<_> = chunks
+        .into_iter()
+        .map(|chunk| tree.extend(chunk).root_hash)
+        .collect();
+    assert_eq!(hashes, provisional_hashes);
+}
+
 fn test_accumulated_commits<DB: Database>(db: DB, chunk_size: usize) -> DB {
     let (kvs, expected_hash) = &*ENTRIES_AND_HASH;
     let mut db = Patched::new(db);
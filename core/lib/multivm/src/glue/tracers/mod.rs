@@ -22,6 +22,10 @@
 //! For `MultiVMTracer` to be implemented, the Tracer must implement all N currently
 //! existing sub-traits.
 //!
+//! Tracers that don't need access to VM-specific state can implement the version-agnostic
+//! [`LifecycleTracer`](crate::tracers::LifecycleTracer) instead and be composed using
+//! a [`TracerChain`](crate::tracers::TracerChain), which implements `MultiVMTracer`.
+//!
 //! ## Adding a new VM version
 //!
 //! To add support for one more VM version to MultiVMTracer, one needs to:
@@ -46,6 +46,8 @@ impl GlueFrom<crate::vm_m5::vm_instance::VmBlockResult> for crate::interface::Fi
                     gas_remaining: value.full_result.gas_remaining,
                     pubdata_published: 0,
                     circuit_statistic: Default::default(),
+                    storage_accesses: Default::default(),
+                    circuit_family_gas: Default::default(),
                 },
                 refunds: Refunds::default(),
             },
@@ -74,6 +76,7 @@ impl GlueFrom<crate::vm_m5::vm_instance::VmBlockResult> for crate::interface::Fi
             },
             final_bootloader_memory: None,
             pubdata_input: None,
+            pubdata_by_contract: None,
         }
     }
 }
@@ -108,6 +111,8 @@ impl GlueFrom<crate::vm_m6::vm_instance::VmBlockResult> for crate::interface::Fi
                     gas_remaining: value.full_result.gas_remaining,
                     pubdata_published: 0,
                     circuit_statistic: Default::default(),
+                    storage_accesses: Default::default(),
+                    circuit_family_gas: Default::default(),
                 },
                 refunds: Refunds::default(),
             },
@@ -136,6 +141,7 @@ impl GlueFrom<crate::vm_m6::vm_instance::VmBlockResult> for crate::interface::Fi
             },
             final_bootloader_memory: None,
             pubdata_input: None,
+            pubdata_by_contract: None,
         }
     }
 }
@@ -168,6 +174,8 @@ impl GlueFrom<crate::vm_1_3_2::vm_instance::VmBlockResult> for crate::interface:
                     gas_remaining: value.full_result.gas_remaining,
                     pubdata_published: 0,
                     circuit_statistic: Default::default(),
+                    storage_accesses: Default::default(),
+                    circuit_family_gas: Default::default(),
                 },
                 refunds: Refunds::default(),
             },
@@ -196,6 +204,7 @@ impl GlueFrom<crate::vm_1_3_2::vm_instance::VmBlockResult> for crate::interface:
             },
             final_bootloader_memory: None,
             pubdata_input: None,
+            pubdata_by_contract: None,
         }
     }
 }
@@ -242,6 +251,8 @@ impl GlueFrom<crate::vm_1_3_2::vm_instance::VmBlockResult>
                 gas_remaining: value.full_result.gas_remaining,
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
+                storage_accesses: Default::default(),
+                circuit_family_gas: Default::default(),
             },
             refunds: Refunds::default(),
         }
@@ -274,6 +285,8 @@ impl GlueFrom<crate::vm_m5::vm_instance::VmBlockResult>
                 gas_remaining: value.full_result.gas_remaining,
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
+                storage_accesses: Default::default(),
+                circuit_family_gas: Default::default(),
             },
             refunds: Refunds::default(),
         }
@@ -322,6 +335,8 @@ impl GlueFrom<crate::vm_m6::vm_instance::VmBlockResult>
                 gas_remaining: value.full_result.gas_remaining,
                 pubdata_published: 0,
                 circuit_statistic: Default::default(),
+                storage_accesses: Default::default(),
+                circuit_family_gas: Default::default(),
             },
             refunds: Refunds::default(),
         }
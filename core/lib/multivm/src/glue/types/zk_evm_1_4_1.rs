@@ -1,11 +1,14 @@
 use zk_evm_1_4_1::{
     aux_structures::{LogQuery as LogQuery_1_4_1, Timestamp as Timestamp_1_4_1},
-    zkevm_opcode_defs::FarCallOpcode as FarCallOpcode_1_4_1,
+    zkevm_opcode_defs::{FarCallOpcode as FarCallOpcode_1_4_1, Opcode, RetOpcode},
 };
 use zksync_types::zk_evm_types::{FarCallOpcode, LogQuery, Timestamp};
 use zksync_utils::u256_to_h256;
 
-use crate::glue::{GlueFrom, GlueInto};
+use crate::{
+    glue::{GlueFrom, GlueInto},
+    tracers::lifecycle::{FarCallOutcome, OpcodeKind},
+};
 
 impl GlueFrom<FarCallOpcode_1_4_1> for FarCallOpcode {
     fn glue_from(value: FarCallOpcode_1_4_1) -> Self {
@@ -17,6 +20,39 @@ impl GlueFrom<FarCallOpcode_1_4_1> for FarCallOpcode {
     }
 }
 
+impl GlueFrom<Opcode> for OpcodeKind {
+    fn glue_from(value: Opcode) -> Self {
+        match value {
+            Opcode::Invalid(_) => Self::Invalid,
+            Opcode::Nop(_) => Self::Nop,
+            Opcode::Add(_) => Self::Add,
+            Opcode::Sub(_) => Self::Sub,
+            Opcode::Mul(_) => Self::Mul,
+            Opcode::Div(_) => Self::Div,
+            Opcode::Jump(_) => Self::Jump,
+            Opcode::Context(_) => Self::Context,
+            Opcode::Shift(_) => Self::Shift,
+            Opcode::Binop(_) => Self::Binop,
+            Opcode::Ptr(_) => Self::Ptr,
+            Opcode::NearCall(_) => Self::NearCall,
+            Opcode::Log(_) => Self::Log,
+            Opcode::FarCall(_) => Self::FarCall,
+            Opcode::Ret(_) => Self::Ret,
+            Opcode::UMA(_) => Self::Uma,
+        }
+    }
+}
+
+impl GlueFrom<RetOpcode> for FarCallOutcome {
+    fn glue_from(value: RetOpcode) -> Self {
+        match value {
+            RetOpcode::Ok => Self::Ok,
+            RetOpcode::Revert => Self::Revert,
+            RetOpcode::Panic => Self::Panic,
+        }
+    }
+}
+
 impl GlueFrom<Timestamp_1_4_1> for Timestamp {
     fn glue_from(value: Timestamp_1_4_1) -> Timestamp {
         Timestamp(value.0)
@@ -47,8 +47,10 @@
 //! let result = vm.execute(multivm::interface::VmExecutionMode::Batch);
 //! ```
 
+use std::collections::HashMap;
+
 use zksync_state::StoragePtr;
-use zksync_types::Transaction;
+use zksync_types::{Transaction, H256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
 use crate::{
@@ -126,6 +128,23 @@ pub trait VmInterface<S, H: HistoryMode> {
         VmExecutionResultAndLogs,
     );
 
+    /// Execute transaction with bytecode compression using custom tracers, reusing bytecodes compressed
+    /// in advance. `compressed_bytecodes` maps hashes of transaction factory deps to compressed bytecodes;
+    /// factory deps missing from the map are compressed by the VM.
+    ///
+    /// By default, precompressed bytecodes are ignored, which is fine for legacy VM versions.
+    fn inspect_transaction_with_precompressed_bytecodes(
+        &mut self,
+        tracer: Self::TracerDispatcher,
+        tx: Transaction,
+        _compressed_bytecodes: &HashMap<H256, Vec<u8>>,
+    ) -> (
+        Result<(), BytecodeCompressionError>,
+        VmExecutionResultAndLogs,
+    ) {
+        self.inspect_transaction_with_bytecode_compression(tracer, tx, true)
+    }
+
     /// Record VM memory metrics.
     fn record_vm_memory_metrics(&self) -> VmMemoryMetrics;
 
@@ -143,6 +162,7 @@ pub trait VmInterface<S, H: HistoryMode> {
             final_execution_state: execution_state,
             final_bootloader_memory: Some(bootloader_memory),
             pubdata_input: None,
+            pubdata_by_contract: None,
         }
     }
 }
@@ -1,6 +1,6 @@
 use std::fmt::{Debug, Display};
 
-use zksync_types::U256;
+use zksync_types::{api, web3::types::Bytes, U256};
 
 #[derive(Debug, thiserror::Error)]
 pub enum VmRevertReasonParsingError {
@@ -29,6 +29,7 @@ pub enum VmRevertReason {
 
 impl VmRevertReason {
     const GENERAL_ERROR_SELECTOR: &'static [u8] = &[0x08, 0xc3, 0x79, 0xa0];
+    const PANIC_SELECTOR: &'static [u8] = &[0x4e, 0x48, 0x7b, 0x71];
     fn parse_general_error(raw_bytes: &[u8]) -> Result<Self, VmRevertReasonParsingError> {
         let bytes = &raw_bytes[4..];
         if bytes.len() < 32 {
@@ -83,6 +84,36 @@ impl VmRevertReason {
         }
     }
 
+    /// Decodes this reason for the API. Returns `None` for reasons not produced by contracts.
+    pub fn to_api_revert_reason(&self) -> Option<api::RevertReason> {
+        match self {
+            Self::General { msg, .. } => Some(api::RevertReason::Error {
+                message: msg.clone(),
+            }),
+            Self::Unknown {
+                function_selector,
+                data,
+            } => {
+                let args = if function_selector == Self::GENERAL_ERROR_SELECTOR {
+                    // Malformed `Error(string)` data; the selector is stripped from it (see the `From<&[u8]>` impl).
+                    data.as_slice()
+                } else {
+                    data.get(function_selector.len()..).unwrap_or_default()
+                };
+                if function_selector == Self::PANIC_SELECTOR && args.len() == 32 {
+                    return Some(api::RevertReason::Panic {
+                        code: U256::from_big_endian(args),
+                    });
+                }
+                Some(api::RevertReason::Custom {
+                    selector: Bytes(function_selector.clone()),
+                    data: Bytes(args.to_vec()),
+                })
+            }
+            Self::InnerTxError | Self::VmError => None,
+        }
+    }
+
     fn try_from_bytes(bytes: &[u8]) -> Result<Self, VmRevertReasonParsingError> {
         if bytes.len() < 4 {
             // Note, that when the method reverts with no data
@@ -167,6 +198,8 @@ impl Display for VmRevertReason {
 
 #[cfg(test)]
 mod tests {
+    use zksync_types::{api, web3::types::Bytes};
+
     use super::VmRevertReason;
 
     #[test]
@@ -190,6 +223,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn decoding_revert_reasons_for_api() {
+        let mut panic_data = vec![0x4e, 0x48, 0x7b, 0x71];
+        panic_data.extend_from_slice(&[0; 31]);
+        panic_data.push(0x11);
+        let reason = VmRevertReason::from(panic_data.as_slice());
+        assert_eq!(
+            reason.to_api_revert_reason(),
+            Some(api::RevertReason::Panic {
+                code: 0x11_u32.into()
+            })
+        );
+
+        let mut custom_data = vec![1, 2, 3, 4];
+        custom_data.extend_from_slice(&[0xff; 32]);
+        let reason = VmRevertReason::from(custom_data.as_slice());
+        assert_eq!(
+            reason.to_api_revert_reason(),
+            Some(api::RevertReason::Custom {
+                selector: Bytes(vec![1, 2, 3, 4]),
+                data: Bytes(vec![0xff; 32]),
+            })
+        );
+
+        let reason = VmRevertReason::from(&[] as &[u8]);
+        assert_eq!(
+            reason.to_api_revert_reason(),
+            Some(api::RevertReason::Custom {
+                selector: Bytes(vec![]),
+                data: Bytes(vec![]),
+            })
+        );
+        assert_eq!(VmRevertReason::VmError.to_api_revert_reason(), None);
+    }
+
     #[test]
     fn revert_reason_with_wrong_function_selector() {
         let msg = vec![
@@ -102,6 +102,8 @@ impl VmExecutionResultAndLogs {
             computational_gas_used: self.statistics.computational_gas_used,
             pubdata_published: self.statistics.pubdata_published,
             circuit_statistic: self.statistics.circuit_statistic,
+            storage_accesses: self.statistics.storage_accesses,
+            circuit_family_gas: self.statistics.circuit_family_gas,
         }
     }
 }
@@ -1,3 +1,5 @@
+use zksync_types::block::ContractPubdata;
+
 use super::{BootloaderMemory, CurrentExecutionState, VmExecutionResultAndLogs};
 
 /// State of the VM after the batch execution.
@@ -10,4 +12,6 @@ pub struct FinishedL1Batch {
     /// Memory of the bootloader with all executed transactions. Could be optional for old versions of the VM.
     pub final_bootloader_memory: Option<BootloaderMemory>,
     pub pubdata_input: Option<Vec<u8>>,
+    /// Pubdata published in the batch attributed to the contracts that caused it. Not tracked by old versions of the VM.
+    pub pubdata_by_contract: Option<Vec<ContractPubdata>>,
 }
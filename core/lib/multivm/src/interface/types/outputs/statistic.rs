@@ -1,4 +1,7 @@
-use zksync_types::circuit::CircuitStatistic;
+use zksync_types::{
+    circuit::{CircuitFamilyGas, CircuitStatistic},
+    tx::tx_execution_info::StorageAccessStatistic,
+};
 
 /// Statistics of the tx execution.
 #[derive(Debug, Default, Clone)]
@@ -17,6 +20,10 @@ pub struct VmExecutionStatistics {
     pub total_log_queries: usize,
     pub pubdata_published: u32,
     pub circuit_statistic: CircuitStatistic,
+    /// Cold / warm storage slot accesses during the tx execution. Only tracked by the latest VM version.
+    pub storage_accesses: StorageAccessStatistic,
+    /// Computational gas spent per circuit family during the tx execution. Only tracked by the latest VM version.
+    pub circuit_family_gas: CircuitFamilyGas,
 }
 
 /// Oracle metrics of the VM.
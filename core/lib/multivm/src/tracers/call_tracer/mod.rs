@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use once_cell::sync::OnceCell;
-use zksync_types::vm_trace::Call;
+use zksync_types::{
+    vm_trace::{Call, CallType},
+    zk_evm_types::FarCallOpcode,
+};
 
 use crate::{glue::tracers::IntoOldVmTracer, tracers::call_tracer::metrics::CALL_METRICS};
 
@@ -45,6 +48,13 @@ impl CallTracer {
         }
     }
 
+    /// Marks a normal far call made with the static flag as a static call.
+    fn mark_static_call(call: &mut Call, is_static: bool) {
+        if is_static && call.r#type == CallType::Call(FarCallOpcode::Normal) {
+            call.r#type = CallType::StaticCall;
+        }
+    }
+
     fn extract_result(&mut self) -> Vec<Call> {
         std::mem::take(&mut self.stack)
             .into_iter()
@@ -2,7 +2,7 @@ use zk_evm_1_3_3::{
     tracing::{AfterExecutionData, VmLocalStateData},
     zkevm_opcode_defs::{
         FarCallABI, FatPointer, Opcode, RetOpcode, CALL_IMPLICIT_CALLDATA_FAT_PTR_REGISTER,
-        RET_IMPLICIT_RETURNDATA_PARAMS_REGISTER,
+        FAR_CALL_STATIC_FLAG_IDX, RET_IMPLICIT_RETURNDATA_PARAMS_REGISTER,
     },
 };
 use zksync_state::{StoragePtr, WriteStorage};
@@ -45,6 +45,7 @@ impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for CallTracer {
                     .map(|call| call.ergs_remaining + current_ergs)
                     .unwrap_or(current_ergs);
 
+                let is_static = data.opcode.variant.flags[FAR_CALL_STATIC_FLAG_IDX];
                 let mut current_call = Call {
                     r#type: CallType::Call(far_call.glue_into()),
                     gas: 0,
@@ -53,6 +54,7 @@ impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for CallTracer {
                 };
 
                 self.handle_far_call_op_code_virtual_blocks(state, data, memory, &mut current_call);
+                Self::mark_static_call(&mut current_call, is_static);
                 self.push_call_and_update_stats(current_call, 0);
             }
             Opcode::Ret(ret_code) => {
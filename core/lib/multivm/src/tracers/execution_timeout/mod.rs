@@ -0,0 +1,75 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use crate::{glue::tracers::IntoOldVmTracer, interface::Halt};
+
+pub mod vm_1_4_1;
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Number of VM cycles between consecutive deadline checks. Checking the wall clock on each cycle
+/// would noticeably slow down the VM.
+const CYCLES_PER_CHECK: usize = 1_024;
+
+/// Tracer stopping the VM execution once the wall-clock deadline is reached.
+///
+/// Since the wall-clock time is not deterministic, this tracer must only be used when halting the execution doesn't
+/// influence the produced state, e.g. to reject a transaction that was never included into a block.
+///
+/// Clones of the tracer share the flag indicating whether the execution was stopped, so that a clone retained
+/// by the caller can be queried after the execution via [`Self::is_triggered()`].
+#[derive(Debug, Clone)]
+pub struct ExecutionTimeout {
+    deadline: Instant,
+    cycles_since_check: usize,
+    is_triggered: Arc<AtomicBool>,
+}
+
+impl ExecutionTimeout {
+    pub fn new(deadline: Instant) -> Self {
+        Self {
+            deadline,
+            cycles_since_check: 0,
+            is_triggered: Arc::default(),
+        }
+    }
+
+    /// Returns whether the tracer has stopped the VM execution. A transaction finishing its execution
+    /// after the deadline without being stopped doesn't trigger the tracer.
+    pub fn is_triggered(&self) -> bool {
+        self.is_triggered.load(Ordering::Relaxed)
+    }
+
+    /// Returns the reason the VM execution is halted with once the deadline is reached.
+    pub fn halt_reason() -> Halt {
+        Halt::TracerCustom("Transaction execution timed out".to_string())
+    }
+
+    /// Checks whether the deadline is reached; if it is, marks the tracer as triggered.
+    fn is_expired(&self) -> bool {
+        let is_expired = Instant::now() >= self.deadline;
+        if is_expired {
+            self.is_triggered.store(true, Ordering::Relaxed);
+        }
+        is_expired
+    }
+
+    /// Returns whether the deadline is reached, only checking the wall clock every [`CYCLES_PER_CHECK`] cycles.
+    fn check_after_cycle(&mut self) -> bool {
+        self.cycles_since_check += 1;
+        if self.cycles_since_check < CYCLES_PER_CHECK {
+            return false;
+        }
+        self.cycles_since_check = 0;
+        self.is_expired()
+    }
+}
+
+impl IntoOldVmTracer for ExecutionTimeout {}
@@ -0,0 +1,22 @@
+use zksync_state::WriteStorage;
+
+use crate::{
+    interface::dyn_tracers::vm_1_3_3::DynTracer,
+    tracers::execution_timeout::ExecutionTimeout,
+    vm_virtual_blocks::{
+        ExecutionEndTracer, ExecutionProcessing, HistoryMode, SimpleMemory, VmTracer,
+    },
+};
+
+impl<H: HistoryMode> ExecutionEndTracer<H> for ExecutionTimeout {
+    fn should_stop_execution(&self) -> bool {
+        // This method takes `&self`, so we cannot throttle checks here.
+        self.is_expired()
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for ExecutionTimeout {}
+
+impl<S: WriteStorage, H: HistoryMode> ExecutionProcessing<S, H> for ExecutionTimeout {}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for ExecutionTimeout {}
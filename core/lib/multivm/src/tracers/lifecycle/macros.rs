@@ -0,0 +1,62 @@
+/// Implements `DynTracer` for [`TracerChain`](super::TracerChain) for a specific VM version. All VM versions
+/// supporting custom tracers expose the same information about opcodes and the call stack, so the implementations
+/// only differ in the types involved.
+macro_rules! impl_dyn_tracer {
+    ($zk_evm:ident, $dyn_tracers:ident, $vm:ident) => {
+        impl<S, H: $crate::$vm::HistoryMode>
+            $crate::interface::dyn_tracers::$dyn_tracers::DynTracer<S, $crate::$vm::SimpleMemory<H>>
+            for $crate::tracers::lifecycle::TracerChain
+        {
+            fn before_execution(
+                &mut self,
+                state: $zk_evm::tracing::VmLocalStateData<'_>,
+                data: $zk_evm::tracing::BeforeExecutionData,
+                _memory: &$crate::$vm::SimpleMemory<H>,
+                _storage: zksync_state::StoragePtr<S>,
+            ) {
+                use $crate::{glue::GlueInto, $vm::tracers::utils::VmHook};
+
+                let tx_ended = matches!(
+                    VmHook::from_opcode_memory(&state, &data),
+                    VmHook::TxHasEnded
+                );
+                let opcode = data.opcode.variant.opcode.glue_into();
+                self.on_before_opcode(opcode, impl_dyn_tracer!(@frame state), tx_ended);
+            }
+
+            fn after_execution(
+                &mut self,
+                state: $zk_evm::tracing::VmLocalStateData<'_>,
+                data: $zk_evm::tracing::AfterExecutionData,
+                _memory: &$crate::$vm::SimpleMemory<H>,
+                _storage: zksync_state::StoragePtr<S>,
+            ) {
+                use $zk_evm::zkevm_opcode_defs::Opcode;
+                use $crate::glue::GlueInto;
+
+                let opcode = data.opcode.variant.opcode;
+                let far_call = match opcode {
+                    Opcode::FarCall(call_type) => Some(call_type.glue_into()),
+                    _ => None,
+                };
+                let ret = match opcode {
+                    Opcode::Ret(ret_opcode) => Some(ret_opcode.glue_into()),
+                    _ => None,
+                };
+                let frame = impl_dyn_tracer!(@frame state);
+                self.on_after_opcode(opcode.glue_into(), frame, far_call, ret);
+            }
+        }
+    };
+
+    (@frame $state:ident) => {{
+        let callstack = &$state.vm_local_state.callstack;
+        $crate::tracers::lifecycle::FrameState {
+            this_address: callstack.current.this_address,
+            code_address: callstack.current.code_address,
+            msg_sender: callstack.current.msg_sender,
+            ergs_remaining: callstack.current.ergs_remaining,
+            depth: callstack.inner.len(),
+        }
+    }};
+}
@@ -0,0 +1,452 @@
+//! Version-agnostic tracers with typed lifecycle hooks.
+//!
+//! Implementing a [`MultiVMTracer`](crate::MultiVMTracer) directly requires implementing VM-specific tracer traits
+//! for each supported VM version. [`LifecycleTracer`] provides an alternative: it receives VM events
+//! (opcode execution, far calls, transaction and bootloader completion) converted to version-agnostic types.
+//! Multiple lifecycle tracers can be composed using a [`TracerChain`], which implements `MultiVMTracer`
+//! and thus can be used with any VM version supporting custom tracers.
+
+use std::{
+    any::Any,
+    fmt,
+    marker::PhantomData,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use zksync_types::{zk_evm_types::FarCallOpcode, Address};
+
+use crate::{glue::tracers::IntoOldVmTracer, interface::tracer::VmExecutionStopReason};
+
+#[macro_use]
+mod macros;
+pub mod vm_1_4_1;
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Kind of a VM opcode, without opcode-specific details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpcodeKind {
+    Invalid,
+    Nop,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Jump,
+    Context,
+    Shift,
+    Binop,
+    Ptr,
+    NearCall,
+    Log,
+    FarCall,
+    Ret,
+    Uma,
+}
+
+/// Execution context of an opcode passed to [`LifecycleTracer`] opcode hooks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OpcodeContext {
+    pub opcode: OpcodeKind,
+    /// Address of the contract in which context the opcode is executed.
+    pub this_address: Address,
+    /// Address of the contract whose code is executed. Differs from `this_address` for delegate calls.
+    pub code_address: Address,
+    /// Ergs remaining in the current frame.
+    pub ergs_remaining: u32,
+}
+
+/// Information about an entered far call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FarCallEnter {
+    pub call_type: FarCallOpcode,
+    /// Caller as seen by the callee (i.e., `msg.sender`).
+    pub caller: Address,
+    /// Address of the contract in which context the call is executed.
+    pub callee: Address,
+    /// Address of the contract whose code is executed.
+    pub code_address: Address,
+    /// Ergs passed to the callee.
+    pub ergs_passed: u32,
+}
+
+/// Outcome of a far call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FarCallOutcome {
+    Ok,
+    Revert,
+    Panic,
+}
+
+/// Information about an exited far call.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FarCallExit {
+    /// Address of the contract in which context the call was executed.
+    pub callee: Address,
+    pub outcome: FarCallOutcome,
+    /// Ergs remaining in the callee frame before it has exited.
+    pub ergs_remaining: u32,
+}
+
+/// VM tracer receiving version-agnostic lifecycle hooks. All hooks have no-op default implementations,
+/// so a tracer only needs to implement hooks it's interested in.
+///
+/// To use lifecycle tracers with a VM, add them to a [`TracerChain`].
+pub trait LifecycleTracer: 'static + Send {
+    /// Called before each opcode is executed.
+    fn before_opcode(&mut self, _context: &OpcodeContext) {}
+    /// Called after each opcode is executed. For far calls and returns, the context corresponds
+    /// to the frame active after the opcode execution.
+    fn after_opcode(&mut self, _context: &OpcodeContext) {}
+    /// Called after a far call frame is entered.
+    fn far_call_enter(&mut self, _call: &FarCallEnter) {}
+    /// Called after a far call frame is exited, either via a return opcode or via a panic (e.g., because
+    /// the frame has run out of ergs).
+    fn far_call_exit(&mut self, _call: &FarCallExit) {}
+    /// Called when the bootloader signals that a transaction has been processed.
+    fn tx_end(&mut self) {}
+    /// Called after the bootloader execution has stopped (e.g., after a single transaction
+    /// or the entire batch is executed, depending on the VM execution mode).
+    fn bootloader_end(&mut self, _reason: &VmExecutionStopReason) {}
+}
+
+/// Object-safe extension of [`LifecycleTracer`] allowing to downcast tracers stored in a [`TracerChain`].
+trait AnyLifecycleTracer: LifecycleTracer {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: LifecycleTracer> AnyLifecycleTracer for T {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+type BoxedLifecycleTracer = Box<dyn AnyLifecycleTracer>;
+/// Tracers stored in a [`TracerChain`]. `None` while the tracers are used by a VM.
+type SharedTracers = Arc<Mutex<Option<Vec<BoxedLifecycleTracer>>>>;
+
+fn with_stored_tracers<R>(
+    tracers: &SharedTracers,
+    action: impl FnOnce(&mut Vec<BoxedLifecycleTracer>) -> R,
+) -> R {
+    let mut tracers = tracers.lock().expect("lifecycle tracers are poisoned");
+    let tracers = tracers
+        .as_mut()
+        .expect("lifecycle tracers cannot be accessed during VM execution");
+    action(tracers)
+}
+
+/// Handle to a tracer added to a [`TracerChain`].
+pub struct LifecycleTracerHandle<T> {
+    tracers: SharedTracers,
+    index: usize,
+    _tracer: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for LifecycleTracerHandle<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("LifecycleTracerHandle")
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+impl<T: LifecycleTracer> LifecycleTracerHandle<T> {
+    /// Provides access to the tracer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called during the VM execution.
+    pub fn with<R>(&self, action: impl FnOnce(&mut T) -> R) -> R {
+        with_stored_tracers(&self.tracers, |tracers| {
+            let tracer = tracers[self.index]
+                .as_any_mut()
+                .downcast_mut::<T>()
+                .expect("unexpected lifecycle tracer type");
+            action(tracer)
+        })
+    }
+}
+
+/// Version-agnostic state of the current VM frame.
+#[derive(Debug, Clone, Copy)]
+struct FrameState {
+    this_address: Address,
+    code_address: Address,
+    msg_sender: Address,
+    ergs_remaining: u32,
+    /// Depth of the call stack, including near call frames.
+    depth: usize,
+}
+
+impl FrameState {
+    fn opcode_context(&self, opcode: OpcodeKind) -> OpcodeContext {
+        OpcodeContext {
+            opcode,
+            this_address: self.this_address,
+            code_address: self.code_address,
+            ergs_remaining: self.ergs_remaining,
+        }
+    }
+}
+
+/// Chain of [`LifecycleTracer`]s. Hooks are dispatched to tracers in the order they were added to the chain.
+///
+/// The chain is cheaply cloneable; clones share the tracers. Since VM-specific tracers are created
+/// by cloning the chain, tracers should be accessed after the VM execution via handles returned
+/// from [`Self::add()`]. During the VM execution, the tracers are exclusively owned by the VM-specific tracer,
+/// so that hooks are dispatched without synchronization.
+pub struct TracerChain {
+    tracers: SharedTracers,
+    /// Tracers taken from `tracers` for the duration of the VM execution.
+    active_tracers: Option<Vec<BoxedLifecycleTracer>>,
+    /// Callees of the far call frames entered during the VM execution, together with the call stack depth
+    /// of the frame.
+    far_call_frames: Vec<(Address, usize)>,
+    /// State of the frame before executing the last opcode.
+    frame_before_opcode: Option<FrameState>,
+}
+
+impl fmt::Debug for TracerChain {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("TracerChain")
+            .field("is_active", &self.active_tracers.is_some())
+            .field("far_call_depth", &self.far_call_frames.len())
+            .finish()
+    }
+}
+
+impl Default for TracerChain {
+    fn default() -> Self {
+        Self::from_shared(Arc::new(Mutex::new(Some(vec![]))))
+    }
+}
+
+/// Clones share the tracers, but not the execution state.
+impl Clone for TracerChain {
+    fn clone(&self) -> Self {
+        Self::from_shared(self.tracers.clone())
+    }
+}
+
+impl Drop for TracerChain {
+    fn drop(&mut self) {
+        self.release_tracers();
+    }
+}
+
+impl TracerChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn from_shared(tracers: SharedTracers) -> Self {
+        Self {
+            tracers,
+            active_tracers: None,
+            far_call_frames: vec![],
+            frame_before_opcode: None,
+        }
+    }
+
+    /// Adds a tracer to the end of the chain. Returns a handle allowing to access the tracer
+    /// (e.g., to get its results after the VM execution).
+    pub fn add<T: LifecycleTracer>(&mut self, tracer: T) -> LifecycleTracerHandle<T> {
+        let index = with_stored_tracers(&self.tracers, |tracers| {
+            tracers.push(Box::new(tracer));
+            tracers.len() - 1
+        });
+        LifecycleTracerHandle {
+            tracers: self.tracers.clone(),
+            index,
+            _tracer: PhantomData,
+        }
+    }
+
+    /// Adds a tracer to the end of the chain.
+    #[must_use]
+    pub fn with<T: LifecycleTracer>(mut self, tracer: T) -> Self {
+        self.add(tracer);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        with_stored_tracers(&self.tracers, |tracers| tracers.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn for_each(&mut self, mut action: impl FnMut(&mut dyn AnyLifecycleTracer)) {
+        let tracers = &self.tracers;
+        let active_tracers = self.active_tracers.get_or_insert_with(|| {
+            tracers
+                .lock()
+                .expect("lifecycle tracers are poisoned")
+                .take()
+                .expect("lifecycle tracers are already used by another VM")
+        });
+        for tracer in active_tracers {
+            action(tracer.as_mut());
+        }
+    }
+
+    /// Returns tracers to the shared storage, so that they can be accessed via handles.
+    fn release_tracers(&mut self) {
+        if let Some(tracers) = self.active_tracers.take() {
+            let mut stored_tracers = self.tracers.lock().unwrap_or_else(PoisonError::into_inner);
+            *stored_tracers = Some(tracers);
+        }
+        self.far_call_frames.clear();
+        self.frame_before_opcode = None;
+    }
+
+    fn on_before_opcode(&mut self, opcode: OpcodeKind, frame: FrameState, tx_ended: bool) {
+        self.frame_before_opcode = Some(frame);
+        let context = frame.opcode_context(opcode);
+        self.for_each(|tracer| tracer.before_opcode(&context));
+        if tx_ended {
+            self.for_each(|tracer| tracer.tx_end());
+        }
+    }
+
+    /// `far_call` and `ret` are set if the executed opcode is a far call or a return, respectively.
+    fn on_after_opcode(
+        &mut self,
+        opcode: OpcodeKind,
+        frame: FrameState,
+        far_call: Option<FarCallOpcode>,
+        ret: Option<FarCallOutcome>,
+    ) {
+        let frame_before_opcode = self.frame_before_opcode.take();
+        // Frames can be exited not only by return opcodes, but also by panics on any opcode (e.g., on running
+        // out of ergs), so exits are detected by the call stack depth.
+        while let Some(&(callee, depth)) = self.far_call_frames.last() {
+            if depth <= frame.depth {
+                break;
+            }
+            self.far_call_frames.pop();
+            let call = FarCallExit {
+                callee,
+                outcome: ret.unwrap_or(FarCallOutcome::Panic),
+                ergs_remaining: frame_before_opcode.map_or(0, |frame| frame.ergs_remaining),
+            };
+            self.for_each(|tracer| tracer.far_call_exit(&call));
+        }
+
+        let depth_before_opcode = frame_before_opcode.map_or(0, |frame| frame.depth);
+        if let Some(call_type) = far_call.filter(|_| frame.depth > depth_before_opcode) {
+            self.far_call_frames.push((frame.this_address, frame.depth));
+            let call = FarCallEnter {
+                call_type,
+                caller: frame.msg_sender,
+                callee: frame.this_address,
+                code_address: frame.code_address,
+                ergs_passed: frame.ergs_remaining,
+            };
+            self.for_each(|tracer| tracer.far_call_enter(&call));
+        }
+        let context = frame.opcode_context(opcode);
+        self.for_each(|tracer| tracer.after_opcode(&context));
+    }
+
+    fn on_bootloader_end(&mut self, reason: &VmExecutionStopReason) {
+        self.for_each(|tracer| tracer.bootloader_end(reason));
+        self.release_tracers();
+    }
+}
+
+impl IntoOldVmTracer for TracerChain {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default)]
+    struct CallRecorder {
+        entered: Vec<Address>,
+        exited: Vec<FarCallExit>,
+    }
+
+    impl LifecycleTracer for CallRecorder {
+        fn far_call_enter(&mut self, call: &FarCallEnter) {
+            self.entered.push(call.callee);
+        }
+
+        fn far_call_exit(&mut self, call: &FarCallExit) {
+            self.exited.push(*call);
+        }
+    }
+
+    fn frame(this_address: Address, ergs_remaining: u32, depth: usize) -> FrameState {
+        FrameState {
+            this_address,
+            code_address: this_address,
+            msg_sender: Address::zero(),
+            ergs_remaining,
+            depth,
+        }
+    }
+
+    #[test]
+    fn far_call_exits_are_detected_by_call_stack_depth() {
+        let caller = Address::repeat_byte(1);
+        let callee = Address::repeat_byte(2);
+        let mut chain = TracerChain::new();
+        let recorder = chain.add(CallRecorder::default());
+
+        chain.on_before_opcode(OpcodeKind::FarCall, frame(caller, 1_000, 1), false);
+        let far_call = Some(FarCallOpcode::Normal);
+        chain.on_after_opcode(OpcodeKind::FarCall, frame(callee, 500, 2), far_call, None);
+        // Near calls in the callee must not be treated as far call exits.
+        chain.on_before_opcode(OpcodeKind::NearCall, frame(callee, 400, 2), false);
+        chain.on_after_opcode(OpcodeKind::NearCall, frame(callee, 300, 3), None, None);
+        chain.on_before_opcode(OpcodeKind::Ret, frame(callee, 200, 3), false);
+        chain.on_after_opcode(
+            OpcodeKind::Ret,
+            frame(callee, 250, 2),
+            None,
+            Some(FarCallOutcome::Ok),
+        );
+        // The callee panics on a non-return opcode.
+        chain.on_before_opcode(OpcodeKind::Add, frame(callee, 10, 2), false);
+        chain.on_after_opcode(OpcodeKind::Add, frame(caller, 500, 1), None, None);
+        chain.on_bootloader_end(&VmExecutionStopReason::VmFinished);
+
+        recorder.with(|recorder| {
+            assert_eq!(recorder.entered, [callee]);
+            assert_eq!(
+                recorder.exited,
+                [FarCallExit {
+                    callee,
+                    outcome: FarCallOutcome::Panic,
+                    ergs_remaining: 10,
+                }]
+            );
+        });
+    }
+
+    #[test]
+    fn tracers_are_returned_to_chain_when_vm_tracer_is_dropped() {
+        let mut chain = TracerChain::new();
+        let recorder = chain.add(CallRecorder::default());
+        let mut vm_tracer = chain.clone();
+        let far_call = Some(FarCallOpcode::Normal);
+        vm_tracer.on_before_opcode(OpcodeKind::FarCall, frame(Address::zero(), 100, 1), false);
+        vm_tracer.on_after_opcode(
+            OpcodeKind::FarCall,
+            frame(Address::zero(), 50, 2),
+            far_call,
+            None,
+        );
+        drop(vm_tracer);
+
+        recorder.with(|recorder| assert_eq!(recorder.entered.len(), 1));
+        assert_eq!(chain.len(), 1);
+    }
+}
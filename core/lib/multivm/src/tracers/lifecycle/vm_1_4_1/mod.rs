@@ -0,0 +1,20 @@
+use zksync_state::WriteStorage;
+
+use crate::{
+    interface::tracer::VmExecutionStopReason,
+    tracers::lifecycle::TracerChain,
+    vm_1_4_1::{BootloaderState, HistoryMode, VmTracer, ZkSyncVmState},
+};
+
+impl_dyn_tracer!(zk_evm_1_4_1, vm_1_4_1, vm_1_4_1);
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for TracerChain {
+    fn after_vm_execution(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        stop_reason: VmExecutionStopReason,
+    ) {
+        self.on_bootloader_end(&stop_reason);
+    }
+}
@@ -0,0 +1,27 @@
+use zksync_state::WriteStorage;
+
+use crate::{
+    interface::tracer::VmExecutionStopReason,
+    tracers::lifecycle::TracerChain,
+    vm_virtual_blocks::{
+        BootloaderState, ExecutionEndTracer, ExecutionProcessing, HistoryMode, VmTracer,
+        ZkSyncVmState,
+    },
+};
+
+impl_dyn_tracer!(zk_evm_1_3_3, vm_1_3_3, vm_virtual_blocks);
+
+impl<H: HistoryMode> ExecutionEndTracer<H> for TracerChain {}
+
+impl<S: WriteStorage, H: HistoryMode> ExecutionProcessing<S, H> for TracerChain {
+    fn after_vm_execution(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        stop_reason: VmExecutionStopReason,
+    ) {
+        self.on_bootloader_end(&stop_reason);
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for TracerChain {}
@@ -1,9 +1,13 @@
 pub mod call_tracer;
+pub mod execution_timeout;
+pub mod lifecycle;
 mod multivm_dispatcher;
 pub mod old_tracers;
 pub mod storage_invocation;
 pub mod validator;
 
 pub use call_tracer::CallTracer;
+pub use execution_timeout::ExecutionTimeout;
+pub use lifecycle::{LifecycleTracer, TracerChain};
 pub use multivm_dispatcher::TracerDispatcher;
 pub use storage_invocation::StorageInvocations;
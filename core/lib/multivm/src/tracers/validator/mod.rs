@@ -1,4 +1,11 @@
-use std::{collections::HashSet, marker::PhantomData, sync::Arc};
+use std::{
+    collections::HashSet,
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+};
 
 use once_cell::sync::OnceCell;
 use zksync_state::{StoragePtr, WriteStorage};
@@ -33,8 +40,8 @@ pub struct ValidationTracer<H> {
     auxilary_allowed_slots: HashSet<H256>,
 
     user_address: Address,
-    #[allow(dead_code)]
     paymaster_address: Address,
+    paymaster_only: bool,
     should_stop_execution: bool,
     trusted_slots: HashSet<(Address, U256)>,
     trusted_addresses: HashSet<Address>,
@@ -42,6 +49,8 @@ pub struct ValidationTracer<H> {
     computational_gas_used: u32,
     computational_gas_limit: u32,
     pub result: Arc<OnceCell<ViolatedValidationRule>>,
+    /// Computational gas used by the validated step so far. Shared so that it can be read after the VM has finished.
+    pub computational_gas: Arc<AtomicU32>,
     _marker: PhantomData<fn(H) -> H>,
 }
 
@@ -58,18 +67,44 @@ impl<H> ValidationTracer<H> {
                 should_stop_execution: false,
                 user_address: params.user_address,
                 paymaster_address: params.paymaster_address,
+                paymaster_only: params.paymaster_only,
                 trusted_slots: params.trusted_slots,
                 trusted_addresses: params.trusted_addresses,
                 trusted_address_slots: params.trusted_address_slots,
                 computational_gas_used: 0,
                 computational_gas_limit: params.computational_gas_limit,
                 result: result.clone(),
+                computational_gas: Arc::default(),
                 _marker: Default::default(),
             },
             result,
         )
     }
 
+    /// Checks whether validation rules should be applied in the current validation mode.
+    fn should_check_restrictions(&self) -> bool {
+        match self.validation_mode {
+            ValidationTracerMode::UserTxValidation => !self.paymaster_only,
+            ValidationTracerMode::PaymasterTxValidation => self.paymaster_only,
+            ValidationTracerMode::NoValidation => false,
+        }
+    }
+
+    /// Returns the address which storage is considered owned by the validated step.
+    fn validated_address(&self) -> Address {
+        if self.paymaster_only {
+            self.paymaster_address
+        } else {
+            self.user_address
+        }
+    }
+
+    fn record_computational_gas(&mut self, gas: u32) {
+        self.computational_gas_used = self.computational_gas_used.saturating_add(gas);
+        self.computational_gas
+            .store(self.computational_gas_used, Ordering::Relaxed);
+    }
+
     fn process_validation_round_result(&mut self, result: ValidationRoundResult) {
         match result {
             Ok(NewTrustedValidationItems {
@@ -98,11 +133,8 @@ impl<H> ValidationTracer<H> {
         msg_sender: Address,
     ) -> bool {
         // If there are no restrictions, all storage reads are valid.
-        // We also don't support the paymaster validation for now.
-        if matches!(
-            self.validation_mode,
-            ValidationTracerMode::NoValidation | ValidationTracerMode::PaymasterTxValidation
-        ) {
+        // The paymaster validation is only restricted if explicitly requested.
+        if !self.should_check_restrictions() {
             return true;
         }
 
@@ -123,9 +155,10 @@ impl<H> ValidationTracer<H> {
             return true;
         }
 
-        // The user is allowed to touch its own slots or slots semantically related to him.
-        let valid_users_slot = address == self.user_address
-            || u256_to_account_address(&key) == self.user_address
+        // The user (or the paymaster) is allowed to touch its own slots or slots semantically related to it.
+        let validated_address = self.validated_address();
+        let valid_users_slot = address == validated_address
+            || u256_to_account_address(&key) == validated_address
             || self.auxilary_allowed_slots.contains(&u256_to_h256(key));
         if valid_users_slot {
             return true;
@@ -182,6 +215,7 @@ impl<H> ValidationTracer<H> {
             trusted_addresses: self.trusted_addresses.clone(),
             trusted_address_slots: self.trusted_address_slots.clone(),
             computational_gas_limit: self.computational_gas_limit,
+            paymaster_only: self.paymaster_only,
         }
     }
 }
@@ -35,6 +35,9 @@ pub struct ValidationTracerParams {
     pub trusted_address_slots: HashSet<(Address, U256)>,
     /// Number of computational gas that validation step is allowed to use.
     pub computational_gas_limit: u32,
+    /// If set, validation rules are checked for the paymaster validation step (with the paymaster treated
+    /// as the validated address) instead of the account validation step. This is used to debug paymasters.
+    pub paymaster_only: bool,
 }
 
 #[derive(Debug, Clone)]
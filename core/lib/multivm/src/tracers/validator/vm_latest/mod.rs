@@ -65,7 +65,7 @@ impl<H: HistoryMode> ValidationTracer<H> {
                     );
 
                     let slot_to_add =
-                        self.slot_to_add_from_keccak_call(&calldata, self.user_address);
+                        self.slot_to_add_from_keccak_call(&calldata, self.validated_address());
 
                     if let Some(slot) = slot_to_add {
                         return Ok(NewTrustedValidationItems {
@@ -73,7 +73,7 @@ impl<H: HistoryMode> ValidationTracer<H> {
                             ..Default::default()
                         });
                     }
-                } else if called_address != self.user_address {
+                } else if called_address != self.validated_address() {
                     let code_key = get_code_key(&called_address);
                     let code = storage.borrow_mut().read_value(&code_key);
 
@@ -137,11 +137,8 @@ impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H::Vm1_4_2>>
         memory: &SimpleMemory<H::Vm1_4_2>,
         storage: StoragePtr<S>,
     ) {
-        // For now, we support only validations for users.
-        if let ValidationTracerMode::UserTxValidation = self.validation_mode {
-            self.computational_gas_used = self
-                .computational_gas_used
-                .saturating_add(computational_gas_price(state, &data));
+        if self.should_check_restrictions() {
+            self.record_computational_gas(computational_gas_price(state, &data));
 
             let validation_round_result =
                 self.check_user_restrictions_vm_latest(state, data, memory, storage);
@@ -43,6 +43,8 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
             total_log_queries: total_log_queries_count,
             pubdata_published,
             circuit_statistic,
+            storage_accesses: Default::default(),
+            circuit_family_gas: Default::default(),
         }
     }
 
@@ -182,6 +182,7 @@ impl<S: WriteStorage, H: HistoryMode> VmInterface<S, H> for Vm<S, H> {
                     .clone()
                     .build_pubdata(false),
             ),
+            pubdata_by_contract: None,
         }
     }
 }
@@ -1,12 +1,18 @@
+use std::collections::HashMap;
+
 use itertools::Itertools;
 use zksync_state::{StoragePtr, WriteStorage};
-use zksync_types::U256;
+use zksync_types::{H256, U256};
 use zksync_utils::{
     bytecode::{compress_bytecode, hash_bytecode, CompressedBytecodeInfo},
     bytes_to_be_words,
 };
 
-use crate::{interface::VmInterface, vm_latest::Vm, HistoryMode};
+use crate::{
+    interface::{BytecodeCompressionError, VmExecutionMode, VmExecutionResultAndLogs, VmInterface},
+    vm_latest::{tracers::dispatcher::TracerDispatcher, Vm},
+    HistoryMode,
+};
 
 impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
     /// Checks the last transaction has successfully published compressed bytecodes and returns `true` if there is at least one is still unknown.
@@ -21,6 +27,26 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
                 .is_bytecode_known(&hash_bytecode(&info.original))
         })
     }
+
+    /// Executes the transaction pushed into the bootloader memory and checks that it has published
+    /// its compressed bytecodes.
+    pub(crate) fn inspect_pushed_transaction(
+        &mut self,
+        tracer: TracerDispatcher<S, H::Vm1_4_2>,
+    ) -> (
+        Result<(), BytecodeCompressionError>,
+        VmExecutionResultAndLogs,
+    ) {
+        let result = self.inspect_inner(tracer, VmExecutionMode::OneTx, None);
+        if self.has_unpublished_bytecodes() {
+            (
+                Err(BytecodeCompressionError::BytecodeCompressionFailed),
+                result,
+            )
+        } else {
+            (Ok(()), result)
+        }
+    }
 }
 
 /// Converts bytecode to tokens and hashes it.
@@ -33,9 +59,12 @@ pub(crate) fn bytecode_to_factory_dep(bytecode: Vec<u8>) -> (U256, Vec<U256>) {
     (bytecode_hash, bytecode_words)
 }
 
+/// Compresses bytecodes not yet known to the `storage`. `precompressed_bytecodes` (keyed by bytecode hash)
+/// are used instead of compressing the corresponding bytecodes.
 pub(crate) fn compress_bytecodes<S: WriteStorage>(
     bytecodes: &[Vec<u8>],
     storage: StoragePtr<S>,
+    precompressed_bytecodes: &HashMap<H256, Vec<u8>>,
 ) -> Vec<CompressedBytecodeInfo> {
     bytecodes
         .iter()
@@ -45,14 +74,14 @@ pub(crate) fn compress_bytecodes<S: WriteStorage>(
         .filter(|(_idx, dep)| !storage.borrow_mut().is_bytecode_known(&hash_bytecode(dep)))
         .sorted_by_key(|(idx, _dep)| *idx)
         .filter_map(|(_idx, dep)| {
-            let compressed_bytecode = compress_bytecode(dep);
-
-            compressed_bytecode
-                .ok()
-                .map(|compressed| CompressedBytecodeInfo {
-                    original: dep.clone(),
-                    compressed,
-                })
+            let compressed = match precompressed_bytecodes.get(&hash_bytecode(dep)) {
+                Some(compressed) => compressed.clone(),
+                None => compress_bytecode(dep).ok()?,
+            };
+            Some(CompressedBytecodeInfo {
+                original: dep.clone(),
+                compressed,
+            })
         })
         .collect()
 }
@@ -1,6 +1,9 @@
 use zk_evm_1_4_1::aux_structures::Timestamp;
 use zksync_state::WriteStorage;
-use zksync_types::{circuit::CircuitStatistic, U256};
+use zksync_types::{
+    circuit::{CircuitFamilyGas, CircuitStatistic},
+    U256,
+};
 
 use crate::{
     interface::{VmExecutionStatistics, VmMemoryMetrics},
@@ -25,6 +28,7 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
         pubdata_published: u32,
         total_log_queries_count: usize,
         circuit_statistic: CircuitStatistic,
+        circuit_family_gas: CircuitFamilyGas,
     ) -> VmExecutionStatistics {
         let computational_gas_used = self.calculate_computational_gas_used(
             tracer,
@@ -43,6 +47,11 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
             total_log_queries: total_log_queries_count,
             pubdata_published,
             circuit_statistic,
+            storage_accesses: self
+                .state
+                .storage
+                .storage_access_statistic_after_timestamp(timestamp_initial),
+            circuit_family_gas,
         }
     }
 
@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use zk_evm_1_4_1::aux_structures::Timestamp;
 use zksync_state::WriteStorage;
-use zksync_types::{l1::is_l1_tx_type, Transaction};
+use zksync_types::{l1::is_l1_tx_type, Transaction, H256};
 
 use crate::{
     vm_latest::{
@@ -19,6 +21,24 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
         predefined_overhead: u32,
         predefined_refund: u32,
         with_compression: bool,
+    ) {
+        let no_precompressed_bytecodes = HashMap::new();
+        self.push_raw_transaction_inner(
+            tx,
+            predefined_overhead,
+            predefined_refund,
+            with_compression.then_some(&no_precompressed_bytecodes),
+        );
+    }
+
+    /// Pushes a transaction into the bootloader memory. Bytecodes are compressed only if
+    /// `precompressed_bytecodes` are provided.
+    fn push_raw_transaction_inner(
+        &mut self,
+        tx: TransactionData,
+        predefined_overhead: u32,
+        predefined_refund: u32,
+        precompressed_bytecodes: Option<&HashMap<H256, Vec<u8>>>,
     ) {
         let timestamp = Timestamp(self.state.local_state.timestamp);
         let codes_for_decommiter = tx
@@ -27,11 +47,14 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
             .map(|dep| bytecode_to_factory_dep(dep.clone()))
             .collect();
 
-        let compressed_bytecodes = if is_l1_tx_type(tx.tx_type) || !with_compression {
+        let compressed_bytecodes = match precompressed_bytecodes {
             // L1 transactions do not need compression
-            vec![]
-        } else {
-            compress_bytecodes(&tx.factory_deps, self.state.storage.storage.get_ptr())
+            Some(precompressed_bytecodes) if !is_l1_tx_type(tx.tx_type) => compress_bytecodes(
+                &tx.factory_deps,
+                self.state.storage.storage.get_ptr(),
+                precompressed_bytecodes,
+            ),
+            _ => vec![],
         };
 
         self.state
@@ -63,4 +86,14 @@ impl<S: WriteStorage, H: HistoryMode> Vm<S, H> {
         let overhead = tx.overhead_gas();
         self.push_raw_transaction(tx, overhead, 0, with_compression);
     }
+
+    pub(crate) fn push_transaction_with_precompressed_bytecodes(
+        &mut self,
+        tx: Transaction,
+        precompressed_bytecodes: &HashMap<H256, Vec<u8>>,
+    ) {
+        let tx: TransactionData = tx.into();
+        let overhead = tx.overhead_gas();
+        self.push_raw_transaction_inner(tx, overhead, 0, Some(precompressed_bytecodes));
+    }
 }
@@ -517,10 +517,40 @@ impl<T> AppDataFrameManagerWithHistory<T, HistoryEnabled> {
 
 const PRIMITIVE_VALUE_EMPTY: PrimitiveValue = PrimitiveValue::empty();
 const PAGE_SUBDIVISION_LEN: usize = 64;
+/// Maximum number of leaves retained in [`LeafPool`].
+const MAX_POOLED_LEAVES: usize = 4_096;
+
+type MemoryLeaf = Box<[PrimitiveValue; PAGE_SUBDIVISION_LEN]>;
+
+/// Pool of memory page leaves freed when pages are cleared (i.e., when far calls finish). Pooled leaves are reused
+/// for new pages, which reduces allocation churn for call-dense transactions. Since the memory persists for the entire
+/// L1 batch, leaves are reused between transactions in the batch as well.
+#[derive(Debug, Default, Clone)]
+struct LeafPool {
+    leaves: Vec<MemoryLeaf>,
+}
+
+impl LeafPool {
+    fn take(&mut self) -> MemoryLeaf {
+        if let Some(mut leaf) = self.leaves.pop() {
+            // Leaves are cleared on reuse rather than on release, so that clearing is only performed when necessary.
+            leaf.fill(PrimitiveValue::empty());
+            leaf
+        } else {
+            Box::new([PrimitiveValue::empty(); PAGE_SUBDIVISION_LEN])
+        }
+    }
+
+    fn release(&mut self, page: MemoryPage) {
+        let free_capacity = MAX_POOLED_LEAVES.saturating_sub(self.leaves.len());
+        let leaves = page.root.into_iter().flatten().take(free_capacity);
+        self.leaves.extend(leaves);
+    }
+}
 
 #[derive(Debug, Default, Clone)]
 struct MemoryPage {
-    root: Vec<Option<Box<[PrimitiveValue; PAGE_SUBDIVISION_LEN]>>>,
+    root: Vec<Option<MemoryLeaf>>,
 }
 
 impl MemoryPage {
@@ -531,7 +561,7 @@ impl MemoryPage {
             .map(|leaf| &leaf[slot % PAGE_SUBDIVISION_LEN])
             .unwrap_or(&PRIMITIVE_VALUE_EMPTY)
     }
-    fn set(&mut self, slot: usize, value: PrimitiveValue) -> PrimitiveValue {
+    fn set(&mut self, slot: usize, value: PrimitiveValue, pool: &mut LeafPool) -> PrimitiveValue {
         let root_index = slot / PAGE_SUBDIVISION_LEN;
         let leaf_index = slot % PAGE_SUBDIVISION_LEN;
 
@@ -545,9 +575,9 @@ impl MemoryPage {
             leaf[leaf_index] = value;
             old
         } else {
-            let mut leaf = [PrimitiveValue::empty(); PAGE_SUBDIVISION_LEN];
+            let mut leaf = pool.take();
             leaf[leaf_index] = value;
-            self.root[root_index] = Some(Box::new(leaf));
+            self.root[root_index] = Some(leaf);
             PrimitiveValue::empty()
         }
     }
@@ -573,6 +603,7 @@ impl PartialEq for MemoryPage {
 #[derive(Debug, Default, Clone)]
 pub struct MemoryWrapper {
     memory: Vec<MemoryPage>,
+    leaf_pool: LeafPool,
 }
 
 impl PartialEq for MemoryWrapper {
@@ -648,7 +679,7 @@ impl WithHistory for MemoryWrapper {
 
         self.ensure_page_exists(page);
         let page_handle = self.memory.get_mut(page).unwrap();
-        let prev_value = page_handle.set(slot, set_value);
+        let prev_value = page_handle.set(slot, set_value, &mut self.leaf_pool);
 
         let undo = MemoryHistoryRecord {
             page,
@@ -697,7 +728,8 @@ impl<H: HistoryMode> HistoryRecorder<MemoryWrapper, H> {
                         }
                     }
                 }
-                inner.memory[page] = MemoryPage::default();
+                let page_handle = std::mem::take(&mut inner.memory[page]);
+                inner.leaf_pool.release(page_handle);
             }
         });
     }
@@ -774,8 +806,8 @@ mod tests {
     use zksync_types::U256;
 
     use crate::vm_latest::{
-        old_vm::history_recorder::{HistoryRecorder, MemoryWrapper},
-        HistoryDisabled,
+        old_vm::history_recorder::{HistoryRecorder, MemoryWrapper, PAGE_SUBDIVISION_LEN},
+        HistoryDisabled, HistoryEnabled,
     };
 
     #[test]
@@ -808,4 +840,44 @@ mod tests {
         write(&mut a, nonzero);
         assert_eq!(a, b);
     }
+
+    #[test]
+    fn cleared_pages_are_reused() {
+        let mut memory: HistoryRecorder<MemoryWrapper, HistoryEnabled> = Default::default();
+        let value = |value: u64| PrimitiveValue {
+            value: value.into(),
+            is_pointer: false,
+        };
+
+        for slot in 0..PAGE_SUBDIVISION_LEN {
+            memory.write_to_memory(1, slot, value(slot as u64 + 1), Timestamp(0));
+        }
+        memory.write_to_memory(1, PAGE_SUBDIVISION_LEN * 3, value(1), Timestamp(0));
+        memory.clear_page(1, Timestamp(1_000));
+        assert_eq!(memory.inner().leaf_pool.leaves.len(), 2);
+        assert_eq!(*memory.inner().read_slot(1, 0), PrimitiveValue::empty());
+
+        // Check that a reused leaf contains no values from the cleared page.
+        memory.write_to_memory(2, 1, value(42), Timestamp(2_000));
+        assert_eq!(memory.inner().leaf_pool.leaves.len(), 1);
+        for slot in 0..PAGE_SUBDIVISION_LEN {
+            let expected = if slot == 1 {
+                value(42)
+            } else {
+                PrimitiveValue::empty()
+            };
+            assert_eq!(*memory.inner().read_slot(2, slot), expected);
+        }
+
+        // Check that the cleared page is correctly restored on rollback.
+        memory.rollback_to_timestamp(Timestamp(1_000));
+        for slot in 0..PAGE_SUBDIVISION_LEN {
+            assert_eq!(*memory.inner().read_slot(1, slot), value(slot as u64 + 1));
+            assert_eq!(*memory.inner().read_slot(2, slot), PrimitiveValue::empty());
+        }
+        assert_eq!(
+            *memory.inner().read_slot(1, PAGE_SUBDIVISION_LEN * 3),
+            value(1)
+        );
+    }
 }
@@ -1,5 +1,6 @@
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
+use anyhow::Context as _;
 use zk_evm_1_4_1::{
     abstractions::{DecommittmentProcessor, Memory, MemoryType},
     aux_structures::{
@@ -21,8 +22,9 @@ pub struct DecommitterOracle<const B: bool, S, H: HistoryMode> {
     /// Pointer that enables to read contract bytecodes from the database.
     storage: StoragePtr<S>,
     /// The cache of bytecodes that the bootloader "knows", but that are not necessarily in the database.
-    /// And it is also used as a database cache.
-    pub known_bytecodes: HistoryRecorder<HashMap<U256, Vec<U256>>, H>,
+    /// And it is also used as a database cache. Bytecodes are reference-counted, so that getting a bytecode
+    /// from the cache and recording cache history don't copy bytecodes.
+    pub known_bytecodes: HistoryRecorder<HashMap<U256, Arc<[U256]>>, H>,
     /// Stores pages of memory where certain code hashes have already been decommitted.
     /// It is expected that they all are present in the DB.
     // `decommitted_code_hashes` history is necessary
@@ -42,25 +44,40 @@ impl<S: ReadStorage, const B: bool, H: HistoryMode> DecommitterOracle<B, S, H> {
     }
 
     /// Gets the bytecode for a given hash (either from storage, or from 'known_bytecodes' that were populated by `populate` method).
-    /// Panics if bytecode doesn't exist.
-    pub fn get_bytecode(&mut self, hash: U256, timestamp: Timestamp) -> Vec<U256> {
+    /// Returns an error if the bytecode doesn't exist, or if its length doesn't match the length encoded in the hash.
+    pub fn get_bytecode(
+        &mut self,
+        hash: U256,
+        timestamp: Timestamp,
+    ) -> anyhow::Result<Arc<[U256]>> {
         let entry = self.known_bytecodes.inner().get(&hash);
 
         match entry {
-            Some(x) => x.clone(),
+            Some(x) => Ok(x.clone()),
             None => {
-                // It is ok to panic here, since the decommitter is never called directly by
-                // the users and always called by the VM. VM will never let decommit the
-                // code hash which we didn't previously claim to know the preimage of.
+                // The VM will never let decommit the code hash which we didn't previously claim
+                // to know the preimage of, so the errors below indicate an inconsistent storage.
+                let hash_h256 = u256_to_h256(hash);
                 let value = self
                     .storage
                     .borrow_mut()
-                    .load_factory_dep(u256_to_h256(hash))
-                    .expect("Trying to decode unexisting hash");
-
-                let value = bytes_to_be_words(value);
+                    .load_factory_dep(hash_h256)
+                    .with_context(|| format!("Trying to decode unexisting hash {hash_h256:?}"))?;
+                anyhow::ensure!(
+                    value.len() % 32 == 0,
+                    "Bytecode for hash {hash_h256:?} has length {} not divisible by 32",
+                    value.len()
+                );
+                let expected_len_in_words = bytecode_len_in_words(&hash_h256) as usize;
+                anyhow::ensure!(
+                    value.len() / 32 == expected_len_in_words,
+                    "Bytecode for hash {hash_h256:?} has length {} words, while the hash specifies {expected_len_in_words} words",
+                    value.len() / 32
+                );
+
+                let value: Arc<[U256]> = bytes_to_be_words(value).into();
                 self.known_bytecodes.insert(hash, value.clone(), timestamp);
-                value
+                Ok(value)
             }
         }
     }
@@ -68,7 +85,8 @@ impl<S: ReadStorage, const B: bool, H: HistoryMode> DecommitterOracle<B, S, H> {
     /// Adds additional bytecodes. They will take precedent over the bytecodes from storage.
     pub fn populate(&mut self, bytecodes: Vec<(U256, Vec<U256>)>, timestamp: Timestamp) {
         for (hash, bytecode) in bytecodes {
-            self.known_bytecodes.insert(hash, bytecode, timestamp);
+            self.known_bytecodes
+                .insert(hash, bytecode.into(), timestamp);
         }
     }
 
@@ -119,7 +137,7 @@ impl<S: ReadStorage, const B: bool, H: HistoryMode> DecommitterOracle<B, S, H> {
 
     pub(crate) fn get_history_size(&self) -> usize {
         let known_bytecodes_stack_size = self.known_bytecodes.borrow_history(|h| h.len(), 0)
-            * std::mem::size_of::<<HashMap<U256, Vec<U256>> as WithHistory>::HistoryRecord>();
+            * std::mem::size_of::<<HashMap<U256, Arc<[U256]>> as WithHistory>::HistoryRecord>();
         let known_bytecodes_heap_size = self.known_bytecodes.borrow_history(
             |h| {
                 h.iter()
@@ -190,7 +208,7 @@ impl<S: ReadStorage + Debug, const B: bool, H: HistoryMode> DecommittmentProcess
             Ok((partial_query, None))
         } else {
             // We are fetching a fresh bytecode that we didn't read before.
-            let values = self.get_bytecode(partial_query.hash, partial_query.timestamp);
+            let values = self.get_bytecode(partial_query.hash, partial_query.timestamp)?;
             let page_to_use = partial_query.memory_page;
             let timestamp = partial_query.timestamp;
             partial_query.decommitted_length = values.len() as u16;
@@ -212,22 +230,18 @@ impl<S: ReadStorage + Debug, const B: bool, H: HistoryMode> DecommittmentProcess
             self.decommitted_code_hashes
                 .insert(partial_query.hash, page_to_use.0, timestamp);
 
-            // Copy the bytecode (that is stored in 'values' Vec) into the memory page.
+            // Copy the bytecode (that is shared with `known_bytecodes`) into the memory page.
+            for (i, value) in values.iter().enumerate() {
+                tmp_q.location.index = MemoryIndex(i as u32);
+                tmp_q.value = *value;
+                memory.specialized_code_query(monotonic_cycle_counter, tmp_q);
+            }
             if B {
-                for (i, value) in values.iter().enumerate() {
-                    tmp_q.location.index = MemoryIndex(i as u32);
-                    tmp_q.value = *value;
-                    memory.specialized_code_query(monotonic_cycle_counter, tmp_q);
-                }
-                // If we're in the witness mode - we also have to return the values.
-                Ok((partial_query, Some(values)))
+                // If we're in the witness mode - we also have to return the values. The owned `Vec` is required
+                // by the `DecommittmentProcessor` trait; this copy happens at most once per decommitted bytecode,
+                // and is never made by the VM used by the server, which runs with `B = false`.
+                Ok((partial_query, Some(values.to_vec())))
             } else {
-                for (i, value) in values.into_iter().enumerate() {
-                    tmp_q.location.index = MemoryIndex(i as u32);
-                    tmp_q.value = value;
-                    memory.specialized_code_query(monotonic_cycle_counter, tmp_q);
-                }
-
                 Ok((partial_query, None))
             }
         }
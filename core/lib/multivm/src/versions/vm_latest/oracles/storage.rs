@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use zk_evm_1_4_1::{
     abstractions::{RefundType, RefundedAmounts, Storage as VmStorageOracle},
@@ -7,6 +7,7 @@ use zk_evm_1_4_1::{
 };
 use zksync_state::{StoragePtr, WriteStorage};
 use zksync_types::{
+    tx::tx_execution_info::StorageAccessStatistic,
     utils::storage_key_for_eth_balance,
     writes::{
         compression::compress_with_best_strategy, BYTES_PER_DERIVED_KEY,
@@ -304,6 +305,31 @@ impl<S: WriteStorage, H: HistoryMode> StorageOracle<S, H> {
             .unwrap_or(&[])
     }
 
+    /// Returns cold / warm storage slot access counts for storage log queries from the current frame
+    /// where `log.log_query.timestamp >= from_timestamp`. An access is cold if the slot wasn't accessed
+    /// by earlier queries in this range, and warm otherwise. Rollback queries are not counted as accesses.
+    pub(crate) fn storage_access_statistic_after_timestamp(
+        &self,
+        from_timestamp: Timestamp,
+    ) -> StorageAccessStatistic {
+        let mut accessed_keys = HashSet::new();
+        let mut statistic = StorageAccessStatistic::default();
+        let queries = self.storage_log_queries_after_timestamp(from_timestamp);
+        for query in queries.iter().filter(|query| !query.log_query.rollback) {
+            let key = triplet_to_storage_key(
+                query.log_query.shard_id,
+                query.log_query.address,
+                query.log_query.key,
+            );
+            if accessed_keys.insert(key) {
+                statistic.cold += 1;
+            } else {
+                statistic.warm += 1;
+            }
+        }
+        statistic
+    }
+
     pub(crate) fn get_final_log_queries(&self) -> Vec<StorageLogQuery> {
         assert_eq!(
             self.frames_stack.len(),
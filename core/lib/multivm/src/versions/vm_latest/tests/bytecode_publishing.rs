@@ -1,8 +1,10 @@
+use std::collections::HashMap;
+
 use zksync_types::event::extract_long_l2_to_l1_messages;
-use zksync_utils::bytecode::compress_bytecode;
+use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
 
 use crate::{
-    interface::{TxExecutionMode, VmExecutionMode, VmInterface},
+    interface::{TxExecutionMode, VmExecutionMode, VmInterface, VmInterfaceHistoryEnabled},
     vm_latest::{
         tests::{
             tester::{DeployContractsTx, TxType, VmTesterBuilder},
@@ -41,3 +43,41 @@ fn test_bytecode_publishing() {
         "Bytecode not published"
     );
 }
+
+#[test]
+fn test_bytecode_publishing_with_precompressed_bytecodes() {
+    let mut vm = VmTesterBuilder::new(HistoryEnabled)
+        .with_empty_in_memory_storage()
+        .with_execution_mode(TxExecutionMode::VerifyExecute)
+        .with_random_rich_accounts(1)
+        .build();
+
+    let counter = read_test_contract();
+    let counter_hash = hash_bytecode(&counter);
+    let account = &mut vm.rich_accounts[0];
+    let compressed_bytecode = compress_bytecode(&counter).unwrap();
+
+    // Invalid precompressed bytecodes must not be published.
+    let DeployContractsTx { tx, .. } = account.get_deploy_tx(&counter, None, TxType::L2);
+    let invalid_bytecodes = HashMap::from([(counter_hash, vec![0; compressed_bytecode.len()])]);
+    vm.vm.make_snapshot();
+    let (compression_result, _) = vm.vm.inspect_transaction_with_precompressed_bytecodes(
+        Default::default(),
+        tx.clone(),
+        &invalid_bytecodes,
+    );
+    assert!(compression_result.is_err());
+    vm.vm.rollback_to_the_latest_snapshot();
+
+    let precompressed_bytecodes = HashMap::from([(counter_hash, compressed_bytecode.clone())]);
+    let (compression_result, result) = vm.vm.inspect_transaction_with_precompressed_bytecodes(
+        Default::default(),
+        tx,
+        &precompressed_bytecodes,
+    );
+    compression_result.unwrap();
+    assert!(!result.result.is_failed(), "Transaction wasn't successful");
+    let last_tx_bytecodes = vm.vm.get_last_tx_compressed_bytecodes();
+    assert_eq!(last_tx_bytecodes.len(), 1);
+    assert_eq!(last_tx_bytecodes[0].compressed, compressed_bytecode);
+}
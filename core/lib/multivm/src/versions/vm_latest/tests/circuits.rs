@@ -66,4 +66,15 @@ fn test_circuits() {
             );
         }
     }
+
+    // Check that computational gas is attributed to the circuit families used by the transaction.
+    let gas = res.statistics.circuit_family_gas;
+    assert!(gas.storage > 0, "{gas:?}");
+    assert!(gas.keccak256 > 0, "{gas:?}");
+    assert!(gas.ecrecover > 0, "{gas:?}");
+    assert_eq!(gas.sha256, 0, "{gas:?}");
+    assert!(
+        gas.total() <= res.statistics.computational_gas_used,
+        "{gas:?}"
+    );
 }
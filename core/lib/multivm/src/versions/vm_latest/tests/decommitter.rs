@@ -0,0 +1,126 @@
+use std::{sync::Arc, time::Duration};
+
+use zksync_types::H256;
+use zksync_utils::{bytecode::hash_bytecode, bytes_to_be_words, h256_to_u256};
+
+use crate::vm_latest::tests::{
+    tester::{get_empty_storage, DecommitterHarness, FactoryDepFault, FaultyStorage},
+    utils::read_test_contract,
+};
+
+fn prepare_harness() -> (DecommitterHarness, Vec<u8>, H256) {
+    let bytecode = read_test_contract();
+    let hash = hash_bytecode(&bytecode);
+    let mut storage = get_empty_storage();
+    storage.store_factory_dep(hash, bytecode.clone());
+    let harness = DecommitterHarness::new(FaultyStorage::new(storage));
+    (harness, bytecode, hash)
+}
+
+#[test]
+fn decommitting_bytecode() {
+    let (mut harness, bytecode, hash) = prepare_harness();
+
+    let query = harness.decommit(hash).unwrap();
+    assert!(query.is_fresh);
+    assert_eq!(usize::from(query.decommitted_length), bytecode.len() / 32);
+    assert_eq!(harness.read_code(&query), bytes_to_be_words(bytecode));
+
+    // The repeated decommitment should reuse the memory page and not touch the storage.
+    let repeated_query = harness.decommit(hash).unwrap();
+    assert!(!repeated_query.is_fresh);
+    assert_eq!(repeated_query.memory_page.0, query.memory_page.0);
+    assert_eq!(repeated_query.decommitted_length, query.decommitted_length);
+    assert_eq!(harness.storage.borrow().factory_dep_requests(), [hash]);
+}
+
+#[test]
+fn decommitting_missing_bytecode() {
+    let (mut harness, _, hash) = prepare_harness();
+    harness
+        .storage
+        .borrow_mut()
+        .set_fault(hash, FactoryDepFault::Missing);
+
+    let err = harness.decommit(hash).unwrap_err().to_string();
+    assert!(err.contains("unexisting hash"), "{err}");
+    assert!(harness.decommitter.get_used_bytecode_hashes().is_empty());
+
+    // The decommitter should recover once the bytecode is available.
+    harness.storage.borrow_mut().clear_faults();
+    let query = harness.decommit(hash).unwrap();
+    assert!(query.is_fresh);
+}
+
+#[test]
+fn decommitting_bytecode_with_corrupted_length() {
+    let (mut harness, bytecode, hash) = prepare_harness();
+    let corrupted_lengths = [bytecode.len() - 1, bytecode.len() - 32, bytecode.len() + 32];
+    for len in corrupted_lengths {
+        harness
+            .storage
+            .borrow_mut()
+            .set_fault(hash, FactoryDepFault::CorruptedLength(len));
+        let err = harness.decommit(hash).unwrap_err().to_string();
+        assert!(err.contains("has length"), "{err}");
+    }
+    assert!(harness.decommitter.known_bytecodes.inner().is_empty());
+}
+
+#[test]
+fn decommitting_bytecode_with_delays_is_deterministic() {
+    let (mut harness, bytecode, hash) = prepare_harness();
+    let query = harness.decommit(hash).unwrap();
+
+    let (mut delayed_harness, _, _) = prepare_harness();
+    delayed_harness
+        .storage
+        .borrow_mut()
+        .set_fault(hash, FactoryDepFault::Delayed(Duration::from_millis(50)));
+    let delayed_query = delayed_harness.decommit(hash).unwrap();
+
+    assert_eq!(delayed_query.memory_page.0, query.memory_page.0);
+    assert_eq!(delayed_query.decommitted_length, query.decommitted_length);
+    assert_eq!(delayed_query.is_fresh, query.is_fresh);
+    assert_eq!(
+        delayed_harness.read_code(&delayed_query),
+        bytes_to_be_words(bytecode)
+    );
+}
+
+#[test]
+fn rolling_back_decommitment() {
+    let (mut harness, _, hash) = prepare_harness();
+    let timestamp = harness.timestamp();
+    let query = harness.decommit(hash).unwrap();
+    assert!(query.is_fresh);
+
+    harness.rollback_to_timestamp(timestamp);
+    assert!(harness.decommitter.get_used_bytecode_hashes().is_empty());
+    assert!(harness.decommitter.known_bytecodes.inner().is_empty());
+
+    // After the rollback, the bytecode should be loaded from the storage again.
+    let query = harness.decommit(hash).unwrap();
+    assert!(query.is_fresh);
+    assert_eq!(
+        harness.storage.borrow().factory_dep_requests(),
+        [hash, hash]
+    );
+}
+
+#[test]
+fn decommitted_bytecodes_are_shared_with_cache() {
+    let (mut harness, bytecode, hash) = prepare_harness();
+    let timestamp = harness.timestamp();
+    let hash = h256_to_u256(hash);
+
+    let loaded = harness.decommitter.get_bytecode(hash, timestamp).unwrap();
+    assert_eq!(loaded.to_vec(), bytes_to_be_words(bytecode));
+    let cached = harness.decommitter.get_bytecode(hash, timestamp).unwrap();
+    assert!(Arc::ptr_eq(&loaded, &cached));
+    assert!(Arc::ptr_eq(
+        &loaded,
+        &harness.decommitter.known_bytecodes.inner()[&hash]
+    ));
+    assert_eq!(harness.storage.borrow().factory_dep_requests().len(), 1);
+}
@@ -1,4 +1,7 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use itertools::Itertools;
 use zksync_state::WriteStorage;
@@ -93,7 +96,7 @@ fn test_get_used_contracts() {
 
 fn known_bytecodes_without_aa_code<S: WriteStorage, H: HistoryMode>(
     vm: &Vm<S, H>,
-) -> HashMap<U256, Vec<U256>> {
+) -> HashMap<U256, Arc<[U256]>> {
     let mut known_bytecodes_without_aa_code = vm
         .state
         .decommittment_processor
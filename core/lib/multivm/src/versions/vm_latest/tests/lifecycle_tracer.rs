@@ -0,0 +1,187 @@
+use ethabi::Token;
+use zksync_contracts::load_contract;
+use zksync_types::{zk_evm_types::FarCallOpcode, Address, Execute};
+
+use crate::{
+    interface::{
+        tracer::{TracerExecutionStopReason, VmExecutionStopReason},
+        TxExecutionMode, VmExecutionMode, VmInterface,
+    },
+    tracers::{
+        lifecycle::{FarCallEnter, FarCallExit, FarCallOutcome, OpcodeContext, OpcodeKind},
+        LifecycleTracer, TracerChain,
+    },
+    vm_latest::{
+        constants::BLOCK_GAS_LIMIT,
+        tests::{tester::VmTesterBuilder, utils::read_test_contract},
+        HistoryEnabled, ToTracerPointer,
+    },
+};
+
+const COUNTER_CONTRACT_PATH: &str =
+    "etc/contracts-test-data/artifacts-zk/contracts/counter/counter.sol/Counter.json";
+
+#[derive(Debug, Default)]
+struct OpcodeCounter {
+    before_count: usize,
+    after_count: usize,
+    far_call_count: usize,
+}
+
+impl LifecycleTracer for OpcodeCounter {
+    fn before_opcode(&mut self, context: &OpcodeContext) {
+        self.before_count += 1;
+        if context.opcode == OpcodeKind::FarCall {
+            self.far_call_count += 1;
+        }
+    }
+
+    fn after_opcode(&mut self, _context: &OpcodeContext) {
+        self.after_count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+struct CallRecorder {
+    entered: Vec<FarCallEnter>,
+    exited: Vec<FarCallExit>,
+    /// Callees of the currently active far call frames.
+    active_callees: Vec<Address>,
+    tx_end_count: usize,
+    bootloader_end_reasons: Vec<VmExecutionStopReason>,
+}
+
+impl LifecycleTracer for CallRecorder {
+    fn far_call_enter(&mut self, call: &FarCallEnter) {
+        self.entered.push(*call);
+        self.active_callees.push(call.callee);
+    }
+
+    fn far_call_exit(&mut self, call: &FarCallExit) {
+        // Far call frames must be exited in the reverse order.
+        assert_eq!(self.active_callees.pop(), Some(call.callee));
+        self.exited.push(*call);
+    }
+
+    fn tx_end(&mut self) {
+        assert!(
+            self.active_callees.is_empty(),
+            "transaction has ended with active far calls: {:?}",
+            self.active_callees
+        );
+        self.tx_end_count += 1;
+    }
+
+    fn bootloader_end(&mut self, reason: &VmExecutionStopReason) {
+        self.bootloader_end_reasons.push(reason.clone());
+    }
+}
+
+fn execute_counter_tx(calldata: Vec<u8>) -> (OpcodeCounter, CallRecorder, Address, bool) {
+    let contract = read_test_contract();
+    let address = Address::random();
+    let mut vm = VmTesterBuilder::new(HistoryEnabled)
+        .with_empty_in_memory_storage()
+        .with_random_rich_accounts(1)
+        .with_deployer()
+        .with_gas_limit(BLOCK_GAS_LIMIT)
+        .with_execution_mode(TxExecutionMode::VerifyExecute)
+        .with_custom_contracts(vec![(contract, address, true)])
+        .build();
+
+    let account = &mut vm.rich_accounts[0];
+    let tx = account.get_l2_tx_for_execute(
+        Execute {
+            contract_address: address,
+            calldata,
+            value: Default::default(),
+            factory_deps: None,
+        },
+        None,
+    );
+
+    let mut chain = TracerChain::new();
+    let opcode_counter = chain.add(OpcodeCounter::default());
+    let call_recorder = chain.add(CallRecorder::default());
+    assert_eq!(chain.len(), 2);
+
+    vm.vm.push_transaction(tx);
+    let res = vm
+        .vm
+        .inspect(chain.into_tracer_pointer().into(), VmExecutionMode::OneTx);
+    let opcode_counter = opcode_counter.with(std::mem::take);
+    let call_recorder = call_recorder.with(std::mem::take);
+    (
+        opcode_counter,
+        call_recorder,
+        address,
+        res.result.is_failed(),
+    )
+}
+
+fn assert_calls_consistency(opcode_counter: &OpcodeCounter, call_recorder: &CallRecorder) {
+    assert!(opcode_counter.before_count > 0);
+    assert_eq!(opcode_counter.before_count, opcode_counter.after_count);
+    assert_eq!(call_recorder.entered.len(), opcode_counter.far_call_count);
+    // All far calls made during the transaction must be exited before the transaction ends.
+    assert_eq!(call_recorder.exited.len(), call_recorder.entered.len());
+    assert!(call_recorder.active_callees.is_empty());
+    assert_eq!(call_recorder.tx_end_count, 1);
+    // In the one-tx mode, the VM is stopped by the default tracer once the transaction is processed.
+    assert_eq!(
+        call_recorder.bootloader_end_reasons,
+        [VmExecutionStopReason::TracerRequestedStop(
+            TracerExecutionStopReason::Finish
+        )]
+    );
+}
+
+fn counter_calls(
+    call_recorder: &CallRecorder,
+    address: Address,
+) -> (Vec<&FarCallEnter>, Vec<&FarCallExit>) {
+    let entered: Vec<_> = call_recorder
+        .entered
+        .iter()
+        .filter(|call| call.callee == address)
+        .collect();
+    let exited: Vec<_> = call_recorder
+        .exited
+        .iter()
+        .filter(|call| call.callee == address)
+        .collect();
+    (entered, exited)
+}
+
+#[test]
+fn chaining_lifecycle_tracers() {
+    let increment_by_6_calldata =
+        "7cf5dab00000000000000000000000000000000000000000000000000000000000000006";
+    let (opcode_counter, call_recorder, address, is_failed) =
+        execute_counter_tx(hex::decode(increment_by_6_calldata).unwrap());
+    assert!(!is_failed);
+    assert_calls_consistency(&opcode_counter, &call_recorder);
+
+    let (entered, exited) = counter_calls(&call_recorder, address);
+    assert_eq!(entered.len(), 1);
+    assert_eq!(entered[0].call_type, FarCallOpcode::Normal);
+    assert_eq!(exited.len(), 1);
+    assert_eq!(exited[0].outcome, FarCallOutcome::Ok);
+}
+
+#[test]
+fn lifecycle_tracers_with_reverted_call() {
+    let calldata = load_contract(COUNTER_CONTRACT_PATH)
+        .function("incrementWithRevert")
+        .unwrap()
+        .encode_input(&[Token::Uint(1.into()), Token::Bool(true)])
+        .unwrap();
+    let (opcode_counter, call_recorder, address, is_failed) = execute_counter_tx(calldata);
+    assert!(is_failed);
+    assert_calls_consistency(&opcode_counter, &call_recorder);
+
+    let (entered, exited) = counter_calls(&call_recorder, address);
+    assert_eq!(entered.len(), 1);
+    assert_eq!(exited.len(), 1);
+    assert_eq!(exited[0].outcome, FarCallOutcome::Revert);
+}
@@ -6,17 +6,20 @@ mod block_tip;
 mod bytecode_publishing;
 mod call_tracer;
 mod circuits;
+mod decommitter;
 mod gas_limit;
 mod get_used_contracts;
 mod is_write_initial;
 mod l1_tx_execution;
 mod l2_blocks;
+mod lifecycle_tracer;
 mod nonce_holder;
 mod precompiles;
 mod refunds;
 mod require_eip712;
 mod rollbacks;
 mod simple_execution;
+mod storage_accesses;
 mod tester;
 mod tracing_execution_error;
 mod upgrade;
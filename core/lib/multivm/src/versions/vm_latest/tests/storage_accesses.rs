@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+
+use zksync_types::{Address, Execute, U256};
+
+use crate::{
+    interface::{TxExecutionMode, VmExecutionMode, VmInterface},
+    vm_latest::{tests::tester::VmTesterBuilder, HistoryEnabled},
+};
+
+// Checks that cold / warm storage accesses are consistent with storage logs produced by transactions
+// and are tracked separately for each transaction.
+#[test]
+fn test_storage_accesses() {
+    let mut vm = VmTesterBuilder::new(HistoryEnabled)
+        .with_empty_in_memory_storage()
+        .with_random_rich_accounts(1)
+        .with_deployer()
+        .with_execution_mode(TxExecutionMode::VerifyExecute)
+        .build();
+
+    for _ in 0..2 {
+        let account = &mut vm.rich_accounts[0];
+        let tx = account.get_l2_tx_for_execute(
+            Execute {
+                contract_address: Address::random(),
+                calldata: Vec::new(),
+                value: U256::from(1u8),
+                factory_deps: None,
+            },
+            None,
+        );
+        vm.vm.push_transaction(tx);
+        let res = vm.vm.execute(VmExecutionMode::OneTx);
+        assert!(!res.result.is_failed(), "{:?}", res.result);
+
+        let accesses: Vec<_> = res
+            .logs
+            .storage_logs
+            .iter()
+            .filter(|log| !log.log_query.rollback)
+            .map(|log| (log.log_query.address, log.log_query.key))
+            .collect();
+        let accessed_slots: HashSet<_> = accesses.iter().collect();
+
+        let statistic = res.statistics.storage_accesses;
+        // E.g., the account nonce is read and then incremented in each transaction.
+        assert!(statistic.warm > 0, "{statistic:?}");
+        assert_eq!(statistic.cold, accessed_slots.len());
+        assert_eq!(statistic.total(), accesses.len());
+    }
+}
@@ -0,0 +1,80 @@
+use std::{cell::RefCell, rc::Rc};
+
+use zk_evm_1_4_1::{
+    abstractions::DecommittmentProcessor,
+    aux_structures::{DecommittmentQuery, MemoryPage, Timestamp},
+};
+use zksync_state::{InMemoryStorage, StoragePtr};
+use zksync_types::{H256, U256};
+use zksync_utils::h256_to_u256;
+
+use super::faulty_storage::FaultyStorage;
+use crate::vm_latest::{
+    old_vm::oracles::{decommitter::DecommitterOracle, OracleWithHistory},
+    HistoryEnabled, SimpleMemory,
+};
+
+pub(crate) type HarnessStorage = FaultyStorage<InMemoryStorage>;
+
+/// Deterministic harness for [`DecommitterOracle`] that drives it directly (i.e., without the VM).
+/// Each decommitment request gets a fresh timestamp and memory page, so that runs with the same sequence
+/// of requests produce the same results.
+#[derive(Debug)]
+pub(crate) struct DecommitterHarness {
+    pub(crate) decommitter: DecommitterOracle<false, HarnessStorage, HistoryEnabled>,
+    pub(crate) storage: StoragePtr<HarnessStorage>,
+    pub(crate) memory: SimpleMemory<HistoryEnabled>,
+    timestamp: u32,
+    next_memory_page: u32,
+}
+
+impl DecommitterHarness {
+    const FIRST_MEMORY_PAGE: u32 = 1_000;
+
+    pub(crate) fn new(storage: HarnessStorage) -> Self {
+        let storage = Rc::new(RefCell::new(storage));
+        Self {
+            decommitter: DecommitterOracle::new(storage.clone()),
+            storage,
+            memory: SimpleMemory::default(),
+            timestamp: 0,
+            next_memory_page: Self::FIRST_MEMORY_PAGE,
+        }
+    }
+
+    /// Returns the timestamp that will be used for the next decommitment request.
+    pub(crate) fn timestamp(&self) -> Timestamp {
+        Timestamp(self.timestamp)
+    }
+
+    /// Requests to decommit the bytecode with the specified hash into memory.
+    pub(crate) fn decommit(&mut self, hash: H256) -> anyhow::Result<DecommittmentQuery> {
+        let query = DecommittmentQuery {
+            hash: h256_to_u256(hash),
+            timestamp: Timestamp(self.timestamp),
+            memory_page: MemoryPage(self.next_memory_page),
+            decommitted_length: 0,
+            is_fresh: false,
+        };
+        self.timestamp += 1;
+        self.next_memory_page += 1;
+
+        let (query, _) = self
+            .decommitter
+            .decommit_into_memory(0, query, &mut self.memory)?;
+        Ok(query)
+    }
+
+    /// Reads the code page written by the decommitter for the specified query.
+    pub(crate) fn read_code(&self, query: &DecommittmentQuery) -> Vec<U256> {
+        self.memory.dump_page_content_as_u256_words(
+            query.memory_page.0,
+            0..u32::from(query.decommitted_length),
+        )
+    }
+
+    pub(crate) fn rollback_to_timestamp(&mut self, timestamp: Timestamp) {
+        self.decommitter.rollback_to_timestamp(timestamp);
+        self.memory.rollback_to_timestamp(timestamp);
+    }
+}
@@ -0,0 +1,79 @@
+use std::{collections::HashMap, time::Duration};
+
+use zksync_state::ReadStorage;
+use zksync_types::{StorageKey, StorageValue, H256};
+
+/// Fault that can be injected into [`FaultyStorage`] for a specific factory dependency.
+#[derive(Debug, Clone)]
+pub(crate) enum FactoryDepFault {
+    /// The factory dependency is reported as missing.
+    Missing,
+    /// The factory dependency is returned after the specified delay.
+    Delayed(Duration),
+    /// The factory dependency is returned resized to the specified length in bytes (either truncated,
+    /// or padded with zeros).
+    CorruptedLength(usize),
+}
+
+/// [`ReadStorage`] wrapper allowing to inject faults into factory dependency loading. Used to test
+/// error paths in the VM components that load bytecodes (e.g., the decommitter).
+#[derive(Debug)]
+pub(crate) struct FaultyStorage<S> {
+    inner: S,
+    factory_dep_faults: HashMap<H256, FactoryDepFault>,
+    /// Factory dependency requests in the order they were made.
+    factory_dep_requests: Vec<H256>,
+}
+
+impl<S: ReadStorage> FaultyStorage<S> {
+    pub(crate) fn new(inner: S) -> Self {
+        Self {
+            inner,
+            factory_dep_faults: HashMap::new(),
+            factory_dep_requests: vec![],
+        }
+    }
+
+    pub(crate) fn set_fault(&mut self, hash: H256, fault: FactoryDepFault) {
+        self.factory_dep_faults.insert(hash, fault);
+    }
+
+    pub(crate) fn clear_faults(&mut self) {
+        self.factory_dep_faults.clear();
+    }
+
+    pub(crate) fn factory_dep_requests(&self) -> &[H256] {
+        &self.factory_dep_requests
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for FaultyStorage<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        self.inner.read_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.inner.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        self.factory_dep_requests.push(hash);
+        match self.factory_dep_faults.get(&hash) {
+            None => self.inner.load_factory_dep(hash),
+            Some(FactoryDepFault::Missing) => None,
+            Some(FactoryDepFault::Delayed(delay)) => {
+                std::thread::sleep(*delay);
+                self.inner.load_factory_dep(hash)
+            }
+            Some(FactoryDepFault::CorruptedLength(len)) => {
+                let mut bytecode = self.inner.load_factory_dep(hash)?;
+                bytecode.resize(*len, 0);
+                Some(bytecode)
+            }
+        }
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.inner.get_enumeration_index(key)
+    }
+}
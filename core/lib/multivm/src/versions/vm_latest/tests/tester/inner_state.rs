@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use zk_evm_1_4_1::{aux_structures::Timestamp, vm_state::VmLocalState};
 use zksync_state::WriteStorage;
@@ -42,7 +42,7 @@ pub(crate) struct DecommitterTestInnerState<H: HistoryMode> {
     /// There is no way to "truly" compare the storage pointer,
     /// so we just compare the modified keys. This is reasonable enough.
     pub(crate) modified_storage_keys: ModifiedKeysMap,
-    pub(crate) known_bytecodes: HistoryRecorder<HashMap<U256, Vec<U256>>, H>,
+    pub(crate) known_bytecodes: HistoryRecorder<HashMap<U256, Arc<[U256]>>, H>,
     pub(crate) decommitted_code_hashes: HistoryRecorder<HashMap<U256, u32>, HistoryEnabled>,
 }
 
@@ -1,9 +1,13 @@
+pub(crate) use decommitter_harness::DecommitterHarness;
+pub(crate) use faulty_storage::{FactoryDepFault, FaultyStorage};
 pub(crate) use transaction_test_info::{ExpectedError, TransactionTestInfo, TxModifier};
 pub(crate) use vm_tester::{
     default_l1_batch, get_empty_storage, InMemoryStorageView, VmTester, VmTesterBuilder,
 };
 pub(crate) use zksync_test_account::{Account, DeployContractsTx, TxType};
 
+mod decommitter_harness;
+mod faulty_storage;
 mod inner_state;
 mod transaction_test_info;
 mod vm_tester;
@@ -6,7 +6,10 @@ use zk_evm_1_4_1::{
     zkevm_opcode_defs::{LogOpcode, Opcode, UMAOpcode},
 };
 use zksync_state::{StoragePtr, WriteStorage};
-use zksync_types::circuit::CircuitCycleStatistic;
+use zksync_system_constants::{
+    ECRECOVER_PRECOMPILE_ADDRESS, KECCAK256_PRECOMPILE_ADDRESS, SHA256_PRECOMPILE_ADDRESS,
+};
+use zksync_types::circuit::{CircuitCycleStatistic, CircuitFamilyGas};
 
 use super::circuits_capacity::*;
 use crate::{
@@ -23,6 +26,7 @@ use crate::{
 #[derive(Debug)]
 pub(crate) struct CircuitsTracer<S, H> {
     pub(crate) statistics: CircuitCycleStatistic,
+    pub(crate) circuit_family_gas: CircuitFamilyGas,
     last_decommitment_history_entry_checked: Option<usize>,
     last_written_keys_history_entry_checked: Option<usize>,
     last_read_keys_history_entry_checked: Option<usize>,
@@ -33,12 +37,13 @@ pub(crate) struct CircuitsTracer<S, H> {
 impl<S: WriteStorage, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for CircuitsTracer<S, H> {
     fn before_execution(
         &mut self,
-        _state: VmLocalStateData<'_>,
+        state: VmLocalStateData<'_>,
         data: BeforeExecutionData,
         _memory: &SimpleMemory<H>,
         _storage: StoragePtr<S>,
     ) {
         self.statistics.main_vm_cycles += 1;
+        self.trace_circuit_family_gas(state, &data);
 
         match data.opcode.variant.opcode {
             Opcode::Nop(_)
@@ -135,6 +140,7 @@ impl<S: WriteStorage, H: HistoryMode> CircuitsTracer<S, H> {
     pub(crate) fn new() -> Self {
         Self {
             statistics: CircuitCycleStatistic::new(),
+            circuit_family_gas: CircuitFamilyGas::default(),
             last_decommitment_history_entry_checked: None,
             last_written_keys_history_entry_checked: None,
             last_read_keys_history_entry_checked: None,
@@ -143,6 +149,33 @@ impl<S: WriteStorage, H: HistoryMode> CircuitsTracer<S, H> {
         }
     }
 
+    /// Attributes computational gas of the opcode to a circuit family, similarly to how the total computational gas
+    /// is calculated.
+    fn trace_circuit_family_gas(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: &BeforeExecutionData,
+    ) {
+        let base_price = data.opcode.inner.variant.ergs_price();
+        match data.opcode.variant.opcode {
+            Opcode::Log(LogOpcode::StorageRead | LogOpcode::StorageWrite) => {
+                self.circuit_family_gas.storage += base_price;
+            }
+            Opcode::Log(LogOpcode::PrecompileCall) => {
+                let precompile_price = base_price + data.src1_value.value.low_u32();
+                let address = state.vm_local_state.callstack.current.this_address;
+                if address == KECCAK256_PRECOMPILE_ADDRESS {
+                    self.circuit_family_gas.keccak256 += precompile_price;
+                } else if address == SHA256_PRECOMPILE_ADDRESS {
+                    self.circuit_family_gas.sha256 += precompile_price;
+                } else if address == ECRECOVER_PRECOMPILE_ADDRESS {
+                    self.circuit_family_gas.ecrecover += precompile_price;
+                }
+            }
+            _ => {}
+        }
+    }
+
     fn trace_decommitments(&mut self, state: &ZkSyncVmState<S, H>) {
         let last_decommitment_history_entry_checked = self
             .last_decommitment_history_entry_checked
@@ -1,7 +1,16 @@
+use std::collections::{HashMap, VecDeque};
+
+use zksync_system_constants::{
+    ACCOUNT_CODE_STORAGE_ADDRESS, KNOWN_CODES_STORAGE_ADDRESS, L1_MESSENGER_ADDRESS,
+};
 use zksync_types::{
+    block::ContractPubdata,
     event::L1MessengerL2ToL1Log,
+    web3::signing::keccak256,
     writes::{compress_state_diffs, StateDiffRecord},
+    Address, H256,
 };
+use zksync_utils::{bytecode::hash_bytecode, h256_to_account_address, u256_to_h256};
 
 /// Struct based on which the pubdata blob is formed
 #[derive(Debug, Clone, Default)]
@@ -60,12 +69,84 @@ impl PubdataInput {
 
         l1_messenger_pubdata
     }
+
+    /// Attributes the pubdata to the contracts that caused it being published:
+    ///
+    /// - User L2->L1 logs are attributed to their sender. For logs sent by the L1 messenger on behalf
+    ///   of another contract (i.e., ones accompanying L2->L1 messages), the sender is taken from the log key.
+    /// - L2->L1 messages are attributed to their sender, which is determined by matching the message hash
+    ///   with the values of logs sent by the L1 messenger.
+    /// - Published bytecodes are attributed to the contract deployed with the bytecode in the batch, or to the
+    ///   known codes storage if the bytecode was published without a deployment (e.g., as a factory dependency).
+    /// - State diffs are attributed to the contract owning the modified storage slot; each diff accounts
+    ///   for the size of its compressed representation.
+    ///
+    /// Length prefixes are attributed together with the data they describe. Headers of the pubdata sections
+    /// (e.g., the number of logs) are not attributed to any contract.
+    pub(crate) fn pubdata_by_contract(&self) -> Vec<ContractPubdata> {
+        let mut pubdata_bytes = HashMap::<Address, u64>::new();
+
+        let mut message_senders = HashMap::<H256, VecDeque<Address>>::new();
+        for log in &self.user_logs {
+            let sender = if log.sender == L1_MESSENGER_ADDRESS {
+                let sender = h256_to_account_address(&u256_to_h256(log.key));
+                message_senders
+                    .entry(u256_to_h256(log.value))
+                    .or_default()
+                    .push_back(sender);
+                sender
+            } else {
+                log.sender
+            };
+            *pubdata_bytes.entry(sender).or_default() += log.packed_encoding().len() as u64;
+        }
+
+        for message in &self.l2_to_l1_messages {
+            let sender = message_senders
+                .get_mut(&H256(keccak256(message)))
+                .and_then(VecDeque::pop_front)
+                .unwrap_or(L1_MESSENGER_ADDRESS);
+            *pubdata_bytes.entry(sender).or_default() += 4 + message.len() as u64;
+        }
+
+        let deployed_contracts: HashMap<_, _> = self
+            .state_diffs
+            .iter()
+            .filter(|diff| diff.address == ACCOUNT_CODE_STORAGE_ADDRESS)
+            .map(|diff| {
+                let address = h256_to_account_address(&u256_to_h256(diff.key));
+                (u256_to_h256(diff.final_value), address)
+            })
+            .collect();
+        for bytecode in &self.published_bytecodes {
+            let address = deployed_contracts
+                .get(&hash_bytecode(bytecode))
+                .copied()
+                .unwrap_or(KNOWN_CODES_STORAGE_ADDRESS);
+            *pubdata_bytes.entry(address).or_default() += 4 + bytecode.len() as u64;
+        }
+
+        for state_diff in &self.state_diffs {
+            *pubdata_bytes.entry(state_diff.address).or_default() +=
+                state_diff.compress().len() as u64;
+        }
+
+        let mut pubdata_by_contract: Vec<_> = pubdata_bytes
+            .into_iter()
+            .map(|(address, pubdata_bytes)| ContractPubdata {
+                address,
+                pubdata_bytes,
+            })
+            .collect();
+        pubdata_by_contract.sort_unstable_by_key(|contract| contract.address);
+        pubdata_by_contract
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use zksync_system_constants::{ACCOUNT_CODE_STORAGE_ADDRESS, BOOTLOADER_ADDRESS};
-    use zksync_utils::u256_to_h256;
+    use zksync_system_constants::BOOTLOADER_ADDRESS;
+    use zksync_utils::{address_to_u256, h256_to_u256};
 
     use super::*;
 
@@ -121,4 +202,78 @@ mod tests {
 
         assert_eq!(hex::encode(pubdata), "00000000000000000000000000000000000000000000000000000000000002c700000001000000000000000000000000000000000000000000008001000000000000000000000000000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000800000000100000004deadbeef0000000100000004aaaabbbb0100002a040001000000000000000000000000000000000000000000000000000000000000007e090e0000000c0901000000020000000000000000000000000000000000008002000000000000000000000000000000000000000000000000000000000000009b000000000000000000000000000000000000000000000000000000000000007d000000000000000c000000000000000000000000000000000000000000000000000000000000000b000000000000000000000000000000000000000000000000000000000000000c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000008002000000000000000000000000000000000000000000000000000000000000009c000000000000000000000000000000000000000000000000000000000000007e00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000e000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000");
     }
+
+    #[test]
+    fn attributing_pubdata_to_contracts() {
+        let contract = Address::repeat_byte(1);
+        let other_contract = Address::repeat_byte(2);
+        let message = vec![1, 2, 3, 4, 5];
+        let bytecode = vec![0; 32];
+        let factory_dep = vec![1; 32];
+
+        let user_logs = vec![
+            L1MessengerL2ToL1Log {
+                l2_shard_id: 0,
+                is_service: false,
+                tx_number_in_block: 0,
+                sender: contract,
+                key: 1.into(),
+                value: 2.into(),
+            },
+            L1MessengerL2ToL1Log {
+                l2_shard_id: 0,
+                is_service: true,
+                tx_number_in_block: 0,
+                sender: L1_MESSENGER_ADDRESS,
+                key: address_to_u256(&other_contract),
+                value: h256_to_u256(H256(keccak256(&message))),
+            },
+        ];
+        let state_diffs = vec![
+            StateDiffRecord {
+                address: contract,
+                key: 1.into(),
+                derived_key: [1; 32],
+                enumeration_index: 0,
+                initial_value: 0.into(),
+                final_value: 1.into(),
+            },
+            StateDiffRecord {
+                address: ACCOUNT_CODE_STORAGE_ADDRESS,
+                key: address_to_u256(&other_contract),
+                derived_key: [2; 32],
+                enumeration_index: 0,
+                initial_value: 0.into(),
+                final_value: h256_to_u256(hash_bytecode(&bytecode)),
+            },
+        ];
+        let input = PubdataInput {
+            user_logs,
+            l2_to_l1_messages: vec![message.clone()],
+            published_bytecodes: vec![bytecode.clone(), factory_dep.clone()],
+            state_diffs: state_diffs.clone(),
+        };
+
+        let log_len = input.user_logs[0].packed_encoding().len() as u64;
+        let mut expected = vec![
+            ContractPubdata {
+                address: contract,
+                pubdata_bytes: log_len + state_diffs[0].compress().len() as u64,
+            },
+            ContractPubdata {
+                address: other_contract,
+                pubdata_bytes: log_len + 4 + message.len() as u64 + 4 + bytecode.len() as u64,
+            },
+            ContractPubdata {
+                address: ACCOUNT_CODE_STORAGE_ADDRESS,
+                pubdata_bytes: state_diffs[1].compress().len() as u64,
+            },
+            ContractPubdata {
+                address: KNOWN_CODES_STORAGE_ADDRESS,
+                pubdata_bytes: 4 + factory_dep.len() as u64,
+            },
+        ];
+        expected.sort_unstable_by_key(|contract| contract.address);
+        assert_eq!(input.pubdata_by_contract(), expected);
+    }
 }
@@ -1,9 +1,11 @@
+use std::collections::HashMap;
+
 use zkevm_test_harness_1_4_2::witness::sort_storage_access::sort_storage_access_queries;
 use zksync_state::{StoragePtr, WriteStorage};
 use zksync_types::{
     event::extract_l2tol1logs_from_l1_messenger,
     l2_to_l1_log::{SystemL2ToL1Log, UserL2ToL1Log},
-    Transaction,
+    Transaction, H256,
 };
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
@@ -149,15 +151,20 @@ impl<S: WriteStorage, H: HistoryMode> VmInterface<S, H> for Vm<S, H> {
         VmExecutionResultAndLogs,
     ) {
         self.push_transaction_with_compression(tx, with_compression);
-        let result = self.inspect_inner(tracer, VmExecutionMode::OneTx, None);
-        if self.has_unpublished_bytecodes() {
-            (
-                Err(BytecodeCompressionError::BytecodeCompressionFailed),
-                result,
-            )
-        } else {
-            (Ok(()), result)
-        }
+        self.inspect_pushed_transaction(tracer)
+    }
+
+    fn inspect_transaction_with_precompressed_bytecodes(
+        &mut self,
+        tracer: Self::TracerDispatcher,
+        tx: Transaction,
+        compressed_bytecodes: &HashMap<H256, Vec<u8>>,
+    ) -> (
+        Result<(), BytecodeCompressionError>,
+        VmExecutionResultAndLogs,
+    ) {
+        self.push_transaction_with_precompressed_bytecodes(tx, compressed_bytecodes);
+        self.inspect_pushed_transaction(tracer)
     }
 
     fn record_vm_memory_metrics(&self) -> VmMemoryMetrics {
@@ -172,16 +179,14 @@ impl<S: WriteStorage, H: HistoryMode> VmInterface<S, H> for Vm<S, H> {
         let result = self.execute(VmExecutionMode::Batch);
         let execution_state = self.get_current_execution_state();
         let bootloader_memory = self.get_bootloader_memory();
+        let pubdata_input = self.bootloader_state.get_pubdata_information();
+        let pubdata_by_contract = pubdata_input.pubdata_by_contract();
         FinishedL1Batch {
             block_tip_execution_result: result,
             final_execution_state: execution_state,
             final_bootloader_memory: Some(bootloader_memory),
-            pubdata_input: Some(
-                self.bootloader_state
-                    .get_pubdata_information()
-                    .clone()
-                    .build_pubdata(false),
-            ),
+            pubdata_input: Some(pubdata_input.clone().build_pubdata(false)),
+            pubdata_by_contract: Some(pubdata_by_contract),
         }
     }
 }
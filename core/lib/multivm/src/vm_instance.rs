@@ -1,5 +1,7 @@
+use std::collections::HashMap;
+
 use zksync_state::{StoragePtr, WriteStorage};
-use zksync_types::VmVersion;
+use zksync_types::{VmVersion, H256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
 use crate::{
@@ -114,6 +116,23 @@ impl<S: WriteStorage, H: HistoryMode> VmInterface<S, H> for VmInstance<S, H> {
         ))
     }
 
+    /// Inspect transaction reusing bytecodes compressed in advance.
+    fn inspect_transaction_with_precompressed_bytecodes(
+        &mut self,
+        dispatcher: Self::TracerDispatcher,
+        tx: zksync_types::Transaction,
+        compressed_bytecodes: &HashMap<H256, Vec<u8>>,
+    ) -> (
+        Result<(), BytecodeCompressionError>,
+        VmExecutionResultAndLogs,
+    ) {
+        dispatch_vm!(self.inspect_transaction_with_precompressed_bytecodes(
+            dispatcher.into(),
+            tx,
+            compressed_bytecodes
+        ))
+    }
+
     fn record_vm_memory_metrics(&self) -> VmMemoryMetrics {
         dispatch_vm!(self.record_vm_memory_metrics())
     }
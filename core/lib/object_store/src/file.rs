@@ -33,6 +33,7 @@ impl FileBackedObjectStore {
             Bucket::SchedulerWitnessJobsFri,
             Bucket::ProofsFri,
             Bucket::StorageSnapshot,
+            Bucket::RocksdbBackups,
         ] {
             let bucket_path = format!("{base_dir}/{bucket}");
             fs::create_dir_all(&bucket_path)
@@ -71,6 +72,12 @@ impl ObjectStore for FileBackedObjectStore {
         fs::remove_file(filename).await.map_err(From::from)
     }
 
+    async fn size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        let filename = self.filename(bucket, key);
+        let metadata = fs::metadata(filename).await?;
+        Ok(metadata.len())
+    }
+
     fn storage_prefix_raw(&self, bucket: Bucket) -> String {
         format!("{}/{}", self.base_dir, bucket)
     }
@@ -125,4 +132,26 @@ mod test {
             .await;
         assert!(result.is_ok(), "result must be OK");
     }
+
+    #[tokio::test]
+    async fn test_size() {
+        let dir = TempDir::new("test-data").unwrap();
+        let path = dir.into_path().into_os_string().into_string().unwrap();
+        let object_store = FileBackedObjectStore::new(path).await;
+        object_store
+            .put_raw(Bucket::ProverJobs, "test-key.bin", vec![0, 1, 2])
+            .await
+            .unwrap();
+        let size = object_store
+            .size_raw(Bucket::ProverJobs, "test-key.bin")
+            .await
+            .unwrap();
+        assert_eq!(size, 3);
+
+        let err = object_store
+            .size_raw(Bucket::ProverJobs, "missing-key.bin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    }
 }
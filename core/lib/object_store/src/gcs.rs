@@ -215,6 +215,17 @@ impl ObjectStore for GoogleCloudStorage {
         self.remove_inner(bucket.as_str(), key).await
     }
 
+    async fn size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        let filename = Self::filename(bucket.as_str(), key);
+        let request = GetObjectRequest {
+            bucket: self.bucket_prefix.clone(),
+            object: filename,
+            ..GetObjectRequest::default()
+        };
+        let object = retry(self.max_retries, || self.client.get_object(&request)).await?;
+        Ok(object.size.try_into().unwrap_or(0))
+    }
+
     fn storage_prefix_raw(&self, bucket: Bucket) -> String {
         format!(
             "https://storage.googleapis.com/{}/{}",
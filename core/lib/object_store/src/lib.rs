@@ -3,6 +3,7 @@
 //!
 //! - File-based storage saving blobs as separate files in the local filesystem
 //! - GCS-based storage
+//! - Storage based on S3-compatible services (AWS S3, MinIO, Cloudflare R2 etc.)
 //!
 //! These implementations are not exposed externally. Instead, a store trait object
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
@@ -29,6 +30,7 @@ mod metrics;
 mod mock;
 mod objects;
 mod raw;
+mod s3;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
 pub use bincode;
@@ -39,6 +41,6 @@ pub mod _reexports {
 }
 
 pub use self::{
-    objects::StoredObject,
+    objects::{StoredObject, ZstdSnapshotFactoryDependencies},
     raw::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory},
 };
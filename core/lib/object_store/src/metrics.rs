@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, Histogram, LabeledFamily, LatencyObserver, Metrics};
+use vise::{Buckets, Counter, Histogram, LabeledFamily, LatencyObserver, Metrics};
 
 use crate::Bucket;
 
@@ -29,3 +29,29 @@ impl GcsMetrics {
 
 #[vise::register]
 pub(crate) static GCS_METRICS: vise::Global<GcsMetrics> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_object_store_s3")]
+pub(crate) struct S3Metrics {
+    /// Latency to fetch an object from S3.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
+    fetching_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Latency to store an object in S3.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
+    storing_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of parts uploaded in multipart uploads.
+    pub uploaded_parts: Counter,
+}
+
+impl S3Metrics {
+    pub fn start_fetch(&self, bucket: Bucket) -> LatencyObserver<'_> {
+        self.fetching_time[&bucket.as_str()].start()
+    }
+
+    pub fn start_store(&self, bucket: Bucket) -> LatencyObserver<'_> {
+        self.storing_time[&bucket.as_str()].start()
+    }
+}
+
+#[vise::register]
+pub(crate) static S3_METRICS: vise::Global<S3Metrics> = vise::Global::new();
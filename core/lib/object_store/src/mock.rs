@@ -46,6 +46,10 @@ impl ObjectStore for MockStore {
         Ok(())
     }
 
+    async fn size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        Ok(self.get_raw(bucket, key).await?.len() as u64)
+    }
+
     fn storage_prefix_raw(&self, bucket: Bucket) -> String {
         bucket.to_string()
     }
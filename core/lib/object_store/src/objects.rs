@@ -1,6 +1,6 @@
 //! Stored objects.
 
-use std::io::{Read, Write};
+use std::io::{self, BufReader, Read, Write};
 
 use anyhow::Context;
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
@@ -8,7 +8,8 @@ use prost::Message;
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
     snapshots::{
-        SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotFactoryDependencies, SnapshotFactoryDependency, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
     },
     storage::witness_block_state::WitnessBlockState,
     L1BatchNumber,
@@ -16,6 +17,32 @@ use zksync_types::{
 
 use crate::raw::{BoxedError, Bucket, ObjectStore, ObjectStoreError};
 
+/// Field number of `factory_deps` in the `SnapshotFactoryDependencies` Protobuf message.
+const FACTORY_DEPS_FIELD_NUMBER: u32 = 1;
+/// Protobuf wire type for length-delimited fields (e.g., embedded messages).
+const LENGTH_DELIMITED_WIRE_TYPE: u64 = 2;
+
+/// Reads a Protobuf varint. Returns `None` if the reader has ended before the varint start.
+fn read_varint(reader: &mut impl Read) -> io::Result<Option<u64>> {
+    let mut value = 0_u64;
+    for i in 0..10 {
+        let mut byte = [0_u8];
+        match reader.read_exact(&mut byte) {
+            Ok(()) => {}
+            Err(err) if i == 0 && err.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err),
+        }
+        value |= u64::from(byte[0] & 0x7f) << (7 * i);
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "varint is too long",
+    ))
+}
+
 /// Object that can be stored in an [`ObjectStore`].
 pub trait StoredObject: Sized {
     /// Bucket in which values are stored.
@@ -88,6 +115,74 @@ impl StoredObject for SnapshotFactoryDependencies {
     }
 }
 
+/// Factory dependencies of a [`SnapshotVersion::Version1`] snapshot. Unlike factory dependencies
+/// of version 0 snapshots, they are compressed using zstd rather than gzip.
+///
+/// [`SnapshotVersion::Version1`]: zksync_types::snapshots::SnapshotVersion::Version1
+#[derive(Debug)]
+pub struct ZstdSnapshotFactoryDependencies {
+    pub factory_deps: SnapshotFactoryDependencies,
+    /// zstd compression level used during serialization. Ignored during deserialization.
+    pub compression_level: i32,
+}
+
+impl StoredObject for ZstdSnapshotFactoryDependencies {
+    const BUCKET: Bucket = Bucket::StorageSnapshot;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("snapshot_l1_batch_{key}_factory_deps.proto.zst")
+    }
+
+    /// Encodes and compresses dependencies one by one, so that the entire uncompressed message
+    /// is never held in memory.
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        let mut encoder = zstd::stream::Encoder::new(Vec::new(), self.compression_level)?;
+        let mut encoded_bytes = Vec::new();
+        for factory_dep in &self.factory_deps.factory_deps {
+            // Concatenated encodings of repeated field entries are a valid encoding of the entire message.
+            encoded_bytes.clear();
+            prost::encoding::message::encode(
+                FACTORY_DEPS_FIELD_NUMBER,
+                &factory_dep.build(),
+                &mut encoded_bytes,
+            );
+            encoder.write_all(&encoded_bytes)?;
+        }
+        encoder.finish().map_err(From::from)
+    }
+
+    /// Decompresses and decodes dependencies one by one, mirroring [`Self::serialize()`].
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        let mut decoder = BufReader::new(zstd::stream::Decoder::new(&bytes[..])?);
+        let mut factory_deps = vec![];
+        let mut encoded_bytes = vec![];
+        while let Some(tag) = read_varint(&mut decoder)? {
+            let (field_number, wire_type) = (tag >> 3, tag & 7);
+            if field_number != u64::from(FACTORY_DEPS_FIELD_NUMBER)
+                || wire_type != LENGTH_DELIMITED_WIRE_TYPE
+            {
+                let err = format!(
+                    "unexpected field #{field_number} with wire type {wire_type} in SnapshotFactoryDependencies"
+                );
+                return Err(err.into());
+            }
+            let len = read_varint(&mut decoder)?.ok_or_else(|| {
+                io::Error::new(io::ErrorKind::UnexpectedEof, "missing factory dep length")
+            })?;
+            encoded_bytes.resize(usize::try_from(len)?, 0);
+            decoder.read_exact(&mut encoded_bytes)?;
+            let factory_dep: SnapshotFactoryDependency = decode(&encoded_bytes)
+                .with_context(|| format!("factory_deps[{}]", factory_deps.len()))?;
+            factory_deps.push(factory_dep);
+        }
+        Ok(Self {
+            factory_deps: SnapshotFactoryDependencies { factory_deps },
+            compression_level: 0,
+        })
+    }
+}
+
 impl StoredObject for SnapshotStorageLogsChunk {
     const BUCKET: Bucket = Bucket::StorageSnapshot;
     type Key<'a> = SnapshotStorageLogsStorageKey;
@@ -248,4 +343,41 @@ mod tests {
         let reconstructed_factory_deps = store.get(key).await.unwrap();
         assert_eq!(factory_deps, reconstructed_factory_deps);
     }
+
+    #[tokio::test]
+    async fn test_zstd_factory_deps_can_be_serialized_and_deserialized() {
+        let store = ObjectStoreFactory::mock().create_store().await;
+        let key = L1BatchNumber(123);
+        let factory_deps = SnapshotFactoryDependencies {
+            factory_deps: (0..10)
+                .map(|i| SnapshotFactoryDependency {
+                    bytecode: Bytes(vec![i; 32 * (usize::from(i) + 1)]),
+                })
+                .collect(),
+        };
+        let zstd_factory_deps = ZstdSnapshotFactoryDependencies {
+            factory_deps,
+            compression_level: 3,
+        };
+        let filename = store.put(key, &zstd_factory_deps).await.unwrap();
+        assert!(filename.ends_with(".proto.zst"), "{filename}");
+
+        let reconstructed: ZstdSnapshotFactoryDependencies = store.get(key).await.unwrap();
+        assert_eq!(reconstructed.factory_deps, zstd_factory_deps.factory_deps);
+
+        // Check that the encoding is compatible with the one used for the entire message.
+        let serialized = zstd_factory_deps.serialize().unwrap();
+        let decompressed = zstd::stream::decode_all(&serialized[..]).unwrap();
+        assert_eq!(
+            decompressed,
+            zstd_factory_deps.factory_deps.build().encode_to_vec()
+        );
+
+        let truncated =
+            zstd::stream::encode_all(&decompressed[..decompressed.len() - 1], 3).unwrap();
+        let err = ZstdSnapshotFactoryDependencies::deserialize(truncated)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("fill whole buffer"), "{err}");
+    }
 }
@@ -7,6 +7,7 @@ use crate::{
     file::FileBackedObjectStore,
     gcs::{GoogleCloudStorage, GoogleCloudStorageAuthMode},
     mock::MockStore,
+    s3::S3Storage,
 };
 
 /// Bucket for [`ObjectStore`] in which objects can be placed.
@@ -24,6 +25,7 @@ pub enum Bucket {
     SchedulerWitnessJobsFri,
     ProofsFri,
     StorageSnapshot,
+    RocksdbBackups,
 }
 
 impl Bucket {
@@ -40,6 +42,7 @@ impl Bucket {
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
             Self::StorageSnapshot => "storage_logs_snapshots",
+            Self::RocksdbBackups => "rocksdb_backups",
         }
     }
 }
@@ -120,6 +123,16 @@ pub trait ObjectStore: 'static + fmt::Debug + Send + Sync {
     /// Returns an error if removal fails.
     async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError>;
 
+    /// Returns the size of the value associated with the key in bytes. The default implementation
+    /// fetches the value; implementations should override it to use object metadata if possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object with the `key` does not exist or cannot be accessed.
+    async fn size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        Ok(self.get_raw(bucket, key).await?.len() as u64)
+    }
+
     fn storage_prefix_raw(&self, bucket: Bucket) -> String;
 }
 
@@ -142,6 +155,10 @@ impl<T: ObjectStore + ?Sized> ObjectStore for Arc<T> {
         (**self).remove_raw(bucket, key).await
     }
 
+    async fn size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        (**self).size_raw(bucket, key).await
+    }
+
     fn storage_prefix_raw(&self, bucket: Bucket) -> String {
         (**self).storage_prefix_raw(bucket)
     }
@@ -235,6 +252,24 @@ impl ObjectStoreFactory {
                 .await;
                 Arc::new(store)
             }
+            ObjectStoreMode::S3 {
+                bucket_base_url,
+                s3_endpoint,
+                s3_region,
+                s3_credential_file_path,
+            } => {
+                tracing::trace!("Initialized S3 Object store");
+                let store = S3Storage::new(
+                    s3_endpoint,
+                    s3_region.clone(),
+                    bucket_base_url.clone(),
+                    s3_credential_file_path.clone(),
+                    config.max_retries,
+                )
+                .await
+                .expect("failed initializing S3 object store");
+                Arc::new(store)
+            }
         }
     }
 }
@@ -0,0 +1,541 @@
+//! [`ObjectStore`] implementation for S3-compatible services (AWS S3, MinIO, Cloudflare R2 etc.).
+//!
+//! Requests are sent using the AWS SDK with path-style addressing (`{endpoint}/{bucket}/{object}`), which is supported
+//! by all major S3-compatible services. Objects larger than [`MULTIPART_PART_SIZE`] are uploaded using multipart uploads,
+//! so that a failed request only requires to re-upload a single part rather than the entire object.
+
+use std::{
+    error, fmt,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use aws_config::{retry::RetryConfig, BehaviorVersion, Region};
+use aws_credential_types::{
+    provider::{error::CredentialsError, future, ProvideCredentials},
+    Credentials,
+};
+use aws_sdk_s3::{
+    config::http::HttpResponse,
+    error::SdkError,
+    primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart},
+    Client,
+};
+use bytes::Bytes;
+use serde::Deserialize;
+
+use crate::{
+    metrics::S3_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Size of a single part in multipart uploads. Objects not exceeding this size are uploaded with a single request.
+/// Must be at least 5 MiB, which is the minimum part size supported by S3.
+const MULTIPART_PART_SIZE: usize = 16 << 20;
+/// Interval after which credentials are re-read from the credentials file.
+const CREDENTIALS_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Converts an SDK error into an [`ObjectStoreError`]. Transient errors are already retried by the SDK
+/// at this point, and authorization errors (e.g., `403 Forbidden`) are never retried.
+fn map_sdk_error<E>(err: SdkError<E, HttpResponse>) -> ObjectStoreError
+where
+    E: error::Error + Send + Sync + 'static,
+{
+    let is_not_found = err
+        .raw_response()
+        .map_or(false, |response| response.status().as_u16() == 404);
+    if is_not_found {
+        ObjectStoreError::KeyNotFound(err.into())
+    } else {
+        ObjectStoreError::Other(err.into())
+    }
+}
+
+/// Contents of the credentials file.
+#[derive(Deserialize)]
+struct CredentialsFile {
+    access_key_id: String,
+    secret_access_key: String,
+    #[serde(default)]
+    session_token: Option<String>,
+}
+
+/// Provider reading credentials from a JSON file with `access_key_id`, `secret_access_key` and optional
+/// `session_token` fields. Provided credentials expire after [`CREDENTIALS_REFRESH_INTERVAL`], after which
+/// the SDK re-reads the file, so that credentials can be rotated without restarting the node
+/// (e.g., if the file is a mounted Kubernetes secret).
+#[derive(Debug)]
+struct FileCredentialsProvider {
+    path: String,
+}
+
+impl FileCredentialsProvider {
+    async fn read(&self) -> Result<Credentials, CredentialsError> {
+        let path = &self.path;
+        let contents = tokio::fs::read(path).await.map_err(|err| {
+            CredentialsError::provider_error(format!(
+                "failed reading S3 credentials file `{path}`: {err}"
+            ))
+        })?;
+        let file: CredentialsFile = serde_json::from_slice(&contents).map_err(|err| {
+            CredentialsError::invalid_configuration(format!(
+                "failed parsing S3 credentials file `{path}`: {err}"
+            ))
+        })?;
+        Ok(Credentials::new(
+            file.access_key_id,
+            file.secret_access_key,
+            file.session_token,
+            Some(SystemTime::now() + CREDENTIALS_REFRESH_INTERVAL),
+            "zksync_credentials_file",
+        ))
+    }
+}
+
+impl ProvideCredentials for FileCredentialsProvider {
+    fn provide_credentials<'a>(&'a self) -> future::ProvideCredentials<'a>
+    where
+        Self: 'a,
+    {
+        future::ProvideCredentials::new(self.read())
+    }
+}
+
+/// Checks that the endpoint is a valid absolute URL, so that misconfiguration is detected on initialization
+/// rather than on the first request.
+fn check_endpoint(endpoint: &str) -> anyhow::Result<()> {
+    let uri: http::Uri = endpoint
+        .parse()
+        .with_context(|| format!("invalid S3 endpoint `{endpoint}`"))?;
+    anyhow::ensure!(
+        uri.scheme().is_some() && uri.host().is_some(),
+        "S3 endpoint `{endpoint}` must be an absolute URL"
+    );
+    Ok(())
+}
+
+pub struct S3Storage {
+    client: Client,
+    endpoint: String,
+    bucket: String,
+    multipart_part_size: usize,
+}
+
+impl fmt::Debug for S3Storage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("S3Storage")
+            .field("endpoint", &self.endpoint)
+            .field("bucket", &self.bucket)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Storage {
+    /// Creates a new S3-compatible store. If `credential_file_path` is not specified, credentials are obtained
+    /// using the default AWS credentials chain (e.g., from the `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`
+    /// and `AWS_SESSION_TOKEN` env variables).
+    pub async fn new(
+        endpoint: &str,
+        region: String,
+        bucket: String,
+        credential_file_path: Option<String>,
+        max_retries: u16,
+    ) -> anyhow::Result<Self> {
+        let endpoint = endpoint.trim_end_matches('/').to_owned();
+        check_endpoint(&endpoint)?;
+
+        let retry_config = RetryConfig::standard().with_max_attempts(u32::from(max_retries) + 1);
+        let mut config_loader = aws_config::defaults(BehaviorVersion::latest())
+            .region(Region::new(region))
+            .endpoint_url(&endpoint)
+            .retry_config(retry_config);
+        if let Some(path) = credential_file_path {
+            let provider = FileCredentialsProvider { path };
+            // Check that the credentials file is readable on initialization.
+            provider.read().await.with_context(|| {
+                format!("failed reading S3 credentials file `{}`", provider.path)
+            })?;
+            config_loader = config_loader.credentials_provider(provider);
+        }
+        let sdk_config = config_loader.load().await;
+        let config = aws_sdk_s3::config::Builder::from(&sdk_config)
+            .force_path_style(true)
+            .build();
+
+        Ok(Self {
+            client: Client::from_conf(config),
+            endpoint,
+            bucket,
+            multipart_part_size: MULTIPART_PART_SIZE,
+        })
+    }
+
+    fn object_name(bucket: Bucket, key: &str) -> String {
+        format!("{}/{key}", bucket.as_str())
+    }
+
+    async fn put_multipart(&self, object: &str, value: Bytes) -> Result<(), ObjectStoreError> {
+        let upload = self
+            .client
+            .create_multipart_upload()
+            .bucket(&self.bucket)
+            .key(object)
+            .send()
+            .await
+            .map_err(map_sdk_error)?;
+        let upload_id = upload.upload_id().ok_or_else(|| {
+            let message =
+                format!("no upload ID in response to creating multipart upload for `{object}`");
+            ObjectStoreError::Other(message.into())
+        })?;
+
+        let result = self.upload_parts(object, upload_id, value).await;
+        if result.is_err() {
+            // Abort the upload, so that uploaded parts don't occupy storage.
+            let abort_result = self
+                .client
+                .abort_multipart_upload()
+                .bucket(&self.bucket)
+                .key(object)
+                .upload_id(upload_id)
+                .send()
+                .await;
+            if let Err(err) = abort_result {
+                tracing::warn!("Failed aborting multipart upload for `{object}`: {err}");
+            }
+        }
+        result
+    }
+
+    async fn upload_parts(
+        &self,
+        object: &str,
+        upload_id: &str,
+        value: Bytes,
+    ) -> Result<(), ObjectStoreError> {
+        let mut parts = vec![];
+        for (i, start) in (0..value.len())
+            .step_by(self.multipart_part_size)
+            .enumerate()
+        {
+            let part_number = i32::try_from(i + 1).expect("too many parts in multipart upload");
+            let end = (start + self.multipart_part_size).min(value.len());
+            // `Bytes::slice()` doesn't copy data, and the SDK only clones the handle on retries.
+            let body = ByteStream::from(value.slice(start..end));
+            let output = self
+                .client
+                .upload_part()
+                .bucket(&self.bucket)
+                .key(object)
+                .upload_id(upload_id)
+                .part_number(part_number)
+                .body(body)
+                .send()
+                .await
+                .map_err(map_sdk_error)?;
+            let etag = output.e_tag().ok_or_else(|| {
+                let message = format!("no ETag for part #{part_number} of `{object}`");
+                ObjectStoreError::Other(message.into())
+            })?;
+            parts.push(
+                CompletedPart::builder()
+                    .part_number(part_number)
+                    .e_tag(etag)
+                    .build(),
+            );
+            S3_METRICS.uploaded_parts.inc();
+        }
+
+        let completed_upload = CompletedMultipartUpload::builder()
+            .set_parts(Some(parts))
+            .build();
+        self.client
+            .complete_multipart_upload()
+            .bucket(&self.bucket)
+            .key(object)
+            .upload_id(upload_id)
+            .multipart_upload(completed_upload)
+            .send()
+            .await
+            .map_err(map_sdk_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3Storage {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = S3_METRICS.start_fetch(bucket);
+        let object = Self::object_name(bucket, key);
+        tracing::trace!(
+            "Fetching data from S3 for key {object} from bucket {}",
+            self.bucket
+        );
+
+        let blob = async {
+            let output = self
+                .client
+                .get_object()
+                .bucket(&self.bucket)
+                .key(&object)
+                .send()
+                .await
+                .map_err(map_sdk_error)?;
+            let body = output
+                .body
+                .collect()
+                .await
+                .map_err(|err| ObjectStoreError::Other(err.into()))?;
+            Ok(body.to_vec())
+        }
+        .await;
+
+        let elapsed = fetch_latency.observe();
+        tracing::trace!(
+            "Fetched data from S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        blob
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = S3_METRICS.start_store(bucket);
+        let object = Self::object_name(bucket, key);
+        tracing::trace!(
+            "Storing data to S3 for key {object} from bucket {}",
+            self.bucket
+        );
+
+        let value = Bytes::from(value);
+        let result = if value.len() > self.multipart_part_size {
+            self.put_multipart(&object, value).await
+        } else {
+            self.client
+                .put_object()
+                .bucket(&self.bucket)
+                .key(&object)
+                .body(ByteStream::from(value))
+                .send()
+                .await
+                .map(drop)
+                .map_err(map_sdk_error)
+        };
+
+        let elapsed = store_latency.observe();
+        tracing::trace!(
+            "Stored data to S3 for key {key} from bucket {bucket} and it took: {elapsed:?}"
+        );
+        result
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let object = Self::object_name(bucket, key);
+        tracing::trace!(
+            "Removing data from S3 for key {object} from bucket {}",
+            self.bucket
+        );
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(&object)
+            .send()
+            .await
+            .map(drop)
+            .map_err(map_sdk_error)
+    }
+
+    async fn size_raw(&self, bucket: Bucket, key: &str) -> Result<u64, ObjectStoreError> {
+        let object = Self::object_name(bucket, key);
+        let output = self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(&object)
+            .send()
+            .await
+            .map_err(map_sdk_error)?;
+        let size = output
+            .content_length()
+            .and_then(|len| u64::try_from(len).ok())
+            .ok_or_else(|| {
+                let message = format!("missing or invalid content length for S3 object {object}");
+                ObjectStoreError::Other(message.into())
+            })?;
+        Ok(size)
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!("{}/{}/{}", self.endpoint, self.bucket, bucket.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_smithy_runtime::client::http::test_util::{ReplayEvent, StaticReplayClient};
+    use aws_smithy_types::body::SdkBody;
+
+    use super::*;
+
+    const ENDPOINT: &str = "http://127.0.0.1:9000";
+    const PART_SIZE: usize = 1_024;
+
+    fn mock_storage(http_client: StaticReplayClient) -> S3Storage {
+        let config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .endpoint_url(ENDPOINT)
+            .credentials_provider(Credentials::new("test", "test", None, None, "test"))
+            .force_path_style(true)
+            .http_client(http_client)
+            .build();
+        S3Storage {
+            client: Client::from_conf(config),
+            endpoint: ENDPOINT.to_owned(),
+            bucket: "bucket".to_owned(),
+            multipart_part_size: PART_SIZE,
+        }
+    }
+
+    fn event(
+        request_uri: &str,
+        status: u16,
+        response: http::response::Builder,
+        body: &str,
+    ) -> ReplayEvent {
+        let request = http::Request::builder()
+            .uri(format!("{ENDPOINT}/bucket/{request_uri}"))
+            .body(SdkBody::empty())
+            .unwrap();
+        let response = response.status(status).body(SdkBody::from(body)).unwrap();
+        ReplayEvent::new(request, response)
+    }
+
+    const CREATE_UPLOAD_RESPONSE: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <InitiateMultipartUploadResult><Bucket>bucket</Bucket><Key>proof_fri/1.bin</Key>\
+        <UploadId>upload-id</UploadId></InitiateMultipartUploadResult>";
+
+    fn request_uris(http_client: &StaticReplayClient) -> Vec<String> {
+        http_client
+            .actual_requests()
+            .map(|request| {
+                let uri = request.uri();
+                uri.strip_prefix(ENDPOINT).unwrap_or(uri).to_owned()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn multipart_upload() {
+        let http_client = StaticReplayClient::new(vec![
+            event(
+                "proof_fri/1.bin?uploads",
+                200,
+                http::Response::builder(),
+                CREATE_UPLOAD_RESPONSE,
+            ),
+            event(
+                "proof_fri/1.bin?x-id=UploadPart&partNumber=1&uploadId=upload-id",
+                200,
+                http::Response::builder().header("ETag", "\"etag-1\""),
+                "",
+            ),
+            event(
+                "proof_fri/1.bin?x-id=UploadPart&partNumber=2&uploadId=upload-id",
+                200,
+                http::Response::builder().header("ETag", "\"etag-2\""),
+                "",
+            ),
+            event(
+                "proof_fri/1.bin?uploadId=upload-id",
+                200,
+                http::Response::builder(),
+                "<CompleteMultipartUploadResult><Bucket>bucket</Bucket><Key>proof_fri/1.bin</Key>\
+                 <ETag>\"etag\"</ETag></CompleteMultipartUploadResult>",
+            ),
+        ]);
+        let storage = mock_storage(http_client.clone());
+
+        let value = vec![1_u8; PART_SIZE + PART_SIZE / 2];
+        storage
+            .put_raw(Bucket::ProofsFri, "1.bin", value)
+            .await
+            .unwrap();
+
+        let requests: Vec<_> = http_client.actual_requests().collect();
+        assert_eq!(requests.len(), 4, "{:?}", request_uris(&http_client));
+        let part_sizes: Vec<_> = requests[1..3]
+            .iter()
+            .map(|request| request.body().bytes().unwrap().len())
+            .collect();
+        assert_eq!(part_sizes, [PART_SIZE, PART_SIZE / 2]);
+        let completion = std::str::from_utf8(requests[3].body().bytes().unwrap()).unwrap();
+        assert!(completion.contains("etag-1"), "{completion}");
+        assert!(completion.contains("etag-2"), "{completion}");
+        assert!(
+            completion.contains("<PartNumber>2</PartNumber>"),
+            "{completion}"
+        );
+    }
+
+    #[tokio::test]
+    async fn aborting_failed_multipart_upload() {
+        let http_client = StaticReplayClient::new(vec![
+            event(
+                "proof_fri/1.bin?uploads",
+                200,
+                http::Response::builder(),
+                CREATE_UPLOAD_RESPONSE,
+            ),
+            // Authorization errors must not be retried.
+            event(
+                "proof_fri/1.bin?x-id=UploadPart&partNumber=1&uploadId=upload-id",
+                403,
+                http::Response::builder(),
+                "<Error><Code>AccessDenied</Code><Message>Access Denied</Message></Error>",
+            ),
+            event(
+                "proof_fri/1.bin?x-id=AbortMultipartUpload&uploadId=upload-id",
+                204,
+                http::Response::builder(),
+                "",
+            ),
+        ]);
+        let storage = mock_storage(http_client.clone());
+
+        let value = vec![1_u8; PART_SIZE * 2];
+        let err = storage
+            .put_raw(Bucket::ProofsFri, "1.bin", value)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Other(_)), "{err:?}");
+
+        let requests: Vec<_> = http_client.actual_requests().collect();
+        assert_eq!(requests.len(), 3, "{:?}", request_uris(&http_client));
+        assert_eq!(requests[2].method(), "DELETE");
+    }
+
+    #[tokio::test]
+    async fn missing_object() {
+        let http_client = StaticReplayClient::new(vec![event(
+            "proof_fri/1.bin?x-id=GetObject",
+            404,
+            http::Response::builder(),
+            "<Error><Code>NoSuchKey</Code><Message>The specified key does not exist.</Message></Error>",
+        )]);
+        let storage = mock_storage(http_client.clone());
+
+        let err = storage
+            .get_raw(Bucket::ProofsFri, "1.bin")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err:?}");
+        assert_eq!(http_client.actual_requests().count(), 1);
+    }
+}
@@ -5,7 +5,25 @@ use zksync_protobuf::{
     required,
 };
 
-use crate::{parse_h256, proto};
+use crate::{parse_h160, parse_h256, proto};
+
+impl proto::EthCallFastPathMethod {
+    fn new(x: &api::EthCallFastPathMethod) -> Self {
+        use api::EthCallFastPathMethod as From;
+        match x {
+            From::BalanceOf => Self::BalanceOf,
+            From::Allowance => Self::Allowance,
+        }
+    }
+
+    fn parse(&self) -> api::EthCallFastPathMethod {
+        use api::EthCallFastPathMethod as To;
+        match self {
+            Self::BalanceOf => To::BalanceOf,
+            Self::Allowance => To::Allowance,
+        }
+    }
+}
 
 impl ProtoRepr for proto::Api {
     type Type = ApiConfig;
@@ -31,6 +49,33 @@ impl ProtoRepr for proto::Api {
     }
 }
 
+impl ProtoRepr for proto::SponsoredCall {
+    type Type = api::SponsoredCall;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            contract: required(&self.contract)
+                .and_then(|addr| parse_h160(addr))
+                .context("contract")?,
+            selector: self
+                .selector
+                .as_ref()
+                .map(|selector| {
+                    let selector =
+                        <[u8; 4]>::try_from(selector.as_slice()).context("invalid size")?;
+                    anyhow::Ok(api::FunctionSelector(selector))
+                })
+                .transpose()
+                .context("selector")?,
+        })
+    }
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            contract: Some(this.contract.as_bytes().into()),
+            selector: this.selector.map(|selector| selector.0.to_vec()),
+        }
+    }
+}
+
 impl ProtoRepr for proto::Web3JsonRpc {
     type Type = api::Web3JsonRpcConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -120,6 +165,98 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .transpose()
                 .context("websocket_requests_per_minute_limit")?,
             tree_api_url: self.tree_api_url.clone(),
+            state_keeper_db_replica_path: self.state_keeper_db_replica_path.clone(),
+            state_keeper_db_replica_max_lag: self.state_keeper_db_replica_max_lag,
+            admin_namespace_enabled: self.admin_namespace_enabled.unwrap_or(false),
+            logs_denylisted_addresses: self
+                .logs_denylisted_addresses
+                .iter()
+                .enumerate()
+                .map(|(i, addr)| parse_h160(addr).context(i))
+                .collect::<Result<_, _>>()
+                .context("logs_denylisted_addresses")?,
+            logs_denylisted_topics: self
+                .logs_denylisted_topics
+                .iter()
+                .enumerate()
+                .map(|(i, topic)| parse_h256(topic).context(i))
+                .collect::<Result<_, _>>()
+                .context("logs_denylisted_topics")?,
+            estimate_gas_optimize_search: self.estimate_gas_optimize_search.unwrap_or(false),
+            streamed_methods: self.streamed_methods.clone(),
+            provisional_state_roots_enabled: self.provisional_state_roots_enabled.unwrap_or(false),
+            trace_namespace_enabled: self.trace_namespace_enabled.unwrap_or(false),
+            trace_filter_max_block_range: self.trace_filter_max_block_range,
+            trace_filter_max_traces: self
+                .trace_filter_max_traces
+                .map(|x| x.try_into())
+                .transpose()
+                .context("trace_filter_max_traces")?,
+            eth_call_fast_path_methods: self
+                .eth_call_fast_path_methods
+                .iter()
+                .enumerate()
+                .map(|(i, x)| {
+                    Ok(proto::EthCallFastPathMethod::try_from(*x)
+                        .context(i)?
+                        .parse())
+                })
+                .collect::<anyhow::Result<_>>()
+                .context("eth_call_fast_path_methods")?,
+            eth_call_fast_path_token_code_hashes: self
+                .eth_call_fast_path_token_code_hashes
+                .iter()
+                .enumerate()
+                .map(|(i, hash)| parse_h256(hash).context(i))
+                .collect::<Result<_, _>>()
+                .context("eth_call_fast_path_token_code_hashes")?,
+            rpc_gas_cap: self.rpc_gas_cap,
+            rpc_privileged_gas_cap: self.rpc_privileged_gas_cap,
+            rpc_privileged_api_key_hashes: self
+                .rpc_privileged_api_key_hashes
+                .iter()
+                .enumerate()
+                .map(|(i, hash)| parse_h256(hash).context(i))
+                .collect::<Result<_, _>>()
+                .context("rpc_privileged_api_key_hashes")?,
+            system_contracts_override_enabled: self
+                .system_contracts_override_enabled
+                .unwrap_or(false),
+            sponsor_paymaster_addr: self
+                .sponsor_paymaster_addr
+                .as_ref()
+                .map(|addr| parse_h160(addr))
+                .transpose()
+                .context("sponsor_paymaster_addr")?,
+            sponsored_calls: self
+                .sponsored_calls
+                .iter()
+                .enumerate()
+                .map(|(i, call)| call.read().context(i))
+                .collect::<anyhow::Result<_>>()
+                .context("sponsored_calls")?,
+            sponsorship_budget_gwei: self.sponsorship_budget_gwei,
+            sponsorship_daily_cap_gwei: self.sponsorship_daily_cap_gwei,
+            estimate_gas_batch_max_size: self
+                .estimate_gas_batch_max_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("estimate_gas_batch_max_size")?,
+            get_proof_max_keys: self
+                .get_proof_max_keys
+                .map(|x| x.try_into())
+                .transpose()
+                .context("get_proof_max_keys")?,
+            simulate_bundle_max_size: self
+                .simulate_bundle_max_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("simulate_bundle_max_size")?,
+            estimate_gas_cache_size: self
+                .estimate_gas_cache_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("estimate_gas_cache_size")?,
         })
     }
     fn build(this: &Self::Type) -> Self {
@@ -169,6 +306,55 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .websocket_requests_per_minute_limit
                 .map(|x| x.into()),
             tree_api_url: this.tree_api_url.clone(),
+            state_keeper_db_replica_path: this.state_keeper_db_replica_path.clone(),
+            state_keeper_db_replica_max_lag: this.state_keeper_db_replica_max_lag,
+            admin_namespace_enabled: Some(this.admin_namespace_enabled),
+            logs_denylisted_addresses: this
+                .logs_denylisted_addresses
+                .iter()
+                .map(|addr| addr.as_bytes().into())
+                .collect(),
+            logs_denylisted_topics: this
+                .logs_denylisted_topics
+                .iter()
+                .map(|topic| topic.as_bytes().into())
+                .collect(),
+            estimate_gas_optimize_search: Some(this.estimate_gas_optimize_search),
+            streamed_methods: this.streamed_methods.clone(),
+            provisional_state_roots_enabled: Some(this.provisional_state_roots_enabled),
+            trace_namespace_enabled: Some(this.trace_namespace_enabled),
+            trace_filter_max_block_range: this.trace_filter_max_block_range,
+            trace_filter_max_traces: this.trace_filter_max_traces.map(|x| x.try_into().unwrap()),
+            eth_call_fast_path_methods: this
+                .eth_call_fast_path_methods
+                .iter()
+                .map(|x| proto::EthCallFastPathMethod::new(x).into())
+                .collect(),
+            eth_call_fast_path_token_code_hashes: this
+                .eth_call_fast_path_token_code_hashes
+                .iter()
+                .map(|hash| hash.as_bytes().into())
+                .collect(),
+            rpc_gas_cap: this.rpc_gas_cap,
+            rpc_privileged_gas_cap: this.rpc_privileged_gas_cap,
+            rpc_privileged_api_key_hashes: this
+                .rpc_privileged_api_key_hashes
+                .iter()
+                .map(|hash| hash.as_bytes().into())
+                .collect(),
+            system_contracts_override_enabled: Some(this.system_contracts_override_enabled),
+            sponsor_paymaster_addr: this
+                .sponsor_paymaster_addr
+                .map(|addr| addr.as_bytes().into()),
+            sponsored_calls: this.sponsored_calls.iter().map(ProtoRepr::build).collect(),
+            sponsorship_budget_gwei: this.sponsorship_budget_gwei,
+            sponsorship_daily_cap_gwei: this.sponsorship_daily_cap_gwei,
+            estimate_gas_batch_max_size: this
+                .estimate_gas_batch_max_size
+                .map(|x| x.try_into().unwrap()),
+            get_proof_max_keys: this.get_proof_max_keys.map(|x| x.try_into().unwrap()),
+            simulate_bundle_max_size: this.simulate_bundle_max_size.map(|x| x.try_into().unwrap()),
+            estimate_gas_cache_size: this.estimate_gas_cache_size.map(|x| x.try_into().unwrap()),
         }
     }
 }
@@ -220,11 +406,17 @@ impl ProtoRepr for proto::MerkleTreeApi {
             port: required(&self.port)
                 .and_then(|p| Ok((*p).try_into()?))
                 .context("port")?,
+            grpc_port: self
+                .grpc_port
+                .map(|p| p.try_into())
+                .transpose()
+                .context("grpc_port")?,
         })
     }
     fn build(this: &Self::Type) -> Self {
         Self {
             port: Some(this.port.into()),
+            grpc_port: this.grpc_port.map(Into::into),
         }
     }
 }
@@ -84,6 +84,7 @@ impl ProtoRepr for proto::StateKeeper {
             transaction_slots: required(&self.transaction_slots)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("transaction_slots")?,
+            dynamic_transaction_slots: self.dynamic_transaction_slots.unwrap_or(false),
             block_commit_deadline_ms: *required(&self.block_commit_deadline_ms)
                 .context("block_commit_deadline_ms")?,
             miniblock_commit_deadline_ms: *required(&self.miniblock_commit_deadline_ms)
@@ -140,12 +141,41 @@ impl ProtoRepr for proto::StateKeeper {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("enum_index_migration_chunk_size")?,
+            denylisted_tx_initiators: self
+                .denylisted_tx_initiators
+                .iter()
+                .enumerate()
+                .map(|(i, addr)| parse_h160(addr).context(i))
+                .collect::<Result<_, _>>()
+                .context("denylisted_tx_initiators")?,
+            max_tx_calldata_size: self
+                .max_tx_calldata_size
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_tx_calldata_size")?,
+            tx_execution_timeout_ms: self.tx_execution_timeout_ms,
+            l2_tx_intrinsic_gas_override: self.l2_tx_intrinsic_gas_override,
+            halt_on_l1_state_mismatch: self.halt_on_l1_state_mismatch.unwrap_or(false),
+            upgrade_shadow_execution_batches: self.upgrade_shadow_execution_batches,
+            miniblock_seal_wal_path: self.miniblock_seal_wal_path.clone(),
+            miniblock_seal_wal_max_miniblocks: self
+                .miniblock_seal_wal_max_miniblocks
+                .map(|x| x.try_into())
+                .transpose()
+                .context("miniblock_seal_wal_max_miniblocks")?,
+            fee_input_override_path: self.fee_input_override_path.clone(),
+            fee_input_override_max_fair_l2_gas_price: self.fee_input_override_max_fair_l2_gas_price,
+            fee_input_override_max_fair_pubdata_price: self
+                .fee_input_override_max_fair_pubdata_price,
+            max_witness_input_size_bytes: self.max_witness_input_size_bytes,
+            max_pubdata_per_batch_with_blobs: self.max_pubdata_per_batch_with_blobs,
         })
     }
 
     fn build(this: &Self::Type) -> Self {
         Self {
             transaction_slots: Some(this.transaction_slots.try_into().unwrap()),
+            dynamic_transaction_slots: Some(this.dynamic_transaction_slots),
             block_commit_deadline_ms: Some(this.block_commit_deadline_ms),
             miniblock_commit_deadline_ms: Some(this.miniblock_commit_deadline_ms),
             miniblock_seal_queue_capacity: Some(
@@ -176,6 +206,26 @@ impl ProtoRepr for proto::StateKeeper {
                 .enum_index_migration_chunk_size
                 .as_ref()
                 .map(|x| (*x).try_into().unwrap()),
+            denylisted_tx_initiators: this
+                .denylisted_tx_initiators
+                .iter()
+                .map(|addr| addr.as_bytes().into())
+                .collect(),
+            max_tx_calldata_size: this.max_tx_calldata_size.map(|x| x.try_into().unwrap()),
+            tx_execution_timeout_ms: this.tx_execution_timeout_ms,
+            l2_tx_intrinsic_gas_override: this.l2_tx_intrinsic_gas_override,
+            halt_on_l1_state_mismatch: Some(this.halt_on_l1_state_mismatch),
+            upgrade_shadow_execution_batches: this.upgrade_shadow_execution_batches,
+            miniblock_seal_wal_path: this.miniblock_seal_wal_path.clone(),
+            miniblock_seal_wal_max_miniblocks: this
+                .miniblock_seal_wal_max_miniblocks
+                .map(|x| x.try_into().unwrap()),
+            fee_input_override_path: this.fee_input_override_path.clone(),
+            fee_input_override_max_fair_l2_gas_price: this.fee_input_override_max_fair_l2_gas_price,
+            fee_input_override_max_fair_pubdata_price: this
+                .fee_input_override_max_fair_pubdata_price,
+            max_witness_input_size_bytes: this.max_witness_input_size_bytes,
+            max_pubdata_per_batch_with_blobs: this.max_pubdata_per_batch_with_blobs,
         }
     }
 }
@@ -207,6 +257,8 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: *required(&self.stuck_tx_timeout).context("stuck_tx_timeout")?,
             remove_stuck_txs: *required(&self.remove_stuck_txs).context("remove_stuck_txs")?,
             delay_interval: *required(&self.delay_interval).context("delay_interval")?,
+            fee_priority_ordering: self.fee_priority_ordering.unwrap_or(false),
+            fee_priority_max_wait_ms: self.fee_priority_max_wait_ms,
         })
     }
 
@@ -218,6 +270,8 @@ impl ProtoRepr for proto::Mempool {
             stuck_tx_timeout: Some(this.stuck_tx_timeout),
             remove_stuck_txs: Some(this.remove_stuck_txs),
             delay_interval: Some(this.delay_interval),
+            fee_priority_ordering: Some(this.fee_priority_ordering),
+            fee_priority_max_wait_ms: this.fee_priority_max_wait_ms,
         }
     }
 }
@@ -234,6 +288,10 @@ impl ProtoRepr for proto::CircuitBreaker {
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("http_req_retry_interval_sec")?,
             replication_lag_limit_sec: self.replication_lag_limit_sec,
+            l1_contracts_version_check: self.l1_contracts_version_check.unwrap_or(false),
+            l1_contracts_mismatch_degraded_mode: self
+                .l1_contracts_mismatch_degraded_mode
+                .unwrap_or(false),
         })
     }
 
@@ -243,6 +301,8 @@ impl ProtoRepr for proto::CircuitBreaker {
             http_req_max_retry_number: Some(this.http_req_max_retry_number.try_into().unwrap()),
             http_req_retry_interval_sec: Some(this.http_req_retry_interval_sec.into()),
             replication_lag_limit_sec: this.replication_lag_limit_sec,
+            l1_contracts_version_check: Some(this.l1_contracts_version_check),
+            l1_contracts_mismatch_degraded_mode: Some(this.l1_contracts_mismatch_degraded_mode),
         }
     }
 }
@@ -48,6 +48,7 @@ impl ProtoRepr for proto::MerkleTree {
             max_l1_batches_per_iter: required(&self.max_l1_batches_per_iter)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_l1_batches_per_iter")?,
+            miniblock_root_hashes_enabled: self.miniblock_root_hashes_enabled.unwrap_or(false),
         })
     }
 
@@ -60,6 +61,7 @@ impl ProtoRepr for proto::MerkleTree {
             memtable_capacity_mb: Some(this.memtable_capacity_mb.try_into().unwrap()),
             stalled_writes_timeout_sec: Some(this.stalled_writes_timeout_sec),
             max_l1_batches_per_iter: Some(this.max_l1_batches_per_iter.try_into().unwrap()),
+            miniblock_root_hashes_enabled: Some(this.miniblock_root_hashes_enabled),
         }
     }
 }
@@ -96,6 +98,8 @@ impl ProtoRepr for proto::Postgres {
             statement_timeout_sec: self.statement_timeout_sec,
             long_connection_threshold_ms: self.long_connection_threshold_ms,
             slow_query_threshold_ms: self.slow_query_threshold_ms,
+            read_replica_urls: self.read_replica_urls.clone(),
+            max_replica_lag_sec: self.max_replica_lag_sec,
         })
     }
 
@@ -109,6 +113,8 @@ impl ProtoRepr for proto::Postgres {
             statement_timeout_sec: this.statement_timeout_sec,
             long_connection_threshold_ms: this.long_connection_threshold_ms,
             slow_query_threshold_ms: this.slow_query_threshold_ms,
+            read_replica_urls: this.read_replica_urls.clone(),
+            max_replica_lag_sec: this.max_replica_lag_sec,
         }
     }
 }
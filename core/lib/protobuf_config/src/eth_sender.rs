@@ -2,7 +2,7 @@ use anyhow::Context as _;
 use zksync_config::configs::{self};
 use zksync_protobuf::{read_required_repr, required, ProtoRepr};
 
-use crate::proto;
+use crate::{parse_h160, proto};
 
 impl proto::ProofSendingMode {
     fn new(x: &configs::eth_sender::ProofSendingMode) -> Self {
@@ -66,6 +66,18 @@ impl ProtoRepr for proto::EthSender {
         Ok(Self::Type {
             sender: read_required_repr(&self.sender).context("sender")?,
             gas_adjuster: read_required_repr(&self.gas_adjuster).context("gas_adjuster")?,
+            remote_signer: self
+                .remote_signer
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("remote_signer")?,
+            tx_relay: self
+                .tx_relay
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("tx_relay")?,
         })
     }
 
@@ -73,6 +85,60 @@ impl ProtoRepr for proto::EthSender {
         Self {
             sender: Some(ProtoRepr::build(&this.sender)),
             gas_adjuster: Some(ProtoRepr::build(&this.gas_adjuster)),
+            remote_signer: this.remote_signer.as_ref().map(ProtoRepr::build),
+            tx_relay: this.tx_relay.as_ref().map(ProtoRepr::build),
+        }
+    }
+}
+
+impl ProtoRepr for proto::TxRelay {
+    type Type = configs::eth_sender::TxRelayConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            url: required(&self.url).context("url")?.clone(),
+            public_fallback_timeout_sec: *required(&self.public_fallback_timeout_sec)
+                .context("public_fallback_timeout_sec")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            url: Some(this.url.clone()),
+            public_fallback_timeout_sec: Some(this.public_fallback_timeout_sec),
+        }
+    }
+}
+
+impl ProtoRepr for proto::RemoteSigner {
+    type Type = configs::eth_sender::RemoteSignerConfig;
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            url: required(&self.url).context("url")?.clone(),
+            operator_address: required(&self.operator_address)
+                .and_then(|x| parse_h160(x))
+                .context("operator_address")?,
+            operator_blobs_address: self
+                .operator_blobs_address
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("operator_blobs_address")?,
+            request_timeout_ms: *required(&self.request_timeout_ms)
+                .context("request_timeout_ms")?,
+            max_retries: *required(&self.max_retries).context("max_retries")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            url: Some(this.url.clone()),
+            operator_address: Some(this.operator_address.as_bytes().into()),
+            operator_blobs_address: this
+                .operator_blobs_address
+                .as_ref()
+                .map(|x| x.as_bytes().into()),
+            request_timeout_ms: Some(this.request_timeout_ms),
+            max_retries: Some(this.max_retries),
         }
     }
 }
@@ -128,6 +194,15 @@ impl ProtoRepr for proto::Sender {
                 .and_then(|x| Ok(proto::PubdataSendingMode::try_from(*x)?))
                 .context("pubdata_sending_mode")?
                 .parse(),
+            dynamic_aggregation_windows: self.dynamic_aggregation_windows.unwrap_or(false),
+            send_gate_max_base_fee_in_gwei: self.send_gate_max_base_fee_in_gwei,
+            send_gate_max_blob_base_fee_in_gwei: self.send_gate_max_blob_base_fee_in_gwei,
+            send_gate_max_delay_seconds: self.send_gate_max_delay_seconds,
+            kzg_precomputation_workers: self
+                .kzg_precomputation_workers
+                .map(|x| x.try_into())
+                .transpose()
+                .context("kzg_precomputation_workers")?,
         })
     }
 
@@ -159,6 +234,13 @@ impl ProtoRepr for proto::Sender {
             pubdata_sending_mode: Some(
                 proto::PubdataSendingMode::new(&this.pubdata_sending_mode).into(),
             ),
+            dynamic_aggregation_windows: Some(this.dynamic_aggregation_windows),
+            send_gate_max_base_fee_in_gwei: this.send_gate_max_base_fee_in_gwei,
+            send_gate_max_blob_base_fee_in_gwei: this.send_gate_max_blob_base_fee_in_gwei,
+            send_gate_max_delay_seconds: this.send_gate_max_delay_seconds,
+            kzg_precomputation_workers: this
+                .kzg_precomputation_workers
+                .map(|x| x.try_into().unwrap()),
         }
     }
 }
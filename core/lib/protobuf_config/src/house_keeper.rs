@@ -50,6 +50,19 @@ impl ProtoRepr for proto::HouseKeeper {
                 &self.fri_proof_compressor_stats_reporting_interval_ms,
             )
             .context("fri_proof_compressor_stats_reporting_interval_ms")?,
+            utilization_report_generation_interval_ms: self
+                .utilization_report_generation_interval_ms
+                .unwrap_or(Self::Type::default_utilization_report_generation_interval_ms()),
+            prover_artifacts_gc_interval_ms: self
+                .prover_artifacts_gc_interval_ms
+                .unwrap_or(Self::Type::default_prover_artifacts_gc_interval_ms()),
+            prover_artifacts_retention_secs: self.prover_artifacts_retention_secs,
+            prover_artifacts_gc_dry_run: self.prover_artifacts_gc_dry_run.unwrap_or(false),
+            factory_deps_gc_interval_ms: self
+                .factory_deps_gc_interval_ms
+                .unwrap_or(Self::Type::default_factory_deps_gc_interval_ms()),
+            factory_deps_retention_secs: self.factory_deps_retention_secs,
+            factory_deps_gc_dry_run: self.factory_deps_gc_dry_run.unwrap_or(false),
         })
     }
 
@@ -82,6 +95,15 @@ impl ProtoRepr for proto::HouseKeeper {
             fri_proof_compressor_stats_reporting_interval_ms: Some(
                 this.fri_proof_compressor_stats_reporting_interval_ms,
             ),
+            utilization_report_generation_interval_ms: Some(
+                this.utilization_report_generation_interval_ms,
+            ),
+            prover_artifacts_gc_interval_ms: Some(this.prover_artifacts_gc_interval_ms),
+            prover_artifacts_retention_secs: this.prover_artifacts_retention_secs,
+            prover_artifacts_gc_dry_run: Some(this.prover_artifacts_gc_dry_run),
+            factory_deps_gc_interval_ms: Some(this.factory_deps_gc_interval_ms),
+            factory_deps_retention_secs: this.factory_deps_retention_secs,
+            factory_deps_gc_dry_run: Some(this.factory_deps_gc_dry_run),
         }
     }
 }
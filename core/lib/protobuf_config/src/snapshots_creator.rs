@@ -12,6 +12,18 @@ impl ProtoRepr for proto::SnapshotsCreator {
                 .context("storage_logs_chunk_size")?,
             concurrent_queries_count: *required(&self.concurrent_queries_count)
                 .context("concurrent_queries_count")?,
+            version: self
+                .version
+                .unwrap_or_default()
+                .try_into()
+                .context("version")?,
+            factory_deps_compression_level: self.factory_deps_compression_level,
+            max_chunks_per_minute: self.max_chunks_per_minute,
+            off_peak_start_hour_utc: self.off_peak_start_hour_utc,
+            off_peak_end_hour_utc: self.off_peak_end_hour_utc,
+            max_replication_lag_sec: self.max_replication_lag_sec,
+            max_probe_latency_ms: self.max_probe_latency_ms,
+            throttle_backoff_interval_ms: self.throttle_backoff_interval_ms,
         })
     }
 
@@ -19,6 +31,14 @@ impl ProtoRepr for proto::SnapshotsCreator {
         Self {
             storage_logs_chunk_size: Some(this.storage_logs_chunk_size),
             concurrent_queries_count: Some(this.concurrent_queries_count),
+            version: Some(this.version.into()),
+            factory_deps_compression_level: this.factory_deps_compression_level,
+            max_chunks_per_minute: this.max_chunks_per_minute,
+            off_peak_start_hour_utc: this.off_peak_start_hour_utc,
+            off_peak_end_hour_utc: this.off_peak_end_hour_utc,
+            max_replication_lag_sec: this.max_replication_lag_sec,
+            max_probe_latency_ms: this.max_probe_latency_ms,
+            throttle_backoff_interval_ms: this.throttle_backoff_interval_ms,
         }
     }
 }
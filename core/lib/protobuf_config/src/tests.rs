@@ -1,6 +1,6 @@
 use pretty_assertions::assert_eq;
 use rand::Rng;
-use zksync_config::testonly;
+use zksync_config::{configs::house_keeper::HouseKeeperConfig, testonly};
 use zksync_protobuf::repr::ProtoRepr;
 
 use crate::proto;
@@ -91,3 +91,33 @@ fn test_encoding() {
     encode_decode::<proto::SnapshotsCreator>(rng);
     encode_decode::<proto::WitnessGenerator>(rng);
 }
+
+/// Tests that configs without recently added optional fields can be read.
+#[test]
+fn reading_house_keeper_config_with_defaults() {
+    let rng = &mut rand::thread_rng();
+    let config: HouseKeeperConfig = testonly::Gen {
+        rng,
+        required_only: true,
+        decimal_fractions: false,
+    }
+    .gen();
+    let mut proto = proto::HouseKeeper::build(&config);
+    proto.utilization_report_generation_interval_ms = None;
+    proto.prover_artifacts_gc_interval_ms = None;
+    proto.factory_deps_gc_interval_ms = None;
+
+    let config = proto.read().unwrap();
+    assert_eq!(
+        config.utilization_report_generation_interval_ms,
+        HouseKeeperConfig::default_utilization_report_generation_interval_ms()
+    );
+    assert_eq!(
+        config.prover_artifacts_gc_interval_ms,
+        HouseKeeperConfig::default_prover_artifacts_gc_interval_ms()
+    );
+    assert_eq!(
+        config.factory_deps_gc_interval_ms,
+        HouseKeeperConfig::default_factory_deps_gc_interval_ms()
+    );
+}
@@ -8,12 +8,12 @@ use serde::Serialize;
 use tokio::sync::Semaphore;
 use zksync_dal::{ConnectionPool, SqlxError, StorageProcessor};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_object_store::{ObjectStore, ObjectStoreError, ZstdSnapshotFactoryDependencies};
 use zksync_types::{
     api::en::SyncBlock,
     snapshots::{
         SnapshotFactoryDependencies, SnapshotHeader, SnapshotRecoveryStatus, SnapshotStorageLog,
-        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
+        SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey, SnapshotVersion,
     },
     tokens::TokenInfo,
     web3::futures,
@@ -244,11 +244,12 @@ struct SnapshotsApplier<'a> {
 }
 
 impl<'a> SnapshotsApplier<'a> {
-    /// Recovers [`SnapshotRecoveryStatus`] from the storage and the main node.
+    /// Recovers [`SnapshotRecoveryStatus`] from the storage and the main node. If the status is created
+    /// from scratch, also returns the version of the snapshot.
     async fn prepare_applied_snapshot_status(
         storage: &mut StorageProcessor<'_>,
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
-    ) -> Result<(SnapshotRecoveryStatus, bool), SnapshotsApplierError> {
+    ) -> Result<(SnapshotRecoveryStatus, Option<SnapshotVersion>), SnapshotsApplierError> {
         let latency =
             METRICS.initial_stage_duration[&InitialStage::FetchMetadataFromMainNode].start();
 
@@ -264,7 +265,7 @@ impl<'a> SnapshotsApplier<'a> {
             let latency = latency.observe();
             tracing::info!("Re-initialized snapshots applier after reset/failure in {latency:?}");
 
-            Ok((applied_snapshot_status, false))
+            Ok((applied_snapshot_status, None))
         } else {
             let is_genesis_needed =
                 storage
@@ -281,7 +282,7 @@ impl<'a> SnapshotsApplier<'a> {
                 return Err(SnapshotsApplierError::Fatal(err));
             }
 
-            let recovery_status =
+            let (recovery_status, version) =
                 SnapshotsApplier::create_fresh_recovery_status(main_node_client).await?;
 
             let storage_logs_count = storage
@@ -302,7 +303,7 @@ impl<'a> SnapshotsApplier<'a> {
 
             let latency = latency.observe();
             tracing::info!("Initialized fresh snapshots applier in {latency:?}");
-            Ok((recovery_status, true))
+            Ok((recovery_status, Some(version)))
         }
     }
 
@@ -321,7 +322,7 @@ impl<'a> SnapshotsApplier<'a> {
             SnapshotsApplierError::db(err, "failed starting initial DB transaction")
         })?;
 
-        let (applied_snapshot_status, created_from_scratch) =
+        let (applied_snapshot_status, fresh_snapshot_version) =
             Self::prepare_applied_snapshot_status(&mut storage_transaction, main_node_client)
                 .await?;
 
@@ -331,7 +332,7 @@ impl<'a> SnapshotsApplier<'a> {
             blob_store,
             applied_snapshot_status,
             health_updater,
-            factory_deps_recovered: !created_from_scratch,
+            factory_deps_recovered: fresh_snapshot_version.is_none(),
             tokens_recovered: false,
         };
 
@@ -346,8 +347,9 @@ impl<'a> SnapshotsApplier<'a> {
         );
         this.update_health();
 
-        if created_from_scratch {
-            this.recover_factory_deps(&mut storage_transaction).await?;
+        if let Some(version) = fresh_snapshot_version {
+            this.recover_factory_deps(&mut storage_transaction, version)
+                .await?;
             storage_transaction
                 .snapshot_recovery_dal()
                 .insert_initial_recovery_status(&this.applied_snapshot_status)
@@ -373,15 +375,22 @@ impl<'a> SnapshotsApplier<'a> {
 
     async fn create_fresh_recovery_status(
         main_node_client: &dyn SnapshotsApplierMainNodeClient,
-    ) -> Result<SnapshotRecoveryStatus, SnapshotsApplierError> {
+    ) -> Result<(SnapshotRecoveryStatus, SnapshotVersion), SnapshotsApplierError> {
         let snapshot_response = main_node_client.fetch_newest_snapshot().await?;
 
         let snapshot = snapshot_response
             .context("no snapshots on main node; snapshot recovery is impossible")?;
         let l1_batch_number = snapshot.l1_batch_number;
         let miniblock_number = snapshot.miniblock_number;
+        let version = SnapshotVersion::try_from(snapshot.version).with_context(|| {
+            format!(
+                "snapshot for L1 batch #{l1_batch_number} has a version not supported by this node; \
+                 update the node or use an older snapshot"
+            )
+        })?;
         tracing::info!(
-            "Found snapshot with data up to L1 batch #{l1_batch_number}, storage_logs are divided into {} chunk(s)",
+            "Found snapshot (version {version:?}) with data up to L1 batch #{l1_batch_number}, \
+             storage_logs are divided into {} chunk(s)",
             snapshot.storage_logs_chunks.len()
         );
 
@@ -393,7 +402,7 @@ impl<'a> SnapshotsApplier<'a> {
             .hash
             .context("snapshot miniblock fetched from main node doesn't have hash set")?;
 
-        Ok(SnapshotRecoveryStatus {
+        let status = SnapshotRecoveryStatus {
             l1_batch_number,
             l1_batch_timestamp: snapshot.last_l1_batch_with_metadata.header.timestamp,
             l1_batch_root_hash: snapshot.last_l1_batch_with_metadata.metadata.root_hash,
@@ -406,7 +415,8 @@ impl<'a> SnapshotsApplier<'a> {
                 .protocol_version
                 .unwrap(),
             storage_logs_chunks_processed: vec![false; snapshot.storage_logs_chunks.len()],
-        })
+        };
+        Ok((status, version))
     }
 
     fn update_health(&self) {
@@ -429,18 +439,30 @@ impl<'a> SnapshotsApplier<'a> {
     async fn recover_factory_deps(
         &mut self,
         storage: &mut StorageProcessor<'_>,
+        version: SnapshotVersion,
     ) -> Result<(), SnapshotsApplierError> {
         let latency = METRICS.initial_stage_duration[&InitialStage::ApplyFactoryDeps].start();
 
         tracing::debug!("Fetching factory dependencies from object store");
         let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
-        let factory_deps: SnapshotFactoryDependencies =
-            self.blob_store.get(l1_batch_number).await.map_err(|err| {
-                let context = format!(
-                    "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
-                );
-                SnapshotsApplierError::object_store(err, context)
-            })?;
+        let factory_deps = match version {
+            SnapshotVersion::Version0 => {
+                self.blob_store
+                    .get::<SnapshotFactoryDependencies>(l1_batch_number)
+                    .await
+            }
+            SnapshotVersion::Version1 => self
+                .blob_store
+                .get::<ZstdSnapshotFactoryDependencies>(l1_batch_number)
+                .await
+                .map(|deps| deps.factory_deps),
+        };
+        let factory_deps = factory_deps.map_err(|err| {
+            let context = format!(
+                "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
+            );
+            SnapshotsApplierError::object_store(err, context)
+        })?;
         tracing::debug!(
             "Fetched {} factory dependencies from object store",
             factory_deps.factory_deps.len()
@@ -3,7 +3,7 @@
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use test_casing::test_casing;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{Bucket, ObjectStoreFactory, StoredObject};
 use zksync_types::{
     block::{L1BatchHeader, MiniblockHeader},
     get_code_key, Address, L1BatchNumber, ProtocolVersion, ProtocolVersionId,
@@ -96,6 +96,65 @@ async fn snapshots_creator_can_successfully_recover_db(
         .unwrap();
 }
 
+#[tokio::test]
+async fn recovering_db_from_zstd_compressed_snapshot() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    client
+        .fetch_newest_snapshot_response
+        .as_mut()
+        .unwrap()
+        .version = SnapshotVersion::Version1 as u16;
+    // Remove gzip-compressed factory deps to check that they are not used.
+    let gzip_key = SnapshotFactoryDependencies::encode_key(expected_status.l1_batch_number);
+    object_store
+        .remove_raw(Bucket::StorageSnapshot, &gzip_key)
+        .await
+        .unwrap();
+
+    SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap();
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let current_db_status = storage
+        .snapshot_recovery_dal()
+        .get_applied_snapshot_status()
+        .await
+        .unwrap();
+    assert_eq!(current_db_status.unwrap(), expected_status);
+    let factory_dep_bytes: Vec<u8> = (0..32).collect();
+    let factory_dep = storage
+        .factory_deps_dal()
+        .get_factory_dep(hash_bytecode(&factory_dep_bytes))
+        .await
+        .unwrap();
+    assert_eq!(factory_dep, Some(factory_dep_bytes));
+}
+
+#[tokio::test]
+async fn applier_errors_on_unsupported_snapshot_version() {
+    let pool = ConnectionPool::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (object_store, mut client) = prepare_clients(&expected_status, &storage_logs).await;
+    client
+        .fetch_newest_snapshot_response
+        .as_mut()
+        .unwrap()
+        .version = 42;
+
+    let err = SnapshotsApplierConfig::for_tests()
+        .run(&pool, &client, &object_store)
+        .await
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("unsupported snapshot version: 42"), "{err}");
+}
+
 #[tokio::test]
 async fn applier_errors_after_genesis() {
     let pool = ConnectionPool::test_pool().await;
@@ -119,6 +178,7 @@ async fn applier_errors_after_genesis() {
         base_system_contracts_hashes: Default::default(),
         protocol_version: Some(ProtocolVersionId::latest()),
         virtual_blocks: 0,
+        logs_bloom: Default::default(),
     };
     storage
         .blocks_dal()
@@ -3,7 +3,9 @@
 use std::{collections::HashMap, fmt, sync::Arc};
 
 use async_trait::async_trait;
-use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory};
+use zksync_object_store::{
+    Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory, ZstdSnapshotFactoryDependencies,
+};
 use zksync_types::{
     api::en::SyncBlock,
     block::L1BatchHeader,
@@ -223,6 +225,15 @@ pub(super) async fn prepare_clients(
         .put(status.l1_batch_number, &factory_deps)
         .await
         .unwrap();
+    // Also store zstd-compressed factory deps, so that the clients can be used with any snapshot version.
+    let zstd_factory_deps = ZstdSnapshotFactoryDependencies {
+        factory_deps,
+        compression_level: 3,
+    };
+    object_store
+        .put(status.l1_batch_number, &zstd_factory_deps)
+        .await
+        .unwrap();
 
     let chunk_size = logs
         .len()
@@ -244,6 +255,7 @@ pub(super) async fn prepare_clients(
     }
 
     let snapshot_header = SnapshotHeader {
+        version: 0,
         l1_batch_number: status.l1_batch_number,
         miniblock_number: status.miniblock_number,
         last_l1_batch_with_metadata: l1_block_metadata(
@@ -11,7 +11,8 @@ use zksync_types::{L1BatchNumber, MiniblockNumber, StorageKey, StorageValue, H25
 use self::metrics::{Method, ValuesUpdateStage, CACHE_METRICS, STORAGE_METRICS};
 use crate::{
     cache::{Cache, CacheValue},
-    ReadStorage,
+    rocksdb::RocksdbReplicaView,
+    ReadStorage, RocksdbReplica,
 };
 
 mod metrics;
@@ -280,6 +281,9 @@ impl PostgresStorageCaches {
         );
         tracing::debug!("Initializing VM storage values cache with {capacity}B capacity");
 
+        // Cache updates must never move back in time, which could happen if they were served by read replicas
+        // with different replication lag. Hence, we always use the main database URL for updates.
+        let connection_pool = connection_pool.without_read_replicas();
         let (command_sender, mut command_receiver) = mpsc::unbounded_channel();
         let values_cache = ValuesCache::new(capacity);
         self.values = Some(ValuesCacheAndUpdater {
@@ -339,6 +343,7 @@ pub struct PostgresStorage<'a> {
     pending_l1_batch_number: L1BatchNumber,
     consider_new_l1_batch: bool,
     caches: Option<PostgresStorageCaches>,
+    replica: Option<RocksdbReplicaView>,
 }
 
 impl<'a> PostgresStorage<'a> {
@@ -390,6 +395,7 @@ impl<'a> PostgresStorage<'a> {
             pending_l1_batch_number: resolved.pending_l1_batch,
             consider_new_l1_batch,
             caches: None,
+            replica: None,
         })
     }
 
@@ -402,6 +408,29 @@ impl<'a> PostgresStorage<'a> {
         }
     }
 
+    /// Uses the provided replica of the state keeper cache to read the storage. The replica is only used if it contains
+    /// the state as of the start of the L1 batch of the requested miniblock, and the requested miniblock is not too far
+    /// ahead of the replica. Storage slots modified since the start of the L1 batch are still read from Postgres.
+    ///
+    /// The replica is never used if new L1 batches are considered (i.e., `consider_new_l1_batch` was set
+    /// when creating the storage).
+    ///
+    /// # Errors
+    ///
+    /// Propagates Postgres errors.
+    pub async fn with_rocksdb_replica(mut self, replica: &RocksdbReplica) -> anyhow::Result<Self> {
+        if !self.consider_new_l1_batch {
+            self.replica = replica
+                .view(
+                    &mut self.connection,
+                    self.miniblock_number,
+                    self.l1_batch_number_for_miniblock,
+                )
+                .await?;
+        }
+        Ok(self)
+    }
+
     /// This method is expected to be called for each write that was found in the database, and it decides
     /// whether the change is initial or not. Even if a change is present in the DB, in some cases we would not consider it.
     /// For example, in API we always represent the state at the beginning of an L1 batch, so we discard all the writes
@@ -423,7 +452,9 @@ impl ReadStorage for PostgresStorage<'_> {
     fn read_value(&mut self, &key: &StorageKey) -> StorageValue {
         let latency = STORAGE_METRICS.storage[&Method::ReadValue].start();
         let values_cache = self.values_cache();
-        let cached_value = values_cache.and_then(|cache| cache.get(self.miniblock_number, &key));
+        let cached_value = values_cache
+            .and_then(|cache| cache.get(self.miniblock_number, &key))
+            .or_else(|| self.replica.as_ref()?.read_value(&key));
 
         let value = cached_value.unwrap_or_else(|| {
             let mut dal = self.connection.storage_web3_dal();
@@ -443,6 +474,12 @@ impl ReadStorage for PostgresStorage<'_> {
 
     fn is_write_initial(&mut self, key: &StorageKey) -> bool {
         let latency = STORAGE_METRICS.storage[&Method::IsWriteInitial].start();
+        if let Some(replica) = &self.replica {
+            let is_initial = replica.is_write_initial(key);
+            latency.observe();
+            return is_initial;
+        }
+
         let caches = self.caches.as_ref();
         let cached_value = caches.and_then(|caches| caches.initial_writes.get(key));
 
@@ -496,7 +533,8 @@ impl ReadStorage for PostgresStorage<'_> {
         let cached_value = self
             .caches
             .as_ref()
-            .and_then(|caches| caches.factory_deps.get(&hash));
+            .and_then(|caches| caches.factory_deps.get(&hash))
+            .or_else(|| self.replica.as_ref()?.load_factory_dep(hash));
 
         let result = cached_value.or_else(|| {
             let mut dal = self.connection.storage_web3_dal();
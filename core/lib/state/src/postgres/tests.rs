@@ -7,11 +7,16 @@ use rand::{
     seq::{IteratorRandom, SliceRandom},
     Rng, SeedableRng,
 };
+use tempfile::TempDir;
+use tokio::sync::watch;
 use zksync_dal::ConnectionPool;
 use zksync_types::StorageLog;
 
 use super::*;
-use crate::test_utils::{create_l1_batch, create_miniblock, gen_storage_logs, prepare_postgres};
+use crate::{
+    test_utils::{create_l1_batch, create_miniblock, gen_storage_logs, prepare_postgres},
+    RocksdbStorage,
+};
 
 fn test_postgres_storage_basics(
     pool: &ConnectionPool,
@@ -597,3 +602,111 @@ async fn mini_fuzz_values_cache() {
         .await
         .unwrap();
 }
+
+async fn sync_rocksdb_cache(
+    dir: &TempDir,
+    connection: &mut StorageProcessor<'_>,
+) -> RocksdbStorage {
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    RocksdbStorage::builder(dir.path())
+        .await
+        .unwrap()
+        .synchronize(connection, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("RocksDB synchronization unexpectedly stopped")
+}
+
+#[tokio::test]
+async fn using_rocksdb_replica() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut connection = pool.access_storage().await.unwrap();
+    prepare_postgres(&mut connection).await;
+    let existing_logs = gen_storage_logs(20..40);
+    create_miniblock(&mut connection, MiniblockNumber(1), existing_logs.clone()).await;
+    create_l1_batch(&mut connection, L1BatchNumber(1), &existing_logs).await;
+
+    let cache_dir = TempDir::new().unwrap();
+    let replica_dir = TempDir::new().unwrap();
+    let cache = sync_rocksdb_cache(&cache_dir, &mut connection).await;
+    let (replica, replica_task) = RocksdbReplica::new(
+        cache_dir.path().to_path_buf(),
+        replica_dir.path().to_path_buf(),
+        1,
+    );
+    replica_task.catch_up().await.unwrap();
+
+    // Overwrite some of the existing slots and write new ones in a pending miniblock.
+    let new_logs: Vec<_> = existing_logs[..5]
+        .iter()
+        .map(|log| StorageLog::new_write_log(log.key, H256::repeat_byte(0xff)))
+        .chain(gen_storage_logs(40..45))
+        .collect();
+    create_miniblock(&mut connection, MiniblockNumber(2), new_logs.clone()).await;
+
+    let storage =
+        PostgresStorage::new_async(Handle::current(), connection, MiniblockNumber(2), false)
+            .await
+            .unwrap()
+            .with_rocksdb_replica(&replica)
+            .await
+            .unwrap();
+    assert!(storage.replica.is_some());
+    let (existing_logs_clone, new_logs_clone) = (existing_logs.clone(), new_logs.clone());
+    let storage = tokio::task::spawn_blocking(move || {
+        let mut storage = storage;
+        for log in &existing_logs_clone[5..] {
+            assert_eq!(storage.read_value(&log.key), log.value);
+            assert!(!storage.is_write_initial(&log.key));
+        }
+        for log in &new_logs_clone {
+            assert_eq!(storage.read_value(&log.key), log.value);
+        }
+        for log in &new_logs_clone[5..] {
+            assert!(storage.is_write_initial(&log.key));
+        }
+        storage
+    })
+    .await
+    .unwrap();
+
+    // The replica shouldn't be used if the requested state is too far ahead of it.
+    drop(storage.replica);
+    let mut connection = storage.connection;
+    create_miniblock(&mut connection, MiniblockNumber(3), vec![]).await;
+    let storage =
+        PostgresStorage::new_async(Handle::current(), connection, MiniblockNumber(3), false)
+            .await
+            .unwrap()
+            .with_rocksdb_replica(&replica)
+            .await
+            .unwrap();
+    assert!(storage.replica.is_none());
+
+    // Sealing the L1 batch doesn't invalidate the replica until it catches up with the cache.
+    let mut connection = storage.connection;
+    create_l1_batch(&mut connection, L1BatchNumber(2), &new_logs[5..]).await;
+    drop(cache);
+    let _cache = sync_rocksdb_cache(&cache_dir, &mut connection).await;
+    let storage =
+        PostgresStorage::new_async(Handle::current(), connection, MiniblockNumber(2), false)
+            .await
+            .unwrap()
+            .with_rocksdb_replica(&replica)
+            .await
+            .unwrap();
+    assert!(storage.replica.is_some());
+
+    // Release the replica view, so that the replica can catch up with the cache.
+    drop(storage.replica);
+    let connection = storage.connection;
+    replica_task.catch_up().await.unwrap();
+    let storage =
+        PostgresStorage::new_async(Handle::current(), connection, MiniblockNumber(2), false)
+            .await
+            .unwrap()
+            .with_rocksdb_replica(&replica)
+            .await
+            .unwrap();
+    assert!(storage.replica.is_none());
+}
@@ -2,7 +2,9 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_state_keeper_secondary_storage")]
@@ -51,3 +53,35 @@ pub(super) struct RocksdbRecoveryMetrics {
 
 #[vise::register]
 pub(super) static RECOVERY_METRICS: vise::Global<RocksdbRecoveryMetrics> = vise::Global::new();
+
+/// Outcome of an attempt to use [`RocksdbReplica`](super::RocksdbReplica) for a VM storage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum ReplicaUsage {
+    /// The replica was used.
+    Used,
+    /// The replica isn't opened yet, or is empty.
+    NotReady,
+    /// The replica is catching up with the primary instance.
+    CatchingUp,
+    /// The replica state doesn't correspond to the L1 batch of the requested state.
+    OutOfSync,
+    /// The requested state is too many miniblocks ahead of the replica.
+    Lagging,
+}
+
+/// Metrics for the read-only replica of the state keeper RocksDB cache.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_state_keeper_storage_replica")]
+pub(super) struct RocksdbReplicaMetrics {
+    /// Latency of catching up with the primary instance.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub catch_up: Histogram<Duration>,
+    /// L1 batch number of the replica (i.e., the last L1 batch processed by the primary instance + 1).
+    pub l1_batch_number: Gauge<u64>,
+    /// Number of attempts to use the replica for a VM storage, grouped by the outcome.
+    pub usage: Family<ReplicaUsage, Counter>,
+}
+
+#[vise::register]
+pub(super) static REPLICA_METRICS: vise::Global<RocksdbReplicaMetrics> = vise::Global::new();
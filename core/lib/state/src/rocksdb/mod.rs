@@ -36,12 +36,15 @@ use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256, U256};
 use zksync_utils::{h256_to_u256, u256_to_h256};
 
 use self::metrics::METRICS;
+pub(crate) use self::replica::RocksdbReplicaView;
+pub use self::replica::{RocksdbReplica, RocksdbReplicaTask};
 #[cfg(test)]
 use self::tests::RocksdbStorageEventListener;
 use crate::{InMemoryStorage, ReadStorage};
 
 mod metrics;
 mod recovery;
+mod replica;
 #[cfg(test)]
 mod tests;
 
@@ -214,6 +217,20 @@ impl RocksdbStorage {
             .map(RocksbStorageBuilder)
     }
 
+    /// Creates a consistent checkpoint of the storage at the specified `path`, which must not exist.
+    /// The checkpoint only contains changes persisted in RocksDB; changes in the pending patch are not included.
+    ///
+    /// This method is blocking and should be wrapped in `spawn_blocking(_)` if run in the async context.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB I/O errors.
+    pub fn create_checkpoint(&self, path: &Path) -> anyhow::Result<()> {
+        self.db
+            .create_checkpoint(path)
+            .with_context(|| format!("failed creating checkpoint at `{}`", path.display()))
+    }
+
     async fn new(path: PathBuf) -> anyhow::Result<Self> {
         tokio::task::spawn_blocking(move || {
             Ok(Self {
@@ -640,7 +657,9 @@ impl RocksdbStorage {
     }
 }
 
-impl ReadStorage for RocksdbStorage {
+/// Allows to share the storage among several VM instances (e.g., in order to execute the same transactions
+/// with different VM versions); all reads only need a shared reference.
+impl ReadStorage for &RocksdbStorage {
     fn read_value(&mut self, key: &StorageKey) -> StorageValue {
         self.read_value_inner(key).unwrap_or_else(H256::zero)
     }
@@ -659,7 +678,25 @@ impl ReadStorage for RocksdbStorage {
     fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
         // Can safely unwrap here since it indicates that the migration has not yet ended and boojum will
         // only be deployed when the migration is finished.
-        Self::read_state_value(&self.db, key.hashed_key())
+        RocksdbStorage::read_state_value(&self.db, key.hashed_key())
             .map(|state_value| state_value.enum_index.unwrap())
     }
 }
+
+impl ReadStorage for RocksdbStorage {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        (&*self).read_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        (&*self).is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        (&*self).load_factory_dep(hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        (&*self).get_enumeration_index(key)
+    }
+}
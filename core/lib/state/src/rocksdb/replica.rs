@@ -0,0 +1,257 @@
+//! Read-only replica of [`RocksdbStorage`] maintained by the state keeper.
+
+use std::{
+    collections::HashSet,
+    path::PathBuf,
+    sync::{Arc, Weak},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use tokio::sync::{watch, OwnedRwLockReadGuard, RwLock};
+use zksync_dal::StorageProcessor;
+use zksync_storage::RocksDB;
+use zksync_types::{L1BatchNumber, MiniblockNumber, StorageKey, StorageValue, H256};
+
+use super::{
+    deserialize_l1_batch_number,
+    metrics::{ReplicaUsage, REPLICA_METRICS},
+    RocksdbStorage, StateKeeperColumnFamily,
+};
+
+#[derive(Debug, Default)]
+struct ReplicaState {
+    db: Option<RocksDB<StateKeeperColumnFamily>>,
+    /// Last processed L1 batch number + 1, as in [`RocksdbStorage`].
+    l1_batch_number: Option<L1BatchNumber>,
+}
+
+/// Secondary (read-only) RocksDB instance following the [`RocksdbStorage`] cache of the state keeper.
+///
+/// The replica doesn't see changes in the cache until it catches up with it; this is performed periodically
+/// by [`RocksdbReplicaTask`]. The replica can only be used to read the state within the L1 batch following
+/// the last L1 batch processed by the cache; see [`PostgresStorage::with_rocksdb_replica()`](crate::PostgresStorage::with_rocksdb_replica())
+/// for details.
+#[derive(Debug, Clone)]
+pub struct RocksdbReplica {
+    state: Arc<RwLock<ReplicaState>>,
+    max_lag: u32,
+}
+
+impl RocksdbReplica {
+    /// Creates a replica of the state keeper cache at `primary_path`. The replica keeps its own metadata
+    /// at `secondary_path`. The replica is opened lazily by the returned task, so that the cache doesn't need
+    /// to exist at the time of the call.
+    ///
+    /// `max_lag` is the max number of miniblocks by which the requested state may be ahead of the replica
+    /// for the replica to be used.
+    pub fn new(
+        primary_path: PathBuf,
+        secondary_path: PathBuf,
+        max_lag: u32,
+    ) -> (Self, RocksdbReplicaTask) {
+        let state = Arc::default();
+        let task = RocksdbReplicaTask {
+            state: Arc::downgrade(&state),
+            primary_path,
+            secondary_path,
+        };
+        (Self { state, max_lag }, task)
+    }
+
+    /// Returns a view of the replica to use for the VM state as of `miniblock_number` included into
+    /// (potentially pending) `l1_batch_number`. Returns `None` if the replica cannot be used for this state.
+    pub(crate) async fn view(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<RocksdbReplicaView>> {
+        let outcome = self
+            .try_view(connection, miniblock_number, l1_batch_number)
+            .await?;
+        let (usage, view) = match outcome {
+            Ok(view) => (ReplicaUsage::Used, Some(view)),
+            Err(usage) => (usage, None),
+        };
+        REPLICA_METRICS.usage[&usage].inc();
+        Ok(view)
+    }
+
+    async fn try_view(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        miniblock_number: MiniblockNumber,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Result<RocksdbReplicaView, ReplicaUsage>> {
+        // We don't wait for the lock if the replica is catching up; the catch-up itself is fast, but it may wait
+        // until all in-flight VM invocations using the replica are finished.
+        let Ok(state) = self.state.clone().try_read_owned() else {
+            return Ok(Err(ReplicaUsage::CatchingUp));
+        };
+        let replica_l1_batch_number = state.l1_batch_number;
+        let Ok(db) = OwnedRwLockReadGuard::try_map(state, |state| state.db.as_ref()) else {
+            return Ok(Err(ReplicaUsage::NotReady));
+        };
+        let Some(replica_l1_batch_number) = replica_l1_batch_number else {
+            return Ok(Err(ReplicaUsage::NotReady));
+        };
+        if replica_l1_batch_number != l1_batch_number {
+            return Ok(Err(ReplicaUsage::OutOfSync));
+        }
+
+        // The replica contains the state as of the end of the previous L1 batch; changes made in the miniblocks
+        // of `l1_batch_number` need to be read from Postgres.
+        let Some(prev_l1_batch_number) = l1_batch_number.0.checked_sub(1) else {
+            return Ok(Err(ReplicaUsage::NotReady));
+        };
+        let prev_l1_batch_number = L1BatchNumber(prev_l1_batch_number);
+        let miniblock_range = connection
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(prev_l1_batch_number)
+            .await
+            .with_context(|| {
+                format!("failed getting miniblock range for L1 batch #{prev_l1_batch_number}")
+            })?;
+        // The range may be missing if the previous L1 batch was recovered from a snapshot.
+        let Some((_, last_replicated_miniblock)) = miniblock_range else {
+            return Ok(Err(ReplicaUsage::OutOfSync));
+        };
+        let first_miniblock = last_replicated_miniblock + 1;
+        let lag = (miniblock_number.0 + 1).saturating_sub(first_miniblock.0);
+        if lag > self.max_lag {
+            return Ok(Err(ReplicaUsage::Lagging));
+        }
+
+        let modified_keys = if lag == 0 {
+            HashSet::new()
+        } else {
+            connection
+                .storage_web3_dal()
+                .modified_keys_in_miniblocks(first_miniblock..=miniblock_number)
+                .await
+                .into_iter()
+                .collect()
+        };
+        Ok(Ok(RocksdbReplicaView { db, modified_keys }))
+    }
+}
+
+/// View of a [`RocksdbReplica`] for a specific VM state. The replica doesn't catch up with the primary instance
+/// while the view is alive.
+#[derive(Debug)]
+pub(crate) struct RocksdbReplicaView {
+    db: OwnedRwLockReadGuard<ReplicaState, RocksDB<StateKeeperColumnFamily>>,
+    /// Hashed keys modified since the replica state; these must be read from Postgres.
+    modified_keys: HashSet<H256>,
+}
+
+impl RocksdbReplicaView {
+    /// Returns `None` if the value must be read from Postgres.
+    pub fn read_value(&self, key: &StorageKey) -> Option<StorageValue> {
+        let hashed_key = key.hashed_key();
+        if self.modified_keys.contains(&hashed_key) {
+            return None;
+        }
+        let state_value = RocksdbStorage::read_state_value(&self.db, hashed_key);
+        Some(state_value.map_or_else(H256::zero, |state_value| state_value.value))
+    }
+
+    /// Initial writes only change when an L1 batch is sealed, so the replica is authoritative.
+    pub fn is_write_initial(&self, key: &StorageKey) -> bool {
+        RocksdbStorage::read_state_value(&self.db, key.hashed_key()).is_none()
+    }
+
+    /// Returns `None` if the dependency is missing in the replica; it may still be present in Postgres.
+    pub fn load_factory_dep(&self, hash: H256) -> Option<Vec<u8>> {
+        self.db
+            .get_cf(StateKeeperColumnFamily::FactoryDeps, hash.as_bytes())
+            .expect("failed to read RocksDB replica value")
+    }
+}
+
+/// Task opening a [`RocksdbReplica`] and periodically catching it up with the primary instance.
+/// The task terminates once all replica handles are dropped or a stop signal is received.
+#[derive(Debug)]
+pub struct RocksdbReplicaTask {
+    state: Weak<RwLock<ReplicaState>>,
+    primary_path: PathBuf,
+    secondary_path: PathBuf,
+}
+
+impl RocksdbReplicaTask {
+    /// Runs this task.
+    ///
+    /// # Errors
+    ///
+    /// Propagates RocksDB errors when catching up with the primary instance.
+    pub async fn run(
+        self,
+        catch_up_interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            if self.state.strong_count() == 0 {
+                tracing::info!("RocksDB replica is dropped, stopping catch-up task");
+                return Ok(());
+            }
+            self.catch_up().await?;
+
+            // We don't check the result: if a stop signal is received, we'll return at the start
+            // of the next iteration.
+            tokio::time::timeout(catch_up_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, RocksDB replica catch-up task is shutting down");
+        Ok(())
+    }
+
+    pub(crate) async fn catch_up(&self) -> anyhow::Result<()> {
+        let Some(state) = self.state.upgrade() else {
+            return Ok(());
+        };
+        let mut state = state.write_owned().await;
+        let primary_path = self.primary_path.clone();
+        let secondary_path = self.secondary_path.clone();
+        let latency = REPLICA_METRICS.catch_up.start();
+        let l1_batch_number = tokio::task::spawn_blocking(move || -> anyhow::Result<_> {
+            if let Some(db) = &state.db {
+                db.try_catch_up_with_primary()
+                    .context("failed catching up RocksDB replica with primary instance")?;
+            } else if primary_path.exists() {
+                match RocksDB::open_secondary(&primary_path, &secondary_path) {
+                    Ok(db) => state.db = Some(db),
+                    Err(err) => {
+                        // The primary instance may be in the middle of the initialization.
+                        tracing::warn!("Failed opening RocksDB replica, will retry: {err}");
+                        return Ok(None);
+                    }
+                }
+            } else {
+                return Ok(None);
+            }
+
+            let db = state.db.as_ref().unwrap();
+            let number_bytes = db
+                .get_cf(
+                    StateKeeperColumnFamily::State,
+                    RocksdbStorage::L1_BATCH_NUMBER_KEY,
+                )
+                .context("failed getting L1 batch number from RocksDB replica")?;
+            state.l1_batch_number =
+                number_bytes.map(|bytes| L1BatchNumber(deserialize_l1_batch_number(&bytes)));
+            Ok(state.l1_batch_number)
+        })
+        .await
+        .context("panicked catching up RocksDB replica")??;
+        latency.observe();
+
+        if let Some(l1_batch_number) = l1_batch_number {
+            REPLICA_METRICS
+                .l1_batch_number
+                .set(l1_batch_number.0.into());
+        }
+        Ok(())
+    }
+}
@@ -11,8 +11,9 @@ use std::{
 };
 
 use rocksdb::{
-    properties, BlockBasedOptions, Cache, ColumnFamily, ColumnFamilyDescriptor, DBPinnableSlice,
-    Direction, IteratorMode, Options, PrefixRange, ReadOptions, WriteOptions, DB,
+    checkpoint::Checkpoint, properties, BlockBasedOptions, Cache, ColumnFamily,
+    ColumnFamilyDescriptor, DBPinnableSlice, Direction, IteratorMode, Options, PrefixRange,
+    ReadOptions, WriteOptions, DB,
 };
 
 use crate::metrics::{RocksdbLabels, RocksdbSizeMetrics, METRICS};
@@ -379,6 +380,65 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
         })
     }
 
+    /// Opens a read-only secondary instance following the primary DB at `primary_path`. The secondary instance
+    /// keeps its own info logs and metadata at `secondary_path`, and doesn't see changes made in the primary
+    /// instance until [`Self::try_catch_up_with_primary()`] is called.
+    pub fn open_secondary(
+        primary_path: &Path,
+        secondary_path: &Path,
+    ) -> Result<Self, rocksdb::Error> {
+        let mut db_options = Options::default();
+        // Required by RocksDB for secondary instances, so that they can access all SST files of the primary instance.
+        db_options.set_max_open_files(-1);
+        // Unlike the primary instance, the secondary instance can open a subset of column families.
+        let cf_names: HashSet<_> = CF::ALL.iter().map(NamedColumnFamily::name).collect();
+        let db = DB::open_cf_as_secondary(&db_options, primary_path, secondary_path, &cf_names)?;
+        let inner = Arc::new(RocksDBInner {
+            db,
+            db_name: CF::DB_NAME,
+            cf_names,
+            _registry_entry: RegistryEntry::new(),
+            _caches: RocksDBCaches::new(None),
+        });
+
+        tracing::info!(
+            "Initialized secondary RocksDB `{}` at `{}` following `{}`",
+            CF::DB_NAME,
+            secondary_path.display(),
+            primary_path.display()
+        );
+        Ok(Self {
+            inner,
+            sync_writes: false,
+            stalled_writes_retries: StalledWritesRetries::new(Duration::ZERO),
+            _cf: PhantomData,
+        })
+    }
+
+    /// Makes a secondary instance (see [`Self::open_secondary()`]) catch up with the changes made in the primary instance.
+    /// Changes are applied atomically per write batch, but may be applied while other reads from the instance are in progress.
+    ///
+    /// This method is blocking and should be wrapped in `spawn_blocking(_)` if run in the async context.
+    pub fn try_catch_up_with_primary(&self) -> Result<(), rocksdb::Error> {
+        self.inner.db.try_catch_up_with_primary()
+    }
+
+    /// Creates a consistent point-in-time checkpoint of the DB at the specified `path`, which must not exist.
+    /// The checkpoint can be opened as an ordinary RocksDB instance. SST files are hard-linked into the checkpoint
+    /// if the `path` is on the same filesystem as the DB, so checkpoints are cheap.
+    ///
+    /// This method is blocking and should be wrapped in `spawn_blocking(_)` if run in the async context.
+    pub fn create_checkpoint(&self, path: &Path) -> Result<(), rocksdb::Error> {
+        let checkpoint = Checkpoint::new(&self.inner.db)?;
+        checkpoint.create_checkpoint(path)?;
+        tracing::info!(
+            "Created checkpoint of RocksDB `{}` at `{}`",
+            CF::DB_NAME,
+            path.display()
+        );
+        Ok(())
+    }
+
     /// Switches on sync writes in [`Self::write()`] and [`Self::put()`]. This has a performance
     /// penalty and is mostly useful for tests.
     #[must_use]
@@ -732,4 +792,63 @@ mod tests {
             .unwrap();
         assert_eq!(value, b"value2");
     }
+
+    #[test]
+    fn creating_checkpoint() {
+        let db_dir = TempDir::new().unwrap();
+        let checkpoints_dir = TempDir::new().unwrap();
+        let checkpoint_path = checkpoints_dir.path().join("checkpoint");
+        let db = RocksDB::<NewColumnFamilies>::new(db_dir.path()).unwrap();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Other, b"test", b"value");
+        db.write(batch).unwrap();
+
+        db.create_checkpoint(&checkpoint_path).unwrap();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Other, b"test2", b"value2");
+        db.write(batch).unwrap();
+        drop(db);
+
+        let checkpoint = RocksDB::<NewColumnFamilies>::new(&checkpoint_path).unwrap();
+        let value = checkpoint
+            .get_cf(NewColumnFamilies::Other, b"test")
+            .unwrap();
+        assert_eq!(value.unwrap(), b"value");
+        let value = checkpoint
+            .get_cf(NewColumnFamilies::Other, b"test2")
+            .unwrap();
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn secondary_instance_catches_up_with_primary() {
+        let primary_dir = TempDir::new().unwrap();
+        let secondary_dir = TempDir::new().unwrap();
+        let db = RocksDB::<NewColumnFamilies>::new(primary_dir.path())
+            .unwrap()
+            .with_sync_writes();
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Other, b"test", b"value");
+        db.write(batch).unwrap();
+
+        let secondary =
+            RocksDB::<NewColumnFamilies>::open_secondary(primary_dir.path(), secondary_dir.path())
+                .unwrap();
+        let value = secondary.get_cf(NewColumnFamilies::Other, b"test").unwrap();
+        assert_eq!(value.unwrap(), b"value");
+
+        let mut batch = db.new_write_batch();
+        batch.put_cf(NewColumnFamilies::Other, b"test2", b"value2");
+        db.write(batch).unwrap();
+        let value = secondary
+            .get_cf(NewColumnFamilies::Other, b"test2")
+            .unwrap();
+        assert!(value.is_none());
+
+        secondary.try_catch_up_with_primary().unwrap();
+        let value = secondary
+            .get_cf(NewColumnFamilies::Other, b"test2")
+            .unwrap();
+        assert_eq!(value.unwrap(), b"value2");
+    }
 }
@@ -0,0 +1,63 @@
+//! Benchmarks for sequential and parallel deduplication of storage writes.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use zksync_types::{
+    storage_writes_deduplicator::StorageWritesDeduplicator,
+    zk_evm_types::{LogQuery, Timestamp},
+    Address, StorageLogQuery, StorageLogQueryType, U256,
+};
+
+const WRITE_COUNTS: &[usize] = &[1_000, 10_000];
+
+/// Generates writes where each slot is written ~4 times on average by different transactions.
+fn generate_writes(count: usize) -> Vec<StorageLogQuery> {
+    (0..count)
+        .map(|i| StorageLogQuery {
+            log_query: LogQuery {
+                timestamp: Timestamp(i as u32),
+                tx_number_in_block: (i / 50) as u16,
+                aux_byte: 0,
+                shard_id: 0,
+                address: Address::from_low_u64_be((i % 32) as u64),
+                key: U256::from(i % (count / 4)),
+                read_value: U256::from(i),
+                written_value: U256::from(i + 1),
+                rw_flag: true,
+                rollback: i % 10 == 0,
+                is_service: false,
+            },
+            log_type: if i % 3 == 0 {
+                StorageLogQueryType::InitialWrite
+            } else {
+                StorageLogQueryType::RepeatedWrite
+            },
+        })
+        .collect()
+}
+
+fn deduplication_benches(criterion: &mut Criterion) {
+    let mut group = criterion.benchmark_group("deduplication");
+    for &write_count in WRITE_COUNTS {
+        let writes = generate_writes(write_count);
+        group
+            .throughput(Throughput::Elements(write_count as u64))
+            .bench_with_input(
+                BenchmarkId::new("sequential", write_count),
+                &writes,
+                |bencher, writes| {
+                    bencher.iter(|| StorageWritesDeduplicator::deduplicate_sequentially(writes));
+                },
+            )
+            .bench_with_input(
+                BenchmarkId::new("parallel", write_count),
+                &writes,
+                |bencher, writes| {
+                    bencher.iter(|| StorageWritesDeduplicator::deduplicate_in_parallel(writes));
+                },
+            );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, deduplication_benches);
+criterion_main!(benches);
@@ -1,4 +1,6 @@
-use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::Display;
 use zksync_basic_types::{
@@ -6,15 +8,23 @@ use zksync_basic_types::{
     L1BatchNumber,
 };
 use zksync_contracts::BaseSystemContractsHashes;
+use zksync_system_constants::STATE_DIFF_HASH_KEY;
+use zksync_utils::u256_to_h256;
 
 pub use crate::transaction_request::{
     Eip712Meta, SerializationTransactionError, TransactionRequest,
 };
 use crate::{
+    basic_fri_types::AggregationRound,
+    commitment::{find_blob_linear_hashes, L1BatchWithMetadata},
+    fee_model::{BaseTokenConversionRatio, BatchFeeInput, FeeParams},
+    l2_to_l1_log::SystemL2ToL1Log,
     protocol_version::L1VerifierConfig,
-    vm_trace::{Call, CallType},
+    pubdata_da::PubdataDA,
+    tokens::NftStandard,
+    vm_trace::{Call, CallType, ViolatedValidationRule},
     web3::types::{AccessList, Index, H2048},
-    Address, MiniblockNumber, ProtocolVersionId,
+    Address, L1BlockNumber, MiniblockNumber, PriorityOpId, ProtocolVersionId,
 };
 
 pub mod en;
@@ -328,6 +338,14 @@ pub struct Block<TX> {
     pub mix_hash: H256,
     /// Nonce
     pub nonce: H64,
+    /// Provisional state root hash after the block, computed by the Merkle tree. Unlike `stateRoot`,
+    /// it is not committed to L1. Only returned if enabled in the node configuration.
+    #[serde(
+        rename = "provisionalStateRoot",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub provisional_state_root: Option<H256>,
 }
 
 // We want to implement `Default` for all `TX`s, not only for `TX: Default`, hence this manual impl.
@@ -358,6 +376,7 @@ impl<TX> Default for Block<TX> {
             size: U256::default(),
             mix_hash: H256::default(),
             nonce: H64::default(),
+            provisional_state_root: None,
         }
     }
 }
@@ -523,13 +542,45 @@ pub struct Transaction {
     pub l1_batch_tx_index: Option<U64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TransactionStatus {
     Pending,
     Included,
     Verified,
     Failed,
+    /// The transaction wasn't included before its deadline and was evicted from the mempool.
+    Expired,
+}
+
+/// Inclusion deadline of an L2 transaction. If any of the specified bounds is passed before the transaction
+/// is included into a miniblock, the transaction is evicted from the mempool and gets the
+/// [`Expired`](TransactionStatus::Expired) status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDeadline {
+    /// Latest miniblock timestamp (in seconds since UNIX epoch) the transaction can be included at.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<u64>,
+    /// Latest miniblock number the transaction can be included in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<MiniblockNumber>,
+}
+
+impl TransactionDeadline {
+    /// Checks whether the deadline has no bounds, i.e., doesn't restrict transaction inclusion.
+    pub fn is_empty(&self) -> bool {
+        self.timestamp.is_none() && self.block_number.is_none()
+    }
+
+    /// Checks whether the transaction cannot be included into a miniblock with the specified number and timestamp.
+    pub fn is_passed(&self, miniblock_number: MiniblockNumber, timestamp: u64) -> bool {
+        let is_timestamp_passed = self.timestamp.map_or(false, |bound| timestamp > bound);
+        let is_block_passed = self
+            .block_number
+            .map_or(false, |bound| miniblock_number > bound);
+        is_timestamp_passed || is_block_passed
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -544,6 +595,27 @@ pub struct TransactionDetails {
     pub eth_commit_tx_hash: Option<H256>,
     pub eth_prove_tx_hash: Option<H256>,
     pub eth_execute_tx_hash: Option<H256>,
+    /// Decoded reason of the transaction revert. Only present for transactions reverted by a contract.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<RevertReason>,
+}
+
+/// Reason of a transaction revert, decoded from the data returned by the reverted contract.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RevertReason {
+    /// Revert with the `Error(string)` payload (e.g., produced by `require(condition, message)` in Solidity).
+    Error { message: String },
+    /// Revert with the `Panic(uint256)` payload (e.g., produced by failed assertions or arithmetic overflows).
+    Panic { code: U256 },
+    /// Revert with a custom error, or with data that cannot be decoded.
+    #[serde(rename_all = "camelCase")]
+    Custom {
+        /// Error selector; empty if the contract reverted without data.
+        selector: Bytes,
+        /// ABI-encoded error arguments following the selector.
+        data: Bytes,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -562,6 +634,17 @@ pub struct ResultDebugCall {
     pub result: DebugCall,
 }
 
+/// Call trace persisted for a transaction during its execution, as returned by `zks_getRawBlockTraces`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTrace {
+    pub transaction_hash: H256,
+    pub transaction_index: U64,
+    /// Trace as recorded by the VM. Unlike traces returned by `debug_trace*` methods, it retains
+    /// far call types and gas passed from parent calls.
+    pub trace: Call,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum DebugCallType {
     Call,
@@ -588,7 +671,7 @@ impl From<Call> for DebugCall {
     fn from(value: Call) -> Self {
         let calls = value.calls.into_iter().map(DebugCall::from).collect();
         let debug_type = match value.r#type {
-            CallType::Call(_) => DebugCallType::Call,
+            CallType::Call(_) | CallType::StaticCall => DebugCallType::Call,
             CallType::Create => DebugCallType::Create,
             CallType::NearCall => unreachable!("We have to filter our near calls before"),
         };
@@ -608,6 +691,131 @@ impl From<Call> for DebugCall {
     }
 }
 
+/// Filter for `trace_filter` (OpenEthereum-compatible).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+    /// First miniblock in the range (inclusive). If not specified, the latest sealed miniblock is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<BlockNumber>,
+    /// Last miniblock in the range (inclusive). If not specified, the latest sealed miniblock is used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<BlockNumber>,
+    /// If specified, only traces of calls made by one of these addresses are returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_address: Option<Vec<Address>>,
+    /// If specified, only traces of calls to one of these addresses are returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_address: Option<Vec<Address>>,
+    /// Number of matching traces to skip.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<usize>,
+    /// Maximum number of traces to return.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub count: Option<usize>,
+}
+
+/// Type of a trace returned by `trace_filter`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceType {
+    Call,
+    Create,
+}
+
+/// Call type of a [`TraceCallAction`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceCallType {
+    Call,
+    DelegateCall,
+    StaticCall,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCallAction {
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    pub gas: U256,
+    pub input: Bytes,
+    pub call_type: TraceCallType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceCreateAction {
+    pub from: Address,
+    pub value: U256,
+    pub gas: U256,
+    pub init: Bytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TraceAction {
+    Call(TraceCallAction),
+    Create(TraceCreateAction),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TraceResult {
+    #[serde(rename_all = "camelCase")]
+    Call { gas_used: U256, output: Bytes },
+    #[serde(rename_all = "camelCase")]
+    Create {
+        gas_used: U256,
+        code: Bytes,
+        address: Address,
+    },
+}
+
+/// Single call trace returned by `trace_filter`, localized within a block and a transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedTrace {
+    pub action: TraceAction,
+    /// Call result. Is `None` if the call has failed.
+    pub result: Option<TraceResult>,
+    /// Error message for a failed call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub block_hash: H256,
+    pub block_number: U64,
+    /// Number of direct subcalls.
+    pub subtraces: usize,
+    /// Path to the call in the transaction call tree: indices of subcalls starting from the top-level call.
+    /// Empty for the top-level call.
+    pub trace_address: Vec<usize>,
+    pub transaction_hash: H256,
+    pub transaction_position: U64,
+    #[serde(rename = "type")]
+    pub trace_type: TraceType,
+}
+
+/// Daily report on the capacity utilization of sealed L1 batches returned by `zks_getUtilizationReport`.
+/// Utilization is expressed as a share of the corresponding capacity of an L1 batch (e.g., 0.5 for a half-full batch).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UtilizationReport {
+    /// UTC date the report is generated for.
+    pub date: NaiveDate,
+    pub first_l1_batch_number: Option<L1BatchNumber>,
+    pub last_l1_batch_number: Option<L1BatchNumber>,
+    /// Number of L1 batches sealed on the date.
+    pub l1_batch_count: u64,
+    /// Average gas utilization. `None` if unknown for all L1 batches (e.g., for batches sealed by older server versions).
+    pub avg_gas_utilization: Option<f64>,
+    /// Average pubdata utilization.
+    pub avg_pubdata_utilization: Option<f64>,
+    /// Average circuits utilization.
+    pub avg_circuits_utilization: Option<f64>,
+    /// Number of L1 batches sealed by each seal criterion. Batches with an unknown criterion are reported as `unknown`.
+    pub seal_criteria: BTreeMap<String, u64>,
+}
+
 #[derive(Default, Serialize, Deserialize, Clone, Debug)]
 pub struct ProtocolVersion {
     /// Protocol version ID
@@ -622,6 +830,21 @@ pub struct ProtocolVersion {
     pub l2_system_upgrade_tx_hash: Option<H256>,
 }
 
+/// Protocol version with its activation info returned by `zks_getProtocolVersions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolVersionInfo {
+    #[serde(flatten)]
+    pub version: ProtocolVersion,
+    /// Address of the L1 verifier contract used for the version.
+    pub verifier_address: Address,
+    /// Number of the first L1 batch executed with this version. Is `None` if the version is not activated yet,
+    /// or if all batches with this version are not available on the node (e.g., because of pruning).
+    pub first_l1_batch_number: Option<L1BatchNumber>,
+    /// Timestamp of the first L1 batch executed with this version.
+    pub first_l1_batch_timestamp: Option<u64>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum SupportedTracers {
@@ -640,6 +863,35 @@ pub struct TracerConfig {
     pub tracer: SupportedTracers,
     #[serde(default)]
     pub tracer_config: CallTracerConfig,
+    /// State overrides applied before the call is traced. Only used by `debug_traceCall`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+}
+
+/// State overrides for `eth_call` and `debug_traceCall`, keyed by the account address.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateOverride(pub HashMap<Address, OverrideAccount>);
+
+/// Overrides of the state of a single account.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideAccount {
+    /// Overridden base token balance of the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balance: Option<U256>,
+    /// Overridden nonce of the account. Only the transaction nonce is overridden; the deployment nonce is retained.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<U256>,
+    /// Overridden bytecode of the account.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// Full replacement of the account storage; slots not mentioned are considered to be zero.
+    /// Mutually exclusive with `state_diff`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<HashMap<H256, H256>>,
+    /// Overrides of individual storage slots of the account. Mutually exclusive with `state`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_diff: Option<HashMap<H256, H256>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -685,6 +937,92 @@ pub struct L1BatchDetails {
     pub number: L1BatchNumber,
     #[serde(flatten)]
     pub base: BlockDetailsBase,
+    /// Pubdata mode the batch was sealed with. `None` if the mode was not recorded by the node.
+    pub pubdata_mode: Option<PubdataDA>,
+    /// Status of the proving pipeline for the batch. `None` if proving hasn't started yet, or if the node
+    /// doesn't have access to prover data (e.g., for external nodes).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prover_status: Option<L1BatchProverStatus>,
+}
+
+/// Number of prover jobs of a certain kind (e.g., witness generation jobs for a specific aggregation round)
+/// grouped by their status.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProverJobCounts {
+    /// Jobs that are queued or wait for their dependencies.
+    pub queued: u64,
+    pub in_progress: u64,
+    /// Jobs that have successfully finished or were skipped.
+    pub successful: u64,
+    pub failed: u64,
+}
+
+impl ProverJobCounts {
+    pub fn total(&self) -> u64 {
+        self.queued + self.in_progress + self.successful + self.failed
+    }
+
+    /// Checks whether all jobs have successfully finished. Returns `false` if there are no jobs.
+    pub fn is_done(&self) -> bool {
+        self.successful > 0 && self.successful == self.total()
+    }
+}
+
+/// Status of a single proof aggregation round for an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregationRoundStatus {
+    pub aggregation_round: AggregationRound,
+    /// Witness generation jobs for the round.
+    pub witness_generation: ProverJobCounts,
+    /// Proving jobs for the round.
+    pub proofs: ProverJobCounts,
+}
+
+/// Status of the proving pipeline for an L1 batch returned as a part of [`L1BatchDetails`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchProverStatus {
+    /// Status for each aggregation round that has started, in the order of rounds.
+    pub aggregation_rounds: Vec<AggregationRoundStatus>,
+    /// Status of the final proof compression job, such as `queued`, `in_progress` or `successful`.
+    /// `None` if the compression job is not created yet.
+    pub proof_compression_status: Option<String>,
+    /// Whether the final proof is submitted to the server, i.e., is ready to be sent to L1.
+    pub proof_submitted: bool,
+}
+
+/// Stage of the L1 batch lifecycle.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize
+)]
+#[serde(rename_all = "camelCase")]
+pub enum L1BatchStage {
+    Sealed,
+    Committed,
+    Proven,
+    Executed,
+}
+
+/// Notification emitted by the `l1BatchStatus` subscription when an L1 batch reaches a certain stage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchStatusUpdate {
+    pub number: L1BatchNumber,
+    pub stage: L1BatchStage,
+    /// Hash of the confirmed L1 transaction that has moved the batch to this stage. `None` for the `sealed` stage.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<H256>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -702,3 +1040,599 @@ pub struct Proof {
     pub address: Address,
     pub storage_proof: Vec<StorageProof>,
 }
+
+/// Account proof returned by `eth_getProof`. Mirrors the Ethereum response ([EIP-1186]) adapted to the zkSync
+/// storage model, in which account fields are stored in slots of system contracts:
+///
+/// - Balance is stored in the L2 base token contract at the slot returned by `storage_key_for_eth_balance()`.
+/// - Nonce is stored in the nonce holder contract at the slot returned by `get_nonce_key()`; the slot value
+///   is the full nonce, i.e. `deployment_nonce * 2^128 + tx_nonce`.
+/// - Bytecode hash is stored in the account code storage contract at the slot returned by `get_code_key()`.
+///
+/// All proofs are Merkle paths in the zkSync Merkle tree for the state after [`Self::l1_batch_number`],
+/// and can be verified against [`Self::storage_hash`] (the tree root hash) in the same way as the proofs
+/// returned by `zks_getProof`. The tree key of a proven entry is `blake2s(padded_address ++ key)`, where
+/// `padded_address` is the address of the contract owning the slot (the account itself for `storage_proof`
+/// entries) left-padded to 32 bytes.
+///
+/// [EIP-1186]: https://eips.ethereum.org/EIPS/eip-1186
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    pub address: Address,
+    /// Proofs for account fields in the following order: balance, nonce, bytecode hash.
+    pub account_proof: Vec<StorageProof>,
+    pub balance: U256,
+    /// Versioned bytecode hash of the account; zero if the account has no code.
+    pub code_hash: H256,
+    /// Transaction nonce of the account.
+    pub nonce: U256,
+    /// Root hash of the Merkle tree after [`Self::l1_batch_number`].
+    pub storage_hash: H256,
+    pub storage_proof: Vec<StorageProof>,
+    /// L1 batch containing the requested block (or, for `latest` and `pending` blocks, the last L1 batch
+    /// processed by the Merkle tree). Proofs reflect the state after this batch.
+    pub l1_batch_number: L1BatchNumber,
+}
+
+/// Information about the oldest data retained by the node. Data older than this may have been removed
+/// because of snapshot recovery or pruning.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruningInfo {
+    /// Number of the first miniblock available on the node.
+    pub first_retained_block: MiniblockNumber,
+    /// Number of the first L1 batch available on the node.
+    pub first_retained_l1_batch: L1BatchNumber,
+}
+
+/// Conversion rate between ETH and the base token of the chain returned by `zks_getBaseTokenPrice`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseTokenPrice {
+    /// Number of base token units that 1 wei is worth, expressed as a fraction.
+    pub ratio: BaseTokenConversionRatio,
+    /// UNIX timestamp (in seconds) of the last successful update of the conversion rate.
+    pub updated_at: u64,
+    /// Whether the conversion rate wasn't updated for longer than the configured staleness threshold.
+    pub is_stale: bool,
+}
+
+/// Parameters of the L1 gas oracle used to smooth and bound the sampled L1 fees.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1GasOracleParams {
+    /// Maximum number of L1 blocks in the base fee sample window.
+    pub max_base_fee_samples: usize,
+    /// Maximum number of L1 blocks in the blob base fee sample window.
+    pub max_blob_base_fee_samples: usize,
+    /// Parameter `a` of the pricing formula `a * b^t`, where `t` is the time spent in the L1 mempool.
+    pub pricing_formula_parameter_a: f64,
+    /// Parameter `b` of the pricing formula `a * b^t`, where `t` is the time spent in the L1 mempool.
+    pub pricing_formula_parameter_b: f64,
+    /// Multiplier applied to the estimated L1 gas price.
+    pub internal_l1_pricing_multiplier: f64,
+    /// Multiplier applied to the estimated L1 pubdata price.
+    pub internal_pubdata_pricing_multiplier: f64,
+    /// L1 gas price (in wei) enforced instead of the estimated one, if any.
+    pub internal_enforced_l1_gas_price: Option<u64>,
+    /// Upper bound for the estimated L1 gas price (in wei).
+    pub max_l1_gas_price: u64,
+    /// Upper bound for the estimated blob base fee (in wei).
+    pub max_blob_base_fee: u64,
+    /// Priority fee per gas (in wei) added to the base fee.
+    pub priority_fee_per_gas: u64,
+}
+
+/// Internal state of the L1 gas oracle of the main node returned by `zks_getL1GasOracleState`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1GasOracleState {
+    /// Number of the last L1 block included into the sample windows.
+    pub last_processed_l1_block: u64,
+    /// Base fees (in wei) of L1 blocks in the sample window, from the oldest to the newest.
+    pub base_fee_samples: Vec<u64>,
+    /// Median of `base_fee_samples`.
+    pub median_base_fee: u64,
+    /// Blob base fees (in wei) of L1 blocks in the sample window, from the oldest to the newest.
+    pub blob_base_fee_samples: Vec<U256>,
+    /// Median of `blob_base_fee_samples`.
+    pub median_blob_base_fee: U256,
+    /// Parameters used to smooth and bound the sampled fees.
+    pub params: L1GasOracleParams,
+    /// L1 gas price (in wei) estimated by the oracle.
+    pub effective_l1_gas_price: u64,
+    /// L1 pubdata price (in wei) estimated by the oracle.
+    pub effective_l1_pubdata_price: u64,
+    /// Fee model parameters currently applied by the node. Unlike the estimates above, prices are denominated
+    /// in the base token of the chain.
+    pub fee_params: FeeParams,
+    /// Batch fee input used by the API server. It is computed from `fee_params` with the API scale factor applied,
+    /// and is never lower than the fee input of the last sealed miniblock (except for the components set by
+    /// an emergency fee input override).
+    pub batch_fee_input: BatchFeeInput,
+}
+
+/// Entry of the allow-list applied when validating transactions, e.g. to let paymasters read storage slots
+/// that are normally not accessible during validation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ValidationAllowListEntry {
+    /// Storage slot that can be read during validation. If the address is not specified, the slot is trusted
+    /// for all L2 tokens.
+    Slot {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        address: Option<Address>,
+        slot: H256,
+    },
+    /// Contract all storage slots of which can be read during validation.
+    Address { address: Address },
+    /// Storage slot containing an address that should itself be trusted during validation (e.g., the beacon slot
+    /// of a proxy). If the address is not specified, the slot is trusted for all L2 tokens.
+    AddressSlot {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        address: Option<Address>,
+        slot: H256,
+    },
+}
+
+/// Override of base system contracts used by the API sandbox (e.g., in `eth_call` and gas estimation).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseSystemContractsOverride {
+    /// Bootloader bytecode. If not specified, the bootloader is not overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bootloader: Option<Bytes>,
+    /// Default account bytecode. If not specified, the default account is not overridden.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_aa: Option<Bytes>,
+}
+
+/// Hashes of the bytecodes overriding base system contracts used by the API sandbox.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaseSystemContractsOverrideHashes {
+    pub bootloader: Option<H256>,
+    pub default_aa: Option<H256>,
+}
+
+/// Event emitted by a transaction simulated as a part of a bundle.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedLog {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    pub data: Bytes,
+}
+
+/// Storage slot modified by a transaction simulated as a part of a bundle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedStorageDiff {
+    pub address: Address,
+    pub key: H256,
+    /// Value of the slot after the transaction.
+    pub value: H256,
+}
+
+/// Result of a transaction simulated as a part of a bundle by `zks_simulateBundle`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedTransaction {
+    /// Whether the transaction succeeded, as opposed to being reverted.
+    pub success: bool,
+    /// Data returned by the transaction. Empty for reverted transactions.
+    pub output: Bytes,
+    /// Revert reason for reverted transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revert_reason: Option<String>,
+    pub gas_used: U256,
+    pub logs: Vec<SimulatedLog>,
+    /// Storage slots modified by the transaction, in the order of their first modification.
+    pub state_diffs: Vec<SimulatedStorageDiff>,
+}
+
+/// Result of estimating gas for a single transaction in `zks_estimateGasBatch`. Exactly one of the fields is set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchGasEstimate {
+    /// Estimated gas limit for the transaction.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gas: Option<U256>,
+    /// Reason why gas estimation has failed for the transaction, e.g. its revert reason.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Result of validating the paymaster of a transaction returned by `zks_validatePaymaster`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymasterValidation {
+    /// Whether the validation step succeeded and adhered to the validation rules.
+    pub success: bool,
+    /// Reason why the validation step has failed, e.g., the paymaster revert reason.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Validation rule violated by the paymaster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub violation: Option<ValidationViolation>,
+    /// Gas used by the transaction until the end of the validation step, including account validation.
+    pub gas_used: U256,
+    /// Computational gas used by the paymaster validation step.
+    pub computational_gas_used: u32,
+    /// Maximum computational gas that the paymaster validation step may use.
+    pub computational_gas_limit: u32,
+    /// Funds in the base token that the paymaster must have to pay for the transaction, i.e., `gasLimit * maxFeePerGas`.
+    pub required_funds: U256,
+    /// Current base token balance of the paymaster.
+    pub paymaster_balance: U256,
+    /// Token allowance required from the initiator by the approval-based paymaster flow. `None` for other flows.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required_allowance: Option<PaymasterAllowance>,
+}
+
+/// Token allowance required by the approval-based paymaster flow.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymasterAllowance {
+    pub token: Address,
+    pub min_allowance: U256,
+}
+
+/// Violated rule of the transaction validation step.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ValidationViolation {
+    #[serde(rename_all = "camelCase")]
+    TouchedUnallowedStorageSlot {
+        address: Address,
+        key: H256,
+    },
+    #[serde(rename_all = "camelCase")]
+    CalledContractWithNoCode {
+        address: Address,
+    },
+    TouchedUnallowedContext,
+    #[serde(rename_all = "camelCase")]
+    TookTooManyComputationalGas {
+        gas_limit: u32,
+    },
+}
+
+impl From<&ViolatedValidationRule> for ValidationViolation {
+    fn from(rule: &ViolatedValidationRule) -> Self {
+        match rule {
+            ViolatedValidationRule::TouchedUnallowedStorageSlots(address, key) => {
+                Self::TouchedUnallowedStorageSlot {
+                    address: *address,
+                    key: u256_to_h256(*key),
+                }
+            }
+            ViolatedValidationRule::CalledContractWithNoCode(address) => {
+                Self::CalledContractWithNoCode { address: *address }
+            }
+            ViolatedValidationRule::TouchedUnallowedContext => Self::TouchedUnallowedContext,
+            ViolatedValidationRule::TookTooManyComputationalGas(gas_limit) => {
+                Self::TookTooManyComputationalGas {
+                    gas_limit: *gas_limit,
+                }
+            }
+        }
+    }
+}
+
+/// Token transfer returned by `zks_getTokenTransfers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTransfer {
+    pub block_number: MiniblockNumber,
+    /// Index of the transfer event in the block.
+    pub log_index: u32,
+    pub transaction_hash: H256,
+    /// Address of the token contract. Base token transfers have the L2 base token address.
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: U256,
+}
+
+/// Position of a token transfer in the chain used to paginate `zks_getTokenTransfers` results.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize
+)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTransferCursor {
+    pub block_number: MiniblockNumber,
+    pub log_index: u32,
+}
+
+/// Range of token transfers requested via `zks_getTokenTransfers`. Transfers are returned in the order
+/// of their execution; to get the next page, set `after` to the position of the last returned transfer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTransfersRange {
+    /// First block (inclusive) to return transfers from. By default, transfers are returned from the earliest block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_block: Option<MiniblockNumber>,
+    /// Last block (inclusive) to return transfers from. By default, transfers are returned up to the latest block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_block: Option<MiniblockNumber>,
+    /// Only transfers strictly after this position are returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<TokenTransferCursor>,
+    /// Maximum number of returned transfers. Capped by the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+/// Position of an entry in the account balances returned by `zks_getAccountBalances`. Entries are ordered
+/// by the token address; for the same address, the fungible token balance goes first, followed by NFT holdings
+/// ordered by the token ID.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize
+)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalancesCursor {
+    pub token: Address,
+    /// ID of the NFT; `None` for fungible tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_id: Option<U256>,
+}
+
+/// Options for `zks_getAccountBalances`. To get the next page of balances, set `after` to `nextPage`
+/// from the previous response.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalancesOptions {
+    /// If specified, only balances for these tokens (identified by L2 addresses) are returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<Vec<Address>>,
+    /// Whether to return ERC-721 and ERC-1155 token holdings. Holdings are computed from indexed transfer events,
+    /// so they only account for transfers in blocks indexed by the server.
+    #[serde(default)]
+    pub include_nfts: bool,
+    /// Only entries strictly after this position are returned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<AccountBalancesCursor>,
+    /// Maximum number of returned entries. Capped by the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+/// NFT holding returned by `zks_getAccountBalances`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NftHolding {
+    /// Address of the token contract.
+    pub token: Address,
+    pub standard: NftStandard,
+    pub token_id: U256,
+    /// Held amount of the token. Always 1 for ERC-721 tokens.
+    pub amount: U256,
+}
+
+impl NftHolding {
+    pub fn cursor(&self) -> AccountBalancesCursor {
+        AccountBalancesCursor {
+            token: self.token,
+            token_id: Some(self.token_id),
+        }
+    }
+}
+
+/// Page of account balances returned by `zks_getAccountBalances`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalances {
+    /// Non-zero balances of fungible tokens keyed by the L2 token address.
+    pub balances: BTreeMap<Address, U256>,
+    /// NFT holdings; only returned if requested in the options.
+    pub nfts: Vec<NftHolding>,
+    /// Position of the last entry on this page if more entries may be available; `None` if this is the last page.
+    pub next_page: Option<AccountBalancesCursor>,
+}
+
+/// Issue with a transaction signature detected by `debug_explainSignature`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SignatureIssue {
+    /// The transaction cannot be decoded.
+    MalformedTransaction { message: String },
+    /// The transaction is signed for another chain, or the chain ID required for its type is missing.
+    WrongChainId { chain_id: Option<u64> },
+    /// The signature is malformed (e.g., has an invalid `v` value) or the signer cannot be recovered from it.
+    MalformedSignature { message: String },
+    /// The initiator is an externally owned account, but the signature doesn't have the 65-byte ECDSA length.
+    UnexpectedSignatureLength { length: usize },
+    /// The initiator is an externally owned account, but the signature is produced by another address.
+    /// This usually means that the transaction was signed for another chain or with a wrong EIP-712 domain.
+    SignerMismatch { recovered_signer: Address },
+}
+
+/// Report explaining signature recovery for a raw transaction, returned by `debug_explainSignature`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureReport {
+    /// Type of the transaction (0 for legacy transactions).
+    pub transaction_type: u8,
+    /// Chain ID expected by the server.
+    pub expected_chain_id: u64,
+    /// Chain ID the transaction is signed for.
+    pub chain_id: Option<u64>,
+    /// Whether the signature commits to the chain ID, i.e., the transaction cannot be replayed on other chains.
+    /// Legacy transactions without EIP-155 chain ID are accepted, but are not replay-protected.
+    pub replay_protected: bool,
+    /// Transaction initiator. For EIP-712 transactions, this is the account specified in the transaction;
+    /// for other transactions, the recovered signer.
+    pub initiator: Option<Address>,
+    /// Whether the initiator is a smart contract account. Signatures for such accounts are verified
+    /// by the account itself, so only basic checks are performed for them.
+    pub initiator_is_contract: bool,
+    /// Whether the signature is provided as an EIP-712 custom signature rather than with `v`, `r` and `s` fields.
+    pub custom_signature: bool,
+    pub signature_length: Option<usize>,
+    /// Signer recovered from the signature provided that it is a valid ECDSA signature.
+    pub recovered_signer: Option<Address>,
+    /// Detected issues. If empty, the signature is expected to pass validation.
+    pub issues: Vec<SignatureIssue>,
+}
+
+impl SignatureReport {
+    /// Performs checks that depend on the initiator account type. For externally owned accounts, the signature
+    /// must be a valid ECDSA signature produced by the initiator.
+    pub fn check_initiator_account(&mut self, initiator_is_contract: bool) {
+        self.initiator_is_contract = initiator_is_contract;
+        if initiator_is_contract {
+            return;
+        }
+        let (Some(initiator), Some(signature_length)) = (self.initiator, self.signature_length)
+        else {
+            return;
+        };
+
+        if signature_length != 65 {
+            self.issues.push(SignatureIssue::UnexpectedSignatureLength {
+                length: signature_length,
+            });
+        } else if let Some(recovered_signer) = self.recovered_signer {
+            if recovered_signer != initiator {
+                self.issues
+                    .push(SignatureIssue::SignerMismatch { recovered_signer });
+            }
+        } else {
+            self.issues.push(SignatureIssue::MalformedSignature {
+                message: "cannot recover signer from the ECDSA signature".to_owned(),
+            });
+        }
+    }
+}
+
+/// Status of a priority (L1 -> L2) operation returned by `zks_getPriorityOpStatus`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityOpStatus {
+    /// Serial ID of the operation in the priority queue.
+    pub id: PriorityOpId,
+    pub status: TransactionStatus,
+    /// Hash of the L1 transaction that enqueued the operation. May be missing for operations
+    /// that were persisted before the server started storing L1 transaction hashes.
+    pub l1_tx_hash: Option<H256>,
+    /// Number of the L1 block that enqueued the operation.
+    pub l1_block_number: L1BlockNumber,
+    /// Canonical L2 hash of the operation.
+    pub l2_tx_hash: H256,
+    /// Number of operations ahead of this one in the priority queue (i.e., 0 if the operation is next
+    /// to be processed). `None` if the operation is already executed.
+    pub queue_position: Option<u64>,
+    /// L2 block the operation was executed in.
+    pub miniblock_number: Option<MiniblockNumber>,
+    /// L1 batch the operation was processed in. `None` if the batch is not sealed yet.
+    pub l1_batch_number: Option<L1BatchNumber>,
+    /// L1 batch the operation will be processed in. Only present if the operation is executed, but its L1 batch
+    /// is not sealed yet.
+    pub estimated_l1_batch_number: Option<L1BatchNumber>,
+    /// Hash to fetch the final L2 receipt of the operation with (e.g., via `eth_getTransactionReceipt`).
+    /// `None` until the operation is executed.
+    pub l2_receipt_hash: Option<H256>,
+}
+
+/// Inputs to the commitment of an L1 batch. Allows to recompute the batch commitment
+/// without access to the node database.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitmentArtifacts {
+    pub number: L1BatchNumber,
+    pub protocol_version: Option<ProtocolVersionId>,
+    /// System logs emitted during the batch execution; their linear hash is a part of the auxiliary output.
+    pub system_logs: Vec<SystemL2ToL1Log>,
+    /// Hash of the state diffs of the batch, as announced in the system logs. `None` for pre-boojum batches.
+    pub state_diff_hash: Option<H256>,
+    /// State diffs of the batch in the compressed form they were published in.
+    pub compressed_state_diffs: Bytes,
+    /// Commitment to the initial content of the bootloader heap. `None` for pre-boojum batches.
+    pub bootloader_initial_content_commitment: Option<H256>,
+    /// Commitment to the final state of the events queue. `None` for pre-boojum batches.
+    pub events_queue_commitment: Option<H256>,
+    /// Linear hashes of the published blobs, as announced in the system logs. Zeros for batches not using blobs;
+    /// `None` for pre-boojum batches.
+    pub blob_linear_hashes: Option<[H256; 2]>,
+    /// KZG commitments to the published blobs. Zeros for batches not using blobs; `None` for pre-boojum batches
+    /// and batches with the commitment generated before blob commitments were persisted.
+    pub blob_commitments: Option<[H256; 2]>,
+    pub l2_l1_merkle_root: H256,
+    pub rollup_root_hash: H256,
+    pub rollup_last_leaf_index: u64,
+    pub zkporter_is_available: bool,
+    pub bootloader_code_hash: H256,
+    pub default_aa_code_hash: H256,
+    pub pass_through_data_hash: H256,
+    pub meta_parameters_hash: H256,
+    pub aux_data_hash: H256,
+    /// Batch commitment, i.e. the hash of the pass-through data, meta parameters and auxiliary output hashes.
+    pub commitment: H256,
+}
+
+impl CommitmentArtifacts {
+    pub fn new(batch: &L1BatchWithMetadata, blob_commitments: Option<[H256; 2]>) -> Self {
+        let L1BatchWithMetadata {
+            header, metadata, ..
+        } = batch;
+        let state_diff_hash_key = u256_to_h256(STATE_DIFF_HASH_KEY.into());
+        let state_diff_hash = header
+            .system_logs
+            .iter()
+            .find_map(|log| (log.0.key == state_diff_hash_key).then_some(log.0.value));
+        let protocol_version = header
+            .protocol_version
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined);
+        let blob_linear_hashes = if protocol_version.is_pre_boojum() {
+            None
+        } else if protocol_version.is_post_1_4_2() {
+            Some(find_blob_linear_hashes(&header.system_logs).map(Option::unwrap_or_default))
+        } else {
+            Some([H256::zero(); 2])
+        };
+
+        Self {
+            number: header.number,
+            protocol_version: header.protocol_version,
+            system_logs: header.system_logs.clone(),
+            state_diff_hash,
+            compressed_state_diffs: metadata.state_diffs_compressed.clone().into(),
+            bootloader_initial_content_commitment: metadata.bootloader_initial_content_commitment,
+            events_queue_commitment: metadata.events_queue_commitment,
+            blob_linear_hashes,
+            blob_commitments,
+            l2_l1_merkle_root: metadata.l2_l1_merkle_root,
+            rollup_root_hash: metadata.root_hash,
+            rollup_last_leaf_index: metadata.rollup_last_leaf_index,
+            zkporter_is_available: metadata.block_meta_params.zkporter_is_available,
+            bootloader_code_hash: metadata.block_meta_params.bootloader_code_hash,
+            default_aa_code_hash: metadata.block_meta_params.default_aa_code_hash,
+            pass_through_data_hash: metadata.pass_through_data_hash,
+            meta_parameters_hash: metadata.meta_parameters_hash,
+            aux_data_hash: metadata.aux_data_hash,
+            commitment: metadata.commitment,
+        }
+    }
+}
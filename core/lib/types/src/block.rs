@@ -10,6 +10,7 @@ use crate::{
     fee_model::BatchFeeInput,
     l2_to_l1_log::{SystemL2ToL1Log, UserL2ToL1Log},
     priority_op_onchain_data::PriorityOpOnchainData,
+    pubdata_da::PubdataDA,
     web3::signing::keccak256,
     AccountTreeId, L1BatchNumber, MiniblockNumber, ProtocolVersionId, Transaction,
 };
@@ -57,6 +58,19 @@ pub struct L1BatchHeader {
     /// Version of protocol used for the L1 batch.
     pub protocol_version: Option<ProtocolVersionId>,
     pub pubdata_input: Option<Vec<u8>>,
+    /// Pubdata mode the L1 batch was sealed with. `None` if the mode is unknown (e.g., for batches
+    /// sealed by older server versions, or batches synced by external nodes).
+    pub pubdata_mode: Option<PubdataDA>,
+}
+
+/// Number of pubdata bytes published in an L1 batch attributed to a specific contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractPubdata {
+    /// Address of the contract.
+    pub address: Address,
+    /// Number of pubdata bytes attributed to the contract.
+    pub pubdata_bytes: u64,
 }
 
 /// Holder for the miniblock metadata that is not available from transactions themselves.
@@ -76,6 +90,8 @@ pub struct MiniblockHeader {
     pub protocol_version: Option<ProtocolVersionId>,
     /// The maximal number of virtual blocks to be created in the miniblock.
     pub virtual_blocks: u32,
+    /// Bloom filter for the event logs in the miniblock.
+    pub logs_bloom: H2048,
 }
 
 /// Data needed to execute a miniblock in the VM.
@@ -109,6 +125,7 @@ impl L1BatchHeader {
             system_logs: vec![],
             protocol_version: Some(protocol_version),
             pubdata_input: Some(vec![]),
+            pubdata_mode: None,
         }
     }
 
@@ -131,7 +148,7 @@ impl L1BatchHeader {
     }
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Default)]
+#[derive(Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
 pub struct BlockGasCount {
     pub commit: u32,
     pub prove: u32,
@@ -91,3 +91,37 @@ impl Add for CircuitStatistic {
         }
     }
 }
+
+/// Computational gas spent on opcodes that load individual circuit families. Allows reasoning about
+/// prover bottlenecks that are not visible from the aggregate computational gas.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitFamilyGas {
+    /// Gas spent on `keccak256` precompile calls.
+    pub keccak256: u32,
+    /// Gas spent on `sha256` precompile calls.
+    pub sha256: u32,
+    /// Gas spent on `ecrecover` precompile calls.
+    pub ecrecover: u32,
+    /// Gas spent on storage reads and writes.
+    pub storage: u32,
+}
+
+impl CircuitFamilyGas {
+    /// Returns the total gas spent across all tracked circuit families.
+    pub fn total(&self) -> u32 {
+        self.keccak256 + self.sha256 + self.ecrecover + self.storage
+    }
+}
+
+impl Add for CircuitFamilyGas {
+    type Output = CircuitFamilyGas;
+
+    fn add(self, other: CircuitFamilyGas) -> CircuitFamilyGas {
+        CircuitFamilyGas {
+            keccak256: self.keccak256 + other.keccak256,
+            sha256: self.sha256 + other.sha256,
+            ecrecover: self.ecrecover + other.ecrecover,
+            storage: self.storage + other.storage,
+        }
+    }
+}
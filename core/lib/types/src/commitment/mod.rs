@@ -285,6 +285,17 @@ enum L1BatchAuxiliaryOutput {
     },
 }
 
+/// Finds linear hashes of the published blobs in the system logs emitted in an L1 batch.
+pub(crate) fn find_blob_linear_hashes(system_logs: &[SystemL2ToL1Log]) -> [Option<H256>; 2] {
+    [BLOB1_LINEAR_HASH_KEY, BLOB2_LINEAR_HASH_KEY].map(|key| {
+        system_logs.iter().find_map(|log| {
+            (log.0.sender == PUBDATA_CHUNK_PUBLISHER_ADDRESS
+                && log.0.key == H256::from_low_u64_be(key as u64))
+            .then_some(log.0.value)
+        })
+    })
+}
+
 impl L1BatchAuxiliaryOutput {
     fn new(input: CommitmentInput) -> Self {
         match input {
@@ -355,16 +366,8 @@ impl L1BatchAuxiliaryOutput {
                 let state_diffs_compressed = compress_state_diffs(state_diffs);
 
                 let blob_linear_hashes = if common_input.protocol_version.is_post_1_4_2() {
-                    let blob1_linear_hash = system_logs.iter().find_map(|log| {
-                        (log.0.sender == PUBDATA_CHUNK_PUBLISHER_ADDRESS
-                            && log.0.key == H256::from_low_u64_be(BLOB1_LINEAR_HASH_KEY as u64))
-                        .then_some(log.0.value)
-                    });
-                    let blob2_linear_hash = system_logs.iter().find_map(|log| {
-                        (log.0.sender == PUBDATA_CHUNK_PUBLISHER_ADDRESS
-                            && log.0.key == H256::from_low_u64_be(BLOB2_LINEAR_HASH_KEY as u64))
-                        .then_some(log.0.value)
-                    });
+                    let [blob1_linear_hash, blob2_linear_hash] =
+                        find_blob_linear_hashes(&system_logs);
                     match (&blob1_linear_hash, &blob2_linear_hash) {
                         (Some(_), None) | (None, Some(_)) => {
                             panic!("Only one blob hash was found in system logs")
@@ -593,6 +596,15 @@ impl L1BatchCommitment {
         self.auxiliary_output.common().l2_l1_logs_merkle_root
     }
 
+    pub fn blob_commitments(&self) -> Option<[H256; 2]> {
+        match &self.auxiliary_output {
+            L1BatchAuxiliaryOutput::PostBoojum {
+                blob_commitments, ..
+            } => Some(*blob_commitments),
+            L1BatchAuxiliaryOutput::PreBoojum { .. } => None,
+        }
+    }
+
     pub fn aux_commitments(&self) -> Option<AuxCommitments> {
         match &self.auxiliary_output {
             L1BatchAuxiliaryOutput::PostBoojum {
@@ -644,6 +656,7 @@ impl L1BatchCommitment {
             compressed_state_diffs,
             zkporter_is_available: self.meta_parameters.zkporter_is_available,
             aux_commitments: self.aux_commitments(),
+            blob_commitments: self.blob_commitments(),
             compressed_initial_writes,
             compressed_repeated_writes,
         }
@@ -737,4 +750,6 @@ pub struct L1BatchCommitmentArtifacts {
     pub compressed_repeated_writes: Option<Vec<u8>>,
     pub zkporter_is_available: bool,
     pub aux_commitments: Option<AuxCommitments>,
+    /// KZG commitments to the published blobs. `None` for pre-boojum batches; zeros for batches not using blobs.
+    pub blob_commitments: Option<[H256; 2]>,
 }
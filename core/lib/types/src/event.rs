@@ -8,8 +8,9 @@ use zksync_utils::{h256_to_account_address, u256_to_bytes_be, u256_to_h256};
 use crate::{
     ethabi,
     l2_to_l1_log::L2ToL1Log,
-    tokens::{TokenInfo, TokenMetadata},
-    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, H256, KNOWN_CODES_STORAGE_ADDRESS,
+    tokens::{NftStandard, TokenInfo, TokenMetadata},
+    web3::signing::keccak256,
+    Address, L1BatchNumber, CONTRACT_DEPLOYER_ADDRESS, H2048, H256, KNOWN_CODES_STORAGE_ADDRESS,
     L1_MESSENGER_ADDRESS, U256,
 };
 
@@ -31,6 +32,45 @@ impl VmEvent {
                 topic: (idx as u32, topic),
             })
     }
+
+    /// Adds the address and indexed topics of this event to the logs bloom filter.
+    pub fn accrue_bloom(&self, bloom: &mut H2048) {
+        accrue_bloom(bloom, self.address.as_bytes());
+        for topic in &self.indexed_topics {
+            accrue_bloom(bloom, topic.as_bytes());
+        }
+    }
+}
+
+/// Computes the Ethereum-compatible logs bloom filter for the provided events.
+pub fn logs_bloom<'a>(events: impl IntoIterator<Item = &'a VmEvent>) -> H2048 {
+    let mut bloom = H2048::zero();
+    for event in events {
+        event.accrue_bloom(&mut bloom);
+    }
+    bloom
+}
+
+/// Returns indices of the bits set in a logs bloom filter for the provided item (an event address or topic).
+/// Bits are indexed starting from the least significant bit of the filter, i.e. bit `i` corresponds
+/// to the mask `1 << (i % 8)` in the byte `255 - i / 8`.
+pub fn bloom_bit_indices(item: &[u8]) -> [usize; 3] {
+    let hash = keccak256(item);
+    [0, 2, 4].map(|i| ((usize::from(hash[i]) << 8) | usize::from(hash[i + 1])) % 2_048)
+}
+
+/// Adds an item (an event address or topic) to the logs bloom filter.
+pub fn accrue_bloom(bloom: &mut H2048, item: &[u8]) {
+    for bit in bloom_bit_indices(item) {
+        bloom.0[255 - bit / 8] |= 1 << (bit % 8);
+    }
+}
+
+/// Checks whether an item (an event address or topic) may be present in the logs bloom filter.
+pub fn bloom_contains(bloom: &H2048, item: &[u8]) -> bool {
+    bloom_bit_indices(item)
+        .into_iter()
+        .all(|bit| bloom.0[255 - bit / 8] & (1 << (bit % 8)) != 0)
 }
 
 pub static DEPLOY_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
@@ -333,6 +373,164 @@ pub fn extract_published_bytecodes(all_generated_events: &[VmEvent]) -> Vec<H256
         .collect()
 }
 
+/// Signature of the `Transfer(address,address,uint256)` event emitted by ERC-20 tokens and the base token contract.
+pub static TRANSFER_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "Transfer",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+        ],
+    )
+});
+
+/// Token transfer extracted from a `Transfer` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenTransfer {
+    /// Address of the token contract that emitted the event.
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    pub amount: U256,
+}
+
+impl TokenTransfer {
+    /// Parses an ERC-20 (or base token) transfer from the event. Returns `None` if the event is not a transfer;
+    /// ERC-721 transfers, which have the token ID as an indexed topic, are skipped as well.
+    pub fn from_event(event: &VmEvent) -> Option<Self> {
+        if event.indexed_topics.len() != 3
+            || event.indexed_topics[0] != *TRANSFER_EVENT_SIGNATURE
+            || event.value.len() != 32
+        {
+            return None;
+        }
+        Some(Self {
+            token: event.address,
+            from: h256_to_account_address(&event.indexed_topics[1]),
+            to: h256_to_account_address(&event.indexed_topics[2]),
+            amount: U256::from_big_endian(&event.value),
+        })
+    }
+}
+
+/// Signature of the `TransferSingle(address,address,address,uint256,uint256)` event emitted by ERC-1155 tokens.
+pub static TRANSFER_SINGLE_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    ethabi::long_signature(
+        "TransferSingle",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+            ethabi::ParamType::Uint(256),
+        ],
+    )
+});
+
+/// Signature of the `TransferBatch(address,address,address,uint256[],uint256[])` event emitted by ERC-1155 tokens.
+pub static TRANSFER_BATCH_EVENT_SIGNATURE: Lazy<H256> = Lazy::new(|| {
+    let uint_array = ethabi::ParamType::Array(Box::new(ethabi::ParamType::Uint(256)));
+    ethabi::long_signature(
+        "TransferBatch",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            uint_array.clone(),
+            uint_array,
+        ],
+    )
+});
+
+/// NFT transfer extracted from an ERC-721 `Transfer` event or an ERC-1155 `TransferSingle` / `TransferBatch` event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NftTransfer {
+    /// Address of the token contract that emitted the event.
+    pub token: Address,
+    pub standard: NftStandard,
+    pub token_id: U256,
+    pub from: Address,
+    pub to: Address,
+    /// Transferred amount of the token. Always 1 for ERC-721 tokens.
+    pub amount: U256,
+}
+
+impl NftTransfer {
+    /// Parses NFT transfers from the event. A single ERC-1155 `TransferBatch` event can contain multiple transfers.
+    /// Returns an empty vector if the event is not an NFT transfer or is malformed.
+    pub fn from_event(event: &VmEvent) -> Vec<Self> {
+        let topics = &event.indexed_topics;
+        if topics.len() != 4 {
+            return vec![];
+        }
+
+        if topics[0] == *TRANSFER_EVENT_SIGNATURE {
+            // ERC-721 transfers have all params indexed.
+            if !event.value.is_empty() {
+                return vec![];
+            }
+            return vec![Self {
+                token: event.address,
+                standard: NftStandard::Erc721,
+                token_id: U256::from_big_endian(topics[3].as_bytes()),
+                from: h256_to_account_address(&topics[1]),
+                to: h256_to_account_address(&topics[2]),
+                amount: U256::one(),
+            }];
+        }
+
+        // ERC-1155 events have the operator as the first indexed param.
+        let ids_and_amounts = if topics[0] == *TRANSFER_SINGLE_EVENT_SIGNATURE {
+            Self::decode_single_transfer(&event.value)
+        } else if topics[0] == *TRANSFER_BATCH_EVENT_SIGNATURE {
+            Self::decode_batch_transfer(&event.value)
+        } else {
+            None
+        };
+        let Some(ids_and_amounts) = ids_and_amounts else {
+            return vec![];
+        };
+        let from = h256_to_account_address(&topics[2]);
+        let to = h256_to_account_address(&topics[3]);
+        ids_and_amounts
+            .into_iter()
+            .map(|(token_id, amount)| Self {
+                token: event.address,
+                standard: NftStandard::Erc1155,
+                token_id,
+                from,
+                to,
+                amount,
+            })
+            .collect()
+    }
+
+    fn decode_single_transfer(data: &[u8]) -> Option<Vec<(U256, U256)>> {
+        let param_types = [ethabi::ParamType::Uint(256), ethabi::ParamType::Uint(256)];
+        let mut tokens = ethabi::decode(&param_types, data).ok()?.into_iter();
+        let token_id = tokens.next()?.into_uint()?;
+        let amount = tokens.next()?.into_uint()?;
+        Some(vec![(token_id, amount)])
+    }
+
+    fn decode_batch_transfer(data: &[u8]) -> Option<Vec<(U256, U256)>> {
+        let uint_array = ethabi::ParamType::Array(Box::new(ethabi::ParamType::Uint(256)));
+        let param_types = [uint_array.clone(), uint_array];
+        let mut tokens = ethabi::decode(&param_types, data).ok()?.into_iter();
+        let token_ids = tokens.next()?.into_array()?;
+        let amounts = tokens.next()?.into_array()?;
+        if token_ids.len() != amounts.len() {
+            return None;
+        }
+        token_ids
+            .into_iter()
+            .zip(amounts)
+            .map(|(token_id, amount)| Some((token_id.into_uint()?, amount.into_uint()?)))
+            .collect()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct VmEventGroupKey {
     pub address: Address,
@@ -351,11 +549,12 @@ mod tests {
     use zksync_utils::u256_to_h256;
 
     use super::{
-        extract_bytecode_publication_requests_from_l1_messenger,
-        extract_l2tol1logs_from_l1_messenger, L1MessengerBytecodePublicationRequest,
-        L1MessengerL2ToL1Log,
+        bloom_contains, extract_bytecode_publication_requests_from_l1_messenger,
+        extract_l2tol1logs_from_l1_messenger, logs_bloom, L1MessengerBytecodePublicationRequest,
+        L1MessengerL2ToL1Log, NftTransfer, TokenTransfer, TRANSFER_BATCH_EVENT_SIGNATURE,
+        TRANSFER_EVENT_SIGNATURE, TRANSFER_SINGLE_EVENT_SIGNATURE,
     };
-    use crate::VmEvent;
+    use crate::{tokens::NftStandard, VmEvent, H2048, H256};
 
     fn create_l2_to_l1_log_sent_value(
         tx_number: U256,
@@ -502,4 +701,158 @@ mod tests {
 
         assert_eq!(expected, logs);
     }
+
+    #[test]
+    fn computing_logs_bloom() {
+        let event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: "ef2d6d194084c2de36e0dabfce45d046b37d1106".parse().unwrap(),
+            indexed_topics: vec![
+                "02c69be41d0b7e40352fc85be1cd65eb03d40ef8427a0ca4596b1ead9a00e9fc"
+                    .parse()
+                    .unwrap(),
+            ],
+            value: vec![],
+        };
+        let bloom = logs_bloom([&event]);
+        let expected_bloom: H2048 =
+            "0000000000000000000000000000000000000000100000000000000000000000\
+            0000000000000000000000000000000000000000000000000000000000000000\
+            0000000000000000000000000000000000000000000000000000000000000000\
+            0000000202000000000000000000000000000000000000000000000800000000\
+            1000000000000000000000000000000000000000000000000000001000000000\
+            0000000000000000000000000000000000000000000000000000000000000000\
+            0000000000000000000000000000000000000000000000000000000000000000\
+            0000000000000000000000000000000000000000000000000000000000000000"
+                .parse()
+                .unwrap();
+        assert_eq!(bloom, expected_bloom);
+
+        assert!(bloom_contains(&bloom, event.address.as_bytes()));
+        assert!(bloom_contains(&bloom, event.indexed_topics[0].as_bytes()));
+        assert!(!bloom_contains(&bloom, H256::repeat_byte(1).as_bytes()));
+        assert_eq!(logs_bloom(&[] as &[VmEvent]), H2048::zero());
+    }
+
+    #[test]
+    fn parsing_token_transfers() {
+        let from = Address::repeat_byte(1);
+        let to = Address::repeat_byte(2);
+        let mut event = VmEvent {
+            location: (L1BatchNumber(1), 0u32),
+            address: L2_ETH_TOKEN_ADDRESS,
+            indexed_topics: vec![
+                *TRANSFER_EVENT_SIGNATURE,
+                zksync_utils::address_to_h256(&from),
+                zksync_utils::address_to_h256(&to),
+            ],
+            value: u256_to_h256(U256::from(123)).as_bytes().to_vec(),
+        };
+        let transfer = TokenTransfer::from_event(&event).unwrap();
+        assert_eq!(
+            transfer,
+            TokenTransfer {
+                token: L2_ETH_TOKEN_ADDRESS,
+                from,
+                to,
+                amount: 123.into(),
+            }
+        );
+
+        // ERC-721 transfer
+        event.indexed_topics.push(u256_to_h256(U256::from(1)));
+        event.value = vec![];
+        assert_eq!(TokenTransfer::from_event(&event), None);
+    }
+
+    #[test]
+    fn parsing_nft_transfers() {
+        let token = Address::repeat_byte(0x10);
+        let operator = Address::repeat_byte(0xff);
+        let from = Address::repeat_byte(1);
+        let to = Address::repeat_byte(2);
+        let erc721_event = VmEvent {
+            location: (L1BatchNumber(1), 0u32),
+            address: token,
+            indexed_topics: vec![
+                *TRANSFER_EVENT_SIGNATURE,
+                zksync_utils::address_to_h256(&from),
+                zksync_utils::address_to_h256(&to),
+                u256_to_h256(U256::from(42)),
+            ],
+            value: vec![],
+        };
+        let transfers = NftTransfer::from_event(&erc721_event);
+        assert_eq!(
+            transfers,
+            [NftTransfer {
+                token,
+                standard: NftStandard::Erc721,
+                token_id: 42.into(),
+                from,
+                to,
+                amount: 1.into(),
+            }]
+        );
+
+        let erc1155_topics = |signature: H256| {
+            vec![
+                signature,
+                zksync_utils::address_to_h256(&operator),
+                zksync_utils::address_to_h256(&from),
+                zksync_utils::address_to_h256(&to),
+            ]
+        };
+        let single_event = VmEvent {
+            location: (L1BatchNumber(1), 0u32),
+            address: token,
+            indexed_topics: erc1155_topics(*TRANSFER_SINGLE_EVENT_SIGNATURE),
+            value: ethabi::encode(&[Token::Uint(7.into()), Token::Uint(100.into())]),
+        };
+        let transfers = NftTransfer::from_event(&single_event);
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].standard, NftStandard::Erc1155);
+        assert_eq!((transfers[0].from, transfers[0].to), (from, to));
+        assert_eq!(
+            (transfers[0].token_id, transfers[0].amount),
+            (7.into(), 100.into())
+        );
+
+        let batch_event = VmEvent {
+            location: (L1BatchNumber(1), 0u32),
+            address: token,
+            indexed_topics: erc1155_topics(*TRANSFER_BATCH_EVENT_SIGNATURE),
+            value: ethabi::encode(&[
+                Token::Array(vec![Token::Uint(1.into()), Token::Uint(2.into())]),
+                Token::Array(vec![Token::Uint(10.into()), Token::Uint(20.into())]),
+            ]),
+        };
+        let transfers = NftTransfer::from_event(&batch_event);
+        let ids_and_amounts: Vec<_> = transfers
+            .iter()
+            .map(|transfer| (transfer.token_id, transfer.amount))
+            .collect();
+        assert_eq!(
+            ids_and_amounts,
+            [(1.into(), 10.into()), (2.into(), 20.into())]
+        );
+
+        // Malformed batch with mismatched array lengths
+        let malformed_event = VmEvent {
+            value: ethabi::encode(&[
+                Token::Array(vec![Token::Uint(1.into())]),
+                Token::Array(vec![]),
+            ]),
+            ..batch_event
+        };
+        assert_eq!(NftTransfer::from_event(&malformed_event), []);
+
+        // ERC-20 transfers are not NFT transfers
+        let erc20_event = VmEvent {
+            indexed_topics: erc721_event.indexed_topics[..3].to_vec(),
+            value: u256_to_h256(U256::from(123)).as_bytes().to_vec(),
+            ..erc721_event
+        };
+        assert_eq!(NftTransfer::from_event(&erc20_event), []);
+    }
 }
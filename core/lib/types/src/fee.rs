@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 use zksync_utils::ceil_div;
 
-use crate::{circuit::CircuitStatistic, U256};
+use crate::{
+    circuit::{CircuitFamilyGas, CircuitStatistic},
+    tx::tx_execution_info::StorageAccessStatistic,
+    U256,
+};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", tag = "result")]
@@ -26,6 +30,10 @@ pub struct TransactionExecutionMetrics {
     pub total_updated_values_size: usize,
     pub pubdata_published: u32,
     pub circuit_statistic: CircuitStatistic,
+    #[serde(default)]
+    pub storage_accesses: StorageAccessStatistic,
+    #[serde(default)]
+    pub circuit_family_gas: CircuitFamilyGas,
 }
 
 impl Default for TransactionExecutionMetrics {
@@ -49,6 +57,8 @@ impl Default for TransactionExecutionMetrics {
             total_updated_values_size: 0,
             pubdata_published: 0,
             circuit_statistic: Default::default(),
+            storage_accesses: Default::default(),
+            circuit_family_gas: Default::default(),
         }
     }
 }
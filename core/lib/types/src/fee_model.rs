@@ -1,15 +1,19 @@
+use std::num::NonZeroU64;
+
 use serde::{Deserialize, Serialize};
 use zksync_config::configs::chain::{FeeModelVersion, StateKeeperConfig};
-use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
+use zksync_system_constants::{
+    get_intrinsic_constants, IntrinsicSystemGasConstants, L1_GAS_PER_PUBDATA_BYTE,
+};
 
-use crate::ProtocolVersionId;
+use crate::{ProtocolVersionId, U256};
 
 /// Fee input to be provided into the VM. It contains two options:
 /// - `L1Pegged`: L1 gas price is provided to the VM, and the pubdata price is derived from it. Using this option is required for the
 /// versions of Era prior to 1.4.1 integration.
 /// - `PubdataIndependent`: L1 gas price and pubdata price are not necessarily dependent on one another. This options is more suitable for the
 /// versions of Era after the 1.4.1 integration. It is expected that if a VM supports `PubdataIndependent` version, then it should also support `L1Pegged` version, but converting it into `PubdataIndependentBatchFeeModelInput` in-place.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BatchFeeInput {
     L1Pegged(L1PeggedBatchFeeModelInput),
     PubdataIndependent(PubdataIndependentBatchFeeModelInput),
@@ -137,7 +141,7 @@ impl BatchFeeInput {
 }
 
 /// Pubdata is only published via calldata and so its price is pegged to the L1 gas price.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct L1PeggedBatchFeeModelInput {
     /// Fair L2 gas price to provide
     pub fair_l2_gas_price: u64,
@@ -146,7 +150,7 @@ pub struct L1PeggedBatchFeeModelInput {
 }
 
 /// Pubdata price may be independent from L1 gas price.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PubdataIndependentBatchFeeModelInput {
     /// Fair L2 gas price to provide
     pub fair_l2_gas_price: u64,
@@ -258,3 +262,174 @@ impl FeeParams {
         })
     }
 }
+
+/// Conversion rate between ETH and the base token of the chain, expressed as the number of base token units
+/// that 1 wei is worth (`numerator / denominator`). For chains using ETH as the base token, the ratio is 1:1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaseTokenConversionRatio {
+    pub numerator: NonZeroU64,
+    pub denominator: NonZeroU64,
+}
+
+impl Default for BaseTokenConversionRatio {
+    fn default() -> Self {
+        Self {
+            numerator: NonZeroU64::MIN,
+            denominator: NonZeroU64::MIN,
+        }
+    }
+}
+
+impl BaseTokenConversionRatio {
+    /// Converts a price in wei into the base token units, rounding down. Saturates to `u64::MAX` on overflow.
+    pub fn convert(&self, wei_price: u64) -> u64 {
+        let converted = U256::from(wei_price) * U256::from(self.numerator.get())
+            / U256::from(self.denominator.get());
+        if converted > U256::from(u64::MAX) {
+            u64::MAX
+        } else {
+            converted.as_u64()
+        }
+    }
+}
+
+/// Chain-specific overrides of intrinsic gas constants (see [`IntrinsicSystemGasConstants`]) used to validate
+/// L2 transactions in the API server.
+///
+/// Only the intrinsic gas of L2 transactions can be overridden. Priority operations are validated by the L1 `Mailbox`
+/// facet using the default constants, so the L1 watcher must use the same constants to agree with L1 on which
+/// operations are valid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IntrinsicGasOverrides {
+    pub l2_tx_intrinsic_gas: Option<u32>,
+}
+
+/// Errors that can occur when validating [`IntrinsicGasOverrides`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum IntrinsicGasOverrideError {
+    #[error(
+        "overridden `l2_tx_intrinsic_gas` ({value}) is less than the intrinsic gas charged by the bootloader ({min}); \
+         transactions with such gas limits would fail during execution"
+    )]
+    L2TxIntrinsicGasTooLow { value: u32, min: u32 },
+    #[error(
+        "overridden `l2_tx_intrinsic_gas` ({value}) exceeds the max allowed L2 transaction gas limit ({max}); \
+         no transactions would be accepted"
+    )]
+    L2TxIntrinsicGasTooHigh { value: u32, max: u32 },
+}
+
+impl IntrinsicGasOverrides {
+    pub fn from_state_keeper_config(state_keeper_config: &StateKeeperConfig) -> Self {
+        Self {
+            l2_tx_intrinsic_gas: state_keeper_config.l2_tx_intrinsic_gas_override,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Validates overrides. The intrinsic gas of L2 transactions cannot be lowered below the value charged
+    /// by the bootloader, and it must leave room for transactions within `max_l2_tx_gas_limit`.
+    pub fn validate(&self, max_l2_tx_gas_limit: u32) -> Result<(), IntrinsicGasOverrideError> {
+        let Some(value) = self.l2_tx_intrinsic_gas else {
+            return Ok(());
+        };
+        let min = get_intrinsic_constants().l2_tx_intrinsic_gas;
+        if value < min {
+            return Err(IntrinsicGasOverrideError::L2TxIntrinsicGasTooLow { value, min });
+        }
+        if value >= max_l2_tx_gas_limit {
+            return Err(IntrinsicGasOverrideError::L2TxIntrinsicGasTooHigh {
+                value,
+                max: max_l2_tx_gas_limit,
+            });
+        }
+        Ok(())
+    }
+
+    /// Applies overrides to the default intrinsic constants. Overrides should be [validated](Self::validate())
+    /// beforehand.
+    pub fn apply(&self) -> IntrinsicSystemGasConstants {
+        let mut constants = get_intrinsic_constants();
+        if let Some(value) = self.l2_tx_intrinsic_gas {
+            constants.l2_tx_intrinsic_gas = value;
+        }
+        constants
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converting_prices_to_base_token() {
+        let ratio = BaseTokenConversionRatio::default();
+        assert_eq!(ratio.convert(1_000_000_000), 1_000_000_000);
+
+        let ratio = BaseTokenConversionRatio {
+            numerator: NonZeroU64::new(3).unwrap(),
+            denominator: NonZeroU64::new(2).unwrap(),
+        };
+        assert_eq!(ratio.convert(1_000_000_001), 1_500_000_001);
+
+        let ratio = BaseTokenConversionRatio {
+            numerator: NonZeroU64::new(u64::MAX).unwrap(),
+            denominator: NonZeroU64::MIN,
+        };
+        assert_eq!(ratio.convert(2), u64::MAX);
+    }
+
+    #[test]
+    fn applying_intrinsic_gas_overrides() {
+        let defaults = get_intrinsic_constants();
+        let overrides = IntrinsicGasOverrides::default();
+        overrides.validate(80_000_000).unwrap();
+        assert_eq!(overrides.apply(), defaults);
+
+        let overrides = IntrinsicGasOverrides {
+            l2_tx_intrinsic_gas: Some(defaults.l2_tx_intrinsic_gas * 2),
+        };
+        overrides.validate(80_000_000).unwrap();
+        let constants = overrides.apply();
+        assert_eq!(
+            constants.l2_tx_intrinsic_gas,
+            defaults.l2_tx_intrinsic_gas * 2
+        );
+        assert_eq!(
+            IntrinsicSystemGasConstants {
+                l2_tx_intrinsic_gas: defaults.l2_tx_intrinsic_gas,
+                ..constants
+            },
+            defaults
+        );
+    }
+
+    #[test]
+    fn invalid_intrinsic_gas_overrides() {
+        let defaults = get_intrinsic_constants();
+        let overrides = IntrinsicGasOverrides {
+            l2_tx_intrinsic_gas: Some(defaults.l2_tx_intrinsic_gas - 1),
+        };
+        assert_eq!(
+            overrides.validate(80_000_000).unwrap_err(),
+            IntrinsicGasOverrideError::L2TxIntrinsicGasTooLow {
+                value: defaults.l2_tx_intrinsic_gas - 1,
+                min: defaults.l2_tx_intrinsic_gas,
+            }
+        );
+
+        let overrides = IntrinsicGasOverrides {
+            l2_tx_intrinsic_gas: Some(80_000_000),
+        };
+        assert_eq!(
+            overrides.validate(80_000_000).unwrap_err(),
+            IntrinsicGasOverrideError::L2TxIntrinsicGasTooHigh {
+                value: 80_000_000,
+                max: 80_000_000,
+            }
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use zksync_basic_types::U256;
 
 #[derive(Debug, Error)]
 pub enum L1TxParseError {
@@ -11,3 +12,18 @@ pub enum L1TxParseError {
     #[error("Ethereum ABI error: {0}")]
     AbiError(#[from] crate::ethabi::Error),
 }
+
+/// Error returned when the L2 gas limit of a priority operation is outside protocol bounds.
+#[derive(Debug, Error, PartialEq)]
+pub enum L1TxGasLimitError {
+    #[error("gas limit {gas_limit} exceeds the maximum gas limit for priority operations {max_gas_limit}")]
+    TooHigh {
+        gas_limit: U256,
+        max_gas_limit: U256,
+    },
+    #[error("gas limit {gas_limit} doesn't cover intrinsic costs of the priority operation ({min_gas_limit})")]
+    TooLow {
+        gas_limit: U256,
+        min_gas_limit: U256,
+    },
+}
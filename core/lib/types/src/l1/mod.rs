@@ -7,12 +7,14 @@ use zksync_basic_types::{
     ethabi::{decode, ParamType, Token},
     Address, L1BlockNumber, Log, PriorityOpId, H160, H256, U256,
 };
-use zksync_utils::u256_to_account_address;
+use zksync_system_constants::get_intrinsic_constants;
+use zksync_utils::{ceil_div, u256_to_account_address};
 
 use super::Transaction;
 use crate::{
+    fee::encoding_len,
     helpers::unix_timestamp_ms,
-    l1::error::L1TxParseError,
+    l1::error::{L1TxGasLimitError, L1TxParseError},
     l2::TransactionType,
     priority_op_onchain_data::{PriorityOpOnchainData, PriorityOpOnchainMetadata},
     tx::Execute,
@@ -191,6 +193,60 @@ impl L1Tx {
     pub fn hash(&self) -> H256 {
         self.common_data.hash()
     }
+
+    /// Returns the minimal L2 gas limit covering intrinsic costs of this operation. Mirrors
+    /// `getMinimalPriorityTransactionGasLimit` from the L1 `Mailbox` facet.
+    pub fn minimal_gas_limit(&self) -> U256 {
+        let constants = get_intrinsic_constants();
+        let factory_deps_len = self
+            .execute
+            .factory_deps
+            .as_ref()
+            .map_or(0, |deps| deps.len() as u64);
+        let encoding_len = encoding_len(
+            self.execute.calldata.len() as u64,
+            0,
+            factory_deps_len,
+            0,
+            0,
+        ) as u64
+            * 32;
+
+        let delta_encoding_gas = ceil_div(
+            encoding_len * u64::from(constants.l1_tx_delta_544_encoding_bytes),
+            544,
+        );
+        let computation_gas = u64::from(constants.l1_tx_intrinsic_gas)
+            + delta_encoding_gas
+            + factory_deps_len * u64::from(constants.l1_tx_delta_factory_dep_gas);
+        let computation_gas = computation_gas.max(u64::from(constants.l1_tx_min_gas_base));
+
+        let pubdata = u64::from(constants.l1_tx_intrinsic_pubdata)
+            + factory_deps_len * u64::from(constants.l1_tx_delta_factory_dep_pubdata);
+        let pubdata_gas =
+            U256::from(pubdata).saturating_mul(self.common_data.gas_per_pubdata_limit);
+        U256::from(computation_gas).saturating_add(pubdata_gas)
+    }
+
+    /// Checks that the L2 gas limit of this operation is within protocol bounds, i.e., doesn't exceed
+    /// `max_gas_limit` (as set in the L1 contracts) and covers intrinsic costs.
+    pub fn validate_gas_limit(&self, max_gas_limit: U256) -> Result<(), L1TxGasLimitError> {
+        let gas_limit = self.common_data.gas_limit;
+        if gas_limit > max_gas_limit {
+            return Err(L1TxGasLimitError::TooHigh {
+                gas_limit,
+                max_gas_limit,
+            });
+        }
+        let min_gas_limit = self.minimal_gas_limit();
+        if gas_limit < min_gas_limit {
+            return Err(L1TxGasLimitError::TooLow {
+                gas_limit,
+                min_gas_limit,
+            });
+        }
+        Ok(())
+    }
 }
 
 impl TryFrom<Log> for L1Tx {
@@ -351,3 +407,70 @@ impl TryFrom<Log> for L1Tx {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_l1_tx(gas_limit: u64, factory_deps_count: usize) -> L1Tx {
+        L1Tx {
+            execute: Execute {
+                contract_address: Address::repeat_byte(1),
+                calldata: vec![0; 100],
+                value: U256::zero(),
+                factory_deps: Some(vec![vec![0; 32]; factory_deps_count]),
+            },
+            common_data: L1TxCommonData {
+                gas_limit: gas_limit.into(),
+                gas_per_pubdata_limit: 800.into(),
+                ..L1TxCommonData::default()
+            },
+            received_timestamp_ms: 0,
+        }
+    }
+
+    #[test]
+    fn minimal_gas_limit_for_priority_ops() {
+        let constants = get_intrinsic_constants();
+        let tx = create_l1_tx(0, 0);
+        let min_gas_limit = tx.minimal_gas_limit();
+        let expected_pubdata_gas = u64::from(constants.l1_tx_intrinsic_pubdata) * 800;
+        let expected_min_gas_limit = u64::from(constants.l1_tx_min_gas_base) + expected_pubdata_gas;
+        assert!(min_gas_limit >= U256::from(expected_min_gas_limit));
+
+        // Factory deps increase both computational and pubdata costs.
+        let tx_with_deps = create_l1_tx(0, 2);
+        let expected_delta = 2
+            * (u64::from(constants.l1_tx_delta_factory_dep_gas)
+                + u64::from(constants.l1_tx_delta_factory_dep_pubdata) * 800);
+        assert!(tx_with_deps.minimal_gas_limit() > min_gas_limit);
+        assert!(tx_with_deps.minimal_gas_limit() <= min_gas_limit + U256::from(expected_delta));
+    }
+
+    #[test]
+    fn validating_priority_op_gas_limit() {
+        let max_gas_limit = U256::from(72_000_000);
+        let tx = create_l1_tx(1_000_000, 1);
+        tx.validate_gas_limit(max_gas_limit).unwrap();
+
+        let tx = create_l1_tx(100_000_000, 1);
+        let err = tx.validate_gas_limit(max_gas_limit).unwrap_err();
+        assert_eq!(
+            err,
+            L1TxGasLimitError::TooHigh {
+                gas_limit: 100_000_000.into(),
+                max_gas_limit,
+            }
+        );
+
+        let tx = create_l1_tx(1_000, 1);
+        let err = tx.validate_gas_limit(max_gas_limit).unwrap_err();
+        assert_eq!(
+            err,
+            L1TxGasLimitError::TooLow {
+                gas_limit: 1_000.into(),
+                min_gas_limit: tx.minimal_gas_limit(),
+            }
+        );
+    }
+}
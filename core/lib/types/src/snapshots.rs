@@ -19,9 +19,34 @@ pub struct AllSnapshots {
     pub snapshots_l1_batch_numbers: Vec<L1BatchNumber>,
 }
 
+/// Version of the snapshot format. Determines how snapshot data is encoded in the object store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[repr(u16)]
+pub enum SnapshotVersion {
+    /// Factory dependencies are gzip-compressed.
+    #[default]
+    Version0 = 0,
+    /// Factory dependencies are zstd-compressed.
+    Version1 = 1,
+}
+
+impl TryFrom<u16> for SnapshotVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Version0),
+            1 => Ok(Self::Version1),
+            _ => anyhow::bail!("unsupported snapshot version: {value}"),
+        }
+    }
+}
+
 /// Storage snapshot metadata. Used in DAL to fetch certain snapshot data.
 #[derive(Debug, Clone)]
 pub struct SnapshotMetadata {
+    /// Version of the snapshot format.
+    pub version: SnapshotVersion,
     /// L1 batch for the snapshot. The data in the snapshot captures node storage at the end of this batch.
     pub l1_batch_number: L1BatchNumber,
     /// Path to the factory dependencies blob.
@@ -43,6 +68,10 @@ impl SnapshotMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SnapshotHeader {
+    /// Version of the snapshot format; see [`SnapshotVersion`]. Missing for snapshots created by older nodes,
+    /// which corresponds to [`SnapshotVersion::Version0`].
+    #[serde(default)]
+    pub version: u16,
     pub l1_batch_number: L1BatchNumber,
     pub miniblock_number: MiniblockNumber,
     /// Ordered by chunk IDs.
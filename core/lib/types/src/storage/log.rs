@@ -80,7 +80,7 @@ impl StorageLog {
     }
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum StorageLogQueryType {
     Read,
     InitialWrite,
@@ -88,7 +88,7 @@ pub enum StorageLogQueryType {
 }
 
 /// Log query, which handle initial and repeated writes to the storage
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StorageLogQuery {
     pub log_query: LogQuery,
     pub log_type: StorageLogQueryType,
@@ -1,13 +1,21 @@
 use std::collections::HashMap;
 
+use rayon::prelude::*;
 use zksync_utils::u256_to_h256;
 
 use crate::{
     tx::tx_execution_info::DeduplicatedWritesMetrics,
     writes::compression::compress_with_best_strategy, AccountTreeId, StorageKey, StorageLogQuery,
-    StorageLogQueryType, U256,
+    StorageLogQueryType, H256, U256,
 };
 
+/// Number of shards processed in parallel by [`StorageWritesDeduplicator::deduplicate_in_parallel()`].
+const PARALLEL_SHARD_COUNT: usize = 16;
+/// Minimum number of storage logs for which [`StorageWritesDeduplicator::deduplicate_sorted()`] switches
+/// to parallel deduplication. For smaller miniblocks, dispatching work to the rayon thread pool costs more
+/// than it saves.
+const PARALLEL_DEDUPLICATION_THRESHOLD: usize = 4_096;
+
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct ModifiedSlot {
     /// Value of the slot after modification.
@@ -80,6 +88,82 @@ impl StorageWritesDeduplicator {
         deduplicator.metrics
     }
 
+    /// Deduplicates storage writes, choosing between [`Self::deduplicate_sequentially()`] and
+    /// [`Self::deduplicate_in_parallel()`] based on the number of logs. Both produce the same output.
+    pub fn deduplicate_sorted(logs: &[StorageLogQuery]) -> Vec<(StorageKey, ModifiedSlot)> {
+        if logs.len() < PARALLEL_DEDUPLICATION_THRESHOLD {
+            Self::deduplicate_sequentially(logs)
+        } else {
+            Self::deduplicate_in_parallel(logs)
+        }
+    }
+
+    /// Deduplicates storage writes on the current thread. The output is ordered in the same way
+    /// as for [`Self::deduplicate_in_parallel()`].
+    pub fn deduplicate_sequentially(logs: &[StorageLogQuery]) -> Vec<(StorageKey, ModifiedSlot)> {
+        let mut deduplicator = Self::new();
+        deduplicator.apply(logs.iter().filter(|log| log.log_query.rw_flag));
+        let mut modified_slots: Vec<_> = deduplicator
+            .into_modified_key_values()
+            .into_iter()
+            .map(|(key, slot)| (key.hashed_key(), key, slot))
+            .collect();
+        modified_slots.sort_unstable_by_key(|(hashed_key, _, slot)| (slot.tx_index, *hashed_key));
+        modified_slots
+            .into_iter()
+            .map(|(_, key, slot)| (key, slot))
+            .collect()
+    }
+
+    /// Deduplicates storage writes in parallel. Logs are sharded by the hashed storage key; since deduplication
+    /// of a key only depends on the logs for this key, each shard is deduplicated independently (preserving
+    /// the log order within the shard).
+    ///
+    /// Unlike [`Self::into_modified_key_values()`], the output order is deterministic: entries are sorted
+    /// by the index of the transaction that lastly modified the slot, and then by the hashed key.
+    pub fn deduplicate_in_parallel(logs: &[StorageLogQuery]) -> Vec<(StorageKey, ModifiedSlot)> {
+        let hashed_writes: Vec<_> = logs
+            .par_iter()
+            .filter(|log| log.log_query.rw_flag)
+            .map(|log| {
+                let key = StorageKey::new(
+                    AccountTreeId::new(log.log_query.address),
+                    u256_to_h256(log.log_query.key),
+                );
+                (key.hashed_key(), key, log)
+            })
+            .collect();
+
+        let mut shards = vec![vec![]; PARALLEL_SHARD_COUNT];
+        for write in hashed_writes {
+            let shard_index = write.0 .0[0] as usize % PARALLEL_SHARD_COUNT;
+            shards[shard_index].push(write);
+        }
+
+        let mut modified_slots: Vec<(H256, StorageKey, ModifiedSlot)> = shards
+            .into_par_iter()
+            .flat_map_iter(|shard| {
+                let hashed_keys: HashMap<_, _> = shard
+                    .iter()
+                    .map(|&(hashed_key, key, _)| (key, hashed_key))
+                    .collect();
+                let mut deduplicator = Self::new();
+                deduplicator.apply(shard.iter().map(|&(_, _, log)| log));
+                deduplicator
+                    .into_modified_key_values()
+                    .into_iter()
+                    .map(move |(key, slot)| (hashed_keys[&key], key, slot))
+            })
+            .collect();
+        // Hashed keys are unique, so this ordering is total.
+        modified_slots
+            .par_sort_unstable_by_key(|(hashed_key, _, slot)| (slot.tx_index, *hashed_key));
+        modified_slots
+            .into_iter()
+            .map(|(_, key, slot)| (key, slot))
+            .collect()
+    }
+
     /// Processes storage logs and returns updates for `modified_keys` and `metrics` fields.
     /// Metrics can be used later to rollback the state.
     /// We don't care about `initial_values` changes as we only inserted values there and they are always valid.
@@ -569,4 +653,80 @@ mod tests {
         deduplicator.apply(&logs);
         assert_eq!(expected, deduplicator.modified_key_values);
     }
+
+    #[test]
+    fn parallel_deduplication_matches_sequential_one() {
+        let logs: Vec<_> = (0_u32..2_000)
+            .map(|i| {
+                let address = H160::from_low_u64_be((i % 7).into());
+                let key = U256::from(i % 300);
+                let mut log = storage_log_query(
+                    key,
+                    (i % 5).into(),
+                    (i % 11).into(),
+                    i % 13 == 0,
+                    i % 3 == 0,
+                );
+                log.log_query.address = address;
+                log.log_query.tx_number_in_block = (i / 100) as u16;
+                log.log_query.rw_flag = i % 17 != 0;
+                log
+            })
+            .collect();
+
+        let mut deduplicator = StorageWritesDeduplicator::new();
+        deduplicator.apply(&logs);
+        let expected = deduplicator.into_modified_key_values();
+
+        let modified_slots = StorageWritesDeduplicator::deduplicate_in_parallel(&logs);
+        assert_eq!(modified_slots.len(), expected.len());
+        assert_eq!(
+            modified_slots.iter().copied().collect::<HashMap<_, _>>(),
+            expected
+        );
+        let ordering_keys: Vec<_> = modified_slots
+            .iter()
+            .map(|(key, slot)| (slot.tx_index, key.hashed_key()))
+            .collect();
+        assert!(
+            ordering_keys.windows(2).all(|window| window[0] < window[1]),
+            "{ordering_keys:?}"
+        );
+        assert_eq!(
+            StorageWritesDeduplicator::deduplicate_in_parallel(&logs),
+            modified_slots
+        );
+        assert_eq!(
+            StorageWritesDeduplicator::deduplicate_sequentially(&logs),
+            modified_slots
+        );
+        assert_eq!(
+            StorageWritesDeduplicator::deduplicate_sorted(&logs),
+            modified_slots
+        );
+    }
+
+    #[test]
+    fn sorted_deduplication_for_small_and_large_inputs() {
+        for log_count in [10, PARALLEL_DEDUPLICATION_THRESHOLD + 10] {
+            let logs: Vec<_> = (0..log_count)
+                .map(|i| {
+                    let mut log = storage_log_query(
+                        U256::from(i % 100),
+                        i.into(),
+                        (i + 1).into(),
+                        true,
+                        false,
+                    );
+                    log.log_query.tx_number_in_block = (i / 10) as u16;
+                    log
+                })
+                .collect();
+            assert_eq!(
+                StorageWritesDeduplicator::deduplicate_sorted(&logs),
+                StorageWritesDeduplicator::deduplicate_in_parallel(&logs),
+                "{log_count}"
+            );
+        }
+    }
 }
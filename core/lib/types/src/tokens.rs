@@ -1,3 +1,5 @@
+use std::{fmt, str::FromStr};
+
 use serde::{Deserialize, Serialize};
 use zksync_basic_types::Address;
 pub use zksync_system_constants::ETHEREUM_ADDRESS;
@@ -32,3 +34,38 @@ impl TokenMetadata {
         }
     }
 }
+
+/// Standard of a non-fungible (or semi-fungible) token contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NftStandard {
+    Erc721,
+    Erc1155,
+}
+
+impl NftStandard {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Erc721 => "erc721",
+            Self::Erc1155 => "erc1155",
+        }
+    }
+}
+
+impl fmt::Display for NftStandard {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NftStandard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "erc721" => Ok(Self::Erc721),
+            "erc1155" => Ok(Self::Erc1155),
+            _ => Err(format!("unknown NFT standard: {s}")),
+        }
+    }
+}
@@ -12,6 +12,8 @@ use zksync_utils::{
 
 use super::{EIP_1559_TX_TYPE, EIP_2930_TX_TYPE, EIP_712_TX_TYPE};
 use crate::{
+    api::{SignatureIssue, SignatureReport},
+    ethabi,
     fee::Fee,
     l1::L1Tx,
     l2::{L2Tx, TransactionType},
@@ -265,6 +267,24 @@ impl PaymasterParams {
 
         Ok(result)
     }
+
+    /// Decodes the token and the minimal allowance required by the approval-based paymaster flow, i.e., if
+    /// the paymaster input encodes the `approvalBased(address,uint256,bytes)` call of the `IPaymasterFlow` interface.
+    /// Returns `None` for other flows.
+    pub fn approval_based_allowance(&self) -> Option<(Address, U256)> {
+        const PARAMS: [ethabi::ParamType; 3] = [
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+            ethabi::ParamType::Bytes,
+        ];
+
+        let selector = ethabi::short_signature("approvalBased", &PARAMS);
+        let encoded_params = self.paymaster_input.strip_prefix(&selector)?;
+        let mut tokens = ethabi::decode(&PARAMS, encoded_params).ok()?.into_iter();
+        let token = tokens.next()?.into_address()?;
+        let min_allowance = tokens.next()?.into_uint()?;
+        Some((token, min_allowance))
+    }
 }
 
 #[derive(Default, Serialize, Deserialize, Clone, PartialEq, Debug)]
@@ -561,6 +581,27 @@ impl TransactionRequest {
         bytes: &[u8],
         chain_id: L2ChainId,
     ) -> Result<(Self, H256), SerializationTransactionError> {
+        let mut tx = Self::decode_fields(bytes, Some(chain_id))?;
+        let default_signed_message = tx.get_default_signed_message(tx.chain_id)?;
+
+        tx.from = match tx.from {
+            Some(_) => tx.from,
+            None => tx.recover_default_signer(default_signed_message).ok(),
+        };
+
+        let hash = tx.get_tx_hash_with_signed_message(&default_signed_message, chain_id)?;
+
+        Ok((tx, hash))
+    }
+
+    /// Decodes transaction fields from the raw bytes without recovering the signer. If `chain_id` is specified,
+    /// checks that the transaction is signed for this chain, allowing legacy transactions without
+    /// EIP-155 replay protection.
+    fn decode_fields(
+        bytes: &[u8],
+        chain_id: Option<L2ChainId>,
+    ) -> Result<Self, SerializationTransactionError> {
+        let chain_id = chain_id.map(|chain_id| chain_id.as_u64());
         let rlp;
         let mut tx = match bytes.first() {
             Some(x) if *x >= 0x80 => {
@@ -573,7 +614,7 @@ impl TransactionRequest {
                 let v = rlp.val_at(6)?;
                 let (_, tx_chain_id) = PackedEthSignature::unpack_v(v)
                     .map_err(|_| SerializationTransactionError::MalformedSignature)?;
-                if tx_chain_id.is_some() && tx_chain_id != Some(chain_id.as_u64()) {
+                if chain_id.is_some() && tx_chain_id.is_some() && tx_chain_id != chain_id {
                     return Err(SerializationTransactionError::WrongChainId(tx_chain_id));
                 }
                 Self {
@@ -598,7 +639,7 @@ impl TransactionRequest {
                 }
 
                 let tx_chain_id = rlp.val_at(0).ok();
-                if tx_chain_id != Some(chain_id.as_u64()) {
+                if chain_id.is_some() && tx_chain_id != chain_id {
                     return Err(SerializationTransactionError::WrongChainId(tx_chain_id));
                 }
                 Self {
@@ -618,8 +659,9 @@ impl TransactionRequest {
                         DecoderError::RlpIncorrectListLen,
                     ));
                 }
+                // The chain ID is a part of the EIP-712 domain, so it must always be present.
                 let tx_chain_id = rlp.val_at(10).ok();
-                if tx_chain_id.is_some() && tx_chain_id != Some(chain_id.as_u64()) {
+                if chain_id.is_some() && tx_chain_id != chain_id {
                     return Err(SerializationTransactionError::WrongChainId(tx_chain_id));
                 }
 
@@ -656,17 +698,77 @@ impl TransactionRequest {
             validate_factory_deps(deps)?;
         }
         tx.raw = Some(Bytes(bytes.to_vec()));
+        Ok(tx)
+    }
 
-        let default_signed_message = tx.get_default_signed_message(tx.chain_id)?;
-
-        tx.from = match tx.from {
-            Some(_) => tx.from,
-            None => tx.recover_default_signer(default_signed_message).ok(),
+    /// Explains signature recovery for the raw transaction. Unlike [`Self::from_bytes()`], this method doesn't stop
+    /// on the first error, and collects all detected issues in the returned report. Checks depending on
+    /// the initiator account type must be performed separately using
+    /// [`SignatureReport::check_initiator_account()`].
+    pub fn explain_signature(bytes: &[u8], chain_id: L2ChainId) -> SignatureReport {
+        let mut report = SignatureReport {
+            transaction_type: match bytes.first() {
+                Some(&tx_type) if tx_type < 0x80 => tx_type,
+                _ => LEGACY_TX_TYPE,
+            },
+            expected_chain_id: chain_id.as_u64(),
+            ..SignatureReport::default()
+        };
+        let tx = match Self::decode_fields(bytes, None) {
+            Ok(tx) => tx,
+            Err(err @ SerializationTransactionError::MalformedSignature) => {
+                report.issues.push(SignatureIssue::MalformedSignature {
+                    message: err.to_string(),
+                });
+                return report;
+            }
+            Err(err) => {
+                report.issues.push(SignatureIssue::MalformedTransaction {
+                    message: err.to_string(),
+                });
+                return report;
+            }
         };
 
-        let hash = tx.get_tx_hash_with_signed_message(&default_signed_message, chain_id)?;
-
-        Ok((tx, hash))
+        report.chain_id = tx.chain_id;
+        report.replay_protected = tx.chain_id.is_some();
+        let is_unprotected_legacy_tx = tx.is_legacy_tx() && tx.chain_id.is_none();
+        if !is_unprotected_legacy_tx && tx.chain_id != Some(chain_id.as_u64()) {
+            report.issues.push(SignatureIssue::WrongChainId {
+                chain_id: tx.chain_id,
+            });
+        }
+        report.custom_signature = tx
+            .get_custom_signature()
+            .map_or(false, |signature| !signature.is_empty());
+        let signature = match tx.get_signature() {
+            Ok(signature) => signature,
+            Err(err) => {
+                report.issues.push(SignatureIssue::MalformedSignature {
+                    message: err.to_string(),
+                });
+                return report;
+            }
+        };
+        report.signature_length = Some(signature.len());
+
+        // Recover the signer for the chain the transaction is signed for, so that the report shows
+        // what the transaction actually commits to.
+        let recovered_signer = tx
+            .get_default_signed_message(tx.chain_id)
+            .and_then(|signed_message| tx.recover_default_signer(signed_message));
+        report.recovered_signer = recovered_signer.as_ref().ok().copied();
+        if tx.is_eip712_tx() {
+            report.initiator = tx.from;
+        } else {
+            match recovered_signer {
+                Ok(signer) => report.initiator = Some(signer),
+                Err(err) => report.issues.push(SignatureIssue::MalformedSignature {
+                    message: err.to_string(),
+                }),
+            }
+        }
+        report
     }
 
     fn get_default_signed_message(
@@ -674,10 +776,11 @@ impl TransactionRequest {
         chain_id: Option<u64>,
     ) -> Result<H256, SerializationTransactionError> {
         if self.is_eip712_tx() {
-            let tx_chain_id =
-                chain_id.ok_or(SerializationTransactionError::WrongChainId(chain_id))?;
+            let tx_chain_id = chain_id
+                .and_then(|chain_id| L2ChainId::try_from(chain_id).ok())
+                .ok_or(SerializationTransactionError::WrongChainId(chain_id))?;
             Ok(PackedEthSignature::typed_data_to_signed_bytes(
-                &Eip712Domain::new(L2ChainId::try_from(tx_chain_id).unwrap()),
+                &Eip712Domain::new(tx_chain_id),
                 self,
             ))
         } else {
@@ -1161,6 +1264,118 @@ mod tests {
         );
     }
 
+    fn create_eip712_request(from: Address, custom_signature: Vec<u8>) -> TransactionRequest {
+        TransactionRequest {
+            nonce: U256::from(1u32),
+            to: Some(Address::random()),
+            from: Some(from),
+            value: U256::from(10u32),
+            gas_price: U256::from(11u32),
+            max_priority_fee_per_gas: Some(U256::from(0u32)),
+            gas: U256::from(12u32),
+            input: Bytes::from(vec![1, 2, 3]),
+            transaction_type: Some(U64::from(EIP_712_TX_TYPE)),
+            eip712_meta: Some(Eip712Meta {
+                gas_per_pubdata: U256::from(4u32),
+                factory_deps: None,
+                custom_signature: Some(custom_signature),
+                paymaster_params: None,
+            }),
+            chain_id: Some(270),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn explaining_eip712_signature() {
+        let private_key = H256::random();
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let transaction_request = create_eip712_request(address, vec![]);
+        let domain = Eip712Domain::new(L2ChainId::from(270));
+        let signature =
+            PackedEthSignature::sign_typed_data(&private_key, &domain, &transaction_request)
+                .unwrap();
+        let encoded_tx = transaction_request.get_signed_bytes(&signature, L2ChainId::from(270));
+
+        let mut report = TransactionRequest::explain_signature(&encoded_tx, L2ChainId::from(270));
+        report.check_initiator_account(false);
+        assert_eq!(report.transaction_type, EIP_712_TX_TYPE);
+        assert_eq!(report.chain_id, Some(270));
+        assert!(report.replay_protected);
+        assert!(!report.custom_signature);
+        assert_eq!(report.signature_length, Some(65));
+        assert_eq!(report.initiator, Some(address));
+        assert_eq!(report.recovered_signer, Some(address));
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+
+        let report = TransactionRequest::explain_signature(&encoded_tx, L2ChainId::from(272));
+        assert_eq!(
+            report.issues,
+            [SignatureIssue::WrongChainId {
+                chain_id: Some(270)
+            }]
+        );
+        assert_eq!(report.recovered_signer, Some(address));
+
+        // Signature produced by another key
+        let other_key = H256::random();
+        let signature =
+            PackedEthSignature::sign_typed_data(&other_key, &domain, &transaction_request).unwrap();
+        let encoded_tx = transaction_request.get_signed_bytes(&signature, L2ChainId::from(270));
+        let mut report = TransactionRequest::explain_signature(&encoded_tx, L2ChainId::from(270));
+        let other_address = PackedEthSignature::address_from_private_key(&other_key).unwrap();
+        assert_eq!(report.recovered_signer, Some(other_address));
+        report.check_initiator_account(false);
+        assert_eq!(
+            report.issues,
+            [SignatureIssue::SignerMismatch {
+                recovered_signer: other_address
+            }]
+        );
+    }
+
+    #[test]
+    fn explaining_eip712_custom_signature() {
+        let address = Address::random();
+        let transaction_request = create_eip712_request(address, vec![1, 2, 3]);
+        let dummy_signature = PackedEthSignature::default();
+        let encoded_tx =
+            transaction_request.get_signed_bytes(&dummy_signature, L2ChainId::from(270));
+
+        let report = TransactionRequest::explain_signature(&encoded_tx, L2ChainId::from(270));
+        assert!(report.custom_signature);
+        assert_eq!(report.signature_length, Some(3));
+        assert_eq!(report.recovered_signer, None);
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+
+        // Custom signatures are only checked by smart contract accounts.
+        let mut contract_report = report.clone();
+        contract_report.check_initiator_account(true);
+        assert!(contract_report.initiator_is_contract);
+        assert!(contract_report.issues.is_empty());
+
+        let mut eoa_report = report;
+        eoa_report.check_initiator_account(false);
+        assert_eq!(
+            eoa_report.issues,
+            [SignatureIssue::UnexpectedSignatureLength { length: 3 }]
+        );
+    }
+
+    #[test]
+    fn explaining_malformed_transaction() {
+        let report = TransactionRequest::explain_signature(&[0xff, 1, 2], L2ChainId::from(270));
+        assert!(
+            matches!(
+                report.issues.as_slice(),
+                [SignatureIssue::MalformedTransaction { .. }]
+            ),
+            "{:?}",
+            report.issues
+        );
+        assert_eq!(report.initiator, None);
+    }
+
     #[test]
     fn check_recovered_public_key_eip1559() {
         let private_key = H256::random();
@@ -1530,4 +1745,40 @@ mod tests {
         let tx_request = TransactionRequest::from(call_request.clone());
         assert_eq!(tx_request.input, call_request.input.unwrap());
     }
+
+    #[test]
+    fn decoding_approval_based_paymaster_input() {
+        let token = Address::repeat_byte(0x23);
+        let encoded_params = ethabi::encode(&[
+            ethabi::Token::Address(token),
+            ethabi::Token::Uint(1_000.into()),
+            ethabi::Token::Bytes(vec![]),
+        ]);
+        let selector = ethabi::short_signature(
+            "approvalBased",
+            &[
+                ethabi::ParamType::Address,
+                ethabi::ParamType::Uint(256),
+                ethabi::ParamType::Bytes,
+            ],
+        );
+        let mut params = PaymasterParams {
+            paymaster: Address::repeat_byte(1),
+            paymaster_input: selector.iter().copied().chain(encoded_params).collect(),
+        };
+        assert_eq!(
+            params.approval_based_allowance(),
+            Some((token, U256::from(1_000)))
+        );
+
+        // General paymaster flow
+        params.paymaster_input = ethabi::short_signature("general", &[ethabi::ParamType::Bytes])
+            .iter()
+            .copied()
+            .chain(ethabi::encode(&[ethabi::Token::Bytes(vec![1, 2, 3])]))
+            .collect();
+        assert_eq!(params.approval_based_allowance(), None);
+        params.paymaster_input = vec![];
+        assert_eq!(params.approval_based_allowance(), None);
+    }
 }
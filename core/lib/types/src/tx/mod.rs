@@ -6,18 +6,19 @@
 
 use std::fmt::Debug;
 
+use serde::{Deserialize, Serialize};
 use zksync_basic_types::{Address, H256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
 use self::tx_execution_info::TxExecutionStatus;
 pub use self::{execute::Execute, tx_execution_info::ExecutionMetrics};
-use crate::{vm_trace::Call, Transaction};
+use crate::{api, vm_trace::Call, Transaction};
 
 pub mod execute;
 pub mod primitives;
 pub mod tx_execution_info;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TransactionExecutionResult {
     pub transaction: Transaction,
     pub hash: H256,
@@ -28,6 +29,7 @@ pub struct TransactionExecutionResult {
     pub compressed_bytecodes: Vec<CompressedBytecodeInfo>,
     pub call_traces: Vec<Call>,
     pub revert_reason: Option<String>,
+    pub decoded_revert_reason: Option<api::RevertReason>,
 }
 
 impl TransactionExecutionResult {
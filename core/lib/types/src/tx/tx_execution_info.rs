@@ -1,7 +1,7 @@
 use std::ops::{Add, AddAssign};
 
 use crate::{
-    circuit::CircuitStatistic,
+    circuit::{CircuitFamilyGas, CircuitStatistic},
     commitment::SerializeCommitment,
     fee::TransactionExecutionMetrics,
     l2_to_l1_log::L2ToL1Log,
@@ -12,7 +12,15 @@ use crate::{
     ProtocolVersionId,
 };
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Eq,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize
+)]
 pub enum TxExecutionStatus {
     Success,
     Failure,
@@ -56,7 +64,52 @@ impl DeduplicatedWritesMetrics {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+/// Number of storage slot accesses, split by whether the slot was already accessed earlier in the same transaction.
+///
+/// Not used in the fee model yet; collected to evaluate EIP-2929-style pricing of storage accesses.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize
+)]
+pub struct StorageAccessStatistic {
+    /// Accesses to slots that weren't accessed earlier in the transaction.
+    pub cold: usize,
+    /// Repeated accesses to slots within the transaction.
+    pub warm: usize,
+}
+
+impl StorageAccessStatistic {
+    pub fn total(&self) -> usize {
+        self.cold + self.warm
+    }
+}
+
+impl Add for StorageAccessStatistic {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            cold: self.cold + other.cold,
+            warm: self.warm + other.warm,
+        }
+    }
+}
+
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    serde::Serialize,
+    serde::Deserialize
+)]
 pub struct ExecutionMetrics {
     pub gas_used: usize,
     pub published_bytecode_bytes: usize,
@@ -71,6 +124,8 @@ pub struct ExecutionMetrics {
     pub computational_gas_used: u32,
     pub pubdata_published: u32,
     pub circuit_statistic: CircuitStatistic,
+    pub storage_accesses: StorageAccessStatistic,
+    pub circuit_family_gas: CircuitFamilyGas,
 }
 
 impl ExecutionMetrics {
@@ -89,6 +144,8 @@ impl ExecutionMetrics {
             computational_gas_used: tx_metrics.computational_gas_used,
             pubdata_published: tx_metrics.pubdata_published,
             circuit_statistic: tx_metrics.circuit_statistic,
+            storage_accesses: tx_metrics.storage_accesses,
+            circuit_family_gas: tx_metrics.circuit_family_gas,
         }
     }
 
@@ -123,6 +180,8 @@ impl Add for ExecutionMetrics {
             computational_gas_used: self.computational_gas_used + other.computational_gas_used,
             pubdata_published: self.pubdata_published + other.pubdata_published,
             circuit_statistic: self.circuit_statistic + other.circuit_statistic,
+            storage_accesses: self.storage_accesses + other.storage_accesses,
+            circuit_family_gas: self.circuit_family_gas + other.circuit_family_gas,
         }
     }
 }
@@ -43,6 +43,31 @@ fn key_for_erc20_balance(address: &Address) -> H256 {
     H256(keccak256(&bytes))
 }
 
+/// Create a `key` part of `StorageKey` to access the allowance from ERC20 contract allowances
+fn key_for_erc20_allowance(owner: &Address, spender: &Address) -> H256 {
+    // Allowances are stored in a nested mapping in the storage slot following `balanceOf`
+    // of the default ERC20 contract.
+    let slot_index = H256::from_low_u64_be(52);
+    let mut bytes = [0_u8; 64];
+    bytes[..32].copy_from_slice(address_to_h256(owner).as_bytes());
+    bytes[32..].copy_from_slice(slot_index.as_bytes());
+    let owner_slot = keccak256(&bytes);
+
+    bytes[..32].copy_from_slice(address_to_h256(spender).as_bytes());
+    bytes[32..].copy_from_slice(&owner_slot);
+    H256(keccak256(&bytes))
+}
+
+/// Create a storage key to access the allowance of `spender` for tokens of `owner` in a standard ERC20 token
+/// contract (i.e., a token deployed by the default bridge). Not applicable to the native ETH contract.
+pub fn storage_key_for_standard_token_allowance(
+    token_contract: AccountTreeId,
+    owner: &Address,
+    spender: &Address,
+) -> StorageKey {
+    StorageKey::new(token_contract, key_for_erc20_allowance(owner, spender))
+}
+
 /// Create a storage key to access the balance from supported token contract balances
 pub fn storage_key_for_standard_token_balance(
     token_contract: AccountTreeId,
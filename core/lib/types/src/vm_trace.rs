@@ -70,6 +70,9 @@ pub enum CallType {
     Call(FarCallOpcode),
     Create,
     NearCall,
+    /// Normal far call made with the static flag. Must remain the last variant to keep
+    /// the binary encoding of persisted call traces compatible.
+    StaticCall,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
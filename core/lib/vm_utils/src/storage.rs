@@ -1,4 +1,8 @@
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
 use anyhow::Context;
 use multivm::{
@@ -6,7 +10,7 @@ use multivm::{
     vm_latest::constants::BLOCK_GAS_LIMIT,
     zk_evm_latest::ethereum_types::H256,
 };
-use zksync_contracts::BaseSystemContracts;
+use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes};
 use zksync_dal::StorageProcessor;
 use zksync_types::{
     block::MiniblockHeader, fee_model::BatchFeeInput, snapshots::SnapshotRecoveryStatus, Address,
@@ -81,11 +85,48 @@ pub fn l1_batch_params(
     )
 }
 
+/// Cache of base system contracts keyed by their hashes. Since base system contracts only change
+/// with protocol upgrades, the cache holds a small number of entries (one per protocol version used in
+/// the processed L1 batches), and it is never pruned. The cache is cheaply cloneable, so it can be shared
+/// among multiple [`L1BatchParamsProvider`]s.
+///
+/// Only static per-version data is cached; VM instances themselves are not kept warm across L1 batches,
+/// since a VM instance owns the state of the L1 batch it executes (storage view, bootloader memory,
+/// batch environment) and cannot be reset to execute another batch.
+#[derive(Debug, Clone, Default)]
+pub struct BaseSystemContractsCache(
+    Arc<Mutex<HashMap<BaseSystemContractsHashes, BaseSystemContracts>>>,
+);
+
+impl BaseSystemContractsCache {
+    async fn get_or_load(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        hashes: BaseSystemContractsHashes,
+    ) -> anyhow::Result<BaseSystemContracts> {
+        if let Some(contracts) = self.0.lock().expect("cache is poisoned").get(&hashes) {
+            return Ok(contracts.clone());
+        }
+
+        let contracts = storage
+            .factory_deps_dal()
+            .get_base_system_contracts(hashes.bootloader, hashes.default_aa)
+            .await?;
+        self.0
+            .lock()
+            .expect("cache is poisoned")
+            .insert(hashes, contracts.clone());
+        Ok(contracts)
+    }
+}
+
 /// Provider of L1 batch parameters for state keeper I/O implementations. The provider is stateless; i.e., it doesn't
-/// enforce a particular order of method calls.
+/// enforce a particular order of method calls. Base system contracts loaded by the provider are cached,
+/// so that they are not loaded from Postgres for each L1 batch.
 #[derive(Debug)]
 pub struct L1BatchParamsProvider {
     snapshot: Option<SnapshotRecoveryStatus>,
+    contracts_cache: BaseSystemContractsCache,
 }
 
 impl L1BatchParamsProvider {
@@ -94,7 +135,16 @@ impl L1BatchParamsProvider {
             .snapshot_recovery_dal()
             .get_applied_snapshot_status()
             .await?;
-        Ok(Self { snapshot })
+        Ok(Self {
+            snapshot,
+            contracts_cache: BaseSystemContractsCache::default(),
+        })
+    }
+
+    /// Uses the specified cache for base system contracts instead of a cache private to this provider.
+    pub fn with_contracts_cache(mut self, cache: BaseSystemContractsCache) -> Self {
+        self.contracts_cache = cache;
+        self
     }
 
     /// Returns state root hash and timestamp of an L1 batch with the specified number waiting for the hash to be computed
@@ -280,9 +330,9 @@ impl L1BatchParamsProvider {
         );
 
         let contract_hashes = first_miniblock_in_batch.header.base_system_contracts_hashes;
-        let base_system_contracts = storage
-            .factory_deps_dal()
-            .get_base_system_contracts(contract_hashes.bootloader, contract_hashes.default_aa)
+        let base_system_contracts = self
+            .contracts_cache
+            .get_or_load(storage, contract_hashes)
             .await
             .context("failed getting base system contracts")?;
 
@@ -13,8 +13,12 @@ use std::{
 
 use jsonrpsee::core::ClientError;
 use pin_project_lite::pin_project;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use zksync_types::{api::SerializationTransactionError, L1BatchNumber, MiniblockNumber};
+use zksync_types::{
+    api::SerializationTransactionError, pubdata_da::PubdataDA, Address, L1BatchNumber,
+    MiniblockNumber, H256,
+};
 
 #[derive(Debug, Error)]
 pub enum Web3Error {
@@ -36,10 +40,24 @@ pub enum Web3Error {
     InvalidTransactionData(#[from] zksync_types::ethabi::Error),
     #[error("{0}")]
     SubmitTransactionError(String, Vec<u8>),
+    /// Transaction was rejected during validation. Unlike [`Self::SubmitTransactionError`], the error is accompanied
+    /// by machine-readable error data.
+    #[error("{0}")]
+    TxValidationError(String, TxValidationErrorData),
     #[error("Failed to serialize transaction: {0}")]
     SerializationError(#[from] SerializationTransactionError),
     #[error("Invalid fee parameters: {0}")]
     InvalidFeeParams(String),
+    #[error("Invalid state override: {0}")]
+    InvalidStateOverride(String),
+    #[error("Transaction bundle must contain from 1 to {0} transactions")]
+    InvalidBundleSize(usize),
+    #[error("Gas estimation batch must contain at most {0} transactions")]
+    InvalidEstimationBatchSize(usize),
+    #[error("Proofs can be requested for at most {0} storage keys")]
+    TooManyProofKeys(usize),
+    #[error("Transaction doesn't specify a paymaster")]
+    NoPaymaster,
     #[error("More than four topics in filter")]
     TooManyTopics,
     #[error("Your connection time exceeded the limit")]
@@ -54,6 +72,57 @@ pub enum Web3Error {
     InvalidFilterBlockHash,
     #[error("Tree API is not available")]
     TreeApiUnavailable,
+    #[error("Merkle proofs for block {0} are not available until its L1 batch is processed by the Merkle tree")]
+    ProofsNotAvailable(MiniblockNumber),
+    /// Logs filter explicitly references an address or topic for which logs are not served by the node.
+    #[error("Logs for {0} are not served by this node")]
+    DenylistedLogsFilter(DenylistedLogsFilter),
+    #[error("Trace filter block range must not exceed {0} blocks")]
+    TraceFilterBlockRangeExceeded(u32),
+    #[error("Trace filter must not request more than {0} traces")]
+    TraceFilterCountExceeded(usize),
+    #[error("Overriding base system contracts is disabled on this node")]
+    SystemContractsOverrideDisabled,
+    #[error("Invalid {0} bytecode: {1}")]
+    InvalidSystemContractBytecode(&'static str, String),
+    #[error("Pubdata mode cannot be switched on this node")]
+    PubdataModeSwitchUnavailable,
+    #[error("Pubdata mode {0:?} is not supported by this node")]
+    UnsupportedPubdataMode(PubdataDA),
+}
+
+/// Denylisted item referenced by a logs filter. Returned as the `data` field of the JSON-RPC error.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum DenylistedLogsFilter {
+    Address(Address),
+    Topic(H256),
+}
+
+impl fmt::Display for DenylistedLogsFilter {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address(address) => write!(formatter, "address {address:?}"),
+            Self::Topic(topic) => write!(formatter, "topic {topic:?}"),
+        }
+    }
+}
+
+/// Machine-readable data for a transaction rejected by the server, returned as the `data` field
+/// of the JSON-RPC error.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxValidationErrorData {
+    /// Stable numeric error code. Codes are never reused for different errors.
+    pub code: u16,
+    /// Violated constraint in kebab case, e.g. `max-fee-per-gas-too-low`.
+    pub constraint: String,
+    /// Value required to satisfy the constraint, such as the minimum acceptable fee per gas.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub required: Option<serde_json::Value>,
+    /// Value provided in the transaction or observed for its initiator (e.g., account balance).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provided: Option<serde_json::Value>,
 }
 
 /// Client RPC error with additional details: the method name and arguments of the called method.
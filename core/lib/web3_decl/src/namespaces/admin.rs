@@ -0,0 +1,60 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::{
+    api::{
+        BaseSystemContractsOverride, BaseSystemContractsOverrideHashes, ValidationAllowListEntry,
+    },
+    pubdata_da::PubdataDA,
+};
+
+/// Namespace with methods used by node operators. Not enabled by default.
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "admin")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "admin")
+)]
+pub trait AdminNamespace {
+    /// Returns the allow-list of storage slots and addresses currently used when validating transactions.
+    #[method(name = "getValidationAllowList")]
+    async fn get_validation_allow_list(&self) -> RpcResult<Vec<ValidationAllowListEntry>>;
+
+    /// Reloads the validation allow-list from the database and returns the reloaded entries. Allow-list changes
+    /// are picked up automatically during transaction validation, so this is only necessary to force a reload.
+    #[method(name = "reloadValidationAllowList")]
+    async fn reload_validation_allow_list(&self) -> RpcResult<Vec<ValidationAllowListEntry>>;
+
+    /// Overrides the bootloader and / or default account bytecodes used by the API sandbox for all protocol versions
+    /// (the state keeper is not affected). Bytecodes not specified in the override are reset to their defaults.
+    /// Intended for local development of system contracts; only available if enabled in the node config.
+    #[method(name = "setBaseSystemContractsOverride")]
+    async fn set_base_system_contracts_override(
+        &self,
+        contracts: BaseSystemContractsOverride,
+    ) -> RpcResult<BaseSystemContractsOverrideHashes>;
+
+    /// Returns hashes of the bytecodes currently overriding base system contracts in the API sandbox.
+    #[method(name = "getBaseSystemContractsOverride")]
+    async fn get_base_system_contracts_override(
+        &self,
+    ) -> RpcResult<BaseSystemContractsOverrideHashes>;
+
+    /// Resets the override of base system contracts in the API sandbox.
+    #[method(name = "resetBaseSystemContractsOverride")]
+    async fn reset_base_system_contracts_override(&self) -> RpcResult<()>;
+
+    /// Returns the pubdata mode used by the state keeper for new L1 batches. Only available if the state keeper
+    /// runs in the same process as the API server.
+    #[method(name = "getPubdataMode")]
+    async fn get_pubdata_mode(&self) -> RpcResult<PubdataDA>;
+
+    /// Switches the pubdata mode used by the state keeper for new L1 batches; the currently open L1 batch keeps
+    /// its mode. Only available if the state keeper runs in the same process as the API server.
+    #[method(name = "setPubdataMode")]
+    async fn set_pubdata_mode(&self, mode: PubdataDA) -> RpcResult<()>;
+}
@@ -1,10 +1,10 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, SignatureReport, TracerConfig},
     transaction_request::CallRequest,
 };
 
-use crate::types::H256;
+use crate::types::{Bytes, H256};
 
 #[cfg_attr(
     all(feature = "client", feature = "server"),
@@ -44,4 +44,8 @@ pub trait DebugNamespace {
         tx_hash: H256,
         options: Option<TracerConfig>,
     ) -> RpcResult<Option<DebugCall>>;
+    /// Explains why the signature of a raw transaction (in the same format as for `eth_sendRawTransaction`)
+    /// would fail validation, e.g. because of a wrong chain ID or a malformed custom signature.
+    #[method(name = "explainSignature")]
+    async fn explain_signature(&self, tx_bytes: Bytes) -> RpcResult<SignatureReport>;
 }
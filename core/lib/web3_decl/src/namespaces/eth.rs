@@ -3,7 +3,10 @@ use jsonrpsee::{
     proc_macros::rpc,
 };
 use zksync_types::{
-    api::{BlockId, BlockIdVariant, BlockNumber, Transaction, TransactionVariant},
+    api::{
+        AccountProof, BlockId, BlockIdVariant, BlockNumber, StateOverride, Transaction,
+        TransactionVariant,
+    },
     transaction_request::CallRequest,
     Address, H256,
 };
@@ -33,7 +36,12 @@ pub trait EthNamespace {
     async fn chain_id(&self) -> RpcResult<U64>;
 
     #[method(name = "call")]
-    async fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> RpcResult<Bytes>;
+    async fn call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Bytes>;
 
     #[method(name = "estimateGas")]
     async fn estimate_gas(&self, req: CallRequest, _block: Option<BlockNumber>) -> RpcResult<U256>;
@@ -106,6 +114,17 @@ pub trait EthNamespace {
         block: Option<BlockIdVariant>,
     ) -> RpcResult<H256>;
 
+    /// Returns Merkle proofs for the account fields and storage slots of the specified account.
+    /// Unlike on Ethereum, proofs are provided for the state after the L1 batch containing the block;
+    /// for `latest` and `pending` blocks, proofs are provided for the last L1 batch processed by the Merkle tree.
+    #[method(name = "getProof")]
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<AccountProof>;
+
     #[method(name = "getTransactionCount")]
     async fn get_transaction_count(
         &self,
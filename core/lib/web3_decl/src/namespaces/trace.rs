@@ -0,0 +1,21 @@
+use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use zksync_types::api::{LocalizedTrace, TraceFilter};
+
+/// OpenEthereum-compatible tracing methods served from persisted call traces. Not enabled by default.
+#[cfg_attr(
+    all(feature = "client", feature = "server"),
+    rpc(server, client, namespace = "trace")
+)]
+#[cfg_attr(
+    all(feature = "client", not(feature = "server")),
+    rpc(client, namespace = "trace")
+)]
+#[cfg_attr(
+    all(not(feature = "client"), feature = "server"),
+    rpc(server, namespace = "trace")
+)]
+pub trait TraceNamespace {
+    /// Returns call traces matching the filter, flattened in the execution order.
+    #[method(name = "filter")]
+    async fn filter(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedTrace>>;
+}
@@ -1,18 +1,24 @@
 use std::collections::HashMap;
 
+use chrono::NaiveDate;
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        AccountBalances, AccountBalancesOptions, BaseTokenPrice, BatchGasEstimate, BlockDetails,
+        BlockIdVariant, BridgeAddresses, CommitmentArtifacts, L1BatchDetails, L1GasOracleState,
+        L2ToL1LogProof, PaymasterValidation, PriorityOpStatus, Proof, ProtocolVersion,
+        ProtocolVersionInfo, PruningInfo, SimulatedTransaction, StateOverride, TokenTransfer,
+        TokenTransfersRange, TransactionDeadline, TransactionDetails, TransactionTrace,
+        UtilizationReport,
     },
+    block::ContractPubdata,
     fee::Fee,
     fee_model::FeeParams,
     transaction_request::CallRequest,
-    Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
+    Address, L1BatchNumber, MiniblockNumber, PriorityOpId, H256, U256, U64,
 };
 
-use crate::types::Token;
+use crate::types::{Bytes, Token};
 
 #[cfg_attr(
     all(feature = "client", feature = "server"),
@@ -33,6 +39,9 @@ pub trait ZksNamespace {
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
 
+    #[method(name = "estimateGasBatch")]
+    async fn estimate_gas_batch(&self, reqs: Vec<CallRequest>) -> RpcResult<Vec<BatchGasEstimate>>;
+
     #[method(name = "getBridgehubContract")]
     async fn get_bridgehub_contract(&self) -> RpcResult<Option<Address>>;
 
@@ -51,10 +60,23 @@ pub trait ZksNamespace {
     #[method(name = "getConfirmedTokens")]
     async fn get_confirmed_tokens(&self, from: u32, limit: u8) -> RpcResult<Vec<Token>>;
 
+    #[method(name = "getTokenInfo")]
+    async fn get_token_info(&self, address: Address) -> RpcResult<Option<Token>>;
+
     #[method(name = "getAllAccountBalances")]
     async fn get_all_account_balances(&self, address: Address)
         -> RpcResult<HashMap<Address, U256>>;
 
+    /// Returns a page of fungible token balances and, optionally, NFT holdings of the specified address.
+    /// Unlike `getAllAccountBalances`, results can be filtered by tokens and are paginated;
+    /// see [`AccountBalancesOptions`] for details.
+    #[method(name = "getAccountBalances")]
+    async fn get_account_balances(
+        &self,
+        address: Address,
+        options: Option<AccountBalancesOptions>,
+    ) -> RpcResult<AccountBalances>;
+
     #[method(name = "getL2ToL1MsgProof")]
     async fn get_l2_to_l1_msg_proof(
         &self,
@@ -86,16 +108,39 @@ pub trait ZksNamespace {
     #[method(name = "getTransactionDetails")]
     async fn get_transaction_details(&self, hash: H256) -> RpcResult<Option<TransactionDetails>>;
 
+    /// Returns the status of a priority (L1 -> L2) operation with the specified serial ID, including
+    /// the L1 transaction that enqueued it and its position in the priority queue.
+    #[method(name = "getPriorityOpStatus")]
+    async fn get_priority_op_status(&self, id: PriorityOpId)
+        -> RpcResult<Option<PriorityOpStatus>>;
+
     #[method(name = "getRawBlockTransactions")]
     async fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
     ) -> RpcResult<Vec<zksync_types::Transaction>>;
 
+    /// Returns call traces persisted for all transactions in the specified miniblock in the order
+    /// of their execution. Traces are only available if the node persists them during execution.
+    #[method(name = "getRawBlockTraces")]
+    async fn get_raw_block_traces(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Vec<TransactionTrace>>;
+
     #[method(name = "getL1BatchDetails")]
     async fn get_l1_batch_details(&self, batch: L1BatchNumber)
         -> RpcResult<Option<L1BatchDetails>>;
 
+    /// Returns the inputs to the commitment of the specified L1 batch, which allow to recompute
+    /// and verify the commitment independently. Returns `null` if the batch doesn't exist
+    /// or its commitment isn't computed yet.
+    #[method(name = "getCommitmentArtifacts")]
+    async fn get_commitment_artifacts(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Option<CommitmentArtifacts>>;
+
     #[method(name = "getBytecodeByHash")]
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>>;
 
@@ -105,12 +150,23 @@ pub trait ZksNamespace {
     #[method(name = "getFeeParams")]
     async fn get_fee_params(&self) -> RpcResult<FeeParams>;
 
+    /// Returns the internal state of the L1 gas oracle: the window of sampled L1 base fees and blob base fees,
+    /// parameters used to smooth them, and the values currently applied by the fee model. Returns `null`
+    /// if the node doesn't run an oracle (e.g., for external nodes, which get fee params from the main node).
+    #[method(name = "getL1GasOracleState")]
+    async fn get_l1_gas_oracle_state(&self) -> RpcResult<Option<L1GasOracleState>>;
+
     #[method(name = "getProtocolVersion")]
     async fn get_protocol_version(
         &self,
         version_id: Option<u16>,
     ) -> RpcResult<Option<ProtocolVersion>>;
 
+    /// Returns all protocol versions known to the node ordered by their IDs, together with
+    /// the L1 batches they were activated in.
+    #[method(name = "getProtocolVersions")]
+    async fn get_protocol_versions(&self) -> RpcResult<Vec<ProtocolVersionInfo>>;
+
     #[method(name = "getProof")]
     async fn get_proof(
         &self,
@@ -118,4 +174,65 @@ pub trait ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Proof>;
+
+    /// Returns the first miniblock and L1 batch retained by the node. Requests for older data
+    /// will return a "pruned" error.
+    #[method(name = "getPruningInfo")]
+    async fn get_pruning_info(&self) -> RpcResult<PruningInfo>;
+
+    /// Returns the conversion rate between ETH and the base token of the chain used by the fee model.
+    #[method(name = "getBaseTokenPrice")]
+    async fn get_base_token_price(&self) -> RpcResult<BaseTokenPrice>;
+
+    /// Returns pubdata published in the specified L1 batch split by the contracts it's attributed to,
+    /// ordered by the number of pubdata bytes (largest first).
+    #[method(name = "getBatchPubdataByContract")]
+    async fn get_batch_pubdata_by_contract(
+        &self,
+        batch: L1BatchNumber,
+    ) -> RpcResult<Vec<ContractPubdata>>;
+
+    /// Executes a bundle of transactions one after another on top of the state of the specified block
+    /// (by default, the pending block) without submitting them. Each transaction observes state changes
+    /// made by the preceding transactions in the bundle. If any of the transactions is halted,
+    /// the entire bundle is rejected.
+    #[method(name = "simulateBundle")]
+    async fn simulate_bundle(
+        &self,
+        requests: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Vec<SimulatedTransaction>>;
+
+    /// Runs the validation step of a transaction on top of the pending block, checking validation rules only
+    /// for its paymaster, and returns validation details: gas usage, funds and allowance required by the paymaster,
+    /// and the violated validation rule (if any). The transaction doesn't need to be signed.
+    #[method(name = "validatePaymaster")]
+    async fn validate_paymaster(&self, req: CallRequest) -> RpcResult<PaymasterValidation>;
+
+    /// Returns ERC-20 and base token transfers sent or received by the specified address, in the order
+    /// of their execution. Results are paginated; see [`TokenTransfersRange`] for details.
+    #[method(name = "getTokenTransfers")]
+    async fn get_token_transfers(
+        &self,
+        address: Address,
+        range: Option<TokenTransfersRange>,
+    ) -> RpcResult<Vec<TokenTransfer>>;
+
+    /// Returns the L1 batch utilization report for the specified UTC date (formatted as `YYYY-MM-DD`),
+    /// or `null` if the report is not generated yet. Reports are generated by the house keeper once a day is over.
+    #[method(name = "getUtilizationReport")]
+    async fn get_utilization_report(&self, date: NaiveDate)
+        -> RpcResult<Option<UtilizationReport>>;
+
+    /// Submits a raw transaction similarly to `eth_sendRawTransaction`, but with an inclusion deadline.
+    /// If the transaction isn't included into a miniblock before the deadline, it's evicted from the mempool,
+    /// and `zks_getTransactionDetails` returns the `expired` status for it. The deadline isn't covered
+    /// by the transaction signature.
+    #[method(name = "sendRawTransactionWithDeadline")]
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: TransactionDeadline,
+    ) -> RpcResult<H256>;
 }
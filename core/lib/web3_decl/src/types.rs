@@ -20,8 +20,8 @@ pub use zksync_types::{
     web3::{
         ethabi,
         types::{
-            Address, BlockHeader, Bytes, CallRequest, FeeHistory, Index, SyncState, TraceFilter,
-            Transaction, Work, H160, H256, H64, U256, U64,
+            Address, BlockHeader, Bytes, CallRequest, FeeHistory, Index, TraceFilter, Transaction,
+            Work, H160, H256, H64, U256, U64,
         },
     },
 };
@@ -105,6 +105,71 @@ pub enum FilterChanges {
     Empty([u8; 0]),
 }
 
+/// Sync state returned by `eth_syncing`. Serialized as `false` if the node is not syncing, and as a [`SyncInfo`]
+/// object otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SyncState {
+    Syncing(SyncInfo),
+    NotSyncing,
+}
+
+impl Serialize for SyncState {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Syncing(info) => info.serialize(serializer),
+            Self::NotSyncing => false.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SyncState {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum SyncStateRepr {
+            Syncing(SyncInfo),
+            NotSyncing(bool),
+        }
+
+        match SyncStateRepr::deserialize(deserializer)? {
+            SyncStateRepr::Syncing(info) => Ok(Self::Syncing(info)),
+            SyncStateRepr::NotSyncing(false) => Ok(Self::NotSyncing),
+            SyncStateRepr::NotSyncing(true) => Err(de::Error::invalid_value(
+                de::Unexpected::Bool(true),
+                &"`false` or a sync info object",
+            )),
+        }
+    }
+}
+
+/// Sync progress of a node. Besides standard Web3 fields, contains zkSync-specific information
+/// about the progress of individual node components, which is reported by external nodes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncInfo {
+    /// Miniblock at which the sync has started.
+    pub starting_block: U64,
+    /// Latest miniblock applied by the node.
+    pub current_block: U64,
+    /// Latest miniblock known to be sealed on the main node.
+    pub highest_block: U64,
+    /// Latest miniblock fetched from the main node and queued for execution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetched_block: Option<U64>,
+    /// Latest L1 batch executed and sealed by the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applied_l1_batch: Option<U64>,
+    /// Latest L1 batch processed by the Merkle tree of the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tree_l1_batch: Option<U64>,
+    /// Latest L1 batch executed on L1, as known to the node.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub executed_l1_batch: Option<U64>,
+    /// Estimated time (in seconds) until the node catches up with the main node, based on the recent sync rate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_time_to_head: Option<U64>,
+}
+
 /// Either value or array of values.
 ///
 /// A value must serialize into a string.
@@ -254,6 +319,11 @@ pub struct PubSubFilter {
     pub address: Option<ValueOrArray<H160>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub topics: Option<Vec<Option<ValueOrArray<H256>>>>,
+    /// Sequence number of the last log received by the client in a previous subscription. If specified,
+    /// logs with greater sequence numbers retained by the server are delivered before new logs. Logs removed
+    /// because of a block revert (i.e., with `removed: true`) must not be used as a resubscription point.
+    #[serde(rename = "resubscribeFrom", skip_serializing_if = "Option::is_none")]
+    pub resubscribe_from: Option<U64>,
 }
 
 impl PubSubFilter {
@@ -337,13 +407,25 @@ fn topic_to_option<T>(topic: ethabi::Topic<T>) -> Option<Vec<T>> {
     }
 }
 
+/// Log delivered via the `logs` subscription.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SequencedLog {
+    #[serde(flatten)]
+    pub log: Log,
+    /// Monotonically increasing sequence number of the log, which is determined by the block number
+    /// and the log index in the block. Can be used with `resubscribeFrom` in [`PubSubFilter`] to resume
+    /// the subscription without missing or duplicating logs.
+    pub seq: U64,
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum PubSubResult {
     Header(BlockHeader),
-    Log(Log),
+    Log(SequencedLog),
     TxHash(H256),
+    L1BatchStatus(zksync_types::api::L1BatchStatusUpdate),
     Syncing(bool),
 }
 
@@ -441,4 +523,51 @@ mod tests {
         let restored_value: ValueOrArray<Address> = serde_json::from_value(json).unwrap();
         assert_eq!(restored_value, value);
     }
+
+    #[test]
+    fn sync_state_serde() {
+        let json = serde_json::to_value(SyncState::NotSyncing).unwrap();
+        assert_eq!(json, serde_json::json!(false));
+        let restored: SyncState = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, SyncState::NotSyncing);
+        serde_json::from_value::<SyncState>(serde_json::json!(true)).unwrap_err();
+
+        let info = SyncInfo {
+            starting_block: 0.into(),
+            current_block: 10.into(),
+            highest_block: 100.into(),
+            fetched_block: Some(20.into()),
+            applied_l1_batch: Some(1.into()),
+            tree_l1_batch: None,
+            executed_l1_batch: None,
+            estimated_time_to_head: Some(30.into()),
+        };
+        let json = serde_json::to_value(SyncState::Syncing(info.clone())).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "startingBlock": "0x0",
+                "currentBlock": "0xa",
+                "highestBlock": "0x64",
+                "fetchedBlock": "0x14",
+                "appliedL1Batch": "0x1",
+                "estimatedTimeToHead": "0x1e",
+            })
+        );
+        let restored: SyncState = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, SyncState::Syncing(info));
+
+        // Responses of nodes not reporting component progress must be parsed as well.
+        let json = serde_json::json!({
+            "startingBlock": "0x0",
+            "currentBlock": "0xa",
+            "highestBlock": "0x64",
+        });
+        let restored: SyncState = serde_json::from_value(json).unwrap();
+        let SyncState::Syncing(info) = restored else {
+            panic!("unexpected sync state");
+        };
+        assert_eq!(info.current_block, 10.into());
+        assert_eq!(info.fetched_block, None);
+    }
 }
@@ -1,4 +1,9 @@
 //! Generates rust code from protobufs.
+
+use std::{env, fs, path::PathBuf};
+
+use prost::Message as _;
+
 fn main() {
     zksync_protobuf_build::Config {
         input_root: "src/consensus/proto".into(),
@@ -9,4 +14,27 @@ fn main() {
     }
     .generate()
     .unwrap();
+
+    generate_tree_api_grpc();
+}
+
+/// Generates gRPC server and client for the Merkle tree API. Protobufs are compiled with `protox`,
+/// so that `protoc` is not required to build the crate.
+fn generate_tree_api_grpc() {
+    const PROTO_ROOT: &str = "src/api_server/tree/proto";
+    const PROTO_FILE: &str = "src/api_server/tree/proto/tree_api.proto";
+
+    println!("cargo:rerun-if-changed={PROTO_ROOT}");
+    let file_descriptors =
+        protox::compile([PROTO_FILE], [PROTO_ROOT]).expect("failed compiling tree API protobufs");
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("`OUT_DIR` is not set"));
+    let file_descriptors_path = out_dir.join("tree_api_descriptors.bin");
+    fs::write(&file_descriptors_path, file_descriptors.encode_to_vec())
+        .expect("failed writing tree API file descriptors");
+
+    tonic_build::configure()
+        .skip_protoc_run()
+        .file_descriptor_set_path(&file_descriptors_path)
+        .compile(&[PROTO_FILE], &[PROTO_ROOT])
+        .expect("failed generating tree API gRPC code");
 }
@@ -34,11 +34,13 @@ use zksync_types::{
 use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
 use super::{
+    storage::StorageWithOverrides,
     vm_metrics::{self, SandboxStage, SANDBOX_METRICS},
     BlockArgs, TxExecutionArgs, TxSharedArgs, VmPermit,
 };
 
-type BoxedVm<'a> = Box<VmInstance<StorageView<PostgresStorage<'a>>, HistoryDisabled>>;
+type SandboxStorage<'a> = StorageWithOverrides<PostgresStorage<'a>>;
+type BoxedVm<'a> = Box<VmInstance<StorageView<SandboxStorage<'a>>, HistoryDisabled>>;
 
 #[derive(Debug)]
 struct Sandbox<'a> {
@@ -46,7 +48,7 @@ struct Sandbox<'a> {
     l1_batch_env: L1BatchEnv,
     execution_args: &'a TxExecutionArgs,
     l2_block_info_to_reset: Option<StoredL2BlockInfo>,
-    storage_view: StorageView<PostgresStorage<'a>>,
+    storage_view: StorageView<SandboxStorage<'a>>,
 }
 
 impl<'a> Sandbox<'a> {
@@ -80,7 +82,7 @@ impl<'a> Sandbox<'a> {
         )
         .await?;
 
-        let storage = PostgresStorage::new_async(
+        let mut storage = PostgresStorage::new_async(
             Handle::current(),
             connection,
             resolved_block_info.state_l2_block_number,
@@ -89,7 +91,17 @@ impl<'a> Sandbox<'a> {
         .await
         .context("cannot create `PostgresStorage`")?
         .with_caches(shared_args.caches.clone());
+        if let Some(replica) = &shared_args.rocksdb_replica {
+            storage = storage
+                .with_rocksdb_replica(replica)
+                .await
+                .context("cannot use RocksDB replica")?;
+        }
 
+        let mut storage = StorageWithOverrides::new(storage);
+        if let Some(state_override) = &execution_args.state_override {
+            storage = storage.with_state_override(state_override);
+        }
         let storage_view = StorageView::new(storage);
         let (system_env, l1_batch_env) = Self::prepare_env(
             shared_args,
@@ -236,7 +248,8 @@ impl<'a> Sandbox<'a> {
             zk_porter_available: ZKPORTER_IS_AVAILABLE,
             version: resolved_block_info.protocol_version,
             base_system_smart_contracts: base_system_contracts
-                .get_by_protocol_version(resolved_block_info.protocol_version),
+                .get_by_protocol_version(resolved_block_info.protocol_version)
+                .clone(),
             gas_limit: BLOCK_GAS_LIMIT,
             execution_mode: execution_args.execution_mode,
             default_validation_computational_gas_limit: validation_computational_gas_limit,
@@ -259,7 +272,7 @@ impl<'a> Sandbox<'a> {
         mut self,
         tx: &Transaction,
         adjust_pubdata_price: bool,
-    ) -> (BoxedVm<'a>, StoragePtr<StorageView<PostgresStorage<'a>>>) {
+    ) -> (BoxedVm<'a>, StoragePtr<StorageView<SandboxStorage<'a>>>) {
         self.setup_storage_view(tx);
         let protocol_version = self.system_env.version;
         if adjust_pubdata_price {
@@ -294,7 +307,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
     tx: Transaction,
     block_args: BlockArgs,
     apply: impl FnOnce(
-        &mut VmInstance<StorageView<PostgresStorage<'_>>, HistoryDisabled>,
+        &mut VmInstance<StorageView<SandboxStorage<'_>>, HistoryDisabled>,
         Transaction,
     ) -> T,
 ) -> anyhow::Result<T> {
@@ -424,6 +437,31 @@ impl BlockArgs {
         )
     }
 
+    /// Returns the protocol version of the L1 batch used by the VM when executing transactions with these args.
+    pub(crate) async fn protocol_version(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<ProtocolVersionId> {
+        let miniblock_header = if self.is_pending_miniblock() {
+            connection
+                .blocks_dal()
+                .get_last_sealed_miniblock_header()
+                .await
+                .context("failed getting sealed miniblock header")?
+                .context("no miniblocks in storage")?
+        } else {
+            connection
+                .blocks_dal()
+                .get_miniblock_header(self.resolved_block_number)
+                .await
+                .context("failed getting header of resolved miniblock")?
+                .context("resolved miniblock disappeared from storage")?
+        };
+        Ok(miniblock_header
+            .protocol_version
+            .unwrap_or(ProtocolVersionId::last_potentially_undefined()))
+    }
+
     async fn resolve_block_info(
         &self,
         connection: &mut StorageProcessor<'_>,
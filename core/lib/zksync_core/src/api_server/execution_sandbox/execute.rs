@@ -2,7 +2,7 @@
 
 use anyhow::Context as _;
 use multivm::{
-    interface::{TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
+    interface::{ExecutionResult, TxExecutionMode, VmExecutionResultAndLogs, VmInterface},
     tracers::StorageInvocations,
     vm_latest::constants::ETH_CALL_GAS_LIMIT,
     MultiVMTracer,
@@ -10,8 +10,8 @@ use multivm::{
 use tracing::{span, Level};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
-    fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Nonce,
-    PackedEthSignature, Transaction, U256,
+    api::StateOverride, fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon,
+    Nonce, PackedEthSignature, Transaction, U256,
 };
 
 #[cfg(test)]
@@ -25,6 +25,8 @@ pub(crate) struct TxExecutionArgs {
     pub added_balance: U256,
     pub enforced_base_fee: Option<u64>,
     pub missed_storage_invocation_limit: usize,
+    /// State overrides applied on top of the VM storage. Only used for `eth_call` and alike.
+    pub state_override: Option<StateOverride>,
 }
 
 impl TxExecutionArgs {
@@ -35,12 +37,23 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
             missed_storage_invocation_limit: usize::MAX,
+            state_override: None,
+        }
+    }
+
+    /// Arguments for validating the paymaster of a transaction. The transaction is executed in the gas estimation mode,
+    /// so that it doesn't need to be signed by the initiator.
+    pub fn for_paymaster_validation(tx: &L2Tx) -> Self {
+        Self {
+            execution_mode: TxExecutionMode::EstimateFee,
+            ..Self::for_validation(tx)
         }
     }
 
     fn for_eth_call(
         enforced_base_fee: u64,
         vm_execution_cache_misses_limit: Option<usize>,
+        state_override: Option<StateOverride>,
     ) -> Self {
         let missed_storage_invocation_limit = vm_execution_cache_misses_limit.unwrap_or(usize::MAX);
         Self {
@@ -49,6 +62,7 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(enforced_base_fee),
             missed_storage_invocation_limit,
+            state_override,
         }
     }
 
@@ -72,6 +86,7 @@ impl TxExecutionArgs {
             enforced_nonce: tx.nonce(),
             added_balance,
             enforced_base_fee: Some(base_fee),
+            state_override: None,
         }
     }
 }
@@ -174,10 +189,15 @@ impl TransactionExecutor {
         block_args: BlockArgs,
         vm_execution_cache_misses_limit: Option<usize>,
         custom_tracers: Vec<ApiTracer>,
+        state_override: Option<StateOverride>,
+        gas_cap: Option<u32>,
     ) -> anyhow::Result<VmExecutionResultAndLogs> {
         let enforced_base_fee = tx.common_data.fee.max_fee_per_gas.as_u64();
-        let execution_args =
-            TxExecutionArgs::for_eth_call(enforced_base_fee, vm_execution_cache_misses_limit);
+        let execution_args = TxExecutionArgs::for_eth_call(
+            enforced_base_fee,
+            vm_execution_cache_misses_limit,
+            state_override,
+        );
 
         if tx.common_data.signature.is_empty() {
             tx.common_data.signature = PackedEthSignature::default().serialize_packed().into();
@@ -186,7 +206,9 @@ impl TransactionExecutor {
         // Protection against infinite-loop eth_calls and alike:
         // limiting the amount of gas the call can use.
         // We can't use `BLOCK_ERGS_LIMIT` here since the VM itself has some overhead.
-        tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
+        // The limit is further restricted by the gas cap configured for the API client, if any.
+        let gas_limit = gas_cap.map_or(ETH_CALL_GAS_LIMIT, |cap| cap.min(ETH_CALL_GAS_LIMIT));
+        tx.common_data.fee.gas_limit = gas_limit.into();
         let output = self
             .execute_tx_in_sandbox(
                 vm_permit,
@@ -201,4 +223,98 @@ impl TransactionExecutor {
             .await?;
         Ok(output.vm)
     }
+
+    /// Executes a bundle of transactions one after another in the same sandbox, so that each transaction
+    /// observes the state changes made by the preceding ones. Execution stops after the first halted transaction;
+    /// thus, the returned vector may be shorter than the input one, and its last element is the halted transaction
+    /// in this case.
+    ///
+    /// The bundle shares a single gas budget of [`ETH_CALL_GAS_LIMIT`], same as a single `eth_call`: each transaction
+    /// gets the gas left after executing the preceding transactions, so that a bundle holding a single VM permit
+    /// cannot consume more resources than a single call.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn execute_bundle_eth_call(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        connection_pool: ConnectionPool,
+        txs: Vec<L2Tx>,
+        block_args: BlockArgs,
+        vm_execution_cache_misses_limit: Option<usize>,
+        state_override: Option<StateOverride>,
+    ) -> anyhow::Result<Vec<VmExecutionResultAndLogs>> {
+        let mut txs: Vec<Transaction> = txs
+            .into_iter()
+            .map(|mut tx| {
+                if tx.common_data.signature.is_empty() {
+                    tx.common_data.signature =
+                        PackedEthSignature::default().serialize_packed().into();
+                }
+                tx.common_data.fee.gas_limit = ETH_CALL_GAS_LIMIT.into();
+                tx.into()
+            })
+            .collect();
+        anyhow::ensure!(!txs.is_empty(), "transaction bundle is empty");
+
+        #[cfg(test)]
+        if let Self::Mock(mock_executor) = self {
+            return mock_executor.execute_bundle(&txs, &block_args);
+        }
+
+        // All transactions in the bundle are executed in the context of the same block, so we use
+        // the base fee of the first transaction for the entire bundle.
+        let enforced_base_fee = match &txs[0].common_data {
+            ExecuteTransactionCommon::L2(data) => data.fee.max_fee_per_gas.as_u64(),
+            _ => unreachable!("bundle consists of L2 transactions"),
+        };
+        let execution_args = TxExecutionArgs::for_eth_call(
+            enforced_base_fee,
+            vm_execution_cache_misses_limit,
+            state_override,
+        );
+        let first_tx = txs.remove(0);
+
+        tokio::task::spawn_blocking(move || {
+            let span = span!(Level::DEBUG, "execute_bundle_in_sandbox").entered();
+            let result = apply::apply_vm_in_sandbox(
+                vm_permit,
+                shared_args,
+                false,
+                &execution_args,
+                &connection_pool,
+                first_tx,
+                block_args,
+                |vm, first_tx| {
+                    let mut results = Vec::with_capacity(txs.len() + 1);
+                    let mut remaining_gas = ETH_CALL_GAS_LIMIT;
+                    for mut tx in std::iter::once(first_tx).chain(txs) {
+                        if let ExecuteTransactionCommon::L2(data) = &mut tx.common_data {
+                            data.fee.gas_limit = remaining_gas.into();
+                        }
+                        let storage_invocation_tracer =
+                            StorageInvocations::new(execution_args.missed_storage_invocation_limit);
+                        let tracers = vec![storage_invocation_tracer.into_tracer_pointer()];
+                        let (_, result) = vm.inspect_transaction_with_bytecode_compression(
+                            tracers.into(),
+                            tx,
+                            true,
+                        );
+                        remaining_gas = remaining_gas.saturating_sub(result.statistics.gas_used);
+                        // The VM state after a halted transaction is undefined since the sandbox VM doesn't support rollbacks.
+                        // A transaction executed after the gas budget is exhausted is halted as well.
+                        let is_halted = matches!(result.result, ExecutionResult::Halt { .. });
+                        results.push(result);
+                        if is_halted {
+                            break;
+                        }
+                    }
+                    results
+                },
+            );
+            span.exit();
+            result
+        })
+        .await
+        .context("bundle execution panicked")?
+    }
 }
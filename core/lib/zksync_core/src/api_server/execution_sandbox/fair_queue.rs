@@ -0,0 +1,302 @@
+//! Weighted fair queueing of VM invocations.
+//!
+//! VM invocations are split into pools by their kind (calls, gas estimations, traces etc.). Pools share
+//! the global VM capacity proportionally to their weights (stride scheduling), and within each pool,
+//! permits are handed out to API clients in a round-robin fashion. Thus, a client flooding the server
+//! with `eth_call`s cannot starve gas estimations, or `eth_call`s of other clients.
+
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    future::Future,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+use tokio::{sync::oneshot, task::futures::TaskLocalFuture};
+use vise::{EncodeLabelSet, EncodeLabelValue};
+
+/// Pass increment for a pool with unit weight. Must be divisible by all pool weights.
+const STRIDE: u64 = 1 << 16;
+
+/// Kind of a VM invocation. Each kind is queued in a separate pool.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, EncodeLabelValue, EncodeLabelSet,
+)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub enum VmInvocationKind {
+    /// Validation and dry run of a submitted transaction.
+    SendTransaction,
+    /// `eth_call` and similar methods.
+    Call,
+    /// Gas estimation.
+    EstimateGas,
+    /// Debug traces.
+    Trace,
+}
+
+impl VmInvocationKind {
+    /// Relative share of the VM capacity allocated to the pool if all pools are saturated.
+    fn weight(self) -> u64 {
+        match self {
+            Self::SendTransaction => 4,
+            Self::Call | Self::EstimateGas => 2,
+            Self::Trace => 1,
+        }
+    }
+}
+
+tokio::task_local! {
+    static CLIENT_ID: ApiClientId;
+}
+
+/// Identity of an API client used to fairly share the VM capacity among clients. Set for the duration
+/// of the request processing by the API server middleware.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub(crate) enum ApiClientId {
+    /// Client without a known identity; all such clients share a single queue.
+    #[default]
+    Anonymous,
+    /// Client identified by an API key.
+    ApiKey(String),
+    /// Client identified by its IP address.
+    Ip(IpAddr),
+}
+
+impl ApiClientId {
+    /// Runs the provided future with this client ID set as the current one.
+    pub(crate) fn scope<F: Future>(self, future: F) -> TaskLocalFuture<Self, F> {
+        CLIENT_ID.scope(self, future)
+    }
+
+    /// Returns the ID of the client on behalf of which the current request is processed.
+    pub(crate) fn current() -> Self {
+        CLIENT_ID.try_with(Self::clone).unwrap_or_default()
+    }
+}
+
+type Waiter = oneshot::Sender<FairPermit>;
+
+#[derive(Debug)]
+struct ClientQueue {
+    pass: u64,
+    /// Activation order of the client in the pool; used to break ties.
+    order: u64,
+    waiters: VecDeque<Waiter>,
+}
+
+#[derive(Debug)]
+struct Pool {
+    weight: u64,
+    pass: u64,
+    /// Pass of the last served client in the pool.
+    clients_vtime: u64,
+    next_client_order: u64,
+    clients: HashMap<ApiClientId, ClientQueue>,
+}
+
+impl Pool {
+    fn new(weight: u64) -> Self {
+        Self {
+            weight,
+            pass: 0,
+            clients_vtime: 0,
+            next_client_order: 0,
+            clients: HashMap::new(),
+        }
+    }
+
+    fn push_waiter(&mut self, client: ApiClientId, waiter: Waiter) {
+        let queue = self.clients.entry(client).or_insert_with(|| {
+            self.next_client_order += 1;
+            ClientQueue {
+                pass: self.clients_vtime,
+                order: self.next_client_order,
+                waiters: VecDeque::new(),
+            }
+        });
+        queue.waiters.push_back(waiter);
+    }
+
+    fn pop_waiter(&mut self) -> Option<Waiter> {
+        let (client, queue) = self
+            .clients
+            .iter_mut()
+            .min_by_key(|(_, queue)| (queue.pass, queue.order))?;
+        self.clients_vtime = queue.pass;
+        queue.pass += 1;
+        let waiter = queue.waiters.pop_front();
+        if queue.waiters.is_empty() {
+            let client = client.clone();
+            self.clients.remove(&client);
+        }
+        waiter
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    available_permits: usize,
+    is_closed: bool,
+    /// Pass of the last served pool.
+    pools_vtime: u64,
+    pools: BTreeMap<VmInvocationKind, Pool>,
+}
+
+impl State {
+    fn has_waiters(&self) -> bool {
+        self.pools.values().any(|pool| !pool.clients.is_empty())
+    }
+
+    fn push_waiter(&mut self, kind: VmInvocationKind, client: ApiClientId, waiter: Waiter) {
+        let pool = self
+            .pools
+            .entry(kind)
+            .or_insert_with(|| Pool::new(kind.weight()));
+        if pool.clients.is_empty() {
+            // Do not allow pools that were idle for a long time to monopolize the capacity.
+            pool.pass = pool.pass.max(self.pools_vtime);
+        }
+        pool.push_waiter(client, waiter);
+    }
+
+    fn pop_waiter(&mut self) -> Option<Waiter> {
+        let pool = self
+            .pools
+            .values_mut()
+            .filter(|pool| !pool.clients.is_empty())
+            .min_by_key(|pool| pool.pass)?;
+        self.pools_vtime = pool.pass;
+        pool.pass += STRIDE / pool.weight;
+        pool.pop_waiter()
+    }
+}
+
+#[derive(Debug)]
+struct Shared {
+    max_concurrency: usize,
+    state: Mutex<State>,
+}
+
+impl Shared {
+    /// Hands out available permits to the waiters.
+    fn dispatch(self: &Arc<Self>, state: &mut State) {
+        while state.available_permits > 0 {
+            let Some(waiter) = state.pop_waiter() else {
+                break;
+            };
+            let permit = FairPermit {
+                shared: Some(self.clone()),
+            };
+            match waiter.send(permit) {
+                Ok(()) => state.available_permits -= 1,
+                Err(mut permit) => {
+                    // The waiter has gone away; disarm the permit so that it doesn't return to the queue.
+                    permit.shared = None;
+                }
+            }
+        }
+    }
+
+    fn release(self: &Arc<Self>) {
+        let mut state = self.state.lock().expect("fair queue is poisoned");
+        state.available_permits += 1;
+        self.dispatch(&mut state);
+    }
+}
+
+/// Permit issued by [`FairQueue`]. Returns to the queue on drop.
+#[derive(Debug)]
+pub(super) struct FairPermit {
+    shared: Option<Arc<Shared>>,
+}
+
+impl Drop for FairPermit {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.take() {
+            shared.release();
+        }
+    }
+}
+
+/// Weighted fair queue for VM permits.
+#[derive(Debug, Clone)]
+pub(super) struct FairQueue {
+    shared: Arc<Shared>,
+}
+
+impl FairQueue {
+    pub fn new(max_concurrency: usize) -> Self {
+        let state = State {
+            available_permits: max_concurrency,
+            is_closed: false,
+            pools_vtime: 0,
+            pools: BTreeMap::new(),
+        };
+        Self {
+            shared: Arc::new(Shared {
+                max_concurrency,
+                state: Mutex::new(state),
+            }),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.shared.state.lock().expect("fair queue is poisoned")
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.shared.max_concurrency
+    }
+
+    pub fn available_permits(&self) -> usize {
+        self.lock().available_permits
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.lock().is_closed
+    }
+
+    /// Closes the queue so that it doesn't issue new permits. All pending waiters will receive `None`.
+    pub fn close(&self) {
+        let mut state = self.lock();
+        state.is_closed = true;
+        state.pools.clear();
+    }
+
+    /// Acquires a permit for an invocation of the specified kind on behalf of the current API client.
+    /// The request is queued immediately, i.e., before the returned future is polled.
+    pub fn acquire(
+        &self,
+        kind: VmInvocationKind,
+    ) -> impl Future<Output = Option<FairPermit>> + Send {
+        self.acquire_for_client(kind, ApiClientId::current())
+    }
+
+    pub(super) fn acquire_for_client(
+        &self,
+        kind: VmInvocationKind,
+        client: ApiClientId,
+    ) -> impl Future<Output = Option<FairPermit>> + Send {
+        let receiver = {
+            let mut state = self.lock();
+            if state.is_closed {
+                None
+            } else if state.available_permits > 0 && !state.has_waiters() {
+                state.available_permits -= 1;
+                let (sender, receiver) = oneshot::channel();
+                sender
+                    .send(FairPermit {
+                        shared: Some(self.shared.clone()),
+                    })
+                    .ok();
+                Some(receiver)
+            } else {
+                let (sender, receiver) = oneshot::channel();
+                state.push_waiter(kind, client, sender);
+                Some(receiver)
+            }
+        };
+
+        async move { receiver?.await.ok() }
+    }
+}
@@ -0,0 +1,168 @@
+//! Fast path for `eth_call`s to view methods of recognized token contracts. Such calls are served directly
+//! from storage without invoking the VM.
+
+use std::collections::HashSet;
+
+use once_cell::sync::Lazy;
+use zksync_config::configs::api::EthCallFastPathMethod;
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    ethabi::{short_signature, ParamType},
+    get_code_key,
+    l2::L2Tx,
+    utils::{storage_key_for_standard_token_allowance, storage_key_for_standard_token_balance},
+    AccountTreeId, Address, H256, L2_ETH_TOKEN_ADDRESS,
+};
+
+use super::BlockArgs;
+
+static BALANCE_OF_SELECTOR: Lazy<[u8; 4]> =
+    Lazy::new(|| short_signature("balanceOf", &[ParamType::Address]));
+/// The L2 ETH token exposes `balanceOf(uint256)` instead of the standard ERC-20 method.
+static ETH_BALANCE_OF_SELECTOR: Lazy<[u8; 4]> =
+    Lazy::new(|| short_signature("balanceOf", &[ParamType::Uint(256)]));
+static ALLOWANCE_SELECTOR: Lazy<[u8; 4]> =
+    Lazy::new(|| short_signature("allowance", &[ParamType::Address, ParamType::Address]));
+
+/// Call eligible for the fast path.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum FastPathCall {
+    BalanceOf { owner: Address },
+    Allowance { owner: Address, spender: Address },
+}
+
+impl FastPathCall {
+    /// Parses an address from an ABI-encoded word. Returns `None` if the word has non-zero upper bytes.
+    fn parse_address(word: &[u8]) -> Option<Address> {
+        let (padding, address) = word.split_at(12);
+        padding
+            .iter()
+            .all(|&byte| byte == 0)
+            .then(|| Address::from_slice(address))
+    }
+
+    /// Parses the call to a contract. Returns `None` if the call should be executed in the VM.
+    fn parse(contract_address: Address, calldata: &[u8]) -> Option<Self> {
+        if calldata.len() < 4 || (calldata.len() - 4) % 32 != 0 {
+            return None;
+        }
+        let (selector, args) = calldata.split_at(4);
+        let mut words = args.chunks(32);
+
+        if contract_address == L2_ETH_TOKEN_ADDRESS {
+            return if selector == *ETH_BALANCE_OF_SELECTOR && args.len() == 32 {
+                let owner = Self::parse_address(words.next()?)?;
+                Some(Self::BalanceOf { owner })
+            } else {
+                None
+            };
+        }
+
+        if selector == *BALANCE_OF_SELECTOR && args.len() == 32 {
+            let owner = Self::parse_address(words.next()?)?;
+            Some(Self::BalanceOf { owner })
+        } else if selector == *ALLOWANCE_SELECTOR && args.len() == 64 {
+            let owner = Self::parse_address(words.next()?)?;
+            let spender = Self::parse_address(words.next()?)?;
+            Some(Self::Allowance { owner, spender })
+        } else {
+            None
+        }
+    }
+
+    fn method(&self) -> EthCallFastPathMethod {
+        match self {
+            Self::BalanceOf { .. } => EthCallFastPathMethod::BalanceOf,
+            Self::Allowance { .. } => EthCallFastPathMethod::Allowance,
+        }
+    }
+}
+
+/// Serves `eth_call`s to view methods of recognized token contracts directly from storage. Recognized contracts
+/// are the L2 ETH token and tokens bridged via the default bridge, which have a known storage layout.
+/// A bridged token is only recognized if its bytecode hash at the requested block is one of the configured
+/// hashes; otherwise, the standard layout cannot be assumed, and the call is executed in the VM.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct EthCallFastPath {
+    methods: HashSet<EthCallFastPathMethod>,
+    token_code_hashes: HashSet<H256>,
+}
+
+impl EthCallFastPath {
+    pub fn new(methods: &[EthCallFastPathMethod], token_code_hashes: &[H256]) -> Self {
+        Self {
+            methods: methods.iter().copied().collect(),
+            token_code_hashes: token_code_hashes.iter().copied().collect(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.methods.is_empty()
+    }
+
+    /// Returns the ABI-encoded result of the call, or `None` if the call is not eligible for the fast path
+    /// and should be executed in the VM.
+    pub async fn try_call(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+        block_args: &BlockArgs,
+        tx: &L2Tx,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        if !self.is_enabled() {
+            return Ok(None);
+        }
+        // View methods are not payable and don't deploy contracts, so such calls are left to the VM.
+        let execute = &tx.execute;
+        let has_factory_deps = execute
+            .factory_deps
+            .as_ref()
+            .map_or(false, |deps| !deps.is_empty());
+        if !execute.value.is_zero() || has_factory_deps {
+            return Ok(None);
+        }
+        let contract_address = execute.contract_address;
+        let Some(call) = FastPathCall::parse(contract_address, &execute.calldata) else {
+            return Ok(None);
+        };
+        if !self.methods.contains(&call.method()) {
+            return Ok(None);
+        }
+
+        let block_number = block_args.resolved_block_number();
+        if contract_address != L2_ETH_TOKEN_ADDRESS {
+            if self.token_code_hashes.is_empty() {
+                return Ok(None);
+            }
+            let is_bridged_token = connection
+                .tokens_web3_dal()
+                .is_l2_token_registered(contract_address)
+                .await?;
+            if !is_bridged_token {
+                return Ok(None);
+            }
+            // The token may be not deployed yet at the requested block, or may have a non-standard bytecode.
+            let code_hash = connection
+                .storage_web3_dal()
+                .get_historical_value_unchecked(&get_code_key(&contract_address), block_number)
+                .await?;
+            if !self.token_code_hashes.contains(&code_hash) {
+                return Ok(None);
+            }
+        }
+
+        let token = AccountTreeId::new(contract_address);
+        let key = match call {
+            FastPathCall::BalanceOf { owner } => {
+                storage_key_for_standard_token_balance(token, &owner)
+            }
+            FastPathCall::Allowance { owner, spender } => {
+                storage_key_for_standard_token_allowance(token, &owner, &spender)
+            }
+        };
+        let value = connection
+            .storage_web3_dal()
+            .get_historical_value_unchecked(&key, block_number)
+            .await?;
+        Ok(Some(value.as_bytes().to_vec()))
+    }
+}
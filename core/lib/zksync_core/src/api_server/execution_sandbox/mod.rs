@@ -1,29 +1,38 @@
-use std::{sync::Arc, time::Duration};
+use std::{future::Future, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
-use tokio::runtime::Handle;
+use tokio::{runtime::Handle, sync::watch};
 use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_state::{PostgresStorage, PostgresStorageCaches, ReadStorage, StorageView};
+use zksync_state::{
+    PostgresStorage, PostgresStorageCaches, ReadStorage, RocksdbReplica, StorageView,
+};
 use zksync_system_constants::PUBLISH_BYTECODE_OVERHEAD;
 use zksync_types::{
     api, fee_model::BatchFeeInput, AccountTreeId, L1BatchNumber, L2ChainId, MiniblockNumber,
 };
 use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
 
-use self::vm_metrics::SandboxStage;
+pub use self::fair_queue::VmInvocationKind;
 pub(super) use self::{
     error::SandboxExecutionError,
     execute::{TransactionExecutor, TxExecutionArgs},
+    fair_queue::ApiClientId,
+    fast_path::EthCallFastPath,
+    storage::validate_state_override,
     tracers::ApiTracer,
-    validate::ValidationError,
-    vm_metrics::{SubmitTxStage, SANDBOX_METRICS},
+    validate::{PaymasterValidationOutcome, ValidationAllowList, ValidationError},
+    vm_metrics::{EthCallFastPathResult, GasEstimationCacheResult, SubmitTxStage, SANDBOX_METRICS},
 };
+use self::{fair_queue::FairQueue, vm_metrics::SandboxStage};
 use super::tx_sender::MultiVMBaseSystemContracts;
 
 // Note: keep the modules private, and instead re-export functions that make public interface.
 mod apply;
 mod error;
 mod execute;
+mod fair_queue;
+mod fast_path;
+mod storage;
 #[cfg(test)]
 pub(super) mod testonly;
 #[cfg(test)]
@@ -40,7 +49,7 @@ mod vm_metrics;
 pub struct VmPermit {
     /// A handle to the runtime that is used to query the VM storage.
     rt_handle: Handle,
-    _permit: Arc<tokio::sync::OwnedSemaphorePermit>,
+    _permit: Arc<fair_queue::FairPermit>,
 }
 
 impl VmPermit {
@@ -53,8 +62,7 @@ impl VmPermit {
 /// so that it doesn't issue new permits, and to wait for all permits to drop.
 #[derive(Debug, Clone)]
 pub struct VmConcurrencyBarrier {
-    limiter: Arc<tokio::sync::Semaphore>,
-    max_concurrency: usize,
+    limiter: FairQueue,
 }
 
 impl VmConcurrencyBarrier {
@@ -73,14 +81,14 @@ impl VmConcurrencyBarrier {
             "Cannot wait on non-closed VM concurrency limiter"
         );
 
+        let max_concurrency = self.limiter.max_concurrency();
         loop {
             let current_permits = self.limiter.available_permits();
             tracing::debug!(
-                "Waiting until all VM permits are dropped; currently remaining: {} / {}",
-                self.max_concurrency - current_permits,
-                self.max_concurrency
+                "Waiting until all VM permits are dropped; currently remaining: {} / {max_concurrency}",
+                max_concurrency - current_permits
             );
-            if current_permits == self.max_concurrency {
+            if current_permits == max_concurrency {
                 return;
             }
             tokio::time::sleep(POLL_INTERVAL).await;
@@ -97,10 +105,16 @@ impl VmConcurrencyBarrier {
 /// Note that the actual limit on the number of VMs is a minimum of the limit in this structure,
 /// *and* the size of the blocking tokio threadpool. So, even if the limit is set to 1024, but
 /// tokio is configured to have no more than 512 blocking threads, the actual limit will be 512.
+///
+/// Permits are issued using weighted fair queueing: invocations of different [kinds](VmInvocationKind)
+/// are queued in separate pools sharing the capacity according to their weights, and within a pool,
+/// permits are distributed among API clients in a round-robin fashion. Clients are identified
+/// by the API server middleware (by the API key or the IP address forwarded by a proxy); requests
+/// without a known client identity (e.g., all WebSocket requests) share a single queue.
 #[derive(Debug)]
 pub struct VmConcurrencyLimiter {
-    /// Semaphore that limits the number of concurrent VM executions.
-    limiter: Arc<tokio::sync::Semaphore>,
+    /// Queue that limits the number of concurrent VM executions.
+    limiter: FairQueue,
     rt_handle: Handle,
 }
 
@@ -110,34 +124,33 @@ impl VmConcurrencyLimiter {
         tracing::info!(
             "Initializing the VM concurrency limiter with max concurrency {max_concurrency}"
         );
-        let limiter = Arc::new(tokio::sync::Semaphore::new(max_concurrency));
+        let limiter = FairQueue::new(max_concurrency);
 
         let this = Self {
-            limiter: Arc::clone(&limiter),
+            limiter: limiter.clone(),
             rt_handle: Handle::current(),
         };
-        let barrier = VmConcurrencyBarrier {
-            limiter,
-            max_concurrency,
-        };
+        let barrier = VmConcurrencyBarrier { limiter };
         (this, barrier)
     }
 
-    /// Waits until there is a free slot in the concurrency limiter.
+    /// Waits until there is a free slot in the concurrency limiter for an invocation of the specified kind.
     /// Returns a permit that should be dropped when the VM execution is finished.
-    pub async fn acquire(&self) -> Option<VmPermit> {
+    pub async fn acquire(&self, kind: VmInvocationKind) -> Option<VmPermit> {
         let available_permits = self.limiter.available_permits();
         SANDBOX_METRICS
             .sandbox_execution_permits
             .observe(available_permits);
 
         let latency = SANDBOX_METRICS.sandbox[&SandboxStage::VmConcurrencyLimiterAcquire].start();
-        let permit = Arc::clone(&self.limiter).acquire_owned().await.ok()?;
+        let kind_latency = SANDBOX_METRICS.sandbox_permit_wait[&kind].start();
+        let permit = self.limiter.acquire(kind).await?;
         let elapsed = latency.observe();
+        kind_latency.observe();
         // We don't want to emit too many logs.
         if elapsed > Duration::from_millis(10) {
             tracing::debug!(
-                "Permit is obtained. Available permits: {available_permits}. Took {elapsed:?}"
+                "Permit for {kind:?} is obtained. Available permits: {available_permits}. Took {elapsed:?}"
             );
         }
 
@@ -213,15 +226,19 @@ pub(super) async fn get_pubdata_for_factory_deps(
 pub(crate) struct TxSharedArgs {
     pub operator_account: AccountTreeId,
     pub fee_input: BatchFeeInput,
-    pub base_system_contracts: MultiVMBaseSystemContracts,
+    pub base_system_contracts: Arc<MultiVMBaseSystemContracts>,
     pub caches: PostgresStorageCaches,
+    pub rocksdb_replica: Option<RocksdbReplica>,
     pub validation_computational_gas_limit: u32,
     pub chain_id: L2ChainId,
 }
 
 impl TxSharedArgs {
     #[cfg(test)]
-    pub fn mock(base_system_contracts: MultiVMBaseSystemContracts, pool: ConnectionPool) -> Self {
+    pub fn mock(
+        base_system_contracts: Arc<MultiVMBaseSystemContracts>,
+        pool: ConnectionPool,
+    ) -> Self {
         let mut caches = PostgresStorageCaches::new(1, 1);
         tokio::task::spawn_blocking(caches.configure_storage_values_cache(
             1,
@@ -234,6 +251,7 @@ impl TxSharedArgs {
             fee_input: BatchFeeInput::l1_pegged(55, 555),
             base_system_contracts,
             caches,
+            rocksdb_replica: None,
             validation_computational_gas_limit: u32::MAX,
             chain_id: L2ChainId::default(),
         }
@@ -241,7 +259,7 @@ impl TxSharedArgs {
 }
 
 /// Information about first L1 batch / miniblock in the node storage.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) struct BlockStartInfo {
     /// Number of the first locally available miniblock.
     pub first_miniblock: MiniblockNumber,
@@ -257,14 +275,68 @@ impl BlockStartInfo {
             .await
             .context("failed getting snapshot recovery status")?;
         let snapshot_recovery = snapshot_recovery.as_ref();
+        let pruning_info = storage
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("failed getting pruning info")?;
+
+        let mut first_miniblock =
+            snapshot_recovery.map_or(MiniblockNumber(0), |recovery| recovery.miniblock_number + 1);
+        let mut first_l1_batch =
+            snapshot_recovery.map_or(L1BatchNumber(0), |recovery| recovery.l1_batch_number + 1);
+        // Soft-pruned data is not served via the API, even if it's still present in Postgres.
+        if let Some(pruned_miniblock) = pruning_info.last_soft_pruned_miniblock {
+            first_miniblock = first_miniblock.max(pruned_miniblock + 1);
+        }
+        if let Some(pruned_l1_batch) = pruning_info.last_soft_pruned_l1_batch {
+            first_l1_batch = first_l1_batch.max(pruned_l1_batch + 1);
+        }
         Ok(Self {
-            first_miniblock: snapshot_recovery
-                .map_or(MiniblockNumber(0), |recovery| recovery.miniblock_number + 1),
-            first_l1_batch: snapshot_recovery
-                .map_or(L1BatchNumber(0), |recovery| recovery.l1_batch_number + 1),
+            first_miniblock,
+            first_l1_batch,
         })
     }
 
+    /// Loads the current start info and returns a receiver for it, together with a task that will update it
+    /// on a schedule. Updates are required since the first retained miniblock / L1 batch can change
+    /// if the node prunes its data (see [`DbPruner`](crate::db_pruner::DbPruner)).
+    pub async fn new_updatable(
+        connection_pool: ConnectionPool,
+        update_interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<(
+        watch::Receiver<Self>,
+        impl Future<Output = anyhow::Result<()>>,
+    )> {
+        let mut storage = connection_pool.access_storage_tagged("api").await?;
+        let start_info = Self::new(&mut storage).await?;
+        drop(storage);
+        let (start_info_sender, start_info_receiver) = watch::channel(start_info);
+
+        let update_task = async move {
+            loop {
+                if tokio::time::timeout(update_interval, stop_receiver.changed())
+                    .await
+                    .is_ok()
+                {
+                    tracing::debug!("Stopping block start info updates");
+                    return Ok(());
+                }
+
+                let mut storage = connection_pool.access_storage_tagged("api").await?;
+                let start_info = Self::new(&mut storage).await?;
+                drop(storage);
+                start_info_sender.send_if_modified(|prev_info| {
+                    let is_modified = *prev_info != start_info;
+                    *prev_info = start_info;
+                    is_modified
+                });
+            }
+        };
+        Ok((start_info_receiver, update_task))
+    }
+
     /// Checks whether a block with the specified ID is pruned and returns an error if it is.
     /// The `Err` variant wraps the first non-pruned miniblock.
     pub fn ensure_not_pruned_block(&self, block: api::BlockId) -> Result<(), MiniblockNumber> {
@@ -0,0 +1,247 @@
+//! VM storage functionality specifically used in the VM sandbox.
+
+use std::collections::{HashMap, HashSet};
+
+use zksync_state::ReadStorage;
+use zksync_types::{
+    api::{OverrideAccount, StateOverride},
+    get_code_key, get_known_code_key, get_nonce_key,
+    utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
+    AccountTreeId, StorageKey, StorageValue, H256, U256,
+};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode},
+    h256_to_u256, u256_to_h256,
+};
+
+/// Checks that the provided state override is well-formed, returning a human-readable error otherwise.
+pub(crate) fn validate_state_override(state_override: &StateOverride) -> Result<(), String> {
+    for (address, account) in &state_override.0 {
+        let OverrideAccount {
+            nonce,
+            code,
+            state,
+            state_diff,
+            ..
+        } = account;
+        if state.is_some() && state_diff.is_some() {
+            return Err(format!(
+                "both `state` and `stateDiff` are specified for account {address:?}"
+            ));
+        }
+        if let Some(nonce) = nonce {
+            if *nonce > U256::from(u32::MAX) {
+                return Err(format!(
+                    "nonce {nonce} for account {address:?} is too large"
+                ));
+            }
+        }
+        if let Some(code) = code {
+            validate_bytecode(&code.0)
+                .map_err(|err| format!("invalid code for account {address:?}: {err}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// [`ReadStorage`] wrapper applying [`StateOverride`]s on top of the wrapped storage.
+///
+/// Overrides are applied lazily on reads, so that constructing the wrapper doesn't access the wrapped storage.
+#[derive(Debug)]
+pub(super) struct StorageWithOverrides<S> {
+    storage_handle: S,
+    overridden_slots: HashMap<StorageKey, H256>,
+    /// Overridden transaction nonces keyed by the nonce storage key. The deployment nonce is read
+    /// from the wrapped storage.
+    overridden_nonces: HashMap<StorageKey, U256>,
+    overridden_factory_deps: HashMap<H256, Vec<u8>>,
+    /// Accounts with fully replaced storage; all non-overridden slots of these accounts are zero.
+    overridden_accounts: HashSet<AccountTreeId>,
+}
+
+impl<S: ReadStorage> StorageWithOverrides<S> {
+    pub fn new(storage_handle: S) -> Self {
+        Self {
+            storage_handle,
+            overridden_slots: HashMap::new(),
+            overridden_nonces: HashMap::new(),
+            overridden_factory_deps: HashMap::new(),
+            overridden_accounts: HashSet::new(),
+        }
+    }
+
+    /// Applies the provided state override. The override is assumed to be validated
+    /// with [`validate_state_override()`].
+    pub fn with_state_override(mut self, state_override: &StateOverride) -> Self {
+        for (address, account) in &state_override.0 {
+            if let Some(balance) = account.balance {
+                let balance_key = storage_key_for_eth_balance(address);
+                self.overridden_slots
+                    .insert(balance_key, u256_to_h256(balance));
+            }
+            if let Some(nonce) = account.nonce {
+                self.overridden_nonces.insert(get_nonce_key(address), nonce);
+            }
+            if let Some(code) = &account.code {
+                let code_hash = hash_bytecode(&code.0);
+                self.overridden_slots
+                    .insert(get_code_key(address), code_hash);
+                self.overridden_slots
+                    .insert(get_known_code_key(&code_hash), H256::from_low_u64_be(1));
+                self.overridden_factory_deps
+                    .insert(code_hash, code.0.clone());
+            }
+
+            let account_id = AccountTreeId::new(*address);
+            let slots = match (&account.state, &account.state_diff) {
+                (Some(state), _) => {
+                    self.overridden_accounts.insert(account_id);
+                    state
+                }
+                (None, Some(state_diff)) => state_diff,
+                (None, None) => continue,
+            };
+            for (&slot, &value) in slots {
+                self.overridden_slots
+                    .insert(StorageKey::new(account_id, slot), value);
+            }
+        }
+        self
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for StorageWithOverrides<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        if let Some(value) = self.overridden_slots.get(key) {
+            return *value;
+        }
+        if let Some(&nonce) = self.overridden_nonces.get(key) {
+            let full_nonce = self.storage_handle.read_value(key);
+            let (_, deployment_nonce) = decompose_full_nonce(h256_to_u256(full_nonce));
+            return u256_to_h256(nonces_to_full_nonce(nonce, deployment_nonce));
+        }
+        if self.overridden_accounts.contains(key.account()) {
+            return H256::zero();
+        }
+        self.storage_handle.read_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.storage_handle.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        self.overridden_factory_deps
+            .get(&hash)
+            .cloned()
+            .or_else(|| self.storage_handle.load_factory_dep(hash))
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.storage_handle.get_enumeration_index(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_state::InMemoryStorage;
+    use zksync_types::{Address, Bytes};
+
+    use super::*;
+
+    #[test]
+    fn override_basics() {
+        let address = Address::repeat_byte(1);
+        let untouched_address = Address::repeat_byte(2);
+        let mut storage = InMemoryStorage::default();
+        let slot_key = StorageKey::new(AccountTreeId::new(address), H256::repeat_byte(1));
+        let other_slot_key = StorageKey::new(AccountTreeId::new(address), H256::repeat_byte(2));
+        storage.set_value(slot_key, H256::repeat_byte(0xff));
+        storage.set_value(other_slot_key, H256::repeat_byte(0xfe));
+        let nonce_key = get_nonce_key(&address);
+        let full_nonce = nonces_to_full_nonce(5.into(), 3.into());
+        storage.set_value(nonce_key, u256_to_h256(full_nonce));
+        let untouched_key =
+            StorageKey::new(AccountTreeId::new(untouched_address), H256::repeat_byte(1));
+        storage.set_value(untouched_key, H256::repeat_byte(0xaa));
+
+        let code = vec![0_u8; 32];
+        let account = OverrideAccount {
+            balance: Some(100.into()),
+            nonce: Some(10.into()),
+            code: Some(Bytes(code.clone())),
+            state_diff: Some(HashMap::from([(
+                H256::repeat_byte(1),
+                H256::repeat_byte(3),
+            )])),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride(HashMap::from([(address, account)]));
+        validate_state_override(&state_override).unwrap();
+        let mut storage = StorageWithOverrides::new(storage).with_state_override(&state_override);
+
+        let balance = storage.read_value(&storage_key_for_eth_balance(&address));
+        assert_eq!(h256_to_u256(balance), 100.into());
+        let full_nonce = h256_to_u256(storage.read_value(&nonce_key));
+        assert_eq!(decompose_full_nonce(full_nonce), (10.into(), 3.into()));
+        let code_hash = storage.read_value(&get_code_key(&address));
+        assert_eq!(code_hash, hash_bytecode(&code));
+        assert_eq!(storage.load_factory_dep(code_hash), Some(code));
+        assert!(storage.is_bytecode_known(&code_hash));
+        assert_eq!(storage.read_value(&slot_key), H256::repeat_byte(3));
+        assert_eq!(storage.read_value(&other_slot_key), H256::repeat_byte(0xfe));
+        assert_eq!(storage.read_value(&untouched_key), H256::repeat_byte(0xaa));
+    }
+
+    #[test]
+    fn overriding_full_account_state() {
+        let address = Address::repeat_byte(1);
+        let mut storage = InMemoryStorage::default();
+        let slot_key = StorageKey::new(AccountTreeId::new(address), H256::repeat_byte(1));
+        let other_slot_key = StorageKey::new(AccountTreeId::new(address), H256::repeat_byte(2));
+        storage.set_value(slot_key, H256::repeat_byte(0xff));
+        storage.set_value(other_slot_key, H256::repeat_byte(0xfe));
+
+        let account = OverrideAccount {
+            state: Some(HashMap::from([(
+                H256::repeat_byte(1),
+                H256::repeat_byte(3),
+            )])),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride(HashMap::from([(address, account)]));
+        let mut storage = StorageWithOverrides::new(storage).with_state_override(&state_override);
+
+        assert_eq!(storage.read_value(&slot_key), H256::repeat_byte(3));
+        assert_eq!(storage.read_value(&other_slot_key), H256::zero());
+    }
+
+    #[test]
+    fn validating_state_override() {
+        let address = Address::repeat_byte(1);
+        let account = OverrideAccount {
+            state: Some(HashMap::new()),
+            state_diff: Some(HashMap::new()),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride(HashMap::from([(address, account)]));
+        let err = validate_state_override(&state_override).unwrap_err();
+        assert!(err.contains("stateDiff"), "{err}");
+
+        let account = OverrideAccount {
+            code: Some(Bytes(vec![0; 31])),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride(HashMap::from([(address, account)]));
+        let err = validate_state_override(&state_override).unwrap_err();
+        assert!(err.contains("invalid code"), "{err}");
+
+        let account = OverrideAccount {
+            nonce: Some(U256::MAX),
+            ..OverrideAccount::default()
+        };
+        let state_override = StateOverride(HashMap::from([(address, account)]));
+        let err = validate_state_override(&state_override).unwrap_err();
+        assert!(err.contains("nonce"), "{err}");
+    }
+}
@@ -1,13 +1,16 @@
 use std::fmt;
 
-use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
+use multivm::{
+    interface::{ExecutionResult, Halt, VmExecutionResultAndLogs},
+    tracers::validator,
+};
 use zksync_types::{
     fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Transaction,
 };
 
 use super::{
     execute::{TransactionExecutionOutput, TransactionExecutor},
-    validate::ValidationError,
+    validate::{PaymasterValidationOutcome, ValidationError},
     BlockArgs,
 };
 
@@ -67,6 +70,25 @@ impl MockTransactionExecutor {
         }
     }
 
+    pub fn validate_paymaster(
+        &self,
+        tx: L2Tx,
+        block_args: &BlockArgs,
+    ) -> PaymasterValidationOutcome {
+        let result = match (self.tx_responses)(&tx.into(), block_args) {
+            ExecutionResult::Success { .. } => Ok(()),
+            ExecutionResult::Revert { output } => Err(validator::ValidationError::FailedTx(
+                Halt::PaymasterValidationFailed(output),
+            )),
+            ExecutionResult::Halt { reason } => Err(validator::ValidationError::FailedTx(reason)),
+        };
+        PaymasterValidationOutcome {
+            result,
+            gas_used: 0,
+            computational_gas_used: 0,
+        }
+    }
+
     pub fn execute_tx(
         &self,
         tx: &Transaction,
@@ -86,6 +108,23 @@ impl MockTransactionExecutor {
         Ok(output)
     }
 
+    pub fn execute_bundle(
+        &self,
+        txs: &[Transaction],
+        block_args: &BlockArgs,
+    ) -> anyhow::Result<Vec<VmExecutionResultAndLogs>> {
+        let mut results = vec![];
+        for tx in txs {
+            let output = self.execute_tx(tx, block_args)?;
+            let is_halted = matches!(output.vm.result, ExecutionResult::Halt { .. });
+            results.push(output.vm);
+            if is_halted {
+                break;
+            }
+        }
+        Ok(results)
+    }
+
     fn get_execution_result(&self, tx: &Transaction, block_args: &BlockArgs) -> ExecutionResult {
         if let ExecuteTransactionCommon::L2(data) = &tx.common_data {
             if data.input.is_none() {
@@ -1,6 +1,22 @@
 //! Tests for the VM execution sandbox.
 
+use std::pin::Pin;
+
 use assert_matches::assert_matches;
+use futures::FutureExt;
+use zksync_config::configs::api::EthCallFastPathMethod;
+use zksync_types::{
+    ethabi::{encode, short_signature, ParamType, Token},
+    get_code_key,
+    l2::L2Tx,
+    tokens::{TokenInfo, TokenMetadata},
+    utils::{
+        storage_key_for_eth_balance, storage_key_for_standard_token_allowance,
+        storage_key_for_standard_token_balance,
+    },
+    Address, Execute, StorageLog, H256, L2_ETH_TOKEN_ADDRESS, U256,
+};
+use zksync_utils::{address_to_u256, u256_to_h256};
 
 use super::*;
 use crate::{
@@ -156,6 +172,42 @@ async fn creating_block_args_after_snapshot_recovery() {
     }
 }
 
+#[tokio::test]
+async fn block_start_info_after_soft_pruning() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    for number in 1..=3 {
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(number))
+            .await
+            .unwrap();
+    }
+
+    storage
+        .pruning_dal()
+        .soft_prune_batches_range(L1BatchNumber(0), MiniblockNumber(1))
+        .await
+        .unwrap();
+    let start_info = BlockStartInfo::new(&mut storage).await.unwrap();
+    assert_eq!(start_info.first_miniblock, MiniblockNumber(2));
+    assert_eq!(start_info.first_l1_batch, L1BatchNumber(1));
+
+    let pruned_block = api::BlockId::Number(1.into());
+    let err = BlockArgs::new(&mut storage, pruned_block, start_info)
+        .await
+        .unwrap_err();
+    assert_matches!(err, BlockArgsError::Pruned(MiniblockNumber(1)));
+    let latest_block = api::BlockId::Number(api::BlockNumber::Latest);
+    let latest_block_args = BlockArgs::new(&mut storage, latest_block, start_info)
+        .await
+        .unwrap();
+    assert_eq!(latest_block_args.resolved_block_number, MiniblockNumber(3));
+}
+
 #[tokio::test]
 async fn instantiating_vm() {
     let pool = ConnectionPool::test_pool().await;
@@ -175,7 +227,10 @@ async fn instantiating_vm() {
 
 async fn test_instantiating_vm(pool: ConnectionPool, block_args: BlockArgs) {
     let (vm_concurrency_limiter, _) = VmConcurrencyLimiter::new(1);
-    let vm_permit = vm_concurrency_limiter.acquire().await.unwrap();
+    let vm_permit = vm_concurrency_limiter
+        .acquire(VmInvocationKind::EstimateGas)
+        .await
+        .unwrap();
     let transaction = create_l2_transaction(10, 100).into();
 
     tokio::task::spawn_blocking(move || {
@@ -196,3 +251,248 @@ async fn test_instantiating_vm(pool: ConnectionPool, block_args: BlockArgs) {
     .expect("VM instantiation panicked")
     .expect("VM instantiation errored");
 }
+
+type PendingAcquire = Pin<Box<dyn Future<Output = Option<fair_queue::FairPermit>> + Send>>;
+
+/// Returns the label of the single pending acquisition that has obtained a permit.
+fn take_ready_permit(
+    pending: &mut Vec<(&'static str, PendingAcquire)>,
+) -> (&'static str, fair_queue::FairPermit) {
+    let mut ready = vec![];
+    for (i, (_, acquire)) in pending.iter_mut().enumerate() {
+        if let Some(permit) = acquire.now_or_never() {
+            ready.push((i, permit.expect("queue is closed")));
+        }
+    }
+    assert_eq!(ready.len(), 1, "{} permits are issued", ready.len());
+    let (idx, permit) = ready.pop().unwrap();
+    (pending.remove(idx).0, permit)
+}
+
+fn drain_queue(mut pending: Vec<(&'static str, PendingAcquire)>) -> Vec<&'static str> {
+    let mut order = vec![];
+    while !pending.is_empty() {
+        let (label, permit) = take_ready_permit(&mut pending);
+        order.push(label);
+        drop(permit);
+    }
+    order
+}
+
+#[tokio::test]
+async fn fair_queue_round_robins_clients() {
+    let queue = fair_queue::FairQueue::new(1);
+    let heavy_client = ApiClientId::ApiKey("heavy".to_owned());
+    let light_client = ApiClientId::Ip([127, 0, 0, 1].into());
+    let held_permit = queue
+        .acquire_for_client(VmInvocationKind::Call, heavy_client.clone())
+        .await
+        .unwrap();
+
+    let mut pending: Vec<(_, PendingAcquire)> = vec![];
+    for label in ["heavy0", "heavy1", "heavy2"] {
+        let acquire = queue.acquire_for_client(VmInvocationKind::Call, heavy_client.clone());
+        pending.push((label, Box::pin(acquire)));
+    }
+    let acquire = queue.acquire_for_client(VmInvocationKind::Call, light_client);
+    pending.push(("light", Box::pin(acquire)));
+    assert!(pending
+        .iter_mut()
+        .all(|(_, acquire)| acquire.now_or_never().is_none()));
+
+    drop(held_permit);
+    assert_eq!(
+        drain_queue(pending),
+        ["heavy0", "light", "heavy1", "heavy2"]
+    );
+    assert_eq!(queue.available_permits(), 1);
+}
+
+#[tokio::test]
+async fn fair_queue_shares_capacity_among_pools() {
+    let queue = fair_queue::FairQueue::new(1);
+    let client = ApiClientId::default();
+    let held_permit = queue
+        .acquire_for_client(VmInvocationKind::Call, client.clone())
+        .await
+        .unwrap();
+
+    let mut pending: Vec<(_, PendingAcquire)> = vec![];
+    for _ in 0..4 {
+        let acquire = queue.acquire_for_client(VmInvocationKind::Trace, client.clone());
+        pending.push(("trace", Box::pin(acquire)));
+    }
+    for _ in 0..4 {
+        let acquire = queue.acquire_for_client(VmInvocationKind::Call, client.clone());
+        pending.push(("call", Box::pin(acquire)));
+    }
+
+    drop(held_permit);
+    // Calls have twice the weight of traces.
+    assert_eq!(
+        drain_queue(pending),
+        ["call", "trace", "call", "call", "trace", "call", "trace", "trace"]
+    );
+}
+
+#[tokio::test]
+async fn closing_fair_queue() {
+    let queue = fair_queue::FairQueue::new(1);
+    let held_permit = queue
+        .acquire_for_client(VmInvocationKind::Call, ApiClientId::default())
+        .await
+        .unwrap();
+    let pending = queue.acquire_for_client(VmInvocationKind::EstimateGas, ApiClientId::default());
+    let dropped = queue.acquire_for_client(VmInvocationKind::EstimateGas, ApiClientId::default());
+    drop(dropped);
+
+    queue.close();
+    assert!(pending.await.is_none());
+    assert!(queue
+        .acquire_for_client(VmInvocationKind::Call, ApiClientId::default())
+        .await
+        .is_none());
+    assert_eq!(queue.available_permits(), 0);
+    drop(held_permit);
+    assert_eq!(queue.available_permits(), 1);
+}
+
+fn token_view_call(contract_address: Address, calldata: Vec<u8>) -> L2Tx {
+    let mut tx = create_l2_transaction(10, 100);
+    tx.execute = Execute {
+        contract_address,
+        calldata,
+        value: U256::zero(),
+        factory_deps: None,
+    };
+    tx
+}
+
+#[tokio::test]
+async fn serving_token_view_calls_via_fast_path() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .insert_miniblock(&create_miniblock(1))
+        .await
+        .unwrap();
+
+    let token_address = Address::repeat_byte(1);
+    let owner = Address::repeat_byte(2);
+    let spender = Address::repeat_byte(3);
+    storage
+        .tokens_dal()
+        .add_tokens(&[TokenInfo {
+            l1_address: Address::repeat_byte(0x11),
+            l2_address: token_address,
+            metadata: TokenMetadata {
+                name: "Test".to_owned(),
+                symbol: "TST".to_owned(),
+                decimals: 18,
+            },
+        }])
+        .await
+        .unwrap();
+    let token = AccountTreeId::new(token_address);
+    let token_code_hash = H256::repeat_byte(0xff);
+    let logs = vec![
+        StorageLog::new_write_log(get_code_key(&token_address), token_code_hash),
+        StorageLog::new_write_log(
+            storage_key_for_standard_token_balance(token, &owner),
+            u256_to_h256(100.into()),
+        ),
+        StorageLog::new_write_log(
+            storage_key_for_standard_token_allowance(token, &owner, &spender),
+            u256_to_h256(42.into()),
+        ),
+        StorageLog::new_write_log(
+            storage_key_for_eth_balance(&owner),
+            u256_to_h256(1_000.into()),
+        ),
+    ];
+    storage
+        .storage_logs_dal()
+        .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), logs)])
+        .await
+        .unwrap();
+
+    let balance_of_calldata = [
+        &short_signature("balanceOf", &[ParamType::Address]) as &[u8],
+        &encode(&[Token::Address(owner)]),
+    ]
+    .concat();
+    let allowance_calldata = [
+        &short_signature("allowance", &[ParamType::Address, ParamType::Address]) as &[u8],
+        &encode(&[Token::Address(owner), Token::Address(spender)]),
+    ]
+    .concat();
+    let eth_balance_of_calldata = [
+        &short_signature("balanceOf", &[ParamType::Uint(256)]) as &[u8],
+        &encode(&[Token::Uint(address_to_u256(&owner))]),
+    ]
+    .concat();
+
+    let methods = [
+        EthCallFastPathMethod::BalanceOf,
+        EthCallFastPathMethod::Allowance,
+    ];
+    let fast_path = EthCallFastPath::new(&methods, &[token_code_hash]);
+    let block_args = BlockArgs::pending(&mut storage).await.unwrap();
+    let tx = token_view_call(token_address, balance_of_calldata.clone());
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), Some(u256_to_h256(100.into()).0.to_vec()));
+    let tx = token_view_call(token_address, allowance_calldata.clone());
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), Some(u256_to_h256(42.into()).0.to_vec()));
+    let tx = token_view_call(L2_ETH_TOKEN_ADDRESS, eth_balance_of_calldata.clone());
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), Some(u256_to_h256(1_000.into()).0.to_vec()));
+
+    // The standard ERC-20 selector is not used by the L2 ETH token.
+    let tx = token_view_call(L2_ETH_TOKEN_ADDRESS, balance_of_calldata.clone());
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), None);
+    // Unregistered contracts are executed in the VM.
+    let tx = token_view_call(Address::repeat_byte(0xaa), balance_of_calldata.clone());
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), None);
+    // Calls with dirty address padding are executed in the VM.
+    let mut dirty_calldata = balance_of_calldata.clone();
+    dirty_calldata[4] = 1;
+    let tx = token_view_call(token_address, dirty_calldata);
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), None);
+
+    // The token is not deployed at the genesis block.
+    let start_info = BlockStartInfo::new(&mut storage).await.unwrap();
+    let genesis_block = api::BlockId::Number(api::BlockNumber::Number(0.into()));
+    let genesis_block_args = BlockArgs::new(&mut storage, genesis_block, start_info)
+        .await
+        .unwrap();
+    let tx = token_view_call(token_address, balance_of_calldata.clone());
+    let output = fast_path
+        .try_call(&mut storage, &genesis_block_args, &tx)
+        .await;
+    assert_eq!(output.unwrap(), None);
+
+    // Tokens with an unknown bytecode are executed in the VM, but the L2 ETH token is still served.
+    let fast_path = EthCallFastPath::new(&methods, &[H256::repeat_byte(0xee)]);
+    let tx = token_view_call(token_address, balance_of_calldata.clone());
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), None);
+    let fast_path = EthCallFastPath::new(&methods, &[]);
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), None);
+    let tx = token_view_call(L2_ETH_TOKEN_ADDRESS, eth_balance_of_calldata);
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), Some(u256_to_h256(1_000.into()).0.to_vec()));
+
+    let fast_path = EthCallFastPath::new(&[EthCallFastPathMethod::BalanceOf], &[token_code_hash]);
+    let tx = token_view_call(token_address, allowance_calldata);
+    let output = fast_path.try_call(&mut storage, &block_args, &tx).await;
+    assert_eq!(output.unwrap(), None);
+}
@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    sync::{atomic::Ordering, Arc, RwLock},
+};
 
 use anyhow::Context as _;
 use multivm::{
@@ -10,8 +13,11 @@ use multivm::{
     vm_latest::HistoryDisabled,
     MultiVMTracer,
 };
-use zksync_dal::{ConnectionPool, StorageProcessor};
-use zksync_types::{l2::L2Tx, Transaction, TRUSTED_ADDRESS_SLOTS, TRUSTED_TOKEN_SLOTS};
+use zksync_dal::{
+    validation_allow_list_dal::ValidationAllowListVersion, ConnectionPool, StorageProcessor,
+};
+use zksync_types::{api::ValidationAllowListEntry, l2::L2Tx, Address, Transaction, U256};
+use zksync_utils::h256_to_u256;
 
 use super::{
     apply,
@@ -30,6 +36,71 @@ pub(crate) enum ValidationError {
     Internal(#[from] anyhow::Error),
 }
 
+/// Outcome of validating the paymaster of a transaction in the sandbox.
+#[derive(Debug)]
+pub(crate) struct PaymasterValidationOutcome {
+    pub result: Result<(), validator::ValidationError>,
+    /// Gas used by the transaction until the end of the validation step, including account validation.
+    pub gas_used: u32,
+    /// Computational gas used by the paymaster validation step.
+    pub computational_gas_used: u32,
+}
+
+#[derive(Debug)]
+struct CachedValidationAllowList {
+    version: ValidationAllowListVersion,
+    entries: Arc<Vec<ValidationAllowListEntry>>,
+}
+
+/// Allow-list of storage slots and addresses trusted when validating transactions. The allow-list is stored
+/// in Postgres, so that it can be changed without restarting the node. Loaded entries are cached together with
+/// the allow-list version; the version is checked on each access, so that all API servers sharing the database
+/// observe allow-list changes immediately.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ValidationAllowList(Arc<RwLock<Option<Arc<CachedValidationAllowList>>>>);
+
+impl ValidationAllowList {
+    /// Returns allow-list entries, reloading them from Postgres if the allow-list has changed since it was cached.
+    pub async fn entries(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Arc<Vec<ValidationAllowListEntry>>> {
+        let version = connection
+            .validation_allow_list_dal()
+            .get_version()
+            .await
+            .context("failed getting validation allow-list version")?;
+        let cached = self.0.read().expect("allow-list lock is poisoned").clone();
+        match cached {
+            Some(cached) if cached.version == version => Ok(cached.entries.clone()),
+            _ => self.reload(connection).await,
+        }
+    }
+
+    /// Reloads the allow-list from Postgres.
+    pub async fn reload(
+        &self,
+        connection: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<Arc<Vec<ValidationAllowListEntry>>> {
+        let (entries, version) = connection
+            .validation_allow_list_dal()
+            .get_entries_with_version()
+            .await
+            .context("failed loading validation allow-list")?;
+        tracing::info!(
+            "Loaded validation allow-list with {} entries",
+            entries.len()
+        );
+        let entries = Arc::new(entries);
+        *self.0.write().expect("allow-list lock is poisoned") =
+            Some(Arc::new(CachedValidationAllowList {
+                version,
+                entries: entries.clone(),
+            }));
+        Ok(entries)
+    }
+}
+
 impl TransactionExecutor {
     pub(crate) async fn validate_tx_in_sandbox(
         &self,
@@ -39,6 +110,7 @@ impl TransactionExecutor {
         shared_args: TxSharedArgs,
         block_args: BlockArgs,
         computational_gas_limit: u32,
+        allow_list: &ValidationAllowList,
     ) -> Result<(), ValidationError> {
         #[cfg(test)]
         if let Self::Mock(mock) = self {
@@ -50,8 +122,9 @@ impl TransactionExecutor {
             .access_storage_tagged("api")
             .await
             .context("failed acquiring DB connection")?;
+        let allow_list = allow_list.entries(&mut connection).await?;
         let validation_params =
-            get_validation_params(&mut connection, &tx, computational_gas_limit)
+            get_validation_params(&mut connection, &tx, computational_gas_limit, &allow_list)
                 .await
                 .context("failed getting validation params")?;
         drop(connection);
@@ -111,15 +184,97 @@ impl TransactionExecutor {
         stage_latency.observe();
         validation_result.map_err(ValidationError::Vm)
     }
+
+    /// Runs the validation step of a transaction checking validation rules only for the paymaster. Account validation
+    /// is executed in the gas estimation mode, so the transaction doesn't need to be signed; as a consequence,
+    /// magic values returned by the account and the paymaster are not enforced.
+    pub(crate) async fn validate_paymaster_in_sandbox(
+        &self,
+        connection_pool: ConnectionPool,
+        vm_permit: VmPermit,
+        tx: L2Tx,
+        shared_args: TxSharedArgs,
+        block_args: BlockArgs,
+        computational_gas_limit: u32,
+        allow_list: &ValidationAllowList,
+    ) -> anyhow::Result<PaymasterValidationOutcome> {
+        #[cfg(test)]
+        if let Self::Mock(mock) = self {
+            return Ok(mock.validate_paymaster(tx, &block_args));
+        }
+
+        let mut connection = connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("failed acquiring DB connection")?;
+        let allow_list = allow_list.entries(&mut connection).await?;
+        let validation_params =
+            get_validation_params(&mut connection, &tx, computational_gas_limit, &allow_list)
+                .await
+                .context("failed getting validation params")?;
+        drop(connection);
+        let validation_params = ValidationTracerParams {
+            paymaster_only: true,
+            ..validation_params
+        };
+
+        let execution_args = TxExecutionArgs::for_paymaster_validation(&tx);
+        let tx: Transaction = tx.into();
+
+        let outcome = tokio::task::spawn_blocking(move || {
+            let span = tracing::debug_span!("validate_paymaster_in_sandbox").entered();
+            let result = apply::apply_vm_in_sandbox(
+                vm_permit,
+                shared_args,
+                true,
+                &execution_args,
+                &connection_pool,
+                tx,
+                block_args,
+                |vm, tx| {
+                    vm.push_transaction(tx);
+                    let (tracer, validation_result) =
+                        ValidationTracer::<HistoryDisabled>::new(validation_params);
+                    let computational_gas = tracer.computational_gas.clone();
+
+                    let output = vm.inspect(
+                        vec![tracer.into_tracer_pointer()].into(),
+                        VmExecutionMode::OneTx,
+                    );
+                    let result = match (output.result, validation_result.get()) {
+                        (_, Some(err)) => {
+                            Err(validator::ValidationError::ViolatedRule(err.clone()))
+                        }
+                        (ExecutionResult::Halt { reason }, _) => {
+                            Err(validator::ValidationError::FailedTx(reason))
+                        }
+                        (_, None) => Ok(()),
+                    };
+                    PaymasterValidationOutcome {
+                        result,
+                        gas_used: output.statistics.gas_used,
+                        computational_gas_used: computational_gas.load(Ordering::Relaxed),
+                    }
+                },
+            );
+            span.exit();
+            result
+        })
+        .await
+        .context("paymaster validation panicked")??;
+        Ok(outcome)
+    }
 }
 
 /// Some slots can be marked as "trusted". That is needed for slots which can not be
 /// trusted to change between validation and execution in general case, but
-/// sometimes we can safely rely on them to not change often.
+/// sometimes we can safely rely on them to not change often. Trusted slots and addresses
+/// are defined by the [`ValidationAllowList`].
 async fn get_validation_params(
     connection: &mut StorageProcessor<'_>,
     tx: &L2Tx,
     computational_gas_limit: u32,
+    allow_list: &[ValidationAllowListEntry],
 ) -> anyhow::Result<ValidationTracerParams> {
     let method_latency = EXECUTION_METRICS.get_validation_params.start();
     let user_address = tx.common_data.initiator_address;
@@ -135,20 +290,33 @@ async fn get_validation_params(
     EXECUTION_METRICS.tokens_amount.set(all_tokens.len());
 
     let span = tracing::debug_span!("compute_trusted_slots_for_validation").entered();
-    let trusted_slots: HashSet<_> = all_tokens
-        .iter()
-        .flat_map(|&token| TRUSTED_TOKEN_SLOTS.iter().map(move |&slot| (token, slot)))
-        .collect();
-
-    // We currently don't support any specific trusted addresses.
-    let trusted_addresses = HashSet::new();
+    // Slots without an address are trusted for all tokens.
+    let expand_slot = |address: Option<Address>, slot| -> Vec<(Address, U256)> {
+        let slot = h256_to_u256(slot);
+        match address {
+            Some(address) => vec![(address, slot)],
+            None => all_tokens.iter().map(|&token| (token, slot)).collect(),
+        }
+    };
 
+    let mut trusted_slots = HashSet::new();
+    let mut trusted_addresses = HashSet::new();
     // The slots the value of which will be added as allowed address on the fly.
     // Required for working with transparent proxies.
-    let trusted_address_slots: HashSet<_> = all_tokens
-        .into_iter()
-        .flat_map(|token| TRUSTED_ADDRESS_SLOTS.iter().map(move |&slot| (token, slot)))
-        .collect();
+    let mut trusted_address_slots = HashSet::new();
+    for entry in allow_list {
+        match *entry {
+            ValidationAllowListEntry::Slot { address, slot } => {
+                trusted_slots.extend(expand_slot(address, slot));
+            }
+            ValidationAllowListEntry::Address { address } => {
+                trusted_addresses.insert(address);
+            }
+            ValidationAllowListEntry::AddressSlot { address, slot } => {
+                trusted_address_slots.extend(expand_slot(address, slot));
+            }
+        }
+    }
     EXECUTION_METRICS
         .trusted_address_slots_amount
         .set(trusted_address_slots.len());
@@ -162,5 +330,6 @@ async fn get_validation_params(
         trusted_addresses,
         trusted_address_slots,
         computational_gas_limit,
+        paymaster_only: false,
     })
 }
@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 use multivm::interface::{VmExecutionResultAndLogs, VmMemoryMetrics};
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
 use zksync_state::StorageViewMetrics;
 use zksync_types::{
     event::{extract_long_l2_to_l1_messages, extract_published_bytecodes},
@@ -10,6 +10,7 @@ use zksync_types::{
 };
 use zksync_utils::bytecode::bytecode_len_in_bytes;
 
+use super::VmInvocationKind;
 use crate::metrics::InteractionType;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -96,6 +97,22 @@ pub(in crate::api_server) enum SubmitTxStage {
     DbInsert,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(in crate::api_server) enum GasEstimationCacheResult {
+    Hit,
+    Miss,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(in crate::api_server) enum EthCallFastPathResult {
+    /// Call was served from storage.
+    Served,
+    /// Call was not eligible for the fast path and was executed in the VM.
+    Fallback,
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "api_web3")]
 pub(in crate::api_server) struct SandboxMetrics {
@@ -103,10 +120,19 @@ pub(in crate::api_server) struct SandboxMetrics {
     pub(super) sandbox: Family<SandboxStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::linear(0.0..=2_000.0, 200.0))]
     pub(super) sandbox_execution_permits: Histogram<usize>,
+    /// Time spent waiting for a VM permit, grouped by the invocation kind.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub(super) sandbox_permit_wait: Family<VmInvocationKind, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub submit_tx: Family<SubmitTxStage, Histogram<Duration>>,
+    /// Number of VM invocations performed when searching for the minimum gas limit in gas estimation
+    /// (not including the final estimation step).
     #[metrics(buckets = Buckets::linear(0.0..=30.0, 3.0))]
     pub estimate_gas_binary_search_iterations: Histogram<usize>,
+    /// Number of lookups in the gas estimation cache.
+    pub estimate_gas_cache: Family<GasEstimationCacheResult, Counter>,
+    /// Number of `eth_call`s processed by the fast path (only reported if the fast path is enabled).
+    pub eth_call_fast_path: Family<EthCallFastPathResult, Counter>,
 }
 
 #[vise::register]
@@ -242,5 +268,7 @@ pub(super) fn collect_tx_execution_metrics(
         total_updated_values_size: writes_metrics.total_updated_values_size,
         pubdata_published: result.statistics.pubdata_published,
         circuit_statistic: result.statistics.circuit_statistic,
+        storage_accesses: result.statistics.storage_accesses,
+        circuit_family_gas: result.statistics.circuit_family_gas,
     }
 }
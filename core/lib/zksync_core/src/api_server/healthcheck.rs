@@ -16,6 +16,20 @@ async fn check_health(
     (response_code, Json(response))
 }
 
+/// Liveness probe: the application is live unless one of its components has panicked. Unlike [`check_health()`],
+/// this doesn't fail while the application is initializing or shutting down, so that orchestrators don't restart it.
+async fn check_liveness(
+    app_health_check: State<Arc<AppHealthCheck>>,
+) -> (StatusCode, Json<AppHealth>) {
+    let response = app_health_check.check_health().await;
+    let response_code = if response.is_live() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (response_code, Json(response))
+}
+
 async fn run_server(
     bind_address: &SocketAddr,
     app_health_check: Arc<AppHealthCheck>,
@@ -27,6 +41,8 @@ async fn run_server(
 
     let app = Router::new()
         .route("/health", get(check_health))
+        .route("/health/ready", get(check_health))
+        .route("/health/live", get(check_liveness))
         .with_state(app_health_check);
 
     axum::Server::bind(bind_address)
@@ -0,0 +1,175 @@
+//! gRPC server for the Merkle tree API. Unlike the REST server, it allows reading ranges of tree leaves.
+
+use std::net::SocketAddr;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use tokio_stream::wrappers::TcpListenerStream;
+use tonic::{Request, Response, Status};
+use zksync_merkle_tree::NoVersionError;
+use zksync_types::{L1BatchNumber, H256, U256};
+use zksync_utils::{h256_to_u256, u256_to_h256};
+
+use super::{
+    metrics::{MerkleTreeApiMethod, API_METRICS},
+    MerkleTreeServer, TreeEntryWithProof,
+};
+use crate::metadata_calculator::AsyncTreeReader;
+
+#[allow(clippy::all)]
+pub(crate) mod proto {
+    tonic::include_proto!("zksync.core.tree_api");
+}
+
+use self::proto::tree_api_server::{TreeApi, TreeApiServer};
+
+/// Maximum number of leaves returned by a single `GetLeaves` call.
+const MAX_LEAVES_PER_REQUEST: usize = 1_000;
+
+fn parse_u256(bytes: &[u8], field_name: &str) -> Result<U256, Status> {
+    if bytes.len() != 32 {
+        return Err(Status::invalid_argument(format!(
+            "`{field_name}` must be 32 bytes long, got {} bytes",
+            bytes.len()
+        )));
+    }
+    Ok(h256_to_u256(H256::from_slice(bytes)))
+}
+
+fn u256_to_bytes(value: U256) -> Vec<u8> {
+    u256_to_h256(value).as_bytes().to_vec()
+}
+
+fn no_version_status(err: NoVersionError) -> Status {
+    Status::not_found(err.to_string())
+}
+
+impl From<TreeEntryWithProof> for proto::TreeEntryWithProof {
+    fn from(entry: TreeEntryWithProof) -> Self {
+        Self {
+            value: entry.value.as_bytes().to_vec(),
+            index: entry.index,
+            merkle_path: entry
+                .merkle_path
+                .iter()
+                .map(|hash| hash.as_bytes().to_vec())
+                .collect(),
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl TreeApi for AsyncTreeReader {
+    async fn get_info(
+        &self,
+        _request: Request<proto::GetInfoRequest>,
+    ) -> Result<Response<proto::GetInfoResponse>, Status> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::Info].start();
+        let info = self.clone().info().await;
+        latency.observe();
+        Ok(Response::new(proto::GetInfoResponse {
+            root_hash: info.root_hash.as_bytes().to_vec(),
+            next_l1_batch_number: info.next_l1_batch_number.0,
+            leaf_count: info.leaf_count,
+        }))
+    }
+
+    async fn get_proofs(
+        &self,
+        request: Request<proto::GetProofsRequest>,
+    ) -> Result<Response<proto::GetProofsResponse>, Status> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetProofs].start();
+        let request = request.into_inner();
+        let hashed_keys = request
+            .hashed_keys
+            .iter()
+            .map(|key| parse_u256(key, "hashed_keys"))
+            .collect::<Result<_, _>>()?;
+        let l1_batch_number = L1BatchNumber(request.l1_batch_number);
+        let entries = self
+            .get_proofs_inner(l1_batch_number, hashed_keys)
+            .await
+            .map_err(no_version_status)?;
+        latency.observe();
+        Ok(Response::new(proto::GetProofsResponse {
+            entries: entries.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_leaves(
+        &self,
+        request: Request<proto::GetLeavesRequest>,
+    ) -> Result<Response<proto::GetLeavesResponse>, Status> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetLeaves].start();
+        let request = request.into_inner();
+        let start_key = parse_u256(&request.start_key, "start_key")?;
+        let limit = match request.limit as usize {
+            0 => MAX_LEAVES_PER_REQUEST,
+            limit => limit.min(MAX_LEAVES_PER_REQUEST),
+        };
+        let l1_batch_number = L1BatchNumber(request.l1_batch_number);
+        let entries = self
+            .clone()
+            .entries_in_range(l1_batch_number, start_key, limit)
+            .await
+            .map_err(no_version_status)?;
+        latency.observe();
+
+        let leaves = entries
+            .into_iter()
+            .map(|entry| proto::TreeLeaf {
+                hashed_key: u256_to_bytes(entry.key),
+                value: entry.value.as_bytes().to_vec(),
+                index: entry.leaf_index,
+            })
+            .collect();
+        Ok(Response::new(proto::GetLeavesResponse { leaves }))
+    }
+}
+
+impl AsyncTreeReader {
+    async fn create_grpc_server(
+        self,
+        bind_address: &SocketAddr,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<MerkleTreeServer> {
+        tracing::debug!("Starting Merkle tree gRPC server on {bind_address}");
+
+        let listener = tokio::net::TcpListener::bind(bind_address)
+            .await
+            .with_context(|| format!("Failed binding Merkle tree gRPC server to {bind_address}"))?;
+        let local_addr = listener.local_addr()?;
+        let server = tonic::transport::Server::builder()
+            .add_service(TreeApiServer::new(self))
+            .serve_with_incoming_shutdown(TcpListenerStream::new(listener), async move {
+                if stop_receiver.changed().await.is_err() {
+                    tracing::warn!(
+                        "Stop signal sender for Merkle tree gRPC server was dropped without sending a signal"
+                    );
+                }
+                tracing::info!("Stop signal received, Merkle tree gRPC server is shutting down");
+            });
+        let server_future = async move {
+            server.await.context("Merkle tree gRPC server failed")?;
+            tracing::info!("Merkle tree gRPC server shut down");
+            Ok(())
+        };
+
+        Ok(MerkleTreeServer {
+            local_addr,
+            server_future: Box::pin(server_future),
+        })
+    }
+
+    /// Runs the gRPC API server.
+    pub async fn run_grpc_server(
+        self,
+        bind_address: SocketAddr,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        self.create_grpc_server(&bind_address, stop_receiver)
+            .await?
+            .run()
+            .await
+    }
+}
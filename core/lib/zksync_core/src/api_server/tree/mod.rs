@@ -1,4 +1,5 @@
-//! Primitive Merkle tree API used internally to fetch proofs.
+//! Primitive Merkle tree API used internally to fetch proofs. Besides the REST server, the API is exposed
+//! via gRPC, which can be used by external services.
 
 use std::{fmt, future::Future, net::SocketAddr, pin::Pin};
 
@@ -18,6 +19,7 @@ use zksync_types::{L1BatchNumber, H256, U256};
 use self::metrics::{MerkleTreeApiMethod, API_METRICS};
 use crate::metadata_calculator::{AsyncTreeReader, MerkleTreeInfo};
 
+mod grpc;
 mod metrics;
 #[cfg(test)]
 mod tests;
@@ -190,10 +192,16 @@ impl AsyncTreeReader {
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
     ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
-        let proofs = self
-            .clone()
-            .entries_with_proofs(l1_batch_number, hashed_keys)
-            .await?;
+        // Batching only pays off if there are multiple keys to share the tree traversal.
+        let proofs = if hashed_keys.len() > 1 {
+            self.clone()
+                .entries_with_proofs_batched(l1_batch_number, hashed_keys)
+                .await?
+        } else {
+            self.clone()
+                .entries_with_proofs(l1_batch_number, hashed_keys)
+                .await?
+        };
         Ok(proofs.into_iter().map(TreeEntryWithProof::new).collect())
     }
 
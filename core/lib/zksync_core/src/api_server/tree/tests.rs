@@ -5,7 +5,10 @@ use std::net::Ipv4Addr;
 use tempfile::TempDir;
 use zksync_dal::ConnectionPool;
 
-use super::*;
+use super::{
+    grpc::proto::{self, tree_api_client::TreeApiClient as TreeApiGrpcClient},
+    *,
+};
 use crate::metadata_calculator::tests::{
     gen_storage_logs, reset_db_state, run_calculator, setup_calculator,
 };
@@ -78,3 +81,109 @@ async fn merkle_tree_api() {
     stop_sender.send_replace(true);
     api_server_task.await.unwrap().unwrap();
 }
+
+#[tokio::test]
+async fn merkle_tree_grpc_api() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(temp_dir.path(), &pool).await;
+    let api_addr = (Ipv4Addr::LOCALHOST, 0).into();
+
+    reset_db_state(&pool, 5).await;
+    let tree_reader = calculator.tree_reader();
+    let calculator_task = tokio::spawn(run_calculator(calculator, pool));
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let api_server = tree_reader
+        .await
+        .create_grpc_server(&api_addr, stop_receiver)
+        .await
+        .unwrap();
+    let local_addr = *api_server.local_addr();
+    let api_server_task = tokio::spawn(api_server.run());
+    let mut api_client = TreeApiGrpcClient::connect(format!("http://{local_addr}"))
+        .await
+        .unwrap();
+
+    // Wait until the calculator processes initial L1 batches.
+    calculator_task.await.unwrap();
+
+    let tree_info = api_client
+        .get_info(proto::GetInfoRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(tree_info.leaf_count > 20);
+    assert_eq!(tree_info.next_l1_batch_number, 6);
+
+    let hashed_keys: Vec<_> = gen_storage_logs(20..30, 1)[0]
+        .iter()
+        .map(|log| log.key.hashed_key().as_bytes().to_vec())
+        .collect();
+    let proofs = api_client
+        .get_proofs(proto::GetProofsRequest {
+            l1_batch_number: 5,
+            hashed_keys,
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(proofs.entries.len(), 10);
+    for entry in &proofs.entries {
+        assert_ne!(entry.index, 0);
+        assert!(!entry.merkle_path.is_empty());
+    }
+
+    let request = proto::GetLeavesRequest {
+        l1_batch_number: 5,
+        start_key: vec![0; 32],
+        limit: 10,
+    };
+    let leaves = api_client
+        .get_leaves(request.clone())
+        .await
+        .unwrap()
+        .into_inner()
+        .leaves;
+    assert_eq!(leaves.len(), 10);
+    assert!(leaves
+        .windows(2)
+        .all(|window| window[0].hashed_key < window[1].hashed_key));
+
+    // Request the following page of leaves.
+    let next_key = U256::from_big_endian(&leaves[9].hashed_key) + 1;
+    let mut next_key_bytes = [0_u8; 32];
+    next_key.to_big_endian(&mut next_key_bytes);
+    let next_leaves = api_client
+        .get_leaves(proto::GetLeavesRequest {
+            start_key: next_key_bytes.to_vec(),
+            ..request.clone()
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .leaves;
+    assert_eq!(next_leaves.len(), 10);
+    assert!(next_leaves[0].hashed_key > leaves[9].hashed_key);
+
+    let err = api_client
+        .get_leaves(proto::GetLeavesRequest {
+            l1_batch_number: 10,
+            ..request.clone()
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::NotFound);
+    let err = api_client
+        .get_leaves(proto::GetLeavesRequest {
+            start_key: vec![0; 31],
+            ..request
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+    // Stop the calculator and the gRPC server.
+    stop_sender.send_replace(true);
+    api_server_task.await.unwrap().unwrap();
+}
@@ -0,0 +1,162 @@
+//! Overrides of base system contracts used by the API sandbox. Allows system contract developers to iterate
+//! on the bootloader / default account against realistic state without regenesis.
+
+use std::sync::{Arc, RwLock};
+
+use zksync_contracts::{BaseSystemContracts, SystemContractCode};
+use zksync_types::api::{BaseSystemContractsOverride, BaseSystemContractsOverrideHashes};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode},
+    bytes_to_be_words,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use super::{ApiContracts, MultiVMBaseSystemContracts};
+
+/// Validated bytecodes overriding base system contracts.
+#[derive(Debug, Clone, Default)]
+struct ContractsOverride {
+    bootloader: Option<SystemContractCode>,
+    default_aa: Option<SystemContractCode>,
+}
+
+impl ContractsOverride {
+    fn parse_code(name: &'static str, bytecode: Vec<u8>) -> Result<SystemContractCode, Web3Error> {
+        validate_bytecode(&bytecode)
+            .map_err(|err| Web3Error::InvalidSystemContractBytecode(name, err.to_string()))?;
+        Ok(SystemContractCode {
+            hash: hash_bytecode(&bytecode),
+            code: bytes_to_be_words(bytecode),
+        })
+    }
+
+    fn new(contracts: BaseSystemContractsOverride) -> Result<Self, Web3Error> {
+        Ok(Self {
+            bootloader: contracts
+                .bootloader
+                .map(|code| Self::parse_code("bootloader", code.0))
+                .transpose()?,
+            default_aa: contracts
+                .default_aa
+                .map(|code| Self::parse_code("default account", code.0))
+                .transpose()?,
+        })
+    }
+
+    fn hashes(&self) -> BaseSystemContractsOverrideHashes {
+        BaseSystemContractsOverrideHashes {
+            bootloader: self.bootloader.as_ref().map(|code| code.hash),
+            default_aa: self.default_aa.as_ref().map(|code| code.hash),
+        }
+    }
+
+    fn apply(&self, contracts: &BaseSystemContracts) -> BaseSystemContracts {
+        BaseSystemContracts {
+            bootloader: self
+                .bootloader
+                .clone()
+                .unwrap_or_else(|| contracts.bootloader.clone()),
+            default_aa: self
+                .default_aa
+                .clone()
+                .unwrap_or_else(|| contracts.default_aa.clone()),
+        }
+    }
+
+    fn apply_to_all_versions(
+        &self,
+        contracts: &MultiVMBaseSystemContracts,
+    ) -> Arc<MultiVMBaseSystemContracts> {
+        Arc::new(MultiVMBaseSystemContracts {
+            pre_virtual_blocks: self.apply(&contracts.pre_virtual_blocks),
+            post_virtual_blocks: self.apply(&contracts.post_virtual_blocks),
+            post_virtual_blocks_finish_upgrade_fix: self
+                .apply(&contracts.post_virtual_blocks_finish_upgrade_fix),
+            post_boojum: self.apply(&contracts.post_boojum),
+            post_allowlist_removal: self.apply(&contracts.post_allowlist_removal),
+            post_1_4_1: self.apply(&contracts.post_1_4_1),
+            post_1_4_2: self.apply(&contracts.post_1_4_2),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct OverriddenContracts {
+    hashes: BaseSystemContractsOverrideHashes,
+    contracts: ApiContracts,
+}
+
+/// Base system contracts used by the API sandbox, which can be overridden at runtime if enabled.
+#[derive(Debug)]
+pub(crate) struct OverridableApiContracts {
+    base: ApiContracts,
+    override_enabled: bool,
+    overridden: RwLock<Option<OverriddenContracts>>,
+}
+
+impl OverridableApiContracts {
+    pub fn new(base: ApiContracts, override_enabled: bool) -> Self {
+        Self {
+            base,
+            override_enabled,
+            overridden: RwLock::new(None),
+        }
+    }
+
+    /// Returns the contracts currently used by the sandbox.
+    pub fn get(&self) -> ApiContracts {
+        let overridden = self
+            .overridden
+            .read()
+            .expect("contracts override is poisoned");
+        match &*overridden {
+            Some(overridden) => overridden.contracts.clone(),
+            None => self.base.clone(),
+        }
+    }
+
+    pub fn override_hashes(&self) -> BaseSystemContractsOverrideHashes {
+        let overridden = self
+            .overridden
+            .read()
+            .expect("contracts override is poisoned");
+        overridden
+            .as_ref()
+            .map(|overridden| overridden.hashes)
+            .unwrap_or_default()
+    }
+
+    /// Overrides base system contracts for all protocol versions. The same bytecodes are used both for `eth_call`s
+    /// and for gas estimation.
+    pub fn set_override(
+        &self,
+        contracts: BaseSystemContractsOverride,
+    ) -> Result<BaseSystemContractsOverrideHashes, Web3Error> {
+        if !self.override_enabled {
+            return Err(Web3Error::SystemContractsOverrideDisabled);
+        }
+        let contracts_override = ContractsOverride::new(contracts)?;
+        let hashes = contracts_override.hashes();
+        let contracts = ApiContracts {
+            estimate_gas: contracts_override.apply_to_all_versions(&self.base.estimate_gas),
+            eth_call: contracts_override.apply_to_all_versions(&self.base.eth_call),
+        };
+        *self
+            .overridden
+            .write()
+            .expect("contracts override is poisoned") =
+            Some(OverriddenContracts { hashes, contracts });
+        Ok(hashes)
+    }
+
+    pub fn reset_override(&self) -> Result<(), Web3Error> {
+        if !self.override_enabled {
+            return Err(Web3Error::SystemContractsOverrideDisabled);
+        }
+        *self
+            .overridden
+            .write()
+            .expect("contracts override is poisoned") = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Diagnostics for transactions rejected because of bootloader-level constraints. The bootloader only reports
+//! error codes for such failures; here, the violated constraint is reconstructed with the exact numbers involved,
+//! so that users get actionable errors.
+
+use multivm::{
+    interface::Halt,
+    utils::{derive_base_fee_and_gas_per_pubdata, derive_overhead, get_bootloader_encoding_space},
+    vm_latest::TransactionVmExt,
+};
+use zksync_types::{fee_model::BatchFeeInput, l2::L2Tx, Transaction, VmVersion, U256};
+
+use super::SubmitTxError;
+
+/// Reason of the unexpected VM behavior the bootloader halts with if the gas per pubdata limit of a transaction
+/// is lower than required by the batch.
+const UNACCEPTABLE_PUBDATA_PRICE: &str = "UnacceptablePubdataPrice";
+
+/// Bootloader parameters used to diagnose transaction failures.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct BootloaderParams {
+    pub vm_version: VmVersion,
+    pub fee_input: BatchFeeInput,
+    pub intrinsic_gas: u32,
+}
+
+impl BootloaderParams {
+    fn gas_per_pubdata(&self) -> u64 {
+        derive_base_fee_and_gas_per_pubdata(self.fee_input, self.vm_version).1
+    }
+
+    /// Checks that the transaction fits into the bootloader memory allocated for transaction encodings.
+    pub fn check_encoding_size(&self, tx: &Transaction) -> Result<(), SubmitTxError> {
+        let encoding_size = tx.bootloader_encoding_size();
+        let max_encoding_size = get_bootloader_encoding_space(self.vm_version) as usize;
+        if encoding_size > max_encoding_size {
+            return Err(SubmitTxError::TxEncodingTooLarge {
+                provided: encoding_size,
+                max_allowed: max_encoding_size,
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns the minimum gas limit accepted by the bootloader for the transaction, i.e. the sum
+    /// of the batch overhead and the intrinsic gas.
+    fn min_gas_limit(&self, tx: &Transaction) -> U256 {
+        let gas_limit = u32::try_from(tx.gas_limit()).unwrap_or(u32::MAX);
+        let gas_per_pubdata = u32::try_from(self.gas_per_pubdata()).unwrap_or(u32::MAX);
+        let overhead = derive_overhead(
+            gas_limit,
+            gas_per_pubdata,
+            tx.encoding_len(),
+            tx.tx_format() as u8,
+            self.vm_version,
+        );
+        U256::from(overhead) + U256::from(self.intrinsic_gas)
+    }
+
+    /// Maps a bootloader halt into a structured error if the halt is caused by a bootloader-level constraint
+    /// that can be reconstructed. Returns `None` otherwise.
+    pub fn diagnose_halt(&self, tx: &L2Tx, halt: &Halt) -> Option<SubmitTxError> {
+        match halt {
+            Halt::NotEnoughGasProvided | Halt::BootloaderOutOfGas => {
+                let gas_limit = tx.common_data.fee.gas_limit;
+                let min_gas_limit = self.min_gas_limit(&tx.clone().into());
+                (gas_limit < min_gas_limit).then_some(SubmitTxError::NotEnoughGasForTxOverhead {
+                    provided: gas_limit,
+                    required: min_gas_limit,
+                })
+            }
+            Halt::UnexpectedVMBehavior(reason) if reason == UNACCEPTABLE_PUBDATA_PRICE => {
+                let gas_per_pubdata = U256::from(self.gas_per_pubdata());
+                let gas_per_pubdata_limit = tx.common_data.fee.gas_per_pubdata_limit;
+                (gas_per_pubdata_limit < gas_per_pubdata).then_some(
+                    SubmitTxError::GasPerPubdataLimitTooLow {
+                        provided: gas_per_pubdata_limit,
+                        required: gas_per_pubdata,
+                    },
+                )
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,5 +1,6 @@
+use anyhow::Context as _;
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
-use zksync_types::{fee::TransactionExecutionMetrics, l2::L2Tx};
+use zksync_types::{api::TransactionDeadline, fee::TransactionExecutionMetrics, l2::L2Tx};
 
 use super::{tx_sink::TxSink, SubmitTxError};
 use crate::metrics::{TxStage, APP_METRICS};
@@ -22,14 +23,42 @@ impl TxSink for MasterPoolSink {
         &self,
         tx: L2Tx,
         execution_metrics: TransactionExecutionMetrics,
+        deadline: Option<TransactionDeadline>,
     ) -> Result<L2TxSubmissionResult, SubmitTxError> {
-        let submission_res_handle = self
-            .master_pool
-            .access_storage_tagged("api")
-            .await?
+        let mut storage = self.master_pool.access_storage_tagged("api").await?;
+        let Some(deadline) = deadline else {
+            let submission_res_handle = storage
+                .transactions_dal()
+                .insert_transaction_l2(tx, execution_metrics)
+                .await;
+            APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
+            return Ok(submission_res_handle);
+        };
+
+        // The deadline is set in the same DB transaction, so that the mempool never loads the transaction without it.
+        let tx_hash = tx.hash();
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .context("failed starting DB transaction")?;
+        let submission_res_handle = transaction
             .transactions_dal()
             .insert_transaction_l2(tx, execution_metrics)
             .await;
+        if matches!(
+            submission_res_handle,
+            L2TxSubmissionResult::Added | L2TxSubmissionResult::Replaced
+        ) {
+            transaction
+                .transactions_dal()
+                .set_transaction_deadline(tx_hash, &deadline)
+                .await
+                .context("failed setting transaction deadline")?;
+        }
+        transaction
+            .commit()
+            .await
+            .context("failed committing DB transaction")?;
 
         APP_METRICS.processed_txs[&TxStage::Mempool(submission_res_handle)].inc();
         Ok(submission_res_handle)
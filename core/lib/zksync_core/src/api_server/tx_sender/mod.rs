@@ -1,39 +1,59 @@
 //! Helper module to submit transactions into the zkSync Network.
 
-use std::{cmp, sync::Arc, time::Instant};
+use std::{
+    cmp,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
 
 use anyhow::Context as _;
+use lru::LruCache;
 use multivm::{
-    interface::VmExecutionResultAndLogs,
+    interface::{ExecutionResult, VmExecutionResultAndLogs, VmRevertReason},
+    tracers::validator,
     utils::{adjust_pubdata_price_for_tx, derive_base_fee_and_gas_per_pubdata, derive_overhead},
     vm_latest::constants::BLOCK_GAS_LIMIT,
 };
-use zksync_config::configs::{api::Web3JsonRpcConfig, chain::StateKeeperConfig};
+use zksync_config::configs::{
+    api::{EthCallFastPathMethod, Web3JsonRpcConfig},
+    chain::StateKeeperConfig,
+};
 use zksync_contracts::BaseSystemContracts;
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool, StorageProcessor};
-use zksync_state::PostgresStorageCaches;
+use zksync_state::{PostgresStorageCaches, RocksdbReplica};
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
+    api::{
+        BaseSystemContractsOverride, BaseSystemContractsOverrideHashes, PaymasterAllowance,
+        PaymasterValidation, SimulatedTransaction, StateOverride, TransactionDeadline,
+    },
     fee::{Fee, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
-    get_code_key, get_intrinsic_constants,
+    get_code_key,
     l1::is_l1_tx_type,
     l2::{error::TxCheckError::TxDuplication, L2Tx},
     utils::storage_key_for_eth_balance,
-    AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, MiniblockNumber, Nonce,
-    PackedEthSignature, ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
-    MAX_NEW_FACTORY_DEPS, U256,
+    web3::signing::keccak256,
+    AccountTreeId, Address, ExecuteTransactionCommon, IntrinsicSystemGasConstants, L2ChainId,
+    MiniblockNumber, Nonce, PackedEthSignature, ProtocolVersionId, Transaction, VmVersion, H160,
+    H256, MAX_L2_TX_GAS_LIMIT, MAX_NEW_FACTORY_DEPS, U256,
 };
-use zksync_utils::h256_to_u256;
+use zksync_utils::{h256_to_u256, time::seconds_since_epoch};
+use zksync_web3_decl::error::Web3Error;
 
 pub(super) use self::result::SubmitTxError;
-use self::tx_sink::TxSink;
+use self::{
+    contracts_override::OverridableApiContracts, diagnostics::BootloaderParams,
+    sponsorship::TxSponsorship, tx_sink::TxSink,
+};
 use crate::{
     api_server::{
         execution_sandbox::{
-            get_pubdata_for_factory_deps, BlockArgs, BlockStartInfo, SubmitTxStage,
-            TransactionExecutor, TxExecutionArgs, TxSharedArgs, VmConcurrencyLimiter, VmPermit,
-            SANDBOX_METRICS,
+            get_pubdata_for_factory_deps, BlockArgs, BlockStartInfo, EthCallFastPath,
+            EthCallFastPathResult, GasEstimationCacheResult, SubmitTxStage, TransactionExecutor,
+            TxExecutionArgs, TxSharedArgs, ValidationAllowList, VmConcurrencyLimiter,
+            VmInvocationKind, VmPermit, SANDBOX_METRICS,
         },
         tx_sender::result::ApiCallResult,
     },
@@ -42,9 +62,12 @@ use crate::{
     utils::pending_protocol_version,
 };
 
+mod contracts_override;
+mod diagnostics;
 pub mod master_pool_sink;
 pub mod proxy;
 mod result;
+pub mod sponsorship;
 #[cfg(test)]
 pub(crate) mod tests;
 pub mod tx_sink;
@@ -68,7 +91,9 @@ pub struct MultiVMBaseSystemContracts {
 }
 
 impl MultiVMBaseSystemContracts {
-    pub fn get_by_protocol_version(self, version: ProtocolVersionId) -> BaseSystemContracts {
+    /// Returns contracts for the specified protocol version. Contracts are returned by reference, so that
+    /// the API sandbox only clones contracts for a single version per VM invocation.
+    pub fn get_by_protocol_version(&self, version: ProtocolVersionId) -> &BaseSystemContracts {
         match version {
             ProtocolVersionId::Version0
             | ProtocolVersionId::Version1
@@ -82,32 +107,33 @@ impl MultiVMBaseSystemContracts {
             | ProtocolVersionId::Version9
             | ProtocolVersionId::Version10
             | ProtocolVersionId::Version11
-            | ProtocolVersionId::Version12 => self.pre_virtual_blocks,
-            ProtocolVersionId::Version13 => self.post_virtual_blocks,
+            | ProtocolVersionId::Version12 => &self.pre_virtual_blocks,
+            ProtocolVersionId::Version13 => &self.post_virtual_blocks,
             ProtocolVersionId::Version14
             | ProtocolVersionId::Version15
             | ProtocolVersionId::Version16
-            | ProtocolVersionId::Version17 => self.post_virtual_blocks_finish_upgrade_fix,
-            ProtocolVersionId::Version18 => self.post_boojum,
-            ProtocolVersionId::Version19 => self.post_allowlist_removal,
-            ProtocolVersionId::Version20 => self.post_1_4_1,
-            ProtocolVersionId::Version21 | ProtocolVersionId::Version22 => self.post_1_4_2,
+            | ProtocolVersionId::Version17 => &self.post_virtual_blocks_finish_upgrade_fix,
+            ProtocolVersionId::Version18 => &self.post_boojum,
+            ProtocolVersionId::Version19 => &self.post_allowlist_removal,
+            ProtocolVersionId::Version20 => &self.post_1_4_1,
+            ProtocolVersionId::Version21 | ProtocolVersionId::Version22 => &self.post_1_4_2,
         }
     }
 }
 
 /// Smart contracts to be used in the API sandbox requests, e.g. for estimating gas and
-/// performing `eth_call` requests.
+/// performing `eth_call` requests. Contracts for all protocol versions are loaded once and shared
+/// among all sandbox invocations.
 #[derive(Debug, Clone)]
 pub struct ApiContracts {
     /// Contracts to be used when estimating gas.
     /// These contracts (mainly, bootloader) normally should be tuned to provide accurate
     /// execution metrics.
-    pub(crate) estimate_gas: MultiVMBaseSystemContracts,
+    pub(crate) estimate_gas: Arc<MultiVMBaseSystemContracts>,
     /// Contracts to be used when performing `eth_call` requests.
     /// These contracts (mainly, bootloader) normally should be tuned to provide better UX
     /// experience (e.g. revert messages).
-    pub(crate) eth_call: MultiVMBaseSystemContracts,
+    pub(crate) eth_call: Arc<MultiVMBaseSystemContracts>,
 }
 
 impl ApiContracts {
@@ -116,7 +142,7 @@ impl ApiContracts {
     /// given that there is no way to fetch "playground" contracts from the main node.
     pub fn load_from_disk() -> Self {
         Self {
-            estimate_gas: MultiVMBaseSystemContracts {
+            estimate_gas: Arc::new(MultiVMBaseSystemContracts {
                 pre_virtual_blocks: BaseSystemContracts::estimate_gas_pre_virtual_blocks(),
                 post_virtual_blocks: BaseSystemContracts::estimate_gas_post_virtual_blocks(),
                 post_virtual_blocks_finish_upgrade_fix:
@@ -125,8 +151,8 @@ impl ApiContracts {
                 post_allowlist_removal: BaseSystemContracts::estimate_gas_post_allowlist_removal(),
                 post_1_4_1: BaseSystemContracts::estimate_gas_post_1_4_1(),
                 post_1_4_2: BaseSystemContracts::estimate_gas_post_1_4_2(),
-            },
-            eth_call: MultiVMBaseSystemContracts {
+            }),
+            eth_call: Arc::new(MultiVMBaseSystemContracts {
                 pre_virtual_blocks: BaseSystemContracts::playground_pre_virtual_blocks(),
                 post_virtual_blocks: BaseSystemContracts::playground_post_virtual_blocks(),
                 post_virtual_blocks_finish_upgrade_fix:
@@ -135,7 +161,7 @@ impl ApiContracts {
                 post_allowlist_removal: BaseSystemContracts::playground_post_allowlist_removal(),
                 post_1_4_1: BaseSystemContracts::playground_post_1_4_1(),
                 post_1_4_2: BaseSystemContracts::playground_post_1_4_2(),
-            },
+            }),
         }
     }
 }
@@ -151,6 +177,10 @@ pub struct TxSenderBuilder {
     tx_sink: Arc<dyn TxSink>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Option<Arc<dyn ConditionalSealer>>,
+    /// Replica of the state keeper cache used in VM execution.
+    rocksdb_replica: Option<RocksdbReplica>,
+    /// Sponsorship of transactions using the operator paymaster.
+    sponsorship: Option<TxSponsorship>,
 }
 
 impl TxSenderBuilder {
@@ -164,6 +194,8 @@ impl TxSenderBuilder {
             replica_connection_pool,
             tx_sink,
             sealer: None,
+            rocksdb_replica: None,
+            sponsorship: None,
         }
     }
 
@@ -172,6 +204,16 @@ impl TxSenderBuilder {
         self
     }
 
+    pub fn with_rocksdb_replica(mut self, replica: RocksdbReplica) -> Self {
+        self.rocksdb_replica = Some(replica);
+        self
+    }
+
+    pub fn with_sponsorship(mut self, sponsorship: TxSponsorship) -> Self {
+        self.sponsorship = Some(sponsorship);
+        self
+    }
+
     pub async fn build(
         self,
         batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
@@ -181,6 +223,14 @@ impl TxSenderBuilder {
     ) -> TxSender {
         // Use noop sealer if no sealer was explicitly provided.
         let sealer = self.sealer.unwrap_or_else(|| Arc::new(NoopSealer));
+        let eth_call_fast_path = EthCallFastPath::new(
+            &self.config.eth_call_fast_path_methods,
+            &self.config.eth_call_fast_path_token_code_hashes,
+        );
+        let api_contracts = OverridableApiContracts::new(
+            api_contracts,
+            self.config.system_contracts_override_enabled,
+        );
 
         TxSender(Arc::new(TxSenderInner {
             sender_config: self.config,
@@ -190,8 +240,13 @@ impl TxSenderBuilder {
             api_contracts,
             vm_concurrency_limiter,
             storage_caches,
+            rocksdb_replica: self.rocksdb_replica,
+            sponsorship: self.sponsorship,
             sealer,
             executor: TransactionExecutor::Real,
+            validation_allow_list: ValidationAllowList::default(),
+            gas_estimation_cache: GasEstimationCache::new(self.config.estimate_gas_cache_size),
+            eth_call_fast_path,
         }))
     }
 }
@@ -211,6 +266,16 @@ pub struct TxSenderConfig {
     pub l1_to_l2_transactions_compatibility_mode: bool,
     pub chain_id: L2ChainId,
     pub max_pubdata_per_batch: u64,
+    pub estimate_gas_optimize_search: bool,
+    /// Maximum number of cached gas estimation results; 0 disables caching.
+    pub estimate_gas_cache_size: usize,
+    /// Intrinsic gas constants (potentially with chain-specific overrides) used to validate submitted transactions.
+    pub intrinsic_constants: IntrinsicSystemGasConstants,
+    /// View methods served by `eth_call` directly from storage for recognized token contracts.
+    pub eth_call_fast_path_methods: Vec<EthCallFastPathMethod>,
+    pub eth_call_fast_path_token_code_hashes: Vec<H256>,
+    /// Whether base system contracts used by the sandbox can be overridden via the `admin` namespace.
+    pub system_contracts_override_enabled: bool,
 }
 
 impl TxSenderConfig {
@@ -218,6 +283,7 @@ impl TxSenderConfig {
         state_keeper_config: &StateKeeperConfig,
         web3_json_config: &Web3JsonRpcConfig,
         chain_id: L2ChainId,
+        intrinsic_constants: IntrinsicSystemGasConstants,
     ) -> Self {
         Self {
             fee_account_addr: state_keeper_config.fee_account_addr,
@@ -231,6 +297,14 @@ impl TxSenderConfig {
                 .l1_to_l2_transactions_compatibility_mode,
             chain_id,
             max_pubdata_per_batch: state_keeper_config.max_pubdata_per_batch,
+            estimate_gas_optimize_search: web3_json_config.estimate_gas_optimize_search,
+            estimate_gas_cache_size: web3_json_config.estimate_gas_cache_size(),
+            intrinsic_constants,
+            eth_call_fast_path_methods: web3_json_config.eth_call_fast_path_methods.clone(),
+            eth_call_fast_path_token_code_hashes: web3_json_config
+                .eth_call_fast_path_token_code_hashes
+                .clone(),
+            system_contracts_override_enabled: web3_json_config.system_contracts_override_enabled,
         }
     }
 }
@@ -242,14 +316,105 @@ pub struct TxSenderInner {
     pub replica_connection_pool: ConnectionPool,
     // Used to keep track of gas prices for the fee ticker.
     pub batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
-    pub(super) api_contracts: ApiContracts,
+    /// Base system contracts used by the sandbox; can be overridden via the `admin` namespace if enabled.
+    api_contracts: OverridableApiContracts,
     /// Used to limit the amount of VMs that can be executed simultaneously.
     pub(super) vm_concurrency_limiter: Arc<VmConcurrencyLimiter>,
     // Caches used in VM execution.
     storage_caches: PostgresStorageCaches,
+    rocksdb_replica: Option<RocksdbReplica>,
+    /// Sponsorship of transactions using the operator paymaster. Only set if sponsorship is enabled.
+    sponsorship: Option<TxSponsorship>,
     /// Batch sealer used to check whether transaction can be executed by the sequencer.
     sealer: Arc<dyn ConditionalSealer>,
     pub(super) executor: TransactionExecutor,
+    /// Allow-list of storage slots and addresses trusted during transaction validation.
+    pub(super) validation_allow_list: ValidationAllowList,
+    /// Results of recent gas estimations.
+    pub(super) gas_estimation_cache: GasEstimationCache,
+    /// Fast path for `eth_call`s to view methods of recognized token contracts.
+    pub(super) eth_call_fast_path: EthCallFastPath,
+}
+
+/// Context shared by gas estimations of one or more transactions.
+#[derive(Debug)]
+struct GasEstimationContext {
+    block_args: BlockArgs,
+    protocol_version: ProtocolVersionId,
+    /// Fee input before adjusting the pubdata price for a specific transaction.
+    fee_input: BatchFeeInput,
+}
+
+/// Key of [`GasEstimationCache`]. Gas estimation is deterministic given the state it is performed on top of,
+/// the fee input and the estimated transaction, so identical keys yield identical results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct GasEstimationCacheKey {
+    /// Pending miniblock the estimation is performed for. The state for the pending miniblock
+    /// doesn't change until it's sealed.
+    miniblock: MiniblockNumber,
+    fee_input: BatchFeeInput,
+    /// Digest of the transaction data excluding the fields not influencing execution (e.g., the received timestamp).
+    tx_digest: H256,
+    estimated_fee_scale_factor_bits: u64,
+    acceptable_overestimation: u32,
+    gas_cap: Option<u32>,
+}
+
+impl GasEstimationCacheKey {
+    fn new(
+        context: &GasEstimationContext,
+        tx: &Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+        gas_cap: Option<u32>,
+    ) -> anyhow::Result<Self> {
+        let tx_data = serde_json::to_vec(&(&tx.common_data, &tx.execute))
+            .context("failed serializing transaction")?;
+        Ok(Self {
+            miniblock: context.block_args.resolved_block_number(),
+            fee_input: context.fee_input,
+            tx_digest: H256(keccak256(&tx_data)),
+            estimated_fee_scale_factor_bits: estimated_fee_scale_factor.to_bits(),
+            acceptable_overestimation,
+            gas_cap,
+        })
+    }
+}
+
+/// LRU cache of successful gas estimation results. Allows to avoid repeated VM invocations for identical
+/// `eth_estimateGas` / `zks_estimateFee` requests within a single miniblock, which are common for wallets
+/// polling estimates. The cache is disabled if its capacity is 0.
+#[derive(Debug)]
+pub(super) struct GasEstimationCache(Option<Mutex<LruCache<GasEstimationCacheKey, Fee>>>);
+
+impl GasEstimationCache {
+    fn new(capacity: usize) -> Self {
+        Self(NonZeroUsize::new(capacity).map(|capacity| Mutex::new(LruCache::new(capacity))))
+    }
+
+    fn get(&self, key: &GasEstimationCacheKey) -> Option<Fee> {
+        let cache = self.0.as_ref()?;
+        let fee = cache.lock().expect("cache is poisoned").get(key).cloned();
+        let result = if fee.is_some() {
+            GasEstimationCacheResult::Hit
+        } else {
+            GasEstimationCacheResult::Miss
+        };
+        SANDBOX_METRICS.estimate_gas_cache[&result].inc();
+        fee
+    }
+
+    fn insert(&self, key: GasEstimationCacheKey, fee: Fee) {
+        if let Some(cache) = &self.0 {
+            cache.lock().expect("cache is poisoned").put(key, fee);
+        }
+    }
+
+    fn clear(&self) {
+        if let Some(cache) = &self.0 {
+            cache.lock().expect("cache is poisoned").clear();
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -270,6 +435,36 @@ impl TxSender {
         self.0.storage_caches.clone()
     }
 
+    pub(crate) fn rocksdb_replica(&self) -> Option<RocksdbReplica> {
+        self.0.rocksdb_replica.clone()
+    }
+
+    /// Returns base system contracts currently used by the sandbox.
+    pub(crate) fn api_contracts(&self) -> ApiContracts {
+        self.0.api_contracts.get()
+    }
+
+    pub(crate) fn base_system_contracts_override(&self) -> BaseSystemContractsOverrideHashes {
+        self.0.api_contracts.override_hashes()
+    }
+
+    /// Overrides base system contracts used by the sandbox. Cached gas estimations are discarded, since they
+    /// may depend on the overridden contracts.
+    pub(crate) fn set_base_system_contracts_override(
+        &self,
+        contracts: BaseSystemContractsOverride,
+    ) -> Result<BaseSystemContractsOverrideHashes, Web3Error> {
+        let hashes = self.0.api_contracts.set_override(contracts)?;
+        self.0.gas_estimation_cache.clear();
+        Ok(hashes)
+    }
+
+    pub(crate) fn reset_base_system_contracts_override(&self) -> Result<(), Web3Error> {
+        self.0.api_contracts.reset_override()?;
+        self.0.gas_estimation_cache.clear();
+        Ok(())
+    }
+
     async fn acquire_replica_connection(&self) -> anyhow::Result<StorageProcessor<'_>> {
         self.0
             .replica_connection_pool
@@ -280,17 +475,38 @@ impl TxSender {
 
     #[tracing::instrument(skip(self, tx))]
     pub async fn submit_tx(&self, tx: L2Tx) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        self.submit_tx_with_deadline(tx, None).await
+    }
+
+    /// Submits a transaction with an optional inclusion deadline. If the deadline passes before the transaction
+    /// is included into a miniblock, the transaction is evicted from the mempool.
+    #[tracing::instrument(skip(self, tx))]
+    pub async fn submit_tx_with_deadline(
+        &self,
+        tx: L2Tx,
+        deadline: Option<TransactionDeadline>,
+    ) -> Result<L2TxSubmissionResult, SubmitTxError> {
+        let deadline = deadline.filter(|deadline| !deadline.is_empty());
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::Validate].start();
         self.validate_tx(&tx).await?;
         stage_latency.observe();
 
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::DryRun].start();
         let shared_args = self.shared_args().await;
-        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = self
+            .0
+            .vm_concurrency_limiter
+            .acquire(VmInvocationKind::SendTransaction)
+            .await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
         let mut connection = self.acquire_replica_connection().await?;
         let block_args = BlockArgs::pending(&mut connection).await?;
         drop(connection);
+        if let Some(deadline) = &deadline {
+            if deadline.is_passed(block_args.resolved_block_number(), seconds_since_epoch()) {
+                return Err(SubmitTxError::DeadlinePassed);
+            }
+        }
 
         let execution_output = self
             .0
@@ -314,6 +530,21 @@ impl TxSender {
         );
         stage_latency.observe();
 
+        if let ExecutionResult::Halt { reason } = &execution_output.vm.result {
+            // Diagnostics must use the protocol version of the batch the transaction was executed in.
+            let mut connection = self.acquire_replica_connection().await?;
+            let protocol_version = block_args.protocol_version(&mut connection).await?;
+            drop(connection);
+            let bootloader_params = self.bootloader_params(shared_args.fee_input, protocol_version);
+            if let Some(err) = bootloader_params.diagnose_halt(&tx, reason) {
+                tracing::info!(
+                    "Submitted tx {:?} violates bootloader constraints: {err}",
+                    tx.hash()
+                );
+                return Err(err);
+            }
+        }
+
         let stage_latency = SANDBOX_METRICS.submit_tx[&SubmitTxStage::VerifyExecute].start();
         let computational_gas_limit = self.0.sender_config.validation_computational_gas_limit;
         let validation_result = self
@@ -326,6 +557,7 @@ impl TxSender {
                 shared_args,
                 block_args,
                 computational_gas_limit,
+                &self.0.validation_allow_list,
             )
             .await;
         stage_latency.observe();
@@ -343,11 +575,26 @@ impl TxSender {
         let nonce = tx.common_data.nonce.0;
         let hash = tx.hash();
         let initiator_account = tx.initiator_account();
+        let sponsored_tx = match &self.0.sponsorship {
+            Some(sponsorship) => sponsorship.reserve(&tx).await?,
+            None => None,
+        };
         let submission_res_handle = self
             .0
             .tx_sink
-            .submit_tx(tx, execution_output.metrics)
-            .await?;
+            .submit_tx(tx, execution_output.metrics, deadline)
+            .await;
+        if let (Some(sponsorship), Some(sponsored_tx)) = (&self.0.sponsorship, sponsored_tx) {
+            let is_accepted = !matches!(
+                submission_res_handle,
+                Err(_)
+                    | Ok(L2TxSubmissionResult::AlreadyExecuted | L2TxSubmissionResult::Duplicate)
+            );
+            if !is_accepted {
+                sponsorship.release(sponsored_tx).await?;
+            }
+        }
+        let submission_res_handle = submission_res_handle?;
 
         match submission_res_handle {
             L2TxSubmissionResult::AlreadyExecuted => {
@@ -381,8 +628,9 @@ impl TxSender {
         TxSharedArgs {
             operator_account: AccountTreeId::new(self.0.sender_config.fee_account_addr),
             fee_input: self.0.batch_fee_input_provider.get_batch_fee_input().await,
-            base_system_contracts: self.0.api_contracts.eth_call.clone(),
+            base_system_contracts: self.0.api_contracts.get().eth_call,
             caches: self.storage_caches(),
+            rocksdb_replica: self.rocksdb_replica(),
             validation_computational_gas_limit: self
                 .0
                 .sender_config
@@ -391,33 +639,60 @@ impl TxSender {
         }
     }
 
+    fn bootloader_params(
+        &self,
+        fee_input: BatchFeeInput,
+        protocol_version: ProtocolVersionId,
+    ) -> BootloaderParams {
+        BootloaderParams {
+            vm_version: protocol_version.into(),
+            fee_input,
+            intrinsic_gas: self.0.sender_config.intrinsic_constants.l2_tx_intrinsic_gas,
+        }
+    }
+
     async fn validate_tx(&self, tx: &L2Tx) -> Result<(), SubmitTxError> {
         let max_gas = U256::from(u32::MAX);
-        if tx.common_data.fee.gas_limit > max_gas
-            || tx.common_data.fee.gas_per_pubdata_limit > max_gas
-        {
-            return Err(SubmitTxError::GasLimitIsTooBig);
+        if tx.common_data.fee.gas_limit > max_gas {
+            return Err(SubmitTxError::GasLimitIsTooBig {
+                provided: tx.common_data.fee.gas_limit,
+                max_allowed: max_gas,
+            });
+        }
+        if tx.common_data.fee.gas_per_pubdata_limit > max_gas {
+            return Err(SubmitTxError::GasPerPubdataLimitIsTooBig {
+                provided: tx.common_data.fee.gas_per_pubdata_limit,
+                max_allowed: max_gas,
+            });
         }
 
         let fee_input = self.0.batch_fee_input_provider.get_batch_fee_input().await;
 
         // TODO (SMA-1715): do not subsidize the overhead for the transaction
 
-        if tx.common_data.fee.gas_limit > self.0.sender_config.max_allowed_l2_tx_gas_limit.into() {
+        let max_allowed_gas_limit: U256 = self.0.sender_config.max_allowed_l2_tx_gas_limit.into();
+        if tx.common_data.fee.gas_limit > max_allowed_gas_limit {
             tracing::info!(
                 "Submitted Tx is Unexecutable {:?} because of GasLimitIsTooBig {}",
                 tx.hash(),
                 tx.common_data.fee.gas_limit,
             );
-            return Err(SubmitTxError::GasLimitIsTooBig);
+            return Err(SubmitTxError::GasLimitIsTooBig {
+                provided: tx.common_data.fee.gas_limit,
+                max_allowed: max_allowed_gas_limit,
+            });
         }
-        if tx.common_data.fee.max_fee_per_gas < fee_input.fair_l2_gas_price().into() {
+        let fair_l2_gas_price: U256 = fee_input.fair_l2_gas_price().into();
+        if tx.common_data.fee.max_fee_per_gas < fair_l2_gas_price {
             tracing::info!(
                 "Submitted Tx is Unexecutable {:?} because of MaxFeePerGasTooLow {}",
                 tx.hash(),
                 tx.common_data.fee.max_fee_per_gas
             );
-            return Err(SubmitTxError::MaxFeePerGasTooLow);
+            return Err(SubmitTxError::MaxFeePerGasTooLow {
+                provided: tx.common_data.fee.max_fee_per_gas,
+                required: fair_l2_gas_price,
+            });
         }
         if tx.common_data.fee.max_fee_per_gas < tx.common_data.fee.max_priority_fee_per_gas {
             tracing::info!(
@@ -425,7 +700,10 @@ impl TxSender {
                 tx.hash(),
                 tx.common_data.fee.max_fee_per_gas
             );
-            return Err(SubmitTxError::MaxPriorityFeeGreaterThanMaxFee);
+            return Err(SubmitTxError::MaxPriorityFeeGreaterThanMaxFee {
+                max_priority_fee_per_gas: tx.common_data.fee.max_priority_fee_per_gas,
+                max_fee_per_gas: tx.common_data.fee.max_fee_per_gas,
+            });
         }
         if tx.execute.factory_deps_length() > MAX_NEW_FACTORY_DEPS {
             return Err(SubmitTxError::TooManyFactoryDependencies(
@@ -433,15 +711,21 @@ impl TxSender {
                 MAX_NEW_FACTORY_DEPS,
             ));
         }
+        // Consistent with `ensure_tx_executable()`.
+        self.bootloader_params(fee_input, ProtocolVersionId::latest())
+            .check_encoding_size(&tx.clone().into())?;
 
-        let intrinsic_consts = get_intrinsic_constants();
+        let intrinsic_consts = &self.0.sender_config.intrinsic_constants;
         assert!(
             intrinsic_consts.l2_tx_intrinsic_pubdata == 0,
             "Currently we assume that the L2 transactions do not have any intrinsic pubdata"
         );
         let min_gas_limit = U256::from(intrinsic_consts.l2_tx_intrinsic_gas);
         if tx.common_data.fee.gas_limit < min_gas_limit {
-            return Err(SubmitTxError::IntrinsicGas);
+            return Err(SubmitTxError::IntrinsicGas {
+                provided: tx.common_data.fee.gas_limit,
+                required: min_gas_limit,
+            });
         }
 
         // We still double-check the nonce manually
@@ -619,20 +903,15 @@ impl TxSender {
             fee_input,
             // We want to bypass the computation gas limit check for gas estimation
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
-            base_system_contracts: self.0.api_contracts.estimate_gas.clone(),
+            base_system_contracts: self.0.api_contracts.get().estimate_gas,
             caches: self.storage_caches(),
+            rocksdb_replica: self.rocksdb_replica(),
             chain_id: config.chain_id,
         }
     }
 
-    pub async fn get_txs_fee_in_wei(
-        &self,
-        mut tx: Transaction,
-        estimated_fee_scale_factor: f64,
-        acceptable_overestimation: u32,
-    ) -> Result<Fee, SubmitTxError> {
-        let estimation_started_at = Instant::now();
-
+    /// Prepares the context for estimating gas of one or more transactions on top of the pending block.
+    async fn prepare_gas_estimation(&self) -> Result<GasEstimationContext, SubmitTxError> {
         let mut connection = self.acquire_replica_connection().await?;
         let block_args = BlockArgs::pending(&mut connection).await?;
         let protocol_version = pending_protocol_version(&mut connection)
@@ -640,22 +919,101 @@ impl TxSender {
             .context("failed getting pending protocol version")?;
         drop(connection);
 
-        let fee_input = {
-            // For now, both L1 gas price and pubdata price are scaled with the same coefficient
-            let fee_input = self
-                .0
-                .batch_fee_input_provider
-                .get_batch_fee_input_scaled(
-                    self.0.sender_config.gas_price_scale_factor,
-                    self.0.sender_config.gas_price_scale_factor,
+        let fee_input = self.scaled_batch_fee_input().await;
+
+        Ok(GasEstimationContext {
+            block_args,
+            protocol_version,
+            fee_input,
+        })
+    }
+
+    /// Estimates fees for a transaction. If `gas_cap` is specified, the transaction is never executed with
+    /// a greater gas limit, and [`SubmitTxError::GasCapExceeded`] is returned if the transaction requires more gas.
+    pub async fn get_txs_fee_in_wei(
+        &self,
+        tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+        gas_cap: Option<u32>,
+    ) -> Result<Fee, SubmitTxError> {
+        let context = self.prepare_gas_estimation().await?;
+        self.estimate_fee_in_context(
+            &context,
+            tx,
+            estimated_fee_scale_factor,
+            acceptable_overestimation,
+            gas_cap,
+        )
+        .await
+    }
+
+    /// Estimates fees for multiple transactions on top of the same pending block, reusing the block context.
+    /// Transactions are estimated independently, i.e., a transaction doesn't observe state changes produced
+    /// by the preceding transactions, and an estimation error for one transaction doesn't affect other ones.
+    /// A VM permit is acquired separately for each transaction, so that a large batch doesn't starve other
+    /// VM invocations.
+    pub async fn get_txs_fees_in_wei(
+        &self,
+        txs: Vec<Transaction>,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+        gas_cap: Option<u32>,
+    ) -> Result<Vec<Result<Fee, SubmitTxError>>, SubmitTxError> {
+        let context = self.prepare_gas_estimation().await?;
+        let mut fees = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let fee = self
+                .estimate_fee_in_context(
+                    &context,
+                    tx,
+                    estimated_fee_scale_factor,
+                    acceptable_overestimation,
+                    gas_cap,
                 )
                 .await;
-            adjust_pubdata_price_for_tx(
-                fee_input,
-                tx.gas_per_pubdata_byte_limit(),
-                protocol_version.into(),
-            )
-        };
+            if matches!(fee, Err(SubmitTxError::ServerShuttingDown)) {
+                return Err(SubmitTxError::ServerShuttingDown);
+            }
+            fees.push(fee);
+        }
+        Ok(fees)
+    }
+
+    async fn estimate_fee_in_context(
+        &self,
+        context: &GasEstimationContext,
+        mut tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u32,
+        gas_cap: Option<u32>,
+    ) -> Result<Fee, SubmitTxError> {
+        let estimation_started_at = Instant::now();
+        let cache_key = GasEstimationCacheKey::new(
+            context,
+            &tx,
+            estimated_fee_scale_factor,
+            acceptable_overestimation,
+            gas_cap,
+        )?;
+        if let Some(fee) = self.0.gas_estimation_cache.get(&cache_key) {
+            return Ok(fee);
+        }
+
+        // Acquire the VM permit for the whole duration of the binary search.
+        let vm_permit = self
+            .0
+            .vm_concurrency_limiter
+            .acquire(VmInvocationKind::EstimateGas)
+            .await;
+        let vm_permit = &vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+        let block_args = context.block_args;
+        let protocol_version = context.protocol_version;
+        let fee_input = adjust_pubdata_price_for_tx(
+            context.fee_input,
+            tx.gas_per_pubdata_byte_limit(),
+            protocol_version.into(),
+        );
 
         let (base_fee, gas_per_pubdata_byte) =
             derive_base_fee_and_gas_per_pubdata(fee_input, protocol_version.into());
@@ -688,17 +1046,20 @@ impl TxSender {
                 )
             })?;
 
-        if !tx.is_l1()
-            && account_code_hash == H256::zero()
-            && tx.execute.value > self.get_balance(&tx.initiator_account()).await?
-        {
-            tracing::info!(
-                "fee estimation failed on validation step.
-                account: {} does not have enough funds for for transferring tx.value: {}.",
-                &tx.initiator_account(),
-                tx.execute.value
-            );
-            return Err(SubmitTxError::InsufficientFundsForTransfer);
+        if !tx.is_l1() && account_code_hash == H256::zero() {
+            let balance = self.get_balance(&tx.initiator_account()).await?;
+            if tx.execute.value > balance {
+                tracing::info!(
+                    "fee estimation failed on validation step.
+                    account: {} does not have enough funds for for transferring tx.value: {}.",
+                    &tx.initiator_account(),
+                    tx.execute.value
+                );
+                return Err(SubmitTxError::InsufficientFundsForTransfer {
+                    balance,
+                    value: tx.execute.value,
+                });
+            }
         }
 
         // For L2 transactions we need a properly formatted signature
@@ -711,10 +1072,6 @@ impl TxSender {
                 U256::from(DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE);
         }
 
-        // Acquire the vm token for the whole duration of the binary search.
-        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
-        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
-
         // We already know how many gas is needed to cover for the publishing of the bytecodes.
         // For L1->L2 transactions all the bytecodes have been made available on L1, so no funds need to be
         // spent on re-publishing those.
@@ -722,7 +1079,7 @@ impl TxSender {
             0
         } else {
             let pubdata_for_factory_deps = get_pubdata_for_factory_deps(
-                &vm_permit,
+                vm_permit,
                 &self.0.replica_connection_pool,
                 tx.execute.factory_deps.as_deref().unwrap_or_default(),
                 self.storage_caches(),
@@ -737,10 +1094,17 @@ impl TxSender {
             pubdata_for_factory_deps * (gas_per_pubdata_byte as u32)
         };
 
+        // The gas cap applies to the entire gas limit of the transaction, including gas spent on publishing bytecodes.
+        // Caps not less than the maximum gas limit don't influence estimation.
+        let gas_cap = gas_cap.filter(|&cap| cap < MAX_L2_TX_GAS_LIMIT as u32);
+        let max_tx_body_gas_limit = gas_cap.map_or(MAX_L2_TX_GAS_LIMIT as u32, |cap| {
+            cap.saturating_sub(gas_for_bytecodes_pubdata)
+        });
+
         // We are using binary search to find the minimal values of gas_limit under which
         // the transaction succeeds
         let mut lower_bound = 0;
-        let mut upper_bound = MAX_L2_TX_GAS_LIMIT as u32;
+        let mut upper_bound = max_tx_body_gas_limit;
         let tx_id = format!(
             "{:?}-{}",
             tx.initiator_account(),
@@ -752,25 +1116,69 @@ impl TxSender {
             estimation_started_at.elapsed(),
         );
 
+        let estimate_gas_step = |tx_body_gas_limit: u32| {
+            self.estimate_gas_step(
+                vm_permit.clone(),
+                tx.clone(),
+                gas_for_bytecodes_pubdata + tx_body_gas_limit,
+                gas_per_pubdata_byte as u32,
+                fee_input,
+                block_args,
+                base_fee,
+                protocol_version.into(),
+            )
+        };
+
         let mut number_of_iterations = 0usize;
+        if self.0.sender_config.estimate_gas_optimize_search {
+            // Execute the transaction with the maximum gas limit and use the consumed gas to narrow the search bounds.
+            // If the transaction fails even with the maximum gas limit, there's no point searching; the error
+            // will be returned by the final estimation step.
+            let (result, _) = estimate_gas_step(upper_bound)
+                .await
+                .context("initial estimate_gas step failed")?;
+            number_of_iterations += 1;
+
+            if result.result.is_failed() {
+                lower_bound = upper_bound;
+            } else {
+                let gas_used = result
+                    .statistics
+                    .gas_used
+                    .saturating_sub(gas_for_bytecodes_pubdata);
+                // Far calls only receive 63/64 of the remaining gas, so the minimum gas limit is usually slightly
+                // greater than the consumed gas. The gas consumed with the maximum gas limit is not guaranteed
+                // to be a bound, so both candidate bounds are checked by executing the transaction.
+                let optimistic_gas_limit = gas_used.saturating_add(gas_used / 63);
+                for candidate in [optimistic_gas_limit, gas_used.saturating_sub(1)] {
+                    if candidate < lower_bound || candidate >= upper_bound {
+                        continue;
+                    }
+                    let (result, _) = estimate_gas_step(candidate)
+                        .await
+                        .context("estimate_gas step failed")?;
+                    if result.result.is_failed() {
+                        lower_bound = candidate + 1;
+                    } else {
+                        upper_bound = candidate;
+                    }
+                    number_of_iterations += 1;
+                }
+            }
+            tracing::trace!(
+                "fee estimation tx {tx_id:?}: initialized search bounds after {number_of_iterations} iterations \
+                 ({:?} elapsed). lower_bound: {lower_bound}, upper_bound: {upper_bound}",
+                estimation_started_at.elapsed()
+            );
+        }
+
         while lower_bound + acceptable_overestimation < upper_bound {
             let mid = (lower_bound + upper_bound) / 2;
             // There is no way to distinct between errors due to out of gas
             // or normal execution errors, so we just hope that increasing the
             // gas limit will make the transaction successful
             let iteration_started_at = Instant::now();
-            let try_gas_limit = gas_for_bytecodes_pubdata + mid;
-            let (result, _) = self
-                .estimate_gas_step(
-                    vm_permit.clone(),
-                    tx.clone(),
-                    try_gas_limit,
-                    gas_per_pubdata_byte as u32,
-                    fee_input,
-                    block_args,
-                    base_fee,
-                    protocol_version.into(),
-                )
+            let (result, _) = estimate_gas_step(mid)
                 .await
                 .context("estimate_gas step failed")?;
 
@@ -795,25 +1203,35 @@ impl TxSender {
             .observe(number_of_iterations);
 
         let tx_body_gas_limit = cmp::min(
-            MAX_L2_TX_GAS_LIMIT as u32,
+            max_tx_body_gas_limit,
             ((upper_bound as f64) * estimated_fee_scale_factor) as u32,
         );
 
         let suggested_gas_limit = tx_body_gas_limit + gas_for_bytecodes_pubdata;
-        let (result, tx_metrics) = self
-            .estimate_gas_step(
-                vm_permit,
-                tx.clone(),
-                suggested_gas_limit,
-                gas_per_pubdata_byte as u32,
-                fee_input,
-                block_args,
-                base_fee,
-                protocol_version.into(),
-            )
+        let (result, tx_metrics) = estimate_gas_step(tx_body_gas_limit)
             .await
             .context("final estimate_gas step failed")?;
 
+        if let Some(cap) = gas_cap {
+            // The VM doesn't distinguish between running out of gas and other failures, so (similar to Ethereum nodes)
+            // a transaction failing with the capped gas limit without a revert reason is assumed to require more gas.
+            // Reverts with a reason are returned to the caller as is.
+            let requires_more_gas = match &result.result {
+                ExecutionResult::Success { .. } => false,
+                ExecutionResult::Revert { output } => match output {
+                    VmRevertReason::General { msg, data } => msg.is_empty() && data.is_empty(),
+                    VmRevertReason::Unknown { .. } => false,
+                    VmRevertReason::InnerTxError | VmRevertReason::VmError => true,
+                },
+                ExecutionResult::Halt { .. } => true,
+            };
+            if requires_more_gas && upper_bound >= max_tx_body_gas_limit {
+                return Err(SubmitTxError::GasCapExceeded {
+                    provided: None,
+                    cap,
+                });
+            }
+        }
         result.into_api_call_result()?;
         self.ensure_tx_executable(tx.clone(), &tx_metrics, false)?;
 
@@ -852,20 +1270,65 @@ impl TxSender {
                 }
             };
 
-        Ok(Fee {
+        if let Some(cap) = gas_cap {
+            if full_gas_limit > cap {
+                return Err(SubmitTxError::GasCapExceeded {
+                    provided: None,
+                    cap,
+                });
+            }
+        }
+
+        let fee = Fee {
             max_fee_per_gas: base_fee.into(),
             max_priority_fee_per_gas: 0u32.into(),
             gas_limit: full_gas_limit.into(),
             gas_per_pubdata_limit: gas_per_pubdata_byte.into(),
-        })
+        };
+        self.0.gas_estimation_cache.insert(cache_key, fee.clone());
+        Ok(fee)
+    }
+
+    /// Tries to serve a call to a view method of a recognized contract without invoking the VM.
+    async fn eth_call_fast_path(
+        &self,
+        block_args: &BlockArgs,
+        tx: &L2Tx,
+    ) -> anyhow::Result<Option<Vec<u8>>> {
+        let fast_path = &self.0.eth_call_fast_path;
+        if !fast_path.is_enabled() {
+            return Ok(None);
+        }
+        let mut connection = self.acquire_replica_connection().await?;
+        let output = fast_path.try_call(&mut connection, block_args, tx).await?;
+        let result = if output.is_some() {
+            EthCallFastPathResult::Served
+        } else {
+            EthCallFastPathResult::Fallback
+        };
+        SANDBOX_METRICS.eth_call_fast_path[&result].inc();
+        Ok(output)
     }
 
     pub(super) async fn eth_call(
         &self,
         block_args: BlockArgs,
         tx: L2Tx,
+        state_override: Option<StateOverride>,
+        gas_cap: Option<u32>,
     ) -> Result<Vec<u8>, SubmitTxError> {
-        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        // State overrides may change storage read by the fast path, so such calls are always executed in the VM.
+        if state_override.is_none() {
+            if let Some(output) = self.eth_call_fast_path(&block_args, &tx).await? {
+                return Ok(output);
+            }
+        }
+
+        let vm_permit = self
+            .0
+            .vm_concurrency_limiter
+            .acquire(VmInvocationKind::Call)
+            .await;
         let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
 
         let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
@@ -879,11 +1342,139 @@ impl TxSender {
                 block_args,
                 vm_execution_cache_misses_limit,
                 vec![],
+                state_override,
+                gas_cap,
             )
             .await?
             .into_api_call_result()
     }
 
+    /// Executes a bundle of transactions one after another on top of the state of the specified block.
+    /// If any transaction in the bundle is halted, the entire bundle is rejected.
+    pub(super) async fn simulate_bundle(
+        &self,
+        block_args: BlockArgs,
+        txs: Vec<L2Tx>,
+        state_override: Option<StateOverride>,
+    ) -> Result<Vec<SimulatedTransaction>, SubmitTxError> {
+        let vm_permit = self
+            .0
+            .vm_concurrency_limiter
+            .acquire(VmInvocationKind::Call)
+            .await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let results = self
+            .0
+            .executor
+            .execute_bundle_eth_call(
+                vm_permit,
+                self.shared_args().await,
+                self.0.replica_connection_pool.clone(),
+                txs,
+                block_args,
+                vm_execution_cache_misses_limit,
+                state_override,
+            )
+            .await?;
+        results
+            .into_iter()
+            .map(result::into_simulated_transaction)
+            .collect()
+    }
+
+    /// Runs the validation step of a transaction on top of the pending block, checking validation rules
+    /// for the paymaster only. Unlike transaction submission, returns validation failures as a part of the result.
+    pub(super) async fn validate_paymaster(
+        &self,
+        tx: L2Tx,
+    ) -> Result<PaymasterValidation, SubmitTxError> {
+        let paymaster = tx.common_data.paymaster_params.paymaster;
+        let fee = &tx.common_data.fee;
+        // Transaction fee params are not validated at this point, so they may be arbitrarily large.
+        let required_funds = fee
+            .gas_limit
+            .checked_mul(fee.max_fee_per_gas)
+            .ok_or_else(|| {
+                if fee.max_fee_per_gas > U256::from(u32::MAX) {
+                    SubmitTxError::FeePerGasTooHigh
+                } else {
+                    SubmitTxError::GasLimitIsTooBig {
+                        provided: fee.gas_limit,
+                        max_allowed: MAX_L2_TX_GAS_LIMIT.into(),
+                    }
+                }
+            })?;
+        let required_allowance = tx
+            .common_data
+            .paymaster_params
+            .approval_based_allowance()
+            .map(|(token, min_allowance)| PaymasterAllowance {
+                token,
+                min_allowance,
+            });
+        let paymaster_balance = self.get_balance(&paymaster).await?;
+
+        let mut connection = self.acquire_replica_connection().await?;
+        let block_args = BlockArgs::pending(&mut connection).await?;
+        drop(connection);
+        let fee_input = self.0.batch_fee_input_provider.get_batch_fee_input().await;
+        let shared_args = self.shared_args_for_gas_estimate(fee_input);
+
+        let vm_permit = self
+            .0
+            .vm_concurrency_limiter
+            .acquire(VmInvocationKind::Call)
+            .await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+        let computational_gas_limit = self.0.sender_config.validation_computational_gas_limit;
+        let outcome = self
+            .0
+            .executor
+            .validate_paymaster_in_sandbox(
+                self.0.replica_connection_pool.clone(),
+                vm_permit,
+                tx,
+                shared_args,
+                block_args,
+                computational_gas_limit,
+                &self.0.validation_allow_list,
+            )
+            .await?;
+
+        let (error, violation) = match &outcome.result {
+            Ok(()) => (None, None),
+            Err(validator::ValidationError::FailedTx(reason)) => (Some(reason.to_string()), None),
+            Err(validator::ValidationError::ViolatedRule(rule)) => {
+                (Some(rule.to_string()), Some(rule.into()))
+            }
+        };
+        Ok(PaymasterValidation {
+            success: outcome.result.is_ok(),
+            error,
+            violation,
+            gas_used: outcome.gas_used.into(),
+            computational_gas_used: outcome.computational_gas_used,
+            computational_gas_limit,
+            required_funds,
+            paymaster_balance,
+            required_allowance,
+        })
+    }
+
+    /// Returns the batch fee input used by the API, i.e., with the configured scale factor applied.
+    pub(crate) async fn scaled_batch_fee_input(&self) -> BatchFeeInput {
+        // For now, both the L1 gas price and the L1 pubdata price are scaled with the same coefficient
+        self.0
+            .batch_fee_input_provider
+            .get_batch_fee_input_scaled(
+                self.0.sender_config.gas_price_scale_factor,
+                self.0.sender_config.gas_price_scale_factor,
+            )
+            .await
+    }
+
     pub async fn gas_price(&self) -> anyhow::Result<u64> {
         let mut connection = self.acquire_replica_connection().await?;
         let protocol_version = pending_protocol_version(&mut connection)
@@ -892,14 +1483,7 @@ impl TxSender {
         drop(connection);
 
         let (base_fee, _) = derive_base_fee_and_gas_per_pubdata(
-            // For now, both the L1 gas price and the L1 pubdata price are scaled with the same coefficient
-            self.0
-                .batch_fee_input_provider
-                .get_batch_fee_input_scaled(
-                    self.0.sender_config.gas_price_scale_factor,
-                    self.0.sender_config.gas_price_scale_factor,
-                )
-                .await,
+            self.scaled_batch_fee_input().await,
             protocol_version.into(),
         );
         Ok(base_fee)
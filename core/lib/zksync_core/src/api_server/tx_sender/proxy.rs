@@ -8,7 +8,7 @@ use std::{
 use tokio::sync::{watch, RwLock};
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, ConnectionPool};
 use zksync_types::{
-    api::{BlockId, Transaction, TransactionDetails, TransactionId},
+    api::{BlockId, Transaction, TransactionDeadline, TransactionDetails, TransactionId},
     fee::TransactionExecutionMetrics,
     l2::L2Tx,
     Address, Nonce, H256,
@@ -123,11 +123,23 @@ impl TxProxy {
         }
     }
 
-    async fn submit_tx_impl(&self, tx: &L2Tx) -> EnrichedClientResult<H256> {
+    async fn submit_tx_impl(
+        &self,
+        tx: &L2Tx,
+        deadline: Option<TransactionDeadline>,
+    ) -> EnrichedClientResult<H256> {
         let input_data = tx.common_data.input_data().expect("raw tx is absent");
         let raw_tx = zksync_types::Bytes(input_data.to_vec());
         let tx_hash = tx.hash();
         tracing::info!("Proxying tx {tx_hash:?}");
+        if let Some(deadline) = deadline {
+            return self
+                .client
+                .send_raw_transaction_with_deadline(raw_tx, deadline)
+                .rpc_context("send_raw_transaction_with_deadline")
+                .with_arg("tx_hash", &tx_hash)
+                .await;
+        }
         self.client
             .send_raw_transaction(raw_tx)
             .rpc_context("send_raw_transaction")
@@ -221,12 +233,13 @@ impl TxSink for TxProxy {
         &self,
         tx: L2Tx,
         _execution_metrics: TransactionExecutionMetrics,
+        deadline: Option<TransactionDeadline>,
     ) -> Result<L2TxSubmissionResult, SubmitTxError> {
         // We're running an external node: we have to proxy the transaction to the main node.
         // But before we do that, save the tx to cache in case someone will request it
         // Before it reaches the main node.
         self.save_tx(tx.clone()).await;
-        self.submit_tx_impl(&tx).await?;
+        self.submit_tx_impl(&tx, deadline).await?;
         // Now, after we are sure that the tx is on the main node, remove it from cache
         // since we don't want to store txs that might have been replaced or otherwise removed
         // from the mempool.
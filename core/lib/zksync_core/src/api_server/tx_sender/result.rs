@@ -1,7 +1,13 @@
 use multivm::interface::{ExecutionResult, VmExecutionResultAndLogs};
+use serde_json::json;
 use thiserror::Error;
-use zksync_types::{l2::error::TxCheckError, U256};
-use zksync_web3_decl::error::EnrichedClientError;
+use zksync_types::{
+    api::{SimulatedLog, SimulatedStorageDiff, SimulatedTransaction},
+    l2::error::TxCheckError,
+    U256,
+};
+use zksync_utils::u256_to_h256;
+use zksync_web3_decl::error::{EnrichedClientError, TxValidationErrorData};
 
 use crate::api_server::execution_sandbox::{SandboxExecutionError, ValidationError};
 
@@ -19,7 +25,9 @@ pub enum SubmitTxError {
     #[error("execution reverted{}{}" , if .0.is_empty() { "" } else { ": " }, .0)]
     ExecutionReverted(String, Vec<u8>),
     #[error("exceeds block gas limit")]
-    GasLimitIsTooBig,
+    GasLimitIsTooBig { provided: U256, max_allowed: U256 },
+    #[error("gas per pubdata limit is too big")]
+    GasPerPubdataLimitIsTooBig { provided: U256, max_allowed: U256 },
     #[error("{0}")]
     Unexecutable(String),
     #[error("too many transactions")]
@@ -39,9 +47,12 @@ pub enum SubmitTxError {
     #[error("invalid sender. can't start a transaction from a non-account")]
     FromIsNotAnAccount,
     #[error("max fee per gas less than block base fee")]
-    MaxFeePerGasTooLow,
+    MaxFeePerGasTooLow { provided: U256, required: U256 },
     #[error("max priority fee per gas higher than max fee per gas")]
-    MaxPriorityFeeGreaterThanMaxFee,
+    MaxPriorityFeeGreaterThanMaxFee {
+        max_priority_fee_per_gas: U256,
+        max_fee_per_gas: U256,
+    },
     #[error(
         "virtual machine entered unexpected state. please contact developers and provide transaction details \
         that caused this error. Error description: {0}"
@@ -60,16 +71,43 @@ pub enum SubmitTxError {
     /// InsufficientFundsForTransfer is returned if the transaction sender doesn't
     /// have enough funds for transfer.
     #[error("insufficient balance for transfer")]
-    InsufficientFundsForTransfer,
+    InsufficientFundsForTransfer { balance: U256, value: U256 },
     /// IntrinsicGas is returned if the transaction is specified to use less gas
     /// than required to start the invocation.
     #[error("intrinsic gas too low")]
-    IntrinsicGas,
+    IntrinsicGas { provided: U256, required: U256 },
     /// Error returned from main node
     #[error("{0}")]
     ProxyError(#[from] EnrichedClientError),
     #[error("not enough gas to publish compressed bytecodes")]
     FailedToPublishCompressedBytecodes,
+    /// The transaction doesn't fit into the bootloader memory allocated for transaction encodings.
+    #[error(
+        "transaction encoding is too large: {provided} words, while only {max_allowed} allowed"
+    )]
+    TxEncodingTooLarge { provided: usize, max_allowed: usize },
+    /// The gas limit of the transaction doesn't cover the batch overhead and intrinsic gas charged by the bootloader.
+    #[error("gas limit does not cover transaction overhead: provided {provided}, required at least {required}")]
+    NotEnoughGasForTxOverhead { provided: U256, required: U256 },
+    /// The gas per pubdata limit of the transaction is lower than the gas per pubdata byte charged in the batch.
+    #[error("gas per pubdata limit is too low: provided {provided}, required at least {required}")]
+    GasPerPubdataLimitTooLow { provided: U256, required: U256 },
+    /// Gas required for the sandbox execution (e.g., `eth_call` or gas estimation), or the gas explicitly provided
+    /// in the call request, exceeds the gas cap configured for the API client.
+    #[error("gas required exceeds allowance ({cap})")]
+    GasCapExceeded { provided: Option<U256>, cap: u32 },
+    /// The transaction uses the operator paymaster, but doesn't call a sponsored contract or function.
+    #[error("transaction is not eligible for sponsorship")]
+    SponsorshipNotAllowed,
+    /// The maximum fee of a sponsored transaction exceeds the remaining sponsorship budget of the operator.
+    #[error("sponsorship budget exceeded: fee {subsidy}, remaining budget {remaining}")]
+    SponsorshipBudgetExceeded { subsidy: U256, remaining: U256 },
+    /// The maximum fee of a sponsored transaction exceeds the remaining daily sponsorship cap of the initiator.
+    #[error("daily sponsorship cap exceeded: fee {subsidy}, remaining cap {remaining}")]
+    SponsorshipDailyCapExceeded { subsidy: U256, remaining: U256 },
+    /// The inclusion deadline of the transaction has already passed.
+    #[error("transaction inclusion deadline has already passed")]
+    DeadlinePassed,
     /// Catch-all internal error (e.g., database error) that should not be exposed to the caller.
     #[error("internal error")]
     Internal(#[from] anyhow::Error),
@@ -83,7 +121,8 @@ impl SubmitTxError {
             Self::IncorrectTx(_) => "incorrect-tx",
             Self::NotEnoughBalanceForFeeValue(_, _, _) => "not-enough-balance-for-fee",
             Self::ExecutionReverted(_, _) => "execution-reverted",
-            Self::GasLimitIsTooBig => "gas-limit-is-too-big",
+            Self::GasLimitIsTooBig { .. } => "gas-limit-is-too-big",
+            Self::GasPerPubdataLimitIsTooBig { .. } => "gas-per-pubdata-limit-is-too-big",
             Self::Unexecutable(_) => "unexecutable",
             Self::RateLimitExceeded => "rate-limit-exceeded",
             Self::ServerShuttingDown => "shutting-down",
@@ -93,21 +132,129 @@ impl SubmitTxError {
             Self::PaymasterValidationFailed(_) => "failed-paymaster-validation",
             Self::PrePaymasterPreparationFailed(_) => "failed-prepaymaster-preparation",
             Self::FromIsNotAnAccount => "from-is-not-an-account",
-            Self::MaxFeePerGasTooLow => "max-fee-per-gas-too-low",
-            Self::MaxPriorityFeeGreaterThanMaxFee => "max-priority-fee-greater-than-max-fee",
+            Self::MaxFeePerGasTooLow { .. } => "max-fee-per-gas-too-low",
+            Self::MaxPriorityFeeGreaterThanMaxFee { .. } => "max-priority-fee-greater-than-max-fee",
             Self::UnexpectedVMBehavior(_) => "unexpected-vm-behavior",
             Self::UnrealisticPubdataPriceLimit => "unrealistic-pubdata-price-limit",
             Self::TooManyFactoryDependencies(_, _) => "too-many-factory-dependencies",
             Self::FeePerGasTooHigh => "gas-price-limit-too-high",
             Self::FeePerPubdataByteTooHigh => "pubdata-price-limit-too-high",
-            Self::InsufficientFundsForTransfer => "insufficient-funds-for-transfer",
-            Self::IntrinsicGas => "intrinsic-gas",
+            Self::InsufficientFundsForTransfer { .. } => "insufficient-funds-for-transfer",
+            Self::IntrinsicGas { .. } => "intrinsic-gas",
             Self::ProxyError(_) => "proxy-error",
             Self::FailedToPublishCompressedBytecodes => "failed-to-publish-compressed-bytecodes",
+            Self::TxEncodingTooLarge { .. } => "tx-encoding-too-large",
+            Self::NotEnoughGasForTxOverhead { .. } => "not-enough-gas-for-tx-overhead",
+            Self::GasPerPubdataLimitTooLow { .. } => "gas-per-pubdata-limit-too-low",
+            Self::GasCapExceeded { .. } => "gas-cap-exceeded",
+            Self::SponsorshipNotAllowed => "sponsorship-not-allowed",
+            Self::SponsorshipBudgetExceeded { .. } => "sponsorship-budget-exceeded",
+            Self::SponsorshipDailyCapExceeded { .. } => "sponsorship-daily-cap-exceeded",
+            Self::DeadlinePassed => "deadline-passed",
             Self::Internal(_) => "internal",
         }
     }
 
+    /// Returns a stable numeric code for this error. Codes are a part of the public API and must never be reused
+    /// for other errors; new errors should receive new codes.
+    pub fn error_code(&self) -> u16 {
+        match self {
+            Self::NonceIsTooHigh(_, _, _) => 1,
+            Self::NonceIsTooLow(_, _, _) => 2,
+            Self::IncorrectTx(_) => 3,
+            Self::NotEnoughBalanceForFeeValue(_, _, _) => 4,
+            Self::ExecutionReverted(_, _) => 5,
+            Self::GasLimitIsTooBig { .. } => 6,
+            Self::Unexecutable(_) => 7,
+            Self::RateLimitExceeded => 8,
+            Self::ServerShuttingDown => 9,
+            Self::BootloaderFailure(_) => 10,
+            Self::ValidationFailed(_) => 11,
+            Self::FailedToChargeFee(_) => 12,
+            Self::PaymasterValidationFailed(_) => 13,
+            Self::PrePaymasterPreparationFailed(_) => 14,
+            Self::FromIsNotAnAccount => 15,
+            Self::MaxFeePerGasTooLow { .. } => 16,
+            Self::MaxPriorityFeeGreaterThanMaxFee { .. } => 17,
+            Self::UnexpectedVMBehavior(_) => 18,
+            Self::UnrealisticPubdataPriceLimit => 19,
+            Self::TooManyFactoryDependencies(_, _) => 20,
+            Self::FeePerGasTooHigh => 21,
+            Self::FeePerPubdataByteTooHigh => 22,
+            Self::InsufficientFundsForTransfer { .. } => 23,
+            Self::IntrinsicGas { .. } => 24,
+            Self::ProxyError(_) => 25,
+            Self::FailedToPublishCompressedBytecodes => 26,
+            Self::Internal(_) => 27,
+            Self::GasPerPubdataLimitIsTooBig { .. } => 28,
+            Self::TxEncodingTooLarge { .. } => 29,
+            Self::NotEnoughGasForTxOverhead { .. } => 30,
+            Self::GasPerPubdataLimitTooLow { .. } => 31,
+            Self::GasCapExceeded { .. } => 32,
+            Self::SponsorshipNotAllowed => 33,
+            Self::SponsorshipBudgetExceeded { .. } => 34,
+            Self::SponsorshipDailyCapExceeded { .. } => 35,
+            Self::DeadlinePassed => 36,
+        }
+    }
+
+    /// Returns machine-readable data for this error. The violated constraint is the same as [`Self::prom_error_code()`].
+    pub fn validation_data(&self) -> TxValidationErrorData {
+        let (required, provided) = match self {
+            Self::NonceIsTooHigh(min, max, actual) | Self::NonceIsTooLow(min, max, actual) => {
+                (Some(json!({ "min": min, "max": max })), Some(json!(actual)))
+            }
+            Self::NotEnoughBalanceForFeeValue(balance, fee, value) => (
+                Some(json!(fee.saturating_add(*value))),
+                Some(json!(balance)),
+            ),
+            Self::GasLimitIsTooBig {
+                provided,
+                max_allowed,
+            }
+            | Self::GasPerPubdataLimitIsTooBig {
+                provided,
+                max_allowed,
+            } => (Some(json!({ "max": max_allowed })), Some(json!(provided))),
+            Self::MaxFeePerGasTooLow { provided, required }
+            | Self::IntrinsicGas { provided, required }
+            | Self::NotEnoughGasForTxOverhead { provided, required }
+            | Self::GasPerPubdataLimitTooLow { provided, required } => {
+                (Some(json!({ "min": required })), Some(json!(provided)))
+            }
+            Self::MaxPriorityFeeGreaterThanMaxFee {
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+            } => (
+                Some(json!({ "max": max_fee_per_gas })),
+                Some(json!(max_priority_fee_per_gas)),
+            ),
+            Self::TooManyFactoryDependencies(provided, allowed)
+            | Self::TxEncodingTooLarge {
+                provided,
+                max_allowed: allowed,
+            } => (Some(json!({ "max": allowed })), Some(json!(provided))),
+            Self::InsufficientFundsForTransfer { balance, value } => {
+                (Some(json!(value)), Some(json!(balance)))
+            }
+            Self::GasCapExceeded { provided, cap } => {
+                (Some(json!({ "max": cap })), provided.map(|gas| json!(gas)))
+            }
+            Self::SponsorshipBudgetExceeded { subsidy, remaining }
+            | Self::SponsorshipDailyCapExceeded { subsidy, remaining } => {
+                (Some(json!({ "max": remaining })), Some(json!(subsidy)))
+            }
+            _ => (None, None),
+        };
+
+        TxValidationErrorData {
+            code: self.error_code(),
+            constraint: self.prom_error_code().to_owned(),
+            required,
+            provided,
+        }
+    }
+
     pub fn data(&self) -> Vec<u8> {
         if let Self::ExecutionReverted(_, data) = self {
             data.clone()
@@ -174,3 +321,75 @@ impl ApiCallResult for VmExecutionResultAndLogs {
         }
     }
 }
+
+/// Converts the result of a transaction executed as a part of a bundle into the API representation.
+/// Halted transactions are converted into an error.
+pub(super) fn into_simulated_transaction(
+    result: VmExecutionResultAndLogs,
+) -> Result<SimulatedTransaction, SubmitTxError> {
+    let (success, output, revert_reason) = match result.result {
+        ExecutionResult::Success { output } => (true, output, None),
+        ExecutionResult::Revert { output } => {
+            (false, vec![], Some(output.to_user_friendly_string()))
+        }
+        ExecutionResult::Halt { reason } => {
+            let output: SandboxExecutionError = reason.into();
+            return Err(output.into());
+        }
+    };
+
+    let logs = result
+        .logs
+        .events
+        .into_iter()
+        .map(|event| SimulatedLog {
+            address: event.address,
+            topics: event.indexed_topics,
+            data: event.value.into(),
+        })
+        .collect();
+
+    // Storage logs may contain several writes (including rolled back ones) to the same slot;
+    // we only report the final value of each modified slot.
+    let mut state_diffs: Vec<(SimulatedStorageDiff, U256)> = vec![];
+    for log in result.logs.storage_logs {
+        let query = &log.log_query;
+        if !query.rw_flag {
+            continue;
+        }
+        let key = u256_to_h256(query.key);
+        let new_value = if query.rollback {
+            query.read_value
+        } else {
+            query.written_value
+        };
+        let existing_diff = state_diffs
+            .iter_mut()
+            .find(|(diff, _)| diff.address == query.address && diff.key == key);
+        if let Some((diff, _)) = existing_diff {
+            diff.value = u256_to_h256(new_value);
+        } else {
+            let diff = SimulatedStorageDiff {
+                address: query.address,
+                key,
+                value: u256_to_h256(new_value),
+            };
+            state_diffs.push((diff, query.read_value));
+        }
+    }
+    let state_diffs = state_diffs
+        .into_iter()
+        .filter_map(|(diff, initial_value)| {
+            (diff.value != u256_to_h256(initial_value)).then_some(diff)
+        })
+        .collect();
+
+    Ok(SimulatedTransaction {
+        success,
+        output: output.into(),
+        revert_reason,
+        gas_used: result.statistics.gas_used.into(),
+        logs,
+        state_diffs,
+    })
+}
@@ -0,0 +1,290 @@
+//! Transactions sponsored by the operator. Transactions calling configured contracts or functions can use
+//! the operator paymaster, so that their initiators don't pay fees. Fees subsidized by the operator are persisted
+//! in Postgres and are accounted against the operator budget and per-account daily caps.
+//!
+//! Subsidies are reserved based on the maximum transaction fee when a transaction is submitted. Reservations
+//! are settled to the actual fee, or released for rejected or replaced transactions by [`SponsorshipReconciler`].
+
+use std::{collections::HashSet, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::api::{FunctionSelector, SponsoredCall, Web3JsonRpcConfig};
+use zksync_dal::{sponsored_transactions_dal::SettledSubsidies, ConnectionPool};
+use zksync_types::{l2::L2Tx, Address, H256, U256};
+
+use super::SubmitTxError;
+
+const GWEI: u64 = 1_000_000_000;
+
+/// Sponsorship configuration.
+#[derive(Debug, Clone)]
+pub struct SponsorshipConfig {
+    /// Operator paymaster subsidizing fees for sponsored transactions.
+    pub paymaster: Address,
+    /// Sponsored calls.
+    pub calls: HashSet<SponsoredCall>,
+    /// Total sponsorship budget (in wei).
+    pub budget: Option<U256>,
+    /// Maximum subsidy (in wei) for a single account during [`TxSponsorship::CAP_PERIOD`].
+    pub daily_cap: Option<U256>,
+}
+
+impl SponsorshipConfig {
+    /// Returns `None` if sponsorship is disabled.
+    pub fn new(config: &Web3JsonRpcConfig) -> Option<Self> {
+        let paymaster = config.sponsor_paymaster_addr?;
+        Some(Self {
+            paymaster,
+            calls: config.sponsored_calls.iter().copied().collect(),
+            budget: config
+                .sponsorship_budget_gwei
+                .map(|gwei| U256::from(gwei) * GWEI),
+            daily_cap: config
+                .sponsorship_daily_cap_gwei
+                .map(|gwei| U256::from(gwei) * GWEI),
+        })
+    }
+}
+
+/// Subsidy reserved for a sponsored transaction.
+#[derive(Debug)]
+pub(super) struct SponsoredTx {
+    tx_hash: H256,
+}
+
+/// Checks whether transactions using the operator paymaster are eligible for sponsorship, and reserves subsidies
+/// for sponsored transactions.
+#[derive(Debug)]
+pub struct TxSponsorship {
+    config: SponsorshipConfig,
+    master_pool: ConnectionPool,
+}
+
+impl TxSponsorship {
+    /// Period the per-account cap applies to.
+    const CAP_PERIOD: Duration = Duration::from_secs(86_400);
+
+    pub fn new(config: SponsorshipConfig, master_pool: ConnectionPool) -> Self {
+        Self {
+            config,
+            master_pool,
+        }
+    }
+
+    fn is_sponsored(&self, tx: &L2Tx) -> bool {
+        let contract = tx.execute.contract_address;
+        let any_function_call = SponsoredCall {
+            contract,
+            selector: None,
+        };
+        if self.config.calls.contains(&any_function_call) {
+            return true;
+        }
+        let Some(selector) = tx.execute.calldata.get(..4) else {
+            return false;
+        };
+        let function_call = SponsoredCall {
+            contract,
+            selector: Some(FunctionSelector(selector.try_into().unwrap())),
+        };
+        self.config.calls.contains(&function_call)
+    }
+
+    /// Reserves the subsidy for the transaction if it uses the operator paymaster. The subsidy is equal
+    /// to the maximum fee of the transaction; it is settled to the actual fee once the transaction is executed.
+    /// Returns `Ok(None)` if the transaction doesn't use the paymaster, or if the subsidy for it is already reserved.
+    ///
+    /// Reservations lock the running total of subsidies, so that concurrently submitted transactions
+    /// (including ones submitted to different API servers) cannot exceed the limits.
+    pub(super) async fn reserve(&self, tx: &L2Tx) -> Result<Option<SponsoredTx>, SubmitTxError> {
+        if tx.common_data.paymaster_params.paymaster != self.config.paymaster {
+            return Ok(None);
+        }
+        if !self.is_sponsored(tx) {
+            return Err(SubmitTxError::SponsorshipNotAllowed);
+        }
+
+        let tx_hash = tx.hash();
+        let initiator = tx.initiator_account();
+        let fee = &tx.common_data.fee;
+        let subsidy = fee.gas_limit.saturating_mul(fee.max_fee_per_gas);
+
+        let mut storage = self.master_pool.access_storage_tagged("api").await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .context("failed starting DB transaction")?;
+        let total_subsidy = transaction
+            .sponsored_transactions_dal()
+            .lock_total_subsidy()
+            .await
+            .context("failed locking total subsidy")?;
+        let inserted = transaction
+            .sponsored_transactions_dal()
+            .insert_sponsored_transaction(tx_hash, initiator, tx.execute.contract_address, subsidy)
+            .await
+            .context("failed reserving subsidy")?;
+        if !inserted {
+            return Ok(None);
+        }
+
+        // Subsidies returned from the DB include the subsidy for this transaction.
+        if let Some(daily_cap) = self.config.daily_cap {
+            let account_subsidy = transaction
+                .sponsored_transactions_dal()
+                .get_account_subsidy(initiator, Self::CAP_PERIOD)
+                .await
+                .context("failed getting account subsidy")?;
+            if account_subsidy > daily_cap {
+                let remaining = daily_cap.saturating_sub(account_subsidy - subsidy);
+                return Err(SubmitTxError::SponsorshipDailyCapExceeded { subsidy, remaining });
+            }
+        }
+        let new_total_subsidy = total_subsidy.saturating_add(subsidy);
+        if let Some(budget) = self.config.budget {
+            if new_total_subsidy > budget {
+                let remaining = budget.saturating_sub(total_subsidy);
+                return Err(SubmitTxError::SponsorshipBudgetExceeded { subsidy, remaining });
+            }
+        }
+        transaction
+            .sponsored_transactions_dal()
+            .set_total_subsidy(new_total_subsidy)
+            .await
+            .context("failed updating total subsidy")?;
+        transaction
+            .commit()
+            .await
+            .context("failed committing DB transaction")?;
+
+        tracing::debug!(
+            "Reserved subsidy {subsidy} for transaction {tx_hash:?} from {initiator:?}"
+        );
+        Ok(Some(SponsoredTx { tx_hash }))
+    }
+
+    /// Releases the subsidy reserved for a transaction that was not accepted to the mempool.
+    pub(super) async fn release(&self, tx: SponsoredTx) -> anyhow::Result<()> {
+        let mut storage = self.master_pool.access_storage_tagged("api").await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .context("failed starting DB transaction")?;
+        let total_subsidy = transaction
+            .sponsored_transactions_dal()
+            .lock_total_subsidy()
+            .await
+            .context("failed locking total subsidy")?;
+        let subsidy = transaction
+            .sponsored_transactions_dal()
+            .remove_sponsored_transaction(tx.tx_hash)
+            .await
+            .context("failed releasing subsidy")?;
+        let Some(subsidy) = subsidy else {
+            return Ok(()); // The subsidy was already released by the reconciler
+        };
+        transaction
+            .sponsored_transactions_dal()
+            .set_total_subsidy(total_subsidy.saturating_sub(subsidy))
+            .await
+            .context("failed updating total subsidy")?;
+        transaction
+            .commit()
+            .await
+            .context("failed committing DB transaction")?;
+        tracing::debug!(
+            "Released subsidy {subsidy} for transaction {:?}",
+            tx.tx_hash
+        );
+        Ok(())
+    }
+}
+
+/// Periodically settles subsidies reserved by [`TxSponsorship`]: subsidies for executed transactions are reduced
+/// to the actual fee, and subsidies for rejected (incl. expired) or replaced transactions are released.
+/// Safe to run on multiple API servers at once.
+#[derive(Debug)]
+pub struct SponsorshipReconciler {
+    master_pool: ConnectionPool,
+    poll_interval: Duration,
+}
+
+impl SponsorshipReconciler {
+    /// Maximum number of transactions settled in a single DB transaction.
+    const BATCH_SIZE: usize = 100;
+    /// Subsidies for transactions missing from the DB for this long are released. The timeout accounts for
+    /// the delay between reserving a subsidy and inserting the transaction into the mempool.
+    const MISSING_TX_TIMEOUT: Duration = Duration::from_secs(60);
+
+    pub fn new(master_pool: ConnectionPool) -> Self {
+        Self {
+            master_pool,
+            poll_interval: Duration::from_secs(10),
+        }
+    }
+
+    async fn settle_batch(&self) -> anyhow::Result<SettledSubsidies> {
+        let mut storage = self
+            .master_pool
+            .access_storage_tagged("sponsorship_reconciler")
+            .await?;
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .context("failed starting DB transaction")?;
+        let total_subsidy = transaction
+            .sponsored_transactions_dal()
+            .lock_total_subsidy()
+            .await
+            .context("failed locking total subsidy")?;
+        let settled = transaction
+            .sponsored_transactions_dal()
+            .settle_sponsored_transactions(Self::MISSING_TX_TIMEOUT, Self::BATCH_SIZE)
+            .await
+            .context("failed settling subsidies")?;
+        if settled.released_subsidy > U256::zero() {
+            transaction
+                .sponsored_transactions_dal()
+                .set_total_subsidy(total_subsidy.saturating_sub(settled.released_subsidy))
+                .await
+                .context("failed updating total subsidy")?;
+        }
+        transaction
+            .commit()
+            .await
+            .context("failed committing DB transaction")?;
+        Ok(settled)
+    }
+
+    /// Settles all subsidies that can be settled at the moment.
+    pub(super) async fn settle(&self) -> anyhow::Result<()> {
+        loop {
+            let settled = self.settle_batch().await?;
+            if settled.settled_count > 0 || settled.released_count > 0 {
+                tracing::debug!(
+                    "Settled subsidies for {} sponsored transactions and released subsidies for {} transactions, \
+                     releasing {} wei in total",
+                    settled.settled_count,
+                    settled.released_count,
+                    settled.released_subsidy
+                );
+            }
+            if settled.settled_count + settled.released_count < Self::BATCH_SIZE {
+                return Ok(());
+            }
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        while !*stop_receiver.borrow_and_update() {
+            self.settle().await?;
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, sponsorship reconciler is shutting down");
+        Ok(())
+    }
+}
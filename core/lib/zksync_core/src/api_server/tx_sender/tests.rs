@@ -1,12 +1,26 @@
 //! Tests for the transaction sender.
 
-use zksync_types::{get_nonce_key, L1BatchNumber, StorageLog};
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use assert_matches::assert_matches;
+use multivm::interface::{ExecutionResult, Halt};
+use test_casing::test_casing;
+use zksync_config::configs::api::{FunctionSelector, SponsoredCall};
+use zksync_types::{
+    get_intrinsic_constants, get_nonce_key, vm_trace::VmRevertReason, L1BatchNumber, StorageLog,
+};
 
-use super::*;
+use super::{sponsorship::SponsorshipReconciler, *};
 use crate::{
     api_server::execution_sandbox::{testonly::MockTransactionExecutor, VmConcurrencyBarrier},
     genesis::{ensure_genesis_state, GenesisParams},
-    utils::testonly::{create_miniblock, prepare_recovery_snapshot, MockBatchFeeParamsProvider},
+    utils::testonly::{
+        create_l2_transaction, create_miniblock, prepare_recovery_snapshot,
+        MockBatchFeeParamsProvider,
+    },
 };
 
 pub(crate) async fn create_test_tx_sender(
@@ -16,7 +30,12 @@ pub(crate) async fn create_test_tx_sender(
 ) -> (TxSender, VmConcurrencyBarrier) {
     let web3_config = Web3JsonRpcConfig::for_tests();
     let state_keeper_config = StateKeeperConfig::for_tests();
-    let tx_sender_config = TxSenderConfig::new(&state_keeper_config, &web3_config, l2_chain_id);
+    let tx_sender_config = TxSenderConfig::new(
+        &state_keeper_config,
+        &web3_config,
+        l2_chain_id,
+        get_intrinsic_constants(),
+    );
 
     let mut storage_caches = PostgresStorageCaches::new(1, 1);
     let cache_update_task = storage_caches.configure_storage_values_cache(
@@ -35,6 +54,7 @@ pub(crate) async fn create_test_tx_sender(
         pool,
         batch_fee_model_input_provider,
         storage_caches,
+        None,
     )
     .await;
 
@@ -146,3 +166,481 @@ async fn getting_nonce_for_account_after_snapshot_recovery() {
     let nonce = tx_sender.get_expected_nonce(missing_address).await.unwrap();
     assert_eq!(nonce, Nonce(0));
 }
+
+#[test]
+fn structured_validation_error_data() {
+    let err = SubmitTxError::MaxFeePerGasTooLow {
+        provided: 100.into(),
+        required: 250_000_000.into(),
+    };
+    let data = serde_json::to_value(err.validation_data()).unwrap();
+    assert_eq!(
+        data,
+        serde_json::json!({
+            "code": 16,
+            "constraint": "max-fee-per-gas-too-low",
+            "required": { "min": "0xee6b280" },
+            "provided": "0x64",
+        })
+    );
+
+    let err = SubmitTxError::NonceIsTooLow(5, 50, 3);
+    let data = err.validation_data();
+    assert_eq!(data.code, 2);
+    assert_eq!(data.constraint, "nonce-is-too-low");
+    assert_eq!(
+        data.required,
+        Some(serde_json::json!({ "min": 5, "max": 50 }))
+    );
+    assert_eq!(data.provided, Some(serde_json::json!(3)));
+
+    let data = serde_json::to_value(SubmitTxError::RateLimitExceeded.validation_data()).unwrap();
+    assert_eq!(
+        data,
+        serde_json::json!({ "code": 8, "constraint": "rate-limit-exceeded" })
+    );
+}
+
+#[test]
+fn overriding_base_system_contracts() {
+    let base_contracts = ApiContracts::load_from_disk();
+    let latest_version = ProtocolVersionId::latest();
+    let default_aa = base_contracts
+        .eth_call
+        .get_by_protocol_version(latest_version)
+        .default_aa
+        .clone();
+    let contracts_override = BaseSystemContractsOverride {
+        bootloader: Some(zksync_utils::be_words_to_bytes(&default_aa.code).into()),
+        default_aa: None,
+    };
+
+    let disabled = OverridableApiContracts::new(base_contracts.clone(), false);
+    assert_matches!(
+        disabled.set_override(contracts_override.clone()),
+        Err(Web3Error::SystemContractsOverrideDisabled)
+    );
+
+    let contracts = OverridableApiContracts::new(base_contracts.clone(), true);
+    assert_eq!(
+        contracts.override_hashes(),
+        BaseSystemContractsOverrideHashes::default()
+    );
+    let hashes = contracts.set_override(contracts_override).unwrap();
+    assert_eq!(hashes.bootloader, Some(default_aa.hash));
+    assert_eq!(hashes.default_aa, None);
+    assert_eq!(contracts.override_hashes(), hashes);
+
+    let overridden = contracts.get();
+    for multivm_contracts in [&overridden.eth_call, &overridden.estimate_gas] {
+        for version in [ProtocolVersionId::Version18, latest_version] {
+            let version_contracts = multivm_contracts.get_by_protocol_version(version);
+            assert_eq!(version_contracts.bootloader.hash, default_aa.hash);
+            assert_ne!(version_contracts.default_aa.hash, H256::zero());
+        }
+    }
+    assert_eq!(
+        overridden
+            .eth_call
+            .get_by_protocol_version(latest_version)
+            .default_aa
+            .hash,
+        default_aa.hash
+    );
+
+    let invalid_override = BaseSystemContractsOverride {
+        bootloader: None,
+        default_aa: Some(vec![0; 64].into()),
+    };
+    assert_matches!(
+        contracts.set_override(invalid_override),
+        Err(Web3Error::InvalidSystemContractBytecode(
+            "default account",
+            _
+        ))
+    );
+    // The previous override should be retained.
+    assert_eq!(contracts.override_hashes(), hashes);
+
+    contracts.reset_override().unwrap();
+    assert_eq!(
+        contracts.override_hashes(),
+        BaseSystemContractsOverrideHashes::default()
+    );
+    let reset = contracts.get();
+    assert_eq!(
+        reset.eth_call.get_by_protocol_version(latest_version),
+        base_contracts
+            .eth_call
+            .get_by_protocol_version(latest_version)
+    );
+}
+
+fn sponsored_tx(paymaster: Address, contract_address: Address) -> L2Tx {
+    // Has a maximum fee of 1,000 wei.
+    let mut tx = create_l2_transaction(1, 50);
+    tx.common_data.paymaster_params.paymaster = paymaster;
+    tx.execute.contract_address = contract_address;
+    tx
+}
+
+#[tokio::test]
+async fn reserving_subsidies_for_sponsored_transactions() {
+    let pool = ConnectionPool::test_pool().await;
+    let paymaster = Address::repeat_byte(0x01);
+    let sponsored_contract = Address::repeat_byte(0x10);
+    let sponsored_function_contract = Address::repeat_byte(0x11);
+    let sponsored_selector = [0xa9, 0x05, 0x9c, 0xbb];
+    let config = sponsorship::SponsorshipConfig {
+        paymaster,
+        calls: HashSet::from([
+            SponsoredCall {
+                contract: sponsored_contract,
+                selector: None,
+            },
+            SponsoredCall {
+                contract: sponsored_function_contract,
+                selector: Some(FunctionSelector(sponsored_selector)),
+            },
+        ]),
+        budget: Some(1_500.into()),
+        daily_cap: Some(1_000.into()),
+    };
+    let sponsorship = TxSponsorship::new(config, pool.clone());
+
+    let unsponsored_tx = create_l2_transaction(1, 50);
+    assert!(sponsorship
+        .reserve(&unsponsored_tx)
+        .await
+        .unwrap()
+        .is_none());
+    let tx = sponsored_tx(paymaster, Address::repeat_byte(0x20));
+    assert_matches!(
+        sponsorship.reserve(&tx).await,
+        Err(SubmitTxError::SponsorshipNotAllowed)
+    );
+    // The sponsored selector must not be sponsored for other contracts.
+    let mut tx = sponsored_tx(paymaster, Address::repeat_byte(0x20));
+    tx.execute.calldata = sponsored_selector.to_vec();
+    assert_matches!(
+        sponsorship.reserve(&tx).await,
+        Err(SubmitTxError::SponsorshipNotAllowed)
+    );
+    let tx = sponsored_tx(paymaster, sponsored_function_contract);
+    assert_matches!(
+        sponsorship.reserve(&tx).await,
+        Err(SubmitTxError::SponsorshipNotAllowed)
+    );
+
+    let tx = sponsored_tx(paymaster, sponsored_contract);
+    let reserved = sponsorship.reserve(&tx).await.unwrap();
+    assert!(reserved.is_some());
+    // The subsidy for the same transaction should not be reserved twice.
+    assert!(sponsorship.reserve(&tx).await.unwrap().is_none());
+
+    let mut same_account_tx = sponsored_tx(paymaster, sponsored_contract);
+    same_account_tx.common_data.initiator_address = tx.initiator_account();
+    assert_matches!(
+        sponsorship.reserve(&same_account_tx).await,
+        Err(SubmitTxError::SponsorshipDailyCapExceeded { subsidy, remaining })
+            if subsidy == U256::from(1_000) && remaining.is_zero()
+    );
+
+    let mut other_account_tx = sponsored_tx(paymaster, sponsored_function_contract);
+    other_account_tx.execute.calldata = sponsored_selector.to_vec();
+    assert_matches!(
+        sponsorship.reserve(&other_account_tx).await,
+        Err(SubmitTxError::SponsorshipBudgetExceeded { subsidy, remaining })
+            if subsidy == U256::from(1_000) && remaining == U256::from(500)
+    );
+
+    sponsorship.release(reserved.unwrap()).await.unwrap();
+    assert!(sponsorship
+        .reserve(&other_account_tx)
+        .await
+        .unwrap()
+        .is_some());
+    let mut storage = pool.access_storage().await.unwrap();
+    let total_subsidy = storage
+        .sponsored_transactions_dal()
+        .get_total_subsidy()
+        .await
+        .unwrap();
+    assert_eq!(total_subsidy, U256::from(1_000));
+}
+
+#[tokio::test]
+async fn releasing_subsidies_for_rejected_transactions() {
+    let pool = ConnectionPool::test_pool().await;
+    let paymaster = Address::repeat_byte(0x01);
+    let sponsored_contract = Address::repeat_byte(0x10);
+    let config = sponsorship::SponsorshipConfig {
+        paymaster,
+        calls: HashSet::from([SponsoredCall {
+            contract: sponsored_contract,
+            selector: None,
+        }]),
+        budget: Some(1_000.into()),
+        daily_cap: None,
+    };
+    let sponsorship = TxSponsorship::new(config, pool.clone());
+    let reconciler = SponsorshipReconciler::new(pool.clone());
+
+    let tx = sponsored_tx(paymaster, sponsored_contract);
+    assert!(sponsorship.reserve(&tx).await.unwrap().is_some());
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .transactions_dal()
+        .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+        .await;
+    // Pending transactions must not be settled.
+    reconciler.settle().await.unwrap();
+    let other_tx = sponsored_tx(paymaster, sponsored_contract);
+    assert_matches!(
+        sponsorship.reserve(&other_tx).await,
+        Err(SubmitTxError::SponsorshipBudgetExceeded { .. })
+    );
+
+    storage
+        .transactions_dal()
+        .mark_tx_as_rejected(tx.hash(), "rejected")
+        .await;
+    reconciler.settle().await.unwrap();
+    let total_subsidy = storage
+        .sponsored_transactions_dal()
+        .get_total_subsidy()
+        .await
+        .unwrap();
+    assert_eq!(total_subsidy, U256::zero());
+    assert!(sponsorship.reserve(&other_tx).await.unwrap().is_some());
+}
+
+fn test_bootloader_params() -> BootloaderParams {
+    BootloaderParams {
+        vm_version: ProtocolVersionId::latest().into(),
+        fee_input: BatchFeeInput::l1_pegged(50_000_000_000, 250_000_000),
+        intrinsic_gas: get_intrinsic_constants().l2_tx_intrinsic_gas,
+    }
+}
+
+#[test]
+fn diagnosing_bootloader_failures() {
+    let params = test_bootloader_params();
+    let (_, gas_per_pubdata) =
+        derive_base_fee_and_gas_per_pubdata(params.fee_input, params.vm_version);
+    let mut tx = create_l2_transaction(250_000_000, gas_per_pubdata);
+    params.check_encoding_size(&tx.clone().into()).unwrap();
+
+    let err = params
+        .diagnose_halt(&tx, &Halt::NotEnoughGasProvided)
+        .unwrap();
+    assert_matches!(
+        err,
+        SubmitTxError::NotEnoughGasForTxOverhead { provided, required }
+            if provided == 1_000.into() && required > provided
+    );
+    let data = err.validation_data();
+    assert_eq!(data.code, 30);
+    assert_eq!(data.constraint, "not-enough-gas-for-tx-overhead");
+
+    tx.common_data.fee.gas_limit = 10_000_000.into();
+    let diagnosed = params.diagnose_halt(&tx, &Halt::NotEnoughGasProvided);
+    assert!(diagnosed.is_none(), "{diagnosed:?}");
+
+    tx.common_data.fee.gas_per_pubdata_limit = 1.into();
+    let halt = Halt::UnexpectedVMBehavior("UnacceptablePubdataPrice".to_owned());
+    let err = params.diagnose_halt(&tx, &halt).unwrap();
+    assert_matches!(
+        err,
+        SubmitTxError::GasPerPubdataLimitTooLow { provided, required }
+            if provided == 1.into() && required == gas_per_pubdata.into()
+    );
+
+    // Other unexpected VM behaviors must not be attributed to the gas per pubdata limit.
+    let halt =
+        Halt::UnexpectedVMBehavior("Assertion error: Protocol upgrade tx not first".to_owned());
+    let diagnosed = params.diagnose_halt(&tx, &halt);
+    assert!(diagnosed.is_none(), "{diagnosed:?}");
+
+    let diagnosed = params.diagnose_halt(&tx, &Halt::FromIsNotAnAccount);
+    assert!(diagnosed.is_none(), "{diagnosed:?}");
+}
+
+/// Creates an executor for which transactions succeed iff their gas limit is not less than `gas_limit_threshold`.
+fn executor_with_gas_threshold(
+    gas_limit_threshold: u64,
+    execution_count: Arc<AtomicUsize>,
+) -> TransactionExecutor {
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_call_responses(move |tx, _| {
+        execution_count.fetch_add(1, Ordering::Relaxed);
+        if tx.gas_limit() >= U256::from(gas_limit_threshold) {
+            ExecutionResult::Success { output: vec![] }
+        } else {
+            ExecutionResult::Revert {
+                output: VmRevertReason::VmError,
+            }
+        }
+    });
+    tx_executor.into()
+}
+
+async fn create_tx_sender_for_gas_estimation(
+    gas_limit_threshold: u64,
+    optimize_search: bool,
+) -> (TxSender, Arc<AtomicUsize>) {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let l2_chain_id = L2ChainId::default();
+    ensure_genesis_state(&mut storage, l2_chain_id, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let execution_count = Arc::<AtomicUsize>::default();
+    let tx_executor = executor_with_gas_threshold(gas_limit_threshold, execution_count.clone());
+    let (mut tx_sender, _) = create_test_tx_sender(pool, l2_chain_id, tx_executor).await;
+    Arc::get_mut(&mut tx_sender.0)
+        .unwrap()
+        .sender_config
+        .estimate_gas_optimize_search = optimize_search;
+    (tx_sender, execution_count)
+}
+
+fn estimated_tx() -> Transaction {
+    let mut tx = create_l2_transaction(10, 100);
+    // Make the mock executor treat the transaction as a call.
+    tx.common_data.input = None;
+    tx.into()
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn estimating_gas(optimize_search: bool) {
+    for gas_limit_threshold in [10_000, 100_000, 1_000_000] {
+        let (tx_sender, _) =
+            create_tx_sender_for_gas_estimation(gas_limit_threshold, optimize_search).await;
+        let fee = tx_sender
+            .get_txs_fee_in_wei(estimated_tx(), 1.0, 1_000, None)
+            .await
+            .unwrap();
+        assert!(
+            fee.gas_limit >= gas_limit_threshold.into(),
+            "{fee:?} for threshold {gas_limit_threshold}"
+        );
+        assert!(
+            fee.gas_limit < (gas_limit_threshold * 2).into(),
+            "{fee:?} for threshold {gas_limit_threshold}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn optimized_gas_estimation_fails_fast_for_unexecutable_tx() {
+    let (tx_sender, execution_count) = create_tx_sender_for_gas_estimation(u64::MAX, true).await;
+    let err = tx_sender
+        .get_txs_fee_in_wei(estimated_tx(), 1.0, 1_000, None)
+        .await
+        .unwrap_err();
+    assert_matches!(err, SubmitTxError::ExecutionReverted(..));
+    // The initial execution with the max gas limit + the final estimation step.
+    assert_eq!(execution_count.load(Ordering::Relaxed), 2);
+}
+
+#[tokio::test]
+async fn gas_estimation_results_are_cached() {
+    let (tx_sender, execution_count) = create_tx_sender_for_gas_estimation(100_000, false).await;
+    let tx = estimated_tx();
+    let fee = tx_sender
+        .get_txs_fee_in_wei(tx.clone(), 1.0, 1_000, None)
+        .await
+        .unwrap();
+    let execution_count_after_estimation = execution_count.load(Ordering::Relaxed);
+    assert!(execution_count_after_estimation > 1);
+
+    let cached_fee = tx_sender
+        .get_txs_fee_in_wei(tx.clone(), 1.0, 1_000, None)
+        .await
+        .unwrap();
+    assert_eq!(cached_fee, fee);
+    assert_eq!(
+        execution_count.load(Ordering::Relaxed),
+        execution_count_after_estimation
+    );
+
+    // Changing estimation params should invalidate the cache.
+    tx_sender
+        .get_txs_fee_in_wei(tx, 1.5, 1_000, None)
+        .await
+        .unwrap();
+    assert!(execution_count.load(Ordering::Relaxed) > execution_count_after_estimation);
+}
+
+#[tokio::test]
+async fn gas_estimation_cache_can_be_disabled() {
+    let (mut tx_sender, execution_count) =
+        create_tx_sender_for_gas_estimation(100_000, false).await;
+    Arc::get_mut(&mut tx_sender.0).unwrap().gas_estimation_cache = GasEstimationCache::new(0);
+    let tx = estimated_tx();
+    let fee = tx_sender
+        .get_txs_fee_in_wei(tx.clone(), 1.0, 1_000, None)
+        .await
+        .unwrap();
+    let execution_count_after_estimation = execution_count.load(Ordering::Relaxed);
+
+    let repeated_fee = tx_sender
+        .get_txs_fee_in_wei(tx, 1.0, 1_000, None)
+        .await
+        .unwrap();
+    assert_eq!(repeated_fee, fee);
+    assert_eq!(
+        execution_count.load(Ordering::Relaxed),
+        2 * execution_count_after_estimation
+    );
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn estimating_gas_with_gas_cap(optimize_search: bool) {
+    let (tx_sender, _) = create_tx_sender_for_gas_estimation(100_000, optimize_search).await;
+    let fee = tx_sender
+        .get_txs_fee_in_wei(estimated_tx(), 1.0, 1_000, Some(1_000_000))
+        .await
+        .unwrap();
+    assert!(fee.gas_limit >= 100_000.into(), "{fee:?}");
+    assert!(fee.gas_limit <= 1_000_000.into(), "{fee:?}");
+
+    let err = tx_sender
+        .get_txs_fee_in_wei(estimated_tx(), 1.0, 1_000, Some(50_000))
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::GasCapExceeded {
+            provided: None,
+            cap: 50_000
+        }
+    );
+}
+
+#[tokio::test]
+async fn gas_cap_does_not_mask_revert_reasons() {
+    let (mut tx_sender, _) = create_tx_sender_for_gas_estimation(100_000, false).await;
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_call_responses(|_, _| ExecutionResult::Revert {
+        output: VmRevertReason::General {
+            msg: "insufficient balance".to_owned(),
+            data: vec![],
+        },
+    });
+    Arc::get_mut(&mut tx_sender.0).unwrap().executor = tx_executor.into();
+
+    let err = tx_sender
+        .get_txs_fee_in_wei(estimated_tx(), 1.0, 1_000, Some(1_000_000))
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        SubmitTxError::ExecutionReverted(msg, _) if msg.contains("insufficient balance")
+    );
+}
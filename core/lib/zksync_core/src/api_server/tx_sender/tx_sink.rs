@@ -1,6 +1,6 @@
 use zksync_dal::transactions_dal::L2TxSubmissionResult;
 use zksync_types::{
-    api::{Transaction, TransactionDetails, TransactionId},
+    api::{Transaction, TransactionDeadline, TransactionDetails, TransactionId},
     fee::TransactionExecutionMetrics,
     l2::L2Tx,
     Address, Nonce, H256,
@@ -23,11 +23,12 @@ use super::SubmitTxError;
 /// and may be implemented as no-ops.
 #[async_trait::async_trait]
 pub trait TxSink: std::fmt::Debug + Send + Sync + 'static {
-    /// Ensures that transaction is propagated to the mempool.
+    /// Ensures that transaction is propagated to the mempool together with its optional inclusion deadline.
     async fn submit_tx(
         &self,
         tx: L2Tx,
         execution_metrics: TransactionExecutionMetrics,
+        deadline: Option<TransactionDeadline>,
     ) -> Result<L2TxSubmissionResult, SubmitTxError>;
 
     /// Attempts to look up the pending nonce for the account in the sink-specific storage.
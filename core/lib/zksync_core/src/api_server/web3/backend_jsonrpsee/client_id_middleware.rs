@@ -0,0 +1,112 @@
+use std::{
+    net::IpAddr,
+    task::{Context, Poll},
+};
+
+use axum::http::{HeaderMap, Request};
+use tokio::task::futures::TaskLocalFuture;
+use tower::{Layer, Service};
+
+use crate::api_server::execution_sandbox::ApiClientId;
+
+/// Header containing the API key of the client.
+pub(crate) const API_KEY_HEADER: &str = "x-api-key";
+/// Headers containing the client IP address set by a reverse proxy, in the order of preference.
+const FORWARDED_IP_HEADERS: [&str; 2] = ["x-forwarded-for", "x-real-ip"];
+
+fn client_id_from_headers(headers: &HeaderMap) -> ApiClientId {
+    let api_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .filter(|key| !key.is_empty());
+    if let Some(api_key) = api_key {
+        return ApiClientId::ApiKey(api_key.to_owned());
+    }
+
+    for header in FORWARDED_IP_HEADERS {
+        let ip = headers
+            .get(header)
+            .and_then(|value| value.to_str().ok())
+            // `X-Forwarded-For` may contain a list of proxies; the first entry is the original client.
+            .and_then(|value| value.split(',').next())
+            .and_then(|ip| ip.trim().parse::<IpAddr>().ok());
+        if let Some(ip) = ip {
+            return ApiClientId::Ip(ip);
+        }
+    }
+    ApiClientId::Anonymous
+}
+
+/// HTTP middleware identifying the API client for each request, so that the VM capacity can be shared
+/// fairly among clients.
+///
+/// The client identity is only available while processing the request future, so it isn't propagated
+/// to WebSocket connections (which are served by separate tasks).
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ClientIdLayer;
+
+impl<S> Layer<S> for ClientIdLayer {
+    type Service = ClientIdService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ClientIdService { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ClientIdService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<Request<B>> for ClientIdService<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = TaskLocalFuture<ApiClientId, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        let client_id = client_id_from_headers(request.headers());
+        client_id.scope(self.inner.call(request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    #[test]
+    fn identifying_clients() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_id_from_headers(&headers), ApiClientId::Anonymous);
+
+        headers.insert(
+            "x-forwarded-for",
+            HeaderValue::from_static("10.0.0.1, 192.168.0.1"),
+        );
+        headers.insert("x-real-ip", HeaderValue::from_static("10.0.0.2"));
+        assert_eq!(
+            client_id_from_headers(&headers),
+            ApiClientId::Ip([10, 0, 0, 1].into())
+        );
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("garbage"));
+        assert_eq!(
+            client_id_from_headers(&headers),
+            ApiClientId::Ip([10, 0, 0, 2].into())
+        );
+
+        headers.insert(API_KEY_HEADER, HeaderValue::from_static("key"));
+        assert_eq!(
+            client_id_from_headers(&headers),
+            ApiClientId::ApiKey("key".to_owned())
+        );
+    }
+}
@@ -5,18 +5,28 @@
 use std::fmt;
 
 use zksync_web3_decl::{
-    error::Web3Error,
-    jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned},
+    error::{TxValidationErrorData, Web3Error},
+    jsonrpsee::{
+        core::ClientError,
+        types::{error::ErrorCode, ErrorObjectOwned},
+    },
 };
 
 use crate::api_server::{tx_sender::SubmitTxError, web3::metrics::API_METRICS};
 
 pub mod batch_limiter_middleware;
+pub mod client_id_middleware;
 pub mod namespaces;
+pub mod read_replica_middleware;
+pub mod streaming_middleware;
 
 pub(crate) fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
     let data = match &err {
-        Web3Error::SubmitTransactionError(_, data) => Some(format!("0x{}", hex::encode(data))),
+        Web3Error::SubmitTransactionError(_, data) => {
+            Some(format!("0x{}", hex::encode(data)).into())
+        }
+        Web3Error::TxValidationError(_, data) => serde_json::to_value(data).ok(),
+        Web3Error::DenylistedLogsFilter(item) => serde_json::to_value(item).ok(),
         _ => None,
     };
     ErrorObjectOwned::owned(
@@ -31,15 +41,31 @@ pub(crate) fn into_jsrpc_error(err: Web3Error) -> ErrorObjectOwned {
             | Web3Error::TooManyTopics
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFeeParams(_)
+            | Web3Error::InvalidStateOverride(_)
+            | Web3Error::InvalidBundleSize(_)
+            | Web3Error::InvalidEstimationBatchSize(_)
+            | Web3Error::TooManyProofKeys(_)
+            | Web3Error::NoPaymaster
             | Web3Error::InvalidFilterBlockHash
+            | Web3Error::ProofsNotAvailable(_)
+            | Web3Error::DenylistedLogsFilter(_)
+            | Web3Error::TraceFilterBlockRangeExceeded(_)
+            | Web3Error::TraceFilterCountExceeded(_)
+            | Web3Error::SystemContractsOverrideDisabled
+            | Web3Error::InvalidSystemContractBytecode(_, _)
+            | Web3Error::PubdataModeSwitchUnavailable
+            | Web3Error::UnsupportedPubdataMode(_)
             | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
-            Web3Error::SubmitTransactionError(_, _) | Web3Error::SerializationError(_) => 3,
+            Web3Error::SubmitTransactionError(_, _)
+            | Web3Error::TxValidationError(_, _)
+            | Web3Error::SerializationError(_) => 3,
             Web3Error::PubSubTimeout => 4,
             Web3Error::RequestTimeout => 5,
             Web3Error::TreeApiUnavailable => 6,
         },
         match err {
-            Web3Error::SubmitTransactionError(message, _) => message,
+            Web3Error::SubmitTransactionError(message, _)
+            | Web3Error::TxValidationError(message, _) => message,
             _ => err.to_string(),
         },
         data,
@@ -55,9 +81,21 @@ impl SubmitTxError {
             Self::ProxyError(ref err) => {
                 // Strip internal error details that should not be exposed to the caller.
                 tracing::warn!("Error proxying call to main node in method {method_name}: {err}");
+                // Pass through validation error data returned by the main node, so that it's not lost for the caller.
+                if let ClientError::Call(err) = err.as_ref() {
+                    let data = err
+                        .data()
+                        .and_then(|data| serde_json::from_str(data.get()).ok());
+                    if let Some(data) = data {
+                        return Web3Error::TxValidationError(err.message().to_owned(), data);
+                    }
+                }
                 Web3Error::SubmitTransactionError(err.as_ref().to_string(), self.data())
             }
-            _ => Web3Error::SubmitTransactionError(self.to_string(), self.data()),
+            Self::ExecutionReverted(..) => {
+                Web3Error::SubmitTransactionError(self.to_string(), self.data())
+            }
+            _ => Web3Error::TxValidationError(self.to_string(), self.validation_data()),
         }
     }
 }
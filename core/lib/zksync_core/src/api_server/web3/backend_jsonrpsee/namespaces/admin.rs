@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use zksync_types::{
+    api::{
+        BaseSystemContractsOverride, BaseSystemContractsOverrideHashes, ValidationAllowListEntry,
+    },
+    pubdata_da::PubdataDA,
+};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::AdminNamespaceServer};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::AdminNamespace};
+
+#[async_trait]
+impl AdminNamespaceServer for AdminNamespace {
+    async fn get_validation_allow_list(&self) -> RpcResult<Vec<ValidationAllowListEntry>> {
+        self.get_validation_allow_list_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn reload_validation_allow_list(&self) -> RpcResult<Vec<ValidationAllowListEntry>> {
+        self.reload_validation_allow_list_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn set_base_system_contracts_override(
+        &self,
+        contracts: BaseSystemContractsOverride,
+    ) -> RpcResult<BaseSystemContractsOverrideHashes> {
+        self.set_base_system_contracts_override_impl(contracts)
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_base_system_contracts_override(
+        &self,
+    ) -> RpcResult<BaseSystemContractsOverrideHashes> {
+        Ok(self.get_base_system_contracts_override_impl())
+    }
+
+    async fn reset_base_system_contracts_override(&self) -> RpcResult<()> {
+        self.reset_base_system_contracts_override_impl()
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_pubdata_mode(&self) -> RpcResult<PubdataDA> {
+        self.get_pubdata_mode_impl().map_err(into_jsrpc_error)
+    }
+
+    async fn set_pubdata_mode(&self, mode: PubdataDA) -> RpcResult<()> {
+        self.set_pubdata_mode_impl(mode).map_err(into_jsrpc_error)
+    }
+}
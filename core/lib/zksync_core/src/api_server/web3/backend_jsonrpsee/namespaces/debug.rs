@@ -1,7 +1,7 @@
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, SignatureReport, TracerConfig},
     transaction_request::CallRequest,
-    H256,
+    Bytes, H256,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
@@ -52,4 +52,10 @@ impl DebugNamespaceServer for DebugNamespace {
             .await
             .map_err(into_jsrpc_error)
     }
+
+    async fn explain_signature(&self, tx_bytes: Bytes) -> RpcResult<SignatureReport> {
+        self.debug_explain_signature_impl(tx_bytes)
+            .await
+            .map_err(into_jsrpc_error)
+    }
 }
@@ -1,16 +1,16 @@
 use zksync_types::{
     api::{
-        Block, BlockId, BlockIdVariant, BlockNumber, Log, Transaction, TransactionId,
-        TransactionReceipt, TransactionVariant,
+        AccountProof, Block, BlockId, BlockIdVariant, BlockNumber, Log, StateOverride, Transaction,
+        TransactionId, TransactionReceipt, TransactionVariant,
     },
     transaction_request::CallRequest,
-    web3::types::{FeeHistory, Index, SyncState},
+    web3::types::{FeeHistory, Index},
     Address, Bytes, H256, U256, U64,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
     namespaces::eth::EthNamespaceServer,
-    types::{Filter, FilterChanges},
+    types::{Filter, FilterChanges, SyncState},
 };
 
 use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, EthNamespace};
@@ -25,8 +25,13 @@ impl EthNamespaceServer for EthNamespace {
         Ok(self.chain_id_impl())
     }
 
-    async fn call(&self, req: CallRequest, block: Option<BlockIdVariant>) -> RpcResult<Bytes> {
-        self.call_impl(req, block.map(Into::into))
+    async fn call(
+        &self,
+        req: CallRequest,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Bytes> {
+        self.call_impl(req, block.map(Into::into), state_override)
             .await
             .map_err(into_jsrpc_error)
     }
@@ -148,6 +153,17 @@ impl EthNamespaceServer for EthNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_proof(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block: Option<BlockIdVariant>,
+    ) -> RpcResult<AccountProof> {
+        self.get_proof_impl(address, keys, block.map(Into::into))
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_transaction_count(
         &self,
         address: Address,
@@ -201,7 +217,7 @@ impl EthNamespaceServer for EthNamespace {
     }
 
     async fn syncing(&self) -> RpcResult<SyncState> {
-        Ok(self.syncing_impl())
+        self.syncing_impl().await.map_err(into_jsrpc_error)
     }
 
     async fn accounts(&self) -> RpcResult<Vec<Address>> {
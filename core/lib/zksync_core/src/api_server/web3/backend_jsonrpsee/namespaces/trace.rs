@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+use zksync_types::api::{LocalizedTrace, TraceFilter};
+use zksync_web3_decl::{jsonrpsee::core::RpcResult, namespaces::TraceNamespaceServer};
+
+use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, namespaces::TraceNamespace};
+
+#[async_trait]
+impl TraceNamespaceServer for TraceNamespace {
+    async fn filter(&self, filter: TraceFilter) -> RpcResult<Vec<LocalizedTrace>> {
+        self.filter_impl(filter).await.map_err(into_jsrpc_error)
+    }
+}
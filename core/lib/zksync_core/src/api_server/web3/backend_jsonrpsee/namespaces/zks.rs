@@ -1,19 +1,25 @@
 use std::collections::HashMap;
 
+use chrono::NaiveDate;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetails,
+        AccountBalances, AccountBalancesOptions, BaseTokenPrice, BatchGasEstimate, BlockDetails,
+        BlockIdVariant, BridgeAddresses, CommitmentArtifacts, L1BatchDetails, L1GasOracleState,
+        L2ToL1LogProof, PaymasterValidation, PriorityOpStatus, Proof, ProtocolVersion,
+        ProtocolVersionInfo, PruningInfo, SimulatedTransaction, StateOverride, TokenTransfer,
+        TokenTransfersRange, TransactionDeadline, TransactionDetails, TransactionTrace,
+        UtilizationReport,
     },
+    block::ContractPubdata,
     fee::Fee,
     fee_model::FeeParams,
     transaction_request::CallRequest,
-    Address, L1BatchNumber, MiniblockNumber, H256, U256, U64,
+    Address, L1BatchNumber, MiniblockNumber, PriorityOpId, H256, U256, U64,
 };
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
     namespaces::zks::ZksNamespaceServer,
-    types::Token,
+    types::{Bytes, Token},
 };
 
 use crate::api_server::web3::{backend_jsonrpsee::into_jsrpc_error, ZksNamespace};
@@ -30,6 +36,12 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn estimate_gas_batch(&self, reqs: Vec<CallRequest>) -> RpcResult<Vec<BatchGasEstimate>> {
+        self.estimate_gas_batch_impl(reqs)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_bridgehub_contract(&self) -> RpcResult<Option<Address>> {
         Ok(self.get_bridgehub_contract_impl())
     }
@@ -56,6 +68,12 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_token_info(&self, address: Address) -> RpcResult<Option<Token>> {
+        self.get_token_info_impl(address)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_all_account_balances(
         &self,
         address: Address,
@@ -65,6 +83,16 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_account_balances(
+        &self,
+        address: Address,
+        options: Option<AccountBalancesOptions>,
+    ) -> RpcResult<AccountBalances> {
+        self.get_account_balances_impl(address, options.unwrap_or_default())
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_l2_to_l1_msg_proof(
         &self,
         block: MiniblockNumber,
@@ -114,6 +142,15 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_priority_op_status(
+        &self,
+        id: PriorityOpId,
+    ) -> RpcResult<Option<PriorityOpStatus>> {
+        self.get_priority_op_status_impl(id)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_raw_block_transactions(
         &self,
         block_number: MiniblockNumber,
@@ -123,6 +160,15 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_raw_block_traces(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> RpcResult<Vec<TransactionTrace>> {
+        self.get_raw_block_traces_impl(block_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_l1_batch_details(
         &self,
         batch_number: L1BatchNumber,
@@ -132,6 +178,15 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_commitment_artifacts(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<CommitmentArtifacts>> {
+        self.get_commitment_artifacts_impl(batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_bytecode_by_hash(&self, hash: H256) -> RpcResult<Option<Vec<u8>>> {
         self.get_bytecode_by_hash_impl(hash)
             .await
@@ -146,6 +201,10 @@ impl ZksNamespaceServer for ZksNamespace {
         Ok(self.get_fee_params_impl())
     }
 
+    async fn get_l1_gas_oracle_state(&self) -> RpcResult<Option<L1GasOracleState>> {
+        Ok(self.get_l1_gas_oracle_state_impl().await)
+    }
+
     async fn get_protocol_version(
         &self,
         version_id: Option<u16>,
@@ -155,6 +214,12 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(into_jsrpc_error)
     }
 
+    async fn get_protocol_versions(&self) -> RpcResult<Vec<ProtocolVersionInfo>> {
+        self.get_protocol_versions_impl()
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
     async fn get_proof(
         &self,
         address: Address,
@@ -165,4 +230,67 @@ impl ZksNamespaceServer for ZksNamespace {
             .await
             .map_err(into_jsrpc_error)
     }
+
+    async fn get_pruning_info(&self) -> RpcResult<PruningInfo> {
+        Ok(self.get_pruning_info_impl())
+    }
+
+    async fn get_base_token_price(&self) -> RpcResult<BaseTokenPrice> {
+        self.get_base_token_price_impl().map_err(into_jsrpc_error)
+    }
+
+    async fn get_batch_pubdata_by_contract(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> RpcResult<Vec<ContractPubdata>> {
+        self.get_batch_pubdata_by_contract_impl(batch_number)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn simulate_bundle(
+        &self,
+        requests: Vec<CallRequest>,
+        block: Option<BlockIdVariant>,
+        state_override: Option<StateOverride>,
+    ) -> RpcResult<Vec<SimulatedTransaction>> {
+        self.simulate_bundle_impl(requests, block.map(Into::into), state_override)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn validate_paymaster(&self, req: CallRequest) -> RpcResult<PaymasterValidation> {
+        self.validate_paymaster_impl(req)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_token_transfers(
+        &self,
+        address: Address,
+        range: Option<TokenTransfersRange>,
+    ) -> RpcResult<Vec<TokenTransfer>> {
+        self.get_token_transfers_impl(address, range.unwrap_or_default())
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn get_utilization_report(
+        &self,
+        date: NaiveDate,
+    ) -> RpcResult<Option<UtilizationReport>> {
+        self.get_utilization_report_impl(date)
+            .await
+            .map_err(into_jsrpc_error)
+    }
+
+    async fn send_raw_transaction_with_deadline(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: TransactionDeadline,
+    ) -> RpcResult<H256> {
+        self.send_raw_transaction_with_deadline_impl(tx_bytes, valid_until)
+            .await
+            .map_err(into_jsrpc_error)
+    }
 }
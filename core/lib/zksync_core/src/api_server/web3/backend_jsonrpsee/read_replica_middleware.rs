@@ -0,0 +1,26 @@
+use zksync_dal::{connection::PinnedReadReplicaFuture, ConnectionPool};
+use zksync_web3_decl::jsonrpsee::{server::middleware::rpc::RpcServiceT, types::Request};
+
+/// Middleware pinning a single read replica for all DB connections acquired while handling an RPC call,
+/// so that the call observes a consistent database state. See [`ConnectionPool::pin_read_replica()`].
+#[derive(Debug, Clone)]
+pub(crate) struct PinReadReplicaMiddleware<S> {
+    inner: S,
+}
+
+impl<S> PinReadReplicaMiddleware<S> {
+    pub(crate) fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for PinReadReplicaMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = PinnedReadReplicaFuture<S::Future>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        ConnectionPool::pin_read_replica(self.inner.call(request))
+    }
+}
@@ -0,0 +1,519 @@
+//! HTTP middleware streaming responses of JSON-RPC methods that can return large results.
+
+use std::{
+    collections::HashSet,
+    future::Future,
+    mem,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use anyhow::Context as _;
+use hyper::{body::Sender, header, Body, Method, Request, Response, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tower::{Layer, Service};
+use zksync_types::{
+    api::{BlockId, BlockNumber, TracerConfig},
+    H256,
+};
+use zksync_web3_decl::{
+    error::Web3Error,
+    jsonrpsee::types::{
+        error::{OVERSIZED_RESPONSE_CODE, OVERSIZED_RESPONSE_MSG},
+        ErrorObjectOwned,
+    },
+    types::Filter,
+};
+
+use super::into_jsrpc_error;
+use crate::api_server::web3::{
+    metrics::API_METRICS,
+    namespaces::{DebugNamespace, EthNamespace},
+};
+
+/// Methods which responses can be streamed.
+pub(crate) const STREAMABLE_METHODS: &[&str] = &[
+    "eth_getLogs",
+    "debug_traceBlockByNumber",
+    "debug_traceBlockByHash",
+];
+/// Requests larger than this size are never intercepted by the middleware. Requests to streamable methods
+/// are much smaller in practice.
+const MAX_INTERCEPTED_REQUEST_SIZE: u64 = 64 * 1_024;
+/// Minimum size of a response chunk sent to the client.
+const CHUNK_SIZE: usize = 256 * 1_024;
+
+/// JSON-RPC request as parsed by the middleware. Only requests with positional params are intercepted.
+#[derive(Debug, Deserialize)]
+struct RawCall {
+    jsonrpc: String,
+    id: Option<serde_json::Value>,
+    method: String,
+    #[serde(default)]
+    params: Vec<serde_json::Value>,
+}
+
+#[derive(Debug)]
+enum StreamedCall {
+    GetLogs(Filter),
+    TraceBlock(BlockId, Option<TracerConfig>),
+}
+
+impl StreamedCall {
+    fn method_name(&self) -> &'static str {
+        match self {
+            Self::GetLogs(_) => "eth_getLogs",
+            Self::TraceBlock(BlockId::Number(_), _) => "debug_traceBlockByNumber",
+            Self::TraceBlock(BlockId::Hash(_), _) => "debug_traceBlockByHash",
+        }
+    }
+}
+
+fn parse_param<T: DeserializeOwned>(params: &mut [serde_json::Value], idx: usize) -> Option<T> {
+    let param = params
+        .get_mut(idx)
+        .map_or(serde_json::Value::Null, mem::take);
+    serde_json::from_value(param).ok()
+}
+
+/// Methods with streamed responses together with the namespaces used to serve them.
+#[derive(Debug)]
+pub(crate) struct StreamedMethods {
+    method_names: HashSet<String>,
+    eth: Option<EthNamespace>,
+    debug: Option<DebugNamespace>,
+    response_size_limit: usize,
+}
+
+impl StreamedMethods {
+    pub fn new(method_names: &[String]) -> anyhow::Result<Self> {
+        for name in method_names {
+            anyhow::ensure!(
+                STREAMABLE_METHODS.contains(&name.as_str()),
+                "Method `{name}` doesn't support streamed responses; supported methods are {STREAMABLE_METHODS:?}"
+            );
+        }
+        Ok(Self {
+            method_names: method_names.iter().cloned().collect(),
+            eth: None,
+            debug: None,
+            response_size_limit: usize::MAX,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.method_names.is_empty()
+    }
+
+    pub fn set_eth_namespace(&mut self, eth: EthNamespace) {
+        self.eth = Some(eth);
+    }
+
+    pub fn set_debug_namespace(&mut self, debug: DebugNamespace) {
+        self.debug = Some(debug);
+    }
+
+    /// Sets the size limit for streamed responses. Unlike with `jsonrpsee`, the limit is enforced while
+    /// the response is being written.
+    pub fn set_response_size_limit(&mut self, limit: usize) {
+        self.response_size_limit = limit;
+    }
+
+    fn may_intercept<B>(&self, request: &Request<B>) -> bool {
+        let content_length = request
+            .headers()
+            .get(header::CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        !self.is_empty()
+            && request.method() == Method::POST
+            && content_length.map_or(false, |len| len <= MAX_INTERCEPTED_REQUEST_SIZE)
+    }
+
+    /// Parses a streamed call from the request body. Returns `None` if the request should be handled by `jsonrpsee`;
+    /// this includes batch requests, notifications, requests to other methods and requests with invalid params
+    /// (so that the error responses are consistent with other methods).
+    fn parse_call(&self, body: &[u8]) -> Option<(serde_json::Value, StreamedCall)> {
+        let RawCall {
+            jsonrpc,
+            id,
+            method,
+            mut params,
+        } = serde_json::from_slice(body).ok()?;
+        if jsonrpc != "2.0" || !self.method_names.contains(&method) {
+            return None;
+        }
+        let id = id?;
+
+        let call = match method.as_str() {
+            "eth_getLogs" if self.eth.is_some() && params.len() == 1 => {
+                StreamedCall::GetLogs(parse_param(&mut params, 0)?)
+            }
+            "debug_traceBlockByNumber" if self.debug.is_some() && params.len() <= 2 => {
+                let block_number: BlockNumber = parse_param(&mut params, 0)?;
+                let options = parse_param(&mut params, 1)?;
+                StreamedCall::TraceBlock(BlockId::Number(block_number), options)
+            }
+            "debug_traceBlockByHash" if self.debug.is_some() && params.len() <= 2 => {
+                let block_hash: H256 = parse_param(&mut params, 0)?;
+                let options = parse_param(&mut params, 1)?;
+                StreamedCall::TraceBlock(BlockId::Hash(block_hash), options)
+            }
+            _ => return None,
+        };
+        Some((id, call))
+    }
+
+    async fn handle(&self, id: serde_json::Value, call: StreamedCall) -> Response<Body> {
+        let method_name = call.method_name();
+        let size_limit = self.response_size_limit;
+        match call {
+            StreamedCall::GetLogs(filter) => {
+                let eth = self.eth.as_ref().expect("checked when parsing call");
+                let result = eth.get_logs_impl(filter).await.map(Vec::into_iter);
+                stream_response(method_name, id, result, size_limit).await
+            }
+            StreamedCall::TraceBlock(block_id, options) => {
+                let debug = self.debug.as_ref().expect("checked when parsing call");
+                let result = debug.debug_trace_block_lazy(block_id, options).await;
+                stream_response(method_name, id, result, size_limit).await
+            }
+        }
+    }
+}
+
+fn json_response(body: Body) -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .body(body)
+        .expect("invalid response")
+}
+
+fn error_response(id: &serde_json::Value, error: ErrorObjectOwned) -> Response<Body> {
+    let response = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": error,
+        "id": id,
+    });
+    json_response(Body::from(response.to_string()))
+}
+
+#[derive(Debug)]
+enum WriteError {
+    Serialization(serde_json::Error),
+    TooLarge,
+}
+
+/// Incrementally serializes a JSON-RPC response with an array result, enforcing the response size limit.
+#[derive(Debug)]
+struct ResponseWriter {
+    method_name: &'static str,
+    buffer: Vec<u8>,
+    /// Total size of the previously taken chunks.
+    sent_size: usize,
+    size_limit: usize,
+    item_count: usize,
+}
+
+impl ResponseWriter {
+    /// Length of the response suffix written by [`Self::finish()`].
+    const SUFFIX_LEN: usize = 2;
+
+    fn new(
+        method_name: &'static str,
+        id: &serde_json::Value,
+        size_limit: usize,
+    ) -> Result<Self, WriteError> {
+        let mut buffer = Vec::with_capacity(CHUNK_SIZE);
+        buffer.extend_from_slice(br#"{"jsonrpc":"2.0","id":"#);
+        serde_json::to_writer(&mut buffer, id).map_err(WriteError::Serialization)?;
+        buffer.extend_from_slice(br#","result":["#);
+        let this = Self {
+            method_name,
+            buffer,
+            sent_size: 0,
+            size_limit,
+            item_count: 0,
+        };
+        this.check_size()?;
+        Ok(this)
+    }
+
+    fn response_size(&self) -> usize {
+        self.sent_size + self.buffer.len()
+    }
+
+    fn check_size(&self) -> Result<(), WriteError> {
+        if self.response_size() + Self::SUFFIX_LEN > self.size_limit {
+            Err(WriteError::TooLarge)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn write_item<T: Serialize>(&mut self, item: &T) -> Result<(), WriteError> {
+        if self.item_count > 0 {
+            self.buffer.push(b',');
+        }
+        serde_json::to_writer(&mut self.buffer, item).map_err(WriteError::Serialization)?;
+        self.item_count += 1;
+        self.check_size()
+    }
+
+    /// Takes the next chunk of the response if the buffered data is large enough.
+    fn take_chunk(&mut self) -> Option<Vec<u8>> {
+        if self.buffer.len() < CHUNK_SIZE {
+            return None;
+        }
+        let chunk = mem::replace(&mut self.buffer, Vec::with_capacity(CHUNK_SIZE));
+        self.sent_size += chunk.len();
+        API_METRICS.web3_streamed_bytes[&self.method_name].inc_by(chunk.len() as u64);
+        Some(chunk)
+    }
+
+    /// Returns the last chunk of the response.
+    fn finish(mut self) -> Vec<u8> {
+        self.buffer.extend_from_slice(b"]}");
+        let response_size = self.response_size();
+        let chunk = self.buffer;
+        API_METRICS.web3_streamed_bytes[&self.method_name].inc_by(chunk.len() as u64);
+        API_METRICS.web3_streamed_response_size[&self.method_name].observe(response_size);
+        chunk
+    }
+}
+
+/// Serializes items into the response body chunk by chunk, so that the entire serialized response is never held
+/// in memory. Items are serialized until the first chunk is filled before responding, so that small responses
+/// are sent in one piece, and errors (including the response size limit being exceeded) can be reported
+/// as JSON-RPC errors. If the size limit is exceeded after the first chunk is sent, the response is aborted.
+async fn stream_response<T, I>(
+    method_name: &'static str,
+    id: serde_json::Value,
+    result: Result<I, Web3Error>,
+    size_limit: usize,
+) -> Response<Body>
+where
+    T: Serialize + Send + 'static,
+    I: Iterator<Item = T> + Send + 'static,
+{
+    let convert_err = |err: WriteError| match err {
+        WriteError::Serialization(err) => {
+            tracing::warn!("Failed serializing `{method_name}` response: {err}");
+            into_jsrpc_error(Web3Error::InternalError)
+        }
+        WriteError::TooLarge => ErrorObjectOwned::owned(
+            OVERSIZED_RESPONSE_CODE,
+            OVERSIZED_RESPONSE_MSG,
+            Some(format!("Exceeded max limit of {size_limit}")),
+        ),
+    };
+
+    let mut items = match result {
+        Ok(items) => items,
+        Err(err) => return error_response(&id, into_jsrpc_error(err)),
+    };
+    let mut writer = match ResponseWriter::new(method_name, &id, size_limit) {
+        Ok(writer) => writer,
+        Err(err) => return error_response(&id, convert_err(err)),
+    };
+    let first_chunk = loop {
+        let Some(item) = items.next() else {
+            // The response fits into a single chunk.
+            return json_response(Body::from(writer.finish()));
+        };
+        if let Err(err) = writer.write_item(&item) {
+            return error_response(&id, convert_err(err));
+        }
+        if let Some(chunk) = writer.take_chunk() {
+            break chunk;
+        }
+    };
+
+    // Since the body has no content length, it will be sent using chunked transfer encoding.
+    let (sender, body) = Body::channel();
+    tokio::spawn(async move {
+        if let Err(err) = send_items(sender, first_chunk, writer, items).await {
+            tracing::info!("Failed streaming `{method_name}` response: {err:#}");
+            API_METRICS.web3_streamed_response_aborts[&method_name].inc();
+        }
+    });
+    json_response(body)
+}
+
+async fn send_items<T: Serialize + Send>(
+    mut sender: Sender,
+    first_chunk: Vec<u8>,
+    mut writer: ResponseWriter,
+    items: impl Iterator<Item = T>,
+) -> anyhow::Result<()> {
+    sender
+        .send_data(first_chunk.into())
+        .await
+        .context("client disconnected")?;
+    for item in items {
+        match writer.write_item(&item) {
+            Ok(()) => {}
+            Err(WriteError::Serialization(err)) => {
+                sender.abort();
+                return Err(anyhow::Error::from(err).context("failed serializing response item"));
+            }
+            Err(WriteError::TooLarge) => {
+                sender.abort();
+                anyhow::bail!(
+                    "response exceeded size limit of {} bytes",
+                    writer.size_limit
+                );
+            }
+        }
+        if let Some(chunk) = writer.take_chunk() {
+            sender
+                .send_data(chunk.into())
+                .await
+                .context("client disconnected")?;
+        }
+    }
+    sender
+        .send_data(writer.finish().into())
+        .await
+        .context("client disconnected")?;
+    Ok(())
+}
+
+/// HTTP middleware serving the configured methods with potentially large results (e.g., `eth_getLogs`)
+/// using chunked transfer encoding. Unlike `jsonrpsee`, which serializes the entire response in memory
+/// before sending it, the middleware serializes and sends the response in chunks, so that a single
+/// massive query cannot exhaust the server memory.
+///
+/// All other requests (including batch requests) are passed to `jsonrpsee` as is.
+#[derive(Debug, Clone)]
+pub(crate) struct StreamingLayer {
+    methods: Arc<StreamedMethods>,
+}
+
+impl StreamingLayer {
+    pub fn new(methods: StreamedMethods) -> Self {
+        Self {
+            methods: Arc::new(methods),
+        }
+    }
+}
+
+impl<S> Layer<S> for StreamingLayer {
+    type Service = StreamingService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        StreamingService {
+            inner,
+            methods: self.methods.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct StreamingService<S> {
+    inner: S,
+    methods: Arc<StreamedMethods>,
+}
+
+impl<S> Service<Request<Body>> for StreamingService<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Body>, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if !self.methods.may_intercept(&request) {
+            return Box::pin(self.inner.call(request));
+        }
+
+        // Use the service driven to readiness, leaving its clone in its place.
+        let clone = self.inner.clone();
+        let mut inner = mem::replace(&mut self.inner, clone);
+        let methods = self.methods.clone();
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+            let body = match hyper::body::to_bytes(body).await {
+                Ok(body) => body,
+                Err(err) => {
+                    tracing::debug!("Failed reading request body: {err}");
+                    let response = Response::builder()
+                        .status(StatusCode::BAD_REQUEST)
+                        .body(Body::empty())
+                        .expect("invalid response");
+                    return Ok(response);
+                }
+            };
+
+            if let Some((id, call)) = methods.parse_call(&body) {
+                return Ok(methods.handle(id, call).await);
+            }
+            inner
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validating_streamed_methods() {
+        StreamedMethods::new(&["eth_getLogs".to_owned()]).unwrap();
+        let err = StreamedMethods::new(&["eth_call".to_owned()])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("eth_call"), "{err}");
+    }
+
+    async fn read_response(response: Response<Body>) -> serde_json::Value {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn streaming_items() {
+        let items = (0_u64..100_000).collect::<Vec<_>>().into_iter();
+        let response = stream_response("test", serde_json::json!(1), Ok(items), usize::MAX).await;
+        let response = read_response(response).await;
+        assert_eq!(response["id"], 1);
+        let result = response["result"].as_array().unwrap();
+        assert_eq!(result.len(), 100_000);
+        assert_eq!(result[99_999], 99_999);
+    }
+
+    #[tokio::test]
+    async fn small_response_exceeding_size_limit() {
+        let items = (0_u64..100).collect::<Vec<_>>().into_iter();
+        let response = stream_response("test", serde_json::json!(1), Ok(items), 100).await;
+        let response = read_response(response).await;
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["error"]["code"], OVERSIZED_RESPONSE_CODE);
+
+        let items = (0_u64..10).collect::<Vec<_>>().into_iter();
+        let response = stream_response("test", serde_json::json!(1), Ok(items), 100).await;
+        let response = read_response(response).await;
+        assert_eq!(response["result"].as_array().unwrap().len(), 10);
+    }
+
+    #[tokio::test]
+    async fn large_response_exceeding_size_limit() {
+        let items = (0_u64..1_000_000).collect::<Vec<_>>().into_iter();
+        let response =
+            stream_response("test", serde_json::json!(1), Ok(items), 2 * CHUNK_SIZE).await;
+        // The first chunk is already sent, so the only way to signal the error is aborting the response.
+        hyper::body::to_bytes(response.into_body())
+            .await
+            .unwrap_err();
+    }
+}
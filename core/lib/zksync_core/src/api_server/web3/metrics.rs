@@ -140,6 +140,9 @@ pub(super) struct ApiMetrics {
     /// Number of internal errors grouped by the Web3 method.
     #[metrics(labels = ["method"])]
     pub web3_internal_errors: LabeledFamily<&'static str, Counter>,
+    /// Number of logs requests rejected because their filter references a denylisted address or topic.
+    #[metrics(labels = ["method"])]
+    pub web3_denylisted_logs_requests: LabeledFamily<&'static str, Counter>,
     /// Number of transaction submission errors for a specific submission error reason.
     #[metrics(labels = ["reason"])]
     pub submit_tx_error: LabeledFamily<&'static str, Counter>,
@@ -147,6 +150,17 @@ pub(super) struct ApiMetrics {
     pub web3_in_flight_requests: Family<ApiTransportLabel, Histogram<usize>>,
     /// Number of currently open WebSocket sessions.
     pub ws_open_sessions: Gauge,
+    /// Size of responses streamed using chunked transfer encoding grouped by the Web3 method.
+    #[metrics(buckets = Buckets::exponential(1_024.0..=1_073_741_824.0, 4.0), unit = Unit::Bytes, labels = ["method"])]
+    pub web3_streamed_response_size: LabeledFamily<&'static str, Histogram<usize>>,
+    /// Number of bytes sent in streamed responses grouped by the Web3 method. Unlike `web3_streamed_response_size`,
+    /// this metric is updated while the response is being sent.
+    #[metrics(unit = Unit::Bytes, labels = ["method"])]
+    pub web3_streamed_bytes: LabeledFamily<&'static str, Counter>,
+    /// Number of streamed responses aborted after being partially sent (e.g., because the response size limit
+    /// is exceeded or the client disconnects) grouped by the Web3 method.
+    #[metrics(labels = ["method"])]
+    pub web3_streamed_response_aborts: LabeledFamily<&'static str, Counter>,
 }
 
 impl ApiMetrics {
@@ -179,6 +193,7 @@ pub(super) enum SubscriptionType {
     Blocks,
     Txs,
     Logs,
+    L1BatchStatus,
 }
 
 #[derive(Debug, Metrics)]
@@ -18,8 +18,9 @@ use zksync_web3_decl::{
         RpcModule,
     },
     namespaces::{
-        DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer, EthPubSubServer,
-        NetNamespaceServer, SnapshotsNamespaceServer, Web3NamespaceServer, ZksNamespaceServer,
+        AdminNamespaceServer, DebugNamespaceServer, EnNamespaceServer, EthNamespaceServer,
+        EthPubSubServer, NetNamespaceServer, SnapshotsNamespaceServer, TraceNamespaceServer,
+        Web3NamespaceServer, ZksNamespaceServer,
     },
     types::Filter,
 };
@@ -27,8 +28,8 @@ use zksync_web3_decl::{
 use self::{
     metrics::API_METRICS,
     namespaces::{
-        DebugNamespace, EnNamespace, EthNamespace, NetNamespace, SnapshotsNamespace, Web3Namespace,
-        ZksNamespace,
+        AdminNamespace, DebugNamespace, EnNamespace, EthNamespace, NetNamespace,
+        SnapshotsNamespace, TraceNamespace, Web3Namespace, ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
     state::{Filters, InternalApiConfig, RpcState, SealedMiniblockNumber},
@@ -38,8 +39,15 @@ use crate::{
         execution_sandbox::{BlockStartInfo, VmConcurrencyBarrier},
         tree::TreeApiHttpClient,
         tx_sender::TxSender,
-        web3::backend_jsonrpsee::batch_limiter_middleware::LimitMiddleware,
+        web3::backend_jsonrpsee::{
+            batch_limiter_middleware::LimitMiddleware,
+            client_id_middleware::{ClientIdLayer, API_KEY_HEADER},
+            read_replica_middleware::PinReadReplicaMiddleware,
+            streaming_middleware::{StreamedMethods, StreamingLayer},
+        },
     },
+    base_token_fetcher::BaseTokenFetcher,
+    state_keeper::PubdataModeSwitch,
     sync_layer::SyncState,
     utils::wait_for_l1_batch,
 };
@@ -83,6 +91,8 @@ pub enum Namespace {
     En,
     Pubsub,
     Snapshots,
+    Admin,
+    Trace,
 }
 
 impl Namespace {
@@ -113,8 +123,11 @@ struct OptionalApiParams {
     subscriptions_limit: Option<usize>,
     batch_request_size_limit: Option<usize>,
     response_body_size_limit: Option<usize>,
+    streamed_methods: Vec<String>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tree_api_url: Option<String>,
+    base_token_fetcher: Option<Arc<BaseTokenFetcher>>,
+    pubdata_mode_switch: Option<PubdataModeSwitch>,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -210,6 +223,12 @@ impl ApiBuilder {
         self
     }
 
+    /// Sets methods which responses should be streamed using chunked transfer encoding. Only applies to the HTTP server.
+    pub fn with_streamed_methods(mut self, streamed_methods: Vec<String>) -> Self {
+        self.optional.streamed_methods = streamed_methods;
+        self
+    }
+
     pub fn with_websocket_requests_per_minute_limit(
         mut self,
         websocket_requests_per_minute_limit: NonZeroU32,
@@ -239,6 +258,18 @@ impl ApiBuilder {
         self
     }
 
+    pub fn with_base_token_fetcher(mut self, fetcher: Arc<BaseTokenFetcher>) -> Self {
+        self.optional.base_token_fetcher = Some(fetcher);
+        self
+    }
+
+    /// Allows switching the pubdata mode of the state keeper via the admin namespace. Should only be set
+    /// if the state keeper runs in the same process as the server.
+    pub fn with_pubdata_mode_switch(mut self, pubdata_mode_switch: PubdataModeSwitch) -> Self {
+        self.optional.pubdata_mode_switch = Some(pubdata_mode_switch);
+        self
+    }
+
     #[cfg(test)]
     fn with_pub_sub_events(mut self, sender: mpsc::UnboundedSender<PubSubEvent>) -> Self {
         self.optional.pub_sub_events_sender = Some(sender);
@@ -278,11 +309,8 @@ impl FullApiParams {
     async fn build_rpc_state(
         self,
         last_sealed_miniblock: SealedMiniblockNumber,
+        start_info: watch::Receiver<BlockStartInfo>,
     ) -> anyhow::Result<RpcState> {
-        let mut storage = self.updaters_pool.access_storage_tagged("api").await?;
-        let start_info = BlockStartInfo::new(&mut storage).await?;
-        drop(storage);
-
         let installed_filters = if self.config.filters_disabled {
             None
         } else {
@@ -303,6 +331,8 @@ impl FullApiParams {
                 .optional
                 .tree_api_url
                 .map(|url| TreeApiHttpClient::new(url.as_str())),
+            base_token_fetcher: self.optional.base_token_fetcher,
+            pubdata_mode_switch: self.optional.pubdata_mode_switch,
         })
     }
 
@@ -310,10 +340,21 @@ impl FullApiParams {
         self,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
-    ) -> anyhow::Result<RpcModule<()>> {
+        start_info: watch::Receiver<BlockStartInfo>,
+    ) -> anyhow::Result<(RpcModule<()>, StreamedMethods)> {
         let namespaces = self.namespaces.clone();
         let zksync_network_id = self.config.l2_chain_id;
-        let rpc_state = self.build_rpc_state(last_sealed_miniblock).await?;
+        let mut streamed_methods = if matches!(self.transport, ApiTransport::Http(_)) {
+            StreamedMethods::new(&self.optional.streamed_methods)?
+        } else {
+            StreamedMethods::new(&[])?
+        };
+        if let Some(limit) = self.optional.response_body_size_limit {
+            streamed_methods.set_response_size_limit(limit);
+        }
+        let rpc_state = self
+            .build_rpc_state(last_sealed_miniblock, start_info)
+            .await?;
 
         // Collect all the methods into a single RPC module.
         let mut rpc = RpcModule::new(());
@@ -325,6 +366,9 @@ impl FullApiParams {
         if namespaces.contains(&Namespace::Eth) {
             rpc.merge(EthNamespace::new(rpc_state.clone()).into_rpc())
                 .expect("Can't merge eth namespace");
+            if !streamed_methods.is_empty() {
+                streamed_methods.set_eth_namespace(EthNamespace::new(rpc_state.clone()));
+            }
         }
         if namespaces.contains(&Namespace::Net) {
             rpc.merge(NetNamespace::new(zksync_network_id).into_rpc())
@@ -343,14 +387,26 @@ impl FullApiParams {
                 .expect("Can't merge en namespace");
         }
         if namespaces.contains(&Namespace::Debug) {
-            rpc.merge(DebugNamespace::new(rpc_state.clone()).await.into_rpc())
+            let debug = DebugNamespace::new(rpc_state.clone()).await;
+            if !streamed_methods.is_empty() {
+                streamed_methods.set_debug_namespace(debug.clone());
+            }
+            rpc.merge(debug.into_rpc())
                 .expect("Can't merge debug namespace");
         }
         if namespaces.contains(&Namespace::Snapshots) {
-            rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
+            rpc.merge(SnapshotsNamespace::new(rpc_state.clone()).into_rpc())
                 .expect("Can't merge snapshots namespace");
         }
-        Ok(rpc)
+        if namespaces.contains(&Namespace::Admin) {
+            rpc.merge(AdminNamespace::new(rpc_state.clone()).into_rpc())
+                .expect("Can't merge admin namespace");
+        }
+        if namespaces.contains(&Namespace::Trace) {
+            rpc.merge(TraceNamespace::new(rpc_state).into_rpc())
+                .expect("Can't merge trace namespace");
+        }
+        Ok((rpc, streamed_methods))
     }
 
     async fn spawn_server(
@@ -412,6 +468,9 @@ impl FullApiParams {
         // processes enough requests, information about the latest sealed miniblock will be updated
         // by reporting block difference metrics, so the actual update lag would be much smaller than this value.
         const SEALED_MINIBLOCK_UPDATE_INTERVAL: Duration = Duration::from_millis(25);
+        // Should be significantly smaller than the delay between soft and hard pruning of the node data,
+        // so that the API server stops serving soft-pruned data before it is removed from Postgres.
+        const START_INFO_UPDATE_INTERVAL: Duration = Duration::from_secs(1);
 
         let transport = self.transport;
         let health_check_name = match transport {
@@ -426,11 +485,21 @@ impl FullApiParams {
             stop_receiver.clone(),
         );
 
-        let mut tasks = vec![tokio::spawn(update_task)];
+        let (start_info, start_info_update_task) = BlockStartInfo::new_updatable(
+            self.updaters_pool.clone(),
+            START_INFO_UPDATE_INTERVAL,
+            stop_receiver.clone(),
+        )
+        .await?;
+
+        let mut tasks = vec![
+            tokio::spawn(update_task),
+            tokio::spawn(start_info_update_task),
+        ];
         let pub_sub = if matches!(transport, ApiTransport::WebSocket(_))
             && self.namespaces.contains(&Namespace::Pubsub)
         {
-            let mut pub_sub = EthSubscribe::new();
+            let mut pub_sub = EthSubscribe::new(self.config.logs_denylist.clone());
             if let Some(sender) = &self.optional.pub_sub_events_sender {
                 pub_sub.set_events_sender(sender.clone());
             }
@@ -451,6 +520,7 @@ impl FullApiParams {
             stop_receiver,
             pub_sub,
             last_sealed_miniblock,
+            start_info,
             local_addr_sender,
             health_updater,
         ));
@@ -468,6 +538,7 @@ impl FullApiParams {
         mut stop_receiver: watch::Receiver<bool>,
         pub_sub: Option<EthSubscribe>,
         last_sealed_miniblock: SealedMiniblockNumber,
+        start_info: watch::Receiver<BlockStartInfo>,
         local_addr_sender: oneshot::Sender<SocketAddr>,
         health_updater: HealthUpdater,
     ) -> anyhow::Result<()> {
@@ -509,9 +580,11 @@ impl FullApiParams {
         let subscriptions_limit = self.optional.subscriptions_limit;
         let vm_barrier = self.vm_barrier.clone();
 
-        let rpc = self
-            .build_rpc_module(pub_sub, last_sealed_miniblock)
+        let (rpc, streamed_methods) = self
+            .build_rpc_module(pub_sub, last_sealed_miniblock, start_info)
             .await?;
+        let streaming =
+            (!streamed_methods.is_empty()).then(|| StreamingLayer::new(streamed_methods));
 
         // Setup CORS.
         let cors = is_http.then(|| {
@@ -520,7 +593,10 @@ impl FullApiParams {
                 .allow_methods([reqwest::Method::POST])
                 // Allow requests from any origin
                 .allow_origin(tower_http::cors::Any)
-                .allow_headers([reqwest::header::CONTENT_TYPE])
+                .allow_headers([
+                    reqwest::header::CONTENT_TYPE,
+                    reqwest::header::HeaderName::from_static(API_KEY_HEADER),
+                ])
         });
         // Setup metrics for the number of in-flight requests.
         let (in_flight_requests, counter) = InFlightRequestsLayer::pair();
@@ -533,7 +609,9 @@ impl FullApiParams {
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(cors)
+            .layer(ClientIdLayer)
+            .option_layer(streaming);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -550,6 +628,9 @@ impl FullApiParams {
             // HTTP-specific settings
             let server = server_builder
                 .http_only()
+                .set_rpc_middleware(
+                    RpcServiceBuilder::new().layer_fn(PinReadReplicaMiddleware::new),
+                )
                 .build(addr)
                 .await
                 .context("Failed building HTTP JSON-RPC server")?;
@@ -557,9 +638,13 @@ impl FullApiParams {
         } else {
             // WS specific settings
             let server = server_builder
-                .set_rpc_middleware(RpcServiceBuilder::new().layer_fn(move |a| {
-                    LimitMiddleware::new(a, websocket_requests_per_minute_limit)
-                }))
+                .set_rpc_middleware(
+                    RpcServiceBuilder::new()
+                        .layer_fn(PinReadReplicaMiddleware::new)
+                        .layer_fn(move |a| {
+                            LimitMiddleware::new(a, websocket_requests_per_minute_limit)
+                        }),
+                )
                 .set_id_provider(EthSubscriptionIdProvider)
                 .build(addr)
                 .await
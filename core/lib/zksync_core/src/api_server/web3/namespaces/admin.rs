@@ -0,0 +1,128 @@
+use zksync_types::{
+    api::{
+        BaseSystemContractsOverride, BaseSystemContractsOverrideHashes, ValidationAllowListEntry,
+    },
+    pubdata_da::PubdataDA,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::{
+    backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState,
+};
+
+/// Namespace with methods used by node operators.
+#[derive(Debug, Clone)]
+pub struct AdminNamespace {
+    state: RpcState,
+}
+
+impl AdminNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    pub async fn get_validation_allow_list_impl(
+        &self,
+    ) -> Result<Vec<ValidationAllowListEntry>, Web3Error> {
+        const METHOD_NAME: &str = "get_validation_allow_list";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let entries = self
+            .state
+            .tx_sender
+            .0
+            .validation_allow_list
+            .entries(&mut storage)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(entries.to_vec())
+    }
+
+    pub async fn reload_validation_allow_list_impl(
+        &self,
+    ) -> Result<Vec<ValidationAllowListEntry>, Web3Error> {
+        const METHOD_NAME: &str = "reload_validation_allow_list";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let entries = self
+            .state
+            .tx_sender
+            .0
+            .validation_allow_list
+            .reload(&mut storage)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(entries.to_vec())
+    }
+
+    pub fn set_base_system_contracts_override_impl(
+        &self,
+        contracts: BaseSystemContractsOverride,
+    ) -> Result<BaseSystemContractsOverrideHashes, Web3Error> {
+        const METHOD_NAME: &str = "set_base_system_contracts_override";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let hashes = self
+            .state
+            .tx_sender
+            .set_base_system_contracts_override(contracts)?;
+        tracing::info!("Overridden base system contracts used by the API sandbox: {hashes:?}");
+        method_latency.observe();
+        Ok(hashes)
+    }
+
+    pub fn get_base_system_contracts_override_impl(&self) -> BaseSystemContractsOverrideHashes {
+        self.state.tx_sender.base_system_contracts_override()
+    }
+
+    pub fn reset_base_system_contracts_override_impl(&self) -> Result<(), Web3Error> {
+        const METHOD_NAME: &str = "reset_base_system_contracts_override";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state
+            .tx_sender
+            .reset_base_system_contracts_override()?;
+        tracing::info!("Reset override of base system contracts used by the API sandbox");
+        method_latency.observe();
+        Ok(())
+    }
+
+    pub fn get_pubdata_mode_impl(&self) -> Result<PubdataDA, Web3Error> {
+        let switch = self
+            .state
+            .pubdata_mode_switch
+            .as_ref()
+            .ok_or(Web3Error::PubdataModeSwitchUnavailable)?;
+        Ok(switch.get())
+    }
+
+    pub fn set_pubdata_mode_impl(&self, mode: PubdataDA) -> Result<(), Web3Error> {
+        const METHOD_NAME: &str = "set_pubdata_mode";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let switch = self
+            .state
+            .pubdata_mode_switch
+            .as_ref()
+            .ok_or(Web3Error::PubdataModeSwitchUnavailable)?;
+        switch
+            .set(mode)
+            .map_err(|err| Web3Error::UnsupportedPubdataMode(err.0))?;
+        method_latency.observe();
+        Ok(())
+    }
+}
@@ -4,18 +4,19 @@ use multivm::{interface::ExecutionResult, vm_latest::constants::BLOCK_GAS_LIMIT}
 use once_cell::sync::OnceCell;
 use zksync_system_constants::MAX_ENCODED_TX_SIZE;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, SignatureReport, TracerConfig},
     fee_model::BatchFeeInput,
+    get_code_key,
     l2::L2Tx,
-    transaction_request::CallRequest,
+    transaction_request::{CallRequest, TransactionRequest},
     vm_trace::Call,
-    AccountTreeId, H256,
+    AccountTreeId, Bytes, H256,
 };
 use zksync_web3_decl::error::Web3Error;
 
 use crate::api_server::{
-    execution_sandbox::{ApiTracer, TxSharedArgs},
-    tx_sender::{ApiContracts, TxSenderConfig},
+    execution_sandbox::{validate_state_override, ApiTracer, TxSharedArgs, VmInvocationKind},
+    tx_sender::TxSenderConfig,
     web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState},
 };
 
@@ -23,12 +24,10 @@ use crate::api_server::{
 pub struct DebugNamespace {
     batch_fee_input: BatchFeeInput,
     state: RpcState,
-    api_contracts: ApiContracts,
 }
 
 impl DebugNamespace {
     pub async fn new(state: RpcState) -> Self {
-        let api_contracts = ApiContracts::load_from_disk();
         Self {
             // For now, the same scaling is used for both the L1 gas price and the pubdata price
             batch_fee_input: state
@@ -41,7 +40,6 @@ impl DebugNamespace {
                 )
                 .await,
             state,
-            api_contracts,
         }
     }
 
@@ -55,6 +53,17 @@ impl DebugNamespace {
         block_id: BlockId,
         options: Option<TracerConfig>,
     ) -> Result<Vec<ResultDebugCall>, Web3Error> {
+        let call_traces = self.debug_trace_block_lazy(block_id, options).await?;
+        Ok(call_traces.collect())
+    }
+
+    /// Same as [`Self::debug_trace_block_impl()`], but converts traces to the API format lazily, so that they
+    /// can be serialized one by one.
+    pub(crate) async fn debug_trace_block_lazy(
+        &self,
+        block_id: BlockId,
+        options: Option<TracerConfig>,
+    ) -> Result<impl Iterator<Item = ResultDebugCall> + Send + 'static, Web3Error> {
         const METHOD_NAME: &str = "debug_trace_block";
 
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
@@ -76,20 +85,17 @@ impl DebugNamespace {
             .get_traces_for_miniblock(block_number)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
-        let call_trace = call_traces
-            .into_iter()
-            .map(|call_trace| {
-                let mut result: DebugCall = call_trace.into();
-                if only_top_call {
-                    result.calls = vec![];
-                }
-                ResultDebugCall { result }
-            })
-            .collect();
+        let call_traces = call_traces.into_iter().map(move |call_trace| {
+            let mut result: DebugCall = call_trace.into();
+            if only_top_call {
+                result.calls = vec![];
+            }
+            ResultDebugCall { result }
+        });
 
         let block_diff = self.state.last_sealed_miniblock.diff(block_number);
         method_latency.observe(block_diff);
-        Ok(call_trace)
+        Ok(call_traces)
     }
 
     #[tracing::instrument(skip(self))]
@@ -123,6 +129,34 @@ impl DebugNamespace {
         }))
     }
 
+    #[tracing::instrument(skip(self, tx_bytes))]
+    pub async fn debug_explain_signature_impl(
+        &self,
+        tx_bytes: Bytes,
+    ) -> Result<SignatureReport, Web3Error> {
+        const METHOD_NAME: &str = "debug_explain_signature";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut report =
+            TransactionRequest::explain_signature(&tx_bytes.0, self.state.api_config.l2_chain_id);
+        if let Some(initiator) = report.initiator {
+            let mut connection = self
+                .state
+                .connection_pool
+                .access_storage_tagged("api")
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            let code_hash = connection
+                .storage_web3_dal()
+                .get_value(&get_code_key(&initiator))
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            report.check_initiator_account(!code_hash.is_zero());
+        }
+        method_latency.observe();
+        Ok(report)
+    }
+
     #[tracing::instrument(skip(self, request, block_id))]
     pub async fn debug_trace_call_impl(
         &self,
@@ -134,9 +168,16 @@ impl DebugNamespace {
 
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+        let (only_top_call, state_override) = options
+            .map(|options| (options.tracer_config.only_top_call, options.state_overrides))
+            .unwrap_or_default();
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override).map_err(Web3Error::InvalidStateOverride)?;
+        }
+        let gas_cap = self
+            .state
+            .gas_cap_for_call_request(&request)
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?;
 
         let mut connection = self
             .state
@@ -157,7 +198,7 @@ impl DebugNamespace {
             .state
             .tx_sender
             .vm_concurrency_limiter()
-            .acquire()
+            .acquire(VmInvocationKind::Trace)
             .await;
         let vm_permit = vm_permit.ok_or(Web3Error::InternalError)?;
 
@@ -179,6 +220,8 @@ impl DebugNamespace {
                 block_args,
                 self.sender_config().vm_execution_cache_misses_limit,
                 custom_tracers,
+                state_override,
+                gas_cap,
             )
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
@@ -222,8 +265,9 @@ impl DebugNamespace {
         TxSharedArgs {
             operator_account: AccountTreeId::default(),
             fee_input: self.batch_fee_input,
-            base_system_contracts: self.api_contracts.eth_call.clone(),
+            base_system_contracts: self.state.tx_sender.api_contracts().eth_call,
             caches: self.state.tx_sender.storage_caches().clone(),
+            rocksdb_replica: self.state.tx_sender.rocksdb_replica(),
             validation_computational_gas_limit: BLOCK_GAS_LIMIT,
             chain_id: sender_config.chain_id,
         }
@@ -1,33 +1,45 @@
-use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
+use std::time::Duration;
+
+use zksync_dal::StorageProcessor;
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        AccountProof, BlockId, BlockNumber, GetLogsFilter, StateOverride, StorageProof,
+        Transaction, TransactionId, TransactionReceipt, TransactionVariant,
     },
-    l2::{L2Tx, TransactionType},
+    get_code_key, get_nonce_key,
+    l2::L2Tx,
     transaction_request::CallRequest,
-    utils::decompose_full_nonce,
-    web3::{
-        self,
-        types::{FeeHistory, SyncInfo, SyncState},
-    },
-    AccountTreeId, Bytes, MiniblockNumber, StorageKey, H256, L2_ETH_TOKEN_ADDRESS, U256,
+    utils::{decompose_full_nonce, storage_key_for_eth_balance},
+    web3::{self, types::FeeHistory},
+    AccountTreeId, Bytes, L1BatchNumber, MiniblockNumber, StorageKey, H256, L2_ETH_TOKEN_ADDRESS,
+    U256,
 };
-use zksync_utils::u256_to_h256;
+use zksync_utils::{h256_to_u256, u256_to_h256};
 use zksync_web3_decl::{
     error::Web3Error,
-    types::{Address, Block, Filter, FilterChanges, Log, U64},
+    types::{Address, Block, Filter, FilterChanges, Log, SyncInfo, SyncState, U64},
 };
 
-use crate::api_server::web3::{
-    backend_jsonrpsee::internal_error,
-    metrics::{BlockCallObserver, API_METRICS},
-    state::RpcState,
-    TypedFilter,
+use crate::{
+    api_server::{
+        execution_sandbox::validate_state_override,
+        tree::TreeApiClient,
+        web3::{
+            backend_jsonrpsee::internal_error,
+            metrics::{BlockCallObserver, API_METRICS},
+            state::RpcState,
+            TypedFilter,
+        },
+    },
+    sync_layer::{L1BatchProgress, SyncState as SyncStateTracker},
 };
 
 pub const EVENT_TOPIC_NUMBER_LIMIT: usize = 4;
 pub const PROTOCOL_VERSION: &str = "zks/1";
+/// Maximum lag of the Merkle tree behind the latest sealed L1 batch for an external node to be considered synced.
+const MAX_TREE_L1_BATCH_LAG: u32 = 1;
+/// Time to live for L1 batch progress cached for `eth_syncing`.
+const L1_BATCH_PROGRESS_CACHE_TTL: Duration = Duration::from_secs(5);
 
 #[derive(Debug)]
 pub struct EthNamespace {
@@ -61,14 +73,23 @@ impl EthNamespace {
         Ok(block_number.0.into())
     }
 
-    #[tracing::instrument(skip(self, request, block_id))]
+    #[tracing::instrument(skip(self, request, block_id, state_override))]
     pub async fn call_impl(
         &self,
         request: CallRequest,
         block_id: Option<BlockId>,
+        state_override: Option<StateOverride>,
     ) -> Result<Bytes, Web3Error> {
         const METHOD_NAME: &str = "call";
 
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override).map_err(Web3Error::InvalidStateOverride)?;
+        }
+        let gas_cap = self
+            .state
+            .gas_cap_for_call_request(&request)
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?;
+
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
         let mut connection = self
@@ -86,7 +107,11 @@ impl EthNamespace {
 
         let tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
 
-        let call_result = self.state.tx_sender.eth_call(block_args, tx).await;
+        let call_result = self
+            .state
+            .tx_sender
+            .eth_call(block_args, tx, state_override, gas_cap)
+            .await;
         let res_bytes = call_result.map_err(|err| err.into_web3_error(METHOD_NAME))?;
 
         let block_diff = self
@@ -106,39 +131,15 @@ impl EthNamespace {
         const METHOD_NAME: &str = "estimate_gas";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        let mut request_with_gas_per_pubdata_overridden = request;
-        self.state
-            .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
+        let gas_cap = self
+            .state
+            .gas_cap_for_call_request(&request)
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?;
+        let tx = self
+            .state
+            .l2_tx_for_gas_estimation(request, METHOD_NAME)
             .await?;
 
-        if let Some(eip712_meta) = &mut request_with_gas_per_pubdata_overridden.eip712_meta {
-            if eip712_meta.gas_per_pubdata == U256::zero() {
-                eip712_meta.gas_per_pubdata = DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE.into();
-            }
-        }
-
-        let is_eip712 = request_with_gas_per_pubdata_overridden
-            .eip712_meta
-            .is_some();
-
-        let mut tx: L2Tx = L2Tx::from_request(
-            request_with_gas_per_pubdata_overridden.into(),
-            self.state.api_config.max_tx_size,
-        )?;
-
-        // The user may not include the proper transaction type during the estimation of
-        // the gas fee. However, it is needed for the bootloader checks to pass properly.
-        if is_eip712 {
-            tx.common_data.transaction_type = TransactionType::EIP712Transaction;
-        }
-
-        // When we're estimating fee, we are trying to deduce values related to fee, so we should
-        // not consider provided ones.
-        let gas_price = self.state.tx_sender.gas_price().await;
-        let gas_price = gas_price.map_err(|err| internal_error(METHOD_NAME, err))?;
-        tx.common_data.fee.max_fee_per_gas = gas_price.into();
-        tx.common_data.fee.max_priority_fee_per_gas = tx.common_data.fee.max_fee_per_gas;
-
         // Modify the l1 gas price with the scale factor
         let scale_factor = self.state.api_config.estimate_gas_scale_factor;
         let acceptable_overestimation =
@@ -147,7 +148,7 @@ impl EthNamespace {
         let fee = self
             .state
             .tx_sender
-            .get_txs_fee_in_wei(tx.into(), scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(tx.into(), scale_factor, acceptable_overestimation, gas_cap)
             .await
             .map_err(|err| err.into_web3_error(METHOD_NAME))?;
         method_latency.observe();
@@ -208,11 +209,28 @@ impl EthNamespace {
         observer.observe(block_diff);
     }
 
+    fn ensure_filter_not_denylisted(
+        &self,
+        method_name: &'static str,
+        filter: &Filter,
+    ) -> Result<(), Web3Error> {
+        self.state
+            .api_config
+            .logs_denylist
+            .check(filter)
+            .map_err(|item| {
+                tracing::debug!("Rejected `{method_name}` request referencing denylisted {item}");
+                API_METRICS.web3_denylisted_logs_requests[&method_name].inc();
+                Web3Error::DenylistedLogsFilter(item)
+            })
+    }
+
     #[tracing::instrument(skip(self, filter))]
     pub async fn get_logs_impl(&self, mut filter: Filter) -> Result<Vec<Log>, Web3Error> {
         const METHOD_NAME: &str = "get_logs";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.ensure_filter_not_denylisted(METHOD_NAME, &filter)?;
         self.state.resolve_filter_block_hash(&mut filter).await?;
         let (from_block, to_block) = self.state.resolve_filter_block_range(&filter).await?;
 
@@ -271,8 +289,8 @@ impl EthNamespace {
         };
         let method_latency = API_METRICS.start_block_call(method_name, block_id);
 
-        self.state.start_info.ensure_not_pruned(block_id)?;
-        let block = self
+        self.state.start_info().ensure_not_pruned(block_id)?;
+        let mut block = self
             .state
             .connection_pool
             .access_storage_tagged("api")
@@ -287,7 +305,10 @@ impl EthNamespace {
             .await
             .map_err(|err| internal_error(method_name, err));
 
-        if let Ok(Some(block)) = &block {
+        if let Ok(Some(block)) = &mut block {
+            if !self.state.api_config.provisional_state_roots_enabled {
+                block.provisional_state_root = None;
+            }
             let block_number = MiniblockNumber(block.number.as_u32());
             self.report_latency_with_block_id(method_latency, block_number);
         } else {
@@ -305,7 +326,7 @@ impl EthNamespace {
 
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
 
-        self.state.start_info.ensure_not_pruned(block_id)?;
+        self.state.start_info().ensure_not_pruned(block_id)?;
         let tx_count = self
             .state
             .connection_pool
@@ -334,48 +355,32 @@ impl EthNamespace {
 
         let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
 
-        self.state.start_info.ensure_not_pruned(block_id)?;
+        self.state.start_info().ensure_not_pruned(block_id)?;
 
-        let block = self
+        let mut storage = self
             .state
             .connection_pool
             .access_storage_tagged("api")
             .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let block_number = storage
             .blocks_web3_dal()
-            .get_block_by_web3_block_id(block_id, false, self.state.api_config.l2_chain_id)
+            .resolve_block_id(block_id)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(block_number) = block_number else {
+            method_latency.observe_without_diff();
+            return Ok(vec![]);
+        };
 
-        let transactions: &[TransactionVariant] =
-            block.as_ref().map_or(&[], |block| &block.transactions);
-        let hashes: Vec<_> = transactions
-            .iter()
-            .map(|tx| match tx {
-                TransactionVariant::Full(tx) => tx.hash,
-                TransactionVariant::Hash(hash) => *hash,
-            })
-            .collect();
-
-        let mut receipts = self
-            .state
-            .connection_pool
-            .access_storage_tagged("api")
-            .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?
+        let receipts = storage
             .transactions_web3_dal()
-            .get_transaction_receipts(&hashes)
+            .get_block_receipts(block_number)
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        drop(storage);
 
-        receipts.sort_unstable_by_key(|receipt| receipt.transaction_index);
-
-        if let Some(block) = block {
-            self.report_latency_with_block_id(method_latency, block.number.as_u32().into());
-        } else {
-            method_latency.observe_without_diff();
-        }
-
+        self.report_latency_with_block_id(method_latency, block_number);
         Ok(receipts)
     }
 
@@ -446,6 +451,88 @@ impl EthNamespace {
         Ok(value)
     }
 
+    /// Returns Merkle proofs for the account fields and the specified storage slots of the account.
+    /// See [`AccountProof`] docs for the mapping of account fields to the zkSync storage model.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_proof_impl(
+        &self,
+        address: Address,
+        keys: Vec<H256>,
+        block_id: Option<BlockId>,
+    ) -> Result<AccountProof, Web3Error> {
+        const METHOD_NAME: &str = "get_proof";
+
+        let max_keys = self.state.api_config.get_proof_max_keys;
+        if keys.len() > max_keys {
+            return Err(Web3Error::TooManyProofKeys(max_keys));
+        }
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Latest));
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let tree_api = self
+            .state
+            .tree_api
+            .as_ref()
+            .ok_or(Web3Error::TreeApiUnavailable)?;
+        let mut connection = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let block_number = self
+            .state
+            .resolve_block(&mut connection, block_id, METHOD_NAME)
+            .await?;
+        let (l1_batch_number, root_hash) =
+            resolve_l1_batch_for_proofs(&mut connection, block_id, block_number)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?
+                .ok_or(Web3Error::ProofsNotAvailable(block_number))?;
+        self.state.start_info().ensure_not_pruned(l1_batch_number)?;
+        drop(connection);
+
+        let account_keys = [
+            storage_key_for_eth_balance(&address),
+            get_nonce_key(&address),
+            get_code_key(&address),
+        ];
+        let storage_keys = keys
+            .iter()
+            .map(|&key| StorageKey::new(AccountTreeId::new(address), key));
+        let all_keys: Vec<_> = account_keys.iter().copied().chain(storage_keys).collect();
+        let hashed_keys = all_keys.iter().map(StorageKey::hashed_key_u256).collect();
+        let proofs = tree_api
+            .get_proofs(l1_batch_number, hashed_keys)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut proofs = proofs
+            .into_iter()
+            .zip(&all_keys)
+            .map(|(proof, key)| StorageProof {
+                key: *key.key(),
+                proof: proof.merkle_path,
+                value: proof.value,
+                index: proof.index,
+            });
+
+        let account_proof: Vec<_> = proofs.by_ref().take(account_keys.len()).collect();
+        let storage_proof = proofs.collect();
+        let [balance, full_nonce, code_hash] = [0, 1, 2].map(|i| account_proof[i].value);
+        let (nonce, _) = decompose_full_nonce(h256_to_u256(full_nonce));
+
+        self.report_latency_with_block_id(method_latency, block_number);
+        Ok(AccountProof {
+            address,
+            account_proof,
+            balance: h256_to_u256(balance),
+            code_hash,
+            nonce,
+            storage_hash: root_hash,
+            storage_proof,
+            l1_batch_number,
+        })
+    }
+
     /// Account nonce.
     #[tracing::instrument(skip(self))]
     pub async fn get_transaction_count_impl(
@@ -614,6 +701,7 @@ impl EthNamespace {
                 return Err(Web3Error::TooManyTopics);
             }
         }
+        self.ensure_filter_not_denylisted(METHOD_NAME, &filter)?;
 
         self.state.resolve_filter_block_hash(&mut filter).await?;
         let from_block = self.state.get_filter_from_block(&filter).await?;
@@ -723,22 +811,75 @@ impl EthNamespace {
     }
 
     #[tracing::instrument(skip(self))]
-    pub fn syncing_impl(&self) -> SyncState {
-        if let Some(state) = &self.state.sync_state {
-            // Node supports syncing process (i.e. not the main node).
-            if state.is_synced() {
-                SyncState::NotSyncing
-            } else {
-                SyncState::Syncing(SyncInfo {
-                    starting_block: 0u64.into(), // We always start syncing from genesis right now.
-                    current_block: state.get_local_block().0.into(),
-                    highest_block: state.get_main_node_block().0.into(),
-                })
-            }
-        } else {
+    pub async fn syncing_impl(&self) -> Result<SyncState, Web3Error> {
+        const METHOD_NAME: &str = "syncing";
+
+        let Some(sync_state) = &self.state.sync_state else {
             // If there is no sync state, then the node is the main node and it's always synced.
-            SyncState::NotSyncing
+            return Ok(SyncState::NotSyncing);
+        };
+        // Node supports syncing process (i.e. not the main node).
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let progress = self
+            .l1_batch_progress(sync_state)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err));
+        method_latency.observe();
+        let progress = progress?;
+
+        // The node is only considered synced if the Merkle tree has caught up as well; otherwise,
+        // the node cannot serve proofs and L1 batch details for recent data.
+        let tree_lag = match (progress.applied, progress.tree) {
+            (Some(applied), Some(tree)) => applied.0.saturating_sub(tree.0),
+            (Some(applied), None) => applied.0 + 1,
+            (None, _) => 0,
+        };
+        let is_synced = sync_state.is_synced() && tree_lag <= MAX_TREE_L1_BATCH_LAG;
+        if is_synced {
+            return Ok(SyncState::NotSyncing);
+        }
+
+        Ok(SyncState::Syncing(SyncInfo {
+            starting_block: sync_state.get_starting_block().0.into(),
+            current_block: sync_state.get_local_block().0.into(),
+            highest_block: sync_state.get_main_node_block().0.into(),
+            fetched_block: sync_state.get_fetched_block().map(|block| block.0.into()),
+            applied_l1_batch: progress.applied.map(|number| number.0.into()),
+            tree_l1_batch: progress.tree.map(|number| number.0.into()),
+            executed_l1_batch: progress.executed.map(|number| number.0.into()),
+            estimated_time_to_head: sync_state
+                .estimated_time_to_head()
+                .map(|duration| duration.as_secs().into()),
+        }))
+    }
+
+    /// Returns L1 batch progress of the node. The progress is cached in the sync state since `eth_syncing`
+    /// may be polled frequently (e.g., by load balancers).
+    async fn l1_batch_progress(
+        &self,
+        sync_state: &SyncStateTracker,
+    ) -> anyhow::Result<L1BatchProgress> {
+        if let Some(progress) = sync_state.get_cached_l1_batch_progress(L1_BATCH_PROGRESS_CACHE_TTL)
+        {
+            return Ok(progress);
         }
+
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await?;
+        let mut blocks_dal = storage.blocks_dal();
+        let progress = L1BatchProgress {
+            applied: blocks_dal.get_sealed_l1_batch_number().await?,
+            tree: blocks_dal.get_last_l1_batch_number_with_metadata().await?,
+            executed: blocks_dal
+                .get_number_of_last_l1_batch_executed_on_eth()
+                .await?,
+        };
+        drop(storage);
+        sync_state.cache_l1_batch_progress(progress);
+        Ok(progress)
     }
 
     #[tracing::instrument(skip(self))]
@@ -879,7 +1020,7 @@ impl EthNamespace {
                     );
                 }
 
-                let get_logs_filter = GetLogsFilter {
+                let mut get_logs_filter = GetLogsFilter {
                     from_block: *from_block,
                     to_block,
                     addresses,
@@ -893,9 +1034,23 @@ impl EthNamespace {
                     .await
                     .map_err(|err| internal_error(METHOD_NAME, err))?;
 
+                // Skip miniblocks at the range boundaries that cannot contain matching logs
+                // according to their bloom filters.
+                let block_range = storage
+                    .events_web3_dal()
+                    .get_logs_block_range(&get_logs_filter)
+                    .await
+                    .map_err(|err| internal_error(METHOD_NAME, err))?;
+                let Some((candidates_from_block, candidates_to_block)) = block_range else {
+                    *from_block = to_block + 1;
+                    return Ok(FilterChanges::Logs(vec![]));
+                };
+                get_logs_filter.from_block = candidates_from_block;
+                get_logs_filter.to_block = candidates_to_block;
+
                 // Check if there is more than one block in range and there are more than `req_entities_limit` logs that satisfies filter.
                 // In this case we should return error and suggest requesting logs with smaller block range.
-                if *from_block != to_block {
+                if get_logs_filter.from_block != get_logs_filter.to_block {
                     if let Some(miniblock_number) = storage
                         .events_web3_dal()
                         .get_log_block_number(
@@ -913,11 +1068,17 @@ impl EthNamespace {
                     }
                 }
 
-                let logs = storage
+                let mut logs = storage
                     .events_web3_dal()
                     .get_logs(get_logs_filter, i32::MAX as usize)
                     .await
                     .map_err(|err| internal_error(METHOD_NAME, err))?;
+                // Filters explicitly referencing denylisted items are rejected on creation, but wildcard filters
+                // can still match denylisted logs.
+                self.state
+                    .api_config
+                    .logs_denylist
+                    .retain_allowed(&mut logs);
                 *from_block = to_block + 1;
                 FilterChanges::Logs(logs)
             }
@@ -966,3 +1127,38 @@ impl EthNamespace {
     // - `compile_solidity`.
     // - `compile_serpent`.
 }
+
+/// Resolves the L1 batch (and its state root hash) for which `eth_getProof` returns proofs. For `latest` / `pending`
+/// blocks, this is the last L1 batch processed by the Merkle tree, so that these blocks are provable even if the tree
+/// lags behind the state keeper. For other blocks, this is the L1 batch containing the block; returns `None`
+/// if this batch is not sealed or not yet processed by the tree.
+pub(crate) async fn resolve_l1_batch_for_proofs(
+    connection: &mut StorageProcessor<'_>,
+    block_id: BlockId,
+    block_number: MiniblockNumber,
+) -> anyhow::Result<Option<(L1BatchNumber, H256)>> {
+    let l1_batch_number = if matches!(
+        block_id,
+        BlockId::Number(BlockNumber::Latest | BlockNumber::Pending)
+    ) {
+        connection
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await?
+    } else {
+        connection
+            .storage_web3_dal()
+            .resolve_l1_batch_number_of_miniblock(block_number)
+            .await?
+            .miniblock_l1_batch
+    };
+    let Some(l1_batch_number) = l1_batch_number else {
+        return Ok(None);
+    };
+    // The state root hash is only persisted after the L1 batch is processed by the tree.
+    let root_hash = connection
+        .blocks_dal()
+        .get_l1_batch_state_root(l1_batch_number)
+        .await?;
+    Ok(root_hash.map(|hash| (l1_batch_number, hash)))
+}
@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+
+use zksync_types::{
+    api::{
+        LocalizedTrace, TraceAction, TraceCallAction, TraceCallType, TraceCreateAction,
+        TraceFilter, TraceResult, TraceType,
+    },
+    vm_trace::{Call, CallType},
+    zk_evm_types::FarCallOpcode,
+    Address, Bytes, H256, U256, U64,
+};
+use zksync_web3_decl::error::Web3Error;
+
+use crate::api_server::web3::{
+    backend_jsonrpsee::internal_error, metrics::API_METRICS, state::RpcState,
+};
+
+/// Number of transaction traces loaded from the storage at a time.
+const TX_TRACES_PAGE_SIZE: usize = 100;
+
+/// Location of a transaction, shared by all its traces.
+#[derive(Debug)]
+struct TxLocation {
+    block_hash: H256,
+    block_number: U64,
+    transaction_hash: H256,
+    transaction_position: U64,
+}
+
+/// Collects traces matching a [`TraceFilter`], taking pagination into account.
+#[derive(Debug)]
+struct TraceCollector {
+    from_addresses: Option<HashSet<Address>>,
+    to_addresses: Option<HashSet<Address>>,
+    traces_to_skip: usize,
+    max_traces: usize,
+    traces: Vec<LocalizedTrace>,
+}
+
+impl TraceCollector {
+    fn new(filter: &TraceFilter, max_traces: usize) -> Self {
+        Self {
+            from_addresses: filter
+                .from_address
+                .as_ref()
+                .map(|a| a.iter().copied().collect()),
+            to_addresses: filter
+                .to_address
+                .as_ref()
+                .map(|a| a.iter().copied().collect()),
+            traces_to_skip: filter.after.unwrap_or(0),
+            max_traces,
+            traces: vec![],
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.traces.len() >= self.max_traces
+    }
+
+    fn matches(&self, call: &Call) -> bool {
+        let from_matches = self
+            .from_addresses
+            .as_ref()
+            .map_or(true, |addresses| addresses.contains(&call.from));
+        let to_matches = self
+            .to_addresses
+            .as_ref()
+            .map_or(true, |addresses| addresses.contains(&call.to));
+        from_matches && to_matches
+    }
+
+    /// Visits the call and its subcalls in the depth-first order, which corresponds to the execution order.
+    fn visit(&mut self, tx: &TxLocation, call: &Call, trace_address: &mut Vec<usize>) {
+        // Near calls are not persisted in call traces; skip them just in case.
+        if self.is_full() || matches!(call.r#type, CallType::NearCall) {
+            return;
+        }
+
+        if self.matches(call) {
+            if self.traces_to_skip > 0 {
+                self.traces_to_skip -= 1;
+            } else {
+                self.traces
+                    .push(localized_trace(tx, call, trace_address.clone()));
+            }
+        }
+        for (i, subcall) in call.calls.iter().enumerate() {
+            trace_address.push(i);
+            self.visit(tx, subcall, trace_address);
+            trace_address.pop();
+        }
+    }
+}
+
+fn localized_trace(tx: &TxLocation, call: &Call, trace_address: Vec<usize>) -> LocalizedTrace {
+    let gas_used = U256::from(call.gas_used);
+    let (action, result, trace_type) = if matches!(call.r#type, CallType::Create) {
+        let action = TraceAction::Create(TraceCreateAction {
+            from: call.from,
+            value: call.value,
+            gas: call.gas.into(),
+            init: Bytes::from(call.input.clone()),
+        });
+        let result = TraceResult::Create {
+            gas_used,
+            code: Bytes::from(call.output.clone()),
+            address: call.to,
+        };
+        (action, result, TraceType::Create)
+    } else {
+        let call_type = match call.r#type {
+            CallType::Call(FarCallOpcode::Delegate) => TraceCallType::DelegateCall,
+            CallType::StaticCall => TraceCallType::StaticCall,
+            _ => TraceCallType::Call,
+        };
+        let action = TraceAction::Call(TraceCallAction {
+            from: call.from,
+            to: call.to,
+            value: call.value,
+            gas: call.gas.into(),
+            input: Bytes::from(call.input.clone()),
+            call_type,
+        });
+        let result = TraceResult::Call {
+            gas_used,
+            output: Bytes::from(call.output.clone()),
+        };
+        (action, result, TraceType::Call)
+    };
+
+    let error = call.error.clone().or_else(|| call.revert_reason.clone());
+    LocalizedTrace {
+        action,
+        result: error.is_none().then_some(result),
+        error,
+        block_hash: tx.block_hash,
+        block_number: tx.block_number,
+        subtraces: call.calls.len(),
+        trace_address,
+        transaction_hash: tx.transaction_hash,
+        transaction_position: tx.transaction_position,
+        trace_type,
+    }
+}
+
+/// OpenEthereum-compatible tracing methods.
+#[derive(Debug, Clone)]
+pub struct TraceNamespace {
+    state: RpcState,
+}
+
+impl TraceNamespace {
+    pub fn new(state: RpcState) -> Self {
+        Self { state }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn filter_impl(&self, filter: TraceFilter) -> Result<Vec<LocalizedTrace>, Web3Error> {
+        const METHOD_NAME: &str = "trace_filter";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let max_traces = self.state.api_config.trace_filter_max_traces;
+        let count = filter.count.unwrap_or(max_traces);
+        if count > max_traces {
+            return Err(Web3Error::TraceFilterCountExceeded(max_traces));
+        }
+
+        let from_block = self
+            .state
+            .resolve_filter_block_number(filter.from_block)
+            .await?;
+        let to_block = self
+            .state
+            .resolve_filter_block_number(filter.to_block)
+            .await?;
+        if to_block < from_block || count == 0 {
+            method_latency.observe();
+            return Ok(vec![]);
+        }
+        let max_block_range = self.state.api_config.trace_filter_max_block_range;
+        if to_block.0 - from_block.0 >= max_block_range {
+            return Err(Web3Error::TraceFilterBlockRangeExceeded(max_block_range));
+        }
+        self.state.start_info().ensure_not_pruned(from_block)?;
+
+        let mut storage = self
+            .state
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let mut collector = TraceCollector::new(&filter, count);
+        let mut after_tx = None;
+        loop {
+            let tx_traces = storage
+                .blocks_web3_dal()
+                .get_raw_traces_for_miniblock_range(
+                    from_block..=to_block,
+                    after_tx,
+                    filter.from_address.as_deref(),
+                    filter.to_address.as_deref(),
+                    TX_TRACES_PAGE_SIZE,
+                )
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?;
+            let is_last_page = tx_traces.len() < TX_TRACES_PAGE_SIZE;
+
+            for (block_number, block_hash, tx_trace) in tx_traces {
+                if collector.is_full() {
+                    break;
+                }
+                after_tx = Some((block_number, tx_trace.transaction_index.as_u32()));
+                let tx = TxLocation {
+                    block_hash,
+                    block_number: block_number.0.into(),
+                    transaction_hash: tx_trace.transaction_hash,
+                    transaction_position: tx_trace.transaction_index,
+                };
+                collector.visit(&tx, &tx_trace.trace, &mut vec![]);
+            }
+            if is_last_page || collector.is_full() {
+                break;
+            }
+        }
+        drop(storage);
+
+        method_latency.observe();
+        Ok(collector.traces)
+    }
+}
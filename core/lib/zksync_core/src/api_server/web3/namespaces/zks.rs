@@ -1,13 +1,19 @@
 use std::{collections::HashMap, convert::TryInto};
 
-use zksync_dal::StorageProcessor;
+use chrono::NaiveDate;
+use zksync_dal::{SqlxError, StorageProcessor};
 use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        AccountBalances, AccountBalancesCursor, AccountBalancesOptions, BaseTokenPrice,
+        BatchGasEstimate, BlockDetails, BlockId, BlockNumber, BridgeAddresses, CommitmentArtifacts,
+        GetLogsFilter, L1BatchDetails, L1GasOracleState, L2ToL1LogProof, PaymasterValidation,
+        PriorityOpStatus, Proof, ProtocolVersion, ProtocolVersionInfo, PruningInfo,
+        SimulatedTransaction, StateOverride, StorageProof, TokenTransfer, TokenTransfersRange,
+        TransactionDeadline, TransactionDetails, TransactionTrace, UtilizationReport,
     },
+    block::ContractPubdata,
     fee::Fee,
     fee_model::FeeParams,
     l1::L1Tx,
@@ -16,16 +22,18 @@ use zksync_types::{
     tokens::ETHEREUM_ADDRESS,
     transaction_request::CallRequest,
     utils::storage_key_for_standard_token_balance,
-    AccountTreeId, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, Transaction,
-    L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
+    AccountTreeId, L1BatchNumber, MiniblockNumber, PriorityOpId, ProtocolVersionId, StorageKey,
+    Transaction, L1_MESSENGER_ADDRESS, L2_ETH_TOKEN_ADDRESS,
+    REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
 use zksync_utils::{address_to_h256, h256_to_u256};
 use zksync_web3_decl::{
     error::Web3Error,
-    types::{Address, Token, H256},
+    types::{Address, Bytes, Token, H256},
 };
 
 use crate::api_server::{
+    execution_sandbox::validate_state_override,
     tree::TreeApiClient,
     web3::{backend_jsonrpsee::internal_error, metrics::API_METRICS, RpcState},
 };
@@ -56,6 +64,10 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "estimate_fee";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let gas_cap = self
+            .state
+            .gas_cap_for_call_request(&request)
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?;
         let mut request_with_gas_per_pubdata_overridden = request;
 
         self.state
@@ -76,7 +88,7 @@ impl ZksNamespace {
         tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
         tx.common_data.fee.gas_per_pubdata_limit = U256::from(DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE);
 
-        let fee = self.estimate_fee(tx.into(), METHOD_NAME).await?;
+        let fee = self.estimate_fee(tx.into(), gas_cap, METHOD_NAME).await?;
         method_latency.observe();
         Ok(fee)
     }
@@ -89,6 +101,10 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "estimate_gas_l1_to_l2";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let gas_cap = self
+            .state
+            .gas_cap_for_call_request(&request)
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?;
         let mut request_with_gas_per_pubdata_overridden = request;
         // When we're estimating fee, we are trying to deduce values related to fee, so we should
         // not consider provided ones.
@@ -102,14 +118,234 @@ impl ZksNamespace {
             .try_into()
             .map_err(Web3Error::SerializationError)?;
 
-        let fee = self.estimate_fee(tx.into(), METHOD_NAME).await?;
+        let fee = self.estimate_fee(tx.into(), gas_cap, METHOD_NAME).await?;
         method_latency.observe();
         Ok(fee.gas_limit)
     }
 
+    /// Estimates gas for multiple L2 transactions on top of the same pending block. Transactions are estimated
+    /// independently of each other; an error for a transaction is returned as a part of its estimate and doesn't
+    /// affect other transactions in the batch.
+    #[tracing::instrument(skip(self, requests))]
+    pub async fn estimate_gas_batch_impl(
+        &self,
+        requests: Vec<CallRequest>,
+    ) -> Result<Vec<BatchGasEstimate>, Web3Error> {
+        const METHOD_NAME: &str = "estimate_gas_batch";
+
+        let max_batch_size = self.state.api_config.estimate_gas_batch_max_size;
+        if requests.len() > max_batch_size {
+            return Err(Web3Error::InvalidEstimationBatchSize(max_batch_size));
+        }
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut gas_cap = None;
+        let mut txs = Vec::with_capacity(requests.len());
+        // `None` entries correspond to successfully converted transactions, which are estimated below.
+        let mut estimates = Vec::with_capacity(requests.len());
+        for request in requests {
+            let tx = match self.state.gas_cap_for_call_request(&request) {
+                Ok(cap) => {
+                    // The gas cap only depends on the API client, so it's the same for all requests.
+                    gas_cap = cap;
+                    self.state
+                        .l2_tx_for_gas_estimation(request, METHOD_NAME)
+                        .await
+                }
+                Err(err) => Err(err.into_web3_error(METHOD_NAME)),
+            };
+            match tx {
+                Ok(tx) => {
+                    txs.push(tx.into());
+                    estimates.push(None);
+                }
+                Err(err) => estimates.push(Some(BatchGasEstimate {
+                    gas: None,
+                    error: Some(err.to_string()),
+                })),
+            }
+        }
+
+        let scale_factor = self.state.api_config.estimate_gas_scale_factor;
+        let acceptable_overestimation =
+            self.state.api_config.estimate_gas_acceptable_overestimation;
+        let mut fees = self
+            .state
+            .tx_sender
+            .get_txs_fees_in_wei(txs, scale_factor, acceptable_overestimation, gas_cap)
+            .await
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?
+            .into_iter();
+        let estimates = estimates
+            .into_iter()
+            .map(|estimate| {
+                estimate.unwrap_or_else(|| {
+                    match fees.next().expect("fewer fee estimates than transactions") {
+                        Ok(fee) => BatchGasEstimate {
+                            gas: Some(fee.gas_limit),
+                            error: None,
+                        },
+                        Err(err) => BatchGasEstimate {
+                            gas: None,
+                            error: Some(err.into_web3_error(METHOD_NAME).to_string()),
+                        },
+                    }
+                })
+            })
+            .collect();
+        method_latency.observe();
+        Ok(estimates)
+    }
+
+    /// Executes a bundle of transactions one after another on top of the specified block without submitting them.
+    #[tracing::instrument(skip(self, requests, state_override))]
+    pub async fn simulate_bundle_impl(
+        &self,
+        requests: Vec<CallRequest>,
+        block_id: Option<BlockId>,
+        state_override: Option<StateOverride>,
+    ) -> Result<Vec<SimulatedTransaction>, Web3Error> {
+        const METHOD_NAME: &str = "simulate_bundle";
+
+        let max_bundle_size = self.state.api_config.simulate_bundle_max_size;
+        if requests.is_empty() || requests.len() > max_bundle_size {
+            return Err(Web3Error::InvalidBundleSize(max_bundle_size));
+        }
+        if let Some(state_override) = &state_override {
+            validate_state_override(state_override).map_err(Web3Error::InvalidStateOverride)?;
+        }
+
+        let block_id = block_id.unwrap_or(BlockId::Number(BlockNumber::Pending));
+        let method_latency = API_METRICS.start_block_call(METHOD_NAME, block_id);
+        let mut connection = self.access_storage(METHOD_NAME).await?;
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, block_id, METHOD_NAME)
+            .await?;
+        drop(connection);
+
+        let txs = requests
+            .into_iter()
+            .map(|request| L2Tx::from_request(request.into(), self.state.api_config.max_tx_size))
+            .collect::<Result<Vec<_>, _>>()?;
+        let simulated_txs = self
+            .state
+            .tx_sender
+            .simulate_bundle(block_args, txs, state_override)
+            .await
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?;
+
+        let block_diff = self
+            .state
+            .last_sealed_miniblock
+            .diff_with_block_args(&block_args);
+        method_latency.observe(block_diff);
+        Ok(simulated_txs)
+    }
+
+    #[tracing::instrument(skip(self, request))]
+    pub async fn validate_paymaster_impl(
+        &self,
+        mut request: CallRequest,
+    ) -> Result<PaymasterValidation, Web3Error> {
+        const METHOD_NAME: &str = "validate_paymaster";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.set_nonce_for_call_request(&mut request).await?;
+        let tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
+        if tx.common_data.paymaster_params.paymaster == Address::zero() {
+            return Err(Web3Error::NoPaymaster);
+        }
+
+        let validation = self
+            .state
+            .tx_sender
+            .validate_paymaster(tx)
+            .await
+            .map_err(|err| err.into_web3_error(METHOD_NAME))?;
+        method_latency.observe();
+        Ok(validation)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_token_transfers_impl(
+        &self,
+        address: Address,
+        mut range: TokenTransfersRange,
+    ) -> Result<Vec<TokenTransfer>, Web3Error> {
+        const METHOD_NAME: &str = "get_token_transfers";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let start_info = self.state.start_info();
+        if let Some(from_block) = range.from_block {
+            start_info.ensure_not_pruned(from_block)?;
+        } else {
+            range.from_block = Some(start_info.first_miniblock);
+        }
+        let entities_limit = self.state.api_config.req_entities_limit;
+        let limit = range
+            .limit
+            .map_or(entities_limit, |limit| (limit as usize).min(entities_limit));
+
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let transfers = storage
+            .token_transfers_dal()
+            .get_token_transfers(address, &range, limit)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(transfers)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_utilization_report_impl(
+        &self,
+        date: NaiveDate,
+    ) -> Result<Option<UtilizationReport>, Web3Error> {
+        const METHOD_NAME: &str = "get_utilization_report";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let report = storage
+            .utilization_reports_dal()
+            .get_report(date)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(report)
+    }
+
+    #[tracing::instrument(skip(self, tx_bytes))]
+    pub async fn send_raw_transaction_with_deadline_impl(
+        &self,
+        tx_bytes: Bytes,
+        valid_until: TransactionDeadline,
+    ) -> Result<H256, Web3Error> {
+        const METHOD_NAME: &str = "send_raw_transaction_with_deadline";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let (mut tx, hash) = self.state.parse_transaction_bytes(&tx_bytes.0)?;
+        tx.set_input(tx_bytes.0, hash);
+
+        let submit_result = self
+            .state
+            .tx_sender
+            .submit_tx_with_deadline(tx, Some(valid_until))
+            .await;
+        let submit_result = submit_result.map(|_| hash).map_err(|err| {
+            tracing::debug!("Send raw transaction with deadline error: {err}");
+            API_METRICS.submit_tx_error[&err.prom_error_code()].inc();
+            err.into_web3_error(METHOD_NAME)
+        });
+
+        method_latency.observe();
+        submit_result
+    }
+
     async fn estimate_fee(
         &self,
         tx: Transaction,
+        gas_cap: Option<u32>,
         method_name: &'static str,
     ) -> Result<Fee, Web3Error> {
         let scale_factor = self.state.api_config.estimate_gas_scale_factor;
@@ -118,7 +354,7 @@ impl ZksNamespace {
 
         self.state
             .tx_sender
-            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation)
+            .get_txs_fee_in_wei(tx, scale_factor, acceptable_overestimation, gas_cap)
             .await
             .map_err(|err| err.into_web3_error(method_name))
     }
@@ -180,6 +416,31 @@ impl ZksNamespace {
         Ok(tokens)
     }
 
+    /// Returns metadata of the token with the specified L2 address. Metadata of tokens bridged without
+    /// readable metadata is periodically refreshed from L1.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_token_info_impl(&self, address: Address) -> Result<Option<Token>, Web3Error> {
+        const METHOD_NAME: &str = "get_token_info";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let token_info = storage
+            .tokens_web3_dal()
+            .get_token_info(address)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let token = token_info.map(|token_info| Token {
+            l1_address: token_info.l1_address,
+            l2_address: token_info.l2_address,
+            name: token_info.metadata.name,
+            symbol: token_info.metadata.symbol,
+            decimals: token_info.metadata.decimals,
+        });
+        method_latency.observe();
+        Ok(token)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_all_account_balances_impl(
         &self,
@@ -194,6 +455,19 @@ impl ZksNamespace {
             .get_all_l2_token_addresses()
             .await
             .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let balances = Self::get_token_balances(&mut storage, address, &tokens)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(balances)
+    }
+
+    /// Returns non-zero balances of `address` for the specified fungible tokens.
+    async fn get_token_balances(
+        storage: &mut StorageProcessor<'_>,
+        address: Address,
+        tokens: &[Address],
+    ) -> Result<HashMap<Address, U256>, SqlxError> {
         let hashed_balance_keys = tokens.iter().map(|&token_address| {
             let token_account = AccountTreeId::new(if token_address == ETHEREUM_ADDRESS {
                 L2_ETH_TOKEN_ADDRESS
@@ -210,10 +484,9 @@ impl ZksNamespace {
         let balance_values = storage
             .storage_web3_dal()
             .get_values(&hashed_balance_keys)
-            .await
-            .map_err(|err| internal_error(METHOD_NAME, err))?;
+            .await?;
 
-        let balances = balance_values
+        Ok(balance_values
             .into_iter()
             .filter_map(|(hashed_key, balance)| {
                 let balance = h256_to_u256(balance);
@@ -222,9 +495,90 @@ impl ZksNamespace {
                 }
                 Some((hashed_key_to_token_address[&hashed_key], balance))
             })
+            .collect())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_account_balances_impl(
+        &self,
+        address: Address,
+        options: AccountBalancesOptions,
+    ) -> Result<AccountBalances, Web3Error> {
+        const METHOD_NAME: &str = "get_account_balances";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let entities_limit = self.state.api_config.req_entities_limit;
+        let limit = options
+            .limit
+            .map_or(entities_limit, |limit| (limit as usize).min(entities_limit))
+            .max(1);
+        let tokens = options.tokens.as_deref();
+
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let token_addresses = storage
+            .tokens_web3_dal()
+            .get_l2_token_addresses(options.after.map(|cursor| cursor.token), tokens, limit)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        // If the page of tokens is full, there may be more tokens after the last scanned one. We cannot return entries
+        // after this position; otherwise, balances for the unscanned tokens would be skipped.
+        let mut next_page = (token_addresses.len() == limit).then(|| AccountBalancesCursor {
+            token: *token_addresses.last().unwrap(),
+            token_id: None,
+        });
+        let balances = Self::get_token_balances(&mut storage, address, &token_addresses)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        let nfts = if options.include_nfts {
+            storage
+                .token_transfers_dal()
+                .get_nft_holdings(address, options.after.as_ref(), tokens, limit)
+                .await
+                .map_err(|err| internal_error(METHOD_NAME, err))?
+        } else {
+            vec![]
+        };
+        if nfts.len() == limit {
+            let last_cursor = nfts.last().unwrap().cursor();
+            next_page = Some(next_page.map_or(last_cursor, |cursor| cursor.min(last_cursor)));
+        }
+
+        let fungible_cursors = balances.keys().map(|&token| AccountBalancesCursor {
+            token,
+            token_id: None,
+        });
+        let mut cursors: Vec<_> = fungible_cursors
+            .chain(nfts.iter().map(|holding| holding.cursor()))
+            .filter(|cursor| next_page.map_or(true, |next_page| cursor <= &next_page))
+            .collect();
+        cursors.sort_unstable();
+        if cursors.len() > limit {
+            next_page = Some(cursors[limit - 1]);
+        }
+
+        let is_on_page = |cursor: &AccountBalancesCursor| {
+            next_page.map_or(true, |next_page| *cursor <= next_page)
+        };
+        let balances = balances
+            .into_iter()
+            .filter(|&(token, _)| {
+                is_on_page(&AccountBalancesCursor {
+                    token,
+                    token_id: None,
+                })
+            })
+            .collect();
+        let nfts = nfts
+            .into_iter()
+            .filter(|holding| is_on_page(&holding.cursor()))
             .collect();
         method_latency.observe();
-        Ok(balances)
+        Ok(AccountBalances {
+            balances,
+            nfts,
+            next_page,
+        })
     }
 
     #[tracing::instrument(skip(self))]
@@ -238,7 +592,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_l2_to_l1_msg_proof";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(block_number)?;
+        self.state.start_info().ensure_not_pruned(block_number)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let Some(l1_batch_number) = storage
             .blocks_web3_dal()
@@ -406,7 +760,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_miniblock_range";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(batch)?;
+        self.state.start_info().ensure_not_pruned(batch)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let minmax = storage
             .blocks_web3_dal()
@@ -427,7 +781,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_block_details";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(block_number)?;
+        self.state.start_info().ensure_not_pruned(block_number)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let block_details = storage
             .blocks_web3_dal()
@@ -447,7 +801,7 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_raw_block_transactions";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(block_number)?;
+        self.state.start_info().ensure_not_pruned(block_number)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let transactions = storage
             .transactions_web3_dal()
@@ -459,6 +813,26 @@ impl ZksNamespace {
         transactions
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_raw_block_traces_impl(
+        &self,
+        block_number: MiniblockNumber,
+    ) -> Result<Vec<TransactionTrace>, Web3Error> {
+        const METHOD_NAME: &str = "get_raw_block_traces";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info().ensure_not_pruned(block_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let traces = storage
+            .blocks_web3_dal()
+            .get_raw_traces_for_miniblock(block_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err));
+
+        method_latency.observe();
+        traces
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_transaction_details_impl(
         &self,
@@ -489,6 +863,24 @@ impl ZksNamespace {
         tx_details
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_priority_op_status_impl(
+        &self,
+        id: PriorityOpId,
+    ) -> Result<Option<PriorityOpStatus>, Web3Error> {
+        const METHOD_NAME: &str = "get_priority_op_status";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let status = storage
+            .transactions_web3_dal()
+            .get_priority_op_status(id)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        method_latency.observe();
+        Ok(status)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_l1_batch_details_impl(
         &self,
@@ -497,16 +889,74 @@ impl ZksNamespace {
         const METHOD_NAME: &str = "get_l1_batch";
 
         let method_latency = API_METRICS.start_call(METHOD_NAME);
-        self.state.start_info.ensure_not_pruned(batch_number)?;
+        self.state.start_info().ensure_not_pruned(batch_number)?;
         let mut storage = self.access_storage(METHOD_NAME).await?;
         let l1_batch = storage
             .blocks_web3_dal()
             .get_l1_batch_details(batch_number)
             .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+        let Some(mut l1_batch) = l1_batch else {
+            method_latency.observe();
+            return Ok(None);
+        };
+        l1_batch.prover_status = storage
+            .prover_status_dal()
+            .get_l1_batch_prover_status(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        method_latency.observe();
+        Ok(Some(l1_batch))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_commitment_artifacts_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Option<CommitmentArtifacts>, Web3Error> {
+        const METHOD_NAME: &str = "get_commitment_artifacts";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info().ensure_not_pruned(batch_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let Some(l1_batch) = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?
+        else {
+            method_latency.observe();
+            return Ok(None);
+        };
+        let blob_commitments = storage
+            .blocks_dal()
+            .get_l1_batch_blob_commitments(batch_number)
+            .await
+            .map_err(|err| internal_error(METHOD_NAME, err))?;
+
+        method_latency.observe();
+        Ok(Some(CommitmentArtifacts::new(&l1_batch, blob_commitments)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_batch_pubdata_by_contract_impl(
+        &self,
+        batch_number: L1BatchNumber,
+    ) -> Result<Vec<ContractPubdata>, Web3Error> {
+        const METHOD_NAME: &str = "get_batch_pubdata_by_contract";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        self.state.start_info().ensure_not_pruned(batch_number)?;
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let pubdata_by_contract = storage
+            .blocks_web3_dal()
+            .get_l1_batch_pubdata_by_contract(batch_number)
+            .await
             .map_err(|err| internal_error(METHOD_NAME, err));
 
         method_latency.observe();
-        l1_batch
+        pubdata_by_contract
     }
 
     #[tracing::instrument(skip(self))]
@@ -562,6 +1012,26 @@ impl ZksNamespace {
         fee_model_params
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_l1_gas_oracle_state_impl(&self) -> Option<L1GasOracleState> {
+        const METHOD_NAME: &str = "get_l1_gas_oracle_state";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let tx_sender = &self.state.tx_sender;
+        let mut oracle_state = tx_sender
+            .0
+            .batch_fee_input_provider
+            .get_l1_gas_oracle_state();
+        if let Some(oracle_state) = &mut oracle_state {
+            // Report the fee input actually used by the API, which is scaled and clamped by the fee input
+            // of the last sealed miniblock.
+            oracle_state.batch_fee_input = tx_sender.scaled_batch_fee_input().await;
+        }
+
+        method_latency.observe();
+        oracle_state
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_protocol_version_impl(
         &self,
@@ -590,6 +1060,21 @@ impl ZksNamespace {
         Ok(protocol_version)
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_protocol_versions_impl(&self) -> Result<Vec<ProtocolVersionInfo>, Web3Error> {
+        const METHOD_NAME: &str = "get_protocol_versions";
+
+        let method_latency = API_METRICS.start_call(METHOD_NAME);
+        let mut storage = self.access_storage(METHOD_NAME).await?;
+        let versions = storage
+            .protocol_versions_web3_dal()
+            .get_protocol_version_history()
+            .await;
+
+        method_latency.observe();
+        Ok(versions)
+    }
+
     #[tracing::instrument(skip_all)]
     pub async fn get_proofs_impl(
         &self,
@@ -599,7 +1084,7 @@ impl ZksNamespace {
     ) -> Result<Proof, Web3Error> {
         const METHOD_NAME: &str = "get_proofs";
 
-        self.state.start_info.ensure_not_pruned(l1_batch_number)?;
+        self.state.start_info().ensure_not_pruned(l1_batch_number)?;
         let hashed_keys = keys
             .iter()
             .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
@@ -627,4 +1112,23 @@ impl ZksNamespace {
             storage_proof,
         })
     }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_pruning_info_impl(&self) -> PruningInfo {
+        let start_info = self.state.start_info();
+        PruningInfo {
+            first_retained_block: start_info.first_miniblock,
+            first_retained_l1_batch: start_info.first_l1_batch,
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub fn get_base_token_price_impl(&self) -> Result<BaseTokenPrice, Web3Error> {
+        let fetcher = self
+            .state
+            .base_token_fetcher
+            .as_ref()
+            .ok_or(Web3Error::NotImplemented)?;
+        Ok(fetcher.price())
+    }
 }
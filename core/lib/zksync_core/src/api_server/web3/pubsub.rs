@@ -1,5 +1,10 @@
 //! (Largely) backend-agnostic logic for dealing with Web3 subscriptions.
 
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
 use anyhow::Context as _;
 use futures::FutureExt;
 use tokio::{
@@ -7,8 +12,11 @@ use tokio::{
     task::JoinHandle,
     time::{interval, Duration},
 };
-use zksync_dal::ConnectionPool;
-use zksync_types::{MiniblockNumber, H128, H256};
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_types::{
+    api::{L1BatchStage, L1BatchStatusUpdate},
+    L1BatchNumber, MiniblockNumber, H128, H256, U64,
+};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::{server::SubscriptionMessage, SubscriptionResult},
@@ -17,17 +25,29 @@ use zksync_web3_decl::{
         PendingSubscriptionSink, SendTimeoutError, SubscriptionSink,
     },
     namespaces::EthPubSubServer,
-    types::{BlockHeader, Log, PubSubFilter, PubSubResult},
+    types::{BlockHeader, Log, PubSubFilter, PubSubResult, SequencedLog},
 };
 
 use super::{
-    metrics::{SubscriptionType, PUB_SUB_METRICS},
+    metrics::{SubscriptionType, API_METRICS, PUB_SUB_METRICS},
     namespaces::eth::EVENT_TOPIC_NUMBER_LIMIT,
+    state::LogsDenylist,
 };
 use crate::api_server::execution_sandbox::BlockStartInfo;
 
 const BROADCAST_CHANNEL_CAPACITY: usize = 1024;
 const SUBSCRIPTION_SINK_SEND_TIMEOUT: Duration = Duration::from_secs(1);
+/// Maximum number of L1 batches reported for each stage in a single `l1BatchStatus` notifier iteration.
+const L1_BATCH_STATUS_LIMIT: usize = 100;
+/// Number of latest miniblocks logs from which are retained by the `logs` notifier for resubscriptions.
+const LOG_RETENTION_MINIBLOCKS: u32 = 128;
+/// Maximum number of logs retained by the `logs` notifier for resubscriptions.
+const MAX_RETAINED_LOGS: usize = 10_000;
+const INVALID_PARAMS_MESSAGE: &str = "Rejecting subscription - invalid parameters provided.";
+const NOT_RETAINED_LOGS_MESSAGE: &str =
+    "Rejecting subscription - logs after the specified sequence number are not retained.";
+const DENYLISTED_LOGS_MESSAGE: &str =
+    "Rejecting subscription - filter references a denylisted address or topic.";
 
 #[derive(Debug, Clone, Copy)]
 pub struct EthSubscriptionIdProvider;
@@ -47,6 +67,118 @@ pub(super) enum PubSubEvent {
     MiniblockAdvanced(SubscriptionType, MiniblockNumber),
 }
 
+/// Last L1 batches reported by the `l1BatchStatus` notifier for each batch stage.
+#[derive(Debug, Clone, Copy)]
+struct L1BatchStageCursors {
+    sealed: L1BatchNumber,
+    committed: L1BatchNumber,
+    proven: L1BatchNumber,
+    executed: L1BatchNumber,
+}
+
+impl L1BatchStageCursors {
+    fn advance(&mut self, update: &L1BatchStatusUpdate) {
+        let cursor = match update.stage {
+            L1BatchStage::Sealed => &mut self.sealed,
+            L1BatchStage::Committed => &mut self.committed,
+            L1BatchStage::Proven => &mut self.proven,
+            L1BatchStage::Executed => &mut self.executed,
+        };
+        *cursor = (*cursor).max(update.number);
+    }
+
+    /// Rewinds cursors to the current state of the storage after L1 batches were reverted, so that
+    /// the re-sealed batches are reported again.
+    fn rewind(&mut self, current: Self) {
+        self.sealed = self.sealed.min(current.sealed);
+        self.committed = self.committed.min(current.committed);
+        self.proven = self.proven.min(current.proven);
+        self.executed = self.executed.min(current.executed);
+    }
+}
+
+/// Returns the sequence number of a log. Sequence numbers are derived from the miniblock number and the log index
+/// in the miniblock, so that they are retained across node restarts.
+fn log_sequence_number(log: &Log) -> U64 {
+    let miniblock_number = log.block_number.expect("log without block number").as_u64();
+    let log_index = log.log_index.expect("log without index").as_u64();
+    U64::from((miniblock_number << 32) | log_index)
+}
+
+fn sequence_number_miniblock(seq: U64) -> MiniblockNumber {
+    MiniblockNumber((seq.as_u64() >> 32) as u32)
+}
+
+fn sequenced_log(log: Log) -> SequencedLog {
+    SequencedLog {
+        seq: log_sequence_number(&log),
+        log,
+    }
+}
+
+/// Logs from the latest miniblocks retained by the `logs` notifier, so that clients can resubscribe
+/// without missing logs.
+#[derive(Debug, Default)]
+struct RetainedLogs {
+    /// First miniblock logs from which are retained, or `None` if the notifier is not initialized yet.
+    first_miniblock: Option<MiniblockNumber>,
+    logs: VecDeque<SequencedLog>,
+}
+
+impl RetainedLogs {
+    fn init(&mut self, first_miniblock: MiniblockNumber, logs: Vec<SequencedLog>) {
+        self.first_miniblock = Some(first_miniblock);
+        self.logs = logs.into();
+        self.prune(first_miniblock);
+    }
+
+    fn push(&mut self, logs: &[SequencedLog], last_miniblock: MiniblockNumber) {
+        self.logs.extend(logs.iter().cloned());
+        let first_miniblock = (last_miniblock.0 + 1).saturating_sub(LOG_RETENTION_MINIBLOCKS);
+        self.prune(MiniblockNumber(first_miniblock));
+    }
+
+    fn prune(&mut self, first_miniblock: MiniblockNumber) {
+        let mut first_miniblock = self
+            .first_miniblock
+            .map_or(first_miniblock, |number| number.max(first_miniblock));
+        if self.logs.len() > MAX_RETAINED_LOGS {
+            let excess_log = &self.logs[self.logs.len() - MAX_RETAINED_LOGS - 1];
+            first_miniblock = first_miniblock.max(sequence_number_miniblock(excess_log.seq) + 1);
+        }
+        while let Some(log) = self.logs.front() {
+            if sequence_number_miniblock(log.seq) >= first_miniblock {
+                break;
+            }
+            self.logs.pop_front();
+        }
+        self.first_miniblock = Some(first_miniblock);
+    }
+
+    /// Removes logs from miniblocks after `last_miniblock` and returns them marked as removed.
+    fn revert(&mut self, last_miniblock: MiniblockNumber) -> Vec<SequencedLog> {
+        let retained_count = self
+            .logs
+            .partition_point(|log| sequence_number_miniblock(log.seq) <= last_miniblock);
+        let mut removed_logs: Vec<_> = self.logs.drain(retained_count..).collect();
+        for removed_log in &mut removed_logs {
+            removed_log.log.removed = Some(true);
+        }
+        removed_logs
+    }
+
+    /// Returns retained logs with sequence numbers greater than `seq`, or `None` if some of these logs
+    /// are not retained.
+    fn logs_after(&self, seq: U64) -> Option<Vec<SequencedLog>> {
+        let first_miniblock = self.first_miniblock?;
+        if sequence_number_miniblock(seq) < first_miniblock {
+            return None;
+        }
+        let logs = self.logs.iter().filter(|log| log.seq > seq);
+        Some(logs.cloned().collect())
+    }
+}
+
 /// Manager of notifications for a certain type of subscriptions.
 #[derive(Debug)]
 struct PubSubNotifier {
@@ -54,6 +186,7 @@ struct PubSubNotifier {
     connection_pool: ConnectionPool,
     polling_interval: Duration,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    logs_denylist: LogsDenylist,
 }
 
 impl PubSubNotifier {
@@ -174,8 +307,32 @@ impl PubSubNotifier {
             .context("get_pending_txs_hashes_after()")
     }
 
-    async fn notify_logs(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+    async fn notify_logs(
+        self,
+        retained_logs: Arc<Mutex<RetainedLogs>>,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
         let mut last_block_number = self.get_starting_miniblock_number().await?;
+        // Logs from the genesis miniblock are never retained.
+        let retained_since = last_block_number
+            .0
+            .saturating_sub(LOG_RETENTION_MINIBLOCKS - 1)
+            .max(1);
+        let retained_since = MiniblockNumber(retained_since);
+        // Loading an extra log allows `RetainedLogs` to detect and drop the partially loaded first miniblock.
+        let mut initial_logs = self
+            .latest_logs(retained_since - 1, MAX_RETAINED_LOGS + 1)
+            .await?;
+        if let Some(last_log) = initial_logs.last() {
+            last_block_number =
+                last_block_number.max(MiniblockNumber(last_log.block_number.unwrap().as_u32()));
+        }
+        self.logs_denylist.retain_allowed(&mut initial_logs);
+        let initial_logs = initial_logs.into_iter().map(sequenced_log).collect();
+        retained_logs
+            .lock()
+            .expect("retained logs are poisoned")
+            .init(retained_since, initial_logs);
 
         let mut timer = interval(self.polling_interval);
         loop {
@@ -186,13 +343,36 @@ impl PubSubNotifier {
             timer.tick().await;
 
             let db_latency = PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::Logs].start();
-            let new_logs = self.new_logs(last_block_number).await?;
+            let sealed_miniblock_number = self.sealed_miniblock_number().await?;
+            if let Some(sealed_miniblock_number) = sealed_miniblock_number {
+                if sealed_miniblock_number < last_block_number {
+                    tracing::info!(
+                        "Miniblocks after #{sealed_miniblock_number} were reverted; notifying subscribers about removed logs"
+                    );
+                    let mut retained_logs =
+                        retained_logs.lock().expect("retained logs are poisoned");
+                    let removed_logs = retained_logs.revert(sealed_miniblock_number);
+                    let removed_logs = removed_logs.into_iter().map(PubSubResult::Log).collect();
+                    self.send_pub_sub_results(removed_logs, SubscriptionType::Logs);
+                    last_block_number = sealed_miniblock_number;
+                }
+            }
+            let mut new_logs = self.new_logs(last_block_number).await?;
             db_latency.observe();
 
             if let Some(last_log) = new_logs.last() {
                 last_block_number = MiniblockNumber(last_log.block_number.unwrap().as_u32());
+                // Denylisted logs are neither retained nor broadcast, so that wildcard subscriptions don't receive them.
+                self.logs_denylist.retain_allowed(&mut new_logs);
+                let new_logs: Vec<_> = new_logs.into_iter().map(sequenced_log).collect();
+                // Retaining logs and broadcasting them is atomic w.r.t. resubscriptions, so that resubscribed clients
+                // neither miss nor receive duplicate logs.
+                let mut retained_logs = retained_logs.lock().expect("retained logs are poisoned");
+                retained_logs.push(&new_logs, last_block_number);
                 let new_logs = new_logs.into_iter().map(PubSubResult::Log).collect();
                 self.send_pub_sub_results(new_logs, SubscriptionType::Logs);
+                drop(retained_logs);
+
                 self.emit_event(PubSubEvent::MiniblockAdvanced(
                     SubscriptionType::Logs,
                     last_block_number,
@@ -203,6 +383,17 @@ impl PubSubNotifier {
         Ok(())
     }
 
+    async fn sealed_miniblock_number(&self) -> anyhow::Result<Option<MiniblockNumber>> {
+        self.connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("get_sealed_miniblock_number()")
+    }
+
     async fn new_logs(&self, last_block_number: MiniblockNumber) -> anyhow::Result<Vec<Log>> {
         self.connection_pool
             .access_storage_tagged("api")
@@ -213,6 +404,153 @@ impl PubSubNotifier {
             .await
             .context("events_web3_dal().get_all_logs()")
     }
+
+    async fn latest_logs(
+        &self,
+        last_block_number: MiniblockNumber,
+        limit: usize,
+    ) -> anyhow::Result<Vec<Log>> {
+        self.connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?
+            .events_web3_dal()
+            .get_latest_logs(last_block_number, limit)
+            .await
+            .context("events_web3_dal().get_latest_logs()")
+    }
+
+    async fn get_starting_l1_batch_cursors(&self) -> anyhow::Result<L1BatchStageCursors> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        Self::load_l1_batch_cursors(&mut storage).await
+    }
+
+    async fn load_l1_batch_cursors(
+        storage: &mut StorageProcessor<'_>,
+    ) -> anyhow::Result<L1BatchStageCursors> {
+        let sealed = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number()")?;
+        let sealed = match sealed {
+            Some(number) => number,
+            None => {
+                // We don't have L1 batches in the storage yet. Use the snapshot L1 batch number instead.
+                let start_info = BlockStartInfo::new(storage).await?;
+                L1BatchNumber(start_info.first_l1_batch.saturating_sub(1))
+            }
+        };
+
+        let committed = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_committed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_committed_on_eth()")?;
+        let proven = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_proven_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_proven_on_eth()")?;
+        let executed = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await
+            .context("get_number_of_last_l1_batch_executed_on_eth()")?;
+        Ok(L1BatchStageCursors {
+            sealed,
+            committed: committed.unwrap_or_default(),
+            proven: proven.unwrap_or_default(),
+            executed: executed.unwrap_or_default(),
+        })
+    }
+
+    async fn notify_l1_batch_statuses(
+        self,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let mut cursors = self.get_starting_l1_batch_cursors().await?;
+        let mut timer = interval(self.polling_interval);
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!(
+                    "Stop signal received, pubsub_l1_batch_status_notifier is shutting down"
+                );
+                break;
+            }
+            timer.tick().await;
+
+            let db_latency =
+                PUB_SUB_METRICS.db_poll_latency[&SubscriptionType::L1BatchStatus].start();
+            let updates = self.new_l1_batch_statuses(&mut cursors).await?;
+            db_latency.observe();
+
+            if !updates.is_empty() {
+                for update in &updates {
+                    cursors.advance(update);
+                }
+                let updates = updates
+                    .into_iter()
+                    .map(PubSubResult::L1BatchStatus)
+                    .collect();
+                self.send_pub_sub_results(updates, SubscriptionType::L1BatchStatus);
+            }
+            self.emit_event(PubSubEvent::NotifyIterationFinished(
+                SubscriptionType::L1BatchStatus,
+            ));
+        }
+        Ok(())
+    }
+
+    async fn new_l1_batch_statuses(
+        &self,
+        cursors: &mut L1BatchStageCursors,
+    ) -> anyhow::Result<Vec<L1BatchStatusUpdate>> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("api")
+            .await
+            .context("access_storage_tagged")?;
+        let sealed = storage
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await
+            .context("get_sealed_l1_batch_number()")?;
+        let sealed = sealed.unwrap_or(cursors.sealed);
+        if sealed < cursors.sealed {
+            tracing::info!(
+                "L1 batches were reverted (last sealed batch: {sealed}, last reported: {}); rewinding cursors",
+                cursors.sealed
+            );
+            let current = Self::load_l1_batch_cursors(&mut storage).await?;
+            cursors.rewind(current);
+        }
+        let sealed_updates = (cursors.sealed.0 + 1..=sealed.0)
+            .take(L1_BATCH_STATUS_LIMIT)
+            .map(|number| L1BatchStatusUpdate {
+                number: L1BatchNumber(number),
+                stage: L1BatchStage::Sealed,
+                tx_hash: None,
+            });
+        let mut updates: Vec<_> = sealed_updates.collect();
+
+        let l1_updates = storage
+            .blocks_web3_dal()
+            .get_l1_batch_stage_updates(
+                cursors.committed,
+                cursors.proven,
+                cursors.executed,
+                L1_BATCH_STATUS_LIMIT,
+            )
+            .await
+            .context("get_l1_batch_stage_updates()")?;
+        updates.extend(l1_updates);
+        Ok(updates)
+    }
 }
 
 /// Subscription support for Web3 APIs.
@@ -220,20 +558,27 @@ pub(super) struct EthSubscribe {
     blocks: broadcast::Sender<Vec<PubSubResult>>,
     transactions: broadcast::Sender<Vec<PubSubResult>>,
     logs: broadcast::Sender<Vec<PubSubResult>>,
+    retained_logs: Arc<Mutex<RetainedLogs>>,
+    l1_batch_statuses: broadcast::Sender<Vec<PubSubResult>>,
     events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
+    logs_denylist: LogsDenylist,
 }
 
 impl EthSubscribe {
-    pub fn new() -> Self {
+    pub fn new(logs_denylist: LogsDenylist) -> Self {
         let (blocks, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (transactions, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
         let (logs, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
+        let (l1_batch_statuses, _) = broadcast::channel(BROADCAST_CHANNEL_CAPACITY);
 
         Self {
             blocks,
             transactions,
             logs,
+            retained_logs: Arc::default(),
+            l1_batch_statuses,
             events_sender: None,
+            logs_denylist,
         }
     }
 
@@ -241,10 +586,10 @@ impl EthSubscribe {
         self.events_sender = Some(sender);
     }
 
-    async fn reject(sink: PendingSubscriptionSink) {
+    async fn reject(sink: PendingSubscriptionSink, message: &'static str) {
         sink.reject(ErrorObject::borrowed(
             ErrorCode::InvalidParams.code(),
-            "Rejecting subscription - invalid parameters provided.",
+            message,
             None,
         ))
         .await;
@@ -254,6 +599,7 @@ impl EthSubscribe {
         sink: SubscriptionSink,
         subscription_type: SubscriptionType,
         mut receiver: broadcast::Receiver<Vec<PubSubResult>>,
+        replayed_items: Vec<PubSubResult>,
         filter: Option<PubSubFilter>,
     ) {
         let _guard = PUB_SUB_METRICS.active_subscribers[&subscription_type].inc_guard(1);
@@ -261,6 +607,17 @@ impl EthSubscribe {
         let closed = sink.closed().fuse();
         tokio::pin!(closed);
 
+        if !replayed_items.is_empty() {
+            let handle_result =
+                Self::handle_new_items(&sink, subscription_type, replayed_items, filter.as_ref())
+                    .await;
+            if handle_result.is_err() {
+                PUB_SUB_METRICS.subscriber_send_timeouts[&subscription_type].inc();
+                lifetime_latency.observe();
+                return;
+            }
+        }
+
         loop {
             tokio::select! {
                 new_items_result = receiver.recv() => {
@@ -309,7 +666,7 @@ impl EthSubscribe {
         for item in new_items {
             if let PubSubResult::Log(log) = &item {
                 if let Some(filter) = &filter {
-                    if !filter.matches(log) {
+                    if !filter.matches(&log.log) {
                         continue;
                     }
                 }
@@ -329,6 +686,25 @@ impl EthSubscribe {
         Ok(())
     }
 
+    /// Subscribes to new logs. If the filter specifies a sequence number to resubscribe from, also returns retained logs
+    /// after it. Returns `None` if some of these logs are not retained.
+    fn subscribe_to_logs(
+        &self,
+        filter: &PubSubFilter,
+    ) -> Option<(broadcast::Receiver<Vec<PubSubResult>>, Vec<SequencedLog>)> {
+        // Subscribing is performed while holding the lock, which guarantees that the logs broadcast
+        // after the subscription immediately follow the replayed logs.
+        let retained_logs = self
+            .retained_logs
+            .lock()
+            .expect("retained logs are poisoned");
+        let replayed_logs = match filter.resubscribe_from {
+            Some(seq) => retained_logs.logs_after(seq)?,
+            None => vec![],
+        };
+        Some((self.logs.subscribe(), replayed_logs))
+    }
+
     #[tracing::instrument(skip(self, pending_sink))]
     pub async fn sub(
         &self,
@@ -346,6 +722,7 @@ impl EthSubscribe {
                     sink,
                     SubscriptionType::Blocks,
                     blocks_rx,
+                    vec![],
                     None,
                 ));
 
@@ -360,6 +737,7 @@ impl EthSubscribe {
                     sink,
                     SubscriptionType::Txs,
                     transactions_rx,
+                    vec![],
                     None,
                 ));
                 Some(SubscriptionType::Txs)
@@ -369,22 +747,46 @@ impl EthSubscribe {
                 let topic_count = filter.topics.as_ref().map_or(0, Vec::len);
 
                 if topic_count > EVENT_TOPIC_NUMBER_LIMIT {
-                    Self::reject(pending_sink).await;
+                    Self::reject(pending_sink, INVALID_PARAMS_MESSAGE).await;
+                    None
+                } else if let Err(item) = self.logs_denylist.check_pub_sub(&filter) {
+                    tracing::debug!("Rejected `logs` subscription referencing denylisted {item}");
+                    API_METRICS.web3_denylisted_logs_requests[&"subscribe"].inc();
+                    Self::reject(pending_sink, DENYLISTED_LOGS_MESSAGE).await;
                     None
                 } else {
+                    let Some((logs_rx, replayed_logs)) = self.subscribe_to_logs(&filter) else {
+                        Self::reject(pending_sink, NOT_RETAINED_LOGS_MESSAGE).await;
+                        return;
+                    };
                     let Ok(sink) = pending_sink.accept().await else {
                         return;
                     };
-                    let logs_rx = self.logs.subscribe();
+                    let replayed_logs = replayed_logs.into_iter().map(PubSubResult::Log).collect();
                     tokio::spawn(Self::run_subscriber(
                         sink,
                         SubscriptionType::Logs,
                         logs_rx,
+                        replayed_logs,
                         Some(filter),
                     ));
                     Some(SubscriptionType::Logs)
                 }
             }
+            "l1BatchStatus" => {
+                let Ok(sink) = pending_sink.accept().await else {
+                    return;
+                };
+                let l1_batch_statuses_rx = self.l1_batch_statuses.subscribe();
+                tokio::spawn(Self::run_subscriber(
+                    sink,
+                    SubscriptionType::L1BatchStatus,
+                    l1_batch_statuses_rx,
+                    vec![],
+                    None,
+                ));
+                Some(SubscriptionType::L1BatchStatus)
+            }
             "syncing" => {
                 let Ok(sink) = pending_sink.accept().await else {
                     return;
@@ -400,7 +802,7 @@ impl EthSubscribe {
                 None
             }
             _ => {
-                Self::reject(pending_sink).await;
+                Self::reject(pending_sink, INVALID_PARAMS_MESSAGE).await;
                 None
             }
         };
@@ -419,13 +821,14 @@ impl EthSubscribe {
         polling_interval: Duration,
         stop_receiver: watch::Receiver<bool>,
     ) -> Vec<JoinHandle<anyhow::Result<()>>> {
-        let mut notifier_tasks = Vec::with_capacity(3);
+        let mut notifier_tasks = Vec::with_capacity(4);
 
         let notifier = PubSubNotifier {
             sender: self.blocks.clone(),
             connection_pool: connection_pool.clone(),
             polling_interval,
             events_sender: self.events_sender.clone(),
+            logs_denylist: self.logs_denylist.clone(),
         };
         let notifier_task = tokio::spawn(notifier.notify_blocks(stop_receiver.clone()));
         notifier_tasks.push(notifier_task);
@@ -435,17 +838,30 @@ impl EthSubscribe {
             connection_pool: connection_pool.clone(),
             polling_interval,
             events_sender: self.events_sender.clone(),
+            logs_denylist: self.logs_denylist.clone(),
         };
         let notifier_task = tokio::spawn(notifier.notify_txs(stop_receiver.clone()));
         notifier_tasks.push(notifier_task);
 
         let notifier = PubSubNotifier {
             sender: self.logs.clone(),
+            connection_pool: connection_pool.clone(),
+            polling_interval,
+            events_sender: self.events_sender.clone(),
+            logs_denylist: self.logs_denylist.clone(),
+        };
+        let notifier_task =
+            tokio::spawn(notifier.notify_logs(self.retained_logs.clone(), stop_receiver.clone()));
+        notifier_tasks.push(notifier_task);
+
+        let notifier = PubSubNotifier {
+            sender: self.l1_batch_statuses.clone(),
             connection_pool,
             polling_interval,
             events_sender: self.events_sender.clone(),
+            logs_denylist: self.logs_denylist.clone(),
         };
-        let notifier_task = tokio::spawn(notifier.notify_logs(stop_receiver));
+        let notifier_task = tokio::spawn(notifier.notify_l1_batch_statuses(stop_receiver));
 
         notifier_tasks.push(notifier_task);
         notifier_tasks
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashSet,
     future::Future,
     sync::{
         atomic::{AtomicU32, Ordering},
@@ -8,24 +9,33 @@ use std::{
 };
 
 use lru::LruCache;
+use sha2::{Digest, Sha256};
 use tokio::sync::{watch, Mutex};
 use vise::GaugeGuard;
 use zksync_config::configs::{api::Web3JsonRpcConfig, chain::NetworkConfig, ContractsConfig};
 use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
-    api, l2::L2Tx, transaction_request::CallRequest, Address, L1BatchNumber, L1ChainId, L2ChainId,
-    MiniblockNumber, H256, U256, U64,
+    api,
+    l2::{L2Tx, TransactionType},
+    transaction_request::CallRequest,
+    Address, L1BatchNumber, L1ChainId, L2ChainId, MiniblockNumber, H256, U256, U64,
+};
+use zksync_web3_decl::{
+    error::{DenylistedLogsFilter, Web3Error},
+    types::{Filter, Log, PubSubFilter},
 };
-use zksync_web3_decl::{error::Web3Error, types::Filter};
 
 use super::metrics::{FilterType, FILTER_METRICS};
 use crate::{
     api_server::{
-        execution_sandbox::{BlockArgs, BlockArgsError, BlockStartInfo},
+        execution_sandbox::{ApiClientId, BlockArgs, BlockArgsError, BlockStartInfo},
         tree::TreeApiHttpClient,
-        tx_sender::TxSender,
+        tx_sender::{SubmitTxError, TxSender},
         web3::{backend_jsonrpsee::internal_error, TypedFilter},
     },
+    base_token_fetcher::BaseTokenFetcher,
+    state_keeper::PubdataModeSwitch,
     sync_layer::SyncState,
 };
 
@@ -87,6 +97,14 @@ pub struct InternalApiConfig {
     pub req_entities_limit: usize,
     pub fee_history_limit: u64,
     pub filters_disabled: bool,
+    pub logs_denylist: LogsDenylist,
+    pub provisional_state_roots_enabled: bool,
+    pub trace_filter_max_block_range: u32,
+    pub trace_filter_max_traces: usize,
+    pub estimate_gas_batch_max_size: usize,
+    pub get_proof_max_keys: usize,
+    pub simulate_bundle_max_size: usize,
+    pub gas_caps: GasCaps,
 }
 
 impl InternalApiConfig {
@@ -114,6 +132,134 @@ impl InternalApiConfig {
             req_entities_limit: web3_config.req_entities_limit(),
             fee_history_limit: web3_config.fee_history_limit(),
             filters_disabled: web3_config.filters_disabled,
+            logs_denylist: LogsDenylist::new(
+                web3_config.logs_denylisted_addresses.iter().copied(),
+                web3_config.logs_denylisted_topics.iter().copied(),
+            ),
+            provisional_state_roots_enabled: web3_config.provisional_state_roots_enabled,
+            trace_filter_max_block_range: web3_config.trace_filter_max_block_range(),
+            trace_filter_max_traces: web3_config.trace_filter_max_traces(),
+            estimate_gas_batch_max_size: web3_config.estimate_gas_batch_max_size(),
+            get_proof_max_keys: web3_config.get_proof_max_keys(),
+            simulate_bundle_max_size: web3_config.simulate_bundle_max_size(),
+            gas_caps: GasCaps::new(
+                web3_config.rpc_gas_cap,
+                web3_config.rpc_privileged_gas_cap,
+                web3_config.rpc_privileged_api_key_hashes.iter().copied(),
+            ),
+        }
+    }
+}
+
+/// Maximum gas for sandbox executions (`eth_call`, gas estimation etc.) depending on the API client.
+#[derive(Debug, Clone, Default)]
+pub struct GasCaps {
+    default_cap: Option<u32>,
+    privileged_cap: Option<u32>,
+    /// SHA-256 hashes of privileged API keys.
+    privileged_api_key_hashes: HashSet<H256>,
+}
+
+impl GasCaps {
+    pub fn new(
+        default_cap: Option<u32>,
+        privileged_cap: Option<u32>,
+        privileged_api_key_hashes: impl IntoIterator<Item = H256>,
+    ) -> Self {
+        Self {
+            default_cap,
+            privileged_cap,
+            privileged_api_key_hashes: privileged_api_key_hashes.into_iter().collect(),
+        }
+    }
+
+    fn hash_api_key(key: &str) -> H256 {
+        H256(Sha256::digest(key.as_bytes()).into())
+    }
+
+    /// Returns the gas cap for the specified client; `None` means that executions are not capped.
+    pub(crate) fn for_client(&self, client: &ApiClientId) -> Option<u32> {
+        match client {
+            ApiClientId::ApiKey(key)
+                if self
+                    .privileged_api_key_hashes
+                    .contains(&Self::hash_api_key(key)) =>
+            {
+                self.privileged_cap
+            }
+            _ => self.default_cap,
+        }
+    }
+}
+
+/// Contract addresses and event topics for which logs are not served by the API (e.g., high-cardinality spam tokens).
+#[derive(Debug, Clone, Default)]
+pub struct LogsDenylist {
+    addresses: HashSet<Address>,
+    topics: HashSet<H256>,
+}
+
+impl LogsDenylist {
+    pub fn new(
+        addresses: impl IntoIterator<Item = Address>,
+        topics: impl IntoIterator<Item = H256>,
+    ) -> Self {
+        Self {
+            addresses: addresses.into_iter().collect(),
+            topics: topics.into_iter().collect(),
+        }
+    }
+
+    /// Checks whether the filter explicitly references a denylisted address or topic. Filters not referencing
+    /// any addresses / topics are allowed; denylisted logs are removed from their results
+    /// by [`Self::retain_allowed()`].
+    pub(super) fn check(&self, filter: &Filter) -> Result<(), DenylistedLogsFilter> {
+        let addresses = filter.address.iter().flat_map(|addresses| &addresses.0);
+        let topics = filter
+            .topics
+            .iter()
+            .flatten()
+            .flatten()
+            .flat_map(|topics| &topics.0);
+        self.check_references(addresses, topics)
+    }
+
+    /// Same as [`Self::check()`], but for `logs` subscription filters.
+    pub(super) fn check_pub_sub(&self, filter: &PubSubFilter) -> Result<(), DenylistedLogsFilter> {
+        let addresses = filter.address.iter().flat_map(|addresses| &addresses.0);
+        let topics = filter
+            .topics
+            .iter()
+            .flatten()
+            .flatten()
+            .flat_map(|topics| &topics.0);
+        self.check_references(addresses, topics)
+    }
+
+    fn check_references<'a>(
+        &self,
+        mut addresses: impl Iterator<Item = &'a Address>,
+        mut topics: impl Iterator<Item = &'a H256>,
+    ) -> Result<(), DenylistedLogsFilter> {
+        if let Some(&address) = addresses.find(|&address| self.addresses.contains(address)) {
+            return Err(DenylistedLogsFilter::Address(address));
+        }
+        if let Some(&topic) = topics.find(|&topic| self.topics.contains(topic)) {
+            return Err(DenylistedLogsFilter::Topic(topic));
+        }
+        Ok(())
+    }
+
+    /// Checks whether the log was emitted by a denylisted contract or has a denylisted topic.
+    fn is_denylisted(&self, log: &Log) -> bool {
+        self.addresses.contains(&log.address)
+            || log.topics.iter().any(|topic| self.topics.contains(topic))
+    }
+
+    /// Removes denylisted logs, e.g. ones matched by a filter not referencing any addresses / topics.
+    pub(super) fn retain_allowed(&self, logs: &mut Vec<Log>) {
+        if !self.addresses.is_empty() || !self.topics.is_empty() {
+            logs.retain(|log| !self.is_denylisted(log));
         }
     }
 }
@@ -201,14 +347,21 @@ pub struct RpcState {
     pub tree_api: Option<TreeApiHttpClient>,
     pub tx_sender: TxSender,
     pub sync_state: Option<SyncState>,
+    pub(super) base_token_fetcher: Option<Arc<BaseTokenFetcher>>,
+    pub(super) pubdata_mode_switch: Option<PubdataModeSwitch>,
     pub(super) api_config: InternalApiConfig,
     /// Number of the first locally available miniblock / L1 batch. May differ from 0 if the node state was recovered
-    /// from a snapshot.
-    pub(super) start_info: BlockStartInfo,
+    /// from a snapshot, or if the node prunes its old data.
+    pub(super) start_info: watch::Receiver<BlockStartInfo>,
     pub(super) last_sealed_miniblock: SealedMiniblockNumber,
 }
 
 impl RpcState {
+    /// Returns the current information about the first locally available miniblock / L1 batch.
+    pub(super) fn start_info(&self) -> BlockStartInfo {
+        *self.start_info.borrow()
+    }
+
     pub fn parse_transaction_bytes(&self, bytes: &[u8]) -> Result<(L2Tx, H256), Web3Error> {
         let chain_id = self.api_config.l2_chain_id;
         let (tx_request, hash) = api::TransactionRequest::from_bytes(bytes, chain_id)?;
@@ -233,7 +386,7 @@ impl RpcState {
         block: api::BlockId,
         method_name: &'static str,
     ) -> Result<MiniblockNumber, Web3Error> {
-        self.start_info.ensure_not_pruned(block)?;
+        self.start_info().ensure_not_pruned(block)?;
         let result = connection.blocks_web3_dal().resolve_block_id(block).await;
         result
             .map_err(|err| internal_error(method_name, err))?
@@ -246,7 +399,7 @@ impl RpcState {
         block: api::BlockId,
         method_name: &'static str,
     ) -> Result<BlockArgs, Web3Error> {
-        BlockArgs::new(connection, block, self.start_info)
+        BlockArgs::new(connection, block, self.start_info())
             .await
             .map_err(|err| match err {
                 BlockArgsError::Pruned(number) => Web3Error::PrunedBlock(number),
@@ -370,6 +523,64 @@ impl RpcState {
         call_request.nonce = Some(address_historical_nonce);
         Ok(())
     }
+
+    /// Returns the gas cap for sandbox executions performed on behalf of the current API client, checking that
+    /// the gas explicitly provided in the call request (if any) doesn't exceed it.
+    pub(crate) fn gas_cap_for_call_request(
+        &self,
+        request: &CallRequest,
+    ) -> Result<Option<u32>, SubmitTxError> {
+        let gas_cap = self.api_config.gas_caps.for_client(&ApiClientId::current());
+        if let (Some(cap), Some(provided)) = (gas_cap, request.gas) {
+            if provided > U256::from(cap) {
+                return Err(SubmitTxError::GasCapExceeded {
+                    provided: Some(provided),
+                    cap,
+                });
+            }
+        }
+        Ok(gas_cap)
+    }
+
+    /// Converts a call request into an L2 transaction suitable for gas estimation (as in `eth_estimateGas`).
+    pub(crate) async fn l2_tx_for_gas_estimation(
+        &self,
+        request: CallRequest,
+        method_name: &'static str,
+    ) -> Result<L2Tx, Web3Error> {
+        let mut request_with_gas_per_pubdata_overridden = request;
+        self.set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
+            .await?;
+
+        if let Some(eip712_meta) = &mut request_with_gas_per_pubdata_overridden.eip712_meta {
+            if eip712_meta.gas_per_pubdata == U256::zero() {
+                eip712_meta.gas_per_pubdata = DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE.into();
+            }
+        }
+
+        let is_eip712 = request_with_gas_per_pubdata_overridden
+            .eip712_meta
+            .is_some();
+
+        let mut tx: L2Tx = L2Tx::from_request(
+            request_with_gas_per_pubdata_overridden.into(),
+            self.api_config.max_tx_size,
+        )?;
+
+        // The user may not include the proper transaction type during the estimation of
+        // the gas fee. However, it is needed for the bootloader checks to pass properly.
+        if is_eip712 {
+            tx.common_data.transaction_type = TransactionType::EIP712Transaction;
+        }
+
+        // When we're estimating fee, we are trying to deduce values related to fee, so we should
+        // not consider provided ones.
+        let gas_price = self.tx_sender.gas_price().await;
+        let gas_price = gas_price.map_err(|err| internal_error(method_name, err))?;
+        tx.common_data.fee.max_fee_per_gas = gas_price.into();
+        tx.common_data.fee.max_priority_fee_per_gas = tx.common_data.fee.max_fee_per_gas;
+        Ok(tx)
+    }
 }
 
 /// Contains mapping from index to `Filter`x with optional location.
@@ -504,4 +715,44 @@ mod tests {
         assert!(filters.0.contains(&idx2));
         assert!(!filters.0.contains(&idx3));
     }
+
+    #[test]
+    fn gas_caps_for_clients() {
+        use super::*;
+
+        // SHA-256 hash of `admin`
+        let admin_key_hash: H256 =
+            "0x8c6976e5b5410415bde908bd4dee15dfb167a9c873fc4bb8a81f6f2ab448a918"
+                .parse()
+                .unwrap();
+        let gas_caps = GasCaps::new(Some(1_000_000), Some(10_000_000), [admin_key_hash]);
+        assert_eq!(
+            gas_caps.for_client(&ApiClientId::Anonymous),
+            Some(1_000_000)
+        );
+        assert_eq!(
+            gas_caps.for_client(&ApiClientId::Ip([127, 0, 0, 1].into())),
+            Some(1_000_000)
+        );
+        assert_eq!(
+            gas_caps.for_client(&ApiClientId::ApiKey("user".to_owned())),
+            Some(1_000_000)
+        );
+        assert_eq!(
+            gas_caps.for_client(&ApiClientId::ApiKey("admin".to_owned())),
+            Some(10_000_000)
+        );
+        // The hash itself must not be accepted as a key.
+        assert_eq!(
+            gas_caps.for_client(&ApiClientId::ApiKey(format!("{admin_key_hash:?}"))),
+            Some(1_000_000)
+        );
+
+        let gas_caps = GasCaps::new(Some(1_000_000), None, [admin_key_hash]);
+        assert_eq!(
+            gas_caps.for_client(&ApiClientId::ApiKey("admin".to_owned())),
+            None
+        );
+        assert_eq!(GasCaps::default().for_client(&ApiClientId::Anonymous), None);
+    }
 }
@@ -0,0 +1,51 @@
+//! Tests for the `admin` Web3 namespace.
+
+use zksync_types::api::ValidationAllowListEntry;
+use zksync_web3_decl::namespaces::AdminNamespaceClient;
+
+use super::*;
+
+#[derive(Debug)]
+struct ValidationAllowListReloadTest;
+
+#[async_trait]
+impl HttpTest for ValidationAllowListReloadTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let default_entries = client.get_validation_allow_list().await?;
+        assert!(!default_entries.is_empty());
+
+        let new_entry = ValidationAllowListEntry::Slot {
+            address: Some(Address::repeat_byte(0x11)),
+            slot: H256::repeat_byte(0x22),
+        };
+        let mut storage = pool.access_storage().await?;
+        storage
+            .validation_allow_list_dal()
+            .insert_entry(&new_entry)
+            .await?;
+        drop(storage);
+
+        // Changes made by other processes (e.g., other API servers) are picked up without an explicit reload.
+        let entries = client.get_validation_allow_list().await?;
+        assert_eq!(entries.len(), default_entries.len() + 1);
+        assert_eq!(entries.last(), Some(&new_entry));
+
+        let reloaded_entries = client.reload_validation_allow_list().await?;
+        assert_eq!(reloaded_entries, entries);
+
+        let mut storage = pool.access_storage().await?;
+        storage
+            .validation_allow_list_dal()
+            .remove_entry(&new_entry)
+            .await?;
+        drop(storage);
+        let entries = client.get_validation_allow_list().await?;
+        assert_eq!(entries, default_entries);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn reloading_validation_allow_list() {
+    test_http_server(ValidationAllowListReloadTest).await;
+}
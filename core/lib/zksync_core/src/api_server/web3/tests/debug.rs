@@ -1,7 +1,10 @@
 //! Tests for the `debug` Web3 namespace.
 
-use zksync_types::{tx::TransactionExecutionResult, vm_trace::Call, BOOTLOADER_ADDRESS};
-use zksync_web3_decl::namespaces::DebugNamespaceClient;
+use zksync_types::{
+    tx::TransactionExecutionResult, vm_trace::Call, L2ChainId, PackedEthSignature,
+    BOOTLOADER_ADDRESS, EIP_712_TX_TYPE,
+};
+use zksync_web3_decl::namespaces::{DebugNamespaceClient, ZksNamespaceClient};
 
 use super::*;
 
@@ -92,6 +95,36 @@ async fn tracing_block() {
     test_http_server(TraceBlockTest(MiniblockNumber(1))).await;
 }
 
+#[derive(Debug)]
+struct RawBlockTracesTest;
+
+#[async_trait]
+impl HttpTest for RawBlockTracesTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let tx_results = [0, 1, 2].map(execute_l2_transaction_with_traces);
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let traces = client.get_raw_block_traces(MiniblockNumber(1)).await?;
+        assert_eq!(traces.len(), tx_results.len());
+        for (i, (trace, tx_result)) in traces.iter().zip(&tx_results).enumerate() {
+            assert_eq!(trace.transaction_hash, tx_result.hash);
+            assert_eq!(trace.transaction_index, U64::from(i));
+            assert_eq!(trace.trace, tx_result.call_trace().unwrap());
+        }
+
+        let traces = client.get_raw_block_traces(MiniblockNumber(100)).await?;
+        assert!(traces.is_empty());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_raw_block_traces() {
+    test_http_server(RawBlockTracesTest).await;
+}
+
 #[derive(Debug)]
 struct TraceTransactionTest;
 
@@ -163,3 +196,62 @@ impl HttpTest for TraceBlockTestWithSnapshotRecovery {
 async fn tracing_block_after_snapshot_recovery() {
     test_http_server(TraceBlockTestWithSnapshotRecovery).await;
 }
+
+#[derive(Debug)]
+struct ExplainSignatureTest;
+
+#[async_trait]
+impl HttpTest for ExplainSignatureTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let private_key = H256::repeat_byte(0x42);
+        let address = PackedEthSignature::address_from_private_key(&private_key).unwrap();
+        let chain_id = L2ChainId::default();
+        let tx_request = api::TransactionRequest {
+            nonce: 0.into(),
+            from: Some(address),
+            to: Some(Address::repeat_byte(1)),
+            gas: 100_000.into(),
+            gas_price: 250_000_000.into(),
+            max_priority_fee_per_gas: Some(0.into()),
+            transaction_type: Some(EIP_712_TX_TYPE.into()),
+            eip712_meta: Some(api::Eip712Meta {
+                gas_per_pubdata: 50_000.into(),
+                factory_deps: None,
+                custom_signature: Some(vec![0; 32]),
+                paymaster_params: None,
+            }),
+            chain_id: Some(chain_id.as_u64()),
+            ..api::TransactionRequest::default()
+        };
+        let tx_bytes = tx_request.get_signed_bytes(&PackedEthSignature::default(), chain_id);
+
+        let report = client.explain_signature(tx_bytes.clone().into()).await?;
+        assert_eq!(report.expected_chain_id, chain_id.as_u64());
+        assert_eq!(report.initiator, Some(address));
+        assert!(!report.initiator_is_contract);
+        assert_eq!(
+            report.issues,
+            [api::SignatureIssue::UnexpectedSignatureLength { length: 32 }]
+        );
+
+        // Deploy a contract to the initiator address; custom signatures are checked by the account itself.
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        let code_log = StorageLog::new_write_log(get_code_key(&address), H256::repeat_byte(0xff));
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), vec![code_log])])
+            .await?;
+        drop(storage);
+
+        let report = client.explain_signature(tx_bytes.into()).await?;
+        assert!(report.initiator_is_contract);
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn explaining_signature() {
+    test_http_server(ExplainSignatureTest).await;
+}
@@ -3,7 +3,10 @@
 use std::fmt::Debug;
 
 use jsonrpsee::{core::client::Error, types::error::ErrorCode};
-use zksync_web3_decl::{jsonrpsee::core::ClientError as RpcError, types::FilterChanges};
+use zksync_web3_decl::{
+    jsonrpsee::core::ClientError as RpcError,
+    types::{FilterChanges, ValueOrArray},
+};
 
 use super::*;
 
@@ -303,3 +306,133 @@ impl HttpTest for DisableFiltersTest {
 async fn disable_filters() {
     test_http_server(DisableFiltersTest).await;
 }
+
+fn assert_denylisted<T: Debug>(result: Result<T, Error>, expected_data: serde_json::Value) {
+    assert_matches!(result, Err(Error::Call(e)) => {
+        assert_eq!(e.code(), ErrorCode::InvalidParams.code());
+        assert!(e.message().contains("are not served"), "{e:?}");
+        let data: serde_json::Value = serde_json::from_str(e.data().unwrap().get()).unwrap();
+        assert_eq!(data, expected_data);
+    });
+}
+
+#[derive(Debug)]
+struct LogsDenylistTest;
+
+impl LogsDenylistTest {
+    const DENYLISTED_ADDRESS: Address = Address::repeat_byte(23);
+    const DENYLISTED_TOPIC: H256 = H256::repeat_byte(111);
+}
+
+#[async_trait]
+impl HttpTest for LogsDenylistTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let address_filter = Filter {
+            address: Some(ValueOrArray(vec![
+                Address::repeat_byte(1),
+                Self::DENYLISTED_ADDRESS,
+            ])),
+            ..Filter::default()
+        };
+        let expected_data = serde_json::json!({
+            "kind": "address",
+            "value": format!("{:?}", Self::DENYLISTED_ADDRESS),
+        });
+        assert_denylisted(
+            client.get_logs(address_filter.clone()).await,
+            expected_data.clone(),
+        );
+        assert_denylisted(client.new_filter(address_filter).await, expected_data);
+
+        let topics_filter = Filter {
+            topics: Some(vec![None, Some(Self::DENYLISTED_TOPIC.into())]),
+            ..Filter::default()
+        };
+        let expected_data = serde_json::json!({
+            "kind": "topic",
+            "value": format!("{:?}", Self::DENYLISTED_TOPIC),
+        });
+        assert_denylisted(
+            client.get_logs(topics_filter.clone()).await,
+            expected_data.clone(),
+        );
+        assert_denylisted(client.new_filter(topics_filter).await, expected_data);
+
+        // Filters not referencing denylisted items should be served as usual.
+        let allowed_filter = Filter {
+            address: Some(Address::repeat_byte(1).into()),
+            topics: Some(vec![Some(H256::repeat_byte(1).into())]),
+            ..Filter::default()
+        };
+        let logs = client.get_logs(allowed_filter.clone()).await?;
+        assert!(logs.is_empty(), "{logs:?}");
+        client.new_filter(allowed_filter).await?;
+        let wildcard_filter_id = client.new_filter(Filter::default()).await?;
+
+        let mut storage = pool.access_storage().await?;
+        let (_, events) = store_events(&mut storage, 1, 0).await?;
+        drop(storage);
+        // Events #0 and #3 are emitted by the denylisted address; event #3 additionally has the denylisted topic.
+        let allowed_events = [&events[1], &events[2]];
+
+        // Denylisted logs must not be served for wildcard filters.
+        let logs = client.get_logs(Filter::default()).await?;
+        assert_logs_match(&logs, &allowed_events);
+        let changes = client.get_filter_changes(wildcard_filter_id).await?;
+        let FilterChanges::Logs(logs) = changes else {
+            panic!("Unexpected getFilterChanges output: {changes:?}");
+        };
+        assert_logs_match(&logs, &allowed_events);
+        Ok(())
+    }
+
+    fn logs_denylist(&self) -> LogsDenylist {
+        LogsDenylist::new([Self::DENYLISTED_ADDRESS], [Self::DENYLISTED_TOPIC])
+    }
+}
+
+#[tokio::test]
+async fn logs_denylist() {
+    test_http_server(LogsDenylistTest).await;
+}
+
+#[derive(Debug)]
+struct StreamedLogsTest;
+
+#[async_trait]
+impl HttpTest for StreamedLogsTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        let (_, events) = store_events(&mut storage, 1, 0).await?;
+        drop(storage);
+        let events: Vec<_> = events.iter().collect();
+
+        let all_logs = client.get_logs(Filter::default()).await?;
+        assert_logs_match(&all_logs, &events);
+        let address_filter = Filter {
+            address: Some(Address::repeat_byte(23).into()),
+            ..Filter::default()
+        };
+        let address_logs = client.get_logs(address_filter).await?;
+        assert_logs_match(&address_logs, &[events[0], events[3]]);
+
+        // Errors should be returned in the same way as for non-streamed methods.
+        let invalid_filter = Filter {
+            block_hash: Some(H256::repeat_byte(1)),
+            from_block: Some(api::BlockNumber::Earliest),
+            ..Filter::default()
+        };
+        let err = client.get_logs(invalid_filter).await.unwrap_err();
+        assert_matches!(err, RpcError::Call(err) if err.code() == ErrorCode::InvalidParams.code());
+        Ok(())
+    }
+
+    fn streamed_methods(&self) -> Vec<String> {
+        vec!["eth_getLogs".to_owned()]
+    }
+}
+
+#[tokio::test]
+async fn streamed_logs() {
+    test_http_server(StreamedLogsTest).await;
+}
@@ -1,4 +1,9 @@
-use std::{collections::HashMap, pin::Pin, slice, time::Instant};
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    slice,
+    time::Instant,
+};
 
 use assert_matches::assert_matches;
 use async_trait::async_trait;
@@ -15,40 +20,53 @@ use zksync_health_check::CheckHealth;
 use zksync_types::{
     api,
     block::MiniblockHeader,
+    ethabi,
+    event::{TRANSFER_EVENT_SIGNATURE, TRANSFER_SINGLE_EVENT_SIGNATURE},
     fee::TransactionExecutionMetrics,
+    fee_model::{BatchFeeInput, FeeParams},
     get_nonce_key,
     l2::L2Tx,
     storage::get_code_key,
-    tokens::{TokenInfo, TokenMetadata},
+    tokens::{NftStandard, TokenInfo, TokenMetadata},
     tx::{
         tx_execution_info::TxExecutionStatus, ExecutionMetrics, IncludedTxLocation,
         TransactionExecutionResult,
     },
     utils::{storage_key_for_eth_balance, storage_key_for_standard_token_balance},
-    AccountTreeId, Address, L1BatchNumber, Nonce, StorageKey, StorageLog, VmEvent, H256, U64,
+    AccountTreeId, Address, L1BatchNumber, L2ChainId, Nonce, StorageKey, StorageLog, VmEvent, H256,
+    U64,
 };
-use zksync_utils::u256_to_h256;
+use zksync_utils::{address_to_h256, u256_to_h256};
 use zksync_web3_decl::{
     jsonrpsee::{http_client::HttpClient, types::error::ErrorCode},
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
+    types::SyncState as ApiSyncState,
 };
 
-use super::{metrics::ApiTransportLabel, *};
+use super::{
+    metrics::ApiTransportLabel,
+    namespaces::eth::resolve_l1_batch_for_proofs,
+    state::{GasCaps, LogsDenylist},
+    *,
+};
 use crate::{
     api_server::{
         execution_sandbox::testonly::MockTransactionExecutor,
         tx_sender::tests::create_test_tx_sender,
     },
     genesis::{ensure_genesis_state, GenesisParams},
+    sync_layer::SyncState,
     utils::testonly::{
         create_l1_batch, create_l1_batch_metadata, create_l2_transaction, create_miniblock,
         l1_batch_metadata_to_commitment_artifacts, prepare_recovery_snapshot,
     },
 };
 
+mod admin;
 mod debug;
 mod filters;
 mod snapshots;
+mod trace;
 mod vm;
 mod ws;
 
@@ -111,6 +129,8 @@ pub(crate) async fn spawn_http_server(
         pool,
         None,
         tx_executor,
+        vec![],
+        None,
         stop_receiver,
     )
     .await
@@ -129,6 +149,8 @@ async fn spawn_ws_server(
         pool,
         websocket_requests_per_minute_limit,
         MockTransactionExecutor::default(),
+        vec![],
+        None,
         stop_receiver,
     )
     .await
@@ -140,6 +162,8 @@ async fn spawn_server(
     pool: ConnectionPool,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tx_executor: MockTransactionExecutor,
+    streamed_methods: Vec<String>,
+    sync_state: Option<SyncState>,
     stop_receiver: watch::Receiver<bool>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     let (tx_sender, vm_barrier) =
@@ -147,10 +171,17 @@ async fn spawn_server(
     let (pub_sub_events_sender, pub_sub_events_receiver) = mpsc::unbounded_channel();
 
     let mut namespaces = Namespace::DEFAULT.to_vec();
-    namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
-
-    let server_builder = match transport {
-        ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
+    namespaces.extend([
+        Namespace::Debug,
+        Namespace::Snapshots,
+        Namespace::Admin,
+        Namespace::Trace,
+    ]);
+
+    let mut server_builder = match transport {
+        ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool)
+            .http(0)
+            .with_streamed_methods(streamed_methods),
         ApiTransportLabel::Ws => {
             let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool)
                 .ws(0)
@@ -162,6 +193,9 @@ async fn spawn_server(
             builder
         }
     };
+    if let Some(sync_state) = sync_state {
+        server_builder = server_builder.with_sync_state(sync_state);
+    }
     let server_handles = server_builder
         .with_polling_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender, vm_barrier)
@@ -190,6 +224,26 @@ trait HttpTest: Send + Sync {
     fn filters_disabled(&self) -> bool {
         false
     }
+
+    /// Overrides the logs denylist for HTTP server startup.
+    fn logs_denylist(&self) -> LogsDenylist {
+        LogsDenylist::default()
+    }
+
+    /// Overrides methods with streamed responses for HTTP server startup.
+    fn streamed_methods(&self) -> Vec<String> {
+        vec![]
+    }
+
+    /// Overrides gas caps for sandbox executions for HTTP server startup.
+    fn gas_caps(&self) -> GasCaps {
+        GasCaps::default()
+    }
+
+    /// Provides the sync state for HTTP server startup, emulating an external node.
+    fn sync_state(&self) -> Option<SyncState> {
+        None
+    }
 }
 
 /// Storage initialization strategy.
@@ -266,10 +320,16 @@ async fn test_http_server(test: impl HttpTest) {
     let web3_config = Web3JsonRpcConfig::for_tests();
     let mut api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
     api_config.filters_disabled = test.filters_disabled();
-    let mut server_handles = spawn_http_server(
+    api_config.logs_denylist = test.logs_denylist();
+    api_config.gas_caps = test.gas_caps();
+    let (mut server_handles, _) = spawn_server(
+        ApiTransportLabel::Http,
         api_config,
         pool.clone(),
+        None,
         test.transaction_executor(),
+        test.streamed_methods(),
+        test.sync_state(),
         stop_receiver,
     )
     .await;
@@ -317,6 +377,7 @@ fn execute_l2_transaction(transaction: L2Tx) -> TransactionExecutionResult {
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        decoded_revert_reason: None,
     }
 }
 
@@ -454,6 +515,41 @@ async fn http_server_basics() {
     test_http_server(HttpServerBasicsTest).await;
 }
 
+#[derive(Debug)]
+struct ProtocolVersionsTest;
+
+#[async_trait]
+impl HttpTest for ProtocolVersionsTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let latest_version = client
+            .get_protocol_version(None)
+            .await?
+            .context("no latest protocol version")?;
+        let versions = client.get_protocol_versions().await?;
+        assert_eq!(versions.len(), 1, "{versions:?}");
+        let genesis_version = &versions[0];
+        assert_eq!(
+            genesis_version.version.version_id,
+            latest_version.version_id
+        );
+        assert_eq!(
+            genesis_version.version.base_system_contracts,
+            latest_version.base_system_contracts
+        );
+        assert_eq!(
+            genesis_version.first_l1_batch_number,
+            Some(L1BatchNumber(0))
+        );
+        assert!(genesis_version.first_l1_batch_timestamp.is_some());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_protocol_versions() {
+    test_http_server(ProtocolVersionsTest).await;
+}
+
 #[derive(Debug)]
 struct BlockMethodsWithSnapshotRecovery;
 
@@ -581,6 +677,33 @@ impl HttpTest for L1BatchMethodsWithSnapshotRecovery {
             .unwrap_err();
         assert_pruned_l1_batch_error(&error, l1_batch_number);
 
+        // `get_commitment_artifacts` method
+        let artifacts = client
+            .get_commitment_artifacts(l1_batch_number)
+            .await?
+            .context("no commitment artifacts for sealed L1 batch")?;
+        let expected_metadata = create_l1_batch_metadata(l1_batch_number.0);
+        assert_eq!(artifacts.number, l1_batch_number);
+        assert_eq!(artifacts.commitment, expected_metadata.commitment);
+        assert_eq!(artifacts.rollup_root_hash, expected_metadata.root_hash);
+        assert_eq!(
+            artifacts.events_queue_commitment,
+            expected_metadata.events_queue_commitment
+        );
+        // The batch doesn't publish blobs, and its blob commitments are not persisted.
+        assert_eq!(artifacts.blob_linear_hashes, Some([H256::zero(); 2]));
+        assert_eq!(artifacts.blob_commitments, None);
+
+        let artifacts_for_future_batch =
+            client.get_commitment_artifacts(l1_batch_number + 1).await?;
+        assert!(artifacts_for_future_batch.is_none());
+
+        let error = client
+            .get_commitment_artifacts(l1_batch_number - 1)
+            .await
+            .unwrap_err();
+        assert_pruned_l1_batch_error(&error, l1_batch_number);
+
         Ok(())
     }
 }
@@ -831,7 +954,7 @@ impl HttpTest for TransactionReceiptsTest {
             execute_l2_transaction(tx2.clone()),
         ];
 
-        store_miniblock(&mut storage, miniblock_number, &tx_results).await?;
+        let new_miniblock = store_miniblock(&mut storage, miniblock_number, &tx_results).await?;
 
         let mut expected_receipts = Vec::new();
 
@@ -855,6 +978,20 @@ impl HttpTest for TransactionReceiptsTest {
         for (receipt, expected_receipt) in receipts.iter().zip(&expected_receipts) {
             assert_eq!(receipt, expected_receipt);
         }
+
+        let receipts_by_hash = client
+            .get_block_receipts(api::BlockId::Hash(new_miniblock.hash))
+            .await?;
+        assert_eq!(receipts_by_hash, receipts);
+
+        for missing_block_id in [
+            api::BlockId::Number(api::BlockNumber::Number(100.into())),
+            api::BlockId::Number(api::BlockNumber::Pending),
+            api::BlockId::Hash(H256::repeat_byte(1)),
+        ] {
+            let receipts = client.get_block_receipts(missing_block_id).await?;
+            assert!(receipts.is_empty(), "{receipts:?}");
+        }
         Ok(())
     }
 }
@@ -933,3 +1070,509 @@ impl HttpTest for AllAccountBalancesTest {
 async fn getting_all_account_balances() {
     test_http_server(AllAccountBalancesTest).await;
 }
+
+#[derive(Debug)]
+struct AccountBalancesTest;
+
+impl AccountBalancesTest {
+    const ADDRESS: Address = Address::repeat_byte(0x11);
+    const CUSTOM_TOKEN_ADDRESS: Address = Address::repeat_byte(0xfe);
+    const NFT_ADDRESS: Address = Address::repeat_byte(0x20);
+}
+
+#[async_trait]
+impl HttpTest for AccountBalancesTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        store_miniblock(&mut storage, MiniblockNumber(1), &[]).await?;
+        let eth_balance_key = storage_key_for_eth_balance(&Self::ADDRESS);
+        let eth_balance = U256::one() << 64;
+        let custom_token = TokenInfo {
+            l1_address: Self::CUSTOM_TOKEN_ADDRESS,
+            l2_address: Self::CUSTOM_TOKEN_ADDRESS,
+            metadata: TokenMetadata::default(Self::CUSTOM_TOKEN_ADDRESS),
+        };
+        storage
+            .tokens_dal()
+            .add_tokens(slice::from_ref(&custom_token))
+            .await?;
+        let token_balance_key = storage_key_for_standard_token_balance(
+            AccountTreeId::new(Self::CUSTOM_TOKEN_ADDRESS),
+            &Self::ADDRESS,
+        );
+        let token_balance = U256::from(123);
+        let balance_logs = vec![
+            StorageLog::new_write_log(eth_balance_key, u256_to_h256(eth_balance)),
+            StorageLog::new_write_log(token_balance_key, u256_to_h256(token_balance)),
+        ];
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(1), &[(H256::zero(), balance_logs)])
+            .await?;
+
+        store_miniblock(&mut storage, MiniblockNumber(2), &[]).await?;
+        let nft_transfer = VmEvent {
+            location: (L1BatchNumber(2), 0),
+            address: Self::NFT_ADDRESS,
+            indexed_topics: vec![
+                *TRANSFER_SINGLE_EVENT_SIGNATURE,
+                address_to_h256(&Self::ADDRESS),
+                H256::zero(),
+                address_to_h256(&Self::ADDRESS),
+            ],
+            value: ethabi::encode(&[ethabi::Token::Uint(5.into()), ethabi::Token::Uint(3.into())]),
+        };
+        let tx_location = IncludedTxLocation {
+            tx_hash: H256::repeat_byte(1),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: Self::ADDRESS,
+        };
+        storage
+            .token_transfers_dal()
+            .insert_token_transfers(MiniblockNumber(2), &[(tx_location, vec![&nft_transfer])])
+            .await?;
+        drop(storage);
+
+        let all_balances = client.get_account_balances(Self::ADDRESS, None).await?;
+        assert_eq!(
+            all_balances.balances,
+            BTreeMap::from([
+                (Address::zero(), eth_balance),
+                (Self::CUSTOM_TOKEN_ADDRESS, token_balance),
+            ])
+        );
+        assert!(all_balances.nfts.is_empty(), "{:?}", all_balances.nfts);
+        assert_eq!(all_balances.next_page, None);
+
+        // Paginate through all entries one by one.
+        let mut options = api::AccountBalancesOptions {
+            include_nfts: true,
+            limit: Some(1),
+            ..api::AccountBalancesOptions::default()
+        };
+        let mut balances = BTreeMap::new();
+        let mut nfts = vec![];
+        let mut page_count = 0;
+        loop {
+            let page = client
+                .get_account_balances(Self::ADDRESS, Some(options.clone()))
+                .await?;
+            assert!(page.balances.len() + page.nfts.len() <= 1, "{page:?}");
+            balances.extend(page.balances);
+            nfts.extend(page.nfts);
+            page_count += 1;
+            assert!(page_count <= 10, "too many pages");
+            if page.next_page.is_none() {
+                break;
+            }
+            options.after = page.next_page;
+        }
+        assert_eq!(balances, all_balances.balances);
+        assert_eq!(
+            nfts,
+            [api::NftHolding {
+                token: Self::NFT_ADDRESS,
+                standard: NftStandard::Erc1155,
+                token_id: 5.into(),
+                amount: 3.into(),
+            }]
+        );
+
+        let options = api::AccountBalancesOptions {
+            tokens: Some(vec![Self::CUSTOM_TOKEN_ADDRESS, Self::NFT_ADDRESS]),
+            include_nfts: true,
+            ..api::AccountBalancesOptions::default()
+        };
+        let filtered_balances = client
+            .get_account_balances(Self::ADDRESS, Some(options))
+            .await?;
+        assert_eq!(
+            filtered_balances.balances,
+            BTreeMap::from([(Self::CUSTOM_TOKEN_ADDRESS, token_balance)])
+        );
+        assert_eq!(filtered_balances.nfts, nfts);
+        assert_eq!(filtered_balances.next_page, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_account_balances() {
+    test_http_server(AccountBalancesTest).await;
+}
+
+#[derive(Debug)]
+struct TokenTransfersTest;
+
+impl TokenTransfersTest {
+    const TOKEN_ADDRESS: Address = Address::repeat_byte(0xfe);
+    const ALICE: Address = Address::repeat_byte(1);
+    const BOB: Address = Address::repeat_byte(2);
+
+    fn transfer_event(from: Address, to: Address, amount: u64) -> VmEvent {
+        VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Self::TOKEN_ADDRESS,
+            indexed_topics: vec![
+                *TRANSFER_EVENT_SIGNATURE,
+                address_to_h256(&from),
+                address_to_h256(&to),
+            ],
+            value: u256_to_h256(U256::from(amount)).as_bytes().to_vec(),
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTest for TokenTransfersTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        for number in [1, 2] {
+            store_miniblock(&mut storage, MiniblockNumber(number), &[]).await?;
+            let events = [
+                Self::transfer_event(Self::ALICE, Self::BOB, number.into()),
+                Self::transfer_event(Self::BOB, Self::BOB, 1),
+            ];
+            let tx_location = IncludedTxLocation {
+                tx_hash: H256::from_low_u64_be(number.into()),
+                tx_index_in_miniblock: 0,
+                tx_initiator_address: Self::ALICE,
+            };
+            storage
+                .events_dal()
+                .save_events(
+                    MiniblockNumber(number),
+                    &[(tx_location, events.iter().collect())],
+                )
+                .await;
+        }
+        storage
+            .token_transfers_dal()
+            .index_token_transfers(MiniblockNumber(0)..=MiniblockNumber(2))
+            .await?;
+        drop(storage);
+
+        let transfers = client.get_token_transfers(Self::ALICE, None).await?;
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].block_number, MiniblockNumber(1));
+        assert_eq!(transfers[0].log_index, 0);
+        assert_eq!(transfers[0].transaction_hash, H256::from_low_u64_be(1));
+        assert_eq!(transfers[0].token, Self::TOKEN_ADDRESS);
+        assert_eq!(
+            (transfers[0].from, transfers[0].to),
+            (Self::ALICE, Self::BOB)
+        );
+        assert_eq!(transfers[0].amount, 1.into());
+        assert_eq!(transfers[1].block_number, MiniblockNumber(2));
+        assert_eq!(transfers[1].amount, 2.into());
+
+        // Self-transfers must be returned once.
+        let transfers = client.get_token_transfers(Self::BOB, None).await?;
+        let positions: Vec<_> = transfers
+            .iter()
+            .map(|transfer| (transfer.block_number.0, transfer.log_index))
+            .collect();
+        assert_eq!(positions, [(1, 0), (1, 1), (2, 0), (2, 1)]);
+
+        // Pagination
+        let range = api::TokenTransfersRange {
+            after: Some(api::TokenTransferCursor {
+                block_number: MiniblockNumber(1),
+                log_index: 0,
+            }),
+            limit: Some(2),
+            ..api::TokenTransfersRange::default()
+        };
+        let transfers = client.get_token_transfers(Self::BOB, Some(range)).await?;
+        let positions: Vec<_> = transfers
+            .iter()
+            .map(|transfer| (transfer.block_number.0, transfer.log_index))
+            .collect();
+        assert_eq!(positions, [(1, 1), (2, 0)]);
+
+        let range = api::TokenTransfersRange {
+            from_block: Some(MiniblockNumber(2)),
+            ..api::TokenTransfersRange::default()
+        };
+        let transfers = client.get_token_transfers(Self::ALICE, Some(range)).await?;
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].block_number, MiniblockNumber(2));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_token_transfers() {
+    test_http_server(TokenTransfersTest).await;
+}
+
+#[derive(Debug)]
+struct TokenInfoTest;
+
+#[async_trait]
+impl HttpTest for TokenInfoTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let token_address = Address::repeat_byte(0xfe);
+        let token = client.get_token_info(token_address).await?;
+        assert_eq!(token, None);
+
+        let custom_token = TokenInfo {
+            l1_address: Address::repeat_byte(0xfd),
+            l2_address: token_address,
+            metadata: TokenMetadata {
+                name: "Test".to_owned(),
+                symbol: "TST".to_owned(),
+                decimals: 6,
+            },
+        };
+        let mut storage = pool.access_storage().await?;
+        storage
+            .tokens_dal()
+            .add_tokens(slice::from_ref(&custom_token))
+            .await?;
+
+        let token = client.get_token_info(token_address).await?.unwrap();
+        assert_eq!(token.l1_address, custom_token.l1_address);
+        assert_eq!(token.l2_address, token_address);
+        assert_eq!(token.name, "Test");
+        assert_eq!(token.symbol, "TST");
+        assert_eq!(token.decimals, 6);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_token_info() {
+    test_http_server(TokenInfoTest).await;
+}
+
+#[derive(Debug)]
+struct L1GasOracleStateTest;
+
+#[async_trait]
+impl HttpTest for L1GasOracleStateTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let FeeParams::V1(fee_params) = FeeParams::sensible_v1_default() else {
+            unreachable!("unexpected fee params");
+        };
+        let scale_factor = Web3JsonRpcConfig::for_tests().gas_price_scale_factor;
+        let scaled_l1_gas_price = (fee_params.l1_gas_price as f64 * scale_factor) as u64;
+        let minimal_l2_gas_price = fee_params.config.minimal_l2_gas_price;
+
+        let state = client
+            .get_l1_gas_oracle_state()
+            .await?
+            .expect("no oracle state");
+        assert_eq!(state.effective_l1_gas_price, fee_params.l1_gas_price);
+        // The fee input must be scaled in the same way as for gas estimation.
+        assert_eq!(
+            state.batch_fee_input,
+            BatchFeeInput::l1_pegged(scaled_l1_gas_price, minimal_l2_gas_price)
+        );
+
+        // The fee input must be clamped by the fee input of the last sealed miniblock.
+        let mut new_miniblock = create_miniblock(1);
+        new_miniblock.batch_fee_input =
+            BatchFeeInput::l1_pegged(scaled_l1_gas_price * 2, minimal_l2_gas_price * 2);
+        let mut storage = pool.access_storage().await?;
+        storage
+            .blocks_dal()
+            .insert_miniblock(&new_miniblock)
+            .await?;
+        drop(storage);
+
+        let state = client
+            .get_l1_gas_oracle_state()
+            .await?
+            .expect("no oracle state");
+        assert_eq!(state.effective_l1_gas_price, fee_params.l1_gas_price);
+        assert_eq!(state.batch_fee_input, new_miniblock.batch_fee_input);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_l1_gas_oracle_state() {
+    test_http_server(L1GasOracleStateTest).await;
+}
+
+#[derive(Debug)]
+struct UtilizationReportTest;
+
+#[async_trait]
+impl HttpTest for UtilizationReportTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let date = chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let report = client.get_utilization_report(date).await?;
+        assert_eq!(report, None);
+
+        let report = api::UtilizationReport {
+            date,
+            first_l1_batch_number: Some(L1BatchNumber(1)),
+            last_l1_batch_number: Some(L1BatchNumber(10)),
+            l1_batch_count: 10,
+            avg_gas_utilization: Some(0.25),
+            avg_pubdata_utilization: Some(0.5),
+            avg_circuits_utilization: None,
+            seal_criteria: [("gas".to_owned(), 3), ("no_txs_timeout".to_owned(), 7)].into(),
+        };
+        let mut storage = pool.access_storage().await?;
+        storage
+            .utilization_reports_dal()
+            .insert_report(&report)
+            .await?;
+        drop(storage);
+
+        let returned_report = client.get_utilization_report(date).await?;
+        assert_eq!(returned_report, Some(report));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_utilization_report() {
+    test_http_server(UtilizationReportTest).await;
+}
+
+#[derive(Debug, Default)]
+struct SyncingTest {
+    sync_state: SyncState,
+}
+
+#[async_trait]
+impl HttpTest for SyncingTest {
+    fn sync_state(&self) -> Option<SyncState> {
+        Some(self.sync_state.clone())
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        self.sync_state.set_local_block(MiniblockNumber(0));
+        self.sync_state.set_main_node_block(MiniblockNumber(100));
+        let sync_state = client.syncing().await?;
+        let ApiSyncState::Syncing(info) = sync_state else {
+            panic!("Unexpected sync state: {sync_state:?}");
+        };
+        assert_eq!(info.starting_block, 0.into());
+        assert_eq!(info.current_block, 0.into());
+        assert_eq!(info.highest_block, 100.into());
+        assert_eq!(info.fetched_block, None);
+        // The genesis L1 batch is applied and has tree data, but isn't executed on L1.
+        assert_eq!(info.applied_l1_batch, Some(0.into()));
+        assert_eq!(info.tree_l1_batch, Some(0.into()));
+        assert_eq!(info.executed_l1_batch, None);
+
+        self.sync_state.set_local_block(MiniblockNumber(95));
+        let sync_state = client.syncing().await?;
+        assert_eq!(sync_state, ApiSyncState::NotSyncing);
+
+        // The node isn't synced if the Merkle tree lags behind.
+        let mut storage = pool.access_storage().await?;
+        for number in 1..=2 {
+            let header = create_l1_batch(number);
+            storage.blocks_dal().insert_mock_l1_batch(&header).await?;
+        }
+        drop(storage);
+
+        // L1 batch progress is cached, so the new L1 batches are not visible yet.
+        let sync_state = client.syncing().await?;
+        assert_eq!(sync_state, ApiSyncState::NotSyncing);
+
+        self.sync_state.reset_l1_batch_progress();
+        let sync_state = client.syncing().await?;
+        let ApiSyncState::Syncing(info) = sync_state else {
+            panic!("Unexpected sync state: {sync_state:?}");
+        };
+        assert_eq!(info.current_block, 95.into());
+        assert_eq!(info.applied_l1_batch, Some(2.into()));
+        assert_eq!(info.tree_l1_batch, Some(0.into()));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_sync_state() {
+    test_http_server(SyncingTest::default()).await;
+}
+
+#[derive(Debug)]
+struct ProofKeysLimitTest;
+
+#[async_trait]
+impl HttpTest for ProofKeysLimitTest {
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let max_keys = Web3JsonRpcConfig::for_tests().get_proof_max_keys();
+        let keys: Vec<_> = (0..=max_keys as u64).map(H256::from_low_u64_be).collect();
+        let error = client
+            .get_proof(Address::repeat_byte(1), keys, None)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+            assert!(error.message().contains(&max_keys.to_string()), "{error:?}");
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn capping_proof_keys() {
+    test_http_server(ProofKeysLimitTest).await;
+}
+
+#[tokio::test]
+async fn resolving_l1_batch_for_proofs() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    let genesis_root_hash = storage
+        .blocks_dal()
+        .get_l1_batch_state_root(L1BatchNumber(0))
+        .await
+        .unwrap()
+        .expect("no genesis root hash");
+
+    // Seal L1 batch #1 without the tree data, emulating the Merkle tree lagging behind the state keeper.
+    store_miniblock(&mut storage, MiniblockNumber(1), &[])
+        .await
+        .unwrap();
+    let header = create_l1_batch(1);
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&header)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(L1BatchNumber(1))
+        .await
+        .unwrap();
+
+    let latest = api::BlockId::Number(api::BlockNumber::Latest);
+    let resolved = resolve_l1_batch_for_proofs(&mut storage, latest, MiniblockNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(resolved, Some((L1BatchNumber(0), genesis_root_hash)));
+    let block_id = api::BlockId::Number(api::BlockNumber::Number(1.into()));
+    let resolved = resolve_l1_batch_for_proofs(&mut storage, block_id, MiniblockNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(resolved, None);
+
+    let metadata = create_l1_batch_metadata(1);
+    storage
+        .blocks_dal()
+        .save_l1_batch_tree_data(L1BatchNumber(1), &metadata.tree_data())
+        .await
+        .unwrap();
+    let expected = Some((L1BatchNumber(1), metadata.root_hash));
+    for block_id in [latest, block_id] {
+        let resolved = resolve_l1_batch_for_proofs(&mut storage, block_id, MiniblockNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(resolved, expected);
+    }
+}
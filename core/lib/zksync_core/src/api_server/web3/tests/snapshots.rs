@@ -2,6 +2,7 @@
 
 use std::collections::HashSet;
 
+use zksync_types::snapshots::SnapshotVersion;
 use zksync_web3_decl::namespaces::SnapshotsNamespaceClient;
 
 use super::*;
@@ -38,7 +39,12 @@ impl HttpTest for SnapshotBasicsTest {
         seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
         storage
             .snapshots_dal()
-            .add_snapshot(L1BatchNumber(1), Self::CHUNK_COUNT, "file:///factory_deps")
+            .add_snapshot(
+                SnapshotVersion::Version1,
+                L1BatchNumber(1),
+                Self::CHUNK_COUNT,
+                "file:///factory_deps",
+            )
             .await?;
 
         for &chunk_id in &self.chunk_ids {
@@ -66,6 +72,7 @@ impl HttpTest for SnapshotBasicsTest {
             return Ok(());
         };
 
+        assert_eq!(snapshot_header.version, SnapshotVersion::Version1 as u16);
         assert_eq!(snapshot_header.l1_batch_number, L1BatchNumber(1));
         assert_eq!(snapshot_header.miniblock_number, MiniblockNumber(1));
         assert_eq!(
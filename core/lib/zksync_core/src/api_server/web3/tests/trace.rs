@@ -0,0 +1,175 @@
+//! Tests for the `trace` Web3 namespace.
+
+use zksync_types::{
+    tx::TransactionExecutionResult,
+    vm_trace::{Call, CallType},
+    BOOTLOADER_ADDRESS,
+};
+use zksync_web3_decl::namespaces::TraceNamespaceClient;
+
+use super::*;
+
+fn execute_l2_transaction_with_nested_traces(index_in_block: u8) -> TransactionExecutionResult {
+    let nested_call_trace = Call {
+        from: Address::repeat_byte(index_in_block + 1),
+        to: Address::repeat_byte(0x10 + index_in_block),
+        gas: 50,
+        gas_used: 20,
+        error: Some("out of gas".to_owned()),
+        ..Call::default()
+    };
+    let first_call_trace = Call {
+        from: Address::repeat_byte(index_in_block),
+        to: Address::repeat_byte(index_in_block + 1),
+        gas: 100,
+        gas_used: 42,
+        calls: vec![nested_call_trace],
+        ..Call::default()
+    };
+    let second_call_trace = Call {
+        r#type: CallType::StaticCall,
+        from: Address::repeat_byte(0xff - index_in_block),
+        to: Address::repeat_byte(0xab - index_in_block),
+        value: 123.into(),
+        gas: 58,
+        gas_used: 10,
+        input: b"input".to_vec(),
+        output: b"output".to_vec(),
+        ..Call::default()
+    };
+    TransactionExecutionResult {
+        call_traces: vec![first_call_trace, second_call_trace],
+        ..execute_l2_transaction(create_l2_transaction(1, 2))
+    }
+}
+
+fn assert_filter_error(error: ClientError, expected_message: &str) {
+    if let ClientError::Call(error) = error {
+        assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        assert!(error.message().contains(expected_message), "{error:?}");
+    } else {
+        panic!("Unexpected error: {error:?}");
+    }
+}
+
+#[derive(Debug)]
+struct TraceFilterTest;
+
+#[async_trait]
+impl HttpTest for TraceFilterTest {
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let tx_results = [0, 1, 2].map(execute_l2_transaction_with_nested_traces);
+        let mut storage = pool.access_storage().await?;
+        let new_miniblock = store_miniblock(&mut storage, MiniblockNumber(1), &tx_results).await?;
+        drop(storage);
+
+        let block_filter = api::TraceFilter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Latest),
+            ..api::TraceFilter::default()
+        };
+        let traces = client.filter(block_filter.clone()).await?;
+        // Each transaction has the top-level call, 2 calls and a nested call.
+        assert_eq!(traces.len(), tx_results.len() * 4);
+        for (tx_traces, tx_result) in traces.chunks(4).zip(&tx_results) {
+            for trace in tx_traces {
+                assert_eq!(trace.block_hash, new_miniblock.hash);
+                assert_eq!(trace.block_number, U64::from(1));
+                assert_eq!(trace.transaction_hash, tx_result.hash);
+                assert_eq!(trace.trace_type, api::TraceType::Call);
+            }
+
+            let trace_addresses: Vec<_> = tx_traces
+                .iter()
+                .map(|trace| trace.trace_address.as_slice())
+                .collect();
+            assert_eq!(trace_addresses, [&[] as &[_], &[0], &[0, 0], &[1]]);
+            let subtraces: Vec<_> = tx_traces.iter().map(|trace| trace.subtraces).collect();
+            assert_eq!(subtraces, [2, 1, 0, 0]);
+
+            let api::TraceAction::Call(top_level_action) = &tx_traces[0].action else {
+                panic!("Unexpected action: {:?}", tx_traces[0].action);
+            };
+            assert_eq!(top_level_action.from, Address::zero());
+            assert_eq!(top_level_action.to, BOOTLOADER_ADDRESS);
+
+            let nested_trace = &tx_traces[2];
+            assert_eq!(nested_trace.result, None);
+            assert_eq!(nested_trace.error.as_deref(), Some("out of gas"));
+            let second_trace = &tx_traces[3];
+            let api::TraceAction::Call(second_action) = &second_trace.action else {
+                panic!("Unexpected action: {:?}", second_trace.action);
+            };
+            assert_eq!(second_action.call_type, api::TraceCallType::StaticCall);
+            assert_eq!(
+                second_trace.result,
+                Some(api::TraceResult::Call {
+                    gas_used: 10.into(),
+                    output: b"output".to_vec().into(),
+                })
+            );
+        }
+
+        let address_filter = api::TraceFilter {
+            from_address: Some(vec![Address::repeat_byte(0xfe)]),
+            ..block_filter.clone()
+        };
+        let traces = client.filter(address_filter).await?;
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].transaction_hash, tx_results[1].hash);
+        assert_eq!(traces[0].trace_address, [1]);
+
+        let address_filter = api::TraceFilter {
+            from_address: Some(vec![Address::repeat_byte(1), Address::repeat_byte(2)]),
+            to_address: Some(vec![Address::repeat_byte(0x11)]),
+            ..block_filter.clone()
+        };
+        let traces = client.filter(address_filter).await?;
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0].transaction_hash, tx_results[1].hash);
+        assert_eq!(traces[0].trace_address, [0, 0]);
+
+        let all_traces = client.filter(block_filter.clone()).await?;
+        let paginated_filter = api::TraceFilter {
+            after: Some(2),
+            count: Some(3),
+            ..block_filter.clone()
+        };
+        let traces = client.filter(paginated_filter).await?;
+        assert_eq!(traces, all_traces[2..5]);
+
+        let traces = client
+            .filter(api::TraceFilter {
+                from_block: Some(api::BlockNumber::Number(2.into())),
+                to_block: Some(api::BlockNumber::Number(10.into())),
+                ..api::TraceFilter::default()
+            })
+            .await?;
+        assert!(traces.is_empty());
+
+        let error = client
+            .filter(api::TraceFilter {
+                count: Some(1_000_000),
+                ..block_filter
+            })
+            .await
+            .unwrap_err();
+        assert_filter_error(error, "more than");
+
+        let error = client
+            .filter(api::TraceFilter {
+                from_block: Some(api::BlockNumber::Number(1.into())),
+                to_block: Some(api::BlockNumber::Number(1_000.into())),
+                ..api::TraceFilter::default()
+            })
+            .await
+            .unwrap_err();
+        assert_filter_error(error, "block range");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn filtering_traces() {
+    test_http_server(TraceFilterTest).await;
+}
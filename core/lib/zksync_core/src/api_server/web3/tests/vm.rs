@@ -2,12 +2,17 @@
 
 use std::sync::atomic::{AtomicU32, Ordering};
 
-use multivm::interface::{ExecutionResult, VmRevertReason};
+use multivm::interface::{ExecutionResult, Halt, VmRevertReason};
 use zksync_types::{
-    get_intrinsic_constants, transaction_request::CallRequest, L2ChainId, PackedEthSignature, U256,
+    get_intrinsic_constants,
+    transaction_request::{CallRequest, Eip712Meta, PaymasterParams},
+    L2ChainId, PackedEthSignature, U256,
+};
+use zksync_utils::{time::seconds_since_epoch, u256_to_h256};
+use zksync_web3_decl::{
+    error::TxValidationErrorData,
+    namespaces::{DebugNamespaceClient, ZksNamespaceClient},
 };
-use zksync_utils::u256_to_h256;
-use zksync_web3_decl::namespaces::DebugNamespaceClient;
 
 use super::*;
 
@@ -51,7 +56,9 @@ impl HttpTest for CallTest {
     }
 
     async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
-        let call_result = client.call(Self::call_request(b"pending"), None).await?;
+        let call_result = client
+            .call(Self::call_request(b"pending"), None, None)
+            .await?;
         assert_eq!(call_result.0, b"output");
 
         let valid_block_numbers_and_calldata = [
@@ -62,7 +69,7 @@ impl HttpTest for CallTest {
         for (number, calldata) in valid_block_numbers_and_calldata {
             let number = api::BlockIdVariant::BlockNumber(number);
             let call_result = client
-                .call(Self::call_request(calldata), Some(number))
+                .call(Self::call_request(calldata), Some(number), None)
                 .await?;
             assert_eq!(call_result.0, b"output");
         }
@@ -70,7 +77,7 @@ impl HttpTest for CallTest {
         let invalid_block_number = api::BlockNumber::from(100);
         let number = api::BlockIdVariant::BlockNumber(invalid_block_number);
         let error = client
-            .call(Self::call_request(b"100"), Some(number))
+            .call(Self::call_request(b"100"), Some(number), None)
             .await
             .unwrap_err();
         if let ClientError::Call(error) = error {
@@ -79,6 +86,23 @@ impl HttpTest for CallTest {
             panic!("Unexpected error: {error:?}");
         }
 
+        let invalid_account = api::OverrideAccount {
+            code: Some(vec![0; 31].into()),
+            ..api::OverrideAccount::default()
+        };
+        let state_override =
+            api::StateOverride(HashMap::from([(Address::repeat_byte(2), invalid_account)]));
+        let error = client
+            .call(Self::call_request(b"pending"), None, Some(state_override))
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+            assert!(error.message().contains("state override"), "{error:?}");
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+
         Ok(())
     }
 }
@@ -88,6 +112,62 @@ async fn call_method_basics() {
     test_http_server(CallTest).await;
 }
 
+#[derive(Debug)]
+struct CallWithGasCapTest;
+
+impl CallWithGasCapTest {
+    const GAS_CAP: u32 = 1_000_000;
+}
+
+#[async_trait]
+impl HttpTest for CallWithGasCapTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(|tx, _| {
+            assert_eq!(tx.gas_limit(), U256::from(Self::GAS_CAP));
+            ExecutionResult::Success {
+                output: b"output".to_vec(),
+            }
+        });
+        tx_executor
+    }
+
+    fn gas_caps(&self) -> GasCaps {
+        GasCaps::new(Some(Self::GAS_CAP), None, [])
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut request = CallTest::call_request(b"pending");
+        request.gas = None;
+        let call_result = client.call(request.clone(), None, None).await?;
+        assert_eq!(call_result.0, b"output");
+
+        request.gas = Some(U256::from(Self::GAS_CAP) + 1);
+        let error = client.call(request, None, None).await.unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert!(
+                error.message().contains("gas required exceeds allowance"),
+                "{error:?}"
+            );
+            let data = error.data().expect("no error data");
+            let data: TxValidationErrorData = serde_json::from_str(data.get())?;
+            assert_eq!(data.constraint, "gas-cap-exceeded");
+            assert_eq!(
+                data.required,
+                Some(serde_json::json!({ "max": Self::GAS_CAP }))
+            );
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn call_method_with_gas_cap() {
+    test_http_server(CallWithGasCapTest).await;
+}
+
 #[derive(Debug)]
 struct CallTestAfterSnapshotRecovery;
 
@@ -104,7 +184,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
 
     async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
         let call_result = client
-            .call(CallTest::call_request(b"pending"), None)
+            .call(CallTest::call_request(b"pending"), None, None)
             .await?;
         assert_eq!(call_result.0, b"output");
         let pending_block_number = api::BlockIdVariant::BlockNumber(api::BlockNumber::Pending);
@@ -112,6 +192,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
             .call(
                 CallTest::call_request(b"pending"),
                 Some(pending_block_number),
+                None,
             )
             .await?;
         assert_eq!(call_result.0, b"output");
@@ -121,7 +202,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
         for number in pruned_block_numbers {
             let number = api::BlockIdVariant::BlockNumber(number.into());
             let error = client
-                .call(CallTest::call_request(b"pruned"), Some(number))
+                .call(CallTest::call_request(b"pruned"), Some(number), None)
                 .await
                 .unwrap_err();
             assert_pruned_block_error(&error, first_local_miniblock);
@@ -131,7 +212,7 @@ impl HttpTest for CallTestAfterSnapshotRecovery {
         for number in first_miniblock_numbers {
             let number = api::BlockIdVariant::BlockNumber(number);
             let call_result = client
-                .call(CallTest::call_request(b"first"), Some(number))
+                .call(CallTest::call_request(b"first"), Some(number), None)
                 .await?;
             assert_eq!(call_result.0, b"output");
         }
@@ -254,6 +335,72 @@ async fn send_raw_transaction_after_snapshot_recovery() {
     .await;
 }
 
+#[derive(Debug)]
+struct SendRawTransactionWithDeadlineTest;
+
+#[async_trait]
+impl HttpTest for SendRawTransactionWithDeadlineTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        SendRawTransactionTest {
+            snapshot_recovery: false,
+        }
+        .transaction_executor()
+    }
+
+    async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
+        let mut storage = pool.access_storage().await?;
+        storage
+            .storage_logs_dal()
+            .append_storage_logs(
+                MiniblockNumber(0),
+                &[(
+                    H256::zero(),
+                    vec![SendRawTransactionTest::balance_storage_log()],
+                )],
+            )
+            .await?;
+
+        let (tx_bytes, tx_hash) = SendRawTransactionTest::transaction_bytes_and_hash();
+        // The genesis miniblock is sealed, so this deadline has already passed.
+        let passed_deadline = api::TransactionDeadline {
+            timestamp: None,
+            block_number: Some(MiniblockNumber(0)),
+        };
+        let error = client
+            .send_raw_transaction_with_deadline(tx_bytes.clone().into(), passed_deadline)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            let data = error.data().expect("no error data");
+            let data: TxValidationErrorData = serde_json::from_str(data.get())?;
+            assert_eq!(data.code, 36);
+            assert_eq!(data.constraint, "deadline-passed");
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+
+        let deadline = api::TransactionDeadline {
+            timestamp: Some(seconds_since_epoch() + 3_600),
+            block_number: Some(MiniblockNumber(100)),
+        };
+        let send_result = client
+            .send_raw_transaction_with_deadline(tx_bytes.into(), deadline)
+            .await?;
+        assert_eq!(send_result, tx_hash);
+        let deadlines = storage
+            .transactions_dal()
+            .get_transaction_deadlines(&[tx_hash])
+            .await?;
+        assert_eq!(deadlines, HashMap::from([(tx_hash, deadline)]));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn send_raw_transaction_with_deadline() {
+    test_http_server(SendRawTransactionWithDeadlineTest).await;
+}
+
 #[derive(Debug)]
 struct TraceCallTest;
 
@@ -360,7 +507,7 @@ impl HttpTest for TraceCallTestAfterSnapshotRecovery {
         for number in pruned_block_numbers {
             let number = api::BlockIdVariant::BlockNumber(number.into());
             let error = client
-                .call(CallTest::call_request(b"pruned"), Some(number))
+                .call(CallTest::call_request(b"pruned"), Some(number), None)
                 .await
                 .unwrap_err();
             assert_pruned_block_error(&error, first_local_miniblock);
@@ -432,9 +579,11 @@ impl HttpTest for EstimateGasTest {
     }
 
     async fn test(&self, client: &HttpClient, pool: &ConnectionPool) -> anyhow::Result<()> {
-        let l2_transaction = create_l2_transaction(10, 100);
         for threshold in [10_000, 50_000, 100_000, 1_000_000] {
             self.gas_limit_threshold.store(threshold, Ordering::Relaxed);
+            // Use a new transaction for each threshold; otherwise, the estimate would be returned from the cache
+            // since the state doesn't change.
+            let l2_transaction = create_l2_transaction(10, 100);
             let output = client
                 .estimate_gas(l2_transaction.clone().into(), None)
                 .await?;
@@ -458,7 +607,7 @@ impl HttpTest for EstimateGasTest {
                 .append_storage_logs(MiniblockNumber(0), &[(H256::zero(), vec![storage_log])])
                 .await?;
         }
-        let mut call_request = CallRequest::from(l2_transaction);
+        let mut call_request = CallRequest::from(create_l2_transaction(10, 100));
         call_request.from = Some(SendRawTransactionTest::private_key_and_address().1);
         call_request.value = Some(1_000_000.into());
         client.estimate_gas(call_request.clone(), None).await?;
@@ -487,3 +636,239 @@ async fn estimate_gas_basics() {
 async fn estimate_gas_after_snapshot_recovery() {
     test_http_server(EstimateGasTest::new(true)).await;
 }
+
+#[derive(Debug)]
+struct EstimateGasBatchTest(EstimateGasTest);
+
+#[async_trait]
+impl HttpTest for EstimateGasBatchTest {
+    fn storage_initialization(&self) -> StorageInitialization {
+        self.0.storage_initialization()
+    }
+
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        self.0.transaction_executor()
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let estimates = client.estimate_gas_batch(vec![]).await?;
+        assert!(estimates.is_empty());
+
+        let threshold = 50_000;
+        self.0
+            .gas_limit_threshold
+            .store(threshold, Ordering::Relaxed);
+        let l2_transaction = create_l2_transaction(10, 100);
+        let single_estimate = client
+            .estimate_gas(l2_transaction.clone().into(), None)
+            .await?;
+        let requests = vec![CallRequest::from(l2_transaction.clone()); 3];
+        let estimates = client.estimate_gas_batch(requests).await?;
+        let expected_estimate = api::BatchGasEstimate {
+            gas: Some(single_estimate),
+            error: None,
+        };
+        assert_eq!(estimates, vec![expected_estimate.clone(); 3]);
+
+        // An invalid transaction shouldn't influence estimates for other transactions.
+        let mut invalid_request = CallRequest::from(create_l2_transaction(10, 100));
+        invalid_request.from = Some(SendRawTransactionTest::private_key_and_address().1);
+        invalid_request.value = Some(U256::max_value());
+        let requests = vec![
+            CallRequest::from(l2_transaction.clone()),
+            invalid_request,
+            CallRequest::from(l2_transaction.clone()),
+        ];
+        let estimates = client.estimate_gas_batch(requests).await?;
+        assert_eq!(estimates.len(), 3);
+        assert_eq!(estimates[0], expected_estimate);
+        assert_eq!(estimates[2], expected_estimate);
+        assert_eq!(estimates[1].gas, None);
+        let error_msg = estimates[1].error.as_ref().unwrap();
+        assert!(
+            error_msg.to_lowercase().contains("insufficient"),
+            "{error_msg}"
+        );
+
+        let max_batch_size = Web3JsonRpcConfig::for_tests().estimate_gas_batch_max_size();
+        let requests = vec![CallRequest::from(l2_transaction); max_batch_size + 1];
+        let error = client.estimate_gas_batch(requests).await.unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+            assert!(error.message().contains("at most"), "{error:?}");
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn estimate_gas_batch_basics() {
+    test_http_server(EstimateGasBatchTest(EstimateGasTest::new(false))).await;
+}
+
+#[derive(Debug)]
+struct SimulateBundleTest;
+
+#[async_trait]
+impl HttpTest for SimulateBundleTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(|tx, block_args| {
+            assert_eq!(block_args.resolved_block_number(), MiniblockNumber(1));
+            match tx.execute.calldata() {
+                b"revert" => ExecutionResult::Revert {
+                    output: VmRevertReason::General {
+                        msg: "oops".to_owned(),
+                        data: vec![],
+                    },
+                },
+                b"halt" => ExecutionResult::Halt {
+                    reason: Halt::FailedToSetL2Block("halted".to_owned()),
+                },
+                data => ExecutionResult::Success {
+                    output: data.to_vec(),
+                },
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let requests = vec![
+            CallTest::call_request(b"first"),
+            CallTest::call_request(b"revert"),
+            CallTest::call_request(b"second"),
+        ];
+        let results = client.simulate_bundle(requests, None, None).await?;
+        assert_eq!(results.len(), 3);
+        assert!(results[0].success);
+        assert_eq!(results[0].output.0, b"first");
+        assert!(!results[1].success);
+        assert!(results[1].output.0.is_empty());
+        let revert_reason = results[1].revert_reason.as_ref().unwrap();
+        assert!(revert_reason.contains("oops"), "{revert_reason}");
+        assert!(results[2].success);
+        assert_eq!(results[2].output.0, b"second");
+
+        let requests = vec![
+            CallTest::call_request(b"first"),
+            CallTest::call_request(b"halt"),
+            CallTest::call_request(b"second"),
+        ];
+        let error = client
+            .simulate_bundle(requests, None, None)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert!(error.message().contains("halted"), "{error:?}");
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+
+        let error = client
+            .simulate_bundle(vec![], None, None)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn simulate_bundle_basics() {
+    test_http_server(SimulateBundleTest).await;
+}
+
+#[derive(Debug)]
+struct ValidatePaymasterTest;
+
+impl ValidatePaymasterTest {
+    fn paymaster_request(paymaster: Address, data: &[u8]) -> CallRequest {
+        CallRequest {
+            eip712_meta: Some(Eip712Meta {
+                gas_per_pubdata: 800.into(),
+                paymaster_params: Some(PaymasterParams {
+                    paymaster,
+                    paymaster_input: vec![],
+                }),
+                ..Eip712Meta::default()
+            }),
+            ..CallTest::call_request(data)
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTest for ValidatePaymasterTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_tx_responses(|tx, block_args| {
+            assert_eq!(block_args.resolved_block_number(), MiniblockNumber(1));
+            match tx.execute.calldata() {
+                b"revert" => ExecutionResult::Revert {
+                    output: VmRevertReason::General {
+                        msg: "not enough allowance".to_owned(),
+                        data: vec![],
+                    },
+                },
+                _ => ExecutionResult::Success { output: vec![] },
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(&self, client: &HttpClient, _pool: &ConnectionPool) -> anyhow::Result<()> {
+        let paymaster = Address::repeat_byte(0x42);
+        let request = Self::paymaster_request(paymaster, b"ok");
+        let validation = client.validate_paymaster(request).await?;
+        assert!(validation.success, "{validation:?}");
+        assert_eq!(validation.error, None);
+        assert_eq!(validation.paymaster_balance, U256::zero());
+        assert_eq!(validation.required_allowance, None);
+
+        let request = Self::paymaster_request(paymaster, b"revert");
+        let validation = client.validate_paymaster(request).await?;
+        assert!(!validation.success);
+        let error = validation.error.as_ref().unwrap();
+        assert!(error.contains("not enough allowance"), "{error}");
+        assert!(validation.violation.is_none());
+
+        let error = client
+            .validate_paymaster(CallTest::call_request(b"ok"))
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+
+        // Required funds overflow `U256`.
+        let request = CallRequest {
+            gas: Some(U256::MAX),
+            gas_price: Some(2.into()),
+            ..Self::paymaster_request(paymaster, b"ok")
+        };
+        let error = client.validate_paymaster(request).await.unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert!(
+                error.message().contains("exceeds block gas limit"),
+                "{error:?}"
+            );
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn validating_paymaster() {
+    test_http_server(ValidatePaymasterTest).await;
+}
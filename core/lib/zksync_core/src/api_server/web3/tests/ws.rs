@@ -8,7 +8,9 @@ use reqwest::StatusCode;
 use tokio::sync::watch;
 use zksync_config::configs::chain::NetworkConfig;
 use zksync_dal::ConnectionPool;
-use zksync_types::{api, Address, L1BatchNumber, H256, U64};
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, api, Address, L1BatchNumber, H256, U64,
+};
 use zksync_web3_decl::{
     jsonrpsee::{
         core::client::{Subscription, SubscriptionClientT},
@@ -16,7 +18,7 @@ use zksync_web3_decl::{
         ws_client::{WsClient, WsClientBuilder},
     },
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
-    types::{BlockHeader, PubSubFilter},
+    types::{BlockHeader, PubSubFilter, SequencedLog},
 };
 
 use super::*;
@@ -108,7 +110,7 @@ async fn notifiers_start_after_snapshot_recovery() {
 
     let (stop_sender, stop_receiver) = watch::channel(false);
     let (events_sender, mut events_receiver) = mpsc::unbounded_channel();
-    let mut subscribe_logic = EthSubscribe::new();
+    let mut subscribe_logic = EthSubscribe::new(LogsDenylist::default());
     subscribe_logic.set_events_sender(events_sender);
     let notifier_handles =
         subscribe_logic.spawn_notifiers(pool.clone(), POLL_INTERVAL, stop_receiver);
@@ -159,6 +161,11 @@ trait WsTest: Send + Sync {
     fn websocket_requests_per_minute_limit(&self) -> Option<NonZeroU32> {
         None
     }
+
+    /// Overrides the logs denylist for WS server startup.
+    fn logs_denylist(&self) -> LogsDenylist {
+        LogsDenylist::default()
+    }
 }
 
 async fn test_ws_server(test: impl WsTest) {
@@ -166,7 +173,8 @@ async fn test_ws_server(test: impl WsTest) {
     let network_config = NetworkConfig::for_tests();
     let contracts_config = ContractsConfig::for_tests();
     let web3_config = Web3JsonRpcConfig::for_tests();
-    let api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
+    let mut api_config = InternalApiConfig::new(&network_config, &web3_config, &contracts_config);
+    api_config.logs_denylist = test.logs_denylist();
     let mut storage = pool.access_storage().await.unwrap();
     test.storage_initialization()
         .prepare_storage(&network_config, &mut storage)
@@ -316,6 +324,107 @@ async fn basic_subscriptions_after_snapshot_recovery() {
     .await;
 }
 
+#[derive(Debug)]
+struct L1BatchStatusSubscriptionTest;
+
+#[async_trait]
+impl WsTest for L1BatchStatusSubscriptionTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::L1BatchStatus]).await;
+
+        let params = rpc_params!["l1BatchStatus"];
+        let mut subscription = client
+            .subscribe::<api::L1BatchStatusUpdate, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::L1BatchStatus).await;
+
+        let mut storage = pool.access_storage().await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        drop(storage);
+
+        let update = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for L1 batch status")?
+            .context("L1 batch status subscription terminated")??;
+        assert_eq!(
+            update,
+            api::L1BatchStatusUpdate {
+                number: L1BatchNumber(1),
+                stage: api::L1BatchStage::Sealed,
+                tx_hash: None,
+            }
+        );
+
+        let commit_tx_hash = H256::repeat_byte(1);
+        let mut storage = pool.access_storage().await?;
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::Commit,
+                commit_tx_hash,
+                chrono::Utc::now(),
+            )
+            .await?;
+        drop(storage);
+
+        let update = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for L1 batch status")?
+            .context("L1 batch status subscription terminated")??;
+        assert_eq!(
+            update,
+            api::L1BatchStatusUpdate {
+                number: L1BatchNumber(1),
+                stage: api::L1BatchStage::Committed,
+                tx_hash: Some(commit_tx_hash),
+            }
+        );
+
+        // Revert the L1 batch and wait until the notifier observes the revert.
+        let mut storage = pool.access_storage().await?;
+        storage
+            .blocks_dal()
+            .delete_l1_batches(L1BatchNumber(0))
+            .await?;
+        drop(storage);
+        while pub_sub_events.try_recv().is_ok() {}
+        // The first iteration may have started before the revert.
+        for _ in 0..2 {
+            wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::L1BatchStatus]).await;
+        }
+
+        // The re-sealed batch should be reported again.
+        let mut storage = pool.access_storage().await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        drop(storage);
+        let update = tokio::time::timeout(TEST_TIMEOUT, subscription.next())
+            .await
+            .context("Timed out waiting for L1 batch status")?
+            .context("L1 batch status subscription terminated")??;
+        assert_eq!(
+            update,
+            api::L1BatchStatusUpdate {
+                number: L1BatchNumber(1),
+                stage: api::L1BatchStage::Sealed,
+                tx_hash: None,
+            }
+        );
+        subscription.unsubscribe().await?;
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn l1_batch_status_subscription() {
+    test_ws_server(L1BatchStatusSubscriptionTest).await;
+}
+
 #[derive(Debug)]
 struct LogSubscriptionsTest {
     snapshot_recovery: bool,
@@ -344,6 +453,7 @@ impl LogSubscriptions {
         let address_filter = PubSubFilter {
             address: Some(Address::repeat_byte(23).into()),
             topics: None,
+            resubscribe_from: None,
         };
         let params = rpc_params!["logs", address_filter];
         let address_subscription = client
@@ -352,6 +462,7 @@ impl LogSubscriptions {
         let topic_filter = PubSubFilter {
             address: None,
             topics: Some(vec![Some(H256::repeat_byte(42).into())]),
+            resubscribe_from: None,
         };
         let params = rpc_params!["logs", topic_filter];
         let topic_subscription = client
@@ -463,6 +574,61 @@ async fn log_subscriptions_after_snapshot_recovery() {
     .await;
 }
 
+#[derive(Debug)]
+struct LogSubscriptionsWithDenylistTest;
+
+#[async_trait]
+impl WsTest for LogSubscriptionsWithDenylistTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Logs]).await;
+
+        let denylisted_filter = PubSubFilter {
+            address: Some(Address::repeat_byte(23).into()),
+            topics: None,
+            resubscribe_from: None,
+        };
+        let params = rpc_params!["logs", denylisted_filter];
+        let err = client
+            .subscribe::<api::Log, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(_));
+
+        let params = rpc_params!["logs"];
+        let mut all_logs_subscription = client
+            .subscribe::<api::Log, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(&mut pub_sub_events, SubscriptionType::Logs).await;
+
+        let mut storage = pool.access_storage().await?;
+        let (_, events) = store_events(&mut storage, 1, 0).await?;
+        drop(storage);
+
+        // Events #0 and #3 are emitted by the denylisted address and must not be delivered.
+        let all_logs = collect_logs(&mut all_logs_subscription, 2).await?;
+        assert_logs_match(&all_logs, &[&events[1], &events[2]]);
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Logs]).await;
+        tokio::time::timeout(POLL_INTERVAL, all_logs_subscription.next())
+            .await
+            .unwrap_err();
+        Ok(())
+    }
+
+    fn logs_denylist(&self) -> LogsDenylist {
+        LogsDenylist::new([Address::repeat_byte(23)], [H256::repeat_byte(111)])
+    }
+}
+
+#[tokio::test]
+async fn log_subscriptions_with_denylist() {
+    test_ws_server(LogSubscriptionsWithDenylistTest).await;
+}
+
 #[derive(Debug)]
 struct LogSubscriptionsWithNewBlockTest;
 
@@ -591,6 +757,7 @@ impl WsTest for LogSubscriptionsWithDelayTest {
         let address_and_topic_filter = PubSubFilter {
             address: Some(Address::repeat_byte(23).into()),
             topics: Some(vec![Some(H256::repeat_byte(42).into())]),
+            resubscribe_from: None,
         };
         let params = rpc_params!["logs", address_and_topic_filter];
         let mut address_and_topic_subscription = client
@@ -627,6 +794,114 @@ async fn log_subscriptions_with_delay() {
     test_ws_server(LogSubscriptionsWithDelayTest).await;
 }
 
+#[derive(Debug)]
+struct LogResubscriptionTest;
+
+impl LogResubscriptionTest {
+    async fn subscribe(
+        client: &WsClient,
+        pub_sub_events: &mut mpsc::UnboundedReceiver<PubSubEvent>,
+        resubscribe_from: Option<U64>,
+    ) -> anyhow::Result<Subscription<SequencedLog>> {
+        let filter = PubSubFilter {
+            resubscribe_from,
+            ..PubSubFilter::default()
+        };
+        let params = rpc_params!["logs", filter];
+        let subscription = client
+            .subscribe::<SequencedLog, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await?;
+        wait_for_subscription(pub_sub_events, SubscriptionType::Logs).await;
+        Ok(subscription)
+    }
+
+    async fn collect_logs(
+        sub: &mut Subscription<SequencedLog>,
+        expected_count: usize,
+    ) -> anyhow::Result<Vec<SequencedLog>> {
+        let mut logs = Vec::with_capacity(expected_count);
+        for _ in 0..expected_count {
+            let log = tokio::time::timeout(TEST_TIMEOUT, sub.next())
+                .await
+                .context("Timed out waiting for new log")?
+                .context("Logs subscription terminated")??;
+            logs.push(log);
+        }
+        Ok(logs)
+    }
+}
+
+#[async_trait]
+impl WsTest for LogResubscriptionTest {
+    async fn test(
+        &self,
+        client: &WsClient,
+        pool: &ConnectionPool,
+        mut pub_sub_events: mpsc::UnboundedReceiver<PubSubEvent>,
+    ) -> anyhow::Result<()> {
+        wait_for_notifiers(&mut pub_sub_events, &[SubscriptionType::Logs]).await;
+        let mut subscription = Self::subscribe(client, &mut pub_sub_events, None).await?;
+
+        let mut storage = pool.access_storage().await?;
+        let (_, events) = store_events(&mut storage, 1, 0).await?;
+        drop(storage);
+        let events: Vec<_> = events.iter().collect();
+
+        let logs = Self::collect_logs(&mut subscription, 4).await?;
+        let seqs: Vec<_> = logs.iter().map(|log| log.seq).collect();
+        assert!(
+            seqs.windows(2).all(|window| window[0] < window[1]),
+            "{seqs:?}"
+        );
+        subscription.unsubscribe().await?;
+
+        // Logs from this miniblock are not delivered to any subscribers, but should be retained.
+        let mut storage = pool.access_storage().await?;
+        let (_, new_events) = store_events(&mut storage, 2, 4).await?;
+        drop(storage);
+        let new_events: Vec<_> = new_events.iter().collect();
+        wait_for_notifier_miniblock(
+            &mut pub_sub_events,
+            SubscriptionType::Logs,
+            MiniblockNumber(2),
+        )
+        .await;
+
+        let mut subscription =
+            Self::subscribe(client, &mut pub_sub_events, Some(logs[1].seq)).await?;
+        let resubscribed_logs = Self::collect_logs(&mut subscription, 6).await?;
+        assert_eq!(resubscribed_logs[..2], logs[2..]);
+        let new_logs: Vec<_> = resubscribed_logs[2..]
+            .iter()
+            .map(|log| log.log.clone())
+            .collect();
+        assert_logs_match(&new_logs, &new_events);
+        assert!(resubscribed_logs[2..]
+            .iter()
+            .all(|log| log.seq > logs[3].seq));
+        let old_logs: Vec<_> = logs.into_iter().map(|log| log.log).collect();
+        assert_logs_match(&old_logs, &events);
+
+        // Logs from the genesis miniblock are not retained.
+        let filter = PubSubFilter {
+            resubscribe_from: Some(U64::zero()),
+            ..PubSubFilter::default()
+        };
+        let params = rpc_params!["logs", filter];
+        let err = client
+            .subscribe::<SequencedLog, _>("eth_subscribe", params, "eth_unsubscribe")
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(_));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn log_resubscription() {
+    test_ws_server(LogResubscriptionTest).await;
+}
+
 #[derive(Debug)]
 struct RateLimitingTest;
 
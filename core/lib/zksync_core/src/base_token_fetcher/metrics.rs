@@ -0,0 +1,22 @@
+//! Base token fetcher metrics.
+
+use std::time::Duration;
+
+use vise::{Counter, Gauge, Metrics, Unit};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_base_token_fetcher")]
+pub(super) struct BaseTokenFetcherMetrics {
+    /// Numerator of the latest fetched conversion rate.
+    pub ratio_numerator: Gauge<u64>,
+    /// Denominator of the latest fetched conversion rate.
+    pub ratio_denominator: Gauge<u64>,
+    /// Time since the last successful update of the conversion rate.
+    #[metrics(unit = Unit::Seconds)]
+    pub ratio_age: Gauge<Duration>,
+    /// Number of failed attempts to fetch the conversion rate.
+    pub fetch_errors: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<BaseTokenFetcherMetrics> = vise::Global::new();
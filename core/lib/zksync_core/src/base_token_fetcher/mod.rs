@@ -0,0 +1,309 @@
+//! Fetcher of the conversion rate between ETH and the base token of the chain.
+
+use std::{
+    fmt,
+    num::NonZeroU64,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use tokio::sync::watch;
+use zksync_config::configs::{base_token_fetcher::BaseTokenPriceSource, BaseTokenFetcherConfig};
+use zksync_eth_client::{CallFunctionArgs, EthInterface};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{
+    api::BaseTokenPrice, ethabi, fee_model::BaseTokenConversionRatio, Address, U256,
+};
+use zksync_utils::time::seconds_since_epoch;
+
+use self::metrics::METRICS;
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Source of the conversion rate between ETH and the base token.
+#[async_trait]
+pub trait ConversionRateSource: fmt::Debug + Send + Sync {
+    async fn fetch_conversion_ratio(&self) -> anyhow::Result<BaseTokenConversionRatio>;
+}
+
+/// [`ConversionRateSource`] requesting the rate from an external price API.
+#[derive(Debug)]
+struct PriceApiSource {
+    client: reqwest::Client,
+    url: String,
+}
+
+#[async_trait]
+impl ConversionRateSource for PriceApiSource {
+    async fn fetch_conversion_ratio(&self) -> anyhow::Result<BaseTokenConversionRatio> {
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .context("Failed requesting base token conversion rate")?;
+        let response = response
+            .error_for_status()
+            .context("Requesting base token conversion rate returned non-OK response")?;
+        response
+            .json()
+            .await
+            .context("Failed deserializing base token conversion rate")
+    }
+}
+
+/// ABI of the L1 oracle contract used by [`OnChainOracleSource`].
+static ORACLE_ABI: Lazy<ethabi::Contract> = Lazy::new(|| {
+    let abi = r#"[
+      {
+        "inputs": [],
+        "name": "conversionRatio",
+        "outputs": [
+          { "name": "numerator", "type": "uint256" },
+          { "name": "denominator", "type": "uint256" }
+        ],
+        "stateMutability": "view",
+        "type": "function"
+      }
+    ]"#;
+    serde_json::from_str(abi).unwrap()
+});
+
+/// [`ConversionRateSource`] reading the rate from an oracle contract on L1.
+#[derive(Debug)]
+struct OnChainOracleSource {
+    eth_client: Box<dyn EthInterface>,
+    oracle_address: Address,
+}
+
+impl OnChainOracleSource {
+    fn parse_ratio(tokens: Vec<ethabi::Token>) -> anyhow::Result<BaseTokenConversionRatio> {
+        let [ethabi::Token::Uint(numerator), ethabi::Token::Uint(denominator)] = tokens.as_slice()
+        else {
+            anyhow::bail!("unexpected output of `conversionRatio()`: {tokens:?}");
+        };
+        let to_non_zero = |value: U256, name: &str| {
+            anyhow::ensure!(
+                value <= U256::from(u64::MAX),
+                "{name} {value} overflows u64"
+            );
+            NonZeroU64::new(value.as_u64()).with_context(|| format!("{name} is zero"))
+        };
+        Ok(BaseTokenConversionRatio {
+            numerator: to_non_zero(*numerator, "numerator")?,
+            denominator: to_non_zero(*denominator, "denominator")?,
+        })
+    }
+}
+
+#[async_trait]
+impl ConversionRateSource for OnChainOracleSource {
+    async fn fetch_conversion_ratio(&self) -> anyhow::Result<BaseTokenConversionRatio> {
+        let call = CallFunctionArgs::new("conversionRatio", ())
+            .for_contract(self.oracle_address, ORACLE_ABI.clone());
+        let tokens = self
+            .eth_client
+            .call_contract_function(call)
+            .await
+            .context("Failed calling base token oracle")?;
+        Self::parse_ratio(tokens).context("Invalid base token conversion rate returned by oracle")
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct LatestRatio {
+    ratio: BaseTokenConversionRatio,
+    updated_at: Instant,
+    /// UNIX timestamp of the update in seconds.
+    updated_at_timestamp: u64,
+}
+
+/// Component keeping track of the conversion rate between ETH and the base token of the chain. The rate
+/// is used by the fee model to express L1 gas and pubdata prices in the base token units, and is exposed via
+/// the `zks_getBaseTokenPrice` API method.
+///
+/// If the rate cannot be updated for longer than [`BaseTokenFetcherConfig::max_staleness()`], the last
+/// known rate continues to be used, but the rate is reported as stale.
+#[derive(Debug)]
+pub struct BaseTokenFetcher {
+    source: Option<Box<dyn ConversionRateSource>>,
+    poll_interval: Duration,
+    max_staleness: Duration,
+    latest_ratio: RwLock<LatestRatio>,
+    health_updater: HealthUpdater,
+}
+
+impl BaseTokenFetcher {
+    /// Creates a fetcher for a chain with ETH as the base token.
+    pub fn eth() -> Self {
+        Self::from_source(None, &BaseTokenFetcherConfig::default(), Default::default())
+    }
+
+    /// Creates a fetcher based on the provided config. The initial conversion rate is fetched from the source;
+    /// an error is returned if it cannot be fetched. `eth_client` is only used by the on-chain oracle source.
+    pub async fn new(
+        config: &BaseTokenFetcherConfig,
+        eth_client: Box<dyn EthInterface>,
+    ) -> anyhow::Result<Self> {
+        let source: Box<dyn ConversionRateSource> = match config.source {
+            BaseTokenPriceSource::Eth => return Ok(Self::eth()),
+            BaseTokenPriceSource::Fixed => {
+                let numerator = config
+                    .fixed_numerator
+                    .and_then(NonZeroU64::new)
+                    .context("fixed conversion rate requires a positive `fixed_numerator`")?;
+                let denominator = config
+                    .fixed_denominator
+                    .and_then(NonZeroU64::new)
+                    .context("fixed conversion rate requires a positive `fixed_denominator`")?;
+                let ratio = BaseTokenConversionRatio {
+                    numerator,
+                    denominator,
+                };
+                return Ok(Self::from_source(None, config, ratio));
+            }
+            BaseTokenPriceSource::PriceApi => Box::new(PriceApiSource {
+                client: reqwest::Client::new(),
+                url: config
+                    .price_api_url
+                    .clone()
+                    .context("price API source requires `price_api_url`")?,
+            }),
+            BaseTokenPriceSource::OnChainOracle => Box::new(OnChainOracleSource {
+                eth_client,
+                oracle_address: config
+                    .oracle_address
+                    .context("on-chain oracle source requires `oracle_address`")?,
+            }),
+        };
+        Self::with_source(source, config).await
+    }
+
+    /// Creates a fetcher with a custom conversion rate source.
+    pub async fn with_source(
+        source: Box<dyn ConversionRateSource>,
+        config: &BaseTokenFetcherConfig,
+    ) -> anyhow::Result<Self> {
+        let initial_ratio = source
+            .fetch_conversion_ratio()
+            .await
+            .context("failed fetching initial base token conversion rate")?;
+        Ok(Self::from_source(Some(source), config, initial_ratio))
+    }
+
+    fn from_source(
+        source: Option<Box<dyn ConversionRateSource>>,
+        config: &BaseTokenFetcherConfig,
+        initial_ratio: BaseTokenConversionRatio,
+    ) -> Self {
+        let this = Self {
+            source,
+            poll_interval: config.poll_interval(),
+            max_staleness: config.max_staleness(),
+            latest_ratio: RwLock::new(LatestRatio {
+                ratio: initial_ratio,
+                updated_at: Instant::now(),
+                updated_at_timestamp: seconds_since_epoch(),
+            }),
+            health_updater: ReactiveHealthCheck::new("base_token_fetcher").1,
+        };
+        this.report_ratio(initial_ratio);
+        this
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    fn report_ratio(&self, ratio: BaseTokenConversionRatio) {
+        METRICS.ratio_numerator.set(ratio.numerator.get());
+        METRICS.ratio_denominator.set(ratio.denominator.get());
+        let health_details = serde_json::json!({
+            "ratio": ratio,
+            "is_stale": false,
+        });
+        self.health_updater
+            .update(Health::from(HealthStatus::Ready).with_details(health_details));
+    }
+
+    /// Returns the latest known conversion rate, which may be stale. Staleness is reported by [`Self::run()`].
+    pub fn conversion_ratio(&self) -> BaseTokenConversionRatio {
+        self.latest_ratio.read().unwrap().ratio
+    }
+
+    /// Returns the latest known conversion rate together with its freshness information.
+    pub fn price(&self) -> BaseTokenPrice {
+        let latest = *self.latest_ratio.read().unwrap();
+        BaseTokenPrice {
+            ratio: latest.ratio,
+            updated_at: latest.updated_at_timestamp,
+            is_stale: self.source.is_some() && latest.updated_at.elapsed() > self.max_staleness,
+        }
+    }
+
+    async fn update(&self, source: &dyn ConversionRateSource) -> anyhow::Result<()> {
+        let ratio = source.fetch_conversion_ratio().await?;
+        *self.latest_ratio.write().unwrap() = LatestRatio {
+            ratio,
+            updated_at: Instant::now(),
+            updated_at_timestamp: seconds_since_epoch(),
+        };
+        self.report_ratio(ratio);
+        Ok(())
+    }
+
+    pub async fn run(
+        self: Arc<Self>,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let Some(source) = &self.source else {
+            // The conversion rate is constant; there's nothing to update. We still wait for the stop signal
+            // since the component finishing early is treated as an error.
+            stop_receiver.changed().await.ok();
+            return Ok(());
+        };
+
+        let mut is_stale = false;
+        while !*stop_receiver.borrow_and_update() {
+            if let Err(err) = self.update(source.as_ref()).await {
+                METRICS.fetch_errors.inc();
+                tracing::warn!("Failed updating base token conversion rate: {err:#}");
+            }
+
+            let latest = *self.latest_ratio.read().unwrap();
+            let age = latest.updated_at.elapsed();
+            METRICS.ratio_age.set(age);
+            let was_stale = is_stale;
+            is_stale = age > self.max_staleness;
+            if is_stale && !was_stale {
+                tracing::warn!(
+                    "Base token conversion rate {:?} is stale: it wasn't updated for {age:?}",
+                    latest.ratio
+                );
+            } else if !is_stale && was_stale {
+                tracing::info!("Base token conversion rate is no longer stale");
+            }
+            if is_stale {
+                let health_details = serde_json::json!({
+                    "ratio": latest.ratio,
+                    "is_stale": true,
+                });
+                self.health_updater
+                    .update(Health::from(HealthStatus::Affected).with_details(health_details));
+            }
+
+            // We don't check the result: if a stop signal is received, we'll return at the start
+            // of the next iteration.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, base token fetcher is shutting down");
+        Ok(())
+    }
+}
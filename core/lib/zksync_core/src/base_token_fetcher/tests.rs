@@ -0,0 +1,166 @@
+//! Tests for the base token fetcher.
+
+use std::sync::Mutex;
+
+use zksync_eth_client::clients::MockEthereum;
+use zksync_health_check::CheckHealth;
+
+use super::*;
+
+#[derive(Debug)]
+struct MockSource(Mutex<Vec<anyhow::Result<BaseTokenConversionRatio>>>);
+
+impl MockSource {
+    fn new(mut responses: Vec<anyhow::Result<BaseTokenConversionRatio>>) -> Self {
+        responses.reverse();
+        Self(Mutex::new(responses))
+    }
+}
+
+#[async_trait]
+impl ConversionRateSource for MockSource {
+    async fn fetch_conversion_ratio(&self) -> anyhow::Result<BaseTokenConversionRatio> {
+        self.0
+            .lock()
+            .unwrap()
+            .pop()
+            .unwrap_or_else(|| Err(anyhow::anyhow!("no more responses")))
+    }
+}
+
+fn ratio(numerator: u64, denominator: u64) -> BaseTokenConversionRatio {
+    BaseTokenConversionRatio {
+        numerator: NonZeroU64::new(numerator).unwrap(),
+        denominator: NonZeroU64::new(denominator).unwrap(),
+    }
+}
+
+#[tokio::test]
+async fn fixed_conversion_rate() {
+    let config = BaseTokenFetcherConfig {
+        source: BaseTokenPriceSource::Fixed,
+        fixed_numerator: Some(3),
+        fixed_denominator: Some(2),
+        ..BaseTokenFetcherConfig::default()
+    };
+    let fetcher = BaseTokenFetcher::new(&config, Box::new(MockEthereum::default()))
+        .await
+        .unwrap();
+    assert_eq!(fetcher.conversion_ratio(), ratio(3, 2));
+    assert!(!fetcher.price().is_stale);
+
+    let config = BaseTokenFetcherConfig {
+        fixed_denominator: Some(0),
+        ..config
+    };
+    BaseTokenFetcher::new(&config, Box::new(MockEthereum::default()))
+        .await
+        .unwrap_err();
+}
+
+#[test]
+fn parsing_oracle_conversion_rate() {
+    let tokens = vec![ethabi::Token::Uint(3.into()), ethabi::Token::Uint(2.into())];
+    let parsed = OnChainOracleSource::parse_ratio(tokens).unwrap();
+    assert_eq!(parsed, ratio(3, 2));
+
+    let tokens = vec![ethabi::Token::Uint(3.into()), ethabi::Token::Uint(0.into())];
+    let err = OnChainOracleSource::parse_ratio(tokens)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("denominator is zero"), "{err}");
+
+    let tokens = vec![
+        ethabi::Token::Uint(U256::from(u64::MAX) + 1),
+        ethabi::Token::Uint(1.into()),
+    ];
+    let err = OnChainOracleSource::parse_ratio(tokens)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("overflows"), "{err}");
+
+    let tokens = vec![ethabi::Token::Bool(true)];
+    OnChainOracleSource::parse_ratio(tokens).unwrap_err();
+}
+
+#[tokio::test]
+async fn oracle_source_requires_address() {
+    let config = BaseTokenFetcherConfig {
+        source: BaseTokenPriceSource::OnChainOracle,
+        ..BaseTokenFetcherConfig::default()
+    };
+    let err = BaseTokenFetcher::new(&config, Box::new(MockEthereum::default()))
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("oracle_address"), "{err}");
+}
+
+#[tokio::test]
+async fn updating_conversion_rate() {
+    let config = BaseTokenFetcherConfig {
+        source: BaseTokenPriceSource::PriceApi,
+        poll_interval_ms: 10,
+        max_staleness_ms: 60_000,
+        ..BaseTokenFetcherConfig::default()
+    };
+    let source = MockSource::new(vec![
+        Ok(ratio(2, 1)),
+        Err(anyhow::anyhow!("API is down")),
+        Ok(ratio(5, 2)),
+    ]);
+    let fetcher = BaseTokenFetcher::with_source(Box::new(source), &config)
+        .await
+        .unwrap();
+    let fetcher = Arc::new(fetcher);
+    assert_eq!(fetcher.conversion_ratio(), ratio(2, 1));
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let fetcher_task = tokio::spawn(fetcher.clone().run(stop_receiver));
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while fetcher.conversion_ratio() != ratio(5, 2) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("conversion rate was not updated");
+    assert!(!fetcher.price().is_stale);
+
+    stop_sender.send_replace(true);
+    fetcher_task.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn stale_conversion_rate() {
+    let config = BaseTokenFetcherConfig {
+        source: BaseTokenPriceSource::PriceApi,
+        poll_interval_ms: 10,
+        max_staleness_ms: 0,
+        ..BaseTokenFetcherConfig::default()
+    };
+    let source = MockSource::new(vec![Ok(ratio(2, 1))]);
+    let fetcher = BaseTokenFetcher::with_source(Box::new(source), &config)
+        .await
+        .unwrap();
+    let fetcher = Arc::new(fetcher);
+    let health_check = fetcher.health_check();
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let fetcher_task = tokio::spawn(fetcher.clone().run(stop_receiver));
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while !matches!(
+            health_check.check_health().await.status(),
+            HealthStatus::Affected
+        ) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("conversion rate was not marked as stale");
+    // The last known rate is still used.
+    assert_eq!(fetcher.conversion_ratio(), ratio(2, 1));
+    assert!(fetcher.price().is_stale);
+
+    stop_sender.send_replace(true);
+    fetcher_task.await.unwrap().unwrap();
+}
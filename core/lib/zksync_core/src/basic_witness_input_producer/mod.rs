@@ -4,7 +4,7 @@ use anyhow::Context;
 use async_trait::async_trait;
 use multivm::interface::{L2BlockEnv, VmInterface};
 use tokio::{runtime::Handle, task::JoinHandle};
-use vm_utils::{create_vm, execute_tx};
+use vm_utils::{create_vm, execute_tx, storage::BaseSystemContractsCache};
 use zksync_dal::{basic_witness_input_producer_dal::JOB_MAX_ATTEMPT, ConnectionPool};
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
@@ -23,6 +23,7 @@ pub struct BasicWitnessInputProducer {
     connection_pool: ConnectionPool,
     l2_chain_id: L2ChainId,
     object_store: Arc<dyn ObjectStore>,
+    contracts_cache: BaseSystemContractsCache,
 }
 
 impl BasicWitnessInputProducer {
@@ -35,6 +36,7 @@ impl BasicWitnessInputProducer {
             connection_pool,
             object_store: store_factory.create_store().await,
             l2_chain_id,
+            contracts_cache: BaseSystemContractsCache::default(),
         })
     }
 
@@ -44,6 +46,7 @@ impl BasicWitnessInputProducer {
         started_at: Instant,
         connection_pool: ConnectionPool,
         l2_chain_id: L2ChainId,
+        contracts_cache: &BaseSystemContractsCache,
     ) -> anyhow::Result<WitnessBlockState> {
         let mut connection = rt_handle
             .block_on(connection_pool.access_storage())
@@ -55,9 +58,14 @@ impl BasicWitnessInputProducer {
                 .get_miniblocks_to_execute_for_l1_batch(l1_batch_number),
         )?;
 
-        let (mut vm, storage_view) =
-            create_vm(rt_handle.clone(), l1_batch_number, connection, l2_chain_id)
-                .context("failed to create vm for BasicWitnessInputProducer")?;
+        let (mut vm, storage_view) = create_vm(
+            rt_handle.clone(),
+            l1_batch_number,
+            connection,
+            l2_chain_id,
+            contracts_cache,
+        )
+        .context("failed to create vm for BasicWitnessInputProducer")?;
 
         tracing::info!("Started execution of l1_batch: {l1_batch_number:?}");
 
@@ -145,6 +153,7 @@ impl JobProcessor for BasicWitnessInputProducer {
     ) -> JoinHandle<anyhow::Result<Self::JobArtifacts>> {
         let l2_chain_id = self.l2_chain_id;
         let connection_pool = self.connection_pool.clone();
+        let contracts_cache = self.contracts_cache.clone();
         tokio::task::spawn_blocking(move || {
             let rt_handle = Handle::current();
             Self::process_job_impl(
@@ -153,6 +162,7 @@ impl JobProcessor for BasicWitnessInputProducer {
                 started_at,
                 connection_pool.clone(),
                 l2_chain_id,
+                &contracts_cache,
             )
         })
     }
@@ -0,0 +1,598 @@
+//! Self-contained replay bundles for L1 batches (aka batch evidence bundles).
+//!
+//! A bundle contains everything necessary to re-execute an L1 batch without access to Postgres or RocksDB:
+//! batch environment (including base system contracts), transactions grouped by miniblocks, the read set
+//! of the batch (i.e., all storage slots and factory deps accessed during its execution), and the output
+//! persisted by the state keeper. Bundles are serialized as JSON, so that they can be attached to incident
+//! reports or bug reports and replayed on another machine.
+
+use std::{collections::BTreeMap, fmt};
+
+use anyhow::Context as _;
+use multivm::{
+    interface::{L1BatchEnv, L2BlockEnv, SystemEnv, TxExecutionMode},
+    VmInstance,
+};
+use serde::{Deserialize, Serialize};
+use tokio::runtime::Handle;
+use vm_utils::{load_l1_batch_env, storage::BaseSystemContractsCache};
+use zksync_contracts::{BaseSystemContracts, SystemContractCode};
+use zksync_dal::ConnectionPool;
+use zksync_state::{PostgresStorage, ReadStorage, StorageView};
+use zksync_types::{
+    block::MiniblockExecutionData, event::VmEvent, fee_model::BatchFeeInput,
+    tx::tx_execution_info::TxExecutionStatus, Address, Bytes, L1BatchNumber, L2ChainId,
+    MiniblockNumber, ProtocolVersionId, StorageKey, Transaction, H256,
+};
+use zksync_utils::{be_words_to_bytes, bytecode::hash_bytecode, bytes_to_be_words};
+
+use self::storage::{BundleStorage, RecordingStorage, StorageRecords, StorageSlot};
+use crate::vm_runner::{execute_miniblocks, load_miniblocks, load_persisted_output, BatchOutput};
+
+mod storage;
+#[cfg(test)]
+mod tests;
+
+/// Current version of the bundle format. Bundles with other versions are rejected on replay.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+/// Fee input for the batch. Mirrors [`BatchFeeInput`], which is not serializable.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum FeeInputParams {
+    L1Pegged {
+        l1_gas_price: u64,
+        fair_l2_gas_price: u64,
+    },
+    PubdataIndependent {
+        l1_gas_price: u64,
+        fair_l2_gas_price: u64,
+        fair_pubdata_price: u64,
+    },
+}
+
+impl From<BatchFeeInput> for FeeInputParams {
+    fn from(input: BatchFeeInput) -> Self {
+        match input {
+            BatchFeeInput::L1Pegged(input) => Self::L1Pegged {
+                l1_gas_price: input.l1_gas_price,
+                fair_l2_gas_price: input.fair_l2_gas_price,
+            },
+            BatchFeeInput::PubdataIndependent(input) => Self::PubdataIndependent {
+                l1_gas_price: input.l1_gas_price,
+                fair_l2_gas_price: input.fair_l2_gas_price,
+                fair_pubdata_price: input.fair_pubdata_price,
+            },
+        }
+    }
+}
+
+impl From<FeeInputParams> for BatchFeeInput {
+    fn from(params: FeeInputParams) -> Self {
+        match params {
+            FeeInputParams::L1Pegged {
+                l1_gas_price,
+                fair_l2_gas_price,
+            } => Self::l1_pegged(l1_gas_price, fair_l2_gas_price),
+            FeeInputParams::PubdataIndependent {
+                l1_gas_price,
+                fair_l2_gas_price,
+                fair_pubdata_price,
+            } => Self::pubdata_independent(l1_gas_price, fair_l2_gas_price, fair_pubdata_price),
+        }
+    }
+}
+
+/// Protocol and batch parameters necessary to initialize a VM for the batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct BatchEnvParams {
+    protocol_version: ProtocolVersionId,
+    chain_id: L2ChainId,
+    previous_batch_hash: Option<H256>,
+    timestamp: u64,
+    fee_input: FeeInputParams,
+    fee_account: Address,
+    enforced_base_fee: Option<u64>,
+    gas_limit: u32,
+    first_miniblock_timestamp: u64,
+    first_miniblock_prev_hash: H256,
+    first_miniblock_virtual_blocks: u32,
+    bootloader: Bytes,
+    default_aa: Bytes,
+}
+
+impl BatchEnvParams {
+    fn new(system_env: &SystemEnv, l1_batch_env: &L1BatchEnv) -> Self {
+        let contracts = &system_env.base_system_smart_contracts;
+        Self {
+            protocol_version: system_env.version,
+            chain_id: system_env.chain_id,
+            previous_batch_hash: l1_batch_env.previous_batch_hash,
+            timestamp: l1_batch_env.timestamp,
+            fee_input: l1_batch_env.fee_input.into(),
+            fee_account: l1_batch_env.fee_account,
+            enforced_base_fee: l1_batch_env.enforced_base_fee,
+            gas_limit: system_env.gas_limit,
+            first_miniblock_timestamp: l1_batch_env.first_l2_block.timestamp,
+            first_miniblock_prev_hash: l1_batch_env.first_l2_block.prev_block_hash,
+            first_miniblock_virtual_blocks: l1_batch_env
+                .first_l2_block
+                .max_virtual_blocks_to_create,
+            bootloader: be_words_to_bytes(&contracts.bootloader.code).into(),
+            default_aa: be_words_to_bytes(&contracts.default_aa.code).into(),
+        }
+    }
+
+    fn system_contract_code(bytecode: &Bytes) -> SystemContractCode {
+        SystemContractCode {
+            code: bytes_to_be_words(bytecode.0.clone()),
+            hash: hash_bytecode(&bytecode.0),
+        }
+    }
+
+    fn to_vm_envs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        first_miniblock_number: MiniblockNumber,
+    ) -> (SystemEnv, L1BatchEnv) {
+        let system_env = SystemEnv {
+            zk_porter_available: false,
+            version: self.protocol_version,
+            base_system_smart_contracts: BaseSystemContracts {
+                bootloader: Self::system_contract_code(&self.bootloader),
+                default_aa: Self::system_contract_code(&self.default_aa),
+            },
+            gas_limit: self.gas_limit,
+            execution_mode: TxExecutionMode::VerifyExecute,
+            // Consistent with other components re-executing sealed batches; see `vm_utils::load_l1_batch_env()`.
+            default_validation_computational_gas_limit: u32::MAX,
+            chain_id: self.chain_id,
+        };
+        let l1_batch_env = L1BatchEnv {
+            previous_batch_hash: self.previous_batch_hash,
+            number: l1_batch_number,
+            timestamp: self.timestamp,
+            fee_input: self.fee_input.into(),
+            fee_account: self.fee_account,
+            enforced_base_fee: self.enforced_base_fee,
+            first_l2_block: L2BlockEnv {
+                number: first_miniblock_number.0,
+                timestamp: self.first_miniblock_timestamp,
+                prev_block_hash: self.first_miniblock_prev_hash,
+                max_virtual_blocks_to_create: self.first_miniblock_virtual_blocks,
+            },
+        };
+        (system_env, l1_batch_env)
+    }
+}
+
+/// Serializable version of [`MiniblockExecutionData`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct MiniblockParams {
+    number: MiniblockNumber,
+    timestamp: u64,
+    prev_block_hash: H256,
+    virtual_blocks: u32,
+    txs: Vec<Transaction>,
+}
+
+impl From<MiniblockExecutionData> for MiniblockParams {
+    fn from(data: MiniblockExecutionData) -> Self {
+        Self {
+            number: data.number,
+            timestamp: data.timestamp,
+            prev_block_hash: data.prev_block_hash,
+            virtual_blocks: data.virtual_blocks,
+            txs: data.txs,
+        }
+    }
+}
+
+impl From<MiniblockParams> for MiniblockExecutionData {
+    fn from(params: MiniblockParams) -> Self {
+        Self {
+            number: params.number,
+            timestamp: params.timestamp,
+            prev_block_hash: params.prev_block_hash,
+            virtual_blocks: params.virtual_blocks,
+            txs: params.txs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct FactoryDep {
+    hash: H256,
+    bytecode: Bytes,
+}
+
+/// Outcome of a single transaction in the batch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TxOutcome {
+    pub hash: H256,
+    pub success: bool,
+    pub gas_refunded: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct StorageWrite {
+    key: StorageKey,
+    value: H256,
+}
+
+/// Serializable version of the batch output compared on replay.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct BundleOutput {
+    tx_outcomes: Vec<TxOutcome>,
+    events: Vec<VmEvent>,
+    /// Final values of the storage slots written in the batch, ordered by key.
+    storage_writes: Vec<StorageWrite>,
+}
+
+impl From<BatchOutput> for BundleOutput {
+    fn from(output: BatchOutput) -> Self {
+        let tx_outcomes = output
+            .tx_outcomes
+            .into_iter()
+            .map(|(hash, status, gas_refunded)| TxOutcome {
+                hash,
+                success: status == TxExecutionStatus::Success,
+                gas_refunded,
+            })
+            .collect();
+        let storage_writes: BTreeMap<_, _> = output.storage_writes.into_iter().collect();
+        let storage_writes = storage_writes
+            .into_iter()
+            .map(|(key, value)| StorageWrite { key, value })
+            .collect();
+        Self {
+            tx_outcomes,
+            events: output.events,
+            storage_writes,
+        }
+    }
+}
+
+/// Difference between the output recorded in a bundle and the output of the replayed execution.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplayDiff {
+    /// Transaction outcome differs; `None` means that the transaction is missing in the corresponding output.
+    TxOutcome {
+        index: usize,
+        recorded: Option<TxOutcome>,
+        replayed: Option<TxOutcome>,
+    },
+    /// Number of events emitted in the batch differs. Events are not compared one-by-one in this case.
+    EventCount { recorded: usize, replayed: usize },
+    /// Event differs.
+    Event {
+        index: usize,
+        recorded: Box<VmEvent>,
+        replayed: Box<VmEvent>,
+    },
+    /// Final value of a storage slot differs; `None` means that the slot is not written in the corresponding output.
+    StorageWrite {
+        key: StorageKey,
+        recorded: Option<H256>,
+        replayed: Option<H256>,
+    },
+}
+
+impl fmt::Display for ReplayDiff {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TxOutcome {
+                index,
+                recorded,
+                replayed,
+            } => write!(
+                formatter,
+                "transaction #{index}: recorded {recorded:?}, replayed {replayed:?}"
+            ),
+            Self::EventCount { recorded, replayed } => write!(
+                formatter,
+                "event count: recorded {recorded}, replayed {replayed}"
+            ),
+            Self::Event {
+                index,
+                recorded,
+                replayed,
+            } => write!(
+                formatter,
+                "event #{index}: recorded {recorded:?}, replayed {replayed:?}"
+            ),
+            Self::StorageWrite {
+                key,
+                recorded,
+                replayed,
+            } => write!(
+                formatter,
+                "storage slot {:?}:{:?}: recorded {recorded:?}, replayed {replayed:?}",
+                key.address(),
+                key.key()
+            ),
+        }
+    }
+}
+
+fn diff_outputs(recorded: &BundleOutput, replayed: &BundleOutput) -> Vec<ReplayDiff> {
+    let mut diffs = vec![];
+    let tx_count = recorded.tx_outcomes.len().max(replayed.tx_outcomes.len());
+    for index in 0..tx_count {
+        let recorded = recorded.tx_outcomes.get(index).copied();
+        let replayed = replayed.tx_outcomes.get(index).copied();
+        if recorded != replayed {
+            diffs.push(ReplayDiff::TxOutcome {
+                index,
+                recorded,
+                replayed,
+            });
+        }
+    }
+
+    if recorded.events.len() == replayed.events.len() {
+        let event_pairs = recorded.events.iter().zip(&replayed.events);
+        for (index, (recorded, replayed)) in event_pairs.enumerate() {
+            if recorded != replayed {
+                diffs.push(ReplayDiff::Event {
+                    index,
+                    recorded: Box::new(recorded.clone()),
+                    replayed: Box::new(replayed.clone()),
+                });
+            }
+        }
+    } else {
+        diffs.push(ReplayDiff::EventCount {
+            recorded: recorded.events.len(),
+            replayed: replayed.events.len(),
+        });
+    }
+
+    let mut storage_writes = BTreeMap::<_, (Option<H256>, Option<H256>)>::new();
+    for write in &recorded.storage_writes {
+        storage_writes.entry(write.key).or_default().0 = Some(write.value);
+    }
+    for write in &replayed.storage_writes {
+        storage_writes.entry(write.key).or_default().1 = Some(write.value);
+    }
+    diffs.extend(
+        storage_writes
+            .into_iter()
+            .filter(|(_, (recorded, replayed))| recorded != replayed)
+            .map(|(key, (recorded, replayed))| ReplayDiff::StorageWrite {
+                key,
+                recorded,
+                replayed,
+            }),
+    );
+    diffs
+}
+
+/// Executes a batch on top of the provided storage, recording all storage accesses.
+fn execute_with_recording<S: ReadStorage + fmt::Debug>(
+    storage: S,
+    system_env: SystemEnv,
+    l1_batch_env: L1BatchEnv,
+    miniblocks: &[MiniblockExecutionData],
+) -> anyhow::Result<(BatchOutput, StorageRecords)> {
+    let (storage, records) = RecordingStorage::new(storage);
+    let storage_view = StorageView::new(storage).to_rc_ptr();
+    let mut vm = VmInstance::new(l1_batch_env, system_env, storage_view);
+    let output = execute_miniblocks(&mut vm, miniblocks)?;
+    drop(vm);
+    Ok((output, records.take()))
+}
+
+/// Report produced by [`BatchReplayBundle::replay()`].
+#[derive(Debug)]
+pub struct ReplayReport {
+    pub l1_batch_number: L1BatchNumber,
+    /// Differences between the recorded and replayed outputs.
+    pub diffs: Vec<ReplayDiff>,
+    /// Storage slots accessed during replay, but missing from the bundle.
+    pub missing_storage_slots: Vec<StorageKey>,
+    /// Factory deps accessed during replay, but missing from the bundle.
+    pub missing_factory_deps: Vec<H256>,
+}
+
+impl ReplayReport {
+    /// Checks whether the replayed execution matches the recorded one.
+    pub fn is_consistent(&self) -> bool {
+        self.diffs.is_empty()
+            && self.missing_storage_slots.is_empty()
+            && self.missing_factory_deps.is_empty()
+    }
+}
+
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let l1_batch_number = self.l1_batch_number;
+        if self.is_consistent() {
+            return write!(
+                formatter,
+                "Replayed L1 batch #{l1_batch_number} matches the recorded output"
+            );
+        }
+        write!(
+            formatter,
+            "Replayed L1 batch #{l1_batch_number} diverges from the recorded output"
+        )?;
+        for diff in &self.diffs {
+            write!(formatter, "\n  {diff}")?;
+        }
+        for key in &self.missing_storage_slots {
+            write!(
+                formatter,
+                "\n  storage slot {:?}:{:?} is missing from the bundle",
+                key.address(),
+                key.key()
+            )?;
+        }
+        for hash in &self.missing_factory_deps {
+            write!(
+                formatter,
+                "\n  factory dep {hash:?} is missing from the bundle"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Self-contained replay bundle for an L1 batch. See the [module docs](self) for details.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchReplayBundle {
+    format_version: u32,
+    l1_batch_number: L1BatchNumber,
+    env: BatchEnvParams,
+    miniblocks: Vec<MiniblockParams>,
+    storage_slots: Vec<StorageSlot>,
+    factory_deps: Vec<FactoryDep>,
+    recorded_output: BundleOutput,
+}
+
+impl BatchReplayBundle {
+    /// Exports a bundle for the specified sealed L1 batch. The batch is executed while recording all storage accesses;
+    /// hence, this is a CPU-heavy operation.
+    pub async fn export(
+        pool: &ConnectionPool,
+        l2_chain_id: L2ChainId,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Self> {
+        let mut storage = pool.access_storage_tagged("batch_replay").await?;
+        let miniblocks = load_miniblocks(&mut storage, l1_batch_number).await?;
+        let persisted_output = load_persisted_output(&mut storage, l1_batch_number).await?;
+        drop(storage);
+
+        let pool = pool.clone();
+        tokio::task::spawn_blocking(move || {
+            Self::record_execution(
+                &Handle::current(),
+                &pool,
+                l2_chain_id,
+                l1_batch_number,
+                miniblocks,
+                persisted_output,
+            )
+        })
+        .await
+        .context("batch execution panicked")?
+    }
+
+    fn record_execution(
+        rt_handle: &Handle,
+        pool: &ConnectionPool,
+        l2_chain_id: L2ChainId,
+        l1_batch_number: L1BatchNumber,
+        miniblocks: Vec<MiniblockExecutionData>,
+        persisted_output: BatchOutput,
+    ) -> anyhow::Result<Self> {
+        let mut connection = rt_handle.block_on(pool.access_storage_tagged("batch_replay"))?;
+        let (system_env, l1_batch_env) = load_l1_batch_env(
+            rt_handle,
+            l1_batch_number,
+            &mut connection,
+            l2_chain_id,
+            &BaseSystemContractsCache::default(),
+        )?;
+        let env = BatchEnvParams::new(&system_env, &l1_batch_env);
+
+        let storage_miniblock_number = MiniblockNumber(l1_batch_env.first_l2_block.number - 1);
+        let pg_storage = PostgresStorage::new(
+            rt_handle.clone(),
+            connection,
+            storage_miniblock_number,
+            true,
+        );
+        let (output, records) =
+            execute_with_recording(pg_storage, system_env, l1_batch_env, &miniblocks)
+                .with_context(|| format!("failed executing L1 batch #{l1_batch_number}"))?;
+
+        let recorded_output = BundleOutput::from(persisted_output);
+        let diffs = diff_outputs(&recorded_output, &BundleOutput::from(output));
+        if !diffs.is_empty() {
+            // This is not an error: bundles are useful precisely in the case when a batch cannot be reproduced.
+            tracing::warn!(
+                "Execution of L1 batch #{l1_batch_number} diverges from the persisted output: {diffs:?}"
+            );
+        }
+        Ok(Self::new(
+            l1_batch_number,
+            env,
+            miniblocks,
+            records,
+            recorded_output,
+        ))
+    }
+
+    fn new(
+        l1_batch_number: L1BatchNumber,
+        env: BatchEnvParams,
+        miniblocks: Vec<MiniblockExecutionData>,
+        records: StorageRecords,
+        recorded_output: BundleOutput,
+    ) -> Self {
+        Self {
+            format_version: BUNDLE_FORMAT_VERSION,
+            l1_batch_number,
+            env,
+            miniblocks: miniblocks.into_iter().map(MiniblockParams::from).collect(),
+            storage_slots: records.slots.into_values().collect(),
+            factory_deps: records
+                .factory_deps
+                .into_iter()
+                .map(|(hash, bytecode)| FactoryDep {
+                    hash,
+                    bytecode: bytecode.into(),
+                })
+                .collect(),
+            recorded_output,
+        }
+    }
+
+    /// Returns the number of the L1 batch contained in this bundle.
+    pub fn l1_batch_number(&self) -> L1BatchNumber {
+        self.l1_batch_number
+    }
+
+    /// Re-executes the batch from the bundle and compares the output with the recorded one.
+    /// This method is CPU-heavy and blocking.
+    pub fn replay(&self) -> anyhow::Result<ReplayReport> {
+        anyhow::ensure!(
+            self.format_version == BUNDLE_FORMAT_VERSION,
+            "Unsupported bundle format version {}; expected {BUNDLE_FORMAT_VERSION}",
+            self.format_version
+        );
+        let first_miniblock = self
+            .miniblocks
+            .first()
+            .context("bundle has no miniblocks")?;
+        let (system_env, l1_batch_env) = self
+            .env
+            .to_vm_envs(self.l1_batch_number, first_miniblock.number);
+
+        let factory_deps = self
+            .factory_deps
+            .iter()
+            .map(|dep| (dep.hash, dep.bytecode.0.clone()));
+        let (storage, missing_data) = BundleStorage::new(self.storage_slots.clone(), factory_deps);
+        let storage_view = StorageView::new(storage).to_rc_ptr();
+        let mut vm = VmInstance::new(l1_batch_env, system_env, storage_view);
+        let miniblocks: Vec<_> = self
+            .miniblocks
+            .iter()
+            .cloned()
+            .map(MiniblockExecutionData::from)
+            .collect();
+        let output = execute_miniblocks(&mut vm, &miniblocks)
+            .with_context(|| format!("failed executing L1 batch #{}", self.l1_batch_number))?;
+        drop(vm);
+
+        let missing_data = missing_data.take();
+        Ok(ReplayReport {
+            l1_batch_number: self.l1_batch_number,
+            diffs: diff_outputs(&self.recorded_output, &BundleOutput::from(output)),
+            missing_storage_slots: missing_data.storage_slots.into_iter().collect(),
+            missing_factory_deps: missing_data.factory_deps.into_iter().collect(),
+        })
+    }
+}
@@ -0,0 +1,173 @@
+//! Storage wrappers used to record storage accesses when exporting replay bundles and to serve them on replay.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    rc::Rc,
+};
+
+use serde::{Deserialize, Serialize};
+use zksync_state::ReadStorage;
+use zksync_types::{StorageKey, StorageValue, H256};
+
+/// Storage slot accessed during batch execution together with all data read for it from the underlying storage.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(super) struct StorageSlot {
+    pub key: StorageKey,
+    /// Slot value before the batch execution.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<StorageValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_write_initial: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enumeration_index: Option<u64>,
+}
+
+impl StorageSlot {
+    fn new(key: StorageKey) -> Self {
+        Self {
+            key,
+            value: None,
+            is_write_initial: None,
+            enumeration_index: None,
+        }
+    }
+}
+
+/// Storage accesses recorded by [`RecordingStorage`].
+#[derive(Debug, Default)]
+pub(super) struct StorageRecords {
+    pub slots: BTreeMap<StorageKey, StorageSlot>,
+    pub factory_deps: BTreeMap<H256, Vec<u8>>,
+}
+
+impl StorageRecords {
+    fn slot(&mut self, key: &StorageKey) -> &mut StorageSlot {
+        self.slots
+            .entry(*key)
+            .or_insert_with(|| StorageSlot::new(*key))
+    }
+}
+
+/// Storage wrapper recording all storage accesses to the wrapped storage. Since the storage is moved
+/// into a VM, records are shared via a pointer.
+#[derive(Debug)]
+pub(super) struct RecordingStorage<S> {
+    inner: S,
+    records: Rc<RefCell<StorageRecords>>,
+}
+
+impl<S: ReadStorage> RecordingStorage<S> {
+    pub fn new(inner: S) -> (Self, Rc<RefCell<StorageRecords>>) {
+        let records = Rc::<RefCell<StorageRecords>>::default();
+        let this = Self {
+            inner,
+            records: records.clone(),
+        };
+        (this, records)
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for RecordingStorage<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        let value = self.inner.read_value(key);
+        self.records.borrow_mut().slot(key).value = Some(value);
+        value
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        let is_write_initial = self.inner.is_write_initial(key);
+        self.records.borrow_mut().slot(key).is_write_initial = Some(is_write_initial);
+        is_write_initial
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        let dep = self.inner.load_factory_dep(hash)?;
+        self.records
+            .borrow_mut()
+            .factory_deps
+            .insert(hash, dep.clone());
+        Some(dep)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        let enumeration_index = self.inner.get_enumeration_index(key);
+        self.records.borrow_mut().slot(key).enumeration_index = enumeration_index;
+        enumeration_index
+    }
+}
+
+/// Data requested by the VM during replay, but missing from the bundle. Non-empty missing data means
+/// that the replayed execution has diverged from the recorded one (or that the bundle is corrupted).
+#[derive(Debug, Default)]
+pub(super) struct MissingData {
+    pub storage_slots: BTreeSet<StorageKey>,
+    pub factory_deps: BTreeSet<H256>,
+}
+
+/// Storage serving data recorded in a replay bundle. Missing data is replaced with defaults
+/// (i.e., as if the slot was never written to) and is reported via [`MissingData`].
+#[derive(Debug)]
+pub(super) struct BundleStorage {
+    slots: HashMap<StorageKey, StorageSlot>,
+    factory_deps: HashMap<H256, Vec<u8>>,
+    missing_data: Rc<RefCell<MissingData>>,
+}
+
+impl BundleStorage {
+    pub fn new(
+        slots: impl IntoIterator<Item = StorageSlot>,
+        factory_deps: impl IntoIterator<Item = (H256, Vec<u8>)>,
+    ) -> (Self, Rc<RefCell<MissingData>>) {
+        let missing_data = Rc::<RefCell<MissingData>>::default();
+        let this = Self {
+            slots: slots.into_iter().map(|slot| (slot.key, slot)).collect(),
+            factory_deps: factory_deps.into_iter().collect(),
+            missing_data: missing_data.clone(),
+        };
+        (this, missing_data)
+    }
+
+    fn get_slot_data<T>(
+        &self,
+        key: &StorageKey,
+        selector: impl FnOnce(&StorageSlot) -> Option<T>,
+    ) -> Option<T> {
+        let data = self.slots.get(key).and_then(selector);
+        if data.is_none() {
+            self.missing_data.borrow_mut().storage_slots.insert(*key);
+        }
+        data
+    }
+}
+
+impl ReadStorage for BundleStorage {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        self.get_slot_data(key, |slot| slot.value)
+            .unwrap_or_default()
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.get_slot_data(key, |slot| slot.is_write_initial)
+            .unwrap_or(true)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        let dep = self.factory_deps.get(&hash).cloned();
+        if dep.is_none() {
+            self.missing_data.borrow_mut().factory_deps.insert(hash);
+        }
+        dep
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        // Enumeration index is legitimately missing for slots that were never written to, so we only
+        // report slots that weren't recorded at all.
+        if let Some(slot) = self.slots.get(key) {
+            slot.enumeration_index
+        } else {
+            self.missing_data.borrow_mut().storage_slots.insert(*key);
+            None
+        }
+    }
+}
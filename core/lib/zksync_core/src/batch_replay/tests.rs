@@ -0,0 +1,312 @@
+//! Tests for batch replay bundles.
+
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use multivm::vm_latest::constants::BLOCK_GAS_LIMIT;
+use zksync_state::{InMemoryStorage, IN_MEMORY_STORAGE_DEFAULT_NETWORK_ID};
+use zksync_system_constants::ZKPORTER_IS_AVAILABLE;
+use zksync_types::{block::MiniblockHasher, AccountTreeId, StorageValue, U256};
+use zksync_utils::u256_to_h256;
+
+use super::*;
+
+fn test_storage_key(byte: u8) -> StorageKey {
+    StorageKey::new(
+        AccountTreeId::new(Address::repeat_byte(byte)),
+        H256::repeat_byte(byte),
+    )
+}
+
+#[test]
+fn recording_and_serving_storage() {
+    let mut storage = InMemoryStorage::default();
+    let written_key = test_storage_key(1);
+    storage.set_value(written_key, H256::repeat_byte(0xff));
+    storage.store_factory_dep(H256::repeat_byte(2), vec![2; 32]);
+    let new_key = test_storage_key(3);
+
+    let (mut recording_storage, records) = RecordingStorage::new(&storage);
+    assert_eq!(
+        recording_storage.read_value(&written_key),
+        H256::repeat_byte(0xff)
+    );
+    assert!(!recording_storage.is_write_initial(&written_key));
+    assert_eq!(
+        recording_storage.get_enumeration_index(&written_key),
+        Some(1)
+    );
+    assert_eq!(recording_storage.read_value(&new_key), StorageValue::zero());
+    assert!(recording_storage.is_write_initial(&new_key));
+    assert!(recording_storage
+        .load_factory_dep(H256::repeat_byte(2))
+        .is_some());
+    assert!(recording_storage
+        .load_factory_dep(H256::repeat_byte(4))
+        .is_none());
+
+    let records = records.take();
+    assert_eq!(records.slots.len(), 2);
+    assert_eq!(
+        records.slots[&written_key],
+        StorageSlot {
+            key: written_key,
+            value: Some(H256::repeat_byte(0xff)),
+            is_write_initial: Some(false),
+            enumeration_index: Some(1),
+        }
+    );
+    assert_eq!(
+        records.slots[&new_key],
+        StorageSlot {
+            key: new_key,
+            value: Some(StorageValue::zero()),
+            is_write_initial: Some(true),
+            enumeration_index: None,
+        }
+    );
+    assert_eq!(records.factory_deps.len(), 1);
+
+    let (mut bundle_storage, missing_data) =
+        BundleStorage::new(records.slots.into_values(), records.factory_deps);
+    assert_eq!(
+        bundle_storage.read_value(&written_key),
+        H256::repeat_byte(0xff)
+    );
+    assert!(!bundle_storage.is_write_initial(&written_key));
+    assert_eq!(bundle_storage.get_enumeration_index(&written_key), Some(1));
+    assert!(bundle_storage.is_write_initial(&new_key));
+    assert_eq!(bundle_storage.get_enumeration_index(&new_key), None);
+    assert_eq!(
+        bundle_storage.load_factory_dep(H256::repeat_byte(2)),
+        Some(vec![2; 32])
+    );
+    assert!(missing_data.borrow().storage_slots.is_empty());
+    assert!(missing_data.borrow().factory_deps.is_empty());
+
+    let unknown_key = test_storage_key(5);
+    assert_eq!(
+        bundle_storage.read_value(&unknown_key),
+        StorageValue::zero()
+    );
+    assert!(bundle_storage
+        .load_factory_dep(H256::repeat_byte(4))
+        .is_none());
+    let missing_data = missing_data.take();
+    assert_eq!(
+        missing_data.storage_slots.into_iter().collect::<Vec<_>>(),
+        [unknown_key]
+    );
+    assert_eq!(
+        missing_data.factory_deps.into_iter().collect::<Vec<_>>(),
+        [H256::repeat_byte(4)]
+    );
+}
+
+fn mock_output() -> BundleOutput {
+    let event = VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: Address::repeat_byte(1),
+        indexed_topics: vec![H256::repeat_byte(2)],
+        value: vec![3; 32],
+    };
+    BundleOutput {
+        tx_outcomes: vec![TxOutcome {
+            hash: H256::repeat_byte(0xff),
+            success: true,
+            gas_refunded: 100,
+        }],
+        events: vec![event],
+        storage_writes: vec![StorageWrite {
+            key: test_storage_key(1),
+            value: H256::repeat_byte(4),
+        }],
+    }
+}
+
+#[test]
+fn diffing_outputs() {
+    let recorded = mock_output();
+    assert_eq!(diff_outputs(&recorded, &mock_output()), []);
+
+    let mut replayed = mock_output();
+    replayed.tx_outcomes[0].success = false;
+    replayed.events.clear();
+    replayed.storage_writes.push(StorageWrite {
+        key: test_storage_key(2),
+        value: H256::repeat_byte(5),
+    });
+    let diffs = diff_outputs(&recorded, &replayed);
+    assert_eq!(
+        diffs,
+        [
+            ReplayDiff::TxOutcome {
+                index: 0,
+                recorded: Some(recorded.tx_outcomes[0]),
+                replayed: Some(replayed.tx_outcomes[0]),
+            },
+            ReplayDiff::EventCount {
+                recorded: 1,
+                replayed: 0,
+            },
+            ReplayDiff::StorageWrite {
+                key: test_storage_key(2),
+                recorded: None,
+                replayed: Some(H256::repeat_byte(5)),
+            },
+        ]
+    );
+
+    let mut replayed = mock_output();
+    replayed.tx_outcomes.push(replayed.tx_outcomes[0]);
+    replayed.events[0].value = vec![];
+    let diffs = diff_outputs(&recorded, &replayed);
+    assert_eq!(diffs.len(), 2);
+    assert_matches!(
+        &diffs[0],
+        ReplayDiff::TxOutcome {
+            index: 1,
+            recorded: None,
+            replayed: Some(_),
+        }
+    );
+    assert_matches!(&diffs[1], ReplayDiff::Event { index: 0, .. });
+}
+
+#[test]
+fn fee_input_params_roundtrip() {
+    let inputs = [
+        BatchFeeInput::l1_pegged(100, 10),
+        BatchFeeInput::pubdata_independent(100, 10, 1_000),
+    ];
+    for input in inputs {
+        let params = FeeInputParams::from(input);
+        let params: FeeInputParams =
+            serde_json::from_value(serde_json::to_value(params).unwrap()).unwrap();
+        assert_eq!(BatchFeeInput::from(params), input);
+    }
+}
+
+fn test_envs() -> (SystemEnv, L1BatchEnv) {
+    let system_env = SystemEnv {
+        zk_porter_available: ZKPORTER_IS_AVAILABLE,
+        version: ProtocolVersionId::latest(),
+        base_system_smart_contracts: BaseSystemContracts::load_from_disk(),
+        gas_limit: BLOCK_GAS_LIMIT,
+        execution_mode: TxExecutionMode::VerifyExecute,
+        default_validation_computational_gas_limit: u32::MAX,
+        chain_id: L2ChainId::from(IN_MEMORY_STORAGE_DEFAULT_NETWORK_ID),
+    };
+    let timestamp = 1_000_000;
+    let l1_batch_env = L1BatchEnv {
+        previous_batch_hash: None,
+        number: L1BatchNumber(1),
+        timestamp,
+        fee_input: BatchFeeInput::l1_pegged(50_000_000_000, 250_000_000),
+        fee_account: Address::repeat_byte(0xfe),
+        enforced_base_fee: None,
+        first_l2_block: L2BlockEnv {
+            number: 1,
+            timestamp,
+            prev_block_hash: MiniblockHasher::legacy_hash(MiniblockNumber(0)),
+            max_virtual_blocks_to_create: 1,
+        },
+    };
+    (system_env, l1_batch_env)
+}
+
+fn create_test_bundle() -> BatchReplayBundle {
+    let (system_env, l1_batch_env) = test_envs();
+    let env = BatchEnvParams::new(&system_env, &l1_batch_env);
+    // A batch with a single fictive miniblock; the batch tip is still executed and writes to the storage.
+    let miniblocks = vec![MiniblockExecutionData {
+        number: MiniblockNumber(1),
+        timestamp: l1_batch_env.timestamp,
+        prev_block_hash: l1_batch_env.first_l2_block.prev_block_hash,
+        virtual_blocks: 1,
+        txs: vec![],
+    }];
+
+    let storage = InMemoryStorage::with_system_contracts(hash_bytecode);
+    let (output, records) =
+        execute_with_recording(&storage, system_env, l1_batch_env, &miniblocks).unwrap();
+    assert!(output.tx_outcomes.is_empty());
+    assert!(!output.storage_writes.is_empty());
+    assert!(!records.slots.is_empty());
+
+    BatchReplayBundle::new(L1BatchNumber(1), env, miniblocks, records, output.into())
+}
+
+#[test]
+fn env_params_roundtrip() {
+    let (system_env, l1_batch_env) = test_envs();
+    let params = BatchEnvParams::new(&system_env, &l1_batch_env);
+    let (restored_system_env, restored_l1_batch_env) =
+        params.to_vm_envs(L1BatchNumber(1), MiniblockNumber(1));
+    assert_eq!(
+        restored_system_env.base_system_smart_contracts,
+        system_env.base_system_smart_contracts
+    );
+    assert_eq!(restored_system_env.version, system_env.version);
+    assert_eq!(restored_system_env.chain_id, system_env.chain_id);
+    assert_eq!(restored_l1_batch_env.number, l1_batch_env.number);
+    assert_eq!(restored_l1_batch_env.fee_input, l1_batch_env.fee_input);
+    assert_eq!(
+        restored_l1_batch_env.first_l2_block.prev_block_hash,
+        l1_batch_env.first_l2_block.prev_block_hash
+    );
+    assert_eq!(
+        BatchEnvParams::new(&restored_system_env, &restored_l1_batch_env),
+        params
+    );
+}
+
+#[test]
+fn replaying_bundle() {
+    let bundle = create_test_bundle();
+    let serialized = serde_json::to_string(&bundle).unwrap();
+    let bundle: BatchReplayBundle = serde_json::from_str(&serialized).unwrap();
+
+    let report = bundle.replay().unwrap();
+    assert!(report.is_consistent(), "{report}");
+    assert_eq!(report.l1_batch_number, L1BatchNumber(1));
+}
+
+#[test]
+fn replaying_bundle_with_diffs() {
+    let mut bundle = create_test_bundle();
+    let modified_write = &mut bundle.recorded_output.storage_writes[0];
+    let key = modified_write.key;
+    let replayed_value = modified_write.value;
+    modified_write.value = u256_to_h256(U256::from(12_345));
+    // Remove a slot from the read set as well.
+    let removed_slot = bundle.storage_slots.pop().unwrap();
+
+    let report = bundle.replay().unwrap();
+    assert!(!report.is_consistent());
+    let storage_write_diffs: HashMap<_, _> = report
+        .diffs
+        .iter()
+        .filter_map(|diff| match diff {
+            ReplayDiff::StorageWrite {
+                key,
+                recorded,
+                replayed,
+            } => Some((*key, (*recorded, *replayed))),
+            _ => None,
+        })
+        .collect();
+    // The removed slot may influence the replayed value, so we only check the recorded one.
+    assert_eq!(
+        storage_write_diffs[&key].0,
+        Some(u256_to_h256(U256::from(12_345)))
+    );
+    if removed_slot.key != key {
+        assert_eq!(storage_write_diffs[&key].1, Some(replayed_value));
+    }
+    assert!(report.missing_storage_slots.contains(&removed_slot.key));
+
+    let report = report.to_string();
+    assert!(report.contains("diverges"), "{report}");
+    assert!(report.contains("missing from the bundle"), "{report}");
+}
@@ -246,6 +246,12 @@ impl BlockReverter {
             .events_dal()
             .rollback_events(last_miniblock_to_keep)
             .await;
+        tracing::info!("rolling back token transfers...");
+        transaction
+            .token_transfers_dal()
+            .rollback_token_transfers(last_miniblock_to_keep)
+            .await
+            .unwrap();
         tracing::info!("rolling back l2 to l1 logs...");
         transaction
             .events_dal()
@@ -0,0 +1,236 @@
+//! Fetching L2 blocks from the peer-to-peer gossip network while the main node API is unavailable.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_concurrency::{ctx, error::Wrap as _, scope, time};
+use zksync_consensus_executor as executor;
+use zksync_consensus_storage::BlockStore;
+use zksync_contracts::zksync_contract;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::{CallFunctionArgs, EthInterface};
+use zksync_l1_contract_interface::i_executor::structures::StoredBatchInfo;
+use zksync_types::{
+    ethabi, web3::contract::tokens::Detokenize, Address, L1BatchNumber, H256, U256,
+};
+
+use super::storage::{CtxStorage, Store};
+use crate::sync_layer::{
+    fetcher::MainNodeFetcher, sync_action::ActionQueueSender, MainNodeClient, SyncState,
+};
+
+/// Fetcher of L2 blocks which uses the main node JSON-RPC API while it's available, and falls back
+/// to fetching blocks from peers over the gossip network if the main node is unavailable for `unavailable_timeout`.
+/// The gossip network is used for fetching blocks until the main node becomes available again.
+///
+/// Blocks received from peers are justified by certificates signed by consensus validators. Additionally,
+/// L1 batches containing such blocks are verified against the batch hashes committed on L1; the fetcher
+/// returns an error if a batch doesn't match the committed hash.
+///
+/// While blocks are fetched from the main node, the node serves them (together with certificates) to its peers.
+/// As with [`FetcherConfig`](super::FetcherConfig), the node storage must contain consensus certificates.
+#[derive(Debug)]
+pub struct FallbackFetcher {
+    pub executor: executor::Config,
+    pub main_node_client: Box<dyn MainNodeClient>,
+    pub l1_client: Box<dyn EthInterface>,
+    pub diamond_proxy_addr: Address,
+    pub sync_state: SyncState,
+    pub unavailable_timeout: Duration,
+}
+
+impl FallbackFetcher {
+    const MAIN_NODE_POLL_INTERVAL: time::Duration = time::Duration::seconds(5);
+
+    pub async fn run(
+        self,
+        ctx: &ctx::Ctx,
+        pool: ConnectionPool,
+        actions: ActionQueueSender,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let (first_peer_batch_sender, first_peer_batch) = watch::channel(None);
+        scope::run!(ctx, |ctx, s| async {
+            let block_store = Store::new(pool.clone()).into_block_store();
+            let cursor_slot = block_store.cursor_slot();
+            let (block_store, runner) = BlockStore::new(ctx, Box::new(block_store))
+                .await
+                .wrap("BlockStore::new()")?;
+            s.spawn_bg(runner.run(ctx));
+            let executor = executor::Executor {
+                config: self.executor,
+                block_store,
+                validator: None,
+            };
+            s.spawn_bg(executor.run(ctx));
+            let verifier =
+                L1BatchVerifier::new(pool.clone(), self.l1_client, self.diamond_proxy_addr);
+            s.spawn_bg(verifier.run(ctx, first_peer_batch));
+
+            let mut storage = CtxStorage::access(ctx, &pool).await.wrap("access()")?;
+            let cursor = storage
+                .new_fetcher_cursor(ctx)
+                .await
+                .wrap("new_fetcher_cursor()")?;
+            drop(storage);
+            let mut fetcher = MainNodeFetcher::from_parts(
+                self.main_node_client,
+                cursor,
+                actions,
+                self.sync_state.clone(),
+                stop_receiver.clone(),
+            );
+            loop {
+                let unavailable = ctx
+                    .wait(fetcher.run_until_unavailable(self.unavailable_timeout))
+                    .await??;
+                if !unavailable {
+                    return Ok(());
+                }
+
+                let (client, cursor, actions) = fetcher.into_parts();
+                tracing::warn!(
+                    "Switching to fetching L2 blocks from peers, starting from miniblock {}",
+                    cursor.next_miniblock
+                );
+                first_peer_batch_sender.send_if_modified(|batch| {
+                    let is_first_switch = batch.is_none();
+                    batch.get_or_insert(cursor.l1_batch);
+                    is_first_switch
+                });
+                cursor_slot.set(ctx, cursor, actions).await?;
+
+                Self::wait_for_main_node(ctx, client.as_ref()).await?;
+                let (cursor, actions) = cursor_slot
+                    .take(ctx)
+                    .await?
+                    .context("cursor disappeared from the block store")?;
+                tracing::info!(
+                    "Main node is available again; switching to fetching L2 blocks from it, starting from miniblock {}",
+                    cursor.next_miniblock
+                );
+                fetcher = MainNodeFetcher::from_parts(
+                    client,
+                    cursor,
+                    actions,
+                    self.sync_state.clone(),
+                    stop_receiver.clone(),
+                );
+            }
+        })
+        .await
+    }
+
+    async fn wait_for_main_node(
+        ctx: &ctx::Ctx,
+        client: &dyn MainNodeClient,
+    ) -> ctx::OrCanceled<()> {
+        loop {
+            ctx.sleep(Self::MAIN_NODE_POLL_INTERVAL).await?;
+            if ctx.wait(client.fetch_l2_block_number()).await?.is_ok() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Verifies local L1 batches against batch hashes committed on L1.
+#[derive(Debug)]
+struct L1BatchVerifier {
+    pool: ConnectionPool,
+    l1_client: Box<dyn EthInterface>,
+    diamond_proxy_addr: Address,
+    contract: ethabi::Contract,
+}
+
+impl L1BatchVerifier {
+    const POLL_INTERVAL: time::Duration = time::Duration::seconds(10);
+
+    fn new(
+        pool: ConnectionPool,
+        l1_client: Box<dyn EthInterface>,
+        diamond_proxy_addr: Address,
+    ) -> Self {
+        Self {
+            pool,
+            l1_client,
+            diamond_proxy_addr,
+            contract: zksync_contract(),
+        }
+    }
+
+    /// Verifies all L1 batches starting from the batch received via `first_batch`. Each batch is verified
+    /// once its metadata is computed locally and it is committed on L1. Returns an error if a batch doesn't match
+    /// the hash committed on L1.
+    async fn run(
+        self,
+        ctx: &ctx::Ctx,
+        mut first_batch: watch::Receiver<Option<L1BatchNumber>>,
+    ) -> anyhow::Result<()> {
+        let first_batch = ctx
+            .wait(first_batch.wait_for(Option::is_some))
+            .await?
+            .map(|batch| *batch);
+        let Ok(Some(mut number)) = first_batch else {
+            return Ok(()); // The fetcher has stopped without falling back to peers.
+        };
+
+        loop {
+            let Some(local_hash) = self.local_batch_hash(ctx, number).await? else {
+                ctx.sleep(Self::POLL_INTERVAL).await?;
+                continue;
+            };
+            let l1_hash = match self.l1_batch_hash(ctx, number).await? {
+                Ok(hash) => hash,
+                Err(err) => {
+                    tracing::warn!("Failed getting hash for L1 batch #{number} from L1: {err}");
+                    ctx.sleep(Self::POLL_INTERVAL).await?;
+                    continue;
+                }
+            };
+            if l1_hash == H256::zero() {
+                // The batch is not committed yet.
+                ctx.sleep(Self::POLL_INTERVAL).await?;
+                continue;
+            }
+            anyhow::ensure!(
+                l1_hash == local_hash,
+                "L1 batch #{number} doesn't match the batch committed on L1: \
+                 local hash is {local_hash:?}, committed hash is {l1_hash:?}"
+            );
+            tracing::info!("L1 batch #{number} matches the batch committed on L1");
+            number += 1;
+        }
+    }
+
+    /// Returns the hash of the `StoredBatchInfo` for the batch, or `None` if the batch metadata is not computed yet.
+    async fn local_batch_hash(
+        &self,
+        ctx: &ctx::Ctx,
+        number: L1BatchNumber,
+    ) -> ctx::Result<Option<H256>> {
+        let mut storage = CtxStorage::access(ctx, &self.pool).await.wrap("access()")?;
+        let batch = storage
+            .l1_batch_metadata(ctx, number)
+            .await
+            .wrap("l1_batch_metadata()")?;
+        Ok(batch.map(|batch| StoredBatchInfo(&batch).hash()))
+    }
+
+    /// Returns the hash stored on L1 for the batch; the hash is zero if the batch is not committed.
+    async fn l1_batch_hash(
+        &self,
+        ctx: &ctx::Ctx,
+        number: L1BatchNumber,
+    ) -> ctx::OrCanceled<anyhow::Result<H256>> {
+        let args = CallFunctionArgs::new("storedBatchHash", (U256::from(number.0),))
+            .for_contract(self.diamond_proxy_addr, self.contract.clone());
+        let tokens = ctx
+            .wait(self.l1_client.call_contract_function(args))
+            .await?;
+        Ok(tokens
+            .map_err(anyhow::Error::from)
+            .and_then(|tokens| H256::from_tokens(tokens).map_err(anyhow::Error::from)))
+    }
+}
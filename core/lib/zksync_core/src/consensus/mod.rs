@@ -8,16 +8,19 @@ use zksync_consensus_roles::validator;
 use zksync_consensus_storage::BlockStore;
 use zksync_dal::ConnectionPool;
 
+pub use self::fallback::FallbackFetcher;
 use self::storage::Store;
 use crate::sync_layer::{sync_action::ActionQueueSender, MainNodeClient, SyncState};
 
 pub mod config;
+mod fallback;
 pub mod proto;
 mod storage;
 #[cfg(test)]
 pub(crate) mod testonly;
 #[cfg(test)]
 mod tests;
+pub mod validator_key;
 
 /// Main node consensus config.
 #[derive(Debug, Clone)]
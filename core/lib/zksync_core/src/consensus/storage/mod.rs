@@ -1,12 +1,14 @@
 //! Storage implementation based on DAL.
 
+use std::sync::Arc;
+
 use anyhow::Context as _;
 use zksync_concurrency::{ctx, error::Wrap as _, sync, time};
 use zksync_consensus_bft::PayloadManager;
 use zksync_consensus_roles::validator;
 use zksync_consensus_storage::{BlockStoreState, PersistentBlockStore, ReplicaState, ReplicaStore};
 use zksync_dal::{consensus_dal::Payload, ConnectionPool};
-use zksync_types::MiniblockNumber;
+use zksync_types::{commitment::L1BatchWithMetadata, L1BatchNumber, MiniblockNumber};
 
 #[cfg(test)]
 mod testonly;
@@ -127,6 +129,17 @@ impl<'a> CtxStorage<'a> {
             .context("sqlx")?)
     }
 
+    /// Wrapper for `blocks_dal().get_l1_batch_metadata()`.
+    pub async fn l1_batch_metadata(
+        &mut self,
+        ctx: &ctx::Ctx,
+        number: L1BatchNumber,
+    ) -> ctx::Result<Option<L1BatchWithMetadata>> {
+        Ok(ctx
+            .wait(self.0.blocks_dal().get_l1_batch_metadata(number))
+            .await??)
+    }
+
     /// Wrapper for `FetcherCursor::new()`.
     pub async fn new_fetcher_cursor(&mut self, ctx: &ctx::Ctx) -> ctx::Result<IoCursor> {
         Ok(ctx.wait(IoCursor::for_fetcher(&mut self.0)).await??)
@@ -185,12 +198,49 @@ pub(super) struct Store {
     pool: ConnectionPool,
 }
 
+/// Shared slot for the cursor of a `BlockStore`. Allows handing the responsibility for pushing actions
+/// to the actions queue over to another fetcher and back while the `BlockStore` is running.
+#[derive(Debug, Clone, Default)]
+pub(super) struct CursorSlot(Arc<sync::Mutex<Option<Cursor>>>);
+
+impl CursorSlot {
+    /// Sets the cursor and the actions queue; subsequently stored blocks will be translated into actions.
+    pub async fn set(
+        &self,
+        ctx: &ctx::Ctx,
+        inner: IoCursor,
+        actions: ActionQueueSender,
+    ) -> ctx::OrCanceled<()> {
+        *sync::lock(ctx, &*self.0).await? = Some(Cursor { inner, actions });
+        Ok(())
+    }
+
+    /// Takes the cursor and the actions queue out of the slot, so that stored blocks are no longer translated
+    /// into actions.
+    pub async fn take(
+        &self,
+        ctx: &ctx::Ctx,
+    ) -> ctx::OrCanceled<Option<(IoCursor, ActionQueueSender)>> {
+        let cursor = sync::lock(ctx, &*self.0).await?.take();
+        Ok(cursor.map(|cursor| (cursor.inner, cursor.actions)))
+    }
+
+    /// Advances the cursor with the specified block if the cursor is set.
+    async fn advance(&self, ctx: &ctx::Ctx, block: &validator::FinalBlock) -> ctx::Result<()> {
+        if let Some(cursor) = &mut *sync::lock(ctx, &*self.0).await? {
+            cursor.advance(block).await?;
+        }
+        Ok(())
+    }
+}
+
 /// Wrapper of `ConnectionPool` implementing `PersistentBlockStore`.
 #[derive(Debug)]
 pub(super) struct BlockStore {
     inner: Store,
     /// Mutex preventing concurrent execution of `store_next_block` calls.
-    store_next_block_mutex: sync::Mutex<Option<Cursor>>,
+    store_next_block_mutex: sync::Mutex<()>,
+    cursor: CursorSlot,
 }
 
 impl Store {
@@ -203,7 +253,8 @@ impl Store {
     pub fn into_block_store(self) -> BlockStore {
         BlockStore {
             inner: self,
-            store_next_block_mutex: sync::Mutex::new(None),
+            store_next_block_mutex: sync::Mutex::new(()),
+            cursor: CursorSlot::default(),
         }
     }
 }
@@ -270,8 +321,13 @@ impl BlockStore {
             .new_fetcher_cursor(ctx)
             .await
             .wrap("new_fetcher_cursor()")?;
-        *sync::lock(ctx, &self.store_next_block_mutex).await? = Some(Cursor { inner, actions });
-        Ok(())
+        Ok(self.cursor.set(ctx, inner, actions).await?)
+    }
+
+    /// Returns the cursor slot of this store, which can be used to set the actions queue
+    /// after the store was moved into a `zksync_consensus_storage::BlockStore`.
+    pub fn cursor_slot(&self) -> CursorSlot {
+        self.cursor.clone()
     }
 }
 
@@ -319,7 +375,8 @@ impl PersistentBlockStore for BlockStore {
     }
 
     /// If actions queue is set (and the block has not been stored yet),
-    /// the block will be translated into a sequence of actions.
+    /// the block will be translated into a sequence of actions. The actions queue can be set
+    /// while the call is waiting for the miniblock.
     /// The received actions should be fed
     /// to `ExternalIO`, so that `StateKeeper` will store the corresponding miniblock in the db.
     ///
@@ -332,12 +389,15 @@ impl PersistentBlockStore for BlockStore {
         block: &validator::FinalBlock,
     ) -> ctx::Result<()> {
         // This mutex prevents concurrent `store_next_block` calls.
-        let mut guard = ctx.wait(self.store_next_block_mutex.lock()).await?;
-        if let Some(cursor) = &mut *guard {
-            cursor.advance(block).await.context("cursor.advance()")?;
-        }
+        let _guard = ctx.wait(self.store_next_block_mutex.lock()).await?;
         const POLL_INTERVAL: time::Duration = time::Duration::milliseconds(50);
         loop {
+            // The cursor may be set while we're waiting for the miniblock to be stored by another fetcher,
+            // so we check it on each iteration. Advancing the cursor is a no-op if the block was already processed.
+            self.cursor
+                .advance(ctx, block)
+                .await
+                .wrap("cursor.advance()")?;
             let mut storage = CtxStorage::access(ctx, &self.inner.pool)
                 .await
                 .wrap("access()")?;
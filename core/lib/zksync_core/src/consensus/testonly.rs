@@ -14,7 +14,10 @@ use zksync_types::{
     api, block::MiniblockHasher, snapshots::SnapshotRecoveryStatus, Address, L1BatchNumber,
     L2ChainId, MiniblockNumber, ProtocolVersionId, H256,
 };
-use zksync_web3_decl::error::{EnrichedClientError, EnrichedClientResult};
+use zksync_web3_decl::{
+    error::{EnrichedClientError, EnrichedClientResult},
+    jsonrpsee::core::ClientError,
+};
 
 use crate::{
     consensus::{
@@ -219,6 +222,59 @@ impl MainNodeClient for MockMainNodeClient {
     }
 }
 
+/// Main node client which fails all requests with a transient error, emulating an unavailable main node.
+#[derive(Debug)]
+pub(crate) struct UnavailableMainNodeClient;
+
+impl UnavailableMainNodeClient {
+    fn error<T>(method: &'static str) -> EnrichedClientResult<T> {
+        Err(EnrichedClientError::new(
+            ClientError::RequestTimeout,
+            method,
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl MainNodeClient for UnavailableMainNodeClient {
+    async fn fetch_system_contract_by_hash(
+        &self,
+        _hash: H256,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        Self::error("fetch_system_contract_by_hash")
+    }
+
+    async fn fetch_genesis_contract_bytecode(
+        &self,
+        _address: Address,
+    ) -> EnrichedClientResult<Option<Vec<u8>>> {
+        Self::error("fetch_genesis_contract_bytecode")
+    }
+
+    async fn fetch_protocol_version(
+        &self,
+        _protocol_version: ProtocolVersionId,
+    ) -> EnrichedClientResult<Option<api::ProtocolVersion>> {
+        Self::error("fetch_protocol_version")
+    }
+
+    async fn fetch_genesis_l1_batch_hash(&self) -> EnrichedClientResult<H256> {
+        Self::error("fetch_genesis_l1_batch_hash")
+    }
+
+    async fn fetch_l2_block_number(&self) -> EnrichedClientResult<MiniblockNumber> {
+        Self::error("fetch_l2_block_number")
+    }
+
+    async fn fetch_l2_block(
+        &self,
+        _number: MiniblockNumber,
+        _with_transactions: bool,
+    ) -> EnrichedClientResult<Option<api::en::SyncBlock>> {
+        Self::error("fetch_l2_block")
+    }
+}
+
 /// Fake StateKeeper for tests.
 pub(super) struct StateKeeper {
     // Batch of the `last_block`.
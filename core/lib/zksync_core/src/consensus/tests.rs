@@ -1,6 +1,7 @@
 use std::ops::Range;
 
 use anyhow::Context as _;
+use tokio::sync::watch;
 use tracing::Instrument as _;
 use zksync_concurrency::{ctx, scope};
 use zksync_consensus_executor::testonly::{connect_full_node, ValidatorNode};
@@ -8,7 +9,9 @@ use zksync_consensus_storage as storage;
 use zksync_consensus_storage::PersistentBlockStore as _;
 use zksync_consensus_utils::no_copy::NoCopy;
 use zksync_dal::{connection::TestTemplate, ConnectionPool};
+use zksync_eth_client::clients::MockEthereum;
 use zksync_protobuf::testonly::test_encode_random;
+use zksync_types::Address;
 
 use super::*;
 use crate::consensus::storage::CtxStorage;
@@ -247,6 +250,76 @@ async fn test_fetcher() {
     .unwrap();
 }
 
+// Test fallback fetcher switching to fetching blocks from peers when the main node is unavailable.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fallback_fetcher() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::AffineClock::new(10.));
+    let rng = &mut ctx.rng();
+
+    let cfg = ValidatorNode::new(rng);
+    let validators = cfg.node.validators.clone();
+    let mut cfg = MainNodeConfig {
+        executor: cfg.node,
+        validator: cfg.validator,
+    };
+    let fetcher_cfg = connect_full_node(rng, &mut cfg.executor);
+
+    // Create an initial database snapshot, which contains a cert for genesis block.
+    let pool = scope::run!(ctx, |ctx, s| async {
+        let pool = ConnectionPool::test_pool().await;
+        let (mut sk, runner) = testonly::StateKeeper::new(pool).await?;
+        s.spawn_bg(runner.run(ctx));
+        s.spawn_bg(cfg.clone().run(ctx, sk.pool.clone()));
+        sk.push_random_blocks(rng, 5).await;
+        sk.store()
+            .wait_for_certificate(ctx, sk.last_block())
+            .await?;
+        Ok(sk.pool)
+    })
+    .await
+    .unwrap();
+    let template = TestTemplate::freeze(pool).await.unwrap();
+
+    scope::run!(ctx, |ctx, s| async {
+        let pool = template.create_db(4).await?.build().await?;
+        let (mut validator, runner) = testonly::StateKeeper::new(pool).await?;
+        s.spawn_bg(runner.run(ctx));
+        s.spawn_bg(cfg.run(ctx, validator.pool.clone()));
+
+        let pool = template.create_db(4).await?.build().await?;
+        let (fetcher, runner) = testonly::StateKeeper::new(pool).await?;
+        let fetcher_store = fetcher.store();
+        s.spawn_bg(runner.run(ctx));
+        let fallback_fetcher = FallbackFetcher {
+            executor: fetcher_cfg,
+            main_node_client: Box::new(testonly::UnavailableMainNodeClient),
+            l1_client: Box::<MockEthereum>::default(),
+            diamond_proxy_addr: Address::repeat_byte(1),
+            sync_state: SyncState::default(),
+            unavailable_timeout: std::time::Duration::ZERO,
+        };
+        let (_stop_sender, stop_receiver) = watch::channel(false);
+        s.spawn_bg(fallback_fetcher.run(ctx, fetcher.pool, fetcher.actions_sender, stop_receiver));
+
+        validator.push_random_blocks(rng, 5).await;
+        let want_last = validator.last_block();
+        let want = validator
+            .store()
+            .wait_for_blocks_and_verify(ctx, &validators, want_last)
+            .await?;
+        assert_eq!(
+            want,
+            fetcher_store
+                .wait_for_blocks_and_verify(ctx, &validators, want_last)
+                .await?
+        );
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
 // Test fetcher back filling missing certs.
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fetcher_backfill_certs() {
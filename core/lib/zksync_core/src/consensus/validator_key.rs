@@ -0,0 +1,86 @@
+//! Pluggable sources of the consensus validator key.
+//!
+//! The consensus executor signs messages locally, so all sources produce the secret key itself rather than
+//! signatures.
+
+use std::{fmt, fs, path::PathBuf};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_consensus_crypto::Text;
+use zksync_consensus_roles::validator;
+
+use super::config::read_secret;
+
+/// Source of the consensus validator secret key.
+#[async_trait]
+pub trait ValidatorKeySource: fmt::Debug + Send + Sync {
+    /// Loads the current validator key. Returned errors must not contain the key.
+    async fn validator_key(&self) -> anyhow::Result<validator::SecretKey>;
+}
+
+/// Decodes a validator key making sure that the error message doesn't contain the key.
+fn decode_key(raw: &str, source: &str) -> anyhow::Result<validator::SecretKey> {
+    Text::new(raw.trim())
+        .decode()
+        .map_err(|_| anyhow::anyhow!("validator key from {source} has invalid format"))
+}
+
+/// Reads the validator key from an env var.
+#[derive(Debug)]
+pub struct EnvValidatorKey {
+    pub var_name: String,
+}
+
+#[async_trait]
+impl ValidatorKeySource for EnvValidatorKey {
+    async fn validator_key(&self) -> anyhow::Result<validator::SecretKey> {
+        read_secret(&self.var_name)
+    }
+}
+
+/// Reads the validator key from a file, so that the key doesn't need to be present in the node environment.
+#[derive(Debug)]
+pub struct FileValidatorKey {
+    pub path: PathBuf,
+}
+
+#[async_trait]
+impl ValidatorKeySource for FileValidatorKey {
+    async fn validator_key(&self) -> anyhow::Result<validator::SecretKey> {
+        let raw = fs::read_to_string(&self.path)
+            .with_context(|| format!("failed reading validator key from {:?}", self.path))?;
+        decode_key(&raw, &format!("{:?}", self.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+    use tempfile::TempDir;
+    use zksync_consensus_crypto::TextFmt as _;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn loading_validator_key_from_file() {
+        let rng = &mut rand::thread_rng();
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("validator_key");
+        let source = FileValidatorKey { path: path.clone() };
+
+        let err = source.validator_key().await.unwrap_err();
+        assert!(format!("{err:#}").contains("failed reading"), "{err:#}");
+
+        let key: validator::SecretKey = rng.gen();
+        fs::write(&path, format!("{}\n", key.encode())).unwrap();
+        let loaded_key = source.validator_key().await.unwrap();
+        assert_eq!(loaded_key.public(), key.public());
+
+        let invalid_key = key.encode().replace("secret", "public");
+        fs::write(&path, &invalid_key).unwrap();
+        let err = source.validator_key().await.unwrap_err().to_string();
+        assert!(err.contains("invalid format"), "{err}");
+        assert!(!err.contains(&invalid_key), "{err}");
+    }
+}
@@ -1,6 +1,7 @@
 use std::{fmt, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
+use async_trait::async_trait;
 use serde::Serialize;
 use tokio::sync::watch;
 use zksync_contracts::PRE_BOOJUM_COMMIT_FUNCTION;
@@ -12,6 +13,11 @@ use zksync_l1_contract_interface::{
     Tokenizable,
 };
 use zksync_types::{pubdata_da::PubdataDA, web3::ethabi, L1BatchNumber, H256};
+use zksync_web3_decl::{
+    error::{ClientRpcContext, EnrichedClientError, EnrichedClientResult},
+    jsonrpsee::http_client::HttpClient,
+    namespaces::ZksNamespaceClient,
+};
 
 use crate::{
     metrics::{CheckerComponent, EN_METRICS},
@@ -25,16 +31,46 @@ mod tests;
 enum CheckError {
     #[error("Web3 error communicating with L1")]
     Web3(#[from] L1ClientError),
+    #[error("Error communicating with the main node")]
+    MainNode(#[from] EnrichedClientError),
     #[error("Internal error")]
     Internal(#[from] anyhow::Error),
 }
 
+impl CheckError {
+    fn is_transient(&self) -> bool {
+        matches!(self, Self::Web3(_) | Self::MainNode(_))
+    }
+}
+
 impl From<zksync_dal::SqlxError> for CheckError {
     fn from(err: zksync_dal::SqlxError) -> Self {
         Self::Internal(err.into())
     }
 }
 
+/// Main node data used to check whether resyncing the node would fix an L1 batch inconsistency.
+#[async_trait]
+trait MainNodeClient: fmt::Debug + Send + Sync {
+    async fn l1_batch_root_hash(&self, number: L1BatchNumber)
+        -> EnrichedClientResult<Option<H256>>;
+}
+
+#[async_trait]
+impl MainNodeClient for HttpClient {
+    async fn l1_batch_root_hash(
+        &self,
+        number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<H256>> {
+        Ok(self
+            .get_l1_batch_details(number)
+            .rpc_context("l1_batch_root_hash")
+            .with_arg("number", &number)
+            .await?
+            .and_then(|batch| batch.base.root_hash))
+    }
+}
+
 /// Handler of life cycle events emitted by [`ConsistencyChecker`].
 trait HandleConsistencyCheckerEvent: fmt::Debug + Send + Sync {
     fn initialize(&mut self);
@@ -44,6 +80,8 @@ trait HandleConsistencyCheckerEvent: fmt::Debug + Send + Sync {
     fn update_checked_batch(&mut self, last_checked_batch: L1BatchNumber);
 
     fn report_inconsistent_batch(&mut self, number: L1BatchNumber);
+
+    fn report_pending_resync(&mut self, last_correct_batch: L1BatchNumber);
 }
 
 /// Health details reported by [`ConsistencyChecker`].
@@ -55,6 +93,9 @@ struct ConsistencyCheckerDetails {
     last_checked_batch: Option<L1BatchNumber>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     inconsistent_batches: Vec<L1BatchNumber>,
+    /// Last correct L1 batch the node should be reverted to; awaits operator confirmation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pending_resync_target: Option<L1BatchNumber>,
 }
 
 impl ConsistencyCheckerDetails {
@@ -109,6 +150,28 @@ impl HandleConsistencyCheckerEvent for ConsistencyCheckerHealthUpdater {
         self.current_details.inconsistent_batches.push(number);
         self.inner.update(self.current_details.health());
     }
+
+    fn report_pending_resync(&mut self, last_correct_batch: L1BatchNumber) {
+        // Only the earliest target is relevant; the revert to it will cover all subsequent inconsistencies.
+        if self.current_details.pending_resync_target.is_none() {
+            self.current_details.pending_resync_target = Some(last_correct_batch);
+            self.inner.update(self.current_details.health());
+        }
+    }
+}
+
+/// Policy for resyncing the node after the consistency checker detects an L1 batch inconsistent with L1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResyncPolicy {
+    /// Maximum number of L1 batches that can be reverted during a resync. If the resync requires reverting
+    /// more batches, it is not performed; the inconsistency is only logged and reported via health check.
+    pub max_batches_to_revert: u32,
+    /// If set, the resync is not performed automatically. Instead, the revert target is logged and reported
+    /// via health check, so that the node operator can trigger the revert manually.
+    pub requires_confirmation: bool,
+    /// Maximum number of automatic resyncs caused by the same inconsistent L1 batch. Guards against resync loops
+    /// if the data re-fetched after a revert is still inconsistent with L1.
+    pub max_attempts: u32,
 }
 
 /// Consistency checker behavior when L1 commit data divergence is detected.
@@ -120,6 +183,8 @@ enum L1DataMismatchBehavior {
     #[cfg(test)]
     Bail,
     Log,
+    /// Revert the node to the last L1 batch consistent with L1, so that correct data is re-fetched.
+    Resync(ResyncPolicy),
 }
 
 /// L1 commit data loaded from Postgres.
@@ -215,6 +280,8 @@ pub struct ConsistencyChecker {
     pool: ConnectionPool,
     health_check: ReactiveHealthCheck,
     kzg_settings: Option<Arc<KzgSettings>>,
+    /// Client used to check main node data before resyncing. Set together with the resync policy.
+    main_node_client: Option<Box<dyn MainNodeClient>>,
 }
 
 impl ConsistencyChecker {
@@ -238,9 +305,30 @@ impl ConsistencyChecker {
             pool,
             health_check,
             kzg_settings,
+            main_node_client: None,
         }
     }
 
+    /// Enables resyncing the node on L1 data mismatch according to the provided policy. If the checker
+    /// decides that a resync should be performed, it stops and returns the last correct L1 batch from [`Self::run()`].
+    ///
+    /// Before resyncing, the checker verifies that the state root hash reported by the main node for the inconsistent
+    /// L1 batch matches the one committed on L1, so that the data re-fetched from the main node after the revert
+    /// is expected to be consistent.
+    pub fn with_resync_policy(self, policy: ResyncPolicy, main_node_client: HttpClient) -> Self {
+        self.with_resync_policy_inner(policy, Box::new(main_node_client))
+    }
+
+    fn with_resync_policy_inner(
+        mut self,
+        policy: ResyncPolicy,
+        main_node_client: Box<dyn MainNodeClient>,
+    ) -> Self {
+        self.l1_data_mismatch_behavior = L1DataMismatchBehavior::Resync(policy);
+        self.main_node_client = Some(main_node_client);
+        self
+    }
+
     /// Returns health check associated with this checker.
     pub fn health_check(&self) -> &ReactiveHealthCheck {
         &self.health_check
@@ -251,6 +339,16 @@ impl ConsistencyChecker {
         batch_number: L1BatchNumber,
         local: &LocalL1BatchCommitData,
     ) -> Result<bool, CheckError> {
+        let commitment = self.fetch_l1_commitment(batch_number, local).await?;
+        Ok(local.l1_commit_data_variants.contains(&commitment))
+    }
+
+    /// Fetches the commitment for the specified L1 batch from its commit transaction on L1.
+    async fn fetch_l1_commitment(
+        &self,
+        batch_number: L1BatchNumber,
+        local: &LocalL1BatchCommitData,
+    ) -> Result<ethabi::Token, CheckError> {
         let commit_tx_hash = local.commit_tx_hash;
         tracing::info!("Checking commit tx {commit_tx_hash} for L1 batch #{batch_number}");
 
@@ -285,8 +383,65 @@ impl ConsistencyChecker {
                 .with_context(|| {
                     format!("Failed extracting commit data for transaction {commit_tx_hash:?}")
                 })?;
+        Ok(commitment)
+    }
 
-        Ok(local.l1_commit_data_variants.contains(&commitment))
+    /// Extracts the new state root hash from the L1 batch commitment. The root hash has the same position
+    /// in pre-Boojum and post-Boojum commitments.
+    fn extract_state_root_hash(commitment: &ethabi::Token) -> anyhow::Result<H256> {
+        const STATE_ROOT_HASH_POSITION: usize = 3;
+
+        let ethabi::Token::Tuple(fields) = commitment else {
+            anyhow::bail!("Unexpected L1 batch commitment format");
+        };
+        let root_hash = fields
+            .get(STATE_ROOT_HASH_POSITION)
+            .cloned()
+            .and_then(ethabi::Token::into_fixed_bytes)
+            .filter(|bytes| bytes.len() == 32)
+            .context("Unexpected L1 batch commitment format")?;
+        Ok(H256::from_slice(&root_hash))
+    }
+
+    /// Checks whether resyncing would fix the inconsistency of the specified L1 batch, i.e., whether the main node
+    /// has data consistent with L1 to re-fetch and the resync isn't repeated too many times.
+    /// The commitment is re-fetched from L1 for the check.
+    async fn can_resync(
+        &self,
+        policy: ResyncPolicy,
+        batch_number: L1BatchNumber,
+        local: &LocalL1BatchCommitData,
+    ) -> Result<bool, CheckError> {
+        let attempts = self
+            .pool
+            .access_storage()
+            .await?
+            .consistency_checker_dal()
+            .get_resync_attempts(batch_number)
+            .await?;
+        if attempts >= policy.max_attempts {
+            tracing::error!(
+                "Cannot resync the node: {attempts} resync(s) were already performed because of L1 batch \
+                 #{batch_number}, but it's still inconsistent with L1"
+            );
+            return Ok(false);
+        }
+
+        let commitment = self.fetch_l1_commitment(batch_number, local).await?;
+        let l1_root_hash = Self::extract_state_root_hash(&commitment)?;
+        let main_node_client = self
+            .main_node_client
+            .as_deref()
+            .context("main node client is not set")?;
+        let main_node_root_hash = main_node_client.l1_batch_root_hash(batch_number).await?;
+        if main_node_root_hash != Some(l1_root_hash) {
+            tracing::error!(
+                "Cannot resync the node: state root hash for L1 batch #{batch_number} reported by the main node \
+                 ({main_node_root_hash:?}) differs from the one committed on L1 ({l1_root_hash:?})"
+            );
+            return Ok(false);
+        }
+        Ok(true)
     }
 
     fn extract_commit_data(
@@ -346,7 +501,49 @@ impl ConsistencyChecker {
             .await?)
     }
 
-    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+    /// Determines whether the node should be resynced after detecting an inconsistent L1 batch. The batch is
+    /// the first diverging one, since the checker processes batches sequentially. Returns the last correct
+    /// L1 batch to revert to.
+    async fn resync_target(
+        &self,
+        policy: ResyncPolicy,
+        earliest_l1_batch_number: L1BatchNumber,
+        inconsistent_batch: L1BatchNumber,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        let last_correct_batch = inconsistent_batch - 1;
+        if last_correct_batch < earliest_l1_batch_number {
+            tracing::error!(
+                "Cannot resync the node: L1 batch #{inconsistent_batch} is the earliest L1 batch stored by the node"
+            );
+            return Ok(None);
+        }
+
+        let sealed_l1_batch_number = self
+            .pool
+            .access_storage()
+            .await?
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .context("no L1 batches in Postgres")?;
+        let batches_to_revert = sealed_l1_batch_number.0 - last_correct_batch.0;
+        if batches_to_revert > policy.max_batches_to_revert {
+            tracing::error!(
+                "Cannot resync the node: reverting to L1 batch #{last_correct_batch} requires reverting \
+                 {batches_to_revert} L1 batches, which exceeds the limit ({})",
+                policy.max_batches_to_revert
+            );
+            return Ok(None);
+        }
+        Ok(Some(last_correct_batch))
+    }
+
+    /// Runs the checker until a stop signal is received. If the checker is configured to resync the node
+    /// and decides that resync is necessary, returns the last correct L1 batch the node should be reverted to.
+    pub async fn run(
+        mut self,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
         self.event_handler.initialize();
 
         // It doesn't make sense to start the checker until we have at least one L1 batch with metadata.
@@ -355,7 +552,7 @@ impl ConsistencyChecker {
                 .await?;
 
         let Some(earliest_l1_batch_number) = earliest_l1_batch_number else {
-            return Ok(()); // Stop signal received
+            return Ok(None); // Stop signal received
         };
 
         let last_committed_batch = self
@@ -409,22 +606,68 @@ impl ConsistencyChecker {
                         L1DataMismatchBehavior::Bail => {
                             anyhow::bail!("L1 batch #{batch_number} is inconsistent with L1");
                         }
-                        L1DataMismatchBehavior::Log => {
-                            batch_number += 1; // We don't want to infinitely loop failing the check on the same batch
+                        L1DataMismatchBehavior::Log => {}
+                        &L1DataMismatchBehavior::Resync(policy) => {
+                            let resync_target = self
+                                .resync_target(policy, earliest_l1_batch_number, batch_number)
+                                .await?;
+                            let can_resync = if resync_target.is_some() {
+                                match self.can_resync(policy, batch_number, &local).await {
+                                    Ok(can_resync) => can_resync,
+                                    Err(err) if err.is_transient() => {
+                                        tracing::warn!(
+                                            "Failed checking whether the node can be resynced; will retry \
+                                             after a delay: {:#}",
+                                            anyhow::Error::from(err)
+                                        );
+                                        tokio::time::sleep(self.sleep_interval).await;
+                                        continue;
+                                    }
+                                    Err(err) => {
+                                        let context = format!(
+                                            "Failed checking whether the node can be resynced from L1 batch #{batch_number}"
+                                        );
+                                        return Err(anyhow::Error::from(err).context(context));
+                                    }
+                                }
+                            } else {
+                                false
+                            };
+
+                            if let (Some(last_correct_batch), true) = (resync_target, can_resync) {
+                                if !policy.requires_confirmation {
+                                    tracing::info!(
+                                        "Requesting node resync from L1 batch #{last_correct_batch}"
+                                    );
+                                    self.pool
+                                        .access_storage()
+                                        .await?
+                                        .consistency_checker_dal()
+                                        .record_resync_attempt(batch_number)
+                                        .await?;
+                                    return Ok(Some(last_correct_batch));
+                                }
+                                tracing::warn!(
+                                    "Node resync from L1 batch #{last_correct_batch} requires operator confirmation; \
+                                     restart the node with `--revert-to-l1-batch {last_correct_batch}` to perform it"
+                                );
+                                self.event_handler.report_pending_resync(last_correct_batch);
+                            }
                         }
                     }
+                    batch_number += 1; // We don't want to infinitely loop failing the check on the same batch
                 }
                 Err(CheckError::Web3(err)) => {
                     tracing::warn!("Error accessing L1; will retry after a delay: {err}");
                     tokio::time::sleep(self.sleep_interval).await;
                 }
-                Err(CheckError::Internal(err)) => {
+                Err(err) => {
                     let context =
                         format!("Failed verifying consistency of L1 batch #{batch_number}");
-                    return Err(err.context(context));
+                    return Err(anyhow::Error::from(err).context(context));
                 }
             }
         }
-        Ok(())
+        Ok(None)
     }
 }
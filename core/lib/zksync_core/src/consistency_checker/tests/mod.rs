@@ -8,6 +8,7 @@ use tokio::sync::mpsc;
 use zksync_config::configs::KzgConfig;
 use zksync_dal::StorageProcessor;
 use zksync_eth_client::{clients::MockEthereum, Options};
+use zksync_health_check::CheckHealth;
 use zksync_l1_contract_interface::i_executor::structures::StoredBatchInfo;
 use zksync_types::{
     aggregated_operations::AggregatedActionType, commitment::L1BatchWithMetadata, L2ChainId,
@@ -79,6 +80,7 @@ fn create_mock_checker(client: MockEthereum, pool: ConnectionPool) -> Consistenc
         kzg_settings: Some(Arc::new(KzgSettings::new(
             &KzgConfig::for_tests().trusted_setup_path,
         ))),
+        main_node_client: None,
     }
 }
 
@@ -98,6 +100,10 @@ impl HandleConsistencyCheckerEvent for mpsc::UnboundedSender<L1BatchNumber> {
     fn report_inconsistent_batch(&mut self, _number: L1BatchNumber) {
         // Do nothing
     }
+
+    fn report_pending_resync(&mut self, _last_correct_batch: L1BatchNumber) {
+        // Do nothing
+    }
 }
 
 #[test]
@@ -645,3 +651,230 @@ async fn checker_detects_incorrect_tx_data(kind: IncorrectDataKind, snapshot_rec
 async fn checker_detects_incorrect_tx_data_after_snapshot_recovery() {
     checker_detects_incorrect_tx_data(IncorrectDataKind::CommitDataForAnotherBatch, true).await;
 }
+
+async fn wait_for_checked_batch(
+    health_check: &ReactiveHealthCheck,
+    l1_batch_number: L1BatchNumber,
+) -> Health {
+    loop {
+        let health = health_check.check_health().await;
+        let health_details = serde_json::to_value(&health).unwrap()["details"].clone();
+        if health_details["last_checked_batch"] == l1_batch_number.0 {
+            return health;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Main node client returning root hashes as per [`create_l1_batch_metadata()`].
+#[derive(Debug, Default)]
+struct MockMainNodeClient {
+    root_hashes: HashMap<L1BatchNumber, H256>,
+}
+
+impl MockMainNodeClient {
+    fn consistent_with_l1() -> Self {
+        let root_hashes = (1..=3)
+            .map(|number| {
+                (
+                    L1BatchNumber(number),
+                    create_l1_batch_metadata(number).root_hash,
+                )
+            })
+            .collect();
+        Self { root_hashes }
+    }
+}
+
+#[async_trait]
+impl MainNodeClient for MockMainNodeClient {
+    async fn l1_batch_root_hash(
+        &self,
+        number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<H256>> {
+        Ok(self.root_hashes.get(&number).copied())
+    }
+}
+
+async fn prepare_batches_with_inconsistent_batch(
+    pool: &ConnectionPool,
+    inconsistent_batch: L1BatchNumber,
+) -> MockEthereum {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batches: Vec<_> = (1..=3).map(create_l1_batch_with_metadata).collect();
+    let (client, commit_tx_hash_by_l1_batch) =
+        commit_batches_with_inconsistent_batch(&l1_batches, inconsistent_batch).await;
+    for save_action in SAVE_ACTION_MAPPERS[0].1(&l1_batches) {
+        save_action
+            .apply(&mut storage, &commit_tx_hash_by_l1_batch)
+            .await;
+    }
+    client
+}
+
+/// Creates a mock L1 client with the provided batches committed. Since transaction signing is deterministic,
+/// commit transaction hashes are the same for repeated calls.
+async fn commit_batches_with_inconsistent_batch(
+    l1_batches: &[L1BatchWithMetadata],
+    inconsistent_batch: L1BatchNumber,
+) -> (MockEthereum, HashMap<L1BatchNumber, H256>) {
+    let client = MockEthereum::default();
+    let kzg_settings = Arc::new(KzgSettings::new(&KzgConfig::for_tests().trusted_setup_path));
+    let mut commit_tx_hash_by_l1_batch = HashMap::with_capacity(l1_batches.len());
+    for (nonce, l1_batch) in l1_batches.iter().enumerate() {
+        let mut committed_batch = l1_batch.clone();
+        if committed_batch.header.number == inconsistent_batch {
+            committed_batch.header.timestamp += 1;
+        }
+        let input_data =
+            build_commit_tx_input_data(slice::from_ref(&committed_batch), kzg_settings.clone());
+        let signed_tx = client.sign_prepared_tx(
+            input_data,
+            Options {
+                nonce: Some(nonce.into()),
+                ..Options::default()
+            },
+        );
+        let signed_tx = signed_tx.unwrap();
+        client.send_raw_tx(signed_tx.raw_tx).await.unwrap();
+        client.execute_tx(signed_tx.hash, true, 1);
+        commit_tx_hash_by_l1_batch.insert(l1_batch.header.number, signed_tx.hash);
+    }
+    (client, commit_tx_hash_by_l1_batch)
+}
+
+#[test_casing(3, [0, 1, 10])]
+#[tokio::test]
+async fn checker_requests_resync_on_inconsistent_batch(max_batches_to_revert: u32) {
+    let pool = ConnectionPool::test_pool().await;
+    let client = prepare_batches_with_inconsistent_batch(&pool, L1BatchNumber(2)).await;
+
+    let checker = create_mock_checker(client, pool).with_resync_policy_inner(
+        ResyncPolicy {
+            max_batches_to_revert,
+            requires_confirmation: false,
+            max_attempts: 1,
+        },
+        Box::new(MockMainNodeClient::consistent_with_l1()),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let health_check = checker.health_check().clone();
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    if max_batches_to_revert >= 2 {
+        let last_correct_batch = tokio::time::timeout(Duration::from_secs(30), checker_task)
+            .await
+            .expect("Timed out waiting for checker to stop")
+            .unwrap()
+            .unwrap();
+        assert_eq!(last_correct_batch, Some(L1BatchNumber(1)));
+    } else {
+        // The resync is too large, so the checker should continue checking batches.
+        wait_for_checked_batch(&health_check, L1BatchNumber(3)).await;
+        stop_sender.send_replace(true);
+        let last_correct_batch = checker_task.await.unwrap().unwrap();
+        assert_eq!(last_correct_batch, None);
+    }
+}
+
+#[tokio::test]
+async fn checker_awaits_confirmation_for_resync() {
+    let pool = ConnectionPool::test_pool().await;
+    let client = prepare_batches_with_inconsistent_batch(&pool, L1BatchNumber(2)).await;
+
+    let checker = create_mock_checker(client, pool).with_resync_policy_inner(
+        ResyncPolicy {
+            max_batches_to_revert: 10,
+            requires_confirmation: true,
+            max_attempts: 1,
+        },
+        Box::new(MockMainNodeClient::consistent_with_l1()),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let health_check = checker.health_check().clone();
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    let health = wait_for_checked_batch(&health_check, L1BatchNumber(3)).await;
+    assert_matches!(health.status(), HealthStatus::Affected);
+    let health_details = serde_json::to_value(health).unwrap()["details"].clone();
+    assert_eq!(
+        health_details["inconsistent_batches"],
+        serde_json::json!([2])
+    );
+    assert_eq!(health_details["pending_resync_target"], 1);
+
+    stop_sender.send_replace(true);
+    let last_correct_batch = checker_task.await.unwrap().unwrap();
+    assert_eq!(last_correct_batch, None);
+}
+
+async fn assert_no_resync(checker: ConsistencyChecker) {
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let health_check = checker.health_check().clone();
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+
+    wait_for_checked_batch(&health_check, L1BatchNumber(3)).await;
+    stop_sender.send_replace(true);
+    let last_correct_batch = checker_task.await.unwrap().unwrap();
+    assert_eq!(last_correct_batch, None);
+}
+
+#[tokio::test]
+async fn checker_does_not_resync_if_main_node_is_inconsistent_with_l1() {
+    let pool = ConnectionPool::test_pool().await;
+    let client = prepare_batches_with_inconsistent_batch(&pool, L1BatchNumber(2)).await;
+
+    let mut main_node_client = MockMainNodeClient::consistent_with_l1();
+    main_node_client
+        .root_hashes
+        .insert(L1BatchNumber(2), H256::repeat_byte(0xff));
+    let checker = create_mock_checker(client, pool.clone()).with_resync_policy_inner(
+        ResyncPolicy {
+            max_batches_to_revert: 10,
+            requires_confirmation: false,
+            max_attempts: 1,
+        },
+        Box::new(main_node_client),
+    );
+    assert_no_resync(checker).await;
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let attempts = storage
+        .consistency_checker_dal()
+        .get_resync_attempts(L1BatchNumber(2))
+        .await
+        .unwrap();
+    assert_eq!(attempts, 0);
+}
+
+#[tokio::test]
+async fn checker_does_not_resync_repeatedly_for_same_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    let client = prepare_batches_with_inconsistent_batch(&pool, L1BatchNumber(2)).await;
+    let policy = ResyncPolicy {
+        max_batches_to_revert: 10,
+        requires_confirmation: false,
+        max_attempts: 1,
+    };
+
+    let checker = create_mock_checker(client, pool.clone())
+        .with_resync_policy_inner(policy, Box::new(MockMainNodeClient::consistent_with_l1()));
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let last_correct_batch =
+        tokio::time::timeout(Duration::from_secs(30), checker.run(stop_receiver))
+            .await
+            .expect("Timed out waiting for checker to stop")
+            .unwrap();
+    assert_eq!(last_correct_batch, Some(L1BatchNumber(1)));
+
+    // Emulate the node re-fetching the same inconsistent data after the resync.
+    let l1_batches: Vec<_> = (1..=3).map(create_l1_batch_with_metadata).collect();
+    let (client, _) = commit_batches_with_inconsistent_batch(&l1_batches, L1BatchNumber(2)).await;
+    let checker = create_mock_checker(client, pool)
+        .with_resync_policy_inner(policy, Box::new(MockMainNodeClient::consistent_with_l1()));
+    assert_no_resync(checker).await;
+}
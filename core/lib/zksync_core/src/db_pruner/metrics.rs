@@ -0,0 +1,68 @@
+use std::time::Duration;
+
+use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit};
+use zksync_dal::pruning_dal::HardPruningStats;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "prune_type", rename_all = "snake_case")]
+pub(super) enum MetricPruneType {
+    Soft,
+    Hard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "type", rename_all = "snake_case")]
+enum PrunedEntityType {
+    Miniblock,
+    Transaction,
+    Event,
+    L2ToL1Log,
+    TokenTransfer,
+    StorageLogFromPrunedBatch,
+    StorageLogFromPastBatch,
+}
+
+/// Metrics for the Postgres pruner.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "db_pruner")]
+pub(super) struct DbPrunerMetrics {
+    /// Total latency of pruning a chunk of L1 batches.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub pruning_chunk_duration: Family<MetricPruneType, Histogram<Duration>>,
+    /// Number of rows deleted during the last hard pruning iteration, by entity type.
+    deleted_entities: Family<PrunedEntityType, Gauge<u64>>,
+    /// Last L1 batch that was pruned.
+    pub last_pruned_l1_batch: Family<MetricPruneType, Gauge<u64>>,
+}
+
+impl DbPrunerMetrics {
+    pub fn observe_hard_pruning(&self, stats: HardPruningStats) {
+        let HardPruningStats {
+            deleted_miniblocks,
+            deleted_transactions,
+            deleted_events,
+            deleted_l2_to_l1_logs,
+            deleted_token_transfers,
+            deleted_storage_logs_from_past_batches,
+            deleted_storage_logs_from_pruned_batches,
+        } = stats;
+        tracing::info!(
+            "Performed pruning of database, deleted {deleted_miniblocks} miniblocks, {deleted_transactions} transactions, \
+             {deleted_events} events, {deleted_l2_to_l1_logs} L2-to-L1 logs, {deleted_token_transfers} token transfers, \
+             {deleted_storage_logs_from_past_batches} past storage logs, {deleted_storage_logs_from_pruned_batches} storage logs from pruned batches"
+        );
+
+        self.deleted_entities[&PrunedEntityType::Miniblock].set(deleted_miniblocks);
+        self.deleted_entities[&PrunedEntityType::Transaction].set(deleted_transactions);
+        self.deleted_entities[&PrunedEntityType::Event].set(deleted_events);
+        self.deleted_entities[&PrunedEntityType::L2ToL1Log].set(deleted_l2_to_l1_logs);
+        self.deleted_entities[&PrunedEntityType::TokenTransfer].set(deleted_token_transfers);
+        self.deleted_entities[&PrunedEntityType::StorageLogFromPastBatch]
+            .set(deleted_storage_logs_from_past_batches);
+        self.deleted_entities[&PrunedEntityType::StorageLogFromPrunedBatch]
+            .set(deleted_storage_logs_from_pruned_batches);
+    }
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<DbPrunerMetrics> = vise::Global::new();
@@ -0,0 +1,264 @@
+//! Postgres pruning component.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{pruning_dal::PruningInfo, ConnectionPool, StorageProcessor};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{L1BatchNumber, MiniblockNumber};
+use zksync_utils::time::seconds_since_epoch;
+
+use self::metrics::{MetricPruneType, METRICS};
+
+mod metrics;
+
+/// Configuration of [`DbPruner`].
+#[derive(Debug, Clone)]
+pub struct DbPrunerConfig {
+    /// Delay between soft-pruning and hard-pruning the same L1 batches. Gives API requests
+    /// started before soft-pruning time to finish, so that they don't observe partially removed data.
+    pub soft_and_hard_pruning_time_delta: Duration,
+    /// Sleep interval between pruning iterations if there's nothing to prune.
+    pub next_iterations_delay: Duration,
+    /// Number of L1 batches pruned in a single iteration.
+    pub pruned_batch_chunk_size: u32,
+    /// Minimum age of an L1 batch for it to be eligible for pruning. Age is determined by the batch timestamp.
+    pub minimum_l1_batch_age: Duration,
+}
+
+/// Postgres pruner for the miniblock-level data of old L1 batches. L1 batches are pruned in chunks
+/// in two stages:
+///
+/// 1. Soft pruning: the batches are marked as pruned in Postgres and are no longer served via the API
+///    (the API server periodically reloads the pruning info; see [`BlockStartInfo`]).
+/// 2. Hard pruning: after [`DbPrunerConfig::soft_and_hard_pruning_time_delta`], the miniblock-level data
+///    for the batches is removed from Postgres.
+///
+/// An L1 batch is only pruned if it's executed on L1, has metadata and is older than
+/// [`DbPrunerConfig::minimum_l1_batch_age`].
+///
+/// [`BlockStartInfo`]: crate::api_server::execution_sandbox::BlockStartInfo
+#[derive(Debug)]
+pub struct DbPruner {
+    config: DbPrunerConfig,
+    connection_pool: ConnectionPool,
+    health_updater: HealthUpdater,
+}
+
+impl DbPruner {
+    pub fn new(config: DbPrunerConfig, connection_pool: ConnectionPool) -> Self {
+        Self {
+            config,
+            connection_pool,
+            health_updater: ReactiveHealthCheck::new("db_pruner").1,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    fn update_health(&self, pruning_info: PruningInfo) {
+        let health_details = serde_json::json!({
+            "last_soft_pruned_l1_batch": pruning_info.last_soft_pruned_l1_batch,
+            "last_hard_pruned_l1_batch": pruning_info.last_hard_pruned_l1_batch,
+        });
+        self.health_updater
+            .update(Health::from(HealthStatus::Ready).with_details(health_details));
+    }
+
+    async fn is_l1_batch_prunable(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<bool> {
+        let last_executed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?;
+        if last_executed_l1_batch.map_or(true, |number| number < l1_batch_number) {
+            tracing::debug!(
+                "L1 batch #{l1_batch_number} is not executed on L1 yet (last executed: {last_executed_l1_batch:?})"
+            );
+            return Ok(false);
+        }
+
+        let last_l1_batch_with_metadata = storage
+            .blocks_dal()
+            .get_last_l1_batch_number_with_metadata()
+            .await?;
+        if last_l1_batch_with_metadata.map_or(true, |number| number < l1_batch_number) {
+            tracing::debug!(
+                "L1 batch #{l1_batch_number} doesn't have metadata yet (last with metadata: {last_l1_batch_with_metadata:?})"
+            );
+            return Ok(false);
+        }
+
+        let Some(header) = storage
+            .blocks_dal()
+            .get_l1_batch_header(l1_batch_number)
+            .await?
+        else {
+            return Ok(false);
+        };
+        let l1_batch_age = seconds_since_epoch().saturating_sub(header.timestamp);
+        if l1_batch_age < self.config.minimum_l1_batch_age.as_secs() {
+            tracing::debug!(
+                "L1 batch #{l1_batch_number} is too recent to be pruned (age: {l1_batch_age}s)"
+            );
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    /// Soft-prunes the next chunk of L1 batches. Returns `Ok(None)` if there's nothing to prune.
+    async fn soft_prune(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        pruning_info: PruningInfo,
+    ) -> anyhow::Result<Option<(L1BatchNumber, MiniblockNumber)>> {
+        let latency = METRICS.pruning_chunk_duration[&MetricPruneType::Soft].start();
+
+        let next_l1_batch_to_prune = match pruning_info.last_soft_pruned_l1_batch {
+            Some(number) => number + 1,
+            None => {
+                let Some(earliest_l1_batch) =
+                    storage.blocks_dal().get_earliest_l1_batch_number().await?
+                else {
+                    return Ok(None);
+                };
+                earliest_l1_batch
+            }
+        };
+        let last_l1_batch_to_prune =
+            next_l1_batch_to_prune + self.config.pruned_batch_chunk_size - 1;
+        if !self
+            .is_l1_batch_prunable(storage, last_l1_batch_to_prune)
+            .await?
+        {
+            return Ok(None);
+        }
+
+        let (_, last_miniblock_to_prune) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch_to_prune)
+            .await?
+            .with_context(|| {
+                format!("L1 batch #{last_l1_batch_to_prune} doesn't have miniblocks")
+            })?;
+        storage
+            .pruning_dal()
+            .soft_prune_batches_range(last_l1_batch_to_prune, last_miniblock_to_prune)
+            .await?;
+
+        let latency = latency.observe();
+        METRICS.last_pruned_l1_batch[&MetricPruneType::Soft].set(last_l1_batch_to_prune.0.into());
+        tracing::info!(
+            "Soft-pruned L1 batches up to #{last_l1_batch_to_prune} (miniblocks up to #{last_miniblock_to_prune}) in {latency:?}"
+        );
+        Ok(Some((last_l1_batch_to_prune, last_miniblock_to_prune)))
+    }
+
+    async fn hard_prune(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        last_l1_batch_to_prune: L1BatchNumber,
+        last_miniblock_to_prune: MiniblockNumber,
+    ) -> anyhow::Result<()> {
+        let latency = METRICS.pruning_chunk_duration[&MetricPruneType::Hard].start();
+        let stats = storage
+            .pruning_dal()
+            .hard_prune_batches_range(last_l1_batch_to_prune, last_miniblock_to_prune)
+            .await?;
+        METRICS.observe_hard_pruning(stats);
+
+        let latency = latency.observe();
+        METRICS.last_pruned_l1_batch[&MetricPruneType::Hard].set(last_l1_batch_to_prune.0.into());
+        tracing::info!(
+            "Hard-pruned L1 batches up to #{last_l1_batch_to_prune} (miniblocks up to #{last_miniblock_to_prune}) in {latency:?}"
+        );
+        Ok(())
+    }
+
+    /// Performs a single pruning iteration. Returns `true` if any data was pruned.
+    async fn run_single_iteration(
+        &self,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<bool> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("db_pruner")
+            .await?;
+        let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+        self.update_health(pruning_info);
+
+        // If the node was restarted between soft and hard pruning, finish hard pruning first.
+        let (last_l1_batch_to_prune, last_miniblock_to_prune) = match (
+            pruning_info.last_soft_pruned_l1_batch,
+            pruning_info.last_soft_pruned_miniblock,
+        ) {
+            (Some(l1_batch), Some(miniblock))
+                if pruning_info.last_hard_pruned_l1_batch < Some(l1_batch) =>
+            {
+                (l1_batch, miniblock)
+            }
+            _ => {
+                let Some(pruned) = self.soft_prune(&mut storage, pruning_info).await? else {
+                    return Ok(false);
+                };
+                pruned
+            }
+        };
+        drop(storage);
+
+        // Give API requests that could have started before soft pruning time to finish.
+        if tokio::time::timeout(
+            self.config.soft_and_hard_pruning_time_delta,
+            stop_receiver.changed(),
+        )
+        .await
+        .is_ok()
+        {
+            // The stop signal was received (or its sender was dropped); hard pruning will be performed on restart.
+            return Ok(false);
+        }
+
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("db_pruner")
+            .await?;
+        self.hard_prune(
+            &mut storage,
+            last_l1_batch_to_prune,
+            last_miniblock_to_prune,
+        )
+        .await?;
+        let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+        self.update_health(pruning_info);
+        Ok(true)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.config.pruned_batch_chunk_size > 0,
+            "Pruned batch chunk size must be positive"
+        );
+
+        while !*stop_receiver.borrow_and_update() {
+            let pruned = self
+                .run_single_iteration(&mut stop_receiver)
+                .await
+                .context("failed running pruning iteration")?;
+            if !pruned {
+                // We don't check the result: if a stop signal is received, we'll return at the start
+                // of the next iteration.
+                tokio::time::timeout(self.config.next_iterations_delay, stop_receiver.changed())
+                    .await
+                    .ok();
+            }
+        }
+        tracing::info!("Stop signal received, shutting down DB pruning");
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use zksync_config::configs::eth_sender::{ProofLoadingMode, ProofSendingMode, SenderConfig};
 use zksync_contracts::BaseSystemContractsHashes;
@@ -17,11 +17,14 @@ use zksync_types::{
 
 use super::{
     aggregated_operations::AggregatedOperation,
+    kzg_precomputer::KzgInfoCache,
     publish_criterion::{
-        DataSizeCriterion, GasCriterion, L1BatchPublishCriterion, NumberCriterion,
-        TimestampDeadlineCriterion,
+        DataSizeCriterion, GasCriterion, GasPriceCriterion, L1BatchPublishCriterion,
+        NumberCriterion, TimestampDeadlineCriterion,
     },
+    send_gate::L1FeeSendGate,
 };
+use crate::l1_gas_price::L1TxParamsProvider;
 
 #[derive(Debug)]
 pub struct Aggregator {
@@ -36,19 +39,27 @@ pub struct Aggregator {
     /// means no wait is needed: nonces will still provide the correct ordering of
     /// transactions.
     operate_4844_mode: bool,
+    /// Pubdata mode used for L1 batches that don't have a recorded mode (e.g., ones sealed before
+    /// the mode started being recorded).
     pubdata_da: PubdataDA,
     kzg_settings: Option<Arc<KzgSettings>>,
+    kzg_info_cache: KzgInfoCache,
+    send_gate: Option<L1FeeSendGate>,
 }
 
 impl Aggregator {
+    /// Creates a new aggregator. If `l1_tx_params` are provided, prove and execute aggregation windows
+    /// are sized dynamically based on L1 gas prices (see [`GasPriceCriterion`]).
     pub fn new(
         config: SenderConfig,
         blob_store: Arc<dyn ObjectStore>,
         operate_4844_mode: bool,
         pubdata_da: PubdataDA,
         kzg_settings: Option<Arc<KzgSettings>>,
+        l1_tx_params: Option<Arc<dyn L1TxParamsProvider>>,
     ) -> Self {
-        Self {
+        let kzg_info_cache = KzgInfoCache::new(kzg_settings.clone(), pubdata_da);
+        let mut this = Self {
             commit_criteria: vec![
                 Box::from(NumberCriterion {
                     op: AggregatedActionType::Commit,
@@ -61,8 +72,9 @@ impl Aggregator {
                 Box::from(DataSizeCriterion {
                     op: AggregatedActionType::Commit,
                     data_limit: config.max_eth_tx_data_size,
-                    pubdata_da,
+                    default_pubdata_da: pubdata_da,
                     kzg_settings: kzg_settings.clone(),
+                    kzg_info_cache: kzg_info_cache.clone(),
                 }),
                 Box::from(TimestampDeadlineCriterion {
                     op: AggregatedActionType::Commit,
@@ -108,7 +120,45 @@ impl Aggregator {
             operate_4844_mode,
             pubdata_da,
             kzg_settings,
+            kzg_info_cache,
+            send_gate: None,
+        };
+
+        if let Some(l1_tx_params) = l1_tx_params {
+            this.proof_criteria.push(Box::from(GasPriceCriterion {
+                op: AggregatedActionType::PublishProofOnchain,
+                limit: *this.config.aggregated_proof_sizes.iter().max().unwrap() as u32,
+                l1_tx_params: l1_tx_params.clone(),
+            }));
+            this.execute_criteria.push(Box::from(GasPriceCriterion {
+                op: AggregatedActionType::Execute,
+                limit: this.config.max_aggregated_blocks_to_execute,
+                l1_tx_params,
+            }));
         }
+        this
+    }
+
+    /// Withholds commit and execute operations while L1 fees are high (see [`L1FeeSendGate`]).
+    pub fn with_send_gate(mut self, send_gate: L1FeeSendGate) -> Self {
+        self.send_gate = Some(send_gate);
+        self
+    }
+
+    /// Returns the cache of KZG info used when forming commit operations. The cache can be populated ahead of time
+    /// by a [`KzgPrecomputer`](super::KzgPrecomputer).
+    pub fn kzg_info_cache(&self) -> &KzgInfoCache {
+        &self.kzg_info_cache
+    }
+
+    fn may_send(&mut self, op: AggregatedActionType, l1_batches: &[L1BatchWithMetadata]) -> bool {
+        let (Some(send_gate), Some(first_l1_batch)) = (&mut self.send_gate, l1_batches.first())
+        else {
+            return true;
+        };
+        let uses_blobs = op == AggregatedActionType::Commit
+            && l1_batch_pubdata_da(first_l1_batch, self.pubdata_da) == PubdataDA::Blobs;
+        send_gate.may_send(op, uses_blobs)
     }
 
     pub async fn get_next_ready_operation(
@@ -127,14 +177,15 @@ impl Aggregator {
             return None; // No L1 batches in Postgres; no operations are ready yet
         };
 
-        if let Some(op) = self
+        let execute_op = self
             .get_execute_operations(
                 storage,
                 self.config.max_aggregated_blocks_to_execute as usize,
                 last_sealed_l1_batch_number,
             )
             .await
-        {
+            .filter(|op| self.may_send(AggregatedActionType::Execute, &op.l1_batches));
+        if let Some(op) = execute_op {
             Some(AggregatedOperation::Execute(op))
         } else if let Some(op) = self
             .get_proof_operation(
@@ -155,6 +206,7 @@ impl Aggregator {
                 protocol_version_id,
             )
             .await
+            .filter(|op| self.may_send(AggregatedActionType::Commit, &op.l1_batches))
             .map(AggregatedOperation::Commit)
         }
     }
@@ -179,6 +231,7 @@ impl Aggregator {
             &mut self.execute_criteria,
             ready_for_execute_batches,
             last_sealed_l1_batch,
+            false,
         )
         .await;
 
@@ -198,8 +251,10 @@ impl Aggregator {
             .get_last_committed_to_eth_l1_batch()
             .await
             .unwrap()?;
+        self.kzg_info_cache
+            .prune(last_committed_l1_batch.header.number);
 
-        let ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
+        let mut ready_for_commit_l1_batches = if protocol_version_id.is_pre_boojum() {
             blocks_dal
                 .pre_boojum_get_ready_for_commit_l1_batches(
                     limit,
@@ -232,19 +287,34 @@ impl Aggregator {
                 }
             });
 
+        // If the pubdata mode changes within the ready range, the batches before the change
+        // can be committed right away since their range cannot grow.
+        let (pubdata_da, mode_changed) =
+            retain_same_pubdata_mode(&mut ready_for_commit_l1_batches, self.pubdata_da);
         let batches = extract_ready_subrange(
             storage,
             &mut self.commit_criteria,
             ready_for_commit_l1_batches,
             last_sealed_batch,
+            mode_changed,
         )
         .await;
 
-        batches.map(|batches| CommitBatches {
-            last_committed_l1_batch,
-            l1_batches: batches,
-            pubdata_da: self.pubdata_da,
-            kzg_settings: self.kzg_settings.clone(),
+        batches.map(|batches| {
+            let precomputed_kzg_info = batches
+                .iter()
+                .filter_map(|batch| {
+                    let kzg_info = self.kzg_info_cache.get_or_compute(batch)?;
+                    Some((batch.header.number, kzg_info))
+                })
+                .collect::<HashMap<_, _>>();
+            CommitBatches {
+                last_committed_l1_batch,
+                l1_batches: batches,
+                pubdata_da,
+                kzg_settings: self.kzg_settings.clone(),
+                precomputed_kzg_info,
+            }
         })
     }
 
@@ -352,6 +422,7 @@ impl Aggregator {
             &mut self.proof_criteria,
             ready_for_proof_l1_batches,
             last_sealed_l1_batch,
+            false,
         )
         .await?;
 
@@ -431,10 +502,30 @@ impl Aggregator {
             }
         }
     }
+}
+
+/// Returns the pubdata mode an L1 batch was sealed with, falling back to `default` if the mode is not recorded.
+pub(super) fn l1_batch_pubdata_da(l1_batch: &L1BatchWithMetadata, default: PubdataDA) -> PubdataDA {
+    l1_batch.header.pubdata_mode.unwrap_or(default)
+}
 
-    pub fn pubdata_da(&self) -> PubdataDA {
-        self.pubdata_da
+/// Truncates consecutive L1 batches to the longest prefix using the same pubdata mode, since all L1 batches
+/// in a commit operation must use the same mode. Returns the mode and whether any batches were removed.
+pub(super) fn retain_same_pubdata_mode(
+    l1_batches: &mut Vec<L1BatchWithMetadata>,
+    default: PubdataDA,
+) -> (PubdataDA, bool) {
+    let Some(first_l1_batch) = l1_batches.first() else {
+        return (default, false);
+    };
+    let pubdata_da = l1_batch_pubdata_da(first_l1_batch, default);
+    let mode_change_position = l1_batches
+        .iter()
+        .position(|l1_batch| l1_batch_pubdata_da(l1_batch, default) != pubdata_da);
+    if let Some(position) = mode_change_position {
+        l1_batches.truncate(position);
     }
+    (pubdata_da, mode_change_position.is_some())
 }
 
 async fn extract_ready_subrange(
@@ -442,6 +533,9 @@ async fn extract_ready_subrange(
     publish_criteria: &mut [Box<dyn L1BatchPublishCriterion>],
     unpublished_l1_batches: Vec<L1BatchWithMetadata>,
     last_sealed_l1_batch: L1BatchNumber,
+    // Whether the range cannot be extended with further L1 batches, so it can be published
+    // even if no criterion is triggered.
+    range_is_final: bool,
 ) -> Option<Vec<L1BatchWithMetadata>> {
     let mut last_l1_batch: Option<L1BatchNumber> = None;
     for criterion in publish_criteria {
@@ -453,7 +547,11 @@ async fn extract_ready_subrange(
         }
     }
 
-    let last_l1_batch = last_l1_batch?;
+    let last_l1_batch = if range_is_final {
+        last_l1_batch.or_else(|| Some(unpublished_l1_batches.last()?.header.number))?
+    } else {
+        last_l1_batch?
+    };
     Some(
         unpublished_l1_batches
             .into_iter()
@@ -1,3 +1,4 @@
+use zksync_dal::SqlxError;
 use zksync_types::web3::contract;
 
 #[derive(Debug, thiserror::Error)]
@@ -6,4 +7,6 @@ pub enum ETHSenderError {
     EthereumGateWayError(#[from] zksync_eth_client::Error),
     #[error("Token parsing Error: {0}")]
     ParseError(#[from] contract::Error),
+    #[error("Database error: {0}")]
+    DalError(#[from] SqlxError),
 }
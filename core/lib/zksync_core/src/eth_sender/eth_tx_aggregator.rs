@@ -6,7 +6,7 @@ use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{BoundEthInterface, CallFunctionArgs};
 use zksync_l1_contract_interface::{
-    i_executor::commit::kzg::{KzgInfo, KzgSettings, ZK_SYNC_BYTES_PER_BLOB},
+    i_executor::commit::kzg::{pubdata_to_kzg_info, KzgSettings},
     multicall3::{Multicall3Call, Multicall3Result},
     Detokenize, Tokenizable, Tokenize,
 };
@@ -54,8 +54,6 @@ pub struct EthTxAggregator {
     l1_multicall3_address: Address,
     pub(super) main_zksync_contract_address: Address,
     functions: ZkSyncFunctions,
-    base_nonce: u64,
-    base_nonce_custom_commit_sender: Option<u64>,
     rollup_chain_id: L2ChainId,
     kzg_settings: Option<Arc<KzgSettings>>,
     /// If set to `Some` node is operating in the 4844 mode with two operator
@@ -63,6 +61,8 @@ pub struct EthTxAggregator {
     /// transactions. The `Some` then contains the address of this custom operator
     /// address.
     custom_commit_sender_addr: Option<Address>,
+    /// Signals whether creating new L1 transactions is paused by the circuit breaker.
+    pause_receiver: watch::Receiver<bool>,
 }
 
 struct TxData {
@@ -84,22 +84,6 @@ impl EthTxAggregator {
         custom_commit_sender_addr: Option<Address>,
     ) -> Self {
         let functions = ZkSyncFunctions::default();
-        let base_nonce = eth_client
-            .pending_nonce("eth_sender")
-            .await
-            .unwrap()
-            .as_u64();
-
-        let base_nonce_custom_commit_sender = match custom_commit_sender_addr {
-            Some(addr) => Some(
-                eth_client
-                    .nonce_at_for_account(addr, BlockNumber::Pending, "eth_sender")
-                    .await
-                    .unwrap()
-                    .as_u64(),
-            ),
-            None => None,
-        };
         Self {
             config,
             aggregator,
@@ -108,14 +92,20 @@ impl EthTxAggregator {
             l1_multicall3_address,
             main_zksync_contract_address,
             functions,
-            base_nonce,
-            base_nonce_custom_commit_sender,
             rollup_chain_id,
             kzg_settings,
             custom_commit_sender_addr,
+            pause_receiver: watch::channel(false).1,
         }
     }
 
+    /// Sets the signal pausing creation of new L1 transactions (e.g., if L1 contracts don't match
+    /// the expected protocol version).
+    pub fn with_pause_receiver(mut self, pause_receiver: watch::Receiver<bool>) -> Self {
+        self.pause_receiver = pause_receiver;
+        self
+    }
+
     pub async fn run(
         mut self,
         pool: ConnectionPool,
@@ -129,7 +119,9 @@ impl EthTxAggregator {
                 break;
             }
 
-            if let Err(err) = self.loop_iteration(&mut storage).await {
+            if *self.pause_receiver.borrow() {
+                tracing::debug!("eth_tx_aggregator is paused by the circuit breaker");
+            } else if let Err(err) = self.loop_iteration(&mut storage).await {
                 // Web3 API request failures can cause this,
                 // and anything more important is already properly reported.
                 tracing::warn!("eth_sender error {err:?}");
@@ -437,7 +429,7 @@ impl EthTxAggregator {
             AggregatedOperation::Commit(op) => {
                 if contracts_are_pre_shared_bridge {
                     if let (Some(kzg_settings), PubdataDA::Blobs) =
-                        (&self.kzg_settings, self.aggregator.pubdata_da())
+                        (&self.kzg_settings, op.pubdata_da)
                     {
                         let calldata = self
                             .functions
@@ -445,20 +437,24 @@ impl EthTxAggregator {
                             .encode_input(&op.clone().into_tokens())
                             .expect("Failed to encode commit transaction data");
 
-                        let side_car = op.l1_batches[0]
-                            .header
-                            .pubdata_input
-                            .clone()
-                            .unwrap()
-                            .chunks(ZK_SYNC_BYTES_PER_BLOB)
-                            .map(|blob| {
-                                let kzg_info = KzgInfo::new(kzg_settings, blob);
-                                SidecarBlobV1 {
-                                    blob: kzg_info.blob.to_vec(),
-                                    commitment: kzg_info.kzg_commitment.to_vec(),
-                                    proof: kzg_info.blob_proof.to_vec(),
-                                    versioned_hash: kzg_info.versioned_hash.to_vec(),
-                                }
+                        let first_l1_batch = &op.l1_batches[0];
+                        let kzg_info = match op
+                            .precomputed_kzg_info
+                            .get(&first_l1_batch.header.number)
+                        {
+                            Some(kzg_info) => kzg_info.clone(),
+                            None => {
+                                let pubdata = first_l1_batch.header.pubdata_input.as_ref().unwrap();
+                                pubdata_to_kzg_info(kzg_settings, pubdata, PubdataDA::Blobs).into()
+                            }
+                        };
+                        let side_car = kzg_info
+                            .iter()
+                            .map(|kzg_info| SidecarBlobV1 {
+                                blob: kzg_info.blob.to_vec(),
+                                commitment: kzg_info.kzg_commitment.to_vec(),
+                                proof: kzg_info.blob_proof.to_vec(),
+                                versioned_hash: kzg_info.versioned_hash.to_vec(),
                             })
                             .collect::<Vec<SidecarBlobV1>>();
 
@@ -583,16 +579,19 @@ impl EthTxAggregator {
             .await
             .unwrap()
             .unwrap_or(0);
-        // Between server starts we can execute some txs using operator account or remove some txs from the database
-        // At the start we have to consider this fact and get the max nonce.
-        Ok(if from_addr.is_none() {
-            db_nonce.max(self.base_nonce)
-        } else {
-            db_nonce.max(
-                self.base_nonce_custom_commit_sender
-                    .expect("custom base nonce is expected to be initialized; qed"),
-            )
-        })
+        // Transactions can be sent from the operator account bypassing `eth_sender` (e.g., during manual interventions),
+        // or some transactions can be removed from the database. Thus, we have to take the on-chain nonce into account.
+        // The nonce is requested on each call rather than cached on start, so that operations re-queued
+        // by `EthTxManager` after nonce reconciliation get up-to-date nonces.
+        let base_nonce = match from_addr {
+            None => self.eth_client.pending_nonce("eth_sender").await?,
+            Some(addr) => {
+                self.eth_client
+                    .nonce_at_for_account(addr, BlockNumber::Pending, "eth_sender")
+                    .await?
+            }
+        };
+        Ok(db_nonce.max(base_nonce.as_u64()))
     }
 }
 
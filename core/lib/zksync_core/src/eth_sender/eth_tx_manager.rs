@@ -1,6 +1,7 @@
 use std::{sync::Arc, time::Duration};
 
 use anyhow::Context as _;
+use serde::Serialize;
 use tokio::sync::watch;
 use zksync_config::configs::eth_sender::SenderConfig;
 use zksync_dal::{ConnectionPool, StorageProcessor};
@@ -8,6 +9,7 @@ use zksync_eth_client::{
     encode_blob_tx_with_sidecar, BoundEthInterface, Error, EthInterface, ExecutedTxStatus, Options,
     RawTransactionBytes, SignedCallResult,
 };
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{
     aggregated_operations::AggregatedActionType,
     eth_sender::{EthTx, EthTxBlobSidecar},
@@ -19,7 +21,10 @@ use zksync_types::{
 };
 use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::METRICS, ETHSenderError};
+use super::{
+    metrics::{TxMempool, METRICS},
+    ETHSenderError,
+};
 use crate::{l1_gas_price::L1TxParamsProvider, metrics::BlockL1Stage};
 
 #[derive(Debug)]
@@ -44,6 +49,26 @@ pub(super) struct L1BlockNumbers {
     pub latest: L1BlockNumber,
 }
 
+/// Private transaction relay used to send operator transactions bypassing the public mempool.
+#[derive(Debug)]
+struct TxRelay {
+    client: Arc<dyn EthInterface>,
+    public_fallback_timeout: Duration,
+}
+
+/// Health details for [`EthTxManager`].
+#[derive(Debug, Serialize)]
+struct EthTxManagerHealthDetails {
+    latest_l1_block: L1BlockNumber,
+    inflight_tx_count: usize,
+    max_txs_in_flight: u64,
+    /// Whether sending new transactions is paused by the circuit breaker.
+    paused: bool,
+    /// Age of the oldest in-flight transaction in seconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    oldest_inflight_tx_age_sec: Option<u64>,
+}
+
 /// The component is responsible for managing sending eth_txs attempts:
 /// Based on eth_tx queue the component generates new attempt with the minimum possible fee,
 /// save it to the database, and send it to Ethereum.
@@ -58,6 +83,14 @@ pub struct EthTxManager {
     ethereum_gateway_blobs: Option<Arc<dyn BoundEthInterface>>,
     config: SenderConfig,
     gas_adjuster: Arc<dyn L1TxParamsProvider>,
+    health_updater: HealthUpdater,
+    /// Signals whether sending new transactions is paused by the circuit breaker.
+    pause_receiver: watch::Receiver<bool>,
+    /// If set, transactions are sent to the private relay until the public fallback timeout elapses.
+    tx_relay: Option<TxRelay>,
+    /// Whether nonces of unconfirmed transactions are reconciled with the on-chain operator nonces.
+    /// Only disabled in tests with an L1 mock that doesn't track nonces faithfully.
+    pub(super) reconcile_nonces: bool,
 }
 
 impl EthTxManager {
@@ -72,9 +105,43 @@ impl EthTxManager {
             ethereum_gateway_blobs,
             config,
             gas_adjuster,
+            health_updater: ReactiveHealthCheck::new("eth_tx_manager").1,
+            pause_receiver: watch::channel(false).1,
+            tx_relay: None,
+            reconcile_nonces: true,
         }
     }
 
+    /// Sets the signal pausing sending new transactions (e.g., if L1 contracts don't match the expected
+    /// protocol version). While paused, the manager still monitors transactions already sent to L1.
+    pub fn with_pause_receiver(mut self, pause_receiver: watch::Receiver<bool>) -> Self {
+        self.pause_receiver = pause_receiver;
+        self
+    }
+
+    /// Sets the private relay to send transactions to (e.g., to prevent front-running of execute operations).
+    /// Attempts for a transaction are sent to the public mempool once `public_fallback_timeout` has elapsed
+    /// since its first sent attempt, or if the relay request fails.
+    pub fn with_tx_relay(
+        mut self,
+        client: Arc<dyn EthInterface>,
+        public_fallback_timeout: Duration,
+    ) -> Self {
+        self.tx_relay = Some(TxRelay {
+            client,
+            public_fallback_timeout,
+        });
+        self
+    }
+
+    fn is_paused(&self) -> bool {
+        *self.pause_receiver.borrow()
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
     async fn get_tx_status(
         &self,
         tx_hash: H256,
@@ -268,7 +335,13 @@ impl EthTxManager {
             .unwrap()
         {
             if let Err(error) = self
-                .send_raw_transaction(storage, tx_history_id, signed_tx.raw_tx, current_block)
+                .send_raw_transaction(
+                    storage,
+                    tx.id,
+                    tx_history_id,
+                    signed_tx.raw_tx,
+                    current_block,
+                )
                 .await
             {
                 tracing::warn!(
@@ -283,14 +356,58 @@ impl EthTxManager {
         Ok(signed_tx.hash)
     }
 
+    /// Returns the private relay if the next attempt for the specified transaction should be sent to it.
+    async fn relay_for_tx(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        eth_tx_id: u32,
+    ) -> Option<&dyn EthInterface> {
+        let relay = self.tx_relay.as_ref()?;
+        let elapsed = storage
+            .eth_sender_dal()
+            .get_time_since_first_sent_attempt(eth_tx_id)
+            .await
+            .unwrap();
+        if let Some(elapsed) = elapsed {
+            if elapsed >= relay.public_fallback_timeout {
+                tracing::info!(
+                    "Tx {eth_tx_id} is not mined {elapsed:?} after being sent to the private relay; \
+                     sending it to the public mempool"
+                );
+                METRICS.relayed_attempts[&TxMempool::PublicAfterTimeout].inc();
+                return None;
+            }
+        }
+        Some(relay.client.as_ref())
+    }
+
     async fn send_raw_transaction(
         &self,
         storage: &mut StorageProcessor<'_>,
+        eth_tx_id: u32,
         tx_history_id: u32,
         raw_tx: RawTransactionBytes,
         current_block: L1BlockNumber,
     ) -> Result<H256, ETHSenderError> {
-        match self.ethereum_gateway.send_raw_tx(raw_tx).await {
+        let send_result = if let Some(relay) = self.relay_for_tx(storage, eth_tx_id).await {
+            match relay.send_raw_tx(raw_tx.clone()).await {
+                Ok(tx_hash) => {
+                    METRICS.relayed_attempts[&TxMempool::Private].inc();
+                    Ok(tx_hash)
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Error sending tx {eth_tx_id} to the private relay, sending it to the public mempool: {err}"
+                    );
+                    METRICS.relayed_attempts[&TxMempool::PublicAfterRelayError].inc();
+                    self.ethereum_gateway.send_raw_tx(raw_tx).await
+                }
+            }
+        } else {
+            self.ethereum_gateway.send_raw_tx(raw_tx).await
+        };
+
+        match send_result {
             Ok(tx_hash) => {
                 storage
                     .eth_sender_dal()
@@ -410,6 +527,11 @@ impl EthTxManager {
         METRICS.track_block_numbers(&l1_block_numbers);
         let operator_nonce = self.get_operator_nonce(l1_block_numbers).await?;
         let blobs_operator_nonce = self.get_blobs_operator_nonce(l1_block_numbers).await?;
+        if self.reconcile_nonces {
+            self.reconcile_operator_nonces(storage, operator_nonce, blobs_operator_nonce)
+                .await?;
+        }
+
         let blobs_operator_address = self
             .ethereum_gateway_blobs
             .as_ref()
@@ -440,6 +562,110 @@ impl EthTxManager {
         }
     }
 
+    /// Reconciles nonces of unconfirmed transactions with the operator nonces on L1. Nonces can get out of sync
+    /// if transactions are sent from an operator account bypassing `eth_sender` (e.g., during manual interventions).
+    ///
+    /// A transaction is *stale* if its nonce was consumed on a finalized L1 block, but none of its sending attempts
+    /// was mined; such a transaction can never be mined. If there is a stale transaction, it's removed together
+    /// with all subsequent unconfirmed transactions, so that the aggregator re-creates the corresponding operations
+    /// with up-to-date nonces. This is only safe if none of the removed transactions may still be mined, so
+    /// reconciliation is deferred while there are subsequent transactions that were sent and are not stale.
+    ///
+    /// Since the first loop iteration always monitors in-flight transactions, reconciliation is performed on start as well.
+    async fn reconcile_operator_nonces(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        operator_nonce: OperatorNonce,
+        blobs_operator_nonce: Option<OperatorNonce>,
+    ) -> Result<(), ETHSenderError> {
+        let unconfirmed_txs = storage.eth_sender_dal().get_unconfirmed_txs().await?;
+        let mut first_stale_tx = None;
+        for (i, tx) in unconfirmed_txs.iter().enumerate() {
+            if self
+                .is_stale_tx(storage, tx, operator_nonce, blobs_operator_nonce)
+                .await?
+            {
+                first_stale_tx = Some(i);
+                break;
+            }
+        }
+        let Some(first_stale_tx) = first_stale_tx else {
+            return Ok(());
+        };
+        let stale_tx = &unconfirmed_txs[first_stale_tx];
+        let subsequent_txs = &unconfirmed_txs[first_stale_tx + 1..];
+
+        for tx in subsequent_txs {
+            let was_sent = storage
+                .eth_sender_dal()
+                .get_block_number_on_first_sent_attempt(tx.id)
+                .await?
+                .is_some();
+            if was_sent
+                && !self
+                    .is_stale_tx(storage, tx, operator_nonce, blobs_operator_nonce)
+                    .await?
+            {
+                tracing::warn!(
+                    "Transaction {} with nonce {} cannot be mined since its nonce was consumed on L1, \
+                     but it cannot be re-queued yet because the subsequent transaction {} with nonce {} \
+                     may still be mined",
+                    stale_tx.id,
+                    stale_tx.nonce,
+                    tx.id,
+                    tx.nonce
+                );
+                return Ok(());
+            }
+        }
+
+        let removed_tx_count = storage
+            .eth_sender_dal()
+            .delete_unconfirmed_txs_since(stale_tx.id)
+            .await?;
+        METRICS.transactions_requeued.inc_by(removed_tx_count);
+        tracing::warn!(
+            "Nonce {} of transaction {} ({}) was consumed on L1 by another transaction; re-queued {removed_tx_count} \
+             unconfirmed transactions starting from it",
+            stale_tx.nonce,
+            stale_tx.id,
+            stale_tx.tx_type
+        );
+        Ok(())
+    }
+
+    async fn is_stale_tx(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        tx: &EthTx,
+        operator_nonce: OperatorNonce,
+        blobs_operator_nonce: Option<OperatorNonce>,
+    ) -> Result<bool, ETHSenderError> {
+        let finalized_nonce = match tx.from_addr {
+            None => operator_nonce.finalized,
+            Some(_) => match blobs_operator_nonce {
+                Some(nonce) => nonce.finalized,
+                None => return Ok(false),
+            },
+        };
+        if tx.nonce >= finalized_nonce {
+            return Ok(false);
+        }
+
+        // Unlike `check_all_sending_attempts()`, errors are propagated here since treating a mined transaction
+        // as stale is not recoverable.
+        for history_item in storage
+            .eth_sender_dal()
+            .get_tx_history_to_check(tx.id)
+            .await?
+        {
+            if self.get_tx_status(history_item.tx_hash).await?.is_some() {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
     async fn monitor_inflight_transactions_inner(
         &mut self,
         storage: &mut StorageProcessor<'_>,
@@ -449,6 +675,19 @@ impl EthTxManager {
     ) -> Result<Option<(EthTx, u32)>, ETHSenderError> {
         let inflight_txs = storage.eth_sender_dal().get_inflight_txs().await.unwrap();
         METRICS.number_of_inflight_txs.set(inflight_txs.len());
+        let now = seconds_since_epoch();
+        let health_details = EthTxManagerHealthDetails {
+            latest_l1_block: l1_block_numbers.latest,
+            inflight_tx_count: inflight_txs.len(),
+            max_txs_in_flight: self.config.max_txs_in_flight,
+            paused: self.is_paused(),
+            oldest_inflight_tx_age_sec: inflight_txs
+                .iter()
+                .map(|tx| now.saturating_sub(tx.created_at_timestamp))
+                .max(),
+        };
+        self.health_updater
+            .update(Health::from(HealthStatus::Ready).with_details(health_details));
 
         tracing::trace!(
             "Going through not confirmed txs. \
@@ -598,6 +837,7 @@ impl EthTxManager {
             } else if let Err(error) = self
                 .send_raw_transaction(
                     storage,
+                    tx.eth_tx_id,
                     tx.id,
                     RawTransactionBytes::new_unchecked(tx.signed_raw_tx.clone()),
                     l1_block_numbers.latest,
@@ -781,15 +1021,20 @@ impl EthTxManager {
     }
 
     #[tracing::instrument(skip(self, storage))]
-    async fn loop_iteration(
+    pub(super) async fn loop_iteration(
         &mut self,
         storage: &mut StorageProcessor<'_>,
         previous_block: L1BlockNumber,
     ) -> Result<L1BlockNumber, ETHSenderError> {
         let l1_block_numbers = self.get_l1_block_numbers().await?;
 
-        self.send_new_eth_txs(storage, l1_block_numbers.latest)
-            .await;
+        let is_paused = self.is_paused();
+        if is_paused {
+            tracing::debug!("Sending new eth_txs is paused by the circuit breaker");
+        } else {
+            self.send_new_eth_txs(storage, l1_block_numbers.latest)
+                .await;
+        }
 
         if l1_block_numbers.latest <= previous_block {
             // Nothing to do - no new blocks were mined.
@@ -799,6 +1044,7 @@ impl EthTxManager {
         if let Some((tx, sent_at_block)) = self
             .monitor_inflight_transactions(storage, l1_block_numbers)
             .await?
+            .filter(|_| !is_paused)
         {
             // New gas price depends on the time this tx spent in mempool.
             let time_in_mempool = l1_block_numbers.latest.0 - sent_at_block;
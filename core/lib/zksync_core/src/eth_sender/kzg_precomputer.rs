@@ -0,0 +1,234 @@
+//! Precomputation of KZG info for L1 batch pubdata, so that forming commit operations isn't dominated by KZG.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use anyhow::Context as _;
+use futures::{stream, StreamExt};
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_l1_contract_interface::i_executor::commit::kzg::{
+    pubdata_to_kzg_info, KzgInfo, KzgSettings,
+};
+use zksync_types::{
+    commitment::L1BatchWithMetadata, pubdata_da::PubdataDA, web3::signing::keccak256,
+    L1BatchNumber, ProtocolVersionId, H256,
+};
+
+use crate::eth_sender::metrics::{KzgInfoLookup, METRICS};
+
+#[derive(Debug)]
+struct CachedKzgInfo {
+    pubdata_hash: H256,
+    pubdata_da: PubdataDA,
+    kzg_info: Arc<[KzgInfo]>,
+}
+
+/// Cache of KZG info for L1 batches pending commit, keyed by the batch number. Entries are checked against
+/// the hash of the batch pubdata and its pubdata mode, so they are never used for batches that were reverted
+/// and re-sealed.
+#[derive(Debug, Clone)]
+pub struct KzgInfoCache {
+    kzg_settings: Option<Arc<KzgSettings>>,
+    /// Pubdata mode used for L1 batches without a recorded mode.
+    default_pubdata_da: PubdataDA,
+    entries: Arc<RwLock<HashMap<L1BatchNumber, CachedKzgInfo>>>,
+}
+
+impl KzgInfoCache {
+    pub fn new(kzg_settings: Option<Arc<KzgSettings>>, default_pubdata_da: PubdataDA) -> Self {
+        Self {
+            kzg_settings,
+            default_pubdata_da,
+            entries: Arc::default(),
+        }
+    }
+
+    fn requires_kzg_info(protocol_version: Option<ProtocolVersionId>) -> bool {
+        !protocol_version
+            .unwrap_or_else(ProtocolVersionId::last_potentially_undefined)
+            .is_pre_1_4_2()
+    }
+
+    /// Returns KZG info for the batch, computing and caching it if necessary. Returns `None` if the batch
+    /// doesn't have a pubdata input or doesn't commit to it using KZG.
+    pub fn get_or_compute(&self, l1_batch: &L1BatchWithMetadata) -> Option<Arc<[KzgInfo]>> {
+        let kzg_settings = self.kzg_settings.as_ref()?;
+        let pubdata = l1_batch.header.pubdata_input.as_deref()?;
+        if !Self::requires_kzg_info(l1_batch.header.protocol_version) {
+            return None;
+        }
+
+        let number = l1_batch.header.number;
+        let pubdata_hash = H256(keccak256(pubdata));
+        let pubdata_da = l1_batch
+            .header
+            .pubdata_mode
+            .unwrap_or(self.default_pubdata_da);
+        if let Some(kzg_info) = self.get(number, pubdata_hash, pubdata_da) {
+            METRICS.kzg_info_lookups[&KzgInfoLookup::Hit].inc();
+            return Some(kzg_info);
+        }
+        METRICS.kzg_info_lookups[&KzgInfoLookup::Miss].inc();
+        Some(self.compute(kzg_settings, number, pubdata_hash, pubdata_da, pubdata))
+    }
+
+    /// Computes and caches KZG info for the batch pubdata unless it's already cached.
+    /// Returns whether KZG info was computed.
+    fn precompute(
+        &self,
+        number: L1BatchNumber,
+        protocol_version: Option<ProtocolVersionId>,
+        pubdata_mode: Option<PubdataDA>,
+        pubdata: &[u8],
+    ) -> bool {
+        let Some(kzg_settings) = &self.kzg_settings else {
+            return false;
+        };
+        if !Self::requires_kzg_info(protocol_version) {
+            return false;
+        }
+        let pubdata_hash = H256(keccak256(pubdata));
+        let pubdata_da = pubdata_mode.unwrap_or(self.default_pubdata_da);
+        if self.get(number, pubdata_hash, pubdata_da).is_some() {
+            return false;
+        }
+        self.compute(kzg_settings, number, pubdata_hash, pubdata_da, pubdata);
+        true
+    }
+
+    fn get(
+        &self,
+        number: L1BatchNumber,
+        pubdata_hash: H256,
+        pubdata_da: PubdataDA,
+    ) -> Option<Arc<[KzgInfo]>> {
+        let entries = self.entries.read().expect("KZG info cache is poisoned");
+        let entry = entries.get(&number)?;
+        (entry.pubdata_hash == pubdata_hash && entry.pubdata_da == pubdata_da)
+            .then(|| entry.kzg_info.clone())
+    }
+
+    fn compute(
+        &self,
+        kzg_settings: &KzgSettings,
+        number: L1BatchNumber,
+        pubdata_hash: H256,
+        pubdata_da: PubdataDA,
+        pubdata: &[u8],
+    ) -> Arc<[KzgInfo]> {
+        let latency = METRICS.kzg_info_computation_latency.start();
+        let kzg_info: Arc<[KzgInfo]> =
+            pubdata_to_kzg_info(kzg_settings, pubdata, pubdata_da).into();
+        latency.observe();
+
+        let entry = CachedKzgInfo {
+            pubdata_hash,
+            pubdata_da,
+            kzg_info: kzg_info.clone(),
+        };
+        self.entries
+            .write()
+            .expect("KZG info cache is poisoned")
+            .insert(number, entry);
+        kzg_info
+    }
+
+    /// Removes entries for L1 batches up to and including the specified one.
+    pub fn prune(&self, last_committed_l1_batch: L1BatchNumber) {
+        self.entries
+            .write()
+            .expect("KZG info cache is poisoned")
+            .retain(|&number, _| number > last_committed_l1_batch);
+    }
+
+    #[cfg(test)]
+    pub(super) fn len(&self) -> usize {
+        self.entries
+            .read()
+            .expect("KZG info cache is poisoned")
+            .len()
+    }
+}
+
+/// Computes KZG info for L1 batches as soon as their pubdata is available (i.e., before the batches get metadata
+/// and become ready for commit) on a bounded number of blocking threads, and puts it into a [`KzgInfoCache`].
+#[derive(Debug)]
+pub struct KzgPrecomputer {
+    cache: KzgInfoCache,
+    worker_count: usize,
+    l1_batch_limit: usize,
+    poll_interval: Duration,
+}
+
+impl KzgPrecomputer {
+    pub fn new(
+        cache: KzgInfoCache,
+        worker_count: usize,
+        l1_batch_limit: usize,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            cache,
+            worker_count: worker_count.max(1),
+            l1_batch_limit,
+            poll_interval,
+        }
+    }
+
+    /// Precomputes KZG info for L1 batches pending commit. Returns the number of batches KZG info was computed for.
+    pub(super) async fn precompute_pending(&self, pool: &ConnectionPool) -> anyhow::Result<usize> {
+        let mut storage = pool.access_storage_tagged("eth_sender").await?;
+        let pending_pubdata = storage
+            .blocks_dal()
+            .get_pubdata_inputs_pending_commit(self.l1_batch_limit)
+            .await?;
+        drop(storage);
+
+        let computations =
+            pending_pubdata
+                .into_iter()
+                .map(|(number, protocol_version, pubdata_mode, pubdata)| {
+                    let cache = self.cache.clone();
+                    tokio::task::spawn_blocking(move || {
+                        cache.precompute(number, protocol_version, pubdata_mode, &pubdata)
+                    })
+                });
+        // `spawn_blocking()` is lazily invoked by the stream, so at most `worker_count` computations
+        // run concurrently.
+        let mut computations = stream::iter(computations).buffer_unordered(self.worker_count);
+        let mut computed_count = 0;
+        while let Some(computed) = computations.next().await {
+            if computed.context("KZG info computation panicked")? {
+                computed_count += 1;
+            }
+        }
+        Ok(computed_count)
+    }
+
+    pub async fn run(
+        self,
+        pool: ConnectionPool,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::info!("Stop signal received, KZG precomputer is shutting down");
+                break;
+            }
+
+            let computed_count = self.precompute_pending(&pool).await?;
+            if computed_count > 0 {
+                tracing::debug!("Precomputed KZG info for {computed_count} L1 batches");
+                continue;
+            }
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(())
+    }
+}
@@ -31,6 +31,26 @@ pub(super) enum BlockNumberVariant {
     Safe,
 }
 
+/// Mempool an L1 transaction attempt was sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "mempool", rename_all = "snake_case")]
+pub(super) enum TxMempool {
+    /// Private transaction relay.
+    Private,
+    /// Public mempool after the private relay timeout has elapsed.
+    PublicAfterTimeout,
+    /// Public mempool after the private relay request has failed.
+    PublicAfterRelayError,
+}
+
+/// Result of looking up KZG info for an L1 batch in the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum KzgInfoLookup {
+    Hit,
+    Miss,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet, EncodeLabelValue)]
 #[metrics(label = "type")]
 pub(super) struct ActionTypeLabel(AggregatedActionType);
@@ -84,6 +104,9 @@ pub(super) struct EthSenderMetrics {
     pub block_range_size: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of transactions resent by the Ethereum sender.
     pub transaction_resent: Counter,
+    /// Number of transactions removed by the Ethereum sender during nonce reconciliation, so that
+    /// the corresponding aggregated operations are re-created with up-to-date nonces.
+    pub transactions_requeued: Counter,
     #[metrics(buckets = FEE_BUCKETS)]
     pub used_base_fee_per_gas: Histogram<u64>,
     #[metrics(buckets = FEE_BUCKETS)]
@@ -100,6 +123,17 @@ pub(super) struct EthSenderMetrics {
     pub l1_blocks_waited_in_mempool: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of L1 batches aggregated for publishing with a specific reason.
     pub block_aggregation_reason: Family<AggregationReasonLabels, Counter>,
+    /// Whether operations of a certain type are currently withheld because of high L1 fees (1 if withheld, 0 otherwise).
+    pub send_gate_withheld: Family<ActionTypeLabel, Gauge<u64>>,
+    /// Number of operations sent despite high L1 fees because they were withheld for too long.
+    pub send_gate_overrides: Family<ActionTypeLabel, Counter>,
+    /// Number of transaction attempts sent with the private transaction relay configured, split by the mempool.
+    pub relayed_attempts: Family<TxMempool, Counter>,
+    /// Latency of computing KZG info for the pubdata of a single L1 batch.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub kzg_info_computation_latency: Histogram<Duration>,
+    /// Number of KZG info lookups when forming commit operations, split by whether the info was cached.
+    pub kzg_info_lookups: Family<KzgInfoLookup, Counter>,
 }
 
 impl EthSenderMetrics {
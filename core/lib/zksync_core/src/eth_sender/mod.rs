@@ -3,14 +3,22 @@ mod aggregator;
 mod error;
 mod eth_tx_aggregator;
 mod eth_tx_manager;
+mod kzg_precomputer;
 mod metrics;
 mod publish_criterion;
+mod send_gate;
+mod signer_health;
 mod zksync_functions;
 
 #[cfg(test)]
 mod tests;
 
 pub use self::{
-    aggregator::Aggregator, error::ETHSenderError, eth_tx_aggregator::EthTxAggregator,
+    aggregator::Aggregator,
+    error::ETHSenderError,
+    eth_tx_aggregator::EthTxAggregator,
     eth_tx_manager::EthTxManager,
+    kzg_precomputer::{KzgInfoCache, KzgPrecomputer},
+    send_gate::L1FeeSendGate,
+    signer_health::RemoteSignerHealthCheck,
 };
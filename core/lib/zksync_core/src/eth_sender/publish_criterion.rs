@@ -12,8 +12,8 @@ use zksync_types::{
     pubdata_da::PubdataDA, L1BatchNumber,
 };
 
-use super::metrics::METRICS;
-use crate::gas_tracker::agg_l1_batch_base_cost;
+use super::{aggregator::l1_batch_pubdata_da, kzg_precomputer::KzgInfoCache, metrics::METRICS};
+use crate::{gas_tracker::agg_l1_batch_base_cost, l1_gas_price::L1TxParamsProvider};
 
 #[async_trait]
 pub trait L1BatchPublishCriterion: fmt::Debug + Send + Sync {
@@ -198,12 +198,93 @@ impl L1BatchPublishCriterion for GasCriterion {
     }
 }
 
+/// Criterion dynamically sizing aggregation windows based on L1 gas prices. Publishes all ready L1 batches
+/// once their predicted cost per batch at the current L1 base fee doesn't exceed the cost per batch
+/// of a full window (i.e., `limit` L1 batches) at the median L1 base fee. Thus, small ranges are published
+/// when gas is cheap, while with expensive gas, the sender waits for larger ranges.
+///
+/// This criterion never delays publishing on its own, so it should be combined with [`NumberCriterion`]
+/// and [`TimestampDeadlineCriterion`].
+#[derive(Debug)]
+pub struct GasPriceCriterion {
+    pub op: AggregatedActionType,
+    /// Maximum number of L1 batches to be packed together.
+    pub limit: u32,
+    pub l1_tx_params: Arc<dyn L1TxParamsProvider>,
+}
+
+impl GasPriceCriterion {
+    /// Checks whether publishing `batch_count` L1 batches with the specified total predicted gas
+    /// is at least as cost-efficient as publishing a full window at the median base fee.
+    fn is_cost_efficient(
+        &self,
+        batch_count: u32,
+        batches_gas: u32,
+        current_base_fee: u64,
+        median_base_fee: u64,
+    ) -> bool {
+        let base_cost = f64::from(agg_l1_batch_base_cost(self.op));
+        let batch_count = f64::from(batch_count);
+        let avg_batch_gas = f64::from(batches_gas) / batch_count;
+        let limit = f64::from(self.limit).max(batch_count);
+
+        let current_cost_per_batch =
+            current_base_fee as f64 * (base_cost / batch_count + avg_batch_gas);
+        let full_window_cost_per_batch =
+            median_base_fee as f64 * (base_cost / limit + avg_batch_gas);
+        current_cost_per_batch <= full_window_cost_per_batch
+    }
+}
+
+#[async_trait]
+impl L1BatchPublishCriterion for GasPriceCriterion {
+    fn name(&self) -> &'static str {
+        "gas_price"
+    }
+
+    async fn last_l1_batch_to_publish(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        consecutive_l1_batches: &[L1BatchWithMetadata],
+        _last_sealed_l1_batch: L1BatchNumber,
+    ) -> Option<L1BatchNumber> {
+        let first_l1_batch_number = consecutive_l1_batches.first()?.header.number;
+        let last_l1_batch_number = consecutive_l1_batches.last()?.header.number;
+        let current_base_fee = self.l1_tx_params.get_last_block_base_fee();
+        let median_base_fee = self.l1_tx_params.get_median_base_fee();
+        if current_base_fee == 0 || median_base_fee == 0 {
+            return None; // No information about L1 gas prices yet
+        }
+
+        let batch_count = last_l1_batch_number.0 - first_l1_batch_number.0 + 1;
+        let batches_gas = storage
+            .blocks_dal()
+            .get_l1_batches_predicted_gas(first_l1_batch_number..=last_l1_batch_number, self.op)
+            .await
+            .unwrap();
+        if !self.is_cost_efficient(batch_count, batches_gas, current_base_fee, median_base_fee) {
+            return None;
+        }
+
+        tracing::debug!(
+            "`gas_price` publish criterion (base_fee={current_base_fee}, median={median_base_fee}) \
+             triggered for op {} with L1 batch range {:?}",
+            self.op,
+            first_l1_batch_number.0..=last_l1_batch_number.0
+        );
+        METRICS.block_aggregation_reason[&(self.op, "gas_price").into()].inc();
+        Some(last_l1_batch_number)
+    }
+}
+
 #[derive(Debug)]
 pub struct DataSizeCriterion {
     pub op: AggregatedActionType,
     pub data_limit: usize,
-    pub pubdata_da: PubdataDA,
+    /// Pubdata mode used for L1 batches without a recorded mode.
+    pub default_pubdata_da: PubdataDA,
     pub kzg_settings: Option<Arc<KzgSettings>>,
+    pub kzg_info_cache: KzgInfoCache,
 }
 
 #[async_trait]
@@ -223,14 +304,12 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
 
         for (index, l1_batch) in consecutive_l1_batches.iter().enumerate() {
             // TODO (PLA-771): Make sure that this estimation is correct.
+            let pubdata_da = l1_batch_pubdata_da(l1_batch, self.default_pubdata_da);
+            let commit_batch_info =
+                CommitBatchInfo::new(l1_batch, pubdata_da, self.kzg_settings.clone())
+                    .with_precomputed_kzg_info(self.kzg_info_cache.get_or_compute(l1_batch));
             let l1_commit_data_size =
-                ethabi::encode(&[ethabi::Token::Array(vec![CommitBatchInfo::new(
-                    l1_batch,
-                    self.pubdata_da,
-                    self.kzg_settings.clone(),
-                )
-                .into_token()])])
-                .len();
+                ethabi::encode(&[ethabi::Token::Array(vec![commit_batch_info.into_token()])]).len();
             if data_size_left < l1_commit_data_size {
                 if index == 0 {
                     panic!(
@@ -256,3 +335,62 @@ impl L1BatchPublishCriterion for DataSizeCriterion {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockL1TxParams;
+
+    impl L1TxParamsProvider for MockL1TxParams {
+        fn get_base_fee(&self, _time_in_mempool: u32) -> u64 {
+            unreachable!()
+        }
+
+        fn get_blob_base_fee(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn get_priority_fee(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn get_next_block_minimal_base_fee(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn get_last_block_base_fee(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn get_median_base_fee(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn get_last_block_blob_base_fee(&self) -> u64 {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn gas_price_criterion_efficiency() {
+        const BATCH_GAS: u32 = 100_000;
+
+        let criterion = GasPriceCriterion {
+            op: AggregatedActionType::Execute,
+            limit: 10,
+            l1_tx_params: Arc::new(MockL1TxParams),
+        };
+        // With a typical gas price, only full windows are efficient.
+        assert!(!criterion.is_cost_efficient(1, BATCH_GAS, 10, 10));
+        assert!(!criterion.is_cost_efficient(9, 9 * BATCH_GAS, 10, 10));
+        assert!(criterion.is_cost_efficient(10, 10 * BATCH_GAS, 10, 10));
+        // With cheap gas, even small windows are efficient.
+        assert!(criterion.is_cost_efficient(1, BATCH_GAS, 3, 10));
+        assert!(!criterion.is_cost_efficient(1, BATCH_GAS, 5, 10));
+        assert!(criterion.is_cost_efficient(3, 3 * BATCH_GAS, 5, 10));
+        // With expensive gas, no windows are efficient.
+        assert!(!criterion.is_cost_efficient(10, 10 * BATCH_GAS, 20, 10));
+    }
+}
@@ -0,0 +1,268 @@
+//! Gating of commit and execute operations by L1 fees.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use zksync_config::configs::eth_sender::SenderConfig;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::aggregated_operations::AggregatedActionType;
+
+use super::metrics::METRICS;
+use crate::l1_gas_price::L1TxParamsProvider;
+
+const GWEI: u64 = 1_000_000_000;
+
+#[derive(Debug, Default, Serialize)]
+struct SendGateDetails {
+    base_fee: u64,
+    blob_base_fee: u64,
+    commit_withheld: bool,
+    execute_withheld: bool,
+}
+
+/// Withholds commit and execute operations while L1 fees exceed the configured thresholds, so that the operator
+/// doesn't overpay during L1 gas price spikes. Proofs are never withheld since they are cheap compared to other operations
+/// and block execution otherwise.
+///
+/// An operation is withheld no longer than the configured max delay since it was first withheld.
+#[derive(Debug)]
+pub struct L1FeeSendGate {
+    l1_tx_params: Arc<dyn L1TxParamsProvider>,
+    max_base_fee: Option<u64>,
+    max_blob_base_fee: Option<u64>,
+    max_delay: Option<Duration>,
+    commit_withheld_since: Option<Instant>,
+    execute_withheld_since: Option<Instant>,
+    details: SendGateDetails,
+    health_updater: HealthUpdater,
+}
+
+impl L1FeeSendGate {
+    pub fn new(
+        config: &SenderConfig,
+        l1_tx_params: Arc<dyn L1TxParamsProvider>,
+    ) -> anyhow::Result<Self> {
+        let (_, health_updater) = ReactiveHealthCheck::new("eth_sender_send_gate");
+        health_updater.update(HealthStatus::Ready.into());
+        Ok(Self {
+            l1_tx_params,
+            max_base_fee: Self::gwei_to_wei(config.send_gate_max_base_fee_in_gwei)
+                .context("send_gate_max_base_fee_in_gwei")?,
+            max_blob_base_fee: Self::gwei_to_wei(config.send_gate_max_blob_base_fee_in_gwei)
+                .context("send_gate_max_blob_base_fee_in_gwei")?,
+            max_delay: config.send_gate_max_delay(),
+            commit_withheld_since: None,
+            execute_withheld_since: None,
+            details: SendGateDetails::default(),
+            health_updater,
+        })
+    }
+
+    fn gwei_to_wei(fee_in_gwei: Option<u64>) -> anyhow::Result<Option<u64>> {
+        fee_in_gwei
+            .map(|fee| {
+                fee.checked_mul(GWEI)
+                    .with_context(|| format!("fee {fee} gwei overflows u64 when converted to wei"))
+            })
+            .transpose()
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Checks whether an operation may be sent now. `uses_blobs` specifies whether the operation publishes
+    /// data in blobs.
+    pub(super) fn may_send(&mut self, op: AggregatedActionType, uses_blobs: bool) -> bool {
+        self.may_send_at(op, uses_blobs, Instant::now())
+    }
+
+    fn may_send_at(&mut self, op: AggregatedActionType, uses_blobs: bool, now: Instant) -> bool {
+        let (withheld, withheld_since) = match op {
+            AggregatedActionType::Commit => (
+                &mut self.details.commit_withheld,
+                &mut self.commit_withheld_since,
+            ),
+            AggregatedActionType::Execute => (
+                &mut self.details.execute_withheld,
+                &mut self.execute_withheld_since,
+            ),
+            AggregatedActionType::PublishProofOnchain => return true,
+        };
+
+        let base_fee = self.l1_tx_params.get_last_block_base_fee();
+        let blob_base_fee = self.l1_tx_params.get_last_block_blob_base_fee();
+        self.details.base_fee = base_fee;
+        self.details.blob_base_fee = blob_base_fee;
+
+        let base_fee_exceeded = self.max_base_fee.map_or(false, |max| base_fee > max);
+        let blob_base_fee_exceeded = uses_blobs
+            && self
+                .max_blob_base_fee
+                .map_or(false, |max| blob_base_fee > max);
+        let mut may_send = !base_fee_exceeded && !blob_base_fee_exceeded;
+        if !may_send {
+            let age = now.saturating_duration_since(*withheld_since.get_or_insert(now));
+            if self.max_delay.map_or(false, |max_delay| age >= max_delay) {
+                tracing::info!(
+                    "Sending {op} operation despite high L1 fees (base_fee={base_fee}, blob_base_fee={blob_base_fee}) \
+                     since it was withheld for {age:?}"
+                );
+                METRICS.send_gate_overrides[&op.into()].inc();
+                may_send = true;
+            } else if !*withheld {
+                tracing::info!(
+                    "Withholding {op} operations because of high L1 fees \
+                     (base_fee={base_fee}, blob_base_fee={blob_base_fee})"
+                );
+            }
+        }
+
+        if may_send {
+            *withheld_since = None;
+        }
+        *withheld = !may_send;
+        METRICS.send_gate_withheld[&op.into()].set(u64::from(!may_send));
+        self.update_health();
+        may_send
+    }
+
+    fn update_health(&self) {
+        let status = if self.details.commit_withheld || self.details.execute_withheld {
+            HealthStatus::Affected
+        } else {
+            HealthStatus::Ready
+        };
+        let health = Health::from(status).with_details(&self.details);
+        self.health_updater.update(health);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_config::ETHSenderConfig;
+    use zksync_health_check::CheckHealth;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct MockL1TxParams {
+        base_fee: u64,
+        blob_base_fee: u64,
+    }
+
+    impl L1TxParamsProvider for MockL1TxParams {
+        fn get_base_fee(&self, _time_in_mempool: u32) -> u64 {
+            unreachable!()
+        }
+
+        fn get_blob_base_fee(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn get_priority_fee(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn get_next_block_minimal_base_fee(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn get_last_block_base_fee(&self) -> u64 {
+            self.base_fee
+        }
+
+        fn get_median_base_fee(&self) -> u64 {
+            unreachable!()
+        }
+
+        fn get_last_block_blob_base_fee(&self) -> u64 {
+            self.blob_base_fee
+        }
+    }
+
+    fn create_gate(
+        base_fee: u64,
+        blob_base_fee: u64,
+        max_delay_seconds: Option<u64>,
+    ) -> L1FeeSendGate {
+        let config = SenderConfig {
+            send_gate_max_base_fee_in_gwei: Some(100),
+            send_gate_max_blob_base_fee_in_gwei: Some(10),
+            send_gate_max_delay_seconds: max_delay_seconds,
+            ..ETHSenderConfig::for_tests().sender
+        };
+        let l1_tx_params = MockL1TxParams {
+            base_fee: base_fee * GWEI,
+            blob_base_fee: blob_base_fee * GWEI,
+        };
+        L1FeeSendGate::new(&config, Arc::new(l1_tx_params)).unwrap()
+    }
+
+    #[tokio::test]
+    async fn send_gate_with_low_fees() {
+        let mut gate = create_gate(100, 10, None);
+        assert!(gate.may_send(AggregatedActionType::Commit, true));
+        assert!(gate.may_send(AggregatedActionType::Execute, false));
+
+        let health = gate.health_check().check_health().await;
+        assert_eq!(health.status(), HealthStatus::Ready);
+    }
+
+    #[tokio::test]
+    async fn send_gate_with_high_base_fee() {
+        let mut gate = create_gate(101, 1, None);
+        assert!(!gate.may_send(AggregatedActionType::Commit, false));
+        assert!(!gate.may_send(AggregatedActionType::Execute, false));
+        assert!(gate.may_send(AggregatedActionType::PublishProofOnchain, false));
+
+        let health = gate.health_check().check_health().await;
+        assert_eq!(health.status(), HealthStatus::Affected);
+    }
+
+    #[test]
+    fn send_gate_with_high_blob_base_fee() {
+        let mut gate = create_gate(1, 11, None);
+        assert!(!gate.may_send(AggregatedActionType::Commit, true));
+        // Blob fees don't influence operations without blobs.
+        assert!(gate.may_send(AggregatedActionType::Commit, false));
+        assert!(gate.may_send(AggregatedActionType::Execute, false));
+    }
+
+    #[test]
+    fn send_gate_max_delay_override() {
+        let mut gate = create_gate(1_000, 1_000, Some(3_600));
+        let start = Instant::now();
+        assert!(!gate.may_send_at(AggregatedActionType::Commit, true, start));
+        let later = start + Duration::from_secs(3_599);
+        assert!(!gate.may_send_at(AggregatedActionType::Commit, true, later));
+        // Execute operations are tracked separately; their delay starts on their first deferral.
+        assert!(!gate.may_send_at(AggregatedActionType::Execute, false, later));
+        let later = start + Duration::from_secs(3_600);
+        assert!(gate.may_send_at(AggregatedActionType::Commit, true, later));
+        assert!(!gate.may_send_at(AggregatedActionType::Execute, false, later));
+
+        // After an operation is sent, the delay is measured from the next deferral.
+        assert!(!gate.may_send_at(AggregatedActionType::Commit, true, later));
+        let later = later + Duration::from_secs(3_600);
+        assert!(gate.may_send_at(AggregatedActionType::Commit, true, later));
+        assert!(gate.may_send_at(AggregatedActionType::Execute, false, later));
+    }
+
+    #[test]
+    fn send_gate_with_overflowing_fee() {
+        let config = SenderConfig {
+            send_gate_max_base_fee_in_gwei: Some(u64::MAX / 10),
+            ..ETHSenderConfig::for_tests().sender
+        };
+        let l1_tx_params = MockL1TxParams {
+            base_fee: 0,
+            blob_base_fee: 0,
+        };
+        L1FeeSendGate::new(&config, Arc::new(l1_tx_params)).unwrap_err();
+    }
+}
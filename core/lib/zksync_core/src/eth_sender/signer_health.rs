@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use zksync_eth_signer::RemoteSigner;
+use zksync_health_check::{CheckHealth, Health, HealthStatus};
+
+/// Health check for the external service signing operator transactions. The check is performed on each
+/// health request, so that the signer unavailability is detected before the eth sender needs to sign a transaction.
+#[derive(Debug)]
+pub struct RemoteSignerHealthCheck {
+    signer: RemoteSigner,
+}
+
+impl RemoteSignerHealthCheck {
+    pub fn new(signer: RemoteSigner) -> Self {
+        Self { signer }
+    }
+}
+
+#[async_trait]
+impl CheckHealth for RemoteSignerHealthCheck {
+    fn name(&self) -> &'static str {
+        "remote_signer"
+    }
+
+    async fn check_health(&self) -> Health {
+        match self.signer.check_availability().await {
+            Ok(()) => HealthStatus::Ready.into(),
+            Err(err) => {
+                tracing::warn!("Remote signer is unavailable: {err}");
+                let details = serde_json::json!({
+                    "error": err.to_string(),
+                });
+                Health::from(HealthStatus::NotReady).with_details(details)
+            }
+        }
+    }
+}
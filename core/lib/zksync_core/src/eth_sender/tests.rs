@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use assert_matches::assert_matches;
 use once_cell::sync::Lazy;
@@ -12,8 +12,9 @@ use zksync_config::{
 };
 use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::{clients::MockEthereum, EthInterface};
+use zksync_health_check::{CheckHealth, HealthStatus};
 use zksync_l1_contract_interface::i_executor::{
-    commit::kzg::KzgSettings,
+    commit::kzg::{pubdata_to_kzg_info, KzgSettings},
     methods::{CommitBatches, ExecuteBatches, ProveBatches},
 };
 use zksync_object_store::ObjectStoreFactory;
@@ -29,8 +30,9 @@ use zksync_types::{
 
 use crate::{
     eth_sender::{
-        aggregated_operations::AggregatedOperation, eth_tx_manager::L1BlockNumbers, Aggregator,
-        ETHSenderError, EthTxAggregator, EthTxManager,
+        aggregated_operations::AggregatedOperation, aggregator::retain_same_pubdata_mode,
+        eth_tx_manager::L1BlockNumbers, Aggregator, ETHSenderError, EthTxAggregator, EthTxManager,
+        KzgInfoCache, KzgPrecomputer,
     },
     l1_gas_price::GasAdjuster,
     utils::testonly::{create_l1_batch, l1_batch_metadata_to_commitment_artifacts},
@@ -117,6 +119,7 @@ impl EthSenderTester {
                 aggregator_operate_4844_mode,
                 PubdataDA::Calldata,
                 Some(kzg_settings.clone()),
+                None,
             ),
             gateway.clone(),
             // zkSync contract address
@@ -129,12 +132,15 @@ impl EthSenderTester {
         )
         .await;
 
-        let manager = EthTxManager::new(
+        let mut manager = EthTxManager::new(
             eth_sender_config.sender,
             gas_adjuster.clone(),
             gateway.clone(),
             None,
         );
+        // With out-of-order confirmations, the mock reports operator nonces that cannot occur on L1
+        // (e.g., a nonce consumed while a transaction with a lower nonce is not mined).
+        manager.reconcile_nonces = !non_ordering_confirmations;
         Self {
             gateway,
             manager,
@@ -239,6 +245,12 @@ async fn confirm_many(aggregator_operate_4844_mode: bool) -> anyhow::Result<()>
     // also check that we didn't try to resend it
     assert!(to_resend.is_none());
 
+    // Health details are updated before in-flight transactions are processed.
+    let health = tester.manager.health_check().check_health().await;
+    assert_eq!(health.status(), HealthStatus::Ready);
+    let health_details = serde_json::to_value(health).unwrap()["details"].clone();
+    assert_eq!(health_details["inflight_tx_count"], 5);
+
     Ok(())
 }
 
@@ -426,6 +438,98 @@ async fn dont_resend_already_mined() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn paused_manager_doesnt_send_txs() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false, false).await;
+    let (pause_sender, pause_receiver) = tokio::sync::watch::channel(true);
+    tester.manager = tester.manager.with_pause_receiver(pause_receiver);
+    tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await?;
+
+    tester
+        .manager
+        .loop_iteration(
+            &mut tester.conn.access_storage().await.unwrap(),
+            L1BlockNumber(0),
+        )
+        .await?;
+    assert_eq!(tester.gateway.sent_tx_count(), 0);
+    let health = tester.manager.health_check().check_health().await;
+    let health_details = serde_json::to_value(health).unwrap()["details"].clone();
+    assert_eq!(health_details["paused"], true);
+
+    pause_sender.send_replace(false);
+    tester
+        .manager
+        .loop_iteration(
+            &mut tester.conn.access_storage().await.unwrap(),
+            L1BlockNumber(0),
+        )
+        .await?;
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn txs_are_sent_to_private_relay_before_fallback_timeout() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false, false).await;
+    let relay = Arc::new(MockEthereum::default());
+    // With zero timeout, only the first attempt for each transaction is sent to the relay.
+    tester.manager = tester.manager.with_tx_relay(relay.clone(), Duration::ZERO);
+    let tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await?;
+
+    let block = L1BlockNumber(tester.gateway.block_number("").await?.as_u32());
+    tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &tx,
+            0,
+            block,
+        )
+        .await?;
+    assert_eq!(relay.sent_tx_count(), 1);
+    assert_eq!(tester.gateway.sent_tx_count(), 0);
+
+    tester.gateway.advance_block_number(1);
+    tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &tx,
+            1,
+            block + 1,
+        )
+        .await?;
+    assert_eq!(relay.sent_tx_count(), 1);
+    assert_eq!(tester.gateway.sent_tx_count(), 1);
+
+    let sent_attempts = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_tx_history_to_check(tx.id)
+        .await
+        .unwrap();
+    assert_eq!(sent_attempts.len(), 2);
+    Ok(())
+}
+
 #[tokio::test]
 async fn three_scenarios() -> anyhow::Result<()> {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -540,6 +644,143 @@ async fn failed_eth_tx() {
         .unwrap();
 }
 
+// Tests that transactions are re-queued if their nonces are consumed by a transaction sent bypassing `eth_sender`.
+#[tokio::test]
+async fn txs_are_requeued_after_external_nonce_consumption() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false, false).await;
+
+    let sent_tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await
+        .unwrap();
+    tester
+        .manager
+        .send_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &sent_tx,
+            0,
+            L1BlockNumber(tester.gateway.block_number("").await.unwrap().as_u32()),
+        )
+        .await
+        .unwrap();
+    let unsent_tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await
+        .unwrap();
+    assert_eq!((sent_tx.nonce.0, unsent_tx.nonce.0), (0, 1));
+
+    // Not finalized yet, so the transactions must not be touched.
+    tester
+        .gateway
+        .execute_external_tx(EthSenderTester::WAIT_CONFIRMATIONS - 1);
+    tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.access_storage().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await?;
+    let mut storage = tester.storage().await;
+    assert!(storage
+        .eth_sender_dal()
+        .get_eth_tx(sent_tx.id)
+        .await?
+        .is_some());
+    drop(storage);
+
+    tester.gateway.advance_block_number(1);
+    tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.access_storage().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await?;
+    let mut storage = tester.storage().await;
+    assert!(storage
+        .eth_sender_dal()
+        .get_unconfirmed_txs()
+        .await?
+        .is_empty());
+    drop(storage);
+
+    // Re-created operations should use the on-chain nonce.
+    let requeued_tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.access_storage().await.unwrap(),
+            &DUMMY_OPERATION,
+            true,
+        )
+        .await
+        .unwrap();
+    assert_eq!(requeued_tx.nonce.0, 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn requeuing_txs_is_deferred_if_subsequent_tx_can_be_mined() -> anyhow::Result<()> {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut tester = EthSenderTester::new(connection_pool, vec![100; 100], false, false).await;
+
+    let mut txs = vec![];
+    for _ in 0..2 {
+        let tx = tester
+            .aggregator
+            .save_eth_tx(
+                &mut tester.conn.access_storage().await.unwrap(),
+                &DUMMY_OPERATION,
+                true,
+            )
+            .await
+            .unwrap();
+        tester
+            .manager
+            .send_eth_tx(
+                &mut tester.conn.access_storage().await.unwrap(),
+                &tx,
+                0,
+                L1BlockNumber(tester.gateway.block_number("").await.unwrap().as_u32()),
+            )
+            .await
+            .unwrap();
+        txs.push(tx);
+    }
+
+    tester
+        .gateway
+        .execute_external_tx(EthSenderTester::WAIT_CONFIRMATIONS);
+    let (to_resend, _) = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.access_storage().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await?
+        .expect("second tx should be resent");
+    assert_eq!(to_resend.id, txs[1].id);
+
+    let unconfirmed_txs = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_unconfirmed_txs()
+        .await?;
+    assert_eq!(unconfirmed_txs.len(), 2);
+    Ok(())
+}
+
 fn default_l1_batch_metadata() -> L1BatchMetadata {
     L1BatchMetadata {
         root_hash: Default::default(),
@@ -826,6 +1067,105 @@ async fn skipped_l1_batch_in_the_middle() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn precomputing_kzg_info() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(Default::default())
+        .await;
+    let mut headers = vec![];
+    for number in 1..=2 {
+        let mut header = create_l1_batch(number);
+        header.pubdata_input = Some(vec![number as u8; 1_000]);
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        headers.push(header);
+    }
+    drop(storage);
+
+    let kzg_settings = Arc::new(KzgSettings::new(&KzgConfig::for_tests().trusted_setup_path));
+    let cache = KzgInfoCache::new(Some(kzg_settings.clone()), PubdataDA::Blobs);
+    let precomputer = KzgPrecomputer::new(cache.clone(), 2, 10, Duration::from_millis(10));
+    assert_eq!(precomputer.precompute_pending(&pool).await.unwrap(), 2);
+    assert_eq!(cache.len(), 2);
+    // KZG info is already cached, so it shouldn't be recomputed.
+    assert_eq!(precomputer.precompute_pending(&pool).await.unwrap(), 0);
+
+    let l1_batch = l1_batch_with_metadata(headers[0].clone());
+    let kzg_info = cache.get_or_compute(&l1_batch).unwrap();
+    let pubdata = l1_batch.header.pubdata_input.as_ref().unwrap();
+    assert_eq!(
+        *kzg_info,
+        pubdata_to_kzg_info(&kzg_settings, pubdata, PubdataDA::Blobs)
+    );
+
+    // Cached KZG info must not be used if the batch pubdata has changed (e.g., after a revert).
+    let mut changed_l1_batch = l1_batch.clone();
+    changed_l1_batch.header.pubdata_input = Some(vec![0xff; 1_000]);
+    let changed_kzg_info = cache.get_or_compute(&changed_l1_batch).unwrap();
+    assert_ne!(changed_kzg_info, kzg_info);
+    assert_eq!(cache.len(), 2);
+
+    // ...or if the batch was re-sealed with another pubdata mode.
+    let mut calldata_l1_batch = l1_batch.clone();
+    calldata_l1_batch.header.pubdata_mode = Some(PubdataDA::Calldata);
+    let calldata_kzg_info = cache.get_or_compute(&calldata_l1_batch).unwrap();
+    assert_eq!(
+        *calldata_kzg_info,
+        pubdata_to_kzg_info(&kzg_settings, pubdata, PubdataDA::Calldata)
+    );
+    assert_eq!(cache.len(), 2);
+
+    cache.prune(L1BatchNumber(1));
+    assert_eq!(cache.len(), 1);
+    cache.prune(L1BatchNumber(2));
+    assert_eq!(cache.len(), 0);
+}
+
+#[test]
+fn commit_operations_use_single_pubdata_mode() {
+    let l1_batches_with_modes = |modes: &[Option<PubdataDA>]| -> Vec<_> {
+        modes
+            .iter()
+            .zip(1..)
+            .map(|(&mode, number)| {
+                let mut header = create_l1_batch(number);
+                header.pubdata_mode = mode;
+                l1_batch_with_metadata(header)
+            })
+            .collect()
+    };
+
+    let mut l1_batches = l1_batches_with_modes(&[None, Some(PubdataDA::Calldata), None]);
+    let mode = retain_same_pubdata_mode(&mut l1_batches, PubdataDA::Calldata);
+    assert_eq!(mode, (PubdataDA::Calldata, false));
+    assert_eq!(l1_batches.len(), 3);
+
+    let mut l1_batches = l1_batches_with_modes(&[
+        Some(PubdataDA::Calldata),
+        None,
+        Some(PubdataDA::Blobs),
+        Some(PubdataDA::Calldata),
+    ]);
+    let mode = retain_same_pubdata_mode(&mut l1_batches, PubdataDA::Calldata);
+    assert_eq!(mode, (PubdataDA::Calldata, true));
+    assert_eq!(l1_batches.len(), 2);
+
+    let mut l1_batches = l1_batches_with_modes(&[Some(PubdataDA::Blobs), None]);
+    let mode = retain_same_pubdata_mode(&mut l1_batches, PubdataDA::Calldata);
+    assert_eq!(mode, (PubdataDA::Blobs, true));
+    assert_eq!(l1_batches.len(), 1);
+
+    let mut l1_batches = vec![];
+    let mode = retain_same_pubdata_mode(&mut l1_batches, PubdataDA::Blobs);
+    assert_eq!(mode, (PubdataDA::Blobs, false));
+}
+
 #[tokio::test]
 async fn test_parse_multicall_data() {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -993,6 +1333,7 @@ async fn commit_l1_batch(
         l1_batches: vec![l1_batch_with_metadata(l1_batch)],
         pubdata_da: PubdataDA::Calldata,
         kzg_settings: Some(kzg_settings),
+        precomputed_kzg_info: HashMap::new(),
     });
     send_operation(tester, operation, confirm).await
 }
@@ -1,6 +1,6 @@
 use std::{fmt, sync::Arc};
 
-use zksync_contracts::verifier_contract;
+use zksync_contracts::{verifier_contract, zksync_contract};
 use zksync_eth_client::{CallFunctionArgs, Error as EthClientError, EthInterface};
 use zksync_l1_contract_interface::pre_boojum_verifier::old_l1_vk_commitment;
 use zksync_types::{
@@ -10,7 +10,7 @@ use zksync_types::{
         contract::tokens::Detokenize,
         types::{BlockId, BlockNumber, FilterBuilder, Log},
     },
-    Address, H256,
+    Address, H256, U256,
 };
 
 use super::metrics::METRICS;
@@ -44,6 +44,8 @@ pub trait EthClient: 'static + fmt::Debug + Send + Sync {
     async fn finalized_block_number(&self) -> Result<u64, Error>;
     /// Returns scheduler verification key hash by verifier address.
     async fn scheduler_vk_hash(&self, verifier_address: Address) -> Result<H256, Error>;
+    /// Returns the maximum L2 gas limit for priority operations set in the L1 diamond proxy.
+    async fn priority_tx_max_gas_limit(&self) -> Result<U256, Error>;
     /// Sets list of topics to return events for.
     fn set_topics(&mut self, topics: Vec<H256>);
 }
@@ -60,6 +62,7 @@ pub struct EthHttpQueryClient {
     /// Address of the `Governance` contract. It's optional because it is present only for post-boojum chains.
     /// If address is some then client will listen to events coming from it.
     governance_address: Option<Address>,
+    zksync_contract_abi: Contract,
     verifier_contract_abi: Contract,
     confirmations_for_eth_event: Option<u64>,
 }
@@ -81,6 +84,7 @@ impl EthHttpQueryClient {
             topics: Vec::new(),
             zksync_contract_addr,
             governance_address,
+            zksync_contract_abi: zksync_contract(),
             verifier_contract_abi: verifier_contract(),
             confirmations_for_eth_event,
         }
@@ -130,6 +134,13 @@ impl EthClient for EthHttpQueryClient {
         }
     }
 
+    async fn priority_tx_max_gas_limit(&self) -> Result<U256, Error> {
+        let args = CallFunctionArgs::new("getPriorityTxMaxGasLimit", ())
+            .for_contract(self.zksync_contract_addr, self.zksync_contract_abi.clone());
+        let tokens = self.client.call_contract_function(args).await?;
+        Ok(U256::from_tokens(tokens)?)
+    }
+
     async fn get_events(
         &self,
         from: BlockNumber,
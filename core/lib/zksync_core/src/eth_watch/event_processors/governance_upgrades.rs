@@ -1,16 +1,87 @@
 use std::{convert::TryFrom, time::Instant};
 
+use zksync_contracts::zksync_contract;
 use zksync_dal::StorageProcessor;
 use zksync_types::{
-    ethabi::Contract, protocol_version::GovernanceOperation, web3::types::Log, Address,
-    ProtocolUpgrade, ProtocolVersionId, H256,
+    ethabi::{self, Contract, ParamType, Token},
+    protocol_version::{Call, GovernanceOperation},
+    web3::types::Log,
+    Address, L2ChainId, ProtocolUpgrade, ProtocolVersionId, H256, U256,
 };
 
 use crate::eth_watch::{
     client::{Error, EthClient},
     event_processors::EventProcessor,
+    SharedBridgeParams,
 };
 
+/// Extracts upgrades for a single chain from calls to the shared `StateTransitionManager` contract.
+///
+/// In a shared bridge deployment, governance schedules `executeUpgrade(uint256 _chainId, DiamondCutData _diamondCut)`
+/// calls on the state transition manager for every chain, so calls for other chains must be filtered out.
+#[derive(Debug)]
+struct StateTransitionManagerUpgrades {
+    address: Address,
+    chain_id: L2ChainId,
+    execute_upgrade_selector: [u8; 4],
+    execute_upgrade_params: Vec<ParamType>,
+    diamond_execute_upgrade_selector: [u8; 4],
+}
+
+impl StateTransitionManagerUpgrades {
+    fn new(params: SharedBridgeParams) -> Self {
+        let diamond_execute_upgrade = zksync_contract()
+            .function("executeUpgrade")
+            .expect("executeUpgrade function is missing in abi")
+            .clone();
+        let diamond_cut_param = diamond_execute_upgrade
+            .inputs
+            .first()
+            .expect("executeUpgrade function has no inputs")
+            .kind
+            .clone();
+        let execute_upgrade_params = vec![ParamType::Uint(256), diamond_cut_param];
+        Self {
+            address: params.state_transition_manager_address,
+            chain_id: params.chain_id,
+            execute_upgrade_selector: ethabi::short_signature(
+                "executeUpgrade",
+                &execute_upgrade_params,
+            ),
+            execute_upgrade_params,
+            diamond_execute_upgrade_selector: diamond_execute_upgrade.short_signature(),
+        }
+    }
+
+    /// Converts a call to the state transition manager into an equivalent call to the diamond proxy,
+    /// which can be parsed as a protocol upgrade. Returns `None` if the call is not an upgrade
+    /// or targets another chain.
+    fn extract_chain_upgrade(&self, call: Call) -> Option<Call> {
+        let (selector, data) = call.data.split_at(call.data.len().min(4));
+        if selector != self.execute_upgrade_selector {
+            return None;
+        }
+        let Ok(mut tokens) = ethabi::decode(&self.execute_upgrade_params, data) else {
+            tracing::warn!("Failed to decode state transition manager upgrade call, skipping");
+            return None;
+        };
+        let diamond_cut = tokens.pop()?;
+        let chain_id = tokens.pop()?.into_uint()?;
+        if chain_id != U256::from(self.chain_id.as_u64()) {
+            tracing::debug!("Skipping upgrade for chain {chain_id} from state transition manager");
+            return None;
+        }
+
+        let data = self
+            .diamond_execute_upgrade_selector
+            .iter()
+            .copied()
+            .chain(ethabi::encode(&[diamond_cut]))
+            .collect();
+        Some(Call { data, ..call })
+    }
+}
+
 /// Listens to operation events coming from the governance contract and saves new protocol upgrade proposals to the database.
 #[derive(Debug)]
 pub struct GovernanceUpgradesEventProcessor {
@@ -18,6 +89,8 @@ pub struct GovernanceUpgradesEventProcessor {
     /// Last protocol version seen. Used to skip events for already known upgrade proposals.
     last_seen_version_id: ProtocolVersionId,
     upgrade_proposal_signature: H256,
+    /// Present if the chain is a part of a shared bridge deployment.
+    state_transition_manager: Option<StateTransitionManagerUpgrades>,
 }
 
 impl GovernanceUpgradesEventProcessor {
@@ -25,6 +98,7 @@ impl GovernanceUpgradesEventProcessor {
         diamond_proxy_address: Address,
         last_seen_version_id: ProtocolVersionId,
         governance_contract: &Contract,
+        shared_bridge: Option<SharedBridgeParams>,
     ) -> Self {
         Self {
             diamond_proxy_address,
@@ -33,6 +107,20 @@ impl GovernanceUpgradesEventProcessor {
                 .event("TransparentOperationScheduled")
                 .expect("TransparentOperationScheduled event is missing in abi")
                 .signature(),
+            state_transition_manager: shared_bridge.map(StateTransitionManagerUpgrades::new),
+        }
+    }
+
+    /// Returns a call that can be parsed as a protocol upgrade for this chain, if any.
+    fn upgrade_call(&self, call: Call) -> Option<Call> {
+        if call.target == self.diamond_proxy_address {
+            return Some(call);
+        }
+        let stm = self.state_transition_manager.as_ref()?;
+        if call.target == stm.address {
+            stm.extract_chain_upgrade(call)
+        } else {
+            None
         }
     }
 }
@@ -52,11 +140,12 @@ impl EventProcessor for GovernanceUpgradesEventProcessor {
         {
             let governance_operation = GovernanceOperation::try_from(event)
                 .map_err(|err| Error::LogParse(format!("{:?}", err)))?;
-            // Some calls can target other contracts than Diamond proxy, skip them.
+            // Some calls can target other contracts than Diamond proxy (or other chains
+            // in a shared bridge deployment), skip them.
             for call in governance_operation
                 .calls
                 .into_iter()
-                .filter(|call| call.target == self.diamond_proxy_address)
+                .filter_map(|call| self.upgrade_call(call))
             {
                 // We might not get an upgrade operation here, but something else instead
                 // (e.g. `acceptGovernor` call), so if parsing doesn't work, just skip the call.
@@ -1,14 +1,17 @@
-use std::convert::TryFrom;
+use std::{
+    convert::TryFrom,
+    time::{Duration, Instant},
+};
 
 use zksync_contracts::zksync_contract;
 use zksync_dal::StorageProcessor;
-use zksync_types::{l1::L1Tx, web3::types::Log, PriorityOpId, H256};
+use zksync_types::{l1::L1Tx, web3::types::Log, PriorityOpId, H256, U256};
 
 use crate::{
     eth_watch::{
         client::{Error, EthClient},
         event_processors::EventProcessor,
-        metrics::{PollStage, METRICS},
+        metrics::{InvalidPriorityOpReason, PollStage, METRICS},
     },
     metrics::{TxStage, APP_METRICS},
 };
@@ -18,9 +21,14 @@ use crate::{
 pub struct PriorityOpsEventProcessor {
     next_expected_priority_id: PriorityOpId,
     new_priority_request_signature: H256,
+    /// Cached maximum gas limit for priority operations together with the time it was fetched from L1.
+    cached_max_gas_limit: Option<(U256, Instant)>,
 }
 
 impl PriorityOpsEventProcessor {
+    /// The maximum gas limit only changes on protocol upgrades, so there's no need to fetch it on each poll.
+    const MAX_GAS_LIMIT_CACHE_TTL: Duration = Duration::from_secs(600);
+
     pub fn new(next_expected_priority_id: PriorityOpId) -> Self {
         Self {
             next_expected_priority_id,
@@ -28,6 +36,28 @@ impl PriorityOpsEventProcessor {
                 .event("NewPriorityRequest")
                 .expect("NewPriorityRequest event is missing in abi")
                 .signature(),
+            cached_max_gas_limit: None,
+        }
+    }
+
+    /// Returns the maximum gas limit for priority operations. If fetching it from L1 fails, falls back
+    /// to the cached value (even if it's expired); returns `None` if there is no cached value.
+    async fn priority_tx_max_gas_limit(&mut self, client: &dyn EthClient) -> Option<U256> {
+        if let Some((max_gas_limit, fetched_at)) = self.cached_max_gas_limit {
+            if fetched_at.elapsed() < Self::MAX_GAS_LIMIT_CACHE_TTL {
+                return Some(max_gas_limit);
+            }
+        }
+        match client.priority_tx_max_gas_limit().await {
+            Ok(max_gas_limit) => {
+                self.cached_max_gas_limit = Some((max_gas_limit, Instant::now()));
+                Some(max_gas_limit)
+            }
+            Err(err) => {
+                tracing::warn!("Failed fetching maximum gas limit for priority operations: {err}");
+                self.cached_max_gas_limit
+                    .map(|(max_gas_limit, _)| max_gas_limit)
+            }
         }
     }
 }
@@ -37,7 +67,7 @@ impl EventProcessor for PriorityOpsEventProcessor {
     async fn process_events(
         &mut self,
         storage: &mut StorageProcessor<'_>,
-        _client: &dyn EthClient,
+        client: &dyn EthClient,
         events: Vec<Log>,
     ) -> Result<(), Error> {
         let mut priority_ops = Vec::new();
@@ -84,6 +114,24 @@ impl EventProcessor for PriorityOpsEventProcessor {
             "priority transaction serial id mismatch"
         );
 
+        // Operations with invalid gas limits are still persisted: skipping them would create a gap
+        // in the priority queue, which cannot be processed. Such operations are only reported here;
+        // they are executed by the bootloader as usual, which results in a failed transaction
+        // if the gas limit doesn't cover execution costs. For the same reason, validation is skipped
+        // if the maximum gas limit cannot be obtained.
+        if let Some(max_gas_limit) = self.priority_tx_max_gas_limit(client).await {
+            for new_op in &new_ops {
+                if let Err(err) = new_op.validate_gas_limit(max_gas_limit) {
+                    tracing::warn!(
+                        "Priority operation #{} ({:?}) has invalid gas limit: {err}; its execution is expected to fail",
+                        new_op.serial_id(),
+                        new_op.hash()
+                    );
+                    METRICS.invalid_priority_ops[&InvalidPriorityOpReason::from(&err)].inc();
+                }
+            }
+        }
+
         let stage_latency = METRICS.poll_eth_node[&PollStage::PersistL1Txs].start();
         APP_METRICS.processed_txs[&TxStage::added_to_mempool()].inc();
         APP_METRICS.processed_l1_txs[&TxStage::added_to_mempool()].inc();
@@ -2,7 +2,9 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics};
+use serde::Serialize;
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Metrics, Unit};
+use zksync_types::l1::error::L1TxGasLimitError;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
@@ -12,6 +14,40 @@ pub(super) enum PollStage {
     PersistUpgrades,
 }
 
+/// Outcome of a protocol upgrade dry run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, EncodeLabelValue, EncodeLabelSet)]
+#[serde(rename_all = "snake_case")]
+#[metrics(label = "outcome", rename_all = "snake_case")]
+pub(super) enum UpgradeDryRunOutcome {
+    /// Upgrade transaction was successfully executed.
+    Succeeded,
+    /// Upgrade has no upgrade transaction, so there is nothing to execute.
+    NoUpgradeTx,
+    /// Base system contracts for the new protocol version are not present in the storage.
+    MissingSystemContracts,
+    /// Upgrade transaction was reverted or halted.
+    Failed,
+}
+
+/// Reason a priority operation was deemed invalid by the watcher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(super) enum InvalidPriorityOpReason {
+    /// L2 gas limit exceeds the maximum gas limit set in the L1 contracts.
+    GasLimitTooHigh,
+    /// L2 gas limit doesn't cover intrinsic costs of the operation.
+    GasLimitTooLow,
+}
+
+impl From<&L1TxGasLimitError> for InvalidPriorityOpReason {
+    fn from(err: &L1TxGasLimitError) -> Self {
+        match err {
+            L1TxGasLimitError::TooHigh { .. } => Self::GasLimitTooHigh,
+            L1TxGasLimitError::TooLow { .. } => Self::GasLimitTooLow,
+        }
+    }
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_eth_watch")]
 pub(super) struct EthWatcherMetrics {
@@ -20,6 +56,13 @@ pub(super) struct EthWatcherMetrics {
     pub poll_eth_node: Family<PollStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub get_priority_op_events: Histogram<Duration>,
+    /// Number of protocol upgrade dry runs.
+    pub upgrade_dry_runs: Family<UpgradeDryRunOutcome, Counter>,
+    /// Latency of executing an upgrade transaction during a dry run.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub upgrade_dry_run_latency: Histogram<Duration>,
+    /// Number of received priority operations with gas limits outside protocol bounds.
+    pub invalid_priority_ops: Family<InvalidPriorityOpReason, Counter>,
 }
 
 #[vise::register]
@@ -12,10 +12,11 @@ use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_eth_client::EthInterface;
 use zksync_system_constants::PRIORITY_EXPIRATION;
 use zksync_types::{
-    ethabi::Contract, web3::types::BlockNumber as Web3BlockNumber, Address, PriorityOpId,
-    ProtocolVersionId,
+    ethabi::Contract, web3::types::BlockNumber as Web3BlockNumber, Address, L2ChainId,
+    PriorityOpId, ProtocolVersionId,
 };
 
+pub use self::upgrade_dry_run::UpgradeDryRunner;
 use self::{
     client::{Error, EthClient, EthHttpQueryClient, RETRY_LIMIT},
     event_processors::{
@@ -30,6 +31,16 @@ mod event_processors;
 mod metrics;
 #[cfg(test)]
 mod tests;
+mod upgrade_dry_run;
+
+/// Parameters of the chain within a shared bridge deployment, where a single `StateTransitionManager`
+/// contract serves multiple chains.
+#[derive(Debug, Clone, Copy)]
+pub struct SharedBridgeParams {
+    pub state_transition_manager_address: Address,
+    /// Chain ID used to filter out upgrades for other chains.
+    pub chain_id: L2ChainId,
+}
 
 #[derive(Debug)]
 struct EthWatchState {
@@ -52,6 +63,7 @@ impl EthWatch {
     pub async fn new(
         diamond_proxy_address: Address,
         governance_contract: Option<Contract>,
+        shared_bridge: Option<SharedBridgeParams>,
         mut client: Box<dyn EthClient>,
         pool: ConnectionPool,
         poll_interval: Duration,
@@ -77,6 +89,7 @@ impl EthWatch {
                 diamond_proxy_address,
                 state.last_seen_version_id,
                 &governance_contract,
+                shared_bridge,
             );
             event_processors.push(Box::new(governance_upgrades_processor))
         }
@@ -195,6 +208,7 @@ pub async fn start_eth_watch(
     eth_gateway: Arc<dyn EthInterface>,
     diamond_proxy_addr: Address,
     governance: (Contract, Address),
+    shared_bridge: Option<SharedBridgeParams>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
     let eth_client = EthHttpQueryClient::new(
@@ -207,6 +221,7 @@ pub async fn start_eth_watch(
     let eth_watch = EthWatch::new(
         diamond_proxy_addr,
         Some(governance.0),
+        shared_bridge,
         Box::new(eth_client),
         pool,
         config.poll_interval(),
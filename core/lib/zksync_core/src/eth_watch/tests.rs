@@ -1,28 +1,44 @@
 use std::{collections::HashMap, convert::TryInto, sync::Arc};
 
 use tokio::sync::RwLock;
-use zksync_contracts::{governance_contract, zksync_contract};
+use zksync_contracts::{deployer_contract, governance_contract, zksync_contract};
 use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_eth_client::Error as EthClientError;
+use zksync_health_check::{CheckHealth, HealthStatus};
 use zksync_types::{
-    ethabi::{encode, Hash, Token},
+    block::BaseSystemContractsHashes,
+    ethabi::{encode, short_signature, Hash, ParamType, Token},
     l1::{L1Tx, OpProcessingType, PriorityQueueType},
     protocol_version::{ProtocolUpgradeTx, ProtocolUpgradeTxCommonData},
-    web3::types::{Address, BlockNumber, Log},
-    Execute, L1TxCommonData, PriorityOpId, ProtocolUpgrade, ProtocolVersion, ProtocolVersionId,
-    Transaction, H256, U256,
+    web3::{
+        self,
+        types::{Address, BlockNumber, Log},
+    },
+    Execute, L1TxCommonData, L2ChainId, PriorityOpId, ProtocolUpgrade, ProtocolVersion,
+    ProtocolVersionId, Transaction, CONTRACT_DEPLOYER_ADDRESS, CONTRACT_FORCE_DEPLOYER_ADDRESS,
+    H256, REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256,
 };
 
 use super::client::Error;
-use crate::eth_watch::{
-    client::EthClient, event_processors::upgrades::UPGRADE_PROPOSAL_SIGNATURE, EthWatch,
+use crate::{
+    eth_watch::{
+        client::EthClient, event_processors::upgrades::UPGRADE_PROPOSAL_SIGNATURE, EthWatch,
+        SharedBridgeParams, UpgradeDryRunner,
+    },
+    genesis::{ensure_genesis_state, GenesisParams},
 };
 
+const PRIORITY_TX_MAX_GAS_LIMIT: u64 = 72_000_000;
+const STATE_TRANSITION_MANAGER_ADDRESS: Address = Address::repeat_byte(0x16);
+
 #[derive(Debug)]
 struct FakeEthClientData {
     transactions: HashMap<u64, Vec<Log>>,
     diamond_upgrades: HashMap<u64, Vec<Log>>,
     governance_upgrades: HashMap<u64, Vec<Log>>,
     last_finalized_block_number: u64,
+    max_gas_limit_requests: usize,
+    max_gas_limit_unavailable: bool,
 }
 
 impl FakeEthClientData {
@@ -32,6 +48,8 @@ impl FakeEthClientData {
             diamond_upgrades: Default::default(),
             governance_upgrades: Default::default(),
             last_finalized_block_number: 0,
+            max_gas_limit_requests: 0,
+            max_gas_limit_unavailable: false,
         }
     }
 
@@ -63,6 +81,22 @@ impl FakeEthClientData {
         }
     }
 
+    fn add_state_transition_manager_upgrades(
+        &mut self,
+        upgrades: &[(ProtocolUpgrade, L2ChainId, u64)],
+    ) {
+        for (upgrade, chain_id, eth_block) in upgrades {
+            self.governance_upgrades
+                .entry(*eth_block)
+                .or_default()
+                .push(upgrade_into_state_transition_manager_log(
+                    upgrade.clone(),
+                    *chain_id,
+                    *eth_block,
+                ));
+        }
+    }
+
     fn set_last_finalized_block_number(&mut self, number: u64) {
         self.last_finalized_block_number = number;
     }
@@ -92,6 +126,16 @@ impl FakeEthClient {
         self.inner.write().await.add_governance_upgrades(upgrades);
     }
 
+    async fn add_state_transition_manager_upgrades(
+        &mut self,
+        upgrades: &[(ProtocolUpgrade, L2ChainId, u64)],
+    ) {
+        self.inner
+            .write()
+            .await
+            .add_state_transition_manager_upgrades(upgrades);
+    }
+
     async fn set_last_finalized_block_number(&mut self, number: u64) {
         self.inner
             .write()
@@ -142,6 +186,15 @@ impl EthClient for FakeEthClient {
         Ok(H256::zero())
     }
 
+    async fn priority_tx_max_gas_limit(&self) -> Result<U256, Error> {
+        let mut inner = self.inner.write().await;
+        inner.max_gas_limit_requests += 1;
+        if inner.max_gas_limit_unavailable {
+            return Err(EthClientError::EthereumGateway(web3::Error::Unreachable).into());
+        }
+        Ok(PRIORITY_TX_MAX_GAS_LIMIT.into())
+    }
+
     async fn finalized_block_number(&self) -> Result<u64, Error> {
         Ok(self.inner.read().await.last_finalized_block_number)
     }
@@ -159,7 +212,7 @@ fn build_l1_tx(serial_id: u64, eth_block: u64) -> L1Tx {
             serial_id: PriorityOpId(serial_id),
             sender: [1u8; 20].into(),
             deadline_block: 0,
-            eth_hash: [2; 32].into(),
+            eth_hash: H256::from_low_u64_be(serial_id + 0x100),
             eth_block,
             gas_limit: Default::default(),
             max_fee_per_gas: Default::default(),
@@ -209,6 +262,7 @@ async fn test_normal_operation_l1_txs() {
     let mut watcher = EthWatch::new(
         Address::default(),
         None,
+        None,
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
@@ -246,6 +300,99 @@ async fn test_normal_operation_l1_txs() {
     assert_eq!(db_txs.len(), 3);
     let db_tx = db_txs[2].clone();
     assert_eq!(db_tx.common_data.serial_id.0, 2);
+    // L1 provenance of priority ops should be persisted.
+    for serial_id in 0..3 {
+        let status = storage
+            .transactions_web3_dal()
+            .get_priority_op_status(PriorityOpId(serial_id))
+            .await
+            .unwrap()
+            .expect("priority op is not persisted");
+        assert_eq!(
+            status.l1_tx_hash,
+            Some(build_l1_tx(serial_id, 0).common_data.eth_hash)
+        );
+    }
+}
+
+#[tokio::test]
+async fn priority_ops_with_invalid_gas_limits_are_persisted() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        None,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut valid_tx = build_l1_tx(0, 10);
+    valid_tx.common_data.gas_limit = 1_000_000.into();
+    valid_tx
+        .validate_gas_limit(PRIORITY_TX_MAX_GAS_LIMIT.into())
+        .unwrap();
+    let mut tx_with_high_gas_limit = build_l1_tx(1, 10);
+    tx_with_high_gas_limit.common_data.gas_limit = (PRIORITY_TX_MAX_GAS_LIMIT + 1).into();
+    let tx_with_low_gas_limit = build_l1_tx(2, 10);
+    tx_with_low_gas_limit
+        .validate_gas_limit(PRIORITY_TX_MAX_GAS_LIMIT.into())
+        .unwrap_err();
+
+    client
+        .add_transactions(&[valid_tx, tx_with_high_gas_limit, tx_with_low_gas_limit])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    // All operations must be persisted, so that there are no gaps in the priority queue.
+    let db_txs = get_all_db_txs(&mut storage).await;
+    let mut serial_ids: Vec<_> = db_txs
+        .into_iter()
+        .map(|tx| L1Tx::try_from(tx).unwrap().serial_id().0)
+        .collect();
+    serial_ids.sort_unstable();
+    assert_eq!(serial_ids, [0, 1, 2]);
+
+    // The max gas limit must be cached between polls.
+    client.add_transactions(&[build_l1_tx(3, 20)]).await;
+    client.set_last_finalized_block_number(25).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_all_db_txs(&mut storage).await.len(), 4);
+    assert_eq!(client.inner.read().await.max_gas_limit_requests, 1);
+}
+
+#[tokio::test]
+async fn priority_ops_are_persisted_if_max_gas_limit_is_unavailable() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let mut client = FakeEthClient::new();
+    client.inner.write().await.max_gas_limit_unavailable = true;
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        None,
+        None,
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14)])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    assert_eq!(get_all_db_txs(&mut storage).await.len(), 2);
+    assert_eq!(client.inner.read().await.max_gas_limit_requests, 1);
 }
 
 #[tokio::test]
@@ -257,6 +404,7 @@ async fn test_normal_operation_upgrades() {
     let mut watcher = EthWatch::new(
         Address::default(),
         None,
+        None,
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
@@ -309,6 +457,68 @@ async fn test_normal_operation_upgrades() {
     assert_eq!(tx.common_data.upgrade_id, ProtocolVersionId::next());
 }
 
+#[tokio::test]
+async fn test_shared_bridge_governance_upgrades() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    setup_db(&connection_pool).await;
+
+    let chain_id = L2ChainId::from(270);
+    let other_chain_id = L2ChainId::from(271);
+    let mut client = FakeEthClient::new();
+    let mut watcher = EthWatch::new(
+        Address::default(),
+        Some(governance_contract()),
+        Some(SharedBridgeParams {
+            state_transition_manager_address: STATE_TRANSITION_MANAGER_ADDRESS,
+            chain_id,
+        }),
+        Box::new(client.clone()),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await;
+
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    client
+        .add_state_transition_manager_upgrades(&[
+            (
+                ProtocolUpgrade {
+                    id: ProtocolVersionId::latest(),
+                    tx: None,
+                    ..Default::default()
+                },
+                other_chain_id,
+                10,
+            ),
+            (
+                ProtocolUpgrade {
+                    id: ProtocolVersionId::latest(),
+                    tx: None,
+                    ..Default::default()
+                },
+                chain_id,
+                11,
+            ),
+            (
+                ProtocolUpgrade {
+                    id: ProtocolVersionId::next(),
+                    tx: Some(build_upgrade_tx(ProtocolVersionId::next(), 12)),
+                    ..Default::default()
+                },
+                other_chain_id,
+                12,
+            ),
+        ])
+        .await;
+    client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    // Only the upgrade for our chain should be saved.
+    let db_ids = storage.protocol_versions_dal().all_version_ids().await;
+    assert_eq!(db_ids.len(), 2);
+    assert_eq!(db_ids[1], ProtocolVersionId::latest());
+}
+
 #[tokio::test]
 async fn test_gap_in_upgrades() {
     let connection_pool = ConnectionPool::test_pool().await;
@@ -318,6 +528,7 @@ async fn test_gap_in_upgrades() {
     let mut watcher = EthWatch::new(
         Address::default(),
         None,
+        None,
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
@@ -357,6 +568,7 @@ async fn test_normal_operation_governance_upgrades() {
     let mut watcher = EthWatch::new(
         Address::default(),
         Some(governance_contract()),
+        None,
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
@@ -419,6 +631,7 @@ async fn test_gap_in_single_batch() {
     let mut watcher = EthWatch::new(
         Address::default(),
         None,
+        None,
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
@@ -449,6 +662,7 @@ async fn test_gap_between_batches() {
     let mut watcher = EthWatch::new(
         Address::default(),
         None,
+        None,
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
@@ -484,6 +698,7 @@ async fn test_overlapping_batches() {
     let mut watcher = EthWatch::new(
         Address::default(),
         None,
+        None,
         Box::new(client.clone()),
         connection_pool.clone(),
         std::time::Duration::from_nanos(1),
@@ -534,6 +749,7 @@ async fn get_all_db_txs(storage: &mut StorageProcessor<'_>) -> Vec<Transaction>
 
 fn tx_into_log(tx: L1Tx) -> Log {
     let eth_block = tx.eth_block().0.into();
+    let eth_hash = tx.common_data.eth_hash;
 
     let tx_data_token = Token::Tuple(vec![
         Token::Uint(0xff.into()),
@@ -576,7 +792,7 @@ fn tx_into_log(tx: L1Tx) -> Log {
         data: data.into(),
         block_hash: Some(H256::repeat_byte(0x11)),
         block_number: Some(eth_block),
-        transaction_hash: Some(H256::random()),
+        transaction_hash: Some(eth_hash),
         transaction_index: Some(0u64.into()),
         log_index: Some(0u64.into()),
         transaction_log_index: Some(0u64.into()),
@@ -614,10 +830,36 @@ fn upgrade_into_governor_log(upgrade: ProtocolUpgrade, eth_block: u64) -> Log {
         .copied()
         .chain(encode(&[diamond_cut]))
         .collect();
+    governance_operation_log(Address::default(), diamond_upgrade_calldata, eth_block)
+}
+
+fn upgrade_into_state_transition_manager_log(
+    upgrade: ProtocolUpgrade,
+    chain_id: L2ChainId,
+    eth_block: u64,
+) -> Log {
+    let diamond_cut = upgrade_into_diamond_cut(upgrade);
+    let diamond_cut_param = zksync_contract().function("executeUpgrade").unwrap().inputs[0]
+        .kind
+        .clone();
+    let params = [ParamType::Uint(256), diamond_cut_param];
+    let execute_upgrade_selector = short_signature("executeUpgrade", &params);
+    let calldata = execute_upgrade_selector
+        .iter()
+        .copied()
+        .chain(encode(&[
+            Token::Uint(chain_id.as_u64().into()),
+            diamond_cut,
+        ]))
+        .collect();
+    governance_operation_log(STATE_TRANSITION_MANAGER_ADDRESS, calldata, eth_block)
+}
+
+fn governance_operation_log(target: Address, calldata: Vec<u8>, eth_block: u64) -> Log {
     let governance_call = Token::Tuple(vec![
-        Token::Address(Default::default()),
+        Token::Address(target),
         Token::Uint(U256::default()),
-        Token::Bytes(diamond_upgrade_calldata),
+        Token::Bytes(calldata),
     ]);
     let governance_operation = Token::Tuple(vec![
         Token::Array(vec![governance_call]),
@@ -773,3 +1015,143 @@ async fn setup_db(connection_pool: &ConnectionPool) {
         })
         .await;
 }
+
+async fn setup_upgrade_dry_runner(version: ProtocolVersion) -> UpgradeDryRunner {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    storage
+        .protocol_versions_dal()
+        .save_protocol_version_with_tx(version)
+        .await;
+    drop(storage);
+
+    UpgradeDryRunner::new(
+        connection_pool,
+        L2ChainId::default(),
+        std::time::Duration::from_millis(10),
+    )
+}
+
+#[tokio::test]
+async fn dry_running_upgrade_without_tx() {
+    let dry_runner = setup_upgrade_dry_runner(ProtocolVersion {
+        id: ProtocolVersionId::next(),
+        ..Default::default()
+    })
+    .await;
+
+    let checked_version = dry_runner.process_next_upgrade(None).await.unwrap();
+    assert_eq!(checked_version, Some(ProtocolVersionId::next()));
+    assert_eq!(
+        dry_runner.health_check().check_health().await.status(),
+        HealthStatus::Ready
+    );
+}
+
+#[tokio::test]
+async fn dry_running_upgrade_with_missing_system_contracts() {
+    let dry_runner = setup_upgrade_dry_runner(ProtocolVersion {
+        id: ProtocolVersionId::next(),
+        base_system_contracts_hashes: BaseSystemContractsHashes {
+            bootloader: H256::repeat_byte(1),
+            default_aa: H256::repeat_byte(2),
+        },
+        tx: Some(build_upgrade_tx(ProtocolVersionId::next(), 10)),
+        ..Default::default()
+    })
+    .await;
+
+    let checked_version = dry_runner.process_next_upgrade(None).await.unwrap();
+    assert_eq!(checked_version, Some(ProtocolVersionId::next()));
+    let health = dry_runner.health_check().check_health().await;
+    assert_eq!(health.status(), HealthStatus::Affected);
+    let health = serde_json::to_value(health).unwrap();
+    assert_eq!(health["details"]["outcome"], "missing_system_contracts");
+
+    // The upgrade must not be dry-run again.
+    let checked_version = dry_runner
+        .process_next_upgrade(checked_version)
+        .await
+        .unwrap();
+    assert_eq!(checked_version, Some(ProtocolVersionId::next()));
+}
+
+/// Builds an upgrade transaction force-deploying a contract with the specified bytecode hash, similarly to real
+/// protocol upgrades.
+fn build_force_deploy_upgrade_tx(id: ProtocolVersionId, bytecode_hash: H256) -> ProtocolUpgradeTx {
+    let deployment = Token::Tuple(vec![
+        Token::FixedBytes(bytecode_hash.as_bytes().to_vec()),
+        Token::Address(Address::repeat_byte(0x23)),
+        Token::Bool(false),
+        Token::Uint(U256::zero()),
+        Token::Bytes(vec![]),
+    ]);
+    let calldata = deployer_contract()
+        .function("forceDeployOnAddresses")
+        .unwrap()
+        .encode_input(&[Token::Array(vec![deployment])])
+        .unwrap();
+
+    let mut tx = build_upgrade_tx(id, 10);
+    tx.execute = Execute {
+        contract_address: CONTRACT_DEPLOYER_ADDRESS,
+        calldata,
+        factory_deps: None,
+        value: U256::zero(),
+    };
+    tx.common_data.sender = CONTRACT_FORCE_DEPLOYER_ADDRESS;
+    tx.common_data.gas_limit = 200_000_000_u32.into();
+    tx.common_data.gas_per_pubdata_limit = REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE.into();
+    tx
+}
+
+#[tokio::test]
+async fn dry_running_real_upgrade_tx() {
+    let base_system_contracts_hashes = GenesisParams::mock().base_system_contracts.hashes();
+    // The default account bytecode is known after genesis, so it can be force-deployed.
+    let known_bytecode_hash = base_system_contracts_hashes.default_aa;
+    let dry_runner = setup_upgrade_dry_runner(ProtocolVersion {
+        id: ProtocolVersionId::next(),
+        base_system_contracts_hashes,
+        tx: Some(build_force_deploy_upgrade_tx(
+            ProtocolVersionId::next(),
+            known_bytecode_hash,
+        )),
+        ..Default::default()
+    })
+    .await;
+
+    let checked_version = dry_runner.process_next_upgrade(None).await.unwrap();
+    assert_eq!(checked_version, Some(ProtocolVersionId::next()));
+    assert_eq!(
+        dry_runner.health_check().check_health().await.status(),
+        HealthStatus::Ready
+    );
+}
+
+#[tokio::test]
+async fn dry_running_reverting_upgrade_tx() {
+    let base_system_contracts_hashes = GenesisParams::mock().base_system_contracts.hashes();
+    // Force-deploying an unknown bytecode reverts.
+    let unknown_bytecode_hash = H256::repeat_byte(0x01);
+    let dry_runner = setup_upgrade_dry_runner(ProtocolVersion {
+        id: ProtocolVersionId::next(),
+        base_system_contracts_hashes,
+        tx: Some(build_force_deploy_upgrade_tx(
+            ProtocolVersionId::next(),
+            unknown_bytecode_hash,
+        )),
+        ..Default::default()
+    })
+    .await;
+
+    let checked_version = dry_runner.process_next_upgrade(None).await.unwrap();
+    assert_eq!(checked_version, Some(ProtocolVersionId::next()));
+    let health = dry_runner.health_check().check_health().await;
+    assert_eq!(health.status(), HealthStatus::Affected);
+    let health = serde_json::to_value(health).unwrap();
+    assert_eq!(health["details"]["outcome"], "failed");
+}
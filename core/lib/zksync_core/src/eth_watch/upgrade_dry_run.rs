@@ -0,0 +1,275 @@
+//! Dry runs of protocol upgrades detected by [`EthWatch`](super::EthWatch).
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use multivm::{
+    interface::{ExecutionResult, L1BatchEnv, SystemEnv},
+    VmInstance,
+};
+use serde::Serialize;
+use tokio::{runtime::Handle, sync::watch};
+use vm_utils::{execute_tx, storage::l1_batch_params};
+use zksync_contracts::BaseSystemContracts;
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_state::{PostgresStorage, StorageView};
+use zksync_types::{L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction};
+use zksync_utils::time::seconds_since_epoch;
+
+use super::metrics::{UpgradeDryRunOutcome, METRICS};
+
+#[derive(Debug, Serialize)]
+struct DryRunFailureDetails {
+    protocol_version: u16,
+    outcome: UpgradeDryRunOutcome,
+    reason: String,
+}
+
+/// Component executing the upgrade transaction of the next scheduled protocol upgrade in a sandbox on top
+/// of the latest sealed L1 batch. This allows to detect broken upgrades (e.g., reverting upgrade transactions
+/// or base system contracts missing from the storage) before the activation batch is reached. Failures are reported
+/// via logs, metrics and the health check; they do not stop the component.
+///
+/// Each upgrade is dry-run once. Only the next upgrade (i.e., the one with the least version greater than
+/// the version of the last sealed L1 batch) is dry-run since later upgrades depend on the state produced by it.
+#[derive(Debug)]
+pub struct UpgradeDryRunner {
+    connection_pool: ConnectionPool,
+    l2_chain_id: L2ChainId,
+    poll_interval: Duration,
+    health_updater: HealthUpdater,
+}
+
+impl UpgradeDryRunner {
+    pub fn new(
+        connection_pool: ConnectionPool,
+        l2_chain_id: L2ChainId,
+        poll_interval: Duration,
+    ) -> Self {
+        Self {
+            connection_pool,
+            l2_chain_id,
+            poll_interval,
+            health_updater: ReactiveHealthCheck::new("upgrade_dry_runner").1,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Returns the next protocol version that hasn't been activated yet.
+    async fn next_pending_version(&self) -> anyhow::Result<Option<ProtocolVersionId>> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("upgrade_dry_runner")
+            .await?;
+        let Some(last_used_version) = storage.protocol_versions_dal().last_used_version_id().await
+        else {
+            return Ok(None);
+        };
+        let all_versions = storage.protocol_versions_dal().all_version_ids().await;
+        Ok(all_versions
+            .into_iter()
+            .filter(|&id| id as u16 > last_used_version as u16)
+            .min_by_key(|&id| id as u16))
+    }
+
+    /// Prepares the VM environment for the batch following the last sealed L1 batch. Returns `None` if
+    /// the environment cannot be prepared yet (e.g., if the node was recently recovered from a snapshot).
+    async fn prepare_env(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        version_id: ProtocolVersionId,
+        activation_timestamp: u64,
+        base_system_contracts: BaseSystemContracts,
+    ) -> anyhow::Result<Option<(SystemEnv, L1BatchEnv, MiniblockNumber)>> {
+        let Some(sealed_l1_batch) = storage.blocks_dal().get_sealed_l1_batch_number().await? else {
+            return Ok(None);
+        };
+        let Some((_, last_miniblock)) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(sealed_l1_batch)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let last_miniblock_header = storage
+            .blocks_dal()
+            .get_miniblock_header(last_miniblock)
+            .await?
+            .with_context(|| format!("header for miniblock #{last_miniblock} is missing"))?;
+        // The hash isn't checked by the VM; it may be missing if the Merkle tree lags behind.
+        let previous_batch_hash = storage
+            .blocks_dal()
+            .get_l1_batch_state_root(sealed_l1_batch)
+            .await?
+            .unwrap_or_default();
+
+        // The upgrade cannot be activated before its timestamp.
+        let timestamp = seconds_since_epoch()
+            .max(last_miniblock_header.timestamp + 1)
+            .max(activation_timestamp);
+        let (system_env, l1_batch_env) = l1_batch_params(
+            sealed_l1_batch + 1,
+            last_miniblock_header.fee_account_address,
+            timestamp,
+            previous_batch_hash,
+            last_miniblock_header.batch_fee_input,
+            last_miniblock + 1,
+            last_miniblock_header.hash,
+            base_system_contracts,
+            u32::MAX,
+            version_id,
+            1,
+            self.l2_chain_id,
+        );
+        Ok(Some((system_env, l1_batch_env, last_miniblock)))
+    }
+
+    /// This method is blocking.
+    fn execute_upgrade_tx(
+        rt_handle: Handle,
+        connection_pool: ConnectionPool,
+        (system_env, l1_batch_env, miniblock_number): (SystemEnv, L1BatchEnv, MiniblockNumber),
+        upgrade_tx: &Transaction,
+    ) -> anyhow::Result<ExecutionResult> {
+        let connection = rt_handle
+            .block_on(connection_pool.access_storage_tagged("upgrade_dry_runner"))
+            .context("failed to get connection for upgrade dry run")?;
+        let storage = PostgresStorage::new(rt_handle, connection, miniblock_number, true);
+        let storage_view = StorageView::new(storage).to_rc_ptr();
+        let mut vm = VmInstance::new(l1_batch_env, system_env, storage_view);
+        let result = execute_tx(upgrade_tx, &mut vm)?;
+        Ok(result.result)
+    }
+
+    /// Dry-runs the upgrade to the specified protocol version. Returns `None` if the dry run should be retried later.
+    async fn dry_run(
+        &self,
+        version_id: ProtocolVersionId,
+    ) -> anyhow::Result<Option<(UpgradeDryRunOutcome, String)>> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("upgrade_dry_runner")
+            .await?;
+        let version = storage
+            .protocol_versions_dal()
+            .get_protocol_version(version_id)
+            .await
+            .with_context(|| format!("protocol version {version_id:?} is missing"))?;
+        let Some(upgrade_tx) = version.tx else {
+            return Ok(Some((UpgradeDryRunOutcome::NoUpgradeTx, String::new())));
+        };
+
+        let hashes = version.base_system_contracts_hashes;
+        let base_system_contracts = match storage
+            .factory_deps_dal()
+            .get_base_system_contracts(hashes.bootloader, hashes.default_aa)
+            .await
+        {
+            Ok(contracts) => contracts,
+            Err(err) => {
+                let outcome = UpgradeDryRunOutcome::MissingSystemContracts;
+                return Ok(Some((outcome, format!("{err:#}"))));
+            }
+        };
+
+        let Some(env) = self
+            .prepare_env(
+                &mut storage,
+                version_id,
+                version.timestamp,
+                base_system_contracts,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        drop(storage);
+
+        let latency = METRICS.upgrade_dry_run_latency.start();
+        let connection_pool = self.connection_pool.clone();
+        let upgrade_tx = Transaction::from(upgrade_tx);
+        let result = tokio::task::spawn_blocking(move || {
+            Self::execute_upgrade_tx(Handle::current(), connection_pool, env, &upgrade_tx)
+        })
+        .await
+        .context("upgrade dry run panicked")?
+        .with_context(|| {
+            format!("failed dry-running upgrade to protocol version {version_id:?}")
+        })?;
+        latency.observe();
+
+        Ok(Some(match result {
+            ExecutionResult::Success { .. } => (UpgradeDryRunOutcome::Succeeded, String::new()),
+            ExecutionResult::Revert { output } => {
+                (UpgradeDryRunOutcome::Failed, format!("reverted: {output}"))
+            }
+            ExecutionResult::Halt { reason } => {
+                (UpgradeDryRunOutcome::Failed, format!("halted: {reason}"))
+            }
+        }))
+    }
+
+    /// Dry-runs the next pending upgrade unless it was dry-run before. Returns the last dry-run protocol version.
+    pub(super) async fn process_next_upgrade(
+        &self,
+        last_checked_version: Option<ProtocolVersionId>,
+    ) -> anyhow::Result<Option<ProtocolVersionId>> {
+        let Some(version_id) = self.next_pending_version().await? else {
+            return Ok(last_checked_version);
+        };
+        if last_checked_version == Some(version_id) {
+            return Ok(last_checked_version);
+        }
+
+        let Some((outcome, reason)) = self.dry_run(version_id).await? else {
+            tracing::info!(
+                "Cannot dry-run upgrade to protocol version {version_id:?} yet, will retry later"
+            );
+            return Ok(last_checked_version);
+        };
+        METRICS.upgrade_dry_runs[&outcome].inc();
+        match outcome {
+            UpgradeDryRunOutcome::Succeeded => {
+                tracing::info!("Dry run of upgrade to protocol version {version_id:?} succeeded");
+                self.health_updater.update(HealthStatus::Ready.into());
+            }
+            UpgradeDryRunOutcome::NoUpgradeTx => {
+                tracing::info!(
+                    "Upgrade to protocol version {version_id:?} has no upgrade transaction; skipping dry run"
+                );
+                self.health_updater.update(HealthStatus::Ready.into());
+            }
+            UpgradeDryRunOutcome::MissingSystemContracts | UpgradeDryRunOutcome::Failed => {
+                tracing::error!(
+                    "Dry run of upgrade to protocol version {version_id:?} failed ({outcome:?}): {reason}"
+                );
+                let details = DryRunFailureDetails {
+                    protocol_version: version_id as u16,
+                    outcome,
+                    reason,
+                };
+                self.health_updater
+                    .update(Health::from(HealthStatus::Affected).with_details(details));
+            }
+        }
+        Ok(Some(version_id))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater.update(HealthStatus::Ready.into());
+        let mut last_checked_version = None;
+        while !*stop_receiver.borrow_and_update() {
+            last_checked_version = self.process_next_upgrade(last_checked_version).await?;
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, upgrade dry runner is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,336 @@
+//! Emergency override of the batch fee input. Allows node operators to respond to a bug in the fee model without
+//! redeploying the node: the override is read from a JSON file specified in the state keeper config and is re-read
+//! each time the node receives `SIGUSR1`.
+
+use std::{
+    fs, io, mem,
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+use tokio::{
+    signal::unix::{signal, SignalKind},
+    sync::watch,
+};
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::fee_model::BatchFeeInput;
+
+/// Override of batch fee input components. Components not specified in the override are computed by the fee model
+/// as usual.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeInputOverride {
+    pub fair_l2_gas_price: Option<u64>,
+    /// Ignored if the batch fee input doesn't have a pubdata price independent of the L1 gas price
+    /// (i.e., for the `V1` fee model).
+    pub fair_pubdata_price: Option<u64>,
+    /// Human-readable reason for the override. Only used for audit logging.
+    #[serde(default)]
+    pub reason: String,
+}
+
+impl FeeInputOverride {
+    fn apply(&self, input: BatchFeeInput) -> BatchFeeInput {
+        match input {
+            BatchFeeInput::L1Pegged(mut input) => {
+                if let Some(price) = self.fair_l2_gas_price {
+                    input.fair_l2_gas_price = price;
+                }
+                BatchFeeInput::L1Pegged(input)
+            }
+            BatchFeeInput::PubdataIndependent(mut input) => {
+                if let Some(price) = self.fair_l2_gas_price {
+                    input.fair_l2_gas_price = price;
+                }
+                if let Some(price) = self.fair_pubdata_price {
+                    input.fair_pubdata_price = price;
+                }
+                BatchFeeInput::PubdataIndependent(input)
+            }
+        }
+    }
+}
+
+/// Bounds for prices set by a [`FeeInputOverride`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeInputOverrideBounds {
+    pub min_fair_l2_gas_price: u64,
+    pub max_fair_l2_gas_price: Option<u64>,
+    pub max_fair_pubdata_price: Option<u64>,
+}
+
+impl FeeInputOverrideBounds {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            min_fair_l2_gas_price: config.minimal_l2_gas_price,
+            max_fair_l2_gas_price: config.fee_input_override_max_fair_l2_gas_price,
+            max_fair_pubdata_price: config.fee_input_override_max_fair_pubdata_price,
+        }
+    }
+
+    fn check(&self, value: &FeeInputOverride) -> anyhow::Result<()> {
+        if let Some(price) = value.fair_l2_gas_price {
+            anyhow::ensure!(
+                price >= self.min_fair_l2_gas_price,
+                "fair L2 gas price {price} is lower than the minimal L2 gas price {}",
+                self.min_fair_l2_gas_price
+            );
+            if let Some(max_price) = self.max_fair_l2_gas_price {
+                anyhow::ensure!(
+                    price <= max_price,
+                    "fair L2 gas price {price} exceeds the configured maximum {max_price}"
+                );
+            }
+        }
+        if let (Some(price), Some(max_price)) =
+            (value.fair_pubdata_price, self.max_fair_pubdata_price)
+        {
+            anyhow::ensure!(
+                price <= max_price,
+                "fair pubdata price {price} exceeds the configured maximum {max_price}"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// [`FeeInputOverride`] shared among all fee input providers in the node, so that the state keeper
+/// and the API server apply the same override.
+#[derive(Debug, Default)]
+pub struct SharedFeeInputOverride(RwLock<Option<FeeInputOverride>>);
+
+impl SharedFeeInputOverride {
+    /// Returns the currently active override, if any.
+    pub fn get(&self) -> Option<FeeInputOverride> {
+        self.0
+            .read()
+            .expect("fee input override is poisoned")
+            .clone()
+    }
+
+    pub(crate) fn set(&self, value: Option<FeeInputOverride>) -> Option<FeeInputOverride> {
+        let mut guard = self.0.write().expect("fee input override is poisoned");
+        mem::replace(&mut *guard, value)
+    }
+
+    /// Applies the active override (if any) to the provided fee input.
+    pub(crate) fn apply(&self, input: BatchFeeInput) -> BatchFeeInput {
+        let guard = self.0.read().expect("fee input override is poisoned");
+        guard
+            .as_ref()
+            .map_or(input, |fee_override| fee_override.apply(input))
+    }
+}
+
+/// Component loading [`FeeInputOverride`] from a file and reloading it on `SIGUSR1`.
+///
+/// A missing or empty file resets the override. An override that cannot be parsed or is out of bounds is rejected;
+/// on node startup, this is an error, and after a reload, the previously active override remains in effect.
+#[derive(Debug)]
+pub struct FeeInputOverrideReloader {
+    path: PathBuf,
+    bounds: FeeInputOverrideBounds,
+    shared: Arc<SharedFeeInputOverride>,
+}
+
+impl FeeInputOverrideReloader {
+    /// Creates a reloader and loads the initial override from the file.
+    pub fn new(
+        path: PathBuf,
+        bounds: FeeInputOverrideBounds,
+        shared: Arc<SharedFeeInputOverride>,
+    ) -> anyhow::Result<Self> {
+        let this = Self {
+            path,
+            bounds,
+            shared,
+        };
+        let initial_value = this.read_override()?;
+        this.update(initial_value);
+        Ok(this)
+    }
+
+    fn read_override(&self) -> anyhow::Result<Option<FeeInputOverride>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(anyhow::Error::new(err).context(format!(
+                    "failed reading fee input override from {:?}",
+                    self.path
+                )));
+            }
+        };
+        if contents.trim().is_empty() {
+            return Ok(None);
+        }
+        let value: FeeInputOverride = serde_json::from_str(&contents)
+            .with_context(|| format!("failed parsing fee input override from {:?}", self.path))?;
+        self.bounds
+            .check(&value)
+            .context("fee input override is out of bounds")?;
+        Ok(Some(value))
+    }
+
+    fn update(&self, new_value: Option<FeeInputOverride>) {
+        let prev_value = self.shared.set(new_value.clone());
+        if prev_value == new_value {
+            tracing::info!(
+                "Batch fee input override loaded from {:?} is unchanged",
+                self.path
+            );
+            return;
+        }
+
+        // Overrides are logged on the `warn` level so that they are easily discoverable during audits.
+        match new_value {
+            Some(value) => tracing::warn!(
+                "Batch fee input override loaded from {:?}: fair L2 gas price = {:?}, fair pubdata price = {:?}, \
+                 reason: {:?} (previous override: {prev_value:?})",
+                self.path,
+                value.fair_l2_gas_price,
+                value.fair_pubdata_price,
+                value.reason
+            ),
+            None => tracing::warn!(
+                "Batch fee input override was reset (previous override: {prev_value:?})"
+            ),
+        }
+    }
+
+    fn reload(&self) {
+        match self.read_override() {
+            Ok(new_value) => self.update(new_value),
+            Err(err) => {
+                tracing::error!(
+                    "Rejected batch fee input override; the previous override ({:?}) remains in effect: {err:#}",
+                    self.shared.get()
+                );
+            }
+        }
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut signals =
+            signal(SignalKind::user_defined1()).context("failed installing SIGUSR1 handler")?;
+        loop {
+            tokio::select! {
+                Some(()) = signals.recv() => {
+                    tracing::info!("Received SIGUSR1, reloading batch fee input override from {:?}", self.path);
+                    self.reload();
+                }
+                _ = stop_receiver.changed() => break,
+            }
+        }
+        tracing::info!("Stop signal received, fee input override reloader is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const BOUNDS: FeeInputOverrideBounds = FeeInputOverrideBounds {
+        min_fair_l2_gas_price: 100,
+        max_fair_l2_gas_price: Some(1_000),
+        max_fair_pubdata_price: None,
+    };
+
+    #[test]
+    fn applying_fee_input_override() {
+        let fee_override = FeeInputOverride {
+            fair_l2_gas_price: Some(500),
+            fair_pubdata_price: Some(50),
+            reason: String::new(),
+        };
+        let input = BatchFeeInput::pubdata_independent(10, 100, 1_000);
+        assert_eq!(
+            fee_override.apply(input),
+            BatchFeeInput::pubdata_independent(10, 500, 50)
+        );
+
+        // The pubdata price is pegged to the L1 gas price, so it cannot be overridden.
+        let input = BatchFeeInput::l1_pegged(10, 100);
+        assert_eq!(fee_override.apply(input), BatchFeeInput::l1_pegged(10, 500));
+
+        let fee_override = FeeInputOverride {
+            fair_l2_gas_price: None,
+            ..fee_override
+        };
+        let input = BatchFeeInput::pubdata_independent(10, 100, 1_000);
+        assert_eq!(
+            fee_override.apply(input),
+            BatchFeeInput::pubdata_independent(10, 100, 50)
+        );
+    }
+
+    #[test]
+    fn checking_fee_input_override_bounds() {
+        let mut fee_override = FeeInputOverride {
+            fair_l2_gas_price: Some(500),
+            fair_pubdata_price: Some(1_000_000),
+            reason: String::new(),
+        };
+        BOUNDS.check(&fee_override).unwrap();
+
+        fee_override.fair_l2_gas_price = Some(99);
+        let err = BOUNDS.check(&fee_override).unwrap_err().to_string();
+        assert!(err.contains("lower than the minimal L2 gas price"), "{err}");
+        fee_override.fair_l2_gas_price = Some(1_001);
+        let err = BOUNDS.check(&fee_override).unwrap_err().to_string();
+        assert!(err.contains("exceeds the configured maximum"), "{err}");
+
+        let bounds = FeeInputOverrideBounds {
+            max_fair_pubdata_price: Some(1_000),
+            ..BOUNDS
+        };
+        fee_override.fair_l2_gas_price = None;
+        let err = bounds.check(&fee_override).unwrap_err().to_string();
+        assert!(err.contains("fair pubdata price"), "{err}");
+    }
+
+    #[test]
+    fn reloading_fee_input_override() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("fee_input_override.json");
+        let shared = Arc::<SharedFeeInputOverride>::default();
+        let reloader = FeeInputOverrideReloader::new(path.clone(), BOUNDS, shared.clone()).unwrap();
+        assert_eq!(shared.get(), None);
+
+        fs::write(
+            &path,
+            r#"{ "fair_l2_gas_price": 500, "reason": "fee model bug" }"#,
+        )
+        .unwrap();
+        reloader.reload();
+        let expected_override = FeeInputOverride {
+            fair_l2_gas_price: Some(500),
+            fair_pubdata_price: None,
+            reason: "fee model bug".to_owned(),
+        };
+        assert_eq!(shared.get(), Some(expected_override.clone()));
+
+        // Invalid overrides must not affect the active override.
+        fs::write(&path, r#"{ "fair_l2_gas_price": 5000 }"#).unwrap();
+        reloader.reload();
+        assert_eq!(shared.get(), Some(expected_override.clone()));
+        fs::write(&path, r#"{ "fair_l2_gas_prise": 500 }"#).unwrap();
+        reloader.reload();
+        assert_eq!(shared.get(), Some(expected_override));
+
+        fs::write(&path, "").unwrap();
+        reloader.reload();
+        assert_eq!(shared.get(), None);
+
+        // Invalid overrides should be rejected on startup.
+        fs::write(&path, r#"{ "fair_l2_gas_price": 5000 }"#).unwrap();
+        FeeInputOverrideReloader::new(path.clone(), BOUNDS, shared.clone()).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        FeeInputOverrideReloader::new(path, BOUNDS, shared).unwrap();
+    }
+}
@@ -2,6 +2,7 @@ use std::{fmt, sync::Arc};
 
 use zksync_dal::ConnectionPool;
 use zksync_types::{
+    api::L1GasOracleState,
     fee_model::{
         BatchFeeInput, FeeModelConfig, FeeModelConfigV2, FeeParams, FeeParamsV1, FeeParamsV2,
         L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput,
@@ -10,7 +11,10 @@ use zksync_types::{
 };
 use zksync_utils::ceil_div_u256;
 
-use crate::l1_gas_price::GasAdjuster;
+use crate::{
+    base_token_fetcher::BaseTokenFetcher, fee_input_override::SharedFeeInputOverride,
+    l1_gas_price::GasAdjuster,
+};
 
 /// Trait responsible for providing fee info for a batch
 #[async_trait::async_trait]
@@ -22,21 +26,11 @@ pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
         l1_gas_price_scale_factor: f64,
         l1_pubdata_price_scale_factor: f64,
     ) -> BatchFeeInput {
-        let params = self.get_fee_model_params();
-
-        match params {
-            FeeParams::V1(params) => BatchFeeInput::L1Pegged(compute_batch_fee_model_input_v1(
-                params,
-                l1_gas_price_scale_factor,
-            )),
-            FeeParams::V2(params) => {
-                BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
-                    params,
-                    l1_gas_price_scale_factor,
-                    l1_pubdata_price_scale_factor,
-                ))
-            }
-        }
+        compute_batch_fee_input(
+            self.get_fee_model_params(),
+            l1_gas_price_scale_factor,
+            l1_pubdata_price_scale_factor,
+        )
     }
 
     /// Returns the batch fee input as-is, i.e. without any scaling for the L1 gas and pubdata prices.
@@ -46,41 +40,112 @@ pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
 
     /// Returns the fee model parameters.
     fn get_fee_model_params(&self) -> FeeParams;
+
+    /// Returns the internal state of the L1 gas oracle used by this provider, or `None` if the provider
+    /// doesn't use an oracle (e.g., gets fee params from the main node).
+    fn get_l1_gas_oracle_state(&self) -> Option<L1GasOracleState> {
+        None
+    }
+
+    /// Applies the emergency fee input override used by this provider (if any) to the provided fee input.
+    fn apply_fee_input_override(&self, input: BatchFeeInput) -> BatchFeeInput {
+        input
+    }
 }
 
 /// The struct that represents the batch fee input provider to be used in the main node of the server, i.e.
 /// it explicitly gets the L1 gas price from the provider and uses it to calculate the batch fee input instead of getting
 /// it from other node.
+///
+/// If the chain uses a custom base token, L1 gas and pubdata prices (which are denominated in wei) are converted
+/// to the base token units using the conversion rate from [`BaseTokenFetcher`].
+///
+/// The computed batch fee input can be overridden by node operators in emergencies; see [`SharedFeeInputOverride`].
 #[derive(Debug)]
 pub struct MainNodeFeeInputProvider {
     provider: Arc<GasAdjuster>,
+    base_token_fetcher: Option<Arc<BaseTokenFetcher>>,
+    fee_input_override: Option<Arc<SharedFeeInputOverride>>,
     config: FeeModelConfig,
 }
 
+#[async_trait::async_trait]
 impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
+    async fn get_batch_fee_input_scaled(
+        &self,
+        l1_gas_price_scale_factor: f64,
+        l1_pubdata_price_scale_factor: f64,
+    ) -> BatchFeeInput {
+        let input = compute_batch_fee_input(
+            self.get_fee_model_params(),
+            l1_gas_price_scale_factor,
+            l1_pubdata_price_scale_factor,
+        );
+        self.apply_fee_input_override(input)
+    }
+
     fn get_fee_model_params(&self) -> FeeParams {
+        let ratio = self
+            .base_token_fetcher
+            .as_ref()
+            .map(|fetcher| fetcher.conversion_ratio())
+            .unwrap_or_default();
         match self.config {
             FeeModelConfig::V1(config) => FeeParams::V1(FeeParamsV1 {
                 config,
-                l1_gas_price: self.provider.estimate_effective_gas_price(),
+                l1_gas_price: ratio.convert(self.provider.estimate_effective_gas_price()),
             }),
             FeeModelConfig::V2(config) => FeeParams::V2(FeeParamsV2 {
                 config,
-                l1_gas_price: self.provider.estimate_effective_gas_price(),
-                l1_pubdata_price: self.provider.estimate_effective_pubdata_price(),
+                l1_gas_price: ratio.convert(self.provider.estimate_effective_gas_price()),
+                l1_pubdata_price: ratio.convert(self.provider.estimate_effective_pubdata_price()),
             }),
         }
     }
+
+    fn get_l1_gas_oracle_state(&self) -> Option<L1GasOracleState> {
+        let fee_params = self.get_fee_model_params();
+        let batch_fee_input =
+            self.apply_fee_input_override(compute_batch_fee_input(fee_params, 1.0, 1.0));
+        Some(self.provider.oracle_state(fee_params, batch_fee_input))
+    }
+
+    fn apply_fee_input_override(&self, input: BatchFeeInput) -> BatchFeeInput {
+        self.fee_input_override
+            .as_ref()
+            .map_or(input, |fee_input_override| fee_input_override.apply(input))
+    }
 }
 
 impl MainNodeFeeInputProvider {
     pub fn new(provider: Arc<GasAdjuster>, config: FeeModelConfig) -> Self {
-        Self { provider, config }
+        Self {
+            provider,
+            base_token_fetcher: None,
+            fee_input_override: None,
+            config,
+        }
+    }
+
+    /// Sets the fetcher of the base token conversion rate. If not set, the base token is assumed to be ETH.
+    pub fn with_base_token_fetcher(mut self, fetcher: Arc<BaseTokenFetcher>) -> Self {
+        self.base_token_fetcher = Some(fetcher);
+        self
+    }
+
+    /// Sets the emergency override of the batch fee input applied on top of the fee model.
+    pub fn with_fee_input_override(
+        mut self,
+        fee_input_override: Arc<SharedFeeInputOverride>,
+    ) -> Self {
+        self.fee_input_override = Some(fee_input_override);
+        self
     }
 }
 
 /// The fee model provider to be used in the API. It returns the maximal batch fee input between the projected main node one and
-/// the one from the last sealed miniblock.
+/// the one from the last sealed miniblock. The emergency fee input override of the inner provider takes precedence, so that
+/// the override is reflected by the API immediately rather than after the next miniblock is sealed.
 #[derive(Debug)]
 pub(crate) struct ApiFeeInputProvider {
     inner: Arc<dyn BatchFeeModelInputProvider>,
@@ -120,15 +185,45 @@ impl BatchFeeModelInputProvider for ApiFeeInputProvider {
             .await
             .unwrap();
 
-        last_miniblock_params
+        let input = last_miniblock_params
             .map(|header| inner_input.stricter(header.batch_fee_input))
-            .unwrap_or(inner_input)
+            .unwrap_or(inner_input);
+        self.inner.apply_fee_input_override(input)
     }
 
     /// Returns the fee model parameters.
     fn get_fee_model_params(&self) -> FeeParams {
         self.inner.get_fee_model_params()
     }
+
+    fn get_l1_gas_oracle_state(&self) -> Option<L1GasOracleState> {
+        self.inner.get_l1_gas_oracle_state()
+    }
+
+    fn apply_fee_input_override(&self, input: BatchFeeInput) -> BatchFeeInput {
+        self.inner.apply_fee_input_override(input)
+    }
+}
+
+/// Calculates the batch fee input based on the fee model params of any supported version.
+fn compute_batch_fee_input(
+    params: FeeParams,
+    l1_gas_price_scale_factor: f64,
+    l1_pubdata_price_scale_factor: f64,
+) -> BatchFeeInput {
+    match params {
+        FeeParams::V1(params) => BatchFeeInput::L1Pegged(compute_batch_fee_model_input_v1(
+            params,
+            l1_gas_price_scale_factor,
+        )),
+        FeeParams::V2(params) => {
+            BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
+                params,
+                l1_gas_price_scale_factor,
+                l1_pubdata_price_scale_factor,
+            ))
+        }
+    }
 }
 
 /// Calculates the batch fee input based on the main node parameters.
@@ -214,7 +309,14 @@ fn compute_batch_fee_model_input_v2(
 
 #[cfg(test)]
 mod tests {
+    use zksync_types::L2ChainId;
+
     use super::*;
+    use crate::{
+        fee_input_override::FeeInputOverride,
+        genesis::{ensure_genesis_state, GenesisParams},
+        utils::testonly::create_miniblock,
+    };
 
     // To test that overflow never happens, we'll use giant L1 gas price, i.e.
     // almost realistic very large value of 100k gwei. Since it is so large, we'll also
@@ -437,4 +539,55 @@ mod tests {
             "Max pubdata increase lowers pubdata price"
         );
     }
+
+    /// Fee input provider with constant fee params and a fee input override.
+    #[derive(Debug)]
+    struct MockOverriddenFeeInputProvider {
+        params: FeeParams,
+        fee_input_override: SharedFeeInputOverride,
+    }
+
+    impl BatchFeeModelInputProvider for MockOverriddenFeeInputProvider {
+        fn get_fee_model_params(&self) -> FeeParams {
+            self.params
+        }
+
+        fn apply_fee_input_override(&self, input: BatchFeeInput) -> BatchFeeInput {
+            self.fee_input_override.apply(input)
+        }
+    }
+
+    #[tokio::test]
+    async fn api_fee_input_provider_applies_lowering_override() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        let mut miniblock = create_miniblock(1);
+        miniblock.batch_fee_input = BatchFeeInput::l1_pegged(10_000_000_000, 1_000_000_000);
+        storage
+            .blocks_dal()
+            .insert_miniblock(&miniblock)
+            .await
+            .unwrap();
+        drop(storage);
+
+        let inner = Arc::new(MockOverriddenFeeInputProvider {
+            params: FeeParams::sensible_v1_default(),
+            fee_input_override: SharedFeeInputOverride::default(),
+        });
+        let provider = ApiFeeInputProvider::new(inner.clone(), pool);
+        let input = provider.get_batch_fee_input().await;
+        assert_eq!(input, miniblock.batch_fee_input);
+
+        inner.fee_input_override.set(Some(FeeInputOverride {
+            fair_l2_gas_price: Some(200_000_000),
+            fair_pubdata_price: None,
+            reason: String::new(),
+        }));
+        let input = provider.get_batch_fee_input().await;
+        // The override is applied even though the last sealed miniblock has a greater fair L2 gas price.
+        assert_eq!(input, BatchFeeInput::l1_pegged(10_000_000_000, 200_000_000));
+    }
 }
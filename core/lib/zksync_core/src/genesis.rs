@@ -2,12 +2,19 @@
 //! It initializes the Merkle tree with the basic setup (such as fields of special service accounts),
 //! setups the required databases, and outputs the data required to initialize a smart contract.
 
+use std::{
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fs,
+    path::Path,
+};
+
 use anyhow::Context as _;
 use multivm::{
     utils::get_max_gas_per_pubdata_byte,
     zk_evm_latest::aux_structures::{LogQuery as MultiVmLogQuery, Timestamp as MultiVMTimestamp},
     zkevm_test_harness_latest::witness::sort_storage_access::sort_storage_access_queries,
 };
+use serde::{Deserialize, Serialize};
 use zksync_contracts::{BaseSystemContracts, SET_CHAIN_ID_EVENT};
 use zksync_dal::StorageProcessor;
 use zksync_eth_client::{clients::QueryClient, EthInterface};
@@ -20,15 +27,20 @@ use zksync_types::{
     },
     commitment::{CommitmentInput, L1BatchCommitment},
     fee_model::BatchFeeInput,
-    get_code_key, get_system_context_init_logs,
+    get_code_key, get_known_code_key, get_nonce_key, get_system_context_init_logs,
     protocol_version::{decode_set_chain_id_event, L1VerifierConfig, ProtocolVersion},
     tokens::{TokenInfo, TokenMetadata, ETHEREUM_ADDRESS},
+    utils::{nonces_to_full_nonce, storage_key_for_eth_balance},
     web3::types::{BlockNumber, FilterBuilder},
     zk_evm_types::{LogQuery, Timestamp},
-    AccountTreeId, Address, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId,
-    StorageKey, StorageLog, StorageLogKind, H256,
+    AccountTreeId, Address, Bytes, L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId,
+    StorageKey, StorageLog, StorageLogKind, H256, U256,
+};
+use zksync_utils::{
+    be_words_to_bytes,
+    bytecode::{hash_bytecode, validate_bytecode},
+    h256_to_u256, u256_to_h256,
 };
-use zksync_utils::{be_words_to_bytes, bytecode::hash_bytecode, h256_to_u256, u256_to_h256};
 
 use crate::metadata_calculator::L1BatchWithLogs;
 
@@ -40,6 +52,8 @@ pub struct GenesisParams {
     pub system_contracts: Vec<DeployedContract>,
     pub first_verifier_address: Address,
     pub first_l1_verifier_config: L1VerifierConfig,
+    /// Additional state to include into the genesis L1 batch on top of system contracts.
+    pub state_dump: Option<GenesisStateDump>,
 }
 
 impl GenesisParams {
@@ -54,7 +68,134 @@ impl GenesisParams {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::zero(),
+            state_dump: None,
+        }
+    }
+}
+
+/// State of an account in [`GenesisStateDump`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisAccount {
+    pub address: Address,
+    #[serde(default)]
+    pub balance: U256,
+    /// Transaction nonce of the account.
+    #[serde(default)]
+    pub nonce: U256,
+    /// Deployment nonce of the account, i.e. the number of contracts deployed by it.
+    #[serde(default)]
+    pub deployment_nonce: U256,
+    /// Bytecode of the contract deployed at the account address, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+/// Custom state (e.g., exported from an existing chain) to initialize genesis with. The state is applied
+/// on top of system contracts and must not overwrite any of their storage slots.
+///
+/// Note that external nodes initialize genesis from system contracts only, so they cannot sync
+/// from a main node with custom genesis state.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisStateDump {
+    pub accounts: Vec<GenesisAccount>,
+    /// Expected genesis root hash. If specified, genesis fails if the computed root hash differs from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_hash: Option<H256>,
+}
+
+impl GenesisStateDump {
+    /// Loads a state dump from the JSON file at the specified path and validates it.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed reading genesis state dump from {path:?}"))?;
+        let dump: Self = serde_json::from_str(&raw)
+            .with_context(|| format!("failed parsing genesis state dump from {path:?}"))?;
+        dump.validate()?;
+        Ok(dump)
+    }
+
+    /// Checks that the dump is well-formed and doesn't define any storage slot more than once.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        let mut addresses = HashSet::with_capacity(self.accounts.len());
+        for account in &self.accounts {
+            let address = account.address;
+            anyhow::ensure!(
+                addresses.insert(address),
+                "account {address:?} is specified multiple times"
+            );
+            anyhow::ensure!(
+                account.nonce <= U256::from(u128::MAX)
+                    && account.deployment_nonce <= U256::from(u128::MAX),
+                "nonces of account {address:?} are too large"
+            );
+            if let Some(code) = &account.code {
+                validate_bytecode(&code.0).map_err(|err| {
+                    anyhow::anyhow!("invalid code for account {address:?}: {err}")
+                })?;
+            }
+        }
+
+        let mut keys = HashSet::new();
+        for log in self.all_storage_logs() {
+            anyhow::ensure!(
+                keys.insert(log.key),
+                "storage slot {:?} of account {:?} is defined multiple times",
+                log.key.key(),
+                log.key.address()
+            );
+        }
+        Ok(())
+    }
+
+    /// Returns storage logs corresponding to the dump. Zero values are omitted since they correspond
+    /// to the default storage state.
+    fn storage_logs(&self) -> Vec<StorageLog> {
+        let mut logs = self.all_storage_logs();
+        logs.retain(|log| !log.value.is_zero());
+        logs
+    }
+
+    fn all_storage_logs(&self) -> Vec<StorageLog> {
+        let mut logs = vec![];
+        // Several accounts may share the same code, so known code hashes are deduplicated.
+        let mut known_code_hashes = BTreeSet::new();
+        for account in &self.accounts {
+            let address = &account.address;
+            logs.push(StorageLog::new_write_log(
+                storage_key_for_eth_balance(address),
+                u256_to_h256(account.balance),
+            ));
+            let full_nonce = nonces_to_full_nonce(account.nonce, account.deployment_nonce);
+            logs.push(StorageLog::new_write_log(
+                get_nonce_key(address),
+                u256_to_h256(full_nonce),
+            ));
+            if let Some(code) = &account.code {
+                let code_hash = hash_bytecode(&code.0);
+                logs.push(StorageLog::new_write_log(get_code_key(address), code_hash));
+                known_code_hashes.insert(code_hash);
+            }
+
+            let account_id = AccountTreeId::new(*address);
+            logs.extend(account.storage.iter().map(|(&slot, &value)| {
+                StorageLog::new_write_log(StorageKey::new(account_id, slot), value)
+            }));
         }
+        logs.extend(known_code_hashes.iter().map(|code_hash| {
+            StorageLog::new_write_log(get_known_code_key(code_hash), H256::from_low_u64_be(1))
+        }));
+        logs
+    }
+
+    fn factory_deps(&self) -> impl Iterator<Item = (H256, Vec<u8>)> + '_ {
+        self.accounts.iter().filter_map(|account| {
+            let code = account.code.as_ref()?;
+            Some((hash_bytecode(&code.0), code.0.clone()))
+        })
     }
 }
 
@@ -84,6 +225,7 @@ pub async fn ensure_genesis_state(
         system_contracts,
         first_verifier_address,
         first_l1_verifier_config,
+        state_dump,
     } = genesis_params;
 
     let base_system_contracts_hashes = base_system_contracts.hashes();
@@ -97,6 +239,7 @@ pub async fn ensure_genesis_state(
         system_contracts,
         *first_l1_verifier_config,
         *first_verifier_address,
+        state_dump.as_ref(),
     )
     .await?;
     tracing::info!("chain_schema_genesis is complete");
@@ -108,6 +251,13 @@ pub async fn ensure_genesis_state(
     let metadata = ZkSyncTree::process_genesis_batch(&storage_logs);
     let genesis_root_hash = metadata.root_hash;
     let rollup_last_leaf_index = metadata.leaf_count + 1;
+    if let Some(expected_root_hash) = state_dump.as_ref().and_then(|dump| dump.root_hash) {
+        anyhow::ensure!(
+            genesis_root_hash == expected_root_hash,
+            "genesis root hash {genesis_root_hash:?} differs from the one expected by the state dump \
+             ({expected_root_hash:?})"
+        );
+    }
 
     let commitment_input = CommitmentInput::for_genesis_batch(
         genesis_root_hash,
@@ -176,10 +326,11 @@ async fn insert_system_contracts(
     storage: &mut StorageProcessor<'_>,
     contracts: &[DeployedContract],
     chain_id: L2ChainId,
+    state_dump: Option<&GenesisStateDump>,
 ) -> anyhow::Result<()> {
     let system_context_init_logs = (H256::default(), get_system_context_init_logs(chain_id));
 
-    let storage_logs: Vec<_> = contracts
+    let mut storage_logs: Vec<_> = contracts
         .iter()
         .map(|contract| {
             let hash = hash_bytecode(&contract.bytecode);
@@ -192,6 +343,33 @@ async fn insert_system_contracts(
         .chain(Some(system_context_init_logs))
         .collect();
 
+    let mut factory_deps: Vec<_> = contracts
+        .iter()
+        .map(|c| (hash_bytecode(&c.bytecode), c.bytecode.clone()))
+        .collect();
+
+    if let Some(state_dump) = state_dump {
+        let system_keys: HashSet<_> = storage_logs
+            .iter()
+            .flat_map(|(_, logs)| logs.iter().map(|log| log.key))
+            .collect();
+        let dump_logs = state_dump.storage_logs();
+        if let Some(log) = dump_logs.iter().find(|log| system_keys.contains(&log.key)) {
+            anyhow::bail!(
+                "genesis state dump overwrites storage slot {:?} of account {:?} set by system contracts",
+                log.key.key(),
+                log.key.address()
+            );
+        }
+        // Split logs into chunks so that log timestamps produced below remain unique.
+        storage_logs.extend(
+            dump_logs
+                .chunks(1 << 16)
+                .map(|chunk| (H256::default(), chunk.to_vec())),
+        );
+        factory_deps.extend(state_dump.factory_deps());
+    }
+
     let mut transaction = storage.start_transaction().await?;
     transaction
         .storage_logs_dal()
@@ -272,10 +450,7 @@ async fn insert_system_contracts(
         .apply_storage_logs(&storage_logs)
         .await;
 
-    let factory_deps = contracts
-        .iter()
-        .map(|c| (hash_bytecode(&c.bytecode), c.bytecode.clone()))
-        .collect();
+    let factory_deps = factory_deps.into_iter().collect();
     transaction
         .factory_deps_dal()
         .insert_factory_deps(MiniblockNumber(0), &factory_deps)
@@ -296,6 +471,7 @@ pub(crate) async fn create_genesis_l1_batch(
     system_contracts: &[DeployedContract],
     l1_verifier_config: L1VerifierConfig,
     verifier_address: Address,
+    state_dump: Option<&GenesisStateDump>,
 ) -> anyhow::Result<()> {
     let version = ProtocolVersion {
         id: protocol_version,
@@ -326,6 +502,7 @@ pub(crate) async fn create_genesis_l1_batch(
         base_system_contracts_hashes: base_system_contracts.hashes(),
         protocol_version: Some(protocol_version),
         virtual_blocks: 0,
+        logs_bloom: Default::default(),
     };
 
     let mut transaction = storage.start_transaction().await?;
@@ -343,6 +520,7 @@ pub(crate) async fn create_genesis_l1_batch(
             &[],
             &[],
             Default::default(),
+            Default::default(),
         )
         .await
         .context("failed inserting genesis L1 batch")?;
@@ -358,7 +536,7 @@ pub(crate) async fn create_genesis_l1_batch(
         .context("failed assigning genesis miniblock to L1 batch")?;
 
     insert_base_system_contracts_to_factory_deps(&mut transaction, base_system_contracts).await?;
-    insert_system_contracts(&mut transaction, system_contracts, chain_id)
+    insert_system_contracts(&mut transaction, system_contracts, chain_id, state_dump)
         .await
         .context("cannot insert system contracts")?;
     add_eth_token(&mut transaction).await?;
@@ -479,6 +657,7 @@ mod tests {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::random(),
+            state_dump: None,
         };
         ensure_genesis_state(&mut conn, L2ChainId::from(270), &params)
             .await
@@ -512,6 +691,7 @@ mod tests {
             system_contracts: get_system_smart_contracts(),
             first_l1_verifier_config: L1VerifierConfig::default(),
             first_verifier_address: Address::random(),
+            state_dump: None,
         };
         ensure_genesis_state(&mut conn, L2ChainId::max(), &params)
             .await
@@ -540,4 +720,140 @@ mod tests {
             .unwrap();
         assert!(!conn.blocks_dal().is_genesis_needed().await.unwrap());
     }
+
+    fn mock_state_dump() -> GenesisStateDump {
+        let account = GenesisAccount {
+            address: Address::repeat_byte(0x23),
+            balance: 1_000.into(),
+            nonce: 3.into(),
+            deployment_nonce: 1.into(),
+            code: Some(Bytes(vec![1; 32])),
+            storage: BTreeMap::from([
+                (H256::repeat_byte(1), H256::repeat_byte(0xff)),
+                (H256::repeat_byte(2), H256::zero()),
+            ]),
+        };
+        let eoa = GenesisAccount {
+            address: Address::repeat_byte(0x24),
+            balance: 500.into(),
+            ..GenesisAccount::default()
+        };
+        GenesisStateDump {
+            accounts: vec![account, eoa],
+            root_hash: None,
+        }
+    }
+
+    async fn run_genesis(params: &GenesisParams) -> anyhow::Result<H256> {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut conn, L2ChainId::from(270), params).await
+    }
+
+    #[tokio::test]
+    async fn running_genesis_with_state_dump() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let state_dump = mock_state_dump();
+        let params = GenesisParams {
+            state_dump: Some(state_dump.clone()),
+            ..GenesisParams::mock()
+        };
+        let root_hash = ensure_genesis_state(&mut conn, L2ChainId::from(270), &params)
+            .await
+            .unwrap();
+
+        let account = &state_dump.accounts[0];
+        let balance_key = storage_key_for_eth_balance(&account.address);
+        let balance = conn
+            .storage_web3_dal()
+            .get_value(&balance_key)
+            .await
+            .unwrap();
+        assert_eq!(h256_to_u256(balance), account.balance);
+        let nonce_key = get_nonce_key(&account.address);
+        let full_nonce = conn.storage_web3_dal().get_value(&nonce_key).await.unwrap();
+        assert_eq!(
+            h256_to_u256(full_nonce),
+            nonces_to_full_nonce(3.into(), 1.into())
+        );
+        let slot_key = StorageKey::new(AccountTreeId::new(account.address), H256::repeat_byte(1));
+        let slot_value = conn.storage_web3_dal().get_value(&slot_key).await.unwrap();
+        assert_eq!(slot_value, H256::repeat_byte(0xff));
+
+        let code = account.code.as_ref().unwrap();
+        let code_key = get_code_key(&account.address);
+        let code_hash = conn.storage_web3_dal().get_value(&code_key).await.unwrap();
+        assert_eq!(code_hash, hash_bytecode(&code.0));
+        let stored_code = conn
+            .factory_deps_dal()
+            .get_factory_dep(code_hash)
+            .await
+            .unwrap();
+        assert_eq!(stored_code.as_ref(), Some(&code.0));
+
+        let default_root_hash = run_genesis(&GenesisParams::mock()).await.unwrap();
+        assert_ne!(root_hash, default_root_hash);
+
+        // Genesis with the same state dump must be reproducible.
+        let params = GenesisParams {
+            state_dump: Some(GenesisStateDump {
+                root_hash: Some(root_hash),
+                ..state_dump.clone()
+            }),
+            ..GenesisParams::mock()
+        };
+        assert_eq!(run_genesis(&params).await.unwrap(), root_hash);
+    }
+
+    #[tokio::test]
+    async fn genesis_with_state_dump_fails_on_root_hash_mismatch() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut conn = pool.access_storage().await.unwrap();
+        let params = GenesisParams {
+            state_dump: Some(GenesisStateDump {
+                root_hash: Some(H256::repeat_byte(1)),
+                ..mock_state_dump()
+            }),
+            ..GenesisParams::mock()
+        };
+        let err = ensure_genesis_state(&mut conn, L2ChainId::from(270), &params)
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("root hash"), "{err}");
+        assert!(conn.blocks_dal().is_genesis_needed().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn genesis_with_state_dump_fails_on_overwriting_system_contracts() {
+        let system_contract = get_system_smart_contracts().remove(0);
+        let mut state_dump = mock_state_dump();
+        state_dump.accounts[0].address = *system_contract.account_id.address();
+        let params = GenesisParams {
+            state_dump: Some(state_dump),
+            ..GenesisParams::mock()
+        };
+        let err = format!("{:#}", run_genesis(&params).await.unwrap_err());
+        assert!(err.contains("set by system contracts"), "{err}");
+    }
+
+    #[test]
+    fn validating_state_dump() {
+        let state_dump = mock_state_dump();
+        state_dump.validate().unwrap();
+        let serialized = serde_json::to_string(&state_dump).unwrap();
+        let deserialized: GenesisStateDump = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, state_dump);
+
+        let mut invalid_dump = state_dump.clone();
+        invalid_dump.accounts[1].address = invalid_dump.accounts[0].address;
+        let err = invalid_dump.validate().unwrap_err().to_string();
+        assert!(err.contains("multiple times"), "{err}");
+
+        let mut invalid_dump = state_dump;
+        invalid_dump.accounts[0].code = Some(Bytes(vec![1; 31]));
+        let err = invalid_dump.validate().unwrap_err().to_string();
+        assert!(err.contains("invalid code"), "{err}");
+    }
 }
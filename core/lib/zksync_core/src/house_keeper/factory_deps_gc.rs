@@ -0,0 +1,121 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_dal::ConnectionPool;
+use zksync_types::H256;
+
+use super::metrics::FACTORY_DEPS_GC_METRICS;
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Garbage-collects factory deps that were never marked as known in `KnownCodesStorage` (e.g., because the transaction
+/// publishing them has failed) and aren't base system contracts of any protocol version. Known deps are never removed,
+/// even if they aren't deployed, since they can be deployed by their hash at any time. Only deps inserted in L1 batches
+/// executed on L1 at least the retention period ago are considered.
+///
+/// In the dry-run mode, factory deps are not removed; instead, the collector reports the space that would be reclaimed.
+#[derive(Debug)]
+pub struct FactoryDepsGarbageCollector {
+    gc_interval_ms: u64,
+    retention: Duration,
+    dry_run: bool,
+    connection_pool: ConnectionPool,
+    /// Factory deps are scanned in the order of their bytecode hashes; the scan restarts once it reaches the end.
+    next_hash: Option<H256>,
+    reclaimable_byte_count: u64,
+}
+
+impl FactoryDepsGarbageCollector {
+    /// Maximum number of factory deps scanned in a single run.
+    const MAX_FACTORY_DEPS_PER_RUN: usize = 1_000;
+
+    pub fn new(
+        gc_interval_ms: u64,
+        retention: Duration,
+        dry_run: bool,
+        connection_pool: ConnectionPool,
+    ) -> Self {
+        Self {
+            gc_interval_ms,
+            retention,
+            dry_run,
+            connection_pool,
+            next_hash: None,
+            reclaimable_byte_count: 0,
+        }
+    }
+
+    /// Returns the number of processed factory deps.
+    async fn collect_garbage(&mut self) -> anyhow::Result<usize> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("house_keeper")
+            .await?;
+        let last_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth_before(self.retention)
+            .await?;
+        let Some(last_l1_batch) = last_l1_batch else {
+            return Ok(0);
+        };
+        let (_, last_miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_l1_batch)
+            .await?
+            .with_context(|| format!("L1 batch #{last_l1_batch} doesn't have miniblocks"))?;
+
+        let (unknown_deps, next_hash) = storage
+            .factory_deps_dal()
+            .get_unknown_factory_deps(
+                last_miniblock,
+                self.next_hash,
+                Self::MAX_FACTORY_DEPS_PER_RUN,
+            )
+            .await?;
+        self.next_hash = next_hash;
+        let processed_count = unknown_deps.len();
+        if unknown_deps.is_empty() {
+            return Ok(0);
+        }
+
+        if self.dry_run {
+            let byte_count: u64 = unknown_deps.iter().map(|&(_, len)| len).sum();
+            self.reclaimable_byte_count += byte_count;
+            FACTORY_DEPS_GC_METRICS.reclaimable.inc_by(byte_count);
+            tracing::info!(
+                "[dry run] {processed_count} unknown factory deps from miniblocks up to #{last_miniblock} \
+                 can be removed, reclaiming {byte_count} bytes ({} bytes in total since start)",
+                self.reclaimable_byte_count
+            );
+        } else {
+            let hashes: Vec<_> = unknown_deps.iter().map(|&(hash, _)| hash).collect();
+            let (removed_count, byte_count) = storage
+                .factory_deps_dal()
+                .remove_unknown_factory_deps(&hashes)
+                .await?;
+            FACTORY_DEPS_GC_METRICS
+                .pruned_factory_deps
+                .inc_by(removed_count as u64);
+            FACTORY_DEPS_GC_METRICS.reclaimed.inc_by(byte_count);
+            tracing::info!(
+                "Removed {removed_count} unknown factory deps from miniblocks up to #{last_miniblock}, \
+                 reclaiming {byte_count} bytes"
+            );
+        }
+        Ok(processed_count)
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for FactoryDepsGarbageCollector {
+    const SERVICE_NAME: &'static str = "FactoryDepsGarbageCollector";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        self.collect_garbage().await?;
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.gc_interval_ms
+    }
+}
@@ -0,0 +1,18 @@
+use vise::{Counter, Metrics, Unit};
+
+/// Metrics for garbage collection of factory deps.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_factory_deps_gc")]
+pub(super) struct FactoryDepsGcMetrics {
+    /// Number of factory deps removed from Postgres.
+    pub pruned_factory_deps: Counter,
+    /// Total length of bytecodes of removed factory deps.
+    #[metrics(unit = Unit::Bytes)]
+    pub reclaimed: Counter,
+    /// Total length of bytecodes of factory deps that would be removed outside the dry-run mode.
+    #[metrics(unit = Unit::Bytes)]
+    pub reclaimable: Counter,
+}
+
+#[vise::register]
+pub(super) static FACTORY_DEPS_GC_METRICS: vise::Global<FactoryDepsGcMetrics> = vise::Global::new();
@@ -1,4 +1,5 @@
 pub mod blocks_state_reporter;
+pub mod factory_deps_gc;
 pub mod fri_proof_compressor_job_retry_manager;
 pub mod fri_proof_compressor_queue_monitor;
 pub mod fri_prover_job_retry_manager;
@@ -6,5 +7,8 @@ pub mod fri_prover_queue_monitor;
 pub mod fri_scheduler_circuit_queuer;
 pub mod fri_witness_generator_jobs_retry_manager;
 pub mod fri_witness_generator_queue_monitor;
+mod metrics;
 pub mod periodic_job;
+pub mod prover_artifacts_gc;
+pub mod utilization_report_generator;
 pub mod waiting_to_queued_fri_witness_job_mover;
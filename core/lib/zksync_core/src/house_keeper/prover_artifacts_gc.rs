@@ -0,0 +1,189 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use zksync_dal::{
+    fri_prover_artifacts_dal::{ProverArtifact, ProverArtifactType},
+    ConnectionPool,
+};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError};
+use zksync_types::L1BatchNumber;
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Statistics for artifacts of a single L1 batch.
+#[derive(Debug, Default)]
+struct CollectedArtifacts {
+    artifact_count: usize,
+    byte_count: u64,
+}
+
+/// Garbage-collects intermediate artifacts of the FRI proving pipeline (witness inputs, circuits, intermediate
+/// proofs etc.) from the prover object store for L1 batches with proofs verified on L1 at least the retention period
+/// ago and a completed proof compression job. The latter condition matters for proof sending modes that mark batches
+/// as proven without waiting for real proofs (`SkipEveryProof`, `OnlySampledProofs`). The final proof submitted to L1
+/// is retained.
+///
+/// In the dry-run mode, artifacts are not removed; instead, the collector reports the space that would be reclaimed.
+#[derive(Debug)]
+pub struct ProverArtifactsGarbageCollector {
+    gc_interval_ms: u64,
+    retention: Duration,
+    dry_run: bool,
+    connection_pool: ConnectionPool,
+    prover_connection_pool: ConnectionPool,
+    blob_store: Arc<dyn ObjectStore>,
+    /// Artifacts are not marked as cleaned in the dry-run mode, so the progress is tracked in memory.
+    next_l1_batch: L1BatchNumber,
+    reclaimable_byte_count: u64,
+}
+
+impl ProverArtifactsGarbageCollector {
+    /// Maximum number of L1 batches processed in a single run.
+    const MAX_L1_BATCHES_PER_RUN: usize = 10;
+
+    pub fn new(
+        gc_interval_ms: u64,
+        retention: Duration,
+        dry_run: bool,
+        connection_pool: ConnectionPool,
+        prover_connection_pool: ConnectionPool,
+        blob_store: Arc<dyn ObjectStore>,
+    ) -> Self {
+        Self {
+            gc_interval_ms,
+            retention,
+            dry_run,
+            connection_pool,
+            prover_connection_pool,
+            blob_store,
+            next_l1_batch: L1BatchNumber(0),
+            reclaimable_byte_count: 0,
+        }
+    }
+
+    fn bucket(artifact_type: ProverArtifactType) -> Bucket {
+        match artifact_type {
+            ProverArtifactType::WitnessInputs => Bucket::WitnessInput,
+            ProverArtifactType::Circuit => Bucket::ProverJobsFri,
+            ProverArtifactType::Proof => Bucket::ProofsFri,
+            ProverArtifactType::LeafAggregationInputs => Bucket::LeafAggregationWitnessJobsFri,
+            ProverArtifactType::NodeAggregationInputs => Bucket::NodeAggregationWitnessJobsFri,
+            ProverArtifactType::SchedulerInputs => Bucket::SchedulerWitnessJobsFri,
+        }
+    }
+
+    /// Removes artifacts from the object store (or measures them in the dry-run mode). Artifacts that are already
+    /// missing from the store are skipped.
+    async fn collect_artifacts(
+        &self,
+        artifacts: &[ProverArtifact],
+    ) -> Result<CollectedArtifacts, ObjectStoreError> {
+        let mut collected = CollectedArtifacts::default();
+        let unique_artifacts: HashSet<_> = artifacts
+            .iter()
+            .map(|artifact| (Self::bucket(artifact.artifact_type), &artifact.blob_url))
+            .collect();
+        for (bucket, key) in unique_artifacts {
+            let result = if self.dry_run {
+                self.blob_store.size_raw(bucket, key).await
+            } else {
+                self.blob_store.remove_raw(bucket, key).await.map(|()| 0)
+            };
+            match result {
+                Ok(byte_count) => {
+                    collected.artifact_count += 1;
+                    collected.byte_count += byte_count;
+                }
+                Err(ObjectStoreError::KeyNotFound(_)) => {
+                    tracing::debug!("Prover artifact `{key}` is missing from bucket `{bucket}`");
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(collected)
+    }
+
+    /// Returns the number of processed L1 batches.
+    async fn collect_garbage(&mut self) -> anyhow::Result<usize> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("house_keeper")
+            .await?;
+        let last_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_proven_on_eth_before(self.retention)
+            .await?;
+        drop(storage);
+        let Some(last_l1_batch) = last_l1_batch else {
+            return Ok(0);
+        };
+
+        let mut prover_storage = self
+            .prover_connection_pool
+            .access_storage_tagged("house_keeper")
+            .await?;
+        let l1_batches = prover_storage
+            .fri_prover_artifacts_dal()
+            .get_l1_batches_with_uncleaned_artifacts(
+                self.next_l1_batch,
+                last_l1_batch,
+                Self::MAX_L1_BATCHES_PER_RUN,
+            )
+            .await?;
+
+        let mut processed_count = 0;
+        for l1_batch_number in l1_batches {
+            let artifacts = prover_storage
+                .fri_prover_artifacts_dal()
+                .get_artifacts(l1_batch_number)
+                .await?;
+            let collected = match self.collect_artifacts(&artifacts).await {
+                Ok(collected) => collected,
+                Err(err) => {
+                    // Object store errors are transient; the batch will be retried on the next run.
+                    tracing::warn!(
+                        "Failed garbage-collecting prover artifacts for L1 batch #{l1_batch_number}: {err}"
+                    );
+                    break;
+                }
+            };
+
+            if self.dry_run {
+                self.reclaimable_byte_count += collected.byte_count;
+                tracing::info!(
+                    "[dry run] {} prover artifacts for L1 batch #{l1_batch_number} can be removed, \
+                     reclaiming {} bytes ({} bytes in total since start)",
+                    collected.artifact_count,
+                    collected.byte_count,
+                    self.reclaimable_byte_count
+                );
+            } else {
+                prover_storage
+                    .fri_prover_artifacts_dal()
+                    .mark_artifacts_as_cleaned(l1_batch_number)
+                    .await?;
+                tracing::info!(
+                    "Removed {} prover artifacts for L1 batch #{l1_batch_number}",
+                    collected.artifact_count
+                );
+            }
+            self.next_l1_batch = l1_batch_number + 1;
+            processed_count += 1;
+        }
+        Ok(processed_count)
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for ProverArtifactsGarbageCollector {
+    const SERVICE_NAME: &'static str = "ProverArtifactsGarbageCollector";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        self.collect_garbage().await?;
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.gc_interval_ms
+    }
+}
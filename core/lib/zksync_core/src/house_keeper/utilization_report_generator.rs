@@ -0,0 +1,150 @@
+use anyhow::Context as _;
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use zksync_dal::{utilization_reports_dal::date_timestamp_range, ConnectionPool};
+
+use crate::house_keeper::periodic_job::PeriodicJob;
+
+/// Generates daily reports on the capacity utilization of sealed L1 batches (average gas, pubdata and circuits
+/// utilization, and the histogram of seal criteria). A report is generated for each completed UTC day
+/// after the latest reported one. A day is considered completed once an L1 batch with a timestamp after the day
+/// is sealed, so that reports include L1 batches opened before midnight but sealed after it.
+#[derive(Debug)]
+pub struct UtilizationReportGenerator {
+    generation_interval_ms: u64,
+    connection_pool: ConnectionPool,
+}
+
+impl UtilizationReportGenerator {
+    /// Maximum number of reports generated in a single run, so that catching up after a long downtime
+    /// doesn't hog the database.
+    const MAX_REPORTS_PER_RUN: usize = 7;
+
+    pub fn new(generation_interval_ms: u64, connection_pool: ConnectionPool) -> Self {
+        Self {
+            generation_interval_ms,
+            connection_pool,
+        }
+    }
+
+    /// Generates reports for completed days before `today`. Returns the number of generated reports.
+    async fn generate_reports(&self, today: NaiveDate) -> anyhow::Result<usize> {
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("house_keeper")
+            .await?;
+        let latest_report_date = storage
+            .utilization_reports_dal()
+            .get_latest_report_date()
+            .await?;
+        // If there are no reports yet, start from the last completed day instead of backfilling the entire history.
+        let mut date = match latest_report_date {
+            Some(date) => date.succ_opt(),
+            None => today.pred_opt(),
+        }
+        .context("report date is out of range")?;
+
+        let Some(latest_l1_batch_timestamp) = storage
+            .utilization_reports_dal()
+            .get_latest_l1_batch_timestamp()
+            .await?
+        else {
+            return Ok(0);
+        };
+        let is_completed = |date: NaiveDate| {
+            let (_, end_timestamp) = date_timestamp_range(date);
+            date < today && end_timestamp <= latest_l1_batch_timestamp
+        };
+
+        let mut report_count = 0;
+        while is_completed(date) && report_count < Self::MAX_REPORTS_PER_RUN {
+            let report = storage
+                .utilization_reports_dal()
+                .compute_report(date)
+                .await?;
+            storage
+                .utilization_reports_dal()
+                .insert_report(&report)
+                .await?;
+            tracing::info!(
+                "Generated utilization report for {date} covering {} L1 batches",
+                report.l1_batch_count
+            );
+            report_count += 1;
+            date = date.succ_opt().context("report date is out of range")?;
+        }
+        Ok(report_count)
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for UtilizationReportGenerator {
+    const SERVICE_NAME: &'static str = "UtilizationReportGenerator";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let today = Utc::now().date_naive();
+        self.generate_reports(today).await?;
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.generation_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::L1BatchNumber;
+
+    use super::*;
+    use crate::utils::testonly::create_l1_batch;
+
+    async fn insert_l1_batch(pool: &ConnectionPool, number: u32, timestamp: i64) {
+        let mut header = create_l1_batch(number);
+        header.timestamp = timestamp as u64;
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reports_are_generated_only_for_completed_days() {
+        let pool = ConnectionPool::test_pool().await;
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .protocol_versions_dal()
+            .save_protocol_version_with_tx(Default::default())
+            .await;
+        drop(storage);
+
+        let today = NaiveDate::from_ymd_opt(2024, 3, 3).unwrap();
+        let yesterday = today.pred_opt().unwrap();
+        let (yesterday_start, today_start) = date_timestamp_range(yesterday);
+        let generator = UtilizationReportGenerator::new(1_000, pool.clone());
+        assert_eq!(generator.generate_reports(today).await.unwrap(), 0);
+
+        // The L1 batch opened yesterday may be sealed today; the report must wait for the next L1 batch.
+        insert_l1_batch(&pool, 1, yesterday_start + 100).await;
+        assert_eq!(generator.generate_reports(today).await.unwrap(), 0);
+
+        insert_l1_batch(&pool, 2, today_start + 1).await;
+        assert_eq!(generator.generate_reports(today).await.unwrap(), 1);
+        let mut storage = pool.access_storage().await.unwrap();
+        let report = storage
+            .utilization_reports_dal()
+            .get_report(yesterday)
+            .await
+            .unwrap()
+            .expect("no report");
+        assert_eq!(report.l1_batch_count, 1);
+        assert_eq!(report.first_l1_batch_number, Some(L1BatchNumber(1)));
+        drop(storage);
+
+        // Reports for today must not be generated even if there are L1 batches from tomorrow.
+        insert_l1_batch(&pool, 3, today_start + 86_400).await;
+        assert_eq!(generator.generate_reports(today).await.unwrap(), 0);
+    }
+}
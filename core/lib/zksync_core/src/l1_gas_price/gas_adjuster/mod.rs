@@ -10,7 +10,11 @@ use tokio::sync::watch;
 use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
 use zksync_eth_client::{Error, EthInterface};
 use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
-use zksync_types::{U256, U64};
+use zksync_types::{
+    api::{L1GasOracleParams, L1GasOracleState},
+    fee_model::{BatchFeeInput, FeeParams},
+    U256, U64,
+};
 
 use self::metrics::METRICS;
 use super::L1TxParamsProvider;
@@ -203,6 +207,39 @@ impl GasAdjuster {
         }
     }
 
+    /// Returns a snapshot of the internal state of this adjuster, together with the fee model values
+    /// applied by the caller.
+    pub(crate) fn oracle_state(
+        &self,
+        fee_params: FeeParams,
+        batch_fee_input: BatchFeeInput,
+    ) -> L1GasOracleState {
+        let config = &self.config;
+        L1GasOracleState {
+            last_processed_l1_block: self.base_fee_statistics.last_processed_block() as u64,
+            base_fee_samples: self.base_fee_statistics.samples(),
+            median_base_fee: self.base_fee_statistics.median(),
+            blob_base_fee_samples: self.blob_base_fee_statistics.samples(),
+            median_blob_base_fee: self.blob_base_fee_statistics.median(),
+            params: L1GasOracleParams {
+                max_base_fee_samples: config.max_base_fee_samples,
+                max_blob_base_fee_samples: config.num_samples_for_blob_base_fee_estimate,
+                pricing_formula_parameter_a: config.pricing_formula_parameter_a,
+                pricing_formula_parameter_b: config.pricing_formula_parameter_b,
+                internal_l1_pricing_multiplier: config.internal_l1_pricing_multiplier,
+                internal_pubdata_pricing_multiplier: config.internal_pubdata_pricing_multiplier,
+                internal_enforced_l1_gas_price: config.internal_enforced_l1_gas_price,
+                max_l1_gas_price: config.max_l1_gas_price(),
+                max_blob_base_fee: config.max_blob_base_fee(),
+                priority_fee_per_gas: self.get_priority_fee(),
+            },
+            effective_l1_gas_price: self.estimate_effective_gas_price(),
+            effective_l1_pubdata_price: self.estimate_effective_pubdata_price(),
+            fee_params,
+            batch_fee_input,
+        }
+    }
+
     /// Returns vector of base fees and blob base fees for given block range.
     /// Note, that data for pre-dencun blocks won't be included in the vector returned.
     async fn get_base_fees_history(
@@ -305,6 +342,23 @@ impl L1TxParamsProvider for GasAdjuster {
         last_block_base_fee * 875 / 1000
     }
 
+    fn get_last_block_base_fee(&self) -> u64 {
+        self.base_fee_statistics.last_added_value()
+    }
+
+    fn get_median_base_fee(&self) -> u64 {
+        self.base_fee_statistics.median()
+    }
+
+    fn get_last_block_blob_base_fee(&self) -> u64 {
+        let blob_base_fee = self.blob_base_fee_statistics.last_added_value();
+        if blob_base_fee > U256::from(u64::MAX) {
+            u64::MAX
+        } else {
+            blob_base_fee.as_u64()
+        }
+    }
+
     // Priority fee is set to constant, sourced from config.
     // Reasoning behind this is the following:
     // High `priority_fee` means high demand for block space,
@@ -395,4 +449,9 @@ impl<T: Ord + Copy + Default> GasStatistics<T> {
     pub fn last_processed_block(&self) -> usize {
         self.0.read().unwrap().last_processed_block
     }
+
+    /// Returns samples in the window, from the oldest to the newest.
+    pub fn samples(&self) -> Vec<T> {
+        self.0.read().unwrap().samples.iter().copied().collect()
+    }
 }
@@ -2,6 +2,7 @@ use std::{collections::VecDeque, sync::Arc};
 
 use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
 use zksync_eth_client::clients::MockEthereum;
+use zksync_types::fee_model::{BatchFeeInput, FeeParams};
 
 use super::{GasAdjuster, GasStatisticsInner};
 
@@ -111,6 +112,22 @@ async fn kept_updated() {
         adjuster.blob_base_fee_statistics.0.read().unwrap().median(),
         expected_median_blob_base_fee
     );
+
+    let fee_params = FeeParams::sensible_v1_default();
+    let batch_fee_input = BatchFeeInput::l1_pegged(1_000_000_000, 100_000_000);
+    let state = adjuster.oracle_state(fee_params, batch_fee_input);
+    assert_eq!(state.last_processed_l1_block, 7);
+    assert_eq!(state.base_fee_samples, [8, 7, 5, 5, 8]);
+    assert_eq!(state.median_base_fee, 7);
+    assert_eq!(state.blob_base_fee_samples.len(), 3);
+    assert_eq!(state.median_blob_base_fee, expected_median_blob_base_fee);
+    assert_eq!(state.params.max_base_fee_samples, 5);
+    assert_eq!(state.params.max_blob_base_fee_samples, 3);
+    assert_eq!(state.params.priority_fee_per_gas, 5);
+    // `(floor(1.5 * 7) + 5) * 0.8`, where 7 is the median base fee and 5 is the priority fee
+    assert_eq!(state.effective_l1_gas_price, 12);
+    assert_eq!(state.effective_l1_pubdata_price, 12 * 17);
+    assert_eq!(state.batch_fee_input, batch_fee_input);
 }
 
 #[test]
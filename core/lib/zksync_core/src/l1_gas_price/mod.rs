@@ -26,4 +26,13 @@ pub trait L1TxParamsProvider: fmt::Debug + 'static + Send + Sync {
 
     /// Returns a lower bound for the `base_fee` value for the next L1 block.
     fn get_next_block_minimal_base_fee(&self) -> u64;
+
+    /// Returns the `base_fee` value of the last observed L1 block.
+    fn get_last_block_base_fee(&self) -> u64;
+
+    /// Returns the median `base_fee` value over the recently observed L1 blocks.
+    fn get_median_base_fee(&self) -> u64;
+
+    /// Returns the blob base fee value (EIP4844) of the last observed L1 block.
+    fn get_last_block_blob_base_fee(&self) -> u64;
 }
@@ -0,0 +1,14 @@
+use vise::{Counter, Gauge, Metrics};
+
+/// Metrics for the L1 state checker.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_l1_state_checker")]
+pub(super) struct L1StateCheckerMetrics {
+    /// Number of the last L1 batch executed on L1 that matches the local data.
+    pub last_checked_batch: Gauge<u64>,
+    /// Number of L1 batches executed on L1 that don't match the local data.
+    pub mismatched_batches: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<L1StateCheckerMetrics> = vise::Global::new();
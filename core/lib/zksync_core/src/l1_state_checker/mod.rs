@@ -0,0 +1,272 @@
+//! Periodic cross-check of L1 batches executed on L1 against the data computed by the node itself.
+//! Unlike the consistency checker used by external nodes, this component is intended for the main node,
+//! which is the source of the data committed to L1 in the first place.
+
+use std::{fmt, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::{CallFunctionArgs, Error as L1ClientError, EthInterface};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_l1_contract_interface::i_executor::structures::StoredBatchInfo;
+use zksync_types::{
+    ethabi,
+    web3::{
+        contract::tokens::{Detokenize, Tokenize},
+        types::{BlockId, BlockNumber},
+    },
+    Address, L1BatchNumber, H256, U256,
+};
+
+use self::metrics::METRICS;
+use crate::utils::wait_for_l1_batch_with_metadata;
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// L1 data used by [`L1StateChecker`]. All data is read as of the latest finalized L1 block.
+#[async_trait]
+trait L1StateClient: fmt::Debug + Send + Sync {
+    /// Returns the number of the last L1 batch executed on L1.
+    async fn last_executed_batch(&self) -> Result<L1BatchNumber, L1ClientError>;
+
+    /// Returns the hash of `StoredBatchInfo` for the specified batch stored by the L1 contract.
+    async fn stored_batch_hash(&self, number: L1BatchNumber) -> Result<H256, L1ClientError>;
+}
+
+/// [`L1StateClient`] implementation querying the diamond proxy contract.
+#[derive(Debug)]
+struct DiamondProxyClient {
+    eth_client: Box<dyn EthInterface>,
+    diamond_proxy_address: Address,
+    diamond_proxy_abi: ethabi::Contract,
+}
+
+impl DiamondProxyClient {
+    async fn call<T: Detokenize>(
+        &self,
+        function_name: &str,
+        params: impl Tokenize,
+    ) -> Result<T, L1ClientError> {
+        let args = CallFunctionArgs::new(function_name, params)
+            .with_block(BlockId::Number(BlockNumber::Finalized))
+            .for_contract(self.diamond_proxy_address, self.diamond_proxy_abi.clone());
+        let tokens = self.eth_client.call_contract_function(args).await?;
+        Ok(T::from_tokens(tokens)?)
+    }
+}
+
+#[async_trait]
+impl L1StateClient for DiamondProxyClient {
+    async fn last_executed_batch(&self) -> Result<L1BatchNumber, L1ClientError> {
+        let number: U256 = self.call("getTotalBatchesExecuted", ()).await?;
+        Ok(L1BatchNumber(number.as_u32()))
+    }
+
+    async fn stored_batch_hash(&self, number: L1BatchNumber) -> Result<H256, L1ClientError> {
+        self.call("storedBatchHash", (U256::from(number.0),)).await
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum CheckError {
+    #[error("Web3 error communicating with L1")]
+    Web3(#[from] L1ClientError),
+    #[error("Internal error")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Health details reported by [`L1StateChecker`].
+#[derive(Debug, Default, Serialize)]
+struct L1StateCheckerDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_executed_batch: Option<L1BatchNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_checked_batch: Option<L1BatchNumber>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mismatched_batches: Vec<L1BatchNumber>,
+}
+
+impl L1StateCheckerDetails {
+    fn health(&self) -> Health {
+        let status = if self.mismatched_batches.is_empty() {
+            HealthStatus::Ready
+        } else {
+            HealthStatus::Affected
+        };
+        Health::from(status).with_details(self)
+    }
+}
+
+/// Component comparing state roots and commitments of L1 batches executed (i.e., finalized) on L1
+/// with the data computed locally. A mismatch means that either the local data or the L1 state is corrupted,
+/// which should never happen on a correctly operating main node.
+///
+/// Mismatches are reported via logs, metrics and the health check. If the checker is configured to halt
+/// on mismatch, it returns an error from [`Self::run()`], which stops the node (including the state keeper),
+/// so that no new blocks are produced on top of the diverged state.
+#[derive(Debug)]
+pub struct L1StateChecker {
+    pool: ConnectionPool,
+    l1_client: Box<dyn L1StateClient>,
+    max_batches_to_recheck: u32,
+    halt_on_mismatch: bool,
+    poll_interval: Duration,
+    health_updater: HealthUpdater,
+    details: L1StateCheckerDetails,
+}
+
+impl L1StateChecker {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+    /// How many executed batches to recheck when starting.
+    const DEFAULT_MAX_BATCHES_TO_RECHECK: u32 = 10;
+
+    pub fn new(
+        pool: ConnectionPool,
+        eth_client: Box<dyn EthInterface>,
+        diamond_proxy_address: Address,
+        halt_on_mismatch: bool,
+    ) -> Self {
+        let l1_client = DiamondProxyClient {
+            eth_client,
+            diamond_proxy_address,
+            diamond_proxy_abi: zksync_contracts::zksync_contract(),
+        };
+        Self::from_client(pool, Box::new(l1_client), halt_on_mismatch)
+    }
+
+    fn from_client(
+        pool: ConnectionPool,
+        l1_client: Box<dyn L1StateClient>,
+        halt_on_mismatch: bool,
+    ) -> Self {
+        let (_, health_updater) = ReactiveHealthCheck::new("l1_state_checker");
+        Self {
+            pool,
+            l1_client,
+            max_batches_to_recheck: Self::DEFAULT_MAX_BATCHES_TO_RECHECK,
+            halt_on_mismatch,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            health_updater,
+            details: L1StateCheckerDetails::default(),
+        }
+    }
+
+    /// Returns health check associated with this checker.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Returns the hash of `StoredBatchInfo` for the locally stored batch, or `None` if the batch
+    /// or its metadata is not persisted.
+    async fn local_batch_hash(&self, number: L1BatchNumber) -> anyhow::Result<Option<H256>> {
+        let mut storage = self.pool.access_storage_tagged("l1_state_checker").await?;
+        let l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_metadata(number)
+            .await
+            .with_context(|| format!("failed loading metadata for L1 batch #{number}"))?;
+        Ok(l1_batch.map(|l1_batch| StoredBatchInfo(&l1_batch).hash()))
+    }
+
+    /// Checks all batches executed on L1 starting from `next_batch`, advancing it.
+    async fn check_executed_batches(
+        &mut self,
+        next_batch: &mut L1BatchNumber,
+    ) -> Result<(), CheckError> {
+        let last_executed_batch = self.l1_client.last_executed_batch().await?;
+        self.details.last_executed_batch = Some(last_executed_batch);
+
+        while *next_batch <= last_executed_batch {
+            let number = *next_batch;
+            let Some(local_hash) = self.local_batch_hash(number).await? else {
+                // The batch may be executed on L1 before its metadata is persisted locally, e.g. if the node
+                // is recovering; we'll retry on the next iteration.
+                tracing::debug!("Metadata for L1 batch #{number} is not persisted yet");
+                break;
+            };
+            let l1_hash = self.l1_client.stored_batch_hash(number).await?;
+
+            if l1_hash == local_hash {
+                tracing::debug!("L1 batch #{number} executed on L1 matches local data");
+                METRICS.last_checked_batch.set(number.0.into());
+                self.details.last_checked_batch = Some(number);
+            } else {
+                tracing::error!(
+                    "L1 batch #{number} executed on L1 doesn't match local data: \
+                     local stored batch hash is {local_hash:?}, hash on L1 is {l1_hash:?}"
+                );
+                METRICS.mismatched_batches.inc();
+                self.details.mismatched_batches.push(number);
+                self.health_updater.update(self.details.health());
+                if self.halt_on_mismatch {
+                    let err = anyhow::anyhow!(
+                        "L1 batch #{number} executed on L1 doesn't match local data; halting the node"
+                    );
+                    return Err(err.into());
+                }
+            }
+            *next_batch += 1;
+        }
+        self.health_updater.update(self.details.health());
+        Ok(())
+    }
+
+    /// Determines the first batch to check based on the number of batches executed on L1.
+    async fn first_batch_to_check(
+        &self,
+        earliest_l1_batch_number: L1BatchNumber,
+    ) -> Result<L1BatchNumber, CheckError> {
+        let last_executed_batch = self.l1_client.last_executed_batch().await?;
+        let first_batch_to_check =
+            L1BatchNumber((last_executed_batch.0 + 1).saturating_sub(self.max_batches_to_recheck));
+        // The genesis batch is never committed to L1, so we skip it.
+        Ok(first_batch_to_check
+            .max(earliest_l1_batch_number)
+            .max(L1BatchNumber(1)))
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater.update(self.details.health());
+        let earliest_l1_batch_number =
+            wait_for_l1_batch_with_metadata(&self.pool, self.poll_interval, &mut stop_receiver)
+                .await?;
+        let Some(earliest_l1_batch_number) = earliest_l1_batch_number else {
+            tracing::info!("Stop signal received, L1 state checker is shutting down");
+            return Ok(());
+        };
+
+        let mut next_batch = None;
+        while !*stop_receiver.borrow_and_update() {
+            let result = match next_batch.as_mut() {
+                Some(next_batch) => self.check_executed_batches(next_batch).await,
+                None => match self.first_batch_to_check(earliest_l1_batch_number).await {
+                    Ok(first_batch) => {
+                        tracing::info!("Starting L1 state checks from L1 batch #{first_batch}");
+                        next_batch = Some(first_batch);
+                        continue;
+                    }
+                    Err(err) => Err(err),
+                },
+            };
+
+            match result {
+                Ok(()) => {}
+                Err(CheckError::Web3(err)) => {
+                    tracing::warn!("Error accessing L1; will retry after a delay: {err}");
+                }
+                Err(CheckError::Internal(err)) => return Err(err),
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, L1 state checker is shutting down");
+        Ok(())
+    }
+}
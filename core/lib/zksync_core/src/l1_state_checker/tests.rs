@@ -0,0 +1,204 @@
+//! Tests for the L1 state checker.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use assert_matches::assert_matches;
+use zksync_health_check::CheckHealth;
+use zksync_types::{commitment::L1BatchWithMetadata, L2ChainId};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::{
+        create_l1_batch, create_l1_batch_metadata, l1_batch_metadata_to_commitment_artifacts,
+    },
+};
+
+#[derive(Debug, Default)]
+struct MockL1State {
+    last_executed_batch: L1BatchNumber,
+    stored_batch_hashes: HashMap<L1BatchNumber, H256>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MockL1StateClient(Arc<Mutex<MockL1State>>);
+
+impl MockL1StateClient {
+    fn execute_batch(&self, l1_batch: &L1BatchWithMetadata) {
+        let mut state = self.0.lock().unwrap();
+        let number = l1_batch.header.number;
+        state.last_executed_batch = state.last_executed_batch.max(number);
+        state
+            .stored_batch_hashes
+            .insert(number, StoredBatchInfo(l1_batch).hash());
+    }
+}
+
+#[async_trait]
+impl L1StateClient for MockL1StateClient {
+    async fn last_executed_batch(&self) -> Result<L1BatchNumber, L1ClientError> {
+        Ok(self.0.lock().unwrap().last_executed_batch)
+    }
+
+    async fn stored_batch_hash(&self, number: L1BatchNumber) -> Result<H256, L1ClientError> {
+        let state = self.0.lock().unwrap();
+        Ok(state
+            .stored_batch_hashes
+            .get(&number)
+            .copied()
+            .unwrap_or_default())
+    }
+}
+
+fn create_l1_batch_with_metadata(number: u32) -> L1BatchWithMetadata {
+    L1BatchWithMetadata {
+        header: create_l1_batch(number),
+        metadata: create_l1_batch_metadata(number),
+        raw_published_factory_deps: vec![],
+    }
+}
+
+async fn prepare_storage(pool: &ConnectionPool) -> Vec<L1BatchWithMetadata> {
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+
+    let l1_batches: Vec<_> = (1..=3).map(create_l1_batch_with_metadata).collect();
+    for l1_batch in &l1_batches {
+        let number = l1_batch.header.number;
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&l1_batch.header)
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .save_l1_batch_tree_data(number, &l1_batch.metadata.tree_data())
+            .await
+            .unwrap();
+        storage
+            .blocks_dal()
+            .save_l1_batch_commitment_artifacts(
+                number,
+                &l1_batch_metadata_to_commitment_artifacts(&l1_batch.metadata),
+            )
+            .await
+            .unwrap();
+    }
+    l1_batches
+}
+
+fn create_checker(
+    pool: ConnectionPool,
+    client: &MockL1StateClient,
+    halt_on_mismatch: bool,
+) -> L1StateChecker {
+    let mut checker = L1StateChecker::from_client(pool, Box::new(client.clone()), halt_on_mismatch);
+    checker.poll_interval = Duration::from_millis(10);
+    checker
+}
+
+#[tokio::test]
+async fn checking_executed_batches() {
+    let pool = ConnectionPool::test_pool().await;
+    let l1_batches = prepare_storage(&pool).await;
+    let client = MockL1StateClient::default();
+    client.execute_batch(&l1_batches[0]);
+
+    let mut checker = create_checker(pool, &client, false);
+    let health_check = checker.health_check();
+    let mut next_batch = checker
+        .first_batch_to_check(L1BatchNumber(0))
+        .await
+        .unwrap();
+    assert_eq!(next_batch, L1BatchNumber(1));
+
+    checker
+        .check_executed_batches(&mut next_batch)
+        .await
+        .unwrap();
+    assert_eq!(next_batch, L1BatchNumber(2));
+    assert_eq!(checker.details.last_checked_batch, Some(L1BatchNumber(1)));
+
+    for l1_batch in &l1_batches[1..] {
+        client.execute_batch(l1_batch);
+    }
+    checker
+        .check_executed_batches(&mut next_batch)
+        .await
+        .unwrap();
+    assert_eq!(next_batch, L1BatchNumber(4));
+    assert_eq!(checker.details.last_checked_batch, Some(L1BatchNumber(3)));
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+}
+
+#[tokio::test]
+async fn reporting_mismatched_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    let l1_batches = prepare_storage(&pool).await;
+    let client = MockL1StateClient::default();
+    for (i, l1_batch) in l1_batches.iter().enumerate() {
+        let mut l1_batch = l1_batch.clone();
+        if i == 1 {
+            l1_batch.metadata.root_hash = H256::repeat_byte(0xff);
+        }
+        client.execute_batch(&l1_batch);
+    }
+
+    let mut checker = create_checker(pool, &client, false);
+    let health_check = checker.health_check();
+    let mut next_batch = L1BatchNumber(1);
+    checker
+        .check_executed_batches(&mut next_batch)
+        .await
+        .unwrap();
+    // The checker should continue checking batches after the mismatch.
+    assert_eq!(next_batch, L1BatchNumber(4));
+    assert_eq!(checker.details.mismatched_batches, [L1BatchNumber(2)]);
+    assert_eq!(checker.details.last_checked_batch, Some(L1BatchNumber(3)));
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Affected
+    );
+}
+
+#[tokio::test]
+async fn halting_on_mismatched_batch() {
+    let pool = ConnectionPool::test_pool().await;
+    let l1_batches = prepare_storage(&pool).await;
+    let client = MockL1StateClient::default();
+    let mut l1_batch = l1_batches[0].clone();
+    l1_batch.metadata.commitment = H256::repeat_byte(0xff);
+    client.execute_batch(&l1_batch);
+
+    let checker = create_checker(pool, &client, true);
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let result = tokio::time::timeout(Duration::from_secs(10), checker.run(stop_receiver))
+        .await
+        .expect("L1 state checker timed out");
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("L1 batch #1"), "{err}");
+}
+
+#[tokio::test]
+async fn checker_stops_on_signal() {
+    let pool = ConnectionPool::test_pool().await;
+    prepare_storage(&pool).await;
+    let client = MockL1StateClient::default();
+    let checker = create_checker(pool, &client, true);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let checker_task = tokio::spawn(checker.run(stop_receiver));
+    stop_sender.send_replace(true);
+    let result = tokio::time::timeout(Duration::from_secs(10), checker_task)
+        .await
+        .expect("L1 state checker timed out");
+    assert_matches!(result.unwrap(), Ok(()));
+}
@@ -1,17 +1,27 @@
 #![allow(clippy::upper_case_acronyms, clippy::derive_partial_eq_without_eq)]
 
-use std::{net::Ipv4Addr, str::FromStr, sync::Arc, time::Instant};
+use std::{
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use api_server::tx_sender::master_pool_sink::MasterPoolSink;
+use fee_input_override::{
+    FeeInputOverrideBounds, FeeInputOverrideReloader, SharedFeeInputOverride,
+};
 use fee_model::{ApiFeeInputProvider, BatchFeeModelInputProvider, MainNodeFeeInputProvider};
 use futures::channel::oneshot;
 use prometheus_exporter::PrometheusExporterConfig;
 use temp_config_store::TempConfigStore;
 use tokio::{sync::watch, task::JoinHandle};
 use zksync_circuit_breaker::{
-    l1_txs::FailedL1TransactionChecker, replication_lag::ReplicationLagChecker, CircuitBreaker,
-    CircuitBreakerChecker, CircuitBreakerError,
+    l1_contracts::L1ContractsVersionChecker, l1_txs::FailedL1TransactionChecker,
+    replication_lag::ReplicationLagChecker, CircuitBreaker, CircuitBreakerChecker,
+    CircuitBreakerError,
 };
 use zksync_concurrency::{ctx, scope};
 use zksync_config::{
@@ -24,25 +34,26 @@ use zksync_config::{
         contracts::ProverAtGenesis,
         database::{MerkleTreeConfig, MerkleTreeMode},
     },
-    ApiConfig, ContractsConfig, DBConfig, ETHSenderConfig, PostgresConfig,
+    ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, PostgresConfig,
 };
 use zksync_contracts::{governance_contract, BaseSystemContracts};
 use zksync_dal::{healthcheck::ConnectionPoolHealthCheck, ConnectionPool};
 use zksync_eth_client::{
-    clients::{PKSigningClient, QueryClient},
+    clients::{PKSigningClient, QueryClient, RemoteSigningClient},
     BoundEthInterface, CallFunctionArgs, EthInterface,
 };
+use zksync_eth_signer::RemoteSigner;
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_l1_contract_interface::i_executor::commit::kzg::KzgSettings;
 use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_queued_job_processor::JobProcessor;
-use zksync_state::PostgresStorageCaches;
+use zksync_state::{PostgresStorageCaches, RocksdbReplica};
 use zksync_types::{
-    fee_model::FeeModelConfig,
+    fee_model::{FeeModelConfig, IntrinsicGasOverrides},
     protocol_version::{L1VerifierConfig, VerifierParams},
     system_contracts::get_system_smart_contracts,
     web3::contract::tokens::Detokenize,
-    L2ChainId, PackedEthSignature, ProtocolVersionId,
+    Address, IntrinsicSystemGasConstants, L2ChainId, PackedEthSignature, ProtocolVersionId,
 };
 
 use crate::{
@@ -50,16 +61,24 @@ use crate::{
         contract_verification,
         execution_sandbox::{VmConcurrencyBarrier, VmConcurrencyLimiter},
         healthcheck::HealthCheckHandle,
-        tx_sender::{ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig},
+        tx_sender::{
+            sponsorship::{SponsorshipConfig, SponsorshipReconciler, TxSponsorship},
+            ApiContracts, TxSender, TxSenderBuilder, TxSenderConfig,
+        },
         web3,
         web3::{state::InternalApiConfig, ApiServerHandles, Namespace},
     },
+    base_token_fetcher::BaseTokenFetcher,
     basic_witness_input_producer::BasicWitnessInputProducer,
     commitment_generator::CommitmentGenerator,
-    eth_sender::{Aggregator, EthTxAggregator, EthTxManager},
-    eth_watch::start_eth_watch,
+    eth_sender::{
+        Aggregator, EthTxAggregator, EthTxManager, KzgPrecomputer, L1FeeSendGate,
+        RemoteSignerHealthCheck,
+    },
+    eth_watch::{start_eth_watch, SharedBridgeParams, UpgradeDryRunner},
     house_keeper::{
         blocks_state_reporter::L1BatchMetricsReporter,
+        factory_deps_gc::FactoryDepsGarbageCollector,
         fri_proof_compressor_job_retry_manager::FriProofCompressorJobRetryManager,
         fri_proof_compressor_queue_monitor::FriProofCompressorStatsReporter,
         fri_prover_job_retry_manager::FriProverJobRetryManager,
@@ -67,40 +86,58 @@ use crate::{
         fri_scheduler_circuit_queuer::SchedulerCircuitQueuer,
         fri_witness_generator_jobs_retry_manager::FriWitnessGeneratorJobRetryManager,
         fri_witness_generator_queue_monitor::FriWitnessGeneratorStatsReporter,
-        periodic_job::PeriodicJob,
+        periodic_job::PeriodicJob, prover_artifacts_gc::ProverArtifactsGarbageCollector,
+        utilization_report_generator::UtilizationReportGenerator,
         waiting_to_queued_fri_witness_job_mover::WaitingToQueuedFriWitnessJobMover,
     },
-    l1_gas_price::GasAdjusterSingleton,
+    l1_gas_price::{GasAdjusterSingleton, L1TxParamsProvider},
+    l1_state_checker::L1StateChecker,
     metadata_calculator::{MetadataCalculator, MetadataCalculatorConfig},
     metrics::{InitStage, APP_METRICS},
     state_keeper::{
-        create_state_keeper, MempoolFetcher, MempoolGuard, MiniblockSealer, SequencerSealer,
+        create_state_keeper, BytecodeCompressor, MempoolFetcher, MempoolGuard, MiniblockSealWal,
+        MiniblockSealer, PubdataModeSwitch, SequencerSealer, StateKeeperHealthCheck,
     },
+    token_metadata_refresher::TokenMetadataRefresher,
+    token_transfers_indexer::TokenTransfersIndexer,
+    vm_runner::VmRunner,
 };
 
 pub mod api_server;
+pub mod base_token_fetcher;
 pub mod basic_witness_input_producer;
+pub mod batch_replay;
 pub mod block_reverter;
 pub mod commitment_generator;
 pub mod consensus;
 pub mod consistency_checker;
+pub mod db_pruner;
 pub mod eth_sender;
 pub mod eth_watch;
+pub mod fee_input_override;
 pub mod fee_model;
 pub mod gas_tracker;
 pub mod genesis;
 pub mod house_keeper;
 pub mod l1_gas_price;
+pub mod l1_state_checker;
 pub mod metadata_calculator;
 mod metrics;
 pub mod proof_data_handler;
 pub mod reorg_detector;
+pub mod rocksdb_backup;
 pub mod state_keeper;
 pub mod sync_layer;
 pub mod temp_config_store;
+pub mod token_metadata_refresher;
+pub mod token_transfers_indexer;
 mod utils;
+pub mod vm_runner;
 
 /// Inserts the initial information about zkSync tokens into the database.
+///
+/// If `state_dump_path` is specified, genesis additionally includes the state from the
+/// [state dump](genesis::GenesisStateDump) at this path.
 pub async fn genesis_init(
     postgres_config: &PostgresConfig,
     eth_sender: &ETHSenderConfig,
@@ -108,7 +145,12 @@ pub async fn genesis_init(
     contracts_config: &ContractsConfig,
     eth_client_url: &str,
     wait_for_set_chain_id: bool,
+    state_dump_path: Option<&Path>,
 ) -> anyhow::Result<()> {
+    let state_dump = state_dump_path
+        .map(genesis::GenesisStateDump::load)
+        .transpose()
+        .context("failed loading genesis state dump")?;
     let db_url = postgres_config.master_url()?;
     let pool = ConnectionPool::singleton(db_url)
         .build()
@@ -176,6 +218,7 @@ pub async fn genesis_init(
             system_contracts: get_system_smart_contracts(),
             first_verifier_address: contracts_config.verifier_addr,
             first_l1_verifier_config,
+            state_dump,
         },
     )
     .await?;
@@ -254,6 +297,15 @@ pub enum Component {
     Consensus,
     /// Component generating commitment for L1 batches.
     CommitmentGenerator,
+    /// Component re-executing sealed L1 batches with the latest VM and comparing the results
+    /// with the persisted data.
+    VmRunner,
+    /// Component comparing L1 batches executed on L1 with the locally computed state roots and commitments.
+    L1StateChecker,
+    /// Component re-reading metadata of tokens marked as stale from L1.
+    TokenMetadataRefresher,
+    /// Component indexing token transfers served by the token transfers and account balances API methods.
+    TokenTransfersIndexer,
 }
 
 #[derive(Debug)]
@@ -290,6 +342,10 @@ impl FromStr for Components {
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
             "consensus" => Ok(Components(vec![Component::Consensus])),
             "commitment_generator" => Ok(Components(vec![Component::CommitmentGenerator])),
+            "vm_runner" => Ok(Components(vec![Component::VmRunner])),
+            "l1_state_checker" => Ok(Components(vec![Component::L1StateChecker])),
+            "token_metadata_refresher" => Ok(Components(vec![Component::TokenMetadataRefresher])),
+            "token_transfers_indexer" => Ok(Components(vec![Component::TokenTransfersIndexer])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -321,11 +377,15 @@ pub async fn initialize_components(
         .await
         .context("failed to build connection_pool")?;
     // We're most interested in setting acquire / statement timeouts for the API server, which puts the most load
-    // on Postgres.
+    // on Postgres. For the same reason, read replicas are only used by this pool; it's never used to write data.
     let replica_connection_pool =
         ConnectionPool::builder(postgres_config.replica_url()?, pool_size)
             .set_acquire_timeout(postgres_config.acquire_timeout())
             .set_statement_timeout(postgres_config.statement_timeout())
+            .set_read_replicas(
+                postgres_config.read_replica_urls.clone(),
+                postgres_config.max_replica_lag(),
+            )
             .build()
             .await
             .context("failed to build replica_connection_pool")?;
@@ -352,17 +412,23 @@ pub async fn initialize_components(
         .clone()
         .context("circuit_breaker_config")?;
 
+    let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
     let circuit_breaker_checker = CircuitBreakerChecker::new(
-        circuit_breakers_for_components(&components, &postgres_config, &circuit_breaker_config)
-            .await
-            .context("circuit_breakers_for_components")?,
+        circuit_breakers_for_components(
+            &components,
+            &postgres_config,
+            &circuit_breaker_config,
+            &query_client,
+            contracts_config.diamond_proxy_addr,
+        )
+        .await
+        .context("circuit_breakers_for_components")?,
         &circuit_breaker_config,
     );
     circuit_breaker_checker.check().await.unwrap_or_else(|err| {
         panic!("Circuit breaker triggered: {}", err);
     });
-
-    let query_client = QueryClient::new(&eth_client_config.web3_url).unwrap();
+    let eth_sender_pause_receiver = circuit_breaker_checker.eth_sender_pause_receiver();
     let gas_adjuster_config = configs.gas_adjuster_config.context("gas_adjuster_config")?;
 
     let eth_sender_config = configs
@@ -401,6 +467,44 @@ pub async fn initialize_components(
         tokio::spawn(circuit_breaker_checker.run(cb_sender, stop_receiver.clone())),
     ];
 
+    let base_token_fetcher_config = configs
+        .base_token_fetcher_config
+        .clone()
+        .unwrap_or_default();
+    let base_token_fetcher = Arc::new(
+        BaseTokenFetcher::new(&base_token_fetcher_config, Box::new(query_client.clone()))
+            .await
+            .context("BaseTokenFetcher::new()")?,
+    );
+    app_health.insert_component(base_token_fetcher.health_check());
+    task_futures.push(tokio::spawn(
+        base_token_fetcher.clone().run(stop_receiver.clone()),
+    ));
+
+    // The override is shared among all fee input providers, so that the state keeper and the API server
+    // apply the same override.
+    let fee_input_override = Arc::<SharedFeeInputOverride>::default();
+    if let Some(state_keeper_config) = &configs.state_keeper_config {
+        if let Some(path) = &state_keeper_config.fee_input_override_path {
+            let reloader = FeeInputOverrideReloader::new(
+                path.into(),
+                FeeInputOverrideBounds::new(state_keeper_config),
+                fee_input_override.clone(),
+            )
+            .context("FeeInputOverrideReloader::new()")?;
+            task_futures.push(tokio::spawn(reloader.run(stop_receiver.clone())));
+        }
+    }
+    // The pubdata mode switch is shared between the state keeper and the admin API, so that the mode
+    // can be switched at runtime if both components run in this process.
+    let pubdata_mode_switch = PubdataModeSwitch::new(
+        eth_sender_config.sender.pubdata_sending_mode.into(),
+        is_blobs_operator_configured(&eth_sender_config),
+    );
+    let api_pubdata_mode_switch = components
+        .contains(&Component::StateKeeper)
+        .then(|| pubdata_mode_switch.clone());
+
     if components.contains(&Component::WsApi)
         || components.contains(&Component::HttpApi)
         || components.contains(&Component::ContractVerificationApi)
@@ -411,10 +515,12 @@ pub async fn initialize_components(
             .clone()
             .context("state_keeper_config")?;
         let network_config = configs.network_config.clone().context("network_config")?;
+        let intrinsic_constants = resolve_intrinsic_constants(&state_keeper_config)?;
         let tx_sender_config = TxSenderConfig::new(
             &state_keeper_config,
             &api_config.web3_json_rpc,
             network_config.zksync_network_id,
+            intrinsic_constants,
         );
         let internal_api_config = InternalApiConfig::new(
             &network_config,
@@ -427,6 +533,20 @@ pub async fn initialize_components(
         // terminate immediately if storage caches are dropped, which will lead to the (unexpected)
         // program termination.
         let mut storage_caches = None;
+        let rocksdb_replica =
+            if components.contains(&Component::HttpApi) || components.contains(&Component::WsApi) {
+                build_rocksdb_replica(configs, stop_receiver.clone(), &mut task_futures)
+                    .context("build_rocksdb_replica()")?
+            } else {
+                None
+            };
+        let is_sponsorship_enabled = SponsorshipConfig::new(&api_config.web3_json_rpc).is_some();
+        if is_sponsorship_enabled
+            && (components.contains(&Component::HttpApi) || components.contains(&Component::WsApi))
+        {
+            let reconciler = SponsorshipReconciler::new(connection_pool.clone());
+            task_futures.push(tokio::spawn(reconciler.run(stop_receiver.clone())));
+        }
 
         if components.contains(&Component::HttpApi) {
             storage_caches = Some(
@@ -440,10 +560,14 @@ pub async fn initialize_components(
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
+            let batch_fee_input_provider = Arc::new(
+                MainNodeFeeInputProvider::new(
+                    bounded_gas_adjuster,
+                    FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+                )
+                .with_base_token_fetcher(base_token_fetcher.clone())
+                .with_fee_input_override(fee_input_override.clone()),
+            );
             let server_handles = run_http_api(
                 &postgres_config,
                 &tx_sender_config,
@@ -454,8 +578,11 @@ pub async fn initialize_components(
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 batch_fee_input_provider,
+                base_token_fetcher.clone(),
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
+                rocksdb_replica.clone(),
+                api_pubdata_mode_switch.clone(),
             )
             .await
             .context("run_http_api")?;
@@ -483,10 +610,14 @@ pub async fn initialize_components(
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?;
-            let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-                bounded_gas_adjuster,
-                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-            ));
+            let batch_fee_input_provider = Arc::new(
+                MainNodeFeeInputProvider::new(
+                    bounded_gas_adjuster,
+                    FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+                )
+                .with_base_token_fetcher(base_token_fetcher.clone())
+                .with_fee_input_override(fee_input_override.clone()),
+            );
             let server_handles = run_ws_api(
                 &postgres_config,
                 &tx_sender_config,
@@ -494,10 +625,12 @@ pub async fn initialize_components(
                 &internal_api_config,
                 &api_config,
                 batch_fee_input_provider,
+                base_token_fetcher.clone(),
                 connection_pool.clone(),
                 replica_connection_pool.clone(),
                 stop_receiver.clone(),
                 storage_caches,
+                rocksdb_replica,
             )
             .await
             .context("run_ws_api")?;
@@ -544,10 +677,14 @@ pub async fn initialize_components(
             .state_keeper_config
             .clone()
             .context("state_keeper_config")?;
-        let batch_fee_input_provider = Arc::new(MainNodeFeeInputProvider::new(
-            bounded_gas_adjuster,
-            FeeModelConfig::from_state_keeper_config(&state_keeper_config),
-        ));
+        let batch_fee_input_provider = Arc::new(
+            MainNodeFeeInputProvider::new(
+                bounded_gas_adjuster,
+                FeeModelConfig::from_state_keeper_config(&state_keeper_config),
+            )
+            .with_base_token_fetcher(base_token_fetcher.clone())
+            .with_fee_input_override(fee_input_override.clone()),
+        );
         add_state_keeper_to_task_futures(
             &mut task_futures,
             &postgres_config,
@@ -557,11 +694,16 @@ pub async fn initialize_components(
             &db_config,
             &configs.mempool_config.clone().context("mempool_config")?,
             batch_fee_input_provider,
+            pubdata_mode_switch.clone(),
             store_factory.create_store().await,
             stop_receiver.clone(),
         )
         .await
         .context("add_state_keeper_to_task_futures()")?;
+        app_health.insert_custom_component(Arc::new(StateKeeperHealthCheck::new(
+            connection_pool.clone(),
+        )));
+        app_health.add_dependency("state_keeper", "connection_pool");
 
         let elapsed = started_at.elapsed();
         APP_METRICS.init_latency[&InitStage::StateKeeper].set(elapsed);
@@ -615,6 +757,22 @@ pub async fn initialize_components(
             .eth_watch_config
             .clone()
             .context("eth_watch_config")?;
+        let upgrade_dry_run_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build upgrade_dry_run_pool")?;
+        let chain_id = configs
+            .network_config
+            .as_ref()
+            .context("network_config")?
+            .zksync_network_id;
+        let upgrade_dry_runner = UpgradeDryRunner::new(
+            upgrade_dry_run_pool,
+            chain_id,
+            eth_watch_config.poll_interval(),
+        );
+        app_health.insert_component(upgrade_dry_runner.health_check());
+        task_futures.push(tokio::spawn(upgrade_dry_runner.run(stop_receiver.clone())));
         task_futures.push(
             start_eth_watch(
                 eth_watch_config,
@@ -622,6 +780,12 @@ pub async fn initialize_components(
                 Arc::new(query_client.clone()),
                 main_zksync_contract_address,
                 governance,
+                contracts_config.state_transition_proxy_addr.map(
+                    |state_transition_manager_address| SharedBridgeParams {
+                        state_transition_manager_address,
+                        chain_id,
+                    },
+                ),
                 stop_receiver.clone(),
             )
             .await
@@ -636,6 +800,25 @@ pub async fn initialize_components(
         .kzg_config
         .as_ref()
         .map(|k| Arc::new(KzgSettings::new(&k.trusted_setup_path)));
+    if components.contains(&Component::EthTxAggregator)
+        || components.contains(&Component::EthTxManager)
+    {
+        let eth_sender = configs
+            .eth_sender_config
+            .as_ref()
+            .context("eth_sender_config")?;
+        if let Some(remote_signer) = &eth_sender.remote_signer {
+            let signer = RemoteSigner::new(
+                &remote_signer.url,
+                remote_signer.operator_address,
+                remote_signer.request_timeout(),
+                remote_signer.max_retries,
+            )
+            .context("failed creating remote signer")?;
+            app_health.insert_custom_component(Arc::new(RemoteSignerHealthCheck::new(signer)));
+        }
+    }
+
     if components.contains(&Component::EthTxAggregator) {
         let started_at = Instant::now();
         tracing::info!("initializing ETH-TxAggregator");
@@ -648,22 +831,60 @@ pub async fn initialize_components(
             .eth_sender_config
             .clone()
             .context("eth_sender_config")?;
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_client_blobs_addr =
-            PKSigningClient::from_config_blobs(&eth_sender, &contracts_config, &eth_client_config)
-                .map(|k| k.sender_account());
+        let (eth_client, eth_client_blobs) =
+            create_operator_eth_clients(&eth_sender, &contracts_config, &eth_client_config);
+        let eth_client_blobs_addr = eth_client_blobs.map(|client| client.sender_account());
+        let l1_tx_params: Option<Arc<dyn L1TxParamsProvider>> =
+            if eth_sender.sender.dynamic_aggregation_windows {
+                let gas_adjuster = gas_adjuster
+                    .get_or_init()
+                    .await
+                    .context("gas_adjuster.get_or_init()")?;
+                Some(gas_adjuster)
+            } else {
+                None
+            };
+        let mut aggregator = Aggregator::new(
+            eth_sender.sender.clone(),
+            store_factory.create_store().await,
+            eth_client_blobs_addr.is_some(),
+            eth_sender.sender.pubdata_sending_mode.into(),
+            kzg_settings.clone(),
+            l1_tx_params,
+        );
+        if eth_sender.sender.is_send_gated() {
+            let gas_adjuster = gas_adjuster
+                .get_or_init()
+                .await
+                .context("gas_adjuster.get_or_init()")?;
+            let send_gate = L1FeeSendGate::new(&eth_sender.sender, gas_adjuster)
+                .context("L1FeeSendGate::new()")?;
+            app_health.insert_component(send_gate.health_check());
+            aggregator = aggregator.with_send_gate(send_gate);
+        }
+
+        if let (Some(worker_count), Some(_)) =
+            (eth_sender.sender.kzg_precomputation_workers, &kzg_settings)
+        {
+            let kzg_precomputer_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build kzg_precomputer_pool")?;
+            let kzg_precomputer = KzgPrecomputer::new(
+                aggregator.kzg_info_cache().clone(),
+                worker_count,
+                eth_sender.sender.max_aggregated_blocks_to_commit as usize,
+                eth_sender.sender.aggregate_tx_poll_period(),
+            );
+            task_futures.push(tokio::spawn(
+                kzg_precomputer.run(kzg_precomputer_pool, stop_receiver.clone()),
+            ));
+        }
 
         let eth_tx_aggregator_actor = EthTxAggregator::new(
             eth_sender.sender.clone(),
-            Aggregator::new(
-                eth_sender.sender.clone(),
-                store_factory.create_store().await,
-                eth_client_blobs_addr.is_some(),
-                eth_sender.sender.pubdata_sending_mode.into(),
-                kzg_settings.clone(),
-            ),
-            Arc::new(eth_client),
+            aggregator,
+            eth_client,
             contracts_config.validator_timelock_addr,
             contracts_config.l1_multicall3_addr,
             main_zksync_contract_address,
@@ -675,7 +896,8 @@ pub async fn initialize_components(
             kzg_settings.clone(),
             eth_client_blobs_addr,
         )
-        .await;
+        .await
+        .with_pause_receiver(eth_sender_pause_receiver.clone());
         task_futures.push(tokio::spawn(
             eth_tx_aggregator_actor.run(eth_sender_pool, stop_receiver.clone()),
         ));
@@ -695,19 +917,26 @@ pub async fn initialize_components(
             .eth_sender_config
             .clone()
             .context("eth_sender_config")?;
-        let eth_client =
-            PKSigningClient::from_config(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_client_blobs =
-            PKSigningClient::from_config_blobs(&eth_sender, &contracts_config, &eth_client_config);
-        let eth_tx_manager_actor = EthTxManager::new(
+        let (eth_client, eth_client_blobs) =
+            create_operator_eth_clients(&eth_sender, &contracts_config, &eth_client_config);
+        let mut eth_tx_manager_actor = EthTxManager::new(
             eth_sender.sender,
             gas_adjuster
                 .get_or_init()
                 .await
                 .context("gas_adjuster.get_or_init()")?,
-            Arc::new(eth_client),
-            eth_client_blobs.map(|c| Arc::new(c) as Arc<dyn BoundEthInterface>),
-        );
+            eth_client,
+            eth_client_blobs,
+        )
+        .with_pause_receiver(eth_sender_pause_receiver.clone());
+        if let Some(tx_relay) = &eth_sender.tx_relay {
+            let relay_client =
+                QueryClient::new(&tx_relay.url).context("failed creating private relay client")?;
+            eth_tx_manager_actor = eth_tx_manager_actor
+                .with_tx_relay(Arc::new(relay_client), tx_relay.public_fallback_timeout());
+        }
+        app_health.insert_component(eth_tx_manager_actor.health_check());
+        app_health.add_dependency("eth_tx_manager", "connection_pool");
         task_futures.extend([tokio::spawn(
             eth_tx_manager_actor.run(eth_manager_pool, stop_receiver.clone()),
         )]);
@@ -775,11 +1004,70 @@ pub async fn initialize_components(
         let commitment_generator =
             CommitmentGenerator::new(commitment_generator_pool, &kzg_config.trusted_setup_path);
         app_health.insert_component(commitment_generator.health_check());
+        app_health.add_dependency("commitment_generator", "tree");
         task_futures.push(tokio::spawn(
             commitment_generator.run(stop_receiver.clone()),
         ));
     }
 
+    if components.contains(&Component::VmRunner) {
+        let network_config = configs.network_config.clone().context("network_config")?;
+        let vm_runner_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build vm_runner_pool")?;
+        let vm_runner = VmRunner::new(vm_runner_pool, network_config.zksync_network_id);
+        app_health.insert_component(vm_runner.health_check());
+        task_futures.push(tokio::spawn(vm_runner.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::L1StateChecker) {
+        let state_keeper_config = configs
+            .state_keeper_config
+            .as_ref()
+            .context("state_keeper_config")?;
+        let l1_state_checker_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+            .build()
+            .await
+            .context("failed to build l1_state_checker_pool")?;
+        let l1_state_checker = L1StateChecker::new(
+            l1_state_checker_pool,
+            Box::new(query_client.clone()),
+            contracts_config.diamond_proxy_addr,
+            state_keeper_config.halt_on_l1_state_mismatch,
+        );
+        app_health.insert_component(l1_state_checker.health_check());
+        task_futures.push(tokio::spawn(l1_state_checker.run(stop_receiver.clone())));
+    }
+
+    if components.contains(&Component::TokenMetadataRefresher) {
+        let token_metadata_refresher_pool =
+            ConnectionPool::singleton(postgres_config.master_url()?)
+                .build()
+                .await
+                .context("failed to build token_metadata_refresher_pool")?;
+        let token_metadata_refresher = TokenMetadataRefresher::new(
+            token_metadata_refresher_pool,
+            Box::new(query_client.clone()),
+        );
+        app_health.insert_component(token_metadata_refresher.health_check());
+        task_futures.push(tokio::spawn(
+            token_metadata_refresher.run(stop_receiver.clone()),
+        ));
+    }
+
+    if components.contains(&Component::TokenTransfersIndexer) {
+        let token_transfers_indexer_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+            .build()
+            .await
+            .context("failed to build token_transfers_indexer_pool")?;
+        let token_transfers_indexer = TokenTransfersIndexer::new(token_transfers_indexer_pool);
+        app_health.insert_component(token_transfers_indexer.health_check());
+        task_futures.push(tokio::spawn(
+            token_transfers_indexer.run(stop_receiver.clone()),
+        ));
+    }
+
     // Run healthcheck server for all components.
     let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool);
     app_health.insert_custom_component(Arc::new(db_health_check));
@@ -802,6 +1090,7 @@ async fn add_state_keeper_to_task_futures(
     db_config: &DBConfig,
     mempool_config: &MempoolConfig,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    pubdata_mode_switch: PubdataModeSwitch,
     object_store: Arc<dyn ObjectStore>,
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<()> {
@@ -810,12 +1099,29 @@ async fn add_state_keeper_to_task_futures(
         .build()
         .await
         .context("failed to build state_keeper_pool")?;
+    let miniblock_seal_wal = if let Some(path) = &state_keeper_config.miniblock_seal_wal_path {
+        let path = PathBuf::from(path);
+        let wal = tokio::task::spawn_blocking(move || MiniblockSealWal::new(&path))
+            .await
+            .context("panicked opening miniblock seal WAL")??;
+        // Buffered miniblocks must be persisted before the mempool and the state keeper are initialized.
+        let replayed_count = wal
+            .replay(&state_keeper_pool)
+            .await
+            .context("failed replaying miniblock seal WAL")?;
+        if replayed_count > 0 {
+            tracing::info!("Persisted {replayed_count} miniblocks from seal WAL");
+        }
+        Some(wal)
+    } else {
+        None
+    };
     let mempool = {
         let mut storage = state_keeper_pool
             .access_storage()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(&mut storage, mempool_config).await;
         mempool.register_metrics();
         mempool
     };
@@ -824,12 +1130,19 @@ async fn add_state_keeper_to_task_futures(
         .build()
         .await
         .context("failed to build miniblock_sealer_pool")?;
-    let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
+    let (mut miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
         miniblock_sealer_pool,
         state_keeper_config.miniblock_seal_queue_capacity,
     );
+    if let Some(wal) = miniblock_seal_wal.clone() {
+        miniblock_sealer =
+            miniblock_sealer.with_wal(wal, state_keeper_config.miniblock_seal_wal_max_miniblocks());
+    }
     task_futures.push(tokio::spawn(miniblock_sealer.run()));
 
+    // Bytecodes are compressed when transactions are loaded into the mempool, and compression results
+    // are picked up by the state keeper when executing transactions.
+    let bytecode_compressor = BytecodeCompressor::default();
     let state_keeper = create_state_keeper(
         contracts_config,
         state_keeper_config,
@@ -839,6 +1152,8 @@ async fn add_state_keeper_to_task_futures(
         state_keeper_pool.clone(),
         mempool.clone(),
         batch_fee_input_provider.clone(),
+        pubdata_mode_switch,
+        bytecode_compressor.clone(),
         miniblock_sealer_handle,
         object_store,
         stop_receiver.clone(),
@@ -854,17 +1169,68 @@ async fn add_state_keeper_to_task_futures(
         .build()
         .await
         .context("failed to build mempool_fetcher_pool")?;
-    let mempool_fetcher = MempoolFetcher::new(
+    let mut mempool_fetcher = MempoolFetcher::new(
         mempool,
         batch_fee_input_provider,
         mempool_config,
         mempool_fetcher_pool,
-    );
+    )
+    .with_bytecode_compressor(bytecode_compressor);
+    if miniblock_seal_wal.is_some() {
+        mempool_fetcher = mempool_fetcher.with_db_outage_tolerance();
+    }
     let mempool_fetcher_handle = tokio::spawn(mempool_fetcher.run(stop_receiver));
     task_futures.push(mempool_fetcher_handle);
     Ok(())
 }
 
+/// Creates clients signing operator transactions (the main one and the optional one for blob transactions),
+/// either using the operator private keys or an external signer service.
+fn create_operator_eth_clients(
+    eth_sender: &ETHSenderConfig,
+    contracts_config: &ContractsConfig,
+    eth_client_config: &ETHClientConfig,
+) -> (
+    Arc<dyn BoundEthInterface>,
+    Option<Arc<dyn BoundEthInterface>>,
+) {
+    if let Some(remote_signer) = &eth_sender.remote_signer {
+        let eth_client = RemoteSigningClient::from_config(
+            eth_sender,
+            remote_signer,
+            contracts_config,
+            eth_client_config,
+        );
+        let eth_client_blobs = RemoteSigningClient::from_config_blobs(
+            eth_sender,
+            remote_signer,
+            contracts_config,
+            eth_client_config,
+        );
+        (
+            Arc::new(eth_client),
+            eth_client_blobs.map(|client| Arc::new(client) as Arc<dyn BoundEthInterface>),
+        )
+    } else {
+        let eth_client =
+            PKSigningClient::from_config(eth_sender, contracts_config, eth_client_config);
+        let eth_client_blobs =
+            PKSigningClient::from_config_blobs(eth_sender, contracts_config, eth_client_config);
+        (
+            Arc::new(eth_client),
+            eth_client_blobs.map(|client| Arc::new(client) as Arc<dyn BoundEthInterface>),
+        )
+    }
+}
+
+/// Checks whether a dedicated operator account for blob transactions is configured for the Ethereum sender.
+fn is_blobs_operator_configured(eth_sender: &ETHSenderConfig) -> bool {
+    match &eth_sender.remote_signer {
+        Some(remote_signer) => remote_signer.operator_blobs_address.is_some(),
+        None => eth_sender.sender.private_key_blobs().is_some(),
+    }
+}
+
 async fn add_trees_to_task_futures(
     configs: &TempConfigStore,
     task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
@@ -948,10 +1314,23 @@ async fn run_tree(
                 .run_api_server(address, stop_receiver)
                 .await
         }));
+
+        if let Some(grpc_port) = api_config.grpc_port {
+            let address = (Ipv4Addr::UNSPECIFIED, grpc_port).into();
+            let tree_reader = metadata_calculator.tree_reader();
+            let stop_receiver = stop_receiver.clone();
+            task_futures.push(tokio::spawn(async move {
+                tree_reader
+                    .await
+                    .run_grpc_server(address, stop_receiver)
+                    .await
+            }));
+        }
     }
 
     let tree_health_check = metadata_calculator.tree_health_check();
     app_health.insert_component(tree_health_check);
+    app_health.add_dependency("tree", "connection_pool");
     let pool = ConnectionPool::singleton(postgres_config.master_url()?)
         .build()
         .await
@@ -1021,6 +1400,28 @@ async fn add_house_keeper_to_task_futures(
     .context("failed to build a prover_connection_pool")?;
     task_futures.push(tokio::spawn(l1_batch_metrics_reporter.run()));
 
+    // Reports are written to the DB, so the generator cannot use the replica pool.
+    let master_connection_pool = ConnectionPool::singleton(postgres_config.master_url()?)
+        .build()
+        .await
+        .context("failed to build a master_connection_pool")?;
+    let utilization_report_generator = UtilizationReportGenerator::new(
+        house_keeper_config.utilization_report_generation_interval_ms,
+        master_connection_pool.clone(),
+    );
+    task_futures.push(tokio::spawn(utilization_report_generator.run()));
+
+    // Factory deps are removed from the DB, so the collector cannot use the replica pool either.
+    if let Some(retention) = house_keeper_config.factory_deps_retention() {
+        let factory_deps_gc = FactoryDepsGarbageCollector::new(
+            house_keeper_config.factory_deps_gc_interval_ms,
+            retention,
+            house_keeper_config.factory_deps_gc_dry_run,
+            master_connection_pool,
+        );
+        task_futures.push(tokio::spawn(factory_deps_gc.run()));
+    }
+
     // All FRI Prover related components are configured below.
     let fri_prover_config = configs
         .fri_prover_config
@@ -1093,9 +1494,46 @@ async fn add_house_keeper_to_task_futures(
         prover_connection_pool.clone(),
     );
     task_futures.push(tokio::spawn(fri_proof_compressor_retry_manager.run()));
+
+    if let Some(retention) = house_keeper_config.prover_artifacts_retention() {
+        let prover_object_store_config = configs
+            .prover_object_store_config
+            .clone()
+            .context("prover_object_store_config")?;
+        let prover_blob_store = ObjectStoreFactory::new(prover_object_store_config)
+            .create_store()
+            .await;
+        let prover_artifacts_gc = ProverArtifactsGarbageCollector::new(
+            house_keeper_config.prover_artifacts_gc_interval_ms,
+            retention,
+            house_keeper_config.prover_artifacts_gc_dry_run,
+            connection_pool.clone(),
+            prover_connection_pool.clone(),
+            prover_blob_store,
+        );
+        task_futures.push(tokio::spawn(prover_artifacts_gc.run()));
+    }
     Ok(())
 }
 
+/// Resolves intrinsic gas constants used to validate L2 transactions in the API server, taking chain-specific overrides
+/// from the state keeper config into account.
+fn resolve_intrinsic_constants(
+    state_keeper_config: &StateKeeperConfig,
+) -> anyhow::Result<IntrinsicSystemGasConstants> {
+    let overrides = IntrinsicGasOverrides::from_state_keeper_config(state_keeper_config);
+    overrides
+        .validate(state_keeper_config.max_allowed_l2_tx_gas_limit)
+        .context("invalid intrinsic gas overrides")?;
+    let constants = overrides.apply();
+    if !overrides.is_empty() {
+        tracing::info!(
+            "Using intrinsic gas constants with chain-specific overrides: {constants:?}"
+        );
+    }
+    Ok(constants)
+}
+
 fn build_storage_caches(
     configs: &TempConfigStore,
     replica_connection_pool: &ConnectionPool,
@@ -1122,6 +1560,33 @@ fn build_storage_caches(
     Ok(storage_caches)
 }
 
+/// Interval between attempts of the state keeper cache replica to catch up with the cache.
+const ROCKSDB_REPLICA_CATCH_UP_INTERVAL: Duration = Duration::from_millis(500);
+
+fn build_rocksdb_replica(
+    configs: &TempConfigStore,
+    stop_receiver: watch::Receiver<bool>,
+    task_futures: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+) -> anyhow::Result<Option<RocksdbReplica>> {
+    let rpc_config = configs
+        .web3_json_rpc_config
+        .as_ref()
+        .context("web3_json_rpc_config")?;
+    let Some(replica_path) = &rpc_config.state_keeper_db_replica_path else {
+        return Ok(None);
+    };
+    let db_config = configs.db_config.as_ref().context("db_config")?;
+    let (replica, replica_task) = RocksdbReplica::new(
+        db_config.state_keeper_db_path.clone().into(),
+        replica_path.into(),
+        rpc_config.state_keeper_db_replica_max_lag(),
+    );
+    task_futures.push(tokio::spawn(
+        replica_task.run(ROCKSDB_REPLICA_CATCH_UP_INTERVAL, stop_receiver),
+    ));
+    Ok(Some(replica))
+}
+
 async fn build_tx_sender(
     tx_sender_config: &TxSenderConfig,
     web3_json_config: &Web3JsonRpcConfig,
@@ -1130,15 +1595,27 @@ async fn build_tx_sender(
     master_pool: ConnectionPool,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     storage_caches: PostgresStorageCaches,
+    rocksdb_replica: Option<RocksdbReplica>,
 ) -> (TxSender, VmConcurrencyBarrier) {
     let sequencer_sealer = SequencerSealer::new(state_keeper_config.clone());
-    let master_pool_sink = MasterPoolSink::new(master_pool);
-    let tx_sender_builder = TxSenderBuilder::new(
+    let master_pool_sink = MasterPoolSink::new(master_pool.clone());
+    let mut tx_sender_builder = TxSenderBuilder::new(
         tx_sender_config.clone(),
         replica_pool.clone(),
         Arc::new(master_pool_sink),
     )
     .with_sealer(Arc::new(sequencer_sealer));
+    if let Some(rocksdb_replica) = rocksdb_replica {
+        tx_sender_builder = tx_sender_builder.with_rocksdb_replica(rocksdb_replica);
+    }
+    if let Some(sponsorship_config) = SponsorshipConfig::new(web3_json_config) {
+        tracing::info!(
+            "Transactions using paymaster {:?} are sponsored by the operator",
+            sponsorship_config.paymaster
+        );
+        let sponsorship = TxSponsorship::new(sponsorship_config, master_pool);
+        tx_sender_builder = tx_sender_builder.with_sponsorship(sponsorship);
+    }
 
     let max_concurrency = web3_json_config.vm_concurrency_limit();
     let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
@@ -1168,8 +1645,11 @@ async fn run_http_api(
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    base_token_fetcher: Arc<BaseTokenFetcher>,
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
+    rocksdb_replica: Option<RocksdbReplica>,
+    pubdata_mode_switch: Option<PubdataModeSwitch>,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1179,6 +1659,7 @@ async fn run_http_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        rocksdb_replica,
     )
     .await;
 
@@ -1187,13 +1668,19 @@ async fn run_http_api(
         namespaces.push(Namespace::Debug)
     }
     namespaces.push(Namespace::Snapshots);
+    if api_config.web3_json_rpc.admin_namespace_enabled {
+        namespaces.push(Namespace::Admin);
+    }
+    if api_config.web3_json_rpc.trace_namespace_enabled {
+        namespaces.push(Namespace::Trace);
+    }
 
     let updaters_pool = ConnectionPool::builder(postgres_config.replica_url()?, 2)
         .build()
         .await
         .context("failed to build last_miniblock_pool")?;
 
-    let api_builder =
+    let mut api_builder =
         web3::ApiBuilder::jsonrpsee_backend(internal_api.clone(), replica_connection_pool)
             .http(api_config.web3_json_rpc.http_port)
             .with_updaters_pool(updaters_pool)
@@ -1201,8 +1688,13 @@ async fn run_http_api(
             .with_tree_api(api_config.web3_json_rpc.tree_api_url())
             .with_batch_request_size_limit(api_config.web3_json_rpc.max_batch_request_size())
             .with_response_body_size_limit(api_config.web3_json_rpc.max_response_body_size())
+            .with_streamed_methods(api_config.web3_json_rpc.streamed_methods.clone())
             .with_tx_sender(tx_sender, vm_barrier)
+            .with_base_token_fetcher(base_token_fetcher)
             .enable_api_namespaces(namespaces);
+    if let Some(pubdata_mode_switch) = pubdata_mode_switch {
+        api_builder = api_builder.with_pubdata_mode_switch(pubdata_mode_switch);
+    }
     api_builder.build(stop_receiver).await
 }
 
@@ -1214,10 +1706,12 @@ async fn run_ws_api(
     internal_api: &InternalApiConfig,
     api_config: &ApiConfig,
     batch_fee_model_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    base_token_fetcher: Arc<BaseTokenFetcher>,
     master_connection_pool: ConnectionPool,
     replica_connection_pool: ConnectionPool,
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
+    rocksdb_replica: Option<RocksdbReplica>,
 ) -> anyhow::Result<ApiServerHandles> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1227,6 +1721,7 @@ async fn run_ws_api(
         master_connection_pool,
         batch_fee_model_input_provider,
         storage_caches,
+        rocksdb_replica,
     )
     .await;
     let last_miniblock_pool = ConnectionPool::singleton(postgres_config.replica_url()?)
@@ -1253,6 +1748,7 @@ async fn run_ws_api(
             .with_polling_interval(api_config.web3_json_rpc.pubsub_interval())
             .with_tree_api(api_config.web3_json_rpc.tree_api_url())
             .with_tx_sender(tx_sender, vm_barrier)
+            .with_base_token_fetcher(base_token_fetcher)
             .enable_api_namespaces(namespaces);
 
     api_builder.build(stop_receiver.clone()).await
@@ -1262,6 +1758,8 @@ async fn circuit_breakers_for_components(
     components: &[Component],
     postgres_config: &PostgresConfig,
     circuit_breaker_config: &CircuitBreakerConfig,
+    query_client: &QueryClient,
+    diamond_proxy_address: Address,
 ) -> anyhow::Result<Vec<Box<dyn CircuitBreaker>>> {
     let mut circuit_breakers: Vec<Box<dyn CircuitBreaker>> = Vec::new();
 
@@ -1274,6 +1772,19 @@ async fn circuit_breakers_for_components(
             .await
             .context("failed to build a connection pool")?;
         circuit_breakers.push(Box::new(FailedL1TransactionChecker { pool }));
+
+        if circuit_breaker_config.l1_contracts_version_check {
+            let pool = ConnectionPool::singleton(postgres_config.replica_url()?)
+                .build()
+                .await
+                .context("failed to build a connection pool")?;
+            circuit_breakers.push(Box::new(L1ContractsVersionChecker::new(
+                pool,
+                Arc::new(query_client.clone()),
+                diamond_proxy_address,
+                circuit_breaker_config,
+            )));
+        }
     }
 
     if components.iter().any(|c| {
@@ -1,8 +1,9 @@
 //! Various helpers for the metadata calculator.
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashMap},
     future::Future,
+    ops,
     path::{Path, PathBuf},
     time::Duration,
 };
@@ -20,7 +21,7 @@ use zksync_merkle_tree::{
     Database, Key, NoVersionError, RocksDBWrapper, TreeEntry, TreeEntryWithProof, TreeInstruction,
 };
 use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries};
-use zksync_types::{block::L1BatchHeader, L1BatchNumber, StorageKey, H256};
+use zksync_types::{block::L1BatchHeader, L1BatchNumber, MiniblockNumber, StorageKey, H256};
 
 use super::metrics::{LoadChangesStage, TreeUpdateStage, METRICS};
 
@@ -42,7 +43,24 @@ pub(super) enum MerkleTreeHealth {
         chunk_count: u64,
         recovered_chunk_count: u64,
     },
-    MainLoop(MerkleTreeInfo),
+    MainLoop {
+        #[serde(flatten)]
+        info: MerkleTreeInfo,
+        /// Number of sealed L1 batches not yet processed by the tree.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        l1_batch_lag: Option<u32>,
+    },
+}
+
+impl MerkleTreeHealth {
+    pub(super) fn main_loop(
+        info: MerkleTreeInfo,
+        last_sealed_l1_batch: Option<L1BatchNumber>,
+    ) -> Self {
+        let l1_batch_lag = last_sealed_l1_batch
+            .map(|number| (number.0 + 1).saturating_sub(info.next_l1_batch_number.0));
+        Self::MainLoop { info, l1_batch_lag }
+    }
 }
 
 impl From<MerkleTreeHealth> for Health {
@@ -53,7 +71,7 @@ impl From<MerkleTreeHealth> for Health {
 
 impl From<MerkleTreeInfo> for Health {
     fn from(info: MerkleTreeInfo) -> Self {
-        Self::from(HealthStatus::Ready).with_details(MerkleTreeHealth::MainLoop(info))
+        Self::from(HealthStatus::Ready).with_details(MerkleTreeHealth::main_loop(info, None))
     }
 }
 
@@ -183,6 +201,19 @@ impl AsyncTree {
         metadata
     }
 
+    pub async fn miniblock_root_hashes(&mut self, miniblock_writes: MiniblockWrites) -> Vec<H256> {
+        let mut tree = self.inner.take().expect(Self::INCONSISTENT_MSG);
+        let (tree, root_hashes) = tokio::task::spawn_blocking(move || {
+            let root_hashes = tree.miniblock_root_hashes(&miniblock_writes.writes);
+            (tree, root_hashes)
+        })
+        .await
+        .unwrap();
+
+        self.inner = Some(tree);
+        root_hashes
+    }
+
     pub async fn save(&mut self) {
         let mut tree = self.inner.take().expect(Self::INCONSISTENT_MSG);
         self.inner = Some(
@@ -219,6 +250,13 @@ impl AsyncTreeReader {
         .unwrap()
     }
 
+    /// Creates a checkpoint of the tree RocksDB at the specified `path`. This method is blocking.
+    pub fn create_checkpoint(&self, path: &Path) -> anyhow::Result<()> {
+        self.inner
+            .create_checkpoint(path)
+            .with_context(|| format!("failed creating checkpoint at `{}`", path.display()))
+    }
+
     pub async fn entries_with_proofs(
         self,
         l1_batch_number: L1BatchNumber,
@@ -228,6 +266,33 @@ impl AsyncTreeReader {
             .await
             .unwrap()
     }
+
+    pub async fn entries_with_proofs_batched(
+        self,
+        l1_batch_number: L1BatchNumber,
+        keys: Vec<Key>,
+    ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
+        tokio::task::spawn_blocking(move || {
+            self.inner
+                .entries_with_proofs_batched(l1_batch_number, &keys)
+        })
+        .await
+        .unwrap()
+    }
+
+    pub async fn entries_in_range(
+        self,
+        l1_batch_number: L1BatchNumber,
+        start_key: Key,
+        limit: usize,
+    ) -> Result<Vec<TreeEntry>, NoVersionError> {
+        tokio::task::spawn_blocking(move || {
+            self.inner
+                .entries_in_range(l1_batch_number, start_key, limit)
+        })
+        .await
+        .unwrap()
+    }
 }
 
 /// Async wrapper for [`MerkleTreeRecovery`].
@@ -448,6 +513,143 @@ impl L1BatchWithLogs {
     }
 }
 
+/// Storage writes for each miniblock in an L1 batch used to compute provisional miniblock root hashes.
+#[derive(Debug)]
+pub(crate) struct MiniblockWrites {
+    pub miniblock_numbers: Vec<MiniblockNumber>,
+    /// Writes for each miniblock in `miniblock_numbers`.
+    pub writes: Vec<Vec<TreeEntry<StorageKey>>>,
+}
+
+impl MiniblockWrites {
+    pub async fn new(
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Option<Self> {
+        let (first_miniblock, last_miniblock) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(l1_batch_number)
+            .await
+            .unwrap()?;
+        let mut touched_slots = storage
+            .storage_logs_dal()
+            .get_touched_slots_for_miniblocks(first_miniblock..=last_miniblock)
+            .await
+            .unwrap();
+
+        let hashed_keys: Vec<_> = touched_slots
+            .values()
+            .flat_map(HashMap::keys)
+            .map(StorageKey::hashed_key)
+            .collect();
+        let l1_batches_for_initial_writes = storage
+            .storage_logs_dal()
+            .get_l1_batches_and_indices_for_initial_writes(&hashed_keys)
+            .await
+            .unwrap();
+
+        let miniblock_numbers: Vec<_> = (first_miniblock.0..=last_miniblock.0)
+            .map(MiniblockNumber)
+            .collect();
+        let writes = miniblock_numbers
+            .iter()
+            .map(|number| {
+                let slots = touched_slots.remove(number).unwrap_or_default();
+                let mut writes: Vec<_> = slots
+                    .into_iter()
+                    .filter_map(|(storage_key, value)| {
+                        let &(initial_write_batch, leaf_index) =
+                            l1_batches_for_initial_writes.get(&storage_key.hashed_key())?;
+                        // Same as for L1 batches, filter out writes that were deduplicated (e.g., a write
+                        // to a new slot that was reverted within the batch).
+                        (initial_write_batch <= l1_batch_number)
+                            .then(|| TreeEntry::new(storage_key, leaf_index, value))
+                    })
+                    .collect();
+                writes.sort_unstable_by_key(|entry| entry.leaf_index);
+                writes
+            })
+            .collect();
+        Some(Self {
+            miniblock_numbers,
+            writes,
+        })
+    }
+
+    /// Loads writes for miniblocks not included into a sealed L1 batch yet. Enumeration indices for new storage slots
+    /// are only assigned once the L1 batch is sealed, so new slots get provisional indices following the greatest
+    /// assigned index in the order of their first write (slots first written in the same miniblock are ordered
+    /// by their hashed keys). Writes of zero values to new slots are skipped since they don't create slots.
+    pub async fn for_pending_miniblocks(
+        storage: &mut StorageProcessor<'_>,
+        miniblock_numbers: ops::RangeInclusive<MiniblockNumber>,
+    ) -> Self {
+        let mut touched_slots = storage
+            .storage_logs_dal()
+            .get_touched_slots_for_miniblocks(miniblock_numbers.clone())
+            .await
+            .unwrap();
+        let hashed_keys: Vec<_> = touched_slots
+            .values()
+            .flat_map(HashMap::keys)
+            .map(StorageKey::hashed_key)
+            .collect();
+        let initial_writes = storage
+            .storage_logs_dal()
+            .get_l1_batches_and_indices_for_initial_writes(&hashed_keys)
+            .await
+            .unwrap();
+        let mut next_leaf_index = storage
+            .storage_logs_dedup_dal()
+            .max_enumeration_index()
+            .await
+            .unwrap_or(0)
+            + 1;
+
+        let mut provisional_indices = HashMap::new();
+        let miniblock_numbers: Vec<_> = (miniblock_numbers.start().0..=miniblock_numbers.end().0)
+            .map(MiniblockNumber)
+            .collect();
+        let writes = miniblock_numbers
+            .iter()
+            .map(|number| {
+                let mut slots: Vec<_> = touched_slots
+                    .remove(number)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect();
+                slots.sort_unstable_by_key(|(storage_key, _)| storage_key.hashed_key());
+                let mut writes: Vec<_> = slots
+                    .into_iter()
+                    .filter_map(|(storage_key, value)| {
+                        let hashed_key = storage_key.hashed_key();
+                        let leaf_index =
+                            if let Some(&(_, leaf_index)) = initial_writes.get(&hashed_key) {
+                                leaf_index
+                            } else if let Some(&leaf_index) = provisional_indices.get(&hashed_key) {
+                                leaf_index
+                            } else if value.is_zero() {
+                                return None;
+                            } else {
+                                let leaf_index = next_leaf_index;
+                                next_leaf_index += 1;
+                                provisional_indices.insert(hashed_key, leaf_index);
+                                leaf_index
+                            };
+                        Some(TreeEntry::new(storage_key, leaf_index, value))
+                    })
+                    .collect();
+                writes.sort_unstable_by_key(|entry| entry.leaf_index);
+                writes
+            })
+            .collect();
+        Self {
+            miniblock_numbers,
+            writes,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use tempfile::TempDir;
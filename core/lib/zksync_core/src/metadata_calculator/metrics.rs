@@ -17,6 +17,8 @@ use crate::metrics::{BlockStage, APP_METRICS};
 pub(super) enum TreeUpdateStage {
     LoadChanges,
     Compute,
+    LoadMiniblockChanges,
+    ComputeMiniblocks,
     CheckConsistency,
     SavePostgres,
     SaveRocksdb,
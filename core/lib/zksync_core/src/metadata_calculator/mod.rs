@@ -22,6 +22,7 @@ use self::{
     helpers::{create_db, Delayer, GenericAsyncTree, MerkleTreeHealth},
     updater::TreeUpdater,
 };
+use crate::rocksdb_backup::RocksdbCheckpointer;
 
 mod helpers;
 mod metrics;
@@ -51,6 +52,8 @@ pub struct MetadataCalculatorConfig {
     pub memtable_capacity: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     pub stalled_writes_timeout: Duration,
+    /// Whether to compute and persist provisional root hashes for each miniblock.
+    pub miniblock_root_hashes_enabled: bool,
 }
 
 impl MetadataCalculatorConfig {
@@ -67,6 +70,7 @@ impl MetadataCalculatorConfig {
             block_cache_capacity: merkle_tree_config.block_cache_size(),
             memtable_capacity: merkle_tree_config.memtable_capacity(),
             stalled_writes_timeout: merkle_tree_config.stalled_writes_timeout(),
+            miniblock_root_hashes_enabled: merkle_tree_config.miniblock_root_hashes_enabled,
         }
     }
 }
@@ -79,6 +83,7 @@ pub struct MetadataCalculator {
     delayer: Delayer,
     health_updater: HealthUpdater,
     max_l1_batches_per_iter: usize,
+    tree_checkpointer: Option<RocksdbCheckpointer>,
 }
 
 impl MetadataCalculator {
@@ -99,10 +104,18 @@ impl MetadataCalculator {
             delayer: Delayer::new(config.delay_interval),
             health_updater,
             max_l1_batches_per_iter: config.max_l1_batches_per_iter,
+            tree_checkpointer: None,
             config,
         })
     }
 
+    /// Enables periodic checkpoints of the tree RocksDB, e.g. to upload tree backups to the object store.
+    #[must_use]
+    pub fn with_tree_checkpointer(mut self, checkpointer: RocksdbCheckpointer) -> Self {
+        self.tree_checkpointer = Some(checkpointer);
+        self
+    }
+
     /// Returns a health check for this calculator.
     pub fn tree_health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
@@ -171,7 +184,9 @@ impl MetadataCalculator {
         );
         self.tree_reader.send_replace(Some(tree_reader));
 
-        let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
+        let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store)
+            .with_miniblock_root_hashes(self.config.miniblock_root_hashes_enabled)
+            .with_checkpointer(self.tree_checkpointer);
         updater
             .loop_updating_tree(self.delayer, &pool, stop_receiver, self.health_updater)
             .await
@@ -125,10 +125,12 @@ async fn status_receiver_has_correct_states() {
 
     let calculator_handle = tokio::spawn(calculator.run(pool, stop_rx));
     delay_rx.recv().await.unwrap();
-    assert_eq!(
-        tree_health_check.check_health().await.status(),
-        HealthStatus::Ready
-    );
+    let health = tree_health_check.check_health().await;
+    assert_eq!(health.status(), HealthStatus::Ready);
+    let health_details = serde_json::to_value(health).unwrap()["details"].clone();
+    assert_eq!(health_details["stage"], "main_loop");
+    assert_eq!(health_details["next_l1_batch_number"], 2);
+    assert_eq!(health_details["l1_batch_lag"], 0);
     assert_eq!(
         other_tree_health_check.check_health().await.status(),
         HealthStatus::Ready
@@ -186,6 +188,154 @@ async fn multi_l1_batch_workflow() {
     }
 }
 
+#[tokio::test]
+async fn computing_miniblock_root_hashes() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Lightweight);
+    merkle_tree_config.miniblock_root_hashes_enabled = true;
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+
+    // Create an L1 batch with 2 miniblocks; the second miniblock overwrites some slots written in the first one.
+    let mut first_miniblock_logs = gen_storage_logs(0..20, 1).pop().unwrap();
+    let mut second_miniblock_logs = first_miniblock_logs.split_off(10);
+    second_miniblock_logs.extend(
+        first_miniblock_logs[..5]
+            .iter()
+            .map(|log| StorageLog::new_write_log(log.key, H256::repeat_byte(0xff))),
+    );
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let mut transaction = storage.start_transaction().await.unwrap();
+    let l1_batch_header = create_l1_batch(1);
+    transaction
+        .blocks_dal()
+        .insert_mock_l1_batch(&l1_batch_header)
+        .await
+        .unwrap();
+    for (number, logs) in [(1, first_miniblock_logs), (2, second_miniblock_logs)] {
+        transaction
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(number))
+            .await
+            .unwrap();
+        transaction
+            .storage_logs_dal()
+            .insert_storage_logs(MiniblockNumber(number), &[(H256::zero(), logs)])
+            .await
+            .unwrap();
+    }
+    transaction
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(l1_batch_header.number)
+        .await
+        .unwrap();
+    insert_initial_writes_for_batch(&mut transaction, l1_batch_header.number).await;
+    transaction.commit().await.unwrap();
+
+    let root_hash = run_calculator(calculator, pool.clone()).await;
+
+    let mut root_hashes = vec![];
+    for number in [1, 2] {
+        let root_hash = storage
+            .blocks_dal()
+            .get_miniblock_provisional_root_hash(MiniblockNumber(number))
+            .await
+            .unwrap()
+            .expect("no provisional root hash");
+        root_hashes.push(root_hash);
+    }
+    assert_ne!(root_hashes[0], root_hash);
+    assert_eq!(root_hashes[1], root_hash);
+}
+
+#[tokio::test]
+async fn computing_root_hashes_for_pending_miniblocks() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (mut merkle_tree_config, operation_config) =
+        create_config(temp_dir.path(), MerkleTreeMode::Lightweight);
+    merkle_tree_config.miniblock_root_hashes_enabled = true;
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let logs = gen_storage_logs(0..20, 1).pop().unwrap();
+    extend_db_state(&mut storage, [logs.clone()]).await;
+
+    // Add 2 miniblocks to the open L1 batch #2: the first one updates an existing slot and creates a new one,
+    // and the second one updates the new slot.
+    let new_key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+    let pending_logs = [
+        vec![
+            StorageLog::new_write_log(logs[0].key, H256::repeat_byte(0xff)),
+            StorageLog::new_write_log(new_key, H256::repeat_byte(1)),
+        ],
+        vec![StorageLog::new_write_log(new_key, H256::repeat_byte(2))],
+    ];
+    let first_pending_miniblock = storage
+        .blocks_dal()
+        .get_sealed_miniblock_number()
+        .await
+        .unwrap()
+        .unwrap()
+        + 1;
+    for (i, logs) in pending_logs.into_iter().enumerate() {
+        let number = first_pending_miniblock + i as u32;
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(number.0))
+            .await
+            .unwrap();
+        storage
+            .storage_logs_dal()
+            .insert_storage_logs(number, &[(H256::zero(), logs)])
+            .await
+            .unwrap();
+    }
+
+    let l1_batch_root_hash = run_calculator(calculator, pool.clone()).await;
+    let mut pending_root_hashes = vec![];
+    for number in [first_pending_miniblock, first_pending_miniblock + 1] {
+        let root_hash = storage
+            .blocks_dal()
+            .get_miniblock_provisional_root_hash(number)
+            .await
+            .unwrap()
+            .expect("no provisional root hash for a pending miniblock");
+        pending_root_hashes.push(root_hash);
+    }
+    assert_ne!(pending_root_hashes[0], l1_batch_root_hash);
+    assert_ne!(pending_root_hashes[0], pending_root_hashes[1]);
+
+    // Seal the L1 batch. Since it creates a single slot, its provisional enumeration index matches the final one,
+    // and so does the root hash.
+    let l1_batch_header = create_l1_batch(2);
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&l1_batch_header)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_miniblocks_as_executed_in_l1_batch(l1_batch_header.number)
+        .await
+        .unwrap();
+    insert_initial_writes_for_batch(&mut storage, l1_batch_header.number).await;
+
+    let calculator =
+        setup_calculator_with_options(&merkle_tree_config, &operation_config, &pool, None).await;
+    let l1_batch_root_hash = run_calculator(calculator, pool.clone()).await;
+    assert_eq!(pending_root_hashes[1], l1_batch_root_hash);
+    let last_root_hash = storage
+        .blocks_dal()
+        .get_miniblock_provisional_root_hash(first_pending_miniblock + 1)
+        .await
+        .unwrap();
+    assert_eq!(last_root_hash, Some(l1_batch_root_hash));
+}
+
 #[tokio::test]
 async fn running_metadata_calculator_with_additional_blocks() {
     let pool = ConnectionPool::test_pool().await;
@@ -12,21 +12,25 @@ use zksync_object_store::ObjectStore;
 use zksync_types::{
     block::{L1BatchHeader, L1BatchTreeData},
     writes::InitialStorageWrite,
-    L1BatchNumber, U256,
+    L1BatchNumber, MiniblockNumber, U256,
 };
 
 use super::{
-    helpers::{AsyncTree, Delayer, L1BatchWithLogs},
+    helpers::{AsyncTree, Delayer, L1BatchWithLogs, MerkleTreeHealth, MiniblockWrites},
     metrics::{TreeUpdateStage, METRICS},
     MetadataCalculator,
 };
-use crate::utils::wait_for_l1_batch;
+use crate::{rocksdb_backup::RocksdbCheckpointer, utils::wait_for_l1_batch};
 
 #[derive(Debug)]
 pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_l1_batches_per_iter: usize,
     object_store: Option<Arc<dyn ObjectStore>>,
+    miniblock_root_hashes_enabled: bool,
+    /// Last miniblock in the open L1 batch for which a provisional root hash is computed.
+    last_pending_miniblock_with_root_hash: Option<MiniblockNumber>,
+    checkpointer: Option<RocksdbCheckpointer>,
 }
 
 impl TreeUpdater {
@@ -39,9 +43,131 @@ impl TreeUpdater {
             tree,
             max_l1_batches_per_iter,
             object_store,
+            miniblock_root_hashes_enabled: false,
+            last_pending_miniblock_with_root_hash: None,
+            checkpointer: None,
         }
     }
 
+    /// Enables periodic checkpoints of the tree RocksDB, which are created after saving processed L1 batches.
+    #[must_use]
+    pub fn with_checkpointer(mut self, checkpointer: Option<RocksdbCheckpointer>) -> Self {
+        self.checkpointer = checkpointer;
+        self
+    }
+
+    /// Enables computing provisional root hashes for miniblocks.
+    #[must_use]
+    pub fn with_miniblock_root_hashes(mut self, enabled: bool) -> Self {
+        self.miniblock_root_hashes_enabled = enabled;
+        self
+    }
+
+    /// Computes and persists provisional root hashes for miniblocks in the specified L1 batch. Must be called
+    /// before the batch is processed by the tree.
+    async fn save_miniblock_root_hashes(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+    ) {
+        let load_latency = METRICS.start_stage(TreeUpdateStage::LoadMiniblockChanges);
+        let Some(miniblock_writes) = MiniblockWrites::new(storage, l1_batch_number).await else {
+            tracing::warn!(
+                "L1 batch #{l1_batch_number} has no miniblocks; skipping computing their root hashes"
+            );
+            return;
+        };
+        load_latency.observe();
+
+        let compute_latency = METRICS.start_stage(TreeUpdateStage::ComputeMiniblocks);
+        let miniblock_numbers = miniblock_writes.miniblock_numbers.clone();
+        let root_hashes = self.tree.miniblock_root_hashes(miniblock_writes).await;
+        compute_latency.observe();
+
+        let root_hashes: Vec<_> = miniblock_numbers.into_iter().zip(root_hashes).collect();
+        storage
+            .blocks_dal()
+            .save_miniblock_provisional_root_hashes(&root_hashes)
+            .await
+            .unwrap();
+        tracing::debug!(
+            "Saved provisional root hashes for {} miniblocks in L1 batch #{l1_batch_number}",
+            root_hashes.len()
+        );
+    }
+
+    /// Computes and persists provisional root hashes for new miniblocks in the open L1 batch. Must be called
+    /// after the tree has processed all sealed L1 batches.
+    ///
+    /// Since new storage slots get provisional enumeration indices (see [`MiniblockWrites::for_pending_miniblocks()`]),
+    /// root hashes computed by this method may change once the batch is sealed; they are recomputed
+    /// by [`Self::save_miniblock_root_hashes()`] before the tree processes the batch.
+    async fn save_pending_miniblock_root_hashes(
+        &mut self,
+        storage: &mut StorageProcessor<'_>,
+        last_sealed_l1_batch: L1BatchNumber,
+    ) {
+        let load_latency = METRICS.start_stage(TreeUpdateStage::LoadMiniblockChanges);
+        let Some((_, last_sealed_batch_miniblock)) = storage
+            .blocks_dal()
+            .get_miniblock_range_of_l1_batch(last_sealed_l1_batch)
+            .await
+            .unwrap()
+        else {
+            return;
+        };
+        let Some(last_miniblock) = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .unwrap()
+        else {
+            return;
+        };
+        if self.last_pending_miniblock_with_root_hash > Some(last_miniblock) {
+            // Pending miniblocks were reverted.
+            self.last_pending_miniblock_with_root_hash = None;
+        }
+
+        let first_pending_miniblock = last_sealed_batch_miniblock + 1;
+        let first_new_miniblock = self
+            .last_pending_miniblock_with_root_hash
+            .map_or(first_pending_miniblock, |number| {
+                (number + 1).max(first_pending_miniblock)
+            });
+        if first_new_miniblock > last_miniblock {
+            return;
+        }
+        // Provisional root hashes depend on all previous miniblocks in the batch, so writes are loaded
+        // starting from the first pending miniblock.
+        let miniblock_writes = MiniblockWrites::for_pending_miniblocks(
+            storage,
+            first_pending_miniblock..=last_miniblock,
+        )
+        .await;
+        load_latency.observe();
+
+        let compute_latency = METRICS.start_stage(TreeUpdateStage::ComputeMiniblocks);
+        let miniblock_numbers = miniblock_writes.miniblock_numbers.clone();
+        let root_hashes = self.tree.miniblock_root_hashes(miniblock_writes).await;
+        compute_latency.observe();
+
+        let root_hashes: Vec<_> = miniblock_numbers
+            .into_iter()
+            .zip(root_hashes)
+            .filter(|(number, _)| *number >= first_new_miniblock)
+            .collect();
+        storage
+            .blocks_dal()
+            .save_miniblock_provisional_root_hashes(&root_hashes)
+            .await
+            .unwrap();
+        self.last_pending_miniblock_with_root_hash = Some(last_miniblock);
+        tracing::debug!(
+            "Saved provisional root hashes for pending miniblocks #{first_new_miniblock}..=#{last_miniblock}"
+        );
+    }
+
     async fn process_l1_batch(
         &mut self,
         l1_batch: L1BatchWithLogs,
@@ -103,6 +229,10 @@ impl TreeUpdater {
                 return l1_batch_number;
             };
             total_logs += current_l1_batch_data.storage_logs.len();
+            if self.miniblock_root_hashes_enabled {
+                self.save_miniblock_root_hashes(storage, l1_batch_number)
+                    .await;
+            }
 
             let process_l1_batch_task = self.process_l1_batch(current_l1_batch_data);
             let load_next_l1_batch_task = async {
@@ -162,14 +292,26 @@ impl TreeUpdater {
         save_rocksdb_latency.observe();
         MetadataCalculator::update_metrics(&updated_headers, total_logs, start);
 
+        if let Some(checkpointer) = self.checkpointer.clone() {
+            let reader = self.tree.reader();
+            tokio::task::spawn_blocking(move || {
+                checkpointer.maybe_create_checkpoint(last_l1_batch_number, |path| {
+                    reader.create_checkpoint(path)
+                });
+            })
+            .await
+            .unwrap();
+        }
+
         last_l1_batch_number + 1
     }
 
+    /// Processes the next chunk of sealed L1 batches. Returns the number of the last sealed L1 batch in Postgres.
     async fn step(
         &mut self,
         mut storage: StorageProcessor<'_>,
         next_l1_batch_to_seal: &mut L1BatchNumber,
-    ) {
+    ) -> Option<L1BatchNumber> {
         let Some(last_sealed_l1_batch) = storage
             .blocks_dal()
             .get_sealed_l1_batch_number()
@@ -177,7 +319,7 @@ impl TreeUpdater {
             .unwrap()
         else {
             tracing::trace!("No L1 batches to seal: Postgres storage is empty");
-            return;
+            return None;
         };
         let last_requested_l1_batch =
             next_l1_batch_to_seal.0 + self.max_l1_batches_per_iter as u32 - 1;
@@ -193,6 +335,13 @@ impl TreeUpdater {
                 .process_multiple_batches(&mut storage, l1_batch_numbers)
                 .await;
         }
+
+        if self.miniblock_root_hashes_enabled && *next_l1_batch_to_seal == last_sealed_l1_batch + 1
+        {
+            self.save_pending_miniblock_root_hashes(&mut storage, last_sealed_l1_batch)
+                .await;
+        }
+        Some(last_sealed_l1_batch)
     }
 
     /// The processing loop for this updater.
@@ -280,7 +429,7 @@ impl TreeUpdater {
             let storage = pool.access_storage_tagged("metadata_calculator").await?;
 
             let snapshot = *next_l1_batch_to_seal;
-            self.step(storage, &mut next_l1_batch_to_seal).await;
+            let last_sealed_l1_batch = self.step(storage, &mut next_l1_batch_to_seal).await;
             let delay = if snapshot == *next_l1_batch_to_seal {
                 tracing::trace!(
                     "Metadata calculator (next L1 batch: #{next_l1_batch_to_seal}) \
@@ -289,7 +438,8 @@ impl TreeUpdater {
                 delayer.wait(&self.tree).left_future()
             } else {
                 let tree_info = self.tree.reader().info().await;
-                health_updater.update(tree_info.into());
+                let health = MerkleTreeHealth::main_loop(tree_info, last_sealed_l1_batch);
+                health_updater.update(health.into());
 
                 tracing::trace!(
                     "Metadata calculator (next L1 batch: #{next_l1_batch_to_seal}) made progress from #{snapshot}"
@@ -0,0 +1,34 @@
+//! Metrics for RocksDB backups.
+
+use std::time::Duration;
+
+use vise::{Buckets, Counter, Gauge, Histogram, LabeledFamily, Metrics, Unit};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "rocksdb_backup")]
+pub(super) struct RocksdbBackupMetrics {
+    /// Latency of creating a local checkpoint of a DB.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["db"])]
+    pub checkpoint_latency: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Latency of uploading a checkpoint to the object store.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["db"])]
+    pub upload_latency: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Total number of bytes uploaded to the object store.
+    #[metrics(unit = Unit::Bytes, labels = ["db"])]
+    pub uploaded_bytes: LabeledFamily<&'static str, Counter>,
+    /// Number of failed checkpoint creations or uploads.
+    #[metrics(labels = ["db"])]
+    pub errors: LabeledFamily<&'static str, Counter>,
+    /// Number of failed removals of uploaded checkpoints. Failed removals are retried.
+    #[metrics(labels = ["db"])]
+    pub cleanup_errors: LabeledFamily<&'static str, Counter>,
+    /// Last L1 batch covered by an uploaded backup.
+    #[metrics(labels = ["db"])]
+    pub last_backed_up_l1_batch: LabeledFamily<&'static str, Gauge<u64>>,
+    /// Latency of restoring a backup from the object store.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["db"])]
+    pub restore_latency: LabeledFamily<&'static str, Histogram<Duration>>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<RocksdbBackupMetrics> = vise::Global::new();
@@ -0,0 +1,545 @@
+//! Backups of RocksDB instances (the state keeper cache and the Merkle tree) in the object store.
+//!
+//! Recovering the state keeper cache or the Merkle tree from Postgres can take hours for large chains. To speed up
+//! node recovery, the DB owners can periodically create consistent RocksDB checkpoints (see [`RocksdbCheckpointer`]),
+//! which are then uploaded to the object store by [`RocksdbBackupUploader`]. On startup, a DB can be restored
+//! from the latest uploaded backup using [`restore_rocksdb_backup()`]; the restored DB is then caught up
+//! with Postgres as usual.
+//!
+//! Backup files are content-addressed, so unchanged files (most SST files) are uploaded once and shared
+//! among successive backups.
+
+use std::{
+    collections::HashSet,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    sync::{mpsc, watch},
+};
+use zksync_dal::ConnectionPool;
+use zksync_object_store::{
+    serialize_using_bincode, Bucket, ObjectStore, ObjectStoreError, StoredObject,
+};
+use zksync_types::L1BatchNumber;
+
+use self::metrics::METRICS;
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Default maximum size of a single object uploaded to the object store. Larger files are split into parts
+/// of this size, so that uploads and downloads don't need to hold entire files (SST files can be hundreds of MBs)
+/// in memory.
+const DEFAULT_PART_SIZE: u64 = 64 * 1_024 * 1_024;
+
+/// Kind of a RocksDB instance that can be backed up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RocksdbBackupKind {
+    /// State keeper cache.
+    StateKeeperCache,
+    /// Merkle tree.
+    MerkleTree,
+}
+
+impl RocksdbBackupKind {
+    fn db_name(self) -> &'static str {
+        match self {
+            Self::StateKeeperCache => "state_keeper_cache",
+            Self::MerkleTree => "merkle_tree",
+        }
+    }
+}
+
+impl fmt::Display for RocksdbBackupKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.db_name())
+    }
+}
+
+/// Location of backups in the object store: a node-specific prefix and the kind of the backed up DB.
+/// The prefix allows several nodes to share a bucket without overwriting each other's backups.
+#[derive(Debug, Clone, Copy)]
+pub struct RocksdbBackupLocation<'a> {
+    pub prefix: &'a str,
+    pub kind: RocksdbBackupKind,
+}
+
+impl fmt::Display for RocksdbBackupLocation<'_> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}_{}", self.prefix, self.kind)
+    }
+}
+
+/// Manifest of the latest backup of a RocksDB instance stored in the object store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RocksdbBackupManifest {
+    /// Last L1 batch processed by the backed up DB.
+    pub l1_batch_number: L1BatchNumber,
+    /// Files constituting the backup.
+    pub files: Vec<RocksdbBackupFile>,
+}
+
+impl StoredObject for RocksdbBackupManifest {
+    const BUCKET: Bucket = Bucket::RocksdbBackups;
+    type Key<'a> = RocksdbBackupLocation<'a>;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("{key}_latest_backup.bin")
+    }
+
+    serialize_using_bincode!();
+}
+
+/// File in a [`RocksdbBackupManifest`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RocksdbBackupFile {
+    /// Name of the file in the RocksDB directory.
+    pub name: String,
+    /// Object store key of the file contents. The key is derived from the SHA-256 digest of the contents,
+    /// so that files with the same contents (e.g., unchanged SST files) are shared among backups.
+    pub key: String,
+    /// Number of parts the file contents are split into; see [`Self::part_keys()`].
+    pub part_count: u64,
+}
+
+impl RocksdbBackupFile {
+    fn new(
+        location: RocksdbBackupLocation<'_>,
+        name: String,
+        digest: &[u8],
+        size: u64,
+        part_size: u64,
+    ) -> Self {
+        Self {
+            name,
+            key: format!("{location}_{}", hex::encode(digest)),
+            part_count: size.div_ceil(part_size).max(1),
+        }
+    }
+
+    /// Returns object store keys of the parts of the file contents.
+    pub(crate) fn part_keys(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.part_count).map(|i| format!("{}_part{i}", self.key))
+    }
+}
+
+#[derive(Debug)]
+struct RocksdbCheckpoint {
+    path: PathBuf,
+    l1_batch_number: L1BatchNumber,
+}
+
+/// Periodically creates checkpoints of a RocksDB instance and sends them to the paired [`RocksdbBackupUploader`].
+/// Checkpoints are created by the DB owner (the batch executor for the state keeper cache, or the Merkle tree
+/// updater) between L1 batches, when the DB is consistent.
+///
+/// A checkpoint is skipped if the previous checkpoint is still being uploaded.
+#[derive(Debug, Clone)]
+pub struct RocksdbCheckpointer {
+    kind: RocksdbBackupKind,
+    checkpoints_dir: PathBuf,
+    interval: Duration,
+    last_checkpoint_at: Arc<Mutex<Instant>>,
+    checkpoints_sender: mpsc::Sender<RocksdbCheckpoint>,
+}
+
+impl RocksdbCheckpointer {
+    /// Creates a checkpointer storing checkpoints in `checkpoints_dir` with the specified interval between checkpoints.
+    /// `checkpoints_dir` should be located on the same filesystem as the DB, so that checkpoints are cheap
+    /// to create. The first checkpoint is created after `interval` elapses.
+    ///
+    /// Backups are stored in the object store under the specified `prefix`, which must be unique for each node
+    /// sharing the object store bucket.
+    pub fn new(
+        kind: RocksdbBackupKind,
+        prefix: String,
+        checkpoints_dir: PathBuf,
+        interval: Duration,
+        object_store: Arc<dyn ObjectStore>,
+    ) -> (Self, RocksdbBackupUploader) {
+        // The uploader processes checkpoints one by one, so capacity 1 is enough.
+        let (checkpoints_sender, checkpoints_receiver) = mpsc::channel(1);
+        let this = Self {
+            kind,
+            checkpoints_dir,
+            interval,
+            last_checkpoint_at: Arc::new(Mutex::new(Instant::now())),
+            checkpoints_sender,
+        };
+        let uploader = RocksdbBackupUploader {
+            kind,
+            prefix,
+            part_size: DEFAULT_PART_SIZE,
+            object_store,
+            checkpoints_receiver,
+        };
+        (this, uploader)
+    }
+
+    /// Creates a checkpoint using `create_checkpoint` if the checkpoint interval has elapsed. `create_checkpoint`
+    /// receives the path to create the checkpoint at; the path is guaranteed not to exist. Errors are logged
+    /// rather than propagated, since backups are not critical for the DB owner.
+    ///
+    /// This method is blocking.
+    pub(crate) fn maybe_create_checkpoint(
+        &self,
+        last_processed_l1_batch: L1BatchNumber,
+        create_checkpoint: impl FnOnce(&Path) -> anyhow::Result<()>,
+    ) {
+        let kind = self.kind;
+        let mut last_checkpoint_at = self
+            .last_checkpoint_at
+            .lock()
+            .expect("checkpointer mutex is poisoned");
+        if last_checkpoint_at.elapsed() < self.interval {
+            return;
+        }
+        let Ok(permit) = self.checkpoints_sender.try_reserve() else {
+            tracing::info!(
+                "Skipping `{kind}` checkpoint for L1 batch #{last_processed_l1_batch} \
+                 since the previous checkpoint is not uploaded yet"
+            );
+            return;
+        };
+
+        let path = self
+            .checkpoints_dir
+            .join(format!("l1_batch_{last_processed_l1_batch}"));
+        let latency = METRICS.checkpoint_latency[&kind.db_name()].start();
+        match self
+            .prepare_checkpoint_path(&path)
+            .and_then(|()| create_checkpoint(&path))
+        {
+            Ok(()) => {
+                latency.observe();
+                *last_checkpoint_at = Instant::now();
+                permit.send(RocksdbCheckpoint {
+                    path,
+                    l1_batch_number: last_processed_l1_batch,
+                });
+            }
+            Err(err) => {
+                METRICS.errors[&kind.db_name()].inc();
+                tracing::warn!(
+                    "Failed creating `{kind}` checkpoint for L1 batch #{last_processed_l1_batch}: {err:#}"
+                );
+            }
+        }
+    }
+
+    fn prepare_checkpoint_path(&self, path: &Path) -> anyhow::Result<()> {
+        fs::create_dir_all(&self.checkpoints_dir).with_context(|| {
+            format!(
+                "failed creating checkpoints directory `{}`",
+                self.checkpoints_dir.display()
+            )
+        })?;
+        if path.exists() {
+            // Can happen if the node was restarted during the upload.
+            fs::remove_dir_all(path).with_context(|| {
+                format!("failed removing stale checkpoint `{}`", path.display())
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Uploads checkpoints created by [`RocksdbCheckpointer`] to the object store and removes them locally.
+#[derive(Debug)]
+pub struct RocksdbBackupUploader {
+    kind: RocksdbBackupKind,
+    prefix: String,
+    part_size: u64,
+    object_store: Arc<dyn ObjectStore>,
+    checkpoints_receiver: mpsc::Receiver<RocksdbCheckpoint>,
+}
+
+impl RocksdbBackupUploader {
+    fn location(&self) -> RocksdbBackupLocation<'_> {
+        RocksdbBackupLocation {
+            prefix: &self.prefix,
+            kind: self.kind,
+        }
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let kind = self.kind;
+        // Checkpoints that are processed, but weren't removed yet.
+        let mut processed_checkpoints = vec![];
+        loop {
+            let checkpoint = tokio::select! {
+                checkpoint = self.checkpoints_receiver.recv() => checkpoint,
+                _ = stop_receiver.changed() => break,
+            };
+            let Some(checkpoint) = checkpoint else {
+                tracing::info!("`{kind}` checkpointer was dropped");
+                break;
+            };
+
+            let latency = METRICS.upload_latency[&kind.db_name()].start();
+            match self.upload_checkpoint(&checkpoint).await {
+                Ok(()) => {
+                    latency.observe();
+                    METRICS.last_backed_up_l1_batch[&kind.db_name()]
+                        .set(checkpoint.l1_batch_number.0.into());
+                    tracing::info!(
+                        "Uploaded backup of `{kind}` for L1 batch #{}",
+                        checkpoint.l1_batch_number
+                    );
+                }
+                Err(err) => {
+                    METRICS.errors[&kind.db_name()].inc();
+                    tracing::warn!(
+                        "Failed uploading backup of `{kind}` for L1 batch #{}: {err:#}",
+                        checkpoint.l1_batch_number
+                    );
+                }
+            }
+            processed_checkpoints.push(checkpoint.path);
+            self.remove_checkpoints(&mut processed_checkpoints).await;
+        }
+        tracing::info!("Stop signal received, `{kind}` backup uploader is shutting down");
+        Ok(())
+    }
+
+    /// Removes the specified checkpoint directories. Directories that cannot be removed are retained in `paths`,
+    /// so that their removal is retried later.
+    async fn remove_checkpoints(&self, paths: &mut Vec<PathBuf>) {
+        let kind = self.kind;
+        let mut remaining_paths = vec![];
+        for path in paths.drain(..) {
+            match tokio::fs::remove_dir_all(&path).await {
+                Ok(()) => { /* Removed successfully */ }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => { /* Already removed */ }
+                Err(err) => {
+                    METRICS.cleanup_errors[&kind.db_name()].inc();
+                    tracing::warn!(
+                        "Failed removing `{kind}` checkpoint `{}`: {err}; will retry later",
+                        path.display()
+                    );
+                    remaining_paths.push(path);
+                }
+            }
+        }
+        *paths = remaining_paths;
+    }
+
+    async fn upload_checkpoint(&self, checkpoint: &RocksdbCheckpoint) -> anyhow::Result<()> {
+        let prev_manifest = match self
+            .object_store
+            .get::<RocksdbBackupManifest>(self.location())
+            .await
+        {
+            Ok(manifest) => Some(manifest),
+            Err(ObjectStoreError::KeyNotFound(_)) => None,
+            Err(err) => return Err(err).context("failed getting previous backup manifest"),
+        };
+        let prev_files: HashSet<_> = prev_manifest
+            .iter()
+            .flat_map(|manifest| &manifest.files)
+            .map(|file| (file.key.as_str(), file.part_count))
+            .collect();
+
+        let mut files = vec![];
+        let mut dir_entries = tokio::fs::read_dir(&checkpoint.path)
+            .await
+            .context("failed reading checkpoint directory")?;
+        while let Some(entry) = dir_entries.next_entry().await? {
+            let name = entry
+                .file_name()
+                .into_string()
+                .map_err(|name| anyhow::anyhow!("non-UTF8 file name in checkpoint: {name:?}"))?;
+            let path = entry.path();
+            let (digest, size) = hash_file(&path)
+                .await
+                .with_context(|| format!("failed hashing checkpoint file `{name}`"))?;
+            let file = RocksdbBackupFile::new(self.location(), name, &digest, size, self.part_size);
+            if prev_files.contains(&(file.key.as_str(), file.part_count)) {
+                tracing::debug!("File `{}` is already uploaded, skipping", file.name);
+            } else {
+                self.upload_file(&path, &file)
+                    .await
+                    .with_context(|| format!("failed uploading checkpoint file `{}`", file.name))?;
+            }
+            files.push(file);
+        }
+
+        let manifest = RocksdbBackupManifest {
+            l1_batch_number: checkpoint.l1_batch_number,
+            files,
+        };
+        self.object_store
+            .put(self.location(), &manifest)
+            .await
+            .context("failed uploading backup manifest")?;
+
+        // Remove files of the previous backup that are not referenced by the new one. This can break a restoration
+        // from the previous backup running concurrently, but it will fail on a missing file rather than produce
+        // an inconsistent DB.
+        let new_keys: HashSet<_> = manifest
+            .files
+            .iter()
+            .map(|file| file.key.as_str())
+            .collect();
+        let obsolete_files = prev_manifest
+            .iter()
+            .flat_map(|manifest| &manifest.files)
+            .filter(|file| !new_keys.contains(file.key.as_str()));
+        for obsolete_key in obsolete_files.flat_map(RocksdbBackupFile::part_keys) {
+            if let Err(err) = self
+                .object_store
+                .remove_raw(Bucket::RocksdbBackups, &obsolete_key)
+                .await
+            {
+                tracing::warn!("Failed removing obsolete backup file `{obsolete_key}`: {err}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Uploads the file part by part, so that at most one part is held in memory at a time.
+    async fn upload_file(&self, path: &Path, file: &RocksdbBackupFile) -> anyhow::Result<()> {
+        let mut reader = tokio::fs::File::open(path).await?;
+        for part_key in file.part_keys() {
+            let mut part = vec![];
+            (&mut reader)
+                .take(self.part_size)
+                .read_to_end(&mut part)
+                .await?;
+            METRICS.uploaded_bytes[&self.kind.db_name()].inc_by(part.len() as u64);
+            self.object_store
+                .put_raw(Bucket::RocksdbBackups, &part_key, part)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Computes the SHA-256 digest and the size of the file without reading it into memory entirely.
+async fn hash_file(path: &Path) -> anyhow::Result<(Vec<u8>, u64)> {
+    const BUFFER_SIZE: usize = 1_024 * 1_024;
+
+    let mut reader = tokio::fs::File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0_u8; BUFFER_SIZE];
+    let mut size = 0_u64;
+    loop {
+        let read_bytes = reader.read(&mut buffer).await?;
+        if read_bytes == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read_bytes]);
+        size += read_bytes as u64;
+    }
+    Ok((hasher.finalize().to_vec(), size))
+}
+
+/// Restores a RocksDB instance of the specified `kind` at `db_path` from the latest backup stored
+/// in the object store under `prefix`. The DB is only restored if it doesn't exist locally, and if the backup
+/// isn't ahead of Postgres (the DB cannot be rolled back in this case).
+///
+/// Returns the last L1 batch processed by the restored DB, or `None` if the DB wasn't restored.
+pub async fn restore_rocksdb_backup(
+    object_store: &dyn ObjectStore,
+    pool: &ConnectionPool,
+    kind: RocksdbBackupKind,
+    prefix: &str,
+    db_path: &Path,
+) -> anyhow::Result<Option<L1BatchNumber>> {
+    if let Ok(mut dir_entries) = tokio::fs::read_dir(db_path).await {
+        if dir_entries.next_entry().await?.is_some() {
+            tracing::info!(
+                "RocksDB `{kind}` already exists at `{}`; not restoring it from backup",
+                db_path.display()
+            );
+            return Ok(None);
+        }
+    }
+
+    let location = RocksdbBackupLocation { prefix, kind };
+    let manifest = match object_store.get::<RocksdbBackupManifest>(location).await {
+        Ok(manifest) => manifest,
+        Err(ObjectStoreError::KeyNotFound(_)) => {
+            tracing::info!("No backups of RocksDB `{location}` are found in the object store");
+            return Ok(None);
+        }
+        Err(err) => return Err(err).context("failed getting backup manifest"),
+    };
+    let mut storage = pool.access_storage_tagged("rocksdb_backup").await?;
+    let sealed_l1_batch = storage
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await
+        .context("failed getting sealed L1 batch number")?;
+    drop(storage);
+    if sealed_l1_batch.map_or(true, |number| number < manifest.l1_batch_number) {
+        tracing::warn!(
+            "Latest backup of RocksDB `{location}` is for L1 batch #{}, which is ahead of the last sealed \
+             L1 batch in Postgres ({sealed_l1_batch:?}); not restoring it",
+            manifest.l1_batch_number
+        );
+        return Ok(None);
+    }
+
+    tracing::info!(
+        "Restoring RocksDB `{location}` for L1 batch #{} from {} backup files",
+        manifest.l1_batch_number,
+        manifest.files.len()
+    );
+    let latency = METRICS.restore_latency[&kind.db_name()].start();
+    // Download files to a temporary directory first, so that an interrupted restoration doesn't leave
+    // a partially restored DB.
+    let temp_path = db_path.with_extension("restoring");
+    if tokio::fs::try_exists(&temp_path).await? {
+        tokio::fs::remove_dir_all(&temp_path).await?;
+    }
+    tokio::fs::create_dir_all(&temp_path)
+        .await
+        .with_context(|| format!("failed creating directory `{}`", temp_path.display()))?;
+    for file in &manifest.files {
+        download_file(object_store, file, &temp_path.join(&file.name))
+            .await
+            .with_context(|| format!("failed restoring backup file `{}`", file.name))?;
+    }
+    if tokio::fs::try_exists(db_path).await? {
+        // The directory is empty as checked above.
+        tokio::fs::remove_dir(db_path).await?;
+    }
+    tokio::fs::rename(&temp_path, db_path)
+        .await
+        .with_context(|| format!("failed moving restored DB to `{}`", db_path.display()))?;
+
+    let elapsed = latency.observe();
+    tracing::info!(
+        "Restored RocksDB `{location}` for L1 batch #{} in {elapsed:?}",
+        manifest.l1_batch_number
+    );
+    Ok(Some(manifest.l1_batch_number))
+}
+
+/// Downloads the file part by part, so that at most one part is held in memory at a time.
+async fn download_file(
+    object_store: &dyn ObjectStore,
+    file: &RocksdbBackupFile,
+    path: &Path,
+) -> anyhow::Result<()> {
+    let mut writer = tokio::fs::File::create(path).await?;
+    for part_key in file.part_keys() {
+        let part = object_store
+            .get_raw(Bucket::RocksdbBackups, &part_key)
+            .await
+            .with_context(|| format!("failed downloading `{part_key}`"))?;
+        writer.write_all(&part).await?;
+    }
+    writer.sync_all().await?;
+    Ok(())
+}
@@ -0,0 +1,419 @@
+//! Tests for RocksDB backups.
+
+use tempfile::TempDir;
+use zksync_merkle_tree::{domain::ZkSyncTree, RocksDBWrapper, TreeInstruction};
+use zksync_object_store::ObjectStoreFactory;
+use zksync_state::RocksdbStorage;
+use zksync_types::{AccountTreeId, Address, L2ChainId, StorageKey, H256};
+
+use super::*;
+use crate::genesis::{ensure_genesis_state, GenesisParams};
+
+const PREFIX: &str = "test_node";
+
+fn location(kind: RocksdbBackupKind) -> RocksdbBackupLocation<'static> {
+    RocksdbBackupLocation {
+        prefix: PREFIX,
+        kind,
+    }
+}
+
+fn state_keeper_cache_checkpointer(
+    checkpoints_dir: PathBuf,
+    object_store: Arc<dyn ObjectStore>,
+) -> (RocksdbCheckpointer, RocksdbBackupUploader) {
+    RocksdbCheckpointer::new(
+        RocksdbBackupKind::StateKeeperCache,
+        PREFIX.to_owned(),
+        checkpoints_dir,
+        Duration::ZERO,
+        object_store,
+    )
+}
+
+fn checkpoint_cache(
+    checkpointer: &RocksdbCheckpointer,
+    cache: &RocksdbStorage,
+    l1_batch_number: L1BatchNumber,
+) {
+    checkpointer.maybe_create_checkpoint(l1_batch_number, |path| cache.create_checkpoint(path));
+}
+
+async fn prepare_cache(pool: &ConnectionPool, cache_path: &Path) -> RocksdbStorage {
+    let mut storage = pool.access_storage().await.unwrap();
+    if storage.blocks_dal().is_genesis_needed().await.unwrap() {
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+    }
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    RocksdbStorage::builder(cache_path)
+        .await
+        .unwrap()
+        .synchronize(&mut storage, &stop_receiver)
+        .await
+        .unwrap()
+        .expect("cache synchronization was interrupted")
+}
+
+async fn restore_state_keeper_cache(
+    object_store: &dyn ObjectStore,
+    pool: &ConnectionPool,
+    db_path: &Path,
+) -> Option<L1BatchNumber> {
+    restore_rocksdb_backup(
+        object_store,
+        pool,
+        RocksdbBackupKind::StateKeeperCache,
+        PREFIX,
+        db_path,
+    )
+    .await
+    .unwrap()
+}
+
+async fn wait_for_manifest(
+    object_store: &dyn ObjectStore,
+    kind: RocksdbBackupKind,
+) -> RocksdbBackupManifest {
+    loop {
+        match object_store
+            .get::<RocksdbBackupManifest>(location(kind))
+            .await
+        {
+            Ok(manifest) => return manifest,
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            Err(err) => panic!("{err}"),
+        }
+    }
+}
+
+#[test]
+fn backup_file_keys() {
+    let location = location(RocksdbBackupKind::StateKeeperCache);
+    assert_eq!(
+        RocksdbBackupManifest::encode_key(location),
+        "test_node_state_keeper_cache_latest_backup.bin"
+    );
+
+    let file = RocksdbBackupFile::new(location, "000012.sst".to_owned(), &[0xab; 4], 100, 128);
+    assert_eq!(file.key, "test_node_state_keeper_cache_abababab");
+    assert_eq!(
+        file.part_keys().collect::<Vec<_>>(),
+        [format!("{}_part0", file.key)]
+    );
+
+    let file = RocksdbBackupFile::new(location, "CURRENT".to_owned(), &[0xab; 4], 0, 128);
+    assert_eq!(file.part_count, 1);
+    let file = RocksdbBackupFile::new(location, "000013.sst".to_owned(), &[0xab; 4], 128, 128);
+    assert_eq!(file.part_count, 1);
+    let file = RocksdbBackupFile::new(location, "000014.sst".to_owned(), &[0xab; 4], 129, 128);
+    assert_eq!(file.part_count, 2);
+    assert_eq!(
+        file.part_keys().collect::<Vec<_>>(),
+        [format!("{}_part0", file.key), format!("{}_part1", file.key)]
+    );
+}
+
+#[tokio::test]
+async fn uploading_and_downloading_file_in_parts() {
+    const PART_SIZE: u64 = 1_000;
+
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("000012.sst");
+    let contents: Vec<_> = (0..PART_SIZE * 2 + 3).map(|i| i as u8).collect();
+    tokio::fs::write(&path, &contents).await.unwrap();
+
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let (_checkpointer, mut uploader) =
+        state_keeper_cache_checkpointer(temp_dir.path().join("checkpoints"), object_store.clone());
+    uploader.part_size = PART_SIZE;
+    let (digest, size) = hash_file(&path).await.unwrap();
+    assert_eq!(size, contents.len() as u64);
+    assert_eq!(digest, Sha256::digest(&contents).to_vec());
+    let file = RocksdbBackupFile::new(
+        uploader.location(),
+        "000012.sst".to_owned(),
+        &digest,
+        size,
+        PART_SIZE,
+    );
+    assert_eq!(file.part_count, 3);
+    uploader.upload_file(&path, &file).await.unwrap();
+
+    let mut part_sizes = vec![];
+    for part_key in file.part_keys() {
+        let part = object_store
+            .get_raw(Bucket::RocksdbBackups, &part_key)
+            .await
+            .unwrap();
+        part_sizes.push(part.len() as u64);
+    }
+    assert_eq!(part_sizes, [PART_SIZE, PART_SIZE, 3]);
+
+    let restored_path = temp_dir.path().join("restored.sst");
+    download_file(&*object_store, &file, &restored_path)
+        .await
+        .unwrap();
+    assert_eq!(tokio::fs::read(&restored_path).await.unwrap(), contents);
+}
+
+#[tokio::test]
+async fn failed_checkpoint_removals_are_retried() {
+    let temp_dir = TempDir::new().unwrap();
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let (_checkpointer, uploader) =
+        state_keeper_cache_checkpointer(temp_dir.path().join("checkpoints"), object_store);
+
+    let checkpoint_path = temp_dir.path().join("l1_batch_1");
+    tokio::fs::create_dir(&checkpoint_path).await.unwrap();
+    // `remove_dir_all()` fails for files.
+    let unremovable_path = temp_dir.path().join("l1_batch_2");
+    tokio::fs::write(&unremovable_path, b"test").await.unwrap();
+    let missing_path = temp_dir.path().join("l1_batch_3");
+
+    let mut paths = vec![
+        checkpoint_path.clone(),
+        unremovable_path.clone(),
+        missing_path,
+    ];
+    uploader.remove_checkpoints(&mut paths).await;
+    assert_eq!(paths, [unremovable_path.clone()]);
+    assert!(!checkpoint_path.exists());
+
+    tokio::fs::remove_file(&unremovable_path).await.unwrap();
+    tokio::fs::create_dir(&unremovable_path).await.unwrap();
+    uploader.remove_checkpoints(&mut paths).await;
+    assert!(paths.is_empty());
+    assert!(!unremovable_path.exists());
+}
+
+#[tokio::test]
+async fn backing_up_and_restoring_state_keeper_cache() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().unwrap();
+    let cache_path = temp_dir.path().join("cache");
+    let checkpoints_dir = temp_dir.path().join("checkpoints");
+    let cache = prepare_cache(&pool, &cache_path).await;
+
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let (checkpointer, uploader) =
+        state_keeper_cache_checkpointer(checkpoints_dir.clone(), object_store.clone());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let uploader_task = tokio::spawn(uploader.run(stop_receiver));
+
+    let checkpoint_path = checkpoints_dir.join("l1_batch_0");
+    tokio::task::spawn_blocking(move || {
+        checkpoint_cache(&checkpointer, &cache, L1BatchNumber(0));
+    })
+    .await
+    .unwrap();
+
+    let manifest = wait_for_manifest(&*object_store, RocksdbBackupKind::StateKeeperCache).await;
+    assert_eq!(manifest.l1_batch_number, L1BatchNumber(0));
+    assert!(manifest.files.iter().any(|file| file.name == "CURRENT"));
+    for part_key in manifest.files.iter().flat_map(RocksdbBackupFile::part_keys) {
+        object_store
+            .get_raw(Bucket::RocksdbBackups, &part_key)
+            .await
+            .unwrap();
+    }
+    stop_sender.send_replace(true);
+    uploader_task.await.unwrap().unwrap();
+    assert!(!checkpoint_path.exists());
+
+    let restored_path = temp_dir.path().join("restored_cache");
+    let restored_l1_batch = restore_state_keeper_cache(&*object_store, &pool, &restored_path).await;
+    assert_eq!(restored_l1_batch, Some(L1BatchNumber(0)));
+    let restored_cache = RocksdbStorage::builder(&restored_path).await.unwrap();
+    assert_eq!(
+        restored_cache.l1_batch_number().await,
+        Some(L1BatchNumber(1))
+    );
+    drop(restored_cache);
+
+    // The cache must not be overwritten if it exists.
+    let restored_l1_batch = restore_state_keeper_cache(&*object_store, &pool, &restored_path).await;
+    assert_eq!(restored_l1_batch, None);
+}
+
+#[tokio::test]
+async fn backup_is_not_restored_if_it_is_ahead_of_postgres() {
+    let pool = ConnectionPool::test_pool().await;
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let manifest = RocksdbBackupManifest {
+        l1_batch_number: L1BatchNumber(1),
+        files: vec![],
+    };
+    object_store
+        .put(location(RocksdbBackupKind::StateKeeperCache), &manifest)
+        .await
+        .unwrap();
+
+    let temp_dir = TempDir::new().unwrap();
+    let restored_path = temp_dir.path().join("cache");
+    let restored_l1_batch = restore_state_keeper_cache(&*object_store, &pool, &restored_path).await;
+    assert_eq!(restored_l1_batch, None);
+    assert!(!restored_path.exists());
+}
+
+#[tokio::test]
+async fn second_backup_reuses_sst_files() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().unwrap();
+    let cache = prepare_cache(&pool, &temp_dir.path().join("cache")).await;
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let (checkpointer, uploader) =
+        state_keeper_cache_checkpointer(temp_dir.path().join("checkpoints"), object_store.clone());
+
+    let (cache, checkpointer) = tokio::task::spawn_blocking(move || {
+        checkpoint_cache(&checkpointer, &cache, L1BatchNumber(0));
+        (cache, checkpointer)
+    })
+    .await
+    .unwrap();
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let uploader_task = tokio::spawn(uploader.run(stop_receiver));
+    let first_manifest =
+        wait_for_manifest(&*object_store, RocksdbBackupKind::StateKeeperCache).await;
+
+    // Wait until the uploader is ready to accept the next checkpoint.
+    while checkpointer.checkpoints_sender.capacity() == 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    tokio::task::spawn_blocking(move || {
+        checkpoint_cache(&checkpointer, &cache, L1BatchNumber(1));
+    })
+    .await
+    .unwrap();
+    let second_manifest = loop {
+        let manifest = wait_for_manifest(&*object_store, RocksdbBackupKind::StateKeeperCache).await;
+        if manifest.l1_batch_number == L1BatchNumber(1) {
+            break manifest;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    };
+    stop_sender.send_replace(true);
+    uploader_task.await.unwrap().unwrap();
+
+    let first_sst_keys: HashSet<_> = first_manifest
+        .files
+        .iter()
+        .filter(|file| file.name.ends_with(".sst"))
+        .map(|file| &file.key)
+        .collect();
+    let second_sst_keys: HashSet<_> = second_manifest
+        .files
+        .iter()
+        .filter(|file| file.name.ends_with(".sst"))
+        .map(|file| &file.key)
+        .collect();
+    assert_eq!(first_sst_keys, second_sst_keys);
+
+    // Files of the first backup not referenced by the second one should be removed.
+    let second_keys: HashSet<_> = second_manifest.files.iter().map(|file| &file.key).collect();
+    for file in &first_manifest.files {
+        let result = object_store
+            .get_raw(Bucket::RocksdbBackups, &format!("{}_part0", file.key))
+            .await;
+        if second_keys.contains(&file.key) {
+            result.unwrap();
+        } else {
+            assert!(matches!(result, Err(ObjectStoreError::KeyNotFound(_))));
+        }
+    }
+}
+
+#[tokio::test]
+async fn backups_of_different_nodes_do_not_interfere() {
+    let pool = ConnectionPool::test_pool().await;
+    let temp_dir = TempDir::new().unwrap();
+    let cache = prepare_cache(&pool, &temp_dir.path().join("cache")).await;
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let (checkpointer, uploader) =
+        state_keeper_cache_checkpointer(temp_dir.path().join("checkpoints"), object_store.clone());
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let uploader_task = tokio::spawn(uploader.run(stop_receiver));
+    tokio::task::spawn_blocking(move || {
+        checkpoint_cache(&checkpointer, &cache, L1BatchNumber(0));
+    })
+    .await
+    .unwrap();
+    wait_for_manifest(&*object_store, RocksdbBackupKind::StateKeeperCache).await;
+    stop_sender.send_replace(true);
+    uploader_task.await.unwrap().unwrap();
+
+    let other_path = temp_dir.path().join("other_cache");
+    let restored_l1_batch = restore_rocksdb_backup(
+        &*object_store,
+        &pool,
+        RocksdbBackupKind::StateKeeperCache,
+        "other_node",
+        &other_path,
+    )
+    .await
+    .unwrap();
+    assert_eq!(restored_l1_batch, None);
+    assert!(!other_path.exists());
+}
+
+#[tokio::test]
+async fn backing_up_and_restoring_merkle_tree() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let temp_dir = TempDir::new().unwrap();
+    let tree_path = temp_dir.path().join("tree");
+    let db = RocksDBWrapper::new(&tree_path).unwrap();
+    let mut tree = ZkSyncTree::new_lightweight(db);
+    let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+    tree.process_l1_batch(&[TreeInstruction::write(key, 1, H256::repeat_byte(2))]);
+    tree.save();
+    let reader = tree.reader();
+    let root_hash = reader.root_hash();
+
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let (checkpointer, uploader) = RocksdbCheckpointer::new(
+        RocksdbBackupKind::MerkleTree,
+        PREFIX.to_owned(),
+        temp_dir.path().join("tree_checkpoints"),
+        Duration::ZERO,
+        object_store.clone(),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let uploader_task = tokio::spawn(uploader.run(stop_receiver));
+    tokio::task::spawn_blocking(move || {
+        checkpointer.maybe_create_checkpoint(L1BatchNumber(0), |path| {
+            reader.create_checkpoint(path).map_err(Into::into)
+        });
+    })
+    .await
+    .unwrap();
+    let manifest = wait_for_manifest(&*object_store, RocksdbBackupKind::MerkleTree).await;
+    assert_eq!(manifest.l1_batch_number, L1BatchNumber(0));
+    stop_sender.send_replace(true);
+    uploader_task.await.unwrap().unwrap();
+
+    let restored_path = temp_dir.path().join("restored_tree");
+    let restored_l1_batch = restore_rocksdb_backup(
+        &*object_store,
+        &pool,
+        RocksdbBackupKind::MerkleTree,
+        PREFIX,
+        &restored_path,
+    )
+    .await
+    .unwrap();
+    assert_eq!(restored_l1_batch, Some(L1BatchNumber(0)));
+    let restored_db = RocksDBWrapper::new(&restored_path).unwrap();
+    let restored_tree = ZkSyncTree::new_lightweight(restored_db);
+    let restored_reader = restored_tree.reader();
+    assert_eq!(restored_reader.root_hash(), root_hash);
+    assert_eq!(restored_reader.next_l1_batch_number(), L1BatchNumber(1));
+}
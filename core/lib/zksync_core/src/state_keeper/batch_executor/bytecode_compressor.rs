@@ -0,0 +1,188 @@
+//! Bytecode compression offloaded from the VM thread.
+
+use std::{
+    collections::{HashMap, HashSet},
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+use tokio::{sync::Semaphore, task::JoinHandle};
+use zksync_types::{Transaction, H256};
+use zksync_utils::bytecode::{compress_bytecode, hash_bytecode};
+
+use crate::state_keeper::metrics::{BytecodeCompressionCacheResult, EXECUTOR_METRICS};
+
+#[derive(Debug)]
+struct CompressorState {
+    /// `None` values correspond to bytecodes that cannot be compressed (e.g., because of the dictionary overflow).
+    cache: LruCache<H256, Option<Vec<u8>>>,
+    /// Hashes of bytecodes being compressed at the moment.
+    pending: HashSet<H256>,
+}
+
+/// Compresses bytecodes published by transactions on a pool of blocking workers, caching compression
+/// results by bytecode hash. Compression is scheduled when transactions are loaded into the mempool
+/// (see [`MempoolFetcher`](crate::state_keeper::MempoolFetcher)), i.e., well before the transactions
+/// are executed. The cache is shared among all L1 batches processed by
+/// [`MainBatchExecutor`](super::MainBatchExecutor), so that repeated deployments of the same bytecode
+/// don't recompress it. Bytecodes not compressed by the time of execution are compressed by the VM.
+#[derive(Debug, Clone)]
+pub struct BytecodeCompressor {
+    state: Arc<Mutex<CompressorState>>,
+    workers: Arc<Semaphore>,
+}
+
+impl Default for BytecodeCompressor {
+    fn default() -> Self {
+        Self::new(
+            NonZeroUsize::new(Self::DEFAULT_CACHE_CAPACITY).unwrap(),
+            Self::DEFAULT_WORKER_COUNT,
+        )
+    }
+}
+
+impl BytecodeCompressor {
+    const DEFAULT_CACHE_CAPACITY: usize = 512;
+    const DEFAULT_WORKER_COUNT: usize = 4;
+
+    pub fn new(cache_capacity: NonZeroUsize, worker_count: usize) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(CompressorState {
+                cache: LruCache::new(cache_capacity),
+                pending: HashSet::new(),
+            })),
+            workers: Arc::new(Semaphore::new(worker_count.max(1))),
+        }
+    }
+
+    fn bytecodes_to_compress(tx: &Transaction) -> &[Vec<u8>] {
+        // L1 transactions do not need compression
+        if tx.is_l1() {
+            return &[];
+        }
+        tx.execute.factory_deps.as_deref().unwrap_or_default()
+    }
+
+    /// Schedules compression of the bytecodes of the provided transaction that are neither cached
+    /// nor being compressed. Doesn't wait for compression to complete.
+    pub fn schedule(&self, tx: &Transaction) {
+        self.spawn_compression(tx);
+    }
+
+    fn spawn_compression(&self, tx: &Transaction) -> Vec<JoinHandle<()>> {
+        let bytecodes = Self::bytecodes_to_compress(tx);
+        if bytecodes.is_empty() {
+            return vec![];
+        }
+
+        let mut state = self
+            .state
+            .lock()
+            .expect("bytecode compression cache is poisoned");
+        let mut tasks = vec![];
+        for bytecode in bytecodes {
+            let hash = hash_bytecode(bytecode);
+            if state.cache.contains(&hash) || !state.pending.insert(hash) {
+                continue;
+            }
+
+            let bytecode = bytecode.clone();
+            let state = self.state.clone();
+            let workers = self.workers.clone();
+            tasks.push(tokio::spawn(async move {
+                let _permit = workers
+                    .acquire_owned()
+                    .await
+                    .expect("bytecode compression workers are closed");
+                let compressed = tokio::task::spawn_blocking(move || {
+                    let latency = EXECUTOR_METRICS.bytecode_compression_latency.start();
+                    let compressed = compress_bytecode(&bytecode).ok();
+                    latency.observe();
+                    compressed
+                })
+                .await
+                .expect("bytecode compression panicked");
+
+                let mut state = state
+                    .lock()
+                    .expect("bytecode compression cache is poisoned");
+                state.pending.remove(&hash);
+                state.cache.put(hash, compressed);
+            }));
+        }
+        tasks
+    }
+
+    /// Returns cached compressed bytecodes for the provided transaction, keyed by bytecode hash.
+    pub(super) fn cached_bytecodes(&self, tx: &Transaction) -> HashMap<H256, Vec<u8>> {
+        let bytecodes = Self::bytecodes_to_compress(tx);
+        if bytecodes.is_empty() {
+            return HashMap::new();
+        }
+
+        let mut state = self
+            .state
+            .lock()
+            .expect("bytecode compression cache is poisoned");
+        bytecodes
+            .iter()
+            .filter_map(|bytecode| {
+                let hash = hash_bytecode(bytecode);
+                let compressed = state.cache.get(&hash);
+                let cache_result = if compressed.is_some() {
+                    BytecodeCompressionCacheResult::Hit
+                } else {
+                    BytecodeCompressionCacheResult::Miss
+                };
+                EXECUTOR_METRICS.bytecode_compression_cache[&cache_result].inc();
+                Some((hash, compressed?.clone()?))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testonly::create_l2_transaction;
+
+    #[tokio::test]
+    async fn compressing_bytecodes() {
+        let bytecodes = [vec![1; 32], vec![2; 96]];
+        let mut tx = create_l2_transaction(10, 100);
+        tx.execute.factory_deps = Some(bytecodes.to_vec());
+        let tx = Transaction::from(tx);
+
+        let compressor = BytecodeCompressor::new(NonZeroUsize::new(16).unwrap(), 1);
+        assert!(compressor.cached_bytecodes(&tx).is_empty());
+        let tasks = compressor.spawn_compression(&tx);
+        assert_eq!(tasks.len(), 2);
+        // Bytecodes being compressed must not be scheduled again.
+        assert!(compressor.spawn_compression(&tx).is_empty());
+        futures::future::try_join_all(tasks).await.unwrap();
+
+        let expected_bytecodes: HashMap<_, _> = bytecodes
+            .iter()
+            .map(|bytecode| {
+                (
+                    hash_bytecode(bytecode),
+                    compress_bytecode(bytecode).unwrap(),
+                )
+            })
+            .collect();
+        assert_eq!(compressor.cached_bytecodes(&tx), expected_bytecodes);
+        assert!(compressor.state.lock().unwrap().pending.is_empty());
+
+        // Compression results must be reused for other transactions.
+        let mut other_tx = create_l2_transaction(10, 100);
+        other_tx.execute.factory_deps = Some(vec![bytecodes[0].clone()]);
+        let other_tx = Transaction::from(other_tx);
+        assert!(compressor.spawn_compression(&other_tx).is_empty());
+        assert_eq!(compressor.state.lock().unwrap().cache.len(), 2);
+        let cached_bytecodes = compressor.cached_bytecodes(&other_tx);
+        assert_eq!(cached_bytecodes.len(), 1);
+        let hash = hash_bytecode(&bytecodes[0]);
+        assert_eq!(cached_bytecodes[&hash], expected_bytecodes[&hash]);
+    }
+}
@@ -1,25 +1,33 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
+use anyhow::Context as _;
 use async_trait::async_trait;
 use multivm::{
     interface::{
         ExecutionResult, FinishedL1Batch, Halt, L1BatchEnv, L2BlockEnv, SystemEnv, VmExecutionMode,
         VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled,
     },
-    tracers::CallTracer,
+    tracers::{CallTracer, ExecutionTimeout},
     vm_latest::HistoryEnabled,
-    MultiVMTracer, VmInstance,
+    MultiVMTracer, MultiVmTracerPointer, VmInstance,
 };
 use once_cell::sync::OnceCell;
 use tokio::sync::{mpsc, watch};
-use zksync_dal::ConnectionPool;
+use zksync_dal::{ConnectionPool, StorageProcessor};
 use zksync_state::{RocksdbStorage, StorageView, WriteStorage};
-use zksync_types::{vm_trace::Call, Transaction, U256};
+use zksync_types::{vm_trace::Call, vm_version::VmVersion, L1BatchNumber, Transaction, U256};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
-use super::{BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult};
+use super::{
+    shadow::ShadowVm, BatchExecutor, BatchExecutorHandle, BytecodeCompressor, Command,
+    ExecutorSnapshot, TxExecutionResult, TxOrigin,
+};
 use crate::{
     metrics::{InteractionType, TxStage, APP_METRICS},
+    rocksdb_backup::RocksdbCheckpointer,
     state_keeper::{
         metrics::{TxExecutionStage, BATCH_TIP_METRICS, EXECUTOR_METRICS, KEEPER_METRICS},
         types::ExecutionMetricsForCriteria,
@@ -37,6 +45,10 @@ pub struct MainBatchExecutor {
     upload_witness_inputs_to_gcs: bool,
     enum_index_migration_chunk_size: usize,
     optional_bytecode_compression: bool,
+    bytecode_compressor: BytecodeCompressor,
+    cache_checkpointer: Option<RocksdbCheckpointer>,
+    tx_execution_timeout: Option<Duration>,
+    upgrade_shadow_execution_batches: Option<u32>,
 }
 
 impl MainBatchExecutor {
@@ -57,7 +69,98 @@ impl MainBatchExecutor {
             upload_witness_inputs_to_gcs,
             enum_index_migration_chunk_size,
             optional_bytecode_compression,
+            bytecode_compressor: BytecodeCompressor::default(),
+            cache_checkpointer: None,
+            tx_execution_timeout: None,
+            upgrade_shadow_execution_batches: None,
+        }
+    }
+
+    /// Sets the compressor whose cache is used for bytecodes published by executed transactions. The compressor
+    /// should be shared with the [`MempoolFetcher`](crate::state_keeper::MempoolFetcher), which schedules
+    /// compression for new transactions; otherwise, the VM compresses bytecodes on its own.
+    pub fn set_bytecode_compressor(&mut self, compressor: BytecodeCompressor) {
+        self.bytecode_compressor = compressor;
+    }
+
+    /// Enables periodic checkpoints of the state keeper cache, which are created before executing an L1 batch.
+    pub fn set_cache_checkpointer(&mut self, checkpointer: RocksdbCheckpointer) {
+        self.cache_checkpointer = Some(checkpointer);
+    }
+
+    /// Sets the wall-clock budget for executing a single new transaction. Transactions exceeding the budget
+    /// are rejected by the VM.
+    ///
+    /// Since the wall-clock time is not deterministic, the budget must only be set on the main node, which decides
+    /// which transactions are included into blocks. External nodes must execute all transactions included by the main node.
+    pub fn set_tx_execution_timeout(&mut self, timeout: Duration) {
+        self.tx_execution_timeout = Some(timeout);
+    }
+
+    /// Enables shadow execution of L1 batches around protocol upgrades. Each such batch is additionally executed
+    /// with the VM of the adjacent protocol version, and divergences between the VMs are logged. The first `batches`
+    /// L1 batches after an upgrade are shadowed with the VM of the previous protocol version; batches before an upgrade
+    /// (i.e., while a newer protocol version is known to the node) are shadowed with the VM of the upcoming version.
+    ///
+    /// Shadow execution doesn't influence the main execution, but it roughly doubles the batch execution time.
+    pub fn set_upgrade_shadow_execution(&mut self, batches: u32) {
+        self.upgrade_shadow_execution_batches = Some(batches);
+    }
+
+    /// Returns the system environment to shadow-execute the specified batch with, or `None` if the batch
+    /// shouldn't be shadowed.
+    ///
+    /// The shadow protocol version is the version immediately preceding the batch version (if the upgrade
+    /// to the batch version happened within the last `batches_after_upgrade` batches), or otherwise the version
+    /// immediately following it. Batches are only shadowed if the shadow version has a different VM version.
+    /// The shadow environment uses base system contracts (in particular, the bootloader) of the shadow version.
+    async fn shadow_system_env(
+        storage: &mut StorageProcessor<'_>,
+        l1_batch_number: L1BatchNumber,
+        system_env: &SystemEnv,
+        batches_after_upgrade: u32,
+    ) -> anyhow::Result<Option<SystemEnv>> {
+        let protocol_version = system_env.version;
+        let earlier_l1_batch =
+            L1BatchNumber(l1_batch_number.0.saturating_sub(batches_after_upgrade));
+        let earlier_version = storage
+            .blocks_dal()
+            .get_batch_protocol_version_id(earlier_l1_batch)
+            .await?;
+
+        let shadow_protocol_version =
+            if earlier_version.map_or(false, |version| version < protocol_version) {
+                storage
+                    .protocol_versions_dal()
+                    .load_previous_version(protocol_version)
+                    .await
+                    .map(|version| version.id)
+            } else {
+                let all_versions = storage.protocol_versions_dal().all_version_ids().await;
+                all_versions
+                    .into_iter()
+                    .filter(|&version| version > protocol_version)
+                    .min()
+            };
+        let Some(shadow_protocol_version) = shadow_protocol_version else {
+            return Ok(None);
+        };
+        if VmVersion::from(shadow_protocol_version) == VmVersion::from(protocol_version) {
+            return Ok(None);
         }
+
+        let base_system_contracts = storage
+            .protocol_versions_dal()
+            .load_base_system_contracts_by_version_id(shadow_protocol_version as u16)
+            .await?
+            .with_context(|| {
+                format!("base system contracts for protocol version {shadow_protocol_version:?} are missing")
+            })?;
+        Ok(Some(SystemEnv {
+            version: shadow_protocol_version,
+            base_system_smart_contracts: base_system_contracts,
+            ..system_env.clone()
+        }))
     }
 }
 
@@ -83,6 +186,20 @@ impl BatchExecutor for MainBatchExecutor {
             .await
             .expect("Failed synchronizing secondary state keeper storage")?;
 
+        let shadow_system_env = if let Some(batches) = self.upgrade_shadow_execution_batches {
+            Self::shadow_system_env(&mut conn, l1_batch_params.number, &system_env, batches)
+                .await
+                .unwrap_or_else(|err| {
+                    tracing::warn!(
+                    "Failed determining shadow VM environment, skipping shadow execution: {err:#}"
+                );
+                    None
+                })
+        } else {
+            None
+        };
+        drop(conn);
+
         // Since we process `BatchExecutor` commands one-by-one (the next command is never enqueued
         // until a previous command is processed), capacity 1 is enough for the commands channel.
         let (commands_sender, commands_receiver) = mpsc::channel(1);
@@ -90,11 +207,25 @@ impl BatchExecutor for MainBatchExecutor {
             save_call_traces: self.save_call_traces,
             max_allowed_tx_gas_limit: self.max_allowed_tx_gas_limit,
             optional_bytecode_compression: self.optional_bytecode_compression,
+            bytecode_compressor: self.bytecode_compressor.clone(),
+            tx_execution_timeout: self.tx_execution_timeout,
+            shadow_system_env,
             commands: commands_receiver,
         };
         let upload_witness_inputs_to_gcs = self.upload_witness_inputs_to_gcs;
+        let cache_checkpointer = self.cache_checkpointer.clone();
 
         let handle = tokio::task::spawn_blocking(move || {
+            // The cache is synchronized with Postgres, i.e., reflects the state after the previous L1 batch.
+            let last_processed_l1_batch = l1_batch_params.number.0.checked_sub(1);
+            if let (Some(checkpointer), Some(last_processed_l1_batch)) =
+                (&cache_checkpointer, last_processed_l1_batch)
+            {
+                checkpointer
+                    .maybe_create_checkpoint(L1BatchNumber(last_processed_l1_batch), |path| {
+                        secondary_storage.create_checkpoint(path)
+                    });
+            }
             executor.run(
                 secondary_storage,
                 l1_batch_params,
@@ -120,6 +251,9 @@ struct CommandReceiver {
     save_call_traces: bool,
     max_allowed_tx_gas_limit: U256,
     optional_bytecode_compression: bool,
+    bytecode_compressor: BytecodeCompressor,
+    tx_execution_timeout: Option<Duration>,
+    shadow_system_env: Option<SystemEnv>,
     commands: mpsc::Receiver<Command>,
 }
 
@@ -133,26 +267,74 @@ impl CommandReceiver {
     ) {
         tracing::info!("Starting executing batch #{:?}", &l1_batch_params.number);
 
-        let storage_view = StorageView::new(secondary_storage).to_rc_ptr();
+        // The storage is shared with the shadow VM (if any); each VM has its own storage view with uncommitted changes.
+        let mut shadow_vm = self.shadow_system_env.take().map(|shadow_system_env| {
+            let storage_view = StorageView::new(&secondary_storage).to_rc_ptr();
+            ShadowVm::new(
+                l1_batch_params.clone(),
+                shadow_system_env,
+                storage_view,
+                system_env.version,
+            )
+        });
+        let storage_view = StorageView::new(&secondary_storage).to_rc_ptr();
 
         let mut vm = VmInstance::new(l1_batch_params, system_env, storage_view.clone());
+        // Number of transactions executed in the batch, which is equal to the number of per-transaction VM snapshots.
+        let mut tx_count = 0_usize;
+        // Value of `tx_count` when the current miniblock was started.
+        let mut miniblock_start_tx_count = 0_usize;
 
         while let Some(cmd) = self.commands.blocking_recv() {
             match cmd {
-                Command::ExecuteTx(tx, resp) => {
-                    let result = self.execute_tx(&tx, &mut vm);
+                Command::ExecuteTx(tx, origin, resp) => {
+                    let result = self.execute_tx(&tx, origin, &mut vm, shadow_vm.as_mut());
+                    tx_count += 1;
                     resp.send(result).unwrap();
                 }
                 Command::RollbackLastTx(resp) => {
                     self.rollback_last_tx(&mut vm);
+                    if let Some(shadow_vm) = &mut shadow_vm {
+                        shadow_vm.rollback_last_tx();
+                    }
+                    tx_count = tx_count.saturating_sub(1);
+                    resp.send(()).unwrap();
+                }
+                Command::MakeSnapshot(resp) => {
+                    resp.send(ExecutorSnapshot { tx_count }).unwrap();
+                }
+                Command::RollbackToSnapshot(snapshot, resp) => {
+                    assert!(
+                        snapshot.tx_count <= tx_count,
+                        "Snapshot {snapshot:?} is invalidated; {tx_count} transactions are executed in the batch"
+                    );
+                    assert!(
+                        snapshot.tx_count >= miniblock_start_tx_count,
+                        "Snapshot {snapshot:?} was created in a previous miniblock, which cannot be rolled back"
+                    );
+                    let rolled_back_count = tx_count - snapshot.tx_count;
+                    if rolled_back_count > 0 {
+                        self.rollback_txs(&mut vm, rolled_back_count);
+                        if let Some(shadow_vm) = &mut shadow_vm {
+                            shadow_vm.rollback_txs(rolled_back_count);
+                        }
+                    }
+                    tx_count = snapshot.tx_count;
                     resp.send(()).unwrap();
                 }
                 Command::StartNextMiniblock(l2_block_env, resp) => {
+                    if let Some(shadow_vm) = &mut shadow_vm {
+                        shadow_vm.start_next_miniblock(l2_block_env.clone());
+                    }
                     self.start_next_miniblock(l2_block_env, &mut vm);
+                    miniblock_start_tx_count = tx_count;
                     resp.send(()).unwrap();
                 }
                 Command::FinishBatch(resp) => {
                     let vm_block_result = self.finish_batch(&mut vm);
+                    if let Some(shadow_vm) = shadow_vm.take() {
+                        shadow_vm.finish_batch(&vm_block_result);
+                    }
                     let witness_block_state = if upload_witness_inputs_to_gcs {
                         Some(storage_view.borrow_mut().witness_block_state())
                     } else {
@@ -178,10 +360,15 @@ impl CommandReceiver {
     fn execute_tx<S: WriteStorage>(
         &self,
         tx: &Transaction,
+        origin: TxOrigin,
         vm: &mut VmInstance<S, HistoryEnabled>,
+        mut shadow_vm: Option<&mut ShadowVm<S>>,
     ) -> TxExecutionResult {
         // Save pre-`execute_next_tx` VM snapshot.
         vm.make_snapshot();
+        if let Some(shadow_vm) = &mut shadow_vm {
+            shadow_vm.make_snapshot();
+        }
 
         // Reject transactions with too big gas limit.
         // They are also rejected on the API level, but
@@ -197,18 +384,48 @@ impl CommandReceiver {
             };
         }
 
+        // The execution budget isn't applied to re-executed transactions: they were executed successfully before,
+        // and are expected to be executed in exactly the same way.
+        let timeout = match origin {
+            TxOrigin::New => self
+                .tx_execution_timeout
+                .map(|timeout| ExecutionTimeout::new(Instant::now() + timeout)),
+            TxOrigin::Reexecuted => None,
+        };
+
         // Execute the transaction.
         let latency = KEEPER_METRICS.tx_execution_time[&TxExecutionStage::Execution].start();
         let (tx_result, compressed_bytecodes, call_tracer_result) =
             if self.optional_bytecode_compression {
-                self.execute_tx_in_vm_with_optional_compression(tx, vm)
+                self.execute_tx_in_vm_with_optional_compression(tx, timeout.as_ref(), vm)
             } else {
-                self.execute_tx_in_vm(tx, vm)
+                self.execute_tx_in_vm(tx, timeout.as_ref(), vm)
             };
         latency.observe();
         APP_METRICS.processed_txs[&TxStage::StateKeeper].inc();
         APP_METRICS.processed_l1_txs[&TxStage::StateKeeper].inc_by(tx.is_l1().into());
 
+        // The VM may report a different halt reason if it was stopped by the timeout tracer (e.g., a failure
+        // to publish bytecodes), so we check the tracer directly. Transactions that have finished executing
+        // after the deadline without being stopped are not rejected.
+        if timeout
+            .as_ref()
+            .map_or(false, ExecutionTimeout::is_triggered)
+        {
+            tracing::warn!(
+                "Transaction {:?} exceeded execution budget of {:?}; rejecting",
+                tx.hash(),
+                self.tx_execution_timeout
+            );
+            EXECUTOR_METRICS.tx_execution_timeouts.inc();
+            return TxExecutionResult::RejectedByVm {
+                reason: ExecutionTimeout::halt_reason(),
+            };
+        }
+        if let Some(shadow_vm) = shadow_vm {
+            shadow_vm.execute_tx(tx, &tx_result, self.optional_bytecode_compression);
+        }
+
         if let ExecutionResult::Halt { reason } = tx_result.result {
             return match reason {
                 Halt::BootloaderOutOfGas => TxExecutionResult::BootloaderOutOfGasForTx,
@@ -250,6 +467,21 @@ impl CommandReceiver {
         latency.observe();
     }
 
+    /// Rolls back the specified number of the latest transactions. Intermediate per-transaction snapshots
+    /// are discarded, after which the VM is rolled back to the snapshot preceding the earliest of the transactions.
+    fn rollback_txs<S: WriteStorage>(
+        &self,
+        vm: &mut VmInstance<S, HistoryEnabled>,
+        tx_count: usize,
+    ) {
+        let latency = KEEPER_METRICS.tx_execution_time[&TxExecutionStage::TxRollback].start();
+        for _ in 1..tx_count {
+            vm.pop_snapshot_no_rollback();
+        }
+        vm.rollback_to_the_latest_snapshot();
+        latency.observe();
+    }
+
     fn start_next_miniblock<S: WriteStorage>(
         &self,
         l2_block_env: L2BlockEnv,
@@ -279,6 +511,7 @@ impl CommandReceiver {
     fn execute_tx_in_vm_with_optional_compression<S: WriteStorage>(
         &self,
         tx: &Transaction,
+        timeout: Option<&ExecutionTimeout>,
         vm: &mut VmInstance<S, HistoryEnabled>,
     ) -> (
         VmExecutionResultAndLogs,
@@ -298,15 +531,14 @@ impl CommandReceiver {
         vm.make_snapshot();
 
         let call_tracer_result = Arc::new(OnceCell::default());
-        let tracer = if self.save_call_traces {
-            vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
-        } else {
-            vec![]
-        };
-
-        if let (Ok(()), result) =
-            vm.inspect_transaction_with_bytecode_compression(tracer.into(), tx.clone(), true)
-        {
+        let tracer = self.tracers(&call_tracer_result, timeout);
+
+        let precompressed_bytecodes = self.bytecode_compressor.cached_bytecodes(tx);
+        if let (Ok(()), result) = vm.inspect_transaction_with_precompressed_bytecodes(
+            tracer.into(),
+            tx.clone(),
+            &precompressed_bytecodes,
+        ) {
             let compressed_bytecodes = vm.get_last_tx_compressed_bytecodes();
             vm.pop_snapshot_no_rollback();
 
@@ -319,11 +551,7 @@ impl CommandReceiver {
         vm.rollback_to_the_latest_snapshot();
 
         let call_tracer_result = Arc::new(OnceCell::default());
-        let tracer = if self.save_call_traces {
-            vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
-        } else {
-            vec![]
-        };
+        let tracer = self.tracers(&call_tracer_result, timeout);
 
         let result =
             vm.inspect_transaction_with_bytecode_compression(tracer.into(), tx.clone(), false);
@@ -348,6 +576,7 @@ impl CommandReceiver {
     fn execute_tx_in_vm<S: WriteStorage>(
         &self,
         tx: &Transaction,
+        timeout: Option<&ExecutionTimeout>,
         vm: &mut VmInstance<S, HistoryEnabled>,
     ) -> (
         VmExecutionResultAndLogs,
@@ -355,14 +584,15 @@ impl CommandReceiver {
         Vec<Call>,
     ) {
         let call_tracer_result = Arc::new(OnceCell::default());
-        let tracer = if self.save_call_traces {
-            vec![CallTracer::new(call_tracer_result.clone()).into_tracer_pointer()]
-        } else {
-            vec![]
-        };
-
-        let (published_bytecodes, mut result) =
-            vm.inspect_transaction_with_bytecode_compression(tracer.into(), tx.clone(), true);
+        let tracer = self.tracers(&call_tracer_result, timeout);
+
+        let precompressed_bytecodes = self.bytecode_compressor.cached_bytecodes(tx);
+        let (published_bytecodes, mut result) = vm
+            .inspect_transaction_with_precompressed_bytecodes(
+                tracer.into(),
+                tx.clone(),
+                &precompressed_bytecodes,
+            );
         if published_bytecodes.is_ok() {
             let compressed_bytecodes = vm.get_last_tx_compressed_bytecodes();
 
@@ -380,6 +610,21 @@ impl CommandReceiver {
         }
     }
 
+    fn tracers<S: WriteStorage>(
+        &self,
+        call_tracer_result: &Arc<OnceCell<Vec<Call>>>,
+        timeout: Option<&ExecutionTimeout>,
+    ) -> Vec<MultiVmTracerPointer<S, HistoryEnabled>> {
+        let mut tracers = vec![];
+        if self.save_call_traces {
+            tracers.push(CallTracer::new(call_tracer_result.clone()).into_tracer_pointer());
+        }
+        if let Some(timeout) = timeout {
+            tracers.push(timeout.clone().into_tracer_pointer());
+        }
+        tracers
+    }
+
     fn dryrun_block_tip<S: WriteStorage>(
         &self,
         vm: &mut VmInstance<S, HistoryEnabled>,
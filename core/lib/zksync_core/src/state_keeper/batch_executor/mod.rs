@@ -11,11 +11,14 @@ use tokio::{
 use zksync_types::{vm_trace::Call, witness_block_state::WitnessBlockState, Transaction};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
+pub use self::bytecode_compressor::BytecodeCompressor;
 use crate::state_keeper::{
     metrics::{ExecutorCommand, EXECUTOR_METRICS},
     types::ExecutionMetricsForCriteria,
 };
 
+mod bytecode_compressor;
+mod shadow;
 #[cfg(test)]
 mod tests;
 
@@ -57,6 +60,16 @@ impl TxExecutionResult {
     }
 }
 
+/// Snapshot of the batch executor state (i.e., the VM together with its oracles) at a transaction boundary,
+/// created with [`BatchExecutorHandle::make_snapshot()`].
+///
+/// The VM already keeps a snapshot before each executed transaction, so a snapshot only records the number
+/// of transactions executed in the batch; creating it is cheap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ExecutorSnapshot {
+    tx_count: usize,
+}
+
 /// An abstraction that allows us to create different kinds of batch executors.
 /// The only requirement is to return a [`BatchExecutorHandle`], which does its work
 /// by communicating with the externally initialized thread.
@@ -87,12 +100,22 @@ impl BatchExecutorHandle {
         Self { handle, commands }
     }
 
+    /// Executes a new transaction, i.e. one that isn't included into a miniblock yet.
     pub(super) async fn execute_tx(&self, tx: Transaction) -> TxExecutionResult {
+        self.execute_tx_inner(tx, TxOrigin::New).await
+    }
+
+    /// Re-executes a transaction already included into a sealed miniblock (e.g., when restoring a pending L1 batch).
+    pub(super) async fn reexecute_tx(&self, tx: Transaction) -> TxExecutionResult {
+        self.execute_tx_inner(tx, TxOrigin::Reexecuted).await
+    }
+
+    async fn execute_tx_inner(&self, tx: Transaction, origin: TxOrigin) -> TxExecutionResult {
         let tx_gas_limit = tx.gas_limit().as_u32();
 
         let (response_sender, response_receiver) = oneshot::channel();
         self.commands
-            .send(Command::ExecuteTx(Box::new(tx), response_sender))
+            .send(Command::ExecuteTx(Box::new(tx), origin, response_sender))
             .await
             .unwrap();
 
@@ -149,6 +172,45 @@ impl BatchExecutorHandle {
         latency.observe();
     }
 
+    /// Creates a snapshot of the executor state after the last executed transaction. The snapshot can be used
+    /// to speculatively execute one or more transactions (e.g., to inspect their execution metrics) and then
+    /// cheaply roll them back with [`Self::rollback_to_snapshot()`].
+    pub async fn make_snapshot(&self) -> ExecutorSnapshot {
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.commands
+            .send(Command::MakeSnapshot(response_sender))
+            .await
+            .unwrap();
+        let latency = EXECUTOR_METRICS.batch_executor_command_response_time
+            [&ExecutorCommand::MakeSnapshot]
+            .start();
+        let snapshot = response_receiver.await.unwrap();
+        latency.observe();
+        snapshot
+    }
+
+    /// Rolls back all transactions executed after the snapshot was created. Snapshots created after `snapshot`
+    /// are invalidated; `snapshot` itself remains valid and can be rolled back to again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the snapshot is invalidated, or if it was created in a previous miniblock. (Miniblock
+    /// boundaries cannot be rolled back.)
+    pub async fn rollback_to_snapshot(&self, snapshot: ExecutorSnapshot) {
+        // While we don't get anything from the channel, it's useful to have it as a confirmation that the operation
+        // indeed has been processed.
+        let (response_sender, response_receiver) = oneshot::channel();
+        self.commands
+            .send(Command::RollbackToSnapshot(snapshot, response_sender))
+            .await
+            .unwrap();
+        let latency = EXECUTOR_METRICS.batch_executor_command_response_time
+            [&ExecutorCommand::RollbackToSnapshot]
+            .start();
+        response_receiver.await.unwrap();
+        latency.observe();
+    }
+
     pub(super) async fn finish_batch(self) -> (FinishedL1Batch, Option<WitnessBlockState>) {
         let (response_sender, response_receiver) = oneshot::channel();
         self.commands
@@ -165,10 +227,26 @@ impl BatchExecutorHandle {
     }
 }
 
+/// Origin of a transaction sent to the batch executor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum TxOrigin {
+    /// The transaction is new; it may be rejected by the state keeper.
+    New,
+    /// The transaction was executed and included into a miniblock before. It must be executed
+    /// in the same way as originally, so non-deterministic execution limits are not applied to it.
+    Reexecuted,
+}
+
 #[derive(Debug)]
 pub(super) enum Command {
-    ExecuteTx(Box<Transaction>, oneshot::Sender<TxExecutionResult>),
+    ExecuteTx(
+        Box<Transaction>,
+        TxOrigin,
+        oneshot::Sender<TxExecutionResult>,
+    ),
     StartNextMiniblock(L2BlockEnv, oneshot::Sender<()>),
     RollbackLastTx(oneshot::Sender<()>),
+    MakeSnapshot(oneshot::Sender<ExecutorSnapshot>),
+    RollbackToSnapshot(ExecutorSnapshot, oneshot::Sender<()>),
     FinishBatch(oneshot::Sender<(FinishedL1Batch, Option<WitnessBlockState>)>),
 }
@@ -0,0 +1,291 @@
+//! Shadow execution of L1 batches with an alternative VM version around protocol upgrades.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+use multivm::{
+    interface::{
+        ExecutionResult, FinishedL1Batch, Halt, L1BatchEnv, L2BlockEnv, SystemEnv,
+        VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled,
+    },
+    vm_latest::HistoryEnabled,
+    VmInstance,
+};
+use zksync_state::{StoragePtr, WriteStorage};
+use zksync_types::{
+    storage_writes_deduplicator::StorageWritesDeduplicator, vm_version::VmVersion, L1BatchNumber,
+    ProtocolVersionId, StorageKey, Transaction, H256,
+};
+use zksync_utils::u256_to_h256;
+
+use crate::state_keeper::metrics::{ShadowVmDivergence, EXECUTOR_METRICS};
+
+fn storage_writes(result: &VmExecutionResultAndLogs) -> HashMap<StorageKey, H256> {
+    let mut deduplicator = StorageWritesDeduplicator::new();
+    deduplicator.apply(&result.logs.storage_logs);
+    let modified_slots = deduplicator.into_modified_key_values();
+    modified_slots
+        .into_iter()
+        .map(|(key, slot)| (key, u256_to_h256(slot.value)))
+        .collect()
+}
+
+/// Returns kinds of divergences between the results of executing the same transaction (or the batch tip)
+/// in the main and the shadow VM.
+fn divergences(
+    main: &VmExecutionResultAndLogs,
+    shadow: &VmExecutionResultAndLogs,
+) -> Vec<ShadowVmDivergence> {
+    let mut divergences = vec![];
+    if main.result != shadow.result {
+        divergences.push(ShadowVmDivergence::ExecutionResult);
+    }
+    if main.refunds.gas_refunded != shadow.refunds.gas_refunded {
+        divergences.push(ShadowVmDivergence::Refunds);
+    }
+    if main.logs.events != shadow.logs.events {
+        divergences.push(ShadowVmDivergence::Events);
+    }
+    if storage_writes(main) != storage_writes(shadow) {
+        divergences.push(ShadowVmDivergence::StorageWrites);
+    }
+    if main.logs.user_l2_to_l1_logs != shadow.logs.user_l2_to_l1_logs {
+        divergences.push(ShadowVmDivergence::L2ToL1Logs);
+    }
+    divergences
+}
+
+/// VM executing the same commands as the main VM of the batch executor, but with a different VM version.
+///
+/// Shadow execution never influences the main execution: divergences are only logged and reported via metrics.
+/// If the shadow VM panics (which may happen since it can run system contracts it wasn't designed for),
+/// shadow execution is stopped for the rest of the batch.
+pub(super) struct ShadowVm<S: WriteStorage> {
+    vm: Option<VmInstance<S, HistoryEnabled>>,
+    vm_version: VmVersion,
+    l1_batch_number: L1BatchNumber,
+}
+
+impl<S: WriteStorage> fmt::Debug for ShadowVm<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ShadowVm")
+            .field("is_active", &self.vm.is_some())
+            .field("vm_version", &self.vm_version)
+            .field("l1_batch_number", &self.l1_batch_number)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S: WriteStorage> ShadowVm<S> {
+    /// Creates a shadow VM. `system_env` must correspond to the shadow protocol version (i.e., contain its VM version
+    /// and base system contracts); `main_protocol_version` is only used for logging.
+    pub fn new(
+        l1_batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+        storage: StoragePtr<S>,
+        main_protocol_version: ProtocolVersionId,
+    ) -> Self {
+        let l1_batch_number = l1_batch_env.number;
+        let vm_version = VmVersion::from(system_env.version);
+        tracing::info!(
+            "Executing L1 batch #{l1_batch_number} with shadow VM {vm_version:?} for protocol version {:?} \
+             (main VM is {:?} for protocol version {main_protocol_version:?})",
+            system_env.version,
+            VmVersion::from(main_protocol_version)
+        );
+        EXECUTOR_METRICS.shadow_vm_batches.inc();
+        let vm = VmInstance::new(l1_batch_env, system_env, storage);
+        Self {
+            vm: Some(vm),
+            vm_version,
+            l1_batch_number,
+        }
+    }
+
+    /// Runs the action with the shadow VM, provided that it hasn't panicked before.
+    fn with_vm<R>(
+        &mut self,
+        action_name: &str,
+        action: impl FnOnce(&mut VmInstance<S, HistoryEnabled>) -> R,
+    ) -> Option<R> {
+        let vm = self.vm.as_mut()?;
+        match panic::catch_unwind(AssertUnwindSafe(|| action(vm))) {
+            Ok(output) => Some(output),
+            Err(_) => {
+                tracing::error!(
+                    "Shadow VM {:?} panicked during {action_name} in L1 batch #{}; \
+                     shadow execution is stopped for the rest of the batch",
+                    self.vm_version,
+                    self.l1_batch_number
+                );
+                EXECUTOR_METRICS.shadow_vm_panics.inc();
+                self.vm = None;
+                None
+            }
+        }
+    }
+
+    fn report_divergences(
+        &self,
+        subject: &str,
+        main: &VmExecutionResultAndLogs,
+        shadow: &VmExecutionResultAndLogs,
+    ) {
+        let divergences = divergences(main, shadow);
+        if divergences.is_empty() {
+            return;
+        }
+        for &kind in &divergences {
+            EXECUTOR_METRICS.shadow_vm_divergences[&kind].inc();
+        }
+        tracing::error!(
+            "Shadow VM {:?} diverged from the main VM on {subject} in L1 batch #{}: {divergences:?}; \
+             main result: {:?}, shadow result: {:?}",
+            self.vm_version,
+            self.l1_batch_number,
+            main.result,
+            shadow.result
+        );
+    }
+
+    pub fn make_snapshot(&mut self) {
+        self.with_vm("making snapshot", |vm| vm.make_snapshot());
+    }
+
+    pub fn rollback_last_tx(&mut self) {
+        self.with_vm("rolling back transaction", |vm| {
+            vm.rollback_to_the_latest_snapshot();
+        });
+    }
+
+    /// Rolls back the specified number of the latest transactions.
+    pub fn rollback_txs(&mut self, tx_count: usize) {
+        self.with_vm("rolling back transactions", |vm| {
+            for _ in 1..tx_count {
+                vm.pop_snapshot_no_rollback();
+            }
+            vm.rollback_to_the_latest_snapshot();
+        });
+    }
+
+    pub fn start_next_miniblock(&mut self, l2_block_env: L2BlockEnv) {
+        self.with_vm("starting miniblock", |vm| {
+            vm.start_new_l2_block(l2_block_env);
+        });
+    }
+
+    /// Executes a transaction and compares the result with the one produced by the main VM.
+    ///
+    /// Mirrors the bytecode compression logic of the main VM: if `optional_compression` is set and the transaction
+    /// fails to publish compressed bytecodes, it is re-executed without compression.
+    pub fn execute_tx(
+        &mut self,
+        tx: &Transaction,
+        main_result: &VmExecutionResultAndLogs,
+        optional_compression: bool,
+    ) {
+        let shadow_result = self.with_vm("executing transaction", |vm| {
+            if optional_compression {
+                vm.make_snapshot();
+                if let (Ok(()), result) =
+                    vm.execute_transaction_with_bytecode_compression(tx.clone(), true)
+                {
+                    vm.pop_snapshot_no_rollback();
+                    return result;
+                }
+                vm.rollback_to_the_latest_snapshot();
+                let (_, result) =
+                    vm.execute_transaction_with_bytecode_compression(tx.clone(), false);
+                return result;
+            }
+
+            let (published_bytecodes, mut result) =
+                vm.execute_transaction_with_bytecode_compression(tx.clone(), true);
+            if published_bytecodes.is_err() {
+                // Mirrors the main VM behavior.
+                result.result = ExecutionResult::Halt {
+                    reason: Halt::FailedToPublishCompressedBytecodes,
+                };
+            }
+            result
+        });
+        if let Some(shadow_result) = shadow_result {
+            let subject = format!("transaction {:?}", tx.hash());
+            self.report_divergences(&subject, main_result, &shadow_result);
+        }
+    }
+
+    /// Finishes the batch and compares the batch tip execution with the one performed by the main VM.
+    pub fn finish_batch(mut self, main_result: &FinishedL1Batch) {
+        let shadow_result = self.with_vm("finishing batch", |vm| vm.finish_batch());
+        if let Some(shadow_result) = shadow_result {
+            self.report_divergences(
+                "batch tip",
+                &main_result.block_tip_execution_result,
+                &shadow_result.block_tip_execution_result,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use multivm::interface::VmRevertReason;
+    use zksync_types::{event::VmEvent, Address};
+
+    use super::*;
+
+    fn mock_result() -> VmExecutionResultAndLogs {
+        let mut result = VmExecutionResultAndLogs {
+            result: ExecutionResult::Success { output: vec![] },
+            logs: Default::default(),
+            statistics: Default::default(),
+            refunds: Default::default(),
+        };
+        result.logs.events.push(VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: Address::repeat_byte(1),
+            indexed_topics: vec![H256::repeat_byte(2)],
+            value: vec![3; 32],
+        });
+        result.refunds.gas_refunded = 100;
+        result
+    }
+
+    #[test]
+    fn no_divergences_for_equal_results() {
+        assert_eq!(divergences(&mock_result(), &mock_result()), []);
+    }
+
+    #[test]
+    fn detecting_divergences() {
+        let main_result = mock_result();
+
+        let mut shadow_result = mock_result();
+        shadow_result.refunds.gas_refunded = 0;
+        assert_eq!(
+            divergences(&main_result, &shadow_result),
+            [ShadowVmDivergence::Refunds]
+        );
+
+        let mut shadow_result = mock_result();
+        shadow_result.result = ExecutionResult::Revert {
+            output: VmRevertReason::General {
+                msg: "oops".to_owned(),
+                data: vec![],
+            },
+        };
+        shadow_result.logs.events.clear();
+        assert_eq!(
+            divergences(&main_result, &shadow_result),
+            [
+                ShadowVmDivergence::ExecutionResult,
+                ShadowVmDivergence::Events
+            ]
+        );
+    }
+}
@@ -1,4 +1,7 @@
+use std::time::Duration;
+
 use assert_matches::assert_matches;
+use multivm::tracers::ExecutionTimeout;
 use test_casing::test_casing;
 use zksync_dal::ConnectionPool;
 use zksync_test_account::Account;
@@ -133,6 +136,33 @@ async fn execute_l2_and_l1_txs() {
     executor.finish_batch().await;
 }
 
+/// Checks that new transactions exceeding the execution budget are rejected, while re-executed transactions
+/// are not subject to the budget.
+#[tokio::test]
+async fn tx_execution_timeout() {
+    let connection_pool = ConnectionPool::constrained_test_pool(1).await;
+    let mut alice = Account::random();
+
+    let mut config = TestConfig::new();
+    config.tx_execution_timeout = Some(Duration::ZERO);
+    let tester = Tester::with_config(connection_pool, config);
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let executor = tester.create_batch_executor().await;
+
+    let tx = alice.execute();
+    let res = executor.execute_tx(tx.clone()).await;
+    assert_matches!(
+        res,
+        TxExecutionResult::RejectedByVm { reason } if reason == ExecutionTimeout::halt_reason()
+    );
+    executor.rollback_last_tx().await;
+
+    let res = executor.reexecute_tx(tx).await;
+    assert_executed(&res);
+    executor.finish_batch().await;
+}
+
 /// Checks that we can successfully rollback the transaction and execute it once again.
 #[tokio::test]
 async fn rollback() {
@@ -177,6 +207,57 @@ async fn rollback() {
     executor.finish_batch().await;
 }
 
+/// Checks that multiple transactions can be speculatively executed and rolled back using executor snapshots.
+#[tokio::test]
+async fn rollback_to_snapshot() {
+    let connection_pool = ConnectionPool::constrained_test_pool(1).await;
+    let mut alice = Account::random();
+    let mut bob = Account::random();
+
+    let tester = Tester::new(connection_pool);
+
+    tester.genesis().await;
+    tester.fund(&[alice.address(), bob.address()]).await;
+    let executor = tester.create_batch_executor().await;
+
+    let res = executor.execute_tx(alice.execute()).await;
+    assert_executed(&res);
+
+    let snapshot = executor.make_snapshot().await;
+    // Rolling back to a snapshot without transactions executed after it is a no-op.
+    executor.rollback_to_snapshot(snapshot).await;
+
+    let candidate_txs = [alice.execute(), bob.execute()];
+    let mut old_metrics = vec![];
+    for tx in &candidate_txs {
+        let res = executor.execute_tx(tx.clone()).await;
+        assert_executed(&res);
+        let TxExecutionResult::Success { tx_metrics, .. } = res else {
+            unreachable!();
+        };
+        old_metrics.push(tx_metrics);
+    }
+    let nested_snapshot = executor.make_snapshot().await;
+    assert!(nested_snapshot > snapshot);
+    executor.rollback_to_snapshot(snapshot).await;
+
+    // Rolled back transactions must have the same execution results when executed again.
+    for (tx, old_metrics) in candidate_txs.into_iter().zip(old_metrics) {
+        let res = executor.execute_tx(tx).await;
+        assert_executed(&res);
+        let TxExecutionResult::Success { tx_metrics, .. } = res else {
+            unreachable!();
+        };
+        assert_eq!(
+            tx_metrics, old_metrics,
+            "Execution results must be the same"
+        );
+    }
+    assert_eq!(executor.make_snapshot().await, nested_snapshot);
+
+    executor.finish_batch().await;
+}
+
 /// Checks that incorrect transactions are marked as rejected.
 #[tokio::test]
 async fn reject_tx() {
@@ -1,7 +1,7 @@
 //! Testing harness for the batch executor.
 //! Contains helper functionality to initialize test context and perform tests without too much boilerplate.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use multivm::{
     interface::{L1BatchEnv, L2BlockEnv, SystemEnv},
@@ -45,6 +45,7 @@ pub(super) struct TestConfig {
     pub(super) max_allowed_tx_gas_limit: u32,
     pub(super) validation_computational_gas_limit: u32,
     pub(super) upload_witness_inputs_to_gcs: bool,
+    pub(super) tx_execution_timeout: Option<Duration>,
 }
 
 impl TestConfig {
@@ -57,6 +58,7 @@ impl TestConfig {
             max_allowed_tx_gas_limit: config.max_allowed_l2_tx_gas_limit,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
             upload_witness_inputs_to_gcs: false,
+            tx_execution_timeout: None,
         }
     }
 }
@@ -112,6 +114,9 @@ impl Tester {
             100,
             false,
         );
+        if let Some(timeout) = self.config.tx_execution_timeout {
+            builder.set_tx_execution_timeout(timeout);
+        }
         let (_stop_sender, stop_receiver) = watch::channel(false);
         builder
             .init_batch(l1_batch_env, system_env, &stop_receiver)
@@ -172,6 +177,7 @@ impl Tester {
                 &get_system_smart_contracts(),
                 Default::default(),
                 Default::default(),
+                None,
             )
             .await
             .unwrap();
@@ -0,0 +1,92 @@
+use serde::Serialize;
+use zksync_dal::ConnectionPool;
+use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
+use zksync_types::L1BatchNumber;
+use zksync_utils::time::seconds_since_epoch;
+
+/// Health details for the state keeper.
+#[derive(Debug, Serialize)]
+struct StateKeeperHealthDetails {
+    last_sealed_l1_batch: L1BatchNumber,
+    /// Seconds elapsed since the timestamp of the last sealed L1 batch.
+    last_sealed_l1_batch_age_sec: u64,
+}
+
+/// Health check for the state keeper reporting the age of the last sealed L1 batch. Unlike reactive health checks,
+/// the age is computed when the health is checked, so it grows if the state keeper is stuck.
+#[derive(Debug)]
+pub struct StateKeeperHealthCheck {
+    pool: ConnectionPool,
+}
+
+impl StateKeeperHealthCheck {
+    pub fn new(pool: ConnectionPool) -> Self {
+        Self { pool }
+    }
+
+    async fn details(&self) -> anyhow::Result<Option<StateKeeperHealthDetails>> {
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let last_sealed_l1_batch = storage
+            .blocks_dal()
+            .get_last_sealed_l1_batch_timestamp()
+            .await?;
+        Ok(
+            last_sealed_l1_batch.map(|(number, timestamp)| StateKeeperHealthDetails {
+                last_sealed_l1_batch: number,
+                last_sealed_l1_batch_age_sec: seconds_since_epoch().saturating_sub(timestamp),
+            }),
+        )
+    }
+}
+
+#[async_trait]
+impl CheckHealth for StateKeeperHealthCheck {
+    fn name(&self) -> &'static str {
+        "state_keeper"
+    }
+
+    async fn check_health(&self) -> Health {
+        match self.details().await {
+            Ok(Some(details)) => Health::from(HealthStatus::Ready).with_details(details),
+            Ok(None) => HealthStatus::NotReady.into(),
+            Err(err) => {
+                tracing::warn!("Failed getting state keeper health details: {err:?}");
+                let details = serde_json::json!({
+                    "error": format!("{err:?}"),
+                });
+                Health::from(HealthStatus::NotReady).with_details(details)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testonly::create_l1_batch;
+
+    #[tokio::test]
+    async fn reporting_last_sealed_l1_batch_age() {
+        let pool = ConnectionPool::test_pool().await;
+        let health_check = StateKeeperHealthCheck::new(pool.clone());
+        let health = health_check.check_health().await;
+        assert_eq!(health.status(), HealthStatus::NotReady);
+
+        let mut l1_batch = create_l1_batch(1);
+        l1_batch.timestamp = seconds_since_epoch() - 100;
+        let mut storage = pool.access_storage().await.unwrap();
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&l1_batch)
+            .await
+            .unwrap();
+        drop(storage);
+
+        let health = health_check.check_health().await;
+        assert_eq!(health.status(), HealthStatus::Ready);
+        let details = &serde_json::to_value(&health).unwrap()["details"];
+        assert_eq!(details["last_sealed_l1_batch"], 1);
+        let age = details["last_sealed_l1_batch_age_sec"].as_u64().unwrap();
+        assert!((100..200).contains(&age), "{age}");
+    }
+}
@@ -6,7 +6,7 @@
 use std::{collections::HashMap, ops};
 
 use futures::FutureExt;
-use vm_utils::storage::L1BatchParamsProvider;
+use vm_utils::storage::{BaseSystemContractsCache, L1BatchParamsProvider};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::ConnectionPool;
 use zksync_types::{
@@ -439,6 +439,76 @@ async fn loading_pending_batch_after_snapshot_recovery() {
     );
 }
 
+#[tokio::test]
+async fn base_system_contracts_are_cached_across_providers() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    let snapshot_recovery =
+        prepare_recovery_snapshot(&mut storage, L1BatchNumber(23), MiniblockNumber(42), &[]).await;
+    let starting_miniblock_number = snapshot_recovery.miniblock_number.0 + 1;
+    store_pending_miniblocks(
+        &mut storage,
+        starting_miniblock_number..=starting_miniblock_number + 1,
+        GenesisParams::mock().base_system_contracts.hashes(),
+    )
+    .await;
+
+    let contracts_cache = BaseSystemContractsCache::default();
+    let provider = L1BatchParamsProvider::new(&mut storage)
+        .await
+        .unwrap()
+        .with_contracts_cache(contracts_cache.clone());
+    let first_miniblock_in_batch = provider
+        .load_first_miniblock_in_batch(&mut storage, snapshot_recovery.l1_batch_number + 1)
+        .await
+        .unwrap()
+        .expect("no first miniblock");
+    let (system_env, _) = provider
+        .load_l1_batch_params(
+            &mut storage,
+            &first_miniblock_in_batch,
+            u32::MAX,
+            L2ChainId::default(),
+        )
+        .await
+        .unwrap();
+
+    // Remove base system contracts from Postgres; they should still be available from the cache.
+    storage
+        .factory_deps_dal()
+        .rollback_factory_deps(snapshot_recovery.miniblock_number - 1)
+        .await
+        .unwrap();
+    let uncached_provider = L1BatchParamsProvider::new(&mut storage).await.unwrap();
+    uncached_provider
+        .load_l1_batch_params(
+            &mut storage,
+            &first_miniblock_in_batch,
+            u32::MAX,
+            L2ChainId::default(),
+        )
+        .await
+        .unwrap_err();
+
+    let provider = L1BatchParamsProvider::new(&mut storage)
+        .await
+        .unwrap()
+        .with_contracts_cache(contracts_cache);
+    let (cached_system_env, _) = provider
+        .load_l1_batch_params(
+            &mut storage,
+            &first_miniblock_in_batch,
+            u32::MAX,
+            L2ChainId::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        cached_system_env.base_system_smart_contracts.hashes(),
+        system_env.base_system_smart_contracts.hashes()
+    );
+}
+
 #[tokio::test]
 async fn getting_batch_version_with_genesis() {
     let pool = ConnectionPool::test_pool().await;
@@ -17,11 +17,12 @@ use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
 use zksync_object_store::ObjectStore;
 use zksync_types::{
-    protocol_version::ProtocolUpgradeTx, witness_block_state::WitnessBlockState, Address,
-    L1BatchNumber, L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
+    protocol_version::ProtocolUpgradeTx, pubdata_da::PubdataDA,
+    witness_block_state::WitnessBlockState, Address, L1BatchNumber, L2ChainId, MiniblockNumber,
+    ProtocolVersionId, Transaction, H256,
 };
 // TODO (SMA-1206): use seconds instead of milliseconds.
-use zksync_utils::time::millis_since_epoch;
+use zksync_utils::time::{millis_since_epoch, seconds_since_epoch};
 
 use crate::{
     fee_model::BatchFeeModelInputProvider,
@@ -34,7 +35,9 @@ use crate::{
         },
         mempool_actor::l2_tx_filter,
         metrics::KEEPER_METRICS,
+        pubdata_mode::PubdataModeSwitch,
         seal_criteria::{IoSealCriteria, TimeoutSealer},
+        tx_filter::{ConfiguredTransactionFilter, TransactionFilter},
         updates::{MiniblockUpdates, UpdatesManager},
         MempoolGuard,
     },
@@ -51,6 +54,7 @@ pub struct MempoolIO {
     object_store: Arc<dyn ObjectStore>,
     timeout_sealer: TimeoutSealer,
     filter: L2TxFilter,
+    tx_filter: Arc<dyn TransactionFilter>,
     current_miniblock_number: MiniblockNumber,
     prev_miniblock_hash: H256,
     prev_miniblock_timestamp: u64,
@@ -64,6 +68,7 @@ pub struct MempoolIO {
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
     l2_erc20_bridge_addr: Address,
     chain_id: L2ChainId,
+    pubdata_mode: Option<PubdataModeSwitch>,
 
     virtual_blocks_interval: u32,
     virtual_blocks_per_miniblock: u32,
@@ -78,6 +83,10 @@ impl IoSealCriteria for MempoolIO {
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
         self.timeout_sealer.should_seal_miniblock(manager)
     }
+
+    fn unconditional_seal_criterion(&self) -> &'static str {
+        self.timeout_sealer.unconditional_seal_criterion()
+    }
 }
 
 #[async_trait]
@@ -90,6 +99,14 @@ impl StateKeeperIO for MempoolIO {
         self.current_miniblock_number
     }
 
+    fn pubdata_da(&self) -> Option<PubdataDA> {
+        Some(
+            self.pubdata_mode
+                .as_ref()
+                .map_or(PubdataDA::Calldata, PubdataModeSwitch::get),
+        )
+    }
+
     async fn load_pending_batch(&mut self) -> anyhow::Result<Option<PendingBatchData>> {
         let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
 
@@ -247,7 +264,23 @@ impl StateKeeperIO for MempoolIO {
             let res = self.mempool.next_transaction(&self.filter);
             get_latency.observe();
             if let Some(res) = res {
-                return Some(res);
+                if self.is_expired(&res) {
+                    if let Err(err) = self.expire(&res).await {
+                        tracing::warn!("Failed expiring transaction {}: {err:#}", res.hash());
+                    }
+                    continue;
+                }
+                let rejection_reason = if res.is_l1() {
+                    None // L1 transactions cannot be rejected
+                } else {
+                    self.tx_filter.rejection_reason(&res)
+                };
+                let Some(reason) = rejection_reason else {
+                    return Some(res);
+                };
+                if let Err(err) = self.reject(&res, &reason).await {
+                    tracing::warn!("Failed rejecting transaction {}: {err:#}", res.hash());
+                }
             } else {
                 tokio::time::sleep(self.delay_interval).await;
                 continue;
@@ -271,6 +304,7 @@ impl StateKeeperIO for MempoolIO {
 
         // Reset the nonces in the mempool, but don't insert the transaction back.
         self.mempool.rollback(rejected);
+        self.mempool.remove_deadlines([&rejected.hash()]);
 
         // Mark tx as rejected in the storage.
         let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
@@ -294,6 +328,12 @@ impl StateKeeperIO for MempoolIO {
             false,
         );
         self.miniblock_sealer_handle.submit(command).await;
+        let executed_tx_hashes = updates_manager
+            .miniblock
+            .executed_transactions
+            .iter()
+            .map(|tx| &tx.hash);
+        self.mempool.remove_deadlines(executed_tx_hashes);
         self.update_miniblock_fields(&updates_manager.miniblock);
     }
 
@@ -342,7 +382,7 @@ impl StateKeeperIO for MempoolIO {
                 finished_batch,
                 self.l2_erc20_bridge_addr,
             )
-            .await;
+            .await?;
         self.update_miniblock_fields(&fictive_miniblock);
         self.current_l1_batch_number += 1;
         Ok(())
@@ -459,6 +499,7 @@ impl MempoolIO {
             timeout_sealer: TimeoutSealer::new(config),
             filter: L2TxFilter::default(),
             // ^ Will be initialized properly on the first newly opened batch
+            tx_filter: Arc::new(ConfiguredTransactionFilter::new(config)),
             current_l1_batch_number: cursor.l1_batch,
             miniblock_sealer_handle,
             current_miniblock_number: cursor.next_miniblock,
@@ -471,11 +512,57 @@ impl MempoolIO {
             batch_fee_input_provider,
             l2_erc20_bridge_addr,
             chain_id,
+            pubdata_mode: None,
             virtual_blocks_interval: config.virtual_blocks_interval,
             virtual_blocks_per_miniblock: config.virtual_blocks_per_miniblock,
         })
     }
 
+    /// Replaces the filter applied to L2 transactions taken from the mempool. By default, a filter based on
+    /// [`StateKeeperConfig`] is used.
+    pub fn with_transaction_filter(mut self, tx_filter: Arc<dyn TransactionFilter>) -> Self {
+        self.tx_filter = tx_filter;
+        self
+    }
+
+    /// Sets the source of the pubdata mode recorded for sealed L1 batches and used to determine pubdata limits
+    /// for them. The mode is read each time an L1 batch is opened. By default, pubdata is assumed to be published
+    /// via calldata.
+    pub fn with_pubdata_mode(mut self, pubdata_mode: PubdataModeSwitch) -> Self {
+        self.pubdata_mode = Some(pubdata_mode);
+        self
+    }
+
+    /// Checks whether the inclusion deadline of the transaction has passed. The current time is used
+    /// instead of the miniblock timestamp; it's never less than the latter, so no transaction is included past its deadline.
+    fn is_expired(&self, tx: &Transaction) -> bool {
+        let Some(deadline) = self.mempool.deadline(&tx.hash()) else {
+            return false;
+        };
+        deadline.is_passed(self.current_miniblock_number, seconds_since_epoch())
+    }
+
+    async fn expire(&mut self, expired: &Transaction) -> anyhow::Result<()> {
+        // Reset the nonces in the mempool, but don't insert the transaction back.
+        self.mempool.rollback(expired);
+        self.mempool.remove_deadlines([&expired.hash()]);
+
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let is_marked = storage
+            .transactions_dal()
+            .mark_tx_as_expired(expired.hash())
+            .await?;
+        // The transaction may be already marked as expired by `MempoolFetcher`.
+        if is_marked {
+            KEEPER_METRICS.expired_transactions.inc();
+            tracing::info!(
+                "transaction {} is expired since its inclusion deadline has passed",
+                expired.hash()
+            );
+        }
+        Ok(())
+    }
+
     fn update_miniblock_fields(&mut self, miniblock: &MiniblockUpdates) {
         assert_eq!(
             miniblock.number, self.current_miniblock_number.0,
@@ -1,18 +1,21 @@
 use std::{
+    collections::VecDeque,
     fmt,
     time::{Duration, Instant},
 };
 
+use anyhow::Context as _;
 use async_trait::async_trait;
 use multivm::interface::{FinishedL1Batch, L1BatchEnv, SystemEnv};
 use tokio::sync::{mpsc, oneshot};
-use zksync_dal::ConnectionPool;
+use zksync_dal::{ConnectionPool, SqlxError};
 use zksync_types::{
-    block::MiniblockExecutionData, protocol_version::ProtocolUpgradeTx,
+    block::MiniblockExecutionData, protocol_version::ProtocolUpgradeTx, pubdata_da::PubdataDA,
     witness_block_state::WitnessBlockState, L1BatchNumber, MiniblockNumber, ProtocolVersionId,
     Transaction,
 };
 
+use self::seal_wal::MiniblockSealWal;
 use super::{
     metrics::{MiniblockQueueStage, MINIBLOCK_METRICS},
     seal_criteria::IoSealCriteria,
@@ -23,6 +26,7 @@ pub(crate) mod common;
 pub(crate) mod fee_address_migration;
 pub(crate) mod mempool;
 pub(crate) mod seal_logic;
+pub(crate) mod seal_wal;
 #[cfg(test)]
 mod tests;
 
@@ -70,6 +74,9 @@ pub trait StateKeeperIO: 'static + Send + IoSealCriteria {
     fn current_l1_batch_number(&self) -> L1BatchNumber;
     /// Returns the number of the currently processed miniblock (aka L2 block).
     fn current_miniblock_number(&self) -> MiniblockNumber;
+    /// Returns the pubdata mode used for L1 batches processed by this IO, or `None` if the mode
+    /// is not known (e.g., if batches are synced from the main node).
+    fn pubdata_da(&self) -> Option<PubdataDA>;
 
     /// Returns the data on the batch that was not sealed before the server restart.
     /// See `PendingBatchData` doc-comment for details.
@@ -217,12 +224,19 @@ impl MiniblockSealerHandle {
 pub struct MiniblockSealer {
     pool: ConnectionPool,
     is_sync: bool,
+    wal: Option<MiniblockSealWal>,
+    max_buffered_miniblocks: usize,
     // Weak sender handle to get queue capacity stats.
     commands_sender: mpsc::WeakSender<Completable<MiniblockSealCommand>>,
     commands_receiver: mpsc::Receiver<Completable<MiniblockSealCommand>>,
 }
 
 impl MiniblockSealer {
+    /// Timeout acquiring a Postgres connection after which Postgres is considered unavailable
+    /// by [`Self::run_with_wal()`]. Without it, detecting an outage would take several connection
+    /// acquisition timeouts.
+    const OUTAGE_DETECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
     /// Creates a sealer that will use the provided Postgres connection and will have the specified
     /// `command_capacity` for unprocessed sealing commands.
     pub fn new(pool: ConnectionPool, mut command_capacity: usize) -> (Self, MiniblockSealerHandle) {
@@ -233,6 +247,8 @@ impl MiniblockSealer {
         let this = Self {
             pool,
             is_sync,
+            wal: None,
+            max_buffered_miniblocks: 0,
             commands_sender: commands_sender.downgrade(),
             commands_receiver,
         };
@@ -244,6 +260,19 @@ impl MiniblockSealer {
         (this, handle)
     }
 
+    /// Enables buffering sealing commands in the provided `wal` if Postgres is unavailable. At most
+    /// `max_buffered_miniblocks` miniblocks are buffered; after that, the sealer applies back pressure
+    /// to the state keeper until Postgres is available again.
+    ///
+    /// Since completion of buffered commands is only reported once they are persisted to Postgres,
+    /// buffering is only useful for async sealers (i.e., ones with non-zero command capacity).
+    #[must_use]
+    pub fn with_wal(mut self, wal: MiniblockSealWal, max_buffered_miniblocks: usize) -> Self {
+        self.wal = Some(wal);
+        self.max_buffered_miniblocks = max_buffered_miniblocks.max(1);
+        self
+    }
+
     /// Seals miniblocks as they are received from the [`MiniblockSealerHandle`]. This should be run
     /// on a separate Tokio task.
     pub async fn run(mut self) -> anyhow::Result<()> {
@@ -257,28 +286,177 @@ impl MiniblockSealer {
         } else {
             tracing::warn!("Miniblock sealer not started, since its handle is already dropped");
         }
+        if let Some(wal) = self.wal.take() {
+            return self.run_with_wal(wal).await;
+        }
 
         let mut miniblock_seal_delta: Option<Instant> = None;
         // Commands must be processed sequentially: a later miniblock cannot be saved before
         // an earlier one.
         while let Some(completable) = self.next_command().await {
-            let mut conn = self
-                .pool
-                .access_storage_tagged("state_keeper")
+            let mut conn = self.pool.access_storage_tagged("state_keeper").await?;
+            let miniblock_number = completable.command.miniblock_number;
+            completable
+                .command
+                .seal(&mut conn)
                 .await
-                .unwrap();
-            completable.command.seal(&mut conn).await;
-            if let Some(delta) = miniblock_seal_delta {
-                MINIBLOCK_METRICS.seal_delta.observe(delta.elapsed());
+                .with_context(|| format!("failed sealing miniblock #{miniblock_number}"))?;
+            Self::complete(completable, &mut miniblock_seal_delta);
+        }
+        Ok(())
+    }
+
+    /// Version of [`Self::run()`] buffering commands in the WAL while Postgres is unavailable.
+    ///
+    /// Each command is written to the WAL before it is persisted to Postgres, so that it can be replayed
+    /// if the node stops (or crashes) in between. Failing to acquire a Postgres connection within
+    /// [`Self::OUTAGE_DETECTION_TIMEOUT`], or a connection-level error when persisting a miniblock,
+    /// is treated as an outage; persisting buffered miniblocks is retried until it succeeds.
+    /// Other errors (e.g., constraint violations) are not transient, so they are returned.
+    async fn run_with_wal(mut self, wal: MiniblockSealWal) -> anyhow::Result<()> {
+        const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+        tracing::info!(
+            "Miniblock sealer will buffer up to {} miniblocks in WAL during Postgres outages",
+            self.max_buffered_miniblocks
+        );
+        let mut miniblock_seal_delta: Option<Instant> = None;
+        let mut buffered = VecDeque::<Completable<MiniblockSealCommand>>::new();
+        // Set if persisting a miniblock has failed. In this case, the miniblock may still be persisted
+        // (e.g., if the connection was lost when committing the transaction), so it needs to be checked.
+        let mut check_persisted = false;
+        loop {
+            let new_command = if buffered.is_empty() {
+                let Some(completable) = self.next_command().await else {
+                    break;
+                };
+                Some(completable)
+            } else if buffered.len() < self.max_buffered_miniblocks {
+                tokio::select! {
+                    completable = self.next_command() => {
+                        let Some(completable) = completable else {
+                            break;
+                        };
+                        Some(completable)
+                    }
+                    () = tokio::time::sleep(RETRY_INTERVAL) => None,
+                }
+            } else {
+                tokio::time::sleep(RETRY_INTERVAL).await;
+                None
+            };
+            if let Some(completable) = new_command {
+                wal.push(&completable.command)?;
+                buffered.push_back(completable);
+            }
+
+            let conn = tokio::time::timeout(
+                Self::OUTAGE_DETECTION_TIMEOUT,
+                self.pool.access_storage_tagged("state_keeper"),
+            )
+            .await;
+            let mut conn = match conn {
+                Ok(Ok(conn)) => conn,
+                Ok(Err(err)) => {
+                    tracing::warn!(
+                        "Postgres is unavailable ({err:#}); {} miniblocks are buffered in WAL",
+                        buffered.len()
+                    );
+                    MINIBLOCK_METRICS
+                        .wal_buffered_miniblocks
+                        .set(buffered.len());
+                    continue;
+                }
+                Err(_) => {
+                    tracing::warn!(
+                        "Timed out acquiring Postgres connection; {} miniblocks are buffered in WAL",
+                        buffered.len()
+                    );
+                    MINIBLOCK_METRICS
+                        .wal_buffered_miniblocks
+                        .set(buffered.len());
+                    continue;
+                }
+            };
+
+            if check_persisted {
+                let last_sealed_miniblock =
+                    match conn.blocks_dal().get_sealed_miniblock_number().await {
+                        Ok(number) => number,
+                        Err(err) if is_connection_error(&err) => {
+                            tracing::warn!("Failed getting sealed miniblock number: {err}");
+                            continue;
+                        }
+                        Err(err) => {
+                            return Err(anyhow::Error::from(err)
+                                .context("failed getting sealed miniblock number"));
+                        }
+                    };
+                while let Some(completable) = buffered.front() {
+                    let miniblock_number = completable.command.miniblock_number;
+                    if Some(miniblock_number) > last_sealed_miniblock {
+                        break;
+                    }
+                    tracing::info!("Miniblock #{miniblock_number} from WAL is already persisted");
+                    let completable = buffered.pop_front().unwrap();
+                    wal.remove(miniblock_number)?;
+                    Self::complete(completable, &mut miniblock_seal_delta);
+                }
+                check_persisted = false;
             }
-            miniblock_seal_delta = Some(Instant::now());
 
-            completable.completion_sender.send(()).ok();
-            // ^ We don't care whether anyone listens to the processing progress
+            if buffered.len() > 1 {
+                tracing::info!("Persisting {} miniblocks buffered in WAL", buffered.len());
+            }
+            while let Some(completable) = buffered.front() {
+                let miniblock_number = completable.command.miniblock_number;
+                if let Err(err) = completable.command.seal(&mut conn).await {
+                    if !err.chain().any(|err| {
+                        err.downcast_ref::<SqlxError>()
+                            .map_or(false, is_connection_error)
+                    }) {
+                        return Err(err.context(format!(
+                            "failed persisting miniblock #{miniblock_number} buffered in WAL"
+                        )));
+                    }
+                    tracing::warn!(
+                        "Failed persisting miniblock #{miniblock_number} buffered in WAL, will retry: {err:#}"
+                    );
+                    check_persisted = true;
+                    break;
+                }
+                let completable = buffered.pop_front().unwrap();
+                wal.remove(miniblock_number)?;
+                Self::complete(completable, &mut miniblock_seal_delta);
+            }
+            MINIBLOCK_METRICS
+                .wal_buffered_miniblocks
+                .set(buffered.len());
+        }
+
+        if !buffered.is_empty() {
+            tracing::warn!(
+                "Miniblock sealer is shutting down with {} miniblocks buffered in WAL; they will be \
+                 persisted on the next node start",
+                buffered.len()
+            );
         }
         Ok(())
     }
 
+    fn complete(
+        completable: Completable<MiniblockSealCommand>,
+        miniblock_seal_delta: &mut Option<Instant>,
+    ) {
+        if let Some(delta) = *miniblock_seal_delta {
+            MINIBLOCK_METRICS.seal_delta.observe(delta.elapsed());
+        }
+        *miniblock_seal_delta = Some(Instant::now());
+
+        completable.completion_sender.send(()).ok();
+        // ^ We don't care whether anyone listens to the processing progress
+    }
+
     async fn next_command(&mut self) -> Option<Completable<MiniblockSealCommand>> {
         tracing::debug!("Polling miniblock seal queue for next command");
         let start = Instant::now();
@@ -302,3 +480,17 @@ impl MiniblockSealer {
         command
     }
 }
+
+/// Checks whether a Postgres error is caused by the connection to the database (i.e., is transient),
+/// rather than by the query itself.
+fn is_connection_error(err: &SqlxError) -> bool {
+    matches!(
+        err,
+        SqlxError::Io(_)
+            | SqlxError::Tls(_)
+            | SqlxError::Protocol(_)
+            | SqlxError::PoolTimedOut
+            | SqlxError::PoolClosed
+            | SqlxError::WorkerCrashed
+    )
+}
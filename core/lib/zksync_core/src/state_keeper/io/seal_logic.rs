@@ -3,6 +3,7 @@
 
 use std::time::{Duration, Instant};
 
+use anyhow::Context as _;
 use chrono::Utc;
 use itertools::Itertools;
 use multivm::{
@@ -11,8 +12,8 @@ use multivm::{
 };
 use zksync_dal::StorageProcessor;
 use zksync_types::{
-    block::{unpack_block_info, L1BatchHeader, MiniblockHeader},
-    event::{extract_added_tokens, extract_long_l2_to_l1_messages},
+    block::{unpack_block_info, ContractPubdata, L1BatchHeader, MiniblockHeader},
+    event::{extract_added_tokens, extract_long_l2_to_l1_messages, logs_bloom},
     l1::L1Tx,
     l2::L2Tx,
     l2_to_l1_log::{SystemL2ToL1Log, UserL2ToL1Log},
@@ -54,10 +55,13 @@ impl UpdatesManager {
         l1_batch_env: &L1BatchEnv,
         finished_batch: FinishedL1Batch,
         l2_erc20_bridge_addr: Address,
-    ) -> MiniblockUpdates {
+    ) -> anyhow::Result<MiniblockUpdates> {
         let started_at = Instant::now();
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::VmFinalization);
-        let mut transaction = storage.start_transaction().await.unwrap();
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .context("start_transaction()")?;
         progress.observe(None);
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::FictiveMiniblock);
@@ -77,7 +81,10 @@ impl UpdatesManager {
             l2_erc20_bridge_addr,
             false, // fictive miniblocks don't have txs, so it's fine to pass `false` here.
         );
-        miniblock_command.seal_inner(&mut transaction, true).await;
+        miniblock_command
+            .seal_inner(&mut transaction, true)
+            .await
+            .context("failed sealing fictive miniblock")?;
         progress.observe(None);
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::LogDeduplication);
@@ -125,12 +132,13 @@ impl UpdatesManager {
             l2_tx_count: l2_tx_count as u16,
             l2_to_l1_logs: finished_batch.final_execution_state.user_l2_to_l1_logs,
             l2_to_l1_messages,
-            bloom: Default::default(),
+            bloom: logs_bloom(&finished_batch.final_execution_state.events),
             used_contract_hashes: finished_batch.final_execution_state.used_contract_hashes,
             base_system_contracts_hashes: self.base_system_contract_hashes(),
             protocol_version: Some(self.protocol_version()),
             system_logs: finished_batch.final_execution_state.system_logs,
             pubdata_input: finished_batch.pubdata_input,
+            pubdata_mode: self.pubdata_da(),
         };
 
         let events_queue = finished_batch
@@ -150,9 +158,19 @@ impl UpdatesManager {
                 &events_queue,
                 &finished_batch.final_execution_state.storage_refunds,
                 self.pending_execution_metrics().circuit_statistic,
+                self.pending_execution_metrics().storage_accesses,
+            )
+            .await
+            .context("blocks_dal().insert_l1_batch()")?;
+        transaction
+            .blocks_dal()
+            .set_l1_batch_seal_stats(
+                l1_batch_env.number,
+                self.l1_batch.seal_criterion,
+                &self.l1_batch.fullness,
             )
             .await
-            .unwrap();
+            .context("blocks_dal().set_l1_batch_seal_stats()")?;
         progress.observe(None);
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::SetL1BatchNumberForMiniblocks);
@@ -160,7 +178,7 @@ impl UpdatesManager {
             .blocks_dal()
             .mark_miniblocks_as_executed_in_l1_batch(l1_batch_env.number)
             .await
-            .unwrap();
+            .context("blocks_dal().mark_miniblocks_as_executed_in_l1_batch()")?;
         progress.observe(None);
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::MarkTxsAsExecutedInL1Batch);
@@ -183,7 +201,7 @@ impl UpdatesManager {
             .storage_logs_dedup_dal()
             .insert_protective_reads(l1_batch_env.number, &protective_reads)
             .await
-            .unwrap();
+            .context("storage_logs_dedup_dal().insert_protective_reads()")?;
         progress.observe(protective_reads.len());
 
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::FilterWrittenSlots);
@@ -215,11 +233,20 @@ impl UpdatesManager {
             .storage_logs_dedup_dal()
             .insert_initial_writes(l1_batch_env.number, &written_storage_keys)
             .await
-            .unwrap();
+            .context("storage_logs_dedup_dal().insert_initial_writes()")?;
         progress.observe(deduplicated_writes.len());
 
+        let pubdata_by_contract = finished_batch.pubdata_by_contract.unwrap_or_default();
+        let progress = L1_BATCH_METRICS.start(L1BatchSealStage::InsertPubdataByContract);
+        transaction
+            .blocks_dal()
+            .insert_l1_batch_pubdata_by_contract(l1_batch_env.number, &pubdata_by_contract)
+            .await
+            .context("blocks_dal().insert_l1_batch_pubdata_by_contract()")?;
+        progress.observe(pubdata_by_contract.len());
+
         let progress = L1_BATCH_METRICS.start(L1BatchSealStage::CommitL1Batch);
-        transaction.commit().await.unwrap();
+        transaction.commit().await.context("commit()")?;
         progress.observe(None);
 
         let writes_metrics = self.storage_writes_deduplicator.metrics();
@@ -235,8 +262,9 @@ impl UpdatesManager {
             l1_batch_env.number,
             l1_batch_env.timestamp,
             &writes_metrics,
+            &pubdata_by_contract,
         );
-        miniblock_command.miniblock
+        Ok(miniblock_command.miniblock)
     }
 
     fn report_l1_batch_metrics(
@@ -245,6 +273,7 @@ impl UpdatesManager {
         current_l1_batch_number: L1BatchNumber,
         block_timestamp: u64,
         writes_metrics: &DeduplicatedWritesMetrics,
+        pubdata_by_contract: &[ContractPubdata],
     ) {
         L1_BATCH_METRICS
             .initial_writes
@@ -255,6 +284,25 @@ impl UpdatesManager {
         L1_BATCH_METRICS
             .transactions_in_l1_batch
             .observe(self.l1_batch.executed_transactions.len());
+        L1_BATCH_METRICS
+            .observe_circuit_family_gas(&self.pending_execution_metrics().circuit_family_gas);
+        L1_BATCH_METRICS
+            .contracts_with_pubdata
+            .observe(pubdata_by_contract.len());
+        let total_pubdata: u64 = pubdata_by_contract
+            .iter()
+            .map(|contract| contract.pubdata_bytes)
+            .sum();
+        if total_pubdata > 0 {
+            let largest_contract_pubdata = pubdata_by_contract
+                .iter()
+                .map(|contract| contract.pubdata_bytes)
+                .max()
+                .unwrap_or_default();
+            L1_BATCH_METRICS
+                .largest_contract_pubdata_share
+                .observe(largest_contract_pubdata as f64 / total_pubdata as f64);
+        }
 
         let l1_batch_latency =
             ((millis_since_epoch() - block_timestamp as u128 * 1_000) as f64) / 1_000.0;
@@ -268,8 +316,8 @@ impl UpdatesManager {
 }
 
 impl MiniblockSealCommand {
-    pub async fn seal(&self, storage: &mut StorageProcessor<'_>) {
-        self.seal_inner(storage, false).await;
+    pub async fn seal(&self, storage: &mut StorageProcessor<'_>) -> anyhow::Result<()> {
+        self.seal_inner(storage, false).await
     }
 
     /// Seals a miniblock with the given number.
@@ -281,10 +329,17 @@ impl MiniblockSealCommand {
     /// one for sending fees to the operator).
     ///
     /// `l2_erc20_bridge_addr` is required to extract the information on newly added tokens.
-    async fn seal_inner(&self, storage: &mut StorageProcessor<'_>, is_fictive: bool) {
+    async fn seal_inner(
+        &self,
+        storage: &mut StorageProcessor<'_>,
+        is_fictive: bool,
+    ) -> anyhow::Result<()> {
         self.assert_valid_miniblock(is_fictive);
 
-        let mut transaction = storage.start_transaction().await.unwrap();
+        let mut transaction = storage
+            .start_transaction()
+            .await
+            .context("start_transaction()")?;
         if self.pre_insert_txs {
             let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::PreInsertTxs, is_fictive);
             for tx in &self.miniblock.executed_transactions {
@@ -348,13 +403,14 @@ impl MiniblockSealCommand {
                     .into(),
             ),
             virtual_blocks: self.miniblock.virtual_blocks,
+            logs_bloom: logs_bloom(&self.miniblock.events),
         };
 
         transaction
             .blocks_dal()
             .insert_miniblock(&miniblock_header)
             .await
-            .unwrap();
+            .context("blocks_dal().insert_miniblock()")?;
         progress.observe(None);
 
         let progress =
@@ -376,7 +432,7 @@ impl MiniblockSealCommand {
             .storage_logs_dal()
             .insert_storage_logs(miniblock_number, &write_logs)
             .await
-            .unwrap();
+            .context("storage_logs_dal().insert_storage_logs()")?;
         progress.observe(write_log_count);
 
         #[allow(deprecated)] // Will be removed shortly
@@ -398,7 +454,7 @@ impl MiniblockSealCommand {
                 .factory_deps_dal()
                 .insert_factory_deps(miniblock_number, new_factory_deps)
                 .await
-                .unwrap();
+                .context("factory_deps_dal().insert_factory_deps()")?;
         }
         progress.observe(new_factory_deps_count);
 
@@ -412,7 +468,7 @@ impl MiniblockSealCommand {
                 .tokens_dal()
                 .add_tokens(&added_tokens)
                 .await
-                .unwrap();
+                .context("tokens_dal().add_tokens()")?;
         }
         progress.observe(added_tokens_len);
 
@@ -461,11 +517,11 @@ impl MiniblockSealCommand {
                 CURRENT_VIRTUAL_BLOCK_INFO_POSITION,
             ))
             .await
-            .expect("failed getting virtual block info from VM state");
+            .context("failed getting virtual block info from VM state")?;
         let (current_l2_virtual_block_number, _) =
             unpack_block_info(h256_to_u256(current_l2_virtual_block_info));
 
-        transaction.commit().await.unwrap();
+        transaction.commit().await.context("commit()")?;
         progress.observe(None);
 
         let progress = MINIBLOCK_METRICS.start(MiniblockSealStage::ReportTxMetrics, is_fictive);
@@ -488,6 +544,7 @@ impl MiniblockSealCommand {
         progress.observe(Some(self.miniblock.executed_transactions.len()));
 
         self.report_miniblock_metrics(started_at, current_l2_virtual_block_number);
+        Ok(())
     }
 
     /// Performs several sanity checks to make sure that the miniblock is valid.
@@ -513,16 +570,8 @@ impl MiniblockSealCommand {
     }
 
     fn extract_deduplicated_write_logs(&self, is_fictive: bool) -> Vec<(H256, Vec<StorageLog>)> {
-        let mut storage_writes_deduplicator = StorageWritesDeduplicator::new();
-        storage_writes_deduplicator.apply(
-            self.miniblock
-                .storage_logs
-                .iter()
-                .filter(|log| log.log_query.rw_flag),
-        );
-        let deduplicated_logs = storage_writes_deduplicator.into_modified_key_values();
-
-        deduplicated_logs
+        // Logs are already sorted by `tx_index`.
+        StorageWritesDeduplicator::deduplicate_sorted(&self.miniblock.storage_logs)
             .into_iter()
             .map(
                 |(
@@ -532,7 +581,6 @@ impl MiniblockSealCommand {
                     },
                 )| (tx_index, (key, value)),
             )
-            .sorted_by_key(|(tx_index, _)| *tx_index)
             .group_by(|(tx_index, _)| *tx_index)
             .into_iter()
             .map(|(tx_index, logs)| {
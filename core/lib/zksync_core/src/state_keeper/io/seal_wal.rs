@@ -0,0 +1,203 @@
+//! Local write-ahead log (WAL) for miniblock sealing commands. The WAL allows the state keeper to continue
+//! producing miniblocks during short Postgres outages; buffered miniblocks are persisted to Postgres
+//! once it becomes available again, or on the next node start.
+
+use std::path::Path;
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_dal::ConnectionPool;
+use zksync_storage::{db::NamedColumnFamily, RocksDB};
+use zksync_types::MiniblockNumber;
+
+use crate::state_keeper::updates::MiniblockSealCommand;
+
+#[derive(Debug, Clone, Copy)]
+enum MiniblockSealWalColumnFamily {
+    /// Serialized sealing commands keyed by the miniblock number.
+    Commands,
+}
+
+impl NamedColumnFamily for MiniblockSealWalColumnFamily {
+    const DB_NAME: &'static str = "miniblock_seal_wal";
+    const ALL: &'static [Self] = &[Self::Commands];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Commands => "commands",
+        }
+    }
+}
+
+/// Version of the serialization format for WAL entries. Must be bumped on incompatible changes
+/// to [`MiniblockSealCommand`] serialization; entries with an unknown version are not replayed.
+const FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+struct WalEntryRef<'a> {
+    version: u32,
+    command: &'a MiniblockSealCommand,
+}
+
+#[derive(Debug, Deserialize)]
+struct WalEntryHeader {
+    version: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct WalEntry {
+    command: MiniblockSealCommand,
+}
+
+fn serialize_entry(command: &MiniblockSealCommand) -> anyhow::Result<Vec<u8>> {
+    let entry = WalEntryRef {
+        version: FORMAT_VERSION,
+        command,
+    };
+    serde_json::to_vec(&entry).context("failed serializing seal command")
+}
+
+fn deserialize_entry(raw: &[u8]) -> anyhow::Result<MiniblockSealCommand> {
+    let header: WalEntryHeader =
+        serde_json::from_slice(raw).context("failed deserializing WAL entry header")?;
+    anyhow::ensure!(
+        header.version == FORMAT_VERSION,
+        "unsupported WAL entry version {} (expected {FORMAT_VERSION}); the WAL was probably created \
+         by another node version",
+        header.version
+    );
+    let entry: WalEntry =
+        serde_json::from_slice(raw).context("failed deserializing seal command")?;
+    Ok(entry.command)
+}
+
+/// Big-endian encoding ensures that commands are iterated in the order of miniblock numbers.
+fn serialize_miniblock_number(number: MiniblockNumber) -> [u8; 4] {
+    number.0.to_be_bytes()
+}
+
+/// RocksDB-backed WAL for [`MiniblockSealCommand`]s that could not be persisted to Postgres.
+///
+/// Only the main node state keeper uses the WAL; it's cheaply cloneable.
+#[derive(Debug, Clone)]
+pub struct MiniblockSealWal {
+    db: RocksDB<MiniblockSealWalColumnFamily>,
+}
+
+impl MiniblockSealWal {
+    /// Opens (or creates) the WAL at the specified path.
+    ///
+    /// This method is blocking and should be wrapped in `spawn_blocking(_)` if run in the async context.
+    pub fn new(path: &Path) -> anyhow::Result<Self> {
+        let db = RocksDB::new(path).with_context(|| {
+            format!("failed opening miniblock seal WAL at `{}`", path.display())
+        })?;
+        Ok(Self {
+            // Writes must be durable; otherwise, the WAL is pointless.
+            db: db.with_sync_writes(),
+        })
+    }
+
+    /// Persists a sealing command. An existing command for the same miniblock is overwritten.
+    pub(crate) fn push(&self, command: &MiniblockSealCommand) -> anyhow::Result<()> {
+        let value = serialize_entry(command)?;
+        let mut batch = self.db.new_write_batch();
+        batch.put_cf(
+            MiniblockSealWalColumnFamily::Commands,
+            &serialize_miniblock_number(command.miniblock_number),
+            &value,
+        );
+        self.db
+            .write(batch)
+            .context("failed writing seal command to WAL")
+    }
+
+    /// Removes the sealing command for the specified miniblock (e.g., after it is persisted to Postgres).
+    pub(crate) fn remove(&self, miniblock_number: MiniblockNumber) -> anyhow::Result<()> {
+        let mut batch = self.db.new_write_batch();
+        batch.delete_cf(
+            MiniblockSealWalColumnFamily::Commands,
+            &serialize_miniblock_number(miniblock_number),
+        );
+        self.db
+            .write(batch)
+            .context("failed removing seal command from WAL")
+    }
+
+    /// Returns all commands stored in the WAL ordered by the miniblock number.
+    pub(crate) fn commands(&self) -> anyhow::Result<Vec<MiniblockSealCommand>> {
+        self.db
+            .from_iterator_cf(MiniblockSealWalColumnFamily::Commands, &[])
+            .map(|(key, value)| {
+                deserialize_entry(&value)
+                    .with_context(|| format!("failed deserializing WAL entry with key {key:?}"))
+            })
+            .collect()
+    }
+
+    /// Persists all commands remaining in the WAL to Postgres and clears the WAL. Commands for miniblocks
+    /// that are already persisted (e.g., if the node was stopped after sealing a miniblock, but before
+    /// removing it from the WAL) are skipped. Returns the number of replayed commands.
+    ///
+    /// This method must be called before the state keeper is started.
+    pub async fn replay(&self, pool: &ConnectionPool) -> anyhow::Result<usize> {
+        let commands = self.commands()?;
+        if commands.is_empty() {
+            return Ok(0);
+        }
+
+        let mut storage = pool.access_storage_tagged("state_keeper").await?;
+        let last_sealed_miniblock = storage
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("failed getting sealed miniblock number")?;
+        let mut replayed_count = 0;
+        for command in commands {
+            let miniblock_number = command.miniblock_number;
+            if Some(miniblock_number) <= last_sealed_miniblock {
+                tracing::info!(
+                    "Miniblock #{miniblock_number} from seal WAL is already persisted; skipping"
+                );
+            } else {
+                tracing::info!("Persisting miniblock #{miniblock_number} from seal WAL");
+                command
+                    .seal(&mut storage)
+                    .await
+                    .with_context(|| format!("failed persisting miniblock #{miniblock_number}"))?;
+                replayed_count += 1;
+            }
+            self.remove(miniblock_number)?;
+        }
+        Ok(replayed_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{Address, L1BatchNumber};
+
+    use super::*;
+    use crate::state_keeper::tests::create_updates_manager;
+
+    #[test]
+    fn entry_serialization_roundtrip() {
+        let command = create_updates_manager().seal_miniblock_command(
+            L1BatchNumber(1),
+            MiniblockNumber(1),
+            Address::default(),
+            false,
+        );
+        let raw = serialize_entry(&command).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&raw).unwrap();
+        assert_eq!(json["version"], FORMAT_VERSION);
+        let restored = deserialize_entry(&raw).unwrap();
+        assert_eq!(restored.miniblock_number, command.miniblock_number);
+
+        let mut json = json;
+        json["version"] = (FORMAT_VERSION + 1).into();
+        let raw = serde_json::to_vec(&json).unwrap();
+        let err = deserialize_entry(&raw).unwrap_err().to_string();
+        assert!(err.contains("unsupported WAL entry version"), "{err}");
+    }
+}
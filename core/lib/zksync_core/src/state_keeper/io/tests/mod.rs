@@ -1,32 +1,36 @@
-use std::time::Duration;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use futures::FutureExt;
 use multivm::utils::derive_base_fee_and_gas_per_pubdata;
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::ConnectionPool;
 use zksync_mempool::L2TxFilter;
 use zksync_types::{
+    api::{TransactionDeadline, TransactionStatus},
     block::{BlockGasCount, MiniblockHasher},
     fee::TransactionExecutionMetrics,
     fee_model::{BatchFeeInput, PubdataIndependentBatchFeeModelInput},
+    pubdata_da::PubdataDA,
     tx::ExecutionMetrics,
-    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, ProtocolVersionId, StorageKey, VmEvent,
-    H256, U256,
+    AccountTreeId, Address, L1BatchNumber, MiniblockNumber, PriorityOpId, ProtocolVersionId,
+    StorageKey, VmEvent, H256, U256,
 };
 use zksync_utils::time::seconds_since_epoch;
 
 use self::tester::Tester;
 use crate::{
     state_keeper::{
-        io::{MiniblockParams, MiniblockSealer, StateKeeperIO},
+        io::{seal_wal::MiniblockSealWal, MiniblockParams, MiniblockSealer, StateKeeperIO},
         mempool_actor::l2_tx_filter,
         tests::{
             create_execution_result, create_transaction, create_updates_manager,
             default_l1_batch_env, default_system_env, default_vm_block_result, Query,
         },
         updates::{MiniblockSealCommand, MiniblockUpdates, UpdatesManager},
+        ConfiguredTransactionFilter, MempoolGuard,
     },
-    utils::testonly::prepare_recovery_snapshot,
+    utils::testonly::{create_l2_transaction, prepare_recovery_snapshot},
 };
 
 mod tester;
@@ -265,7 +269,7 @@ async fn processing_storage_logs_when_sealing_miniblock() {
     conn.protocol_versions_dal()
         .save_protocol_version_with_tx(Default::default())
         .await;
-    seal_command.seal(&mut conn).await;
+    seal_command.seal(&mut conn).await.unwrap();
 
     // Manually mark the miniblock as executed so that getting touched slots from it works
     conn.blocks_dal()
@@ -343,7 +347,7 @@ async fn processing_events_when_sealing_miniblock() {
     conn.protocol_versions_dal()
         .save_protocol_version_with_tx(Default::default())
         .await;
-    seal_command.seal(&mut conn).await;
+    seal_command.seal(&mut conn).await.unwrap();
 
     let logs = conn
         .events_web3_dal()
@@ -380,7 +384,8 @@ async fn test_miniblock_and_l1_batch_processing(
         .await;
 
     let l1_batch_env = default_l1_batch_env(1, 1, Address::random());
-    let mut updates = UpdatesManager::new(&l1_batch_env, &default_system_env());
+    let mut updates =
+        UpdatesManager::new(&l1_batch_env, &default_system_env(), Some(PubdataDA::Blobs));
 
     let tx = create_transaction(10, 100);
     updates.extend_from_executed_transaction(
@@ -419,6 +424,15 @@ async fn test_miniblock_and_l1_batch_processing(
         .unwrap()
         .expect("No L1 batch #1");
     assert_eq!(l1_batch_header.l2_tx_count, 1);
+    assert_eq!(l1_batch_header.pubdata_mode, Some(PubdataDA::Blobs));
+
+    let l1_batch_details = conn
+        .blocks_web3_dal()
+        .get_l1_batch_details(L1BatchNumber(1))
+        .await
+        .unwrap()
+        .expect("No L1 batch #1");
+    assert_eq!(l1_batch_details.pubdata_mode, Some(PubdataDA::Blobs));
 }
 
 #[tokio::test]
@@ -485,7 +499,7 @@ async fn miniblock_processing_after_snapshot_recovery() {
         snapshot_recovery.miniblock_hash
     );
 
-    let mut updates = UpdatesManager::new(&l1_batch_env, &system_env);
+    let mut updates = UpdatesManager::new(&l1_batch_env, &system_env, None);
 
     let tx_hash = tx.hash();
     updates.extend_from_executed_transaction(
@@ -656,6 +670,134 @@ async fn miniblock_sealer_handle_parallel_processing() {
     sealer_handle.wait_for_all_commands().await;
 }
 
+#[tokio::test]
+async fn replaying_miniblock_seal_wal() {
+    let pool = ConnectionPool::test_pool().await;
+    let tester = Tester::new();
+    tester.genesis(&pool).await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let wal = MiniblockSealWal::new(temp_dir.path()).unwrap();
+
+    let updates_manager = create_updates_manager();
+    for number in [1, 2] {
+        let seal_command = updates_manager.seal_miniblock_command(
+            L1BatchNumber(1),
+            MiniblockNumber(number),
+            Address::default(),
+            false,
+        );
+        wal.push(&seal_command).unwrap();
+    }
+    let commands = wal.commands().unwrap();
+    let miniblock_numbers: Vec<_> = commands.iter().map(|cmd| cmd.miniblock_number).collect();
+    assert_eq!(miniblock_numbers, [MiniblockNumber(1), MiniblockNumber(2)]);
+
+    // Emulate the node being stopped after persisting miniblock #1, but before removing it from the WAL.
+    let mut storage = pool.access_storage().await.unwrap();
+    commands[0].seal(&mut storage).await.unwrap();
+
+    let replayed_count = wal.replay(&pool).await.unwrap();
+    assert_eq!(replayed_count, 1);
+    assert!(wal.commands().unwrap().is_empty());
+    let sealed_miniblock_number = storage
+        .blocks_dal()
+        .get_sealed_miniblock_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_miniblock_number, Some(MiniblockNumber(2)));
+
+    let replayed_count = wal.replay(&pool).await.unwrap();
+    assert_eq!(replayed_count, 0);
+}
+
+#[tokio::test]
+async fn miniblock_sealer_with_wal_replays_commands_after_crash() {
+    let pool = ConnectionPool::test_pool().await;
+    let tester = Tester::new();
+    tester.genesis(&pool).await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let wal = MiniblockSealWal::new(temp_dir.path()).unwrap();
+
+    let (sealer, mut sealer_handle) = MiniblockSealer::new(pool.clone(), 5);
+    let sealer = sealer.with_wal(wal.clone(), 10);
+    let sealer_task = tokio::spawn(sealer.run());
+    let updates_manager = create_updates_manager();
+    for number in [1, 2] {
+        let seal_command = updates_manager.seal_miniblock_command(
+            L1BatchNumber(1),
+            MiniblockNumber(number),
+            Address::default(),
+            false,
+        );
+        sealer_handle.submit(seal_command).await;
+    }
+    sealer_handle.wait_for_all_commands().await;
+    // Persisted commands must be removed from the WAL.
+    assert!(wal.commands().unwrap().is_empty());
+    drop(sealer_handle);
+    sealer_task.await.unwrap().unwrap();
+
+    // Emulate the node crashing after writing the command to the WAL, but before persisting it to Postgres.
+    let seal_command = updates_manager.seal_miniblock_command(
+        L1BatchNumber(1),
+        MiniblockNumber(3),
+        Address::default(),
+        false,
+    );
+    wal.push(&seal_command).unwrap();
+    drop(wal);
+
+    let wal = MiniblockSealWal::new(temp_dir.path()).unwrap();
+    let replayed_count = wal.replay(&pool).await.unwrap();
+    assert_eq!(replayed_count, 1);
+    assert!(wal.commands().unwrap().is_empty());
+    let sealed_miniblock_number = pool
+        .access_storage()
+        .await
+        .unwrap()
+        .blocks_dal()
+        .get_sealed_miniblock_number()
+        .await
+        .unwrap();
+    assert_eq!(sealed_miniblock_number, Some(MiniblockNumber(3)));
+}
+
+#[tokio::test]
+async fn miniblock_sealer_with_wal_does_not_retry_non_connection_errors() {
+    let pool = ConnectionPool::test_pool().await;
+    let tester = Tester::new();
+    tester.genesis(&pool).await;
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let wal = MiniblockSealWal::new(temp_dir.path()).unwrap();
+
+    let updates_manager = create_updates_manager();
+    let seal_command = updates_manager.seal_miniblock_command(
+        L1BatchNumber(1),
+        MiniblockNumber(1),
+        Address::default(),
+        false,
+    );
+    // Persist the miniblock beforehand, so that persisting it again violates a DB constraint.
+    let mut storage = pool.access_storage().await.unwrap();
+    seal_command.seal(&mut storage).await.unwrap();
+    drop(storage);
+
+    let (sealer, mut sealer_handle) = MiniblockSealer::new(pool, 5);
+    let sealer = sealer.with_wal(wal.clone(), 10);
+    let sealer_task = tokio::spawn(sealer.run());
+    sealer_handle.submit(seal_command).await;
+
+    let err = tokio::time::timeout(Duration::from_secs(10), sealer_task)
+        .await
+        .expect("miniblock sealer keeps retrying a non-connection error")
+        .unwrap()
+        .unwrap_err();
+    let err = format!("{err:#}");
+    assert!(err.contains("miniblock #1"), "{err}");
+    // The command must stay in the WAL, so that it's not lost.
+    assert_eq!(wal.commands().unwrap().len(), 1);
+}
+
 /// Ensure that subsequent miniblocks that belong to the same L1 batch have different timestamps
 #[tokio::test]
 async fn different_timestamp_for_miniblocks_in_same_batch() {
@@ -675,3 +817,122 @@ async fn different_timestamp_for_miniblocks_in_same_batch() {
         .expect("no new miniblock params");
     assert!(miniblock_params.timestamp > current_timestamp);
 }
+
+#[tokio::test]
+async fn rejecting_transactions_not_passing_filter() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let tester = Tester::new();
+    tester.genesis(&connection_pool).await;
+    let (mempool, mut mempool_guard) = tester
+        .create_test_mempool_io(connection_pool.clone(), 1)
+        .await;
+
+    let good_tx = tester.insert_tx(&mut mempool_guard, 100, 100);
+    let denylisted_tx = tester.insert_tx(&mut mempool_guard, 100, 100);
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    for tx in [&good_tx, &denylisted_tx] {
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+    }
+
+    let config = StateKeeperConfig {
+        denylisted_tx_initiators: vec![denylisted_tx.initiator_account()],
+        ..StateKeeperConfig::default()
+    };
+    let mut mempool =
+        mempool.with_transaction_filter(Arc::new(ConfiguredTransactionFilter::new(&config)));
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_secs(2))
+        .await
+        .expect("no transaction");
+    assert_eq!(tx.hash(), good_tx.hash());
+    let tx = mempool.wait_for_next_tx(Duration::from_millis(10)).await;
+    assert!(tx.is_none(), "{tx:?}");
+
+    let rejected_tx = storage
+        .transactions_web3_dal()
+        .get_transaction_details(denylisted_tx.hash())
+        .await
+        .unwrap()
+        .expect("rejected transaction is missing");
+    assert_eq!(rejected_tx.status, TransactionStatus::Failed);
+}
+
+#[tokio::test]
+async fn expiring_transactions_with_passed_deadline() {
+    let connection_pool = ConnectionPool::test_pool().await;
+    let tester = Tester::new();
+    tester.genesis(&connection_pool).await;
+    let (mut mempool, mut mempool_guard) = tester
+        .create_test_mempool_io(connection_pool.clone(), 1)
+        .await;
+
+    let good_tx = create_l2_transaction(100, 100);
+    let expired_tx = create_l2_transaction(100, 100);
+    let mut storage = connection_pool.access_storage().await.unwrap();
+    for tx in [&good_tx, &expired_tx] {
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(tx.clone(), TransactionExecutionMetrics::default())
+            .await;
+    }
+    // The genesis miniblock is sealed, so the deadline of the transaction has passed.
+    let deadline = TransactionDeadline {
+        timestamp: None,
+        block_number: Some(MiniblockNumber(0)),
+    };
+    mempool_guard.insert_with_deadlines(
+        vec![good_tx.clone().into(), expired_tx.clone().into()],
+        HashMap::new(),
+        HashMap::from([(expired_tx.hash(), deadline)]),
+    );
+
+    let tx = mempool
+        .wait_for_next_tx(Duration::from_secs(2))
+        .await
+        .expect("no transaction");
+    assert_eq!(tx.hash(), good_tx.hash());
+    let tx = mempool.wait_for_next_tx(Duration::from_millis(10)).await;
+    assert!(tx.is_none(), "{tx:?}");
+    assert_eq!(mempool_guard.deadline(&expired_tx.hash()), None);
+
+    let expired_tx = storage
+        .transactions_web3_dal()
+        .get_transaction_details(expired_tx.hash())
+        .await
+        .unwrap()
+        .expect("expired transaction is missing");
+    assert_eq!(expired_tx.status, TransactionStatus::Expired);
+}
+
+#[test]
+fn deadlines_of_dropped_transactions_are_pruned() {
+    let mut mempool_guard = MempoolGuard::new(PriorityOpId(0), 100);
+    let replaced_tx = create_l2_transaction(100, 100);
+    let mut replacement_tx = replaced_tx.clone();
+    replacement_tx.set_input(H256::random().0.to_vec(), H256::random());
+    let deadline = TransactionDeadline {
+        timestamp: Some(seconds_since_epoch() + 3_600),
+        block_number: None,
+    };
+
+    mempool_guard.insert_with_deadlines(
+        vec![replaced_tx.clone().into()],
+        HashMap::new(),
+        HashMap::from([(replaced_tx.hash(), deadline)]),
+    );
+    mempool_guard.insert_with_deadlines(
+        vec![replacement_tx.clone().into()],
+        HashMap::new(),
+        HashMap::from([(replacement_tx.hash(), deadline)]),
+    );
+    let mempool_info = mempool_guard.get_mempool_info();
+    assert_eq!(mempool_info.dropped_transactions, [replaced_tx.hash()]);
+    assert_eq!(mempool_guard.deadline(&replaced_tx.hash()), None);
+    assert_eq!(
+        mempool_guard.deadline(&replacement_tx.hash()),
+        Some(deadline)
+    );
+}
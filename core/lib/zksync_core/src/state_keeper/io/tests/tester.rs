@@ -150,6 +150,7 @@ impl Tester {
                 &get_system_smart_contracts(),
                 L1VerifierConfig::default(),
                 Address::zero(),
+                None,
             )
             .await
             .unwrap();
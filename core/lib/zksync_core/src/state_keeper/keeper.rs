@@ -21,7 +21,7 @@ use super::{
     batch_executor::{BatchExecutor, BatchExecutorHandle, TxExecutionResult},
     extractors,
     io::{MiniblockParams, PendingBatchData, StateKeeperIO},
-    metrics::{AGGREGATION_METRICS, KEEPER_METRICS, L1_BATCH_METRICS},
+    metrics::{AGGREGATION_METRICS, KEEPER_METRICS, L1_BATCH_METRICS, SEAL_METRICS},
     seal_criteria::{ConditionalSealer, SealData, SealResolution},
     types::ExecutionMetricsForCriteria,
     updates::UpdatesManager,
@@ -158,7 +158,8 @@ impl ZkSyncStateKeeper {
         };
 
         let protocol_version = system_env.version;
-        let mut updates_manager = UpdatesManager::new(&l1_batch_env, &system_env);
+        let mut updates_manager =
+            UpdatesManager::new(&l1_batch_env, &system_env, self.io.pubdata_da());
 
         let mut protocol_upgrade_tx: Option<ProtocolUpgradeTx> = self
             .load_protocol_upgrade_tx(&pending_miniblocks, protocol_version, l1_batch_env.number)
@@ -183,8 +184,11 @@ impl ZkSyncStateKeeper {
             self.process_l1_batch(&batch_executor, &mut updates_manager, protocol_upgrade_tx)
                 .await?;
 
+            self.record_l1_batch_fullness(&mut updates_manager);
+
             // Finish current batch.
             if !updates_manager.miniblock.executed_transactions.is_empty() {
+                SEAL_METRICS.miniblock_sealed_by("l1_batch_seal");
                 self.io.seal_miniblock(&updates_manager).await;
                 // We've sealed the miniblock that we had, but we still need to setup the timestamp
                 // for the fictive miniblock.
@@ -214,7 +218,7 @@ impl ZkSyncStateKeeper {
 
             // Start the new batch.
             (system_env, l1_batch_env) = self.wait_for_new_batch_params().await?;
-            updates_manager = UpdatesManager::new(&l1_batch_env, &system_env);
+            updates_manager = UpdatesManager::new(&l1_batch_env, &system_env, self.io.pubdata_da());
             batch_executor = self
                 .batch_executor_base
                 .init_batch(
@@ -371,7 +375,7 @@ impl ZkSyncStateKeeper {
                 miniblock_number
             );
             for tx in miniblock.txs {
-                let result = batch_executor.execute_tx(tx.clone()).await;
+                let result = batch_executor.reexecute_tx(tx.clone()).await;
 
                 let TxExecutionResult::Success {
                     tx_result,
@@ -459,6 +463,8 @@ impl ZkSyncStateKeeper {
                     "L1 batch #{} should be sealed unconditionally as per sealing rules",
                     self.io.current_l1_batch_number()
                 );
+                updates_manager.l1_batch.seal_criterion =
+                    Some(self.io.unconditional_seal_criterion());
                 return Ok(());
             }
 
@@ -493,7 +499,7 @@ impl ZkSyncStateKeeper {
             waiting_latency.observe();
 
             let tx_hash = tx.hash();
-            let (seal_resolution, exec_result) = self
+            let (seal_resolution, seal_criterion, exec_result) = self
                 .process_one_tx(batch_executor, updates_manager, tx.clone())
                 .await;
 
@@ -543,12 +549,35 @@ impl ZkSyncStateKeeper {
                      transaction {tx_hash}",
                     self.io.current_l1_batch_number()
                 );
+                updates_manager.l1_batch.seal_criterion = seal_criterion;
                 return Ok(());
             }
         }
         Err(Error::Canceled)
     }
 
+    /// Computes fullness of the L1 batch that is about to be sealed, reports it in metrics and records it
+    /// in `updates_manager` so that it's persisted together with the batch.
+    fn record_l1_batch_fullness(&self, updates_manager: &mut UpdatesManager) {
+        let block_data = SealData {
+            execution_metrics: updates_manager.pending_execution_metrics(),
+            gas_count: updates_manager.pending_l1_gas_count(),
+            cumulative_size: updates_manager.pending_txs_encoding_size(),
+            writes_metrics: updates_manager.storage_writes_deduplicator.metrics(),
+            pubdata_da: updates_manager.pubdata_da(),
+            ..SealData::default()
+        };
+        let fullness = self.sealer.l1_batch_fullness(
+            updates_manager.pending_executed_transactions_len(),
+            &block_data,
+            updates_manager.protocol_version(),
+        );
+        for &(criterion, capacity_filled) in &fullness {
+            SEAL_METRICS.observe_l1_batch_fullness(criterion, capacity_filled);
+        }
+        updates_manager.l1_batch.fullness = fullness;
+    }
+
     async fn process_upgrade_tx(
         &mut self,
         batch_executor: &BatchExecutorHandle,
@@ -559,7 +588,7 @@ impl ZkSyncStateKeeper {
         assert_eq!(updates_manager.pending_executed_transactions_len(), 0);
 
         let tx: Transaction = protocol_upgrade_tx.into();
-        let (seal_resolution, exec_result) = self
+        let (seal_resolution, _, exec_result) = self
             .process_one_tx(batch_executor, updates_manager, tx.clone())
             .await;
 
@@ -615,6 +644,7 @@ impl ZkSyncStateKeeper {
     /// 1. The VM entered an incorrect state (e.g. out of gas). In that case, we must revert the transaction and seal
     /// the block.
     /// 2. Seal manager decided that batch is ready to be sealed.
+    /// Returns the seal resolution together with the name of the criterion responsible for it (if the batch should be sealed).
     /// Note: this method doesn't mutate `updates_manager` in the end. However, reference should be mutable
     /// because we use `apply_and_rollback` method of `updates_manager.storage_writes_deduplicator`.
     async fn process_one_tx(
@@ -622,7 +652,7 @@ impl ZkSyncStateKeeper {
         batch_executor: &BatchExecutorHandle,
         updates_manager: &mut UpdatesManager,
         tx: Transaction,
-    ) -> (SealResolution, TxExecutionResult) {
+    ) -> (SealResolution, Option<&'static str>, TxExecutionResult) {
         let exec_result = batch_executor.execute_tx(tx.clone()).await;
         // All of `TxExecutionResult::BootloaderOutOfGasForTx`, `TxExecutionResult::BootloaderOutOfGasForBlockTip`,
         // `Halt::NotEnoughGasProvided` correspond to out-of-gas errors but of different nature.
@@ -635,7 +665,7 @@ impl ZkSyncStateKeeper {
         // Otherwise, `ExcludeAndSeal` resolution is returned, i.e. batch will be sealed and transaction will be included in the next L1 batch.
 
         let is_first_tx = updates_manager.pending_executed_transactions_len() == 0;
-        let resolution = match &exec_result {
+        let (resolution, seal_criterion) = match &exec_result {
             TxExecutionResult::BootloaderOutOfGasForTx
             | TxExecutionResult::BootloaderOutOfGasForBlockTip
             | TxExecutionResult::RejectedByVm {
@@ -657,10 +687,13 @@ impl ZkSyncStateKeeper {
                     SealResolution::ExcludeAndSeal
                 };
                 AGGREGATION_METRICS.inc(error_message, &resolution);
-                resolution
+                if resolution.should_seal() {
+                    SEAL_METRICS.l1_batch_sealed_by(error_message, &resolution);
+                }
+                (resolution, Some(error_message))
             }
             TxExecutionResult::RejectedByVm { reason } => {
-                SealResolution::Unexecutable(reason.to_string())
+                (SealResolution::Unexecutable(reason.to_string()), None)
             }
             TxExecutionResult::Success {
                 tx_result,
@@ -733,6 +766,7 @@ impl ZkSyncStateKeeper {
                     cumulative_size: encoding_len,
                     writes_metrics: tx_writes_metrics,
                     gas_remaining: *gas_remaining,
+                    pubdata_da: updates_manager.pubdata_da(),
                 };
                 let block_data = SealData {
                     execution_metrics: tx_data.execution_metrics
@@ -744,6 +778,7 @@ impl ZkSyncStateKeeper {
                         + updates_manager.pending_txs_encoding_size(),
                     writes_metrics: block_writes_metrics,
                     gas_remaining: *gas_remaining,
+                    pubdata_da: updates_manager.pubdata_da(),
                 };
 
                 self.sealer.should_seal_l1_batch(
@@ -756,6 +791,6 @@ impl ZkSyncStateKeeper {
                 )
             }
         };
-        (resolution, exec_result)
+        (resolution, seal_criterion, exec_result)
     }
 }
@@ -11,8 +11,9 @@ use zksync_mempool::L2TxFilter;
 #[cfg(test)]
 use zksync_types::H256;
 use zksync_types::{get_nonce_key, Address, Nonce, Transaction, VmVersion};
+use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::KEEPER_METRICS, types::MempoolGuard};
+use super::{batch_executor::BytecodeCompressor, metrics::KEEPER_METRICS, types::MempoolGuard};
 use crate::{fee_model::BatchFeeModelInputProvider, utils::pending_protocol_version};
 
 /// Creates a mempool filter for L2 transactions based on the current L1 gas price.
@@ -40,6 +41,8 @@ pub struct MempoolFetcher {
     sync_interval: Duration,
     sync_batch_size: usize,
     stuck_tx_timeout: Option<Duration>,
+    tolerate_db_outages: bool,
+    bytecode_compressor: Option<BytecodeCompressor>,
     #[cfg(test)]
     transaction_hashes_sender: mpsc::UnboundedSender<Vec<H256>>,
 }
@@ -58,12 +61,30 @@ impl MempoolFetcher {
             sync_interval: config.sync_interval(),
             sync_batch_size: config.sync_batch_size,
             stuck_tx_timeout: config.remove_stuck_txs.then(|| config.stuck_tx_timeout()),
+            tolerate_db_outages: false,
+            bytecode_compressor: None,
             #[cfg(test)]
             transaction_hashes_sender: mpsc::unbounded_channel().0,
         }
     }
 
-    pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+    /// Makes the fetcher tolerate errors syncing the mempool (e.g., caused by a Postgres outage) instead of
+    /// terminating on them. Syncing is retried after the configured sync interval.
+    #[must_use]
+    pub fn with_db_outage_tolerance(mut self) -> Self {
+        self.tolerate_db_outages = true;
+        self
+    }
+
+    /// Makes the fetcher schedule compression of bytecodes published by loaded transactions, so that
+    /// the compression doesn't block transaction execution.
+    #[must_use]
+    pub fn with_bytecode_compressor(mut self, compressor: BytecodeCompressor) -> Self {
+        self.bytecode_compressor = Some(compressor);
+        self
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
         if let Some(stuck_tx_timeout) = self.stuck_tx_timeout {
             let removed_txs = storage
@@ -85,47 +106,110 @@ impl MempoolFetcher {
                 tracing::info!("Stop signal received, mempool is shutting down");
                 break;
             }
-            let latency = KEEPER_METRICS.mempool_sync.start();
-            let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
-            let mempool_info = self.mempool.get_mempool_info();
-            let protocol_version = pending_protocol_version(&mut storage)
-                .await
-                .context("failed getting pending protocol version")?;
+            let all_transactions_loaded = match self.sync_mempool().await {
+                Ok(all_transactions_loaded) => all_transactions_loaded,
+                Err(err) if self.tolerate_db_outages => {
+                    tracing::warn!("Failed syncing mempool, will retry: {err:#}");
+                    true
+                }
+                Err(err) => return Err(err),
+            };
+            if all_transactions_loaded {
+                // We don't check the result: if a stop signal is received, we'll break at the start
+                // of the next iteration.
+                tokio::time::timeout(self.sync_interval, stop_receiver.changed())
+                    .await
+                    .ok();
+            }
+        }
 
-            let l2_tx_filter = l2_tx_filter(
-                self.batch_fee_input_provider.as_ref(),
-                protocol_version.into(),
+        // Stashed and purged accounts are only persisted on the next mempool sync. Persist them now,
+        // so that transactions purged from the mempool aren't resurrected after the restart.
+        let mempool_info = self.mempool.get_mempool_info();
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        storage
+            .transactions_dal()
+            .persist_mempool_accounts(
+                &mempool_info.stashed_accounts,
+                &mempool_info.purged_accounts,
             )
-            .await;
+            .await
+            .context("failed persisting mempool state")?;
+        Ok(())
+    }
 
-            let transactions = storage
+    /// Loads the next batch of transactions from Postgres into the mempool. Returns whether all
+    /// eligible transactions are loaded.
+    ///
+    /// Before loading, pending transactions with passed inclusion deadlines are marked as expired, so that they
+    /// are not loaded. Expired transactions already in the mempool are evicted by the state keeper.
+    async fn sync_mempool(&mut self) -> anyhow::Result<bool> {
+        let latency = KEEPER_METRICS.mempool_sync.start();
+        let mut storage = self.pool.access_storage_tagged("state_keeper").await?;
+        let expired_tx_hashes = storage
+            .transactions_dal()
+            .expire_transactions(seconds_since_epoch())
+            .await
+            .context("failed expiring transactions")?;
+        if !expired_tx_hashes.is_empty() {
+            tracing::info!(
+                "Expired {} transactions with passed inclusion deadlines",
+                expired_tx_hashes.len()
+            );
+            KEEPER_METRICS
+                .expired_transactions
+                .inc_by(expired_tx_hashes.len() as u64);
+        }
+
+        let mempool_info = self.mempool.get_mempool_info();
+        let protocol_version = pending_protocol_version(&mut storage)
+            .await
+            .context("failed getting pending protocol version")?;
+
+        let l2_tx_filter = l2_tx_filter(
+            self.batch_fee_input_provider.as_ref(),
+            protocol_version.into(),
+        )
+        .await;
+
+        let transactions = storage
+            .transactions_dal()
+            .sync_mempool(
+                &mempool_info.stashed_accounts,
+                &mempool_info.purged_accounts,
+                l2_tx_filter.gas_per_pubdata,
+                l2_tx_filter.fee_per_gas,
+                self.sync_batch_size,
+            )
+            .await
+            .context("failed syncing mempool")?;
+        let nonces = get_transaction_nonces(&mut storage, &transactions).await?;
+        let tx_hashes: Vec<_> = transactions.iter().map(Transaction::hash).collect();
+        let deadlines = if tx_hashes.is_empty() {
+            HashMap::new()
+        } else {
+            storage
                 .transactions_dal()
-                .sync_mempool(
-                    &mempool_info.stashed_accounts,
-                    &mempool_info.purged_accounts,
-                    l2_tx_filter.gas_per_pubdata,
-                    l2_tx_filter.fee_per_gas,
-                    self.sync_batch_size,
-                )
+                .get_transaction_deadlines(&tx_hashes)
                 .await
-                .context("failed syncing mempool")?;
-            let nonces = get_transaction_nonces(&mut storage, &transactions).await?;
-            drop(storage);
-
-            #[cfg(test)]
-            {
-                let transaction_hashes = transactions.iter().map(Transaction::hash).collect();
-                self.transaction_hashes_sender.send(transaction_hashes).ok();
-            }
-            let all_transactions_loaded = transactions.len() < self.sync_batch_size;
-            self.mempool.insert(transactions, nonces);
-            latency.observe();
+                .context("failed getting transaction deadlines")?
+        };
+        drop(storage);
 
-            if all_transactions_loaded {
-                tokio::time::sleep(self.sync_interval).await;
+        #[cfg(test)]
+        {
+            self.transaction_hashes_sender.send(tx_hashes).ok();
+        }
+        let all_transactions_loaded = transactions.len() < self.sync_batch_size;
+        if let Some(compressor) = &self.bytecode_compressor {
+            for tx in &transactions {
+                compressor.schedule(tx);
             }
         }
-        Ok(())
+        self.mempool
+            .insert_with_deadlines(transactions, nonces, deadlines);
+        latency.observe();
+        Ok(all_transactions_loaded)
     }
 }
 
@@ -160,9 +244,11 @@ async fn get_transaction_nonces(
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
     use zksync_types::{
-        fee::TransactionExecutionMetrics, L2ChainId, MiniblockNumber, PriorityOpId,
-        ProtocolVersionId, StorageLog, H256,
+        api::{TransactionDeadline, TransactionStatus},
+        fee::TransactionExecutionMetrics,
+        L2ChainId, MiniblockNumber, PriorityOpId, ProtocolVersionId, StorageLog, H256,
     };
     use zksync_utils::u256_to_h256;
 
@@ -179,6 +265,8 @@ mod tests {
         stuck_tx_timeout: 0,
         remove_stuck_txs: false,
         delay_interval: 10,
+        fee_priority_ordering: false,
+        fee_priority_max_wait_ms: None,
     };
 
     #[tokio::test]
@@ -365,4 +453,152 @@ mod tests {
         stop_sender.send_replace(true);
         fetcher_task.await.unwrap().expect("fetcher errored");
     }
+
+    #[tokio::test]
+    async fn persisting_stashed_accounts_on_shutdown() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let mut mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let fee_params_provider = Arc::new(MockBatchFeeParamsProvider::default());
+        let fee_input = fee_params_provider.get_batch_fee_input().await;
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, ProtocolVersionId::latest().into());
+
+        // Use a large sync interval, so that stashed accounts can only be persisted on shutdown.
+        let config = MempoolConfig {
+            sync_interval_ms: 3_600_000,
+            ..TEST_MEMPOOL_CONFIG
+        };
+        let mut fetcher =
+            MempoolFetcher::new(mempool.clone(), fee_params_provider, &config, pool.clone());
+        let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
+        fetcher.transaction_hashes_sender = tx_hashes_sender;
+
+        let cheap_transaction = create_l2_transaction(base_fee, gas_per_pubdata);
+        let cheap_transaction_hash = cheap_transaction.hash();
+        // Ensure that transactions have distinct receipt timestamps, so that their mempool ordering is deterministic.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let expensive_transaction = create_l2_transaction(base_fee * 2, gas_per_pubdata);
+        let expensive_transaction_hash = expensive_transaction.hash();
+        let mut storage = pool.access_storage().await.unwrap();
+        for tx in [cheap_transaction, expensive_transaction] {
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await;
+        }
+        drop(storage);
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let fetcher_task = tokio::spawn(fetcher.run(stop_receiver));
+        let mut tx_hashes = wait_for_new_transactions(&mut tx_hashes_receiver).await;
+        tx_hashes.sort_unstable();
+        let mut expected_tx_hashes = vec![cheap_transaction_hash, expensive_transaction_hash];
+        expected_tx_hashes.sort_unstable();
+        assert_eq!(tx_hashes, expected_tx_hashes);
+
+        // The cheap transaction doesn't match the filter, so its account is stashed.
+        let filter = L2TxFilter {
+            fee_input,
+            fee_per_gas: base_fee + 1,
+            gas_per_pubdata: gas_per_pubdata as u32,
+        };
+        let tx = mempool.next_transaction(&filter).unwrap();
+        assert_eq!(tx.hash(), expensive_transaction_hash);
+        assert_eq!(mempool.stats().l2_transaction_count, 0);
+
+        stop_sender.send_replace(true);
+        fetcher_task.await.unwrap().expect("fetcher errored");
+
+        // The stashed transaction must be returned to the pool of transactions not in the mempool.
+        let mut storage = pool.access_storage().await.unwrap();
+        let transactions = storage
+            .transactions_dal()
+            .sync_mempool(&[], &[], 0, 0, 100)
+            .await
+            .unwrap();
+        let tx_hashes: Vec<_> = transactions.iter().map(Transaction::hash).collect();
+        assert_eq!(tx_hashes, [cheap_transaction_hash]);
+    }
+
+    #[tokio::test]
+    async fn syncing_mempool_with_deadlines() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let mut storage = pool.access_storage().await.unwrap();
+        ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+            .await
+            .unwrap();
+        drop(storage);
+
+        let mempool = MempoolGuard::new(PriorityOpId(0), 100);
+        let fee_params_provider = Arc::new(MockBatchFeeParamsProvider::default());
+        let fee_input = fee_params_provider.get_batch_fee_input().await;
+        let (base_fee, gas_per_pubdata) =
+            derive_base_fee_and_gas_per_pubdata(fee_input, ProtocolVersionId::latest().into());
+
+        let mut fetcher = MempoolFetcher::new(
+            mempool.clone(),
+            fee_params_provider,
+            &TEST_MEMPOOL_CONFIG,
+            pool.clone(),
+        );
+        let (tx_hashes_sender, mut tx_hashes_receiver) = mpsc::unbounded_channel();
+        fetcher.transaction_hashes_sender = tx_hashes_sender;
+
+        // The deadline of the first transaction has passed since the genesis miniblock is sealed.
+        let expired_deadline = TransactionDeadline {
+            timestamp: None,
+            block_number: Some(MiniblockNumber(0)),
+        };
+        let deadline = TransactionDeadline {
+            timestamp: Some(seconds_since_epoch() + 3_600),
+            block_number: Some(MiniblockNumber(100)),
+        };
+        let expired_transaction = create_l2_transaction(base_fee, gas_per_pubdata);
+        let expired_transaction_hash = expired_transaction.hash();
+        let transaction = create_l2_transaction(base_fee, gas_per_pubdata);
+        let transaction_hash = transaction.hash();
+        let mut storage = pool.access_storage().await.unwrap();
+        for (tx, deadline) in [
+            (expired_transaction, expired_deadline),
+            (transaction, deadline),
+        ] {
+            let tx_hash = tx.hash();
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(tx, TransactionExecutionMetrics::default())
+                .await;
+            storage
+                .transactions_dal()
+                .set_transaction_deadline(tx_hash, &deadline)
+                .await
+                .unwrap();
+        }
+        drop(storage);
+
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let fetcher_task = tokio::spawn(fetcher.run(stop_receiver));
+        let tx_hashes = wait_for_new_transactions(&mut tx_hashes_receiver).await;
+        assert_eq!(tx_hashes, [transaction_hash]);
+        assert_eq!(mempool.stats().l2_transaction_count, 1);
+        assert_eq!(mempool.deadline(&transaction_hash), Some(deadline));
+        assert_eq!(mempool.deadline(&expired_transaction_hash), None);
+
+        stop_sender.send_replace(true);
+        fetcher_task.await.unwrap().expect("fetcher errored");
+
+        let mut storage = pool.access_storage().await.unwrap();
+        let details = storage
+            .transactions_web3_dal()
+            .get_transaction_details(expired_transaction_hash)
+            .await
+            .unwrap()
+            .expect("no transaction details");
+        assert_matches!(details.status, TransactionStatus::Expired);
+    }
 }
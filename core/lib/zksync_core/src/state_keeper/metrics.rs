@@ -7,11 +7,13 @@ use std::{
 
 use multivm::interface::VmExecutionResultAndLogs;
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
-    Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    LatencyObserver, Metrics,
 };
 use zksync_mempool::MempoolStore;
-use zksync_types::{tx::tx_execution_info::DeduplicatedWritesMetrics, ProtocolVersionId};
+use zksync_types::{
+    circuit::CircuitFamilyGas, tx::tx_execution_info::DeduplicatedWritesMetrics, ProtocolVersionId,
+};
 
 use super::seal_criteria::SealResolution;
 use crate::metrics::InteractionType;
@@ -69,6 +71,8 @@ pub(crate) struct StateKeeperMetrics {
     pub get_tx_from_mempool: Histogram<Duration>,
     /// Number of transactions rejected by the state keeper.
     pub rejected_transactions: Counter,
+    /// Number of L2 transactions evicted from the mempool because their inclusion deadline has passed.
+    pub expired_transactions: Counter,
     /// Time spent waiting for the hash of a previous L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub wait_for_prev_hash_time: Histogram<Duration>,
@@ -182,6 +186,54 @@ impl TxAggregationMetrics {
 #[vise::register]
 pub(super) static AGGREGATION_METRICS: vise::Global<TxAggregationMetrics> = vise::Global::new();
 
+/// Metrics related to the reasons why L1 batches and miniblocks are sealed, and to the fullness of sealed L1 batches.
+///
+/// Unlike [`TxAggregationMetrics`], which are reported each time a seal criterion is triggered, these metrics
+/// are reported once per sealed L1 batch / miniblock.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_state_keeper")]
+pub(super) struct SealMetrics {
+    /// Number of sealed L1 batches split by the seal criterion that triggered sealing and the seal resolution.
+    /// The resolution is empty for I/O-dependent criteria (e.g., timeouts).
+    l1_batch_sealed_by: Family<TxAggregationLabels, Counter>,
+    /// Number of sealed miniblocks split by the criterion that triggered sealing.
+    #[metrics(labels = ["criterion"])]
+    miniblock_sealed_by: LabeledFamily<&'static str, Counter>,
+    /// Fullness of sealed L1 batches (i.e., the ratio of the used capacity to its limit) split by the seal criterion
+    /// responsible for the capacity (gas, pubdata, circuits, transaction slots).
+    #[metrics(labels = ["criterion"], buckets = Buckets::linear(0.0..=1.0, 0.05))]
+    l1_batch_fullness: LabeledFamily<&'static str, Histogram<f64>>,
+}
+
+impl SealMetrics {
+    pub fn l1_batch_sealed_by(&self, criterion: &'static str, resolution: &SealResolution) {
+        let labels = TxAggregationLabels {
+            criterion,
+            seal_resolution: Some(resolution.into()),
+        };
+        self.l1_batch_sealed_by[&labels].inc();
+    }
+
+    pub fn l1_batch_sealed_by_io_criterion(&self, criterion: &'static str) {
+        let labels = TxAggregationLabels {
+            criterion,
+            seal_resolution: None,
+        };
+        self.l1_batch_sealed_by[&labels].inc();
+    }
+
+    pub fn miniblock_sealed_by(&self, criterion: &'static str) {
+        self.miniblock_sealed_by[&criterion].inc();
+    }
+
+    pub fn observe_l1_batch_fullness(&self, criterion: &'static str, fullness: f64) {
+        self.l1_batch_fullness[&criterion].observe(fullness);
+    }
+}
+
+#[vise::register]
+pub(super) static SEAL_METRICS: vise::Global<SealMetrics> = vise::Global::new();
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
 pub(super) enum L1BatchSealStage {
@@ -194,6 +246,7 @@ pub(super) enum L1BatchSealStage {
     InsertProtectiveReads,
     FilterWrittenSlots,
     InsertInitialWrites,
+    InsertPubdataByContract,
     CommitL1Batch,
 }
 
@@ -206,6 +259,16 @@ const L1_BATCH_SEAL_DELTA_BUCKETS: Buckets = Buckets::values(&[
     0.1, 0.5, 1.0, 5.0, 10.0, 20.0, 30.0, 40.0, 60.0, 90.0, 120.0, 180.0, 240.0, 300.0,
 ]);
 
+/// Circuit family tracked in [`CircuitFamilyGas`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "circuit_family", rename_all = "snake_case")]
+pub(super) enum CircuitFamily {
+    Keccak256,
+    Sha256,
+    Ecrecover,
+    Storage,
+}
+
 /// Metrics related to L1 batch sealing.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_state_keeper_l1_batch")]
@@ -222,6 +285,15 @@ pub(crate) struct L1BatchMetrics {
     /// Number of transactions in a single L1 batch.
     #[metrics(buckets = COUNT_BUCKETS)]
     pub transactions_in_l1_batch: Histogram<usize>,
+    /// Number of contracts that pubdata published in a single L1 batch is attributed to.
+    #[metrics(buckets = COUNT_BUCKETS)]
+    pub contracts_with_pubdata: Histogram<usize>,
+    /// Share of pubdata published in a single L1 batch attributed to the contract with the largest share.
+    #[metrics(buckets = Buckets::linear(0.0..=1.0, 0.1))]
+    pub largest_contract_pubdata_share: Histogram<f64>,
+    /// Computational gas spent in a single L1 batch on opcodes loading a specific circuit family.
+    #[metrics(buckets = Buckets::exponential(1_000.0..=1_000_000_000.0, 4.0))]
+    circuit_family_gas: Family<CircuitFamily, Histogram<u64>>,
     /// Total latency of sealing an L1 batch.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub sealed_time: Histogram<Duration>,
@@ -247,6 +319,18 @@ impl L1BatchMetrics {
             latency_per_unit: &self.sealed_entity_per_unit[&stage],
         }
     }
+
+    pub(super) fn observe_circuit_family_gas(&self, gas: &CircuitFamilyGas) {
+        let families = [
+            (CircuitFamily::Keccak256, gas.keccak256),
+            (CircuitFamily::Sha256, gas.sha256),
+            (CircuitFamily::Ecrecover, gas.ecrecover),
+            (CircuitFamily::Storage, gas.storage),
+        ];
+        for (family, gas) in families {
+            self.circuit_family_gas[&family].observe(gas.into());
+        }
+    }
 }
 
 #[vise::register]
@@ -296,6 +380,8 @@ pub(super) struct MiniblockMetrics {
     /// Latency of a certain operation concerning the seal queue for miniblocks.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub seal_queue_latency: Family<MiniblockQueueStage, Histogram<Duration>>,
+    /// Number of miniblocks buffered in the seal WAL because Postgres is unavailable.
+    pub wal_buffered_miniblocks: Gauge<usize>,
     /// Number of transactions in a single miniblock.
     #[metrics(buckets = Buckets::linear(0.0..=50.0, 5.0))]
     pub transactions_in_miniblock: Histogram<usize>,
@@ -373,9 +459,34 @@ pub(super) enum ExecutorCommand {
     ExecuteTx,
     StartNextMiniblock,
     RollbackLastTx,
+    MakeSnapshot,
+    RollbackToSnapshot,
     FinishBatch,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum BytecodeCompressionCacheResult {
+    Hit,
+    Miss,
+}
+
+/// Kind of divergence between the main VM and the shadow VM executing the same transactions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(super) enum ShadowVmDivergence {
+    /// Execution results (success / revert / halt with the corresponding output) differ.
+    ExecutionResult,
+    /// Refunded gas differs.
+    Refunds,
+    /// Emitted events differ.
+    Events,
+    /// Final values of the written storage slots differ.
+    StorageWrites,
+    /// User L2-to-L1 logs differ.
+    L2ToL1Logs,
+}
+
 const GAS_PER_NANOSECOND_BUCKETS: Buckets = Buckets::values(&[
     0.01, 0.03, 0.1, 0.3, 0.5, 0.75, 1., 1.5, 3., 5., 10., 20., 50.,
 ]);
@@ -395,6 +506,19 @@ pub(super) struct ExecutorMetrics {
     pub computational_gas_per_nanosecond: Histogram<f64>,
     #[metrics(buckets = GAS_PER_NANOSECOND_BUCKETS)]
     pub failed_tx_gas_limit_per_nanosecond: Histogram<f64>,
+    /// Number of lookups in the bytecode compression cache.
+    pub bytecode_compression_cache: Family<BytecodeCompressionCacheResult, Counter>,
+    /// Latency of compressing bytecodes published by a single transaction before its execution.
+    #[metrics(buckets = Buckets::LATENCIES)]
+    pub bytecode_compression_latency: Histogram<Duration>,
+    /// Number of transactions rejected because they have exceeded the execution budget.
+    pub tx_execution_timeouts: Counter,
+    /// Number of L1 batches executed with a shadow VM around protocol upgrades.
+    pub shadow_vm_batches: Counter,
+    /// Number of divergences between the main and the shadow VM, grouped by kind.
+    pub shadow_vm_divergences: Family<ShadowVmDivergence, Counter>,
+    /// Number of L1 batches for which shadow execution was aborted because the shadow VM has panicked.
+    pub shadow_vm_panics: Counter,
 }
 
 #[vise::register]
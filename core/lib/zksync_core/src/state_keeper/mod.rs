@@ -9,24 +9,33 @@ use zksync_dal::ConnectionPool;
 use zksync_object_store::ObjectStore;
 
 pub use self::{
-    batch_executor::{main_executor::MainBatchExecutor, BatchExecutor},
-    io::{mempool::MempoolIO, MiniblockSealer, MiniblockSealerHandle, StateKeeperIO},
+    batch_executor::{main_executor::MainBatchExecutor, BatchExecutor, BytecodeCompressor},
+    health::StateKeeperHealthCheck,
+    io::{
+        mempool::MempoolIO, seal_wal::MiniblockSealWal, MiniblockSealer, MiniblockSealerHandle,
+        StateKeeperIO,
+    },
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
+    pubdata_mode::{PubdataModeSwitch, UnsupportedPubdataMode},
     seal_criteria::SequencerSealer,
+    tx_filter::{ConfiguredTransactionFilter, TransactionFilter},
     types::MempoolGuard,
 };
 use crate::fee_model::BatchFeeModelInputProvider;
 
 mod batch_executor;
 pub(crate) mod extractors;
+mod health;
 pub(crate) mod io;
 mod keeper;
 mod mempool_actor;
 pub(crate) mod metrics;
+mod pubdata_mode;
 pub mod seal_criteria;
 #[cfg(test)]
 pub(crate) mod tests;
+mod tx_filter;
 pub(crate) mod types;
 pub(crate) mod updates;
 
@@ -40,11 +49,13 @@ pub(crate) async fn create_state_keeper(
     pool: ConnectionPool,
     mempool: MempoolGuard,
     batch_fee_input_provider: Arc<dyn BatchFeeModelInputProvider>,
+    pubdata_mode: PubdataModeSwitch,
+    bytecode_compressor: BytecodeCompressor,
     miniblock_sealer_handle: MiniblockSealerHandle,
     object_store: Arc<dyn ObjectStore>,
     stop_receiver: watch::Receiver<bool>,
 ) -> ZkSyncStateKeeper {
-    let batch_executor_base = MainBatchExecutor::new(
+    let mut batch_executor_base = MainBatchExecutor::new(
         db_config.state_keeper_db_path.clone(),
         pool.clone(),
         state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
@@ -53,6 +64,13 @@ pub(crate) async fn create_state_keeper(
         state_keeper_config.enum_index_migration_chunk_size(),
         false,
     );
+    batch_executor_base.set_bytecode_compressor(bytecode_compressor);
+    if let Some(timeout) = state_keeper_config.tx_execution_timeout() {
+        batch_executor_base.set_tx_execution_timeout(timeout);
+    }
+    if let Some(batches) = state_keeper_config.upgrade_shadow_execution_batches {
+        batch_executor_base.set_upgrade_shadow_execution(batches);
+    }
 
     let io = MempoolIO::new(
         mempool,
@@ -67,7 +85,8 @@ pub(crate) async fn create_state_keeper(
         network_config.zksync_network_id,
     )
     .await
-    .expect("Failed initializing main node I/O for state keeper");
+    .expect("Failed initializing main node I/O for state keeper")
+    .with_pubdata_mode(pubdata_mode);
 
     let sealer = SequencerSealer::new(state_keeper_config);
     ZkSyncStateKeeper::new(
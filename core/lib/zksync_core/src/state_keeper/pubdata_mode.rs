@@ -0,0 +1,66 @@
+//! Runtime-switchable pubdata mode used by the state keeper.
+
+use std::sync::Arc;
+
+use tokio::sync::watch;
+use zksync_types::pubdata_da::PubdataDA;
+
+/// Pubdata mode used by the state keeper for newly opened L1 batches. The mode can be switched at runtime
+/// (e.g., via the admin API); already opened L1 batches keep the mode they were opened with.
+#[derive(Debug, Clone)]
+pub struct PubdataModeSwitch {
+    sender: Arc<watch::Sender<PubdataDA>>,
+    blobs_supported: bool,
+}
+
+impl PubdataModeSwitch {
+    /// Creates a switch with the specified initial mode. `blobs_supported` specifies whether the Ethereum sender
+    /// is able to commit L1 batches using blobs; if not, switching to [`PubdataDA::Blobs`] is rejected.
+    pub fn new(initial_mode: PubdataDA, blobs_supported: bool) -> Self {
+        Self {
+            sender: Arc::new(watch::channel(initial_mode).0),
+            blobs_supported,
+        }
+    }
+
+    /// Returns the current pubdata mode.
+    pub fn get(&self) -> PubdataDA {
+        *self.sender.borrow()
+    }
+
+    /// Switches the pubdata mode. Returns an error if the mode is not supported by the node.
+    pub fn set(&self, mode: PubdataDA) -> Result<(), UnsupportedPubdataMode> {
+        if mode == PubdataDA::Blobs && !self.blobs_supported {
+            return Err(UnsupportedPubdataMode(mode));
+        }
+        let prev_mode = self.sender.send_replace(mode);
+        if prev_mode != mode {
+            tracing::info!("Switched pubdata mode from {prev_mode:?} to {mode:?}");
+        }
+        Ok(())
+    }
+}
+
+/// Error returned by [`PubdataModeSwitch::set()`].
+#[derive(Debug, thiserror::Error)]
+#[error("pubdata mode {0:?} is not supported by the Ethereum sender configuration")]
+pub struct UnsupportedPubdataMode(pub PubdataDA);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn switching_pubdata_mode() {
+        let switch = PubdataModeSwitch::new(PubdataDA::Calldata, true);
+        let switch_clone = switch.clone();
+        switch.set(PubdataDA::Blobs).unwrap();
+        assert_eq!(switch_clone.get(), PubdataDA::Blobs);
+        switch_clone.set(PubdataDA::Calldata).unwrap();
+        assert_eq!(switch.get(), PubdataDA::Calldata);
+
+        let switch = PubdataModeSwitch::new(PubdataDA::Calldata, false);
+        switch.set(PubdataDA::Blobs).unwrap_err();
+        assert_eq!(switch.get(), PubdataDA::Calldata);
+    }
+}
@@ -9,7 +9,7 @@ use std::fmt;
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_types::ProtocolVersionId;
 
-use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
+use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS, SEAL_METRICS};
 
 /// Checks if an L1 batch should be sealed after executing a transaction.
 pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
@@ -22,7 +22,8 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
         protocol_version: ProtocolVersionId,
     ) -> Option<&'static str>;
 
-    /// Returns the action that should be taken by the state keeper after executing a transaction.
+    /// Returns the action that should be taken by the state keeper after executing a transaction,
+    /// together with the name of the criterion responsible for the action (`None` if the batch shouldn't be sealed).
     fn should_seal_l1_batch(
         &self,
         l1_batch_number: u32,
@@ -31,7 +32,18 @@ pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
         block_data: &SealData,
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
-    ) -> SealResolution;
+    ) -> (SealResolution, Option<&'static str>);
+
+    /// Returns fullness of an L1 batch with the specified `block_data` for each criterion tracking capacity.
+    /// The default implementation returns an empty list.
+    fn l1_batch_fullness(
+        &self,
+        _tx_count: usize,
+        _block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Vec<(&'static str, f64)> {
+        vec![]
+    }
 }
 
 /// Implementation of [`ConditionalSealer`] used by the main node.
@@ -78,7 +90,7 @@ impl ConditionalSealer for SequencerSealer {
         block_data: &SealData,
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
-    ) -> SealResolution {
+    ) -> (SealResolution, Option<&'static str>) {
         tracing::trace!(
             "Determining seal resolution for L1 batch #{l1_batch_number} with {tx_count} transactions \
              and metrics {:?}",
@@ -86,6 +98,7 @@ impl ConditionalSealer for SequencerSealer {
         );
 
         let mut final_seal_resolution = SealResolution::NoSeal;
+        let mut triggered_criteria = vec![];
         for sealer in &self.sealers {
             let seal_resolution = sealer.should_seal(
                 &self.config,
@@ -104,13 +117,43 @@ impl ConditionalSealer for SequencerSealer {
                         name = sealer.prom_criterion_name()
                     );
                     AGGREGATION_METRICS.inc(sealer.prom_criterion_name(), &seal_resolution);
+                    triggered_criteria
+                        .push((sealer.prom_criterion_name(), seal_resolution.clone()));
                 }
                 SealResolution::NoSeal => { /* Don't do anything */ }
             }
 
             final_seal_resolution = final_seal_resolution.stricter(seal_resolution);
         }
-        final_seal_resolution
+
+        let mut seal_criterion = None;
+        if final_seal_resolution.should_seal() {
+            // Only report criteria that have led to the final resolution; e.g., if one criterion requires to include
+            // the transaction and another one to exclude it, only the latter is reported.
+            for (criterion, resolution) in &triggered_criteria {
+                if *resolution == final_seal_resolution {
+                    SEAL_METRICS.l1_batch_sealed_by(criterion, resolution);
+                    seal_criterion = seal_criterion.or(Some(*criterion));
+                }
+            }
+        }
+        (final_seal_resolution, seal_criterion)
+    }
+
+    fn l1_batch_fullness(
+        &self,
+        tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Vec<(&'static str, f64)> {
+        self.sealers
+            .iter()
+            .filter_map(|sealer| {
+                let capacity_filled =
+                    sealer.capacity_filled(&self.config, tx_count, block_data, protocol_version)?;
+                Some((sealer.prom_criterion_name(), capacity_filled))
+            })
+            .collect()
     }
 }
 
@@ -129,16 +172,23 @@ impl SequencerSealer {
     }
 
     fn default_sealers(config: &StateKeeperConfig) -> Vec<Box<dyn SealCriterion>> {
-        vec![
+        let mut sealers: Vec<Box<dyn SealCriterion>> = vec![
             Box::new(criteria::SlotsCriterion),
             Box::new(criteria::GasCriterion),
             Box::new(criteria::PubDataBytesCriterion {
                 max_pubdata_per_batch: config.max_pubdata_per_batch,
+                max_pubdata_per_batch_with_blobs: config.max_pubdata_per_batch_with_blobs,
             }),
             Box::new(criteria::CircuitsCriterion),
             Box::new(criteria::TxEncodingSizeCriterion),
             Box::new(criteria::GasForBatchTipCriterion),
-        ]
+        ];
+        if let Some(max_witness_input_size) = config.max_witness_input_size_bytes {
+            sealers.push(Box::new(criteria::WitnessInputSizeCriterion {
+                max_witness_input_size,
+            }));
+        }
+        sealers
     }
 }
 
@@ -165,7 +215,7 @@ impl ConditionalSealer for NoopSealer {
         _block_data: &SealData,
         _tx_data: &SealData,
         _protocol_version: ProtocolVersionId,
-    ) -> SealResolution {
-        SealResolution::NoSeal
+    ) -> (SealResolution, Option<&'static str>) {
+        (SealResolution::NoSeal, None)
     }
 }
@@ -43,6 +43,18 @@ impl SealCriterion for GasCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        let gas_count = &block_data.gas_count;
+        let max_gas = gas_count.commit.max(gas_count.prove).max(gas_count.execute);
+        Some(f64::from(max_gas) / f64::from(config.max_single_tx_gas))
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "gas"
     }
@@ -50,9 +62,13 @@ impl SealCriterion for GasCriterion {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
     use zksync_types::block::BlockGasCount;
 
     use super::*;
+    use crate::state_keeper::seal_criteria::criteria::testonly::{
+        check_invariants, seal_data_strategy, seal_input_strategy, SealInput,
+    };
 
     #[test]
     fn test_gas_seal_criterion() {
@@ -68,17 +84,12 @@ mod tests {
 
         // Empty block should fit into gas criterion.
         let empty_block_gas = new_block_gas_count();
-        let empty_block_resolution = criterion.should_seal(
-            &config,
-            0,
-            0,
-            &SealData {
+        let empty_block_resolution = SealInput::new(&config)
+            .with_block_data(SealData {
                 gas_count: empty_block_gas,
                 ..SealData::default()
-            },
-            &SealData::default(),
-            ProtocolVersionId::latest(),
-        );
+            })
+            .resolve(&criterion);
         assert_eq!(empty_block_resolution, SealResolution::NoSeal);
 
         let tx_gas = BlockGasCount {
@@ -87,20 +98,17 @@ mod tests {
             execute: 0,
         };
         // Transaction that needs more gas than a block limit should be unexecutable.
-        let huge_transaction_resolution = criterion.should_seal(
-            &config,
-            0,
-            1,
-            &SealData {
+        let huge_transaction_resolution = SealInput::new(&config)
+            .with_tx_count(1)
+            .with_block_data(SealData {
                 gas_count: empty_block_gas + tx_gas,
                 ..SealData::default()
-            },
-            &SealData {
+            })
+            .with_tx_data(SealData {
                 gas_count: tx_gas,
                 ..SealData::default()
-            },
-            ProtocolVersionId::latest(),
-        );
+            })
+            .resolve(&criterion);
         assert_eq!(
             huge_transaction_resolution,
             SealResolution::Unexecutable("Transaction requires too much gas".into())
@@ -114,36 +122,28 @@ mod tests {
             prove: reject_tx_bound - empty_block_gas.prove,
             execute: reject_tx_bound - empty_block_gas.execute,
         };
-        let resolution_after_first_tx = criterion.should_seal(
-            &config,
-            0,
-            1,
-            &SealData {
+        let tx_data = SealData {
+            gas_count: tx_gas,
+            ..SealData::default()
+        };
+        let resolution_after_first_tx = SealInput::new(&config)
+            .with_tx_count(1)
+            .with_block_data(SealData {
                 gas_count: empty_block_gas + tx_gas,
                 ..SealData::default()
-            },
-            &SealData {
-                gas_count: tx_gas,
-                ..SealData::default()
-            },
-            ProtocolVersionId::latest(),
-        );
+            })
+            .with_tx_data(tx_data.clone())
+            .resolve(&criterion);
         assert_eq!(resolution_after_first_tx, SealResolution::NoSeal);
 
-        let resolution_after_second_tx = criterion.should_seal(
-            &config,
-            0,
-            2,
-            &SealData {
+        let resolution_after_second_tx = SealInput::new(&config)
+            .with_tx_count(2)
+            .with_block_data(SealData {
                 gas_count: empty_block_gas + tx_gas + tx_gas,
                 ..SealData::default()
-            },
-            &SealData {
-                gas_count: tx_gas,
-                ..SealData::default()
-            },
-            ProtocolVersionId::latest(),
-        );
+            })
+            .with_tx_data(tx_data)
+            .resolve(&criterion);
         assert_eq!(resolution_after_second_tx, SealResolution::ExcludeAndSeal);
 
         // Check criterion workflow
@@ -159,20 +159,27 @@ mod tests {
             prove: close_bound + 1,
             execute: close_bound + 1,
         };
-        let resolution_after_first_tx = criterion.should_seal(
-            &config,
-            0,
-            1,
-            &SealData {
+        let resolution_after_first_tx = SealInput::new(&config)
+            .with_tx_count(1)
+            .with_block_data(SealData {
                 gas_count: block_gas,
                 ..SealData::default()
-            },
-            &SealData {
+            })
+            .with_tx_data(SealData {
                 gas_count: tx_gas,
                 ..SealData::default()
-            },
-            ProtocolVersionId::latest(),
-        );
+            })
+            .resolve(&criterion);
         assert_eq!(resolution_after_first_tx, SealResolution::IncludeAndSeal);
     }
+
+    proptest! {
+        #[test]
+        fn gas_criterion_invariants(
+            input in seal_input_strategy(),
+            block_delta in seal_data_strategy(),
+        ) {
+            check_invariants(&GasCriterion, &input, &block_delta)?;
+        }
+    }
 }
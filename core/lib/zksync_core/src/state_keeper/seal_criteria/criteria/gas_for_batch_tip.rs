@@ -41,8 +41,12 @@ impl SealCriterion for GasForBatchTipCriterion {
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
+    use proptest::prelude::*;
 
     use super::*;
+    use crate::state_keeper::seal_criteria::criteria::testonly::{
+        check_invariants, seal_data_strategy, seal_input_strategy, SealInput,
+    };
 
     #[test]
     fn test_gas_for_batch_tip_seal_criterion() {
@@ -56,44 +60,40 @@ mod tests {
             gas_remaining: gas_bootloader_batch_tip_overhead(protocol_version.into()),
             ..Default::default()
         };
-        let almost_full_block_resolution = criterion.should_seal(
-            &config,
-            Default::default(),
-            1,
-            &seal_data,
-            &seal_data,
-            protocol_version,
-        );
+        let almost_full_block_resolution = SealInput::new(&config)
+            .with_tx_count(1)
+            .with_block_data(seal_data.clone())
+            .with_tx_data(seal_data)
+            .resolve(&criterion);
         assert_eq!(almost_full_block_resolution, SealResolution::NoSeal);
 
         let seal_data = SealData {
             gas_remaining: gas_bootloader_batch_tip_overhead(protocol_version.into()) - 1,
             ..Default::default()
         };
-        let full_block_first_tx_resolution = criterion.should_seal(
-            &config,
-            Default::default(),
-            1,
-            &seal_data,
-            &seal_data,
-            protocol_version,
-        );
+        let input = SealInput::new(&config)
+            .with_block_data(seal_data.clone())
+            .with_tx_data(seal_data);
+        let full_block_first_tx_resolution = input.clone().with_tx_count(1).resolve(&criterion);
         assert_matches!(
             full_block_first_tx_resolution,
             SealResolution::Unexecutable(_)
         );
 
-        let full_block_second_tx_resolution = criterion.should_seal(
-            &config,
-            Default::default(),
-            2,
-            &seal_data,
-            &seal_data,
-            protocol_version,
-        );
+        let full_block_second_tx_resolution = input.with_tx_count(2).resolve(&criterion);
         assert_eq!(
             full_block_second_tx_resolution,
             SealResolution::ExcludeAndSeal
         );
     }
+
+    proptest! {
+        #[test]
+        fn gas_for_batch_tip_criterion_invariants(
+            input in seal_input_strategy(),
+            block_delta in seal_data_strategy(),
+        ) {
+            check_invariants(&GasForBatchTipCriterion, &input, &block_delta)?;
+        }
+    }
 }
@@ -51,6 +51,17 @@ where
         }
     }
 
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version_id: ProtocolVersionId,
+    ) -> Option<f64> {
+        let used = T::extract(&block_data.execution_metrics);
+        Some(used as f64 / T::limit_per_block(protocol_version_id) as f64)
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         T::PROM_METRIC_CRITERION_NAME
     }
@@ -76,9 +87,13 @@ impl MetricExtractor for CircuitsCriterion {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
     use zksync_types::circuit::CircuitStatistic;
 
     use super::*;
+    use crate::state_keeper::seal_criteria::criteria::testonly::{
+        check_invariants, seal_data_strategy, seal_input_strategy, SealInput,
+    };
 
     fn get_config() -> StateKeeperConfig {
         StateKeeperConfig {
@@ -88,23 +103,29 @@ mod tests {
         }
     }
 
+    fn resolve_for_block(
+        block_execution_metrics: ExecutionMetrics,
+        criterion: &dyn SealCriterion,
+        protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        SealInput {
+            protocol_version,
+            ..SealInput::new(&get_config())
+        }
+        .with_block_data(SealData {
+            execution_metrics: block_execution_metrics,
+            ..SealData::default()
+        })
+        .resolve(criterion)
+    }
+
     fn test_no_seal_block_resolution(
         block_execution_metrics: ExecutionMetrics,
         criterion: &dyn SealCriterion,
         protocol_version: ProtocolVersionId,
     ) {
-        let config = get_config();
-        let block_resolution = criterion.should_seal(
-            &config,
-            Default::default(),
-            0,
-            &SealData {
-                execution_metrics: block_execution_metrics,
-                ..SealData::default()
-            },
-            &SealData::default(),
-            protocol_version,
-        );
+        let block_resolution =
+            resolve_for_block(block_execution_metrics, criterion, protocol_version);
         assert_eq!(block_resolution, SealResolution::NoSeal);
     }
 
@@ -113,18 +134,8 @@ mod tests {
         criterion: &dyn SealCriterion,
         protocol_version: ProtocolVersionId,
     ) {
-        let config = get_config();
-        let block_resolution = criterion.should_seal(
-            &config,
-            Default::default(),
-            0,
-            &SealData {
-                execution_metrics: block_execution_metrics,
-                ..SealData::default()
-            },
-            &SealData::default(),
-            protocol_version,
-        );
+        let block_resolution =
+            resolve_for_block(block_execution_metrics, criterion, protocol_version);
         assert_eq!(block_resolution, SealResolution::IncludeAndSeal);
     }
 
@@ -133,18 +144,8 @@ mod tests {
         criterion: &dyn SealCriterion,
         protocol_version: ProtocolVersionId,
     ) {
-        let config = get_config();
-        let block_resolution = criterion.should_seal(
-            &config,
-            Default::default(),
-            0,
-            &SealData {
-                execution_metrics: block_execution_metrics,
-                ..SealData::default()
-            },
-            &SealData::default(),
-            protocol_version,
-        );
+        let block_resolution =
+            resolve_for_block(block_execution_metrics, criterion, protocol_version);
         assert_eq!(block_resolution, SealResolution::ExcludeAndSeal);
     }
 
@@ -153,18 +154,15 @@ mod tests {
         criterion: &dyn SealCriterion,
         protocol_version: ProtocolVersionId,
     ) {
-        let config = get_config();
-        let block_resolution = criterion.should_seal(
-            &config,
-            Default::default(),
-            0,
-            &SealData::default(),
-            &SealData {
-                execution_metrics: tx_execution_metrics,
-                ..SealData::default()
-            },
+        let block_resolution = SealInput {
             protocol_version,
-        );
+            ..SealInput::new(&get_config())
+        }
+        .with_tx_data(SealData {
+            execution_metrics: tx_execution_metrics,
+            ..SealData::default()
+        })
+        .resolve(criterion);
 
         assert_eq!(
             block_resolution,
@@ -229,4 +227,14 @@ mod tests {
 
         test_unexecutable_tx_resolution(tx_execution_metrics, &CircuitsCriterion, protocol_version);
     }
+
+    proptest! {
+        #[test]
+        fn circuits_criterion_invariants(
+            input in seal_input_strategy(),
+            block_delta in seal_data_strategy(),
+        ) {
+            check_invariants(&CircuitsCriterion, &input, &block_delta)?;
+        }
+    }
 }
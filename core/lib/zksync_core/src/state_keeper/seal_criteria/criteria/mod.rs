@@ -3,10 +3,17 @@ mod gas_for_batch_tip;
 mod geometry_seal_criteria;
 mod pubdata_bytes;
 mod slots;
+#[cfg(test)]
+mod testonly;
 mod tx_encoding_size;
+mod witness_input_size;
 
 pub(in crate::state_keeper) use self::{
-    gas::GasCriterion, gas_for_batch_tip::GasForBatchTipCriterion,
-    geometry_seal_criteria::CircuitsCriterion, pubdata_bytes::PubDataBytesCriterion,
-    slots::SlotsCriterion, tx_encoding_size::TxEncodingSizeCriterion,
+    gas::GasCriterion,
+    gas_for_batch_tip::GasForBatchTipCriterion,
+    geometry_seal_criteria::CircuitsCriterion,
+    pubdata_bytes::PubDataBytesCriterion,
+    slots::SlotsCriterion,
+    tx_encoding_size::TxEncodingSizeCriterion,
+    witness_input_size::{WitnessInputSizeCriterion, WitnessInputSizeEstimator},
 };
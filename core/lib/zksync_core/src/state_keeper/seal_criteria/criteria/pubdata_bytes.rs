@@ -1,4 +1,5 @@
-use zksync_types::ProtocolVersionId;
+use multivm::vm_latest::constants::MAX_VM_PUBDATA_PER_BATCH;
+use zksync_types::{pubdata_da::PubdataDA, ProtocolVersionId};
 
 use crate::state_keeper::seal_criteria::{
     SealCriterion, SealData, SealResolution, StateKeeperConfig,
@@ -6,11 +7,26 @@ use crate::state_keeper::seal_criteria::{
 
 #[derive(Debug)]
 pub struct PubDataBytesCriterion {
-    /// This value changes based on the DA solution.
-    /// If we use calldata, the limit is `128kb`
-    /// If we use blobs then the value can be up to `252kb`, up to `126kb` will fill 1 blob,
-    /// more than that will switch over to 2 blobs.
+    /// Pubdata limit for L1 batches publishing pubdata via calldata (would be `128kb`), or batches
+    /// with an unknown pubdata mode.
     pub max_pubdata_per_batch: u64,
+    /// Pubdata limit for L1 batches publishing pubdata via blobs. The limit is capped by the blob capacity
+    /// (`252kb`; up to `126kb` will fill 1 blob, more than that will switch over to 2 blobs), which is also used
+    /// if the limit is not set.
+    pub max_pubdata_per_batch_with_blobs: Option<u64>,
+}
+
+impl PubDataBytesCriterion {
+    fn max_pubdata_per_l1_batch(&self, pubdata_da: Option<PubdataDA>) -> usize {
+        match pubdata_da {
+            Some(PubdataDA::Blobs) => self
+                .max_pubdata_per_batch_with_blobs
+                .map_or(MAX_VM_PUBDATA_PER_BATCH, |limit| {
+                    (limit as usize).min(MAX_VM_PUBDATA_PER_BATCH)
+                }),
+            Some(PubdataDA::Calldata) | None => self.max_pubdata_per_batch as usize,
+        }
+    }
 }
 
 impl SealCriterion for PubDataBytesCriterion {
@@ -23,7 +39,7 @@ impl SealCriterion for PubDataBytesCriterion {
         tx_data: &SealData,
         protocol_version: ProtocolVersionId,
     ) -> SealResolution {
-        let max_pubdata_per_l1_batch = self.max_pubdata_per_batch as usize;
+        let max_pubdata_per_l1_batch = self.max_pubdata_per_l1_batch(block_data.pubdata_da);
         let reject_bound =
             (max_pubdata_per_l1_batch as f64 * config.reject_tx_at_eth_params_percentage).round();
         let include_and_seal_bound =
@@ -51,6 +67,19 @@ impl SealCriterion for PubDataBytesCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        let block_size =
+            block_data.execution_metrics.size() + block_data.writes_metrics.size(protocol_version);
+        let max_pubdata_per_l1_batch = self.max_pubdata_per_l1_batch(block_data.pubdata_da);
+        Some(block_size as f64 / max_pubdata_per_l1_batch as f64)
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "pub_data_size"
     }
@@ -58,9 +87,24 @@ impl SealCriterion for PubDataBytesCriterion {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
     use zksync_types::tx::ExecutionMetrics;
 
     use super::*;
+    use crate::state_keeper::seal_criteria::criteria::testonly::{
+        check_invariants, seal_data_strategy, seal_input_strategy, SealInput,
+    };
+
+    fn block_data(l2_l1_long_messages: usize, pubdata_da: Option<PubdataDA>) -> SealData {
+        SealData {
+            execution_metrics: ExecutionMetrics {
+                l2_l1_long_messages,
+                ..ExecutionMetrics::default()
+            },
+            pubdata_da,
+            ..SealData::default()
+        }
+    }
 
     #[test]
     fn seal_criterion() {
@@ -74,65 +118,79 @@ mod tests {
 
         let criterion = PubDataBytesCriterion {
             max_pubdata_per_batch: 100000,
+            max_pubdata_per_batch_with_blobs: None,
         };
 
-        let block_execution_metrics = ExecutionMetrics {
-            l2_l1_long_messages: (config.max_pubdata_per_batch as f64
-                * config.close_block_at_eth_params_percentage
-                - 1.0)
-                .round() as usize,
-            ..ExecutionMetrics::default()
-        };
-
-        let empty_block_resolution = criterion.should_seal(
-            &config,
-            0,
-            0,
-            &SealData {
-                execution_metrics: block_execution_metrics,
-                ..SealData::default()
-            },
-            &SealData::default(),
-            ProtocolVersionId::latest(),
-        );
+        let l2_l1_long_messages = (config.max_pubdata_per_batch as f64
+            * config.close_block_at_eth_params_percentage
+            - 1.0)
+            .round() as usize;
+        let empty_block_resolution = SealInput::new(&config)
+            .with_block_data(block_data(l2_l1_long_messages, None))
+            .resolve(&criterion);
         assert_eq!(empty_block_resolution, SealResolution::NoSeal);
 
-        let block_execution_metrics = ExecutionMetrics {
-            l2_l1_long_messages: (config.max_pubdata_per_batch as f64
-                * config.close_block_at_eth_params_percentage
-                + 1f64)
-                .round() as usize,
-            ..ExecutionMetrics::default()
-        };
-
-        let full_block_resolution = criterion.should_seal(
-            &config,
-            0,
-            0,
-            &SealData {
-                execution_metrics: block_execution_metrics,
-                ..SealData::default()
-            },
-            &SealData::default(),
-            ProtocolVersionId::latest(),
-        );
+        let l2_l1_long_messages = (config.max_pubdata_per_batch as f64
+            * config.close_block_at_eth_params_percentage
+            + 1f64)
+            .round() as usize;
+        let full_block_resolution = SealInput::new(&config)
+            .with_block_data(block_data(l2_l1_long_messages, None))
+            .resolve(&criterion);
         assert_eq!(full_block_resolution, SealResolution::IncludeAndSeal);
 
-        let block_execution_metrics = ExecutionMetrics {
-            l2_l1_long_messages: config.max_pubdata_per_batch as usize + 1,
-            ..ExecutionMetrics::default()
-        };
-        let full_block_resolution = criterion.should_seal(
-            &config,
-            0,
-            0,
-            &SealData {
-                execution_metrics: block_execution_metrics,
-                ..SealData::default()
-            },
-            &SealData::default(),
-            ProtocolVersionId::latest(),
-        );
+        let l2_l1_long_messages = config.max_pubdata_per_batch as usize + 1;
+        let full_block_resolution = SealInput::new(&config)
+            .with_block_data(block_data(l2_l1_long_messages, None))
+            .resolve(&criterion);
         assert_eq!(full_block_resolution, SealResolution::ExcludeAndSeal);
     }
+
+    #[test]
+    fn seal_criterion_depends_on_pubdata_mode() {
+        let config = StateKeeperConfig {
+            reject_tx_at_eth_params_percentage: 0.95,
+            close_block_at_eth_params_percentage: 0.95,
+            max_pubdata_per_batch: 100000,
+            ..Default::default()
+        };
+        let criterion = PubDataBytesCriterion {
+            max_pubdata_per_batch: 100000,
+            max_pubdata_per_batch_with_blobs: None,
+        };
+
+        let l2_l1_long_messages = config.max_pubdata_per_batch as usize + 1;
+        for (pubdata_da, expected_resolution) in [
+            (None, SealResolution::ExcludeAndSeal),
+            (Some(PubdataDA::Calldata), SealResolution::ExcludeAndSeal),
+            (Some(PubdataDA::Blobs), SealResolution::NoSeal),
+        ] {
+            let resolution = SealInput::new(&config)
+                .with_block_data(block_data(l2_l1_long_messages, pubdata_da))
+                .resolve(&criterion);
+            assert_eq!(resolution, expected_resolution, "{pubdata_da:?}");
+        }
+
+        let resolution = SealInput::new(&config)
+            .with_block_data(block_data(
+                MAX_VM_PUBDATA_PER_BATCH + 1,
+                Some(PubdataDA::Blobs),
+            ))
+            .resolve(&criterion);
+        assert_eq!(resolution, SealResolution::ExcludeAndSeal);
+    }
+
+    proptest! {
+        #[test]
+        fn pubdata_criterion_invariants(
+            input in seal_input_strategy(),
+            block_delta in seal_data_strategy(),
+        ) {
+            let criterion = PubDataBytesCriterion {
+                max_pubdata_per_batch: input.config.max_pubdata_per_batch,
+                max_pubdata_per_batch_with_blobs: input.config.max_pubdata_per_batch_with_blobs,
+            };
+            check_invariants(&criterion, &input, &block_delta)?;
+        }
+    }
 }
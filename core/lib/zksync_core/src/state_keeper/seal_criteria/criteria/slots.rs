@@ -6,6 +6,10 @@ use crate::state_keeper::seal_criteria::{
 };
 
 /// Checks whether we should seal the block because we've run out of transaction slots.
+///
+/// If [`StateKeeperConfig::dynamic_transaction_slots`] is set, the number of slots is only limited by the bootloader;
+/// the space occupied by transaction encodings in the bootloader memory is then the limiting factor, which is enforced
+/// by [`TxEncodingSizeCriterion`](super::TxEncodingSizeCriterion).
 #[derive(Debug)]
 pub struct SlotsCriterion;
 
@@ -26,13 +30,33 @@ impl SealCriterion for SlotsCriterion {
             config.transaction_slots, max_txs_in_batch, protocol_version as u16
         );
 
-        if tx_count >= config.transaction_slots {
+        let transaction_slots = if config.dynamic_transaction_slots {
+            max_txs_in_batch
+        } else {
+            config.transaction_slots
+        };
+        if tx_count >= transaction_slots {
             SealResolution::IncludeAndSeal
         } else {
             SealResolution::NoSeal
         }
     }
 
+    fn capacity_filled(
+        &self,
+        config: &StateKeeperConfig,
+        tx_count: usize,
+        _block_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        let transaction_slots = if config.dynamic_transaction_slots {
+            get_bootloader_max_txs_in_batch(protocol_version.into())
+        } else {
+            config.transaction_slots
+        };
+        Some(tx_count as f64 / transaction_slots as f64)
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "slots"
     }
@@ -40,7 +64,13 @@ impl SealCriterion for SlotsCriterion {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use multivm::utils::get_bootloader_encoding_space;
+    use proptest::prelude::*;
+
+    use super::{super::TxEncodingSizeCriterion, *};
+    use crate::state_keeper::seal_criteria::criteria::testonly::{
+        check_invariants, seal_data_strategy, seal_input_strategy, SealInput,
+    };
 
     #[test]
     fn test_slots_seal_criterion() {
@@ -52,24 +82,88 @@ mod tests {
 
         let criterion = SlotsCriterion;
 
-        let almost_full_block_resolution = criterion.should_seal(
-            &config,
-            Default::default(),
-            config.transaction_slots - 1,
-            &SealData::default(),
-            &SealData::default(),
-            ProtocolVersionId::latest(),
-        );
+        let almost_full_block_resolution = SealInput::new(&config)
+            .with_tx_count(config.transaction_slots - 1)
+            .resolve(&criterion);
         assert_eq!(almost_full_block_resolution, SealResolution::NoSeal);
 
-        let full_block_resolution = criterion.should_seal(
-            &config,
-            Default::default(),
-            config.transaction_slots,
-            &SealData::default(),
-            &SealData::default(),
-            ProtocolVersionId::latest(),
+        let full_block_resolution = SealInput::new(&config)
+            .with_tx_count(config.transaction_slots)
+            .resolve(&criterion);
+        assert_eq!(full_block_resolution, SealResolution::IncludeAndSeal);
+
+        let capacity_filled = SealInput::new(&config)
+            .with_tx_count(1)
+            .capacity_filled(&criterion);
+        assert_eq!(capacity_filled, Some(0.5));
+    }
+
+    #[test]
+    fn dynamic_slots_seal_criterion() {
+        let protocol_version = ProtocolVersionId::latest();
+        let max_txs_in_batch = get_bootloader_max_txs_in_batch(protocol_version.into());
+        let encoding_space = get_bootloader_encoding_space(protocol_version.into()) as usize;
+        // `transaction_slots` must be ignored.
+        let config = StateKeeperConfig {
+            transaction_slots: 2,
+            dynamic_transaction_slots: true,
+            ..Default::default()
+        };
+        let criterion = SlotsCriterion;
+
+        let small_txs_resolution = SealInput::new(&config)
+            .with_tx_count(10)
+            .with_block_data(SealData {
+                cumulative_size: 10 * 100,
+                ..SealData::default()
+            })
+            .resolve(&criterion);
+        assert_eq!(small_txs_resolution, SealResolution::NoSeal);
+
+        // Encoding space is enforced and reported by `TxEncodingSizeCriterion`.
+        let large_txs_input = SealInput::new(&config)
+            .with_tx_count(10)
+            .with_block_data(SealData {
+                cumulative_size: encoding_space / 2,
+                ..SealData::default()
+            });
+        assert_eq!(large_txs_input.resolve(&criterion), SealResolution::NoSeal);
+        let capacity_filled = large_txs_input
+            .capacity_filled(&TxEncodingSizeCriterion)
+            .unwrap();
+        assert!((capacity_filled - 0.5).abs() < 1e-6, "{capacity_filled}");
+        let slots_filled = large_txs_input.capacity_filled(&criterion).unwrap();
+        assert!(
+            (slots_filled - 10.0 / max_txs_in_batch as f64).abs() < 1e-6,
+            "{slots_filled}"
         );
+
+        let full_block_resolution = SealInput::new(&config)
+            .with_tx_count(max_txs_in_batch)
+            .resolve(&criterion);
         assert_eq!(full_block_resolution, SealResolution::IncludeAndSeal);
     }
+
+    proptest! {
+        #[test]
+        fn slots_criterion_invariants(
+            input in seal_input_strategy(),
+            block_delta in seal_data_strategy(),
+        ) {
+            check_invariants(&SlotsCriterion, &input, &block_delta)?;
+        }
+
+        #[test]
+        fn slots_criterion_is_monotonic_in_tx_count(
+            input in seal_input_strategy(),
+            extra_tx_count in 0_usize..100,
+        ) {
+            let resolution = input.resolve(&SlotsCriterion);
+            let next_input = input.clone().with_tx_count(input.tx_count + extra_tx_count);
+            let next_resolution = next_input.resolve(&SlotsCriterion);
+            if resolution.should_seal() {
+                prop_assert!(next_resolution.should_seal());
+            }
+        }
+    }
 }
@@ -0,0 +1,332 @@
+//! Test harness for [`SealCriterion`] implementations.
+
+use multivm::{
+    utils::{
+        gas_bootloader_batch_tip_overhead, get_bootloader_encoding_space,
+        get_bootloader_max_txs_in_batch,
+    },
+    vm_latest::constants::MAX_VM_PUBDATA_PER_BATCH,
+};
+use proptest::prelude::*;
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::{
+    block::BlockGasCount,
+    circuit::CircuitStatistic,
+    pubdata_da::PubdataDA,
+    tx::{tx_execution_info::DeduplicatedWritesMetrics, ExecutionMetrics},
+    ProtocolVersionId,
+};
+
+use crate::state_keeper::seal_criteria::{SealCriterion, SealData, SealResolution};
+
+/// Upper bound for `max_single_tx_gas` in generated configs.
+const MAX_SINGLE_TX_GAS: u32 = 80_000_000;
+/// Upper bound for `max_pubdata_per_batch` in generated configs.
+const MAX_PUBDATA_PER_BATCH: u64 = 120_000;
+/// Upper bound for the number of circuits in generated data. Exceeds the circuits limit per L1 batch.
+const MAX_CIRCUITS: f32 = 40_000.0;
+
+/// Inputs for [`SealCriterion::should_seal()`].
+#[derive(Debug, Clone)]
+pub(super) struct SealInput {
+    pub config: StateKeeperConfig,
+    pub tx_count: usize,
+    pub block_data: SealData,
+    pub tx_data: SealData,
+    pub protocol_version: ProtocolVersionId,
+}
+
+impl SealInput {
+    /// Creates input for an empty L1 batch and an empty transaction with the latest protocol version.
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            config: config.clone(),
+            tx_count: 0,
+            block_data: SealData::default(),
+            tx_data: SealData::default(),
+            protocol_version: ProtocolVersionId::latest(),
+        }
+    }
+
+    pub fn with_tx_count(mut self, tx_count: usize) -> Self {
+        self.tx_count = tx_count;
+        self
+    }
+
+    pub fn with_block_data(mut self, block_data: SealData) -> Self {
+        self.block_data = block_data;
+        self
+    }
+
+    pub fn with_tx_data(mut self, tx_data: SealData) -> Self {
+        self.tx_data = tx_data;
+        self
+    }
+
+    pub fn resolve(&self, criterion: &dyn SealCriterion) -> SealResolution {
+        criterion.should_seal(
+            &self.config,
+            0,
+            self.tx_count,
+            &self.block_data,
+            &self.tx_data,
+            self.protocol_version,
+        )
+    }
+
+    pub fn capacity_filled(&self, criterion: &dyn SealCriterion) -> Option<f64> {
+        criterion.capacity_filled(
+            &self.config,
+            self.tx_count,
+            &self.block_data,
+            self.protocol_version,
+        )
+    }
+}
+
+/// Returns seal data with all metrics of `data` increased by the corresponding metrics of `delta`.
+/// The pubdata mode is taken from `data`.
+fn grow_seal_data(data: &SealData, delta: &SealData) -> SealData {
+    let writes = &data.writes_metrics;
+    let writes_delta = &delta.writes_metrics;
+    SealData {
+        execution_metrics: data.execution_metrics + delta.execution_metrics,
+        gas_count: data.gas_count + delta.gas_count,
+        cumulative_size: data.cumulative_size + delta.cumulative_size,
+        writes_metrics: DeduplicatedWritesMetrics {
+            initial_storage_writes: writes.initial_storage_writes
+                + writes_delta.initial_storage_writes,
+            repeated_storage_writes: writes.repeated_storage_writes
+                + writes_delta.repeated_storage_writes,
+            total_updated_values_size: writes.total_updated_values_size
+                + writes_delta.total_updated_values_size,
+        },
+        gas_remaining: data.gas_remaining,
+        pubdata_da: data.pubdata_da,
+    }
+}
+
+/// Generates values up to `max`, with small values being overrepresented so that thresholds
+/// in limits that are much smaller than `max` are regularly crossed.
+fn value_up_to(max: usize) -> impl Strategy<Value = usize> {
+    prop_oneof![0..=max / 16, 0..=max]
+}
+
+fn percentage() -> impl Strategy<Value = f64> {
+    0.5..=1.0_f64
+}
+
+fn config_strategy() -> impl Strategy<Value = StateKeeperConfig> {
+    let max_slots = get_bootloader_max_txs_in_batch(ProtocolVersionId::latest().into());
+    let gas_percentages = (percentage(), percentage());
+    let eth_params_percentages = (percentage(), percentage());
+    let geometry_percentages = (percentage(), percentage());
+    (
+        1..=max_slots,
+        1_000_000..=MAX_SINGLE_TX_GAS,
+        10_000..=MAX_PUBDATA_PER_BATCH,
+        gas_percentages,
+        eth_params_percentages,
+        geometry_percentages,
+        any::<bool>(),
+    )
+        .prop_map(
+            |(
+                transaction_slots,
+                max_single_tx_gas,
+                max_pubdata_per_batch,
+                (reject_tx_at_gas_percentage, close_block_at_gas_percentage),
+                (reject_tx_at_eth_params_percentage, close_block_at_eth_params_percentage),
+                (reject_tx_at_geometry_percentage, close_block_at_geometry_percentage),
+                dynamic_transaction_slots,
+            )| StateKeeperConfig {
+                transaction_slots,
+                dynamic_transaction_slots,
+                max_single_tx_gas,
+                max_pubdata_per_batch,
+                reject_tx_at_gas_percentage,
+                close_block_at_gas_percentage,
+                reject_tx_at_eth_params_percentage,
+                close_block_at_eth_params_percentage,
+                reject_tx_at_geometry_percentage,
+                close_block_at_geometry_percentage,
+                ..StateKeeperConfig::default()
+            },
+        )
+}
+
+fn execution_metrics_strategy() -> impl Strategy<Value = ExecutionMetrics> {
+    (
+        value_up_to(MAX_VM_PUBDATA_PER_BATCH),
+        value_up_to(MAX_VM_PUBDATA_PER_BATCH),
+        value_up_to(1_000),
+        value_up_to(2 * MAX_VM_PUBDATA_PER_BATCH),
+        prop_oneof![0.0..=MAX_CIRCUITS / 16.0, 0.0..=MAX_CIRCUITS],
+    )
+        .prop_map(
+            |(
+                l2_l1_long_messages,
+                published_bytecode_bytes,
+                l2_to_l1_logs,
+                pubdata_published,
+                circuits,
+            )| ExecutionMetrics {
+                l2_l1_long_messages,
+                published_bytecode_bytes,
+                l2_to_l1_logs,
+                pubdata_published: pubdata_published as u32,
+                circuit_statistic: CircuitStatistic {
+                    main_vm: circuits,
+                    ..CircuitStatistic::default()
+                },
+                ..ExecutionMetrics::default()
+            },
+        )
+}
+
+fn gas_count_strategy() -> impl Strategy<Value = BlockGasCount> {
+    let max_gas = 2 * MAX_SINGLE_TX_GAS as usize;
+    (
+        value_up_to(max_gas),
+        value_up_to(max_gas),
+        value_up_to(max_gas),
+    )
+        .prop_map(|(commit, prove, execute)| BlockGasCount {
+            commit: commit as u32,
+            prove: prove as u32,
+            execute: execute as u32,
+        })
+}
+
+fn writes_metrics_strategy() -> impl Strategy<Value = DeduplicatedWritesMetrics> {
+    (value_up_to(1_000), value_up_to(1_000), value_up_to(10_000)).prop_map(
+        |(initial_storage_writes, repeated_storage_writes, total_updated_values_size)| {
+            DeduplicatedWritesMetrics {
+                initial_storage_writes,
+                repeated_storage_writes,
+                total_updated_values_size,
+            }
+        },
+    )
+}
+
+pub(super) fn seal_data_strategy() -> impl Strategy<Value = SealData> {
+    let protocol_version = ProtocolVersionId::latest().into();
+    let encoding_space = get_bootloader_encoding_space(protocol_version) as usize;
+    let batch_tip_overhead = gas_bootloader_batch_tip_overhead(protocol_version) as usize;
+    (
+        execution_metrics_strategy(),
+        gas_count_strategy(),
+        value_up_to(2 * encoding_space),
+        writes_metrics_strategy(),
+        0..=2 * batch_tip_overhead,
+        prop::option::of(prop_oneof![
+            Just(PubdataDA::Calldata),
+            Just(PubdataDA::Blobs)
+        ]),
+    )
+        .prop_map(
+            |(
+                execution_metrics,
+                gas_count,
+                cumulative_size,
+                writes_metrics,
+                gas_remaining,
+                pubdata_da,
+            )| SealData {
+                execution_metrics,
+                gas_count,
+                cumulative_size,
+                writes_metrics,
+                gas_remaining: gas_remaining as u32,
+                pubdata_da,
+            },
+        )
+}
+
+/// Generates inputs where the L1 batch data includes the transaction data.
+pub(super) fn seal_input_strategy() -> impl Strategy<Value = SealInput> {
+    let max_slots = get_bootloader_max_txs_in_batch(ProtocolVersionId::latest().into());
+    (
+        config_strategy(),
+        prop_oneof![Just(1), 1..=2 * max_slots],
+        seal_data_strategy(),
+        seal_data_strategy(),
+    )
+        .prop_map(|(config, tx_count, prev_block_data, tx_data)| SealInput {
+            block_data: grow_seal_data(&prev_block_data, &tx_data),
+            tx_data,
+            ..SealInput::new(&config).with_tx_count(tx_count)
+        })
+}
+
+fn strictness(resolution: &SealResolution) -> u8 {
+    match resolution {
+        SealResolution::NoSeal => 0,
+        SealResolution::IncludeAndSeal => 1,
+        SealResolution::ExcludeAndSeal => 2,
+        SealResolution::Unexecutable(_) => 3,
+    }
+}
+
+/// Checks invariants that must hold for every seal criterion:
+///
+/// - Growing L1 batch data never makes the resolution less strict (e.g., adding pubdata never turns
+///   `ExcludeAndSeal` into `NoSeal`).
+/// - Whether a transaction is unexecutable doesn't depend on the L1 batch data (other than its pubdata mode).
+/// - The reported capacity is consistent with the resolution: an L1 batch that isn't sealed
+///   isn't overfilled, and an L1 batch that doesn't fit the transaction is full.
+pub(super) fn check_invariants(
+    criterion: &dyn SealCriterion,
+    input: &SealInput,
+    block_delta: &SealData,
+) -> Result<(), TestCaseError> {
+    let resolution = input.resolve(criterion);
+    let grown_input = SealInput {
+        block_data: grow_seal_data(&input.block_data, block_delta),
+        ..input.clone()
+    };
+    let grown_resolution = grown_input.resolve(criterion);
+    prop_assert!(
+        strictness(&grown_resolution) >= strictness(&resolution),
+        "Growing L1 batch data changed resolution from {resolution:?} to {grown_resolution:?}"
+    );
+
+    let is_unexecutable = matches!(resolution, SealResolution::Unexecutable(_));
+    prop_assert_eq!(
+        is_unexecutable,
+        matches!(grown_resolution, SealResolution::Unexecutable(_))
+    );
+    let empty_block_input = SealInput {
+        block_data: SealData {
+            pubdata_da: input.block_data.pubdata_da,
+            ..SealData::default()
+        },
+        ..input.clone()
+    };
+    let empty_block_resolution = empty_block_input.resolve(criterion);
+    prop_assert_eq!(
+        is_unexecutable,
+        matches!(empty_block_resolution, SealResolution::Unexecutable(_))
+    );
+
+    if let Some(capacity) = input.capacity_filled(criterion) {
+        prop_assert!(capacity >= 0.0, "Negative capacity: {capacity}");
+        match resolution {
+            SealResolution::NoSeal => {
+                prop_assert!(
+                    capacity <= 1.0,
+                    "L1 batch isn't sealed at capacity {capacity}"
+                );
+            }
+            SealResolution::ExcludeAndSeal => {
+                prop_assert!(
+                    capacity >= 1.0,
+                    "Transaction is excluded at capacity {capacity}"
+                );
+            }
+            _ => { /* no restrictions */ }
+        }
+    }
+    Ok(())
+}
@@ -39,6 +39,18 @@ impl SealCriterion for TxEncodingSizeCriterion {
         }
     }
 
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        protocol_version_id: ProtocolVersionId,
+    ) -> Option<f64> {
+        let bootloader_tx_encoding_space =
+            get_bootloader_encoding_space(protocol_version_id.into());
+        Some(block_data.cumulative_size as f64 / bootloader_tx_encoding_space as f64)
+    }
+
     fn prom_criterion_name(&self) -> &'static str {
         "tx_encoding_size"
     }
@@ -46,7 +58,12 @@ impl SealCriterion for TxEncodingSizeCriterion {
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
+
     use super::*;
+    use crate::state_keeper::seal_criteria::criteria::testonly::{
+        check_invariants, seal_data_strategy, seal_input_strategy, SealInput,
+    };
 
     #[test]
     fn seal_criterion() {
@@ -62,27 +79,15 @@ mod tests {
 
         let criterion = TxEncodingSizeCriterion;
 
-        let empty_block_resolution = criterion.should_seal(
-            &config,
-            0,
-            0,
-            &SealData::default(),
-            &SealData::default(),
-            ProtocolVersionId::latest(),
-        );
+        let empty_block_resolution = SealInput::new(&config).resolve(&criterion);
         assert_eq!(empty_block_resolution, SealResolution::NoSeal);
 
-        let unexecutable_resolution = criterion.should_seal(
-            &config,
-            0,
-            0,
-            &SealData::default(),
-            &SealData {
+        let unexecutable_resolution = SealInput::new(&config)
+            .with_tx_data(SealData {
                 cumulative_size: bootloader_tx_encoding_space as usize + 1,
                 ..SealData::default()
-            },
-            ProtocolVersionId::latest(),
-        );
+            })
+            .resolve(&criterion);
         assert_eq!(
             unexecutable_resolution,
             SealResolution::Unexecutable(
@@ -90,36 +95,36 @@ mod tests {
             )
         );
 
-        let exclude_and_seal_resolution = criterion.should_seal(
-            &config,
-            0,
-            0,
-            &SealData {
+        let small_tx_data = SealData {
+            cumulative_size: 1,
+            ..SealData::default()
+        };
+        let exclude_and_seal_resolution = SealInput::new(&config)
+            .with_block_data(SealData {
                 cumulative_size: bootloader_tx_encoding_space as usize + 1,
                 ..SealData::default()
-            },
-            &SealData {
-                cumulative_size: 1,
-                ..SealData::default()
-            },
-            ProtocolVersionId::latest(),
-        );
+            })
+            .with_tx_data(small_tx_data.clone())
+            .resolve(&criterion);
         assert_eq!(exclude_and_seal_resolution, SealResolution::ExcludeAndSeal);
 
-        let include_and_seal_resolution = criterion.should_seal(
-            &config,
-            0,
-            0,
-            &SealData {
+        let include_and_seal_resolution = SealInput::new(&config)
+            .with_block_data(SealData {
                 cumulative_size: bootloader_tx_encoding_space as usize,
                 ..SealData::default()
-            },
-            &SealData {
-                cumulative_size: 1,
-                ..SealData::default()
-            },
-            ProtocolVersionId::latest(),
-        );
+            })
+            .with_tx_data(small_tx_data)
+            .resolve(&criterion);
         assert_eq!(include_and_seal_resolution, SealResolution::IncludeAndSeal);
     }
+
+    proptest! {
+        #[test]
+        fn tx_encoding_size_criterion_invariants(
+            input in seal_input_strategy(),
+            block_delta in seal_data_strategy(),
+        ) {
+            check_invariants(&TxEncodingSizeCriterion, &input, &block_delta)?;
+        }
+    }
 }
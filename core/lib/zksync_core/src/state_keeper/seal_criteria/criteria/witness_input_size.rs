@@ -0,0 +1,203 @@
+use zksync_types::ProtocolVersionId;
+
+use crate::state_keeper::seal_criteria::{
+    SealCriterion, SealData, SealResolution, StateKeeperConfig,
+};
+
+/// Estimates the size of basic witness inputs for an L1 batch: Merkle paths produced by the metadata calculator,
+/// the witness block state produced by the basic witness input producer, published bytecodes and transaction data.
+///
+/// The estimate is conservative, i.e., it's expected to exceed the actual size of the inputs.
+#[derive(Debug)]
+pub(in crate::state_keeper) struct WitnessInputSizeEstimator;
+
+impl WitnessInputSizeEstimator {
+    /// Serialized size of `StorageLogMetadata` excluding its Merkle path.
+    const STORAGE_LOG_METADATA_SIZE: u64 = 146;
+    /// Number of hashes in a Merkle path stored in the compact form. Hashes for empty subtrees are shared with
+    /// the first path, so this is approximately a binary logarithm of the number of tree leaves (with a safety margin).
+    const MERKLE_PATH_HASH_COUNT: u64 = 64;
+    const HASH_SIZE: u64 = 32;
+    /// Size of an entry in the witness block state (storage key, read value and the initial write flag).
+    const WITNESS_STATE_ENTRY_SIZE: u64 = 52 + 32 + 1;
+
+    const STORAGE_LOG_SIZE: u64 = Self::STORAGE_LOG_METADATA_SIZE
+        + Self::MERKLE_PATH_HASH_COUNT * Self::HASH_SIZE
+        + Self::WITNESS_STATE_ENTRY_SIZE;
+
+    pub fn estimate(data: &SealData) -> u64 {
+        // Merkle paths are produced for each storage slot accessed in the L1 batch. Cold storage accesses
+        // are deduplicated only within a transaction, so they provide an upper bound for the number of accessed slots.
+        // Older VMs don't report storage accesses; for them, only writes can be accounted for.
+        let writes = &data.writes_metrics;
+        let written_slots = writes.initial_storage_writes + writes.repeated_storage_writes;
+        let accessed_slots = data
+            .execution_metrics
+            .storage_accesses
+            .cold
+            .max(written_slots);
+
+        accessed_slots as u64 * Self::STORAGE_LOG_SIZE
+            + data.execution_metrics.published_bytecode_bytes as u64
+            + data.cumulative_size as u64
+    }
+}
+
+/// Seals L1 batches before their estimated basic witness inputs exceed the limit manageable
+/// by the object store and the prover.
+#[derive(Debug)]
+pub struct WitnessInputSizeCriterion {
+    pub max_witness_input_size: u64,
+}
+
+impl SealCriterion for WitnessInputSizeCriterion {
+    fn should_seal(
+        &self,
+        config: &StateKeeperConfig,
+        _block_open_timestamp_ms: u128,
+        _tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let reject_bound =
+            (self.max_witness_input_size as f64 * config.reject_tx_at_geometry_percentage).round();
+        let include_and_seal_bound = (self.max_witness_input_size as f64
+            * config.close_block_at_geometry_percentage)
+            .round();
+
+        let block_size = block_data.estimated_witness_input_size();
+        if tx_data.estimated_witness_input_size() > reject_bound as u64 {
+            let message = "Transaction cannot be proven due to large witness input size";
+            SealResolution::Unexecutable(message.into())
+        } else if block_size > self.max_witness_input_size {
+            SealResolution::ExcludeAndSeal
+        } else if block_size > include_and_seal_bound as u64 {
+            SealResolution::IncludeAndSeal
+        } else {
+            SealResolution::NoSeal
+        }
+    }
+
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        let block_size = block_data.estimated_witness_input_size();
+        Some(block_size as f64 / self.max_witness_input_size as f64)
+    }
+
+    fn prom_criterion_name(&self) -> &'static str {
+        "witness_input_size"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+    use zksync_types::tx::{
+        tx_execution_info::{DeduplicatedWritesMetrics, StorageAccessStatistic},
+        ExecutionMetrics,
+    };
+
+    use super::*;
+    use crate::state_keeper::seal_criteria::criteria::testonly::{
+        check_invariants, seal_data_strategy, seal_input_strategy, SealInput,
+    };
+
+    const MAX_WITNESS_INPUT_SIZE: u64 = 2 << 20;
+
+    fn seal_data(cold_storage_accesses: usize, initial_storage_writes: usize) -> SealData {
+        SealData {
+            execution_metrics: ExecutionMetrics {
+                storage_accesses: StorageAccessStatistic {
+                    cold: cold_storage_accesses,
+                    warm: 0,
+                },
+                ..ExecutionMetrics::default()
+            },
+            writes_metrics: DeduplicatedWritesMetrics {
+                initial_storage_writes,
+                ..DeduplicatedWritesMetrics::default()
+            },
+            ..SealData::default()
+        }
+    }
+
+    #[test]
+    fn estimating_witness_input_size() {
+        let size = WitnessInputSizeEstimator::STORAGE_LOG_SIZE;
+        assert_eq!(seal_data(0, 0).estimated_witness_input_size(), 0);
+        assert_eq!(seal_data(10, 5).estimated_witness_input_size(), 10 * size);
+        // Writes must be accounted for even if storage accesses are not reported.
+        assert_eq!(seal_data(0, 5).estimated_witness_input_size(), 5 * size);
+
+        let data = SealData {
+            cumulative_size: 1_000,
+            ..seal_data(1, 0)
+        };
+        assert_eq!(data.estimated_witness_input_size(), size + 1_000);
+    }
+
+    #[test]
+    fn seal_criterion() {
+        let config = StateKeeperConfig {
+            reject_tx_at_geometry_percentage: 0.9,
+            close_block_at_geometry_percentage: 0.95,
+            ..Default::default()
+        };
+        let criterion = WitnessInputSizeCriterion {
+            max_witness_input_size: MAX_WITNESS_INPUT_SIZE,
+        };
+        let max_slots =
+            (MAX_WITNESS_INPUT_SIZE / WitnessInputSizeEstimator::STORAGE_LOG_SIZE) as usize;
+
+        let empty_block_resolution = SealInput::new(&config).resolve(&criterion);
+        assert_eq!(empty_block_resolution, SealResolution::NoSeal);
+
+        let unexecutable_resolution = SealInput::new(&config)
+            .with_tx_data(seal_data(max_slots, 0))
+            .resolve(&criterion);
+        assert_eq!(
+            unexecutable_resolution,
+            SealResolution::Unexecutable(
+                "Transaction cannot be proven due to large witness input size".into()
+            )
+        );
+
+        let small_tx_data = seal_data(1, 0);
+        let exclude_and_seal_resolution = SealInput::new(&config)
+            .with_block_data(seal_data(max_slots + 1, 0))
+            .with_tx_data(small_tx_data.clone())
+            .resolve(&criterion);
+        assert_eq!(exclude_and_seal_resolution, SealResolution::ExcludeAndSeal);
+
+        let include_and_seal_resolution = SealInput::new(&config)
+            .with_block_data(seal_data(max_slots, 0))
+            .with_tx_data(small_tx_data.clone())
+            .resolve(&criterion);
+        assert_eq!(include_and_seal_resolution, SealResolution::IncludeAndSeal);
+
+        let no_seal_resolution = SealInput::new(&config)
+            .with_block_data(seal_data(max_slots / 2, 0))
+            .with_tx_data(small_tx_data)
+            .resolve(&criterion);
+        assert_eq!(no_seal_resolution, SealResolution::NoSeal);
+    }
+
+    proptest! {
+        #[test]
+        fn witness_input_size_criterion_invariants(
+            input in seal_input_strategy(),
+            block_delta in seal_data_strategy(),
+        ) {
+            let criterion = WitnessInputSizeCriterion {
+                max_witness_input_size: MAX_WITNESS_INPUT_SIZE,
+            };
+            check_invariants(&criterion, &input, &block_delta)?;
+        }
+    }
+}
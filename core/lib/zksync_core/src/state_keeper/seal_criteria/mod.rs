@@ -17,6 +17,7 @@ use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_types::{
     block::BlockGasCount,
     fee::TransactionExecutionMetrics,
+    pubdata_da::PubdataDA,
     tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
     ProtocolVersionId, Transaction,
 };
@@ -26,7 +27,11 @@ mod conditional_sealer;
 pub(super) mod criteria;
 
 pub use self::conditional_sealer::{ConditionalSealer, NoopSealer, SequencerSealer};
-use super::{extractors, metrics::AGGREGATION_METRICS, updates::UpdatesManager};
+use super::{
+    extractors,
+    metrics::{AGGREGATION_METRICS, SEAL_METRICS},
+    updates::UpdatesManager,
+};
 use crate::gas_tracker::{gas_count_from_tx_and_metrics, gas_count_from_writes};
 
 /// Reported decision regarding block sealing.
@@ -75,13 +80,15 @@ impl SealResolution {
 
 /// Information about transaction or block applicable either to a single transaction, or
 /// to the entire miniblock / L1 batch.
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct SealData {
     pub(super) execution_metrics: ExecutionMetrics,
     pub(super) gas_count: BlockGasCount,
     pub(super) cumulative_size: usize,
     pub(super) writes_metrics: DeduplicatedWritesMetrics,
     pub(super) gas_remaining: u32,
+    /// Pubdata mode of the L1 batch, if known.
+    pub(super) pubdata_da: Option<PubdataDA>,
 }
 
 impl SealData {
@@ -102,8 +109,14 @@ impl SealData {
             cumulative_size: transaction.bootloader_encoding_size(),
             writes_metrics,
             gas_remaining: tx_metrics.gas_remaining,
+            pubdata_da: None,
         }
     }
+
+    /// Returns the estimated size of basic witness inputs for the transaction / L1 batch.
+    pub(super) fn estimated_witness_input_size(&self) -> u64 {
+        criteria::WitnessInputSizeEstimator::estimate(self)
+    }
 }
 
 pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
@@ -117,6 +130,18 @@ pub(super) trait SealCriterion: fmt::Debug + Send + Sync + 'static {
         protocol_version: ProtocolVersionId,
     ) -> SealResolution;
 
+    /// Returns the used share of the L1 batch capacity tracked by this criterion (e.g., 0.5 if the batch is half-full),
+    /// or `None` if the criterion doesn't track capacity. Used for metrics only.
+    fn capacity_filled(
+        &self,
+        _config: &StateKeeperConfig,
+        _tx_count: usize,
+        _block_data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<f64> {
+        None
+    }
+
     // We need self here only for rust restrictions for creating an object from trait
     // https://doc.rust-lang.org/reference/items/traits.html#object-safety
     fn prom_criterion_name(&self) -> &'static str;
@@ -129,6 +154,11 @@ pub trait IoSealCriteria {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool;
     /// Checks whether a miniblock should be sealed given the provided `manager` state.
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool;
+
+    /// Returns the name of the criterion persisted for L1 batches sealed unconditionally.
+    fn unconditional_seal_criterion(&self) -> &'static str {
+        "io"
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -138,6 +168,8 @@ pub(super) struct TimeoutSealer {
 }
 
 impl TimeoutSealer {
+    const L1_BATCH_RULE_NAME: &'static str = "no_txs_timeout";
+
     pub fn new(config: &StateKeeperConfig) -> Self {
         Self {
             block_commit_deadline_ms: config.block_commit_deadline_ms,
@@ -148,7 +180,7 @@ impl TimeoutSealer {
 
 impl IoSealCriteria for TimeoutSealer {
     fn should_seal_l1_batch_unconditionally(&mut self, manager: &UpdatesManager) -> bool {
-        const RULE_NAME: &str = "no_txs_timeout";
+        const RULE_NAME: &str = TimeoutSealer::L1_BATCH_RULE_NAME;
 
         if manager.pending_executed_transactions_len() == 0 {
             // Regardless of which sealers are provided, we never want to seal an empty batch.
@@ -162,6 +194,7 @@ impl IoSealCriteria for TimeoutSealer {
 
         if should_seal_timeout {
             AGGREGATION_METRICS.inc_criterion(RULE_NAME);
+            SEAL_METRICS.l1_batch_sealed_by_io_criterion(RULE_NAME);
             tracing::debug!(
                 "Decided to seal L1 batch using rule `{RULE_NAME}`; batch timestamp: {}, \
                  commit deadline: {block_commit_deadline_ms}ms",
@@ -172,8 +205,18 @@ impl IoSealCriteria for TimeoutSealer {
     }
 
     fn should_seal_miniblock(&mut self, manager: &UpdatesManager) -> bool {
-        !manager.miniblock.executed_transactions.is_empty()
-            && millis_since(manager.miniblock.timestamp) > self.miniblock_commit_deadline_ms
+        const RULE_NAME: &str = "miniblock_timeout";
+
+        let should_seal = !manager.miniblock.executed_transactions.is_empty()
+            && millis_since(manager.miniblock.timestamp) > self.miniblock_commit_deadline_ms;
+        if should_seal {
+            SEAL_METRICS.miniblock_sealed_by(RULE_NAME);
+        }
+        should_seal
+    }
+
+    fn unconditional_seal_criterion(&self) -> &'static str {
+        Self::L1_BATCH_RULE_NAME
     }
 }
 
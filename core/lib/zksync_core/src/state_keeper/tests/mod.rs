@@ -112,12 +112,13 @@ pub(super) fn default_vm_block_result() -> FinishedL1Batch {
         },
         final_bootloader_memory: Some(vec![]),
         pubdata_input: Some(vec![]),
+        pubdata_by_contract: Some(vec![]),
     }
 }
 
 pub(super) fn create_updates_manager() -> UpdatesManager {
     let l1_batch_env = default_l1_batch_env(1, 1, Address::default());
-    UpdatesManager::new(&l1_batch_env, &default_system_env())
+    UpdatesManager::new(&l1_batch_env, &default_system_env(), None)
 }
 
 pub(super) fn create_transaction(fee_per_gas: u64, gas_per_pubdata: u64) -> Transaction {
@@ -152,6 +153,8 @@ pub(super) fn create_execution_result(
             total_log_queries,
             pubdata_published: 0,
             circuit_statistic: Default::default(),
+            storage_accesses: Default::default(),
+            circuit_family_gas: Default::default(),
         },
         refunds: Refunds::default(),
     }
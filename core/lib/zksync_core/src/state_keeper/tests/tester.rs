@@ -17,8 +17,8 @@ use multivm::{
 use tokio::sync::{mpsc, watch};
 use zksync_types::{
     block::MiniblockExecutionData, fee_model::BatchFeeInput, protocol_version::ProtocolUpgradeTx,
-    witness_block_state::WitnessBlockState, Address, L1BatchNumber, L2ChainId, MiniblockNumber,
-    ProtocolVersionId, Transaction, H256,
+    pubdata_da::PubdataDA, witness_block_state::WitnessBlockState, Address, L1BatchNumber,
+    L2ChainId, MiniblockNumber, ProtocolVersionId, Transaction, H256,
 };
 
 use crate::{
@@ -511,7 +511,7 @@ impl TestBatchExecutor {
     pub(super) fn run(mut self) {
         while let Some(cmd) = self.commands.blocking_recv() {
             match cmd {
-                Command::ExecuteTx(tx, resp) => {
+                Command::ExecuteTx(tx, _, resp) => {
                     let result = self
                         .txs
                         .get_mut(&tx.hash())
@@ -544,6 +544,9 @@ impl TestBatchExecutor {
                     // It's OK to not update `last_executed_tx`, since state keeper never should rollback more than 1
                     // tx in a row, and it's going to cause a panic anyway.
                 }
+                Command::MakeSnapshot(_) | Command::RollbackToSnapshot(..) => {
+                    panic!("State keeper is not expected to use executor snapshots");
+                }
                 Command::FinishBatch(resp) => {
                     // Blanket result, it doesn't really matter.
                     resp.send((default_vm_block_result(), None)).unwrap();
@@ -643,6 +646,10 @@ impl StateKeeperIO for TestIO {
         self.miniblock_number
     }
 
+    fn pubdata_da(&self) -> Option<PubdataDA> {
+        None
+    }
+
     async fn load_pending_batch(&mut self) -> anyhow::Result<Option<PendingBatchData>> {
         Ok(self.scenario.pending_batch.take())
     }
@@ -809,9 +816,12 @@ impl BatchExecutor for MockBatchExecutor {
             let mut recv = recv;
             while let Some(cmd) = recv.recv().await {
                 match cmd {
-                    Command::ExecuteTx(_, resp) => resp.send(successful_exec()).unwrap(),
+                    Command::ExecuteTx(_, _, resp) => resp.send(successful_exec()).unwrap(),
                     Command::StartNextMiniblock(_, resp) => resp.send(()).unwrap(),
                     Command::RollbackLastTx(_) => panic!("unexpected rollback"),
+                    Command::MakeSnapshot(_) | Command::RollbackToSnapshot(..) => {
+                        panic!("unexpected snapshot")
+                    }
                     Command::FinishBatch(resp) => {
                         // Blanket result, it doesn't really matter.
                         resp.send((default_vm_block_result(), None)).unwrap();
@@ -0,0 +1,89 @@
+//! Operator-defined policies for transactions included into blocks by the state keeper.
+//!
+//! Unlike seal criteria, transaction filters don't depend on the transaction execution; they are applied
+//! by [`MempoolIO`](super::MempoolIO) to L2 transactions taken from the mempool before they are executed.
+
+use std::{collections::HashSet, fmt};
+
+use zksync_config::configs::chain::StateKeeperConfig;
+use zksync_types::{Address, Transaction};
+
+/// Filter deciding whether an L2 transaction taken from the mempool can be included into a block.
+/// Transactions not passing the filter are rejected.
+pub trait TransactionFilter: 'static + fmt::Debug + Send + Sync {
+    /// Returns a human-readable reason why the transaction should be rejected, or `None` if the transaction
+    /// can be included into a block.
+    fn rejection_reason(&self, tx: &Transaction) -> Option<String>;
+}
+
+/// [`TransactionFilter`] configured using [`StateKeeperConfig`]. Used by the main node.
+#[derive(Debug, Default)]
+pub struct ConfiguredTransactionFilter {
+    denylisted_initiators: HashSet<Address>,
+    max_calldata_size: Option<usize>,
+}
+
+impl ConfiguredTransactionFilter {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            denylisted_initiators: config.denylisted_tx_initiators.iter().copied().collect(),
+            max_calldata_size: config.max_tx_calldata_size,
+        }
+    }
+}
+
+impl TransactionFilter for ConfiguredTransactionFilter {
+    fn rejection_reason(&self, tx: &Transaction) -> Option<String> {
+        let initiator = tx.initiator_account();
+        if self.denylisted_initiators.contains(&initiator) {
+            return Some(format!("initiator {initiator:?} is denylisted"));
+        }
+
+        let calldata_size = tx.execute.calldata.len();
+        if let Some(max_calldata_size) = self.max_calldata_size {
+            if calldata_size > max_calldata_size {
+                return Some(format!(
+                    "calldata size ({calldata_size} bytes) exceeds the limit ({max_calldata_size} bytes)"
+                ));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::testonly::create_l2_transaction;
+
+    fn create_tx(initiator: Address, calldata_size: usize) -> Transaction {
+        let mut tx = create_l2_transaction(10, 100);
+        tx.common_data.initiator_address = initiator;
+        tx.execute.calldata = vec![0; calldata_size];
+        tx.into()
+    }
+
+    #[test]
+    fn configured_filter_rejects_transactions() {
+        let denylisted = Address::repeat_byte(0xde);
+        let config = StateKeeperConfig {
+            denylisted_tx_initiators: vec![denylisted],
+            max_tx_calldata_size: Some(100),
+            ..StateKeeperConfig::for_tests()
+        };
+        let filter = ConfiguredTransactionFilter::new(&config);
+
+        let tx = create_tx(Address::repeat_byte(1), 100);
+        assert_eq!(filter.rejection_reason(&tx), None);
+        let tx = create_tx(denylisted, 10);
+        let reason = filter.rejection_reason(&tx).unwrap();
+        assert!(reason.contains("denylisted"), "{reason}");
+        let tx = create_tx(Address::repeat_byte(1), 101);
+        let reason = filter.rejection_reason(&tx).unwrap();
+        assert!(reason.contains("calldata size"), "{reason}");
+
+        let filter = ConfiguredTransactionFilter::default();
+        let tx = create_tx(denylisted, 1_000);
+        assert_eq!(filter.rejection_reason(&tx), None);
+    }
+}
@@ -4,77 +4,142 @@ use std::{
 };
 
 use multivm::interface::VmExecutionResultAndLogs;
+use zksync_config::configs::chain::MempoolConfig;
 use zksync_dal::StorageProcessor;
-use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolStore};
+use zksync_mempool::{L2TxFilter, MempoolInfo, MempoolOrdering, MempoolStore};
 use zksync_types::{
-    block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce, PriorityOpId, Transaction,
+    api::TransactionDeadline, block::BlockGasCount, tx::ExecutionMetrics, Address, Nonce,
+    PriorityOpId, Transaction, H256,
 };
 
 use super::metrics::StateKeeperGauges;
 use crate::gas_tracker::{gas_count_from_metrics, gas_count_from_tx_and_metrics};
 
 #[derive(Debug, Clone)]
-pub struct MempoolGuard(Arc<Mutex<MempoolStore>>);
+pub struct MempoolGuard {
+    store: Arc<Mutex<MempoolStore>>,
+    /// Inclusion deadlines of L2 transactions in the mempool. Entries are removed once a transaction
+    /// is executed, rejected, expired, or dropped from the mempool store (e.g., replaced by nonce or purged).
+    deadlines: Arc<Mutex<HashMap<H256, TransactionDeadline>>>,
+}
 
 impl MempoolGuard {
-    pub async fn from_storage(storage_processor: &mut StorageProcessor<'_>, capacity: u64) -> Self {
+    pub async fn from_storage(
+        storage_processor: &mut StorageProcessor<'_>,
+        config: &MempoolConfig,
+    ) -> Self {
         let next_priority_id = storage_processor
             .transactions_dal()
             .next_priority_id()
             .await;
-        Self::new(next_priority_id, capacity)
+        let ordering = if config.fee_priority_ordering {
+            MempoolOrdering::FeePriority {
+                max_wait: config.fee_priority_max_wait(),
+            }
+        } else {
+            MempoolOrdering::Fifo
+        };
+        let store = MempoolStore::new(next_priority_id, config.capacity).with_ordering(ordering);
+        Self::from_store(store)
     }
 
     pub(super) fn new(next_priority_id: PriorityOpId, capacity: u64) -> Self {
-        let store = MempoolStore::new(next_priority_id, capacity);
-        Self(Arc::new(Mutex::new(store)))
+        Self::from_store(MempoolStore::new(next_priority_id, capacity))
+    }
+
+    fn from_store(store: MempoolStore) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            deadlines: Arc::default(),
+        }
     }
 
     pub fn insert(&mut self, transactions: Vec<Transaction>, nonces: HashMap<Address, Nonce>) {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .insert(transactions, nonces);
     }
 
+    /// Inserts transactions together with their inclusion deadlines.
+    pub fn insert_with_deadlines(
+        &mut self,
+        transactions: Vec<Transaction>,
+        nonces: HashMap<Address, Nonce>,
+        deadlines: HashMap<H256, TransactionDeadline>,
+    ) {
+        // Deadlines are updated first, so that transactions are never observable without their deadlines.
+        self.deadlines
+            .lock()
+            .expect("failed to acquire deadlines lock")
+            .extend(deadlines);
+        self.insert(transactions, nonces);
+    }
+
+    /// Returns the inclusion deadline of the specified transaction, if any.
+    pub fn deadline(&self, tx_hash: &H256) -> Option<TransactionDeadline> {
+        self.deadlines
+            .lock()
+            .expect("failed to acquire deadlines lock")
+            .get(tx_hash)
+            .copied()
+    }
+
+    /// Forgets inclusion deadlines of the specified transactions (e.g., because they were executed or rejected).
+    pub fn remove_deadlines<'a>(&mut self, tx_hashes: impl IntoIterator<Item = &'a H256>) {
+        let mut deadlines = self
+            .deadlines
+            .lock()
+            .expect("failed to acquire deadlines lock");
+        if deadlines.is_empty() {
+            return;
+        }
+        for tx_hash in tx_hashes {
+            deadlines.remove(tx_hash);
+        }
+    }
+
     pub fn has_next(&self, filter: &L2TxFilter) -> bool {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .has_next(filter)
     }
 
     pub fn next_transaction(&mut self, filter: &L2TxFilter) -> Option<Transaction> {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .next_transaction(filter)
     }
 
     pub fn rollback(&mut self, rejected: &Transaction) {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .rollback(rejected);
     }
 
     pub fn get_mempool_info(&mut self) -> MempoolInfo {
-        self.0
+        let mempool_info = self
+            .store
             .lock()
             .expect("failed to acquire mempool lock")
-            .get_mempool_info()
+            .get_mempool_info();
+        self.remove_deadlines(&mempool_info.dropped_transactions);
+        mempool_info
     }
 
     #[cfg(test)]
     pub fn stats(&self) -> zksync_mempool::MempoolStats {
-        self.0
+        self.store
             .lock()
             .expect("failed to acquire mempool lock")
             .stats()
     }
 
     pub fn register_metrics(&self) {
-        StateKeeperGauges::register(Arc::downgrade(&self.0));
+        StateKeeperGauges::register(Arc::downgrade(&self.store));
     }
 }
 
@@ -16,6 +16,11 @@ pub struct L1BatchUpdates {
     // how much L1 gas will it take to submit this block?
     pub l1_gas_count: BlockGasCount,
     pub txs_encoding_size: usize,
+    /// Name of the criterion that has triggered sealing the batch. `None` if the batch is not sealed yet,
+    /// or if the criterion is unknown (e.g., for batches restored after a restart).
+    pub seal_criterion: Option<&'static str>,
+    /// Fullness of the batch for each seal criterion tracking capacity. Set when the batch is about to be sealed.
+    pub fullness: Vec<(&'static str, f64)>,
 }
 
 impl L1BatchUpdates {
@@ -26,6 +31,8 @@ impl L1BatchUpdates {
             block_execution_metrics: Default::default(),
             l1_gas_count: new_block_gas_count(),
             txs_encoding_size: 0,
+            seal_criterion: None,
+            fullness: vec![],
         }
     }
 
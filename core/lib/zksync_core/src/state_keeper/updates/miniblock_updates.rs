@@ -4,6 +4,7 @@ use multivm::{
     interface::{ExecutionResult, L2BlockEnv, VmExecutionResultAndLogs},
     vm_latest::TransactionVmExt,
 };
+use serde::{Deserialize, Serialize};
 use zksync_types::{
     block::{BlockGasCount, MiniblockHasher},
     event::extract_bytecodes_marked_as_known,
@@ -14,7 +15,7 @@ use zksync_types::{
 };
 use zksync_utils::bytecode::{hash_bytecode, CompressedBytecodeInfo};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MiniblockUpdates {
     pub executed_transactions: Vec<TransactionExecutionResult>,
     pub events: Vec<VmEvent>,
@@ -106,6 +107,10 @@ impl MiniblockUpdates {
             ExecutionResult::Revert { output } => Some(output.to_string()),
             ExecutionResult::Halt { reason } => Some(reason.to_string()),
         };
+        let decoded_revert_reason = match &tx_execution_result.result {
+            ExecutionResult::Revert { output } => output.to_api_revert_reason(),
+            ExecutionResult::Success { .. } | ExecutionResult::Halt { .. } => None,
+        };
 
         // Get transaction factory deps
         let factory_deps = tx.execute.factory_deps.as_deref().unwrap_or_default();
@@ -143,6 +148,7 @@ impl MiniblockUpdates {
             compressed_bytecodes,
             call_traces,
             revert_reason,
+            decoded_revert_reason,
         });
     }
 
@@ -2,9 +2,10 @@ use multivm::{
     interface::{L1BatchEnv, SystemEnv, VmExecutionResultAndLogs},
     utils::get_batch_base_fee,
 };
+use serde::{Deserialize, Serialize};
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_types::{
-    block::BlockGasCount, fee_model::BatchFeeInput,
+    block::BlockGasCount, fee_model::BatchFeeInput, pubdata_da::PubdataDA,
     storage_writes_deduplicator::StorageWritesDeduplicator,
     tx::tx_execution_info::ExecutionMetrics, vm_trace::Call, Address, L1BatchNumber,
     MiniblockNumber, ProtocolVersionId, Transaction,
@@ -31,13 +32,18 @@ pub struct UpdatesManager {
     base_fee_per_gas: u64,
     base_system_contract_hashes: BaseSystemContractsHashes,
     protocol_version: ProtocolVersionId,
+    pubdata_da: Option<PubdataDA>,
     pub l1_batch: L1BatchUpdates,
     pub miniblock: MiniblockUpdates,
     pub storage_writes_deduplicator: StorageWritesDeduplicator,
 }
 
 impl UpdatesManager {
-    pub(crate) fn new(l1_batch_env: &L1BatchEnv, system_env: &SystemEnv) -> Self {
+    pub(crate) fn new(
+        l1_batch_env: &L1BatchEnv,
+        system_env: &SystemEnv,
+        pubdata_da: Option<PubdataDA>,
+    ) -> Self {
         let protocol_version = system_env.version;
         Self {
             batch_timestamp: l1_batch_env.timestamp,
@@ -45,6 +51,7 @@ impl UpdatesManager {
             batch_fee_input: l1_batch_env.fee_input,
             base_fee_per_gas: get_batch_base_fee(l1_batch_env, protocol_version.into()),
             protocol_version,
+            pubdata_da,
             base_system_contract_hashes: system_env.base_system_smart_contracts.hashes(),
             l1_batch: L1BatchUpdates::new(),
             miniblock: MiniblockUpdates::new(
@@ -66,6 +73,10 @@ impl UpdatesManager {
         self.base_system_contract_hashes
     }
 
+    pub(crate) fn pubdata_da(&self) -> Option<PubdataDA> {
+        self.pubdata_da
+    }
+
     pub(crate) fn seal_miniblock_command(
         &self,
         l1_batch_number: L1BatchNumber,
@@ -158,7 +169,7 @@ impl UpdatesManager {
 }
 
 /// Command to seal a miniblock containing all necessary data for it.
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct MiniblockSealCommand {
     pub l1_batch_number: L1BatchNumber,
     pub miniblock_number: MiniblockNumber,
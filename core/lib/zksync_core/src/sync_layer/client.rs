@@ -181,6 +181,11 @@ impl CachingMainNodeClient {
         }
     }
 
+    /// Returns the wrapped client, discarding the cache.
+    pub fn into_inner(self) -> Box<dyn MainNodeClient> {
+        self.client
+    }
+
     /// Cached version of [`HttpClient::sync_l2_block`].
     pub async fn fetch_l2_block(
         &mut self,
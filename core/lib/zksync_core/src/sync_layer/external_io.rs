@@ -8,8 +8,8 @@ use zksync_contracts::{BaseSystemContracts, SystemContractCode};
 use zksync_dal::ConnectionPool;
 use zksync_types::{
     ethabi::Address, fee_model::BatchFeeInput, protocol_version::ProtocolUpgradeTx,
-    witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId, MiniblockNumber,
-    ProtocolVersionId, Transaction, H256,
+    pubdata_da::PubdataDA, witness_block_state::WitnessBlockState, L1BatchNumber, L2ChainId,
+    MiniblockNumber, ProtocolVersionId, Transaction, H256,
 };
 use zksync_utils::bytes_to_be_words;
 
@@ -271,6 +271,10 @@ impl StateKeeperIO for ExternalIO {
         self.current_miniblock_number
     }
 
+    fn pubdata_da(&self) -> Option<PubdataDA> {
+        None // The pubdata mode is not synced from the main node
+    }
+
     async fn load_pending_batch(&mut self) -> anyhow::Result<Option<PendingBatchData>> {
         let mut storage = self.pool.access_storage_tagged("sync_layer").await?;
 
@@ -540,7 +544,7 @@ impl StateKeeperIO for ExternalIO {
                 finished_batch,
                 self.l2_erc20_bridge_addr,
             )
-            .await;
+            .await?;
         drop(storage);
 
         self.update_miniblock_fields(&fictive_miniblock);
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Context as _;
 use tokio::sync::watch;
@@ -227,6 +227,29 @@ impl MainNodeFetcher {
         })
     }
 
+    /// Creates a fetcher continuing from the provided cursor, e.g. one previously used by another fetcher.
+    pub(crate) fn from_parts(
+        client: Box<dyn MainNodeClient>,
+        cursor: IoCursor,
+        actions: ActionQueueSender,
+        sync_state: SyncState,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> Self {
+        Self {
+            client: CachingMainNodeClient::new(client),
+            cursor,
+            actions,
+            sync_state,
+            stop_receiver,
+        }
+    }
+
+    /// Returns the main node client, the cursor and the actions queue, so that another fetcher can continue
+    /// from where this one stopped.
+    pub(crate) fn into_parts(self) -> (Box<dyn MainNodeClient>, IoCursor, ActionQueueSender) {
+        (self.client.into_inner(), self.cursor, self.actions)
+    }
+
     pub async fn run(mut self) -> anyhow::Result<()> {
         tracing::info!(
             "Starting the fetcher routine. Initial miniblock: {}, initial l1 batch: {}",
@@ -250,6 +273,44 @@ impl MainNodeFetcher {
         }
     }
 
+    /// Runs the fetcher until the stop signal is received, or until the main node is unavailable
+    /// (i.e., transport errors occur) for at least `unavailable_timeout`. Returns `true` in the latter case.
+    pub(crate) async fn run_until_unavailable(
+        &mut self,
+        unavailable_timeout: Duration,
+    ) -> anyhow::Result<bool> {
+        loop {
+            match self.run_inner().await {
+                Ok(()) => return Ok(false),
+                Err(err) if err.is_transient() => {
+                    tracing::warn!("Following transport error occurred: {err}");
+                    if !self.wait_for_main_node(unavailable_timeout).await {
+                        tracing::warn!(
+                            "Main node is unavailable for {unavailable_timeout:?}, stopping the fetcher routine"
+                        );
+                        return Ok(true);
+                    }
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+
+    /// Waits until the main node responds to requests or the stop signal is received, but no longer than `timeout`.
+    /// Returns `false` if the main node remained unavailable.
+    async fn wait_for_main_node(&self, timeout: Duration) -> bool {
+        let started_at = Instant::now();
+        loop {
+            tokio::time::sleep(RETRY_DELAY_INTERVAL.min(timeout)).await;
+            if self.check_if_cancelled() || self.client.fetch_l2_block_number().await.is_ok() {
+                return true;
+            }
+            if started_at.elapsed() >= timeout {
+                return false;
+            }
+        }
+    }
+
     fn check_if_cancelled(&self) -> bool {
         *self.stop_receiver.borrow()
     }
@@ -310,6 +371,7 @@ impl MainNodeFetcher {
         let prev_miniblock_number = MiniblockNumber(block_number.0.saturating_sub(1));
         self.client.forget_miniblock(prev_miniblock_number);
         self.actions.push_actions(new_actions).await;
+        self.sync_state.set_fetched_block(block_number);
 
         total_latency.observe();
         Ok(true)
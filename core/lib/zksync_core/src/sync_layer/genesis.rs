@@ -95,6 +95,7 @@ async fn create_genesis_params(client: &dyn MainNodeClient) -> anyhow::Result<Ge
         first_validator,
         first_l1_verifier_config,
         first_verifier_address,
+        state_dump: None,
     })
 }
 
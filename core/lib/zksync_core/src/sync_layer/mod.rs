@@ -9,6 +9,7 @@ mod sync_state;
 #[cfg(test)]
 mod tests;
 
+pub(crate) use self::sync_state::L1BatchProgress;
 pub use self::{
     client::MainNodeClient, external_io::ExternalIO, sync_action::ActionQueue,
     sync_state::SyncState,
@@ -1,9 +1,13 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 use serde::Serialize;
 use zksync_health_check::{CheckHealth, Health, HealthStatus};
-use zksync_types::MiniblockNumber;
+use zksync_types::{L1BatchNumber, MiniblockNumber};
 
 use crate::metrics::EN_METRICS;
 
@@ -21,6 +25,21 @@ pub struct SyncState {
 /// A threshold constant intended to keep the sync status less flaky.
 /// This gives the external node some room to fetch new miniblocks without losing the sync status.
 const SYNC_MINIBLOCK_DELTA: u32 = 10;
+/// Time window used to estimate the sync rate of the node.
+const SYNC_RATE_WINDOW: Duration = Duration::from_secs(60);
+/// Minimum interval between local block samples used to estimate the sync rate.
+const SYNC_RATE_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Progress of the node in terms of L1 batches. Since obtaining it requires DB queries, it is cached in [`SyncState`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct L1BatchProgress {
+    /// Latest sealed L1 batch.
+    pub applied: Option<L1BatchNumber>,
+    /// Latest L1 batch processed by the Merkle tree.
+    pub tree: Option<L1BatchNumber>,
+    /// Latest L1 batch executed on L1.
+    pub executed: Option<L1BatchNumber>,
+}
 
 impl SyncState {
     pub(crate) fn get_main_node_block(&self) -> MiniblockNumber {
@@ -35,6 +54,30 @@ impl SyncState {
         self.inner.read().unwrap().local_block.unwrap_or_default()
     }
 
+    /// Returns the first local block observed by this state, i.e., the block at which the sync has started.
+    pub(crate) fn get_starting_block(&self) -> MiniblockNumber {
+        self.inner
+            .read()
+            .unwrap()
+            .starting_block
+            .unwrap_or_default()
+    }
+
+    /// Returns the latest miniblock fetched from the main node and queued for execution.
+    pub(crate) fn get_fetched_block(&self) -> Option<MiniblockNumber> {
+        self.inner.read().unwrap().fetched_block
+    }
+
+    pub(super) fn set_fetched_block(&self, block: MiniblockNumber) {
+        self.inner.write().unwrap().fetched_block = Some(block);
+    }
+
+    /// Estimates time until the node catches up with the main node based on the sync rate over the recent period.
+    /// Returns `None` if the rate cannot be estimated (e.g., right after the node start, or if the node is stuck).
+    pub(crate) fn estimated_time_to_head(&self) -> Option<Duration> {
+        self.inner.read().unwrap().estimated_time_to_head()
+    }
+
     pub(crate) fn set_main_node_block(&self, block: MiniblockNumber) {
         let mut inner = self.inner.write().unwrap();
         if let Some(local_block) = inner.local_block {
@@ -51,7 +94,7 @@ impl SyncState {
         inner.update_sync_metric();
     }
 
-    pub(super) fn set_local_block(&self, block: MiniblockNumber) {
+    pub(crate) fn set_local_block(&self, block: MiniblockNumber) {
         let mut inner = self.inner.write().unwrap();
         if let Some(main_node_block) = inner.main_node_block {
             if block.0 > main_node_block.0 {
@@ -63,7 +106,7 @@ impl SyncState {
                 );
             }
         }
-        inner.local_block = Some(block);
+        inner.record_local_block(block, Instant::now());
         inner.update_sync_metric();
     }
 
@@ -71,6 +114,25 @@ impl SyncState {
         let inner = self.inner.read().unwrap();
         inner.is_synced().0
     }
+
+    /// Returns L1 batch progress if it was cached no longer than `max_age` ago.
+    pub(crate) fn get_cached_l1_batch_progress(
+        &self,
+        max_age: Duration,
+    ) -> Option<L1BatchProgress> {
+        let inner = self.inner.read().unwrap();
+        let (cached_at, progress) = inner.l1_batch_progress?;
+        (cached_at.elapsed() <= max_age).then_some(progress)
+    }
+
+    pub(crate) fn cache_l1_batch_progress(&self, progress: L1BatchProgress) {
+        self.inner.write().unwrap().l1_batch_progress = Some((Instant::now(), progress));
+    }
+
+    #[cfg(test)]
+    pub(crate) fn reset_l1_batch_progress(&self) {
+        self.inner.write().unwrap().l1_batch_progress = None;
+    }
 }
 
 #[async_trait]
@@ -88,9 +150,52 @@ impl CheckHealth for SyncState {
 struct SyncStateInner {
     main_node_block: Option<MiniblockNumber>,
     local_block: Option<MiniblockNumber>,
+    starting_block: Option<MiniblockNumber>,
+    fetched_block: Option<MiniblockNumber>,
+    /// Samples of the local block used to estimate the sync rate, ordered by time.
+    local_block_samples: VecDeque<(Instant, MiniblockNumber)>,
+    /// L1 batch progress together with the time it was cached.
+    l1_batch_progress: Option<(Instant, L1BatchProgress)>,
 }
 
 impl SyncStateInner {
+    fn record_local_block(&mut self, block: MiniblockNumber, now: Instant) {
+        self.local_block = Some(block);
+        self.starting_block.get_or_insert(block);
+
+        let should_sample = self
+            .local_block_samples
+            .back()
+            .map_or(true, |&(sampled_at, _)| {
+                now.duration_since(sampled_at) >= SYNC_RATE_SAMPLE_INTERVAL
+            });
+        if should_sample {
+            self.local_block_samples.push_back((now, block));
+        }
+        while let Some(&(sampled_at, _)) = self.local_block_samples.front() {
+            if now.duration_since(sampled_at) <= SYNC_RATE_WINDOW {
+                break;
+            }
+            self.local_block_samples.pop_front();
+        }
+    }
+
+    fn estimated_time_to_head(&self) -> Option<Duration> {
+        let lag = self.main_node_block?.0.checked_sub(self.local_block?.0)?;
+        if lag == 0 {
+            return Some(Duration::ZERO);
+        }
+        let (first_sampled_at, first_block) = *self.local_block_samples.front()?;
+        let (last_sampled_at, last_block) = *self.local_block_samples.back()?;
+        let elapsed = last_sampled_at.duration_since(first_sampled_at);
+        let synced_blocks = last_block.0.checked_sub(first_block.0)?;
+        if synced_blocks == 0 || elapsed.is_zero() {
+            return None;
+        }
+        let blocks_per_sec = f64::from(synced_blocks) / elapsed.as_secs_f64();
+        Some(Duration::from_secs_f64(f64::from(lag) / blocks_per_sec))
+    }
+
     fn is_synced(&self) -> (bool, Option<u32>) {
         if let (Some(main_node_block), Some(local_block)) = (self.main_node_block, self.local_block)
         {
@@ -180,6 +285,59 @@ mod tests {
         assert!(!sync_state.is_synced());
     }
 
+    #[test]
+    fn caching_l1_batch_progress() {
+        let sync_state = SyncState::default();
+        assert_eq!(sync_state.get_cached_l1_batch_progress(Duration::MAX), None);
+
+        let progress = L1BatchProgress {
+            applied: Some(L1BatchNumber(2)),
+            tree: Some(L1BatchNumber(1)),
+            executed: None,
+        };
+        sync_state.cache_l1_batch_progress(progress);
+        assert_eq!(
+            sync_state.get_cached_l1_batch_progress(Duration::MAX),
+            Some(progress)
+        );
+        assert_eq!(
+            sync_state.get_cached_l1_batch_progress(Duration::ZERO),
+            None
+        );
+    }
+
+    #[test]
+    fn estimating_time_to_head() {
+        let mut state = SyncStateInner::default();
+        state.main_node_block = Some(MiniblockNumber(1_000));
+        let start = Instant::now();
+        state.record_local_block(MiniblockNumber(100), start);
+        assert_eq!(state.starting_block, Some(MiniblockNumber(100)));
+        // There is a single sample, so the sync rate cannot be estimated.
+        assert_eq!(state.estimated_time_to_head(), None);
+
+        // Samples at sub-second intervals should be skipped.
+        state.record_local_block(MiniblockNumber(105), start + Duration::from_millis(500));
+        assert_eq!(state.local_block_samples.len(), 1);
+        state.record_local_block(MiniblockNumber(110), start + Duration::from_secs(1));
+        state.record_local_block(MiniblockNumber(200), start + Duration::from_secs(10));
+        assert_eq!(state.local_block_samples.len(), 3);
+        // The node syncs 10 blocks per second, and the lag is 800 blocks.
+        assert_eq!(
+            state.estimated_time_to_head(),
+            Some(Duration::from_secs(80))
+        );
+
+        // Old samples should be evicted.
+        state.record_local_block(MiniblockNumber(200), start + Duration::from_secs(71));
+        assert_eq!(state.local_block_samples.len(), 1);
+        assert_eq!(state.starting_block, Some(MiniblockNumber(100)));
+        assert_eq!(state.estimated_time_to_head(), None);
+
+        state.main_node_block = Some(MiniblockNumber(200));
+        assert_eq!(state.estimated_time_to_head(), Some(Duration::ZERO));
+    }
+
     #[test]
     fn test_sync_state_doesnt_panic_on_local_block() {
         let sync_state = SyncState::default();
@@ -7,8 +7,9 @@ use zksync_config::{
         },
         fri_prover_group::FriProverGroupConfig,
         house_keeper::HouseKeeperConfig,
-        FriProofCompressorConfig, FriProverConfig, FriWitnessGeneratorConfig, KzgConfig,
-        PrometheusConfig, ProofDataHandlerConfig, WitnessGeneratorConfig,
+        BaseTokenFetcherConfig, FriProofCompressorConfig, FriProverConfig,
+        FriWitnessGeneratorConfig, KzgConfig, PrometheusConfig, ProofDataHandlerConfig,
+        WitnessGeneratorConfig,
     },
     ApiConfig, ContractsConfig, DBConfig, ETHClientConfig, ETHSenderConfig, ETHWatchConfig,
     GasAdjusterConfig, ObjectStoreConfig, PostgresConfig,
@@ -46,6 +47,8 @@ pub struct TempConfigStore {
     pub eth_watch_config: Option<ETHWatchConfig>,
     pub gas_adjuster_config: Option<GasAdjusterConfig>,
     pub object_store_config: Option<ObjectStoreConfig>,
+    pub prover_object_store_config: Option<ObjectStoreConfig>,
     pub kzg_config: Option<KzgConfig>,
+    pub base_token_fetcher_config: Option<BaseTokenFetcherConfig>,
     pub consensus_config: Option<consensus::MainNodeConfig>,
 }
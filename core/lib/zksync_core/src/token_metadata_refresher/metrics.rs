@@ -0,0 +1,14 @@
+use vise::{Counter, Metrics};
+
+/// Metrics for the token metadata refresher.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_token_metadata_refresher")]
+pub(super) struct TokenMetadataRefresherMetrics {
+    /// Number of tokens with metadata successfully refreshed from L1.
+    pub refreshed_tokens: Counter,
+    /// Number of tokens with metadata that couldn't be read from L1.
+    pub failed_tokens: Counter,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<TokenMetadataRefresherMetrics> = vise::Global::new();
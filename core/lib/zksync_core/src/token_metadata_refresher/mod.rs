@@ -0,0 +1,335 @@
+//! Background refresher of token metadata. Tokens bridged via the default bridge get their metadata from
+//! the bridge initialization event; if the bridge couldn't read metadata of the L1 token (e.g., because the token
+//! returns `bytes32` instead of `string` from its `name()` / `symbol()` getters), the token is marked as having
+//! stale metadata. This component periodically re-reads metadata of such tokens from L1.
+
+use std::{fmt, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_eth_client::{CallFunctionArgs, Error as L1ClientError, EthInterface};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{
+    ethabi,
+    tokens::TokenMetadata,
+    web3::{
+        self,
+        contract::{tokens::Detokenize, Error as ContractError},
+    },
+    Address, H256,
+};
+
+use self::metrics::METRICS;
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// ABI of ERC-20 metadata getters.
+static ERC20_METADATA_ABI: Lazy<ethabi::Contract> = Lazy::new(|| {
+    let abi = r#"[
+      {
+        "inputs": [],
+        "name": "name",
+        "outputs": [{ "name": "", "type": "string" }],
+        "stateMutability": "view",
+        "type": "function"
+      },
+      {
+        "inputs": [],
+        "name": "symbol",
+        "outputs": [{ "name": "", "type": "string" }],
+        "stateMutability": "view",
+        "type": "function"
+      },
+      {
+        "inputs": [],
+        "name": "decimals",
+        "outputs": [{ "name": "", "type": "uint8" }],
+        "stateMutability": "view",
+        "type": "function"
+      }
+    ]"#;
+    serde_json::from_str(abi).unwrap()
+});
+
+/// ABI of ERC-20 metadata getters for legacy tokens (e.g., MKR) returning `bytes32` names and symbols.
+static BYTES32_ERC20_METADATA_ABI: Lazy<ethabi::Contract> = Lazy::new(|| {
+    let abi = r#"[
+      {
+        "inputs": [],
+        "name": "name",
+        "outputs": [{ "name": "", "type": "bytes32" }],
+        "stateMutability": "view",
+        "type": "function"
+      },
+      {
+        "inputs": [],
+        "name": "symbol",
+        "outputs": [{ "name": "", "type": "bytes32" }],
+        "stateMutability": "view",
+        "type": "function"
+      }
+    ]"#;
+    serde_json::from_str(abi).unwrap()
+});
+
+/// Checks whether the error is caused by the contract returning unexpected data (as opposed to a transport error).
+fn is_invalid_output(err: &L1ClientError) -> bool {
+    matches!(
+        err,
+        L1ClientError::Contract(ContractError::Abi(_) | ContractError::InvalidOutputType(_))
+    )
+}
+
+/// Checks whether the error is caused by L1 being unreachable (as opposed to an error specific to the token contract,
+/// such as a reverted call).
+fn is_transport_error(err: &L1ClientError) -> bool {
+    matches!(
+        err,
+        L1ClientError::EthereumGateway(
+            web3::Error::Unreachable | web3::Error::Transport(_) | web3::Error::Io(_)
+        )
+    )
+}
+
+/// Decodes a `bytes32` string, which is right-padded with zero bytes.
+fn decode_bytes32_string(bytes: H256) -> String {
+    let bytes = bytes.as_bytes();
+    let len = bytes
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .map_or(0, |pos| pos + 1);
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+/// L1 data used by [`TokenMetadataRefresher`].
+#[async_trait]
+trait TokenMetadataClient: fmt::Debug + Send + Sync {
+    /// Reads metadata of the L1 token with the specified address.
+    async fn token_metadata(&self, l1_address: Address) -> Result<TokenMetadata, L1ClientError>;
+}
+
+/// [`TokenMetadataClient`] implementation calling ERC-20 getters of L1 token contracts.
+#[derive(Debug)]
+struct Erc20MetadataClient {
+    eth_client: Box<dyn EthInterface>,
+}
+
+impl Erc20MetadataClient {
+    async fn call<T: Detokenize>(
+        &self,
+        token_address: Address,
+        abi: &ethabi::Contract,
+        function_name: &str,
+    ) -> Result<T, L1ClientError> {
+        let args =
+            CallFunctionArgs::new(function_name, ()).for_contract(token_address, abi.clone());
+        let tokens = self.eth_client.call_contract_function(args).await?;
+        Ok(T::from_tokens(tokens)?)
+    }
+
+    /// Calls a `string` getter, falling back to a `bytes32` getter if the output cannot be decoded as a string.
+    async fn call_string_getter(
+        &self,
+        token_address: Address,
+        function_name: &str,
+    ) -> Result<String, L1ClientError> {
+        let result = self
+            .call(token_address, &ERC20_METADATA_ABI, function_name)
+            .await;
+        match result {
+            Err(err) if is_invalid_output(&err) => {
+                tracing::debug!(
+                    "Output of `{function_name}()` for token {token_address:?} is not a string ({err}); \
+                     trying to decode it as `bytes32`"
+                );
+                let bytes = self
+                    .call(token_address, &BYTES32_ERC20_METADATA_ABI, function_name)
+                    .await?;
+                Ok(decode_bytes32_string(bytes))
+            }
+            result => result,
+        }
+    }
+}
+
+#[async_trait]
+impl TokenMetadataClient for Erc20MetadataClient {
+    async fn token_metadata(&self, l1_address: Address) -> Result<TokenMetadata, L1ClientError> {
+        let name = self.call_string_getter(l1_address, "name").await?;
+        let symbol = self.call_string_getter(l1_address, "symbol").await?;
+        let decimals: u8 = self
+            .call(l1_address, &ERC20_METADATA_ABI, "decimals")
+            .await?;
+        Ok(TokenMetadata {
+            name,
+            symbol,
+            decimals,
+        })
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+enum RefreshError {
+    #[error("Web3 error communicating with L1")]
+    Web3(#[from] L1ClientError),
+    #[error("Internal error")]
+    Internal(#[from] anyhow::Error),
+}
+
+/// Health details reported by [`TokenMetadataRefresher`].
+#[derive(Debug, Default, Serialize)]
+struct TokenMetadataRefresherDetails {
+    refreshed_tokens: u64,
+    /// Unreadable tokens; capped at [`Self::MAX_FAILED_TOKENS`] entries.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    failed_tokens: Vec<Address>,
+}
+
+impl TokenMetadataRefresherDetails {
+    /// Maximum number of unreadable tokens reported in health details.
+    const MAX_FAILED_TOKENS: usize = 100;
+
+    fn add_failed_token(&mut self, l2_address: Address) {
+        if self.failed_tokens.len() < Self::MAX_FAILED_TOKENS
+            && !self.failed_tokens.contains(&l2_address)
+        {
+            self.failed_tokens.push(l2_address);
+        }
+    }
+
+    fn health(&self) -> Health {
+        Health::from(HealthStatus::Ready).with_details(self)
+    }
+}
+
+/// Component re-reading metadata of tokens marked as stale from L1.
+///
+/// Tokens for which metadata cannot be read (e.g., because the L1 token doesn't implement ERC-20 metadata getters,
+/// or one of the getters reverts) remain stale and are moved to the end of the refresh queue, so that they don't block refreshing other tokens.
+#[derive(Debug)]
+pub struct TokenMetadataRefresher {
+    pool: ConnectionPool,
+    client: Box<dyn TokenMetadataClient>,
+    batch_size: usize,
+    poll_interval: Duration,
+    health_updater: HealthUpdater,
+    details: TokenMetadataRefresherDetails,
+}
+
+impl TokenMetadataRefresher {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(60);
+    /// Maximum number of tokens refreshed on a single iteration.
+    const DEFAULT_BATCH_SIZE: usize = 50;
+
+    pub fn new(pool: ConnectionPool, eth_client: Box<dyn EthInterface>) -> Self {
+        Self::from_client(pool, Box::new(Erc20MetadataClient { eth_client }))
+    }
+
+    fn from_client(pool: ConnectionPool, client: Box<dyn TokenMetadataClient>) -> Self {
+        let (_, health_updater) = ReactiveHealthCheck::new("token_metadata_refresher");
+        Self {
+            pool,
+            client,
+            batch_size: Self::DEFAULT_BATCH_SIZE,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            health_updater,
+            details: TokenMetadataRefresherDetails::default(),
+        }
+    }
+
+    /// Returns health check associated with this refresher.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Refreshes metadata for a batch of stale tokens. Returns the number of refreshed tokens.
+    async fn refresh_stale_tokens(&mut self) -> Result<usize, RefreshError> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("token_metadata_refresher")
+            .await?;
+        let stale_tokens = storage
+            .tokens_dal()
+            .get_tokens_with_stale_metadata(self.batch_size)
+            .await
+            .context("failed getting tokens with stale metadata")?;
+        // Don't hold a DB connection while communicating with L1.
+        drop(storage);
+
+        let mut refreshed_count = 0;
+        for token in stale_tokens {
+            let l2_address = token.l2_address;
+            let metadata = match self.client.token_metadata(token.l1_address).await {
+                Ok(metadata) => metadata,
+                // Transport errors are not specific to the token, so they abort the entire batch.
+                Err(err) if is_transport_error(&err) => return Err(err.into()),
+                Err(err) => {
+                    tracing::warn!(
+                        "Cannot read metadata of L1 token {:?} (L2 address: {l2_address:?}): {err}",
+                        token.l1_address
+                    );
+                    METRICS.failed_tokens.inc();
+                    self.details.add_failed_token(l2_address);
+                    // Bumps the update timestamp, moving the token to the end of the queue.
+                    let mut storage = self
+                        .pool
+                        .access_storage_tagged("token_metadata_refresher")
+                        .await?;
+                    storage
+                        .tokens_dal()
+                        .mark_token_metadata_as_stale(l2_address)
+                        .await
+                        .with_context(|| format!("failed marking token {l2_address:?} as stale"))?;
+                    continue;
+                }
+            };
+
+            tracing::info!("Refreshed metadata of token {l2_address:?}: {metadata:?}");
+            let mut storage = self
+                .pool
+                .access_storage_tagged("token_metadata_refresher")
+                .await?;
+            storage
+                .tokens_dal()
+                .update_token_metadata(l2_address, &metadata)
+                .await
+                .with_context(|| format!("failed updating metadata of token {l2_address:?}"))?;
+            METRICS.refreshed_tokens.inc();
+            self.details.refreshed_tokens += 1;
+            self.details
+                .failed_tokens
+                .retain(|&addr| addr != l2_address);
+            refreshed_count += 1;
+        }
+        self.health_updater.update(self.details.health());
+        Ok(refreshed_count)
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater.update(self.details.health());
+        while !*stop_receiver.borrow_and_update() {
+            match self.refresh_stale_tokens().await {
+                Ok(0) => {}
+                Ok(refreshed_count) => {
+                    tracing::info!("Refreshed metadata of {refreshed_count} tokens");
+                }
+                Err(RefreshError::Web3(err)) => {
+                    tracing::warn!("Error accessing L1; will retry after a delay: {err}");
+                }
+                Err(RefreshError::Internal(err)) => return Err(err),
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        tracing::info!("Stop signal received, token metadata refresher is shutting down");
+        Ok(())
+    }
+}
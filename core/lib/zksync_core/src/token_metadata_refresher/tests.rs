@@ -0,0 +1,196 @@
+//! Tests for the token metadata refresher.
+
+use std::{
+    collections::{HashMap, HashSet},
+    slice,
+};
+
+use assert_matches::assert_matches;
+use zksync_types::tokens::TokenInfo;
+
+use super::*;
+
+#[derive(Debug, Default)]
+struct MockTokenMetadataClient {
+    metadata: HashMap<Address, TokenMetadata>,
+    reverting_tokens: HashSet<Address>,
+    transport_error: bool,
+}
+
+#[async_trait]
+impl TokenMetadataClient for MockTokenMetadataClient {
+    async fn token_metadata(&self, l1_address: Address) -> Result<TokenMetadata, L1ClientError> {
+        if self.transport_error {
+            let err = zksync_types::web3::Error::Unreachable;
+            return Err(L1ClientError::EthereumGateway(err));
+        }
+        if self.reverting_tokens.contains(&l1_address) {
+            let err = zksync_types::web3::Error::InvalidResponse("execution reverted".to_owned());
+            return Err(L1ClientError::EthereumGateway(err));
+        }
+        self.metadata.get(&l1_address).cloned().ok_or_else(|| {
+            let err = ContractError::InvalidOutputType("unexpected output".to_owned());
+            L1ClientError::Contract(err)
+        })
+    }
+}
+
+fn token_without_metadata(byte: u8) -> TokenInfo {
+    TokenInfo {
+        l1_address: Address::repeat_byte(byte),
+        l2_address: Address::repeat_byte(byte + 1),
+        metadata: TokenMetadata {
+            name: String::new(),
+            symbol: String::new(),
+            decimals: 18,
+        },
+    }
+}
+
+fn test_metadata() -> TokenMetadata {
+    TokenMetadata {
+        name: "Maker".to_owned(),
+        symbol: "MKR".to_owned(),
+        decimals: 18,
+    }
+}
+
+#[test]
+fn decoding_bytes32_strings() {
+    let mut bytes = H256::zero();
+    bytes.0[..5].copy_from_slice(b"Maker");
+    assert_eq!(decode_bytes32_string(bytes), "Maker");
+    assert_eq!(decode_bytes32_string(H256::zero()), "");
+    assert_eq!(
+        decode_bytes32_string(H256::repeat_byte(b'A')),
+        "A".repeat(32)
+    );
+}
+
+#[tokio::test]
+async fn refreshing_stale_tokens() {
+    let pool = ConnectionPool::test_pool().await;
+    let refreshed_token = token_without_metadata(1);
+    let unreadable_token = token_without_metadata(3);
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .tokens_dal()
+        .add_tokens(&[refreshed_token.clone(), unreadable_token.clone()])
+        .await
+        .unwrap();
+    drop(storage);
+
+    let client = MockTokenMetadataClient {
+        metadata: HashMap::from([(refreshed_token.l1_address, test_metadata())]),
+        ..MockTokenMetadataClient::default()
+    };
+    let mut refresher = TokenMetadataRefresher::from_client(pool.clone(), Box::new(client));
+    let refreshed_count = refresher.refresh_stale_tokens().await.unwrap();
+    assert_eq!(refreshed_count, 1);
+    assert_eq!(refresher.details.refreshed_tokens, 1);
+    assert_eq!(
+        refresher.details.failed_tokens,
+        [unreadable_token.l2_address]
+    );
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let token = storage
+        .tokens_web3_dal()
+        .get_token_info(refreshed_token.l2_address)
+        .await
+        .unwrap()
+        .expect("no token info");
+    assert_eq!(token.metadata, test_metadata());
+    let stale_tokens = storage
+        .tokens_dal()
+        .get_tokens_with_stale_metadata(10)
+        .await
+        .unwrap();
+    assert_eq!(stale_tokens, [unreadable_token]);
+}
+
+#[tokio::test]
+async fn transport_errors_are_not_treated_as_unreadable_metadata() {
+    let pool = ConnectionPool::test_pool().await;
+    let token = token_without_metadata(1);
+    let mut storage = pool.access_storage().await.unwrap();
+    storage
+        .tokens_dal()
+        .add_tokens(slice::from_ref(&token))
+        .await
+        .unwrap();
+    drop(storage);
+
+    let client = MockTokenMetadataClient {
+        metadata: HashMap::from([(token.l1_address, test_metadata())]),
+        transport_error: true,
+        ..MockTokenMetadataClient::default()
+    };
+    let mut refresher = TokenMetadataRefresher::from_client(pool.clone(), Box::new(client));
+    let err = refresher.refresh_stale_tokens().await.unwrap_err();
+    assert_matches!(err, RefreshError::Web3(_));
+    assert!(refresher.details.failed_tokens.is_empty());
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let stale_tokens = storage
+        .tokens_dal()
+        .get_tokens_with_stale_metadata(10)
+        .await
+        .unwrap();
+    assert_eq!(stale_tokens, [token]);
+}
+
+#[tokio::test]
+async fn reverting_token_does_not_block_refreshing_other_tokens() {
+    let pool = ConnectionPool::test_pool().await;
+    let reverting_token = token_without_metadata(1);
+    let refreshed_token = token_without_metadata(3);
+    let mut storage = pool.access_storage().await.unwrap();
+    // Tokens are added separately, so that the reverting token is the least recently updated one.
+    for token in [&reverting_token, &refreshed_token] {
+        storage
+            .tokens_dal()
+            .add_tokens(slice::from_ref(token))
+            .await
+            .unwrap();
+    }
+    drop(storage);
+
+    let client = MockTokenMetadataClient {
+        metadata: HashMap::from([(refreshed_token.l1_address, test_metadata())]),
+        reverting_tokens: HashSet::from([reverting_token.l1_address]),
+        ..MockTokenMetadataClient::default()
+    };
+    let mut refresher = TokenMetadataRefresher::from_client(pool.clone(), Box::new(client));
+    // Only refresh a single token per batch, so that the reverting token would block refreshes if it weren't bumped.
+    refresher.batch_size = 1;
+    let refreshed_count = refresher.refresh_stale_tokens().await.unwrap();
+    assert_eq!(refreshed_count, 0);
+    assert_eq!(
+        refresher.details.failed_tokens,
+        [reverting_token.l2_address]
+    );
+    let refreshed_count = refresher.refresh_stale_tokens().await.unwrap();
+    assert_eq!(refreshed_count, 1);
+
+    let mut storage = pool.access_storage().await.unwrap();
+    let stale_tokens = storage
+        .tokens_dal()
+        .get_tokens_with_stale_metadata(10)
+        .await
+        .unwrap();
+    assert_eq!(stale_tokens, [reverting_token]);
+}
+
+#[test]
+fn failed_tokens_in_health_details_are_bounded() {
+    let mut details = TokenMetadataRefresherDetails::default();
+    for i in 0..2 * TokenMetadataRefresherDetails::MAX_FAILED_TOKENS {
+        details.add_failed_token(Address::from_low_u64_be(i as u64));
+    }
+    details.add_failed_token(Address::from_low_u64_be(0));
+    assert_eq!(
+        details.failed_tokens.len(),
+        TokenMetadataRefresherDetails::MAX_FAILED_TOKENS
+    );
+}
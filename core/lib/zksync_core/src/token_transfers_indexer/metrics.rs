@@ -0,0 +1,12 @@
+use vise::{Gauge, Metrics};
+
+/// Metrics for the token transfers indexer.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_token_transfers_indexer")]
+pub(super) struct TokenTransfersIndexerMetrics {
+    /// Last miniblock with indexed token transfers.
+    pub last_indexed_miniblock: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<TokenTransfersIndexerMetrics> = vise::Global::new();
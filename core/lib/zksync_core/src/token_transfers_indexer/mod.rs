@@ -0,0 +1,149 @@
+//! Background indexer of token transfers. Fungible and NFT transfers are extracted from the events persisted
+//! for sealed miniblocks and are used by the `zks_getTokenTransfers` and `zks_getAccountBalances` API methods.
+//! Since the indexer reads persisted events, it backfills the index for miniblocks sealed before it was enabled.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::ConnectionPool;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::MiniblockNumber;
+
+use self::metrics::METRICS;
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Health details reported by [`TokenTransfersIndexer`].
+#[derive(Debug, Serialize)]
+struct TokenTransfersIndexerDetails {
+    last_indexed_miniblock: Option<MiniblockNumber>,
+}
+
+impl TokenTransfersIndexerDetails {
+    fn health(&self) -> Health {
+        Health::from(HealthStatus::Ready).with_details(self)
+    }
+}
+
+/// Component indexing token transfers for sealed miniblocks in chunks.
+#[derive(Debug)]
+pub struct TokenTransfersIndexer {
+    pool: ConnectionPool,
+    chunk_size: u32,
+    poll_interval: Duration,
+    health_updater: HealthUpdater,
+}
+
+impl TokenTransfersIndexer {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+    /// Maximum number of miniblocks indexed on a single iteration.
+    const DEFAULT_CHUNK_SIZE: u32 = 100;
+
+    pub fn new(pool: ConnectionPool) -> Self {
+        let (_, health_updater) = ReactiveHealthCheck::new("token_transfers_indexer");
+        Self {
+            pool,
+            chunk_size: Self::DEFAULT_CHUNK_SIZE,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            health_updater,
+        }
+    }
+
+    /// Returns health check associated with this indexer.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    /// Indexes the next chunk of sealed miniblocks. Returns the last indexed miniblock, or `None` if there are
+    /// no miniblocks to index.
+    async fn index_next_chunk(&self) -> anyhow::Result<Option<MiniblockNumber>> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("token_transfers_indexer")
+            .await?;
+        let mut transaction = storage.start_transaction().await?;
+        let last_indexed_miniblock = transaction
+            .token_transfers_dal()
+            .get_last_indexed_miniblock()
+            .await
+            .context("failed getting last indexed miniblock")?;
+        let pruning_info = transaction
+            .pruning_dal()
+            .get_pruning_info()
+            .await
+            .context("failed getting pruning info")?;
+        // Events for hard-pruned miniblocks are removed, so there's nothing to index for them.
+        let first_miniblock_to_index = last_indexed_miniblock
+            .max(pruning_info.last_hard_pruned_miniblock)
+            .map_or(MiniblockNumber(0), |number| number + 1);
+        let Some(sealed_miniblock) = transaction
+            .blocks_dal()
+            .get_sealed_miniblock_number()
+            .await
+            .context("failed getting sealed miniblock number")?
+        else {
+            return Ok(None);
+        };
+        if first_miniblock_to_index > sealed_miniblock {
+            return Ok(None);
+        }
+
+        let last_miniblock_to_index =
+            sealed_miniblock.min(first_miniblock_to_index + (self.chunk_size - 1));
+        let range = first_miniblock_to_index..=last_miniblock_to_index;
+        transaction
+            .token_transfers_dal()
+            .index_token_transfers(range.clone())
+            .await
+            .with_context(|| format!("failed indexing token transfers for miniblocks {range:?}"))?;
+        transaction.commit().await?;
+        Ok(Some(last_miniblock_to_index))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut storage = self
+            .pool
+            .access_storage_tagged("token_transfers_indexer")
+            .await?;
+        let last_indexed_miniblock = storage
+            .token_transfers_dal()
+            .get_last_indexed_miniblock()
+            .await?;
+        drop(storage);
+        tracing::info!(
+            "Starting token transfers indexer with last indexed miniblock: {last_indexed_miniblock:?}"
+        );
+        self.health_updater.update(
+            TokenTransfersIndexerDetails {
+                last_indexed_miniblock,
+            }
+            .health(),
+        );
+
+        while !*stop_receiver.borrow_and_update() {
+            let Some(last_indexed_miniblock) = self.index_next_chunk().await? else {
+                // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+                tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                    .await
+                    .ok();
+                continue;
+            };
+            tracing::debug!("Indexed token transfers up to miniblock #{last_indexed_miniblock}");
+            METRICS
+                .last_indexed_miniblock
+                .set(last_indexed_miniblock.0.into());
+            self.health_updater.update(
+                TokenTransfersIndexerDetails {
+                    last_indexed_miniblock: Some(last_indexed_miniblock),
+                }
+                .health(),
+            );
+        }
+        tracing::info!("Stop signal received, token transfers indexer is shutting down");
+        Ok(())
+    }
+}
@@ -0,0 +1,154 @@
+//! Tests for the token transfers indexer.
+
+use std::ops;
+
+use zksync_dal::StorageProcessor;
+use zksync_types::{
+    api, event::TRANSFER_EVENT_SIGNATURE, tx::IncludedTxLocation, Address, L1BatchNumber,
+    L2ChainId, VmEvent, H256, U256,
+};
+use zksync_utils::{address_to_h256, u256_to_h256};
+
+use super::*;
+use crate::{
+    genesis::{ensure_genesis_state, GenesisParams},
+    utils::testonly::create_miniblock,
+};
+
+const TOKEN: Address = Address::repeat_byte(0x10);
+const ALICE: Address = Address::repeat_byte(1);
+const BOB: Address = Address::repeat_byte(2);
+
+fn create_transfer_event(from: Address, to: Address, amount: u64) -> VmEvent {
+    VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: TOKEN,
+        indexed_topics: vec![
+            *TRANSFER_EVENT_SIGNATURE,
+            address_to_h256(&from),
+            address_to_h256(&to),
+        ],
+        value: u256_to_h256(U256::from(amount)).as_bytes().to_vec(),
+    }
+}
+
+/// Stores miniblocks with a single transfer from Alice to Bob each, preceded by an unrelated event.
+async fn store_miniblocks_with_transfers(
+    storage: &mut StorageProcessor<'_>,
+    numbers: ops::RangeInclusive<u32>,
+) {
+    for number in numbers {
+        storage
+            .blocks_dal()
+            .insert_miniblock(&create_miniblock(number))
+            .await
+            .unwrap();
+        let unrelated_event = VmEvent {
+            location: (L1BatchNumber(1), 0),
+            address: TOKEN,
+            indexed_topics: vec![H256::repeat_byte(0xff)],
+            value: vec![],
+        };
+        let transfer_event = create_transfer_event(ALICE, BOB, number.into());
+        let tx_location = IncludedTxLocation {
+            tx_hash: H256::from_low_u64_be(number.into()),
+            tx_index_in_miniblock: 0,
+            tx_initiator_address: ALICE,
+        };
+        storage
+            .events_dal()
+            .save_events(
+                MiniblockNumber(number),
+                &[(tx_location, vec![&unrelated_event, &transfer_event])],
+            )
+            .await;
+    }
+}
+
+async fn get_transfers(storage: &mut StorageProcessor<'_>) -> Vec<api::TokenTransfer> {
+    storage
+        .token_transfers_dal()
+        .get_token_transfers(BOB, &api::TokenTransfersRange::default(), 100)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn indexing_token_transfers_in_chunks() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    store_miniblocks_with_transfers(&mut storage, 1..=3).await;
+
+    let mut indexer = TokenTransfersIndexer::new(pool.clone());
+    indexer.chunk_size = 2;
+    let last_indexed_miniblock = indexer.index_next_chunk().await.unwrap();
+    assert_eq!(last_indexed_miniblock, Some(MiniblockNumber(1)));
+    let transfers = get_transfers(&mut storage).await;
+    assert_eq!(transfers.len(), 1);
+    assert_eq!(transfers[0].block_number, MiniblockNumber(1));
+    assert_eq!(transfers[0].log_index, 1);
+    assert_eq!(transfers[0].transaction_hash, H256::from_low_u64_be(1));
+    assert_eq!((transfers[0].from, transfers[0].to), (ALICE, BOB));
+    assert_eq!(transfers[0].amount, 1.into());
+
+    let last_indexed_miniblock = indexer.index_next_chunk().await.unwrap();
+    assert_eq!(last_indexed_miniblock, Some(MiniblockNumber(3)));
+    assert_eq!(indexer.index_next_chunk().await.unwrap(), None);
+    let transfers = get_transfers(&mut storage).await;
+    let block_numbers: Vec<_> = transfers
+        .iter()
+        .map(|transfer| transfer.block_number.0)
+        .collect();
+    assert_eq!(block_numbers, [1, 2, 3]);
+
+    // New miniblocks must be picked up by the indexer.
+    store_miniblocks_with_transfers(&mut storage, 4..=4).await;
+    let last_indexed_miniblock = indexer.index_next_chunk().await.unwrap();
+    assert_eq!(last_indexed_miniblock, Some(MiniblockNumber(4)));
+    assert_eq!(get_transfers(&mut storage).await.len(), 4);
+}
+
+#[tokio::test]
+async fn indexer_progress_is_rolled_back_on_revert() {
+    let pool = ConnectionPool::test_pool().await;
+    let mut storage = pool.access_storage().await.unwrap();
+    ensure_genesis_state(&mut storage, L2ChainId::default(), &GenesisParams::mock())
+        .await
+        .unwrap();
+    store_miniblocks_with_transfers(&mut storage, 1..=3).await;
+
+    let indexer = TokenTransfersIndexer::new(pool.clone());
+    let last_indexed_miniblock = indexer.index_next_chunk().await.unwrap();
+    assert_eq!(last_indexed_miniblock, Some(MiniblockNumber(3)));
+
+    storage
+        .token_transfers_dal()
+        .rollback_token_transfers(MiniblockNumber(1))
+        .await
+        .unwrap();
+    let last_indexed_miniblock = storage
+        .token_transfers_dal()
+        .get_last_indexed_miniblock()
+        .await
+        .unwrap();
+    assert_eq!(last_indexed_miniblock, Some(MiniblockNumber(1)));
+    assert_eq!(get_transfers(&mut storage).await.len(), 1);
+
+    // Reverted miniblocks must be re-indexed.
+    storage
+        .events_dal()
+        .rollback_events(MiniblockNumber(1))
+        .await;
+    storage
+        .blocks_dal()
+        .delete_miniblocks(MiniblockNumber(1))
+        .await
+        .unwrap();
+    store_miniblocks_with_transfers(&mut storage, 2..=2).await;
+    let last_indexed_miniblock = indexer.index_next_chunk().await.unwrap();
+    assert_eq!(last_indexed_miniblock, Some(MiniblockNumber(2)));
+    assert_eq!(get_transfers(&mut storage).await.len(), 2);
+}
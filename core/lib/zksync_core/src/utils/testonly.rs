@@ -6,8 +6,9 @@ use multivm::utils::get_max_gas_per_pubdata_byte;
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::StorageProcessor;
 use zksync_merkle_tree::{domain::ZkSyncTree, TreeInstruction};
-use zksync_system_constants::ZKPORTER_IS_AVAILABLE;
+use zksync_system_constants::{L1_GAS_PER_PUBDATA_BYTE, ZKPORTER_IS_AVAILABLE};
 use zksync_types::{
+    api::{L1GasOracleParams, L1GasOracleState},
     block::{L1BatchHeader, MiniblockHeader},
     commitment::{
         AuxCommitments, L1BatchCommitmentArtifacts, L1BatchCommitmentHash, L1BatchMetaParameters,
@@ -40,6 +41,7 @@ pub(crate) fn create_miniblock(number: u32) -> MiniblockHeader {
         base_system_contracts_hashes: BaseSystemContractsHashes::default(),
         protocol_version: Some(ProtocolVersionId::latest()),
         virtual_blocks: 1,
+        logs_bloom: Default::default(),
     }
 }
 
@@ -104,6 +106,7 @@ pub(crate) fn l1_batch_metadata_to_commitment_artifacts(
             }
             _ => None,
         },
+        blob_commitments: None,
     }
 }
 
@@ -145,6 +148,7 @@ pub(crate) fn execute_l2_transaction(transaction: L2Tx) -> TransactionExecutionR
         compressed_bytecodes: vec![],
         call_traces: vec![],
         revert_reason: None,
+        decoded_revert_reason: None,
     }
 }
 
@@ -251,4 +255,47 @@ impl BatchFeeModelInputProvider for MockBatchFeeParamsProvider {
     fn get_fee_model_params(&self) -> FeeParams {
         self.0
     }
+
+    /// Returns an oracle state with an empty sample window and the unscaled batch fee input.
+    fn get_l1_gas_oracle_state(&self) -> Option<L1GasOracleState> {
+        let (l1_gas_price, l1_pubdata_price, batch_fee_input) = match self.0 {
+            FeeParams::V1(params) => (
+                params.l1_gas_price,
+                params.l1_gas_price * u64::from(L1_GAS_PER_PUBDATA_BYTE),
+                BatchFeeInput::l1_pegged(params.l1_gas_price, params.config.minimal_l2_gas_price),
+            ),
+            FeeParams::V2(params) => (
+                params.l1_gas_price,
+                params.l1_pubdata_price,
+                BatchFeeInput::pubdata_independent(
+                    params.l1_gas_price,
+                    params.config.minimal_l2_gas_price,
+                    params.l1_pubdata_price,
+                ),
+            ),
+        };
+        Some(L1GasOracleState {
+            last_processed_l1_block: 0,
+            base_fee_samples: vec![],
+            median_base_fee: 0,
+            blob_base_fee_samples: vec![],
+            median_blob_base_fee: U256::zero(),
+            params: L1GasOracleParams {
+                max_base_fee_samples: 0,
+                max_blob_base_fee_samples: 0,
+                pricing_formula_parameter_a: 1.0,
+                pricing_formula_parameter_b: 1.0,
+                internal_l1_pricing_multiplier: 1.0,
+                internal_pubdata_pricing_multiplier: 1.0,
+                internal_enforced_l1_gas_price: None,
+                max_l1_gas_price: u64::MAX,
+                max_blob_base_fee: u64::MAX,
+                priority_fee_per_gas: 0,
+            },
+            effective_l1_gas_price: l1_gas_price,
+            effective_l1_pubdata_price: l1_pubdata_price,
+            fee_params: self.0,
+            batch_fee_input,
+        })
+    }
 }
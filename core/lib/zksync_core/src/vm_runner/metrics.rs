@@ -0,0 +1,37 @@
+use std::time::Duration;
+
+use serde::Serialize;
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+
+/// Kind of divergence between the re-executed L1 batch and the data persisted by the state keeper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, EncodeLabelValue, EncodeLabelSet)]
+#[serde(rename_all = "snake_case")]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(super) enum DivergenceKind {
+    /// Transaction hashes, execution statuses or refunded gas differ.
+    TxOutcomes,
+    /// Emitted events differ.
+    Events,
+    /// Final values of the storage slots touched in the batch differ.
+    StorageWrites,
+}
+
+/// Metrics for the VM runner.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_vm_runner")]
+pub(super) struct VmRunnerMetrics {
+    /// Number of the last L1 batch processed by the VM runner.
+    pub last_processed_l1_batch: Gauge<u64>,
+    /// Latency of re-executing a single L1 batch.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub batch_execution_latency: Histogram<Duration>,
+    /// Number of L1 batches skipped because they were sealed with an older VM version.
+    pub skipped_l1_batches: Counter,
+    /// Number of divergences between re-executed L1 batches and the persisted data.
+    pub divergences: Family<DivergenceKind, Counter>,
+}
+
+#[vise::register]
+pub(super) static METRICS: vise::Global<VmRunnerMetrics> = vise::Global::new();
@@ -0,0 +1,362 @@
+//! VM runner re-executing sealed L1 batches with the latest VM and comparing the results with the data
+//! persisted by the state keeper. Used as a canary for VM upgrades: any divergence means that the VM
+//! behaves differently from the one that has sealed the batch.
+
+use std::{collections::HashMap, time::Duration};
+
+use anyhow::Context as _;
+use multivm::{
+    interface::{L2BlockEnv, VmExecutionResultAndLogs, VmInterface},
+    vm_latest::HistoryEnabled,
+    VmInstance,
+};
+use serde::Serialize;
+use tokio::{runtime::Handle, sync::watch};
+use vm_utils::{create_vm, execute_tx, storage::BaseSystemContractsCache};
+use zksync_dal::{ConnectionPool, StorageProcessor};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_state::WriteStorage;
+use zksync_types::{
+    block::MiniblockExecutionData, event::VmEvent,
+    storage_writes_deduplicator::StorageWritesDeduplicator,
+    tx::tx_execution_info::TxExecutionStatus, vm_version::VmVersion, L1BatchNumber, L2ChainId,
+    StorageKey, H256,
+};
+use zksync_utils::u256_to_h256;
+
+use self::metrics::{DivergenceKind, METRICS};
+
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Outputs of an L1 batch execution that are compared by the VM runner.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct BatchOutput {
+    /// Hash, execution status and refunded gas for each transaction in the batch.
+    pub tx_outcomes: Vec<(H256, TxExecutionStatus, u32)>,
+    /// All events emitted in the batch, including the ones emitted by the batch tip.
+    pub events: Vec<VmEvent>,
+    /// Final values of the storage slots written in the batch.
+    pub storage_writes: HashMap<StorageKey, H256>,
+}
+
+impl BatchOutput {
+    fn push_tx_result(&mut self, tx_hash: H256, result: &VmExecutionResultAndLogs) {
+        let status = TxExecutionStatus::from_has_failed(result.result.is_failed());
+        self.tx_outcomes
+            .push((tx_hash, status, result.refunds.gas_refunded));
+        self.events.extend_from_slice(&result.logs.events);
+    }
+
+    /// Applies storage writes from a single miniblock. Writes are deduplicated per miniblock,
+    /// in the same way as they are persisted by the state keeper.
+    fn push_miniblock_writes<'a>(
+        &mut self,
+        results: impl Iterator<Item = &'a VmExecutionResultAndLogs>,
+    ) {
+        let mut deduplicator = StorageWritesDeduplicator::new();
+        for result in results {
+            deduplicator.apply(&result.logs.storage_logs);
+        }
+        let modified_slots = deduplicator.into_modified_key_values();
+        self.storage_writes.extend(
+            modified_slots
+                .into_iter()
+                .map(|(key, slot)| (key, u256_to_h256(slot.value))),
+        );
+    }
+
+    /// Returns kinds of divergences between this (re-executed) output and the persisted one.
+    fn divergences(&self, persisted: &Self) -> Vec<DivergenceKind> {
+        let mut divergences = vec![];
+        if self.tx_outcomes != persisted.tx_outcomes {
+            divergences.push(DivergenceKind::TxOutcomes);
+        }
+        if self.events != persisted.events {
+            divergences.push(DivergenceKind::Events);
+        }
+        if self.storage_writes != persisted.storage_writes {
+            divergences.push(DivergenceKind::StorageWrites);
+        }
+        divergences
+    }
+}
+
+/// Loads the output of the specified L1 batch as persisted by the state keeper.
+pub(crate) async fn load_persisted_output(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<BatchOutput> {
+    let tx_outcomes = storage
+        .transactions_dal()
+        .get_execution_outcomes_for_l1_batch(l1_batch_number)
+        .await?;
+    let events = storage
+        .events_dal()
+        .get_vm_events_for_l1_batch(l1_batch_number)
+        .await?;
+    let storage_writes = storage
+        .storage_logs_dal()
+        .get_touched_slots_for_l1_batch(l1_batch_number)
+        .await?;
+    Ok(BatchOutput {
+        tx_outcomes,
+        events,
+        storage_writes,
+    })
+}
+
+/// Loads execution data for all miniblocks in the batch, including the fictive one (which is not returned
+/// by the transactions DAL since it has no transactions).
+pub(crate) async fn load_miniblocks(
+    storage: &mut StorageProcessor<'_>,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<Vec<MiniblockExecutionData>> {
+    let mut miniblocks = storage
+        .transactions_dal()
+        .get_miniblocks_to_execute_for_l1_batch(l1_batch_number)
+        .await?;
+    let (_, last_miniblock) = storage
+        .blocks_dal()
+        .get_miniblock_range_of_l1_batch(l1_batch_number)
+        .await?
+        .with_context(|| format!("L1 batch #{l1_batch_number} has no miniblocks"))?;
+    if miniblocks.last().map(|miniblock| miniblock.number) == Some(last_miniblock) {
+        return Ok(miniblocks);
+    }
+
+    let fictive_header = storage
+        .blocks_dal()
+        .get_miniblock_header(last_miniblock)
+        .await?
+        .with_context(|| format!("header for miniblock #{last_miniblock} is missing"))?;
+    let prev_miniblock = last_miniblock - 1;
+    let prev_header = storage
+        .blocks_dal()
+        .get_miniblock_header(prev_miniblock)
+        .await?
+        .with_context(|| format!("header for miniblock #{prev_miniblock} is missing"))?;
+    miniblocks.push(MiniblockExecutionData {
+        number: last_miniblock,
+        timestamp: fictive_header.timestamp,
+        prev_block_hash: prev_header.hash,
+        virtual_blocks: fictive_header.virtual_blocks,
+        txs: vec![],
+    });
+    Ok(miniblocks)
+}
+
+/// Executes all miniblocks of an L1 batch (including the batch tip) in the provided VM, which must be initialized
+/// with the first miniblock of the batch.
+pub(crate) fn execute_miniblocks<S: WriteStorage>(
+    vm: &mut VmInstance<S, HistoryEnabled>,
+    miniblocks: &[MiniblockExecutionData],
+) -> anyhow::Result<BatchOutput> {
+    let mut output = BatchOutput::default();
+    let miniblock_count = miniblocks.len();
+    for (i, miniblock) in miniblocks.iter().enumerate() {
+        if i > 0 {
+            vm.start_new_l2_block(L2BlockEnv::from_miniblock_data(miniblock));
+        }
+        let mut results = Vec::with_capacity(miniblock.txs.len() + 1);
+        for tx in &miniblock.txs {
+            let result = execute_tx(tx, vm)
+                .with_context(|| format!("failed to execute transaction {:?}", tx.hash()))?;
+            output.push_tx_result(tx.hash(), &result);
+            results.push(result);
+        }
+        // The batch tip is executed in the last (fictive) miniblock of the batch.
+        if i + 1 == miniblock_count {
+            let finished_batch = vm.finish_batch();
+            let block_tip_result = finished_batch.block_tip_execution_result;
+            output
+                .events
+                .extend_from_slice(&block_tip_result.logs.events);
+            results.push(block_tip_result);
+        }
+        output.push_miniblock_writes(results.iter());
+    }
+    Ok(output)
+}
+
+#[derive(Debug, Serialize)]
+struct DivergenceDetails {
+    l1_batch_number: L1BatchNumber,
+    kinds: Vec<DivergenceKind>,
+}
+
+/// Component continuously re-executing sealed L1 batches with the latest VM and comparing transaction outcomes,
+/// events and storage writes with the data persisted by the state keeper. Divergences are reported via logs,
+/// metrics and the health check; they do not stop the component.
+///
+/// The runner starts from the last sealed L1 batch at the moment of its start and only processes batches
+/// executed with the latest VM version; older batches are skipped.
+#[derive(Debug)]
+pub struct VmRunner {
+    connection_pool: ConnectionPool,
+    l2_chain_id: L2ChainId,
+    contracts_cache: BaseSystemContractsCache,
+    health_updater: HealthUpdater,
+}
+
+impl VmRunner {
+    pub fn new(connection_pool: ConnectionPool, l2_chain_id: L2ChainId) -> Self {
+        Self {
+            connection_pool,
+            l2_chain_id,
+            contracts_cache: BaseSystemContractsCache::default(),
+            health_updater: ReactiveHealthCheck::new("vm_runner").1,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    fn execute_batch(
+        rt_handle: Handle,
+        l1_batch_number: L1BatchNumber,
+        miniblocks: Vec<MiniblockExecutionData>,
+        connection_pool: ConnectionPool,
+        l2_chain_id: L2ChainId,
+        contracts_cache: &BaseSystemContractsCache,
+    ) -> anyhow::Result<BatchOutput> {
+        let connection = rt_handle
+            .block_on(connection_pool.access_storage_tagged("vm_runner"))
+            .context("failed to get connection for VM runner")?;
+        let (mut vm, _) = create_vm(
+            rt_handle,
+            l1_batch_number,
+            connection,
+            l2_chain_id,
+            contracts_cache,
+        )
+        .context("failed to create VM")?;
+        execute_miniblocks(&mut vm, &miniblocks)
+    }
+
+    async fn process_l1_batch(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<()> {
+        let protocol_version = self
+            .connection_pool
+            .access_storage_tagged("vm_runner")
+            .await?
+            .blocks_dal()
+            .get_batch_protocol_version_id(l1_batch_number)
+            .await?;
+        let vm_version = protocol_version.map(VmVersion::from);
+        if vm_version != Some(VmVersion::latest()) {
+            tracing::info!(
+                "Skipping L1 batch #{l1_batch_number} executed with protocol version {protocol_version:?} \
+                 ({vm_version:?}); only batches executed with the latest VM version are verified"
+            );
+            METRICS.skipped_l1_batches.inc();
+            return Ok(());
+        }
+
+        let mut storage = self
+            .connection_pool
+            .access_storage_tagged("vm_runner")
+            .await?;
+        let persisted_output = load_persisted_output(&mut storage, l1_batch_number).await?;
+        let miniblocks = load_miniblocks(&mut storage, l1_batch_number).await?;
+        drop(storage);
+
+        let latency = METRICS.batch_execution_latency.start();
+        let connection_pool = self.connection_pool.clone();
+        let l2_chain_id = self.l2_chain_id;
+        let contracts_cache = self.contracts_cache.clone();
+        let output = tokio::task::spawn_blocking(move || {
+            Self::execute_batch(
+                Handle::current(),
+                l1_batch_number,
+                miniblocks,
+                connection_pool,
+                l2_chain_id,
+                &contracts_cache,
+            )
+        })
+        .await
+        .context("VM runner execution panicked")?
+        .with_context(|| format!("failed re-executing L1 batch #{l1_batch_number}"))?;
+        let latency = latency.observe();
+        tracing::debug!("Re-executed L1 batch #{l1_batch_number} in {latency:?}");
+
+        let divergences = output.divergences(&persisted_output);
+        if divergences.is_empty() {
+            tracing::info!("L1 batch #{l1_batch_number} was successfully verified");
+        } else {
+            tracing::error!(
+                "Re-execution of L1 batch #{l1_batch_number} diverged from the persisted data: {divergences:?}; \
+                 re-executed output: {output:?}, persisted output: {persisted_output:?}"
+            );
+            for &kind in &divergences {
+                METRICS.divergences[&kind].inc();
+            }
+            let details = DivergenceDetails {
+                l1_batch_number,
+                kinds: divergences,
+            };
+            self.health_updater
+                .update(Health::from(HealthStatus::Affected).with_details(details));
+        }
+        METRICS
+            .last_processed_l1_batch
+            .set(l1_batch_number.0.into());
+        Ok(())
+    }
+
+    async fn wait_for_sealed_l1_batch(
+        &self,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        while !*stop_receiver.borrow_and_update() {
+            let sealed_l1_batch = self
+                .connection_pool
+                .access_storage_tagged("vm_runner")
+                .await?
+                .blocks_dal()
+                .get_sealed_l1_batch_number()
+                .await?;
+            if sealed_l1_batch.is_some() {
+                return Ok(sealed_l1_batch);
+            }
+            // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+            tokio::time::timeout(POLL_INTERVAL, stop_receiver.changed())
+                .await
+                .ok();
+        }
+        Ok(None)
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let Some(mut next_l1_batch) = self.wait_for_sealed_l1_batch(&mut stop_receiver).await?
+        else {
+            tracing::info!("Stop signal received, VM runner is shutting down");
+            return Ok(());
+        };
+        tracing::info!("Starting VM runner from L1 batch #{next_l1_batch}");
+        self.health_updater.update(HealthStatus::Ready.into());
+
+        while !*stop_receiver.borrow_and_update() {
+            let sealed_l1_batch = self
+                .wait_for_sealed_l1_batch(&mut stop_receiver)
+                .await?
+                .unwrap_or(L1BatchNumber(0));
+            if sealed_l1_batch < next_l1_batch {
+                // Error here corresponds to a timeout w/o `stop_receiver` changed; we're OK with this.
+                tokio::time::timeout(POLL_INTERVAL, stop_receiver.changed())
+                    .await
+                    .ok();
+                continue;
+            }
+
+            self.process_l1_batch(next_l1_batch).await?;
+            next_l1_batch += 1;
+        }
+        tracing::info!("Stop signal received, VM runner is shutting down");
+        Ok(())
+    }
+}
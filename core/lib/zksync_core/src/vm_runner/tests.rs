@@ -0,0 +1,54 @@
+//! Tests for the VM runner.
+
+use zksync_types::{AccountTreeId, Address};
+
+use super::*;
+
+fn mock_output() -> BatchOutput {
+    let event = VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: Address::repeat_byte(1),
+        indexed_topics: vec![H256::repeat_byte(2)],
+        value: vec![3; 32],
+    };
+    let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+    BatchOutput {
+        tx_outcomes: vec![(H256::repeat_byte(0xff), TxExecutionStatus::Success, 100)],
+        events: vec![event],
+        storage_writes: HashMap::from([(key, H256::repeat_byte(4))]),
+    }
+}
+
+#[test]
+fn no_divergences_for_equal_outputs() {
+    let output = mock_output();
+    assert_eq!(output.divergences(&mock_output()), []);
+}
+
+#[test]
+fn detecting_divergences() {
+    let output = mock_output();
+
+    let mut persisted_output = mock_output();
+    persisted_output.tx_outcomes[0].2 = 0;
+    assert_eq!(
+        output.divergences(&persisted_output),
+        [DivergenceKind::TxOutcomes]
+    );
+
+    let mut persisted_output = mock_output();
+    persisted_output.tx_outcomes[0].1 = TxExecutionStatus::Failure;
+    persisted_output.events[0].location.1 = 1;
+    assert_eq!(
+        output.divergences(&persisted_output),
+        [DivergenceKind::TxOutcomes, DivergenceKind::Events]
+    );
+
+    let mut persisted_output = mock_output();
+    persisted_output.events.clear();
+    persisted_output.storage_writes.clear();
+    assert_eq!(
+        output.divergences(&persisted_output),
+        [DivergenceKind::Events, DivergenceKind::StorageWrites]
+    );
+}
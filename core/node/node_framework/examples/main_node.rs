@@ -113,6 +113,7 @@ impl MainNodeBuilder {
         self.node.add_layer(EthWatchLayer::new(
             ETHWatchConfig::from_env()?,
             ContractsConfig::from_env()?,
+            NetworkConfig::from_env()?,
         ));
         Ok(self)
     }
@@ -1,8 +1,8 @@
 use std::time::Duration;
 
-use zksync_config::{ContractsConfig, ETHWatchConfig};
+use zksync_config::{configs::chain::NetworkConfig, ContractsConfig, ETHWatchConfig};
 use zksync_contracts::governance_contract;
-use zksync_core::eth_watch::{client::EthHttpQueryClient, EthWatch};
+use zksync_core::eth_watch::{client::EthHttpQueryClient, EthWatch, SharedBridgeParams};
 use zksync_dal::ConnectionPool;
 use zksync_types::{ethabi::Contract, Address};
 
@@ -17,13 +17,19 @@ use crate::{
 pub struct EthWatchLayer {
     eth_watch_config: ETHWatchConfig,
     contracts_config: ContractsConfig,
+    network_config: NetworkConfig,
 }
 
 impl EthWatchLayer {
-    pub fn new(eth_watch_config: ETHWatchConfig, contracts_config: ContractsConfig) -> Self {
+    pub fn new(
+        eth_watch_config: ETHWatchConfig,
+        contracts_config: ContractsConfig,
+        network_config: NetworkConfig,
+    ) -> Self {
         Self {
             eth_watch_config,
             contracts_config,
+            network_config,
         }
     }
 }
@@ -50,6 +56,12 @@ impl WiringLayer for EthWatchLayer {
             main_pool,
             client: eth_client,
             governance_contract: Some(governance_contract()),
+            shared_bridge: self.contracts_config.state_transition_proxy_addr.map(
+                |state_transition_manager_address| SharedBridgeParams {
+                    state_transition_manager_address,
+                    chain_id: self.network_config.zksync_network_id,
+                },
+            ),
             diamond_proxy_address: self.contracts_config.diamond_proxy_addr,
             poll_interval: self.eth_watch_config.poll_interval(),
         }));
@@ -63,6 +75,7 @@ struct EthWatchTask {
     main_pool: ConnectionPool,
     client: EthHttpQueryClient,
     governance_contract: Option<Contract>,
+    shared_bridge: Option<SharedBridgeParams>,
     diamond_proxy_address: Address,
     poll_interval: Duration,
 }
@@ -77,6 +90,7 @@ impl Task for EthWatchTask {
         let eth_watch = EthWatch::new(
             self.diamond_proxy_address,
             self.governance_contract,
+            self.shared_bridge,
             Box::new(self.client),
             self.main_pool,
             self.poll_interval,
@@ -84,6 +84,10 @@ impl WiringLayer for PoolsLayer {
             let mut replica_pool =
                 ConnectionPool::builder(self.config.replica_url()?, self.config.max_connections()?);
             replica_pool.set_statement_timeout(self.config.statement_timeout());
+            replica_pool.set_read_replicas(
+                self.config.read_replica_urls.clone(),
+                self.config.max_replica_lag(),
+            );
             context.insert_resource(ReplicaPoolResource::new(replica_pool))?;
         }
 
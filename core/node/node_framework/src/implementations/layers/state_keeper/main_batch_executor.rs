@@ -2,7 +2,10 @@ use zksync_config::{configs::chain::StateKeeperConfig, DBConfig};
 use zksync_core::state_keeper::MainBatchExecutor;
 
 use crate::{
-    implementations::resources::{pools::MasterPoolResource, state_keeper::BatchExecutorResource},
+    implementations::resources::{
+        pools::MasterPoolResource,
+        state_keeper::{BatchExecutorResource, BytecodeCompressorResource},
+    },
     resource::Unique,
     service::ServiceContext,
     wiring_layer::{WiringError, WiringLayer},
@@ -31,8 +34,11 @@ impl WiringLayer for MainBatchExecutorLayer {
 
     async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
         let master_pool = context.get_resource::<MasterPoolResource>().await?;
+        let bytecode_compressor = context
+            .get_resource_or_default::<BytecodeCompressorResource>()
+            .await;
 
-        let builder = MainBatchExecutor::new(
+        let mut builder = MainBatchExecutor::new(
             self.db_config.state_keeper_db_path,
             master_pool.get_singleton().await?,
             self.state_keeper_config.max_allowed_l2_tx_gas_limit.into(),
@@ -41,6 +47,10 @@ impl WiringLayer for MainBatchExecutorLayer {
             self.state_keeper_config.enum_index_migration_chunk_size(),
             false,
         );
+        builder.set_bytecode_compressor(bytecode_compressor.0);
+        if let Some(timeout) = self.state_keeper_config.tx_execution_timeout() {
+            builder.set_tx_execution_timeout(timeout);
+        }
 
         context.insert_resource(BatchExecutorResource(Unique::new(Box::new(builder))))?;
         Ok(())
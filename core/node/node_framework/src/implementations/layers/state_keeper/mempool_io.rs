@@ -14,7 +14,9 @@ use crate::{
         fee_input::FeeInputResource,
         object_store::ObjectStoreResource,
         pools::MasterPoolResource,
-        state_keeper::{ConditionalSealerResource, StateKeeperIOResource},
+        state_keeper::{
+            BytecodeCompressorResource, ConditionalSealerResource, StateKeeperIOResource,
+        },
     },
     resource::Unique,
     service::{ServiceContext, StopReceiver},
@@ -57,7 +59,7 @@ impl MempoolIOLayer {
             .access_storage()
             .await
             .context("Access storage to build mempool")?;
-        let mempool = MempoolGuard::from_storage(&mut storage, self.mempool_config.capacity).await;
+        let mempool = MempoolGuard::from_storage(&mut storage, &self.mempool_config).await;
         mempool.register_metrics();
         Ok(mempool)
     }
@@ -74,6 +76,9 @@ impl WiringLayer for MempoolIOLayer {
         let batch_fee_input_provider = context.get_resource::<FeeInputResource>().await?.0;
         let object_store = context.get_resource::<ObjectStoreResource>().await?.0;
         let master_pool = context.get_resource::<MasterPoolResource>().await?;
+        let bytecode_compressor = context
+            .get_resource_or_default::<BytecodeCompressorResource>()
+            .await;
 
         // Create miniblock sealer task.
         let (miniblock_sealer, miniblock_sealer_handle) = MiniblockSealer::new(
@@ -96,7 +101,8 @@ impl WiringLayer for MempoolIOLayer {
             batch_fee_input_provider.clone(),
             &self.mempool_config,
             mempool_fetcher_pool,
-        );
+        )
+        .with_bytecode_compressor(bytecode_compressor.0);
         context.add_task(Box::new(MempoolFetcherTask(mempool_fetcher)));
 
         // Create mempool IO resource.
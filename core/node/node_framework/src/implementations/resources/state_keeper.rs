@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
-use zksync_core::state_keeper::{seal_criteria::ConditionalSealer, BatchExecutor, StateKeeperIO};
+use zksync_core::state_keeper::{
+    seal_criteria::ConditionalSealer, BatchExecutor, BytecodeCompressor, StateKeeperIO,
+};
 
 use crate::resource::{Resource, ResourceId, Unique};
 
@@ -30,3 +32,14 @@ impl Resource for ConditionalSealerResource {
         "state_keeper/conditional_sealer".into()
     }
 }
+
+/// Bytecode compressor shared between the mempool fetcher (which schedules compression) and the batch executor
+/// (which uses compression results).
+#[derive(Debug, Clone, Default)]
+pub struct BytecodeCompressorResource(pub BytecodeCompressor);
+
+impl Resource for BytecodeCompressorResource {
+    fn resource_id() -> ResourceId {
+        "state_keeper/bytecode_compressor".into()
+    }
+}
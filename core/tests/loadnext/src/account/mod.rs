@@ -101,7 +101,7 @@ impl AccountLifespan {
 
     pub async fn run(self, limiters: &RequestLimiters) {
         let duration = self.config.duration();
-        let tx_execution_task = self.clone().run_tx_execution();
+        let tx_execution_task = self.clone().run_tx_execution(limiters);
         let api_requests_task = self.clone().run_api_requests_task(limiters);
 
         tokio::select! {
@@ -118,7 +118,7 @@ impl AccountLifespan {
         }
     }
 
-    async fn run_tx_execution(mut self) -> Result<(), Aborted> {
+    async fn run_tx_execution(mut self, limiters: &RequestLimiters) -> Result<(), Aborted> {
         // Every account starts with deploying a contract.
         let deploy_command = TxCommand {
             command_type: TxType::DeployContract,
@@ -145,6 +145,9 @@ impl AccountLifespan {
                     timer.tick().await;
                     self.check_inflight_txs().await?;
                 } else {
+                    if let Some(rate_limiter) = &limiters.transactions {
+                        rate_limiter.acquire().await;
+                    }
                     self.execute_command(command).await?;
                     l1_tx_count += u64::from(is_l1_transaction);
                     break;
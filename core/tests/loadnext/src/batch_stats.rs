@@ -0,0 +1,97 @@
+//! Statistics on L1 batches sealed by the server during the loadtest.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use zksync::{HttpClient, ZksNamespaceClient};
+use zksync_types::L1BatchNumber;
+
+/// Statistics on L1 batches sealed during the loadtest.
+///
+/// The server doesn't expose the seal criterion that closed a batch, but it can be inferred from these statistics:
+/// e.g., a constant number of transactions per batch suggests that batches are sealed by the transaction slots limit,
+/// while a constant interval between batches suggests that they are sealed by timeout.
+#[derive(Debug, Default)]
+pub struct L1BatchStats {
+    tx_counts: Vec<usize>,
+    miniblock_counts: Vec<u64>,
+    seal_intervals: Vec<Duration>,
+}
+
+impl L1BatchStats {
+    /// Collects statistics for L1 batches in the provided inclusive range.
+    pub async fn collect(
+        client: &HttpClient,
+        first_batch: L1BatchNumber,
+        last_batch: L1BatchNumber,
+    ) -> anyhow::Result<Self> {
+        let mut stats = Self::default();
+        let mut prev_timestamp = None;
+        for number in first_batch.0..=last_batch.0 {
+            let number = L1BatchNumber(number);
+            let details = client
+                .get_l1_batch_details(number)
+                .await?
+                .with_context(|| format!("L1 batch #{number} is missing"))?;
+            let (first_miniblock, last_miniblock) = client
+                .get_miniblock_range(number)
+                .await?
+                .with_context(|| format!("miniblock range for L1 batch #{number} is missing"))?;
+
+            stats
+                .tx_counts
+                .push(details.base.l1_tx_count + details.base.l2_tx_count);
+            stats
+                .miniblock_counts
+                .push(last_miniblock.as_u64() + 1 - first_miniblock.as_u64());
+            if let Some(prev_timestamp) = prev_timestamp {
+                let interval = details.base.timestamp.saturating_sub(prev_timestamp);
+                stats.seal_intervals.push(Duration::from_secs(interval));
+            }
+            prev_timestamp = Some(details.base.timestamp);
+        }
+        Ok(stats)
+    }
+
+    pub fn report(&self, prometheus_label: String) {
+        let batch_count = self.tx_counts.len();
+        if batch_count == 0 {
+            tracing::info!("No L1 batches were sealed during the test");
+            return;
+        }
+
+        let total_txs: usize = self.tx_counts.iter().sum();
+        let avg_txs = total_txs as f64 / batch_count as f64;
+        let max_txs = self.tx_counts.iter().max().copied().unwrap_or_default();
+        let total_miniblocks: u64 = self.miniblock_counts.iter().sum();
+        let avg_miniblocks = total_miniblocks as f64 / batch_count as f64;
+
+        tracing::info!("L1 batches sealed during the test: {batch_count}");
+        tracing::info!("Transactions per L1 batch: avg {avg_txs:.1}, max {max_txs}");
+        tracing::info!("Miniblocks per L1 batch: avg {avg_miniblocks:.1}");
+        if !self.seal_intervals.is_empty() {
+            let total_interval: Duration = self.seal_intervals.iter().sum();
+            let avg_interval = total_interval / self.seal_intervals.len() as u32;
+            let max_interval = self
+                .seal_intervals
+                .iter()
+                .max()
+                .copied()
+                .unwrap_or_default();
+            tracing::info!(
+                "Interval between L1 batches: avg {avg_interval:?}, max {max_interval:?}"
+            );
+        }
+
+        metrics::gauge!(
+            "loadtest.l1_batches",
+            batch_count as f64,
+            "label" => prometheus_label.clone(),
+        );
+        metrics::gauge!(
+            "loadtest.avg_txs_per_l1_batch",
+            avg_txs,
+            "label" => prometheus_label,
+        );
+    }
+}
@@ -1,7 +1,10 @@
-use std::{path::PathBuf, time::Duration};
+use std::{path::PathBuf, sync::Mutex, time::Duration};
 
 use serde::Deserialize;
-use tokio::sync::Semaphore;
+use tokio::{
+    sync::Semaphore,
+    time::{self, Instant},
+};
 use zksync_contracts::test_contracts::LoadnextContractExecutionParams;
 use zksync_types::{network::Network, Address, L2ChainId, H160};
 
@@ -136,6 +139,13 @@ pub struct LoadtestConfig {
     /// in an eventual test failure anyway (e.g., a failure processing transactions).
     #[serde(default)]
     pub fail_fast: bool,
+
+    /// Target number of transactions per second sent by all accounts combined. If set, transactions
+    /// are sent no faster than this rate; this allows to check how the node behaves under a fixed load
+    /// (e.g., before changing seal criteria in the node configuration).
+    /// If not set, each account sends transactions as fast as `max_inflight_txs` allows.
+    #[serde(default = "default_target_tps")]
+    pub target_tps: Option<f64>,
 }
 
 fn default_max_inflight_txs() -> usize {
@@ -261,6 +271,12 @@ fn default_prometheus_label() -> String {
     result
 }
 
+fn default_target_tps() -> Option<f64> {
+    let result = None;
+    tracing::info!("Using default TARGET_TPS: {result:?}");
+    result
+}
+
 impl LoadtestConfig {
     pub fn from_env() -> envy::Result<Self> {
         envy::from_env()
@@ -336,6 +352,7 @@ fn default_contract_execution_params() -> LoadnextContractExecutionParams {
 pub struct RequestLimiters {
     pub api_requests: Semaphore,
     pub subscriptions: Semaphore,
+    pub transactions: Option<TxRateLimiter>,
 }
 
 impl RequestLimiters {
@@ -343,6 +360,66 @@ impl RequestLimiters {
         Self {
             api_requests: Semaphore::new(config.sync_api_requests_limit),
             subscriptions: Semaphore::new(config.sync_pubsub_subscriptions_limit),
+            transactions: config.target_tps.map(TxRateLimiter::new),
         }
     }
 }
+
+/// Limits the rate of transactions sent by all accounts combined.
+///
+/// Each transaction is assigned a send slot spaced by `1 / target_tps` from the previous one, and waiting for slots
+/// is concurrent. Thus, the rate is not bounded by the timer resolution: for target rates exceeding it, several
+/// transactions are sent on each timer tick. Slots in the past are not assigned, so if accounts cannot keep up with
+/// the target rate for some time (e.g., because all of them wait for inflight transactions), the limiter won't send
+/// a burst of transactions afterwards.
+#[derive(Debug)]
+pub struct TxRateLimiter {
+    period: Duration,
+    next_slot: Mutex<Option<Instant>>,
+}
+
+impl TxRateLimiter {
+    fn new(target_tps: f64) -> Self {
+        assert!(
+            target_tps.is_finite() && target_tps > 0.0,
+            "TARGET_TPS must be positive, got {target_tps}"
+        );
+        Self {
+            period: Duration::from_secs_f64(1.0 / target_tps),
+            next_slot: Mutex::new(None),
+        }
+    }
+
+    fn reserve_slot(&self, now: Instant) -> Instant {
+        let mut next_slot = self.next_slot.lock().expect("rate limiter is poisoned");
+        let slot = next_slot.map_or(now, |next_slot| next_slot.max(now));
+        *next_slot = Some(slot + self.period);
+        slot
+    }
+
+    /// Waits until the next transaction can be sent.
+    pub async fn acquire(&self) {
+        let slot = self.reserve_slot(Instant::now());
+        time::sleep_until(slot).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tx_rate_limiter_slots() {
+        let limiter = TxRateLimiter::new(10_000.0);
+        let now = Instant::now();
+        let slots: Vec<_> = (0..100).map(|_| limiter.reserve_slot(now)).collect();
+        // Slots are not rounded to the timer resolution.
+        assert_eq!(slots[0], now);
+        assert_eq!(slots[99] - now, limiter.period * 99);
+
+        // After the limiter is idle, slots start from the current time, without a burst of past slots.
+        let later = now + Duration::from_secs(1);
+        assert_eq!(limiter.reserve_slot(later), later);
+        assert_eq!(limiter.reserve_slot(later), later + limiter.period);
+    }
+}
@@ -14,13 +14,14 @@ use zksync_eth_client::{BoundEthInterface, EthInterface, Options};
 use zksync_eth_signer::PrivateKeySigner;
 use zksync_system_constants::MAX_L1_TRANSACTION_GAS_LIMIT;
 use zksync_types::{
-    api::BlockNumber, tokens::ETHEREUM_ADDRESS, Address, Nonce,
+    api::BlockNumber, tokens::ETHEREUM_ADDRESS, Address, L1BatchNumber, Nonce,
     REQUIRED_L1_TO_L2_GAS_PER_PUBDATA_BYTE, U256, U64,
 };
 
 use crate::{
     account::AccountLifespan,
     account_pool::AccountPool,
+    batch_stats::L1BatchStats,
     config::{ExecutionConfig, LoadtestConfig, RequestLimiters},
     constants::*,
     report::ReportBuilder,
@@ -583,6 +584,7 @@ impl Executor {
             account_tasks.extend(new_account_futures);
         }
 
+        let last_batch_before_test = self.last_sealed_l1_batch().await?;
         report_sender
             .send(ReportBuilder::build_init_complete_report())
             .await
@@ -600,7 +602,39 @@ impl Executor {
         future::try_join_all(account_tasks).await?;
         tracing::info!("All the spawned tasks are completed");
 
-        Ok(report_collector_future.await?)
+        let result = report_collector_future.await?;
+        // Failing to collect L1 batch stats doesn't influence the test result.
+        if let Err(err) = self.report_l1_batch_stats(last_batch_before_test).await {
+            tracing::warn!("Failed collecting L1 batch statistics: {err:#}");
+        }
+        Ok(result)
+    }
+
+    async fn last_sealed_l1_batch(&self) -> anyhow::Result<L1BatchNumber> {
+        let number = self
+            .pool
+            .master_wallet
+            .provider
+            .get_l1_batch_number()
+            .await?;
+        Ok(L1BatchNumber(number.as_u32()))
+    }
+
+    /// Reports statistics on L1 batches sealed by the server during the test,
+    /// which can be used to estimate which seal criteria are hit under the load.
+    async fn report_l1_batch_stats(
+        &self,
+        last_batch_before_test: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let last_batch = self.last_sealed_l1_batch().await?;
+        let stats = L1BatchStats::collect(
+            &self.pool.master_wallet.provider,
+            last_batch_before_test + 1,
+            last_batch,
+        )
+        .await?;
+        stats.report(self.config.prometheus_label.clone());
+        Ok(())
     }
 
     /// Calculates amount of ETH to be distributed per account in order to make them
@@ -1,5 +1,15 @@
+use std::path::Path;
+
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use vm_benchmark_harness::{cut_to_allowed_bytecode_size, get_deploy_tx, BenchmarkingVm};
+use vm_benchmark_harness::{
+    cut_to_allowed_bytecode_size, get_deploy_tx, get_deploy_tx_with_nonce, BenchmarkingVm,
+};
+
+/// Call-dense bytecodes executed as a batch of transactions, which allows measuring VM memory reuse between
+/// transactions in a batch.
+const CALL_HEAVY_BENCHMARKS: &[&str] = &["call_far", "finish_eventful_frames"];
+/// Number of transactions in a batch for call-heavy benchmarks.
+const TXS_IN_BATCH: u32 = 10;
 
 fn benches_in_folder(c: &mut Criterion) {
     for path in std::fs::read_dir("deployment_benchmarks").unwrap() {
@@ -16,5 +26,26 @@ fn benches_in_folder(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, benches_in_folder);
+fn call_heavy_batches(c: &mut Criterion) {
+    for &name in CALL_HEAVY_BENCHMARKS {
+        let path = Path::new("deployment_benchmarks").join(name);
+        let test_contract = std::fs::read(path).expect("failed to read file");
+
+        let code = cut_to_allowed_bytecode_size(&test_contract).unwrap();
+        let txs: Vec<_> = (0..TXS_IN_BATCH)
+            .map(|nonce| get_deploy_tx_with_nonce(code, nonce))
+            .collect();
+
+        c.bench_function(&format!("{name}_batch_of_{TXS_IN_BATCH}"), |b| {
+            b.iter(|| {
+                let mut vm = BenchmarkingVm::new();
+                for tx in &txs {
+                    black_box(vm.run_transaction(black_box(tx)));
+                }
+            })
+        });
+    }
+}
+
+criterion_group!(benches, benches_in_folder, call_heavy_batches);
 criterion_main!(benches);
@@ -103,6 +103,12 @@ impl BenchmarkingVm {
 }
 
 pub fn get_deploy_tx(code: &[u8]) -> Transaction {
+    get_deploy_tx_with_nonce(code, 0)
+}
+
+/// Same as [`get_deploy_tx()`], but allows to specify the transaction nonce, so that multiple deployment transactions
+/// can be executed in a single batch.
+pub fn get_deploy_tx_with_nonce(code: &[u8], nonce: u32) -> Transaction {
     let params = [
         Token::FixedBytes(vec![0u8; 32]),
         Token::FixedBytes(hash_bytecode(code).0.to_vec()),
@@ -117,7 +123,7 @@ pub fn get_deploy_tx(code: &[u8]) -> Transaction {
     let mut signed = L2Tx::new_signed(
         CONTRACT_DEPLOYER_ADDRESS,
         calldata,
-        Nonce(0),
+        Nonce(nonce),
         Fee {
             gas_limit: U256::from(30000000u32),
             max_fee_per_gas: U256::from(250_000_000),
@@ -99,6 +99,14 @@ export async function migrate(opts: DbOpts) {
     }
 }
 
+export async function validateMigrations(opts: { databaseUrl: string; maxCostRatio?: string }) {
+    console.log('Validating pending migrations against a database clone...');
+    const maxCostRatioArg = opts.maxCostRatio ? ` --max-cost-ratio ${opts.maxCostRatio}` : '';
+    await utils.spawn(
+        `cargo run --release --bin migration_validator -- --dal-path ${DalPath.CoreDal} --database-url ${opts.databaseUrl}${maxCostRatioArg}`
+    );
+}
+
 async function generateMigrationForDal(dalPath: DalPath, dbUrl: string, name: String) {
     console.log(`Generating migration for ${dalPath}...`);
     await utils.spawn(`cd ${dalPath} && cargo sqlx migrate add -r ${name}`);
@@ -200,6 +208,12 @@ export const command = new Command('db').description('database management');
 
 command.command('drop').description('drop the database').option('-p, --prover').option('-s, --server').action(drop);
 command.command('migrate').description('run migrations').option('-p, --prover').option('-s, --server').action(migrate);
+command
+    .command('validate-migrations')
+    .description('validate pending server migrations against a disposable clone of the server database')
+    .requiredOption('--database-url <url>', 'URL of the database clone; must not point to the primary database')
+    .option('--max-cost-ratio <ratio>', 'maximum allowed increase of estimated query costs')
+    .action(validateMigrations);
 command
     .command('new-migration')
     .description('generate a new migration for a specific database')
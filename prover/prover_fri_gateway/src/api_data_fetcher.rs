@@ -7,7 +7,7 @@ use tokio::{sync::watch, time::sleep};
 use zksync_dal::ConnectionPool;
 use zksync_object_store::ObjectStore;
 
-use crate::metrics::METRICS;
+use crate::{metrics::METRICS, proof_verifier::ProofVerifier};
 
 /// The path to the API endpoint that returns the next proof generation data.
 pub(crate) const PROOF_GENERATION_DATA_PATH: &str = "/proof_generation_data";
@@ -21,6 +21,8 @@ pub(crate) struct PeriodicApiStruct {
     pub(crate) api_url: String,
     pub(crate) poll_duration: Duration,
     pub(crate) client: Client,
+    /// Verifier for proofs submitted to the server. Only set for the proof submitter.
+    pub(crate) proof_verifier: Option<ProofVerifier>,
 }
 
 impl PeriodicApiStruct {
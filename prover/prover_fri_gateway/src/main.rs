@@ -8,13 +8,18 @@ use zksync_env_config::{object_store::ProverObjectStoreConfig, FromEnv};
 use zksync_object_store::ObjectStoreFactory;
 use zksync_prover_interface::api::{ProofGenerationDataRequest, SubmitProofRequest};
 use zksync_utils::wait_for_tasks::wait_for_tasks;
+use zksync_vk_setup_data_server_fri::keystore::Keystore;
 
-use crate::api_data_fetcher::{PeriodicApiStruct, PROOF_GENERATION_DATA_PATH, SUBMIT_PROOF_PATH};
+use crate::{
+    api_data_fetcher::{PeriodicApiStruct, PROOF_GENERATION_DATA_PATH, SUBMIT_PROOF_PATH},
+    proof_verifier::ProofVerifier,
+};
 
 mod api_data_fetcher;
 mod metrics;
 mod proof_gen_data_fetcher;
 mod proof_submitter;
+mod proof_verifier;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -48,12 +53,15 @@ async fn main() -> anyhow::Result<()> {
         ProverObjectStoreConfig::from_env().context("ProverObjectStoreConfig::from_env()")?;
     let store_factory = ObjectStoreFactory::new(object_store_config.0);
 
+    let proof_verifier =
+        ProofVerifier::new(&Keystore::default()).context("failed loading proof verifier")?;
     let proof_submitter = PeriodicApiStruct {
         blob_store: store_factory.create_store().await,
         pool: pool.clone(),
         api_url: format!("{}{SUBMIT_PROOF_PATH}", config.api_url),
         poll_duration: config.api_poll_duration(),
         client: Client::new(),
+        proof_verifier: Some(proof_verifier),
     };
     let proof_gen_data_fetcher = PeriodicApiStruct {
         blob_store: store_factory.create_store().await,
@@ -61,6 +69,7 @@ async fn main() -> anyhow::Result<()> {
         api_url: format!("{}{PROOF_GENERATION_DATA_PATH}", config.api_url),
         poll_duration: config.api_poll_duration(),
         client: Client::new(),
+        proof_verifier: None,
     };
 
     let (stop_sender, stop_receiver) = watch::channel(false);
@@ -5,6 +5,8 @@ use vise::{Counter, LabeledFamily, Metrics};
 pub(crate) struct ProverFriGatewayMetrics {
     #[metrics(labels = ["service_name"])]
     pub http_error: LabeledFamily<&'static str, Counter>,
+    /// Number of proofs that failed local verification and were not submitted to the server.
+    pub invalid_proofs: Counter,
 }
 
 #[vise::register]
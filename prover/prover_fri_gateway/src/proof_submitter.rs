@@ -3,7 +3,10 @@ use zksync_dal::fri_proof_compressor_dal::ProofCompressionJobStatus;
 use zksync_prover_interface::api::{SubmitProofRequest, SubmitProofResponse};
 use zksync_types::L1BatchNumber;
 
-use crate::api_data_fetcher::{PeriodicApi, PeriodicApiStruct};
+use crate::{
+    api_data_fetcher::{PeriodicApi, PeriodicApiStruct},
+    metrics::METRICS,
+};
 
 impl PeriodicApiStruct {
     async fn next_submit_proof_request(&self) -> Option<(L1BatchNumber, SubmitProofRequest)> {
@@ -23,6 +26,14 @@ impl PeriodicApiStruct {
                     .get(l1_batch_number)
                     .await
                     .expect("Failed to get compressed snark proof from blob store");
+                if let Some(verifier) = &self.proof_verifier {
+                    if let Err(err) = verifier.verify(l1_batch_number, &proof) {
+                        tracing::error!("Not submitting invalid proof: {err}");
+                        METRICS.invalid_proofs.inc();
+                        self.reject_proof(l1_batch_number, &err.to_string()).await;
+                        return None;
+                    }
+                }
                 SubmitProofRequest::Proof(Box::new(proof))
             }
             ProofCompressionJobStatus::Skipped => SubmitProofRequest::SkippedProofGeneration,
@@ -35,6 +46,18 @@ impl PeriodicApiStruct {
         Some((l1_batch_number, request))
     }
 
+    /// Marks the compression job as failed, so that the proof is re-compressed (or requires manual intervention
+    /// once the compressor runs out of attempts) instead of being submitted to the server.
+    async fn reject_proof(&self, l1_batch_number: L1BatchNumber, error: &str) {
+        self.pool
+            .access_storage()
+            .await
+            .unwrap()
+            .fri_proof_compressor_dal()
+            .mark_proof_compression_job_failed(error, l1_batch_number)
+            .await;
+    }
+
     async fn save_successful_sent_proof(&self, l1_batch_number: L1BatchNumber) {
         self.pool
             .access_storage()
@@ -0,0 +1,144 @@
+use std::fmt;
+
+use anyhow::Context as _;
+use zkevm_test_harness_1_3_3::{
+    abstract_zksync_circuit::concrete_circuits::{
+        ZkSyncCircuit, ZkSyncProof, ZkSyncVerificationKey,
+    },
+    bellman::{
+        bn256::Bn256, plonk::better_better_cs::setup::VerificationKey as SnarkVerificationKey,
+    },
+    witness::oracle::VmWitnessOracle,
+};
+use zksync_prover_interface::outputs::L1BatchProofForL1;
+use zksync_types::L1BatchNumber;
+use zksync_vk_setup_data_server_fri::keystore::Keystore;
+
+/// Error returned if a proof is rejected by [`ProofVerifier`].
+#[derive(Debug)]
+pub(crate) enum ProofVerificationError {
+    /// Proof doesn't pass verification against the SNARK verification key.
+    InvalidProof { l1_batch_number: L1BatchNumber },
+    /// Verification panicked, e.g., because the proof is malformed.
+    Panicked {
+        l1_batch_number: L1BatchNumber,
+        message: String,
+    },
+}
+
+impl fmt::Display for ProofVerificationError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidProof { l1_batch_number } => write!(
+                formatter,
+                "proof for L1 batch #{l1_batch_number} failed verification"
+            ),
+            Self::Panicked {
+                l1_batch_number,
+                message,
+            } => write!(
+                formatter,
+                "verification of proof for L1 batch #{l1_batch_number} panicked: {message}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ProofVerificationError {}
+
+/// Verifies compressed (SNARK) proofs locally before they are submitted to the server, so that
+/// a buggy prover or compressor cannot feed invalid proofs into the submission pipeline.
+pub(crate) struct ProofVerifier {
+    vk: ZkSyncVerificationKey<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>,
+}
+
+impl fmt::Debug for ProofVerifier {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ProofVerifier")
+            .finish_non_exhaustive()
+    }
+}
+
+impl ProofVerifier {
+    /// Loads the SNARK verification key from the provided keystore.
+    pub(crate) fn new(keystore: &Keystore) -> anyhow::Result<Self> {
+        // The key is stored as a raw string, so that the keystore doesn't depend on the old test harness.
+        let vk_serialized = keystore
+            .load_snark_verification_key()
+            .context("load_snark_verification_key()")?;
+        let vk = serde_json::from_str::<
+            SnarkVerificationKey<Bn256, ZkSyncCircuit<Bn256, VmWitnessOracle<Bn256>>>,
+        >(&vk_serialized)
+        .context("failed deserializing SNARK verification key")?;
+        Ok(Self {
+            vk: ZkSyncVerificationKey::from_verification_key_and_numeric_type(0, vk),
+        })
+    }
+
+    pub(crate) fn verify(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        proof: &L1BatchProofForL1,
+    ) -> Result<(), ProofVerificationError> {
+        let proof = ZkSyncProof::from_proof_and_numeric_type(0, proof.scheduler_proof.clone());
+        // The verifier may panic on malformed proofs (e.g., ones with points not on the curve).
+        let is_valid = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            self.vk.verify_proof(&proof)
+        }))
+        .map_err(|panic| {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|&message| message.to_owned())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_owned());
+            ProofVerificationError::Panicked {
+                l1_batch_number,
+                message,
+            }
+        })?;
+
+        if is_valid {
+            Ok(())
+        } else {
+            Err(ProofVerificationError::InvalidProof { l1_batch_number })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zkevm_test_harness_1_3_3::bellman::plonk::better_better_cs::proof::Proof;
+
+    use super::*;
+
+    fn create_verifier() -> ProofVerifier {
+        let keystore_path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../vk_setup_data_generator_server_fri/data"
+        );
+        let keystore = Keystore::new_with_optional_setup_path(keystore_path.to_owned(), None);
+        ProofVerifier::new(&keystore).unwrap()
+    }
+
+    #[test]
+    fn rejecting_malformed_proof() {
+        let verifier = create_verifier();
+        let proof = L1BatchProofForL1 {
+            aggregation_result_coords: [[0; 32]; 4],
+            scheduler_proof: Proof::empty(),
+        };
+        let err = verifier.verify(L1BatchNumber(1), &proof).unwrap_err();
+        assert!(err.to_string().contains("L1 batch #1"), "{err}");
+    }
+
+    #[test]
+    fn loading_verifier_with_missing_key() {
+        let keystore = Keystore::new_with_optional_setup_path("/non-existing".to_owned(), None);
+        let err = ProofVerifier::new(&keystore).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("load_snark_verification_key"),
+            "{err:#}"
+        );
+    }
+}